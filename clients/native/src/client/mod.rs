@@ -53,10 +53,12 @@ impl SocketClient {
         let ClientInput {
             connection_command_sender,
             input_sender,
+            ..
         } = client_input;
 
         let ClientOutput {
             received_buffer_request_sender,
+            ..
         } = client_output;
 
         let ClientState {
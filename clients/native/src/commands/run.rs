@@ -3,7 +3,10 @@
 
 use crate::commands::try_load_current_config;
 use crate::{
-    client::{config::Config, SocketClient},
+    client::{
+        config::{default_config_filepath, Config},
+        SocketClient,
+    },
     commands::{override_config, OverrideConfig},
     error::ClientError,
 };
@@ -11,6 +14,7 @@ use clap::Args;
 use log::*;
 use nym_bin_common::version_checker::is_minor_version_compatible;
 use nym_client_core::cli_helpers::client_run::CommonClientRunArgs;
+use nym_client_core::config::LayeredConfigLoader;
 use std::error::Error;
 use std::net::IpAddr;
 
@@ -30,6 +34,10 @@ pub(crate) struct Run {
     /// Ip for the socket (if applicable) to listen for requests.
     #[clap(long)]
     host: Option<IpAddr>,
+
+    /// Encrypt the records of the on-disk reply storage with a key derived from the identity key.
+    #[clap(long)]
+    encrypt_reply_storage: Option<bool>,
 }
 
 impl From<Run> for OverrideConfig {
@@ -43,6 +51,7 @@ impl From<Run> for OverrideConfig {
             no_cover: run_config.common_args.no_cover,
             nyxd_urls: run_config.common_args.nyxd_urls,
             enabled_credentials_mode: run_config.common_args.enabled_credentials_mode,
+            encrypt_reply_storage: run_config.encrypt_reply_storage,
         }
     }
 }
@@ -70,6 +79,22 @@ pub(crate) async fn execute(args: Run) -> Result<(), Box<dyn Error + Send + Sync
     eprintln!("Starting client {}...", args.common_args.id);
 
     let mut config = try_load_current_config(&args.common_args.id).await?;
+
+    // the values from the environment take precedence over the config file,
+    // but the explicit cli arguments take precedence over both
+    config.base = LayeredConfigLoader::from_file_config(
+        config.base,
+        default_config_filepath(&args.common_args.id),
+    )
+    .load()
+    .map_err(ClientError::from)?;
+    debug!(
+        "effective config sources: {}",
+        config.base.effective_sources()
+    );
+    if !config.validate() {
+        return Err(Box::new(ClientError::ConfigValidationFailure));
+    }
     config = override_config(config, OverrideConfig::from(args.clone()));
 
     if !version_check(&config) {
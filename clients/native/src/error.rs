@@ -26,6 +26,9 @@ pub enum ClientError {
     #[error(transparent)]
     ConfigUpgradeFailure(#[from] nym_client_core::config::ConfigUpgradeFailure),
 
+    #[error(transparent)]
+    ConfigLayeringFailure(#[from] nym_client_core::config::ConfigLayeringError),
+
     #[error(transparent)]
     NymIdError(#[from] NymIdError),
 }
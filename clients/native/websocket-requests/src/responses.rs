@@ -143,6 +143,7 @@ impl ServerResponse {
         Ok(ServerResponse::Received(ReconstructedMessage {
             message: message.to_vec(),
             sender_tag,
+            fragment_set_id: None,
         }))
     }
 
@@ -324,6 +325,7 @@ mod tests {
         let received_with_sender_tag = ServerResponse::Received(ReconstructedMessage {
             message: b"foomp".to_vec(),
             sender_tag: Some([42u8; SENDER_TAG_SIZE].into()),
+            fragment_set_id: None,
         });
         let bytes = received_with_sender_tag.serialize();
         let recovered = ServerResponse::deserialize(&bytes).unwrap();
@@ -341,6 +343,7 @@ mod tests {
         let received_without_sender_tag = ServerResponse::Received(ReconstructedMessage {
             message: b"foomp".to_vec(),
             sender_tag: None,
+            fragment_set_id: None,
         });
         let bytes = received_without_sender_tag.serialize();
         let recovered = ServerResponse::deserialize(&bytes).unwrap();
@@ -4,9 +4,10 @@
 use crate::commands::CliSocks5Client;
 use crate::config::{
     default_config_directory, default_config_filepath, default_data_directory, Config,
+    Socks5Authentication,
 };
 use crate::{
-    commands::{override_config, OverrideConfig},
+    commands::{override_config, socks5_authentication, OverrideConfig},
     error::Socks5ClientError,
 };
 use clap::Args;
@@ -68,6 +69,15 @@ pub(crate) struct Init {
     #[clap(long)]
     host: Option<IpAddr>,
 
+    /// Require the applications connecting to the socks5 listener to authenticate with this username
+    /// and the password set in the `NYM_SOCKS5_PASSWORD` environment variable.
+    /// Only a salted hash of the password is stored in the config.
+    #[clap(long)]
+    socks5_username: Option<String>,
+
+    #[clap(skip)]
+    authentication: Option<Socks5Authentication>,
+
     #[clap(short, long, default_value_t = OutputFormat::default())]
     output: OutputFormat,
 }
@@ -92,6 +102,8 @@ impl From<Init> for OverrideConfig {
             nyxd_urls: init_config.common_args.nyxd_urls,
             enabled_credentials_mode: init_config.common_args.enabled_credentials_mode,
             outfox: false,
+            encrypt_reply_storage: None,
+            authentication: init_config.authentication,
         }
     }
 }
@@ -126,9 +138,11 @@ impl Display for InitResults {
     }
 }
 
-pub(crate) async fn execute(args: Init) -> Result<(), Socks5ClientError> {
+pub(crate) async fn execute(mut args: Init) -> Result<(), Socks5ClientError> {
     eprintln!("Initialising client...");
 
+    args.authentication = socks5_authentication(args.socks5_username.as_deref())?;
+
     let user_agent = nym_bin_common::bin_info!().into();
     let output = args.output;
     let res = initialise_client::<CliSocks5Client>(args, Some(user_agent)).await?;
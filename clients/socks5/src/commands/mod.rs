@@ -7,7 +7,7 @@ use crate::config::old_config_v1_1_20::ConfigV1_1_20;
 use crate::config::old_config_v1_1_20_2::ConfigV1_1_20_2;
 use crate::config::old_config_v1_1_30::ConfigV1_1_30;
 use crate::config::old_config_v1_1_33::ConfigV1_1_33;
-use crate::config::{BaseClientConfig, Config};
+use crate::config::{BaseClientConfig, Config, Socks5Authentication};
 use crate::error::Socks5ClientError;
 use clap::CommandFactory;
 use clap::{Parser, Subcommand};
@@ -111,6 +111,27 @@ pub(crate) struct OverrideConfig {
     nyxd_urls: Option<Vec<url::Url>>,
     enabled_credentials_mode: Option<bool>,
     outfox: bool,
+    encrypt_reply_storage: Option<bool>,
+    authentication: Option<Socks5Authentication>,
+}
+
+/// Environment variable holding the password the applications connecting to the socks5 listener
+/// have to present. It's not accepted as an argument so that it wouldn't be visible in the process list.
+pub(crate) const SOCKS5_PASSWORD_ENV: &str = "NYM_SOCKS5_PASSWORD";
+
+/// Hashes the credentials for the socks5 listener if the username has been provided,
+/// with the password read from [SOCKS5_PASSWORD_ENV].
+pub(crate) fn socks5_authentication(
+    username: Option<&str>,
+) -> Result<Option<Socks5Authentication>, Socks5ClientError> {
+    let Some(username) = username else {
+        return Ok(None);
+    };
+    let password =
+        std::env::var(SOCKS5_PASSWORD_ENV).map_err(|_| Socks5ClientError::MissingSocks5Password)?;
+    Socks5Authentication::new(username, &password)
+        .map(Some)
+        .map_err(|err| Socks5ClientError::Socks5PasswordHashingFailure(err.to_string()))
 }
 
 pub(crate) async fn execute(args: Cli) -> Result<(), Box<dyn Error + Send + Sync>> {
@@ -180,6 +201,7 @@ pub(crate) fn override_config(config: Config, args: OverrideConfig) -> Config {
         .with_optional(Config::with_anonymous_replies, args.use_anonymous_replies)
         .with_optional(Config::with_port, args.port)
         .with_optional(Config::with_ip, args.ip)
+        .with_optional(Config::with_authentication, args.authentication)
         .with_optional_base_custom_env(
             BaseClientConfig::with_custom_nym_apis,
             args.nym_apis,
@@ -196,6 +218,10 @@ pub(crate) fn override_config(config: Config, args: OverrideConfig) -> Config {
             BaseClientConfig::with_disabled_credentials,
             args.enabled_credentials_mode.map(|b| !b),
         )
+        .with_optional_base(
+            BaseClientConfig::with_encrypted_reply_storage,
+            args.encrypt_reply_storage,
+        )
 }
 
 async fn try_upgrade_v1_1_13_config(id: &str) -> Result<bool, Socks5ClientError> {
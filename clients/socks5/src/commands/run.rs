@@ -2,9 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::commands::try_load_current_config;
-use crate::config::Config;
+use crate::config::{Config, Socks5Authentication};
 use crate::{
-    commands::{override_config, OverrideConfig},
+    commands::{override_config, socks5_authentication, OverrideConfig},
     error::Socks5ClientError,
 };
 use clap::Args;
@@ -43,6 +43,19 @@ pub(crate) struct Run {
     #[clap(long)]
     host: Option<IpAddr>,
 
+    /// Encrypt the records of the on-disk reply storage with a key derived from the identity key.
+    #[clap(long)]
+    encrypt_reply_storage: Option<bool>,
+
+    /// Require the applications connecting to the socks5 listener to authenticate with this username
+    /// and the password set in the `NYM_SOCKS5_PASSWORD` environment variable.
+    /// It overrides the credentials stored in the config.
+    #[clap(long)]
+    socks5_username: Option<String>,
+
+    #[clap(skip)]
+    authentication: Option<Socks5Authentication>,
+
     /// Set geo-aware mixnode selection when sending mixnet traffic, for experiments only.
     #[clap(long, hide = true, value_parser = validate_country_group, group="routing")]
     geo_routing: Option<CountryGroup>,
@@ -70,6 +83,8 @@ impl From<Run> for OverrideConfig {
             nyxd_urls: run_config.common_args.nyxd_urls,
             enabled_credentials_mode: run_config.common_args.enabled_credentials_mode,
             outfox: run_config.outfox,
+            encrypt_reply_storage: run_config.encrypt_reply_storage,
+            authentication: run_config.authentication,
         }
     }
 }
@@ -102,9 +117,11 @@ fn version_check(cfg: &Config) -> bool {
     }
 }
 
-pub(crate) async fn execute(args: Run) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+pub(crate) async fn execute(mut args: Run) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     eprintln!("Starting client {}...", args.common_args.id);
 
+    args.authentication = socks5_authentication(args.socks5_username.as_deref())?;
+
     let mut config = try_load_current_config(&args.common_args.id).await?;
     config = override_config(config, OverrideConfig::from(args.clone()));
 
@@ -19,6 +19,7 @@ use std::str::FromStr;
 pub use crate::config::persistence::SocksClientPaths;
 pub use nym_client_core::config::Config as BaseClientConfig;
 pub use nym_socks5_client_core::config::Config as CoreConfig;
+pub use nym_socks5_client_core::config::Socks5Authentication;
 
 pub mod old_config_v1_1_13;
 pub mod old_config_v1_1_20;
@@ -142,6 +143,12 @@ impl Config {
         self
     }
 
+    #[must_use]
+    pub fn with_authentication(mut self, authentication: Socks5Authentication) -> Self {
+        self.core = self.core.with_authentication(Some(authentication));
+        self
+    }
+
     // poor man's 'builder' method
 
     pub fn with_base<F, T>(mut self, f: F, val: T) -> Self
@@ -188,3 +195,29 @@ impl Config {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authentication_survives_saving_the_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        let config = Config::new("socks5-test", "provider");
+        config.save_to(&path).unwrap();
+        assert!(Config::read_from_toml_file(&path)
+            .unwrap()
+            .core
+            .socks5
+            .authentication
+            .is_none());
+
+        let authentication = Socks5Authentication::new("foo", "bar").unwrap();
+        let config = config.with_authentication(authentication.clone());
+        config.save_to(&path).unwrap();
+        let loaded = Config::read_from_toml_file(&path).unwrap();
+        assert_eq!(loaded.core.socks5.authentication, Some(authentication));
+    }
+}
@@ -82,6 +82,26 @@ bind_address = '{{ core.socks5.bind_address }}'
 # Note that some service providers might not support this.
 send_anonymously = {{ core.socks5.send_anonymously }}
 
+{{#if core.socks5.authentication }}
+[core.socks5.authentication]
+# Username the applications connecting to the listener have to present.
+username = '{{ core.socks5.authentication.username }}'
+
+# Salted argon2 hash of the password the applications connecting to the listener have to present.
+password_hash = '{{{ core.socks5.authentication.password_hash }}}'
+{{/if}}
+
+[core.socks5.activity_log]
+# Specifies whether the recently proxied connections should be kept track of (in memory only)
+# to help with figuring out which application is misbehaving.
+enabled = {{ core.socks5.activity_log.enabled }}
+
+# Maximum number of the connections kept in the log.
+retention = {{ core.socks5.activity_log.retention }}
+
+# Specifies whether the destinations should be stored in plaintext rather than hashed.
+plaintext_destinations = {{ core.socks5.activity_log.plaintext_destinations }}
+
 ##### logging configuration options #####
 
 [logging]
@@ -20,6 +20,12 @@ pub enum Socks5ClientError {
     #[error("Fail to bind address")]
     FailToBindAddress,
 
+    #[error("the socks5 username has been provided without setting the password in the NYM_SOCKS5_PASSWORD environment variable")]
+    MissingSocks5Password,
+
+    #[error("failed to hash the socks5 password: {0}")]
+    Socks5PasswordHashingFailure(String),
+
     #[error(transparent)]
     ClientCoreError(#[from] ClientCoreError),
 
@@ -10,4 +10,11 @@ pub enum BandwidthStatusMessage {
 
     #[error("no bandwidth left")]
     NoBandwidth,
+
+    #[error("ticketbook {ticketbook_id} got rejected by the gateway (code: {error_code:?}): {reason}. it has been quarantined")]
+    CredentialRejected {
+        ticketbook_id: i64,
+        error_code: Option<u16>,
+        reason: String,
+    },
 }
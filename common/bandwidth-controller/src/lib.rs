@@ -12,7 +12,7 @@ use crate::utils::{
     ApiClientsWrapper,
 };
 use log::error;
-use nym_credential_storage::models::RetrievedTicketbook;
+use nym_credential_storage::models::{QuarantinedTicketbook, RetrievedTicketbook};
 use nym_credential_storage::storage::Storage;
 use nym_credentials::ecash::bandwidth::CredentialSpendingData;
 use nym_credentials_interface::{
@@ -101,6 +101,26 @@ impl<C, St: Storage> BandwidthController<C, St> {
             .map_err(BandwidthControllerError::credential_storage_error)
     }
 
+    /// Prevents the ticketbook from being used again after it got rejected by the gateway.
+    pub async fn quarantine_ticketbook(
+        &self,
+        info: PreparedCredentialMetadata,
+        error_code: Option<u16>,
+        reason: String,
+    ) -> Result<(), BandwidthControllerError>
+    where
+        <St as Storage>::StorageError: Send + Sync + 'static,
+    {
+        self.storage
+            .quarantine_ticketbook(QuarantinedTicketbook {
+                ticketbook_id: info.ticketbook_id,
+                error_code,
+                reason,
+            })
+            .await
+            .map_err(BandwidthControllerError::credential_storage_error)
+    }
+
     async fn get_aggregate_verification_key(
         &self,
         epoch_id: EpochId,
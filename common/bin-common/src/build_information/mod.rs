@@ -48,6 +48,9 @@ pub struct BinaryBuildInformation {
     // VERGEN_CARGO_TARGET_TRIPLE
     /// Provides the cargo target triple that was used for the build.
     pub cargo_triple: &'static str,
+
+    /// Indicates whether the binary has been built in the strict FIPS/approved-crypto mode.
+    pub fips_mode: bool,
 }
 
 impl BinaryBuildInformation {
@@ -71,6 +74,7 @@ impl BinaryBuildInformation {
             rustc_channel: env!("VERGEN_RUSTC_CHANNEL"),
             cargo_profile,
             cargo_triple: env!("VERGEN_CARGO_TARGET_TRIPLE"),
+            fips_mode: cfg!(feature = "fips"),
         }
     }
 
@@ -101,6 +105,7 @@ impl BinaryBuildInformation {
             rustc_channel: env!("VERGEN_RUSTC_CHANNEL"),
             cargo_profile,
             cargo_triple: env!("VERGEN_CARGO_TARGET_TRIPLE"),
+            fips_mode: cfg!(feature = "fips"),
         }
     }
 
@@ -116,6 +121,7 @@ impl BinaryBuildInformation {
             rustc_channel: self.rustc_channel.to_owned(),
             cargo_profile: self.cargo_profile.to_owned(),
             cargo_triple: self.cargo_triple.to_owned(),
+            fips_mode: self.fips_mode,
         }
     }
 
@@ -168,6 +174,10 @@ pub struct BinaryBuildInformationOwned {
     /// Provides the cargo target triple that was used for the build.
     #[serde(default = "unknown")]
     pub cargo_triple: String,
+
+    /// Indicates whether the binary has been built in the strict FIPS/approved-crypto mode.
+    #[serde(default)]
+    pub fips_mode: bool,
 }
 
 fn unknown() -> String {
@@ -188,6 +198,7 @@ impl Display for BinaryBuildInformationOwned {
 {:<20}{}
 {:<20}{}
 {:<20}{}
+{:<20}{}
 "#,
             "Binary Name:",
             self.binary_name,
@@ -207,6 +218,8 @@ impl Display for BinaryBuildInformationOwned {
             self.rustc_channel,
             "cargo Profile:",
             self.cargo_profile,
+            "FIPS Mode:",
+            self.fips_mode,
         )
     }
 }
@@ -20,6 +20,7 @@ pub const DEFAULT_PUBLIC_ENCRYPTION_KEY_FILENAME: &str = "public_encryption.pem"
 pub const DEFAULT_ACK_KEY_FILENAME: &str = "ack_key.pem";
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields)]
 pub struct CommonClientPaths {
     pub keys: ClientKeysPaths,
@@ -49,6 +50,7 @@ impl CommonClientPaths {
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 pub struct ClientKeysPaths {
     /// Path to file containing private identity key.
     pub private_identity_key_file: PathBuf,
@@ -1,6 +1,7 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::layered::ConfigSource;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -17,3 +18,31 @@ pub enum InvalidTrafficModeFailure {
     #[error("attempted to set medium toggle traffic mode with no cover flag")]
     MediumToggleWithNoCover,
 }
+
+#[derive(Error, Debug)]
+#[error("'{raw}' is not a valid 'major.minor.patch' node version")]
+pub struct InvalidNodeVersion {
+    pub raw: String,
+}
+
+#[derive(Error, Debug)]
+pub enum ConfigLayeringError {
+    #[error("failed to serialize the base configuration: {source}")]
+    SerializationFailure {
+        #[source]
+        source: toml::ser::Error,
+    },
+
+    #[error("failed to deserialize the layered configuration: {source}")]
+    DeserializationFailure {
+        #[source]
+        source: toml::de::Error,
+    },
+
+    #[error("the value of '{option}' set by the {origin} is invalid: {reason}")]
+    InvalidOverride {
+        option: String,
+        origin: ConfigSource,
+        reason: String,
+    },
+}
@@ -0,0 +1,364 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Layered loading of the client configuration, so that the values could be tuned without
+//! having to template the config files, for example in containerised deployments.
+//!
+//! The layers are applied in the following order, with the later ones taking precedence:
+//! 1. the config file (or the built-in defaults if there's no file),
+//! 2. the environment variables,
+//! 3. the overrides explicitly set on the [`LayeredConfigLoader`].
+//!
+//! Each option is identified by its path within the config, e.g. `debug.traffic.average_packet_delay`.
+//! The corresponding environment variable is made of the prefix (`NYM_CLIENT` by default)
+//! and the uppercased path segments, all separated by double underscores,
+//! e.g. `NYM_CLIENT__DEBUG__TRAFFIC__AVERAGE_PACKET_DELAY=100ms`.
+//! The values are specified in the same format as in the config file, with the exception of lists,
+//! whose elements are separated by commas, e.g. `NYM_CLIENT__CLIENT__NYM_API_URLS=https://a.com,https://b.com`.
+
+use crate::error::ConfigLayeringError;
+use crate::Config;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+pub const DEFAULT_ENV_PREFIX: &str = "NYM_CLIENT";
+const ENV_SEPARATOR: &str = "__";
+
+/// Origin of a configuration value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// The built-in default value.
+    Defaults,
+
+    /// The config file at the specified path.
+    File(PathBuf),
+
+    /// The environment variable of the specified name.
+    Environment(String),
+
+    /// An override explicitly set on the [`LayeredConfigLoader`].
+    Override,
+}
+
+impl Display for ConfigSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Defaults => write!(f, "defaults"),
+            ConfigSource::File(path) => write!(f, "file '{}'", path.display()),
+            ConfigSource::Environment(variable) => write!(f, "environment variable '{variable}'"),
+            ConfigSource::Override => write!(f, "explicit override"),
+        }
+    }
+}
+
+/// Report of where the effective configuration values came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigSources {
+    base: ConfigSource,
+    overridden: BTreeMap<String, ConfigSource>,
+}
+
+impl Default for ConfigSources {
+    fn default() -> Self {
+        ConfigSources {
+            base: ConfigSource::Defaults,
+            overridden: BTreeMap::new(),
+        }
+    }
+}
+
+impl ConfigSources {
+    /// Source of all the values that have not been overridden.
+    pub fn base(&self) -> &ConfigSource {
+        &self.base
+    }
+
+    /// Returns the source of the specified option, e.g. `debug.traffic.average_packet_delay`.
+    pub fn source_of(&self, option: &str) -> &ConfigSource {
+        self.overridden.get(option).unwrap_or(&self.base)
+    }
+
+    /// Returns all the overridden options alongside their sources.
+    pub fn overridden(&self) -> impl Iterator<Item = (&str, &ConfigSource)> {
+        self.overridden
+            .iter()
+            .map(|(option, source)| (option.as_str(), source))
+    }
+}
+
+impl Display for ConfigSources {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "base configuration: {}", self.base)?;
+        for (option, source) in &self.overridden {
+            write!(f, "\n{option}: {source}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Loader combining the base configuration with the environment variables and explicit overrides.
+#[derive(Debug, Clone)]
+pub struct LayeredConfigLoader {
+    base: Config,
+    base_source: ConfigSource,
+    env_prefix: String,
+    environment: Option<Vec<(String, String)>>,
+    overrides: Vec<(String, String)>,
+}
+
+impl LayeredConfigLoader {
+    /// Starts with the provided configuration consisting of the built-in defaults.
+    pub fn new(defaults: Config) -> Self {
+        LayeredConfigLoader {
+            base: defaults,
+            base_source: ConfigSource::Defaults,
+            env_prefix: DEFAULT_ENV_PREFIX.to_string(),
+            environment: None,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Starts with the configuration that has been read from the file at the specified path.
+    pub fn from_file_config<P: Into<PathBuf>>(config: Config, path: P) -> Self {
+        LayeredConfigLoader {
+            base_source: ConfigSource::File(path.into()),
+            ..Self::new(config)
+        }
+    }
+
+    #[must_use]
+    pub fn with_env_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.env_prefix = prefix.into();
+        self
+    }
+
+    /// Uses the provided variables instead of the process environment.
+    #[must_use]
+    pub fn with_environment<I, K, V>(mut self, variables: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.environment = Some(
+            variables
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        );
+        self
+    }
+
+    /// Explicitly sets the value of the specified option, e.g. `debug.traffic.average_packet_delay`,
+    /// taking precedence over both the base configuration and the environment.
+    #[must_use]
+    pub fn with_override<S1: Into<String>, S2: Into<String>>(
+        mut self,
+        option: S1,
+        value: S2,
+    ) -> Self {
+        self.overrides.push((option.into(), value.into()));
+        self
+    }
+
+    fn env_overrides(&self) -> Vec<(String, String, ConfigSource)> {
+        let prefix = format!("{}{ENV_SEPARATOR}", self.env_prefix);
+        let variables = match &self.environment {
+            Some(variables) => variables.clone(),
+            None => std::env::vars().collect(),
+        };
+
+        let mut overrides = variables
+            .into_iter()
+            .filter_map(|(variable, value)| {
+                let option = variable
+                    .strip_prefix(&prefix)?
+                    .split(ENV_SEPARATOR)
+                    .map(|segment| segment.to_lowercase())
+                    .collect::<Vec<_>>()
+                    .join(".");
+                Some((option, value, ConfigSource::Environment(variable)))
+            })
+            .collect::<Vec<_>>();
+
+        // make sure the result doesn't depend on the iteration order of the environment
+        overrides.sort_by(|a, b| a.0.cmp(&b.0));
+        overrides
+    }
+
+    pub fn load(self) -> Result<Config, ConfigLayeringError> {
+        let mut layered = toml::Value::try_from(&self.base)
+            .map_err(|source| ConfigLayeringError::SerializationFailure { source })?;
+
+        let explicit = self
+            .overrides
+            .iter()
+            .map(|(option, value)| (option.clone(), value.clone(), ConfigSource::Override));
+
+        let mut overridden = BTreeMap::new();
+        for (option, raw, origin) in self.env_overrides().into_iter().chain(explicit) {
+            // the overrides are validated one by one so that any failure is attributed to the right option
+            set_option(&mut layered, &option, &raw).map_err(|reason| {
+                ConfigLayeringError::InvalidOverride {
+                    option: option.clone(),
+                    origin: origin.clone(),
+                    reason,
+                }
+            })?;
+            overridden.insert(option, origin);
+        }
+
+        let mut config: Config = layered
+            .try_into()
+            .map_err(|source| ConfigLayeringError::DeserializationFailure { source })?;
+        config.sources = ConfigSources {
+            base: self.base_source,
+            overridden,
+        };
+        Ok(config)
+    }
+}
+
+fn set_option(config: &mut toml::Value, option: &str, raw: &str) -> Result<(), String> {
+    let segments = option.split('.').collect::<Vec<_>>();
+    let Some((field, parents)) = segments.split_last() else {
+        return Err("the option is empty".to_string());
+    };
+
+    let candidates = match get_table_mut(config, parents)?.get(*field) {
+        Some(toml::Value::Table(_)) => {
+            return Err(format!("'{field}' is a config section, not an option"))
+        }
+        Some(current) => vec![parse_like(current, raw)?],
+        // unset optional values don't appear in the serialized config, so we don't know their type
+        None => infer_candidates(raw),
+    };
+
+    let mut reason = String::new();
+    for candidate in candidates {
+        let mut attempt = config.clone();
+        get_table_mut(&mut attempt, parents)?.insert(field.to_string(), candidate);
+        match attempt.clone().try_into::<Config>() {
+            Ok(_) => {
+                *config = attempt;
+                return Ok(());
+            }
+            Err(err) => reason = err.to_string(),
+        }
+    }
+    Err(reason)
+}
+
+fn get_table_mut<'a>(
+    config: &'a mut toml::Value,
+    path: &[&str],
+) -> Result<&'a mut toml::Table, String> {
+    let mut table = config;
+    for segment in path {
+        table = table
+            .get_mut(*segment)
+            .filter(|value| value.is_table())
+            .ok_or_else(|| format!("'{segment}' is not a valid config section"))?;
+    }
+    table
+        .as_table_mut()
+        .ok_or_else(|| "the option does not belong to a config section".to_string())
+}
+
+fn infer_candidates(raw: &str) -> Vec<toml::Value> {
+    let mut candidates = vec![toml::Value::String(raw.to_string())];
+    if let Ok(boolean) = raw.parse() {
+        candidates.push(toml::Value::Boolean(boolean))
+    }
+    if let Ok(integer) = raw.parse() {
+        candidates.push(toml::Value::Integer(integer))
+    }
+    if let Ok(float) = raw.parse() {
+        candidates.push(toml::Value::Float(float))
+    }
+    candidates
+}
+
+// parses the raw value into the same type as the current value of the option
+fn parse_like(current: &toml::Value, raw: &str) -> Result<toml::Value, String> {
+    let invalid = |typ: &str| format!("'{raw}' is not a valid {typ}");
+    Ok(match current {
+        toml::Value::Boolean(_) => {
+            toml::Value::Boolean(raw.parse().map_err(|_| invalid("boolean"))?)
+        }
+        toml::Value::Integer(_) => {
+            toml::Value::Integer(raw.parse().map_err(|_| invalid("integer"))?)
+        }
+        toml::Value::Float(_) => toml::Value::Float(raw.parse().map_err(|_| invalid("float"))?),
+        toml::Value::Array(_) => toml::Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|element| !element.is_empty())
+                .map(|element| toml::Value::String(element.to_string()))
+                .collect(),
+        ),
+        _ => toml::Value::String(raw.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn later_layers_take_precedence() {
+        let config =
+            LayeredConfigLoader::from_file_config(Config::new("foo", "1.0.0"), "/tmp/config.toml")
+                .with_environment([
+                    ("NYM_CLIENT__DEBUG__TRAFFIC__AVERAGE_PACKET_DELAY", "10ms"),
+                    (
+                        "NYM_CLIENT__DEBUG__COVER_TRAFFIC__DISABLE_LOOP_COVER_TRAFFIC_STREAM",
+                        "true",
+                    ),
+                    ("UNRELATED", "42"),
+                ])
+                .with_override("debug.traffic.average_packet_delay", "20ms")
+                .load()
+                .unwrap();
+
+        assert_eq!(
+            config.debug.traffic.average_packet_delay,
+            Duration::from_millis(20)
+        );
+        assert!(config.debug.cover_traffic.disable_loop_cover_traffic_stream);
+
+        let sources = config.effective_sources();
+        assert_eq!(
+            sources.source_of("debug.traffic.average_packet_delay"),
+            &ConfigSource::Override
+        );
+        assert_eq!(
+            sources.source_of("debug.cover_traffic.disable_loop_cover_traffic_stream"),
+            &ConfigSource::Environment(
+                "NYM_CLIENT__DEBUG__COVER_TRAFFIC__DISABLE_LOOP_COVER_TRAFFIC_STREAM".to_string()
+            )
+        );
+        assert_eq!(
+            sources.source_of("client.id"),
+            &ConfigSource::File("/tmp/config.toml".into())
+        );
+    }
+
+    #[test]
+    fn invalid_overrides_are_rejected() {
+        let base = Config::new("foo", "1.0.0");
+
+        let unknown = LayeredConfigLoader::new(base.clone())
+            .with_environment([("NYM_CLIENT__DEBUG__NOT_A_SECTION__FOO", "1")])
+            .load();
+        assert!(unknown.is_err());
+
+        let malformed = LayeredConfigLoader::new(base)
+            .with_environment(Vec::<(String, String)>::new())
+            .with_override("debug.traffic.use_versioned_message_envelope", "maybe")
+            .load();
+        assert!(malformed.is_err());
+    }
+}
@@ -1,27 +1,37 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::error::InvalidNodeVersion;
 use nym_config::defaults::NymNetworkDetails;
 use nym_sphinx_addressing::Recipient;
 use nym_sphinx_params::{PacketSize, PacketType};
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::time::Duration;
 use url::Url;
 
 #[cfg(feature = "disk-persistence")]
 pub mod disk_persistence;
 pub mod error;
+pub mod layered;
 pub mod old;
 
-pub use error::ConfigUpgradeFailure;
+pub use error::{ConfigLayeringError, ConfigUpgradeFailure};
+pub use layered::{ConfigSource, ConfigSources, LayeredConfigLoader};
 
 // 'DEBUG'
 const DEFAULT_ACK_WAIT_MULTIPLIER: f64 = 1.5;
 
 const DEFAULT_ACK_WAIT_ADDITION: Duration = Duration::from_millis(1_500);
+const DEFAULT_RETRANSMISSION_BACKOFF_MULTIPLIER: f64 = 1.0;
+const DEFAULT_MAXIMUM_RETRANSMISSION_TIMEOUT: Duration = Duration::from_secs(10 * 60);
 const DEFAULT_LOOP_COVER_STREAM_AVERAGE_DELAY: Duration = Duration::from_millis(200);
 const DEFAULT_MESSAGE_STREAM_AVERAGE_DELAY: Duration = Duration::from_millis(20);
 const DEFAULT_AVERAGE_PACKET_DELAY: Duration = Duration::from_millis(50);
+
+const DEFAULT_SCHEDULING_BURST_SIZE: u32 = 8;
 const DEFAULT_TOPOLOGY_REFRESH_RATE: Duration = Duration::from_secs(5 * 60); // every 5min
 const DEFAULT_TOPOLOGY_RESOLUTION_TIMEOUT: Duration = Duration::from_millis(5_000);
 
@@ -30,13 +40,23 @@ const DEFAULT_MIN_MIXNODE_PERFORMANCE: u8 = 50;
 const DEFAULT_MIN_GATEWAY_PERFORMANCE: u8 = 50;
 
 const DEFAULT_MAX_STARTUP_GATEWAY_WAITING_PERIOD: Duration = Duration::from_secs(70 * 60); // 70min -> full epoch (1h) + a bit of overhead
+const DEFAULT_EPOCH_TRANSITION_MAX_HOLD: Duration = Duration::from_secs(30);
 
 // Set this to a high value for now, so that we don't risk sporadic timeouts that might cause
 // bought bandwidth tokens to not have time to be spent; Once we remove the gateway from the
 // bandwidth bridging protocol, we can come back to a smaller timeout value
 const DEFAULT_GATEWAY_RESPONSE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+const DEFAULT_NETWORK_MONITOR_INTERVAL: Duration = Duration::from_secs(2);
+
+const DEFAULT_MAX_QUEUED_STARTUP_MESSAGES: usize = 64;
+const DEFAULT_STARTUP_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+const DEFAULT_PROTOCOL_STATS_EXCHANGE_INTERVAL: Duration = Duration::from_secs(30);
+const DEFAULT_PROTOCOL_STATS_LOSS_SLOWDOWN_THRESHOLD: f32 = 0.05;
+const DEFAULT_PROTOCOL_STATS_QUEUE_DEPTH_SLOWDOWN_THRESHOLD: u32 = 1000;
 
 const DEFAULT_COVER_TRAFFIC_PRIMARY_SIZE_RATIO: f64 = 0.70;
+const DEFAULT_MINIMUM_ADAPTIVE_COVER_TRAFFIC_RATIO: f64 = 0.1;
 
 // reply-surbs related:
 
@@ -52,6 +72,12 @@ const DEFAULT_MAXIMUM_REPLY_SURB_REQUEST_SIZE: u32 = 100;
 
 const DEFAULT_MAXIMUM_ALLOWED_SURB_REQUEST_SIZE: u32 = 500;
 
+const DEFAULT_REPLY_SURB_REQUEST_RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+const DEFAULT_MAXIMUM_REPLY_SURB_REQUESTS_PER_WINDOW: u32 = 30;
+// with the default maximum request size that's 4 max-sized requests per correspondent
+const DEFAULT_MAXIMUM_REQUESTED_SURBS_PER_WINDOW: u32 = 2000;
+const DEFAULT_MAXIMUM_TOTAL_REQUESTED_SURBS_PER_WINDOW: u32 = 10000;
+
 const DEFAULT_MAXIMUM_REPLY_SURB_REREQUEST_WAITING_PERIOD: Duration = Duration::from_secs(10);
 const DEFAULT_MAXIMUM_REPLY_SURB_DROP_WAITING_PERIOD: Duration = Duration::from_secs(5 * 60);
 
@@ -65,12 +91,18 @@ use crate::error::InvalidTrafficModeFailure;
 pub use nym_country_group::CountryGroup;
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub client: Client,
 
     #[serde(default)]
     pub debug: DebugConfig,
+
+    /// Origins of the effective configuration values, populated by the [`LayeredConfigLoader`].
+    #[cfg_attr(feature = "config_schema", schemars(skip))]
+    #[serde(skip)]
+    sources: ConfigSources,
 }
 
 impl Config {
@@ -82,11 +114,23 @@ impl Config {
         Config {
             client: Client::new_default(id, version),
             debug: Default::default(),
+            sources: Default::default(),
         }
     }
 
     pub fn from_client_config(client: Client, debug: DebugConfig) -> Self {
-        Config { client, debug }
+        Config {
+            client,
+            debug,
+            sources: Default::default(),
+        }
+    }
+
+    /// Returns the report of where the effective configuration values came from.
+    /// Unless the config has been loaded with the [`LayeredConfigLoader`], all of them are attributed
+    /// to the built-in defaults.
+    pub fn effective_sources(&self) -> &ConfigSources {
+        &self.sources
     }
 
     pub fn validate(&self) -> bool {
@@ -103,6 +147,15 @@ impl Config {
         self
     }
 
+    pub fn with_encrypted_reply_storage(mut self, encrypted: bool) -> Self {
+        self.debug.reply_surbs.storage_encryption = if encrypted {
+            ReplySurbStorageEncryption::IdentityKey
+        } else {
+            ReplySurbStorageEncryption::Disabled
+        };
+        self
+    }
+
     pub fn with_custom_nyxd(mut self, urls: Vec<Url>) -> Self {
         self.client.nyxd_urls = urls;
         self
@@ -249,6 +302,13 @@ impl Config {
         self.debug.topology.topology_structure = topology_structure;
     }
 
+    /// Makes the client use the topology loaded from the provided file rather than from nym-api.
+    pub fn with_topology_file<P: Into<PathBuf>>(mut self, topology_file: P) -> Self {
+        self.client.topology_file = Some(topology_file.into());
+        self.set_topology_structure(TopologyStructure::File);
+        self
+    }
+
     pub fn with_no_per_hop_delays(mut self, no_per_hop_delays: bool) -> Self {
         if no_per_hop_delays {
             self.set_no_per_hop_delays()
@@ -292,6 +352,7 @@ impl Config {
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 // note: the deny_unknown_fields is VITAL here to allow upgrades from v1.1.20_2
 #[serde(deny_unknown_fields)]
 pub struct Client {
@@ -314,6 +375,11 @@ pub struct Client {
     /// Addresses to APIs running on validator from which the client gets the view of the network.
     #[serde(alias = "validator_api_urls")]
     pub nym_api_urls: Vec<Url>,
+
+    /// Path to the local (JSON or TOML) topology description used instead of the one
+    /// retrieved from nym-api if `File` topology structure is specified.
+    #[serde(default)]
+    pub topology_file: Option<PathBuf>,
 }
 
 impl Client {
@@ -340,6 +406,7 @@ impl Client {
             disabled_credentials_mode: true,
             nyxd_urls,
             nym_api_urls,
+            topology_file: None,
         }
     }
 
@@ -356,17 +423,20 @@ impl Client {
             disabled_credentials_mode,
             nyxd_urls,
             nym_api_urls,
+            topology_file: None,
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(default, deny_unknown_fields)]
 pub struct Traffic {
     /// The parameter of Poisson distribution determining how long, on average,
     /// sent packet is going to be delayed at any given mix node.
     /// So for a packet going through three mix nodes, on average, it will take three times this value
     /// until the packet reaches its destination.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub average_packet_delay: Duration,
 
@@ -374,6 +444,7 @@ pub struct Traffic {
     /// it is going to take another 'real traffic stream' message to be sent.
     /// If no real packets are available and cover traffic is enabled,
     /// a loop cover message is sent instead in order to preserve the rate.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub message_sending_average_delay: Duration,
 
@@ -383,14 +454,31 @@ pub struct Traffic {
 
     /// Specifies the packet size used for sent messages.
     /// Do not override it unless you understand the consequences of that change.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     pub primary_packet_size: PacketSize,
 
     /// Specifies the optional auxiliary packet size for optimizing message streams.
     /// Note that its use decreases overall anonymity.
     /// Do not set it it unless you understand the consequences of that change.
+    #[cfg_attr(feature = "config_schema", schemars(with = "Option<String>"))]
     pub secondary_packet_size: Option<PacketSize>,
 
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     pub packet_type: PacketType,
+
+    /// Controls whether outgoing messages should be wrapped in the versioned envelope,
+    /// allowing usage of features that depend on the message version or capability flags.
+    /// Note that receivers that predate the envelope versioning are unable to parse such messages.
+    pub use_versioned_message_envelope: bool,
+
+    /// Specifies the model used for scheduling packets of both the real traffic stream
+    /// and the loop cover traffic stream. Note that any model other than `Poisson` decreases overall anonymity.
+    /// Do not change it unless you understand the consequences of that change.
+    pub scheduling: TrafficScheduling,
+
+    /// Specifies the number of packets sent back-to-back in a single burst.
+    /// Only applicable if `scheduling` is set to `Bursty`.
+    pub scheduling_burst_size: u32,
 }
 
 impl Traffic {
@@ -415,15 +503,38 @@ impl Default for Traffic {
             primary_packet_size: PacketSize::RegularPacket,
             secondary_packet_size: None,
             packet_type: PacketType::Mix,
+            use_versioned_message_envelope: false,
+            scheduling: TrafficScheduling::Poisson,
+            scheduling_burst_size: DEFAULT_SCHEDULING_BURST_SIZE,
         }
     }
 }
 
+/// Model determining the delays between subsequent packets sent by the traffic streams.
+/// Regardless of the model, the average sending rate stays the same.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
+pub enum TrafficScheduling {
+    /// The delays are sampled from the exponential distribution, meaning the packets are sent
+    /// according to a Poisson process. It's the model the anonymity properties of the mixnet rely on.
+    #[default]
+    Poisson,
+
+    /// The packets are sent at fixed intervals.
+    ConstantRate,
+
+    /// The packets are sent in back-to-back bursts, with the delays between the bursts
+    /// sampled from the exponential distribution.
+    Bursty,
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(default, deny_unknown_fields)]
 pub struct CoverTraffic {
     /// The parameter of Poisson distribution determining how long, on average,
     /// it is going to take for another loop cover traffic message to be sent.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub loop_cover_traffic_average_delay: Duration,
 
@@ -434,6 +545,22 @@ pub struct CoverTraffic {
     /// Controls whether the dedicated loop cover traffic stream should be enabled.
     /// (and sending packets, on average, every [Self::loop_cover_traffic_average_delay])
     pub disable_loop_cover_traffic_stream: bool,
+
+    /// Specifies the fraction of cover traffic packets that, rather than looping back to this client,
+    /// are sent to clients we have recently sent real messages to (which discard them upon receipt).
+    /// This makes genuine conversations less distinguishable at the cost of some additional traffic
+    /// towards our correspondents. Set to 0 (the default) to disable it.
+    pub correspondent_cover_traffic_ratio: f64,
+
+    /// Controls whether the loop cover traffic stream should adapt to the volume of the real traffic.
+    /// If enabled, it sends fewer packets while real messages are being sent and more while idle,
+    /// so that the combined rate of real and loop cover packets stays close to the rate implied
+    /// by [Self::loop_cover_traffic_average_delay] rather than exceeding it.
+    pub adaptive_loop_cover_traffic: bool,
+
+    /// Specifies the fraction of the loop cover traffic rate that is sent regardless of the volume
+    /// of the real traffic. Only applicable if [Self::adaptive_loop_cover_traffic] is enabled.
+    pub minimum_adaptive_cover_traffic_ratio: f64,
 }
 
 impl Default for CoverTraffic {
@@ -442,34 +569,136 @@ impl Default for CoverTraffic {
             loop_cover_traffic_average_delay: DEFAULT_LOOP_COVER_STREAM_AVERAGE_DELAY,
             cover_traffic_primary_size_ratio: DEFAULT_COVER_TRAFFIC_PRIMARY_SIZE_RATIO,
             disable_loop_cover_traffic_stream: false,
+            correspondent_cover_traffic_ratio: 0.0,
+            adaptive_loop_cover_traffic: false,
+            minimum_adaptive_cover_traffic_ratio: DEFAULT_MINIMUM_ADAPTIVE_COVER_TRAFFIC_RATIO,
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(default, deny_unknown_fields)]
 pub struct GatewayConnection {
     /// How long we're willing to wait for a response to a message sent to the gateway,
     /// before giving up on it.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub gateway_response_timeout: Duration,
+
+    /// Specifies whether the client should periodically check whether the local network has changed
+    /// (for example after switching to a different Wi-Fi) in order to immediately re-establish
+    /// the gateway connection. Embedders with access to platform connectivity notifications
+    /// can instead report the changes directly.
+    /// Note: this setting has no effect in wasm environments.
+    pub monitor_network_changes: bool,
+
+    /// Defines how often the client is going to check the local network for changes.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
+    #[serde(with = "humantime_serde")]
+    pub network_monitor_interval: Duration,
 }
 
 impl Default for GatewayConnection {
     fn default() -> Self {
         GatewayConnection {
             gateway_response_timeout: DEFAULT_GATEWAY_RESPONSE_TIMEOUT,
+            monitor_network_changes: true,
+            network_monitor_interval: DEFAULT_NETWORK_MONITOR_INTERVAL,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
+#[serde(default, deny_unknown_fields)]
+pub struct Startup {
+    /// Defines the maximum amount of time the client can spend on its startup, i.e. on setting up
+    /// its gateway, obtaining the initial network topology and establishing the gateway connection.
+    /// If not set, the startup is unbounded.
+    #[cfg_attr(feature = "config_schema", schemars(with = "Option<String>"))]
+    #[serde(with = "humantime_serde")]
+    pub deadline: Option<Duration>,
+
+    /// Specifies whether the client should start in a degraded mode, rather than fail,
+    /// if it exceeds the startup deadline while obtaining the network topology or connecting to its gateway.
+    /// In that mode the client input is queued while the remaining startup is retried in the background.
+    /// Note: the gateway setup itself always has to complete within the deadline
+    /// as the client address is not known before then.
+    pub allow_degraded_start: bool,
+
+    /// Defines the maximum number of input messages that can be queued while the client
+    /// is running in the degraded mode or is waiting for a routable topology after an offline-tolerant start.
+    pub max_queued_messages: usize,
+
+    /// Defines how long the client is going to wait before retrying a failed startup stage
+    /// while running in the degraded mode.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
+    #[serde(with = "humantime_serde")]
+    pub retry_interval: Duration,
+}
+
+impl Default for Startup {
+    fn default() -> Self {
+        Startup {
+            deadline: None,
+            allow_degraded_start: false,
+            max_queued_messages: DEFAULT_MAX_QUEUED_STARTUP_MESSAGES,
+            retry_interval: DEFAULT_STARTUP_RETRY_INTERVAL,
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
+#[serde(default, deny_unknown_fields)]
+pub struct ProtocolStats {
+    /// Specifies whether the client should periodically exchange protocol statistics with its gateway
+    /// and slow down its sending rate based on the gateway's hints.
+    /// Note that unless any of the `share_*` options is enabled, nothing about the client is revealed.
+    pub enabled: bool,
+
+    /// Defines how often the statistics are exchanged.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
+    #[serde(with = "humantime_serde")]
+    pub exchange_interval: Duration,
+
+    /// Specifies whether the fraction of the client's packets that had to be retransmitted
+    /// is shared with the gateway.
+    pub share_observed_loss: bool,
+
+    /// Specifies whether the number of packets waiting in the client's send queue is shared with the gateway.
+    pub share_queue_depth: bool,
+
+    /// The packet loss reported by the gateway at or above which the client slows down its sending rate.
+    pub loss_slowdown_threshold: f32,
+
+    /// The queue depth reported by the gateway at or above which the client slows down its sending rate.
+    pub queue_depth_slowdown_threshold: u32,
+}
+
+impl Default for ProtocolStats {
+    fn default() -> Self {
+        ProtocolStats {
+            enabled: false,
+            exchange_interval: DEFAULT_PROTOCOL_STATS_EXCHANGE_INTERVAL,
+            share_observed_loss: false,
+            share_queue_depth: false,
+            loss_slowdown_threshold: DEFAULT_PROTOCOL_STATS_LOSS_SLOWDOWN_THRESHOLD,
+            queue_depth_slowdown_threshold: DEFAULT_PROTOCOL_STATS_QUEUE_DEPTH_SLOWDOWN_THRESHOLD,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(default, deny_unknown_fields)]
 pub struct Acknowledgements {
     /// The parameter of Poisson distribution determining how long, on average,
     /// sent acknowledgement is going to be delayed at any given mix node.
     /// So for an ack going through three mix nodes, on average, it will take three times this value
     /// until the packet reaches its destination.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub average_ack_delay: Duration,
 
@@ -481,6 +710,7 @@ pub struct Acknowledgements {
     /// Value added to the expected round trip time of an acknowledgement packet before
     /// it is assumed it was lost and retransmission of the data packet happens.
     /// In an ideal network with 0 latency, this value would have been 0.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub ack_wait_addition: Duration,
 }
@@ -496,16 +726,70 @@ impl Default for Acknowledgements {
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
+#[serde(default, deny_unknown_fields)]
+pub struct Retransmission {
+    /// Defines how long the client waits for the acknowledgement of the first transmission of a fragment
+    /// before retransmitting it.
+    /// If not set, it is derived from the expected round trip time of the packet
+    /// using `ack_wait_multiplier` and `ack_wait_addition`.
+    #[cfg_attr(feature = "config_schema", schemars(with = "Option<String>"))]
+    #[serde(with = "humantime_serde")]
+    pub initial_timeout: Option<Duration>,
+
+    /// Value the acknowledgement timeout is multiplied by with every subsequent retransmission
+    /// of the same fragment, so that the client backs off when the network is struggling.
+    /// The value of 1 disables the backoff.
+    pub backoff_multiplier: f64,
+
+    /// Upper bound on the acknowledgement timeout regardless of the number of retransmissions.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
+    #[serde(with = "humantime_serde")]
+    pub maximum_timeout: Duration,
+
+    /// Maximum number of times a fragment is going to be sent, including its first transmission,
+    /// before the client gives up on it (and reports the delivery of the whole message as failed).
+    /// If not set, the fragments are retransmitted until they get acknowledged.
+    pub max_attempts: Option<u32>,
+
+    /// Fraction of the acknowledgement timeout by which it is randomly extended or shortened,
+    /// so that the retransmissions of fragments lost at the same time are spread out.
+    /// It must be within the [0, 1] range.
+    pub jitter: f64,
+}
+
+impl Retransmission {
+    pub fn validate(&self) -> bool {
+        self.backoff_multiplier >= 1.0 && (0.0..=1.0).contains(&self.jitter)
+    }
+}
+
+impl Default for Retransmission {
+    fn default() -> Self {
+        Retransmission {
+            initial_timeout: None,
+            backoff_multiplier: DEFAULT_RETRANSMISSION_BACKOFF_MULTIPLIER,
+            maximum_timeout: DEFAULT_MAXIMUM_RETRANSMISSION_TIMEOUT,
+            max_attempts: None,
+            jitter: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(default, deny_unknown_fields)]
 pub struct Topology {
     /// The uniform delay every which clients are querying the directory server
     /// to try to obtain a compatible network topology to send sphinx packets through.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub topology_refresh_rate: Duration,
 
     /// During topology refresh, test packets are sent through every single possible network
     /// path. This timeout determines waiting period until it is decided that the packet
     /// did not reach its destination.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub topology_resolution_timeout: Duration,
 
@@ -516,6 +800,7 @@ pub struct Topology {
 
     /// Defines how long the client is going to wait on startup for its gateway to come online,
     /// before abandoning the procedure.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub max_startup_gateway_waiting_period: Duration,
 
@@ -529,21 +814,110 @@ pub struct Topology {
     /// Specifies a minimum performance of a gateway that is used on route construction.
     /// This setting is only applicable when `NymApi` topology is used.
     pub minimum_gateway_performance: u8,
+
+    /// Specifies whether standby gateways, i.e. ones that are currently not meeting the performance
+    /// requirements of the network, should still be included in the topology as failover candidates
+    /// so that clients behind them remain reachable during active set churn.
+    /// This setting is only applicable when `NymApi` topology is used.
+    pub include_standby_gateways: bool,
+
+    /// Specifies the minimum version of a mixnode that is used on route construction.
+    /// Nodes outside the version range compatible with this client are always ignored.
+    #[cfg_attr(feature = "config_schema", schemars(with = "Option<String>"))]
+    pub minimum_mixnode_version: Option<NodeVersionRequirement>,
+
+    /// Specifies the minimum version of a gateway that is used on route construction.
+    /// Nodes outside the version range compatible with this client are always ignored.
+    #[cfg_attr(feature = "config_schema", schemars(with = "Option<String>"))]
+    pub minimum_gateway_version: Option<NodeVersionRequirement>,
+
+    /// Specifies whether the client should briefly hold non-urgent real traffic
+    /// and refresh its topology when the network transitions between epochs,
+    /// as the active set might get rotated at that point.
+    /// Note: this setting has no effect if the poisson traffic stream is disabled.
+    pub hold_traffic_during_epoch_transition: bool,
+
+    /// Defines the maximum amount of time the client is going to hold its non-urgent traffic
+    /// while waiting for the new epoch to begin.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
+    #[serde(with = "humantime_serde")]
+    pub epoch_transition_max_hold: Duration,
+
+    /// Specifies whether the client should still start if it can't obtain a routable network topology,
+    /// for example because it's currently offline. In that case any input messages are queued
+    /// and they're going to be sent once the topology refresher manages to get a routable view of the network.
+    /// Note: this setting has no effect if topology refreshing is disabled.
+    pub offline_tolerant_startup: bool,
+}
+
+/// A `major.minor.patch` version a node must be running (at least) to be used by the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct NodeVersionRequirement {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl FromStr for NodeVersionRequirement {
+    type Err = InvalidNodeVersion;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidNodeVersion { raw: s.to_string() };
+
+        let mut parts = s.trim().split('.').map(|part| part.parse::<u64>());
+        let (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+
+        Ok(NodeVersionRequirement {
+            major,
+            minor,
+            patch,
+        })
+    }
+}
+
+impl TryFrom<String> for NodeVersionRequirement {
+    type Error = InvalidNodeVersion;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<NodeVersionRequirement> for String {
+    fn from(value: NodeVersionRequirement) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for NodeVersionRequirement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
 }
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Default, Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 pub enum TopologyStructure {
     #[default]
     NymApi,
     GeoAware(GroupBy),
+
+    /// Use the static topology loaded from the file specified in `client.topology_file`.
+    File,
 }
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 pub enum GroupBy {
-    CountryGroup(CountryGroup),
-    NymAddress(Recipient),
+    CountryGroup(#[cfg_attr(feature = "config_schema", schemars(with = "String"))] CountryGroup),
+    NymAddress(#[cfg_attr(feature = "config_schema", schemars(with = "Vec<u8>"))] Recipient),
 }
 
 impl std::fmt::Display for GroupBy {
@@ -565,11 +939,18 @@ impl Default for Topology {
             topology_structure: TopologyStructure::default(),
             minimum_mixnode_performance: DEFAULT_MIN_MIXNODE_PERFORMANCE,
             minimum_gateway_performance: DEFAULT_MIN_GATEWAY_PERFORMANCE,
+            include_standby_gateways: false,
+            minimum_mixnode_version: None,
+            minimum_gateway_version: None,
+            hold_traffic_during_epoch_transition: false,
+            epoch_transition_max_hold: DEFAULT_EPOCH_TRANSITION_MAX_HOLD,
+            offline_tolerant_startup: false,
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(default, deny_unknown_fields)]
 pub struct ReplySurbs {
     /// Defines the minimum number of reply surbs the client wants to keep in its storage at all times.
@@ -588,29 +969,73 @@ pub struct ReplySurbs {
     /// Defines the maximum number of reply surbs a remote party is allowed to request from this client at once.
     pub maximum_allowed_reply_surb_request_size: u32,
 
+    /// Specifies whether the requests for additional reply surbs made by remote parties are rate limited.
+    /// It's disabled by default, since the default limits of 30 requests and 2000 surbs per remote party
+    /// and 10000 surbs in total per 60s window would cap a busy reply stream at about 33 surbs/s.
+    pub enable_reply_surb_request_rate_limiting: bool,
+
+    /// Defines the length of the window over which the requests for additional reply surbs
+    /// made by remote parties are rate limited. By default it's 60s.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
+    #[serde(with = "humantime_serde")]
+    pub reply_surb_request_rate_limit_window: Duration,
+
+    /// Defines the maximum number of requests for additional reply surbs a single remote party
+    /// can make within the rate limit window. Any further requests are ignored. By default it's 30.
+    pub maximum_reply_surb_requests_per_window: u32,
+
+    /// Defines the maximum number of reply surbs a single remote party can obtain from this client
+    /// by requesting them within the rate limit window. By default it's 2000.
+    pub maximum_requested_surbs_per_window: u32,
+
+    /// Defines the maximum number of reply surbs all the remote parties combined can obtain
+    /// from this client by requesting them within the rate limit window. By default it's 10000.
+    pub maximum_total_requested_surbs_per_window: u32,
+
     /// Defines maximum amount of time the client is going to wait for reply surbs before explicitly asking
     /// for more even though in theory they wouldn't need to.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub maximum_reply_surb_rerequest_waiting_period: Duration,
 
     /// Defines maximum amount of time the client is going to wait for reply surbs before
     /// deciding it's never going to get them and would drop all pending messages
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub maximum_reply_surb_drop_waiting_period: Duration,
 
     /// Defines maximum amount of time given reply surb is going to be valid for.
     /// This is going to be superseded by key rotation once implemented.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub maximum_reply_surb_age: Duration,
 
     /// Defines maximum amount of time given reply key is going to be valid for.
     /// This is going to be superseded by key rotation once implemented.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub maximum_reply_key_age: Duration,
 
     /// Specifies the number of mixnet hops the packet should go through. If not specified, then
     /// the default value is used.
     pub surb_mix_hops: Option<u8>,
+
+    /// Specifies whether the records of the on-disk reply storage are encrypted.
+    pub storage_encryption: ReplySurbStorageEncryption,
+}
+
+/// Encryption of the records (reply keys, reply SURBs and sender tags) of the on-disk reply storage.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
+pub enum ReplySurbStorageEncryption {
+    /// The records are stored in plaintext.
+    #[default]
+    Disabled,
+
+    /// The records are encrypted with a key derived from the client's identity key.
+    /// It requires the identity private key to be stored on the disk.
+    /// Existing plaintext storage is going to get encrypted on its next flush.
+    IdentityKey,
 }
 
 impl Default for ReplySurbs {
@@ -621,17 +1046,25 @@ impl Default for ReplySurbs {
             minimum_reply_surb_request_size: DEFAULT_MINIMUM_REPLY_SURB_REQUEST_SIZE,
             maximum_reply_surb_request_size: DEFAULT_MAXIMUM_REPLY_SURB_REQUEST_SIZE,
             maximum_allowed_reply_surb_request_size: DEFAULT_MAXIMUM_ALLOWED_SURB_REQUEST_SIZE,
+            enable_reply_surb_request_rate_limiting: false,
+            reply_surb_request_rate_limit_window: DEFAULT_REPLY_SURB_REQUEST_RATE_LIMIT_WINDOW,
+            maximum_reply_surb_requests_per_window: DEFAULT_MAXIMUM_REPLY_SURB_REQUESTS_PER_WINDOW,
+            maximum_requested_surbs_per_window: DEFAULT_MAXIMUM_REQUESTED_SURBS_PER_WINDOW,
+            maximum_total_requested_surbs_per_window:
+                DEFAULT_MAXIMUM_TOTAL_REQUESTED_SURBS_PER_WINDOW,
             maximum_reply_surb_rerequest_waiting_period:
                 DEFAULT_MAXIMUM_REPLY_SURB_REREQUEST_WAITING_PERIOD,
             maximum_reply_surb_drop_waiting_period: DEFAULT_MAXIMUM_REPLY_SURB_DROP_WAITING_PERIOD,
             maximum_reply_surb_age: DEFAULT_MAXIMUM_REPLY_SURB_AGE,
             maximum_reply_key_age: DEFAULT_MAXIMUM_REPLY_KEY_AGE,
             surb_mix_hops: None,
+            storage_encryption: ReplySurbStorageEncryption::Disabled,
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(default, deny_unknown_fields)]
 pub struct DebugConfig {
     /// Defines all configuration options related to traffic streams.
@@ -646,17 +1079,27 @@ pub struct DebugConfig {
     /// Defines all configuration options related to acknowledgements, such as delays or wait timeouts.
     pub acknowledgements: Acknowledgements,
 
+    /// Defines all configuration options related to the retransmission of the unacknowledged packets,
+    /// such as the backoff or the maximum number of attempts.
+    pub retransmission: Retransmission,
+
     /// Defines all configuration options related topology, such as refresh rates or timeouts.
     pub topology: Topology,
 
     /// Defines all configuration options related to reply SURBs.
     pub reply_surbs: ReplySurbs,
+
+    /// Defines all configuration options related to the client startup, such as its deadline.
+    pub startup: Startup,
+
+    /// Defines all configuration options related to the protocol statistics exchanged with the gateway.
+    pub protocol_stats: ProtocolStats,
 }
 
 impl DebugConfig {
     pub fn validate(&self) -> bool {
         // no other sections have explicit requirements (yet)
-        self.traffic.validate()
+        self.traffic.validate() && self.retransmission.validate()
     }
 }
 
@@ -670,8 +1113,11 @@ impl Default for DebugConfig {
             cover_traffic: Default::default(),
             gateway_connection: Default::default(),
             acknowledgements: Default::default(),
+            retransmission: Default::default(),
             topology: Default::default(),
             reply_surbs: Default::default(),
+            startup: Default::default(),
+            protocol_stats: Default::default(),
         }
     }
 }
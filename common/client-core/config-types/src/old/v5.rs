@@ -96,6 +96,7 @@ impl From<ConfigV5> for Config {
                 disabled_credentials_mode: value.client.disabled_credentials_mode,
                 nyxd_urls: value.client.nyxd_urls,
                 nym_api_urls: value.client.nym_api_urls,
+                topology_file: None,
             },
             debug: DebugConfig {
                 traffic: Traffic {
@@ -111,6 +112,7 @@ impl From<ConfigV5> for Config {
                     primary_packet_size: value.debug.traffic.primary_packet_size,
                     secondary_packet_size: value.debug.traffic.secondary_packet_size,
                     packet_type: value.debug.traffic.packet_type,
+                    ..Traffic::default()
                 },
                 cover_traffic: CoverTraffic {
                     loop_cover_traffic_average_delay: value
@@ -125,18 +127,21 @@ impl From<ConfigV5> for Config {
                         .debug
                         .cover_traffic
                         .disable_loop_cover_traffic_stream,
+                    ..CoverTraffic::default()
                 },
                 gateway_connection: GatewayConnection {
                     gateway_response_timeout: value
                         .debug
                         .gateway_connection
                         .gateway_response_timeout,
+                    ..GatewayConnection::default()
                 },
                 acknowledgements: Acknowledgements {
                     average_ack_delay: value.debug.acknowledgements.average_ack_delay,
                     ack_wait_multiplier: value.debug.acknowledgements.ack_wait_multiplier,
                     ack_wait_addition: value.debug.acknowledgements.ack_wait_addition,
                 },
+                retransmission: Default::default(),
                 topology: Topology {
                     topology_refresh_rate: value.debug.topology.topology_refresh_rate,
                     topology_resolution_timeout: value.debug.topology.topology_resolution_timeout,
@@ -180,8 +185,12 @@ impl From<ConfigV5> for Config {
                     maximum_reply_surb_age: value.debug.reply_surbs.maximum_reply_surb_age,
                     maximum_reply_key_age: value.debug.reply_surbs.maximum_reply_key_age,
                     surb_mix_hops: value.debug.reply_surbs.surb_mix_hops,
+                    ..ReplySurbs::default()
                 },
+                startup: Default::default(),
+                protocol_stats: Default::default(),
             },
+            sources: Default::default(),
         }
     }
 }
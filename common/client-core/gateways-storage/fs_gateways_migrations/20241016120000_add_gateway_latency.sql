@@ -0,0 +1,14 @@
+/*
+ * Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- note: this is intentionally not referencing `registered_gateway` as we also keep track of gateways
+-- we have measured (for example during initial selection), but never registered with
+CREATE TABLE gateway_latency
+(
+    gateway_id_bs58    TEXT                        NOT NULL UNIQUE PRIMARY KEY,
+    average_latency_us INTEGER                     NOT NULL,
+    samples            INTEGER                     NOT NULL,
+    last_measured      TIMESTAMP WITHOUT TIME ZONE NOT NULL
+);
@@ -0,0 +1,12 @@
+/*
+ * Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- unlike the latencies, the bandwidth is only ever tracked for the gateways we have registered with
+CREATE TABLE gateway_bandwidth
+(
+    gateway_id_bs58 TEXT                        NOT NULL UNIQUE PRIMARY KEY REFERENCES registered_gateway (gateway_id_bs58),
+    remaining       INTEGER                     NOT NULL,
+    last_updated    TIMESTAMP WITHOUT TIME ZONE NOT NULL
+);
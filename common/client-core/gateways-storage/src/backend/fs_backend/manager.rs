@@ -4,7 +4,8 @@
 use crate::{
     backend::fs_backend::error::StorageError,
     types::{
-        RawActiveGateway, RawCustomGatewayDetails, RawRegisteredGateway, RawRemoteGatewayDetails,
+        RawActiveGateway, RawCustomGatewayDetails, RawGatewayBandwidth, RawGatewayLatency,
+        RawRegisteredGateway, RawRemoteGatewayDetails,
     },
 };
 use log::{debug, error};
@@ -255,4 +256,80 @@ impl StorageManager {
             .await
             .map(|records| records.into_iter().map(|r| r.gateway_id_bs58).collect())
     }
+
+    pub(crate) async fn maybe_get_gateway_latency(
+        &self,
+        gateway_id: &str,
+    ) -> Result<Option<RawGatewayLatency>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM gateway_latency WHERE gateway_id_bs58 = ?")
+            .bind(gateway_id)
+            .fetch_optional(&self.connection_pool)
+            .await
+    }
+
+    pub(crate) async fn get_gateway_latencies(
+        &self,
+    ) -> Result<Vec<RawGatewayLatency>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM gateway_latency")
+            .fetch_all(&self.connection_pool)
+            .await
+    }
+
+    pub(crate) async fn set_gateway_latency(
+        &self,
+        latency: &RawGatewayLatency,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+                INSERT OR REPLACE INTO gateway_latency(gateway_id_bs58, average_latency_us, samples, last_measured)
+                VALUES (?, ?, ?, ?)
+            "#,
+            latency.gateway_id_bs58,
+            latency.average_latency_us,
+            latency.samples,
+            latency.last_measured,
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn get_gateway_bandwidths(
+        &self,
+    ) -> Result<Vec<RawGatewayBandwidth>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM gateway_bandwidth")
+            .fetch_all(&self.connection_pool)
+            .await
+    }
+
+    pub(crate) async fn set_gateway_bandwidth(
+        &self,
+        bandwidth: &RawGatewayBandwidth,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+                INSERT OR REPLACE INTO gateway_bandwidth(gateway_id_bs58, remaining, last_updated)
+                VALUES (?, ?, ?)
+            "#,
+            bandwidth.gateway_id_bs58,
+            bandwidth.remaining,
+            bandwidth.last_updated,
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn remove_gateway_bandwidth(
+        &self,
+        gateway_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM gateway_bandwidth WHERE gateway_id_bs58 = ?",
+            gateway_id
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
 }
@@ -2,14 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    ActiveGateway, BadGateway, GatewayDetails, GatewayRegistration, GatewayType,
-    GatewaysDetailsStore, StorageError,
+    ActiveGateway, BadGateway, GatewayBandwidth, GatewayDetails, GatewayLatency,
+    GatewayRegistration, GatewayType, GatewaysDetailsStore, StorageError,
 };
 use async_trait::async_trait;
 use manager::StorageManager;
 use nym_crypto::asymmetric::ed25519;
 use nym_gateway_requests::SharedSymmetricKey;
 use std::path::Path;
+use std::time::Duration;
 
 pub mod error;
 mod manager;
@@ -158,14 +159,163 @@ impl GatewaysDetailsStore for OnDiskGatewaysDetails {
             }
         }
 
-        // just try remove it from all tables even if it doesn't actually exist
-        self.manager.remove_registered_gateway(gateway_id).await?;
+        // just try remove it from all tables even if it doesn't actually exist.
+        // the registration goes last as all the other entries reference it
+        self.manager.remove_gateway_bandwidth(gateway_id).await?;
         self.manager
             .remove_remote_gateway_details(gateway_id)
             .await?;
         self.manager
             .remove_custom_gateway_details(gateway_id)
             .await?;
+        self.manager.remove_registered_gateway(gateway_id).await?;
+        Ok(())
+    }
+
+    async fn record_gateway_latency(
+        &self,
+        gateway_id: ed25519::PublicKey,
+        latency: Duration,
+    ) -> Result<(), Self::StorageError> {
+        let updated = match self
+            .manager
+            .maybe_get_gateway_latency(&gateway_id.to_base58_string())
+            .await?
+        {
+            Some(raw) => {
+                let mut existing = GatewayLatency::try_from(raw)?;
+                existing.update(latency);
+                existing
+            }
+            None => GatewayLatency::new(gateway_id, latency),
+        };
+
+        self.manager.set_gateway_latency(&(&updated).into()).await?;
         Ok(())
     }
+
+    async fn gateway_latencies(&self) -> Result<Vec<GatewayLatency>, Self::StorageError> {
+        Ok(self
+            .manager
+            .get_gateway_latencies()
+            .await?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?)
+    }
+
+    async fn record_gateway_bandwidth(
+        &self,
+        gateway_id: ed25519::PublicKey,
+        remaining: i64,
+    ) -> Result<(), Self::StorageError> {
+        let bandwidth = GatewayBandwidth::new(gateway_id, remaining);
+        self.manager
+            .set_gateway_bandwidth(&(&bandwidth).into())
+            .await?;
+        Ok(())
+    }
+
+    async fn gateway_bandwidths(&self) -> Result<Vec<GatewayBandwidth>, Self::StorageError> {
+        Ok(self
+            .manager
+            .get_gateway_bandwidths()
+            .await?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gateway_id(seed: u8) -> ed25519::PublicKey {
+        ed25519::PrivateKey::from_bytes(&[seed; 32])
+            .unwrap()
+            .public_key()
+    }
+
+    #[tokio::test]
+    async fn bandwidth_is_tracked_for_registered_gateways() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = OnDiskGatewaysDetails::init(dir.path().join("gateways.sqlite"))
+            .await
+            .unwrap();
+
+        let registered = gateway_id(1);
+        storage
+            .store_gateway_details(&GatewayDetails::new_custom(registered, None).into())
+            .await
+            .unwrap();
+
+        storage
+            .record_gateway_bandwidth(registered, 1000)
+            .await
+            .unwrap();
+        storage
+            .record_gateway_bandwidth(registered, 400)
+            .await
+            .unwrap();
+        let bandwidths = storage.gateway_bandwidths().await.unwrap();
+        assert_eq!(bandwidths.len(), 1);
+        assert_eq!(bandwidths[0].gateway_id, registered);
+        assert_eq!(bandwidths[0].remaining, 400);
+
+        // it only makes sense for the gateways we hold the shared keys with
+        assert!(storage
+            .record_gateway_bandwidth(gateway_id(2), 1000)
+            .await
+            .is_err());
+
+        storage
+            .remove_gateway_details(&registered.to_base58_string())
+            .await
+            .unwrap();
+        assert!(storage.gateway_bandwidths().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn latencies_survive_reloading_the_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("gateways.sqlite");
+        let storage = OnDiskGatewaysDetails::init(&db_path).await.unwrap();
+
+        // the gateway does not have to be registered
+        let measured = gateway_id(1);
+        storage
+            .record_gateway_latency(measured, Duration::from_millis(100))
+            .await
+            .unwrap();
+        storage
+            .record_gateway_latency(measured, Duration::from_millis(200))
+            .await
+            .unwrap();
+
+        let mut expected = GatewayLatency::new(measured, Duration::from_millis(100));
+        expected.update(Duration::from_millis(200));
+
+        let stored = storage.gateway_latencies().await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].gateway_id, measured);
+        assert_eq!(stored[0].samples, 2);
+        assert_eq!(stored[0].average.as_micros(), expected.average.as_micros());
+        drop(storage);
+
+        let reloaded = OnDiskGatewaysDetails::init(&db_path).await.unwrap();
+        assert_eq!(reloaded.gateway_latencies().await.unwrap(), stored);
+
+        // and the average keeps getting updated from where it was left off
+        reloaded
+            .record_gateway_latency(measured, Duration::from_millis(50))
+            .await
+            .unwrap();
+        let mut expected = stored[0];
+        expected.update(Duration::from_millis(50));
+
+        let updated = reloaded.gateway_latencies().await.unwrap();
+        assert_eq!(updated[0].samples, 3);
+        assert_eq!(updated[0].average.as_micros(), expected.average.as_micros());
+    }
 }
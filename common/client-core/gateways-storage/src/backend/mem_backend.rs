@@ -1,13 +1,14 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::types::{ActiveGateway, GatewayRegistration};
+use crate::types::{ActiveGateway, GatewayBandwidth, GatewayLatency, GatewayRegistration};
 use crate::{BadGateway, GatewayDetails, GatewaysDetailsStore};
 use async_trait::async_trait;
 use nym_crypto::asymmetric::ed25519::PublicKey;
 use nym_gateway_requests::{SharedGatewayKey, SharedSymmetricKey};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::RwLock;
 
@@ -29,6 +30,8 @@ pub struct InMemGatewaysDetails {
 struct InMemStorageInner {
     active_gateway: Option<String>,
     gateways: HashMap<String, GatewayRegistration>,
+    latencies: HashMap<String, GatewayLatency>,
+    bandwidths: HashMap<String, GatewayBandwidth>,
 }
 
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
@@ -127,7 +130,57 @@ impl GatewaysDetailsStore for InMemGatewaysDetails {
             }
         }
         guard.gateways.remove(gateway_id);
+        guard.bandwidths.remove(gateway_id);
 
         Ok(())
     }
+
+    async fn record_gateway_latency(
+        &self,
+        gateway_id: PublicKey,
+        latency: Duration,
+    ) -> Result<(), Self::StorageError> {
+        self.inner
+            .write()
+            .await
+            .latencies
+            .entry(gateway_id.to_base58_string())
+            .and_modify(|existing| existing.update(latency))
+            .or_insert_with(|| GatewayLatency::new(gateway_id, latency));
+        Ok(())
+    }
+
+    async fn gateway_latencies(&self) -> Result<Vec<GatewayLatency>, Self::StorageError> {
+        Ok(self
+            .inner
+            .read()
+            .await
+            .latencies
+            .values()
+            .copied()
+            .collect())
+    }
+
+    async fn record_gateway_bandwidth(
+        &self,
+        gateway_id: PublicKey,
+        remaining: i64,
+    ) -> Result<(), Self::StorageError> {
+        self.inner.write().await.bandwidths.insert(
+            gateway_id.to_base58_string(),
+            GatewayBandwidth::new(gateway_id, remaining),
+        );
+        Ok(())
+    }
+
+    async fn gateway_bandwidths(&self) -> Result<Vec<GatewayBandwidth>, Self::StorageError> {
+        Ok(self
+            .inner
+            .read()
+            .await
+            .bandwidths
+            .values()
+            .copied()
+            .collect())
+    }
 }
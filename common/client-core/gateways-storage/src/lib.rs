@@ -8,6 +8,7 @@ use async_trait::async_trait;
 use nym_crypto::asymmetric::identity;
 use nym_gateway_requests::SharedSymmetricKey;
 use std::error::Error;
+use std::time::Duration;
 
 pub mod backend;
 pub mod error;
@@ -70,4 +71,28 @@ pub trait GatewaysDetailsStore {
 
     /// Remove given gateway details from the underlying store.
     async fn remove_gateway_details(&self, gateway_id: &str) -> Result<(), Self::StorageError>;
+
+    /// Record a new latency measurement of the provided gateway, updating its historical average.
+    /// Note: the gateway does not have to be registered.
+    /// Backends that do not persist latencies are free to discard the measurement.
+    async fn record_gateway_latency(
+        &self,
+        gateway_id: identity::PublicKey,
+        latency: Duration,
+    ) -> Result<(), Self::StorageError>;
+
+    /// Returns historical latencies of all measured gateways.
+    async fn gateway_latencies(&self) -> Result<Vec<GatewayLatency>, Self::StorageError>;
+
+    /// Record the amount of bandwidth still available with the provided registered gateway,
+    /// so that it could be reused the next time we connect to it.
+    /// Backends that do not persist bandwidth are free to discard the value.
+    async fn record_gateway_bandwidth(
+        &self,
+        gateway_id: identity::PublicKey,
+        remaining: i64,
+    ) -> Result<(), Self::StorageError>;
+
+    /// Returns the last known remaining bandwidth of all registered gateways that have it recorded.
+    async fn gateway_bandwidths(&self) -> Result<Vec<GatewayBandwidth>, Self::StorageError>;
 }
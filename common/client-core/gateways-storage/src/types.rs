@@ -10,6 +10,7 @@ use std::fmt::{Display, Formatter};
 use std::ops::Deref;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use time::OffsetDateTime;
 use url::Url;
 use zeroize::{Zeroize, ZeroizeOnDrop};
@@ -138,6 +139,150 @@ impl Display for GatewayType {
     }
 }
 
+/// Historical latency of a particular gateway, kept as an exponentially weighted moving average
+/// of all the measurements performed so far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GatewayLatency {
+    pub gateway_id: identity::PublicKey,
+
+    pub average: Duration,
+
+    pub samples: u32,
+
+    pub last_measured: OffsetDateTime,
+}
+
+impl GatewayLatency {
+    /// Weight given to the most recent measurement when updating the average.
+    pub const SMOOTHING_FACTOR: f64 = 0.3;
+
+    pub fn new(gateway_id: identity::PublicKey, latency: Duration) -> Self {
+        GatewayLatency {
+            gateway_id,
+            average: latency,
+            samples: 1,
+            last_measured: OffsetDateTime::now_utc(),
+        }
+    }
+
+    pub fn update(&mut self, latency: Duration) {
+        self.average = Duration::from_secs_f64(
+            Self::SMOOTHING_FACTOR * latency.as_secs_f64()
+                + (1. - Self::SMOOTHING_FACTOR) * self.average.as_secs_f64(),
+        );
+        self.samples = self.samples.saturating_add(1);
+        self.last_measured = OffsetDateTime::now_utc();
+    }
+
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.last_measured + max_age < OffsetDateTime::now_utc()
+    }
+}
+
+impl TryFrom<RawGatewayLatency> for GatewayLatency {
+    type Error = BadGateway;
+
+    fn try_from(value: RawGatewayLatency) -> Result<Self, Self::Error> {
+        let gateway_id =
+            identity::PublicKey::from_base58_string(&value.gateway_id_bs58).map_err(|source| {
+                BadGateway::MalformedGatewayIdentity {
+                    gateway_id: value.gateway_id_bs58.clone(),
+                    source,
+                }
+            })?;
+
+        Ok(GatewayLatency {
+            gateway_id,
+            average: Duration::from_micros(value.average_latency_us.max(0) as u64),
+            samples: value.samples.clamp(0, u32::MAX as i64) as u32,
+            last_measured: value.last_measured,
+        })
+    }
+}
+
+impl<'a> From<&'a GatewayLatency> for RawGatewayLatency {
+    fn from(value: &'a GatewayLatency) -> Self {
+        RawGatewayLatency {
+            gateway_id_bs58: value.gateway_id.to_base58_string(),
+            average_latency_us: value.average.as_micros().min(i64::MAX as u128) as i64,
+            samples: value.samples as i64,
+            last_measured: value.last_measured,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct RawGatewayLatency {
+    pub gateway_id_bs58: String,
+    pub average_latency_us: i64,
+    pub samples: i64,
+    pub last_measured: OffsetDateTime,
+}
+
+/// The last known amount of bandwidth still available with a particular registered gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GatewayBandwidth {
+    pub gateway_id: identity::PublicKey,
+
+    /// The remaining bandwidth (in bytes), as last reported by the gateway.
+    pub remaining: i64,
+
+    pub last_updated: OffsetDateTime,
+}
+
+impl GatewayBandwidth {
+    pub fn new(gateway_id: identity::PublicKey, remaining: i64) -> Self {
+        GatewayBandwidth {
+            gateway_id,
+            remaining,
+            last_updated: OffsetDateTime::now_utc(),
+        }
+    }
+
+    pub fn has_allowance(&self) -> bool {
+        self.remaining > 0
+    }
+}
+
+impl TryFrom<RawGatewayBandwidth> for GatewayBandwidth {
+    type Error = BadGateway;
+
+    fn try_from(value: RawGatewayBandwidth) -> Result<Self, Self::Error> {
+        let gateway_id =
+            identity::PublicKey::from_base58_string(&value.gateway_id_bs58).map_err(|source| {
+                BadGateway::MalformedGatewayIdentity {
+                    gateway_id: value.gateway_id_bs58.clone(),
+                    source,
+                }
+            })?;
+
+        Ok(GatewayBandwidth {
+            gateway_id,
+            remaining: value.remaining,
+            last_updated: value.last_updated,
+        })
+    }
+}
+
+impl<'a> From<&'a GatewayBandwidth> for RawGatewayBandwidth {
+    fn from(value: &'a GatewayBandwidth) -> Self {
+        RawGatewayBandwidth {
+            gateway_id_bs58: value.gateway_id.to_base58_string(),
+            remaining: value.remaining,
+            last_updated: value.last_updated,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct RawGatewayBandwidth {
+    pub gateway_id_bs58: String,
+    pub remaining: i64,
+    pub last_updated: OffsetDateTime,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
 pub struct RawActiveGateway {
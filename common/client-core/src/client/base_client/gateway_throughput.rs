@@ -0,0 +1,153 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Throughput self-test of the connection to the gateway the client has registered with.
+//!
+//! It's meant to be run on a [`PreparedClient`], i.e. before any of the client tasks get started,
+//! so that the embedder could benchmark the gateway before committing to it.
+
+use crate::client::base_client::storage::MixnetClientStorage;
+use crate::client::base_client::{BaseClientBuilder, PreparedClient};
+use crate::error::ClientCoreError;
+use crate::init::types::InitialisationResult;
+use futures::channel::mpsc;
+use log::*;
+use nym_bandwidth_controller::BandwidthController;
+use nym_client_core_gateways_storage::GatewaysDetailsStore;
+use nym_credential_storage::storage::Storage as CredentialStorage;
+use nym_gateway_client::PacketRouter;
+use nym_sphinx::cover::generate_gateway_loop_cover_packet;
+use nym_sphinx::forwarding::packet::MixPacket;
+use nym_task::TaskClient;
+use nym_topology::{NymTopology, NymTopologyError};
+use nym_validator_client::nyxd::contract_traits::DkgQueryClient;
+use rand::rngs::OsRng;
+
+pub use nym_gateway_client::ThroughputTestResult;
+
+#[derive(Debug, Clone, Copy)]
+pub struct GatewayThroughputTestConfig {
+    /// Number of packets pushed to the gateway during the test.
+    pub packets: usize,
+}
+
+impl Default for GatewayThroughputTestConfig {
+    fn default() -> Self {
+        GatewayThroughputTestConfig { packets: 100 }
+    }
+}
+
+impl<'a, C, S> PreparedClient<'a, C, S>
+where
+    S: MixnetClientStorage + 'static,
+    C: DkgQueryClient + Send + Sync + 'static,
+{
+    async fn current_topology(&mut self) -> Result<NymTopology, ClientCoreError> {
+        let topology = match self.builder.custom_topology_provider.as_mut() {
+            Some(provider) => provider.get_new_topology().await,
+            None => {
+                let mut provider = BaseClientBuilder::<C, S>::setup_topology_provider(
+                    None,
+                    self.builder.config.debug.topology,
+                    self.builder.config.get_nym_api_endpoints(),
+                    self.builder.config.client.topology_file.clone(),
+                    self.builder.user_agent.clone(),
+                )?;
+                provider.get_new_topology().await
+            }
+        };
+        topology.ok_or(ClientCoreError::InsufficientNetworkTopology(
+            NymTopologyError::EmptyNetworkTopology,
+        ))
+    }
+
+    fn gateway_loop_packets(
+        &self,
+        topology: &NymTopology,
+        packets: usize,
+    ) -> Result<Vec<MixPacket>, ClientCoreError> {
+        let config = &self.builder.config.debug;
+        let ack_key = self.init_res.client_keys.ack_key();
+        let address = self.address();
+        let mut rng = OsRng;
+
+        (0..packets)
+            .map(|_| {
+                generate_gateway_loop_cover_packet(
+                    &mut rng,
+                    topology,
+                    &ack_key,
+                    &address,
+                    config.acknowledgements.average_ack_delay,
+                    config.traffic.primary_packet_size,
+                    config.traffic.packet_type,
+                )
+                .map_err(Into::into)
+            })
+            .collect()
+    }
+
+    /// Connects to the gateway the client has registered with, pushes the configured number of
+    /// gateway loop packets (i.e. ones that do not go through any mixnodes and are sent straight
+    /// back to us) and measures the goodput in both directions.
+    ///
+    /// The test does not claim any additional bandwidth, so if the credentials mode is enabled,
+    /// the client must already have sufficient allowance with the gateway.
+    /// Any connection kept open after a fresh registration is released beforehand and the
+    /// connection used for the test is closed afterwards, so [`PreparedClient::start`]
+    /// is going to establish a new one.
+    pub async fn run_gateway_throughput_test(
+        &mut self,
+        config: GatewayThroughputTestConfig,
+    ) -> Result<ThroughputTestResult, ClientCoreError>
+    where
+        <S::CredentialStore as CredentialStorage>::StorageError: Send + Sync + 'static,
+        <S::GatewaysDetailsStore as GatewaysDetailsStore>::StorageError: Sync + Send,
+    {
+        let topology = self.current_topology().await?;
+        let packets = self.gateway_loop_packets(&topology, config.packets)?;
+
+        self.release_gateway_connection().await;
+
+        // nothing received during the test is going to be processed, but the channels
+        // must remain open so that the router wouldn't complain about it
+        let (ack_sender, _ack_receiver) = mpsc::unbounded();
+        let (mixnet_messages_sender, _mixnet_messages_receiver) = mpsc::unbounded();
+        let packet_router = PacketRouter::new(
+            ack_sender,
+            mixnet_messages_sender,
+            TaskClient::dummy().named("throughput-test-packet-router"),
+        );
+
+        let init_res = InitialisationResult::new_loaded(
+            self.init_res.gateway_registration.clone(),
+            self.init_res.client_keys.clone(),
+        );
+        let mut gateway_client = BaseClientBuilder::<C, S>::new_gateway_client(
+            self.builder.config,
+            init_res,
+            None::<BandwidthController<C, S::CredentialStore>>,
+            packet_router,
+            TaskClient::dummy().named("throughput-test-gateway-client"),
+        )?;
+        BaseClientBuilder::<C, S>::connect_gateway_client(
+            &mut gateway_client,
+            self.builder.client_store.gateway_details_store(),
+        )
+        .await?;
+
+        let gateway_id = gateway_client.gateway_identity().to_base58_string();
+        let result = gateway_client
+            .run_throughput_test(packets)
+            .await
+            .map_err(|err| ClientCoreError::gateway_client_failure(gateway_id.clone(), err));
+
+        if let Err(err) = gateway_client.close_connection().await {
+            warn!("failed to cleanly close the throughput test connection to the gateway: {err}")
+        }
+
+        let result = result?;
+        info!("gateway {gateway_id} throughput test: {result}");
+        Ok(result)
+    }
+}
@@ -3,29 +3,60 @@
 
 use super::packet_statistics_control::PacketStatisticsReporter;
 use super::received_buffer::ReceivedBufferMessage;
+#[cfg(not(target_arch = "wasm32"))]
+use super::topology_control::file_provider::FileTopologyProvider;
 use super::topology_control::geo_aware_provider::GeoAwareTopologyProvider;
+use crate::client::base_client::startup::{
+    await_background_startup, await_startup_within_deadline, spawn_startup, MaybeSendSync,
+    StartupRetries, StartupStage,
+};
 use crate::client::base_client::storage::helpers::store_client_keys;
 use crate::client::base_client::storage::MixnetClientStorage;
+use crate::client::control::{ClientControl, RuntimeParameters, RuntimeParametersListener};
+use crate::client::correspondents::RecentCorrespondents;
 use crate::client::cover_traffic_stream::LoopCoverTrafficStream;
+use crate::client::delivery::{DeliveryReceipts, DeliveryStatusReceiver};
+use crate::client::diagnostics::{ClientDiagnostics, EchoProbes, GatewayProbeReceiver};
+use crate::client::drain::{ClientDrain, DrainConfig, DrainState, DrainSummary};
+use crate::client::events::{
+    forward_gateway_session_events, ClientEvent, ClientEventSender, ClientEvents,
+};
+use crate::client::gateway_bandwidth::GatewayBandwidthRecorder;
+use crate::client::gateway_session::{GatewaySession, GatewaySessionInfo};
+use crate::client::helpers::{get_time_now, timeout, Instant};
 use crate::client::inbound_messages::{InputMessage, InputMessageReceiver, InputMessageSender};
+use crate::client::inbox::{InboxMessageId, InboxStorage};
 use crate::client::key_manager::persistence::KeyStore;
-use crate::client::key_manager::ClientKeys;
+use crate::client::key_manager::{ClientKeys, ManagedKeys};
 use crate::client::mix_traffic::transceiver::{GatewayReceiver, GatewayTransceiver, RemoteGateway};
 use crate::client::mix_traffic::{BatchMixMessageSender, MixTrafficController};
+use crate::client::outbox::controller::{InputMessageSource, OutboxController};
+use crate::client::outbox::OutboxStorage;
 use crate::client::packet_statistics_control::PacketStatisticsControl;
+use crate::client::protocol_stats::ProtocolStatsTracker;
 use crate::client::real_messages_control;
 use crate::client::real_messages_control::RealMessagesController;
+use crate::client::real_traffic_rate::RealTrafficCounter;
 use crate::client::received_buffer::{
     ReceivedBufferRequestReceiver, ReceivedBufferRequestSender, ReceivedMessagesBufferController,
 };
 use crate::client::replies::reply_controller;
 use crate::client::replies::reply_controller::{ReplyControllerReceiver, ReplyControllerSender};
 use crate::client::replies::reply_storage::{
-    CombinedReplyStorage, PersistentReplyStorage, ReplyStorageBackend, SentReplyKeys,
+    CombinedReplyStorage, FlushRequestReceiver, PersistentReplyStorage, ReplyStorageBackend,
+    SentReplyKeys,
+};
+use crate::client::rng::{ClientRng, ClientRngSource};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::client::roaming::NetworkMonitor;
+use crate::client::roaming::{NetworkChangeListener, NetworkChangeNotifier};
+use crate::client::streams::{
+    self, ReconstructedStreamReceiver, StreamConfig, StreamDestination, StreamError, StreamId,
 };
 use crate::client::topology_control::nym_api_provider::NymApiTopologyProvider;
 use crate::client::topology_control::{
-    nym_api_provider, TopologyAccessor, TopologyRefresher, TopologyRefresherConfig,
+    self, nym_api_provider, RoutingFilter, TopologyAccessor, TopologyRefresher,
+    TopologyRefresherConfig,
 };
 use crate::config::{Config, DebugConfig};
 use crate::error::ClientCoreError;
@@ -35,15 +66,17 @@ use crate::init::{
 };
 use crate::{config, spawn_future};
 use futures::channel::mpsc;
+use futures::io::AsyncRead;
 use log::*;
 use nym_bandwidth_controller::BandwidthController;
 use nym_client_core_gateways_storage::{GatewayDetails, GatewaysDetailsStore};
 use nym_credential_storage::storage::Storage as CredentialStorage;
-use nym_crypto::asymmetric::{encryption, identity};
+use nym_crypto::asymmetric::identity;
 use nym_gateway_client::client::config::GatewayClientConfig;
 use nym_gateway_client::{
     AcknowledgementReceiver, GatewayClient, GatewayConfig, MixnetMessageReceiver, PacketRouter,
 };
+use nym_gateway_requests::CipherSuite;
 use nym_sphinx::acknowledgements::AckKey;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::addressing::nodes::NodeIdentity;
@@ -57,8 +90,9 @@ use nym_validator_client::{nyxd::contract_traits::DkgQueryClient, UserAgent};
 use rand::rngs::OsRng;
 use std::fmt::Debug;
 use std::os::raw::c_int as RawFd;
-use std::path::Path;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 use url::Url;
 
 #[cfg(all(
@@ -68,13 +102,17 @@ use url::Url;
 ))]
 pub mod non_wasm_helpers;
 
+pub mod gateway_throughput;
 pub mod helpers;
+pub mod standby;
+pub mod startup;
 pub mod storage;
 
 #[derive(Clone)]
 pub struct ClientInput {
     pub connection_command_sender: ConnectionCommandSender,
     pub input_sender: InputMessageSender,
+    pub lane_queue_lengths: LaneQueueLengths,
 }
 
 impl ClientInput {
@@ -84,11 +122,40 @@ impl ClientInput {
     ) -> Result<(), tokio::sync::mpsc::error::SendError<InputMessage>> {
         self.input_sender.send(message).await
     }
+
+    /// Sends the whole content of the reader as a stream, using the default [`StreamConfig`].
+    /// The recipient is going to receive it as a `ReconstructedStream` if it has registered
+    /// a stream receiver, otherwise as the individual chunks.
+    pub async fn send_stream<R: AsyncRead + Unpin>(
+        &self,
+        destination: StreamDestination,
+        reader: R,
+    ) -> Result<StreamId, StreamError> {
+        self.send_stream_with_config(destination, reader, StreamConfig::default())
+            .await
+    }
+
+    pub async fn send_stream_with_config<R: AsyncRead + Unpin>(
+        &self,
+        destination: StreamDestination,
+        reader: R,
+        config: StreamConfig,
+    ) -> Result<StreamId, StreamError> {
+        streams::send_stream(
+            &self.input_sender,
+            &self.lane_queue_lengths,
+            destination,
+            reader,
+            config,
+        )
+        .await
+    }
 }
 
 #[derive(Clone)]
 pub struct ClientOutput {
     pub received_buffer_request_sender: ReceivedBufferRequestSender,
+    delivery_receipts: DeliveryReceipts,
 }
 
 impl ClientOutput {
@@ -105,6 +172,36 @@ impl ClientOutput {
 
         Ok(reconstructed_receiver)
     }
+
+    /// Registers the receiver of the incoming chunked streams. Once registered, stream chunks
+    /// are no longer delivered through the regular receiver.
+    pub fn register_stream_receiver(
+        &mut self,
+    ) -> Result<ReconstructedStreamReceiver, ClientCoreError> {
+        let (stream_sender, stream_receiver) = mpsc::unbounded();
+
+        self.received_buffer_request_sender
+            .unbounded_send(ReceivedBufferMessage::StreamReceiverAnnounce(stream_sender))
+            .map_err(|_| ClientCoreError::FailedToRegisterReceiver)?;
+
+        Ok(stream_receiver)
+    }
+
+    /// Registers the receiver of the delivery statuses of the messages sent with a `DeliveryToken` attached.
+    /// Note that registering a new receiver replaces the previous one.
+    pub fn register_delivery_status_receiver(&mut self) -> DeliveryStatusReceiver {
+        let (status_sender, status_receiver) = mpsc::unbounded();
+        self.delivery_receipts.set_listener(status_sender);
+        status_receiver
+    }
+
+    /// Acknowledge the received message has been fully processed and thus can be removed
+    /// from the persistent inbox (if enabled).
+    pub fn ack(&self, message_id: InboxMessageId) -> Result<(), ClientCoreError> {
+        self.received_buffer_request_sender
+            .unbounded_send(ReceivedBufferMessage::Ack(message_id))
+            .map_err(|_| ClientCoreError::FailedToAcknowledgeMessage)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -113,11 +210,51 @@ pub struct ClientState {
     pub reply_controller_sender: ReplyControllerSender,
     pub topology_accessor: TopologyAccessor,
     pub gateway_connection: GatewayConnection,
+    pub network_change_notifier: NetworkChangeNotifier,
+    pub client_control: ClientControl,
+    pub diagnostics: ClientDiagnostics,
+    pub drain: ClientDrain,
+    pub managed_keys: ManagedKeys,
+    pub gateway_session: GatewaySession,
+    pub client_events: ClientEventSender,
 }
 
-#[derive(Clone, Copy, Debug)]
+impl ClientState {
+    /// Returns the current status of the connection with the gateway,
+    /// unless it hasn't been set up yet.
+    pub fn gateway_session_info(&self) -> Option<GatewaySessionInfo> {
+        self.gateway_session.info()
+    }
+
+    /// Subscribes to the events published by the client subsystems from now on.
+    pub fn subscribe_events(&self) -> ClientEvents {
+        self.client_events.subscribe()
+    }
+}
+
+/// Information about the connection with the gateway.
+/// If the client got started in the degraded mode, the connection only gets established
+/// once the startup completes in the background.
+#[derive(Clone, Debug, Default)]
 pub struct GatewayConnection {
-    pub gateway_ws_fd: Option<RawFd>,
+    gateway_ws_fd: Arc<OnceLock<Option<RawFd>>>,
+}
+
+impl GatewayConnection {
+    pub(crate) fn set_established(&self, gateway_ws_fd: Option<RawFd>) {
+        let _ = self.gateway_ws_fd.set(gateway_ws_fd);
+    }
+
+    /// Specifies whether the connection with the gateway has already been established.
+    pub fn is_established(&self) -> bool {
+        self.gateway_ws_fd.get().is_some()
+    }
+
+    /// File descriptor of the gateway websocket, if the connection has already been established
+    /// and the underlying transport exposes one.
+    pub fn gateway_ws_fd(&self) -> Option<RawFd> {
+        self.gateway_ws_fd.get().copied().flatten()
+    }
 }
 
 pub enum ClientInputStatus {
@@ -184,6 +321,10 @@ pub struct BaseClientBuilder<'a, C, S: MixnetClientStorage> {
     custom_gateway_transceiver: Option<Box<dyn GatewayTransceiver + Send>>,
     shutdown: Option<TaskClient>,
     user_agent: Option<UserAgent>,
+    routing_filter: Option<RoutingFilter>,
+
+    #[cfg(feature = "deterministic-rng")]
+    rng_seed: Option<u64>,
 
     setup_method: GatewaySetup,
 }
@@ -207,6 +348,9 @@ where
             custom_gateway_transceiver: None,
             shutdown: None,
             user_agent: None,
+            routing_filter: None,
+            #[cfg(feature = "deterministic-rng")]
+            rng_seed: None,
             setup_method: GatewaySetup::MustLoad { gateway_id: None },
         }
     }
@@ -250,6 +394,33 @@ where
         self
     }
 
+    /// Excludes the specified mix nodes from the routes of all packets sent by the client.
+    /// The filter can also be changed at runtime via the [`TopologyAccessor`].
+    #[must_use]
+    pub fn with_routing_filter(mut self, filter: RoutingFilter) -> Self {
+        self.routing_filter = Some(filter);
+        self
+    }
+
+    /// Derives all the randomness used for the route selection, packet delays and cover traffic
+    /// from the provided seed, so that the runs of the client could be reproduced.
+    /// Only meant for tests and simulations.
+    #[cfg(feature = "deterministic-rng")]
+    #[must_use]
+    pub fn with_rng_seed(mut self, seed: u64) -> Self {
+        self.rng_seed = Some(seed);
+        self
+    }
+
+    fn rng_source(&self) -> ClientRngSource {
+        #[cfg(feature = "deterministic-rng")]
+        if let Some(seed) = self.rng_seed {
+            warn!("the client is using a seeded rng. it must never be used outside tests and simulations");
+            return ClientRngSource::new_seeded(seed);
+        }
+        ClientRngSource::new()
+    }
+
     pub fn with_stored_topology<P: AsRef<Path>>(
         mut self,
         file: P,
@@ -267,37 +438,47 @@ where
 
     // future constantly pumping loop cover traffic at some specified average rate
     // the pumped traffic goes to the MixTrafficController
+    #[allow(clippy::too_many_arguments)]
     fn start_cover_traffic_stream(
+        rng: ClientRng,
         debug_config: &DebugConfig,
         ack_key: Arc<AckKey>,
-        self_address: Recipient,
+        self_keys: ManagedKeys,
         topology_accessor: TopologyAccessor,
         mix_tx: BatchMixMessageSender,
         stats_tx: PacketStatisticsReporter,
+        recent_correspondents: Option<RecentCorrespondents>,
+        real_traffic_counter: Option<RealTrafficCounter>,
+        runtime_parameters: RuntimeParametersListener,
         shutdown: TaskClient,
     ) {
         info!("Starting loop cover traffic stream...");
 
         let stream = LoopCoverTrafficStream::new(
+            rng,
             ack_key,
             debug_config.acknowledgements.average_ack_delay,
             mix_tx,
-            self_address,
+            self_keys,
             topology_accessor,
             debug_config.traffic,
             debug_config.cover_traffic,
             stats_tx,
-        );
+        )
+        .with_recent_correspondents(recent_correspondents)
+        .with_real_traffic_counter(real_traffic_counter)
+        .with_runtime_parameters(runtime_parameters);
 
         stream.start_with_shutdown(shutdown);
     }
 
     #[allow(clippy::too_many_arguments)]
     fn start_real_traffic_controller(
+        rng_source: &mut ClientRngSource,
         controller_config: real_messages_control::Config,
         topology_accessor: TopologyAccessor,
         ack_receiver: AcknowledgementReceiver,
-        input_receiver: InputMessageReceiver,
+        input_source: InputMessageSource,
         mix_sender: BatchMixMessageSender,
         reply_storage: CombinedReplyStorage,
         reply_controller_sender: ReplyControllerSender,
@@ -311,9 +492,10 @@ where
         info!("Starting real traffic stream...");
 
         RealMessagesController::new(
+            rng_source,
             controller_config,
             ack_receiver,
-            input_receiver,
+            input_source,
             mix_sender,
             topology_accessor,
             reply_storage,
@@ -328,48 +510,73 @@ where
 
     // buffer controlling all messages fetched from provider
     // required so that other components would be able to use them (say the websocket)
+    #[allow(clippy::too_many_arguments)]
     fn start_received_messages_buffer_controller(
-        local_encryption_keypair: Arc<encryption::KeyPair>,
+        local_encryption_keys: ManagedKeys,
         query_receiver: ReceivedBufferRequestReceiver,
         mixnet_receiver: MixnetMessageReceiver,
         reply_key_storage: SentReplyKeys,
         reply_controller_sender: ReplyControllerSender,
+        inbox: S::InboxStore,
         shutdown: TaskClient,
         packet_statistics_control: PacketStatisticsReporter,
-    ) {
+        echo_probes: EchoProbes,
+    ) where
+        S::InboxStore: Send + Sync,
+    {
         info!("Starting received messages buffer controller...");
-        let controller: ReceivedMessagesBufferController<SphinxMessageReceiver> =
+        if inbox.is_enabled() {
+            info!("received messages are going to be persisted until acknowledged");
+        }
+        let controller: ReceivedMessagesBufferController<SphinxMessageReceiver, S::InboxStore> =
             ReceivedMessagesBufferController::new(
-                local_encryption_keypair,
+                local_encryption_keys,
                 query_receiver,
                 mixnet_receiver,
                 reply_key_storage,
                 reply_controller_sender,
+                inbox,
                 packet_statistics_control,
+                echo_probes,
             );
         controller.start_with_shutdown(shutdown)
     }
 
-    async fn start_gateway_client(
+    // optional journal of all the input messages, so that they could be replayed if the client
+    // got stopped before managing to process them
+    fn start_outbox_controller(
+        outbox: S::OutboxStore,
+        ack_key: &AckKey,
+        input_receiver: InputMessageReceiver,
+        shutdown: TaskClient,
+    ) -> InputMessageSource
+    where
+        S::OutboxStore: Send + Sync,
+    {
+        if !outbox.is_enabled() {
+            return input_receiver.into();
+        }
+
+        info!("Starting outbox controller...");
+        let (controller, input_source) = OutboxController::new(outbox, ack_key, input_receiver);
+        controller.start_with_shutdown(shutdown);
+        input_source
+    }
+
+    fn new_gateway_client(
         config: &Config,
         initialisation_result: InitialisationResult,
         bandwidth_controller: Option<BandwidthController<C, S::CredentialStore>>,
-        details_store: &S::GatewaysDetailsStore,
         packet_router: PacketRouter,
         shutdown: TaskClient,
-    ) -> Result<GatewayClient<C, S::CredentialStore>, ClientCoreError>
-    where
-        <S::KeyStore as KeyStore>::StorageError: Send + Sync + 'static,
-        <S::CredentialStore as CredentialStorage>::StorageError: Send + Sync + 'static,
-        <S::GatewaysDetailsStore as GatewaysDetailsStore>::StorageError: Sync + Send,
-    {
+    ) -> Result<GatewayClient<C, S::CredentialStore>, ClientCoreError> {
         let managed_keys = initialisation_result.client_keys;
         let GatewayDetails::Remote(details) = initialisation_result.gateway_registration.details
         else {
             return Err(ClientCoreError::UnexpectedPersistedCustomGatewayDetails);
         };
 
-        let mut gateway_client =
+        let gateway_client =
             if let Some(existing_client) = initialisation_result.authenticated_ephemeral_client {
                 existing_client.upgrade(packet_router, bandwidth_controller, shutdown)
             } else {
@@ -388,7 +595,7 @@ where
                             config.debug.gateway_connection.gateway_response_timeout,
                         ),
                     cfg,
-                    managed_keys.identity_keypair(),
+                    managed_keys.identity_signer(),
                     Some(details.shared_key),
                     packet_router,
                     bandwidth_controller,
@@ -396,12 +603,21 @@ where
                 )
             };
 
+        Ok(gateway_client)
+    }
+
+    async fn connect_gateway_client(
+        gateway_client: &mut GatewayClient<C, S::CredentialStore>,
+        details_store: &S::GatewaysDetailsStore,
+    ) -> Result<(), ClientCoreError>
+    where
+        <S::CredentialStore as CredentialStorage>::StorageError: Send + Sync + 'static,
+        <S::GatewaysDetailsStore as GatewaysDetailsStore>::StorageError: Sync + Send,
+    {
+        let gateway_id = gateway_client.gateway_identity();
         let gateway_failure = |err| {
             log::error!("Could not authenticate and start up the gateway connection - {err}");
-            ClientCoreError::GatewayClientError {
-                gateway_id: details.gateway_id.to_base58_string(),
-                source: err,
-            }
+            ClientCoreError::gateway_client_failure(gateway_id.to_base58_string(), err)
         };
 
         // the gateway client startup procedure is slightly more complicated now
@@ -441,9 +657,10 @@ where
             .start_listening_for_mixnet_messages()
             .map_err(gateway_failure)?;
 
-        Ok(gateway_client)
+        Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn setup_gateway_transceiver(
         custom_gateway_transceiver: Option<Box<dyn GatewayTransceiver + Send>>,
         config: &Config,
@@ -451,6 +668,7 @@ where
         bandwidth_controller: Option<BandwidthController<C, S::CredentialStore>>,
         details_store: &S::GatewaysDetailsStore,
         packet_router: PacketRouter,
+        mut retries: Option<&mut StartupRetries>,
         mut shutdown: TaskClient,
     ) -> Result<Box<dyn GatewayTransceiver + Send>, ClientCoreError>
     where
@@ -475,15 +693,24 @@ where
         }
 
         // otherwise, setup normal gateway client, etc
-        let gateway_client = Self::start_gateway_client(
+        let mut gateway_client = Self::new_gateway_client(
             config,
             initialisation_result,
             bandwidth_controller,
-            details_store,
             packet_router,
             shutdown,
-        )
-        .await?;
+        )?;
+
+        let mut attempt = 1;
+        while let Err(err) = Self::connect_gateway_client(&mut gateway_client, details_store).await
+        {
+            let Some(retries) = retries.as_deref_mut() else {
+                return Err(err);
+            };
+            retries
+                .wait_for_retry(StartupStage::GatewayConnection, &mut attempt, err)
+                .await?;
+        }
 
         Ok(Box::new(RemoteGateway::new(gateway_client)))
     }
@@ -492,53 +719,100 @@ where
         custom_provider: Option<Box<dyn TopologyProvider + Send + Sync>>,
         config_topology: config::Topology,
         nym_api_urls: Vec<Url>,
+        topology_file: Option<PathBuf>,
         user_agent: Option<UserAgent>,
-    ) -> Box<dyn TopologyProvider + Send + Sync> {
-        // if no custom provider was ... provided ..., create one using nym-api
-        custom_provider.unwrap_or_else(|| match config_topology.topology_structure {
+    ) -> Result<Box<dyn TopologyProvider + Send + Sync>, ClientCoreError> {
+        if let Some(custom_provider) = custom_provider {
+            return Ok(custom_provider);
+        }
+
+        let client_version = env!("CARGO_PKG_VERSION");
+        let version_constraints =
+            topology_control::version_constraints(client_version, &config_topology);
+
+        // if no custom provider was ... provided ..., create one based on the config
+        let provider: Box<dyn TopologyProvider + Send + Sync> = match config_topology
+            .topology_structure
+        {
             config::TopologyStructure::NymApi => Box::new(NymApiTopologyProvider::new(
                 nym_api_provider::Config {
                     min_mixnode_performance: config_topology.minimum_mixnode_performance,
                     min_gateway_performance: config_topology.minimum_gateway_performance,
+                    include_standby_gateways: config_topology.include_standby_gateways,
+                    version_constraints,
                 },
                 nym_api_urls,
-                env!("CARGO_PKG_VERSION").to_string(),
+                client_version.to_string(),
                 user_agent,
             )),
-            config::TopologyStructure::GeoAware(group_by) => {
-                Box::new(GeoAwareTopologyProvider::new(
-                    nym_api_urls,
-                    env!("CARGO_PKG_VERSION").to_string(),
-                    group_by,
-                ))
+            config::TopologyStructure::GeoAware(group_by) => Box::new(
+                GeoAwareTopologyProvider::new(nym_api_urls, client_version.to_string(), group_by)
+                    .with_version_constraints(version_constraints),
+            ),
+            #[cfg(not(target_arch = "wasm32"))]
+            config::TopologyStructure::File => {
+                let topology_file =
+                    topology_file.ok_or(ClientCoreError::UnspecifiedTopologyFile)?;
+                Box::new(
+                    FileTopologyProvider::new(topology_file)
+                        .with_version_constraints(version_constraints),
+                )
             }
-        })
+            #[cfg(target_arch = "wasm32")]
+            config::TopologyStructure::File => {
+                let _ = topology_file;
+                return Err(ClientCoreError::UnsupportedFileTopology);
+            }
+        };
+        Ok(provider)
     }
 
-    // future responsible for periodically polling directory server and updating
-    // the current global view of topology
-    async fn start_topology_refresher(
+    fn setup_topology_refresher(
         topology_provider: Box<dyn TopologyProvider + Send + Sync>,
         topology_config: config::Topology,
         topology_accessor: TopologyAccessor,
-        local_gateway: &NodeIdentity,
-        wait_for_gateway: bool,
-        mut shutdown: TaskClient,
-    ) -> Result<(), ClientCoreError> {
-        let topology_refresher_config =
+        network_changes: NetworkChangeListener,
+        runtime_parameters: RuntimeParametersListener,
+        client_events: ClientEventSender,
+    ) -> TopologyRefresher {
+        let mut topology_refresher_config =
             TopologyRefresherConfig::new(topology_config.topology_refresh_rate);
+        if topology_config.hold_traffic_during_epoch_transition {
+            topology_refresher_config = topology_refresher_config
+                .with_epoch_transition_hold(topology_config.epoch_transition_max_hold);
+        }
 
-        let mut topology_refresher = TopologyRefresher::new(
+        TopologyRefresher::new(
             topology_refresher_config,
             topology_accessor,
             topology_provider,
-        );
+        )
+        .with_network_change_listener(network_changes)
+        .with_runtime_parameters(runtime_parameters)
+        .with_client_events(client_events)
+    }
+
+    async fn obtain_initial_topology(
+        topology_refresher: &mut TopologyRefresher,
+        topology_config: config::Topology,
+        local_gateway: &NodeIdentity,
+        wait_for_gateway: bool,
+    ) -> Result<(), ClientCoreError> {
         // before returning, block entire runtime to refresh the current network view so that any
         // components depending on topology would see a non-empty view
         info!("Obtaining initial network topology");
         topology_refresher.try_refresh().await;
 
         if let Err(err) = topology_refresher.ensure_topology_is_routable().await {
+            // the refresher is going to keep on trying to obtain a routable topology in the background
+            // while any input messages are held until then
+            if topology_config.offline_tolerant_startup && !topology_config.disable_refreshing {
+                warn!(
+                    "The current network topology is insufficient to route any packets through - \
+                    the client is going to start regardless and only begin sending once the topology recovers - source: {err}"
+                );
+                return Ok(());
+            }
             log::error!(
                 "The current network topology seem to be insufficient to route any packets through \
                 - check if enough nodes and a gateway are online - source: {err}"
@@ -572,6 +846,16 @@ where
             }
         }
 
+        Ok(())
+    }
+
+    // future responsible for periodically polling directory server and updating
+    // the current global view of topology
+    fn start_topology_refresher(
+        topology_refresher: TopologyRefresher,
+        topology_config: config::Topology,
+        mut shutdown: TaskClient,
+    ) {
         if topology_config.disable_refreshing {
             // if we're not spawning the refresher, don't cause shutdown immediately
             info!("The topology refesher is not going to be started");
@@ -582,8 +866,6 @@ where
             info!("Starting topology refresher...");
             topology_refresher.start_with_shutdown(shutdown);
         }
-
-        Ok(())
     }
 
     fn start_packet_statistics_control(shutdown: TaskClient) -> PacketStatisticsReporter {
@@ -595,17 +877,40 @@ where
 
     fn start_mix_traffic_controller(
         gateway_transceiver: Box<dyn GatewayTransceiver + Send>,
+        network_changes: NetworkChangeListener,
+        protocol_stats: Option<ProtocolStatsTracker>,
+        gateway_probes: GatewayProbeReceiver,
         shutdown: TaskClient,
     ) -> BatchMixMessageSender {
         info!("Starting mix traffic controller...");
         let (mix_traffic_controller, mix_tx) = MixTrafficController::new(gateway_transceiver);
-        mix_traffic_controller.start_with_shutdown(shutdown);
+        mix_traffic_controller
+            .with_network_change_listener(network_changes)
+            .with_protocol_stats(protocol_stats)
+            .with_gateway_probes(gateway_probes)
+            .start_with_shutdown(shutdown);
         mix_tx
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    fn start_network_monitor(
+        gateway_connection_config: config::GatewayConnection,
+        notifier: NetworkChangeNotifier,
+        shutdown: TaskClient,
+    ) {
+        if !gateway_connection_config.monitor_network_changes {
+            return;
+        }
+
+        info!("Starting network monitor...");
+        NetworkMonitor::new(notifier, gateway_connection_config.network_monitor_interval)
+            .start_with_shutdown(shutdown);
+    }
+
     // TODO: rename it as it implies the data is persistent whilst one can use InMemBackend
     async fn setup_persistent_reply_storage(
         backend: S::ReplyStore,
+        flush_requests: FlushRequestReceiver,
         shutdown: TaskClient,
     ) -> Result<CombinedReplyStorage, ClientCoreError>
     where
@@ -624,7 +929,7 @@ where
         let store_clone = mem_store.clone();
         spawn_future(async move {
             persistent_storage
-                .flush_on_shutdown(store_clone, shutdown)
+                .flush_on_shutdown(store_clone, flush_requests, shutdown)
                 .await
         });
 
@@ -644,32 +949,98 @@ where
         if key_store.load_keys().await.is_err() {
             info!("could not find valid client keys - a new set will be generated");
             let mut rng = OsRng;
-            let keys = ClientKeys::generate_new(&mut rng);
+            let keys = ClientKeys::generate_for_store(&mut rng, key_store);
             store_client_keys(keys, key_store).await?;
         }
 
         setup_gateway(setup_method, key_store, details_store).await
     }
 
-    pub async fn start_base(mut self) -> Result<BaseClient, ClientCoreError>
+    /// Performs the first phase of the client startup, i.e. sets up the client keys and registers
+    /// with the gateway (unless that has already happened before), without starting any traffic.
+    /// This allows the registration to be done well ahead of time, such as during the onboarding
+    /// of a mobile app, so that the subsequent [`PreparedClient::start`] could be quick.
+    pub async fn prepare(mut self) -> Result<PreparedClient<'a, C, S>, ClientCoreError>
+    where
+        <S::KeyStore as KeyStore>::StorageError: Send + Sync,
+        <S::GatewaysDetailsStore as GatewaysDetailsStore>::StorageError: Sync + Send,
+    {
+        info!("Preparing nym client");
+        let setup_method = std::mem::replace(
+            &mut self.setup_method,
+            GatewaySetup::MustLoad { gateway_id: None },
+        );
+
+        // derive (or load) client keys and gateway configuration
+        let initialisation = Self::initialise_keys_and_gateway(
+            setup_method,
+            self.client_store.key_store(),
+            self.client_store.gateway_details_store(),
+        );
+        let init_res = match self.config.debug.startup.deadline {
+            Some(deadline) => timeout(deadline, initialisation).await.map_err(|_| {
+                ClientCoreError::StartupDeadlineExceeded {
+                    stage: StartupStage::GatewaySetup,
+                }
+            })??,
+            None => initialisation.await?,
+        };
+
+        Ok(PreparedClient {
+            builder: self,
+            init_res,
+        })
+    }
+
+    /// Sets up the client keys, registers with the gateway and starts all the client tasks.
+    /// It's equivalent to calling [`BaseClientBuilder::prepare`] followed by [`PreparedClient::start`],
+    /// except the startup deadline, if any, applies to both of those phases combined.
+    pub async fn start_base(self) -> Result<BaseClient, ClientCoreError>
+    where
+        S::ReplyStore: Send + Sync,
+        S::InboxStore: Send + Sync,
+        S::OutboxStore: Send + Sync,
+        S::GatewaysDetailsStore: MaybeSendSync,
+        <S::KeyStore as KeyStore>::StorageError: Send + Sync,
+        <S::ReplyStore as ReplyStorageBackend>::StorageError: Sync + Send,
+        <S::CredentialStore as CredentialStorage>::StorageError: Send + Sync + 'static,
+        <S::GatewaysDetailsStore as GatewaysDetailsStore>::StorageError: Sync + Send,
+    {
+        let startup_started = get_time_now();
+        let prepared = self.prepare().await?;
+        prepared
+            .builder
+            .start_prepared(prepared.init_res, startup_started)
+            .await
+    }
+
+    async fn start_prepared(
+        mut self,
+        init_res: InitialisationResult,
+        startup_started: Instant,
+    ) -> Result<BaseClient, ClientCoreError>
     where
         S::ReplyStore: Send + Sync,
+        S::InboxStore: Send + Sync,
+        S::OutboxStore: Send + Sync,
+        S::GatewaysDetailsStore: MaybeSendSync,
         <S::KeyStore as KeyStore>::StorageError: Send + Sync,
         <S::ReplyStore as ReplyStorageBackend>::StorageError: Sync + Send,
         <S::CredentialStore as CredentialStorage>::StorageError: Send + Sync + 'static,
         <S::GatewaysDetailsStore as GatewaysDetailsStore>::StorageError: Sync + Send,
     {
         info!("Starting nym client");
+        info!(
+            "using the {} cipher suite for the channel with the gateway",
+            CipherSuite::local()
+        );
 
-        // derive (or load) client keys and gateway configuration
-        let init_res = Self::initialise_keys_and_gateway(
-            self.setup_method,
-            self.client_store.key_store(),
-            self.client_store.gateway_details_store(),
-        )
-        .await?;
+        let startup_config = self.config.debug.startup;
+        let mut rng_source = self.rng_source();
+        let degraded_start =
+            startup_config.deadline.is_some() && startup_config.allow_degraded_start;
 
-        let (reply_storage_backend, credential_store, details_store) =
+        let (reply_storage_backend, credential_store, details_store, inbox_store, outbox_store) =
             self.client_store.into_runtime_stores();
 
         // channels for inter-component communication
@@ -685,11 +1056,34 @@ where
         let (received_buffer_request_sender, received_buffer_request_receiver) = mpsc::unbounded();
 
         // channels responsible for controlling real messages
-        let (input_sender, input_receiver) = tokio::sync::mpsc::channel::<InputMessage>(1);
+        // (in the degraded or offline-tolerant mode they have to be able to hold the input
+        // until the startup completes or the topology becomes routable)
+        let offline_tolerant = self.config.debug.topology.offline_tolerant_startup;
+        let input_buffer_size = if degraded_start || offline_tolerant {
+            startup_config.max_queued_messages.max(1)
+        } else {
+            1
+        };
+        let (input_sender, input_receiver) =
+            tokio::sync::mpsc::channel::<InputMessage>(input_buffer_size);
 
         // channels responsible for controlling ack messages
         let (ack_sender, ack_receiver) = mpsc::unbounded();
         let shared_topology_accessor = TopologyAccessor::new();
+        if let Some(routing_filter) = self.routing_filter.take() {
+            shared_topology_accessor
+                .set_routing_filter(routing_filter)
+                .await;
+        }
+
+        // used for reacting to the changes of the local network, such as switching to a different Wi-Fi
+        let network_change_notifier = NetworkChangeNotifier::new();
+
+        // used for publishing the typed events of all the subsystems to the embedders
+        let client_events = ClientEventSender::new();
+
+        // used for adjusting some of the client parameters, such as traffic rates, at runtime
+        let client_control = ClientControl::new(RuntimeParameters::new(&self.config.debug));
 
         // Shutdown notifier for signalling tasks to stop
         let shutdown = self
@@ -702,10 +1096,47 @@ where
         let (reply_controller_sender, reply_controller_receiver) =
             reply_controller::requests::new_control_channels();
 
+        // Channels that the websocket listener can use to signal downstream to the real traffic
+        // controller that connections are closed.
+        let (client_connection_tx, client_connection_rx) = mpsc::unbounded();
+
+        // Shared queue length data. Published by the `OutQueueController` in the client, and used
+        // primarily to throttle incoming connections (e.g socks5 for attached network-requesters)
+        let shared_lane_queue_lengths = LaneQueueLengths::new();
+
         let self_address = Self::mix_address(&init_res);
         let ack_key = init_res.client_keys.ack_key();
-        let encryption_keys = init_res.client_keys.encryption_keypair();
-        let identity_keys = init_res.client_keys.identity_keypair();
+        let identity_keys = init_res.client_keys.identity_signer();
+
+        // used for rotating the encryption keys (and thus changing our address) at runtime
+        let managed_keys = ManagedKeys::new(init_res.client_keys.clone(), init_res.gateway_id());
+        let self_keys = managed_keys.clone();
+
+        // used for running the health checks on demand
+        let (diagnostics, gateway_probes, echo_probes) = ClientDiagnostics::new(
+            managed_keys.clone(),
+            input_sender.clone(),
+            shared_topology_accessor.clone(),
+            self.config.debug.topology.topology_refresh_rate,
+        );
+
+        // used for reporting the delivery status of the messages to the `ClientOutput`
+        let delivery_receipts = DeliveryReceipts::default();
+
+        // used for gracefully draining the client before its shutdown
+        let drain_state = DrainState::default();
+        let (reply_flush_sender, reply_flush_receiver) = mpsc::unbounded();
+        let drain = ClientDrain::new(
+            drain_state.clone(),
+            shared_lane_queue_lengths.clone(),
+            reply_flush_sender,
+        );
+
+        #[cfg(feature = "pcap")]
+        nym_pcap::init_from_env(format!(
+            "client-{}",
+            identity_keys.identity_key().to_base58_string()
+        ));
 
         // the components are started in very specific order. Unless you know what you are doing,
         // do not change that.
@@ -717,111 +1148,258 @@ where
             self.custom_topology_provider.take(),
             self.config.debug.topology,
             self.config.get_nym_api_endpoints(),
+            self.config.client.topology_file.clone(),
             self.user_agent.clone(),
-        );
+        )?;
 
-        // needs to be started as the first thing to block if required waiting for the gateway
-        Self::start_topology_refresher(
+        let topology_refresher = Self::setup_topology_refresher(
             topology_provider,
             self.config.debug.topology,
             shared_topology_accessor.clone(),
-            self_address.gateway(),
-            self.wait_for_gateway,
-            shutdown.fork("topology_refresher"),
-        )
-        .await?;
+            network_change_notifier.subscribe(),
+            client_control.subscribe(),
+            client_events.clone(),
+        );
 
-        let packet_stats_reporter =
-            Self::start_packet_statistics_control(shutdown.fork("packet_statistics_control"));
+        // in the degraded mode, rather than failing, the network-dependent stages are retried
+        // until they succeed
+        let mut retries = degraded_start.then(|| {
+            StartupRetries::new(
+                startup_config.retry_interval,
+                shutdown.fork("startup_retries"),
+            )
+        });
+        let topology_obtained = Arc::new(AtomicBool::new(false));
+
+        let config = self.config.clone();
+        let wait_for_gateway = self.wait_for_gateway;
+        let custom_gateway_transceiver = self.custom_gateway_transceiver;
+        let mut task_client = shutdown.get_handle();
+        let topology_accessor = shared_topology_accessor.clone();
+        let reply_sender = reply_controller_sender.clone();
+        let lane_queue_lengths = shared_lane_queue_lengths.clone();
+        let network_notifier = network_change_notifier.clone();
+        let runtime_control = client_control.clone();
+        let receipts = delivery_receipts.clone();
+        let topology_progress = Arc::clone(&topology_obtained);
+        let shared_gateway_session = GatewaySession::new();
+        let gateway_session = shared_gateway_session.clone();
+        let events = client_events.clone();
+
+        // everything from this point onwards depends on the network, so it might have to be
+        // finished in the background if the client is allowed to start in the degraded mode
+        let start_components = async move {
+            let mut topology_refresher = topology_refresher;
+
+            // needs to be started as the first thing to block if required waiting for the gateway
+            let mut attempt = 1;
+            while let Err(err) = Self::obtain_initial_topology(
+                &mut topology_refresher,
+                config.debug.topology,
+                self_address.gateway(),
+                wait_for_gateway,
+            )
+            .await
+            {
+                let Some(retries) = retries.as_mut() else {
+                    return Err(err);
+                };
+                retries
+                    .wait_for_retry(StartupStage::Topology, &mut attempt, err)
+                    .await?;
+            }
+            topology_progress.store(true, Ordering::Relaxed);
 
-        let gateway_packet_router = PacketRouter::new(
-            ack_sender,
-            mixnet_messages_sender,
-            shutdown.get_handle().named("gateway-packet-router"),
-        );
+            Self::start_topology_refresher(
+                topology_refresher,
+                config.debug.topology,
+                task_client.fork("topology_refresher"),
+            );
 
-        let gateway_transceiver = Self::setup_gateway_transceiver(
-            self.custom_gateway_transceiver,
-            self.config,
-            init_res,
-            bandwidth_controller,
-            &details_store,
-            gateway_packet_router,
-            shutdown.fork("gateway_transceiver"),
-        )
-        .await?;
-        let gateway_ws_fd = gateway_transceiver.ws_fd();
+            let packet_stats_reporter = Self::start_packet_statistics_control(
+                task_client.fork("packet_statistics_control"),
+            );
 
-        let reply_storage = Self::setup_persistent_reply_storage(
-            reply_storage_backend,
-            shutdown.fork("persistent_reply_storage"),
-        )
-        .await?;
-
-        Self::start_received_messages_buffer_controller(
-            encryption_keys,
-            received_buffer_request_receiver,
-            mixnet_messages_receiver,
-            reply_storage.key_storage(),
-            reply_controller_sender.clone(),
-            shutdown.fork("received_messages_buffer"),
-            packet_stats_reporter.clone(),
-        );
+            let gateway_packet_router = PacketRouter::new(
+                ack_sender,
+                mixnet_messages_sender,
+                task_client.clone().named("gateway-packet-router"),
+            );
 
-        // The message_sender is the transmitter for any component generating sphinx packets
-        // that are to be sent to the mixnet. They are used by cover traffic stream and real
-        // traffic stream.
-        // The MixTrafficController then sends the actual traffic
-        let message_sender = Self::start_mix_traffic_controller(
-            gateway_transceiver,
-            shutdown.fork("mix_traffic_controller"),
-        );
+            let gateway_transceiver = Self::setup_gateway_transceiver(
+                custom_gateway_transceiver,
+                &config,
+                init_res,
+                bandwidth_controller,
+                &details_store,
+                gateway_packet_router,
+                retries.as_mut(),
+                task_client.fork("gateway_transceiver"),
+            )
+            .await?;
+            let gateway_ws_fd = gateway_transceiver.ws_fd();
+            let gateway_identity = gateway_transceiver.gateway_identity();
+            let session_stats = gateway_transceiver.session_stats();
+            if let Some(session_stats) = &session_stats {
+                forward_gateway_session_events(
+                    gateway_identity,
+                    session_stats,
+                    events.clone(),
+                    task_client.fork("gateway_session_events"),
+                );
+                GatewayBandwidthRecorder::new(
+                    gateway_identity,
+                    session_stats.clone(),
+                    details_store,
+                )
+                .start_with_shutdown(task_client.fork("gateway_bandwidth_recorder"));
+            }
+            gateway_session.set_connected(gateway_identity, session_stats);
+            events.emit(ClientEvent::GatewayConnected {
+                gateway: gateway_identity,
+            });
+
+            let reply_storage = Self::setup_persistent_reply_storage(
+                reply_storage_backend,
+                reply_flush_receiver,
+                task_client.fork("persistent_reply_storage"),
+            )
+            .await?;
+
+            Self::start_received_messages_buffer_controller(
+                self_keys.clone(),
+                received_buffer_request_receiver,
+                mixnet_messages_receiver,
+                reply_storage.key_storage(),
+                reply_sender.clone(),
+                inbox_store,
+                task_client.fork("received_messages_buffer"),
+                packet_stats_reporter.clone(),
+                echo_probes,
+            );
 
-        // Channels that the websocket listener can use to signal downstream to the real traffic
-        // controller that connections are closed.
-        let (client_connection_tx, client_connection_rx) = mpsc::unbounded();
+            // The message_sender is the transmitter for any component generating sphinx packets
+            // that are to be sent to the mixnet. They are used by cover traffic stream and real
+            // traffic stream.
+            // The MixTrafficController then sends the actual traffic
+            let protocol_stats = config
+                .debug
+                .protocol_stats
+                .enabled
+                .then(|| ProtocolStatsTracker::new(config.debug.protocol_stats));
+            let message_sender = Self::start_mix_traffic_controller(
+                gateway_transceiver,
+                network_notifier.subscribe(),
+                protocol_stats.clone(),
+                gateway_probes,
+                task_client.fork("mix_traffic_controller"),
+            );
 
-        // Shared queue length data. Published by the `OutQueueController` in the client, and used
-        // primarily to throttle incoming connections (e.g socks5 for attached network-requesters)
-        let shared_lane_queue_lengths = LaneQueueLengths::new();
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::start_network_monitor(
+                config.debug.gateway_connection,
+                network_notifier,
+                task_client.fork("network_monitor"),
+            );
 
-        let controller_config = real_messages_control::Config::new(
-            &self.config.debug,
-            Arc::clone(&ack_key),
-            self_address,
-        );
+            // only keep track of our correspondents if we're actually going to send them cover traffic
+            let cover_traffic_config = config.debug.cover_traffic;
+            let recent_correspondents = (!cover_traffic_config.disable_loop_cover_traffic_stream
+                && cover_traffic_config.correspondent_cover_traffic_ratio > 0.0)
+                .then(RecentCorrespondents::new);
+            let real_traffic_counter = (!cover_traffic_config.disable_loop_cover_traffic_stream
+                && cover_traffic_config.adaptive_loop_cover_traffic)
+                .then(RealTrafficCounter::new);
+
+            let controller_config = real_messages_control::Config::new(
+                &config.debug,
+                Arc::clone(&ack_key),
+                self_keys.clone(),
+            )
+            .with_recent_correspondents(recent_correspondents.clone())
+            .with_real_traffic_counter(real_traffic_counter.clone())
+            .with_runtime_parameters(runtime_control.subscribe())
+            .with_protocol_stats(protocol_stats)
+            .with_drain_state(drain_state)
+            .with_delivery_receipts(receipts)
+            .with_client_events(events)
+            .with_routability_hold(
+                offline_tolerant.then(|| topology_accessor.routability_changes()),
+            );
 
-        Self::start_real_traffic_controller(
-            controller_config,
-            shared_topology_accessor.clone(),
-            ack_receiver,
-            input_receiver,
-            message_sender.clone(),
-            reply_storage,
-            reply_controller_sender.clone(),
-            reply_controller_receiver,
-            shared_lane_queue_lengths.clone(),
-            client_connection_rx,
-            shutdown.fork("real_traffic_controller"),
-            self.config.debug.traffic.packet_type,
-            packet_stats_reporter.clone(),
-        );
+            let input_source = Self::start_outbox_controller(
+                outbox_store,
+                &ack_key,
+                input_receiver,
+                task_client.fork("outbox_controller"),
+            );
 
-        if !self
-            .config
-            .debug
-            .cover_traffic
-            .disable_loop_cover_traffic_stream
-        {
-            Self::start_cover_traffic_stream(
-                &self.config.debug,
-                ack_key,
-                self_address,
-                shared_topology_accessor.clone(),
-                message_sender,
-                packet_stats_reporter,
-                shutdown.fork("cover_traffic_stream"),
+            Self::start_real_traffic_controller(
+                &mut rng_source,
+                controller_config,
+                topology_accessor.clone(),
+                ack_receiver,
+                input_source,
+                message_sender.clone(),
+                reply_storage,
+                reply_sender,
+                reply_controller_receiver,
+                lane_queue_lengths,
+                client_connection_rx,
+                task_client.fork("real_traffic_controller"),
+                config.debug.traffic.packet_type,
+                packet_stats_reporter.clone(),
             );
+
+            if !config.debug.cover_traffic.disable_loop_cover_traffic_stream {
+                Self::start_cover_traffic_stream(
+                    rng_source.component_rng(),
+                    &config.debug,
+                    ack_key,
+                    self_keys,
+                    topology_accessor,
+                    message_sender,
+                    packet_stats_reporter,
+                    recent_correspondents,
+                    real_traffic_counter,
+                    runtime_control.subscribe(),
+                    task_client.fork("cover_traffic_stream"),
+                );
+            }
+
+            // all the components have been started with their own handles
+            task_client.disarm();
+            Ok::<_, ClientCoreError>(gateway_ws_fd)
+        };
+
+        let gateway_connection = GatewayConnection::default();
+        match startup_config.deadline {
+            None => gateway_connection.set_established(start_components.await?),
+            Some(deadline) => {
+                let remaining =
+                    deadline.saturating_sub(get_time_now().duration_since(startup_started));
+                let stage = || {
+                    if topology_obtained.load(Ordering::Relaxed) {
+                        StartupStage::GatewayConnection
+                    } else {
+                        StartupStage::Topology
+                    }
+                };
+
+                if degraded_start {
+                    await_background_startup(
+                        spawn_startup(start_components),
+                        remaining,
+                        gateway_connection.clone(),
+                        shutdown.get_handle(),
+                    )
+                    .await?
+                } else {
+                    gateway_connection.set_established(
+                        await_startup_within_deadline(start_components, remaining, stage).await?,
+                    )
+                }
+            }
         }
 
         debug!("Core client startup finished!");
@@ -834,30 +1412,169 @@ where
                 client_input: ClientInput {
                     connection_command_sender: client_connection_tx,
                     input_sender,
+                    lane_queue_lengths: shared_lane_queue_lengths.clone(),
                 },
             },
             client_output: ClientOutputStatus::AwaitingConsumer {
                 client_output: ClientOutput {
                     received_buffer_request_sender,
+                    delivery_receipts,
                 },
             },
             client_state: ClientState {
                 shared_lane_queue_lengths,
                 reply_controller_sender,
                 topology_accessor: shared_topology_accessor,
-                gateway_connection: GatewayConnection { gateway_ws_fd },
+                gateway_connection,
+                network_change_notifier,
+                client_control,
+                diagnostics,
+                drain,
+                managed_keys,
+                gateway_session: shared_gateway_session,
+                client_events,
             },
             task_handle: shutdown,
         })
     }
 }
 
+/// Client that has already set up its keys and registered with the gateway,
+/// but hasn't started any of its tasks yet. It's obtained with [`BaseClientBuilder::prepare`].
+///
+/// Note that the gateway registration is persisted in the client storage, so if the prepared client
+/// is dropped before being started (say, the app got suspended), it can later be resumed by building
+/// a new client with [`GatewaySetup::MustLoad`] without having to register again.
+pub struct PreparedClient<'a, C, S: MixnetClientStorage> {
+    builder: BaseClientBuilder<'a, C, S>,
+    init_res: InitialisationResult,
+}
+
+impl<'a, C, S> PreparedClient<'a, C, S>
+where
+    S: MixnetClientStorage + 'static,
+    C: DkgQueryClient + Send + Sync + 'static,
+{
+    /// Address the client is going to use once started.
+    pub fn address(&self) -> Recipient {
+        BaseClientBuilder::<C, S>::mix_address(&self.init_res)
+    }
+
+    /// Identity of the gateway the client has registered with.
+    pub fn gateway_id(&self) -> identity::PublicKey {
+        self.init_res.gateway_id()
+    }
+
+    /// Closes the connection to the gateway that was kept open after a fresh registration.
+    /// It should be called if the traffic isn't going to be started straight away,
+    /// in which case [`PreparedClient::start`] is going to re-establish the connection
+    /// using the persisted registration details.
+    pub async fn release_gateway_connection(&mut self) {
+        if let Some(mut ephemeral_client) = self.init_res.authenticated_ephemeral_client.take() {
+            if let Err(err) = ephemeral_client.close_connection().await {
+                warn!("failed to cleanly close the connection to the gateway: {err}")
+            }
+        }
+    }
+
+    /// Performs the second phase of the client startup, i.e. connects to the gateway
+    /// and starts all the client tasks.
+    pub async fn start(self) -> Result<BaseClient, ClientCoreError>
+    where
+        S::ReplyStore: Send + Sync,
+        S::InboxStore: Send + Sync,
+        S::OutboxStore: Send + Sync,
+        S::GatewaysDetailsStore: MaybeSendSync,
+        <S::KeyStore as KeyStore>::StorageError: Send + Sync,
+        <S::ReplyStore as ReplyStorageBackend>::StorageError: Sync + Send,
+        <S::CredentialStore as CredentialStorage>::StorageError: Send + Sync + 'static,
+        <S::GatewaysDetailsStore as GatewaysDetailsStore>::StorageError: Sync + Send,
+    {
+        self.builder
+            .start_prepared(self.init_res, get_time_now())
+            .await
+    }
+}
+
 pub struct BaseClient {
+    /// Address of the client at the time of its startup.
+    /// It changes whenever the encryption keys get rotated via the [`ClientState::managed_keys`].
     pub address: Recipient,
-    pub identity_keys: Arc<identity::KeyPair>,
+    pub identity_keys: Arc<dyn identity::IdentitySigner>,
     pub client_input: ClientInputStatus,
     pub client_output: ClientOutputStatus,
     pub client_state: ClientState,
 
     pub task_handle: TaskHandle,
 }
+
+impl BaseClient {
+    /// Gracefully drains the client (see [`ClientDrain::drain`]) and only then signals all the tasks to stop.
+    /// If the shutdown is controlled externally, signalling it is left up to the caller.
+    pub async fn drain_and_shutdown(&self, config: DrainConfig) -> DrainSummary {
+        let summary = self.client_state.drain.drain(config).await;
+        if let TaskHandle::Internal(task_manager) = &self.task_handle {
+            if task_manager.signal_shutdown().is_err() {
+                warn!("failed to signal the shutdown - the tasks might have already stopped");
+            }
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::base_client::storage::Ephemeral;
+    use nym_validator_client::QueryHttpRpcNyxdClient;
+
+    #[tokio::test]
+    async fn preparing_reuses_the_persisted_registration() {
+        let client_store = Ephemeral::default();
+        let keys = ClientKeys::generate_new(&mut OsRng);
+        store_client_keys(keys.clone(), client_store.key_store())
+            .await
+            .unwrap();
+
+        let gateway_id = *identity::KeyPair::new(&mut OsRng).public_key();
+        let details_store = client_store.gateway_details_store();
+        details_store
+            .store_gateway_details(&GatewayDetails::new_custom(gateway_id, None).into())
+            .await
+            .unwrap();
+        details_store
+            .set_active_gateway(&gateway_id.to_base58_string())
+            .await
+            .unwrap();
+
+        let config = Config::new("prepare-test", "1.0.0");
+        let prepared =
+            BaseClientBuilder::<QueryHttpRpcNyxdClient, _>::new(&config, client_store, None)
+                .prepare()
+                .await
+                .unwrap();
+
+        assert_eq!(prepared.gateway_id(), gateway_id);
+        let address = prepared.address();
+        assert_eq!(*address.gateway(), gateway_id);
+        assert_eq!(*address.identity(), keys.identity_public_key());
+        assert_eq!(
+            address.encryption_key(),
+            keys.encryption_keypair().public_key()
+        );
+    }
+
+    #[tokio::test]
+    async fn preparing_fails_without_a_registration_to_load() {
+        let config = Config::new("prepare-test", "1.0.0");
+        let res = BaseClientBuilder::<QueryHttpRpcNyxdClient, _>::new(
+            &config,
+            Ephemeral::default(),
+            None,
+        )
+        .prepare()
+        .await;
+
+        assert!(matches!(res, Err(ClientCoreError::NoActiveGatewaySet)));
+    }
+}
@@ -1,11 +1,13 @@
 // Copyright 2022-2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::key_manager::persistence::OnDiskKeys;
+use crate::client::replies::reply_storage::fs_backend::ReplyStoragePassphrase;
 use crate::client::replies::reply_storage::{
     fs_backend, CombinedReplyStorage, ReplyStorageBackend,
 };
 use crate::config;
-use crate::config::Config;
+use crate::config::{Config, ReplySurbStorageEncryption};
 use crate::error::ClientCoreError;
 use log::{error, info, trace};
 use nym_bandwidth_controller::BandwidthController;
@@ -21,9 +23,14 @@ use url::Url;
 async fn setup_fresh_backend<P: AsRef<Path>>(
     db_path: P,
     surb_config: &config::ReplySurbs,
+    passphrase: Option<&ReplyStoragePassphrase>,
 ) -> Result<fs_backend::Backend, ClientCoreError> {
     info!("creating fresh surb database");
-    let mut storage_backend = match fs_backend::Backend::init(db_path).await {
+    let backend = match passphrase {
+        Some(passphrase) => fs_backend::Backend::init_encrypted(db_path, passphrase).await,
+        None => fs_backend::Backend::init(db_path).await,
+    };
+    let mut storage_backend = match backend {
         Ok(backend) => backend,
         Err(err) => {
             error!("failed to setup persistent storage backend for our reply needs: {err}");
@@ -82,23 +89,75 @@ fn archive_corrupted_database<P: AsRef<Path>>(db_path: P) -> io::Result<()> {
 pub async fn setup_fs_reply_surb_backend<P: AsRef<Path>>(
     db_path: P,
     surb_config: &config::ReplySurbs,
+) -> Result<fs_backend::Backend, ClientCoreError> {
+    setup_fs_reply_surb_backend_inner(db_path.as_ref(), surb_config, None).await
+}
+
+/// Sets up the reply surb storage whose records are encrypted with a key derived from the provided
+/// passphrase. Existing plaintext storage is going to get encrypted on its next flush.
+pub async fn setup_encrypted_fs_reply_surb_backend<P: AsRef<Path>>(
+    db_path: P,
+    surb_config: &config::ReplySurbs,
+    passphrase: &ReplyStoragePassphrase,
+) -> Result<fs_backend::Backend, ClientCoreError> {
+    setup_fs_reply_surb_backend_inner(db_path.as_ref(), surb_config, Some(passphrase)).await
+}
+
+/// Sets up the reply surb storage encrypted according to the `storage_encryption` setting
+/// of the provided config.
+pub async fn setup_configured_fs_reply_surb_backend<P: AsRef<Path>>(
+    db_path: P,
+    surb_config: &config::ReplySurbs,
+    key_store: &OnDiskKeys,
+) -> Result<fs_backend::Backend, ClientCoreError> {
+    match surb_config.storage_encryption {
+        ReplySurbStorageEncryption::Disabled => {
+            setup_fs_reply_surb_backend(db_path, surb_config).await
+        }
+        ReplySurbStorageEncryption::IdentityKey => {
+            let identity_keys = key_store.load_identity_keypair().map_err(|err| {
+                ClientCoreError::KeyStoreError {
+                    source: Box::new(err),
+                }
+            })?;
+            let passphrase =
+                ReplyStoragePassphrase::derive_from_identity(identity_keys.private_key());
+            setup_encrypted_fs_reply_surb_backend(db_path, surb_config, &passphrase).await
+        }
+    }
+}
+
+async fn setup_fs_reply_surb_backend_inner(
+    db_path: &Path,
+    surb_config: &config::ReplySurbs,
+    passphrase: Option<&ReplyStoragePassphrase>,
 ) -> Result<fs_backend::Backend, ClientCoreError> {
     // if the database file doesnt exist, initialise fresh storage, otherwise attempt to load
     // the existing one
-    let db_path = db_path.as_ref();
     if db_path.exists() {
         info!("loading existing surb database");
-        match fs_backend::Backend::try_load(db_path).await {
+        let backend = match passphrase {
+            Some(passphrase) => fs_backend::Backend::try_load_encrypted(db_path, passphrase).await,
+            None => fs_backend::Backend::try_load(db_path).await,
+        };
+        match backend {
             Ok(backend) => Ok(backend),
+            // the data is fine, we just can't read it - don't throw it away
+            Err(
+                err @ (fs_backend::StorageError::MissingPassphrase
+                | fs_backend::StorageError::InvalidPassphrase),
+            ) => Err(ClientCoreError::SurbStorageError {
+                source: Box::new(err),
+            }),
             Err(err) => {
                 error!("failed to setup persistent storage backend for our reply needs: {err}. We're going to create a fresh database instead. This behaviour might change in the future");
 
                 archive_corrupted_database(db_path)?;
-                setup_fresh_backend(db_path, surb_config).await
+                setup_fresh_backend(db_path, surb_config, passphrase).await
             }
         }
     } else {
-        setup_fresh_backend(db_path, surb_config).await
+        setup_fresh_backend(db_path, surb_config, passphrase).await
     }
 }
 
@@ -151,3 +210,50 @@ pub fn default_query_dkg_client(nyxd_url: Url) -> QueryHttpRpcNyxdClient {
     QueryHttpRpcNyxdClient::connect(client_config, nyxd_url.as_str())
         .expect("Could not construct query client")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::key_manager::ClientKeys;
+    use crate::config::disk_persistence::ClientKeysPaths;
+    use rand::rngs::OsRng;
+
+    #[tokio::test]
+    async fn reply_storage_is_encrypted_with_the_identity_key_if_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("surbs.sqlite");
+        let key_store = OnDiskKeys::new(ClientKeysPaths::new_base(dir.path()));
+        let surb_config = config::ReplySurbs {
+            storage_encryption: ReplySurbStorageEncryption::IdentityKey,
+            ..Default::default()
+        };
+
+        // the identity key has to be available
+        let res = setup_configured_fs_reply_surb_backend(&db_path, &surb_config, &key_store).await;
+        assert!(matches!(res, Err(ClientCoreError::KeyStoreError { .. })));
+
+        ClientKeys::generate_new(&mut OsRng)
+            .persist_keys(&key_store)
+            .await
+            .unwrap();
+        let mut backend =
+            setup_configured_fs_reply_surb_backend(&db_path, &surb_config, &key_store)
+                .await
+                .unwrap();
+        backend.start_storage_session().await.unwrap();
+        backend
+            .flush_surb_storage(&CombinedReplyStorage::new(10, 100))
+            .await
+            .unwrap();
+        backend.stop_storage_session().await.unwrap();
+
+        // the storage can't be opened without the key, but it doesn't get discarded either
+        let res = setup_fs_reply_surb_backend(&db_path, &surb_config).await;
+        assert!(matches!(res, Err(ClientCoreError::SurbStorageError { .. })));
+        assert!(
+            setup_configured_fs_reply_surb_backend(&db_path, &surb_config, &key_store)
+                .await
+                .is_ok()
+        );
+    }
+}
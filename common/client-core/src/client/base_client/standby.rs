@@ -0,0 +1,301 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Warm standby clients for minimising the downtime when the gateway of the primary client fails.
+//!
+//! The standby goes through the expensive part of the startup, i.e. the key setup and the gateway
+//! registration, ahead of time and is then kept suspended until it's needed. Promoting it only requires
+//! connecting to its (already registered) gateway and starting the client tasks.
+
+use crate::client::base_client::startup::MaybeSendSync;
+use crate::client::base_client::storage::helpers::{
+    get_all_registered_identities, set_active_gateway,
+};
+use crate::client::base_client::storage::MixnetClientStorage;
+use crate::client::base_client::{BaseClient, PreparedClient};
+use crate::client::key_manager::persistence::KeyStore;
+use crate::client::replies::reply_storage::ReplyStorageBackend;
+use crate::config::Config;
+use crate::error::ClientCoreError;
+use crate::init::registered_gateway_with_allowance;
+use crate::init::types::{GatewaySelectionSpecification, GatewaySetup};
+use log::{debug, info};
+use nym_client_core_gateways_storage::GatewaysDetailsStore;
+use nym_credential_storage::storage::Storage as CredentialStorage;
+use nym_crypto::asymmetric::identity;
+use nym_sphinx::addressing::clients::Recipient;
+use nym_topology::gateway;
+use nym_validator_client::nyxd::contract_traits::DkgQueryClient;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Mutex;
+
+/// Returns a copy of the primary client config suitable for its standby.
+/// The client id gets a `-standby` suffix so that the logs of both clients could be told apart.
+pub fn standby_config(primary: &Config) -> Config {
+    let mut config = primary.clone();
+    config.client.id = format!("{}-standby", primary.client.id);
+    config
+}
+
+/// Chooses the gateway for the standby of the client using the `primary` gateway.
+///
+/// Gateways we have previously registered with are preferred, starting with the ones still holding
+/// some of our bandwidth, so that the failover wouldn't require spending fresh credentials.
+/// Otherwise, a new gateway is chosen uniformly from the `available_gateways`.
+pub async fn standby_gateway_setup<D>(
+    details_store: &D,
+    primary: identity::PublicKey,
+    available_gateways: Vec<gateway::Node>,
+    must_use_tls: bool,
+) -> Result<GatewaySetup, ClientCoreError>
+where
+    D: GatewaysDetailsStore + Sync,
+    D::StorageError: Send + Sync + 'static,
+{
+    if let Some(setup) = registered_gateway_with_allowance(details_store, &[primary]).await? {
+        return Ok(setup);
+    }
+
+    let registered = get_all_registered_identities(details_store).await?;
+    if let Some(gateway_id) = registered.iter().find(|id| **id != primary) {
+        debug!("using previously registered gateway {gateway_id} for the standby client");
+        return Ok(GatewaySetup::MustLoad {
+            gateway_id: Some(gateway_id.to_base58_string()),
+        });
+    }
+
+    Ok(GatewaySetup::New {
+        specification: GatewaySelectionSpecification::UniformRemote { must_use_tls },
+        available_gateways: available_gateways
+            .into_iter()
+            .filter(|node| node.identity_key != primary)
+            .collect(),
+    })
+}
+
+/// Client that has been fully prepared (see [`BaseClientBuilder::prepare`](super::BaseClientBuilder::prepare))
+/// and is waiting to take over from the primary client.
+///
+/// Note that the standby doesn't detect the failures on its own. The embedder is expected to watch the
+/// primary, e.g. via its [`ClientEvent`](crate::client::events::ClientEvent)s, and call [`WarmStandby::promote`].
+pub struct WarmStandby<'a, C, S: MixnetClientStorage> {
+    address: Recipient,
+    gateway_id: identity::PublicKey,
+    promoted: AtomicBool,
+    prepared: Mutex<Option<PreparedClient<'a, C, S>>>,
+}
+
+impl<'a, C, S> WarmStandby<'a, C, S>
+where
+    S: MixnetClientStorage + 'static,
+    C: DkgQueryClient + Send + Sync + 'static,
+{
+    /// Suspends the provided client until it's promoted.
+    /// The connection to the gateway is released, since it would otherwise have to be kept alive
+    /// for as long as the primary works fine.
+    pub async fn new(mut prepared: PreparedClient<'a, C, S>) -> Self {
+        prepared.release_gateway_connection().await;
+
+        WarmStandby {
+            address: prepared.address(),
+            gateway_id: prepared.gateway_id(),
+            promoted: AtomicBool::new(false),
+            prepared: Mutex::new(Some(prepared)),
+        }
+    }
+
+    /// Address the client is going to use once promoted.
+    pub fn address(&self) -> Recipient {
+        self.address
+    }
+
+    /// Identity of the gateway the standby is going to connect to.
+    pub fn gateway_id(&self) -> identity::PublicKey {
+        self.gateway_id
+    }
+
+    /// Returns whether the standby is still available for promotion.
+    pub fn is_available(&self) -> bool {
+        !self.promoted.load(Ordering::Acquire)
+    }
+
+    /// Starts the standby client. Only the first call succeeds, all subsequent ones
+    /// (including the concurrent ones) return [`ClientCoreError::StandbyAlreadyPromoted`].
+    ///
+    /// The gateway of the standby becomes the active gateway of the client storage,
+    /// so that it keeps being used if the client gets restarted.
+    pub async fn promote(&self) -> Result<BaseClient, ClientCoreError>
+    where
+        S::ReplyStore: Send + Sync,
+        S::InboxStore: Send + Sync,
+        S::OutboxStore: Send + Sync,
+        S::GatewaysDetailsStore: MaybeSendSync,
+        <S::KeyStore as KeyStore>::StorageError: Send + Sync,
+        <S::ReplyStore as ReplyStorageBackend>::StorageError: Sync + Send,
+        <S::CredentialStore as CredentialStorage>::StorageError: Send + Sync + 'static,
+        <S::GatewaysDetailsStore as GatewaysDetailsStore>::StorageError: Sync + Send,
+    {
+        if self.promoted.swap(true, Ordering::AcqRel) {
+            return Err(ClientCoreError::StandbyAlreadyPromoted);
+        }
+        let prepared = self
+            .prepared
+            .lock()
+            .await
+            .take()
+            .ok_or(ClientCoreError::StandbyAlreadyPromoted)?;
+
+        info!(
+            "promoting the standby client using gateway {}",
+            self.gateway_id
+        );
+        set_active_gateway(
+            prepared.builder.client_store.gateway_details_store(),
+            &self.gateway_id.to_base58_string(),
+        )
+        .await?;
+        prepared.start().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::base_client::storage::helpers::store_client_keys;
+    use crate::client::base_client::storage::Ephemeral;
+    use crate::client::base_client::BaseClientBuilder;
+    use crate::client::key_manager::ClientKeys;
+    use nym_client_core_gateways_storage::{GatewayDetails, InMemGatewaysDetails};
+    use nym_crypto::asymmetric::encryption;
+    use nym_validator_client::QueryHttpRpcNyxdClient;
+    use rand::rngs::OsRng;
+    use std::time::Duration;
+
+    fn gateway_id() -> identity::PublicKey {
+        *identity::KeyPair::new(&mut OsRng).public_key()
+    }
+
+    fn gateway_node(identity_key: identity::PublicKey) -> gateway::Node {
+        gateway::Node {
+            owner: None,
+            host: "1.2.3.4".parse().unwrap(),
+            mix_host: "1.2.3.4:1789".parse().unwrap(),
+            clients_ws_port: 9000,
+            clients_wss_port: None,
+            identity_key,
+            sphinx_key: *encryption::KeyPair::new(&mut OsRng).public_key(),
+            version: "1.1.0".into(),
+        }
+    }
+
+    async fn register<D: GatewaysDetailsStore>(details_store: &D, gateway_id: identity::PublicKey) {
+        details_store
+            .store_gateway_details(&GatewayDetails::new_custom(gateway_id, None).into())
+            .await
+            .unwrap();
+    }
+
+    fn loaded_gateway(setup: &GatewaySetup) -> Option<&str> {
+        match setup {
+            GatewaySetup::MustLoad { gateway_id } => gateway_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn standby_prefers_the_registered_gateways_other_than_the_primary() {
+        let details_store = InMemGatewaysDetails::default();
+        let primary = gateway_id();
+        let other = gateway_id();
+        register(&details_store, primary).await;
+
+        // nothing else to reuse, so pick a fresh one, as long as it's not the primary
+        let setup = standby_gateway_setup(
+            &details_store,
+            primary,
+            vec![gateway_node(primary), gateway_node(other)],
+            false,
+        )
+        .await
+        .unwrap();
+        let GatewaySetup::New {
+            available_gateways, ..
+        } = setup
+        else {
+            panic!("expected a new gateway to be selected")
+        };
+        assert_eq!(available_gateways.len(), 1);
+        assert_eq!(available_gateways[0].identity_key, other);
+
+        let registered = gateway_id();
+        register(&details_store, registered).await;
+        let setup = standby_gateway_setup(&details_store, primary, Vec::new(), false)
+            .await
+            .unwrap();
+        assert_eq!(
+            loaded_gateway(&setup),
+            Some(registered.to_base58_string().as_str())
+        );
+
+        // unless there's another registered gateway that still holds some of our bandwidth
+        let with_allowance = gateway_id();
+        register(&details_store, with_allowance).await;
+        details_store
+            .record_gateway_bandwidth(with_allowance, 1000)
+            .await
+            .unwrap();
+        let setup = standby_gateway_setup(&details_store, primary, Vec::new(), false)
+            .await
+            .unwrap();
+        assert_eq!(
+            loaded_gateway(&setup),
+            Some(with_allowance.to_base58_string().as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn standby_can_only_be_promoted_once() {
+        let primary_storage = Ephemeral::default();
+        let client_store = primary_storage.new_standby();
+        let keys = ClientKeys::generate_new(&mut OsRng);
+        store_client_keys(keys.clone(), client_store.key_store())
+            .await
+            .unwrap();
+        let standby_gateway = gateway_id();
+        register(client_store.gateway_details_store(), standby_gateway).await;
+
+        let mut config = standby_config(&Config::new("primary", "1.0.0"));
+        assert_eq!(config.client.id, "primary-standby");
+        // make sure the startup can't reach anything
+        config.client.nym_api_urls = vec!["http://127.0.0.1:1".parse().unwrap()];
+        config.client.nyxd_urls = vec!["http://127.0.0.1:1".parse().unwrap()];
+
+        let prepared =
+            BaseClientBuilder::<QueryHttpRpcNyxdClient, _>::new(&config, client_store, None)
+                .with_gateway_setup(GatewaySetup::MustLoad {
+                    gateway_id: Some(standby_gateway.to_base58_string()),
+                })
+                .prepare()
+                .await
+                .unwrap();
+
+        let standby = WarmStandby::new(prepared).await;
+        assert!(standby.is_available());
+        assert_eq!(standby.gateway_id(), standby_gateway);
+        assert_eq!(*standby.address().identity(), keys.identity_public_key());
+
+        // there's no network for the client to start in, but the standby is used up regardless
+        let promoted = tokio::time::timeout(Duration::from_secs(10), standby.promote())
+            .await
+            .unwrap();
+        assert!(matches!(
+            promoted,
+            Err(ClientCoreError::InsufficientNetworkTopology(_))
+        ));
+        assert!(!standby.is_available());
+        assert!(matches!(
+            standby.promote().await,
+            Err(ClientCoreError::StandbyAlreadyPromoted)
+        ));
+    }
+}
@@ -0,0 +1,337 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::base_client::GatewayConnection;
+use crate::client::helpers::{sleep, timeout};
+use crate::error::{ClientCoreError, ClientCoreStatusMessage};
+use crate::spawn_future;
+use futures::channel::oneshot;
+use log::{error, info, warn};
+use nym_task::TaskClient;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::os::raw::c_int as RawFd;
+use std::time::Duration;
+
+/// Bound on the storages moved into the startup task, which only has to be thread-safe
+/// on native targets, where the startup might continue on another thread in the background.
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSendSync: Send + Sync {}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send + Sync> MaybeSendSync for T {}
+
+#[cfg(target_arch = "wasm32")]
+pub trait MaybeSendSync {}
+
+#[cfg(target_arch = "wasm32")]
+impl<T> MaybeSendSync for T {}
+
+/// Stages of the client startup that might take a considerable amount of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupStage {
+    /// Loading (or choosing and registering with) the gateway.
+    GatewaySetup,
+
+    /// Obtaining the initial network topology.
+    Topology,
+
+    /// Establishing and authenticating the gateway connection.
+    GatewayConnection,
+}
+
+impl Display for StartupStage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartupStage::GatewaySetup => write!(f, "gateway setup"),
+            StartupStage::Topology => write!(f, "network topology retrieval"),
+            StartupStage::GatewayConnection => write!(f, "gateway connection"),
+        }
+    }
+}
+
+/// Allows retrying the failed startup stages, rather than failing immediately,
+/// when the client is allowed to start in the degraded mode.
+pub(crate) struct StartupRetries {
+    interval: Duration,
+    task_client: TaskClient,
+}
+
+impl StartupRetries {
+    pub(crate) fn new(interval: Duration, mut task_client: TaskClient) -> Self {
+        // the retries are done once the startup completes
+        task_client.disarm();
+        StartupRetries {
+            interval,
+            task_client,
+        }
+    }
+
+    /// Waits before the next attempt of the failed stage.
+    /// Returns the original error if the client got shut down in the meantime.
+    pub(crate) async fn wait_for_retry(
+        &mut self,
+        stage: StartupStage,
+        attempt: &mut u32,
+        err: ClientCoreError,
+    ) -> Result<(), ClientCoreError> {
+        warn!(
+            "failed to complete the {stage} (attempt {attempt}): {err}. retrying in {:?}",
+            self.interval
+        );
+        self.task_client
+            .send_status_msg(Box::new(ClientCoreStatusMessage::StartupStageFailed {
+                stage,
+                attempt: *attempt,
+            }));
+        *attempt += 1;
+
+        tokio::select! {
+            _ = self.task_client.recv() => Err(err),
+            _ = sleep(self.interval) => Ok(()),
+        }
+    }
+}
+
+/// Spawns the remaining startup procedure as a separate task so that it could continue
+/// even if the client is returned before it's done.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn spawn_startup<F>(startup: F) -> oneshot::Receiver<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let (result_sender, result_receiver) = oneshot::channel();
+    spawn_future(async move {
+        let _ = result_sender.send(startup.await);
+    });
+    result_receiver
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn spawn_startup<F>(startup: F) -> oneshot::Receiver<F::Output>
+where
+    F: Future + 'static,
+{
+    let (result_sender, result_receiver) = oneshot::channel();
+    spawn_future(async move {
+        let _ = result_sender.send(startup.await);
+    });
+    result_receiver
+}
+
+/// Waits (for at most `deadline`) for the startup to complete, failing with
+/// [`ClientCoreError::StartupDeadlineExceeded`] at the stage it's got stuck at otherwise.
+pub(crate) async fn await_startup_within_deadline<F, T>(
+    startup: F,
+    deadline: Duration,
+    stage: impl Fn() -> StartupStage,
+) -> Result<T, ClientCoreError>
+where
+    F: Future<Output = Result<T, ClientCoreError>>,
+{
+    timeout(deadline, startup)
+        .await
+        .map_err(|_| ClientCoreError::StartupDeadlineExceeded { stage: stage() })?
+}
+
+/// Waits (for at most `deadline`) for the remaining components to get started in the background.
+/// If that doesn't happen in time, the client is returned in the degraded mode and the startup
+/// keeps on going, with the gateway connection getting established once it's done.
+pub(crate) async fn await_background_startup(
+    mut startup_result: oneshot::Receiver<Result<Option<RawFd>, ClientCoreError>>,
+    deadline: Duration,
+    gateway_connection: GatewayConnection,
+    mut status_client: TaskClient,
+) -> Result<(), ClientCoreError> {
+    // this client is only used for reporting the progress
+    status_client.disarm();
+
+    match timeout(deadline, &mut startup_result).await {
+        Ok(Ok(result)) => {
+            gateway_connection.set_established(result?);
+            Ok(())
+        }
+        Ok(Err(_)) => Err(ClientCoreError::BackgroundStartupFailure),
+        Err(_) => {
+            warn!("the client has not started within the deadline - continuing in the degraded mode while the startup completes in the background");
+            status_client.send_status_msg(Box::new(ClientCoreStatusMessage::DegradedStartup));
+
+            spawn_future(async move {
+                match startup_result.await {
+                    Ok(Ok(gateway_ws_fd)) => {
+                        info!("the client has completed its startup");
+                        gateway_connection.set_established(gateway_ws_fd);
+                        status_client
+                            .send_status_msg(Box::new(ClientCoreStatusMessage::StartupCompleted));
+                    }
+                    Ok(Err(err)) => {
+                        if !status_client.is_shutdown_poll() {
+                            error!(
+                                "failed to complete the client startup in the background: {err}"
+                            );
+                            status_client.send_we_stopped(Box::new(err));
+                        }
+                    }
+                    Err(_) => {
+                        if !status_client.is_shutdown_poll() {
+                            error!("the background client startup has been aborted");
+                            status_client.send_we_stopped(Box::new(
+                                ClientCoreError::BackgroundStartupFailure,
+                            ));
+                        }
+                    }
+                }
+            });
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use nym_task::manager::TaskStatus;
+    use nym_task::{StatusReceiver, TaskManager};
+
+    async fn status_messages(task_manager: &mut TaskManager) -> StatusReceiver {
+        let (status_sender, mut status_receiver) = futures::channel::mpsc::channel(16);
+        task_manager
+            .start_status_listener(status_sender, TaskStatus::Ready)
+            .await;
+        // the initial status
+        status_receiver.next().await.unwrap();
+        status_receiver
+    }
+
+    #[tokio::test]
+    async fn startup_exceeding_the_deadline_fails_at_the_current_stage() {
+        let res = await_startup_within_deadline(
+            futures::future::pending::<Result<(), ClientCoreError>>(),
+            Duration::from_millis(10),
+            || StartupStage::Topology,
+        )
+        .await;
+        assert!(matches!(
+            res,
+            Err(ClientCoreError::StartupDeadlineExceeded {
+                stage: StartupStage::Topology
+            })
+        ));
+
+        let res = await_startup_within_deadline(async { Ok(42) }, Duration::from_secs(10), || {
+            StartupStage::Topology
+        })
+        .await;
+        assert_eq!(res.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn startup_within_deadline_establishes_the_connection() {
+        let task_manager = TaskManager::default();
+        let connection = GatewayConnection::default();
+
+        let startup = spawn_startup(async { Ok(Some(42)) });
+        await_background_startup(
+            startup,
+            Duration::from_secs(10),
+            connection.clone(),
+            task_manager.subscribe(),
+        )
+        .await
+        .unwrap();
+        assert!(connection.is_established());
+        assert_eq!(connection.gateway_ws_fd(), Some(42));
+
+        // failures within the deadline are returned immediately
+        let startup = spawn_startup(async { Err(ClientCoreError::BackgroundStartupFailure) });
+        assert!(await_background_startup(
+            startup,
+            Duration::from_secs(10),
+            GatewayConnection::default(),
+            task_manager.subscribe(),
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn degraded_startup_completes_in_the_background() {
+        let mut task_manager = TaskManager::default();
+        let mut statuses = status_messages(&mut task_manager).await;
+        let connection = GatewayConnection::default();
+
+        let (finish, finished) = oneshot::channel::<()>();
+        let startup = spawn_startup(async move {
+            let _ = finished.await;
+            Ok(Some(42))
+        });
+
+        // the client is returned before the startup completes, without the connection
+        await_background_startup(
+            startup,
+            Duration::from_millis(10),
+            connection.clone(),
+            task_manager.subscribe(),
+        )
+        .await
+        .unwrap();
+        assert!(!connection.is_established());
+        assert_eq!(connection.gateway_ws_fd(), None);
+        assert_eq!(
+            statuses.next().await.unwrap().to_string(),
+            ClientCoreStatusMessage::DegradedStartup.to_string()
+        );
+
+        // and gets established once it does
+        finish.send(()).unwrap();
+        assert_eq!(
+            statuses.next().await.unwrap().to_string(),
+            ClientCoreStatusMessage::StartupCompleted.to_string()
+        );
+        assert!(connection.is_established());
+        assert_eq!(connection.gateway_ws_fd(), Some(42));
+    }
+
+    #[tokio::test]
+    async fn failed_stages_are_retried_until_shutdown() {
+        let mut task_manager = TaskManager::default();
+        let mut statuses = status_messages(&mut task_manager).await;
+        let mut retries = StartupRetries::new(Duration::from_millis(1), task_manager.subscribe());
+
+        let mut attempt = 1;
+        retries
+            .wait_for_retry(
+                StartupStage::Topology,
+                &mut attempt,
+                ClientCoreError::BackgroundStartupFailure,
+            )
+            .await
+            .unwrap();
+        assert_eq!(attempt, 2);
+        assert_eq!(
+            statuses.next().await.unwrap().to_string(),
+            ClientCoreStatusMessage::StartupStageFailed {
+                stage: StartupStage::Topology,
+                attempt: 1,
+            }
+            .to_string()
+        );
+
+        // once the client is shut down, the original error is returned instead
+        let mut retries = StartupRetries::new(Duration::from_secs(60), task_manager.subscribe());
+        task_manager.signal_shutdown().unwrap();
+        let res = retries
+            .wait_for_retry(
+                StartupStage::GatewayConnection,
+                &mut attempt,
+                ClientCoreError::BackgroundStartupFailure,
+            )
+            .await;
+        assert!(matches!(
+            res,
+            Err(ClientCoreError::BackgroundStartupFailure)
+        ));
+    }
+}
@@ -4,8 +4,11 @@
 use crate::client::key_manager::persistence::KeyStore;
 use crate::client::key_manager::ClientKeys;
 use crate::error::ClientCoreError;
-use nym_client_core_gateways_storage::{ActiveGateway, GatewayRegistration, GatewaysDetailsStore};
+use nym_client_core_gateways_storage::{
+    ActiveGateway, GatewayBandwidth, GatewayLatency, GatewayRegistration, GatewaysDetailsStore,
+};
 use nym_crypto::asymmetric::identity;
+use std::time::Duration;
 
 // helpers for error wrapping
 pub async fn set_active_gateway<D>(
@@ -132,6 +135,68 @@ where
         })
 }
 
+pub async fn record_gateway_latency<D>(
+    details_store: &D,
+    gateway_id: identity::PublicKey,
+    latency: Duration,
+) -> Result<(), ClientCoreError>
+where
+    D: GatewaysDetailsStore,
+    D::StorageError: Send + Sync + 'static,
+{
+    details_store
+        .record_gateway_latency(gateway_id, latency)
+        .await
+        .map_err(|source| ClientCoreError::GatewaysDetailsStoreError {
+            source: Box::new(source),
+        })
+}
+
+pub async fn load_gateway_latencies<D>(
+    details_store: &D,
+) -> Result<Vec<GatewayLatency>, ClientCoreError>
+where
+    D: GatewaysDetailsStore,
+    D::StorageError: Send + Sync + 'static,
+{
+    details_store.gateway_latencies().await.map_err(|source| {
+        ClientCoreError::GatewaysDetailsStoreError {
+            source: Box::new(source),
+        }
+    })
+}
+
+pub async fn record_gateway_bandwidth<D>(
+    details_store: &D,
+    gateway_id: identity::PublicKey,
+    remaining: i64,
+) -> Result<(), ClientCoreError>
+where
+    D: GatewaysDetailsStore,
+    D::StorageError: Send + Sync + 'static,
+{
+    details_store
+        .record_gateway_bandwidth(gateway_id, remaining)
+        .await
+        .map_err(|source| ClientCoreError::GatewaysDetailsStoreError {
+            source: Box::new(source),
+        })
+}
+
+pub async fn load_gateway_bandwidths<D>(
+    details_store: &D,
+) -> Result<Vec<GatewayBandwidth>, ClientCoreError>
+where
+    D: GatewaysDetailsStore,
+    D::StorageError: Send + Sync + 'static,
+{
+    details_store.gateway_bandwidths().await.map_err(|source| {
+        ClientCoreError::GatewaysDetailsStoreError {
+            source: Box::new(source),
+        }
+    })
+}
+
 pub async fn load_client_keys<K>(key_store: &K) -> Result<ClientKeys, ClientCoreError>
 where
     K: KeyStore,
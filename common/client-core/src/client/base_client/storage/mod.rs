@@ -4,11 +4,15 @@
 // TODO: combine those more closely. Perhaps into a single underlying store.
 // Like for persistent, on-disk, storage, what's the point of having 3 different databases?
 
+use crate::client::inbox::{self, InboxStorage};
 use crate::client::key_manager::persistence::{InMemEphemeralKeys, KeyStore};
+use crate::client::outbox::{self, OutboxStorage};
 use crate::client::replies::reply_storage;
 use crate::client::replies::reply_storage::ReplyStorageBackend;
 use nym_credential_storage::ephemeral_storage::EphemeralStorage as EphemeralCredentialStorage;
 use nym_credential_storage::storage::Storage as CredentialStorage;
+use nym_crypto::asymmetric::identity::IdentitySigner;
+use std::sync::Arc;
 
 #[cfg(all(
     not(target_arch = "wasm32"),
@@ -17,14 +21,20 @@ use nym_credential_storage::storage::Storage as CredentialStorage;
 ))]
 use crate::{
     client::{
-        base_client::non_wasm_helpers, key_manager::persistence::OnDiskKeys,
-        replies::reply_storage::fs_backend,
+        base_client::non_wasm_helpers, inbox::OnDiskInbox, key_manager::persistence::OnDiskKeys,
+        outbox::SqliteOutbox, replies::reply_storage::fs_backend,
     },
     config::{self, disk_persistence::CommonClientPaths},
     error::ClientCoreError,
 };
 #[cfg(all(not(target_arch = "wasm32"), feature = "fs-credentials-storage"))]
 use nym_credential_storage::persistent_storage::PersistentStorage as PersistentCredentialStorage;
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    feature = "fs-surb-storage",
+    feature = "fs-gateways-storage"
+))]
+use std::path::PathBuf;
 
 pub use nym_client_core_gateways_storage as gateways_storage;
 pub use nym_client_core_gateways_storage::{GatewaysDetailsStore, InMemGatewaysDetails};
@@ -48,6 +58,8 @@ pub trait MixnetClientStorage {
     type ReplyStore: ReplyStorageBackend;
     type CredentialStore: CredentialStorage;
     type GatewaysDetailsStore: GatewaysDetailsStore;
+    type InboxStore: InboxStorage;
+    type OutboxStore: OutboxStorage;
 
     fn into_runtime_stores(
         self,
@@ -55,12 +67,16 @@ pub trait MixnetClientStorage {
         Self::ReplyStore,
         Self::CredentialStore,
         Self::GatewaysDetailsStore,
+        Self::InboxStore,
+        Self::OutboxStore,
     );
 
     fn key_store(&self) -> &Self::KeyStore;
     fn reply_store(&self) -> &Self::ReplyStore;
     fn credential_store(&self) -> &Self::CredentialStore;
     fn gateway_details_store(&self) -> &Self::GatewaysDetailsStore;
+    fn inbox_store(&self) -> &Self::InboxStore;
+    fn outbox_store(&self) -> &Self::OutboxStore;
 }
 
 #[derive(Default)]
@@ -69,12 +85,35 @@ pub struct Ephemeral {
     reply_store: reply_storage::Empty,
     credential_store: EphemeralCredentialStorage,
     gateway_details_store: InMemGatewaysDetails,
+    inbox_store: inbox::Disabled,
+    outbox_store: outbox::Disabled,
 }
 
 impl Ephemeral {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Use the provided external signer as the client identity rather than generating a fresh identity key.
+    #[must_use]
+    pub fn with_external_identity(mut self, identity_signer: Arc<dyn IdentitySigner>) -> Self {
+        self.key_store = InMemEphemeralKeys::default().with_external_identity(identity_signer);
+        self
+    }
+
+    /// Creates the storage for a warm standby (see [`WarmStandby`](super::standby::WarmStandby))
+    /// of the client using this storage. Apart from the credentials, which are shared with the primary,
+    /// the standby gets its own ephemeral stores, including a fresh set of keys.
+    pub fn new_standby(&self) -> Self {
+        let mut standby = Ephemeral {
+            credential_store: self.credential_store.clone(),
+            ..Default::default()
+        };
+        if let Some(identity_signer) = self.key_store.external_identity() {
+            standby = standby.with_external_identity(identity_signer);
+        }
+        standby
+    }
 }
 
 impl MixnetClientStorage for Ephemeral {
@@ -82,6 +121,8 @@ impl MixnetClientStorage for Ephemeral {
     type ReplyStore = reply_storage::Empty;
     type CredentialStore = EphemeralCredentialStorage;
     type GatewaysDetailsStore = InMemGatewaysDetails;
+    type InboxStore = inbox::Disabled;
+    type OutboxStore = outbox::Disabled;
 
     fn into_runtime_stores(
         self,
@@ -89,11 +130,15 @@ impl MixnetClientStorage for Ephemeral {
         Self::ReplyStore,
         Self::CredentialStore,
         Self::GatewaysDetailsStore,
+        Self::InboxStore,
+        Self::OutboxStore,
     ) {
         (
             self.reply_store,
             self.credential_store,
             self.gateway_details_store,
+            self.inbox_store,
+            self.outbox_store,
         )
     }
 
@@ -112,6 +157,14 @@ impl MixnetClientStorage for Ephemeral {
     fn gateway_details_store(&self) -> &Self::GatewaysDetailsStore {
         &self.gateway_details_store
     }
+
+    fn inbox_store(&self) -> &Self::InboxStore {
+        &self.inbox_store
+    }
+
+    fn outbox_store(&self) -> &Self::OutboxStore {
+        &self.outbox_store
+    }
 }
 
 #[cfg(all(
@@ -124,6 +177,8 @@ pub struct OnDiskPersistent {
     pub(crate) reply_store: fs_backend::Backend,
     pub(crate) credential_store: PersistentCredentialStorage,
     pub(crate) gateway_details_store: OnDiskGatewaysDetails,
+    pub(crate) inbox_store: Option<OnDiskInbox>,
+    pub(crate) outbox_store: Option<SqliteOutbox>,
 }
 
 #[cfg(all(
@@ -143,18 +198,80 @@ impl OnDiskPersistent {
             reply_store,
             credential_store,
             gateway_details_store,
+            inbox_store: None,
+            outbox_store: None,
         }
     }
 
+    /// Persist all received messages in the provided inbox until they're explicitly acknowledged.
+    #[must_use]
+    pub fn with_persistent_inbox(mut self, inbox: OnDiskInbox) -> Self {
+        self.inbox_store = Some(inbox);
+        self
+    }
+
+    /// Journal all outbound messages in the provided outbox until they're fully processed,
+    /// so that they could be replayed if the client got stopped in the meantime.
+    #[must_use]
+    pub fn with_persistent_outbox(mut self, outbox: SqliteOutbox) -> Self {
+        self.outbox_store = Some(outbox);
+        self
+    }
+
+    /// Use the provided external signer as the client identity, so that the identity private key
+    /// is never read from nor written to the disk.
+    #[must_use]
+    pub fn with_external_identity(mut self, identity_signer: Arc<dyn IdentitySigner>) -> Self {
+        self.key_store = self.key_store.with_external_identity(identity_signer);
+        self
+    }
+
+    /// Creates the storage for a warm standby (see [`WarmStandby`](super::standby::WarmStandby))
+    /// of the client using this storage, with `paths` being the ones the primary storage got created from.
+    ///
+    /// The credentials are shared through the same connection pool, so that none of the tickets
+    /// could get spent twice, whilst the keys and gateway registrations are read from the same files.
+    /// The reply SURBs are kept in the separate `reply_surb_database`, as the primary persists
+    /// its own ones on shutdown. Neither the inbox nor the outbox are carried over.
+    pub async fn new_standby(
+        &self,
+        paths: CommonClientPaths,
+        reply_surb_database: PathBuf,
+        debug_config: &config::DebugConfig,
+    ) -> Result<Self, ClientCoreError> {
+        let mut key_store = OnDiskKeys::new(paths.keys);
+        if let Some(identity_signer) = self.key_store.external_identity() {
+            key_store = key_store.with_external_identity(identity_signer);
+        }
+
+        let reply_store = non_wasm_helpers::setup_configured_fs_reply_surb_backend(
+            reply_surb_database,
+            &debug_config.reply_surbs,
+            &key_store,
+        )
+        .await?;
+
+        let gateway_details_store =
+            non_wasm_helpers::setup_fs_gateways_storage(paths.gateway_registrations).await?;
+
+        Ok(OnDiskPersistent::new(
+            key_store,
+            reply_store,
+            self.credential_store.clone(),
+            gateway_details_store,
+        ))
+    }
+
     pub async fn from_paths(
         paths: CommonClientPaths,
         debug_config: &config::DebugConfig,
     ) -> Result<Self, ClientCoreError> {
         let key_store = OnDiskKeys::new(paths.keys);
 
-        let reply_store = non_wasm_helpers::setup_fs_reply_surb_backend(
+        let reply_store = non_wasm_helpers::setup_configured_fs_reply_surb_backend(
             paths.reply_surb_database,
             &debug_config.reply_surbs,
+            &key_store,
         )
         .await?;
 
@@ -169,6 +286,8 @@ impl OnDiskPersistent {
             reply_store,
             credential_store,
             gateway_details_store,
+            inbox_store: None,
+            outbox_store: None,
         })
     }
 }
@@ -183,6 +302,8 @@ impl MixnetClientStorage for OnDiskPersistent {
     type ReplyStore = fs_backend::Backend;
     type CredentialStore = PersistentCredentialStorage;
     type GatewaysDetailsStore = OnDiskGatewaysDetails;
+    type InboxStore = Option<OnDiskInbox>;
+    type OutboxStore = Option<SqliteOutbox>;
 
     fn into_runtime_stores(
         self,
@@ -190,11 +311,15 @@ impl MixnetClientStorage for OnDiskPersistent {
         Self::ReplyStore,
         Self::CredentialStore,
         Self::GatewaysDetailsStore,
+        Self::InboxStore,
+        Self::OutboxStore,
     ) {
         (
             self.reply_store,
             self.credential_store,
             self.gateway_details_store,
+            self.inbox_store,
+            self.outbox_store,
         )
     }
 
@@ -213,4 +338,12 @@ impl MixnetClientStorage for OnDiskPersistent {
     fn gateway_details_store(&self) -> &Self::GatewaysDetailsStore {
         &self.gateway_details_store
     }
+
+    fn inbox_store(&self) -> &Self::InboxStore {
+        &self.inbox_store
+    }
+
+    fn outbox_store(&self) -> &Self::OutboxStore {
+        &self.outbox_store
+    }
 }
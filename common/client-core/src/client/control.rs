@@ -0,0 +1,178 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runtime reconfiguration of an already running client.
+//!
+//! Most of the client parameters are fixed once the client has started, however, some of them,
+//! such as the traffic rates, can be safely adjusted on the fly through the [`ClientControl`]
+//! handle, for example to lower the bandwidth (and battery) usage of a mobile application
+//! that got moved to the background.
+
+use crate::config::{DebugConfig, TrafficScheduling};
+use crate::error::ClientCoreError;
+use log::*;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Subset of the client parameters that can be changed at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuntimeParameters {
+    /// The parameter of Poisson distribution determining how long, on average,
+    /// sent packet is going to be delayed at any given mix node.
+    pub average_packet_delay: Duration,
+
+    /// The parameter of Poisson distribution determining how long, on average,
+    /// it is going to take for another loop cover traffic message to be sent.
+    pub loop_cover_traffic_average_delay: Duration,
+
+    /// The frequency at which the network topology gets refreshed.
+    pub topology_refresh_rate: Duration,
+
+    /// The model used for scheduling packets of the real and the loop cover traffic streams.
+    pub traffic_scheduling: TrafficScheduling,
+}
+
+impl RuntimeParameters {
+    pub(crate) fn new(debug_config: &DebugConfig) -> Self {
+        RuntimeParameters {
+            average_packet_delay: debug_config.traffic.average_packet_delay,
+            loop_cover_traffic_average_delay: debug_config
+                .cover_traffic
+                .loop_cover_traffic_average_delay,
+            topology_refresh_rate: debug_config.topology.topology_refresh_rate,
+            traffic_scheduling: debug_config.traffic.scheduling,
+        }
+    }
+}
+
+/// Handle allowing to adjust parameters of a running client.
+/// All the changes are picked up by the relevant components without having to restart the client.
+#[derive(Debug, Clone)]
+pub struct ClientControl {
+    sender: Arc<watch::Sender<RuntimeParameters>>,
+}
+
+impl ClientControl {
+    pub(crate) fn new(initial: RuntimeParameters) -> Self {
+        let (sender, _) = watch::channel(initial);
+        ClientControl {
+            sender: Arc::new(sender),
+        }
+    }
+
+    /// Returns the currently used runtime parameters.
+    pub fn parameters(&self) -> RuntimeParameters {
+        *self.sender.borrow()
+    }
+
+    pub fn set_average_packet_delay(&self, delay: Duration) {
+        self.update(|params| params.average_packet_delay = delay)
+    }
+
+    pub fn set_loop_cover_traffic_average_delay(
+        &self,
+        delay: Duration,
+    ) -> Result<(), ClientCoreError> {
+        if delay.is_zero() {
+            return Err(ClientCoreError::InvalidRuntimeParameter {
+                parameter: "loop_cover_traffic_average_delay",
+            });
+        }
+        self.update(|params| params.loop_cover_traffic_average_delay = delay);
+        Ok(())
+    }
+
+    pub fn set_topology_refresh_rate(&self, refresh_rate: Duration) -> Result<(), ClientCoreError> {
+        if refresh_rate.is_zero() {
+            return Err(ClientCoreError::InvalidRuntimeParameter {
+                parameter: "topology_refresh_rate",
+            });
+        }
+        self.update(|params| params.topology_refresh_rate = refresh_rate);
+        Ok(())
+    }
+
+    /// Switches the traffic streams to a different scheduling model.
+    /// Note that any model other than `Poisson` decreases overall anonymity.
+    pub fn set_traffic_scheduling(&self, scheduling: TrafficScheduling) {
+        self.update(|params| params.traffic_scheduling = scheduling)
+    }
+
+    fn update<F: FnOnce(&mut RuntimeParameters)>(&self, update: F) {
+        self.sender.send_if_modified(|params| {
+            let old = *params;
+            update(params);
+            if old != *params {
+                debug!("updating runtime parameters: {old:?} -> {params:?}");
+                true
+            } else {
+                false
+            }
+        });
+    }
+
+    pub(crate) fn subscribe(&self) -> RuntimeParametersListener {
+        RuntimeParametersListener {
+            receiver: self.sender.subscribe(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct RuntimeParametersListener {
+    receiver: watch::Receiver<RuntimeParameters>,
+}
+
+impl RuntimeParametersListener {
+    /// Returns the new parameters if they have changed since the last check.
+    pub(crate) fn try_changed(&mut self) -> Option<RuntimeParameters> {
+        if self.receiver.has_changed().unwrap_or_default() {
+            Some(*self.receiver.borrow_and_update())
+        } else {
+            None
+        }
+    }
+
+    /// Waits until the parameters change. Returns `None` if the control handle got dropped.
+    pub(crate) async fn changed(&mut self) -> Option<RuntimeParameters> {
+        self.receiver.changed().await.ok()?;
+        Some(*self.receiver.borrow_and_update())
+    }
+}
+
+/// Waits for the next change of the runtime parameters if the component is listening for them at all.
+pub(crate) async fn next_parameters_change(
+    listener: Option<&mut RuntimeParametersListener>,
+) -> Option<RuntimeParameters> {
+    match listener {
+        Some(listener) => listener.changed().await,
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listeners_only_observe_actual_changes() {
+        let control = ClientControl::new(RuntimeParameters::new(&DebugConfig::default()));
+        let mut listener = control.subscribe();
+        assert!(listener.try_changed().is_none());
+
+        let current = control.parameters();
+        control.set_average_packet_delay(current.average_packet_delay);
+        assert!(listener.try_changed().is_none());
+
+        control.set_average_packet_delay(current.average_packet_delay * 2);
+        assert_eq!(
+            listener.try_changed().unwrap().average_packet_delay,
+            current.average_packet_delay * 2
+        );
+        assert!(listener.try_changed().is_none());
+
+        assert!(control.set_topology_refresh_rate(Duration::ZERO).is_err());
+        assert!(listener.try_changed().is_none());
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tracking of clients we have recently sent real messages to, so that the cover traffic stream
+//! could occasionally target them (rather than only ourselves) and make genuine conversations
+//! less distinguishable from the background noise.
+
+use crate::client::helpers::{get_time_now, Instant};
+use nym_sphinx::addressing::clients::Recipient;
+use rand::seq::IteratorRandom;
+use rand::Rng;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Maximum number of correspondents we keep track of at any given time.
+const MAX_TRACKED_CORRESPONDENTS: usize = 16;
+
+/// Duration after which a correspondent is forgotten if we haven't sent them anything.
+const CORRESPONDENT_EXPIRY: Duration = Duration::from_secs(10 * 60);
+
+/// Minimum amount of time between two cover packets sent to the same correspondent.
+const MIN_CORRESPONDENT_COVER_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy)]
+struct Correspondent {
+    address: Recipient,
+    last_message: Instant,
+    last_cover: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RecentCorrespondents {
+    inner: Arc<Mutex<Vec<Correspondent>>>,
+}
+
+impl RecentCorrespondents {
+    pub(crate) fn new() -> Self {
+        Default::default()
+    }
+
+    /// Records that a real message has just been sent to the provided recipient.
+    pub(crate) fn record(&self, address: Recipient) {
+        let now = get_time_now();
+
+        #[allow(clippy::unwrap_used)]
+        let mut guard = self.inner.lock().unwrap();
+        guard.retain(|c| now.duration_since(c.last_message) < CORRESPONDENT_EXPIRY);
+
+        if let Some(existing) = guard.iter_mut().find(|c| c.address == address) {
+            existing.last_message = now;
+            return;
+        }
+
+        if guard.len() >= MAX_TRACKED_CORRESPONDENTS {
+            // evict whoever we haven't talked to for the longest time
+            if let Some((oldest, _)) = guard.iter().enumerate().min_by_key(|(_, c)| c.last_message)
+            {
+                guard.swap_remove(oldest);
+            }
+        }
+
+        guard.push(Correspondent {
+            address,
+            last_message: now,
+            last_cover: None,
+        })
+    }
+
+    /// Chooses a random correspondent that is currently allowed to receive another cover packet
+    /// (and marks it as such).
+    pub(crate) fn choose_cover_target<R: Rng>(&self, rng: &mut R) -> Option<Recipient> {
+        let now = get_time_now();
+
+        #[allow(clippy::unwrap_used)]
+        let mut guard = self.inner.lock().unwrap();
+        let target = guard
+            .iter_mut()
+            .filter(|c| now.duration_since(c.last_message) < CORRESPONDENT_EXPIRY)
+            .filter(|c| {
+                c.last_cover.map_or(true, |last_cover| {
+                    now.duration_since(last_cover) >= MIN_CORRESPONDENT_COVER_INTERVAL
+                })
+            })
+            .choose(rng)?;
+
+        target.last_cover = Some(now);
+        Some(target.address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_crypto::asymmetric::{encryption, identity};
+    use rand::rngs::OsRng;
+
+    fn random_recipient() -> Recipient {
+        let mut rng = OsRng;
+        Recipient::new(
+            *identity::KeyPair::new(&mut rng).public_key(),
+            *encryption::KeyPair::new(&mut rng).public_key(),
+            *identity::KeyPair::new(&mut rng).public_key(),
+        )
+    }
+
+    #[test]
+    fn cover_targets_are_rate_limited() {
+        let correspondents = RecentCorrespondents::new();
+        let recipient = random_recipient();
+        correspondents.record(recipient);
+
+        assert_eq!(
+            correspondents.choose_cover_target(&mut OsRng),
+            Some(recipient)
+        );
+        assert!(correspondents.choose_cover_target(&mut OsRng).is_none());
+    }
+
+    #[test]
+    fn number_of_tracked_correspondents_is_bounded() {
+        let correspondents = RecentCorrespondents::new();
+        for _ in 0..MAX_TRACKED_CORRESPONDENTS + 5 {
+            correspondents.record(random_recipient());
+        }
+
+        assert_eq!(
+            correspondents.inner.lock().unwrap().len(),
+            MAX_TRACKED_CORRESPONDENTS
+        );
+    }
+}
@@ -1,19 +1,28 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::control::{
+    next_parameters_change, RuntimeParameters, RuntimeParametersListener,
+};
+use crate::client::correspondents::RecentCorrespondents;
+use crate::client::helpers::get_time_now;
+use crate::client::key_manager::ManagedKeys;
 use crate::client::mix_traffic::BatchMixMessageSender;
 use crate::client::packet_statistics_control::{PacketStatisticsEvent, PacketStatisticsReporter};
+use crate::client::real_traffic_rate::{AdaptiveCoverRate, RealTrafficCounter};
+use crate::client::rng::ClientRng;
 use crate::client::topology_control::TopologyAccessor;
+use crate::client::traffic_scheduler::{new_traffic_scheduler, TrafficScheduler};
+use crate::config::TrafficScheduling;
 use crate::{config, spawn_future};
 use futures::task::{Context, Poll};
 use futures::{Future, Stream, StreamExt};
 use log::*;
 use nym_sphinx::acknowledgements::AckKey;
 use nym_sphinx::addressing::clients::Recipient;
-use nym_sphinx::cover::generate_loop_cover_packet;
+use nym_sphinx::cover::{generate_correspondent_cover_packet, generate_loop_cover_packet};
 use nym_sphinx::params::{PacketSize, PacketType};
-use nym_sphinx::utils::sample_poisson_duration;
-use rand::{rngs::OsRng, CryptoRng, Rng};
+use rand::{CryptoRng, Rng};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
@@ -42,12 +51,19 @@ where
     /// used to keep track of when a next packet should be sent out.
     next_delay: Pin<Box<Sleep>>,
 
+    /// The currently used scheduling model alongside the scheduler implementing it.
+    scheduling: TrafficScheduling,
+    scheduler: Box<dyn TrafficScheduler>,
+
+    /// Number of packets sent in a single burst if the bursty scheduling is used.
+    scheduling_burst_size: u32,
+
     /// Channel used for sending prepared nym packets to `MixTrafficController` that sends them
     /// out to the network without any further delays.
     mix_tx: BatchMixMessageSender,
 
-    /// Represents full address of this client.
-    our_full_destination: Recipient,
+    /// Keys of this client, used for determining its current full address.
+    our_keys: ManagedKeys,
 
     /// Instance of a cryptographically secure random number generator.
     rng: R,
@@ -64,6 +80,16 @@ where
     packet_type: PacketType,
 
     stats_tx: PacketStatisticsReporter,
+
+    /// Clients we have recently sent real messages to that might also receive some of our cover traffic.
+    recent_correspondents: Option<RecentCorrespondents>,
+
+    /// Optional listener for the changes of the runtime parameters, such as the cover traffic rate.
+    runtime_parameters: Option<RuntimeParametersListener>,
+
+    /// If the adaptive mode is enabled, scales the cover traffic rate according to the volume
+    /// of the real traffic.
+    adaptive_rate: Option<AdaptiveCoverRate>,
 }
 
 impl<R> Stream for LoopCoverTrafficStream<R>
@@ -84,35 +110,48 @@ where
 
         // we know it's time to send a message, so let's prepare delay for the next one
         // Get the `now` by looking at the current `delay` deadline
-        let avg_delay = self.cover_traffic.loop_cover_traffic_average_delay;
-        let next_poisson_delay = sample_poisson_duration(&mut self.rng, avg_delay);
+        let avg_delay = self.current_average_delay();
+        let this = &mut *self;
+        let next_scheduled_delay = this.scheduler.next_delay(&mut this.rng, avg_delay);
 
-        // The next interval value is `next_poisson_delay` after the one that just
+        // The next interval value is `next_scheduled_delay` after the one that just
         // yielded.
         let now = self.next_delay.deadline();
-        let next = now + next_poisson_delay;
+        let next = now + next_scheduled_delay;
         self.next_delay.as_mut().reset(next);
 
         Poll::Ready(Some(()))
     }
 }
 
-// obviously when we finally make shared rng that is on 'higher' level, this should become
-// generic `R`
-impl LoopCoverTrafficStream<OsRng> {
+impl<R> LoopCoverTrafficStream<R>
+where
+    R: CryptoRng + Rng,
+{
+    /// Returns the average delay between the cover packets, adjusted for the volume of the real traffic
+    /// if the adaptive mode is enabled.
+    fn current_average_delay(&mut self) -> Duration {
+        let target_delay = self.cover_traffic.loop_cover_traffic_average_delay;
+        match &mut self.adaptive_rate {
+            Some(adaptive_rate) => adaptive_rate.average_delay(target_delay, get_time_now()),
+            None => target_delay,
+        }
+    }
+}
+
+impl LoopCoverTrafficStream<ClientRng> {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
+        rng: ClientRng,
         ack_key: Arc<AckKey>,
         average_ack_delay: Duration,
         mix_tx: BatchMixMessageSender,
-        our_full_destination: Recipient,
+        our_keys: ManagedKeys,
         topology_access: TopologyAccessor,
         traffic_config: config::Traffic,
         cover_config: config::CoverTraffic,
         stats_tx: PacketStatisticsReporter,
     ) -> Self {
-        let rng = OsRng;
-
         let next_delay = Box::pin(sleep(Default::default()));
 
         LoopCoverTrafficStream {
@@ -120,17 +159,87 @@ impl LoopCoverTrafficStream<OsRng> {
             average_ack_delay,
             cover_traffic: cover_config,
             next_delay,
+            scheduling: traffic_config.scheduling,
+            scheduler: new_traffic_scheduler(
+                traffic_config.scheduling,
+                traffic_config.scheduling_burst_size,
+            ),
+            scheduling_burst_size: traffic_config.scheduling_burst_size,
             mix_tx,
-            our_full_destination,
+            our_keys,
             rng,
             topology_access,
             primary_packet_size: traffic_config.primary_packet_size,
             secondary_packet_size: traffic_config.secondary_packet_size,
             packet_type: traffic_config.packet_type,
             stats_tx,
+            recent_correspondents: None,
+            runtime_parameters: None,
+            adaptive_rate: None,
         }
     }
 
+    #[must_use]
+    pub(crate) fn with_recent_correspondents(
+        mut self,
+        recent_correspondents: Option<RecentCorrespondents>,
+    ) -> Self {
+        self.recent_correspondents = recent_correspondents;
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_runtime_parameters(
+        mut self,
+        runtime_parameters: RuntimeParametersListener,
+    ) -> Self {
+        self.runtime_parameters = Some(runtime_parameters);
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_real_traffic_counter(
+        mut self,
+        real_traffic_counter: Option<RealTrafficCounter>,
+    ) -> Self {
+        self.adaptive_rate = real_traffic_counter.map(|counter| {
+            AdaptiveCoverRate::new(
+                counter,
+                self.cover_traffic.minimum_adaptive_cover_traffic_ratio,
+            )
+        });
+        self
+    }
+
+    fn sample_next_delay(&mut self, average_delay: Duration) -> Duration {
+        self.scheduler.next_delay(&mut self.rng, average_delay)
+    }
+
+    fn update_runtime_parameters(&mut self, parameters: RuntimeParameters) {
+        let average_delay = parameters.loop_cover_traffic_average_delay;
+        let scheduling = parameters.traffic_scheduling;
+        if self.cover_traffic.loop_cover_traffic_average_delay == average_delay
+            && self.scheduling == scheduling
+        {
+            return;
+        }
+
+        if self.cover_traffic.loop_cover_traffic_average_delay != average_delay {
+            info!("changing the average loop cover traffic delay to {average_delay:?}");
+            self.cover_traffic.loop_cover_traffic_average_delay = average_delay;
+        }
+        if self.scheduling != scheduling {
+            info!("switching the loop cover traffic stream to {scheduling:?} scheduling");
+            self.scheduling = scheduling;
+            self.scheduler = new_traffic_scheduler(scheduling, self.scheduling_burst_size);
+        }
+
+        // resample the current delay so that we wouldn't have to wait for the old (possibly very long) one
+        let average_delay = self.current_average_delay();
+        let sampled = self.sample_next_delay(average_delay);
+        self.set_next_delay(sampled);
+    }
+
     fn set_next_delay(&mut self, amount: Duration) {
         let next_delay = Box::pin(sleep(amount));
         self.next_delay = next_delay;
@@ -152,21 +261,40 @@ impl LoopCoverTrafficStream<OsRng> {
         }
     }
 
+    /// Determines whether the next cover message should be sent to one of our recent correspondents
+    /// rather than looped back to ourselves.
+    fn correspondent_cover_target(&mut self) -> Option<Recipient> {
+        let recent_correspondents = self.recent_correspondents.as_ref()?;
+
+        let ratio = self
+            .cover_traffic
+            .correspondent_cover_traffic_ratio
+            .clamp(0.0, 1.0);
+        if !self.rng.gen_bool(ratio) {
+            return None;
+        }
+
+        recent_correspondents.choose_cover_target(&mut self.rng)
+    }
+
     async fn on_new_message(&mut self) {
         trace!("next cover message!");
 
         let cover_traffic_packet_size = self.loop_cover_message_size();
         trace!("the next loop cover message will be put in a {cover_traffic_packet_size} packet");
 
+        let correspondent = self.correspondent_cover_target();
+        let our_full_destination = self.our_keys.address();
+        let destination = correspondent.unwrap_or(our_full_destination);
+
         // TODO for way down the line: in very rare cases (during topology update) we might have
         // to wait a really tiny bit before actually obtaining the permit hence messing with our
         // poisson delay, but is it really a problem?
         let topology_permit = self.topology_access.get_read_permit().await;
         // the ack is sent back to ourselves (and then ignored)
-        let topology_ref = match topology_permit.try_get_valid_topology_ref(
-            &self.our_full_destination,
-            Some(&self.our_full_destination),
-        ) {
+        let topology_ref = match topology_permit
+            .try_get_valid_topology_ref(&our_full_destination, Some(&destination))
+        {
             Ok(topology) => topology,
             Err(err) => {
                 warn!("We're not going to send any loop cover message this time, as the current topology seem to be invalid - {err}");
@@ -174,16 +302,31 @@ impl LoopCoverTrafficStream<OsRng> {
             }
         };
 
-        let cover_message = generate_loop_cover_packet(
-            &mut self.rng,
-            topology_ref,
-            &self.ack_key,
-            &self.our_full_destination,
-            self.average_ack_delay,
-            self.cover_traffic.loop_cover_traffic_average_delay,
-            cover_traffic_packet_size,
-            self.packet_type,
-        )
+        let cover_message = if let Some(correspondent) = correspondent {
+            trace!("the next cover message is going to be sent to {correspondent}");
+            generate_correspondent_cover_packet(
+                &mut self.rng,
+                topology_ref,
+                &self.ack_key,
+                &our_full_destination,
+                &correspondent,
+                self.average_ack_delay,
+                self.cover_traffic.loop_cover_traffic_average_delay,
+                cover_traffic_packet_size,
+                self.packet_type,
+            )
+        } else {
+            generate_loop_cover_packet(
+                &mut self.rng,
+                topology_ref,
+                &self.ack_key,
+                &our_full_destination,
+                self.average_ack_delay,
+                self.cover_traffic.loop_cover_traffic_average_delay,
+                cover_traffic_packet_size,
+                self.packet_type,
+            )
+        }
         .expect("Somehow failed to generate a loop cover message with a valid topology");
 
         if let Err(err) = self.mix_tx.try_send(vec![cover_message]) {
@@ -224,12 +367,12 @@ impl LoopCoverTrafficStream<OsRng> {
         }
 
         // we should set initial delay only when we actually start the stream
-        let sampled = sample_poisson_duration(
-            &mut self.rng,
-            self.cover_traffic.loop_cover_traffic_average_delay,
-        );
+        let average_delay = self.current_average_delay();
+        let sampled = self.sample_next_delay(average_delay);
         self.set_next_delay(sampled);
 
+        let mut runtime_parameters = self.runtime_parameters.take();
+
         spawn_future(async move {
             debug!("Started LoopCoverTrafficStream with graceful shutdown support");
 
@@ -239,6 +382,9 @@ impl LoopCoverTrafficStream<OsRng> {
                     _ = shutdown.recv() => {
                         log::trace!("LoopCoverTrafficStream: Received shutdown");
                     }
+                    Some(parameters) = next_parameters_change(runtime_parameters.as_mut()) => {
+                        self.update_runtime_parameters(parameters);
+                    }
                     next = self.next() => {
                         if next.is_some() {
                             self.on_new_message().await;
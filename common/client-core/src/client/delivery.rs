@@ -0,0 +1,250 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Delivery receipts of the messages sent with a [`DeliveryToken`] attached.
+//!
+//! A message is considered delivered once acknowledgements for all of its fragments have been received.
+//! Note that the acknowledgements are sent by the gateway of the recipient, so a delivered message
+//! is guaranteed to have reached that gateway rather than the recipient itself.
+//! A message is considered failed if it couldn't have been sent at all or if the client gave up
+//! on retransmitting any of its fragments, which only ever happens if the maximum number
+//! of attempts has been configured in the `retransmission` section of the debug config.
+
+use futures::channel::mpsc;
+use log::*;
+use nym_sphinx::chunking::fragment::FragmentIdentifier;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+pub type DeliveryStatusSender = mpsc::UnboundedSender<DeliveryStatus>;
+pub type DeliveryStatusReceiver = mpsc::UnboundedReceiver<DeliveryStatus>;
+
+/// Caller-chosen identifier of a message whose delivery status should be reported.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct DeliveryToken(u64);
+
+impl DeliveryToken {
+    pub fn new(id: u64) -> Self {
+        DeliveryToken(id)
+    }
+
+    pub fn random() -> Self {
+        DeliveryToken(rand::random())
+    }
+
+    pub fn id(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for DeliveryToken {
+    fn from(id: u64) -> Self {
+        DeliveryToken(id)
+    }
+}
+
+impl Display for DeliveryToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// All the fragments of the message have been acknowledged.
+    Delivered(DeliveryToken),
+
+    /// The message couldn't have been sent or the retransmission of some of its fragments has been abandoned.
+    Failed(DeliveryToken),
+}
+
+impl DeliveryStatus {
+    pub fn token(&self) -> DeliveryToken {
+        match self {
+            DeliveryStatus::Delivered(token) | DeliveryStatus::Failed(token) => *token,
+        }
+    }
+
+    pub fn is_delivered(&self) -> bool {
+        matches!(self, DeliveryStatus::Delivered(_))
+    }
+}
+
+/// Handle for publishing the delivery statuses to the registered listener (if any).
+#[derive(Clone, Default)]
+pub(crate) struct DeliveryReceipts {
+    listener: Arc<Mutex<Option<DeliveryStatusSender>>>,
+}
+
+impl DeliveryReceipts {
+    fn listener(&self) -> MutexGuard<'_, Option<DeliveryStatusSender>> {
+        self.listener.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn set_listener(&self, listener: DeliveryStatusSender) {
+        *self.listener() = Some(listener)
+    }
+
+    pub(crate) fn publish(&self, status: DeliveryStatus) {
+        let mut listener = self.listener();
+        let Some(sender) = listener.as_ref() else {
+            trace!("there's no listener for the delivery status {status:?}");
+            return;
+        };
+        if sender.unbounded_send(status).is_err() {
+            debug!("the delivery status listener is gone");
+            *listener = None;
+        }
+    }
+}
+
+/// Keeps track of the fragments of the messages that have a delivery token attached.
+pub(crate) struct DeliveryTracker {
+    receipts: DeliveryReceipts,
+    fragments: HashMap<FragmentIdentifier, DeliveryToken>,
+    remaining: HashMap<DeliveryToken, usize>,
+}
+
+impl DeliveryTracker {
+    pub(crate) fn new(receipts: DeliveryReceipts) -> Self {
+        DeliveryTracker {
+            receipts,
+            fragments: HashMap::new(),
+            remaining: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn track(&mut self, token: DeliveryToken, fragments: Vec<FragmentIdentifier>) {
+        if fragments.is_empty() {
+            // nothing to wait for
+            self.receipts.publish(DeliveryStatus::Delivered(token));
+            return;
+        }
+
+        *self.remaining.entry(token).or_default() += fragments.len();
+        for fragment in fragments {
+            self.fragments.insert(fragment, token);
+        }
+    }
+
+    pub(crate) fn report_failure(&mut self, token: DeliveryToken) {
+        // some of the fragments might have already been sent under this token (say, to other recipients),
+        // but they should no longer result in the message being reported as delivered
+        if self.remaining.remove(&token).is_some() {
+            self.fragments.retain(|_, t| *t != token);
+        }
+        self.receipts.publish(DeliveryStatus::Failed(token))
+    }
+
+    pub(crate) fn on_acknowledged(&mut self, fragment: FragmentIdentifier) {
+        let Some(token) = self.fragments.remove(&fragment) else {
+            return;
+        };
+        let Some(remaining) = self.remaining.get_mut(&token) else {
+            return;
+        };
+        *remaining -= 1;
+        if *remaining == 0 {
+            self.remaining.remove(&token);
+            self.receipts.publish(DeliveryStatus::Delivered(token))
+        }
+    }
+
+    pub(crate) fn on_abandoned(&mut self, fragment: FragmentIdentifier) {
+        let Some(token) = self.fragments.remove(&fragment) else {
+            return;
+        };
+        // the message can't be delivered anymore, so there's no point in keeping track of its other fragments
+        if self.remaining.contains_key(&token) {
+            self.report_failure(token)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fragment(position: u8) -> FragmentIdentifier {
+        FragmentIdentifier::try_from_bytes([0, 0, 0, 42, position]).unwrap()
+    }
+
+    #[test]
+    fn statuses_are_published_once_all_fragments_are_resolved() {
+        let receipts = DeliveryReceipts::default();
+        let (listener, mut statuses) = mpsc::unbounded();
+        receipts.set_listener(listener);
+
+        let mut tracker = DeliveryTracker::new(receipts);
+        let delivered = DeliveryToken::new(1);
+        let failed = DeliveryToken::new(2);
+        tracker.track(delivered, vec![fragment(0), fragment(1)]);
+        tracker.track(failed, vec![fragment(2), fragment(3)]);
+
+        tracker.on_acknowledged(fragment(0));
+        assert!(statuses.try_next().is_err());
+
+        tracker.on_acknowledged(fragment(1));
+        assert_eq!(
+            statuses.try_next().unwrap(),
+            Some(DeliveryStatus::Delivered(delivered))
+        );
+
+        tracker.on_abandoned(fragment(3));
+        tracker.on_acknowledged(fragment(2));
+        assert_eq!(
+            statuses.try_next().unwrap(),
+            Some(DeliveryStatus::Failed(failed))
+        );
+        assert!(statuses.try_next().is_err());
+    }
+
+    #[test]
+    fn message_sent_to_multiple_recipients_is_delivered_once_all_of_them_acknowledge() {
+        let receipts = DeliveryReceipts::default();
+        let (listener, mut statuses) = mpsc::unbounded();
+        receipts.set_listener(listener);
+
+        let mut tracker = DeliveryTracker::new(receipts);
+        let token = DeliveryToken::new(1);
+
+        // every recipient gets its own copy of the message tracked under the same token
+        tracker.track(token, vec![fragment(0), fragment(1)]);
+        tracker.track(token, vec![fragment(2)]);
+
+        tracker.on_acknowledged(fragment(0));
+        tracker.on_acknowledged(fragment(1));
+        assert!(statuses.try_next().is_err());
+
+        tracker.on_acknowledged(fragment(2));
+        assert_eq!(
+            statuses.try_next().unwrap(),
+            Some(DeliveryStatus::Delivered(token))
+        );
+        assert!(statuses.try_next().is_err());
+    }
+
+    #[test]
+    fn failing_to_reach_one_of_the_recipients_fails_the_message() {
+        let receipts = DeliveryReceipts::default();
+        let (listener, mut statuses) = mpsc::unbounded();
+        receipts.set_listener(listener);
+
+        let mut tracker = DeliveryTracker::new(receipts);
+        let token = DeliveryToken::new(1);
+
+        // the copy for the first recipient went out, but the one for the second couldn't be sent
+        tracker.track(token, vec![fragment(0), fragment(1)]);
+        tracker.report_failure(token);
+        assert_eq!(
+            statuses.try_next().unwrap(),
+            Some(DeliveryStatus::Failed(token))
+        );
+
+        // so the acknowledgements of the first copy no longer matter
+        tracker.on_acknowledged(fragment(0));
+        tracker.on_acknowledged(fragment(1));
+        assert!(statuses.try_next().is_err());
+    }
+}
@@ -0,0 +1,363 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! On-demand health check of a running client, meant for powering "connection doctor" style
+//! troubleshooting in the user-facing applications.
+//!
+//! Unlike the startup self-test, the diagnostics can be run at any point after the client has started,
+//! even while the embedder is using its input and output, as the echo messages addressed to ourselves
+//! are intercepted before they could be delivered.
+
+use crate::client::helpers::{get_time_now, timeout};
+use crate::client::inbound_messages::{InputMessage, InputMessageSender};
+use crate::client::key_manager::ManagedKeys;
+use crate::client::topology_control::TopologyAccessor;
+use futures::channel::{mpsc, oneshot};
+use log::*;
+use nym_task::connections::TransmissionLane;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+const ECHO_MAGIC: &[u8] = b"NYM_DIAGNOSTICS_ECHO";
+const ECHO_PAYLOAD_LEN: usize = ECHO_MAGIC.len() + 8;
+
+// the topology is considered stale if we've missed that many consecutive refreshes
+const STALE_TOPOLOGY_REFRESHES: u32 = 3;
+
+pub(crate) type GatewayProbeResponder = oneshot::Sender<Result<bool, String>>;
+pub(crate) type GatewayProbeSender = mpsc::UnboundedSender<GatewayProbeResponder>;
+pub(crate) type GatewayProbeReceiver = mpsc::UnboundedReceiver<GatewayProbeResponder>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DiagnosticsConfig {
+    /// Maximum amount of time we're going to wait for the gateway to respond to our probe.
+    pub gateway_timeout: Duration,
+
+    /// Maximum amount of time we're going to wait for the echo message to come back to us.
+    pub echo_timeout: Duration,
+}
+
+impl Default for DiagnosticsConfig {
+    fn default() -> Self {
+        DiagnosticsConfig {
+            gateway_timeout: Duration::from_secs(10),
+            echo_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GatewayHealth {
+    /// The gateway has responded to our probe.
+    Reachable { round_trip: Duration },
+
+    /// The gateway has failed to respond to our probe.
+    Unreachable { reason: String },
+
+    /// The gateway connection doesn't support being probed, for example because it's running
+    /// within the same process.
+    Unknown,
+}
+
+impl GatewayHealth {
+    pub fn is_reachable(&self) -> bool {
+        matches!(self, GatewayHealth::Reachable { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopologyHealth {
+    /// Time since the topology was last successfully refreshed, if it has ever been obtained.
+    pub age: Option<Duration>,
+
+    /// Indicates whether the topology hasn't been refreshed for suspiciously long.
+    /// It's never set if the topology is being controlled manually.
+    pub is_stale: bool,
+
+    pub controlled_manually: bool,
+
+    pub in_epoch_transition: bool,
+
+    /// Reason for why we're unable to construct routes through the current topology, if any.
+    pub routing_error: Option<String>,
+}
+
+impl TopologyHealth {
+    pub fn is_routable(&self) -> bool {
+        self.routing_error.is_none()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EchoHealth {
+    /// The message addressed to ourselves has made it through the mixnet.
+    Received { round_trip: Duration },
+
+    /// The message didn't come back before the timeout.
+    TimedOut,
+
+    /// The message couldn't have been sent at all.
+    Failed { reason: String },
+
+    /// The message wasn't sent as the earlier checks have already determined it couldn't arrive.
+    Skipped,
+}
+
+impl EchoHealth {
+    pub fn is_received(&self) -> bool {
+        matches!(self, EchoHealth::Received { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    pub gateway: GatewayHealth,
+    pub topology: TopologyHealth,
+    pub mixnet_echo: EchoHealth,
+}
+
+impl HealthReport {
+    /// Indicates whether the client is able to communicate through the mixnet.
+    pub fn is_healthy(&self) -> bool {
+        self.mixnet_echo.is_received()
+    }
+
+    /// Produces human-readable hints on how to go about any of the detected problems.
+    pub fn suggestions(&self) -> Vec<&'static str> {
+        let mut suggestions = Vec::new();
+        if let GatewayHealth::Unreachable { .. } = self.gateway {
+            suggestions.push("the gateway is not responding - check your internet connection or try switching to a different gateway");
+        }
+        if self.topology.age.is_none() {
+            suggestions.push("the network topology has never been obtained - check whether the nym-api endpoints are reachable");
+        } else if self.topology.is_stale {
+            suggestions.push("the network topology is outdated - check whether the nym-api endpoints are reachable");
+        }
+        if self.topology.age.is_some() && !self.topology.is_routable() {
+            suggestions.push("the network topology doesn't allow constructing any routes - try again in a few minutes");
+        }
+        if self.topology.in_epoch_transition {
+            suggestions
+                .push("the network is transitioning between epochs - some delays are expected");
+        }
+        if self.mixnet_echo == EchoHealth::TimedOut && self.gateway.is_reachable() {
+            suggestions.push("the mixnet appears to be congested or some of the nodes are misbehaving - try again later");
+        }
+        suggestions
+    }
+}
+
+/// Echo messages currently awaiting their return, shared with the received messages buffer.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EchoProbes {
+    pending: Arc<Mutex<HashMap<u64, oneshot::Sender<()>>>>,
+}
+
+impl EchoProbes {
+    fn pending(&self) -> MutexGuard<'_, HashMap<u64, oneshot::Sender<()>>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn register(&self) -> (u64, oneshot::Receiver<()>) {
+        let (sender, receiver) = oneshot::channel();
+        let mut pending = self.pending();
+        let mut nonce = rand::random();
+        while pending.contains_key(&nonce) {
+            nonce = rand::random();
+        }
+        pending.insert(nonce, sender);
+        (nonce, receiver)
+    }
+
+    fn remove(&self, nonce: u64) {
+        self.pending().remove(&nonce);
+    }
+
+    /// Checks whether the received message is one of our echo messages, in which case
+    /// the relevant waiter gets notified and the message should not be delivered any further.
+    pub(crate) fn try_complete(&self, message: &[u8]) -> bool {
+        let Some(nonce) = parse_echo_payload(message) else {
+            return false;
+        };
+        match self.pending().remove(&nonce) {
+            Some(waiter) => {
+                let _ = waiter.send(());
+            }
+            None => debug!("received an echo message after its diagnostics have finished"),
+        }
+        true
+    }
+}
+
+fn echo_payload(nonce: u64) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(ECHO_PAYLOAD_LEN);
+    payload.extend_from_slice(ECHO_MAGIC);
+    payload.extend_from_slice(&nonce.to_be_bytes());
+    payload
+}
+
+fn parse_echo_payload(payload: &[u8]) -> Option<u64> {
+    if payload.len() != ECHO_PAYLOAD_LEN || !payload.starts_with(ECHO_MAGIC) {
+        return None;
+    }
+    let nonce = payload[ECHO_MAGIC.len()..].try_into().ok()?;
+    Some(u64::from_be_bytes(nonce))
+}
+
+/// Handle for running the health checks of the client.
+#[derive(Debug, Clone)]
+pub struct ClientDiagnostics {
+    keys: ManagedKeys,
+    input_sender: InputMessageSender,
+    topology_accessor: TopologyAccessor,
+    topology_refresh_rate: Duration,
+    gateway_probes: GatewayProbeSender,
+    echo_probes: EchoProbes,
+}
+
+impl ClientDiagnostics {
+    pub(crate) fn new(
+        keys: ManagedKeys,
+        input_sender: InputMessageSender,
+        topology_accessor: TopologyAccessor,
+        topology_refresh_rate: Duration,
+    ) -> (Self, GatewayProbeReceiver, EchoProbes) {
+        let (gateway_probes, probes_receiver) = mpsc::unbounded();
+        let echo_probes = EchoProbes::default();
+        (
+            ClientDiagnostics {
+                keys,
+                input_sender,
+                topology_accessor,
+                topology_refresh_rate,
+                gateway_probes,
+                echo_probes: echo_probes.clone(),
+            },
+            probes_receiver,
+            echo_probes,
+        )
+    }
+
+    /// Runs all the health checks using the default [`DiagnosticsConfig`].
+    pub async fn run(&self) -> HealthReport {
+        self.run_with_config(DiagnosticsConfig::default()).await
+    }
+
+    /// Checks the gateway reachability and the freshness of the topology and, if they look fine,
+    /// sends a message addressed to ourselves through the mixnet to measure its round trip.
+    pub async fn run_with_config(&self, config: DiagnosticsConfig) -> HealthReport {
+        info!("running the client diagnostics");
+
+        let gateway = self.check_gateway(config.gateway_timeout).await;
+        let topology = self.check_topology().await;
+        let mixnet_echo =
+            if matches!(gateway, GatewayHealth::Unreachable { .. }) || !topology.is_routable() {
+                EchoHealth::Skipped
+            } else {
+                self.check_mixnet_echo(config.echo_timeout).await
+            };
+
+        let report = HealthReport {
+            gateway,
+            topology,
+            mixnet_echo,
+        };
+        info!("client diagnostics finished: {report:?}");
+        report
+    }
+
+    pub async fn check_gateway(&self, max_wait: Duration) -> GatewayHealth {
+        let (responder, response) = oneshot::channel();
+        if self.gateway_probes.unbounded_send(responder).is_err() {
+            return GatewayHealth::Unreachable {
+                reason: "the gateway connection is no longer running".to_string(),
+            };
+        }
+
+        let started = get_time_now();
+        match timeout(max_wait, response).await {
+            Err(_) => GatewayHealth::Unreachable {
+                reason: format!("the gateway has failed to respond within {max_wait:?}"),
+            },
+            Ok(Err(_)) => GatewayHealth::Unreachable {
+                reason: "the gateway connection is no longer running".to_string(),
+            },
+            Ok(Ok(Ok(true))) => GatewayHealth::Reachable {
+                round_trip: get_time_now().duration_since(started),
+            },
+            Ok(Ok(Ok(false))) => GatewayHealth::Unknown,
+            Ok(Ok(Err(reason))) => GatewayHealth::Unreachable { reason },
+        }
+    }
+
+    pub async fn check_topology(&self) -> TopologyHealth {
+        let age = self.topology_accessor.time_since_last_update();
+        let controlled_manually = self.topology_accessor.controlled_manually();
+        let is_stale = !controlled_manually
+            && age.map_or(true, |age| {
+                age > self.topology_refresh_rate * STALE_TOPOLOGY_REFRESHES
+            });
+
+        TopologyHealth {
+            age,
+            is_stale,
+            controlled_manually,
+            in_epoch_transition: self.topology_accessor.is_in_epoch_transition(),
+            routing_error: self
+                .topology_accessor
+                .ensure_is_routable()
+                .await
+                .err()
+                .map(|err| err.to_string()),
+        }
+    }
+
+    pub async fn check_mixnet_echo(&self, max_wait: Duration) -> EchoHealth {
+        let (nonce, echo) = self.echo_probes.register();
+        let message = InputMessage::new_regular(
+            self.keys.address(),
+            echo_payload(nonce),
+            TransmissionLane::General,
+            None,
+        );
+
+        let started = get_time_now();
+        if self.input_sender.send(message).await.is_err() {
+            self.echo_probes.remove(nonce);
+            return EchoHealth::Failed {
+                reason: "the client is no longer accepting messages".to_string(),
+            };
+        }
+
+        let health = match timeout(max_wait, echo).await {
+            Ok(Ok(_)) => EchoHealth::Received {
+                round_trip: get_time_now().duration_since(started),
+            },
+            Ok(Err(_)) => EchoHealth::Failed {
+                reason: "the received messages buffer is no longer running".to_string(),
+            },
+            Err(_) => EchoHealth::TimedOut,
+        };
+        self.echo_probes.remove(nonce);
+        health
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_echo_messages_are_intercepted() {
+        let probes = EchoProbes::default();
+        let (nonce, mut echo) = probes.register();
+
+        assert!(!probes.try_complete(b"hello"));
+        assert!(probes.try_complete(&echo_payload(nonce)));
+        assert_eq!(echo.try_recv(), Ok(Some(())));
+
+        // late echoes must not leak to the user either
+        assert!(probes.try_complete(&echo_payload(nonce)));
+    }
+}
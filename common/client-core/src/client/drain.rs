@@ -0,0 +1,189 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Graceful shutdown of the client. Rather than stopping all the tasks straight away and dropping
+//! whatever was still in flight, the client first stops accepting new messages and waits (for a bounded
+//! amount of time) until the already accepted ones have been sent out and acknowledged.
+
+use crate::client::helpers::{get_time_now, sleep};
+use futures::channel::oneshot;
+use log::*;
+use nym_client_core_surb_storage::FlushRequestSender;
+use nym_task::connections::LaneQueueLengths;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DrainConfig {
+    /// Maximum amount of time we're going to wait for the pending messages to get delivered.
+    pub timeout: Duration,
+
+    /// How often we're going to check whether everything has been delivered.
+    pub poll_interval: Duration,
+}
+
+impl Default for DrainConfig {
+    fn default() -> Self {
+        DrainConfig {
+            timeout: Duration::from_secs(30),
+            poll_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Summary of the messages that have not been delivered by the time the drain has finished.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DrainSummary {
+    /// Number of messages rejected since they were submitted after the drain has started.
+    /// If the outbox is enabled, they will be resent on the next startup.
+    pub rejected_messages: usize,
+
+    /// Number of packets that have been sent, but never got acknowledged.
+    pub unacknowledged_packets: usize,
+
+    /// Number of packets that were still waiting in the lane queues.
+    pub queued_packets: usize,
+
+    /// Indicates whether we have given up waiting for the pending packets.
+    pub timed_out: bool,
+
+    /// Indicates whether the reply-related data has been successfully flushed to the storage.
+    pub reply_storage_flushed: bool,
+}
+
+impl DrainSummary {
+    /// Indicates whether all the accepted messages have been delivered.
+    pub fn is_clean(&self) -> bool {
+        self.rejected_messages == 0 && self.unacknowledged_packets == 0 && self.queued_packets == 0
+    }
+}
+
+#[derive(Debug, Default)]
+struct DrainStateInner {
+    draining: AtomicBool,
+    rejected_messages: AtomicUsize,
+    pending_acks: AtomicUsize,
+}
+
+/// State shared between the `ClientDrain` and the components handling the real traffic.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DrainState {
+    inner: Arc<DrainStateInner>,
+}
+
+impl DrainState {
+    pub(crate) fn is_draining(&self) -> bool {
+        self.inner.draining.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_rejected_message(&self) {
+        self.inner.rejected_messages.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_pending_acks(&self, pending_acks: usize) {
+        self.inner
+            .pending_acks
+            .store(pending_acks, Ordering::Relaxed);
+    }
+
+    fn pending_acks(&self) -> usize {
+        self.inner.pending_acks.load(Ordering::Relaxed)
+    }
+}
+
+/// Handle for gracefully draining the client before its shutdown.
+#[derive(Debug, Clone)]
+pub struct ClientDrain {
+    state: DrainState,
+    lane_queue_lengths: LaneQueueLengths,
+    reply_storage_flush: FlushRequestSender,
+}
+
+impl ClientDrain {
+    pub(crate) fn new(
+        state: DrainState,
+        lane_queue_lengths: LaneQueueLengths,
+        reply_storage_flush: FlushRequestSender,
+    ) -> Self {
+        ClientDrain {
+            state,
+            lane_queue_lengths,
+            reply_storage_flush,
+        }
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.state.is_draining()
+    }
+
+    /// Stops accepting new input messages, waits until all the pending packets have been sent
+    /// and acknowledged (or the timeout is reached) and flushes the reply storage.
+    /// Note that it does not stop any of the tasks, that is up to the caller once this resolves.
+    pub async fn drain(&self, config: DrainConfig) -> DrainSummary {
+        info!("draining the client before shutdown");
+        self.state.inner.draining.store(true, Ordering::Relaxed);
+
+        let started = get_time_now();
+        let mut timed_out = false;
+        loop {
+            if self.state.pending_acks() == 0 && self.lane_queue_lengths.total() == 0 {
+                break;
+            }
+            if get_time_now().duration_since(started) >= config.timeout {
+                warn!(
+                    "failed to deliver all the pending messages within {:?}",
+                    config.timeout
+                );
+                timed_out = true;
+                break;
+            }
+            sleep(config.poll_interval).await;
+        }
+
+        let summary = DrainSummary {
+            rejected_messages: self.state.inner.rejected_messages.load(Ordering::Relaxed),
+            unacknowledged_packets: self.state.pending_acks(),
+            queued_packets: self.lane_queue_lengths.total(),
+            timed_out,
+            reply_storage_flushed: self.flush_reply_storage().await,
+        };
+        info!("client drain finished: {summary:?}");
+        summary
+    }
+
+    async fn flush_reply_storage(&self) -> bool {
+        let (responder, response) = oneshot::channel();
+        if self.reply_storage_flush.unbounded_send(responder).is_err() {
+            warn!("the reply storage is no longer running - it can't be flushed");
+            return false;
+        }
+        response.await.unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::mpsc;
+
+    #[test]
+    fn drain_reports_rejected_messages() {
+        let state = DrainState::default();
+        let (flush_sender, flush_requests) = mpsc::unbounded();
+        drop(flush_requests);
+
+        let drain = ClientDrain::new(state.clone(), LaneQueueLengths::new(), flush_sender);
+        assert!(!state.is_draining());
+
+        state.set_pending_acks(0);
+        state.record_rejected_message();
+        let summary = futures::executor::block_on(drain.drain(DrainConfig::default()));
+
+        assert!(state.is_draining());
+        assert!(!summary.timed_out);
+        assert!(!summary.reply_storage_flushed);
+        assert_eq!(summary.rejected_messages, 1);
+        assert!(!summary.is_clean());
+    }
+}
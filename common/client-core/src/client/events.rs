@@ -0,0 +1,157 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed events published by the client subsystems, so that the embedders could drive their UI state
+//! (say, a connection indicator) without having to scrape the logs.
+
+use crate::spawn_future;
+use log::*;
+use nym_crypto::asymmetric::identity;
+use nym_gateway_client::{GatewaySessionEvent, GatewaySessionStats};
+use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nym_sphinx::chunking::fragment::FragmentIdentifier;
+use nym_task::TaskClient;
+use tokio::sync::broadcast;
+
+// slow subscribers are going to miss some events rather than hold up the client
+const CLIENT_EVENTS_CHANNEL_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ClientEvent {
+    /// The connection with the gateway has been (re-)established.
+    GatewayConnected { gateway: identity::PublicKey },
+
+    /// The connection with the gateway has been lost.
+    /// Unless the client is shutting down, it's going to attempt to reconnect.
+    GatewayDisconnected { gateway: identity::PublicKey },
+
+    /// A new network topology has been obtained.
+    TopologyRefreshed { routable: bool },
+
+    /// The fragment hasn't been acknowledged in time and is going to be sent again.
+    Retransmission { fragment: FragmentIdentifier },
+
+    /// There weren't enough reply SURBs for sending all the replies to the specified anonymous sender.
+    /// The remaining replies are held until more SURBs arrive.
+    SurbsExhausted { sender_tag: AnonymousSenderTag },
+
+    /// The provided number of zk-nym tickets has been spent to obtain more bandwidth from the gateway.
+    CredentialSpent {
+        gateway: identity::PublicKey,
+        tickets: u32,
+    },
+}
+
+/// Handle shared by all the client subsystems for publishing [`ClientEvent`]s.
+#[derive(Debug, Clone)]
+pub struct ClientEventSender {
+    sender: broadcast::Sender<ClientEvent>,
+}
+
+impl Default for ClientEventSender {
+    fn default() -> Self {
+        ClientEventSender {
+            sender: broadcast::channel(CLIENT_EVENTS_CHANNEL_CAPACITY).0,
+        }
+    }
+}
+
+impl ClientEventSender {
+    pub(crate) fn new() -> Self {
+        Default::default()
+    }
+
+    pub(crate) fn emit(&self, event: ClientEvent) {
+        trace!("client event: {event:?}");
+        // it's fine if nobody is listening
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribes to all the events published from now on.
+    pub fn subscribe(&self) -> ClientEvents {
+        ClientEvents {
+            receiver: self.sender.subscribe(),
+        }
+    }
+}
+
+/// Stream of the [`ClientEvent`]s.
+#[derive(Debug)]
+pub struct ClientEvents {
+    receiver: broadcast::Receiver<ClientEvent>,
+}
+
+impl ClientEvents {
+    /// Waits for the next event. Returns `None` once all the publishers are gone.
+    pub async fn next(&mut self) -> Option<ClientEvent> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => return Some(event),
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    warn!("the client events are not being consumed fast enough - {missed} events got dropped")
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// Republishes the changes to the session with the gateway as [`ClientEvent`]s.
+pub(crate) fn forward_gateway_session_events(
+    gateway: identity::PublicKey,
+    session_stats: &GatewaySessionStats,
+    events: ClientEventSender,
+    mut shutdown: TaskClient,
+) {
+    let mut session_events = session_stats.subscribe_events();
+    spawn_future(async move {
+        debug!("Started gateway session events forwarder with graceful shutdown support");
+
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = shutdown.recv() => {
+                    trace!("GatewaySessionEventsForwarder: Received shutdown");
+                }
+                event = session_events.recv() => match event {
+                    Ok(GatewaySessionEvent::Connected) => {
+                        events.emit(ClientEvent::GatewayConnected { gateway })
+                    }
+                    Ok(GatewaySessionEvent::Disconnected) => {
+                        events.emit(ClientEvent::GatewayDisconnected { gateway })
+                    }
+                    Ok(GatewaySessionEvent::CredentialSpent { tickets }) => {
+                        events.emit(ClientEvent::CredentialSpent { gateway, tickets })
+                    }
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        warn!("missed {missed} gateway session events")
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }
+        shutdown.recv_timeout().await;
+        debug!("GatewaySessionEventsForwarder: Exiting");
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    #[test]
+    fn events_are_delivered_to_all_subscribers() {
+        let sender = ClientEventSender::new();
+        let mut first = sender.subscribe();
+        let mut second = sender.subscribe();
+
+        let event = ClientEvent::TopologyRefreshed { routable: true };
+        sender.emit(event.clone());
+
+        assert_eq!(first.next().now_or_never(), Some(Some(event.clone())));
+        assert_eq!(second.next().now_or_never(), Some(Some(event)));
+        assert!(first.next().now_or_never().is_none());
+    }
+}
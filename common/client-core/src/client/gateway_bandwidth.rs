@@ -0,0 +1,97 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Accounting of the bandwidth still available with the gateway. The last known value is persisted
+//! alongside the gateway details, so that the allowance could be reused rather than claimed afresh
+//! when the client restarts or fails over to one of the gateways it has previously registered with.
+
+use crate::client::base_client::storage::helpers::record_gateway_bandwidth;
+use crate::client::helpers::new_interval_stream;
+use crate::spawn_future;
+use futures::StreamExt;
+use log::*;
+use nym_client_core_gateways_storage::GatewaysDetailsStore;
+use nym_crypto::asymmetric::identity;
+use nym_gateway_client::{GatewaySessionEvent, GatewaySessionStats};
+use nym_task::TaskClient;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+// the remaining bandwidth changes with every packet sent, so rather than persisting each change,
+// only save it every so often (and whenever the state of the session changes)
+const BANDWIDTH_PERSISTENCE_INTERVAL: Duration = Duration::from_secs(60);
+
+pub(crate) struct GatewayBandwidthRecorder<D> {
+    gateway: identity::PublicKey,
+    session_stats: GatewaySessionStats,
+    details_store: D,
+    last_recorded: Option<i64>,
+}
+
+impl<D> GatewayBandwidthRecorder<D>
+where
+    D: GatewaysDetailsStore + Send + Sync + 'static,
+    D::StorageError: Send + Sync + 'static,
+{
+    pub(crate) fn new(
+        gateway: identity::PublicKey,
+        session_stats: GatewaySessionStats,
+        details_store: D,
+    ) -> Self {
+        GatewayBandwidthRecorder {
+            gateway,
+            session_stats,
+            details_store,
+            last_recorded: None,
+        }
+    }
+
+    async fn record(&mut self) {
+        let remaining = self.session_stats.remaining_bandwidth();
+        if self.last_recorded == Some(remaining) {
+            return;
+        }
+
+        // failing to persist it is not critical, at worst we'll spend a credential we didn't have to
+        match record_gateway_bandwidth(&self.details_store, self.gateway, remaining).await {
+            Ok(_) => self.last_recorded = Some(remaining),
+            Err(err) => warn!(
+                "failed to persist the remaining bandwidth of gateway {}: {err}",
+                self.gateway
+            ),
+        }
+    }
+
+    pub(crate) fn start_with_shutdown(mut self, mut shutdown: TaskClient) {
+        let mut session_events = self.session_stats.subscribe_events();
+        spawn_future(async move {
+            debug!("Started GatewayBandwidthRecorder with graceful shutdown support");
+
+            // the gateway reports the available bandwidth upon authentication, which has already happened
+            self.record().await;
+
+            let mut interval = new_interval_stream(BANDWIDTH_PERSISTENCE_INTERVAL);
+            while !shutdown.is_shutdown() {
+                tokio::select! {
+                    biased;
+                    _ = shutdown.recv() => {
+                        trace!("GatewayBandwidthRecorder: Received shutdown");
+                    }
+                    event = session_events.recv() => match event {
+                        Ok(GatewaySessionEvent::Connected)
+                        | Ok(GatewaySessionEvent::Disconnected)
+                        | Ok(GatewaySessionEvent::CredentialSpent { .. })
+                        | Err(broadcast::error::RecvError::Lagged(_)) => self.record().await,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    _ = interval.next() => self.record().await,
+                }
+            }
+
+            // make sure to save whatever we have consumed since the last update
+            self.record().await;
+            shutdown.recv_timeout().await;
+            debug!("GatewayBandwidthRecorder: Exiting");
+        })
+    }
+}
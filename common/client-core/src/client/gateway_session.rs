@@ -0,0 +1,130 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Status of the connection with the gateway, meant for being displayed by the user-facing applications.
+
+use nym_crypto::asymmetric::identity;
+use nym_gateway_client::GatewaySessionStats;
+use nym_gateway_requests::CipherSuite;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GatewaySessionInfo {
+    pub gateway_identity: identity::PublicKey,
+
+    /// Time since the current connection has been established.
+    /// It's not set if we're currently disconnected or the gateway transceiver doesn't keep track of it.
+    pub uptime: Option<Duration>,
+
+    /// The number of times the connection with the gateway had to be re-established.
+    pub reconnections: u64,
+
+    /// The amount of bandwidth (in bytes) consumed since the client has started.
+    pub bandwidth_consumed: u64,
+
+    /// The amount of bandwidth (in bytes) still available, as last reported by the gateway.
+    pub remaining_bandwidth: Option<i64>,
+
+    /// The moment we have last received any packets from the gateway.
+    pub last_received: Option<OffsetDateTime>,
+
+    /// The cipher suite negotiated with the gateway for the current connection.
+    /// It's not set if we're currently disconnected, the connection relies on the legacy keys
+    /// or the gateway transceiver doesn't keep track of it.
+    pub cipher_suite: Option<CipherSuite>,
+}
+
+struct ConnectedGateway {
+    identity: identity::PublicKey,
+
+    // custom gateway transceivers don't have to track any statistics
+    stats: Option<GatewaySessionStats>,
+}
+
+/// Shared handle to the session with the gateway, populated once the connection has been set up.
+#[derive(Clone, Default)]
+pub struct GatewaySession {
+    inner: Arc<Mutex<Option<ConnectedGateway>>>,
+}
+
+impl GatewaySession {
+    pub(crate) fn new() -> Self {
+        Default::default()
+    }
+
+    fn inner(&self) -> MutexGuard<'_, Option<ConnectedGateway>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn set_connected(
+        &self,
+        identity: identity::PublicKey,
+        stats: Option<GatewaySessionStats>,
+    ) {
+        *self.inner() = Some(ConnectedGateway { identity, stats })
+    }
+
+    /// Returns the current status of the session with the gateway,
+    /// unless the connection hasn't been set up yet.
+    pub fn info(&self) -> Option<GatewaySessionInfo> {
+        let guard = self.inner();
+        let connected = guard.as_ref()?;
+
+        let Some(stats) = &connected.stats else {
+            return Some(GatewaySessionInfo {
+                gateway_identity: connected.identity,
+                uptime: None,
+                reconnections: 0,
+                bandwidth_consumed: 0,
+                remaining_bandwidth: None,
+                last_received: None,
+                cipher_suite: None,
+            });
+        };
+
+        let now = OffsetDateTime::now_utc();
+        Some(GatewaySessionInfo {
+            gateway_identity: connected.identity,
+            uptime: stats
+                .connected_since()
+                .map(|since| (now - since).unsigned_abs()),
+            reconnections: stats.reconnections(),
+            bandwidth_consumed: stats.bandwidth_consumed(),
+            remaining_bandwidth: Some(stats.remaining_bandwidth()),
+            last_received: stats.last_received(),
+            cipher_suite: stats.cipher_suite(),
+        })
+    }
+}
+
+impl Debug for GatewaySession {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GatewaySession")
+            .field("info", &self.info())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn session_is_only_reported_once_connected() {
+        let session = GatewaySession::new();
+        assert!(session.info().is_none());
+
+        let gateway = *identity::KeyPair::new(&mut OsRng).public_key();
+        session.set_connected(gateway, None);
+
+        let info = session.info().unwrap();
+        assert_eq!(info.gateway_identity, gateway);
+        assert!(info.uptime.is_none());
+        assert_eq!(info.reconnections, 0);
+        assert!(info.cipher_suite.is_none());
+    }
+}
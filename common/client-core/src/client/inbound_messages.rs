@@ -1,11 +1,13 @@
 // Copyright 2020-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::delivery::DeliveryToken;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nym_sphinx::forwarding::packet::MixPacket;
 use nym_sphinx::params::PacketType;
-use nym_task::connections::TransmissionLane;
+use nym_sphinx::preparer::latency::LatencyBudget;
+use nym_task::connections::{MessagePriority, TransmissionLane};
 
 pub type InputMessageSender = tokio::sync::mpsc::Sender<InputMessage>;
 pub type InputMessageReceiver = tokio::sync::mpsc::Receiver<InputMessage>;
@@ -18,6 +20,7 @@ pub enum InputMessage {
     Premade {
         msgs: Vec<MixPacket>,
         lane: TransmissionLane,
+        priority: MessagePriority,
     },
 
     /// The simplest message variant where no additional information is attached.
@@ -28,6 +31,9 @@ pub enum InputMessage {
         recipient: Recipient,
         data: Vec<u8>,
         lane: TransmissionLane,
+        priority: MessagePriority,
+        delivery_token: Option<DeliveryToken>,
+        latency_budget: Option<LatencyBudget>,
         mix_hops: Option<u8>,
     },
 
@@ -44,6 +50,29 @@ pub enum InputMessage {
         data: Vec<u8>,
         reply_surbs: u32,
         lane: TransmissionLane,
+        priority: MessagePriority,
+        delivery_token: Option<DeliveryToken>,
+        latency_budget: Option<LatencyBudget>,
+        mix_hops: Option<u8>,
+    },
+
+    /// Sends the same `data` to all of the specified `recipients`. Each recipient gets its own copy
+    /// of the message that's split and encrypted separately, alongside its own set of `reply_surbs`,
+    /// if any were requested.
+    ///
+    /// A delivery token attached to this message is only reported as delivered once the message
+    /// has reached all of the recipients.
+    ///
+    /// Ends up with either `NymMessage::Repliable` (if `reply_surbs` is set) or `NymMessage::Plain`
+    /// variant for every recipient
+    MultiRecipient {
+        recipients: Vec<Recipient>,
+        data: Vec<u8>,
+        reply_surbs: Option<u32>,
+        lane: TransmissionLane,
+        priority: MessagePriority,
+        delivery_token: Option<DeliveryToken>,
+        latency_budget: Option<LatencyBudget>,
         mix_hops: Option<u8>,
     },
 
@@ -55,6 +84,8 @@ pub enum InputMessage {
         recipient_tag: AnonymousSenderTag,
         data: Vec<u8>,
         lane: TransmissionLane,
+        priority: MessagePriority,
+        delivery_token: Option<DeliveryToken>,
     },
 
     MessageWrapper {
@@ -69,7 +100,11 @@ impl InputMessage {
         lane: TransmissionLane,
         packet_type: PacketType,
     ) -> Self {
-        let message = InputMessage::Premade { msgs, lane };
+        let message = InputMessage::Premade {
+            msgs,
+            lane,
+            priority: MessagePriority::Normal,
+        };
         if packet_type == PacketType::Mix {
             message
         } else {
@@ -94,6 +129,9 @@ impl InputMessage {
             recipient,
             data,
             lane,
+            priority: MessagePriority::Normal,
+            delivery_token: None,
+            latency_budget: None,
             mix_hops: None,
         };
         if let Some(packet_type) = packet_type {
@@ -116,6 +154,9 @@ impl InputMessage {
             recipient,
             data,
             lane,
+            priority: MessagePriority::Normal,
+            delivery_token: None,
+            latency_budget: None,
             mix_hops,
         };
         if let Some(packet_type) = packet_type {
@@ -137,6 +178,9 @@ impl InputMessage {
             data,
             reply_surbs,
             lane,
+            priority: MessagePriority::Normal,
+            delivery_token: None,
+            latency_budget: None,
             mix_hops: None,
         };
         if let Some(packet_type) = packet_type {
@@ -161,6 +205,9 @@ impl InputMessage {
             data,
             reply_surbs,
             lane,
+            priority: MessagePriority::Normal,
+            delivery_token: None,
+            latency_budget: None,
             mix_hops,
         };
         if let Some(packet_type) = packet_type {
@@ -170,6 +217,30 @@ impl InputMessage {
         }
     }
 
+    pub fn new_multi_recipient(
+        recipients: Vec<Recipient>,
+        data: Vec<u8>,
+        reply_surbs: Option<u32>,
+        lane: TransmissionLane,
+        packet_type: Option<PacketType>,
+    ) -> Self {
+        let message = InputMessage::MultiRecipient {
+            recipients,
+            data,
+            reply_surbs,
+            lane,
+            priority: MessagePriority::Normal,
+            delivery_token: None,
+            latency_budget: None,
+            mix_hops: None,
+        };
+        if let Some(packet_type) = packet_type {
+            InputMessage::new_wrapper(message, packet_type)
+        } else {
+            message
+        }
+    }
+
     pub fn new_reply(
         recipient_tag: AnonymousSenderTag,
         data: Vec<u8>,
@@ -180,6 +251,8 @@ impl InputMessage {
             recipient_tag,
             data,
             lane,
+            priority: MessagePriority::Normal,
+            delivery_token: None,
         };
         if let Some(packet_type) = packet_type {
             InputMessage::new_wrapper(message, packet_type)
@@ -192,9 +265,101 @@ impl InputMessage {
         match self {
             InputMessage::Regular { lane, .. }
             | InputMessage::Anonymous { lane, .. }
+            | InputMessage::MultiRecipient { lane, .. }
             | InputMessage::Reply { lane, .. }
             | InputMessage::Premade { lane, .. } => lane,
             InputMessage::MessageWrapper { message, .. } => message.lane(),
         }
     }
+
+    /// Sets the scheduling priority of the lane the message is sent on.
+    /// Note that the priority applies to the whole lane rather than to this message alone,
+    /// i.e. it affects all the packets queued on it until a message with a different priority arrives.
+    #[must_use]
+    pub fn with_priority(mut self, priority: MessagePriority) -> Self {
+        self.set_priority(priority);
+        self
+    }
+
+    fn set_priority(&mut self, new_priority: MessagePriority) {
+        match self {
+            InputMessage::Regular { priority, .. }
+            | InputMessage::Anonymous { priority, .. }
+            | InputMessage::MultiRecipient { priority, .. }
+            | InputMessage::Reply { priority, .. }
+            | InputMessage::Premade { priority, .. } => *priority = new_priority,
+            InputMessage::MessageWrapper { message, .. } => message.set_priority(new_priority),
+        }
+    }
+
+    pub fn priority(&self) -> MessagePriority {
+        match self {
+            InputMessage::Regular { priority, .. }
+            | InputMessage::Anonymous { priority, .. }
+            | InputMessage::MultiRecipient { priority, .. }
+            | InputMessage::Reply { priority, .. }
+            | InputMessage::Premade { priority, .. } => *priority,
+            InputMessage::MessageWrapper { message, .. } => message.priority(),
+        }
+    }
+
+    /// Attaches the token under which the delivery status of the message is going to be reported.
+    /// Note that it has no effect on the premade packets, as they're never acknowledged.
+    #[must_use]
+    pub fn with_delivery_token(mut self, token: DeliveryToken) -> Self {
+        self.set_delivery_token(token);
+        self
+    }
+
+    fn set_delivery_token(&mut self, token: DeliveryToken) {
+        match self {
+            InputMessage::Regular { delivery_token, .. }
+            | InputMessage::Anonymous { delivery_token, .. }
+            | InputMessage::MultiRecipient { delivery_token, .. }
+            | InputMessage::Reply { delivery_token, .. } => *delivery_token = Some(token),
+            InputMessage::Premade { .. } => {}
+            InputMessage::MessageWrapper { message, .. } => message.set_delivery_token(token),
+        }
+    }
+
+    /// Attaches the upper bound on the delay the packets of the message can be subjected to
+    /// at the mix nodes, trading (some of) the anonymity of the message for lower latency.
+    /// Note that it has no effect on the replies, whose routes are determined by the SURBs
+    /// of the recipient, nor on the premade packets.
+    #[must_use]
+    pub fn with_latency_budget(mut self, budget: LatencyBudget) -> Self {
+        self.set_latency_budget(budget);
+        self
+    }
+
+    fn set_latency_budget(&mut self, budget: LatencyBudget) {
+        match self {
+            InputMessage::Regular { latency_budget, .. }
+            | InputMessage::Anonymous { latency_budget, .. }
+            | InputMessage::MultiRecipient { latency_budget, .. } => *latency_budget = Some(budget),
+            InputMessage::Reply { .. } | InputMessage::Premade { .. } => {}
+            InputMessage::MessageWrapper { message, .. } => message.set_latency_budget(budget),
+        }
+    }
+
+    pub fn latency_budget(&self) -> Option<LatencyBudget> {
+        match self {
+            InputMessage::Regular { latency_budget, .. }
+            | InputMessage::Anonymous { latency_budget, .. }
+            | InputMessage::MultiRecipient { latency_budget, .. } => *latency_budget,
+            InputMessage::Reply { .. } | InputMessage::Premade { .. } => None,
+            InputMessage::MessageWrapper { message, .. } => message.latency_budget(),
+        }
+    }
+
+    pub fn delivery_token(&self) -> Option<DeliveryToken> {
+        match self {
+            InputMessage::Regular { delivery_token, .. }
+            | InputMessage::Anonymous { delivery_token, .. }
+            | InputMessage::MultiRecipient { delivery_token, .. }
+            | InputMessage::Reply { delivery_token, .. } => *delivery_token,
+            InputMessage::Premade { .. } => None,
+            InputMessage::MessageWrapper { message, .. } => message.delivery_token(),
+        }
+    }
 }
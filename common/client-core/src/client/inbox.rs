@@ -0,0 +1,497 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optional persistent storage of reconstructed messages.
+//!
+//! When enabled, every reconstructed message is durably written to the inbox before it's handed
+//! to the consumer and it's only removed once the consumer explicitly acknowledges it via
+//! [`ClientOutput::ack`](crate::client::base_client::ClientOutput::ack).
+//! Any messages left in the inbox (say, because the client crashed mid-processing) are redelivered
+//! upon the next startup.
+
+use async_trait::async_trait;
+use nym_sphinx::receiver::ReconstructedMessage;
+use sha2::{Digest, Sha256};
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use fs_backend::{OnDiskInbox, OnDiskInboxError};
+
+pub const INBOX_MESSAGE_ID_SIZE: usize = 32;
+
+/// Identifier of a reconstructed message.
+/// It's derived from the id of the fragment set the message has been reconstructed from
+/// (alongside the sender and the content), so that all retransmissions of the same message
+/// share the same id, which allows the inbox to deduplicate them, while distinct messages
+/// that merely happen to have identical content are still delivered separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InboxMessageId([u8; INBOX_MESSAGE_ID_SIZE]);
+
+impl InboxMessageId {
+    pub fn for_message(message: &ReconstructedMessage) -> Self {
+        let mut hasher = Sha256::new();
+        match message.sender_tag {
+            Some(sender_tag) => {
+                hasher.update([1]);
+                hasher.update(sender_tag.to_bytes());
+            }
+            None => hasher.update([0]),
+        }
+        match message.fragment_set_id {
+            Some(set_id) => {
+                hasher.update([1]);
+                hasher.update(set_id.to_be_bytes());
+            }
+            None => hasher.update([0]),
+        }
+        hasher.update(&message.message);
+        InboxMessageId(hasher.finalize().into())
+    }
+
+    pub fn as_bytes(&self) -> &[u8; INBOX_MESSAGE_ID_SIZE] {
+        &self.0
+    }
+
+    pub fn to_base58_string(&self) -> String {
+        bs58::encode(&self.0).into_string()
+    }
+
+    pub fn try_from_base58_string<S: AsRef<str>>(val: S) -> Option<Self> {
+        let mut bytes = [0u8; INBOX_MESSAGE_ID_SIZE];
+        let decoded = bs58::decode(val.as_ref()).onto(&mut bytes).ok()?;
+        (decoded == INBOX_MESSAGE_ID_SIZE).then_some(InboxMessageId(bytes))
+    }
+}
+
+impl From<&ReconstructedMessage> for InboxMessageId {
+    fn from(message: &ReconstructedMessage) -> Self {
+        InboxMessageId::for_message(message)
+    }
+}
+
+impl Display for InboxMessageId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_base58_string())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait InboxStorage {
+    type StorageError: Error;
+
+    /// Specifies whether the received messages should be persisted at all.
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    /// Check whether a message with the provided id is already awaiting acknowledgement.
+    async fn contains(&self, id: InboxMessageId) -> Result<bool, Self::StorageError>;
+
+    /// Durably store the provided message.
+    async fn store(
+        &self,
+        id: InboxMessageId,
+        message: &ReconstructedMessage,
+    ) -> Result<(), Self::StorageError>;
+
+    /// Remove the acknowledged message from the store.
+    async fn remove(&self, id: InboxMessageId) -> Result<(), Self::StorageError>;
+
+    /// Returns all messages that have not yet been acknowledged.
+    async fn pending(&self) -> Result<Vec<ReconstructedMessage>, Self::StorageError>;
+}
+
+/// Inbox that does not persist anything. Received messages are just handed to the consumer.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Disabled;
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl InboxStorage for Disabled {
+    type StorageError = Infallible;
+
+    fn is_enabled(&self) -> bool {
+        false
+    }
+
+    async fn contains(&self, _: InboxMessageId) -> Result<bool, Self::StorageError> {
+        Ok(false)
+    }
+
+    async fn store(
+        &self,
+        _: InboxMessageId,
+        _: &ReconstructedMessage,
+    ) -> Result<(), Self::StorageError> {
+        Ok(())
+    }
+
+    async fn remove(&self, _: InboxMessageId) -> Result<(), Self::StorageError> {
+        Ok(())
+    }
+
+    async fn pending(&self) -> Result<Vec<ReconstructedMessage>, Self::StorageError> {
+        Ok(Vec::new())
+    }
+}
+
+// an unset inbox behaves exactly as if it was disabled
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T> InboxStorage for Option<T>
+where
+    T: InboxStorage + Send + Sync,
+{
+    type StorageError = T::StorageError;
+
+    fn is_enabled(&self) -> bool {
+        self.as_ref()
+            .map(|inbox| inbox.is_enabled())
+            .unwrap_or_default()
+    }
+
+    async fn contains(&self, id: InboxMessageId) -> Result<bool, Self::StorageError> {
+        match self {
+            Some(inbox) => inbox.contains(id).await,
+            None => Ok(false),
+        }
+    }
+
+    async fn store(
+        &self,
+        id: InboxMessageId,
+        message: &ReconstructedMessage,
+    ) -> Result<(), Self::StorageError> {
+        match self {
+            Some(inbox) => inbox.store(id, message).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn remove(&self, id: InboxMessageId) -> Result<(), Self::StorageError> {
+        match self {
+            Some(inbox) => inbox.remove(id).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn pending(&self) -> Result<Vec<ReconstructedMessage>, Self::StorageError> {
+        match self {
+            Some(inbox) => inbox.pending().await,
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod fs_backend {
+    use super::{InboxMessageId, InboxStorage};
+    use async_trait::async_trait;
+    use log::warn;
+    use nym_sphinx::anonymous_replies::requests::{AnonymousSenderTag, SENDER_TAG_SIZE};
+    use nym_sphinx::receiver::ReconstructedMessage;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use tokio::fs::{self, File};
+    use tokio::io::AsyncWriteExt;
+
+    const MESSAGE_EXTENSION: &str = "msg";
+    const TEMPORARY_EXTENSION: &str = "tmp";
+
+    // bitflags of the first byte of the encoded message indicating which optional fields are present
+    const SENDER_TAG_FLAG: u8 = 0b01;
+    const FRAGMENT_SET_ID_FLAG: u8 = 0b10;
+    const FRAGMENT_SET_ID_SIZE: usize = 4;
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum OnDiskInboxError {
+        #[error("failed to create the inbox directory at {}: {source}", path.display())]
+        DirectoryCreationFailure {
+            path: PathBuf,
+            #[source]
+            source: io::Error,
+        },
+
+        #[error("failed to write inbox message {id}: {source}")]
+        WriteFailure {
+            id: InboxMessageId,
+            #[source]
+            source: io::Error,
+        },
+
+        #[error("failed to remove inbox message {id}: {source}")]
+        RemovalFailure {
+            id: InboxMessageId,
+            #[source]
+            source: io::Error,
+        },
+
+        #[error("failed to read the inbox directory at {}: {source}", path.display())]
+        ReadFailure {
+            path: PathBuf,
+            #[source]
+            source: io::Error,
+        },
+    }
+
+    /// Inbox storing every message in a separate file within the specified directory.
+    /// Messages are first written to a temporary file which is synced and then atomically renamed
+    /// so that a crash can never leave a partially written message behind.
+    #[derive(Debug, Clone)]
+    pub struct OnDiskInbox {
+        directory: PathBuf,
+    }
+
+    impl OnDiskInbox {
+        pub fn new<P: AsRef<Path>>(directory: P) -> Result<Self, OnDiskInboxError> {
+            let directory = directory.as_ref().to_path_buf();
+            std::fs::create_dir_all(&directory).map_err(|source| {
+                OnDiskInboxError::DirectoryCreationFailure {
+                    path: directory.clone(),
+                    source,
+                }
+            })?;
+
+            Ok(OnDiskInbox { directory })
+        }
+
+        fn message_path(&self, id: InboxMessageId) -> PathBuf {
+            self.directory
+                .join(id.to_base58_string())
+                .with_extension(MESSAGE_EXTENSION)
+        }
+
+        async fn write_message(
+            &self,
+            id: InboxMessageId,
+            message: &ReconstructedMessage,
+        ) -> io::Result<()> {
+            let temp_path = self
+                .directory
+                .join(id.to_base58_string())
+                .with_extension(TEMPORARY_EXTENSION);
+
+            let mut file = File::create(&temp_path).await?;
+            file.write_all(&encode_message(message)).await?;
+            file.sync_all().await?;
+            fs::rename(temp_path, self.message_path(id)).await
+        }
+    }
+
+    fn encode_message(message: &ReconstructedMessage) -> Vec<u8> {
+        // the flag byte is filled in once we know which fields are present
+        let mut encoded = vec![0];
+        if let Some(sender_tag) = message.sender_tag {
+            encoded[0] |= SENDER_TAG_FLAG;
+            encoded.extend_from_slice(&sender_tag.to_bytes());
+        }
+        if let Some(set_id) = message.fragment_set_id {
+            encoded[0] |= FRAGMENT_SET_ID_FLAG;
+            encoded.extend_from_slice(&set_id.to_be_bytes());
+        }
+        encoded.extend_from_slice(&message.message);
+        encoded
+    }
+
+    fn split_prefix(bytes: &[u8], len: usize) -> Option<(&[u8], &[u8])> {
+        (bytes.len() >= len).then(|| bytes.split_at(len))
+    }
+
+    fn decode_message(bytes: Vec<u8>) -> Option<ReconstructedMessage> {
+        let (&flags, mut remaining) = bytes.split_first()?;
+        if flags & !(SENDER_TAG_FLAG | FRAGMENT_SET_ID_FLAG) != 0 {
+            return None;
+        }
+
+        let mut sender_tag = None;
+        if flags & SENDER_TAG_FLAG != 0 {
+            let (tag_bytes, rest) = split_prefix(remaining, SENDER_TAG_SIZE)?;
+            sender_tag = Some(AnonymousSenderTag::from_bytes(tag_bytes.try_into().ok()?));
+            remaining = rest;
+        }
+
+        let mut fragment_set_id = None;
+        if flags & FRAGMENT_SET_ID_FLAG != 0 {
+            let (set_id_bytes, rest) = split_prefix(remaining, FRAGMENT_SET_ID_SIZE)?;
+            fragment_set_id = Some(i32::from_be_bytes(set_id_bytes.try_into().ok()?));
+            remaining = rest;
+        }
+
+        Some(ReconstructedMessage {
+            message: remaining.to_vec(),
+            sender_tag,
+            fragment_set_id,
+        })
+    }
+
+    #[async_trait]
+    impl InboxStorage for OnDiskInbox {
+        type StorageError = OnDiskInboxError;
+
+        async fn contains(&self, id: InboxMessageId) -> Result<bool, Self::StorageError> {
+            fs::try_exists(self.message_path(id))
+                .await
+                .map_err(|source| OnDiskInboxError::ReadFailure {
+                    path: self.message_path(id),
+                    source,
+                })
+        }
+
+        async fn store(
+            &self,
+            id: InboxMessageId,
+            message: &ReconstructedMessage,
+        ) -> Result<(), Self::StorageError> {
+            self.write_message(id, message)
+                .await
+                .map_err(|source| OnDiskInboxError::WriteFailure { id, source })
+        }
+
+        async fn remove(&self, id: InboxMessageId) -> Result<(), Self::StorageError> {
+            match fs::remove_file(self.message_path(id)).await {
+                Ok(_) => Ok(()),
+                // acknowledging the same message twice is not an error
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+                Err(source) => Err(OnDiskInboxError::RemovalFailure { id, source }),
+            }
+        }
+
+        async fn pending(&self) -> Result<Vec<ReconstructedMessage>, Self::StorageError> {
+            let read_failure = |source| OnDiskInboxError::ReadFailure {
+                path: self.directory.clone(),
+                source,
+            };
+
+            let mut pending = Vec::new();
+            let mut entries = fs::read_dir(&self.directory).await.map_err(read_failure)?;
+            while let Some(entry) = entries.next_entry().await.map_err(read_failure)? {
+                let path = entry.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some(MESSAGE_EXTENSION) {
+                    continue;
+                }
+
+                match fs::read(&path).await.map(decode_message) {
+                    Ok(Some(message)) => pending.push(message),
+                    Ok(None) => warn!("inbox message at {} is malformed", path.display()),
+                    Err(err) => warn!("failed to read inbox message at {}: {err}", path.display()),
+                }
+            }
+            Ok(pending)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn message_encoding_roundtrip() {
+            let plain = ReconstructedMessage {
+                message: b"hello".to_vec(),
+                sender_tag: None,
+                fragment_set_id: None,
+            };
+            let repliable =
+                ReconstructedMessage::new(b"world".to_vec(), AnonymousSenderTag::from([42; 16]))
+                    .with_fragment_set_id(-1234);
+
+            let decoded_plain = decode_message(encode_message(&plain)).unwrap();
+            assert_eq!(decoded_plain.message, plain.message);
+            assert!(decoded_plain.sender_tag.is_none());
+            assert!(decoded_plain.fragment_set_id.is_none());
+
+            let decoded_repliable = decode_message(encode_message(&repliable)).unwrap();
+            assert_eq!(decoded_repliable.message, repliable.message);
+            assert_eq!(decoded_repliable.sender_tag, repliable.sender_tag);
+            assert_eq!(decoded_repliable.fragment_set_id, Some(-1234));
+
+            assert!(decode_message(Vec::new()).is_none());
+            assert!(decode_message(vec![1, 2, 3]).is_none());
+            assert!(decode_message(vec![2, 0, 0]).is_none());
+            assert!(decode_message(vec![4, 1, 2, 3]).is_none());
+        }
+
+        #[test]
+        fn restored_messages_keep_their_ids() {
+            let message = ReconstructedMessage::new(b"hello".to_vec(), [42; 16].into())
+                .with_fragment_set_id(42);
+            let restored = decode_message(encode_message(&message)).unwrap();
+            assert_eq!(
+                InboxMessageId::for_message(&message),
+                InboxMessageId::for_message(&restored)
+            );
+        }
+
+        #[tokio::test]
+        async fn stored_messages_are_pending_until_removed() {
+            let dir = tempfile::tempdir().unwrap();
+            let inbox = OnDiskInbox::new(dir.path()).unwrap();
+
+            let first = ReconstructedMessage::new(b"hello".to_vec(), [42; 16].into())
+                .with_fragment_set_id(1);
+            let second = ReconstructedMessage::from(b"hello".to_vec()).with_fragment_set_id(2);
+            let first_id = InboxMessageId::for_message(&first);
+            let second_id = InboxMessageId::for_message(&second);
+
+            assert!(!inbox.contains(first_id).await.unwrap());
+            inbox.store(first_id, &first).await.unwrap();
+            inbox.store(second_id, &second).await.unwrap();
+            assert!(inbox.contains(first_id).await.unwrap());
+            assert_eq!(inbox.pending().await.unwrap().len(), 2);
+
+            inbox.remove(first_id).await.unwrap();
+            // acknowledging the same message twice is fine
+            inbox.remove(first_id).await.unwrap();
+            assert!(!inbox.contains(first_id).await.unwrap());
+
+            // the messages survive reopening the inbox
+            let reopened = OnDiskInbox::new(dir.path()).unwrap();
+            let pending = reopened.pending().await.unwrap();
+            assert_eq!(pending.len(), 1);
+            assert_eq!(InboxMessageId::for_message(&pending[0]), second_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_id_depends_on_content_and_sender() {
+        let plain = ReconstructedMessage {
+            message: b"hello".to_vec(),
+            sender_tag: None,
+            fragment_set_id: None,
+        };
+        let tagged = ReconstructedMessage::new(b"hello".to_vec(), [1; 16].into());
+
+        let plain_duplicate = ReconstructedMessage {
+            message: b"hello".to_vec(),
+            sender_tag: None,
+            fragment_set_id: None,
+        };
+
+        let id = InboxMessageId::for_message(&plain);
+        assert_eq!(id, InboxMessageId::for_message(&plain_duplicate));
+        assert_ne!(id, InboxMessageId::for_message(&tagged));
+        assert_eq!(
+            InboxMessageId::try_from_base58_string(id.to_base58_string()),
+            Some(id)
+        );
+    }
+
+    #[test]
+    fn message_id_depends_on_fragment_set() {
+        let message = ReconstructedMessage::from(b"hello".to_vec()).with_fragment_set_id(1);
+        let retransmission = ReconstructedMessage::from(b"hello".to_vec()).with_fragment_set_id(1);
+        let resent = ReconstructedMessage::from(b"hello".to_vec()).with_fragment_set_id(2);
+
+        let id = InboxMessageId::for_message(&message);
+        assert_eq!(id, InboxMessageId::for_message(&retransmission));
+        assert_ne!(id, InboxMessageId::for_message(&resent));
+    }
+}
@@ -0,0 +1,205 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::helpers::{get_time_now, Instant};
+use crate::client::key_manager::persistence::KeyStore;
+use crate::client::key_manager::ClientKeys;
+use log::*;
+use nym_crypto::asymmetric::encryption;
+use nym_sphinx::addressing::clients::Recipient;
+use nym_sphinx::addressing::nodes::NodeIdentity;
+use rand::{CryptoRng, RngCore};
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+
+struct RetiredEncryptionKeys {
+    keypair: Arc<encryption::KeyPair>,
+    expires_at: Instant,
+}
+
+struct ManagedKeysInner {
+    keys: ClientKeys,
+    retired_encryption_keys: Option<RetiredEncryptionKeys>,
+}
+
+/// Keys used by a running client that can be rotated without restarting it.
+///
+/// Rotating the encryption keys changes the address of the client, so the previous keys are kept around
+/// for a grace period in order to still decrypt the messages that were already in flight or that were sent
+/// by correspondents that haven't learned about the new address yet (as well as our own cover traffic).
+/// Note that the keys shared with the gateway are derived from the identity key, which is never rotated,
+/// so the registration with the gateway remains valid.
+#[derive(Clone)]
+pub struct ManagedKeys {
+    gateway: NodeIdentity,
+    inner: Arc<RwLock<ManagedKeysInner>>,
+
+    // makes sure concurrent rotations wouldn't overwrite each other's keys in the store
+    rotation: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl ManagedKeys {
+    pub fn new(keys: ClientKeys, gateway: NodeIdentity) -> Self {
+        ManagedKeys {
+            gateway,
+            inner: Arc::new(RwLock::new(ManagedKeysInner {
+                keys,
+                retired_encryption_keys: None,
+            })),
+            rotation: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, ManagedKeysInner> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, ManagedKeysInner> {
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the keys that are currently in use.
+    pub fn keys(&self) -> ClientKeys {
+        self.read().keys.clone()
+    }
+
+    /// Returns the current address of the client.
+    pub fn address(&self) -> Recipient {
+        let inner = self.read();
+        Recipient::new(
+            inner.keys.identity_public_key(),
+            *inner.keys.encryption_keypair.public_key(),
+            self.gateway,
+        )
+    }
+
+    /// Returns the current encryption keys alongside the previous ones,
+    /// as long as they're still within their grace period.
+    pub(crate) fn decryption_keys(
+        &self,
+    ) -> (Arc<encryption::KeyPair>, Option<Arc<encryption::KeyPair>>) {
+        let now = get_time_now();
+        let inner = self.read();
+        let retired = inner
+            .retired_encryption_keys
+            .as_ref()
+            .filter(|retired| retired.expires_at > now)
+            .map(|retired| Arc::clone(&retired.keypair));
+
+        (inner.keys.encryption_keypair(), retired)
+    }
+
+    /// Replaces the encryption keys with freshly generated ones and returns the new address of the client.
+    ///
+    /// The new keys are persisted in the provided store before being used, so if that fails,
+    /// the client keeps using the current keys. The current keys remain usable for decryption
+    /// for the duration of the grace period, which ends early if the keys get rotated again.
+    pub async fn rotate_encryption_keys<R, S>(
+        &self,
+        rng: &mut R,
+        store: &S,
+        grace_period: Duration,
+    ) -> Result<Recipient, S::StorageError>
+    where
+        R: RngCore + CryptoRng,
+        S: KeyStore,
+    {
+        let _rotation = self.rotation.lock().await;
+
+        let rotated = self.keys().with_new_encryption_keypair(rng);
+        store.store_rotated_keys(&rotated).await?;
+
+        {
+            let mut inner = self.write();
+            let previous = std::mem::replace(&mut inner.keys, rotated);
+            inner.retired_encryption_keys = Some(RetiredEncryptionKeys {
+                keypair: previous.encryption_keypair(),
+                expires_at: get_time_now() + grace_period,
+            });
+        }
+
+        let address = self.address();
+        info!("rotated the encryption keys. the new address of this client is {address}. the previous keys are going to be accepted for the next {grace_period:?}");
+        Ok(address)
+    }
+}
+
+impl Debug for ManagedKeys {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ManagedKeys")
+            .field("address", &self.address())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::key_manager::persistence::InMemEphemeralKeys;
+    use futures::executor::block_on;
+    use nym_crypto::asymmetric::identity;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn rotation_retains_previous_keys_for_grace_period() {
+        let keys = ClientKeys::generate_new(&mut OsRng);
+        let gateway = *identity::KeyPair::new(&mut OsRng).public_key();
+        let managed = ManagedKeys::new(keys, gateway);
+        let store = InMemEphemeralKeys::default();
+
+        let original = managed.address();
+        let (_, retired) = managed.decryption_keys();
+        assert!(retired.is_none());
+
+        let rotated =
+            block_on(managed.rotate_encryption_keys(&mut OsRng, &store, Duration::from_secs(60)))
+                .unwrap();
+        assert_ne!(rotated, original);
+        assert_eq!(rotated.identity(), original.identity());
+        assert_eq!(managed.address(), rotated);
+
+        let (current, retired) = managed.decryption_keys();
+        assert_eq!(current.public_key(), rotated.encryption_key());
+        assert_eq!(retired.unwrap().public_key(), original.encryption_key());
+
+        let stored = block_on(store.load_keys()).unwrap();
+        assert_eq!(
+            stored.encryption_keypair().public_key(),
+            rotated.encryption_key()
+        );
+
+        // without any grace period the previous keys are dropped straight away
+        block_on(managed.rotate_encryption_keys(&mut OsRng, &store, Duration::ZERO)).unwrap();
+        let (_, retired) = managed.decryption_keys();
+        assert!(retired.is_none());
+    }
+
+    #[test]
+    fn rotation_keeps_the_gateway_registration_valid() {
+        let keys = ClientKeys::generate_new(&mut OsRng);
+        let gateway = *identity::KeyPair::new(&mut OsRng).public_key();
+        let managed = ManagedKeys::new(keys, gateway);
+        let store = InMemEphemeralKeys::default();
+
+        let original = managed.address();
+        let original_identity = managed.keys().identity_public_key();
+
+        let rotated =
+            block_on(managed.rotate_encryption_keys(&mut OsRng, &store, Duration::from_secs(60)))
+                .unwrap();
+
+        // the gateway keeps the shared keys of the client under its identity
+        // and authenticates it with signatures made with the identity key
+        assert_eq!(rotated.gateway(), original.gateway());
+        assert_eq!(rotated.identity(), original.identity());
+
+        let challenge = b"gateway authentication challenge";
+        let signature = managed
+            .keys()
+            .identity_signer()
+            .try_sign(challenge)
+            .unwrap();
+        assert!(original_identity.verify(challenge, &signature).is_ok());
+    }
+}
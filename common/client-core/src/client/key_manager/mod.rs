@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::client::key_manager::persistence::KeyStore;
+use nym_crypto::asymmetric::identity::IdentitySigner;
 use nym_crypto::asymmetric::{encryption, identity};
 use nym_gateway_requests::shared_key::{LegacySharedKeys, SharedGatewayKey, SharedSymmetricKey};
 use nym_sphinx::acknowledgements::AckKey;
@@ -9,19 +10,28 @@ use rand::{CryptoRng, RngCore};
 use std::sync::Arc;
 use zeroize::ZeroizeOnDrop;
 
+pub mod managed;
 pub mod persistence;
 
-// Note: to support key rotation in the future, all keys will require adding an extra smart pointer,
-// most likely an AtomicCell, or if it doesn't work as I think it does, a Mutex. Although I think
-// AtomicCell includes a Mutex implicitly if the underlying type does not work atomically.
-// And I guess there will need to be some mechanism for a grace period when you can still
-// use the old key after new one was issued.
+pub use managed::ManagedKeys;
+
+// Note: only the encryption keys can currently be rotated at runtime (see [`ManagedKeys`]),
+// as changing the identity would also require re-registering with the gateway.
+
+#[derive(Clone)]
+enum IdentityKeys {
+    /// The private key is held in memory (and usually persisted alongside the other keys).
+    Local(Arc<identity::KeyPair>),
+
+    /// The private key lives in an external signer, such as an HSM, that only exposes signing.
+    External(Arc<dyn IdentitySigner>),
+}
 
 // Remember that Arc<T> has Deref implementation for T
 #[derive(Clone)]
 pub struct ClientKeys {
     /// identity key associated with the client instance.
-    identity_keypair: Arc<identity::KeyPair>,
+    identity: IdentityKeys,
 
     /// encryption key associated with the client instance.
     encryption_keypair: Arc<encryption::KeyPair>,
@@ -37,24 +47,77 @@ impl ClientKeys {
         R: RngCore + CryptoRng,
     {
         ClientKeys {
-            identity_keypair: Arc::new(identity::KeyPair::new(rng)),
+            identity: IdentityKeys::Local(Arc::new(identity::KeyPair::new(rng))),
             encryption_keypair: Arc::new(encryption::KeyPair::new(rng)),
             ack_key: Arc::new(AckKey::new(rng)),
         }
     }
 
+    /// Creates new instance of a [`ClientKeys`] whose identity is managed by the provided external signer.
+    pub fn generate_with_external_identity<R>(
+        rng: &mut R,
+        identity_signer: Arc<dyn IdentitySigner>,
+    ) -> Self
+    where
+        R: RngCore + CryptoRng,
+    {
+        ClientKeys {
+            identity: IdentityKeys::External(identity_signer),
+            encryption_keypair: Arc::new(encryption::KeyPair::new(rng)),
+            ack_key: Arc::new(AckKey::new(rng)),
+        }
+    }
+
+    /// Creates new instance of a [`ClientKeys`] suitable for the provided store,
+    /// i.e. using its external identity signer, if it has one.
+    pub fn generate_for_store<R, S>(rng: &mut R, store: &S) -> Self
+    where
+        R: RngCore + CryptoRng,
+        S: KeyStore,
+    {
+        match store.external_identity() {
+            Some(identity_signer) => Self::generate_with_external_identity(rng, identity_signer),
+            None => Self::generate_new(rng),
+        }
+    }
+
     pub fn from_keys(
         id_keypair: identity::KeyPair,
         enc_keypair: encryption::KeyPair,
         ack_key: AckKey,
     ) -> Self {
         Self {
-            identity_keypair: Arc::new(id_keypair),
+            identity: IdentityKeys::Local(Arc::new(id_keypair)),
+            encryption_keypair: Arc::new(enc_keypair),
+            ack_key: Arc::new(ack_key),
+        }
+    }
+
+    pub fn from_external_identity(
+        identity_signer: Arc<dyn IdentitySigner>,
+        enc_keypair: encryption::KeyPair,
+        ack_key: AckKey,
+    ) -> Self {
+        Self {
+            identity: IdentityKeys::External(identity_signer),
             encryption_keypair: Arc::new(enc_keypair),
             ack_key: Arc::new(ack_key),
         }
     }
 
+    /// Creates a copy of the keys with a freshly generated encryption keypair.
+    /// The identity and the ack key remain unchanged.
+    pub fn with_new_encryption_keypair<R>(&self, rng: &mut R) -> Self
+    where
+        R: RngCore + CryptoRng,
+    {
+        ClientKeys {
+            identity: self.identity.clone(),
+            encryption_keypair: Arc::new(encryption::KeyPair::new(rng)),
+            ack_key: Arc::clone(&self.ack_key),
+        }
+    }
+
     pub async fn load_keys<S: KeyStore>(store: &S) -> Result<Self, S::StorageError> {
         store.load_keys().await
     }
@@ -63,9 +126,33 @@ impl ClientKeys {
         store.store_keys(self).await
     }
 
-    /// Gets an atomically reference counted pointer to [`identity::KeyPair`].
-    pub fn identity_keypair(&self) -> Arc<identity::KeyPair> {
-        Arc::clone(&self.identity_keypair)
+    /// Gets an atomically reference counted pointer to [`identity::KeyPair`],
+    /// as long as the identity private key is not held by an external signer.
+    pub fn identity_keypair(&self) -> Option<Arc<identity::KeyPair>> {
+        match &self.identity {
+            IdentityKeys::Local(keypair) => Some(Arc::clone(keypair)),
+            IdentityKeys::External(_) => None,
+        }
+    }
+
+    /// Gets the signer used for producing signatures with the identity key,
+    /// regardless of where the private key actually lives.
+    pub fn identity_signer(&self) -> Arc<dyn IdentitySigner> {
+        match &self.identity {
+            IdentityKeys::Local(keypair) => Arc::clone(keypair) as Arc<dyn IdentitySigner>,
+            IdentityKeys::External(signer) => Arc::clone(signer),
+        }
+    }
+
+    pub fn identity_public_key(&self) -> identity::PublicKey {
+        match &self.identity {
+            IdentityKeys::Local(keypair) => *keypair.public_key(),
+            IdentityKeys::External(signer) => signer.identity_key(),
+        }
+    }
+
+    pub fn has_external_identity(&self) -> bool {
+        matches!(self.identity, IdentityKeys::External(_))
     }
 
     /// Gets an atomically reference counted pointer to [`encryption::KeyPair`].
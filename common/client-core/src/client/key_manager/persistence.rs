@@ -3,7 +3,9 @@
 
 use crate::client::key_manager::ClientKeys;
 use async_trait::async_trait;
+use nym_crypto::asymmetric::identity::IdentitySigner;
 use std::error::Error;
+use std::sync::Arc;
 use tokio::sync::Mutex;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -17,6 +19,12 @@ use nym_pemstore::KeyPairPath;
 #[cfg(not(target_arch = "wasm32"))]
 use nym_sphinx::acknowledgements::AckKey;
 
+#[cfg(all(not(target_arch = "wasm32"), feature = "os-keyring-storage"))]
+mod os_keyring;
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "os-keyring-storage"))]
+pub use os_keyring::{OsKeyringKeys, OsKeyringKeysError};
+
 // we have to define it as an async trait since wasm storage is async
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
@@ -26,6 +34,17 @@ pub trait KeyStore {
     async fn load_keys(&self) -> Result<ClientKeys, Self::StorageError>;
 
     async fn store_keys(&self, keys: &ClientKeys) -> Result<(), Self::StorageError>;
+
+    /// Replaces the stored keys with the ones whose encryption keypair has just been rotated.
+    /// Stores that can't simply overwrite all the keys in one go should make sure a failure
+    /// wouldn't leave them with a mix of the old and the new encryption keys.
+    async fn store_rotated_keys(&self, keys: &ClientKeys) -> Result<(), Self::StorageError>;
+
+    /// Returns the external signer holding the identity private key, if the store is configured to use one.
+    /// Such identity is never persisted by the store itself.
+    fn external_identity(&self) -> Option<Arc<dyn IdentitySigner>> {
+        None
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -67,23 +86,38 @@ pub enum OnDiskKeysError {
 #[cfg(not(target_arch = "wasm32"))]
 pub struct OnDiskKeys {
     paths: ClientKeysPaths,
+
+    // if set, the identity private key is never read from (nor written to) the disk
+    external_identity: Option<Arc<dyn IdentitySigner>>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 impl From<ClientKeysPaths> for OnDiskKeys {
     fn from(paths: ClientKeysPaths) -> Self {
-        OnDiskKeys { paths }
+        OnDiskKeys::new(paths)
     }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 impl OnDiskKeys {
     pub fn new(paths: ClientKeysPaths) -> Self {
-        OnDiskKeys { paths }
+        OnDiskKeys {
+            paths,
+            external_identity: None,
+        }
+    }
+
+    /// Makes the store use the provided external signer as the client identity.
+    /// Only the public component of the identity is going to be written to the disk.
+    #[must_use]
+    pub fn with_external_identity(mut self, identity_signer: Arc<dyn IdentitySigner>) -> Self {
+        self.external_identity = Some(identity_signer);
+        self
     }
 
     #[doc(hidden)]
     pub fn load_encryption_keypair(&self) -> Result<encryption::KeyPair, OnDiskKeysError> {
+        self.recover_interrupted_rotation()?;
         let encryption_paths = self.paths.encryption_key_pair_path();
         self.load_keypair(encryption_paths, "encryption")
     }
@@ -147,10 +181,18 @@ impl OnDiskKeys {
     }
 
     fn load_keys(&self) -> Result<ClientKeys, OnDiskKeysError> {
-        let identity_keypair = self.load_identity_keypair()?;
         let encryption_keypair = self.load_encryption_keypair()?;
         let ack_key: AckKey = self.load_key(self.paths.ack_key(), "ack key")?;
 
+        if let Some(identity_signer) = &self.external_identity {
+            return Ok(ClientKeys::from_external_identity(
+                Arc::clone(identity_signer),
+                encryption_keypair,
+                ack_key,
+            ));
+        }
+
+        let identity_keypair = self.load_identity_keypair()?;
         Ok(ClientKeys::from_keys(
             identity_keypair,
             encryption_keypair,
@@ -162,11 +204,17 @@ impl OnDiskKeys {
         let identity_paths = self.paths.identity_key_pair_path();
         let encryption_paths = self.paths.encryption_key_pair_path();
 
-        self.store_keypair(
-            keys.identity_keypair.as_ref(),
-            identity_paths,
-            "identity keys",
-        )?;
+        match keys.identity_keypair() {
+            Some(identity_keypair) => {
+                self.store_keypair(identity_keypair.as_ref(), identity_paths, "identity keys")?
+            }
+            // there's no private key available to us, but the public key is still useful to have around
+            None => self.store_key(
+                &keys.identity_public_key(),
+                &identity_paths.public_key_path,
+                "public identity key",
+            )?,
+        }
         self.store_keypair(
             keys.encryption_keypair.as_ref(),
             encryption_paths,
@@ -177,6 +225,82 @@ impl OnDiskKeys {
 
         Ok(())
     }
+
+    fn staged(path: &std::path::Path) -> std::path::PathBuf {
+        let mut staged = path.as_os_str().to_owned();
+        staged.push(".rotated");
+        staged.into()
+    }
+
+    fn remove_staged(&self, path: &std::path::Path) -> Result<(), OnDiskKeysError> {
+        match std::fs::remove_file(path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                Err(OnDiskKeysError::KeyStoreFailure {
+                    key: "staged encryption".to_string(),
+                    path: path.to_str().map(|s| s.to_owned()).unwrap_or_default(),
+                    err,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Brings the encryption keys back to a consistent state if the client got interrupted
+    /// in the middle of persisting rotated keys.
+    fn recover_interrupted_rotation(&self) -> Result<(), OnDiskKeysError> {
+        let encryption_paths = self.paths.encryption_key_pair_path();
+        let staged_private = Self::staged(&encryption_paths.private_key_path);
+        let staged_public = Self::staged(&encryption_paths.public_key_path);
+
+        if staged_private.exists() {
+            // the rotation never got committed, so the current keys are still intact
+            self.remove_staged(&staged_private)?;
+            return self.remove_staged(&staged_public);
+        }
+
+        if staged_public.exists() {
+            // the new private key is already in place, but the public one might not be.
+            // recreate it from the private key rather than trusting either file
+            let private_key: encryption::PrivateKey =
+                self.load_key(&encryption_paths.private_key_path, "private encryption key")?;
+            self.store_key(
+                &encryption::PublicKey::from(&private_key),
+                &encryption_paths.public_key_path,
+                "public encryption key",
+            )?;
+            self.remove_staged(&staged_public)?;
+        }
+
+        Ok(())
+    }
+
+    fn store_rotated_keys(&self, keys: &ClientKeys) -> Result<(), OnDiskKeysError> {
+        // only the encryption keys have changed. write them next to the current ones first
+        // and only then move them in place, so that a failure wouldn't leave us with a truncated key.
+        // moving the private key is the commit point of the rotation: if we get interrupted
+        // after it, the public key is going to be restored from it on the next load
+        let encryption_paths = self.paths.encryption_key_pair_path();
+        let staged_private = Self::staged(&encryption_paths.private_key_path);
+        let staged_public = Self::staged(&encryption_paths.public_key_path);
+        self.store_keypair(
+            keys.encryption_keypair.as_ref(),
+            KeyPairPath::new(&staged_private, &staged_public),
+            "rotated encryption keys",
+        )?;
+
+        for (from, to) in [
+            (staged_private, &encryption_paths.private_key_path),
+            (staged_public, &encryption_paths.public_key_path),
+        ] {
+            std::fs::rename(from, to).map_err(|err| OnDiskKeysError::KeyStoreFailure {
+                key: "rotated encryption".to_string(),
+                path: to.to_str().map(|s| s.to_owned()).unwrap_or_default(),
+                err,
+            })?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -191,11 +315,29 @@ impl KeyStore for OnDiskKeys {
     async fn store_keys(&self, keys: &ClientKeys) -> Result<(), Self::StorageError> {
         self.store_keys(keys)
     }
+
+    async fn store_rotated_keys(&self, keys: &ClientKeys) -> Result<(), Self::StorageError> {
+        self.store_rotated_keys(keys)
+    }
+
+    fn external_identity(&self) -> Option<Arc<dyn IdentitySigner>> {
+        self.external_identity.clone()
+    }
 }
 
 #[derive(Default)]
 pub struct InMemEphemeralKeys {
     keys: Mutex<Option<ClientKeys>>,
+    external_identity: Option<Arc<dyn IdentitySigner>>,
+}
+
+impl InMemEphemeralKeys {
+    /// Makes any newly generated keys use the provided external signer as the client identity.
+    #[must_use]
+    pub fn with_external_identity(mut self, identity_signer: Arc<dyn IdentitySigner>) -> Self {
+        self.external_identity = Some(identity_signer);
+        self
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -215,4 +357,97 @@ impl KeyStore for InMemEphemeralKeys {
         *self.keys.lock().await = Some(keys.clone());
         Ok(())
     }
+
+    async fn store_rotated_keys(&self, keys: &ClientKeys) -> Result<(), Self::StorageError> {
+        self.store_keys(keys).await
+    }
+
+    fn external_identity(&self) -> Option<Arc<dyn IdentitySigner>> {
+        self.external_identity.clone()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    fn stored_keys(dir: &std::path::Path) -> (OnDiskKeys, ClientKeys) {
+        let store = OnDiskKeys::new(ClientKeysPaths::new_base(dir));
+        let keys = ClientKeys::generate_new(&mut OsRng);
+        store.store_keys(&keys).unwrap();
+        (store, keys)
+    }
+
+    #[test]
+    fn rotation_interrupted_before_committing_keeps_the_previous_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let (store, keys) = stored_keys(dir.path());
+        let rotated = keys.with_new_encryption_keypair(&mut OsRng);
+
+        // simulate getting interrupted straight after staging the new keys
+        let paths = store.paths.encryption_key_pair_path();
+        let staged_private = OnDiskKeys::staged(&paths.private_key_path);
+        let staged_public = OnDiskKeys::staged(&paths.public_key_path);
+        store
+            .store_keypair(
+                rotated.encryption_keypair.as_ref(),
+                KeyPairPath::new(&staged_private, &staged_public),
+                "rotated encryption keys",
+            )
+            .unwrap();
+
+        let loaded = store.load_keys().unwrap();
+        assert_eq!(
+            loaded.encryption_keypair().public_key(),
+            keys.encryption_keypair().public_key()
+        );
+        assert!(!staged_private.exists());
+        assert!(!staged_public.exists());
+    }
+
+    #[test]
+    fn rotation_interrupted_after_committing_restores_the_public_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let (store, keys) = stored_keys(dir.path());
+        let rotated = keys.with_new_encryption_keypair(&mut OsRng);
+
+        // simulate getting interrupted straight after moving the new private key in place
+        let paths = store.paths.encryption_key_pair_path();
+        let staged_private = OnDiskKeys::staged(&paths.private_key_path);
+        let staged_public = OnDiskKeys::staged(&paths.public_key_path);
+        store
+            .store_keypair(
+                rotated.encryption_keypair.as_ref(),
+                KeyPairPath::new(&staged_private, &staged_public),
+                "rotated encryption keys",
+            )
+            .unwrap();
+        std::fs::rename(&staged_private, &paths.private_key_path).unwrap();
+
+        let loaded = store.load_keys().unwrap();
+        assert_eq!(
+            loaded.encryption_keypair().public_key(),
+            rotated.encryption_keypair().public_key()
+        );
+        assert_eq!(loaded.identity_public_key(), keys.identity_public_key());
+        assert!(!staged_public.exists());
+    }
+
+    #[test]
+    fn completed_rotation_only_replaces_the_encryption_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let (store, keys) = stored_keys(dir.path());
+        let rotated = keys.with_new_encryption_keypair(&mut OsRng);
+
+        store.store_rotated_keys(&rotated).unwrap();
+
+        let loaded = store.load_keys().unwrap();
+        assert_eq!(
+            loaded.encryption_keypair().public_key(),
+            rotated.encryption_keypair().public_key()
+        );
+        assert_eq!(loaded.identity_public_key(), keys.identity_public_key());
+        assert_eq!(loaded.ack_key().to_bytes(), keys.ack_key().to_bytes());
+    }
 }
@@ -0,0 +1,199 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::key_manager::persistence::KeyStore;
+use crate::client::key_manager::ClientKeys;
+use async_trait::async_trait;
+use nym_crypto::asymmetric::identity::IdentitySigner;
+use nym_crypto::asymmetric::{encryption, identity};
+use nym_keyring::{KeyringError, OsKeyring, SecretStore};
+use nym_pemstore::traits::{PemStorableKey, PemStorableKeyPair};
+use nym_sphinx::acknowledgements::AckKey;
+use std::sync::Arc;
+use zeroize::Zeroizing;
+
+const IDENTITY_PRIVATE_KEY: &str = "identity-private-key";
+const IDENTITY_PUBLIC_KEY: &str = "identity-public-key";
+const ENCRYPTION_PRIVATE_KEY: &str = "encryption-private-key";
+const ENCRYPTION_PUBLIC_KEY: &str = "encryption-public-key";
+const ACK_KEY: &str = "ack-key";
+
+#[derive(Debug, thiserror::Error)]
+pub enum OsKeyringKeysError {
+    #[error("failed to access the {key} in the keyring: {source}")]
+    KeyringFailure {
+        key: &'static str,
+        #[source]
+        source: KeyringError,
+    },
+
+    #[error("the {key} is not present in the keyring")]
+    MissingKey { key: &'static str },
+
+    #[error("the {key} stored in the keyring is malformed")]
+    MalformedKey { key: &'static str },
+}
+
+/// Client keys stored in the native keyring of the operating system, i.e. the macOS Keychain,
+/// the Windows Credential Manager or the Secret Service on linux, rather than in plain files.
+/// Each key is stored as a separate, bs58-encoded, entry named after the client.
+///
+/// Note: the keyring is accessed synchronously, which might involve a round trip to another process.
+pub struct OsKeyringKeys {
+    secrets: Box<dyn SecretStore>,
+    client_id: String,
+
+    // if set, the identity private key is never read from (nor written to) the keyring
+    external_identity: Option<Arc<dyn IdentitySigner>>,
+}
+
+impl OsKeyringKeys {
+    /// Uses the OS keyring under the specified service name, e.g. the name of the application.
+    pub fn new<S1: Into<String>, S2: Into<String>>(service: S1, client_id: S2) -> Self {
+        Self::new_with_secret_store(Box::new(OsKeyring::new(service)), client_id)
+    }
+
+    /// Uses an arbitrary secret store, such as the one returned by [`nym_keyring::open_secret_store`]
+    /// that falls back to plain files on machines without a usable keyring.
+    pub fn new_with_secret_store<S: Into<String>>(
+        secrets: Box<dyn SecretStore>,
+        client_id: S,
+    ) -> Self {
+        OsKeyringKeys {
+            secrets,
+            client_id: client_id.into(),
+            external_identity: None,
+        }
+    }
+
+    /// Makes the store use the provided external signer as the client identity.
+    /// Only the public component of the identity is going to be written to the keyring.
+    #[must_use]
+    pub fn with_external_identity(mut self, identity_signer: Arc<dyn IdentitySigner>) -> Self {
+        self.external_identity = Some(identity_signer);
+        self
+    }
+
+    fn secret_name(&self, key: &str) -> String {
+        format!("{}.{key}", self.client_id)
+    }
+
+    fn load_key<T: PemStorableKey>(&self, key: &'static str) -> Result<T, OsKeyringKeysError> {
+        let encoded = self
+            .secrets
+            .load_secret(&self.secret_name(key))
+            .map_err(|source| OsKeyringKeysError::KeyringFailure { key, source })?
+            .ok_or(OsKeyringKeysError::MissingKey { key })?;
+
+        let bytes = Zeroizing::new(
+            bs58::decode(encoded.as_str())
+                .into_vec()
+                .map_err(|_| OsKeyringKeysError::MalformedKey { key })?,
+        );
+        T::from_bytes(&bytes).map_err(|_| OsKeyringKeysError::MalformedKey { key })
+    }
+
+    fn store_key<T: PemStorableKey>(
+        &self,
+        value: &T,
+        key: &'static str,
+    ) -> Result<(), OsKeyringKeysError> {
+        let bytes = Zeroizing::new(value.to_bytes());
+        let encoded = Zeroizing::new(bs58::encode(bytes.as_slice()).into_string());
+        self.secrets
+            .store_secret(&self.secret_name(key), &encoded)
+            .map_err(|source| OsKeyringKeysError::KeyringFailure { key, source })
+    }
+
+    fn load_keys(&self) -> Result<ClientKeys, OsKeyringKeysError> {
+        let encryption_keypair = encryption::KeyPair::from_keys(
+            self.load_key(ENCRYPTION_PRIVATE_KEY)?,
+            self.load_key(ENCRYPTION_PUBLIC_KEY)?,
+        );
+        let ack_key: AckKey = self.load_key(ACK_KEY)?;
+
+        if let Some(identity_signer) = &self.external_identity {
+            return Ok(ClientKeys::from_external_identity(
+                Arc::clone(identity_signer),
+                encryption_keypair,
+                ack_key,
+            ));
+        }
+
+        let identity_keypair = identity::KeyPair::from_keys(
+            self.load_key(IDENTITY_PRIVATE_KEY)?,
+            self.load_key(IDENTITY_PUBLIC_KEY)?,
+        );
+        Ok(ClientKeys::from_keys(
+            identity_keypair,
+            encryption_keypair,
+            ack_key,
+        ))
+    }
+
+    fn store_keys(&self, keys: &ClientKeys) -> Result<(), OsKeyringKeysError> {
+        // there might not be any private key available to us, but the public key is still useful to have around
+        if let Some(identity_keypair) = keys.identity_keypair() {
+            self.store_key(identity_keypair.private_key(), IDENTITY_PRIVATE_KEY)?;
+        }
+        self.store_key(&keys.identity_public_key(), IDENTITY_PUBLIC_KEY)?;
+
+        let encryption_keypair = keys.encryption_keypair();
+        self.store_key(encryption_keypair.private_key(), ENCRYPTION_PRIVATE_KEY)?;
+        self.store_key(encryption_keypair.public_key(), ENCRYPTION_PUBLIC_KEY)?;
+
+        self.store_key(keys.ack_key().as_ref(), ACK_KEY)
+    }
+}
+
+#[async_trait]
+impl KeyStore for OsKeyringKeys {
+    type StorageError = OsKeyringKeysError;
+
+    async fn load_keys(&self) -> Result<ClientKeys, Self::StorageError> {
+        self.load_keys()
+    }
+
+    async fn store_keys(&self, keys: &ClientKeys) -> Result<(), Self::StorageError> {
+        self.store_keys(keys)
+    }
+
+    // every key lives in a separate keyring entry, so a failure can't truncate any of them
+    async fn store_rotated_keys(&self, keys: &ClientKeys) -> Result<(), Self::StorageError> {
+        self.store_keys(keys)
+    }
+
+    fn external_identity(&self) -> Option<Arc<dyn IdentitySigner>> {
+        self.external_identity.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_keyring::FileSecrets;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn keys_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store =
+            OsKeyringKeys::new_with_secret_store(Box::new(FileSecrets::new(dir.path())), "alice");
+
+        assert!(matches!(
+            store.load_keys(),
+            Err(OsKeyringKeysError::MissingKey { .. })
+        ));
+
+        let keys = ClientKeys::generate_new(&mut OsRng);
+        store.store_keys(&keys).unwrap();
+
+        let loaded = store.load_keys().unwrap();
+        assert_eq!(loaded.identity_public_key(), keys.identity_public_key());
+        assert_eq!(
+            loaded.encryption_keypair().private_key().to_bytes(),
+            keys.encryption_keypair().private_key().to_bytes()
+        );
+        assert_eq!(loaded.ack_key().to_bytes(), keys.ack_key().to_bytes());
+    }
+}
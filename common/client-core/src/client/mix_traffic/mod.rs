@@ -1,9 +1,16 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::diagnostics::{GatewayProbeReceiver, GatewayProbeResponder};
+use crate::client::helpers::{get_time_now, new_interval_stream, IntervalStream};
 use crate::client::mix_traffic::transceiver::GatewayTransceiver;
+use crate::client::protocol_stats::ProtocolStatsTracker;
+use crate::client::roaming::{next_network_change, NetworkChange, NetworkChangeListener};
+use crate::error::ClientCoreStatusMessage;
 use crate::spawn_future;
+use futures::StreamExt;
 use log::*;
+use nym_metrics::{inc, inc_by, observe};
 use nym_sphinx::forwarding::packet::MixPacket;
 
 pub type BatchMixMessageSender = tokio::sync::mpsc::Sender<Vec<MixPacket>>;
@@ -15,6 +22,25 @@ pub mod transceiver;
 pub const MIX_MESSAGE_RECEIVER_BUFFER_SIZE: usize = 32;
 const MAX_FAILURE_COUNT: usize = 100;
 
+/// Waits for the next protocol statistics exchange if it's enabled at all.
+async fn next_protocol_stats_exchange(
+    protocol_stats: Option<&mut (ProtocolStatsTracker, IntervalStream)>,
+) -> Option<()> {
+    match protocol_stats {
+        Some((_, interval)) => interval.next().await.map(|_| ()),
+        None => None,
+    }
+}
+
+async fn next_gateway_probe(
+    probes: Option<&mut GatewayProbeReceiver>,
+) -> Option<GatewayProbeResponder> {
+    match probes {
+        Some(probes) => probes.next().await,
+        None => None,
+    }
+}
+
 // that's also disgusting.
 pub struct Empty;
 
@@ -26,6 +52,14 @@ pub struct MixTrafficController {
     // TODO: this is temporary work-around.
     // in long run `gateway_client` will be moved away from `MixTrafficController` anyway.
     consecutive_gateway_failure_count: usize,
+
+    network_changes: Option<NetworkChangeListener>,
+
+    // if enabled, the protocol statistics are periodically exchanged with the gateway
+    protocol_stats: Option<(ProtocolStatsTracker, IntervalStream)>,
+
+    // requests for checking the gateway responsiveness issued by the client diagnostics
+    gateway_probes: Option<GatewayProbeReceiver>,
 }
 
 impl MixTrafficController {
@@ -40,6 +74,9 @@ impl MixTrafficController {
                 gateway_transceiver: Box::new(gateway_transceiver),
                 mix_rx: message_receiver,
                 consecutive_gateway_failure_count: 0,
+                network_changes: None,
+                protocol_stats: None,
+                gateway_probes: None,
             },
             message_sender,
         )
@@ -55,15 +92,122 @@ impl MixTrafficController {
                 gateway_transceiver,
                 mix_rx: message_receiver,
                 consecutive_gateway_failure_count: 0,
+                network_changes: None,
+                protocol_stats: None,
+                gateway_probes: None,
             },
             message_sender,
         )
     }
 
+    #[must_use]
+    pub(crate) fn with_network_change_listener(mut self, listener: NetworkChangeListener) -> Self {
+        self.network_changes = Some(listener);
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_protocol_stats(
+        mut self,
+        protocol_stats: Option<ProtocolStatsTracker>,
+    ) -> Self {
+        self.protocol_stats = protocol_stats.map(|tracker| {
+            let interval = new_interval_stream(tracker.config().exchange_interval);
+            (tracker, interval)
+        });
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_gateway_probes(mut self, probes: GatewayProbeReceiver) -> Self {
+        self.gateway_probes = Some(probes);
+        self
+    }
+
+    async fn on_gateway_probe(&mut self, responder: GatewayProbeResponder) {
+        let result = self
+            .gateway_transceiver
+            .ping()
+            .await
+            .map_err(|err| err.to_string());
+        if let Err(err) = &result {
+            warn!("the gateway has failed to respond to the diagnostics probe: {err}");
+        }
+
+        // the diagnostics might have already given up on waiting
+        let _ = responder.send(result);
+    }
+
+    async fn exchange_protocol_stats(&mut self) {
+        let Some((tracker, _)) = self.protocol_stats.as_ref() else {
+            return;
+        };
+        let local_stats = tracker.local_snapshot();
+
+        match self
+            .gateway_transceiver
+            .exchange_protocol_stats(local_stats)
+            .await
+        {
+            Ok(Some(gateway_stats)) => {
+                trace!("received protocol statistics from the gateway: {gateway_stats:?}");
+                tracker.on_gateway_stats(gateway_stats);
+            }
+            Ok(None) => {
+                debug!("the gateway connection doesn't support protocol statistics exchange");
+                self.protocol_stats = None;
+            }
+            Err(err) => {
+                // most likely the gateway is simply too old to understand the request
+                warn!("failed to exchange protocol statistics with the gateway: {err}. no further exchanges are going to be attempted");
+                self.protocol_stats = None;
+            }
+        }
+    }
+
+    async fn on_network_change(
+        &mut self,
+        change: NetworkChange,
+        shutdown: &mut nym_task::TaskClient,
+    ) {
+        match change {
+            NetworkChange::Lost => {
+                warn!("the local network connectivity has been lost");
+                shutdown.send_status_msg(Box::new(ClientCoreStatusMessage::NetworkLost));
+            }
+            NetworkChange::Available => {
+                info!("the local network has changed - re-establishing the gateway connection");
+                shutdown.send_status_msg(Box::new(ClientCoreStatusMessage::NetworkChanged));
+                match self.gateway_transceiver.reconnect().await {
+                    Ok(_) => {
+                        self.consecutive_gateway_failure_count = 0;
+                        shutdown
+                            .send_status_msg(Box::new(ClientCoreStatusMessage::GatewayReconnected));
+                    }
+                    Err(err) => {
+                        error!(
+                            "failed to reconnect to the gateway after the network change: {err}"
+                        );
+                        shutdown.send_status_msg(Box::new(
+                            ClientCoreStatusMessage::GatewayReconnectionFailed,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
     async fn on_messages(&mut self, mut mix_packets: Vec<MixPacket>) {
         debug_assert!(!mix_packets.is_empty());
 
-        let result = if mix_packets.len() == 1 {
+        #[cfg(feature = "pcap")]
+        for mix_packet in &mix_packets {
+            nym_pcap::record_packet(nym_pcap::EventKind::Sent, mix_packet.packet());
+        }
+
+        let packets = mix_packets.len();
+        let started = get_time_now();
+        let result = if packets == 1 {
             let mix_packet = mix_packets.pop().unwrap();
             self.gateway_transceiver.send_mix_packet(mix_packet).await
         } else {
@@ -72,9 +216,15 @@ impl MixTrafficController {
                 .await
         };
 
+        observe!(
+            "mix_traffic_send_duration_seconds",
+            get_time_now().duration_since(started).as_secs_f64()
+        );
+
         match result {
             Err(err) => {
                 error!("Failed to send sphinx packet(s) to the gateway: {err}");
+                inc!("mix_traffic_send_failures");
                 self.consecutive_gateway_failure_count += 1;
                 if self.consecutive_gateway_failure_count == MAX_FAILURE_COUNT {
                     // todo: in the future this should initiate a 'graceful' shutdown or try
@@ -84,6 +234,8 @@ impl MixTrafficController {
             }
             Ok(_) => {
                 trace!("We *might* have managed to forward sphinx packet(s) to the gateway!");
+                inc!("mix_traffic_batches_sent");
+                inc_by!("mix_traffic_packets_sent", packets);
                 self.consecutive_gateway_failure_count = 0;
             }
         }
@@ -104,6 +256,15 @@ impl MixTrafficController {
                             break;
                         }
                     },
+                    Some(change) = next_network_change(self.network_changes.as_mut()) => {
+                        self.on_network_change(change, &mut shutdown).await;
+                    },
+                    Some(_) = next_protocol_stats_exchange(self.protocol_stats.as_mut()) => {
+                        self.exchange_protocol_stats().await;
+                    },
+                    Some(responder) = next_gateway_probe(self.gateway_probes.as_mut()) => {
+                        self.on_gateway_probe(responder).await;
+                    },
                     _ = shutdown.recv_with_delay() => {
                         log::trace!("MixTrafficController: Received shutdown");
                         break;
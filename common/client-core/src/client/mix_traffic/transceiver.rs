@@ -5,8 +5,9 @@ use async_trait::async_trait;
 use log::{debug, error};
 use nym_credential_storage::storage::Storage as CredentialStorage;
 use nym_crypto::asymmetric::identity;
-use nym_gateway_client::GatewayClient;
+use nym_gateway_client::{GatewayClient, GatewaySessionStats};
 pub use nym_gateway_client::{GatewayPacketRouter, PacketRouter};
+use nym_gateway_requests::ProtocolStats;
 use nym_sphinx::forwarding::packet::MixPacket;
 use nym_validator_client::nyxd::contract_traits::DkgQueryClient;
 use std::fmt::Debug;
@@ -29,6 +30,11 @@ fn erase_err<E: std::error::Error + Send + Sync + 'static>(err: E) -> ErasedGate
 pub trait GatewayTransceiver: GatewaySender + GatewayReceiver {
     fn gateway_identity(&self) -> identity::PublicKey;
     fn ws_fd(&self) -> Option<RawFd>;
+
+    /// Statistics of the session with the gateway, if the transceiver keeps track of them.
+    fn session_stats(&self) -> Option<GatewaySessionStats> {
+        None
+    }
 }
 
 /// This trait defines the functionality of sending `MixPacket` into the mixnet,
@@ -48,6 +54,27 @@ pub trait GatewaySender {
         }
         Ok(())
     }
+
+    /// Re-establishes the underlying connection, for example after the local network has changed.
+    async fn reconnect(&mut self) -> Result<(), ErasedGatewayError> {
+        debug!("no-op gateway reconnection");
+        Ok(())
+    }
+
+    /// Shares our protocol statistics with the gateway and returns the ones it reported back,
+    /// or `None` if the gateway doesn't support such exchange.
+    async fn exchange_protocol_stats(
+        &mut self,
+        _stats: ProtocolStats,
+    ) -> Result<Option<ProtocolStats>, ErasedGatewayError> {
+        Ok(None)
+    }
+
+    /// Performs a request-response round trip with the gateway in order to check
+    /// whether it's still responsive. Returns `false` if such check is not supported.
+    async fn ping(&mut self) -> Result<bool, ErasedGatewayError> {
+        Ok(false)
+    }
 }
 
 /// this trait defines the functionality of being able to correctly route
@@ -73,6 +100,9 @@ impl<G: GatewayTransceiver + ?Sized + Send> GatewayTransceiver for Box<G> {
     fn ws_fd(&self) -> Option<RawFd> {
         (**self).ws_fd()
     }
+    fn session_stats(&self) -> Option<GatewaySessionStats> {
+        (**self).session_stats()
+    }
 }
 
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
@@ -90,6 +120,24 @@ impl<G: GatewaySender + ?Sized + Send> GatewaySender for Box<G> {
     ) -> Result<(), ErasedGatewayError> {
         (**self).batch_send_mix_packets(packets).await
     }
+
+    #[inline]
+    async fn reconnect(&mut self) -> Result<(), ErasedGatewayError> {
+        (**self).reconnect().await
+    }
+
+    #[inline]
+    async fn exchange_protocol_stats(
+        &mut self,
+        stats: ProtocolStats,
+    ) -> Result<Option<ProtocolStats>, ErasedGatewayError> {
+        (**self).exchange_protocol_stats(stats).await
+    }
+
+    #[inline]
+    async fn ping(&mut self) -> Result<bool, ErasedGatewayError> {
+        (**self).ping().await
+    }
 }
 
 impl<G: GatewayReceiver + ?Sized> GatewayReceiver for Box<G> {
@@ -123,6 +171,9 @@ where
     fn ws_fd(&self) -> Option<RawFd> {
         self.gateway_client.ws_fd()
     }
+    fn session_stats(&self) -> Option<GatewaySessionStats> {
+        Some(self.gateway_client.session_stats())
+    }
 }
 
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
@@ -149,6 +200,33 @@ where
             .await
             .map_err(erase_err)
     }
+
+    async fn reconnect(&mut self) -> Result<(), ErasedGatewayError> {
+        self.gateway_client
+            .reconnect_after_network_change()
+            .await
+            .map_err(erase_err)
+    }
+
+    async fn exchange_protocol_stats(
+        &mut self,
+        stats: ProtocolStats,
+    ) -> Result<Option<ProtocolStats>, ErasedGatewayError> {
+        self.gateway_client
+            .exchange_protocol_stats(stats)
+            .await
+            .map(Some)
+            .map_err(erase_err)
+    }
+
+    async fn ping(&mut self) -> Result<bool, ErasedGatewayError> {
+        // any request with a response would do, so pick the cheapest one for the gateway
+        self.gateway_client
+            .get_gateway_protocol()
+            .await
+            .map(|_| true)
+            .map_err(erase_err)
+    }
 }
 
 impl<C, St> GatewayReceiver for RemoteGateway<C, St> {}
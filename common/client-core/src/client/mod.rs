@@ -2,14 +2,31 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod base_client;
+pub mod control;
+pub(crate) mod correspondents;
 pub mod cover_traffic_stream;
+pub mod delivery;
+pub mod diagnostics;
+pub mod drain;
+pub mod events;
+pub(crate) mod gateway_bandwidth;
+pub mod gateway_session;
 pub(crate) mod helpers;
 pub mod inbound_messages;
+pub mod inbox;
 pub mod key_manager;
 pub mod mix_traffic;
+pub mod outbox;
 pub(crate) mod packet_statistics_control;
+pub(crate) mod protocol_stats;
 pub mod real_messages_control;
+pub(crate) mod real_traffic_rate;
 pub mod received_buffer;
 pub mod replies;
+pub(crate) mod rng;
+pub mod roaming;
+pub mod self_test;
+pub mod streams;
 pub mod topology_control;
+pub mod traffic_scheduler;
 pub(crate) mod transmission_buffer;
@@ -0,0 +1,394 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::inbound_messages::{InputMessage, InputMessageReceiver};
+use crate::client::outbox::encryption::OutboxEncryptionKey;
+use crate::client::outbox::{
+    decode_input_message, encode_input_message, is_journaled, OutboxMessageId, OutboxStorage,
+};
+use crate::spawn_future;
+use futures::channel::mpsc;
+use futures::StreamExt;
+use log::*;
+use nym_sphinx::acknowledgements::AckKey;
+use nym_task::TaskClient;
+
+pub(crate) type JournaledInputMessageSender =
+    tokio::sync::mpsc::Sender<(InputMessage, Option<OutboxMessageId>)>;
+pub(crate) type JournaledInputMessageReceiver =
+    tokio::sync::mpsc::Receiver<(InputMessage, Option<OutboxMessageId>)>;
+
+pub(crate) type OutboxAckSender = mpsc::UnboundedSender<OutboxMessageId>;
+pub(crate) type OutboxAckReceiver = mpsc::UnboundedReceiver<OutboxMessageId>;
+
+/// Source of the input messages for the real traffic controller.
+pub(crate) enum InputMessageSource {
+    /// Messages coming straight from the client input.
+    Direct(InputMessageReceiver),
+
+    /// Messages that went through the `OutboxController` and have to be acknowledged
+    /// once they've been fully processed.
+    Journaled {
+        receiver: JournaledInputMessageReceiver,
+        ack_sender: OutboxAckSender,
+    },
+}
+
+impl From<InputMessageReceiver> for InputMessageSource {
+    fn from(receiver: InputMessageReceiver) -> Self {
+        InputMessageSource::Direct(receiver)
+    }
+}
+
+impl InputMessageSource {
+    pub(crate) async fn recv(&mut self) -> Option<(InputMessage, Option<OutboxMessageId>)> {
+        match self {
+            InputMessageSource::Direct(receiver) => receiver.recv().await.map(|msg| (msg, None)),
+            InputMessageSource::Journaled { receiver, .. } => receiver.recv().await,
+        }
+    }
+
+    /// Notify the outbox the message has been fully processed and thus no longer has to be kept around.
+    pub(crate) fn ack(&self, id: OutboxMessageId) {
+        if let InputMessageSource::Journaled { ack_sender, .. } = self {
+            if ack_sender.unbounded_send(id).is_err() {
+                warn!(
+                    "failed to acknowledge outbox message {id}: the outbox controller has stopped"
+                );
+            }
+        }
+    }
+}
+
+/// Sits between the client input and the real traffic controller and journals every accepted
+/// message in the outbox so that it could be replayed if the client got stopped before
+/// managing to process it.
+pub(crate) struct OutboxController<S> {
+    outbox: S,
+    encryption_key: OutboxEncryptionKey,
+    input_receiver: InputMessageReceiver,
+    forward_sender: JournaledInputMessageSender,
+    ack_receiver: OutboxAckReceiver,
+    next_id: OutboxMessageId,
+}
+
+impl<S> OutboxController<S>
+where
+    S: OutboxStorage + Send + Sync + 'static,
+{
+    /// Creates the controller alongside the source of the (journaled) messages
+    /// that is to be used by the real traffic controller.
+    pub(crate) fn new(
+        outbox: S,
+        ack_key: &AckKey,
+        input_receiver: InputMessageReceiver,
+    ) -> (Self, InputMessageSource) {
+        let (forward_sender, forward_receiver) = tokio::sync::mpsc::channel(1);
+        let (ack_sender, ack_receiver) = mpsc::unbounded();
+
+        (
+            OutboxController {
+                outbox,
+                encryption_key: OutboxEncryptionKey::derive(ack_key),
+                input_receiver,
+                forward_sender,
+                ack_receiver,
+                next_id: OutboxMessageId::new(0),
+            },
+            InputMessageSource::Journaled {
+                receiver: forward_receiver,
+                ack_sender,
+            },
+        )
+    }
+
+    async fn forward(&mut self, message: InputMessage, id: Option<OutboxMessageId>) -> bool {
+        if self.forward_sender.send((message, id)).await.is_err() {
+            warn!("failed to forward input message: the real traffic controller has stopped");
+            return false;
+        }
+        true
+    }
+
+    // push all messages that were not fully processed before the client got stopped
+    // back through the pipeline. anything not replayed before the shutdown remains in the outbox
+    // until the next run
+    async fn replay_pending(&mut self, shutdown: &mut TaskClient) {
+        let pending = match self.outbox.pending().await {
+            Ok(pending) => pending,
+            Err(err) => {
+                error!("failed to load unprocessed outbox messages: {err}");
+                return;
+            }
+        };
+
+        // make sure the new messages are never going to overwrite the pending ones,
+        // even if we don't get to replay all of them
+        if let Some((last, _)) = pending.last() {
+            if *last >= self.next_id {
+                self.next_id = last.next();
+            }
+        }
+
+        if pending.is_empty() {
+            return;
+        }
+        info!("replaying {} unprocessed outbox messages", pending.len());
+
+        for (id, content) in pending {
+            let Some(message) = self
+                .encryption_key
+                .decrypt(&content)
+                .and_then(|encoded| decode_input_message(&encoded))
+            else {
+                warn!("outbox message {id} is malformed or was journaled with different keys");
+                self.on_ack(id).await;
+                continue;
+            };
+
+            tokio::select! {
+                biased;
+                _ = shutdown.recv() => {
+                    log::trace!("OutboxController: Received shutdown while replaying pending messages");
+                    return;
+                }
+                forwarded = self.forward(message, Some(id)) => {
+                    if !forwarded {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn on_input_message(&mut self, message: InputMessage) {
+        if !is_journaled(&message) {
+            self.forward(message, None).await;
+            return;
+        }
+
+        let id = self.next_id;
+        self.next_id = id.next();
+
+        let Some(content) = encode_input_message(&message)
+            .and_then(|encoded| self.encryption_key.encrypt(&encoded))
+        else {
+            error!("failed to encode input message {id} for the outbox");
+            self.forward(message, None).await;
+            return;
+        };
+
+        // even if we failed to persist the message, it's still better to attempt to send it
+        // rather than to silently drop it
+        let id = match self.outbox.store(id, &content).await {
+            Ok(_) => Some(id),
+            Err(err) => {
+                error!("failed to persist input message {id} in the outbox: {err}");
+                None
+            }
+        };
+        self.forward(message, id).await;
+    }
+
+    async fn on_ack(&mut self, id: OutboxMessageId) {
+        trace!("removing processed message {id} from the outbox");
+        if let Err(err) = self.outbox.remove(id).await {
+            error!("failed to remove processed message {id} from the outbox: {err}")
+        }
+    }
+
+    pub(crate) async fn run_with_shutdown(&mut self, mut shutdown: TaskClient) {
+        debug!("Started OutboxController with graceful shutdown support");
+
+        self.replay_pending(&mut shutdown).await;
+
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = shutdown.recv_with_delay() => {
+                    log::trace!("OutboxController: Received shutdown");
+                }
+                // process acks first so that we'd remove as much as possible before shutting down
+                Some(id) = self.ack_receiver.next() => {
+                    self.on_ack(id).await;
+                }
+                input_msg = self.input_receiver.recv() => match input_msg {
+                    Some(input_msg) => self.on_input_message(input_msg).await,
+                    None => {
+                        log::trace!("OutboxController: Stopping since channel closed");
+                        break;
+                    }
+                },
+            }
+        }
+        shutdown.recv_timeout().await;
+        log::debug!("OutboxController: Exiting");
+    }
+
+    pub(crate) fn start_with_shutdown(mut self, shutdown: TaskClient) {
+        spawn_future(async move { self.run_with_shutdown(shutdown).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use nym_sphinx::addressing::clients::Recipient;
+    use nym_task::connections::TransmissionLane;
+    use nym_task::TaskManager;
+    use rand::rngs::OsRng;
+    use std::collections::BTreeMap;
+    use std::convert::Infallible;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default, Clone)]
+    struct InMemoryOutbox {
+        messages: Arc<Mutex<BTreeMap<OutboxMessageId, Vec<u8>>>>,
+    }
+
+    impl InMemoryOutbox {
+        fn stored(&self) -> Vec<(OutboxMessageId, Vec<u8>)> {
+            let messages = self.messages.lock().unwrap();
+            messages.iter().map(|(id, m)| (*id, m.clone())).collect()
+        }
+    }
+
+    #[async_trait]
+    impl OutboxStorage for InMemoryOutbox {
+        type StorageError = Infallible;
+
+        async fn store(&self, id: OutboxMessageId, content: &[u8]) -> Result<(), Infallible> {
+            self.messages.lock().unwrap().insert(id, content.to_vec());
+            Ok(())
+        }
+
+        async fn remove(&self, id: OutboxMessageId) -> Result<(), Infallible> {
+            self.messages.lock().unwrap().remove(&id);
+            Ok(())
+        }
+
+        async fn pending(&self) -> Result<Vec<(OutboxMessageId, Vec<u8>)>, Infallible> {
+            Ok(self.stored())
+        }
+    }
+
+    fn message(content: &[u8]) -> InputMessage {
+        let recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+        InputMessage::new_regular(recipient, content.to_vec(), TransmissionLane::General, None)
+    }
+
+    fn content(message: &InputMessage) -> &[u8] {
+        match message {
+            InputMessage::Regular { data, .. } => data,
+            _ => panic!("unexpected message"),
+        }
+    }
+
+    fn controller(
+        outbox: &InMemoryOutbox,
+        ack_key: &AckKey,
+    ) -> (
+        OutboxController<InMemoryOutbox>,
+        InputMessageSource,
+        tokio::sync::mpsc::Sender<InputMessage>,
+    ) {
+        let (input_sender, input_receiver) = tokio::sync::mpsc::channel(8);
+        let (controller, source) = OutboxController::new(outbox.clone(), ack_key, input_receiver);
+        (controller, source, input_sender)
+    }
+
+    #[tokio::test]
+    async fn messages_are_journaled_encrypted_until_acknowledged() {
+        let outbox = InMemoryOutbox::default();
+        let ack_key = AckKey::new(&mut OsRng);
+        let (mut controller, mut source, _input) = controller(&outbox, &ack_key);
+
+        controller.on_input_message(message(b"hello world")).await;
+        let (forwarded, id) = source.recv().await.unwrap();
+        let id = id.unwrap();
+        assert_eq!(content(&forwarded), b"hello world");
+
+        let stored = outbox.stored();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].0, id);
+        assert!(!stored[0].1.windows(11).any(|w| w == b"hello world"));
+
+        source.ack(id);
+        let acked = controller.ack_receiver.next().await.unwrap();
+        controller.on_ack(acked).await;
+        assert!(outbox.stored().is_empty());
+    }
+
+    #[tokio::test]
+    async fn pending_messages_are_replayed_before_new_ones() {
+        let outbox = InMemoryOutbox::default();
+        let ack_key = AckKey::new(&mut OsRng);
+
+        // journal two messages that never got processed
+        let (mut previous, mut previous_source, _input) = controller(&outbox, &ack_key);
+        previous.on_input_message(message(b"first")).await;
+        previous_source.recv().await.unwrap();
+        previous.on_input_message(message(b"second")).await;
+        previous_source.recv().await.unwrap();
+        drop(previous);
+
+        // and an entry that can't be recovered
+        outbox
+            .store(OutboxMessageId::new(10), b"garbage")
+            .await
+            .unwrap();
+
+        let task_manager = TaskManager::default();
+        let (mut controller, mut source, input) = controller(&outbox, &ack_key);
+        let mut shutdown = task_manager.subscribe();
+        let replay = tokio::spawn(async move {
+            controller.replay_pending(&mut shutdown).await;
+            controller
+        });
+
+        let (first, first_id) = source.recv().await.unwrap();
+        let (second, second_id) = source.recv().await.unwrap();
+        assert_eq!(content(&first), b"first");
+        assert_eq!(content(&second), b"second");
+        assert_eq!(first_id, Some(OutboxMessageId::new(0)));
+        assert_eq!(second_id, Some(OutboxMessageId::new(1)));
+
+        // the garbage got dropped and the new messages don't overwrite any of the pending ones
+        let mut controller = replay.await.unwrap();
+        assert_eq!(outbox.stored().len(), 2);
+        controller.on_input_message(message(b"third")).await;
+        drop(input);
+        let (_, third_id) = source.recv().await.unwrap();
+        assert_eq!(third_id, Some(OutboxMessageId::new(11)));
+    }
+
+    #[tokio::test]
+    async fn replay_stops_on_shutdown() {
+        let outbox = InMemoryOutbox::default();
+        let ack_key = AckKey::new(&mut OsRng);
+
+        let (mut previous, mut previous_source, _input) = controller(&outbox, &ack_key);
+        for i in 0..5u8 {
+            previous.on_input_message(message(&[i])).await;
+            previous_source.recv().await.unwrap();
+        }
+        drop(previous);
+
+        let task_manager = TaskManager::default();
+        let mut shutdown = task_manager.subscribe();
+        let (mut controller, _source, _input) = controller(&outbox, &ack_key);
+
+        // nobody is receiving the replayed messages, so without the shutdown it'd block forever
+        task_manager.signal_shutdown().unwrap();
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            controller.replay_pending(&mut shutdown),
+        )
+        .await
+        .unwrap();
+
+        // and all the messages are kept for the next run
+        assert_eq!(outbox.stored().len(), 5);
+    }
+}
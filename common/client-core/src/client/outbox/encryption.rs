@@ -0,0 +1,64 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! At-rest encryption of the journaled messages.
+//!
+//! The key is derived from the client's ack key, as unlike the identity key it's always held
+//! locally and unlike the encryption keypair it's never rotated, so messages journaled
+//! during the previous run remain readable.
+
+use nym_crypto::symmetric::at_rest::AtRestKey;
+use nym_sphinx::acknowledgements::AckKey;
+
+const OUTBOX_KEY_DERIVATION_SALT: &[u8] = b"NYM_CLIENT_OUTBOX_ENCRYPTION_V1";
+
+/// Key used for encrypting the messages journaled in the outbox.
+pub(crate) struct OutboxEncryptionKey {
+    key: AtRestKey,
+}
+
+impl OutboxEncryptionKey {
+    pub(crate) fn derive(ack_key: &AckKey) -> Self {
+        OutboxEncryptionKey {
+            key: AtRestKey::derive(OUTBOX_KEY_DERIVATION_SALT, ack_key.as_bytes(), None),
+        }
+    }
+
+    pub(crate) fn encrypt(&self, content: &[u8]) -> Option<Vec<u8>> {
+        self.key.encrypt(content).ok()
+    }
+
+    pub(crate) fn decrypt(&self, stored: &[u8]) -> Option<Vec<u8>> {
+        self.key.decrypt(stored).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn encryption_roundtrip() {
+        let key = OutboxEncryptionKey::derive(&AckKey::new(&mut OsRng));
+        let message = b"hello world".to_vec();
+
+        let stored = key.encrypt(&message).unwrap();
+        assert!(!stored.windows(message.len()).any(|w| w == message));
+        assert_eq!(key.decrypt(&stored).unwrap(), message);
+    }
+
+    #[test]
+    fn foreign_and_tampered_messages_are_rejected() {
+        let key = OutboxEncryptionKey::derive(&AckKey::new(&mut OsRng));
+        let other = OutboxEncryptionKey::derive(&AckKey::new(&mut OsRng));
+
+        let mut stored = key.encrypt(b"hello world").unwrap();
+        assert!(other.decrypt(&stored).is_none());
+
+        let last = stored.len() - 1;
+        stored[last] ^= 1;
+        assert!(key.decrypt(&stored).is_none());
+        assert!(key.decrypt(&stored[..4]).is_none());
+    }
+}
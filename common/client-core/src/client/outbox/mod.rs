@@ -0,0 +1,496 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optional persistent journal of outbound messages.
+//!
+//! When enabled, every [`InputMessage`] accepted by the client is durably written to the outbox
+//! before it's handed to the real traffic controller and it's only removed once the controller
+//! has fully processed it, i.e. it has been split, encrypted and queued for sending
+//! (or, in the case of replies, handed to the reply controller).
+//! Any messages left in the outbox (say, because the process died while they were still queued)
+//! are replayed upon the next startup.
+//!
+//! The messages are encrypted before they're handed to the storage backend, with a key derived
+//! from the client's ack key, so the backends only ever deal with opaque blobs.
+//!
+//! Note that [`InputMessage::Premade`] messages are never journaled, as no delivery guarantees
+//! are provided for them to begin with.
+//! The message priority isn't journaled either. The replayed messages are always sent with the normal
+//! priority, as by then there's no interactive session left to benefit from the higher one.
+//! Similarly, the delivery tokens are dropped, since nobody is listening for the statuses
+//! of the messages sent during the previous run, and so are the latency budgets,
+//! as the replayed messages are late anyway.
+
+use crate::client::inbound_messages::InputMessage;
+use async_trait::async_trait;
+use nym_sphinx::addressing::clients::{Recipient, RecipientBytes};
+use nym_sphinx::anonymous_replies::requests::{AnonymousSenderTag, SENDER_TAG_SIZE};
+use nym_sphinx::params::PacketType;
+use nym_task::connections::{MessagePriority, TransmissionLane};
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+pub(crate) mod controller;
+mod encryption;
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "fs-outbox-storage"))]
+mod sqlite_backend;
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "fs-outbox-storage"))]
+pub use sqlite_backend::{SqliteOutbox, SqliteOutboxError};
+
+const ENCODING_VERSION: u8 = 1;
+
+const REGULAR_MESSAGE: u8 = 1;
+const ANONYMOUS_MESSAGE: u8 = 2;
+const REPLY_MESSAGE: u8 = 3;
+const WRAPPED_MESSAGE: u8 = 4;
+const MULTI_RECIPIENT_MESSAGE: u8 = 5;
+
+/// Identifier of a journaled message. Messages are replayed in the increasing order of their ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OutboxMessageId(u64);
+
+impl OutboxMessageId {
+    pub const fn new(id: u64) -> Self {
+        OutboxMessageId(id)
+    }
+
+    pub const fn as_u64(&self) -> u64 {
+        self.0
+    }
+
+    pub(crate) fn next(&self) -> Self {
+        OutboxMessageId(self.0.wrapping_add(1))
+    }
+}
+
+impl Display for OutboxMessageId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn encode_lane(lane: &TransmissionLane, buf: &mut Vec<u8>) {
+    match lane {
+        TransmissionLane::General => buf.push(0),
+        TransmissionLane::ReplySurbRequest => buf.push(1),
+        TransmissionLane::AdditionalReplySurbs => buf.push(2),
+        TransmissionLane::Retransmission => buf.push(3),
+        TransmissionLane::ConnectionId(id) => {
+            buf.push(4);
+            buf.extend_from_slice(&id.to_be_bytes())
+        }
+    }
+}
+
+fn encode_mix_hops(mix_hops: Option<u8>, buf: &mut Vec<u8>) {
+    match mix_hops {
+        Some(hops) => buf.extend_from_slice(&[1, hops]),
+        None => buf.push(0),
+    }
+}
+
+fn encode_message_content(message: &InputMessage, buf: &mut Vec<u8>) -> Option<()> {
+    match message {
+        InputMessage::Premade { .. } => return None,
+        InputMessage::Regular {
+            recipient,
+            data,
+            lane,
+            mix_hops,
+            ..
+        } => {
+            buf.push(REGULAR_MESSAGE);
+            buf.extend_from_slice(&recipient.to_bytes());
+            encode_lane(lane, buf);
+            encode_mix_hops(*mix_hops, buf);
+            buf.extend_from_slice(data);
+        }
+        InputMessage::Anonymous {
+            recipient,
+            data,
+            reply_surbs,
+            lane,
+            mix_hops,
+            ..
+        } => {
+            buf.push(ANONYMOUS_MESSAGE);
+            buf.extend_from_slice(&recipient.to_bytes());
+            buf.extend_from_slice(&reply_surbs.to_be_bytes());
+            encode_lane(lane, buf);
+            encode_mix_hops(*mix_hops, buf);
+            buf.extend_from_slice(data);
+        }
+        InputMessage::MultiRecipient {
+            recipients,
+            data,
+            reply_surbs,
+            lane,
+            mix_hops,
+            ..
+        } => {
+            buf.push(MULTI_RECIPIENT_MESSAGE);
+            buf.extend_from_slice(&(recipients.len() as u32).to_be_bytes());
+            for recipient in recipients {
+                buf.extend_from_slice(&recipient.to_bytes());
+            }
+            match reply_surbs {
+                Some(reply_surbs) => {
+                    buf.push(1);
+                    buf.extend_from_slice(&reply_surbs.to_be_bytes())
+                }
+                None => buf.push(0),
+            }
+            encode_lane(lane, buf);
+            encode_mix_hops(*mix_hops, buf);
+            buf.extend_from_slice(data);
+        }
+        InputMessage::Reply {
+            recipient_tag,
+            data,
+            lane,
+            ..
+        } => {
+            buf.push(REPLY_MESSAGE);
+            buf.extend_from_slice(&recipient_tag.to_bytes());
+            encode_lane(lane, buf);
+            buf.extend_from_slice(data);
+        }
+        InputMessage::MessageWrapper {
+            message,
+            packet_type,
+        } => {
+            // wrappers can't be nested
+            if matches!(**message, InputMessage::MessageWrapper { .. }) {
+                return None;
+            }
+            buf.push(WRAPPED_MESSAGE);
+            buf.push(*packet_type as u8);
+            encode_message_content(message, buf)?;
+        }
+    }
+    Some(())
+}
+
+/// Determines whether the provided message is meant to be stored in the outbox.
+pub(crate) fn is_journaled(message: &InputMessage) -> bool {
+    match message {
+        InputMessage::Premade { .. } => false,
+        InputMessage::MessageWrapper { message, .. } => is_journaled(message),
+        _ => true,
+    }
+}
+
+/// Serialises the provided message for the purposes of storing it in the outbox.
+/// Returns `None` if the message is not meant to be journaled, i.e. it's a premade message.
+pub fn encode_input_message(message: &InputMessage) -> Option<Vec<u8>> {
+    let mut buf = vec![ENCODING_VERSION];
+    encode_message_content(message, &mut buf)?;
+    Some(buf)
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < n {
+            return None;
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn take_u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn take_recipient(&mut self) -> Option<Recipient> {
+        let bytes: RecipientBytes = self.take_array()?;
+        Recipient::try_from_bytes(bytes).ok()
+    }
+
+    fn take_lane(&mut self) -> Option<TransmissionLane> {
+        match self.take_u8()? {
+            0 => Some(TransmissionLane::General),
+            1 => Some(TransmissionLane::ReplySurbRequest),
+            2 => Some(TransmissionLane::AdditionalReplySurbs),
+            3 => Some(TransmissionLane::Retransmission),
+            4 => Some(TransmissionLane::ConnectionId(u64::from_be_bytes(
+                self.take_array()?,
+            ))),
+            _ => None,
+        }
+    }
+
+    fn take_mix_hops(&mut self) -> Option<Option<u8>> {
+        match self.take_u8()? {
+            0 => Some(None),
+            1 => Some(Some(self.take_u8()?)),
+            _ => None,
+        }
+    }
+
+    fn take_recipients(&mut self) -> Option<Vec<Recipient>> {
+        let count = u32::from_be_bytes(self.take_array()?);
+        // don't trust the declared count for the allocation, the encoding might be malformed
+        let mut recipients = Vec::new();
+        for _ in 0..count {
+            recipients.push(self.take_recipient()?)
+        }
+        Some(recipients)
+    }
+
+    fn take_reply_surbs(&mut self) -> Option<Option<u32>> {
+        match self.take_u8()? {
+            0 => Some(None),
+            1 => Some(Some(u32::from_be_bytes(self.take_array()?))),
+            _ => None,
+        }
+    }
+
+    fn remaining(self) -> Vec<u8> {
+        self.bytes.to_vec()
+    }
+
+    fn decode_message(mut self) -> Option<InputMessage> {
+        match self.take_u8()? {
+            REGULAR_MESSAGE => Some(InputMessage::Regular {
+                recipient: self.take_recipient()?,
+                lane: self.take_lane()?,
+                priority: MessagePriority::Normal,
+                delivery_token: None,
+                latency_budget: None,
+                mix_hops: self.take_mix_hops()?,
+                data: self.remaining(),
+            }),
+            ANONYMOUS_MESSAGE => Some(InputMessage::Anonymous {
+                recipient: self.take_recipient()?,
+                reply_surbs: u32::from_be_bytes(self.take_array()?),
+                lane: self.take_lane()?,
+                priority: MessagePriority::Normal,
+                delivery_token: None,
+                latency_budget: None,
+                mix_hops: self.take_mix_hops()?,
+                data: self.remaining(),
+            }),
+            MULTI_RECIPIENT_MESSAGE => Some(InputMessage::MultiRecipient {
+                recipients: self.take_recipients()?,
+                reply_surbs: self.take_reply_surbs()?,
+                lane: self.take_lane()?,
+                priority: MessagePriority::Normal,
+                delivery_token: None,
+                latency_budget: None,
+                mix_hops: self.take_mix_hops()?,
+                data: self.remaining(),
+            }),
+            REPLY_MESSAGE => Some(InputMessage::Reply {
+                recipient_tag: AnonymousSenderTag::from_bytes(
+                    self.take_array::<SENDER_TAG_SIZE>()?,
+                ),
+                lane: self.take_lane()?,
+                priority: MessagePriority::Normal,
+                delivery_token: None,
+                data: self.remaining(),
+            }),
+            WRAPPED_MESSAGE => {
+                let packet_type = PacketType::try_from(self.take_u8()?).ok()?;
+                let message = self.decode_message()?;
+                if matches!(message, InputMessage::MessageWrapper { .. }) {
+                    return None;
+                }
+                Some(InputMessage::new_wrapper(message, packet_type))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Recovers message serialised with [`encode_input_message`].
+pub fn decode_input_message(bytes: &[u8]) -> Option<InputMessage> {
+    let (&version, content) = bytes.split_first()?;
+    if version != ENCODING_VERSION {
+        return None;
+    }
+    Decoder { bytes: content }.decode_message()
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait OutboxStorage {
+    type StorageError: Error;
+
+    /// Specifies whether the outbound messages should be journaled at all.
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    /// Durably store the provided (encrypted) message.
+    async fn store(&self, id: OutboxMessageId, content: &[u8]) -> Result<(), Self::StorageError>;
+
+    /// Remove the fully processed message from the store.
+    async fn remove(&self, id: OutboxMessageId) -> Result<(), Self::StorageError>;
+
+    /// Returns all (encrypted) messages that have not yet been fully processed,
+    /// ordered by their ids.
+    async fn pending(&self) -> Result<Vec<(OutboxMessageId, Vec<u8>)>, Self::StorageError>;
+}
+
+/// Outbox that does not persist anything. Input messages are just handed to the client.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Disabled;
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl OutboxStorage for Disabled {
+    type StorageError = Infallible;
+
+    fn is_enabled(&self) -> bool {
+        false
+    }
+
+    async fn store(&self, _: OutboxMessageId, _: &[u8]) -> Result<(), Self::StorageError> {
+        Ok(())
+    }
+
+    async fn remove(&self, _: OutboxMessageId) -> Result<(), Self::StorageError> {
+        Ok(())
+    }
+
+    async fn pending(&self) -> Result<Vec<(OutboxMessageId, Vec<u8>)>, Self::StorageError> {
+        Ok(Vec::new())
+    }
+}
+
+// an unset outbox behaves exactly as if it was disabled
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T> OutboxStorage for Option<T>
+where
+    T: OutboxStorage + Send + Sync,
+{
+    type StorageError = T::StorageError;
+
+    fn is_enabled(&self) -> bool {
+        self.as_ref()
+            .map(|outbox| outbox.is_enabled())
+            .unwrap_or_default()
+    }
+
+    async fn store(&self, id: OutboxMessageId, content: &[u8]) -> Result<(), Self::StorageError> {
+        match self {
+            Some(outbox) => outbox.store(id, content).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn remove(&self, id: OutboxMessageId) -> Result<(), Self::StorageError> {
+        match self {
+            Some(outbox) => outbox.remove(id).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn pending(&self) -> Result<Vec<(OutboxMessageId, Vec<u8>)>, Self::StorageError> {
+        match self {
+            Some(outbox) => outbox.pending().await,
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipient() -> Recipient {
+        Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap()
+    }
+
+    #[test]
+    fn input_message_encoding_roundtrip() {
+        let messages = vec![
+            InputMessage::new_regular(
+                recipient(),
+                b"hello".to_vec(),
+                TransmissionLane::General,
+                None,
+            ),
+            InputMessage::new_regular_with_custom_hops(
+                recipient(),
+                Vec::new(),
+                TransmissionLane::ConnectionId(42),
+                Some(PacketType::Outfox),
+                Some(5),
+            ),
+            InputMessage::new_anonymous(
+                recipient(),
+                b"world".to_vec(),
+                10,
+                TransmissionLane::Retransmission,
+                None,
+            ),
+            InputMessage::new_multi_recipient(
+                vec![recipient(), recipient()],
+                b"everyone".to_vec(),
+                Some(5),
+                TransmissionLane::General,
+                Some(PacketType::Outfox),
+            ),
+            InputMessage::new_multi_recipient(
+                vec![recipient()],
+                b"someone".to_vec(),
+                None,
+                TransmissionLane::ConnectionId(1),
+                None,
+            ),
+            InputMessage::new_reply(
+                AnonymousSenderTag::from([42; SENDER_TAG_SIZE]),
+                b"reply".to_vec(),
+                TransmissionLane::ReplySurbRequest,
+                None,
+            ),
+        ];
+
+        for message in messages {
+            let encoded = encode_input_message(&message).unwrap();
+            let decoded = decode_input_message(&encoded).unwrap();
+            assert_eq!(format!("{message:?}"), format!("{decoded:?}"));
+        }
+
+        let premade =
+            InputMessage::new_premade(Vec::new(), TransmissionLane::General, PacketType::Mix);
+        assert!(encode_input_message(&premade).is_none());
+        assert!(decode_input_message(&[]).is_none());
+        assert!(decode_input_message(&[ENCODING_VERSION, REGULAR_MESSAGE, 1, 2]).is_none());
+    }
+
+    #[test]
+    fn truncated_recipient_list_is_rejected() {
+        let message = InputMessage::new_multi_recipient(
+            vec![recipient(), recipient()],
+            b"everyone".to_vec(),
+            None,
+            TransmissionLane::General,
+            None,
+        );
+        let encoded = encode_input_message(&message).unwrap();
+
+        // claim there are way more recipients than there are bytes for
+        let mut inflated = encoded.clone();
+        inflated[2..6].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(decode_input_message(&inflated).is_none());
+
+        // cut the message in the middle of the second recipient
+        let recipient_len = recipient().to_bytes().len();
+        assert!(decode_input_message(&encoded[..6 + recipient_len + 1]).is_none());
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::outbox::{OutboxMessageId, OutboxStorage};
+use async_trait::async_trait;
+use log::{debug, error};
+use sqlx::{ConnectOptions, Row};
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SqliteOutboxError {
+    #[error("failed to create the parent directory of the outbox database at {}: {source}", path.display())]
+    DirectoryCreationFailure {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("failed to connect to the outbox database: {source}")]
+    ConnectionFailure {
+        #[source]
+        source: sqlx::Error,
+    },
+
+    #[error("outbox database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+/// Outbox journaling all messages in a sqlite database.
+#[derive(Debug, Clone)]
+pub struct SqliteOutbox {
+    connection_pool: sqlx::SqlitePool,
+}
+
+impl SqliteOutbox {
+    pub async fn init<P: AsRef<Path>>(database_path: P) -> Result<Self, SqliteOutboxError> {
+        let database_path = database_path.as_ref();
+
+        // ensure the whole directory structure exists
+        if let Some(parent_dir) = database_path.parent() {
+            std::fs::create_dir_all(parent_dir).map_err(|source| {
+                SqliteOutboxError::DirectoryCreationFailure {
+                    path: parent_dir.to_path_buf(),
+                    source,
+                }
+            })?;
+        }
+
+        let mut opts = sqlx::sqlite::SqliteConnectOptions::new()
+            .filename(database_path)
+            .create_if_missing(true);
+
+        opts.disable_statement_logging();
+
+        let connection_pool = sqlx::SqlitePool::connect_with(opts)
+            .await
+            .map_err(|source| {
+                error!("Failed to connect to the outbox database: {source}");
+                SqliteOutboxError::ConnectionFailure { source }
+            })?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS outbox_message (
+                id INTEGER PRIMARY KEY NOT NULL,
+                content BLOB NOT NULL
+            )",
+        )
+        .execute(&connection_pool)
+        .await?;
+
+        debug!("outbox database is ready");
+        Ok(SqliteOutbox { connection_pool })
+    }
+}
+
+#[async_trait]
+impl OutboxStorage for SqliteOutbox {
+    type StorageError = SqliteOutboxError;
+
+    async fn store(&self, id: OutboxMessageId, content: &[u8]) -> Result<(), Self::StorageError> {
+        // sqlite integers are signed, but the conversion is lossless
+        sqlx::query("INSERT OR REPLACE INTO outbox_message(id, content) VALUES (?, ?)")
+            .bind(id.as_u64() as i64)
+            .bind(content)
+            .execute(&self.connection_pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove(&self, id: OutboxMessageId) -> Result<(), Self::StorageError> {
+        sqlx::query("DELETE FROM outbox_message WHERE id = ?")
+            .bind(id.as_u64() as i64)
+            .execute(&self.connection_pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn pending(&self) -> Result<Vec<(OutboxMessageId, Vec<u8>)>, Self::StorageError> {
+        let rows = sqlx::query("SELECT id, content FROM outbox_message ORDER BY id")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let id = OutboxMessageId::new(row.try_get::<i64, _>("id")? as u64);
+                Ok((id, row.try_get("content")?))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn messages_are_persisted_until_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("outbox").join("outbox.sqlite");
+
+        let outbox = SqliteOutbox::init(&path).await.unwrap();
+        outbox
+            .store(OutboxMessageId::new(2), b"second")
+            .await
+            .unwrap();
+        outbox
+            .store(OutboxMessageId::new(1), b"first")
+            .await
+            .unwrap();
+        outbox
+            .store(OutboxMessageId::new(3), b"third")
+            .await
+            .unwrap();
+        outbox.remove(OutboxMessageId::new(3)).await.unwrap();
+        drop(outbox);
+
+        // the content survives reopening the database and is returned in the id order
+        let outbox = SqliteOutbox::init(&path).await.unwrap();
+        assert_eq!(
+            outbox.pending().await.unwrap(),
+            vec![
+                (OutboxMessageId::new(1), b"first".to_vec()),
+                (OutboxMessageId::new(2), b"second".to_vec()),
+            ]
+        );
+
+        // the full u64 range of ids survives the signed sqlite integers
+        let max = OutboxMessageId::new(u64::MAX);
+        outbox.store(max, b"last").await.unwrap();
+        assert!(outbox
+            .pending()
+            .await
+            .unwrap()
+            .contains(&(max, b"last".to_vec())));
+        outbox.remove(max).await.unwrap();
+        assert_eq!(outbox.pending().await.unwrap().len(), 2);
+    }
+}
@@ -18,7 +18,7 @@ use hyper::server::conn::http1;
 #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
 use hyper::service::service_fn;
 #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
-use hyper::{Request, Response};
+use hyper::{Request, Response, StatusCode};
 #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
 use hyper_util::rt::TokioIo;
 #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
@@ -519,7 +519,7 @@ impl PacketStatisticsControl {
                     match TcpListener::bind(addr).await {
                         Ok(l) => {
                             log::info!("###############################");
-                            log::info!("Metrics endpoint is at: {:?}", l.local_addr());
+                            log::info!("Metrics endpoint is at: http://{}/metrics", addr);
                             log::info!("###############################");
                             listener = Some(l);
                             break;
@@ -605,10 +605,16 @@ impl PacketStatisticsControl {
 
 #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
 async fn serve_metrics(
-    _: Request<hyper::body::Incoming>,
+    request: Request<hyper::body::Incoming>,
 ) -> Result<Response<Full<Bytes>>, Infallible> {
     use nym_metrics::metrics;
 
+    if request.uri().path() != "/metrics" {
+        let mut not_found = Response::new(Full::new(Bytes::new()));
+        *not_found.status_mut() = StatusCode::NOT_FOUND;
+        return Ok(not_found);
+    }
+
     Ok(Response::new(Full::new(Bytes::from(metrics!()))))
 }
 
@@ -0,0 +1,147 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Protocol statistics periodically exchanged with the gateway. The client shares (if allowed to)
+//! the fraction of its packets that had to be retransmitted and the depth of its send queue,
+//! whilst the hints received back are used for slowing down the sending rate
+//! whenever the gateway appears to be struggling with our traffic.
+
+use crate::config;
+use log::*;
+use nym_gateway_requests::ProtocolStats;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+
+#[derive(Default)]
+struct TrackerInner {
+    // counted since the previous exchange
+    real_packets_queued: AtomicU64,
+    retransmissions_queued: AtomicU64,
+
+    queue_depth: AtomicU32,
+
+    // set whenever the gateway's hints suggest we should slow down, cleared once acted upon
+    slowdown_requested: AtomicBool,
+}
+
+/// Shared between the `OutQueueControl`, which records the local observations and adjusts the pacing,
+/// and the `MixTrafficController`, which performs the actual exchange.
+#[derive(Clone)]
+pub(crate) struct ProtocolStatsTracker {
+    config: config::ProtocolStats,
+    inner: Arc<TrackerInner>,
+}
+
+impl ProtocolStatsTracker {
+    pub(crate) fn new(config: config::ProtocolStats) -> Self {
+        ProtocolStatsTracker {
+            config,
+            inner: Default::default(),
+        }
+    }
+
+    pub(crate) fn config(&self) -> &config::ProtocolStats {
+        &self.config
+    }
+
+    pub(crate) fn record_real_packet_queued(&self, is_retransmission: bool) {
+        self.inner
+            .real_packets_queued
+            .fetch_add(1, Ordering::Relaxed);
+        if is_retransmission {
+            self.inner
+                .retransmissions_queued
+                .fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn set_queue_depth(&self, queue_depth: usize) {
+        let queue_depth = u32::try_from(queue_depth).unwrap_or(u32::MAX);
+        self.inner.queue_depth.store(queue_depth, Ordering::Relaxed);
+    }
+
+    /// Produces the statistics to be sent to the gateway, only including the fields
+    /// we're allowed to share, and resets the counters for the next interval.
+    pub(crate) fn local_snapshot(&self) -> ProtocolStats {
+        let queued = self.inner.real_packets_queued.swap(0, Ordering::Relaxed);
+        let retransmitted = self.inner.retransmissions_queued.swap(0, Ordering::Relaxed);
+
+        let observed_loss = if self.config.share_observed_loss && queued > 0 {
+            Some(retransmitted as f32 / queued as f32)
+        } else {
+            None
+        };
+        let queue_depth = self
+            .config
+            .share_queue_depth
+            .then(|| self.inner.queue_depth.load(Ordering::Relaxed));
+
+        ProtocolStats {
+            observed_loss,
+            queue_depth,
+        }
+    }
+
+    pub(crate) fn on_gateway_stats(&self, stats: ProtocolStats) {
+        let excessive_loss = stats
+            .observed_loss
+            .is_some_and(|loss| loss >= self.config.loss_slowdown_threshold);
+        let excessive_queue = stats
+            .queue_depth
+            .is_some_and(|depth| depth >= self.config.queue_depth_slowdown_threshold);
+
+        if excessive_loss || excessive_queue {
+            debug!("the gateway hints at congestion ({stats:?}) - slowing down the sending rate");
+            self.inner.slowdown_requested.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Checks whether the gateway has asked us to slow down since the last call.
+    pub(crate) fn take_slowdown_request(&self) -> bool {
+        self.inner.slowdown_requested.swap(false, Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_permitted_fields_are_shared() {
+        let tracker = ProtocolStatsTracker::new(config::ProtocolStats::default());
+        tracker.record_real_packet_queued(false);
+        tracker.record_real_packet_queued(true);
+        tracker.set_queue_depth(42);
+        assert!(tracker.local_snapshot().is_empty());
+
+        let tracker = ProtocolStatsTracker::new(config::ProtocolStats {
+            share_observed_loss: true,
+            share_queue_depth: true,
+            ..Default::default()
+        });
+        tracker.record_real_packet_queued(false);
+        tracker.record_real_packet_queued(true);
+        tracker.set_queue_depth(42);
+
+        let snapshot = tracker.local_snapshot();
+        assert_eq!(snapshot.observed_loss, Some(0.5));
+        assert_eq!(snapshot.queue_depth, Some(42));
+
+        // the loss is only measured over the latest interval
+        assert_eq!(tracker.local_snapshot().observed_loss, None);
+    }
+
+    #[test]
+    fn congestion_hints_request_slowdown_once() {
+        let tracker = ProtocolStatsTracker::new(config::ProtocolStats::default());
+        tracker.on_gateway_stats(ProtocolStats::default());
+        assert!(!tracker.take_slowdown_request());
+
+        tracker.on_gateway_stats(ProtocolStats {
+            observed_loss: Some(0.5),
+            queue_depth: None,
+        });
+        assert!(tracker.take_slowdown_request());
+        assert!(!tracker.take_slowdown_request());
+    }
+}
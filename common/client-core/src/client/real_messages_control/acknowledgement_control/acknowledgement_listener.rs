@@ -7,6 +7,7 @@ use super::action_controller::{AckActionSender, Action};
 use futures::StreamExt;
 use log::*;
 use nym_gateway_client::AcknowledgementReceiver;
+use nym_metrics::inc;
 use nym_sphinx::{
     acknowledgements::{identifier::recover_identifier, AckKey},
     chunking::fragment::{FragmentIdentifier, COVER_FRAG_ID},
@@ -48,6 +49,7 @@ impl AcknowledgementListener {
             Some(Ok(frag_id)) => frag_id,
             _ => {
                 warn!("Received invalid ACK!"); // should we do anything else about that?
+                inc!("invalid_acks_received");
                 return;
             }
         };
@@ -2,13 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::PendingAcknowledgement;
+use crate::client::delivery::{DeliveryReceipts, DeliveryToken, DeliveryTracker};
+use crate::client::drain::DrainState;
+use crate::client::helpers::{get_time_now, Instant};
 use crate::client::real_messages_control::acknowledgement_control::RetransmissionRequestSender;
+use crate::config;
 use futures::channel::mpsc;
 use futures::StreamExt;
 use log::*;
+use nym_metrics::{inc, observe};
 use nym_nonexhaustive_delayqueue::{Expired, NonExhaustiveDelayQueue, QueueKey};
 use nym_sphinx::chunking::fragment::FragmentIdentifier;
 use nym_sphinx::Delay as SphinxDelay;
+use rand::Rng;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -24,6 +30,7 @@ type PendingAckEntry = (Arc<PendingAcknowledgement>, Option<QueueKey>);
 // - received an ack so we want to remove an entry
 // - start a retransmission timer for sending the packet into the network (on either first try or retransmission)
 // - update the internal sphinx delay of an expired packet
+// - keep track of the delivery of the fragments of a message the caller wants to know the status of
 pub(crate) enum Action {
     /// Inserts new `PendingAcknowledgement`s into the 'shared' state.
     /// Initiated by `InputMessageListener`
@@ -42,6 +49,15 @@ pub(crate) enum Action {
     /// Updates the expected delay of given `PendingAcknowledgement` with the new provided `SphinxDelay`.
     /// Initiated by `RetransmissionRequestListener`
     UpdateDelay(FragmentIdentifier, SphinxDelay),
+
+    /// Associates the fragments of a message with its `DeliveryToken` so that its status could be
+    /// reported once they're all acknowledged. It has to be sent before the corresponding `InsertPending`.
+    /// Initiated by `MessageHandler`
+    TrackDelivery(DeliveryToken, Vec<FragmentIdentifier>),
+
+    /// Reports the message associated with the `DeliveryToken` as failed since it couldn't have been sent at all.
+    /// Initiated by `InputMessageListener` or `ReplyController`
+    ReportUndeliverable(DeliveryToken),
 }
 
 impl Action {
@@ -60,6 +76,17 @@ impl Action {
     pub(crate) fn new_update_delay(frag_id: FragmentIdentifier, delay: SphinxDelay) -> Self {
         Action::UpdateDelay(frag_id, delay)
     }
+
+    pub(crate) fn new_track_delivery(
+        token: DeliveryToken,
+        frag_ids: Vec<FragmentIdentifier>,
+    ) -> Self {
+        Action::TrackDelivery(token, frag_ids)
+    }
+
+    pub(crate) fn new_report_undeliverable(token: DeliveryToken) -> Self {
+        Action::ReportUndeliverable(token)
+    }
 }
 
 /// Configurable parameters of the `ActionController`
@@ -69,15 +96,58 @@ pub(super) struct Config {
 
     /// Given ack timeout in the form a * BASE_DELAY + b, it specifies the multiplier `a`
     ack_wait_multiplier: f64,
+
+    /// Parameters of the retransmission backoff and the maximum number of attempts.
+    /// If the latter is not set, the retransmissions are attempted until the fragment gets acknowledged.
+    retransmission: config::Retransmission,
 }
 
 impl Config {
-    pub(super) fn new(ack_wait_addition: Duration, ack_wait_multiplier: f64) -> Self {
+    pub(super) fn new(
+        ack_wait_addition: Duration,
+        ack_wait_multiplier: f64,
+        retransmission: config::Retransmission,
+    ) -> Self {
         Config {
             ack_wait_addition,
             ack_wait_multiplier,
+            retransmission,
+        }
+    }
+
+    /// Determines the ack timeout of a packet with the given expected delay that has already been
+    /// retransmitted the specified number of times, before applying any jitter.
+    fn ack_timeout(&self, expected_delay: SphinxDelay, retransmissions: u32) -> Duration {
+        let initial = self.retransmission.initial_timeout.unwrap_or_else(|| {
+            (expected_delay * self.ack_wait_multiplier).to_duration() + self.ack_wait_addition
+        });
+
+        let backoff = self
+            .retransmission
+            .backoff_multiplier
+            .max(1.0)
+            .powi(retransmissions.min(i32::MAX as u32) as i32);
+
+        // the backoff might easily overflow the duration after enough retransmissions
+        Duration::try_from_secs_f64(initial.as_secs_f64() * backoff)
+            .unwrap_or(Duration::MAX)
+            .min(self.retransmission.maximum_timeout)
+    }
+
+    fn with_jitter<R: Rng>(&self, timeout: Duration, rng: &mut R) -> Duration {
+        let jitter = self.retransmission.jitter.clamp(0.0, 1.0);
+        if jitter > 0.0 {
+            timeout.mul_f64(rng.gen_range(1.0 - jitter..=1.0 + jitter))
+        } else {
+            timeout
         }
     }
+
+    fn attempts_exhausted(&self, retransmissions: u32) -> bool {
+        self.retransmission
+            .max_attempts
+            .is_some_and(|max_attempts| retransmissions.saturating_add(1) >= max_attempts)
+    }
 }
 
 pub(super) struct ActionController {
@@ -95,11 +165,24 @@ pub(super) struct ActionController {
     /// retransmitted if their timer fires up.
     pending_acks_timers: NonExhaustiveDelayQueue<FragmentIdentifier>,
 
+    /// Moments at which the currently running timers were started, i.e. roughly when the packets
+    /// were sent into the mix network, used for measuring the ack latency.
+    timers_started_at: HashMap<FragmentIdentifier, Instant>,
+
+    /// Number of times the pending fragments have already been retransmitted.
+    retransmissions: HashMap<FragmentIdentifier, u32>,
+
+    /// Tracker of the delivery of the messages that have their status reported.
+    delivery_tracker: DeliveryTracker,
+
     /// Channel for receiving `Action`s from other modules.
     incoming_actions: AckActionReceiver,
 
     /// Channel for notifying `RetransmissionRequestListener` about expired acknowledgements.
     retransmission_sender: RetransmissionRequestSender,
+
+    /// Shared state of the graceful shutdown, which has to know how many packets are yet to be acknowledged.
+    drain_state: DrainState,
 }
 
 impl ActionController {
@@ -107,13 +190,19 @@ impl ActionController {
         config: Config,
         retransmission_sender: RetransmissionRequestSender,
         incoming_actions: AckActionReceiver,
+        drain_state: DrainState,
+        delivery_receipts: DeliveryReceipts,
     ) -> Self {
         ActionController {
             config,
             pending_acks_data: HashMap::new(),
             pending_acks_timers: NonExhaustiveDelayQueue::new(),
+            timers_started_at: HashMap::new(),
+            retransmissions: HashMap::new(),
+            delivery_tracker: DeliveryTracker::new(delivery_receipts),
             incoming_actions,
             retransmission_sender,
+            drain_state,
         }
     }
 
@@ -149,11 +238,20 @@ impl ActionController {
             //     // timer TWICE for the SAME PendingAcknowledgement
             //     panic!("Tried to start an already started ack timer!")
             // }
-            let timeout = (pending_ack_data.delay * self.config.ack_wait_multiplier).to_duration()
-                + self.config.ack_wait_addition;
+            let retransmissions = self
+                .retransmissions
+                .get(&frag_id)
+                .copied()
+                .unwrap_or_default();
+            let timeout = self.config.with_jitter(
+                self.config
+                    .ack_timeout(pending_ack_data.delay, retransmissions),
+                &mut rand::thread_rng(),
+            );
 
             let new_queue_key = self.pending_acks_timers.insert(frag_id, timeout);
-            *queue_key = Some(new_queue_key)
+            *queue_key = Some(new_queue_key);
+            self.timers_started_at.insert(frag_id, get_time_now());
         } else {
             debug!(
                 "Tried to START TIMER on pending ack that is already gone! - {}",
@@ -173,6 +271,15 @@ impl ActionController {
                 );
             }
             Some((_, queue_key)) => {
+                inc!("acks_received");
+                self.retransmissions.remove(&frag_id);
+                self.delivery_tracker.on_acknowledged(frag_id);
+                if let Some(started_at) = self.timers_started_at.remove(&frag_id) {
+                    observe!(
+                        "ack_latency_seconds",
+                        get_time_now().duration_since(started_at).as_secs_f64()
+                    );
+                }
                 if let Some(queue_key) = queue_key {
                     // there are no possible checks here, we must GUARANTEE that we NEVER try
                     // to remove an entry that doesn't exist (and we MUST GUARANTEE that
@@ -234,6 +341,26 @@ impl ActionController {
                 panic!("Ack expired before it was even scheduled!")
             }
             *queue_key = None;
+            self.timers_started_at.remove(&frag_id);
+            inc!("ack_timeouts");
+
+            let retransmissions = self.retransmissions.entry(frag_id).or_default();
+            if self.config.attempts_exhausted(*retransmissions) {
+                warn!(
+                    "{frag_id} has not been acknowledged after {} attempts - abandoning it",
+                    *retransmissions + 1
+                );
+                inc!("retransmissions_abandoned");
+                self.pending_acks_data.remove(&frag_id);
+                self.retransmissions.remove(&frag_id);
+                // this results in the failure being reported if the fragment belongs to a tracked message
+                self.delivery_tracker.on_abandoned(frag_id);
+                self.drain_state
+                    .set_pending_acks(self.pending_acks_data.len());
+                return;
+            }
+            *retransmissions += 1;
+
             // downgrading an arc and then upgrading vs cloning is difference of 30ns vs 15ns
             // so it's literally a NO difference while it might prevent us from unnecessarily
             // resending data (in maybe 1 in 1 million cases, but it's something)
@@ -259,7 +386,11 @@ impl ActionController {
             Action::RemovePending(frag_id) => self.handle_remove(frag_id),
             Action::StartTimer(frag_id) => self.handle_start_timer(frag_id),
             Action::UpdateDelay(frag_id, delay) => self.handle_update_delay(frag_id, delay),
+            Action::TrackDelivery(token, frag_ids) => self.delivery_tracker.track(token, frag_ids),
+            Action::ReportUndeliverable(token) => self.delivery_tracker.report_failure(token),
         }
+        self.drain_state
+            .set_pending_acks(self.pending_acks_data.len());
     }
 
     pub(super) async fn run_with_shutdown(&mut self, mut shutdown: nym_task::TaskClient) {
@@ -293,3 +424,50 @@ impl ActionController {
         log::debug!("ActionController: Exiting");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn ack_timeout_backs_off_up_to_the_maximum() {
+        let config = Config::new(
+            Duration::from_secs(1),
+            2.0,
+            config::Retransmission {
+                backoff_multiplier: 2.0,
+                maximum_timeout: Duration::from_secs(10),
+                max_attempts: Some(3),
+                ..Default::default()
+            },
+        );
+        let delay = SphinxDelay::new_from_millis(500);
+
+        assert_eq!(config.ack_timeout(delay, 0), Duration::from_secs(2));
+        assert_eq!(config.ack_timeout(delay, 1), Duration::from_secs(4));
+        assert_eq!(config.ack_timeout(delay, 2), Duration::from_secs(8));
+        assert_eq!(config.ack_timeout(delay, 3), Duration::from_secs(10));
+        assert_eq!(config.ack_timeout(delay, u32::MAX), Duration::from_secs(10));
+
+        assert!(!config.attempts_exhausted(1));
+        assert!(config.attempts_exhausted(2));
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let config = Config::new(
+            Duration::ZERO,
+            1.0,
+            config::Retransmission {
+                jitter: 0.25,
+                ..Default::default()
+            },
+        );
+        let timeout = Duration::from_secs(4);
+        for _ in 0..100 {
+            let jittered = config.with_jitter(timeout, &mut OsRng);
+            assert!(jittered >= Duration::from_secs(3) && jittered <= Duration::from_secs(5));
+        }
+    }
+}
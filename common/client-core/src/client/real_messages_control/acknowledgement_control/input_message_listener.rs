@@ -1,16 +1,23 @@
 // Copyright 2021-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::client::inbound_messages::{InputMessage, InputMessageReceiver};
-use crate::client::real_messages_control::message_handler::MessageHandler;
+use crate::client::delivery::DeliveryToken;
+use crate::client::drain::DrainState;
+use crate::client::inbound_messages::InputMessage;
+use crate::client::outbox::controller::InputMessageSource;
+use crate::client::real_messages_control::message_handler::{
+    MessageHandler, SurbWrappedPreparationError,
+};
 use crate::client::real_messages_control::real_traffic_stream::RealMessage;
 use crate::client::replies::reply_controller::ReplyControllerSender;
+use crate::client::topology_control::RoutabilityListener;
 use log::*;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nym_sphinx::forwarding::packet::MixPacket;
 use nym_sphinx::params::PacketType;
-use nym_task::connections::TransmissionLane;
+use nym_sphinx::preparer::latency::LatencyBudget;
+use nym_task::connections::{LaneQueueLengths, TransmissionLane};
 use rand::{CryptoRng, Rng};
 
 /// Module responsible for dealing with the received messages: splitting them, creating acknowledgements,
@@ -20,9 +27,13 @@ pub(super) struct InputMessageListener<R>
 where
     R: CryptoRng + Rng,
 {
-    input_receiver: InputMessageReceiver,
+    input_source: InputMessageSource,
     message_handler: MessageHandler<R>,
     reply_controller_sender: ReplyControllerSender,
+    drain_state: DrainState,
+    lane_queue_lengths: LaneQueueLengths,
+    // if set, the input is left queued while the topology is not routable
+    routability_hold: Option<RoutabilityListener>,
 }
 
 impl<R> InputMessageListener<R>
@@ -33,14 +44,40 @@ where
     // some considerable refactoring
     #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
-        input_receiver: InputMessageReceiver,
+        input_source: InputMessageSource,
         message_handler: MessageHandler<R>,
         reply_controller_sender: ReplyControllerSender,
+        drain_state: DrainState,
+        lane_queue_lengths: LaneQueueLengths,
     ) -> Self {
         InputMessageListener {
-            input_receiver,
+            input_source,
             message_handler,
             reply_controller_sender,
+            drain_state,
+            lane_queue_lengths,
+            routability_hold: None,
+        }
+    }
+
+    #[must_use]
+    pub(super) fn with_routability_hold(
+        mut self,
+        routability_hold: Option<RoutabilityListener>,
+    ) -> Self {
+        self.routability_hold = routability_hold;
+        self
+    }
+
+    /// Waits until the topology becomes routable if we're meant to hold the input until then.
+    async fn wait_for_routable_topology(&mut self) {
+        let Some(routability) = self.routability_hold.as_mut() else {
+            return;
+        };
+        if !routability.current().is_routable() {
+            info!("the topology is not routable - holding any input messages until it recovers");
+            routability.wait_until_routable().await;
+            info!("the topology is routable again - resuming processing of input messages");
         }
     }
 
@@ -61,12 +98,14 @@ where
         recipient_tag: AnonymousSenderTag,
         data: Vec<u8>,
         lane: TransmissionLane,
+        delivery_token: Option<DeliveryToken>,
     ) {
         // offload reply handling to the dedicated task
         self.reply_controller_sender
-            .send_reply(recipient_tag, data, lane)
+            .send_reply(recipient_tag, data, lane, delivery_token)
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn handle_plain_message(
         &mut self,
         recipient: Recipient,
@@ -74,16 +113,28 @@ where
         lane: TransmissionLane,
         packet_type: PacketType,
         mix_hops: Option<u8>,
+        delivery_token: Option<DeliveryToken>,
+        latency_budget: Option<LatencyBudget>,
     ) {
         if let Err(err) = self
             .message_handler
-            .try_send_plain_message(recipient, content, lane, packet_type, mix_hops)
+            .try_send_plain_message(
+                recipient,
+                content,
+                lane,
+                packet_type,
+                mix_hops,
+                delivery_token,
+                latency_budget,
+            )
             .await
         {
-            warn!("failed to send a plain message - {err}")
+            warn!("failed to send a plain message - {err}");
+            self.report_undeliverable(delivery_token)
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn handle_repliable_message(
         &mut self,
         recipient: Recipient,
@@ -92,6 +143,8 @@ where
         lane: TransmissionLane,
         packet_type: PacketType,
         mix_hops: Option<u8>,
+        delivery_token: Option<DeliveryToken>,
+        latency_budget: Option<LatencyBudget>,
     ) {
         if let Err(err) = self
             .message_handler
@@ -102,23 +155,102 @@ where
                 lane,
                 packet_type,
                 mix_hops,
+                delivery_token,
+                latency_budget,
             )
             .await
         {
-            warn!("failed to send a repliable message - {err}")
+            warn!("failed to send a repliable message - {err}");
+            self.report_undeliverable(delivery_token)
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_multi_recipient_message(
+        &mut self,
+        recipients: Vec<Recipient>,
+        content: Vec<u8>,
+        reply_surbs: Option<u32>,
+        lane: TransmissionLane,
+        packet_type: PacketType,
+        mix_hops: Option<u8>,
+        mut delivery_token: Option<DeliveryToken>,
+        latency_budget: Option<LatencyBudget>,
+    ) {
+        debug!("sending a message to {} recipients", recipients.len());
+        // the fragments sent to all the recipients are tracked under the same token,
+        // so it's only going to be reported as delivered once every single one of them got the message
+        for recipient in recipients {
+            let res = match reply_surbs {
+                Some(reply_surbs) => {
+                    self.message_handler
+                        .try_send_message_with_reply_surbs(
+                            recipient,
+                            content.clone(),
+                            reply_surbs,
+                            lane,
+                            packet_type,
+                            mix_hops,
+                            delivery_token,
+                            latency_budget,
+                        )
+                        .await
+                }
+                None => self
+                    .message_handler
+                    .try_send_plain_message(
+                        recipient,
+                        content.clone(),
+                        lane,
+                        packet_type,
+                        mix_hops,
+                        delivery_token,
+                        latency_budget,
+                    )
+                    .await
+                    .map_err(SurbWrappedPreparationError::from),
+            };
+
+            if let Err(err) = res {
+                warn!("failed to send a message to {recipient} - {err}");
+                // we still attempt to reach the remaining recipients,
+                // but the message as a whole can't be delivered anymore
+                self.report_undeliverable(delivery_token.take())
+            }
+        }
+    }
+
+    fn report_undeliverable(&self, delivery_token: Option<DeliveryToken>) {
+        if let Some(token) = delivery_token {
+            self.message_handler.report_undeliverable(token)
         }
     }
 
     async fn on_input_message(&mut self, msg: InputMessage) {
+        // the out queue schedules the packets based on the priority of their lane
+        self.lane_queue_lengths
+            .set_priority(*msg.lane(), msg.priority());
+        let delivery_token = msg.delivery_token();
+        let latency_budget = msg.latency_budget();
+
         match msg {
             InputMessage::Regular {
                 recipient,
                 data,
                 lane,
                 mix_hops,
+                ..
             } => {
-                self.handle_plain_message(recipient, data, lane, PacketType::Mix, mix_hops)
-                    .await
+                self.handle_plain_message(
+                    recipient,
+                    data,
+                    lane,
+                    PacketType::Mix,
+                    mix_hops,
+                    delivery_token,
+                    latency_budget,
+                )
+                .await
             }
             InputMessage::Anonymous {
                 recipient,
@@ -126,6 +258,7 @@ where
                 reply_surbs,
                 lane,
                 mix_hops,
+                ..
             } => {
                 self.handle_repliable_message(
                     recipient,
@@ -134,6 +267,28 @@ where
                     lane,
                     PacketType::Mix,
                     mix_hops,
+                    delivery_token,
+                    latency_budget,
+                )
+                .await
+            }
+            InputMessage::MultiRecipient {
+                recipients,
+                data,
+                reply_surbs,
+                lane,
+                mix_hops,
+                ..
+            } => {
+                self.handle_multi_recipient_message(
+                    recipients,
+                    data,
+                    reply_surbs,
+                    lane,
+                    PacketType::Mix,
+                    mix_hops,
+                    delivery_token,
+                    latency_budget,
                 )
                 .await
             }
@@ -141,10 +296,14 @@ where
                 recipient_tag,
                 data,
                 lane,
+                ..
             } => {
-                self.handle_reply(recipient_tag, data, lane).await;
+                self.handle_reply(recipient_tag, data, lane, delivery_token)
+                    .await;
+            }
+            InputMessage::Premade { msgs, lane, .. } => {
+                self.handle_premade_packets(msgs, lane).await
             }
-            InputMessage::Premade { msgs, lane } => self.handle_premade_packets(msgs, lane).await,
             InputMessage::MessageWrapper {
                 message,
                 packet_type,
@@ -154,9 +313,18 @@ where
                     data,
                     lane,
                     mix_hops,
+                    ..
                 } => {
-                    self.handle_plain_message(recipient, data, lane, packet_type, mix_hops)
-                        .await
+                    self.handle_plain_message(
+                        recipient,
+                        data,
+                        lane,
+                        packet_type,
+                        mix_hops,
+                        delivery_token,
+                        latency_budget,
+                    )
+                    .await
                 }
                 InputMessage::Anonymous {
                     recipient,
@@ -164,6 +332,7 @@ where
                     reply_surbs,
                     lane,
                     mix_hops,
+                    ..
                 } => {
                     self.handle_repliable_message(
                         recipient,
@@ -172,6 +341,28 @@ where
                         lane,
                         packet_type,
                         mix_hops,
+                        delivery_token,
+                        latency_budget,
+                    )
+                    .await
+                }
+                InputMessage::MultiRecipient {
+                    recipients,
+                    data,
+                    reply_surbs,
+                    lane,
+                    mix_hops,
+                    ..
+                } => {
+                    self.handle_multi_recipient_message(
+                        recipients,
+                        data,
+                        reply_surbs,
+                        lane,
+                        packet_type,
+                        mix_hops,
+                        delivery_token,
+                        latency_budget,
                     )
                     .await
                 }
@@ -179,10 +370,12 @@ where
                     recipient_tag,
                     data,
                     lane,
+                    ..
                 } => {
-                    self.handle_reply(recipient_tag, data, lane).await;
+                    self.handle_reply(recipient_tag, data, lane, delivery_token)
+                        .await;
                 }
-                InputMessage::Premade { msgs, lane } => {
+                InputMessage::Premade { msgs, lane, .. } => {
                     self.handle_premade_packets(msgs, lane).await
                 }
                 // MessageWrappers can't be nested
@@ -196,9 +389,27 @@ where
 
         while !shutdown.is_shutdown() {
             tokio::select! {
-                input_msg = self.input_receiver.recv() => match input_msg {
-                    Some(input_msg) => {
+                biased;
+                _ = shutdown.recv_with_delay() => {
+                    log::trace!("InputMessageListener: Received shutdown");
+                    continue;
+                }
+                _ = self.wait_for_routable_topology() => {}
+            }
+
+            tokio::select! {
+                input_msg = self.input_source.recv() => match input_msg {
+                    Some((input_msg, outbox_id)) if self.drain_state.is_draining() => {
+                        // the message is not acknowledged in the outbox, so it could be resent on the next startup
+                        debug!("rejecting new input message (outbox id: {outbox_id:?}) as the client is being drained");
+                        self.drain_state.record_rejected_message();
+                        self.report_undeliverable(input_msg.delivery_token());
+                    }
+                    Some((input_msg, outbox_id)) => {
                         self.on_input_message(input_msg).await;
+                        if let Some(outbox_id) = outbox_id {
+                            self.input_source.ack(outbox_id);
+                        }
                     },
                     None => {
                         log::trace!("InputMessageListener: Stopping since channel closed");
@@ -7,10 +7,15 @@ use self::{
     retransmission_request_listener::RetransmissionRequestListener,
     sent_notification_listener::SentNotificationListener,
 };
-use crate::client::inbound_messages::InputMessageReceiver;
+use crate::client::delivery::DeliveryReceipts;
+use crate::client::drain::DrainState;
+use crate::client::events::ClientEventSender;
+use crate::client::outbox::controller::InputMessageSource;
 use crate::client::packet_statistics_control::PacketStatisticsReporter;
 use crate::client::real_messages_control::message_handler::MessageHandler;
 use crate::client::replies::reply_controller::ReplyControllerSender;
+use crate::client::topology_control::RoutabilityListener;
+use crate::config;
 use crate::spawn_future;
 use action_controller::AckActionReceiver;
 use futures::channel::mpsc;
@@ -24,6 +29,7 @@ use nym_sphinx::{
     chunking::fragment::{Fragment, FragmentIdentifier},
     Delay as SphinxDelay,
 };
+use nym_task::connections::LaneQueueLengths;
 use rand::{CryptoRng, Rng};
 use std::{
     sync::{Arc, Weak},
@@ -126,7 +132,7 @@ impl PendingAcknowledgement {
 pub(super) struct AcknowledgementControllerConnectors {
     /// Channel used for receiving raw messages from a client. The messages need to be put
     /// into sphinx packets first.
-    input_receiver: InputMessageReceiver,
+    input_source: InputMessageSource,
 
     /// Channel used for receiving notification about particular packet being sent off to the
     /// mix network (i.e. it was done being delayed by whatever value was determined in the poisson
@@ -145,14 +151,14 @@ pub(super) struct AcknowledgementControllerConnectors {
 
 impl AcknowledgementControllerConnectors {
     pub(super) fn new(
-        input_receiver: InputMessageReceiver,
+        input_source: InputMessageSource,
         sent_notifier: SentPacketNotificationReceiver,
         ack_receiver: AcknowledgementReceiver,
         ack_action_sender: AckActionSender,
         ack_action_receiver: AckActionReceiver,
     ) -> Self {
         AcknowledgementControllerConnectors {
-            input_receiver,
+            input_source,
             sent_notifier,
             ack_receiver,
             ack_action_sender,
@@ -169,16 +175,41 @@ pub(super) struct Config {
     /// Given ack timeout in the form a * BASE_DELAY + b, it specifies the multiplier `a`
     ack_wait_multiplier: f64,
 
+    /// Parameters of the retransmission backoff and the maximum number of attempts.
+    retransmission: config::Retransmission,
+
     /// Predefined packet size used for the encapsulated messages.
     packet_size: PacketSize,
+
+    /// State of the graceful shutdown, used for rejecting new messages and publishing
+    /// the number of pending acknowledgements.
+    drain_state: DrainState,
+
+    /// Handle used for reporting the delivery status of the messages.
+    delivery_receipts: DeliveryReceipts,
+
+    /// If specified, new input messages are not going to be processed while the topology is not routable.
+    routability_hold: Option<RoutabilityListener>,
+
+    /// Handle used for publishing the retransmissions.
+    client_events: ClientEventSender,
 }
 
 impl Config {
-    pub(super) fn new(ack_wait_addition: Duration, ack_wait_multiplier: f64) -> Self {
+    pub(super) fn new(
+        ack_wait_addition: Duration,
+        ack_wait_multiplier: f64,
+        retransmission: config::Retransmission,
+    ) -> Self {
         Config {
             ack_wait_addition,
             ack_wait_multiplier,
+            retransmission,
             packet_size: Default::default(),
+            drain_state: Default::default(),
+            delivery_receipts: Default::default(),
+            routability_hold: None,
+            client_events: Default::default(),
         }
     }
 
@@ -186,6 +217,29 @@ impl Config {
         self.packet_size = packet_size;
         self
     }
+
+    pub(crate) fn with_drain_state(mut self, drain_state: DrainState) -> Self {
+        self.drain_state = drain_state;
+        self
+    }
+
+    pub(crate) fn with_delivery_receipts(mut self, delivery_receipts: DeliveryReceipts) -> Self {
+        self.delivery_receipts = delivery_receipts;
+        self
+    }
+
+    pub(crate) fn with_routability_hold(
+        mut self,
+        routability_hold: Option<RoutabilityListener>,
+    ) -> Self {
+        self.routability_hold = routability_hold;
+        self
+    }
+
+    pub(crate) fn with_client_events(mut self, client_events: ClientEventSender) -> Self {
+        self.client_events = client_events;
+        self
+    }
 }
 
 pub(super) struct AcknowledgementController<R>
@@ -209,16 +263,22 @@ where
         connectors: AcknowledgementControllerConnectors,
         message_handler: MessageHandler<R>,
         reply_controller_sender: ReplyControllerSender,
+        lane_queue_lengths: LaneQueueLengths,
         stats_tx: PacketStatisticsReporter,
     ) -> Self {
         let (retransmission_tx, retransmission_rx) = mpsc::unbounded();
 
-        let action_config =
-            action_controller::Config::new(config.ack_wait_addition, config.ack_wait_multiplier);
+        let action_config = action_controller::Config::new(
+            config.ack_wait_addition,
+            config.ack_wait_multiplier,
+            config.retransmission,
+        );
         let action_controller = ActionController::new(
             action_config,
             retransmission_tx,
             connectors.ack_action_receiver,
+            config.drain_state.clone(),
+            config.delivery_receipts,
         );
 
         // will listen for any acks coming from the network
@@ -231,10 +291,13 @@ where
 
         // will listen for any new messages from the client
         let input_message_listener = InputMessageListener::new(
-            connectors.input_receiver,
+            connectors.input_source,
             message_handler.clone(),
             reply_controller_sender.clone(),
-        );
+            config.drain_state,
+            lane_queue_lengths,
+        )
+        .with_routability_hold(config.routability_hold);
 
         // will listen for any ack timeouts and trigger retransmission
         let retransmission_request_listener = RetransmissionRequestListener::new(
@@ -242,6 +305,7 @@ where
             message_handler,
             retransmission_rx,
             reply_controller_sender,
+            config.client_events,
         );
 
         // will listen for events indicating the packet was sent through the network so that
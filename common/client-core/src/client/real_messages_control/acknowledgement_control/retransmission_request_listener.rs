@@ -5,6 +5,7 @@ use super::{
     action_controller::{AckActionSender, Action},
     PendingAcknowledgement, RetransmissionRequestReceiver,
 };
+use crate::client::events::{ClientEvent, ClientEventSender};
 use crate::client::real_messages_control::acknowledgement_control::PacketDestination;
 use crate::client::real_messages_control::message_handler::{MessageHandler, PreparationError};
 use crate::client::real_messages_control::real_traffic_stream::RealMessage;
@@ -24,6 +25,7 @@ pub(super) struct RetransmissionRequestListener<R> {
     message_handler: MessageHandler<R>,
     request_receiver: RetransmissionRequestReceiver,
     reply_controller_sender: ReplyControllerSender,
+    client_events: ClientEventSender,
 }
 
 impl<R> RetransmissionRequestListener<R>
@@ -35,12 +37,14 @@ where
         message_handler: MessageHandler<R>,
         request_receiver: RetransmissionRequestReceiver,
         reply_controller_sender: ReplyControllerSender,
+        client_events: ClientEventSender,
     ) -> Self {
         RetransmissionRequestListener {
             action_sender,
             message_handler,
             request_receiver,
             reply_controller_sender,
+            client_events,
         }
     }
 
@@ -76,6 +80,9 @@ where
                 return;
             }
         };
+        self.client_events.emit(ClientEvent::Retransmission {
+            fragment: timed_out_ack.message_chunk.fragment_identifier(),
+        });
 
         let maybe_prepared_fragment = match &timed_out_ack.destination {
             PacketDestination::Anonymous {
@@ -1,6 +1,10 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::control::RuntimeParametersListener;
+use crate::client::correspondents::RecentCorrespondents;
+use crate::client::delivery::DeliveryToken;
+use crate::client::key_manager::ManagedKeys;
 use crate::client::real_messages_control::acknowledgement_control::PendingAcknowledgement;
 use crate::client::real_messages_control::real_traffic_stream::{
     BatchRealMessageSender, RealMessage,
@@ -14,8 +18,10 @@ use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::{AnonymousSenderTag, RepliableMessage, ReplyMessage};
 use nym_sphinx::anonymous_replies::{ReplySurb, SurbEncryptionKey};
 use nym_sphinx::chunking::fragment::{Fragment, FragmentIdentifier};
+use nym_sphinx::envelope::EnvelopeHeader;
 use nym_sphinx::message::NymMessage;
 use nym_sphinx::params::{PacketSize, PacketType, DEFAULT_NUM_MIX_HOPS};
+use nym_sphinx::preparer::latency::LatencyBudget;
 use nym_sphinx::preparer::{MessagePreparer, PreparedFragment};
 use nym_sphinx::Delay;
 use nym_task::connections::TransmissionLane;
@@ -87,9 +93,9 @@ pub(crate) struct Config {
     /// Key used to decrypt contents of received SURBAcks
     ack_key: Arc<AckKey>,
 
-    /// Address of this client which also represent an address to which all acknowledgements
-    /// and surb-based are going to be sent.
-    sender_address: Recipient,
+    /// Keys of this client determining its address, which also represents an address to which
+    /// all acknowledgements and surb-based are going to be sent.
+    sender_keys: ManagedKeys,
 
     /// Average delay a data packet is going to get delay at a single mixnode.
     average_packet_delay: Duration,
@@ -106,23 +112,35 @@ pub(crate) struct Config {
 
     /// Optional secondary predefined packet size used for the encapsulated messages.
     secondary_packet_size: Option<PacketSize>,
+
+    /// Optional versioned envelope header attached to all outgoing messages.
+    message_envelope: Option<EnvelopeHeader>,
+
+    /// Optional tracker of recipients of our messages that might also receive some of our cover traffic.
+    recent_correspondents: Option<RecentCorrespondents>,
+
+    /// Optional listener for the changes of the runtime parameters, such as the average packet delay.
+    runtime_parameters: Option<RuntimeParametersListener>,
 }
 
 impl Config {
     pub fn new(
         ack_key: Arc<AckKey>,
-        sender_address: Recipient,
+        sender_keys: ManagedKeys,
         average_packet_delay: Duration,
         average_ack_delay: Duration,
     ) -> Self {
         Config {
             ack_key,
-            sender_address,
+            sender_keys,
             average_packet_delay,
             average_ack_delay,
             num_mix_hops: DEFAULT_NUM_MIX_HOPS,
             primary_packet_size: PacketSize::default(),
             secondary_packet_size: None,
+            message_envelope: None,
+            recent_correspondents: None,
+            runtime_parameters: None,
         }
     }
 
@@ -144,6 +162,30 @@ impl Config {
         self.secondary_packet_size = packet_size;
         self
     }
+
+    /// Allows wrapping all outgoing messages in the versioned envelope.
+    pub fn with_message_envelope(mut self, message_envelope: Option<EnvelopeHeader>) -> Self {
+        self.message_envelope = message_envelope;
+        self
+    }
+
+    /// Allows keeping track of recipients of our messages for the purposes of cover traffic.
+    pub(crate) fn with_recent_correspondents(
+        mut self,
+        recent_correspondents: Option<RecentCorrespondents>,
+    ) -> Self {
+        self.recent_correspondents = recent_correspondents;
+        self
+    }
+
+    /// Allows adjusting the average packet delay while the client is running.
+    pub(crate) fn with_runtime_parameters(
+        mut self,
+        runtime_parameters: Option<RuntimeParametersListener>,
+    ) -> Self {
+        self.runtime_parameters = runtime_parameters;
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -172,15 +214,17 @@ where
         tag_storage: UsedSenderTags,
     ) -> Self
     where
-        R: Copy,
+        R: Clone,
     {
+        // note: the preparer only cares about our identity and gateway, which never change
         let message_preparer = MessagePreparer::new(
-            rng,
-            config.sender_address,
+            rng.clone(),
+            config.sender_keys.address(),
             config.average_packet_delay,
             config.average_ack_delay,
         )
-        .with_mix_hops(config.num_mix_hops);
+        .with_mix_hops(config.num_mix_hops)
+        .with_message_envelope(config.message_envelope);
 
         MessageHandler {
             config,
@@ -194,6 +238,22 @@ where
         }
     }
 
+    /// Makes sure the preparer uses the most recent runtime parameters, if they have been changed.
+    fn apply_runtime_parameters(&mut self) {
+        let Some(listener) = self.config.runtime_parameters.as_mut() else {
+            return;
+        };
+        if let Some(parameters) = listener.try_changed() {
+            debug!(
+                "using the new average packet delay of {:?}",
+                parameters.average_packet_delay
+            );
+            self.config.average_packet_delay = parameters.average_packet_delay;
+            self.message_preparer
+                .set_average_packet_delay(parameters.average_packet_delay);
+        }
+    }
+
     fn get_or_create_sender_tag(&mut self, recipient: &Recipient) -> AnonymousSenderTag {
         if let Some(existing) = self.tag_storage.try_get_existing(recipient) {
             trace!("we already had sender tag for {recipient}");
@@ -211,7 +271,7 @@ where
         &self,
         permit: &'a TopologyReadPermit<'a>,
     ) -> Result<&'a NymTopology, PreparationError> {
-        match permit.try_get_valid_topology_ref(&self.config.sender_address, None) {
+        match permit.try_get_valid_topology_ref(&self.config.sender_keys.address(), None) {
             Ok(topology_ref) => Ok(topology_ref),
             Err(err) => {
                 warn!("Could not process the packet - the network topology is invalid - {err}");
@@ -227,9 +287,16 @@ where
             return self.config.primary_packet_size;
         };
 
-        let primary_count =
-            msg.required_packets(self.config.primary_packet_size, self.config.num_mix_hops);
-        let secondary_count = msg.required_packets(secondary_packet, self.config.num_mix_hops);
+        let primary_count = msg.required_packets(
+            self.config.primary_packet_size,
+            self.config.num_mix_hops,
+            self.config.message_envelope,
+        );
+        let secondary_count = msg.required_packets(
+            secondary_packet,
+            self.config.num_mix_hops,
+            self.config.message_envelope,
+        );
 
         trace!("This message would require: {primary_count} primary packets or {secondary_count} secondary packets...");
         // if there would be no benefit in using the secondary packet - use the primary (duh)
@@ -246,6 +313,8 @@ where
         &mut self,
         amount: usize,
     ) -> Result<(Vec<ReplySurb>, Vec<SurbEncryptionKey>), PreparationError> {
+        self.apply_runtime_parameters();
+
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = self.get_topology(&topology_permit)?;
 
@@ -319,7 +388,7 @@ where
         debug!("requesting {amount} reply SURBs from {from}");
 
         let surbs_request =
-            ReplyMessage::new_surb_request_message(self.config.sender_address, amount);
+            ReplyMessage::new_surb_request_message(self.config.sender_keys.address(), amount);
         self.try_send_single_surb_message(from, surbs_request, reply_surb, true)
             .await
     }
@@ -412,6 +481,7 @@ where
         self.forward_messages(msgs, lane).await;
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn try_send_plain_message(
         &mut self,
         recipient: Recipient,
@@ -419,12 +489,23 @@ where
         lane: TransmissionLane,
         packet_type: PacketType,
         mix_hops: Option<u8>,
+        delivery_token: Option<DeliveryToken>,
+        latency_budget: Option<LatencyBudget>,
     ) -> Result<(), PreparationError> {
         let message = NymMessage::new_plain(message);
-        self.try_split_and_send_non_reply_message(message, recipient, lane, packet_type, mix_hops)
-            .await
+        self.try_split_and_send_non_reply_message(
+            message,
+            recipient,
+            lane,
+            packet_type,
+            mix_hops,
+            delivery_token,
+            latency_budget,
+        )
+        .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn try_split_and_send_non_reply_message(
         &mut self,
         message: NymMessage,
@@ -432,11 +513,21 @@ where
         lane: TransmissionLane,
         packet_type: PacketType,
         mix_hops: Option<u8>,
+        delivery_token: Option<DeliveryToken>,
+        latency_budget: Option<LatencyBudget>,
     ) -> Result<(), PreparationError> {
         debug!("Sending non-reply message with packet type {packet_type}");
         // TODO: I really dislike existence of this assertion, it implies code has to be re-organised
         debug_assert!(!matches!(message, NymMessage::Reply(_)));
 
+        if let Some(recent_correspondents) = &self.config.recent_correspondents {
+            if recipient != self.config.sender_keys.address() {
+                recent_correspondents.record(recipient)
+            }
+        }
+
+        self.apply_runtime_parameters();
+
         // TODO2: it's really annoying we have to get topology permit again here due to borrow-checker
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = self.get_topology(&topology_permit)?;
@@ -458,14 +549,17 @@ where
             // we need to clone it because we need to keep it in memory in case we had to retransmit
             // it. And then we'd need to recreate entire ACK again.
             let chunk_clone = fragment.clone();
-            let prepared_fragment = self.message_preparer.prepare_chunk_for_sending(
-                chunk_clone,
-                topology,
-                &self.config.ack_key,
-                &recipient,
-                packet_type,
-                mix_hops,
-            )?;
+            let prepared_fragment = self
+                .message_preparer
+                .prepare_chunk_for_sending_within_budget(
+                    chunk_clone,
+                    topology,
+                    &self.config.ack_key,
+                    &recipient,
+                    packet_type,
+                    mix_hops,
+                    latency_budget,
+                )?;
 
             let real_message = RealMessage::new(
                 prepared_fragment.mix_packet,
@@ -479,6 +573,15 @@ where
             pending_acks.push(pending_ack);
         }
 
+        if let Some(token) = delivery_token {
+            self.track_delivery(
+                token,
+                pending_acks
+                    .iter()
+                    .map(|ack| ack.inner_fragment_identifier())
+                    .collect(),
+            );
+        }
         self.insert_pending_acks(pending_acks);
         self.forward_messages(real_messages, lane).await;
 
@@ -508,6 +611,8 @@ where
             TransmissionLane::AdditionalReplySurbs,
             packet_type,
             mix_hops,
+            None,
+            None,
         )
         .await?;
 
@@ -517,6 +622,7 @@ where
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn try_send_message_with_reply_surbs(
         &mut self,
         recipient: Recipient,
@@ -525,6 +631,8 @@ where
         lane: TransmissionLane,
         packet_type: PacketType,
         mix_hops: Option<u8>,
+        delivery_token: Option<DeliveryToken>,
+        latency_budget: Option<LatencyBudget>,
     ) -> Result<(), SurbWrappedPreparationError> {
         debug!("Sending message with reply SURBs with packet type {packet_type}");
         let sender_tag = self.get_or_create_sender_tag(&recipient);
@@ -535,8 +643,16 @@ where
         let message =
             NymMessage::new_repliable(RepliableMessage::new_data(message, sender_tag, reply_surbs));
 
-        self.try_split_and_send_non_reply_message(message, recipient, lane, packet_type, mix_hops)
-            .await?;
+        self.try_split_and_send_non_reply_message(
+            message,
+            recipient,
+            lane,
+            packet_type,
+            mix_hops,
+            delivery_token,
+            latency_budget,
+        )
+        .await?;
 
         log::trace!("storing {} reply keys", reply_keys.len());
         self.reply_key_storage.insert_multiple(reply_keys);
@@ -552,6 +668,8 @@ where
         mix_hops: Option<u8>,
     ) -> Result<PreparedFragment, PreparationError> {
         debug!("Sending single chunk with packet type {packet_type}");
+        self.apply_runtime_parameters();
+
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = self.get_topology(&topology_permit)?;
 
@@ -644,6 +762,19 @@ where
             .expect("action control task has died")
     }
 
+    // note: it must be called before the corresponding pending acks are inserted
+    pub(crate) fn track_delivery(&self, token: DeliveryToken, fragments: Vec<FragmentIdentifier>) {
+        self.action_sender
+            .unbounded_send(Action::new_track_delivery(token, fragments))
+            .expect("action control task has died")
+    }
+
+    pub(crate) fn report_undeliverable(&self, token: DeliveryToken) {
+        self.action_sender
+            .unbounded_send(Action::new_report_undeliverable(token))
+            .expect("action control task has died")
+    }
+
     // tells real message sender (with the poisson timer) to send this to the mix network
     pub(crate) async fn forward_messages(
         &self,
@@ -8,16 +8,22 @@
 use self::{
     acknowledgement_control::AcknowledgementController, real_traffic_stream::OutQueueControl,
 };
+use crate::client::control::RuntimeParametersListener;
+use crate::client::correspondents::RecentCorrespondents;
+use crate::client::events::ClientEventSender;
+use crate::client::key_manager::ManagedKeys;
 use crate::client::real_messages_control::message_handler::MessageHandler;
+use crate::client::real_traffic_rate::RealTrafficCounter;
 use crate::client::replies::reply_controller::{
     ReplyController, ReplyControllerReceiver, ReplyControllerSender,
 };
-use crate::client::replies::reply_storage::CombinedReplyStorage;
+use crate::client::replies::reply_storage::{CombinedReplyStorage, ReplyStorageSynchroniser};
 use crate::{
     client::{
-        inbound_messages::InputMessageReceiver, mix_traffic::BatchMixMessageSender,
+        mix_traffic::BatchMixMessageSender,
+        outbox::controller::InputMessageSource,
         real_messages_control::acknowledgement_control::AcknowledgementControllerConnectors,
-        topology_control::TopologyAccessor,
+        topology_control::{RoutabilityListener, TopologyAccessor},
     },
     spawn_future,
 };
@@ -25,10 +31,10 @@ use futures::channel::mpsc;
 use log::*;
 use nym_gateway_client::AcknowledgementReceiver;
 use nym_sphinx::acknowledgements::AckKey;
-use nym_sphinx::addressing::clients::Recipient;
+use nym_sphinx::envelope::EnvelopeHeader;
 use nym_sphinx::params::PacketType;
 use nym_task::connections::{ConnectionCommandReceiver, LaneQueueLengths};
-use rand::{rngs::OsRng, CryptoRng, Rng};
+use rand::{CryptoRng, Rng};
 use std::sync::Arc;
 
 use crate::client::replies::reply_controller;
@@ -36,18 +42,22 @@ use crate::config;
 pub(crate) use acknowledgement_control::{AckActionSender, Action};
 
 use super::packet_statistics_control::PacketStatisticsReporter;
+use super::protocol_stats::ProtocolStatsTracker;
+use crate::client::delivery::DeliveryReceipts;
+use crate::client::drain::DrainState;
+use crate::client::rng::{ClientRng, ClientRngSource};
 
 pub(crate) mod acknowledgement_control;
 pub(crate) mod message_handler;
 pub(crate) mod real_traffic_stream;
 
-// TODO: ack_key and self_recipient shouldn't really be part of this config
+// TODO: ack_key and self_keys shouldn't really be part of this config
 pub struct Config {
     /// Key used to decrypt contents of received SURBAcks
     ack_key: Arc<AckKey>,
 
-    /// Address of `this` client.
-    self_recipient: Recipient,
+    /// Keys of `this` client, determining its current address.
+    self_keys: ManagedKeys,
 
     /// Specifies all traffic related configuration options.
     traffic: config::Traffic,
@@ -58,8 +68,35 @@ pub struct Config {
     /// Specifies all acknowledgements related configuration options.
     acks: config::Acknowledgements,
 
+    /// Specifies all retransmission related configuration options.
+    retransmission: config::Retransmission,
+
     /// Specifies all reply SURBs related configuration options.
     reply_surbs: config::ReplySurbs,
+
+    /// Tracker of recipients of our messages shared with the cover traffic stream.
+    recent_correspondents: Option<RecentCorrespondents>,
+
+    /// Listener for the changes of the runtime parameters made through the `ClientControl`.
+    runtime_parameters: Option<RuntimeParametersListener>,
+
+    /// Tracker of the protocol statistics exchanged with the gateway (if enabled).
+    protocol_stats: Option<ProtocolStatsTracker>,
+
+    /// State of the graceful shutdown shared with the `ClientDrain`.
+    drain_state: DrainState,
+
+    /// Handle for reporting the delivery status of the messages, shared with the `ClientOutput`.
+    delivery_receipts: DeliveryReceipts,
+
+    /// Counter of the real packets sent shared with the cover traffic stream (if it's adaptive).
+    real_traffic_counter: Option<RealTrafficCounter>,
+
+    /// If specified, input messages are going to be held while the topology is not routable.
+    routability_hold: Option<RoutabilityListener>,
+
+    /// Handle for publishing the events of the components, shared with the `ClientState`.
+    client_events: ClientEventSender,
 }
 
 impl<'a> From<&'a Config> for acknowledgement_control::Config {
@@ -67,8 +104,13 @@ impl<'a> From<&'a Config> for acknowledgement_control::Config {
         acknowledgement_control::Config::new(
             cfg.acks.ack_wait_addition,
             cfg.acks.ack_wait_multiplier,
+            cfg.retransmission,
         )
         .with_custom_packet_size(cfg.traffic.primary_packet_size)
+        .with_drain_state(cfg.drain_state.clone())
+        .with_delivery_receipts(cfg.delivery_receipts.clone())
+        .with_routability_hold(cfg.routability_hold.clone())
+        .with_client_events(cfg.client_events.clone())
     }
 }
 
@@ -76,7 +118,7 @@ impl<'a> From<&'a Config> for real_traffic_stream::Config {
     fn from(cfg: &'a Config) -> Self {
         real_traffic_stream::Config::new(
             Arc::clone(&cfg.ack_key),
-            cfg.self_recipient,
+            cfg.self_keys.clone(),
             cfg.acks.average_ack_delay,
             cfg.traffic,
             cfg.cover_traffic.cover_traffic_primary_size_ratio,
@@ -86,7 +128,7 @@ impl<'a> From<&'a Config> for real_traffic_stream::Config {
 
 impl<'a> From<&'a Config> for reply_controller::Config {
     fn from(cfg: &'a Config) -> Self {
-        reply_controller::Config::new(cfg.reply_surbs)
+        reply_controller::Config::new(cfg.reply_surbs).with_client_events(cfg.client_events.clone())
     }
 }
 
@@ -94,12 +136,19 @@ impl<'a> From<&'a Config> for message_handler::Config {
     fn from(cfg: &'a Config) -> Self {
         message_handler::Config::new(
             Arc::clone(&cfg.ack_key),
-            cfg.self_recipient,
+            cfg.self_keys.clone(),
             cfg.traffic.average_packet_delay,
             cfg.acks.average_ack_delay,
         )
         .with_custom_primary_packet_size(cfg.traffic.primary_packet_size)
         .with_custom_secondary_packet_size(cfg.traffic.secondary_packet_size)
+        .with_message_envelope(
+            cfg.traffic
+                .use_versioned_message_envelope
+                .then(EnvelopeHeader::default),
+        )
+        .with_recent_correspondents(cfg.recent_correspondents.clone())
+        .with_runtime_parameters(cfg.runtime_parameters.clone())
     }
 }
 
@@ -107,17 +156,81 @@ impl Config {
     pub fn new(
         base_client_debug_config: &config::DebugConfig,
         ack_key: Arc<AckKey>,
-        self_recipient: Recipient,
+        self_keys: ManagedKeys,
     ) -> Self {
         Config {
             ack_key,
-            self_recipient,
+            self_keys,
             traffic: base_client_debug_config.traffic,
             cover_traffic: base_client_debug_config.cover_traffic,
             acks: base_client_debug_config.acknowledgements,
+            retransmission: base_client_debug_config.retransmission,
             reply_surbs: base_client_debug_config.reply_surbs,
+            recent_correspondents: None,
+            runtime_parameters: None,
+            protocol_stats: None,
+            drain_state: Default::default(),
+            delivery_receipts: Default::default(),
+            real_traffic_counter: None,
+            routability_hold: None,
+            client_events: Default::default(),
         }
     }
+
+    pub(crate) fn with_recent_correspondents(
+        mut self,
+        recent_correspondents: Option<RecentCorrespondents>,
+    ) -> Self {
+        self.recent_correspondents = recent_correspondents;
+        self
+    }
+
+    pub(crate) fn with_runtime_parameters(
+        mut self,
+        runtime_parameters: RuntimeParametersListener,
+    ) -> Self {
+        self.runtime_parameters = Some(runtime_parameters);
+        self
+    }
+
+    pub(crate) fn with_protocol_stats(
+        mut self,
+        protocol_stats: Option<ProtocolStatsTracker>,
+    ) -> Self {
+        self.protocol_stats = protocol_stats;
+        self
+    }
+
+    pub(crate) fn with_drain_state(mut self, drain_state: DrainState) -> Self {
+        self.drain_state = drain_state;
+        self
+    }
+
+    pub(crate) fn with_delivery_receipts(mut self, delivery_receipts: DeliveryReceipts) -> Self {
+        self.delivery_receipts = delivery_receipts;
+        self
+    }
+
+    pub(crate) fn with_real_traffic_counter(
+        mut self,
+        real_traffic_counter: Option<RealTrafficCounter>,
+    ) -> Self {
+        self.real_traffic_counter = real_traffic_counter;
+        self
+    }
+
+    pub(crate) fn with_routability_hold(
+        mut self,
+        routability_hold: Option<RoutabilityListener>,
+    ) -> Self {
+        self.routability_hold = routability_hold;
+        self
+    }
+
+    pub(crate) fn with_client_events(mut self, client_events: ClientEventSender) -> Self {
+        self.client_events = client_events;
+        self
+    }
 }
 
 pub(crate) struct RealMessagesController<R>
@@ -129,14 +242,13 @@ where
     reply_control: ReplyController<R>,
 }
 
-// obviously when we finally make shared rng that is on 'higher' level, this should become
-// generic `R`
-impl RealMessagesController<OsRng> {
+impl RealMessagesController<ClientRng> {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
+        rng_source: &mut ClientRngSource,
         config: Config,
         ack_receiver: AcknowledgementReceiver,
-        input_receiver: InputMessageReceiver,
+        input_source: InputMessageSource,
         mix_sender: BatchMixMessageSender,
         topology_access: TopologyAccessor,
         reply_storage: CombinedReplyStorage,
@@ -147,14 +259,12 @@ impl RealMessagesController<OsRng> {
         client_connection_rx: ConnectionCommandReceiver,
         stats_tx: PacketStatisticsReporter,
     ) -> Self {
-        let rng = OsRng;
-
         // create channels for inter-task communication
         let (real_message_sender, real_message_receiver) = tokio::sync::mpsc::channel(1);
         let (sent_notifier_tx, sent_notifier_rx) = mpsc::unbounded();
         let (ack_action_tx, ack_action_rx) = mpsc::unbounded();
         let ack_controller_connectors = AcknowledgementControllerConnectors::new(
-            input_receiver,
+            input_source,
             sent_notifier_rx,
             ack_receiver,
             ack_action_tx.clone(),
@@ -170,7 +280,7 @@ impl RealMessagesController<OsRng> {
         // create the actual components
         let message_handler = MessageHandler::new(
             message_handler_config,
-            rng,
+            rng_source.component_rng(),
             ack_action_tx,
             real_message_sender,
             topology_access.clone(),
@@ -184,19 +294,27 @@ impl RealMessagesController<OsRng> {
             ack_controller_connectors,
             message_handler.clone(),
             reply_controller_sender,
+            lane_queue_lengths.clone(),
             stats_tx.clone(),
         );
 
+        // the reply storage could only be synchronised with other devices if we hold the identity key
+        let storage_sync =
+            config.self_keys.keys().identity_keypair().map(|keys| {
+                ReplyStorageSynchroniser::new(reply_storage.clone(), keys.private_key())
+            });
+
         let reply_control = ReplyController::new(
             reply_controller_config,
             message_handler,
             reply_storage,
             reply_controller_receiver,
-        );
+        )
+        .with_storage_synchroniser(storage_sync);
 
         let out_queue_control = OutQueueControl::new(
             out_queue_config,
-            rng,
+            rng_source.component_rng(),
             sent_notifier_tx,
             mix_sender,
             real_message_receiver,
@@ -204,7 +322,10 @@ impl RealMessagesController<OsRng> {
             lane_queue_lengths,
             client_connection_rx,
             stats_tx,
-        );
+        )
+        .with_runtime_parameters(config.runtime_parameters.clone())
+        .with_protocol_stats(config.protocol_stats.clone())
+        .with_real_traffic_counter(config.real_traffic_counter.clone());
 
         RealMessagesController {
             out_queue_control,
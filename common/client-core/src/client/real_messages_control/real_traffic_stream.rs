@@ -1,24 +1,29 @@
 // Copyright 2021-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use self::lane_rate_limiter::LaneRateLimiter;
 use self::sending_delay_controller::SendingDelayController;
+use crate::client::control::RuntimeParametersListener;
+use crate::client::key_manager::ManagedKeys;
 use crate::client::mix_traffic::BatchMixMessageSender;
 use crate::client::packet_statistics_control::{PacketStatisticsEvent, PacketStatisticsReporter};
+use crate::client::protocol_stats::ProtocolStatsTracker;
 use crate::client::real_messages_control::acknowledgement_control::SentPacketNotificationSender;
+use crate::client::real_traffic_rate::RealTrafficCounter;
 use crate::client::topology_control::TopologyAccessor;
+use crate::client::traffic_scheduler::{new_traffic_scheduler, TrafficScheduler};
 use crate::client::transmission_buffer::TransmissionBuffer;
 use crate::config;
 use futures::task::{Context, Poll};
 use futures::{Future, Stream, StreamExt};
 use log::*;
+use nym_metrics::inc;
 use nym_sphinx::acknowledgements::AckKey;
-use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::chunking::fragment::FragmentIdentifier;
 use nym_sphinx::cover::generate_loop_cover_packet;
 use nym_sphinx::forwarding::packet::MixPacket;
 use nym_sphinx::params::PacketSize;
 use nym_sphinx::preparer::PreparedFragment;
-use nym_sphinx::utils::sample_poisson_duration;
 use nym_task::connections::{
     ConnectionCommand, ConnectionCommandReceiver, ConnectionId, LaneQueueLengths, TransmissionLane,
 };
@@ -33,15 +38,20 @@ use tokio::time::{sleep, Sleep};
 #[cfg(target_arch = "wasm32")]
 use wasmtimer::tokio::{sleep, Sleep};
 
+mod lane_rate_limiter;
 mod sending_delay_controller;
 
+// How often the rate-limited lanes are re-checked if they're the only ones with pending messages.
+// Only relevant if the poisson distribution of the packets is disabled.
+const THROTTLED_LANES_CHECK_INTERVAL: Duration = Duration::from_millis(20);
+
 /// Configurable parameters of the `OutQueueControl`
 pub(crate) struct Config {
     /// Key used to encrypt and decrypt content of an ACK packet.
     ack_key: Arc<AckKey>,
 
-    /// Represents full address of this client.
-    our_full_destination: Recipient,
+    /// Keys of this client, used for determining its current full address.
+    our_keys: ManagedKeys,
 
     /// Average delay an acknowledgement packet is going to get delay at a single mixnode.
     average_ack_delay: Duration,
@@ -57,14 +67,14 @@ pub(crate) struct Config {
 impl Config {
     pub(crate) fn new(
         ack_key: Arc<AckKey>,
-        our_full_destination: Recipient,
+        our_keys: ManagedKeys,
         average_ack_delay: Duration,
         traffic: config::Traffic,
         cover_traffic_primary_size_ratio: f64,
     ) -> Self {
         Config {
             ack_key,
-            our_full_destination,
+            our_keys,
             average_ack_delay,
             traffic,
             cover_traffic_primary_size_ratio,
@@ -86,6 +96,9 @@ where
     /// used to keep track of when a next packet should be sent out.
     next_delay: Option<Pin<Box<Sleep>>>,
 
+    /// Determines the delays between subsequent packets according to the configured scheduling model.
+    scheduler: Box<dyn TrafficScheduler>,
+
     // To make sure we don't overload the mix_tx channel, we limit the rate we are pushing
     // messages.
     sending_delay_controller: SendingDelayController,
@@ -113,10 +126,27 @@ where
     client_connection_rx: ConnectionCommandReceiver,
 
     /// Report queue lengths so that upstream can backoff sending data, and keep connections open.
+    /// It's also used for reporting the per-connection bandwidth and obtaining the lane rate limits.
     lane_queue_lengths: LaneQueueLengths,
 
+    /// Enforces the rate limits imposed on the individual lanes.
+    rate_limiter: LaneRateLimiter,
+
+    /// Wakes up the stream to re-check the rate-limited lanes that hold the only pending messages.
+    throttled_lanes_check: Option<Pin<Box<Sleep>>>,
+
     /// Channel used for sending statistics events to `PacketStatisticsControl`.
     stats_tx: PacketStatisticsReporter,
+
+    /// Optional listener for the changes of the runtime parameters, such as the average packet delay.
+    runtime_parameters: Option<RuntimeParametersListener>,
+
+    /// If the protocol statistics exchange is enabled, records our observations for the gateway
+    /// and relays its requests to slow down.
+    protocol_stats: Option<ProtocolStatsTracker>,
+
+    /// If the adaptive cover traffic is enabled, counts the real packets sent for the cover traffic stream.
+    real_traffic_counter: Option<RealTrafficCounter>,
 }
 
 #[derive(Debug)]
@@ -159,6 +189,32 @@ pub(crate) enum StreamMessage {
     Real(Box<RealMessage>),
 }
 
+// Pops the next message to be sent, skipping over the lanes that have exceeded their rate limits.
+// If `urgent_only` is set, only the urgent control traffic is considered.
+fn pop_next_scheduled_message<T, R>(
+    transmission_buffer: &mut TransmissionBuffer<T>,
+    rate_limiter: &mut LaneRateLimiter,
+    lane_queue_lengths: &LaneQueueLengths,
+    rng: &mut R,
+    urgent_only: bool,
+) -> Option<(TransmissionLane, T)>
+where
+    R: Rng + ?Sized,
+{
+    let throttled = lane_queue_lengths.with_rate_limits(|limits| {
+        rate_limiter.throttled_lanes(transmission_buffer.lanes(), limits)
+    });
+    if urgent_only {
+        transmission_buffer.pop_next_urgent_message(rng, &throttled)
+    } else {
+        transmission_buffer.pop_next_message_by_priority(
+            rng,
+            &throttled,
+            &lane_queue_lengths.high_priority_lanes(),
+        )
+    }
+}
+
 impl<R> OutQueueControl<R>
 where
     R: CryptoRng + Rng + Unpin,
@@ -177,10 +233,16 @@ where
         client_connection_rx: ConnectionCommandReceiver,
         stats_tx: PacketStatisticsReporter,
     ) -> Self {
+        let scheduler = new_traffic_scheduler(
+            config.traffic.scheduling,
+            config.traffic.scheduling_burst_size,
+        );
+
         OutQueueControl {
             config,
             sent_notifier,
             next_delay: None,
+            scheduler,
             sending_delay_controller: Default::default(),
             mix_tx,
             real_receiver,
@@ -189,7 +251,60 @@ where
             transmission_buffer: TransmissionBuffer::new(),
             client_connection_rx,
             lane_queue_lengths,
+            rate_limiter: Default::default(),
+            throttled_lanes_check: None,
             stats_tx,
+            runtime_parameters: None,
+            protocol_stats: None,
+            real_traffic_counter: None,
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn with_runtime_parameters(
+        mut self,
+        runtime_parameters: Option<RuntimeParametersListener>,
+    ) -> Self {
+        self.runtime_parameters = runtime_parameters;
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_protocol_stats(
+        mut self,
+        protocol_stats: Option<ProtocolStatsTracker>,
+    ) -> Self {
+        self.protocol_stats = protocol_stats;
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_real_traffic_counter(
+        mut self,
+        real_traffic_counter: Option<RealTrafficCounter>,
+    ) -> Self {
+        self.real_traffic_counter = real_traffic_counter;
+        self
+    }
+
+    fn apply_runtime_parameters(&mut self) {
+        let Some(listener) = self.runtime_parameters.as_mut() else {
+            return;
+        };
+        if let Some(parameters) = listener.try_changed() {
+            self.config.traffic.average_packet_delay = parameters.average_packet_delay;
+
+            if self.config.traffic.scheduling != parameters.traffic_scheduling {
+                info!(
+                    "switching the real traffic stream to {:?} scheduling",
+                    parameters.traffic_scheduling
+                );
+                self.config.traffic.scheduling = parameters.traffic_scheduling;
+                self.scheduler = new_traffic_scheduler(
+                    parameters.traffic_scheduling,
+                    self.config.traffic.scheduling_burst_size,
+                );
+            }
         }
     }
 
@@ -219,6 +334,7 @@ where
 
     async fn on_message(&mut self, next_message: StreamMessage) {
         trace!("created new message");
+        self.apply_runtime_parameters();
 
         let (next_message, fragment_id, packet_size) = match next_message {
             StreamMessage::Cover => {
@@ -228,12 +344,12 @@ where
                 // TODO for way down the line: in very rare cases (during topology update) we might have
                 // to wait a really tiny bit before actually obtaining the permit hence messing with our
                 // poisson delay, but is it really a problem?
+                let our_full_destination = self.config.our_keys.address();
                 let topology_permit = self.topology_access.get_read_permit().await;
                 // the ack is sent back to ourselves (and then ignored)
-                let topology_ref = match topology_permit.try_get_valid_topology_ref(
-                    &self.config.our_full_destination,
-                    Some(&self.config.our_full_destination),
-                ) {
+                let topology_ref = match topology_permit
+                    .try_get_valid_topology_ref(&our_full_destination, Some(&our_full_destination))
+                {
                     Ok(topology) => topology,
                     Err(err) => {
                         warn!("We're not going to send any loop cover message this time, as the current topology seem to be invalid - {err}");
@@ -246,7 +362,7 @@ where
                         &mut self.rng,
                         topology_ref,
                         &self.config.ack_key,
-                        &self.config.our_full_destination,
+                        &our_full_destination,
                         self.config.average_ack_delay,
                         self.config.traffic.average_packet_delay,
                         cover_traffic_packet_size,
@@ -303,8 +419,10 @@ where
 
     fn on_close_connection(&mut self, connection_id: ConnectionId) {
         log::debug!("Removing lane for connection: {connection_id}");
-        self.transmission_buffer
-            .remove(&TransmissionLane::ConnectionId(connection_id));
+        let lane = TransmissionLane::ConnectionId(connection_id);
+        self.transmission_buffer.remove(&lane);
+        self.rate_limiter.remove(&lane);
+        self.lane_queue_lengths.remove_connection(connection_id);
     }
 
     fn current_average_message_sending_delay(&self) -> Duration {
@@ -327,11 +445,21 @@ where
             self.sending_delay_controller.record_backpressure_detected();
         }
 
+        // The gateway asking us to slow down is treated just like running out of the buffer space.
+        // The request is only consumed once we're actually able to act upon it.
+        let can_increase_delay = self.sending_delay_controller.not_increased_delay_recently();
+        let slowdown_requested = can_increase_delay
+            && self
+                .protocol_stats
+                .as_ref()
+                .is_some_and(|stats| stats.take_slowdown_request());
+        if slowdown_requested {
+            self.sending_delay_controller.record_backpressure_detected();
+        }
+
         // If the buffer is running out, slow down the sending rate by increasing the delay
         // multiplier.
-        if self.mix_tx.capacity() == 0
-            && self.sending_delay_controller.not_increased_delay_recently()
-        {
+        if (self.mix_tx.capacity() == 0 || slowdown_requested) && can_increase_delay {
             self.sending_delay_controller.increase_delay_multiplier();
         }
 
@@ -350,15 +478,31 @@ where
     }
 
     fn pop_next_message(&mut self) -> Option<RealMessage> {
-        // Pop the next message from the transmission buffer
-        let (lane, real_next) = self
-            .transmission_buffer
-            .pop_next_message_at_random(&mut self.rng)?;
-
-        // Update the published queue length
+        // Pop the next message from the transmission buffer. If the network is transitioning
+        // between epochs, only send urgent messages as the active set might be about to change.
+        // Any held messages will get sent out once the topology gets refreshed.
+        // Lanes that have exceeded their rate limits are skipped until they recover.
+        // Otherwise the high priority lanes are served first. Note that this only affects which
+        // message is sent next, the overall sending rate stays the same.
+        let (lane, real_next) = pop_next_scheduled_message(
+            &mut self.transmission_buffer,
+            &mut self.rate_limiter,
+            &self.lane_queue_lengths,
+            &mut self.rng,
+            self.topology_access.is_in_epoch_transition(),
+        )?;
+
+        // Update the published queue length and the used bandwidth
         let lane_length = self.transmission_buffer.lane_length(&lane);
         self.lane_queue_lengths.set(&lane, lane_length);
 
+        let packet_size = real_next.packet_size();
+        self.rate_limiter.record_sent(&lane, packet_size);
+        if let TransmissionLane::ConnectionId(connection_id) = lane {
+            self.lane_queue_lengths
+                .record_sent(connection_id, packet_size);
+        }
+
         // This is the last step in the pipeline where we know the type of the message, so
         // lets count the number of retransmissions and reply surb messages sent here.
         let stat_event = match lane {
@@ -370,7 +514,10 @@ where
             TransmissionLane::AdditionalReplySurbs => {
                 Some(PacketStatisticsEvent::AdditionalReplySurbRequestQueued)
             }
-            TransmissionLane::Retransmission => Some(PacketStatisticsEvent::RetransmissionQueued),
+            TransmissionLane::Retransmission => {
+                inc!("retransmissions_queued");
+                Some(PacketStatisticsEvent::RetransmissionQueued)
+            }
         };
         if let Some(stat_event) = stat_event {
             self.stats_tx.report(stat_event);
@@ -380,6 +527,15 @@ where
         // even though we also track the actual number of messages sent later in the pipeline.
         self.stats_tx
             .report(PacketStatisticsEvent::RealPacketQueued);
+        if let Some(real_traffic_counter) = &self.real_traffic_counter {
+            real_traffic_counter.record_sent();
+        }
+
+        if let Some(protocol_stats) = &self.protocol_stats {
+            protocol_stats
+                .record_real_packet_queued(matches!(lane, TransmissionLane::Retransmission));
+            protocol_stats.set_queue_depth(self.transmission_buffer.total_size());
+        }
 
         Some(real_next)
     }
@@ -407,12 +563,12 @@ where
 
             // we know it's time to send a message, so let's prepare delay for the next one
             // Get the `now` by looking at the current `delay` deadline
-            let next_poisson_delay = sample_poisson_duration(&mut self.rng, avg_delay);
+            let next_scheduled_delay = self.scheduler.next_delay(&mut self.rng, avg_delay);
 
-            // The next interval value is `next_poisson_delay` after the one that just
+            // The next interval value is `next_scheduled_delay` after the one that just
             // yielded.
             let now = next_delay.deadline();
-            let next = now + next_poisson_delay;
+            let next = now + next_scheduled_delay;
             next_delay.as_mut().reset(next);
 
             // On every iteration we get new messages from upstream. Given that these come bunched
@@ -428,9 +584,14 @@ where
                     log::trace!("handling real_messages: size: {}", real_messages.len());
 
                     self.transmission_buffer.store(&conn_id, real_messages);
-                    let real_next = self.pop_next_message().expect("Just stored one");
 
-                    Poll::Ready(Some(StreamMessage::Real(Box::new(real_next))))
+                    // note: we might not get anything back if we're currently holding
+                    // non-urgent traffic, in which case send cover instead
+                    if let Some(real_next) = self.pop_next_message() {
+                        Poll::Ready(Some(StreamMessage::Real(Box::new(real_next))))
+                    } else {
+                        Poll::Ready(Some(StreamMessage::Cover))
+                    }
                 }
 
                 Poll::Pending => {
@@ -446,7 +607,7 @@ where
             // we never set an initial delay - let's do it now
             cx.waker().wake_by_ref();
 
-            let sampled = sample_poisson_duration(
+            let sampled = self.scheduler.next_delay(
                 &mut self.rng,
                 self.config.traffic.message_sending_average_delay,
             );
@@ -476,21 +637,45 @@ where
 
                 // First store what we got for the given connection id
                 self.transmission_buffer.store(&conn_id, real_messages);
-                let real_next = self.pop_next_message().expect("we just added one");
 
-                Poll::Ready(Some(StreamMessage::Real(Box::new(real_next))))
+                // note: the lane might be currently rate-limited
+                if let Some(real_next) = self.pop_next_message() {
+                    Poll::Ready(Some(StreamMessage::Real(Box::new(real_next))))
+                } else {
+                    self.schedule_throttled_lanes_check(cx);
+                    Poll::Pending
+                }
             }
 
             Poll::Pending => {
                 if let Some(real_next) = self.pop_next_message() {
                     Poll::Ready(Some(StreamMessage::Real(Box::new(real_next))))
                 } else {
+                    self.schedule_throttled_lanes_check(cx);
                     Poll::Pending
                 }
             }
         }
     }
 
+    // Without the poisson delay nothing else would wake us up to send the messages
+    // held back by the rate limits.
+    fn schedule_throttled_lanes_check(&mut self, cx: &mut Context<'_>) {
+        if self.transmission_buffer.total_size() == 0 {
+            self.throttled_lanes_check = None;
+            return;
+        }
+
+        let check = self
+            .throttled_lanes_check
+            .get_or_insert_with(|| Box::pin(sleep(THROTTLED_LANES_CHECK_INTERVAL)));
+        if check.as_mut().poll(cx).is_ready() {
+            // we're going to get polled again straight away
+            self.throttled_lanes_check = None;
+            cx.waker().wake_by_ref();
+        }
+    }
+
     fn poll_next_message(
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -593,3 +778,64 @@ where
         self.poll_next_message(cx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pop_all(
+        buffer: &mut TransmissionBuffer<usize>,
+        rate_limiter: &mut LaneRateLimiter,
+        lane_queue_lengths: &LaneQueueLengths,
+        urgent_only: bool,
+    ) -> Vec<TransmissionLane> {
+        let rng = &mut rand::thread_rng();
+        let mut lanes = Vec::new();
+        while let Some((lane, size)) =
+            pop_next_scheduled_message(buffer, rate_limiter, lane_queue_lengths, rng, urgent_only)
+        {
+            rate_limiter.record_sent(&lane, size);
+            lanes.push(lane);
+        }
+        lanes
+    }
+
+    #[test]
+    fn rate_limited_lanes_are_skipped_once_exhausted() {
+        let limited = TransmissionLane::ConnectionId(1);
+        let unlimited = TransmissionLane::ConnectionId(2);
+        let lane_queue_lengths = LaneQueueLengths::new();
+        lane_queue_lengths.set_rate_limit(limited, Some(2000));
+
+        // each "message" is 1000 bytes in size
+        let mut buffer = TransmissionBuffer::new();
+        buffer.store(&limited, [1000; 10]);
+        buffer.store(&unlimited, [1000; 10]);
+
+        let mut rate_limiter = LaneRateLimiter::default();
+        let sent = pop_all(&mut buffer, &mut rate_limiter, &lane_queue_lengths, false);
+
+        // the limited lane can only go a single packet into debt beyond its burst allowance
+        assert_eq!(sent.iter().filter(|&&lane| lane == unlimited).count(), 10);
+        assert_eq!(sent.iter().filter(|&&lane| lane == limited).count(), 3);
+        assert_eq!(buffer.lane_length(&limited), Some(7));
+
+        // and it resumes as soon as the limit is lifted
+        lane_queue_lengths.set_rate_limit(limited, None);
+        let sent = pop_all(&mut buffer, &mut rate_limiter, &lane_queue_lengths, false);
+        assert_eq!(sent, vec![limited; 7]);
+    }
+
+    #[test]
+    fn only_urgent_lanes_are_served_during_epoch_transition() {
+        let lane_queue_lengths = LaneQueueLengths::new();
+        let mut buffer = TransmissionBuffer::new();
+        buffer.store(&TransmissionLane::General, [1000; 2]);
+        buffer.store(&TransmissionLane::ReplySurbRequest, [1000; 2]);
+
+        let mut rate_limiter = LaneRateLimiter::default();
+        let sent = pop_all(&mut buffer, &mut rate_limiter, &lane_queue_lengths, true);
+        assert_eq!(sent, vec![TransmissionLane::ReplySurbRequest; 2]);
+        assert_eq!(buffer.lane_length(&TransmissionLane::General), Some(2));
+    }
+}
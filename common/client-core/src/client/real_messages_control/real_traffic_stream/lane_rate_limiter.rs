@@ -0,0 +1,107 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::helpers::{get_time_now, Instant};
+use nym_task::connections::TransmissionLane;
+use std::collections::{HashMap, HashSet};
+
+// Token bucket of a single lane. It is allowed to go into debt so that lanes limited to fewer bytes
+// per second than a single packet can still make progress.
+struct TokenBucket {
+    available: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(bytes_per_second: u64) -> Self {
+        TokenBucket {
+            // allow for a burst of at most a second worth of data
+            available: bytes_per_second as f64,
+            last_refill: get_time_now(),
+        }
+    }
+
+    fn refill(&mut self, bytes_per_second: u64) {
+        let now = get_time_now();
+        let elapsed = (now - self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.available =
+            (self.available + elapsed * bytes_per_second as f64).min(bytes_per_second as f64);
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.available < 0.
+    }
+}
+
+/// Enforces the per-lane sending rate limits imposed by the upstream via the `LaneQueueLengths`.
+#[derive(Default)]
+pub(crate) struct LaneRateLimiter {
+    buckets: HashMap<TransmissionLane, TokenBucket>,
+}
+
+impl LaneRateLimiter {
+    /// Returns the set of lanes that have currently exhausted their sending budget.
+    pub(crate) fn throttled_lanes<'a>(
+        &mut self,
+        lanes: impl Iterator<Item = &'a TransmissionLane>,
+        limits: &HashMap<TransmissionLane, u64>,
+    ) -> HashSet<TransmissionLane> {
+        // forget about the lanes whose limits got lifted
+        self.buckets.retain(|lane, _| limits.contains_key(lane));
+        if limits.is_empty() {
+            return HashSet::new();
+        }
+
+        lanes
+            .filter(|lane| {
+                let Some(&limit) = limits.get(lane) else {
+                    return false;
+                };
+                let bucket = self
+                    .buckets
+                    .entry(**lane)
+                    .or_insert_with(|| TokenBucket::new(limit));
+                bucket.refill(limit);
+                bucket.is_exhausted()
+            })
+            .copied()
+            .collect()
+    }
+
+    pub(crate) fn record_sent(&mut self, lane: &TransmissionLane, bytes: usize) {
+        if let Some(bucket) = self.buckets.get_mut(lane) {
+            bucket.available -= bytes as f64;
+        }
+    }
+
+    pub(crate) fn remove(&mut self, lane: &TransmissionLane) {
+        self.buckets.remove(lane);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_limited_lanes_get_throttled() {
+        let limited = TransmissionLane::ConnectionId(1);
+        let unlimited = TransmissionLane::ConnectionId(2);
+        let lanes = [limited, unlimited];
+        let limits = HashMap::from([(limited, 1000)]);
+
+        let mut limiter = LaneRateLimiter::default();
+        assert!(limiter.throttled_lanes(lanes.iter(), &limits).is_empty());
+
+        limiter.record_sent(&limited, 2048);
+        limiter.record_sent(&unlimited, 2048);
+        let throttled = limiter.throttled_lanes(lanes.iter(), &limits);
+        assert_eq!(throttled, HashSet::from([limited]));
+
+        // lifting the limit makes the lane available again straight away
+        assert!(limiter
+            .throttled_lanes(lanes.iter(), &HashMap::new())
+            .is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Measurement of the rate at which real packets are being sent out, so that the loop cover traffic
+//! stream could only fill in whatever the real traffic leaves over rather than always sending
+//! at its full rate.
+
+use crate::client::helpers::Instant;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Minimum amount of time between two measurements of the real traffic rate.
+const MIN_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Weight of the most recent measurement in the smoothed real traffic rate.
+const RATE_SMOOTHING: f64 = 0.3;
+
+/// Upper bound on the adapted cover traffic delay, so that the stream would still notice
+/// the real traffic stopping within a reasonable amount of time.
+const MAX_ADAPTED_DELAY: Duration = Duration::from_secs(30);
+
+/// Number of real packets sent, shared between the real traffic stream and the cover traffic stream.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RealTrafficCounter {
+    sent: Arc<AtomicU64>,
+}
+
+impl RealTrafficCounter {
+    pub(crate) fn new() -> Self {
+        Default::default()
+    }
+
+    pub(crate) fn record_sent(&self) {
+        self.sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn sent(&self) -> u64 {
+        self.sent.load(Ordering::Relaxed)
+    }
+}
+
+/// Adapts the loop cover traffic rate to the (smoothed) rate of the real traffic.
+#[derive(Debug)]
+pub(crate) struct AdaptiveCoverRate {
+    counter: RealTrafficCounter,
+    minimum_ratio: f64,
+    last_sample: Option<(Instant, u64)>,
+
+    // in packets per second
+    real_rate: f64,
+}
+
+impl AdaptiveCoverRate {
+    pub(crate) fn new(counter: RealTrafficCounter, minimum_ratio: f64) -> Self {
+        AdaptiveCoverRate {
+            counter,
+            minimum_ratio: minimum_ratio.clamp(0.0, 1.0),
+            last_sample: None,
+            real_rate: 0.0,
+        }
+    }
+
+    fn sample(&mut self, now: Instant) {
+        let sent = self.counter.sent();
+        let Some((sampled_at, previously_sent)) = self.last_sample else {
+            self.last_sample = Some((now, sent));
+            return;
+        };
+
+        let elapsed = now.duration_since(sampled_at);
+        if elapsed < MIN_SAMPLE_INTERVAL {
+            return;
+        }
+
+        let rate = sent.saturating_sub(previously_sent) as f64 / elapsed.as_secs_f64();
+        self.real_rate = RATE_SMOOTHING * rate + (1.0 - RATE_SMOOTHING) * self.real_rate;
+        self.last_sample = Some((now, sent));
+    }
+
+    /// Determines the average delay between the loop cover packets so that, combined with
+    /// the real traffic, the packets would be sent at the rate implied by the `target_delay`.
+    pub(crate) fn average_delay(&mut self, target_delay: Duration, now: Instant) -> Duration {
+        self.sample(now);
+        if target_delay.is_zero() {
+            return target_delay;
+        }
+
+        let target_rate = 1.0 / target_delay.as_secs_f64();
+        let cover_rate = (target_rate - self.real_rate).max(target_rate * self.minimum_ratio);
+        if cover_rate <= 0.0 {
+            return MAX_ADAPTED_DELAY;
+        }
+        Duration::from_secs_f64(1.0 / cover_rate)
+            .min(MAX_ADAPTED_DELAY)
+            .max(target_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cover_rate_makes_up_for_missing_real_traffic() {
+        let counter = RealTrafficCounter::new();
+        let mut adaptive = AdaptiveCoverRate::new(counter.clone(), 0.1);
+        let target = Duration::from_millis(100);
+        let start = Instant::now();
+
+        // without any real traffic, the cover traffic is sent at the full rate
+        assert_eq!(adaptive.average_delay(target, start), target);
+        let idle = adaptive.average_delay(target, start + Duration::from_secs(1));
+        assert_eq!(idle, target);
+
+        // real traffic at the target rate pushes the cover traffic down to its minimum
+        let mut now = start + Duration::from_secs(1);
+        for _ in 0..20 {
+            for _ in 0..10 {
+                counter.record_sent();
+            }
+            now += Duration::from_secs(1);
+            adaptive.average_delay(target, now);
+        }
+        let busy = adaptive.average_delay(target, now);
+        assert!(busy > Duration::from_millis(900), "{busy:?}");
+        assert!(busy <= Duration::from_secs(1), "{busy:?}");
+
+        // and once the real traffic stops, the cover traffic picks up again
+        for _ in 0..20 {
+            now += Duration::from_secs(1);
+            adaptive.average_delay(target, now);
+        }
+        let recovered = adaptive.average_delay(target, now);
+        assert!(recovered < Duration::from_millis(110), "{recovered:?}");
+    }
+}
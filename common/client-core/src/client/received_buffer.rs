@@ -2,17 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::client::{
+    diagnostics::EchoProbes,
+    inbox::{InboxMessageId, InboxStorage},
+    key_manager::ManagedKeys,
     packet_statistics_control::{PacketStatisticsEvent, PacketStatisticsReporter},
     replies::{reply_controller::ReplyControllerSender, reply_storage::SentReplyKeys},
+    streams::{IncomingStreams, ReconstructedStreamSender},
 };
 use crate::spawn_future;
 use futures::channel::mpsc;
 use futures::lock::Mutex;
 use futures::StreamExt;
 use log::*;
-use nym_crypto::asymmetric::encryption;
 use nym_crypto::Digest;
 use nym_gateway_client::MixnetMessageReceiver;
+use nym_metrics::{inc, inc_by};
 use nym_sphinx::anonymous_replies::requests::{
     RepliableMessage, RepliableMessageContent, ReplyMessage, ReplyMessageContent,
 };
@@ -32,9 +36,9 @@ pub type ReceivedBufferRequestReceiver = mpsc::UnboundedReceiver<ReceivedBufferM
 pub type ReconstructedMessagesSender = mpsc::UnboundedSender<Vec<ReconstructedMessage>>;
 pub type ReconstructedMessagesReceiver = mpsc::UnboundedReceiver<Vec<ReconstructedMessage>>;
 
-struct ReceivedMessagesBufferInner<R: MessageReceiver> {
+struct ReceivedMessagesBufferInner<R: MessageReceiver, S> {
     messages: Vec<ReconstructedMessage>,
-    local_encryption_keypair: Arc<encryption::KeyPair>,
+    local_encryption_keys: ManagedKeys,
 
     // TODO: looking how it 'looks' here, perhaps `MessageReceiver` should be renamed to something
     // else instead.
@@ -46,15 +50,19 @@ struct ReceivedMessagesBufferInner<R: MessageReceiver> {
     // and every now and then remove ids older than X
     recently_reconstructed: HashSet<i32>,
 
+    // optional persistent storage of messages that have not yet been acknowledged by the consumer
+    inbox: S,
+
     stats_tx: PacketStatisticsReporter,
 }
 
-impl<R: MessageReceiver> ReceivedMessagesBufferInner<R> {
+impl<R: MessageReceiver, S: InboxStorage> ReceivedMessagesBufferInner<R, S> {
+    // returns the reconstructed message alongside the id of the first fragment set it was made of
     fn recover_from_fragment(
         &mut self,
         fragment_data: &[u8],
         fragment_data_size: usize,
-    ) -> Option<NymMessage> {
+    ) -> Option<(NymMessage, i32)> {
         if nym_sphinx::cover::is_cover(fragment_data) {
             trace!("The message was a loop cover message! Skipping it");
             // NOTE: it's important to note that there is quite a bit of difference in size of
@@ -67,6 +75,7 @@ impl<R: MessageReceiver> ReceivedMessagesBufferInner<R> {
             return None;
         }
 
+        inc!("received_buffer_fragments");
         self.stats_tx
             .report(PacketStatisticsEvent::RealPacketReceived(
                 fragment_data_size,
@@ -75,6 +84,7 @@ impl<R: MessageReceiver> ReceivedMessagesBufferInner<R> {
         let fragment = match self.message_receiver.recover_fragment(fragment_data) {
             Err(err) => {
                 warn!("failed to recover fragment from raw data: {err}. The whole underlying message might be corrupted and unrecoverable!");
+                inc!("received_buffer_recovery_failures");
                 return None;
             }
             Ok(frag) => frag,
@@ -90,6 +100,7 @@ impl<R: MessageReceiver> ReceivedMessagesBufferInner<R> {
             Err(err) => match err {
                 MessageRecoveryError::MalformedReconstructedMessage { source, used_sets } => {
                     error!("message reconstruction failed - {source}. Attempting to re-use the message sets...");
+                    inc!("received_buffer_recovery_failures");
                     // TODO: should we really insert reconstructed sets? could this be abused for some attack?
                     for set_id in used_sets {
                         if !self.recently_reconstructed.insert(set_id) {
@@ -105,13 +116,14 @@ impl<R: MessageReceiver> ReceivedMessagesBufferInner<R> {
             },
             Ok(reconstruction_result) => match reconstruction_result {
                 Some((reconstructed_message, used_sets)) => {
+                    let first_set_id = *used_sets.first()?;
                     for set_id in used_sets {
                         if !self.recently_reconstructed.insert(set_id) {
                             // or perhaps we should even panic at this point?
                             error!("Reconstructed another message containing already used set id!")
                         }
                     }
-                    Some(reconstructed_message)
+                    Some((reconstructed_message, first_set_id))
                 }
                 None => None,
             },
@@ -122,7 +134,7 @@ impl<R: MessageReceiver> ReceivedMessagesBufferInner<R> {
         &mut self,
         reply_ciphertext: &mut [u8],
         reply_key: SurbEncryptionKey,
-    ) -> Result<Option<NymMessage>, MessageRecoveryError> {
+    ) -> Result<Option<(NymMessage, i32)>, MessageRecoveryError> {
         let reply_ciphertext_size = reply_ciphertext.len();
         // note: this performs decryption IN PLACE without extra allocation
         self.message_receiver
@@ -132,50 +144,159 @@ impl<R: MessageReceiver> ReceivedMessagesBufferInner<R> {
         Ok(self.recover_from_fragment(fragment_data, reply_ciphertext_size))
     }
 
-    fn process_received_regular_packet(&mut self, mut raw_fragment: Vec<u8>) -> Option<NymMessage> {
+    fn process_received_regular_packet(
+        &mut self,
+        mut raw_fragment: Vec<u8>,
+    ) -> Option<(NymMessage, i32)> {
         let raw_fragment_size = raw_fragment.len();
-        let fragment_data = match self.message_receiver.recover_plaintext_from_regular_packet(
-            self.local_encryption_keypair.private_key(),
-            &mut raw_fragment,
-        ) {
+        let (current_keys, retired_keys) = self.local_encryption_keys.decryption_keys();
+
+        // if our keys have been rotated recently, the packet might have been meant for the previous ones.
+        // the decryption happens in place, so we have to hold onto the original ciphertext
+        let retired_attempt = retired_keys.map(|keys| (keys, raw_fragment.clone()));
+
+        let fragment_data = match self
+            .message_receiver
+            .recover_plaintext_from_regular_packet(current_keys.private_key(), &mut raw_fragment)
+        {
             Err(err) => {
                 warn!("failed to recover fragment data: {err}. The whole underlying message might be corrupted and unrecoverable!");
+                inc!("received_buffer_recovery_failures");
                 return None;
             }
             Ok(frag_data) => frag_data,
         };
 
+        if let Some((retired_keys, mut retained_fragment)) = retired_attempt {
+            if !self.is_plausible_plaintext(fragment_data) {
+                if let Ok(retired_data) =
+                    self.message_receiver.recover_plaintext_from_regular_packet(
+                        retired_keys.private_key(),
+                        &mut retained_fragment,
+                    )
+                {
+                    if self.is_plausible_plaintext(retired_data) {
+                        trace!("received a packet encrypted with our previous encryption keys");
+                        return self.recover_from_fragment(retired_data, raw_fragment_size);
+                    }
+                }
+            }
+        }
+
         self.recover_from_fragment(fragment_data, raw_fragment_size)
     }
+
+    // there's no authentication of the packet payload, so the only way of telling whether we have used
+    // the right key is checking whether the result makes any sense
+    fn is_plausible_plaintext(&self, fragment_data: &[u8]) -> bool {
+        nym_sphinx::cover::is_cover(fragment_data)
+            || self
+                .message_receiver
+                .recover_fragment(fragment_data)
+                .is_ok()
+    }
+
+    // persists the messages in the inbox (if enabled) and removes any duplicates
+    // of messages that are still awaiting acknowledgement
+    async fn persist_in_inbox(
+        &mut self,
+        messages: Vec<ReconstructedMessage>,
+    ) -> Vec<ReconstructedMessage> {
+        if !self.inbox.is_enabled() {
+            return messages;
+        }
+
+        let mut new_messages = Vec::with_capacity(messages.len());
+        for message in messages {
+            let id = InboxMessageId::for_message(&message);
+            match self.inbox.contains(id).await {
+                Ok(true) => {
+                    debug!("received a duplicate of inbox message {id} - ignoring it");
+                    continue;
+                }
+                Ok(false) => {}
+                Err(err) => error!("failed to check inbox for message {id}: {err}"),
+            }
+
+            // even if we failed to persist the message, it's still better to deliver it
+            // rather than to silently drop it
+            if let Err(err) = self.inbox.store(id, &message).await {
+                error!("failed to persist message {id} in the inbox: {err}")
+            }
+            new_messages.push(message)
+        }
+        new_messages
+    }
 }
 
-#[derive(Debug, Clone)]
 // Note: you should NEVER create more than a single instance of this using 'new()'.
 // You should always use .clone() to create additional instances
-struct ReceivedMessagesBuffer<R: MessageReceiver> {
-    inner: Arc<Mutex<ReceivedMessagesBufferInner<R>>>,
+struct ReceivedMessagesBuffer<R: MessageReceiver, S> {
+    inner: Arc<Mutex<ReceivedMessagesBufferInner<R, S>>>,
     reply_key_storage: SentReplyKeys,
     reply_controller_sender: ReplyControllerSender,
+    echo_probes: EchoProbes,
+    streams: IncomingStreams,
+}
+
+// manual implementation as we don't want to require the inbox itself to be `Clone`
+impl<R: MessageReceiver, S> Clone for ReceivedMessagesBuffer<R, S> {
+    fn clone(&self) -> Self {
+        ReceivedMessagesBuffer {
+            inner: Arc::clone(&self.inner),
+            reply_key_storage: self.reply_key_storage.clone(),
+            reply_controller_sender: self.reply_controller_sender.clone(),
+            echo_probes: self.echo_probes.clone(),
+            streams: self.streams.clone(),
+        }
+    }
 }
 
-impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
+impl<R: MessageReceiver, S: InboxStorage> ReceivedMessagesBuffer<R, S> {
     fn new(
-        local_encryption_keypair: Arc<encryption::KeyPair>,
+        local_encryption_keys: ManagedKeys,
         reply_key_storage: SentReplyKeys,
         reply_controller_sender: ReplyControllerSender,
+        inbox: S,
         stats_tx: PacketStatisticsReporter,
+        echo_probes: EchoProbes,
     ) -> Self {
         ReceivedMessagesBuffer {
             inner: Arc::new(Mutex::new(ReceivedMessagesBufferInner {
                 messages: Vec::new(),
-                local_encryption_keypair,
+                local_encryption_keys,
                 message_receiver: R::new(),
                 message_sender: None,
                 recently_reconstructed: HashSet::new(),
+                inbox,
                 stats_tx,
             })),
             reply_key_storage,
             reply_controller_sender,
+            echo_probes,
+            streams: IncomingStreams::default(),
+        }
+    }
+
+    // put any messages that were not acknowledged before the client got stopped
+    // back in the buffer so that they'd get redelivered
+    async fn restore_unacknowledged(&mut self) {
+        let mut guard = self.inner.lock().await;
+        match guard.inbox.pending().await {
+            Ok(pending) => {
+                if !pending.is_empty() {
+                    info!("restoring {} unacknowledged inbox messages", pending.len());
+                    guard.messages.extend(pending)
+                }
+            }
+            Err(err) => error!("failed to load unacknowledged inbox messages: {err}"),
+        }
+    }
+
+    async fn acknowledge(&mut self, id: InboxMessageId) {
+        let guard = self.inner.lock().await;
+        if let Err(err) = guard.inbox.remove(id).await {
+            error!("failed to remove acknowledged message {id} from the inbox: {err}")
         }
     }
 
@@ -219,17 +340,19 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
 
     fn handle_reconstructed_plain_messages(
         &mut self,
-        msgs: Vec<PlainMessage>,
+        msgs: Vec<(PlainMessage, i32)>,
     ) -> Vec<ReconstructedMessage> {
-        msgs.into_iter().map(Into::into).collect()
+        msgs.into_iter()
+            .map(|(msg, set_id)| ReconstructedMessage::from(msg).with_fragment_set_id(set_id))
+            .collect()
     }
 
     fn handle_reconstructed_repliable_messages(
         &mut self,
-        msgs: Vec<RepliableMessage>,
+        msgs: Vec<(RepliableMessage, i32)>,
     ) -> Vec<ReconstructedMessage> {
         let mut reconstructed = Vec::new();
-        for msg in msgs {
+        for (msg, set_id) in msgs {
             let (reply_surbs, from_surb_request) = match msg.content {
                 RepliableMessageContent::Data {
                     message,
@@ -241,7 +364,10 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
                         msg.sender_tag
                     );
 
-                    reconstructed.push(ReconstructedMessage::new(message, msg.sender_tag));
+                    reconstructed.push(
+                        ReconstructedMessage::new(message, msg.sender_tag)
+                            .with_fragment_set_id(set_id),
+                    );
 
                     (reply_surbs, false)
                 }
@@ -272,12 +398,13 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
 
     fn handle_reconstructed_reply_messages(
         &mut self,
-        msgs: Vec<ReplyMessage>,
+        msgs: Vec<(ReplyMessage, i32)>,
     ) -> Vec<ReconstructedMessage> {
         let mut reconstructed = Vec::new();
-        for msg in msgs {
+        for (msg, set_id) in msgs {
             match msg.content {
-                ReplyMessageContent::Data { message } => reconstructed.push(message.into()),
+                ReplyMessageContent::Data { message } => reconstructed
+                    .push(ReconstructedMessage::from(message).with_fragment_set_id(set_id)),
                 ReplyMessageContent::SurbRequest { recipient, amount } => {
                     debug!("received request for {amount} additional reply SURBs from {recipient}");
                     self.reply_controller_sender
@@ -288,7 +415,7 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
         reconstructed
     }
 
-    async fn handle_reconstructed_messages(&mut self, msgs: Vec<NymMessage>) {
+    async fn handle_reconstructed_messages(&mut self, msgs: Vec<(NymMessage, i32)>) {
         if msgs.is_empty() {
             return;
         }
@@ -297,11 +424,11 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
         let mut repliable_messages = Vec::new();
         let mut reply_messages = Vec::new();
 
-        for msg in msgs {
+        for (msg, set_id) in msgs {
             match msg {
-                NymMessage::Plain(plain) => plain_messages.push(plain),
-                NymMessage::Repliable(repliable) => repliable_messages.push(repliable),
-                NymMessage::Reply(reply) => reply_messages.push(reply),
+                NymMessage::Plain(plain) => plain_messages.push((plain, set_id)),
+                NymMessage::Repliable(repliable) => repliable_messages.push((repliable, set_id)),
+                NymMessage::Reply(reply) => reply_messages.push((reply, set_id)),
             }
         }
 
@@ -311,7 +438,18 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
         reconstructed_messages
             .append(&mut self.handle_reconstructed_reply_messages(reply_messages));
 
+        // the diagnostics echo messages are never meant to reach the user
+        reconstructed_messages.retain(|message| !self.echo_probes.try_complete(&message.message));
+
+        // and neither are the chunks of streams, they're forwarded to their reconstructed streams instead
+        reconstructed_messages.retain(|message| !self.streams.try_consume(message));
+
         let mut inner_guard = self.inner.lock().await;
+        let reconstructed_messages = inner_guard.persist_in_inbox(reconstructed_messages).await;
+        if reconstructed_messages.is_empty() {
+            return;
+        }
+
         debug!(
             "Adding {:?} new messages to the buffer!",
             reconstructed_messages.len()
@@ -361,6 +499,17 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
             msgs.len()
         );
 
+        // the gateway has recorded delivering the messages under the same ids
+        #[cfg(feature = "pcap")]
+        for msg in &msgs {
+            nym_pcap::record(
+                nym_pcap::EventKind::Received,
+                nym_pcap::PacketId::from_bytes(msg),
+                None,
+                msg.len(),
+            );
+        }
+
         let mut completed_messages = Vec::new();
         let mut inner_guard = self.inner.lock().await;
 
@@ -377,7 +526,7 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
                 };
 
             if let Some(completed) = completed_message {
-                debug!("received {completed}");
+                debug!("received {}", completed.0);
                 completed_messages.push(completed)
             }
         }
@@ -385,6 +534,10 @@ impl<R: MessageReceiver> ReceivedMessagesBuffer<R> {
         drop(inner_guard);
 
         if !completed_messages.is_empty() {
+            inc_by!(
+                "received_buffer_messages_reconstructed",
+                completed_messages.len()
+            );
             self.handle_reconstructed_messages(completed_messages).await
         }
         Ok(())
@@ -398,16 +551,24 @@ pub enum ReceivedBufferMessage {
 
     // Explicit signal that Receiver connection will no longer accept messages
     ReceiverDisconnect,
+
+    // Signal that the consumer has fully processed the message, so that it could be removed
+    // from the persistent inbox
+    Ack(InboxMessageId),
+
+    // Signals the consumer wants to receive the chunked streams as `ReconstructedStream`s
+    // rather than as the individual messages
+    StreamReceiverAnnounce(ReconstructedStreamSender),
 }
 
-struct RequestReceiver<R: MessageReceiver> {
-    received_buffer: ReceivedMessagesBuffer<R>,
+struct RequestReceiver<R: MessageReceiver, S> {
+    received_buffer: ReceivedMessagesBuffer<R, S>,
     query_receiver: ReceivedBufferRequestReceiver,
 }
 
-impl<R: MessageReceiver> RequestReceiver<R> {
+impl<R: MessageReceiver, S: InboxStorage> RequestReceiver<R, S> {
     fn new(
-        received_buffer: ReceivedMessagesBuffer<R>,
+        received_buffer: ReceivedMessagesBuffer<R, S>,
         query_receiver: ReceivedBufferRequestReceiver,
     ) -> Self {
         RequestReceiver {
@@ -424,11 +585,18 @@ impl<R: MessageReceiver> RequestReceiver<R> {
             ReceivedBufferMessage::ReceiverDisconnect => {
                 self.received_buffer.disconnect_sender().await
             }
+            ReceivedBufferMessage::Ack(id) => self.received_buffer.acknowledge(id).await,
+            ReceivedBufferMessage::StreamReceiverAnnounce(sender) => {
+                self.received_buffer.streams.set_listener(sender)
+            }
         }
     }
 
     async fn run_with_shutdown(&mut self, mut shutdown: nym_task::TaskClient) {
         debug!("Started RequestReceiver with graceful shutdown support");
+        // make sure the restored messages are buffered before any receiver gets announced
+        self.received_buffer.restore_unacknowledged().await;
+
         while !shutdown.is_shutdown() {
             tokio::select! {
                 biased;
@@ -450,14 +618,14 @@ impl<R: MessageReceiver> RequestReceiver<R> {
     }
 }
 
-struct FragmentedMessageReceiver<R: MessageReceiver> {
-    received_buffer: ReceivedMessagesBuffer<R>,
+struct FragmentedMessageReceiver<R: MessageReceiver, S> {
+    received_buffer: ReceivedMessagesBuffer<R, S>,
     mixnet_packet_receiver: MixnetMessageReceiver,
 }
 
-impl<R: MessageReceiver> FragmentedMessageReceiver<R> {
+impl<R: MessageReceiver, S: InboxStorage> FragmentedMessageReceiver<R, S> {
     fn new(
-        received_buffer: ReceivedMessagesBuffer<R>,
+        received_buffer: ReceivedMessagesBuffer<R, S>,
         mixnet_packet_receiver: MixnetMessageReceiver,
     ) -> Self {
         FragmentedMessageReceiver {
@@ -492,25 +660,34 @@ impl<R: MessageReceiver> FragmentedMessageReceiver<R> {
     }
 }
 
-pub(crate) struct ReceivedMessagesBufferController<R: MessageReceiver> {
-    fragmented_message_receiver: FragmentedMessageReceiver<R>,
-    request_receiver: RequestReceiver<R>,
+pub(crate) struct ReceivedMessagesBufferController<R: MessageReceiver, S> {
+    fragmented_message_receiver: FragmentedMessageReceiver<R, S>,
+    request_receiver: RequestReceiver<R, S>,
 }
 
-impl<R: MessageReceiver + Clone + Send + 'static> ReceivedMessagesBufferController<R> {
+impl<R, S> ReceivedMessagesBufferController<R, S>
+where
+    R: MessageReceiver + Clone + Send + 'static,
+    S: InboxStorage + Send + Sync + 'static,
+{
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
-        local_encryption_keypair: Arc<encryption::KeyPair>,
+        local_encryption_keys: ManagedKeys,
         query_receiver: ReceivedBufferRequestReceiver,
         mixnet_packet_receiver: MixnetMessageReceiver,
         reply_key_storage: SentReplyKeys,
         reply_controller_sender: ReplyControllerSender,
+        inbox: S,
         packet_statistics_reporter: PacketStatisticsReporter,
+        echo_probes: EchoProbes,
     ) -> Self {
         let received_buffer = ReceivedMessagesBuffer::new(
-            local_encryption_keypair,
+            local_encryption_keys,
             reply_key_storage,
             reply_controller_sender,
+            inbox,
             packet_statistics_reporter,
+            echo_probes,
         );
 
         ReceivedMessagesBufferController {
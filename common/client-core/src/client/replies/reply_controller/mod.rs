@@ -1,12 +1,17 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::delivery::DeliveryToken;
+use crate::client::events::{ClientEvent, ClientEventSender};
 use crate::client::real_messages_control::acknowledgement_control::PendingAcknowledgement;
 use crate::client::real_messages_control::message_handler::{MessageHandler, PreparationError};
-use crate::client::replies::reply_storage::CombinedReplyStorage;
+use crate::client::replies::reply_storage::{
+    CombinedReplyStorage, ReplyStorageSynchroniser, ReplySyncError, SurbHandover, SyncMergeSummary,
+};
 use futures::channel::oneshot;
 use futures::StreamExt;
 use log::{debug, error, info, trace, warn};
+use nym_metrics::inc;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nym_sphinx::anonymous_replies::ReplySurb;
@@ -15,30 +20,43 @@ use nym_task::connections::{ConnectionId, TransmissionLane};
 use rand::{CryptoRng, Rng};
 use std::cmp::{max, min};
 use std::collections::btree_map::Entry;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::{Arc, Weak};
 use std::time::Duration;
 use time::OffsetDateTime;
 
-use crate::client::helpers::new_interval_stream;
+use crate::client::helpers::{get_time_now, new_interval_stream};
 use crate::client::transmission_buffer::TransmissionBuffer;
 use crate::config;
 pub(crate) use requests::{ReplyControllerMessage, ReplyControllerReceiver, ReplyControllerSender};
+use surb_policy::DepletionTracker;
+pub use surb_policy::{SurbPolicy, SurbPoolMetrics, SurbPoolStats};
+use surb_requests::SurbRequestLimiter;
+pub use surb_requests::{SurbRequestPolicy, SurbRequestStats};
 
 pub mod requests;
+pub mod surb_policy;
+pub mod surb_requests;
 
 // this is still left as a separate config so I wouldn't need to replace it everywhere
 // plus its not unreasonable to think that we might need something outside config::ReplySurbs struct
 pub struct Config {
     reply_surbs: config::ReplySurbs,
+    client_events: ClientEventSender,
 }
 
 impl Config {
     pub(crate) fn new(reply_surbs_cfg: config::ReplySurbs) -> Self {
         Self {
             reply_surbs: reply_surbs_cfg,
+            client_events: Default::default(),
         }
     }
+
+    pub(crate) fn with_client_events(mut self, client_events: ClientEventSender) -> Self {
+        self.client_events = client_events;
+        self
+    }
 }
 
 // the purpose of this task:
@@ -68,6 +86,44 @@ pub struct ReplyController<R> {
 
     message_handler: MessageHandler<R>,
     full_reply_storage: CombinedReplyStorage,
+
+    /// Policy applied to all the senders that don't have an explicit policy set.
+    default_surb_policy: SurbPolicy,
+    surb_policies: HashMap<AnonymousSenderTag, SurbPolicy>,
+    surb_depletions: DepletionTracker,
+
+    /// Rate limiter of the requests for additional reply SURBs made by our correspondents.
+    surb_request_limiter: SurbRequestLimiter,
+
+    /// Synchronises the reply storage with other devices sharing our identity.
+    /// It's only available if the identity key is held locally.
+    storage_sync: Option<ReplyStorageSynchroniser>,
+}
+
+/// Determines how many additional reply surbs should be requested in order to send `queue_size`
+/// packets whilst keeping the pool at (or above) the policy thresholds or `None` if the surbs we already
+/// have (or are waiting for) are sufficient.
+fn reply_surbs_request_size(
+    queue_size: u32,
+    available_surbs: u32,
+    pending_surbs: u32,
+    policy: &SurbPolicy,
+    cfg: &config::ReplySurbs,
+) -> Option<u32> {
+    let min_surbs_threshold = policy.min_pool_size as u32;
+    let top_up_threshold = policy.top_up_threshold.unwrap_or_default() as u32;
+
+    // the request itself is going to use up one of our surbs
+    let required = max(queue_size + min_surbs_threshold, top_up_threshold) + 1;
+    let deficit = required.saturating_sub(available_surbs + pending_surbs);
+    if deficit == 0 {
+        return None;
+    }
+
+    Some(min(
+        cfg.maximum_reply_surb_request_size,
+        max(deficit, cfg.minimum_reply_surb_request_size),
+    ))
 }
 
 impl<R> ReplyController<R>
@@ -81,15 +137,47 @@ where
         request_receiver: ReplyControllerReceiver,
     ) -> Self {
         ReplyController {
+            default_surb_policy: SurbPolicy::from_config(&config.reply_surbs),
+            surb_request_limiter: SurbRequestLimiter::new(
+                SurbRequestPolicy::from_config(&config.reply_surbs),
+                get_time_now(),
+            ),
             config,
             request_receiver,
             pending_replies: HashMap::new(),
             pending_retransmissions: HashMap::new(),
             message_handler,
             full_reply_storage,
+            surb_policies: HashMap::new(),
+            surb_depletions: DepletionTracker::default(),
+            storage_sync: None,
         }
     }
 
+    #[must_use]
+    pub(crate) fn with_storage_synchroniser(
+        mut self,
+        storage_sync: Option<ReplyStorageSynchroniser>,
+    ) -> Self {
+        self.storage_sync = storage_sync;
+        self
+    }
+
+    fn surb_policy(&self, sender: &AnonymousSenderTag) -> SurbPolicy {
+        self.surb_policies
+            .get(sender)
+            .copied()
+            .unwrap_or(self.default_surb_policy)
+    }
+
+    fn record_surb_depletion(&mut self, sender: AnonymousSenderTag) {
+        debug!("the reply surb pool of {sender} got depleted");
+        self.config
+            .client_events
+            .emit(ClientEvent::SurbsExhausted { sender_tag: sender });
+        self.surb_depletions.record(sender)
+    }
+
     fn insert_pending_replies<I: IntoIterator<Item = Fragment>>(
         &mut self,
         recipient: &AnonymousSenderTag,
@@ -151,9 +239,10 @@ where
             .unwrap_or_default();
 
         let total_queue = pending_queue_size + retransmission_queue;
+        let policy = self.surb_policy(target);
 
-        // simple as that - there's absolutely nothing to retransmit
-        if total_queue == 0 {
+        // simple as that - there's absolutely nothing to retransmit and we don't want to top up the pool
+        if total_queue == 0 && policy.top_up_threshold.is_none() {
             return false;
         }
 
@@ -165,19 +254,16 @@ where
             .full_reply_storage
             .surbs_storage_ref()
             .pending_reception(target) as usize;
-        let min_surbs_threshold = self
-            .full_reply_storage
-            .surbs_storage_ref()
-            .min_surb_threshold();
-        let max_surbs_threshold = self
-            .full_reply_storage
-            .surbs_storage_ref()
-            .max_surb_threshold();
+        let min_surbs_threshold = policy.min_pool_size;
+        let max_surbs_threshold = policy.max_pool_size;
+        let top_up_threshold = policy.top_up_threshold.unwrap_or_default();
 
-        debug!("total queue size: {total_queue} = pending data {pending_queue_size} + pending retransmission {retransmission_queue}, available surbs: {available_surbs} pending surbs: {pending_surbs} threshold range: {min_surbs_threshold}..{max_surbs_threshold}");
+        debug!("total queue size: {total_queue} = pending data {pending_queue_size} + pending retransmission {retransmission_queue}, available surbs: {available_surbs} pending surbs: {pending_surbs} threshold range: {min_surbs_threshold}..{max_surbs_threshold}, top up threshold: {top_up_threshold}");
 
-        (pending_surbs + available_surbs) < max_surbs_threshold
-            && (pending_surbs + available_surbs) < (total_queue + min_surbs_threshold)
+        let pool = pending_surbs + available_surbs;
+        pool < max_surbs_threshold
+            && (pool < top_up_threshold
+                || (total_queue > 0 && pool < total_queue + min_surbs_threshold))
     }
 
     async fn handle_send_reply(
@@ -185,6 +271,7 @@ where
         recipient_tag: AnonymousSenderTag,
         data: Vec<u8>,
         lane: TransmissionLane,
+        delivery_token: Option<DeliveryToken>,
     ) {
         if !self
             .full_reply_storage
@@ -192,22 +279,30 @@ where
             .contains_surbs_for(&recipient_tag)
         {
             warn!("received reply request for {:?} but we don't have any surbs stored for that recipient!", recipient_tag);
+            if let Some(token) = delivery_token {
+                self.message_handler.report_undeliverable(token)
+            }
             return;
         }
 
         trace!("handling reply to {:?}", recipient_tag);
         let mut fragments = self.message_handler.split_reply_message(data);
         let total_size = fragments.len();
+        if let Some(token) = delivery_token {
+            // the fragments might end up being sent at a much later point (once we get more surbs),
+            // but either way they're going to be acknowledged as any other
+            self.message_handler.track_delivery(
+                token,
+                fragments.iter().map(|f| f.fragment_identifier()).collect(),
+            )
+        }
         trace!("This reply requires {:?} SURBs", total_size);
 
         let available_surbs = self
             .full_reply_storage
             .surbs_storage_ref()
             .available_surbs(&recipient_tag);
-        let min_surbs_threshold = self
-            .full_reply_storage
-            .surbs_storage_ref()
-            .min_surb_threshold();
+        let min_surbs_threshold = self.surb_policy(&recipient_tag).min_pool_size;
 
         let max_to_send = if available_surbs > min_surbs_threshold {
             min(fragments.len(), available_surbs - min_surbs_threshold)
@@ -215,11 +310,15 @@ where
             0
         };
 
+        if max_to_send < total_size {
+            self.record_surb_depletion(recipient_tag);
+        }
+
         if max_to_send > 0 {
             let (surbs, _surbs_left) = self
                 .full_reply_storage
                 .surbs_storage_ref()
-                .get_reply_surbs(&recipient_tag, max_to_send);
+                .get_reply_surbs_with_threshold(&recipient_tag, max_to_send, min_surbs_threshold);
 
             if let Some(reply_surbs) = surbs {
                 let to_send = fragments.drain(..max_to_send).collect::<Vec<_>>();
@@ -295,10 +394,7 @@ where
             .full_reply_storage
             .surbs_storage_ref()
             .available_surbs(&target);
-        let min_surbs_threshold = self
-            .full_reply_storage
-            .surbs_storage_ref()
-            .min_surb_threshold();
+        let min_surbs_threshold = self.surb_policy(&target).min_pool_size;
 
         let max_to_clear = if available_surbs > min_surbs_threshold {
             available_surbs - min_surbs_threshold
@@ -336,7 +432,7 @@ where
         let (surbs_for_reply, _) = self
             .full_reply_storage
             .surbs_storage_ref()
-            .get_reply_surbs(&target, to_take.len());
+            .get_reply_surbs_with_threshold(&target, to_take.len(), min_surbs_threshold);
 
         let Some(surbs_for_reply) = surbs_for_reply else {
             error!("somehow different task has stolen our reply surbs! - this should have been impossible");
@@ -395,10 +491,7 @@ where
             .full_reply_storage
             .surbs_storage_ref()
             .available_surbs(&target);
-        let min_surbs_threshold = self
-            .full_reply_storage
-            .surbs_storage_ref()
-            .min_surb_threshold();
+        let min_surbs_threshold = self.surb_policy(&target).min_pool_size;
 
         let max_to_clear = if available_surbs > min_surbs_threshold {
             available_surbs - min_surbs_threshold
@@ -421,7 +514,7 @@ where
             let (surbs_for_reply, _) = self
                 .full_reply_storage
                 .surbs_storage_ref()
-                .get_reply_surbs(&target, to_send_clone.len());
+                .get_reply_surbs_with_threshold(&target, to_send_clone.len(), min_surbs_threshold);
 
             let Some(surbs_for_reply) = surbs_for_reply else {
                 error!("somehow different task has stolen our reply surbs! - this should have been impossible");
@@ -482,14 +575,15 @@ where
     async fn handle_surb_request(&mut self, recipient: Recipient, mut amount: u32) {
         // 1. check whether we sent any surbs in the past to this recipient, otherwise
         // they have no business in asking for more
-        if !self
+        let Some(sender_tag) = self
             .full_reply_storage
             .tags_storage_ref()
-            .exists(&recipient)
-        {
+            .try_get_existing(&recipient)
+        else {
             warn!("{recipient} asked us for reply SURBs even though we never sent them any anonymous messages before!");
+            inc!("reply_surb_requests_unsolicited");
             return;
-        }
+        };
 
         // 2. check whether the requested amount is within sane range
         if amount
@@ -505,7 +599,18 @@ where
                 .maximum_allowed_reply_surb_request_size;
         }
 
-        // 3. construct and send the surbs away
+        // 3. make sure they're not making us flood the network with our surbs
+        inc!("reply_surb_requests_received");
+        let Some(allowed) = self
+            .surb_request_limiter
+            .admit(sender_tag, amount, get_time_now())
+        else {
+            inc!("reply_surb_requests_rejected");
+            return;
+        };
+        amount = allowed;
+
+        // 4. construct and send the surbs away
         // (send them in smaller batches to make the experience a bit smoother
         let mut remaining = amount;
         while remaining > 0 {
@@ -571,9 +676,10 @@ where
                 .surbs_storage_ref()
                 .get_reply_surb_ignoring_threshold(&recipient_tag)
         } else {
+            let min_surbs_threshold = self.surb_policy(&recipient_tag).min_pool_size;
             self.full_reply_storage
                 .surbs_storage_ref()
-                .get_reply_surb(&recipient_tag)
+                .get_reply_surb_with_threshold(&recipient_tag, min_surbs_threshold)
         }
         .expect("attempted to retransmit a packet to an unknown recipient - we shouldn't have sent the original packet in the first place!");
 
@@ -611,6 +717,7 @@ where
                 }
             };
         } else {
+            self.record_surb_depletion(recipient_tag);
             self.buffer_pending_ack(recipient_tag, ack_ref, timed_out_ack);
 
             if self.should_request_more_surbs(&recipient_tag) {
@@ -644,6 +751,134 @@ where
         }
     }
 
+    fn handle_available_reply_surbs(
+        &self,
+        sender_tag: AnonymousSenderTag,
+        response_channel: oneshot::Sender<Option<usize>>,
+    ) {
+        let surbs_storage = self.full_reply_storage.surbs_storage_ref();
+        let available = surbs_storage
+            .contains_surbs_for(&sender_tag)
+            .then(|| surbs_storage.available_surbs(&sender_tag));
+
+        if response_channel.send(available).is_err() {
+            error!("the requester for available reply surbs has dropped the response channel!")
+        }
+    }
+
+    fn handle_set_surb_policy(
+        &mut self,
+        sender_tag: AnonymousSenderTag,
+        policy: Option<SurbPolicy>,
+    ) {
+        match policy {
+            Some(policy) => {
+                debug!("setting reply surb policy of {sender_tag} to {policy:?}");
+                self.surb_policies.insert(sender_tag, policy.normalised());
+            }
+            None => {
+                debug!("resetting reply surb policy of {sender_tag} to the default");
+                self.surb_policies.remove(&sender_tag);
+            }
+        }
+    }
+
+    fn handle_set_default_surb_policy(&mut self, policy: SurbPolicy) {
+        debug!("setting the default reply surb policy to {policy:?}");
+        self.default_surb_policy = policy.normalised();
+    }
+
+    fn handle_set_surb_request_policy(&mut self, policy: SurbRequestPolicy) {
+        debug!("setting the reply surb request policy to {policy:?}");
+        self.surb_request_limiter.set_policy(policy);
+    }
+
+    fn handle_surb_pool_metrics(&self, response_channel: oneshot::Sender<SurbPoolMetrics>) {
+        let senders = self
+            .full_reply_storage
+            .surbs_storage_ref()
+            .as_raw_iter()
+            .map(|map_ref| {
+                let (sender, received) = map_ref.pair();
+                let (depletion_events, last_depleted_at) = self.surb_depletions.get(sender);
+                let stats = SurbPoolStats {
+                    available: received.surbs_ref().len(),
+                    pending_reception: received.pending_reception(),
+                    depletion_events,
+                    last_depleted_at,
+                    policy: self.surb_policy(sender),
+                };
+                (*sender, stats)
+            })
+            .collect();
+
+        let metrics = SurbPoolMetrics {
+            total_depletion_events: self.surb_depletions.total(),
+            senders,
+            surb_requests: self.surb_request_limiter.stats(),
+        };
+        if response_channel.send(metrics).is_err() {
+            error!("the requester for reply surb pool metrics has dropped the response channel!")
+        }
+    }
+
+    fn handle_export_storage_delta(
+        &mut self,
+        surb_handover: SurbHandover,
+        response_channel: oneshot::Sender<Result<Vec<u8>, ReplySyncError>>,
+    ) {
+        let Some(storage_sync) = self.storage_sync.as_mut() else {
+            let _ = response_channel.send(Err(ReplySyncError::unavailable(
+                "the identity key is not held locally",
+            )));
+            return;
+        };
+
+        let exported = storage_sync.export(surb_handover);
+        if let Err(Ok(exported)) = response_channel.send(exported) {
+            // the requester is gone, so put back any reply surbs we have taken out for them
+            error!("the requester for the reply storage delta has dropped the response channel!");
+            if let Err(err) = storage_sync.restore(&exported) {
+                warn!("failed to restore the unclaimed reply storage delta: {err}")
+            }
+        }
+    }
+
+    async fn handle_import_storage_delta(
+        &mut self,
+        delta: Vec<u8>,
+        response_channel: oneshot::Sender<Result<SyncMergeSummary, ReplySyncError>>,
+    ) {
+        let merged = match self.storage_sync.as_mut() {
+            Some(storage_sync) => storage_sync.import(&delta),
+            None => Err(ReplySyncError::unavailable(
+                "the identity key is not held locally",
+            )),
+        };
+        let new_reply_surbs = merged
+            .as_ref()
+            .map(|summary| summary.new_reply_surbs > 0)
+            .unwrap_or_default();
+
+        if response_channel.send(merged).is_err() {
+            error!("the requester for the reply storage import has dropped the response channel!")
+        }
+
+        if new_reply_surbs {
+            // the other device might have handed over the reply surbs our queues are waiting for
+            let targets = self
+                .pending_retransmissions
+                .keys()
+                .chain(self.pending_replies.keys())
+                .copied()
+                .collect::<HashSet<_>>();
+            for target in targets {
+                self.try_clear_pending_retransmission(target).await;
+                self.try_clear_pending_queue(target).await;
+            }
+        }
+    }
+
     async fn handle_request(&mut self, request: ReplyControllerMessage) {
         match request {
             ReplyControllerMessage::RetransmitReply {
@@ -658,7 +893,11 @@ where
                 recipient,
                 message,
                 lane,
-            } => self.handle_send_reply(recipient, message, lane).await,
+                delivery_token,
+            } => {
+                self.handle_send_reply(recipient, message, lane, delivery_token)
+                    .await
+            }
             ReplyControllerMessage::AdditionalSurbs {
                 sender_tag,
                 reply_surbs,
@@ -667,6 +906,22 @@ where
                 self.handle_received_surbs(sender_tag, reply_surbs, from_surb_request)
                     .await
             }
+            ReplyControllerMessage::AvailableReplySurbs {
+                sender_tag,
+                response_channel,
+            } => self.handle_available_reply_surbs(sender_tag, response_channel),
+            ReplyControllerMessage::SetSurbPolicy { sender_tag, policy } => {
+                self.handle_set_surb_policy(sender_tag, policy)
+            }
+            ReplyControllerMessage::SetSurbRequestPolicy { policy } => {
+                self.handle_set_surb_request_policy(policy)
+            }
+            ReplyControllerMessage::SetDefaultSurbPolicy { policy } => {
+                self.handle_set_default_surb_policy(policy)
+            }
+            ReplyControllerMessage::SurbPoolMetrics { response_channel } => {
+                self.handle_surb_pool_metrics(response_channel)
+            }
             ReplyControllerMessage::LaneQueueLength {
                 connection_id,
                 response_channel,
@@ -674,12 +929,30 @@ where
             ReplyControllerMessage::AdditionalSurbsRequest { recipient, amount } => {
                 self.handle_surb_request(*recipient, amount).await
             }
+            ReplyControllerMessage::ExportStorageDelta {
+                surb_handover,
+                response_channel,
+            } => self.handle_export_storage_delta(surb_handover, response_channel),
+            ReplyControllerMessage::ImportStorageDelta {
+                delta,
+                response_channel,
+            } => {
+                self.handle_import_storage_delta(delta, response_channel)
+                    .await
+            }
         }
     }
 
     // TODO: modify this method to more accurately determine the amount of surbs it needs to request
     // it should take into consideration the average latency, sending rate and queue size.
     // it should request as many surbs as it takes to saturate its sending rate before next batch arrives
+    /// Requests enough reply surbs to clear all the pending queues for the given target in one go.
+    ///
+    /// The requested amount accounts for the surbs we already hold or are waiting for, the minimum
+    /// threshold we must keep for ourselves and the surb consumed by the request itself,
+    /// so a reply of any size could be fully sent after a single round trip
+    /// (unless it exceeds the maximum request size, in which case further requests
+    /// are going to be made as the surbs arrive).
     async fn request_reply_surbs_for_queue_clearing(&mut self, target: AnonymousSenderTag) {
         trace!("requesting surbs for queues clearing");
 
@@ -696,19 +969,28 @@ where
             .unwrap_or_default();
 
         let total_queue = (pending_queue_size + retransmission_queue) as u32;
+        let policy = self.surb_policy(&target);
 
-        if total_queue == 0 {
+        if total_queue == 0 && policy.top_up_threshold.is_none() {
             trace!("the pending queues for {:?} are already empty", target);
             return;
         }
 
-        let request_size = min(
-            self.config.reply_surbs.maximum_reply_surb_request_size,
-            max(
-                total_queue,
-                self.config.reply_surbs.minimum_reply_surb_request_size,
-            ),
-        );
+        let surbs_storage = self.full_reply_storage.surbs_storage_ref();
+        let available_surbs = surbs_storage.available_surbs(&target) as u32;
+        let pending_surbs = surbs_storage.pending_reception(&target);
+
+        let Some(request_size) = reply_surbs_request_size(
+            total_queue,
+            available_surbs,
+            pending_surbs,
+            &policy,
+            &self.config.reply_surbs,
+        ) else {
+            trace!("we already have (or are waiting for) enough surbs to clear the queues for {target:?}");
+            return;
+        };
+        debug!("requesting {request_size} additional reply surbs from {target:?} (queue: {total_queue}, available: {available_surbs}, pending: {pending_surbs})");
 
         if let Err(err) = self
             .request_additional_reply_surbs(target, request_size)
@@ -719,6 +1001,8 @@ where
     }
 
     async fn inspect_stale_entries(&mut self) {
+        self.surb_request_limiter.prune(get_time_now());
+
         let mut to_request = Vec::new();
         let mut to_remove = Vec::new();
 
@@ -778,7 +1062,7 @@ where
         }
     }
 
-    async fn invalidate_old_data(&self) {
+    async fn invalidate_old_data(&mut self) {
         let now = OffsetDateTime::now_utc();
 
         let mut to_remove_surbs = Vec::new();
@@ -804,7 +1088,7 @@ where
             };
             let diff = now - last_received_time;
 
-            if diff > self.config.reply_surbs.maximum_reply_surb_age {
+            if diff > self.surb_policy(sender).max_surb_age {
                 info!("it's been {diff:?} since we last received any reply surb from {sender}. Going to remove all stored entries...");
 
                 to_remove_surbs.push(*sender);
@@ -835,11 +1119,19 @@ where
             self.full_reply_storage
                 .surbs_storage_ref()
                 .remove(&to_remove);
+            self.surb_policies.remove(&to_remove);
+            self.surb_depletions.remove(&to_remove);
         }
 
         for to_remove in to_remove_keys {
             self.full_reply_storage.key_storage().remove(to_remove)
         }
+
+        // information about used keys is only relevant for as long as the keys themselves
+        let used_keys_cutoff = now - self.config.reply_surbs.maximum_reply_key_age;
+        self.full_reply_storage
+            .key_storage_ref()
+            .prune_used(used_keys_cutoff.unix_timestamp());
     }
 
     // #[cfg(not(target_arch = "wasm32"))]
@@ -883,3 +1175,191 @@ where
         log::debug!("ReplyController: Exiting");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::key_manager::{ClientKeys, ManagedKeys};
+    use crate::client::real_messages_control::message_handler;
+    use crate::client::replies::reply_controller::requests::new_control_channels;
+    use crate::client::topology_control::TopologyAccessor;
+    use nym_crypto::asymmetric::identity;
+    use rand::rngs::OsRng;
+
+    fn test_controller() -> ReplyController<OsRng> {
+        let keys = ClientKeys::generate_new(&mut OsRng);
+        let gateway = *identity::KeyPair::new(&mut OsRng).public_key();
+        let handler_config = message_handler::Config::new(
+            keys.ack_key(),
+            ManagedKeys::new(keys, gateway),
+            Duration::from_millis(50),
+            Duration::from_millis(50),
+        );
+
+        let reply_surbs = config::ReplySurbs::default();
+        let storage = CombinedReplyStorage::new(
+            reply_surbs.minimum_reply_surb_storage_threshold,
+            reply_surbs.maximum_reply_surb_storage_threshold,
+        );
+        let (action_sender, _) = futures::channel::mpsc::unbounded();
+        let (real_message_sender, _) = tokio::sync::mpsc::channel(1);
+        let message_handler = MessageHandler::new(
+            handler_config,
+            OsRng,
+            action_sender,
+            real_message_sender,
+            TopologyAccessor::new(),
+            storage.key_storage(),
+            storage.tags_storage(),
+        );
+        let (_, request_receiver) = new_control_channels();
+
+        ReplyController::new(
+            Config::new(reply_surbs),
+            message_handler,
+            storage,
+            request_receiver,
+        )
+    }
+
+    fn random_sender() -> AnonymousSenderTag {
+        AnonymousSenderTag::new_random(&mut OsRng)
+    }
+
+    #[test]
+    fn sender_policies_take_precedence_over_the_default() {
+        let mut controller = test_controller();
+        let sender = random_sender();
+        let other = random_sender();
+
+        let default_policy = SurbPolicy::default().with_pool_size(5, 50);
+        controller.handle_set_default_surb_policy(default_policy);
+        assert_eq!(controller.surb_policy(&sender), default_policy);
+
+        // inconsistent policies get normalised before being applied
+        controller
+            .handle_set_surb_policy(sender, Some(SurbPolicy::default().with_pool_size(100, 10)));
+        let applied = controller.surb_policy(&sender);
+        assert_eq!(applied.min_pool_size, 100);
+        assert_eq!(applied.max_pool_size, 100);
+        assert_eq!(controller.surb_policy(&other), default_policy);
+
+        controller.handle_set_surb_policy(sender, None);
+        assert_eq!(controller.surb_policy(&sender), default_policy);
+    }
+
+    #[tokio::test]
+    async fn pool_metrics_report_the_applied_policies_and_depletions() {
+        let mut controller = test_controller();
+        let sender = random_sender();
+        controller
+            .full_reply_storage
+            .surbs_storage_ref()
+            .insert_surbs(&sender, []);
+
+        let policy = SurbPolicy::default().with_top_up_threshold(20);
+        controller.handle_set_surb_policy(sender, Some(policy));
+        controller.record_surb_depletion(sender);
+        controller.record_surb_depletion(sender);
+
+        let (tx, rx) = oneshot::channel();
+        controller.handle_surb_pool_metrics(tx);
+        let metrics = rx.await.unwrap();
+
+        assert_eq!(metrics.total_depletion_events, 2);
+        let stats = metrics.senders[&sender];
+        assert_eq!(stats.available, 0);
+        assert_eq!(stats.depletion_events, 2);
+        assert!(stats.last_depleted_at.is_some());
+        assert_eq!(stats.policy, policy.normalised());
+    }
+
+    #[tokio::test]
+    async fn stale_senders_are_purged_alongside_their_policies() {
+        let mut controller = test_controller();
+        let stale = random_sender();
+        let fresh = random_sender();
+        for sender in [stale, fresh] {
+            controller
+                .full_reply_storage
+                .surbs_storage_ref()
+                .insert_surbs(&sender, []);
+            controller.record_surb_depletion(sender);
+        }
+
+        // the surbs of the stale sender expire immediately
+        controller.handle_set_surb_policy(
+            stale,
+            Some(SurbPolicy::default().with_max_surb_age(Duration::ZERO)),
+        );
+        controller.handle_set_surb_policy(fresh, Some(SurbPolicy::default()));
+
+        controller.invalidate_old_data().await;
+
+        let surbs = controller.full_reply_storage.surbs_storage_ref();
+        assert!(!surbs.contains_surbs_for(&stale));
+        assert!(surbs.contains_surbs_for(&fresh));
+        assert!(!controller.surb_policies.contains_key(&stale));
+        assert!(controller.surb_policies.contains_key(&fresh));
+        assert_eq!(controller.surb_depletions.get(&stale), (0, None));
+        assert_eq!(controller.surb_depletions.get(&fresh).0, 1);
+    }
+
+    fn reply_surbs_config() -> config::ReplySurbs {
+        config::ReplySurbs {
+            minimum_reply_surb_request_size: 10,
+            maximum_reply_surb_request_size: 100,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reply_surbs_request_covers_the_deficit() {
+        let cfg = reply_surbs_config();
+        let policy = SurbPolicy::default().with_pool_size(10, 1000);
+
+        // queue of 50 + 10 kept in the pool + 1 used by the request itself
+        assert_eq!(reply_surbs_request_size(50, 0, 0, &policy, &cfg), Some(61));
+
+        // surbs we hold or are waiting for are taken into account
+        assert_eq!(
+            reply_surbs_request_size(50, 20, 15, &policy, &cfg),
+            Some(26)
+        );
+    }
+
+    #[test]
+    fn no_reply_surbs_are_requested_without_a_deficit() {
+        let cfg = reply_surbs_config();
+        let policy = SurbPolicy::default().with_pool_size(10, 1000);
+
+        assert_eq!(reply_surbs_request_size(50, 61, 0, &policy, &cfg), None);
+        assert_eq!(reply_surbs_request_size(50, 30, 40, &policy, &cfg), None);
+    }
+
+    #[test]
+    fn reply_surbs_request_is_bounded_by_the_config() {
+        let cfg = reply_surbs_config();
+        let policy = SurbPolicy::default().with_pool_size(10, 1000);
+
+        // small deficits are rounded up to the minimum request size
+        assert_eq!(reply_surbs_request_size(1, 10, 0, &policy, &cfg), Some(10));
+
+        // and large ones are capped at the maximum, so further requests would have to be made
+        assert_eq!(
+            reply_surbs_request_size(10_000, 0, 0, &policy, &cfg),
+            Some(100)
+        );
+    }
+
+    #[test]
+    fn reply_surbs_are_requested_up_to_the_top_up_threshold() {
+        let cfg = reply_surbs_config();
+        let policy = SurbPolicy::default()
+            .with_pool_size(10, 1000)
+            .with_top_up_threshold(50);
+
+        assert_eq!(reply_surbs_request_size(0, 20, 0, &policy, &cfg), Some(31));
+        assert_eq!(reply_surbs_request_size(0, 51, 0, &policy, &cfg), None);
+    }
+}
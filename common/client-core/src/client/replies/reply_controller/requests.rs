@@ -1,7 +1,11 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::delivery::DeliveryToken;
 use crate::client::real_messages_control::acknowledgement_control::PendingAcknowledgement;
+use crate::client::replies::reply_controller::surb_policy::{SurbPolicy, SurbPoolMetrics};
+use crate::client::replies::reply_controller::surb_requests::SurbRequestPolicy;
+use crate::client::replies::reply_storage::{ReplySyncError, SurbHandover, SyncMergeSummary};
 use futures::channel::{mpsc, oneshot};
 use log::error;
 use nym_sphinx::addressing::clients::Recipient;
@@ -45,12 +49,14 @@ impl ReplyControllerSender {
         recipient: AnonymousSenderTag,
         message: Vec<u8>,
         lane: TransmissionLane,
+        delivery_token: Option<DeliveryToken>,
     ) {
         self.0
             .unbounded_send(ReplyControllerMessage::SendReply {
                 recipient,
                 message,
                 lane,
+                delivery_token,
             })
             .expect("ReplyControllerReceiver has died!")
     }
@@ -79,6 +85,115 @@ impl ReplyControllerSender {
             .expect("ReplyControllerReceiver has died!")
     }
 
+    /// Returns the number of reply surbs currently available for replying to the provided sender
+    /// or `None` if we have never received any surbs from them.
+    pub async fn available_reply_surbs(&self, sender_tag: AnonymousSenderTag) -> Option<usize> {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.0
+            .unbounded_send(ReplyControllerMessage::AvailableReplySurbs {
+                sender_tag,
+                response_channel: response_tx,
+            })
+            .expect("ReplyControllerReceiver has died!");
+
+        match response_rx.await {
+            Ok(available) => available,
+            Err(_) => {
+                error!("The reply controller has dropped our response channel!");
+                None
+            }
+        }
+    }
+
+    /// Sets the policy for managing reply surbs received from the provided sender.
+    /// If `None` is specified, the sender is going to use the default policy again.
+    /// Note that the policy is forgotten once all the stored reply surbs of the sender get invalidated.
+    pub fn set_surb_policy(&self, sender_tag: AnonymousSenderTag, policy: Option<SurbPolicy>) {
+        self.0
+            .unbounded_send(ReplyControllerMessage::SetSurbPolicy { sender_tag, policy })
+            .expect("ReplyControllerReceiver has died!")
+    }
+
+    /// Sets the policy for managing reply surbs of all senders that don't have an explicit policy set.
+    pub fn set_default_surb_policy(&self, policy: SurbPolicy) {
+        self.0
+            .unbounded_send(ReplyControllerMessage::SetDefaultSurbPolicy { policy })
+            .expect("ReplyControllerReceiver has died!")
+    }
+
+    /// Sets the limits on the requests for additional reply surbs made by our correspondents.
+    pub fn set_surb_request_policy(&self, policy: SurbRequestPolicy) {
+        self.0
+            .unbounded_send(ReplyControllerMessage::SetSurbRequestPolicy { policy })
+            .expect("ReplyControllerReceiver has died!")
+    }
+
+    /// Returns the current state of reply surb pools of all the known senders.
+    pub async fn surb_pool_metrics(&self) -> SurbPoolMetrics {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.0
+            .unbounded_send(ReplyControllerMessage::SurbPoolMetrics {
+                response_channel: response_tx,
+            })
+            .expect("ReplyControllerReceiver has died!");
+
+        match response_rx.await {
+            Ok(metrics) => metrics,
+            Err(_) => {
+                error!("The reply controller has dropped our response channel!");
+                SurbPoolMetrics::default()
+            }
+        }
+    }
+
+    /// Exports all the changes made to the reply storage since the previous export,
+    /// encrypted so that only other devices sharing our identity could import them.
+    ///
+    /// Note that the reply surbs selected by the `surb_handover` are removed from our storage,
+    /// so the resultant delta must be imported by the other device, or they are going to be lost.
+    pub async fn export_reply_storage_delta(
+        &self,
+        surb_handover: SurbHandover,
+    ) -> Result<Vec<u8>, ReplySyncError> {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.0
+            .unbounded_send(ReplyControllerMessage::ExportStorageDelta {
+                surb_handover,
+                response_channel: response_tx,
+            })
+            .expect("ReplyControllerReceiver has died!");
+
+        response_rx.await.unwrap_or_else(|_| {
+            error!("The reply controller has dropped our response channel!");
+            Err(ReplySyncError::unavailable(
+                "the reply controller is not running",
+            ))
+        })
+    }
+
+    /// Imports the reply storage delta exported by another device sharing our identity.
+    /// Any pending replies are going to be sent as soon as possible if the delta contained
+    /// new reply surbs.
+    pub async fn import_reply_storage_delta(
+        &self,
+        delta: Vec<u8>,
+    ) -> Result<SyncMergeSummary, ReplySyncError> {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.0
+            .unbounded_send(ReplyControllerMessage::ImportStorageDelta {
+                delta,
+                response_channel: response_tx,
+            })
+            .expect("ReplyControllerReceiver has died!");
+
+        response_rx.await.unwrap_or_else(|_| {
+            error!("The reply controller has dropped our response channel!");
+            Err(ReplySyncError::unavailable(
+                "the reply controller is not running",
+            ))
+        })
+    }
+
     pub async fn get_lane_queue_length(&self, connection_id: ConnectionId) -> usize {
         let (response_tx, response_rx) = oneshot::channel();
         self.0
@@ -131,6 +246,7 @@ pub(crate) enum ReplyControllerMessage {
         recipient: AnonymousSenderTag,
         message: Vec<u8>,
         lane: TransmissionLane,
+        delivery_token: Option<DeliveryToken>,
     },
 
     AdditionalSurbs {
@@ -139,6 +255,28 @@ pub(crate) enum ReplyControllerMessage {
         from_surb_request: bool,
     },
 
+    AvailableReplySurbs {
+        sender_tag: AnonymousSenderTag,
+        response_channel: oneshot::Sender<Option<usize>>,
+    },
+
+    SetSurbPolicy {
+        sender_tag: AnonymousSenderTag,
+        policy: Option<SurbPolicy>,
+    },
+
+    SetDefaultSurbPolicy {
+        policy: SurbPolicy,
+    },
+
+    SetSurbRequestPolicy {
+        policy: SurbRequestPolicy,
+    },
+
+    SurbPoolMetrics {
+        response_channel: oneshot::Sender<SurbPoolMetrics>,
+    },
+
     // this one doesn't belong here either...
     LaneQueueLength {
         connection_id: ConnectionId,
@@ -151,4 +289,14 @@ pub(crate) enum ReplyControllerMessage {
         recipient: Box<Recipient>,
         amount: u32,
     },
+
+    ExportStorageDelta {
+        surb_handover: SurbHandover,
+        response_channel: oneshot::Sender<Result<Vec<u8>, ReplySyncError>>,
+    },
+
+    ImportStorageDelta {
+        delta: Vec<u8>,
+        response_channel: oneshot::Sender<Result<SyncMergeSummary, ReplySyncError>>,
+    },
 }
@@ -0,0 +1,174 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::replies::reply_controller::surb_requests::SurbRequestStats;
+use crate::config;
+use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use std::collections::HashMap;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+/// Policy governing how the reply SURBs received from a particular sender are managed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SurbPolicy {
+    /// The minimum number of reply SURBs to keep in the pool at all times.
+    /// The pool is only allowed to go below it in order to request additional SURBs.
+    pub min_pool_size: usize,
+
+    /// The maximum number of reply SURBs (including the ones we're still waiting for)
+    /// we want to hold so that we wouldn't over-request them.
+    pub max_pool_size: usize,
+
+    /// If specified, additional reply SURBs are going to be requested as soon as the pool
+    /// (including the SURBs we're still waiting for) drops below this value,
+    /// even if there's no pending data to send.
+    pub top_up_threshold: Option<usize>,
+
+    /// Maximum amount of time since we last received any reply SURBs from the sender
+    /// after which all of its stored SURBs are going to be purged.
+    /// Note that the age is only checked every tenth of the configured `maximum_reply_surb_age`.
+    pub max_surb_age: Duration,
+}
+
+impl SurbPolicy {
+    pub(crate) fn from_config(cfg: &config::ReplySurbs) -> Self {
+        SurbPolicy {
+            min_pool_size: cfg.minimum_reply_surb_storage_threshold,
+            max_pool_size: cfg.maximum_reply_surb_storage_threshold,
+            top_up_threshold: None,
+            max_surb_age: cfg.maximum_reply_surb_age,
+        }
+    }
+
+    #[must_use]
+    pub fn with_pool_size(mut self, min_pool_size: usize, max_pool_size: usize) -> Self {
+        self.min_pool_size = min_pool_size;
+        self.max_pool_size = max_pool_size;
+        self
+    }
+
+    #[must_use]
+    pub fn with_top_up_threshold(mut self, top_up_threshold: usize) -> Self {
+        self.top_up_threshold = Some(top_up_threshold);
+        self
+    }
+
+    #[must_use]
+    pub fn with_max_surb_age(mut self, max_surb_age: Duration) -> Self {
+        self.max_surb_age = max_surb_age;
+        self
+    }
+
+    /// Makes sure the policy is internally consistent, i.e. the maximum pool size is not smaller
+    /// than the minimum and the top up threshold lies within the pool size range.
+    pub(crate) fn normalised(mut self) -> Self {
+        self.max_pool_size = self.max_pool_size.max(self.min_pool_size);
+        self.top_up_threshold = self
+            .top_up_threshold
+            .map(|threshold| threshold.clamp(self.min_pool_size, self.max_pool_size));
+        self
+    }
+}
+
+impl Default for SurbPolicy {
+    fn default() -> Self {
+        SurbPolicy::from_config(&config::ReplySurbs::default())
+    }
+}
+
+/// Reply SURB pool statistics of a single sender.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SurbPoolStats {
+    /// The number of reply SURBs currently available in the pool.
+    pub available: usize,
+
+    /// The number of reply SURBs we have requested, but haven't received yet.
+    pub pending_reception: u32,
+
+    /// The number of times we couldn't send (or retransmit) data to the sender
+    /// due to the pool being depleted.
+    pub depletion_events: u64,
+
+    /// The time of the most recent depletion event.
+    pub last_depleted_at: Option<OffsetDateTime>,
+
+    /// Policy currently applied to the sender's pool.
+    pub policy: SurbPolicy,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SurbPoolMetrics {
+    /// The total number of depletion events across all the senders, including the ones
+    /// whose SURBs have since been purged.
+    pub total_depletion_events: u64,
+
+    pub senders: HashMap<AnonymousSenderTag, SurbPoolStats>,
+
+    /// Counters of the requests for additional reply SURBs made by our correspondents.
+    pub surb_requests: SurbRequestStats,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct DepletionTracker {
+    total: u64,
+    per_sender: HashMap<AnonymousSenderTag, (u64, OffsetDateTime)>,
+}
+
+impl DepletionTracker {
+    pub(crate) fn record(&mut self, sender: AnonymousSenderTag) {
+        self.total += 1;
+        let entry = self
+            .per_sender
+            .entry(sender)
+            .or_insert((0, OffsetDateTime::now_utc()));
+        entry.0 += 1;
+        entry.1 = OffsetDateTime::now_utc();
+    }
+
+    pub(crate) fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub(crate) fn get(&self, sender: &AnonymousSenderTag) -> (u64, Option<OffsetDateTime>) {
+        self.per_sender
+            .get(sender)
+            .map(|(count, at)| (*count, Some(*at)))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn remove(&mut self, sender: &AnonymousSenderTag) {
+        self.per_sender.remove(sender);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalising_policy() {
+        let policy = SurbPolicy::default()
+            .with_pool_size(50, 10)
+            .with_top_up_threshold(1000)
+            .normalised();
+
+        assert_eq!(policy.min_pool_size, 50);
+        assert_eq!(policy.max_pool_size, 50);
+        assert_eq!(policy.top_up_threshold, Some(50));
+    }
+
+    #[test]
+    fn tracking_depletion_events() {
+        let sender = AnonymousSenderTag::new_random(&mut rand::thread_rng());
+        let mut tracker = DepletionTracker::default();
+        assert_eq!(tracker.get(&sender), (0, None));
+
+        tracker.record(sender);
+        tracker.record(sender);
+        assert_eq!(tracker.get(&sender).0, 2);
+
+        tracker.remove(&sender);
+        assert_eq!(tracker.get(&sender), (0, None));
+        assert_eq!(tracker.total(), 2);
+    }
+}
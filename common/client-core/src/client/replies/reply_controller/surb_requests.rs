@@ -0,0 +1,244 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::helpers::Instant;
+use crate::config;
+use log::*;
+use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Limits on the requests for additional reply SURBs made by our correspondents, so that
+/// a malicious one couldn't make us flood the mixnet with SURB packets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SurbRequestPolicy {
+    /// Whether the limits are applied at all.
+    pub enabled: bool,
+
+    /// Length of the window over which the requests are rate limited.
+    pub window: Duration,
+
+    /// Maximum number of requests a single correspondent can make within the window.
+    pub max_requests_per_sender: u32,
+
+    /// Maximum number of reply SURBs a single correspondent can obtain within the window.
+    pub max_surbs_per_sender: u32,
+
+    /// Maximum number of reply SURBs all the correspondents combined can obtain within the window.
+    pub max_total_surbs: u32,
+}
+
+impl SurbRequestPolicy {
+    pub(crate) fn from_config(cfg: &config::ReplySurbs) -> Self {
+        SurbRequestPolicy {
+            enabled: cfg.enable_reply_surb_request_rate_limiting,
+            window: cfg.reply_surb_request_rate_limit_window,
+            max_requests_per_sender: cfg.maximum_reply_surb_requests_per_window,
+            max_surbs_per_sender: cfg.maximum_requested_surbs_per_window,
+            max_total_surbs: cfg.maximum_total_requested_surbs_per_window,
+        }
+    }
+}
+
+impl Default for SurbRequestPolicy {
+    fn default() -> Self {
+        SurbRequestPolicy::from_config(&config::ReplySurbs::default())
+    }
+}
+
+/// Counters of the requests for additional reply SURBs received from our correspondents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SurbRequestStats {
+    /// The number of requests received from the correspondents we have sent anonymous messages to.
+    pub received: u64,
+
+    /// The number of requests that have been ignored due to the rate limits.
+    pub rejected: u64,
+
+    /// The number of requests whose amount has been lowered due to the rate limits.
+    pub trimmed: u64,
+
+    /// The total number of reply SURBs sent in response to the requests.
+    pub surbs_sent: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    started_at: Instant,
+    requests: u32,
+    surbs: u32,
+
+    // so that a misbehaving correspondent wouldn't flood our logs either
+    reported: bool,
+}
+
+impl Window {
+    fn new(now: Instant) -> Self {
+        Window {
+            started_at: now,
+            requests: 0,
+            surbs: 0,
+            reported: false,
+        }
+    }
+
+    fn is_expired(&self, now: Instant, length: Duration) -> bool {
+        now.duration_since(self.started_at) >= length
+    }
+
+    fn refresh(&mut self, now: Instant, length: Duration) {
+        if self.is_expired(now, length) {
+            *self = Window::new(now)
+        }
+    }
+}
+
+pub(crate) struct SurbRequestLimiter {
+    policy: SurbRequestPolicy,
+    total: Window,
+    per_sender: HashMap<AnonymousSenderTag, Window>,
+    stats: SurbRequestStats,
+}
+
+impl SurbRequestLimiter {
+    pub(crate) fn new(policy: SurbRequestPolicy, now: Instant) -> Self {
+        SurbRequestLimiter {
+            policy,
+            total: Window::new(now),
+            per_sender: HashMap::new(),
+            stats: SurbRequestStats::default(),
+        }
+    }
+
+    pub(crate) fn set_policy(&mut self, policy: SurbRequestPolicy) {
+        self.policy = policy
+    }
+
+    pub(crate) fn stats(&self) -> SurbRequestStats {
+        self.stats
+    }
+
+    /// Determines how many of the requested reply SURBs can be sent to the correspondent,
+    /// if any, and accounts for them.
+    pub(crate) fn admit(
+        &mut self,
+        sender: AnonymousSenderTag,
+        requested: u32,
+        now: Instant,
+    ) -> Option<u32> {
+        self.stats.received += 1;
+        if !self.policy.enabled {
+            self.stats.surbs_sent += requested as u64;
+            return Some(requested);
+        }
+
+        let length = self.policy.window;
+        self.total.refresh(now, length);
+        let window = self
+            .per_sender
+            .entry(sender)
+            .or_insert_with(|| Window::new(now));
+        window.refresh(now, length);
+
+        let allowed = if window.requests >= self.policy.max_requests_per_sender {
+            0
+        } else {
+            requested
+                .min(
+                    self.policy
+                        .max_surbs_per_sender
+                        .saturating_sub(window.surbs),
+                )
+                .min(self.policy.max_total_surbs.saturating_sub(self.total.surbs))
+        };
+        window.requests += 1;
+
+        if allowed == 0 {
+            self.stats.rejected += 1;
+            if !window.reported {
+                window.reported = true;
+                warn!("{sender} has exceeded the limits on reply surb requests - ignoring its requests for the next {length:?} at most");
+            }
+            return None;
+        }
+
+        if allowed < requested {
+            self.stats.trimmed += 1;
+            debug!("lowering the amount of reply surbs requested by {sender} from {requested} to {allowed} due to the rate limits");
+        }
+        window.surbs += allowed;
+        self.total.surbs += allowed;
+        self.stats.surbs_sent += allowed as u64;
+        Some(allowed)
+    }
+
+    /// Removes the state of the correspondents whose windows have already expired.
+    pub(crate) fn prune(&mut self, now: Instant) {
+        let length = self.policy.window;
+        self.per_sender
+            .retain(|_, window| !window.is_expired(now, length))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sender() -> AnonymousSenderTag {
+        AnonymousSenderTag::new_random(&mut rand::thread_rng())
+    }
+
+    #[test]
+    fn requests_are_limited_per_sender_and_in_total() {
+        let policy = SurbRequestPolicy {
+            enabled: true,
+            window: Duration::from_secs(60),
+            max_requests_per_sender: 3,
+            max_surbs_per_sender: 250,
+            max_total_surbs: 400,
+        };
+        let start = Instant::now();
+        let mut limiter = SurbRequestLimiter::new(policy, start);
+
+        let alice = sender();
+        let bob = sender();
+
+        assert_eq!(limiter.admit(alice, 100, start), Some(100));
+        assert_eq!(limiter.admit(alice, 200, start), Some(150));
+        // no more surbs for alice within this window
+        assert_eq!(limiter.admit(alice, 1, start), None);
+        // while bob is restricted by the total limit
+        assert_eq!(limiter.admit(bob, 100, start), Some(100));
+        assert_eq!(limiter.admit(bob, 100, start), Some(50));
+        assert_eq!(limiter.admit(bob, 100, start), None);
+
+        let later = start + Duration::from_secs(60);
+        assert_eq!(limiter.admit(alice, 100, later), Some(100));
+
+        assert_eq!(
+            limiter.stats(),
+            SurbRequestStats {
+                received: 7,
+                rejected: 2,
+                trimmed: 2,
+                surbs_sent: 500,
+            }
+        );
+
+        limiter.prune(later);
+        assert_eq!(limiter.per_sender.len(), 1);
+    }
+
+    #[test]
+    fn requests_are_not_limited_by_default() {
+        let start = Instant::now();
+        let mut limiter = SurbRequestLimiter::new(SurbRequestPolicy::default(), start);
+
+        let alice = sender();
+        for _ in 0..100 {
+            assert_eq!(limiter.admit(alice, 500, start), Some(500));
+        }
+        assert_eq!(limiter.stats().rejected, 0);
+        assert_eq!(limiter.stats().surbs_sent, 50000);
+    }
+}
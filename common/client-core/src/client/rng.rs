@@ -0,0 +1,74 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Source of the randomness used by the client for the route selection, packet delays and cover traffic.
+//!
+//! Regular builds always use the OS RNG. With the `deterministic-rng` feature, meant only for tests
+//! and simulations, the client can instead be given a seed that all of its randomness is derived from,
+//! so that its runs could be reproduced.
+
+#[cfg(feature = "deterministic-rng")]
+use rand::{Rng, SeedableRng};
+
+#[cfg(not(feature = "deterministic-rng"))]
+pub(crate) type ClientRng = rand::rngs::OsRng;
+
+#[cfg(feature = "deterministic-rng")]
+pub(crate) type ClientRng = rand_chacha::ChaCha20Rng;
+
+#[derive(Debug, Default)]
+pub(crate) struct ClientRngSource {
+    // if set, the RNGs of all the components are derived from it in the order they're created in
+    #[cfg(feature = "deterministic-rng")]
+    seeded: Option<rand_chacha::ChaCha20Rng>,
+}
+
+impl ClientRngSource {
+    pub(crate) fn new() -> Self {
+        Default::default()
+    }
+
+    #[cfg(feature = "deterministic-rng")]
+    pub(crate) fn new_seeded(seed: u64) -> Self {
+        ClientRngSource {
+            seeded: Some(rand_chacha::ChaCha20Rng::seed_from_u64(seed)),
+        }
+    }
+
+    /// Creates the RNG to be used by a single component of the client.
+    #[cfg(not(feature = "deterministic-rng"))]
+    pub(crate) fn component_rng(&mut self) -> ClientRng {
+        rand::rngs::OsRng
+    }
+
+    /// Creates the RNG to be used by a single component of the client.
+    #[cfg(feature = "deterministic-rng")]
+    pub(crate) fn component_rng(&mut self) -> ClientRng {
+        match &mut self.seeded {
+            Some(seeded) => ClientRng::from_seed(seeded.gen()),
+            None => ClientRng::from_seed(rand::rngs::OsRng.gen()),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "deterministic-rng"))]
+mod tests {
+    use super::*;
+    use rand::RngCore;
+
+    #[test]
+    fn seeded_source_is_reproducible() {
+        let mut first = ClientRngSource::new_seeded(42);
+        let mut second = ClientRngSource::new_seeded(42);
+
+        let mut first_a = first.component_rng();
+        let mut first_b = first.component_rng();
+        let mut second_a = second.component_rng();
+        let mut second_b = second.component_rng();
+
+        assert_eq!(first_a.next_u64(), second_a.next_u64());
+        assert_eq!(first_b.next_u64(), second_b.next_u64());
+        // while the components don't share the same stream
+        assert_ne!(first_a.next_u64(), first_b.next_u64());
+    }
+}
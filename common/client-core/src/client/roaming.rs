@@ -0,0 +1,210 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for clients moving between networks, such as a laptop switching Wi-Fi or a phone
+//! going from Wi-Fi to cellular. Whenever the local network changes, the gateway connection
+//! gets re-established straight away (rather than waiting for the old socket to time out)
+//! and the topology gets refreshed if it might have gone stale while we were offline.
+//!
+//! Network changes can either be reported by the embedder, through the [`NetworkChangeNotifier`]
+//! (which is the preferred approach on mobile platforms with native connectivity callbacks),
+//! or detected by the built-in monitor that periodically checks the local address used
+//! for reaching the internet.
+
+use log::*;
+use tokio::sync::broadcast;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use monitor::NetworkMonitor;
+
+// there's no point in buffering many changes since they get coalesced anyway
+const NETWORK_CHANGE_CHANNEL_CAPACITY: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkChange {
+    /// The device has (re)gained connectivity, possibly through a different network.
+    Available,
+
+    /// The device has lost all connectivity.
+    Lost,
+}
+
+/// Handle used for informing the client about changes to the local network.
+#[derive(Debug, Clone)]
+pub struct NetworkChangeNotifier {
+    sender: broadcast::Sender<NetworkChange>,
+}
+
+impl NetworkChangeNotifier {
+    pub(crate) fn new() -> Self {
+        let (sender, _) = broadcast::channel(NETWORK_CHANGE_CHANNEL_CAPACITY);
+        NetworkChangeNotifier { sender }
+    }
+
+    pub fn notify(&self, change: NetworkChange) {
+        debug!("network change: {change:?}");
+        if self.sender.send(change).is_err() {
+            debug!("there are no client components listening for network changes");
+        }
+    }
+
+    pub fn network_available(&self) {
+        self.notify(NetworkChange::Available)
+    }
+
+    pub fn network_lost(&self) {
+        self.notify(NetworkChange::Lost)
+    }
+
+    pub(crate) fn subscribe(&self) -> NetworkChangeListener {
+        NetworkChangeListener {
+            receiver: self.sender.subscribe(),
+        }
+    }
+}
+
+pub(crate) struct NetworkChangeListener {
+    receiver: broadcast::Receiver<NetworkChange>,
+}
+
+impl NetworkChangeListener {
+    /// Waits for the next network change. If multiple changes got queued up in the meantime
+    /// (as it often happens when switching networks), only the most recent one is returned.
+    pub(crate) async fn next(&mut self) -> Option<NetworkChange> {
+        let mut latest = loop {
+            match self.receiver.recv().await {
+                Ok(change) => break change,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        };
+
+        loop {
+            match self.receiver.try_recv() {
+                Ok(change) => latest = change,
+                Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+                Err(_) => return Some(latest),
+            }
+        }
+    }
+}
+
+/// Waits for the next network change if the component is listening for them at all.
+pub(crate) async fn next_network_change(
+    listener: Option<&mut NetworkChangeListener>,
+) -> Option<NetworkChange> {
+    match listener {
+        Some(listener) => listener.next().await,
+        None => None,
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod monitor {
+    use super::NetworkChangeNotifier;
+    use crate::client::helpers::new_interval_stream;
+    use crate::spawn_future;
+    use futures::StreamExt;
+    use log::*;
+    use std::net::{IpAddr, SocketAddr, UdpSocket};
+    use std::time::Duration;
+
+    // public resolvers used purely for the route lookup. no packets are ever sent to them
+    const IPV4_PROBE_ADDRESS: &str = "1.1.1.1:53";
+    const IPV6_PROBE_ADDRESS: &str = "[2606:4700:4700::1111]:53";
+
+    /// Returns the local address the system would currently use for reaching the internet.
+    fn current_route_address() -> Option<IpAddr> {
+        fn route_address(bind: &str, probe: &str) -> Option<IpAddr> {
+            // connecting a udp socket doesn't send anything, but it makes the system resolve the route
+            let socket = UdpSocket::bind(bind).ok()?;
+            socket.connect(probe).ok()?;
+            socket.local_addr().ok().as_ref().map(SocketAddr::ip)
+        }
+
+        route_address("0.0.0.0:0", IPV4_PROBE_ADDRESS)
+            .or_else(|| route_address("[::]:0", IPV6_PROBE_ADDRESS))
+    }
+
+    /// Detects network changes by periodically checking the local address used for reaching the internet.
+    pub(crate) struct NetworkMonitor {
+        notifier: NetworkChangeNotifier,
+        check_interval: Duration,
+        current_address: Option<IpAddr>,
+    }
+
+    impl NetworkMonitor {
+        pub(crate) fn new(notifier: NetworkChangeNotifier, check_interval: Duration) -> Self {
+            NetworkMonitor {
+                notifier,
+                check_interval,
+                current_address: current_route_address(),
+            }
+        }
+
+        fn check_for_changes(&mut self) {
+            let address = current_route_address();
+            if address == self.current_address {
+                return;
+            }
+
+            let previous = std::mem::replace(&mut self.current_address, address);
+            match address {
+                Some(address) => {
+                    info!("the local network has changed ({previous:?} -> {address})");
+                    self.notifier.network_available()
+                }
+                None => {
+                    info!("the local network is no longer available");
+                    self.notifier.network_lost()
+                }
+            }
+        }
+
+        pub(crate) fn start_with_shutdown(mut self, mut shutdown: nym_task::TaskClient) {
+            spawn_future(async move {
+                debug!("Started NetworkMonitor with graceful shutdown support");
+
+                let mut interval = new_interval_stream(self.check_interval);
+                while !shutdown.is_shutdown() {
+                    tokio::select! {
+                        _ = interval.next() => self.check_for_changes(),
+                        _ = shutdown.recv() => {
+                            log::trace!("NetworkMonitor: Received shutdown");
+                        }
+                    }
+                }
+                shutdown.recv_timeout().await;
+                log::debug!("NetworkMonitor: Exiting");
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn queued_changes_are_coalesced() {
+        let notifier = NetworkChangeNotifier::new();
+        let mut listener = notifier.subscribe();
+
+        notifier.network_lost();
+        notifier.network_available();
+        assert_eq!(block_on(listener.next()), Some(NetworkChange::Available));
+
+        notifier.network_available();
+        notifier.network_lost();
+        assert_eq!(block_on(listener.next()), Some(NetworkChange::Lost));
+    }
+
+    #[test]
+    fn listener_finishes_once_notifier_is_dropped() {
+        let notifier = NetworkChangeNotifier::new();
+        let mut listener = notifier.subscribe();
+        drop(notifier);
+        assert!(block_on(listener.next()).is_none());
+    }
+}
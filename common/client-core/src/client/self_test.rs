@@ -0,0 +1,242 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Startup self-test that sends a few messages addressed to ourselves through a full mix route
+//! in order to verify the client is actually connected and routable before any real traffic
+//! gets sent.
+
+use crate::client::base_client::{BaseClient, ClientInputStatus, ClientOutputStatus};
+use crate::client::helpers::{get_time_now, sleep, Instant};
+use crate::client::inbound_messages::InputMessage;
+use crate::client::received_buffer::ReceivedBufferMessage;
+use crate::error::ClientCoreError;
+use futures::StreamExt;
+use log::*;
+use nym_sphinx::receiver::ReconstructedMessage;
+use nym_task::connections::TransmissionLane;
+use std::time::Duration;
+
+const SELF_TEST_MAGIC: &[u8] = b"NYM_SELF_TEST";
+const SELF_TEST_PAYLOAD_LEN: usize = SELF_TEST_MAGIC.len() + 8 + 4;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SelfTestConfig {
+    /// Number of test messages sent through the mixnet.
+    pub test_messages: u32,
+
+    /// Maximum amount of time we're going to wait for the test messages to come back.
+    pub timeout: Duration,
+}
+
+impl Default for SelfTestConfig {
+    fn default() -> Self {
+        SelfTestConfig {
+            test_messages: 3,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SelfTestPacketResult {
+    pub id: u32,
+
+    /// Time it took for the test message to come back to us, if it did arrive at all.
+    pub round_trip: Option<Duration>,
+}
+
+impl SelfTestPacketResult {
+    pub fn received(&self) -> bool {
+        self.round_trip.is_some()
+    }
+}
+
+#[derive(Debug)]
+pub struct SelfTestReport {
+    pub results: Vec<SelfTestPacketResult>,
+
+    /// Any messages received during the self-test that were not part of it, for example ones
+    /// sent to us by other clients. They are not going to be delivered again, so the caller
+    /// is responsible for handling them.
+    pub unrelated_messages: Vec<ReconstructedMessage>,
+}
+
+impl SelfTestReport {
+    /// Indicates whether at least a single test message has made it through the mixnet.
+    pub fn is_routable(&self) -> bool {
+        self.results.iter().any(|r| r.received())
+    }
+
+    /// Indicates whether all the test messages have made it through the mixnet.
+    pub fn all_received(&self) -> bool {
+        self.results.iter().all(|r| r.received())
+    }
+
+    pub fn received_count(&self) -> usize {
+        self.results.iter().filter(|r| r.received()).count()
+    }
+
+    pub fn average_round_trip(&self) -> Option<Duration> {
+        let received = self
+            .results
+            .iter()
+            .filter_map(|r| r.round_trip)
+            .collect::<Vec<_>>();
+        if received.is_empty() {
+            return None;
+        }
+        Some(received.iter().sum::<Duration>() / received.len() as u32)
+    }
+}
+
+fn test_payload(session: u64, id: u32) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(SELF_TEST_PAYLOAD_LEN);
+    payload.extend_from_slice(SELF_TEST_MAGIC);
+    payload.extend_from_slice(&session.to_be_bytes());
+    payload.extend_from_slice(&id.to_be_bytes());
+    payload
+}
+
+/// Attempts to recover id of the test message from the provided payload, if it belongs
+/// to the current self-test session.
+fn parse_test_payload(session: u64, payload: &[u8]) -> Option<u32> {
+    if payload.len() != SELF_TEST_PAYLOAD_LEN || !payload.starts_with(SELF_TEST_MAGIC) {
+        return None;
+    }
+    let rest = &payload[SELF_TEST_MAGIC.len()..];
+
+    // SAFETY: we've checked the length of the payload
+    #[allow(clippy::unwrap_used)]
+    let received_session = u64::from_be_bytes(rest[..8].try_into().unwrap());
+    if received_session != session {
+        return None;
+    }
+
+    #[allow(clippy::unwrap_used)]
+    Some(u32::from_be_bytes(rest[8..].try_into().unwrap()))
+}
+
+impl BaseClient {
+    /// Sends a few test messages addressed to ourselves through the mixnet and reports which of
+    /// them made it back and how long it took, using the default [`SelfTestConfig`].
+    ///
+    /// It must be called before the client input and output get registered by the embedder.
+    pub async fn run_self_test(&mut self) -> Result<SelfTestReport, ClientCoreError> {
+        self.run_self_test_with_config(SelfTestConfig::default())
+            .await
+    }
+
+    /// Sends test messages addressed to ourselves through the mixnet and reports which of
+    /// them made it back and how long it took.
+    ///
+    /// It must be called before the client input and output get registered by the embedder.
+    pub async fn run_self_test_with_config(
+        &mut self,
+        config: SelfTestConfig,
+    ) -> Result<SelfTestReport, ClientCoreError> {
+        let ClientInputStatus::AwaitingProducer { client_input } = &self.client_input else {
+            return Err(ClientCoreError::SelfTestUnavailable);
+        };
+        let ClientOutputStatus::AwaitingConsumer { client_output } = &mut self.client_output else {
+            return Err(ClientCoreError::SelfTestUnavailable);
+        };
+
+        info!(
+            "starting the self-test with {} test messages",
+            config.test_messages
+        );
+
+        let mut receiver = client_output.register_receiver()?;
+        let session: u64 = rand::random();
+
+        let mut sent_at: Vec<Instant> = Vec::with_capacity(config.test_messages as usize);
+        let mut results = Vec::with_capacity(config.test_messages as usize);
+        for id in 0..config.test_messages {
+            let message = InputMessage::new_regular(
+                self.address,
+                test_payload(session, id),
+                TransmissionLane::General,
+                None,
+            );
+            sent_at.push(get_time_now());
+            results.push(SelfTestPacketResult {
+                id,
+                round_trip: None,
+            });
+
+            if client_input.send(message).await.is_err() {
+                return Err(ClientCoreError::UnexpectedExit);
+            }
+        }
+
+        let mut unrelated_messages = Vec::new();
+        let mut outstanding = config.test_messages;
+
+        let deadline = sleep(config.timeout);
+        tokio::pin!(deadline);
+
+        while outstanding > 0 {
+            tokio::select! {
+                biased;
+                _ = &mut deadline => {
+                    warn!("timed out while waiting for the self-test messages to come back");
+                    break;
+                }
+                received = receiver.next() => {
+                    let Some(received) = received else {
+                        warn!("the received messages channel has been closed during the self-test");
+                        break;
+                    };
+                    let now = get_time_now();
+                    for message in received {
+                        let Some(id) = parse_test_payload(session, &message.message) else {
+                            unrelated_messages.push(message);
+                            continue;
+                        };
+                        let Some(result) = results.get_mut(id as usize) else {
+                            continue;
+                        };
+                        if result.round_trip.is_none() {
+                            let round_trip = now.duration_since(sent_at[id as usize]);
+                            debug!("self-test message {id} came back after {round_trip:?}");
+                            result.round_trip = Some(round_trip);
+                            outstanding -= 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        // make sure the embedder will be able to register its own receiver afterwards
+        client_output
+            .received_buffer_request_sender
+            .unbounded_send(ReceivedBufferMessage::ReceiverDisconnect)
+            .map_err(|_| ClientCoreError::FailedToRegisterReceiver)?;
+
+        let report = SelfTestReport {
+            results,
+            unrelated_messages,
+        };
+        info!(
+            "self-test finished: {}/{} test messages came back (average round trip: {:?})",
+            report.received_count(),
+            config.test_messages,
+            report.average_round_trip()
+        );
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payloads_are_bound_to_the_session() {
+        let payload = test_payload(42, 7);
+        assert_eq!(parse_test_payload(42, &payload), Some(7));
+        assert_eq!(parse_test_payload(43, &payload), None);
+        assert_eq!(parse_test_payload(42, b"hello"), None);
+    }
+}
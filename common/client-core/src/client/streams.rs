@@ -0,0 +1,367 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Transfers of payloads too large to be comfortably held in memory as a single [`InputMessage`].
+//!
+//! The payload is read in chunks, each of which is sent as a separate message tagged with the id
+//! of the stream and its sequence number. The sender stops reading whenever too many packets of
+//! the stream are still waiting to be sent out, so only a bounded part of the payload is ever buffered.
+//! On the receiving side the chunks are put back in order and exposed as a [`ReconstructedStream`],
+//! as long as a stream receiver has been registered. Otherwise the chunks are delivered as regular messages.
+
+use crate::client::helpers::{get_time_now, sleep, Instant};
+use crate::client::inbound_messages::{InputMessage, InputMessageSender};
+use futures::channel::mpsc;
+use futures::io::{AsyncRead, AsyncReadExt};
+use futures::{Stream, StreamExt};
+use log::*;
+use nym_sphinx::addressing::clients::Recipient;
+use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nym_sphinx::receiver::ReconstructedMessage;
+use nym_task::connections::{LaneQueueLengths, TransmissionLane};
+use std::collections::{BTreeMap, HashMap};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use thiserror::Error;
+
+const STREAM_MAGIC: &[u8] = b"NYMSTRM1";
+const CHUNK_HEADER_LEN: usize = STREAM_MAGIC.len() + 8 + 8 + 1;
+const FINAL_CHUNK_FLAG: u8 = 1;
+
+// streams that haven't received anything for that long are assumed to be abandoned by their senders
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+pub type StreamId = u64;
+
+#[derive(Debug, Error)]
+pub enum StreamError {
+    #[error("failed to read the stream payload: {source}")]
+    ReadFailure { source: std::io::Error },
+
+    #[error("the client is no longer accepting messages")]
+    ClientStopped,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StreamConfig {
+    /// Size of the payload carried by each message of the stream.
+    pub chunk_size: usize,
+
+    /// Maximum number of packets of the stream that can be waiting to be sent out
+    /// before the next chunk is read.
+    pub max_queued_packets: usize,
+
+    /// How often we're going to check whether the queued packets have been sent.
+    pub backpressure_poll_interval: Duration,
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        StreamConfig {
+            chunk_size: 64 * 1024,
+            max_queued_packets: 128,
+            backpressure_poll_interval: Duration::from_millis(50),
+        }
+    }
+}
+
+/// Specifies how the chunks of the stream are going to be addressed.
+#[derive(Debug, Clone, Copy)]
+pub enum StreamDestination {
+    Regular(Recipient),
+    Anonymous {
+        recipient: Recipient,
+        reply_surbs: u32,
+    },
+    Reply(AnonymousSenderTag),
+}
+
+impl StreamDestination {
+    fn message(&self, data: Vec<u8>, lane: TransmissionLane) -> InputMessage {
+        match *self {
+            StreamDestination::Regular(recipient) => {
+                InputMessage::new_regular(recipient, data, lane, None)
+            }
+            StreamDestination::Anonymous {
+                recipient,
+                reply_surbs,
+            } => InputMessage::new_anonymous(recipient, data, reply_surbs, lane, None),
+            StreamDestination::Reply(recipient_tag) => {
+                InputMessage::new_reply(recipient_tag, data, lane, None)
+            }
+        }
+    }
+}
+
+fn encode_chunk(stream_id: StreamId, seq: u64, is_final: bool, data: &[u8]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(CHUNK_HEADER_LEN + data.len());
+    payload.extend_from_slice(STREAM_MAGIC);
+    payload.extend_from_slice(&stream_id.to_be_bytes());
+    payload.extend_from_slice(&seq.to_be_bytes());
+    payload.push(if is_final { FINAL_CHUNK_FLAG } else { 0 });
+    payload.extend_from_slice(data);
+    payload
+}
+
+struct StreamChunk<'a> {
+    stream_id: StreamId,
+    seq: u64,
+    is_final: bool,
+    data: &'a [u8],
+}
+
+fn decode_chunk(payload: &[u8]) -> Option<StreamChunk<'_>> {
+    if payload.len() < CHUNK_HEADER_LEN || !payload.starts_with(STREAM_MAGIC) {
+        return None;
+    }
+    let header = &payload[STREAM_MAGIC.len()..];
+    Some(StreamChunk {
+        stream_id: u64::from_be_bytes(header[..8].try_into().ok()?),
+        seq: u64::from_be_bytes(header[8..16].try_into().ok()?),
+        is_final: header[16] & FINAL_CHUNK_FLAG != 0,
+        data: &payload[CHUNK_HEADER_LEN..],
+    })
+}
+
+// reads until the buffer is full or the reader is exhausted
+async fn read_chunk<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    buf: &mut [u8],
+) -> Result<usize, StreamError> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]).await {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(source) => return Err(StreamError::ReadFailure { source }),
+        }
+    }
+    Ok(filled)
+}
+
+pub(crate) async fn send_stream<R: AsyncRead + Unpin>(
+    input_sender: &InputMessageSender,
+    lane_queue_lengths: &LaneQueueLengths,
+    destination: StreamDestination,
+    mut reader: R,
+    config: StreamConfig,
+) -> Result<StreamId, StreamError> {
+    let stream_id = rand::random();
+    let lane = TransmissionLane::ConnectionId(stream_id);
+    let mut buf = vec![0; config.chunk_size.max(1)];
+
+    let mut seq = 0;
+    loop {
+        let read = read_chunk(&mut reader, &mut buf).await?;
+        // note that if the payload size is a multiple of the chunk size, the final chunk is going to be empty
+        let is_final = read < buf.len();
+
+        while lane_queue_lengths.get(&lane).unwrap_or_default() > config.max_queued_packets {
+            sleep(config.backpressure_poll_interval).await;
+        }
+
+        let message =
+            destination.message(encode_chunk(stream_id, seq, is_final, &buf[..read]), lane);
+        input_sender
+            .send(message)
+            .await
+            .map_err(|_| StreamError::ClientStopped)?;
+
+        if is_final {
+            debug!("finished sending stream {stream_id} in {} chunks", seq + 1);
+            return Ok(stream_id);
+        }
+        seq += 1;
+    }
+}
+
+/// Payload received through a stream, yielded in order as the chunks arrive.
+pub struct ReconstructedStream {
+    stream_id: StreamId,
+    sender_tag: Option<AnonymousSenderTag>,
+    chunks: mpsc::UnboundedReceiver<Vec<u8>>,
+}
+
+impl ReconstructedStream {
+    pub fn id(&self) -> StreamId {
+        self.stream_id
+    }
+
+    /// Tag of the sender if the stream has been sent anonymously.
+    pub fn sender_tag(&self) -> Option<AnonymousSenderTag> {
+        self.sender_tag
+    }
+
+    /// Waits for the whole stream and returns its payload.
+    pub async fn read_to_end(mut self) -> Vec<u8> {
+        let mut payload = Vec::new();
+        while let Some(chunk) = self.chunks.next().await {
+            payload.extend_from_slice(&chunk)
+        }
+        payload
+    }
+}
+
+impl Stream for ReconstructedStream {
+    type Item = Vec<u8>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.chunks.poll_next_unpin(cx)
+    }
+}
+
+pub type ReconstructedStreamSender = mpsc::UnboundedSender<ReconstructedStream>;
+pub type ReconstructedStreamReceiver = mpsc::UnboundedReceiver<ReconstructedStream>;
+
+struct PartialStream {
+    next_seq: u64,
+    out_of_order: BTreeMap<u64, (bool, Vec<u8>)>,
+    chunks: mpsc::UnboundedSender<Vec<u8>>,
+    last_activity: Instant,
+}
+
+impl PartialStream {
+    // returns whether the stream has been completed
+    fn push(&mut self, chunk: StreamChunk<'_>) -> bool {
+        self.last_activity = get_time_now();
+        if chunk.seq < self.next_seq {
+            return false;
+        }
+        self.out_of_order
+            .insert(chunk.seq, (chunk.is_final, chunk.data.to_vec()));
+
+        while let Some((is_final, data)) = self.out_of_order.remove(&self.next_seq) {
+            self.next_seq += 1;
+            // if the receiver is gone, we still have to consume the remaining chunks
+            if !data.is_empty() {
+                let _ = self.chunks.unbounded_send(data);
+            }
+            if is_final {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[derive(Default)]
+struct IncomingStreamsInner {
+    listener: Option<ReconstructedStreamSender>,
+    streams: HashMap<(Option<AnonymousSenderTag>, StreamId), PartialStream>,
+}
+
+/// Streams currently being received, shared with the received messages buffer.
+#[derive(Clone, Default)]
+pub(crate) struct IncomingStreams {
+    inner: Arc<Mutex<IncomingStreamsInner>>,
+}
+
+impl IncomingStreams {
+    fn inner(&self) -> MutexGuard<'_, IncomingStreamsInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn set_listener(&self, listener: ReconstructedStreamSender) {
+        self.inner().listener = Some(listener)
+    }
+
+    /// Checks whether the received message is a stream chunk, in which case it gets forwarded
+    /// to its stream and the message should not be delivered any further.
+    pub(crate) fn try_consume(&self, message: &ReconstructedMessage) -> bool {
+        let Some(chunk) = decode_chunk(&message.message) else {
+            return false;
+        };
+
+        let mut inner = self.inner();
+        let Some(listener) = inner.listener.clone() else {
+            return false;
+        };
+
+        let now = get_time_now();
+        inner.streams.retain(|(_, stream_id), stream| {
+            let idle = now.duration_since(stream.last_activity) < STREAM_IDLE_TIMEOUT;
+            if !idle {
+                warn!("stream {stream_id} has been abandoned by its sender");
+            }
+            idle
+        });
+
+        let key = (message.sender_tag, chunk.stream_id);
+        if !inner.streams.contains_key(&key) {
+            let (chunks, receiver) = mpsc::unbounded();
+            let stream = ReconstructedStream {
+                stream_id: chunk.stream_id,
+                sender_tag: message.sender_tag,
+                chunks: receiver,
+            };
+            if listener.unbounded_send(stream).is_err() {
+                debug!("the stream receiver is gone - delivering the chunk as a regular message");
+                inner.listener = None;
+                return false;
+            }
+            inner.streams.insert(
+                key,
+                PartialStream {
+                    next_seq: 0,
+                    out_of_order: BTreeMap::new(),
+                    chunks,
+                    last_activity: now,
+                },
+            );
+        }
+
+        let completed = inner
+            .streams
+            .get_mut(&key)
+            .map(|stream| stream.push(chunk))
+            .unwrap_or_default();
+        if completed {
+            inner.streams.remove(&key);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk_message(seq: u64, is_final: bool, data: &[u8]) -> ReconstructedMessage {
+        ReconstructedMessage {
+            message: encode_chunk(42, seq, is_final, data),
+            sender_tag: None,
+            fragment_set_id: None,
+        }
+    }
+
+    #[test]
+    fn chunks_are_reassembled_in_order() {
+        let incoming = IncomingStreams::default();
+        assert!(!incoming.try_consume(&chunk_message(0, false, b"foo")));
+
+        let (listener, mut streams) = mpsc::unbounded();
+        incoming.set_listener(listener);
+
+        assert!(incoming.try_consume(&chunk_message(1, false, b"bar")));
+        assert!(incoming.try_consume(&chunk_message(2, true, b"baz")));
+        assert!(incoming.try_consume(&chunk_message(0, false, b"foo")));
+        assert!(incoming.inner().streams.is_empty());
+
+        let stream = streams.try_next().unwrap().unwrap();
+        assert_eq!(stream.id(), 42);
+        assert_eq!(
+            futures::executor::block_on(stream.read_to_end()),
+            b"foobarbaz"
+        );
+
+        // regular messages are never consumed
+        assert!(!incoming.try_consume(&ReconstructedMessage {
+            message: b"hello".to_vec(),
+            sender_tag: None,
+            fragment_set_id: None,
+        }));
+    }
+}
@@ -1,23 +1,41 @@
 // Copyright 2021-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::helpers::{get_time_now, Instant};
+use crate::client::topology_control::routability::{
+    RoutabilityListener, RoutabilityState, TopologyRoutability,
+};
+use crate::client::topology_control::routing_filter::RoutingFilter;
+use crate::error::ClientCoreError;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::params::DEFAULT_NUM_MIX_HOPS;
 use nym_topology::{NymTopology, NymTopologyError};
 use std::ops::Deref;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
 use tokio::sync::{Notify, RwLock, RwLockReadGuard};
 
 #[derive(Debug)]
 pub struct TopologyAccessorInner {
     controlled_manually: AtomicBool,
     released_manual_control: Notify,
+    // set while the network is transitioning between epochs and the active set might be changing
+    epoch_transition: AtomicBool,
+    // time of the most recent update that actually provided a topology
+    last_updated: Mutex<Option<Instant>>,
+    // whether the current topology can be used for sending packets, updated alongside the topology itself
+    routability: RoutabilityState,
     // `RwLock` *seems to* be the better approach for this as write access is only requested every
     // few seconds, while reads are needed every single packet generated.
     // However, proper benchmarks will be needed to determine if `RwLock` is indeed a better
     // approach than a `Mutex`
     topology: RwLock<Option<NymTopology>>,
+    // the topology as received, before the routing filter has been applied to it,
+    // so that the filter could be changed at runtime
+    unfiltered_topology: RwLock<Option<NymTopology>>,
+    routing_filter: Mutex<RoutingFilter>,
 }
 
 impl TopologyAccessorInner {
@@ -25,12 +43,48 @@ impl TopologyAccessorInner {
         TopologyAccessorInner {
             controlled_manually: AtomicBool::new(false),
             released_manual_control: Notify::new(),
+            epoch_transition: AtomicBool::new(false),
+            last_updated: Mutex::new(None),
+            routability: RoutabilityState::new(),
             topology: RwLock::new(None),
+            unfiltered_topology: RwLock::new(None),
+            routing_filter: Mutex::new(RoutingFilter::new()),
         }
     }
 
     async fn update(&self, new: Option<NymTopology>) {
-        *self.topology.write().await = new;
+        if new.is_some() {
+            *self
+                .last_updated
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = Some(get_time_now());
+        }
+        let mut unfiltered = self.unfiltered_topology.write().await;
+        *unfiltered = new;
+        self.apply_routing_filter(&unfiltered).await;
+    }
+
+    async fn set_routing_filter(&self, filter: RoutingFilter) {
+        // hold the lock so that we wouldn't race with the concurrent topology update
+        let unfiltered = self.unfiltered_topology.write().await;
+        *self
+            .routing_filter
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = filter;
+        self.apply_routing_filter(&unfiltered).await;
+    }
+
+    async fn apply_routing_filter(&self, unfiltered: &Option<NymTopology>) {
+        let filtered = {
+            let filter = self
+                .routing_filter
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            unfiltered.as_ref().map(|topology| filter.apply(topology))
+        };
+        let routability = TopologyRoutability::of(filtered.as_ref());
+        *self.topology.write().await = filtered;
+        self.routability.update(routability);
     }
 }
 
@@ -109,6 +163,55 @@ impl TopologyAccessor {
         self.inner.controlled_manually.load(Ordering::SeqCst)
     }
 
+    /// Returns the amount of time that has passed since the topology was last successfully updated,
+    /// or `None` if it has never been obtained.
+    pub fn time_since_last_update(&self) -> Option<Duration> {
+        let last_updated = *self
+            .inner
+            .last_updated
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        last_updated.map(|updated| get_time_now().duration_since(updated))
+    }
+
+    /// Returns whether the network is currently transitioning between epochs,
+    /// meaning the active set might be getting rotated and the current topology could be stale.
+    pub fn is_in_epoch_transition(&self) -> bool {
+        self.inner.epoch_transition.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn set_epoch_transition(&self, in_transition: bool) {
+        self.inner
+            .epoch_transition
+            .store(in_transition, Ordering::SeqCst);
+    }
+
+    /// Returns whether the current topology allows constructing routes through the mixnet.
+    pub fn routability(&self) -> TopologyRoutability {
+        self.inner.routability.current()
+    }
+
+    /// Subscribes to the changes of the routability of the topology, for example in order to find out
+    /// when a client started in the offline-tolerant mode is going to actually begin sending its messages.
+    pub fn routability_changes(&self) -> RoutabilityListener {
+        self.inner.routability.subscribe()
+    }
+
+    /// Returns the currently applied routing filter.
+    pub fn routing_filter(&self) -> RoutingFilter {
+        self.inner
+            .routing_filter
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Changes the constraints on the mix nodes used for constructing routes of all subsequent packets,
+    /// both real and cover, and applies them to the current topology straight away.
+    pub async fn set_routing_filter(&self, filter: RoutingFilter) {
+        self.inner.set_routing_filter(filter).await;
+    }
+
     pub async fn get_read_permit(&self) -> TopologyReadPermit<'_> {
         self.inner.topology.read().await.into()
     }
@@ -125,6 +228,25 @@ impl TopologyAccessor {
         self.inner.topology.read().await.clone()
     }
 
+    /// Saves the currently used network topology to the provided file. It can be later used via
+    /// `HardcodedTopologyProvider::new_from_file` in order to replay exactly the same network view.
+    pub async fn export_current_topology<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<(), ClientCoreError> {
+        let path = path.as_ref();
+        let Some(topology) = self.current_topology().await else {
+            return Err(NymTopologyError::EmptyNetworkTopology.into());
+        };
+
+        topology
+            .save_to_file(path)
+            .map_err(|source| ClientCoreError::TopologyExportFailure {
+                file_path: path.to_path_buf(),
+                source,
+            })
+    }
+
     pub async fn manually_change_topology(&self, new_topology: NymTopology) {
         self.inner.controlled_manually.store(true, Ordering::SeqCst);
         self.inner.update(Some(new_topology)).await;
@@ -0,0 +1,261 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Composable wrappers around [`TopologyProvider`]s, allowing to, for example, query nym-api first,
+//! fall back to a bundled topology file and keep on using the last known topology
+//! during temporary directory outages.
+
+use crate::client::helpers::{get_time_now, Instant};
+use log::{debug, warn};
+use nym_topology::provider_trait::{async_trait, EpochBoundary, TopologyProvider};
+use nym_topology::NymTopology;
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+type BoxedTopologyProvider = Box<dyn TopologyProvider + Send + Sync>;
+
+#[cfg(target_arch = "wasm32")]
+type BoxedTopologyProvider = Box<dyn TopologyProvider>;
+
+/// Provider querying the underlying providers in order and returning the first topology it manages to obtain.
+pub struct FallbackProvider {
+    providers: Vec<BoxedTopologyProvider>,
+}
+
+impl FallbackProvider {
+    pub fn new(primary: BoxedTopologyProvider) -> Self {
+        FallbackProvider {
+            providers: vec![primary],
+        }
+    }
+
+    /// Adds another provider that is going to be queried if all the previous ones have failed.
+    #[must_use]
+    pub fn with_fallback(mut self, provider: BoxedTopologyProvider) -> Self {
+        self.providers.push(provider);
+        self
+    }
+
+    async fn get_first_topology(&mut self) -> Option<NymTopology> {
+        for (i, provider) in self.providers.iter_mut().enumerate() {
+            if let Some(topology) = provider.get_new_topology().await {
+                if i > 0 {
+                    warn!("using topology from the fallback provider {i}");
+                }
+                return Some(topology);
+            }
+            debug!("topology provider {i} has failed to return a topology");
+        }
+        None
+    }
+
+    async fn get_first_epoch_boundary(&mut self) -> Option<EpochBoundary> {
+        for provider in self.providers.iter_mut() {
+            if let Some(boundary) = provider.epoch_boundary().await {
+                return Some(boundary);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+impl TopologyProvider for FallbackProvider {
+    async fn get_new_topology(&mut self) -> Option<NymTopology> {
+        self.get_first_topology().await
+    }
+
+    async fn epoch_boundary(&mut self) -> Option<EpochBoundary> {
+        self.get_first_epoch_boundary().await
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+#[async_trait(?Send)]
+impl TopologyProvider for FallbackProvider {
+    async fn get_new_topology(&mut self) -> Option<NymTopology> {
+        self.get_first_topology().await
+    }
+
+    async fn epoch_boundary(&mut self) -> Option<EpochBoundary> {
+        self.get_first_epoch_boundary().await
+    }
+}
+
+struct CachedTopology {
+    topology: NymTopology,
+    retrieved_at: Instant,
+}
+
+/// Provider caching the topology returned by the underlying provider.
+///
+/// The cached topology is returned without querying the inner provider for as long as it's
+/// younger than the specified ttl. Once it expires, the inner provider is queried again, and if
+/// that fails, the stale topology keeps on being served (for at most `max_staleness`, if set)
+/// until the revalidation succeeds.
+pub struct CachedProvider<P> {
+    inner: P,
+    ttl: Duration,
+    max_staleness: Option<Duration>,
+    cached: Option<CachedTopology>,
+}
+
+impl<P> CachedProvider<P> {
+    pub fn new(inner: P, ttl: Duration) -> Self {
+        CachedProvider {
+            inner,
+            ttl,
+            max_staleness: None,
+            cached: None,
+        }
+    }
+
+    /// Limits for how long past its ttl the cached topology can still be served
+    /// if the inner provider keeps on failing.
+    #[must_use]
+    pub fn with_max_staleness(mut self, max_staleness: Duration) -> Self {
+        self.max_staleness = Some(max_staleness);
+        self
+    }
+
+    fn cache_age(&self, now: Instant) -> Option<Duration> {
+        self.cached
+            .as_ref()
+            .map(|cached| now.duration_since(cached.retrieved_at))
+    }
+
+    async fn get_cached_topology(&mut self) -> Option<NymTopology>
+    where
+        P: TopologyProvider,
+    {
+        let now = get_time_now();
+        let age = self.cache_age(now);
+
+        if let Some(age) = age {
+            if age < self.ttl {
+                return self.cached.as_ref().map(|cached| cached.topology.clone());
+            }
+        }
+
+        if let Some(topology) = self.inner.get_new_topology().await {
+            self.cached = Some(CachedTopology {
+                topology: topology.clone(),
+                retrieved_at: now,
+            });
+            return Some(topology);
+        }
+
+        // revalidation has failed, see if we can still use the stale topology
+        let age = age?;
+        if let Some(max_staleness) = self.max_staleness {
+            if age >= self.ttl + max_staleness {
+                warn!("the cached topology is too stale to be used ({age:?} old)");
+                self.cached = None;
+                return None;
+            }
+        }
+
+        warn!("failed to revalidate the topology - using the cached one ({age:?} old)");
+        self.cached.as_ref().map(|cached| cached.topology.clone())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+impl<P> TopologyProvider for CachedProvider<P>
+where
+    P: TopologyProvider,
+{
+    async fn get_new_topology(&mut self) -> Option<NymTopology> {
+        self.get_cached_topology().await
+    }
+
+    async fn epoch_boundary(&mut self) -> Option<EpochBoundary> {
+        self.inner.epoch_boundary().await
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+#[async_trait(?Send)]
+impl<P> TopologyProvider for CachedProvider<P>
+where
+    P: TopologyProvider,
+{
+    async fn get_new_topology(&mut self) -> Option<NymTopology> {
+        self.get_cached_topology().await
+    }
+
+    async fn epoch_boundary(&mut self) -> Option<EpochBoundary> {
+        self.inner.epoch_boundary().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct MockProvider {
+        available: bool,
+        queries: Arc<AtomicUsize>,
+    }
+
+    impl MockProvider {
+        fn new(available: bool) -> (Self, Arc<AtomicUsize>) {
+            let queries = Arc::new(AtomicUsize::new(0));
+            (
+                MockProvider {
+                    available,
+                    queries: queries.clone(),
+                },
+                queries,
+            )
+        }
+    }
+
+    #[async_trait]
+    impl TopologyProvider for MockProvider {
+        async fn get_new_topology(&mut self) -> Option<NymTopology> {
+            self.queries.fetch_add(1, Ordering::SeqCst);
+            self.available.then(NymTopology::default)
+        }
+    }
+
+    #[test]
+    fn fallback_provider_stops_at_first_success() {
+        let (failing, failing_queries) = MockProvider::new(false);
+        let (working, working_queries) = MockProvider::new(true);
+        let (unused, unused_queries) = MockProvider::new(true);
+
+        let mut provider = FallbackProvider::new(Box::new(failing))
+            .with_fallback(Box::new(working))
+            .with_fallback(Box::new(unused));
+
+        assert!(block_on(provider.get_new_topology()).is_some());
+        assert_eq!(failing_queries.load(Ordering::SeqCst), 1);
+        assert_eq!(working_queries.load(Ordering::SeqCst), 1);
+        assert_eq!(unused_queries.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn cached_provider_serves_stale_topology_on_failure() {
+        let (inner, queries) = MockProvider::new(true);
+        let mut provider = CachedProvider::new(inner, Duration::from_secs(3600));
+
+        assert!(block_on(provider.get_new_topology()).is_some());
+        assert!(block_on(provider.get_new_topology()).is_some());
+        assert_eq!(queries.load(Ordering::SeqCst), 1);
+
+        // expire the cache and make the inner provider fail
+        provider.ttl = Duration::ZERO;
+        provider.inner.available = false;
+        assert!(block_on(provider.get_new_topology()).is_some());
+        assert_eq!(queries.load(Ordering::SeqCst), 2);
+
+        provider.max_staleness = Some(Duration::ZERO);
+        assert!(block_on(provider.get_new_topology()).is_none());
+    }
+}
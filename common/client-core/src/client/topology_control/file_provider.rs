@@ -0,0 +1,133 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::topology_control::apply_version_constraints;
+use log::{debug, error, info, warn};
+use nym_topology::filter::VersionConstraints;
+use nym_topology::provider_trait::{async_trait, TopologyProvider};
+use nym_topology::NymTopology;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use std::{fs, io};
+
+#[derive(Debug, thiserror::Error)]
+pub enum FileTopologyError {
+    #[error("failed to read the topology file: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("failed to parse the JSON topology: {0}")]
+    MalformedJson(#[from] serde_json::Error),
+
+    #[error("failed to parse the TOML topology: {0}")]
+    MalformedToml(#[from] toml::de::Error),
+}
+
+fn load_topology(path: &Path) -> Result<NymTopology, FileTopologyError> {
+    let content = fs::read_to_string(path)?;
+
+    let is_toml = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("toml"))
+        .unwrap_or_default();
+
+    if is_toml {
+        // go through an intermediate json value as toml only supports string keys,
+        // while the mix layers are keyed by their numbers
+        let value: serde_json::Value = toml::from_str(&content)?;
+        Ok(serde_json::from_value(value)?)
+    } else {
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+/// Provider of a static topology described in a local JSON or TOML file,
+/// for example for air-gapped test networks where no nym-api is reachable.
+///
+/// The file is watched for changes and re-loaded whenever it gets modified.
+/// If the new content turns out to be invalid, the previously loaded topology keeps on being used.
+pub struct FileTopologyProvider {
+    path: PathBuf,
+    version_constraints: Option<VersionConstraints>,
+    last_modified: Option<SystemTime>,
+    current: Option<NymTopology>,
+}
+
+impl FileTopologyProvider {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        FileTopologyProvider {
+            path: path.into(),
+            version_constraints: None,
+            last_modified: None,
+            current: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_version_constraints(mut self, version_constraints: VersionConstraints) -> Self {
+        self.version_constraints = Some(version_constraints);
+        self
+    }
+
+    fn modification_time(&self) -> Option<SystemTime> {
+        fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|err| warn!("failed to check the topology file metadata: {err}"))
+            .ok()
+    }
+
+    fn reload_if_modified(&mut self) {
+        let modified = self.modification_time();
+        if self.current.is_some() && modified.is_some() && modified == self.last_modified {
+            return;
+        }
+
+        debug!("loading topology from {}", self.path.display());
+        match load_topology(&self.path) {
+            Ok(mut topology) => {
+                if let Some(constraints) = &self.version_constraints {
+                    apply_version_constraints(&mut topology, constraints);
+                }
+                if self.current.is_some() {
+                    info!("the topology file {} has changed", self.path.display());
+                }
+                self.current = Some(topology);
+                self.last_modified = modified;
+            }
+            Err(err) => error!(
+                "failed to load the topology from {}: {err}",
+                self.path.display()
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl TopologyProvider for FileTopologyProvider {
+    async fn get_new_topology(&mut self) -> Option<NymTopology> {
+        self.reload_if_modified();
+        self.current.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn invalid_changes_keep_previous_topology() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("topology.json");
+        NymTopology::default().save_to_file(&path).unwrap();
+
+        let mut provider = FileTopologyProvider::new(&path);
+        assert!(block_on(provider.get_new_topology()).is_some());
+
+        fs::write(&path, "definitely not a topology").unwrap();
+        provider.last_modified = None;
+        assert!(block_on(provider.get_new_topology()).is_some());
+
+        let mut missing = FileTopologyProvider::new(dir.path().join("missing.json"));
+        assert!(block_on(missing.get_new_topology()).is_none());
+    }
+}
@@ -1,13 +1,16 @@
+use crate::client::topology_control::apply_version_constraints;
 use crate::config::GroupBy;
 use log::{debug, error};
-use nym_explorer_client::{ExplorerClient, PrettyDetailedMixNodeBond};
+use nym_explorer_client::{ExplorerClient, PrettyDetailedGatewayBond, PrettyDetailedMixNodeBond};
 use nym_network_defaults::var_names::EXPLORER_API;
 use nym_topology::{
+    filter::VersionConstraints,
     nym_topology_from_detailed,
     provider_trait::{async_trait, TopologyProvider},
     NymTopology,
 };
 use nym_validator_client::client::MixId;
+use nym_validator_client::models::{GatewayBondAnnotated, MixNodeBondAnnotated};
 use rand::{prelude::SliceRandom, thread_rng};
 use std::collections::HashMap;
 use tap::TapOptional;
@@ -17,7 +20,8 @@ pub use nym_country_group::CountryGroup;
 
 const MIN_NODES_PER_LAYER: usize = 1;
 
-fn create_explorer_client() -> Option<ExplorerClient> {
+#[cfg(not(target_arch = "wasm32"))]
+fn explorer_api_url_from_env() -> Option<Url> {
     let Ok(explorer_api_url) = std::env::var(EXPLORER_API) else {
         error!("Missing EXPLORER_API");
         return None;
@@ -27,6 +31,21 @@ fn create_explorer_client() -> Option<ExplorerClient> {
         error!("Failed to parse EXPLORER_API");
         return None;
     };
+    Some(explorer_api_url)
+}
+
+// there's no environment to speak of in the browser
+#[cfg(target_arch = "wasm32")]
+fn explorer_api_url_from_env() -> Option<Url> {
+    error!("No explorer-api url has been provided");
+    None
+}
+
+fn create_explorer_client(explorer_api_url: Option<&Url>) -> Option<ExplorerClient> {
+    let explorer_api_url = match explorer_api_url {
+        Some(url) => url.clone(),
+        None => explorer_api_url_from_env()?,
+    };
 
     log::debug!("Using explorer-api url: {}", explorer_api_url);
     let Ok(client) = nym_explorer_client::ExplorerClient::new(explorer_api_url) else {
@@ -37,20 +56,95 @@ fn create_explorer_client() -> Option<ExplorerClient> {
     Some(client)
 }
 
+// two-letter country codes of the nodes
+#[derive(Default)]
+struct NodeLocations {
+    mixnodes: Vec<(MixId, String)>,
+    gateways: HashMap<String, String>,
+}
+
+impl NodeLocations {
+    fn from_explorer_api(
+        mixnodes: Vec<PrettyDetailedMixNodeBond>,
+        gateways: Vec<PrettyDetailedGatewayBond>,
+    ) -> Self {
+        NodeLocations {
+            mixnodes: mixnodes
+                .into_iter()
+                .filter_map(|m| {
+                    m.location
+                        .map(|location| (m.mix_id, location.two_letter_iso_country_code))
+                })
+                .collect(),
+            gateways: gateways
+                .into_iter()
+                .filter_map(|g| {
+                    g.location.map(|location| {
+                        (g.gateway.identity_key, location.two_letter_iso_country_code)
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    fn from_nym_api(mixnodes: &[MixNodeBondAnnotated], gateways: &[GatewayBondAnnotated]) -> Self {
+        NodeLocations {
+            mixnodes: mixnodes
+                .iter()
+                .filter_map(|m| {
+                    m.location
+                        .as_ref()
+                        .map(|location| (m.mix_id(), location.two_letter_iso_country_code.clone()))
+                })
+                .collect(),
+            gateways: gateways
+                .iter()
+                .filter_map(|g| {
+                    g.location.as_ref().map(|location| {
+                        (
+                            g.identity().clone(),
+                            location.two_letter_iso_country_code.clone(),
+                        )
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
 fn group_mixnodes_by_country_code(
-    mixnodes: Vec<PrettyDetailedMixNodeBond>,
+    mixnodes: Vec<(MixId, String)>,
 ) -> HashMap<CountryGroup, Vec<MixId>> {
-    mixnodes
-        .into_iter()
-        .fold(HashMap::<CountryGroup, Vec<MixId>>::new(), |mut acc, m| {
-            if let Some(ref location) = m.location {
-                let country_code = location.two_letter_iso_country_code.clone();
-                let group_code = CountryGroup::new(country_code.as_str());
-                let mixnodes = acc.entry(group_code).or_default();
-                mixnodes.push(m.mix_id);
-            }
+    mixnodes.into_iter().fold(
+        HashMap::<CountryGroup, Vec<MixId>>::new(),
+        |mut acc, (mix_id, country_code)| {
+            let group_code = CountryGroup::new(country_code.as_str());
+            let mixnodes = acc.entry(group_code).or_default();
+            mixnodes.push(mix_id);
             acc
-        })
+        },
+    )
+}
+
+async fn get_locations_from_explorer_api(explorer_api_url: Option<&Url>) -> Option<NodeLocations> {
+    // Fetch mixnodes cached by explorer-api, with the purpose of getting their geolocation.
+    debug!("Fetching mixnodes from explorer-api...");
+    let explorer_client = create_explorer_client(explorer_api_url)?;
+    let Ok(mixnodes_from_explorer_api) = explorer_client.get_mixnodes().await else {
+        error!("failed to get mixnodes from explorer-api");
+        return None;
+    };
+
+    debug!("Fetching gateways from explorer-api...");
+    let Ok(gateways_from_explorer_api) = explorer_client.get_gateways().await else {
+        error!("failed to get mixnodes from explorer-api");
+        return None;
+    };
+
+    Some(NodeLocations::from_explorer_api(
+        mixnodes_from_explorer_api,
+        gateways_from_explorer_api,
+    ))
 }
 
 fn log_mixnode_distribution(mixnodes: &HashMap<CountryGroup, Vec<MixId>>) {
@@ -85,7 +179,11 @@ fn check_layer_integrity(topology: NymTopology) -> Result<(), ()> {
 pub struct GeoAwareTopologyProvider {
     validator_client: nym_validator_client::client::NymApiClient,
     filter_on: GroupBy,
-    client_version: String,
+    version_constraints: VersionConstraints,
+
+    // explorer-api used for the node locations if nym-api doesn't provide them.
+    // if not set, it's read from the environment (on native targets only)
+    explorer_api_url: Option<Url>,
 }
 
 impl GeoAwareTopologyProvider {
@@ -105,12 +203,31 @@ impl GeoAwareTopologyProvider {
                 nym_api_urls[0].clone(),
             ),
             filter_on,
-            client_version,
+            version_constraints: VersionConstraints::new_for_client(&client_version),
+            explorer_api_url: None,
         }
     }
 
+    /// Specifies the explorer-api to fall back to for the locations of the nodes,
+    /// which otherwise has to be provided via the `EXPLORER_API` environment variable.
+    #[must_use]
+    pub fn with_explorer_api_url(mut self, explorer_api_url: Url) -> Self {
+        self.explorer_api_url = Some(explorer_api_url);
+        self
+    }
+
+    #[must_use]
+    pub fn with_version_constraints(mut self, version_constraints: VersionConstraints) -> Self {
+        self.version_constraints = version_constraints;
+        self
+    }
+
     async fn get_topology(&self) -> Option<NymTopology> {
-        let mixnodes = match self.validator_client.get_cached_active_mixnodes().await {
+        let mixnodes = match self
+            .validator_client
+            .get_cached_active_mixnodes_detailed()
+            .await
+        {
             Err(err) => {
                 error!("failed to get network mixnodes - {err}");
                 return None;
@@ -118,7 +235,7 @@ impl GeoAwareTopologyProvider {
             Ok(mixes) => mixes,
         };
 
-        let gateways = match self.validator_client.get_cached_gateways().await {
+        let gateways = match self.validator_client.get_cached_gateways_detailed().await {
             Err(err) => {
                 error!("failed to get network gateways - {err}");
                 return None;
@@ -126,20 +243,13 @@ impl GeoAwareTopologyProvider {
             Ok(gateways) => gateways,
         };
 
-        // Also fetch mixnodes cached by explorer-api, with the purpose of getting their
-        // geolocation.
-        debug!("Fetching mixnodes from explorer-api...");
-        let explorer_client = create_explorer_client()?;
-        let Ok(mixnodes_from_explorer_api) = explorer_client.get_mixnodes().await else {
-            error!("failed to get mixnodes from explorer-api");
-            return None;
-        };
-
-        debug!("Fetching gateways from explorer-api...");
-        let Ok(gateways_from_explorer_api) = explorer_client.get_gateways().await else {
-            error!("failed to get mixnodes from explorer-api");
-            return None;
-        };
+        // Prefer the locations reconciled by the nym-api (i.e. operator attestations cross-checked
+        // against GeoIP) and only fall back to the explorer-api if they're not available.
+        let mut locations = NodeLocations::from_nym_api(&mixnodes, &gateways);
+        if locations.mixnodes.is_empty() {
+            debug!("nym-api did not provide any node locations, falling back to explorer-api");
+            locations = get_locations_from_explorer_api(self.explorer_api_url.as_ref()).await?;
+        }
 
         // Determine what we should filter around
         let filter_on = match self.filter_on {
@@ -149,13 +259,11 @@ impl GeoAwareTopologyProvider {
                 // using that as the country code.
                 let gateway = recipient.gateway().to_base58_string();
 
-                // Lookup the location of this gateway by using the location data from the
-                // explorer-api
-                let gateway_location = gateways_from_explorer_api
-                    .iter()
-                    .find(|g| g.gateway.identity_key == gateway)
-                    .and_then(|g| g.location.clone())
-                    .map(|location| location.two_letter_iso_country_code)
+                // Lookup the location of this gateway
+                let gateway_location = locations
+                    .gateways
+                    .get(&gateway)
+                    .cloned()
                     .tap_none(|| error!("No location found for the gateway: {}", gateway))?;
                 debug!(
                     "Filtering on nym-address: {}, with location: {}",
@@ -167,12 +275,11 @@ impl GeoAwareTopologyProvider {
         };
         debug!("Filter group: {}", filter_on);
 
-        // Partition mixnodes_from_explorer_api according to the value of
-        // two_letter_iso_country_code.
+        // Partition mixnodes according to the value of two_letter_iso_country_code.
         // NOTE: we construct the full distribution here, but only use the one we're interested in.
         // The reason we this instead of a straight filter is that this opens up the possibility to
         // complement a small grouping with mixnodes from adjecent countries.
-        let mixnode_distribution = group_mixnodes_by_country_code(mixnodes_from_explorer_api);
+        let mixnode_distribution = group_mixnodes_by_country_code(locations.mixnodes);
         log_mixnode_distribution(&mixnode_distribution);
 
         let Some(filtered_mixnode_ids) = mixnode_distribution.get(&filter_on) else {
@@ -183,10 +290,15 @@ impl GeoAwareTopologyProvider {
         let mixnodes = mixnodes
             .into_iter()
             .filter(|m| filtered_mixnode_ids.contains(&m.mix_id()))
+            .map(|m| m.mixnode_details)
+            .collect::<Vec<_>>();
+        let gateways = gateways
+            .into_iter()
+            .map(|g| g.gateway_bond)
             .collect::<Vec<_>>();
 
-        let topology = nym_topology_from_detailed(mixnodes, gateways)
-            .filter_system_version(&self.client_version);
+        let mut topology = nym_topology_from_detailed(mixnodes, gateways);
+        apply_version_constraints(&mut topology, &self.version_constraints);
 
         // TODO: return real error type
         check_layer_integrity(topology.clone()).ok()?;
@@ -1,13 +1,23 @@
 // Copyright 2021-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::control::{next_parameters_change, RuntimeParametersListener};
+use crate::client::events::{ClientEvent, ClientEventSender};
+use crate::client::helpers::{get_time_now, new_interval_stream, Instant};
+use crate::client::roaming::{next_network_change, NetworkChange, NetworkChangeListener};
+use crate::config;
+use crate::error::ClientCoreStatusMessage;
 use crate::spawn_future;
 pub(crate) use accessor::{TopologyAccessor, TopologyReadPermit};
 use futures::StreamExt;
 use log::*;
+use nym_metrics::{inc, observe};
 use nym_sphinx::addressing::nodes::NodeIdentity;
-use nym_topology::provider_trait::TopologyProvider;
-use nym_topology::NymTopologyError;
+use nym_topology::filter::VersionConstraints;
+use nym_topology::provider_trait::{EpochBoundary, TopologyProvider};
+use nym_topology::{NymTopology, NymTopologyError};
+pub use routability::{RoutabilityListener, TopologyRoutability};
+pub use routing_filter::{NodeAnnotation, RoutingFilter};
 use std::time::Duration;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -17,19 +27,84 @@ use tokio::time::sleep;
 use wasmtimer::tokio::sleep;
 
 mod accessor;
+pub mod combinators;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod file_provider;
 pub mod geo_aware_provider;
 pub(crate) mod nym_api_provider;
+mod routability;
+pub mod routing_filter;
 
 // TODO: move it to config later
 const MAX_FAILURE_COUNT: usize = 10;
 
+// how often the topology gets refreshed while we're waiting for the new epoch to begin
+const EPOCH_TRANSITION_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+// how old the topology can get before it gets refreshed straight away after a network change
+const NETWORK_CHANGE_STALENESS_THRESHOLD: Duration = Duration::from_secs(60);
+
+// how often the topology gets refreshed while it's not routable, for example if the client has started offline
+const UNROUTABLE_TOPOLOGY_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Creates version constraints for the nodes used by this client, based on its own version
+/// and any explicitly configured minimum node versions.
+pub(crate) fn version_constraints(
+    client_version: &str,
+    topology_config: &config::Topology,
+) -> VersionConstraints {
+    let to_semver =
+        |v: config::NodeVersionRequirement| semver::Version::new(v.major, v.minor, v.patch);
+
+    VersionConstraints::new_for_client(client_version)
+        .with_minimum_mixnode_version(topology_config.minimum_mixnode_version.map(to_semver))
+        .with_minimum_gateway_version(topology_config.minimum_gateway_version.map(to_semver))
+}
+
+/// Removes nodes that do not satisfy the version constraints from the topology
+/// and reports how many of them got filtered out.
+pub(crate) fn apply_version_constraints(
+    topology: &mut NymTopology,
+    constraints: &VersionConstraints,
+) {
+    let result = topology.apply_version_constraints(constraints);
+    if result.total() > 0 {
+        info!(
+            "ignoring {} mixnodes and {} gateways running incompatible versions",
+            result.filtered_mixnodes, result.filtered_gateways
+        );
+    }
+
+    nym_metrics::REGISTRY.set(
+        "nym_client_core_topology_version_filtered_mixnodes",
+        result.filtered_mixnodes as i64,
+    );
+    nym_metrics::REGISTRY.set(
+        "nym_client_core_topology_version_filtered_gateways",
+        result.filtered_gateways as i64,
+    );
+}
+
 pub struct TopologyRefresherConfig {
     refresh_rate: Duration,
+
+    /// If specified, the refresher is going to keep track of epoch boundaries
+    /// and mark the topology as transitioning for at most this long once the epoch ends.
+    epoch_transition_max_hold: Option<Duration>,
 }
 
 impl TopologyRefresherConfig {
     pub fn new(refresh_rate: Duration) -> Self {
-        TopologyRefresherConfig { refresh_rate }
+        TopologyRefresherConfig {
+            refresh_rate,
+            epoch_transition_max_hold: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_epoch_transition_hold(mut self, max_hold: Duration) -> Self {
+        self.epoch_transition_max_hold = Some(max_hold);
+        self
     }
 }
 
@@ -39,6 +114,16 @@ pub struct TopologyRefresher {
 
     refresh_rate: Duration,
     consecutive_failure_count: usize,
+    last_successful_refresh: Option<Instant>,
+    network_changes: Option<NetworkChangeListener>,
+    runtime_parameters: Option<RuntimeParametersListener>,
+    client_events: ClientEventSender,
+
+    epoch_transition_max_hold: Option<Duration>,
+    epoch_boundary: Option<EpochBoundary>,
+    // id of the last epoch whose end we have already dealt with,
+    // so that we wouldn't keep on holding traffic if the nym-api is slow to advance the epoch
+    handled_epoch: Option<u32>,
 }
 
 impl TopologyRefresher {
@@ -52,9 +137,34 @@ impl TopologyRefresher {
             topology_accessor,
             refresh_rate: cfg.refresh_rate,
             consecutive_failure_count: 0,
+            last_successful_refresh: None,
+            network_changes: None,
+            runtime_parameters: None,
+            client_events: Default::default(),
+            epoch_transition_max_hold: cfg.epoch_transition_max_hold,
+            epoch_boundary: None,
+            handled_epoch: None,
         }
     }
 
+    #[must_use]
+    pub(crate) fn with_network_change_listener(mut self, listener: NetworkChangeListener) -> Self {
+        self.network_changes = Some(listener);
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_runtime_parameters(mut self, listener: RuntimeParametersListener) -> Self {
+        self.runtime_parameters = Some(listener);
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_client_events(mut self, client_events: ClientEventSender) -> Self {
+        self.client_events = client_events;
+        self
+    }
+
     pub fn change_topology_provider(&mut self, provider: Box<dyn TopologyProvider + Send + Sync>) {
         self.topology_provider = provider;
     }
@@ -69,9 +179,18 @@ impl TopologyRefresher {
                 .await;
         }
 
+        let started = get_time_now();
         let new_topology = self.topology_provider.get_new_topology().await;
+        let refreshed = new_topology.is_some();
+        observe!(
+            "topology_refresh_duration_seconds",
+            get_time_now().duration_since(started).as_secs_f64()
+        );
         if new_topology.is_none() {
             warn!("failed to obtain new network topology");
+            inc!("topology_refresh_failures");
+        } else {
+            inc!("topology_refreshes");
         }
 
         if new_topology.is_none() && self.consecutive_failure_count < MAX_FAILURE_COUNT {
@@ -82,11 +201,109 @@ impl TopologyRefresher {
             return;
         } else if new_topology.is_some() {
             self.consecutive_failure_count = 0;
+            self.last_successful_refresh = Some(get_time_now());
         }
 
         self.topology_accessor
             .update_global_topology(new_topology)
             .await;
+
+        if refreshed {
+            self.client_events.emit(ClientEvent::TopologyRefreshed {
+                routable: self.topology_accessor.routability().is_routable(),
+            });
+        }
+
+        if self.epoch_transition_max_hold.is_some() {
+            self.refresh_epoch_boundary().await;
+        }
+    }
+
+    /// Checks whether the topology might have gone stale, for example because we were unable
+    /// to refresh it while the client was offline.
+    fn is_stale(&self) -> bool {
+        if self.consecutive_failure_count > 0 {
+            return true;
+        }
+        match self.last_successful_refresh {
+            Some(last_refresh) => {
+                get_time_now().duration_since(last_refresh) > NETWORK_CHANGE_STALENESS_THRESHOLD
+            }
+            None => true,
+        }
+    }
+
+    async fn on_network_change(&mut self, change: NetworkChange) {
+        if change == NetworkChange::Available && self.is_stale() {
+            info!("refreshing the potentially stale topology after the network change");
+            self.try_refresh().await;
+        }
+    }
+
+    async fn refresh_epoch_boundary(&mut self) {
+        if let Some(boundary) = self.topology_provider.epoch_boundary().await {
+            if self.epoch_boundary != Some(boundary) {
+                debug!(
+                    "epoch {} is expected to end at {}",
+                    boundary.epoch_id, boundary.epoch_end
+                );
+            }
+            self.epoch_boundary = Some(boundary);
+        }
+    }
+
+    /// Returns the amount of time remaining until the end of the current epoch,
+    /// if we're tracking epoch boundaries and haven't already dealt with this one.
+    fn time_until_epoch_transition(&self) -> Option<Duration> {
+        self.epoch_transition_max_hold?;
+        let boundary = self.epoch_boundary?;
+        if self.handled_epoch == Some(boundary.epoch_id) {
+            return None;
+        }
+        Some(boundary.until_end())
+    }
+
+    /// Marks the topology as transitioning and keeps on refreshing it until either
+    /// the new epoch has begun or the maximum hold duration has elapsed.
+    async fn handle_epoch_transition(&mut self) {
+        let (Some(max_hold), Some(boundary)) =
+            (self.epoch_transition_max_hold, self.epoch_boundary)
+        else {
+            return;
+        };
+
+        info!(
+            "epoch {} has ended - going to hold non-urgent traffic until the new topology is available",
+            boundary.epoch_id
+        );
+        self.handled_epoch = Some(boundary.epoch_id);
+        self.topology_accessor.set_epoch_transition(true);
+
+        let deadline = sleep(max_hold);
+        tokio::pin!(deadline);
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = &mut deadline => {
+                    warn!(
+                        "the new epoch did not begin within {max_hold:?} - resuming normal traffic with the existing topology"
+                    );
+                    break;
+                }
+                _ = sleep(EPOCH_TRANSITION_REFRESH_INTERVAL) => {
+                    self.try_refresh().await;
+                    if let Some(current) = self.epoch_boundary {
+                        if current.epoch_id > boundary.epoch_id {
+                            info!("epoch {} has begun - resuming normal traffic", current.epoch_id);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        self.topology_accessor.set_epoch_transition(false);
     }
 
     pub async fn ensure_topology_is_routable(&self) -> Result<(), NymTopologyError> {
@@ -146,20 +363,46 @@ impl TopologyRefresher {
         spawn_future(async move {
             debug!("Started TopologyRefresher with graceful shutdown support");
 
-            #[cfg(not(target_arch = "wasm32"))]
-            let mut interval = tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(
-                self.refresh_rate,
-            ));
-
-            #[cfg(target_arch = "wasm32")]
-            let mut interval =
-                gloo_timers::future::IntervalStream::new(self.refresh_rate.as_millis() as u32);
+            let mut interval = new_interval_stream(self.refresh_rate);
+            let mut routability_changes = self.topology_accessor.routability_changes();
 
             while !shutdown.is_shutdown() {
+                let until_epoch_transition = self.time_until_epoch_transition();
+                let routability = self.topology_accessor.routability();
+
                 tokio::select! {
                     _ = interval.next() => {
                         self.try_refresh().await;
                     },
+                    _ = wait_for_unroutable_retry(routability) => {
+                        debug!("the topology is still not routable - refreshing it ahead of schedule");
+                        self.try_refresh().await;
+                    },
+                    Some(routability) = routability_changes.next() => {
+                        let status = match routability {
+                            TopologyRoutability::Routable => ClientCoreStatusMessage::TopologyRoutable,
+                            TopologyRoutability::Unroutable => ClientCoreStatusMessage::TopologyUnroutable,
+                        };
+                        shutdown.send_status_msg(Box::new(status));
+                    },
+                    Some(change) = next_network_change(self.network_changes.as_mut()) => {
+                        self.on_network_change(change).await;
+                    },
+                    Some(parameters) = next_parameters_change(self.runtime_parameters.as_mut()) => {
+                        if parameters.topology_refresh_rate != self.refresh_rate {
+                            info!("changing the topology refresh rate to {:?}", parameters.topology_refresh_rate);
+                            self.refresh_rate = parameters.topology_refresh_rate;
+                            interval = new_interval_stream(self.refresh_rate);
+                        }
+                    },
+                    _ = wait_for_epoch_transition(until_epoch_transition) => {
+                        tokio::select! {
+                            _ = self.handle_epoch_transition() => {},
+                            _ = shutdown.recv() => {
+                                log::trace!("TopologyRefresher: Received shutdown");
+                            },
+                        }
+                    },
                     _ = shutdown.recv() => {
                         log::trace!("TopologyRefresher: Received shutdown");
                     },
@@ -170,3 +413,140 @@ impl TopologyRefresher {
         })
     }
 }
+
+async fn wait_for_unroutable_retry(routability: TopologyRoutability) {
+    match routability {
+        TopologyRoutability::Unroutable => sleep(UNROUTABLE_TOPOLOGY_REFRESH_INTERVAL).await,
+        TopologyRoutability::Routable => std::future::pending().await,
+    }
+}
+
+async fn wait_for_epoch_transition(until_transition: Option<Duration>) {
+    match until_transition {
+        Some(remaining) => sleep(remaining).await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_topology::provider_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+    use time::OffsetDateTime;
+    use tokio::time::Instant;
+
+    struct EpochProvider {
+        accessor: TopologyAccessor,
+        // number of refreshes after which the provider reports the next epoch
+        new_epoch_after: Option<usize>,
+        // whether the traffic was being held during the particular refresh
+        held_during_refreshes: Arc<Mutex<Vec<bool>>>,
+    }
+
+    #[async_trait]
+    impl TopologyProvider for EpochProvider {
+        async fn get_new_topology(&mut self) -> Option<NymTopology> {
+            self.held_during_refreshes
+                .lock()
+                .unwrap()
+                .push(self.accessor.is_in_epoch_transition());
+            Some(NymTopology::default())
+        }
+
+        async fn epoch_boundary(&mut self) -> Option<EpochBoundary> {
+            let refreshes = self.held_during_refreshes.lock().unwrap().len();
+            let epoch_id = match self.new_epoch_after {
+                Some(after) if refreshes >= after => 2,
+                _ => 1,
+            };
+            Some(EpochBoundary::new(
+                epoch_id,
+                OffsetDateTime::now_utc() + time::Duration::hours(1),
+            ))
+        }
+    }
+
+    fn refresher_at_epoch_end(
+        max_hold: Duration,
+        new_epoch_after: Option<usize>,
+    ) -> (TopologyRefresher, TopologyAccessor, Arc<Mutex<Vec<bool>>>) {
+        let accessor = TopologyAccessor::new();
+        let held_during_refreshes = Arc::new(Mutex::new(Vec::new()));
+        let provider = EpochProvider {
+            accessor: accessor.clone(),
+            new_epoch_after,
+            held_during_refreshes: held_during_refreshes.clone(),
+        };
+
+        let cfg = TopologyRefresherConfig::new(Duration::from_secs(60))
+            .with_epoch_transition_hold(max_hold);
+        let mut refresher = TopologyRefresher::new(cfg, accessor.clone(), Box::new(provider));
+        refresher.epoch_boundary = Some(EpochBoundary::new(1, OffsetDateTime::now_utc()));
+        (refresher, accessor, held_during_refreshes)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn traffic_is_held_until_the_new_epoch_begins() {
+        let max_hold = Duration::from_secs(60);
+        let (mut refresher, accessor, held_during_refreshes) =
+            refresher_at_epoch_end(max_hold, Some(3));
+        assert_eq!(
+            refresher.time_until_epoch_transition(),
+            Some(Duration::ZERO)
+        );
+
+        let started = Instant::now();
+        refresher.handle_epoch_transition().await;
+
+        // the traffic has been held throughout the transition...
+        assert_eq!(*held_during_refreshes.lock().unwrap(), vec![true; 3]);
+        assert!(started.elapsed() >= 3 * EPOCH_TRANSITION_REFRESH_INTERVAL);
+        assert!(started.elapsed() < max_hold);
+
+        // ...and released as soon as the new epoch has begun
+        assert!(!accessor.is_in_epoch_transition());
+        assert_eq!(refresher.epoch_boundary.unwrap().epoch_id, 2);
+        assert!(refresher.time_until_epoch_transition().is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn traffic_is_released_after_the_maximum_hold() {
+        let max_hold = Duration::from_secs(9);
+        let (mut refresher, accessor, held_during_refreshes) =
+            refresher_at_epoch_end(max_hold, None);
+
+        let started = Instant::now();
+        refresher.handle_epoch_transition().await;
+
+        assert!(started.elapsed() >= max_hold);
+        assert!(started.elapsed() < max_hold + EPOCH_TRANSITION_REFRESH_INTERVAL);
+        assert_eq!(*held_during_refreshes.lock().unwrap(), vec![true; 4]);
+        assert!(!accessor.is_in_epoch_transition());
+
+        // even though the nym-api still reports the same epoch, we're not going to hold the traffic again
+        assert_eq!(refresher.epoch_boundary.unwrap().epoch_id, 1);
+        assert_eq!(refresher.time_until_epoch_transition(), None);
+    }
+
+    #[tokio::test]
+    async fn traffic_is_never_held_without_the_hold_configured() {
+        let accessor = TopologyAccessor::new();
+        let provider = EpochProvider {
+            accessor: accessor.clone(),
+            new_epoch_after: None,
+            held_during_refreshes: Default::default(),
+        };
+        let cfg = TopologyRefresherConfig::new(Duration::from_secs(60));
+        let mut refresher = TopologyRefresher::new(cfg, accessor.clone(), Box::new(provider));
+
+        refresher.try_refresh().await;
+        assert!(refresher.epoch_boundary.is_none());
+
+        refresher.epoch_boundary = Some(EpochBoundary::new(1, OffsetDateTime::now_utc()));
+        assert_eq!(refresher.time_until_epoch_transition(), None);
+        refresher.handle_epoch_transition().await;
+        assert!(!accessor.is_in_epoch_transition());
+        assert_eq!(refresher.handled_epoch, None);
+    }
+}
@@ -1,9 +1,11 @@
 // Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::topology_control::apply_version_constraints;
 use async_trait::async_trait;
 use log::{debug, error, warn};
-use nym_topology::provider_trait::TopologyProvider;
+use nym_topology::filter::VersionConstraints;
+use nym_topology::provider_trait::{EpochBoundary, TopologyProvider};
 use nym_topology::{NymTopology, NymTopologyError};
 use nym_validator_client::UserAgent;
 use rand::prelude::SliceRandom;
@@ -17,6 +19,11 @@ pub const DEFAULT_MIN_GATEWAY_PERFORMANCE: u8 = 50;
 pub(crate) struct Config {
     pub(crate) min_mixnode_performance: u8,
     pub(crate) min_gateway_performance: u8,
+
+    /// Specifies whether gateways annotated as standby should be kept as failover candidates
+    /// regardless of their performance.
+    pub(crate) include_standby_gateways: bool,
+    pub(crate) version_constraints: VersionConstraints,
 }
 
 impl Default for Config {
@@ -25,6 +32,8 @@ impl Default for Config {
         Config {
             min_mixnode_performance: DEFAULT_MIN_MIXNODE_PERFORMANCE,
             min_gateway_performance: DEFAULT_MIN_GATEWAY_PERFORMANCE,
+            include_standby_gateways: false,
+            version_constraints: VersionConstraints::new_for_client(env!("CARGO_PKG_VERSION")),
         }
     }
 }
@@ -108,11 +117,17 @@ impl NymApiTopologyProvider {
             Ok(mixes) => mixes,
         };
 
-        let gateways = match self
-            .validator_client
-            .get_basic_gateways(Some(self.client_version.clone()))
-            .await
-        {
+        let gateways = if self.config.include_standby_gateways {
+            self.validator_client
+                .get_basic_gateways_with_standby(Some(self.client_version.clone()))
+                .await
+        } else {
+            self.validator_client
+                .get_basic_gateways(Some(self.client_version.clone()))
+                .await
+        };
+
+        let gateways = match gateways {
             Err(err) => {
                 error!("failed to get network gateways - {err}");
                 return None;
@@ -126,14 +141,17 @@ impl NymApiTopologyProvider {
             gateways.len()
         );
 
-        let topology = NymTopology::from_unordered(
+        let mut topology = NymTopology::from_unordered(
             mixnodes.iter().filter(|m| {
                 m.performance.round_to_integer() >= self.config.min_mixnode_performance
             }),
             gateways.iter().filter(|g| {
-                g.performance.round_to_integer() >= self.config.min_gateway_performance
+                // standby gateways are only returned if we explicitly asked for them
+                g.role.is_standby()
+                    || g.performance.round_to_integer() >= self.config.min_gateway_performance
             }),
         );
+        apply_version_constraints(&mut topology, &self.config.version_constraints);
 
         if let Err(err) = self.check_layer_distribution(&topology) {
             warn!("The current filtered active topology has extremely skewed layer distribution. It cannot be used: {err}");
@@ -143,6 +161,23 @@ impl NymApiTopologyProvider {
             Some(topology)
         }
     }
+
+    async fn get_current_epoch_boundary(&mut self) -> Option<EpochBoundary> {
+        match self.validator_client.get_current_epoch().await {
+            Err(err) => {
+                warn!("failed to get current epoch information - {err}");
+                None
+            }
+            Ok(None) => {
+                debug!("the nym api does not have information about the current epoch");
+                None
+            }
+            Ok(Some(interval)) => Some(EpochBoundary::new(
+                interval.current_epoch_absolute_id(),
+                interval.current_epoch_end(),
+            )),
+        }
+    }
 }
 
 // hehe, wasm
@@ -152,6 +187,10 @@ impl TopologyProvider for NymApiTopologyProvider {
     async fn get_new_topology(&mut self) -> Option<NymTopology> {
         self.get_current_compatible_topology().await
     }
+
+    async fn epoch_boundary(&mut self) -> Option<EpochBoundary> {
+        self.get_current_epoch_boundary().await
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -160,4 +199,8 @@ impl TopologyProvider for NymApiTopologyProvider {
     async fn get_new_topology(&mut self) -> Option<NymTopology> {
         self.get_current_compatible_topology().await
     }
+
+    async fn epoch_boundary(&mut self) -> Option<EpochBoundary> {
+        self.get_current_epoch_boundary().await
+    }
 }
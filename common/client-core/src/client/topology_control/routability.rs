@@ -0,0 +1,127 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use log::*;
+use nym_sphinx::params::DEFAULT_NUM_MIX_HOPS;
+use nym_topology::NymTopology;
+use tokio::sync::watch;
+
+/// Indicates whether the current network topology allows constructing routes through the mixnet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopologyRoutability {
+    /// There's either no topology at all or it's missing nodes on some of the layers,
+    /// so no packets can currently be sent.
+    Unroutable,
+
+    /// Packets can be routed through the current topology.
+    Routable,
+}
+
+impl TopologyRoutability {
+    pub(crate) fn of(topology: Option<&NymTopology>) -> Self {
+        match topology {
+            Some(topology)
+                if topology
+                    .ensure_can_construct_path_through(DEFAULT_NUM_MIX_HOPS)
+                    .is_ok() =>
+            {
+                TopologyRoutability::Routable
+            }
+            _ => TopologyRoutability::Unroutable,
+        }
+    }
+
+    pub fn is_routable(&self) -> bool {
+        matches!(self, TopologyRoutability::Routable)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RoutabilityState {
+    sender: watch::Sender<TopologyRoutability>,
+}
+
+impl RoutabilityState {
+    pub(crate) fn new() -> Self {
+        RoutabilityState {
+            sender: watch::channel(TopologyRoutability::Unroutable).0,
+        }
+    }
+
+    pub(crate) fn current(&self) -> TopologyRoutability {
+        *self.sender.borrow()
+    }
+
+    pub(crate) fn update(&self, routability: TopologyRoutability) {
+        self.sender.send_if_modified(|current| {
+            if *current == routability {
+                return false;
+            }
+            match routability {
+                TopologyRoutability::Routable => info!("the network topology is now routable"),
+                TopologyRoutability::Unroutable => {
+                    warn!("the network topology is no longer routable")
+                }
+            }
+            *current = routability;
+            true
+        });
+    }
+
+    pub(crate) fn subscribe(&self) -> RoutabilityListener {
+        RoutabilityListener {
+            receiver: self.sender.subscribe(),
+        }
+    }
+}
+
+/// Stream of the changes of the routability of the network topology.
+#[derive(Debug, Clone)]
+pub struct RoutabilityListener {
+    receiver: watch::Receiver<TopologyRoutability>,
+}
+
+impl RoutabilityListener {
+    /// Returns the most recently observed routability of the topology.
+    pub fn current(&self) -> TopologyRoutability {
+        *self.receiver.borrow()
+    }
+
+    /// Waits until the routability changes. Returns `None` once the client has been shut down.
+    pub async fn next(&mut self) -> Option<TopologyRoutability> {
+        self.receiver.changed().await.ok()?;
+        Some(*self.receiver.borrow_and_update())
+    }
+
+    /// Waits until the topology becomes routable, returning immediately if it already is.
+    pub async fn wait_until_routable(&mut self) {
+        // an error means the client has been shut down, in which case there's nothing to wait for
+        let _ = self
+            .receiver
+            .wait_for(TopologyRoutability::is_routable)
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    #[test]
+    fn only_actual_changes_are_emitted() {
+        let state = RoutabilityState::new();
+        let mut listener = state.subscribe();
+        assert!(!state.current().is_routable());
+
+        state.update(TopologyRoutability::Unroutable);
+        assert!(listener.next().now_or_never().is_none());
+
+        state.update(TopologyRoutability::Routable);
+        assert_eq!(
+            listener.next().now_or_never(),
+            Some(Some(TopologyRoutability::Routable))
+        );
+        assert!(listener.wait_until_routable().now_or_never().is_some());
+    }
+}
@@ -0,0 +1,251 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Application-supplied constraints on the mix nodes that are allowed to be used for constructing
+//! packet routes. The filter is applied by the [`TopologyAccessor`](super::TopologyAccessor) to every
+//! topology it receives, so that both the real and the cover traffic are always routed through
+//! exactly the same set of nodes.
+
+use crate::error::ClientCoreError;
+use log::{debug, trace};
+use nym_crypto::asymmetric::identity;
+use nym_topology::{mix, NymTopology};
+use nym_validator_client::client::NymApiClient;
+use std::collections::{HashMap, HashSet};
+
+/// Information about a mix node that is not part of the topology itself,
+/// but is needed for evaluating the owner and country based rules of the [`RoutingFilter`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodeAnnotation {
+    /// Address of the account operating the node.
+    pub owner: Option<String>,
+
+    /// Two-letter ISO country code of the node location.
+    pub country: Option<String>,
+}
+
+/// Set of rules specifying which mix nodes must not be used for constructing routes.
+#[derive(Debug, Clone, Default)]
+pub struct RoutingFilter {
+    excluded_identities: HashSet<String>,
+    excluded_owners: HashSet<String>,
+    excluded_countries: HashSet<String>,
+
+    /// Specifies whether nodes with unknown location should be excluded if any countries are.
+    exclude_unknown_countries: bool,
+
+    /// Additional node information, keyed by their base58-encoded identity keys.
+    annotations: HashMap<String, NodeAnnotation>,
+}
+
+impl RoutingFilter {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    #[must_use]
+    pub fn with_excluded_identities(
+        mut self,
+        identities: impl IntoIterator<Item = identity::PublicKey>,
+    ) -> Self {
+        self.excluded_identities
+            .extend(identities.into_iter().map(|id| id.to_base58_string()));
+        self
+    }
+
+    #[must_use]
+    pub fn with_excluded_owners<T: Into<String>>(
+        mut self,
+        owners: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.excluded_owners
+            .extend(owners.into_iter().map(Into::into));
+        self
+    }
+
+    /// Excludes nodes located in any of the specified countries, given as two-letter ISO codes.
+    /// Note that the topology does not include node locations, so they have to be provided
+    /// via [`RoutingFilter::with_node_annotations`].
+    #[must_use]
+    pub fn with_excluded_countries<T: AsRef<str>>(
+        mut self,
+        countries: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.excluded_countries.extend(
+            countries
+                .into_iter()
+                .map(|country| country.as_ref().to_uppercase()),
+        );
+        self
+    }
+
+    /// Specifies whether, if any countries are excluded, nodes with unknown location should be excluded as well.
+    /// By default, they're allowed.
+    #[must_use]
+    pub fn with_unknown_countries_excluded(mut self, exclude_unknown_countries: bool) -> Self {
+        self.exclude_unknown_countries = exclude_unknown_countries;
+        self
+    }
+
+    /// Provides additional information about the nodes, keyed by their base58-encoded identity keys.
+    #[must_use]
+    pub fn with_node_annotations(
+        mut self,
+        annotations: impl IntoIterator<Item = (String, NodeAnnotation)>,
+    ) -> Self {
+        self.annotations.extend(annotations);
+        self
+    }
+
+    /// Obtains the owners and locations of all active mix nodes from the provided nym-api.
+    pub async fn with_node_annotations_from_nym_api(
+        self,
+        nym_api: &NymApiClient,
+    ) -> Result<Self, ClientCoreError> {
+        let annotations = nym_api
+            .get_cached_active_mixnodes_detailed()
+            .await?
+            .into_iter()
+            .map(|node| {
+                let annotation = NodeAnnotation {
+                    owner: Some(node.owner().to_string()),
+                    country: node
+                        .location
+                        .as_ref()
+                        .map(|location| location.two_letter_iso_country_code.clone()),
+                };
+                (node.identity_key().to_string(), annotation)
+            })
+            .collect::<Vec<_>>();
+
+        debug!(
+            "obtained annotations of {} mix nodes for the routing filter",
+            annotations.len()
+        );
+        Ok(self.with_node_annotations(annotations))
+    }
+
+    /// Returns whether the filter doesn't exclude anything.
+    pub fn is_empty(&self) -> bool {
+        self.excluded_identities.is_empty()
+            && self.excluded_owners.is_empty()
+            && self.excluded_countries.is_empty()
+    }
+
+    /// Checks whether the provided mix node is allowed to be used for constructing routes.
+    pub fn allows(&self, node: &mix::Node) -> bool {
+        let identity = node.identity_key.to_base58_string();
+        if self.excluded_identities.contains(&identity) {
+            return false;
+        }
+
+        let annotation = self.annotations.get(&identity);
+        if !self.excluded_owners.is_empty() {
+            let owner = node
+                .owner
+                .as_ref()
+                .or_else(|| annotation.and_then(|a| a.owner.as_ref()));
+            if owner.is_some_and(|owner| self.excluded_owners.contains(owner)) {
+                return false;
+            }
+        }
+
+        if !self.excluded_countries.is_empty() {
+            match annotation.and_then(|a| a.country.as_ref()) {
+                Some(country) => {
+                    if self.excluded_countries.contains(&country.to_uppercase()) {
+                        return false;
+                    }
+                }
+                None => {
+                    if self.exclude_unknown_countries {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns a copy of the provided topology with all the disallowed mix nodes removed.
+    pub fn apply(&self, topology: &NymTopology) -> NymTopology {
+        let mut filtered = topology.clone();
+        if self.is_empty() {
+            return filtered;
+        }
+
+        for (layer, nodes) in topology.mixes() {
+            let allowed = nodes
+                .iter()
+                .filter(|node| self.allows(node))
+                .cloned()
+                .collect::<Vec<_>>();
+            trace!(
+                "the routing filter has excluded {} out of {} nodes on layer {layer}",
+                nodes.len() - allowed.len(),
+                nodes.len()
+            );
+            filtered.set_mixes_in_layer(*layer, allowed);
+        }
+        filtered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_crypto::asymmetric::encryption;
+    use nym_topology::mix::Layer;
+
+    fn mix_node(mix_id: u32, identity: &str, owner: Option<&str>) -> mix::Node {
+        mix::Node {
+            mix_id,
+            owner: owner.map(Into::into),
+            host: "10.20.30.40".parse().unwrap(),
+            mix_host: "10.20.30.40:1789".parse().unwrap(),
+            identity_key: identity::PublicKey::from_base58_string(identity).unwrap(),
+            sphinx_key: encryption::PublicKey::from_base58_string(
+                "B3GzG62aXAZNg14RoMCp3BhELNBrySLr2JqrwyfYFzRc",
+            )
+            .unwrap(),
+            layer: Layer::One,
+            version: "1.1.0".into(),
+        }
+    }
+
+    #[test]
+    fn excludes_nodes_by_identity_owner_and_country() {
+        let first = mix_node(1, "3ebjp1Fb9hdcS1AR6AZihgeJiMHkB5jjJUsvqNnfQwU7", None);
+        let second = mix_node(
+            2,
+            "D6YaMzLSY7mANtSQRKXsmMZpqgqiVkeiagKM4V4oFPFr",
+            Some("n1owner"),
+        );
+
+        assert!(RoutingFilter::new().allows(&first));
+
+        let by_identity = RoutingFilter::new().with_excluded_identities([first.identity_key]);
+        assert!(!by_identity.allows(&first));
+        assert!(by_identity.allows(&second));
+
+        let by_owner = RoutingFilter::new().with_excluded_owners(["n1owner"]);
+        assert!(by_owner.allows(&first));
+        assert!(!by_owner.allows(&second));
+
+        let by_country = RoutingFilter::new()
+            .with_excluded_countries(["de"])
+            .with_node_annotations([(
+                first.identity_key.to_base58_string(),
+                NodeAnnotation {
+                    owner: None,
+                    country: Some("DE".into()),
+                },
+            )]);
+        assert!(!by_country.allows(&first));
+        assert!(by_country.allows(&second));
+        assert!(!by_country
+            .with_unknown_countries_excluded(true)
+            .allows(&second));
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scheduling of the packets sent by the traffic streams.
+//!
+//! By default the delays between subsequent packets are sampled from the exponential distribution,
+//! which is what the anonymity properties of the mixnet rely on. The alternative models exist
+//! to allow experimenting with different anonymity and latency trade-offs and should not be used
+//! by clients that actually care about their privacy.
+
+use crate::config::TrafficScheduling;
+use nym_sphinx::utils::sample_poisson_duration;
+use rand::RngCore;
+use std::time::Duration;
+
+/// Determines how long a traffic stream should wait before sending its next packet.
+pub trait TrafficScheduler: Send {
+    /// Returns the delay before the next packet, such that on average,
+    /// the packets are sent every `average_delay`.
+    fn next_delay(&mut self, rng: &mut dyn RngCore, average_delay: Duration) -> Duration;
+}
+
+/// Sends packets according to a Poisson process.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PoissonScheduler;
+
+impl TrafficScheduler for PoissonScheduler {
+    fn next_delay(&mut self, rng: &mut dyn RngCore, average_delay: Duration) -> Duration {
+        sample_poisson_duration(rng, average_delay)
+    }
+}
+
+/// Sends packets at fixed intervals.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ConstantRateScheduler;
+
+impl TrafficScheduler for ConstantRateScheduler {
+    fn next_delay(&mut self, _rng: &mut dyn RngCore, average_delay: Duration) -> Duration {
+        average_delay
+    }
+}
+
+/// Sends packets in back-to-back bursts of fixed size, with the delays between the bursts
+/// sampled from the exponential distribution so that the average rate is preserved.
+#[derive(Debug, Clone, Copy)]
+pub struct BurstyScheduler {
+    burst_size: u32,
+
+    // number of packets that still have to be sent in the current burst
+    remaining_in_burst: u32,
+}
+
+impl BurstyScheduler {
+    pub fn new(burst_size: u32) -> Self {
+        BurstyScheduler {
+            burst_size: burst_size.max(1),
+            remaining_in_burst: 0,
+        }
+    }
+}
+
+impl TrafficScheduler for BurstyScheduler {
+    fn next_delay(&mut self, rng: &mut dyn RngCore, average_delay: Duration) -> Duration {
+        if self.remaining_in_burst > 0 {
+            self.remaining_in_burst -= 1;
+            return Duration::ZERO;
+        }
+
+        self.remaining_in_burst = self.burst_size - 1;
+        sample_poisson_duration(rng, average_delay * self.burst_size)
+    }
+}
+
+/// Creates the scheduler implementing the specified model.
+pub fn new_traffic_scheduler(
+    scheduling: TrafficScheduling,
+    burst_size: u32,
+) -> Box<dyn TrafficScheduler> {
+    match scheduling {
+        TrafficScheduling::Poisson => Box::new(PoissonScheduler),
+        TrafficScheduling::ConstantRate => Box::new(ConstantRateScheduler),
+        TrafficScheduling::Bursty => Box::new(BurstyScheduler::new(burst_size)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn bursty_scheduler_sends_packets_back_to_back() {
+        let average = Duration::from_millis(20);
+        let mut scheduler = BurstyScheduler::new(4);
+
+        // the first packet of each burst waits for the whole burst worth of time,
+        // whilst the rest of them are sent straight away
+        for _ in 0..3 {
+            scheduler.next_delay(&mut OsRng, average);
+            for _ in 0..3 {
+                assert_eq!(scheduler.next_delay(&mut OsRng, average), Duration::ZERO);
+            }
+        }
+
+        let mut constant = ConstantRateScheduler;
+        assert_eq!(constant.next_delay(&mut OsRng, average), average);
+    }
+}
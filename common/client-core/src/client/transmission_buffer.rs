@@ -15,6 +15,16 @@ use std::{
 const OLDEST_LANE_SET_SIZE: usize = 4;
 // As a way of prune connections we also check for timeouts.
 const MSG_CONSIDERED_STALE_AFTER_SECS: u64 = 10 * 60;
+// To prevent the normal priority lanes from being starved, after that many consecutive messages
+// from the high priority lanes, the next one is taken from a normal lane (if there's any).
+const MAX_CONSECUTIVE_HIGH_PRIORITY_MESSAGES: usize = 4;
+
+fn is_urgent_lane(lane: &TransmissionLane) -> bool {
+    matches!(
+        lane,
+        TransmissionLane::ReplySurbRequest | TransmissionLane::AdditionalReplySurbs
+    )
+}
 
 // this trait is apparently not used in wasm
 #[allow(dead_code)]
@@ -39,12 +49,16 @@ impl SizedData for Fragment {
 #[derive(Default)]
 pub(crate) struct TransmissionBuffer<T> {
     buffer: HashMap<TransmissionLane, LaneBufferEntry<T>>,
+
+    // number of messages popped from the high priority lanes since the last normal priority one
+    consecutive_high_priority: usize,
 }
 
 impl<T> TransmissionBuffer<T> {
     pub(crate) fn new() -> Self {
         TransmissionBuffer {
             buffer: HashMap::new(),
+            consecutive_high_priority: 0,
         }
     }
 
@@ -57,6 +71,10 @@ impl<T> TransmissionBuffer<T> {
         self.buffer.remove(lane)
     }
 
+    pub(crate) fn lanes(&self) -> impl Iterator<Item = &TransmissionLane> {
+        self.buffer.keys()
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     pub(crate) fn num_lanes(&self) -> usize {
         self.buffer.keys().count()
@@ -99,10 +117,11 @@ impl<T> TransmissionBuffer<T> {
             .sum()
     }
 
-    fn get_oldest_set(&self) -> Vec<TransmissionLane> {
+    fn get_oldest_set(&self, excluded: &HashSet<TransmissionLane>) -> Vec<TransmissionLane> {
         let mut buffer: Vec<_> = self
             .buffer
             .iter()
+            .filter(|(k, _)| !excluded.contains(k))
             .map(|(k, v)| (k, v.messages_transmitted))
             .collect();
         buffer.sort_by_key(|v| v.1);
@@ -133,29 +152,45 @@ impl<T> TransmissionBuffer<T> {
         }
     }
 
-    fn pick_random_lane<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&TransmissionLane> {
-        let lanes: Vec<&TransmissionLane> = self.buffer.keys().collect();
+    fn pick_random_lane<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        excluded: &HashSet<TransmissionLane>,
+    ) -> Option<&TransmissionLane> {
+        let lanes: Vec<&TransmissionLane> = self
+            .buffer
+            .keys()
+            .filter(|k| !excluded.contains(k))
+            .collect();
         lanes.choose(rng).copied()
     }
 
-    fn pick_random_small_lane<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&TransmissionLane> {
+    fn pick_random_small_lane<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        excluded: &HashSet<TransmissionLane>,
+    ) -> Option<&TransmissionLane> {
         let lanes: Vec<&TransmissionLane> = self
             .buffer
             .iter()
-            .filter(|(_, v)| v.is_small())
+            .filter(|(k, v)| v.is_small() && !excluded.contains(k))
             .map(|(k, _)| k)
             .collect();
         lanes.choose(rng).copied()
     }
 
     // 2/3 chance to pick from the old lanes
-    fn pick_random_old_lane<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<TransmissionLane> {
+    fn pick_random_old_lane<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        excluded: &HashSet<TransmissionLane>,
+    ) -> Option<TransmissionLane> {
         let rand = &mut rand::thread_rng();
         if rand.gen_ratio(2, 3) {
-            let lanes = self.get_oldest_set();
+            let lanes = self.get_oldest_set(excluded);
             lanes.choose(rand).copied()
         } else {
-            self.pick_random_lane(rng).copied()
+            self.pick_random_lane(rng, excluded).copied()
         }
     }
 
@@ -179,9 +214,10 @@ impl<T> TransmissionBuffer<T> {
 
         let rng = &mut rand::thread_rng();
         let mut items = Vec::with_capacity(n);
+        let excluded = HashSet::new();
 
         while items.len() < n {
-            let Some(next) = self.pop_next_message_at_random(rng) else {
+            let Some(next) = self.pop_next_message_at_random(rng, &excluded) else {
                 break;
             };
             items.push(next)
@@ -190,10 +226,12 @@ impl<T> TransmissionBuffer<T> {
         Some(items)
     }
 
+    /// Pops the next message from a randomly chosen lane, skipping over the `excluded` lanes.
     pub(crate) fn pop_next_message_at_random<R: Rng + ?Sized>(
         &mut self,
         // turns out the caller always have access to some rng, so no point in instantiating new one
         rng: &mut R,
+        excluded: &HashSet<TransmissionLane>,
     ) -> Option<(TransmissionLane, T)> {
         if self.buffer.is_empty() {
             return None;
@@ -201,12 +239,12 @@ impl<T> TransmissionBuffer<T> {
 
         // Very basic heuristic where we prioritize according to small lanes first, the older lanes
         // to try to finish lanes when possible, then the rest.
-        let lane = if let Some(small_lane) = self.pick_random_small_lane(rng) {
+        let lane = if let Some(small_lane) = self.pick_random_small_lane(rng, excluded) {
             *small_lane
-        } else if let Some(old_lane) = self.pick_random_old_lane(rng) {
+        } else if let Some(old_lane) = self.pick_random_old_lane(rng, excluded) {
             old_lane
         } else {
-            *self.pick_random_lane(rng)?
+            *self.pick_random_lane(rng, excluded)?
         };
 
         let msg = self.pop_front_from_lane(&lane)?;
@@ -214,6 +252,62 @@ impl<T> TransmissionBuffer<T> {
         Some((lane, msg))
     }
 
+    /// Pops the next message, preferring the `high_priority` lanes over the rest of them.
+    /// The normal priority lanes are still guaranteed every few messages, so that they'd never
+    /// get starved by the high priority traffic. Within each priority class the lane is chosen
+    /// as in [`Self::pop_next_message_at_random`].
+    pub(crate) fn pop_next_message_by_priority<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        excluded: &HashSet<TransmissionLane>,
+        high_priority: &HashSet<TransmissionLane>,
+    ) -> Option<(TransmissionLane, T)> {
+        let available = |high: bool| {
+            self.buffer
+                .keys()
+                .any(|lane| high_priority.contains(lane) == high && !excluded.contains(lane))
+        };
+        let pick_high = available(true)
+            && (self.consecutive_high_priority < MAX_CONSECUTIVE_HIGH_PRIORITY_MESSAGES
+                || !available(false));
+
+        // exclude all the lanes of the other priority class
+        let mut excluded = excluded.clone();
+        excluded.extend(
+            self.buffer
+                .keys()
+                .filter(|lane| high_priority.contains(lane) != pick_high),
+        );
+
+        let next = self.pop_next_message_at_random(rng, &excluded)?;
+        if pick_high {
+            self.consecutive_high_priority += 1;
+        } else {
+            self.consecutive_high_priority = 0;
+        }
+        Some(next)
+    }
+
+    /// Pops the next message from one of the lanes carrying urgent control traffic, i.e. reply surbs,
+    /// ignoring everything else.
+    pub(crate) fn pop_next_urgent_message<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        excluded: &HashSet<TransmissionLane>,
+    ) -> Option<(TransmissionLane, T)> {
+        let urgent_lanes: Vec<TransmissionLane> = self
+            .buffer
+            .keys()
+            .filter(|lane| is_urgent_lane(lane) && !excluded.contains(lane))
+            .copied()
+            .collect();
+        let lane = *urgent_lanes.choose(rng)?;
+
+        let msg = self.pop_front_from_lane(&lane)?;
+        log::trace!("picking to send from urgent lane: {:?}", lane);
+        Some((lane, msg))
+    }
+
     pub(crate) fn prune_stale_connections(&mut self) {
         let stale_entries: Vec<_> = self
             .buffer
@@ -283,3 +377,66 @@ impl<T> LaneBufferEntry<T> {
         self.items.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn high_priority_lanes_go_first_without_starving_the_rest() {
+        let interactive = TransmissionLane::ConnectionId(1);
+        let bulk = TransmissionLane::ConnectionId(2);
+        let high_priority = HashSet::from([interactive]);
+
+        let mut buffer = TransmissionBuffer::new();
+        buffer.store(&bulk, 0..20);
+        buffer.store(&interactive, 0..20);
+
+        let rng = &mut rand::thread_rng();
+        let lanes: Vec<_> = (0..10)
+            .map(|_| {
+                buffer
+                    .pop_next_message_by_priority(rng, &HashSet::new(), &high_priority)
+                    .unwrap()
+                    .0
+            })
+            .collect();
+
+        let mut expected = vec![interactive; 4];
+        expected.push(bulk);
+        expected.extend_from_within(..);
+        assert_eq!(lanes, expected);
+
+        // once there's nothing of high priority left, the rest is sent as usual
+        buffer.remove(&interactive);
+        let (lane, _) = buffer
+            .pop_next_message_by_priority(rng, &HashSet::new(), &high_priority)
+            .unwrap();
+        assert_eq!(lane, bulk);
+    }
+
+    #[test]
+    fn only_urgent_lanes_are_popped_while_holding_traffic() {
+        let regular = TransmissionLane::ConnectionId(1);
+        let mut buffer = TransmissionBuffer::new();
+        buffer.store(&regular, 0..2);
+        buffer.store(&TransmissionLane::ReplySurbRequest, 0..1);
+        buffer.store(&TransmissionLane::AdditionalReplySurbs, 0..1);
+
+        let rng = &mut rand::thread_rng();
+        let urgent: HashSet<_> = (0..2)
+            .map(|_| buffer.pop_next_urgent_message(rng).unwrap().0)
+            .collect();
+        assert_eq!(
+            urgent,
+            HashSet::from([
+                TransmissionLane::ReplySurbRequest,
+                TransmissionLane::AdditionalReplySurbs
+            ])
+        );
+
+        // the rest is left for after the hold is over
+        assert!(buffer.pop_next_urgent_message(rng).is_none());
+        assert_eq!(buffer.lane_length(&regular), Some(2));
+    }
+}
@@ -1,9 +1,14 @@
 // Copyright 2022-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::base_client::startup::StartupStage;
 use crate::client::mix_traffic::transceiver::ErasedGatewayError;
 use nym_crypto::asymmetric::identity::Ed25519RecoveryError;
 use nym_gateway_client::error::GatewayClientError;
+use nym_gateway_requests::registration::handshake::error::{
+    HandshakeError, HandshakeFailureReason,
+};
+use nym_sphinx::cover::CoverMessageError;
 use nym_topology::gateway::GatewayConversionError;
 use nym_topology::NymTopologyError;
 use nym_validator_client::ValidatorClientError;
@@ -21,6 +26,14 @@ pub enum ClientCoreError {
         source: GatewayClientError,
     },
 
+    #[error("failed to complete the registration handshake with gateway {gateway_id} ({reason}): {source}")]
+    GatewayHandshakeFailure {
+        gateway_id: String,
+        reason: HandshakeFailureReason,
+        #[source]
+        source: HandshakeError,
+    },
+
     #[error("custom gateway client error: {source}")]
     ErasedGatewayClientError {
         #[from]
@@ -39,6 +52,12 @@ pub enum ClientCoreError {
     #[error("no gateways on network")]
     NoGatewaysOnNetwork,
 
+    #[error("the custom gateway selector failed to choose a gateway: {source}")]
+    GatewaySelectorFailure {
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
     #[error("there are no more new gateways on the network - it seems this client has already registered with all nodes it could have")]
     NoNewGatewaysAvailable,
 
@@ -102,6 +121,15 @@ pub enum ClientCoreError {
     #[error("failed to register receiver for reconstructed mixnet messages")]
     FailedToRegisterReceiver,
 
+    #[error("failed to acknowledge processing of the received mixnet message")]
+    FailedToAcknowledgeMessage,
+
+    #[error("the self-test can only be run before the client input and output get registered")]
+    SelfTestUnavailable,
+
+    #[error("the provided value of the runtime parameter '{parameter}' is invalid")]
+    InvalidRuntimeParameter { parameter: &'static str },
+
     #[error("unexpected exit")]
     UnexpectedExit,
 
@@ -153,12 +181,23 @@ pub enum ClientCoreError {
     #[error("this client has performed gateway initialisation in another session")]
     NoInitClientPresent,
 
+    #[error("the standby client has already been promoted")]
+    StandbyAlreadyPromoted,
+
     #[error("there are no gateways supporting the wss protocol available")]
     NoWssGateways,
 
     #[error("the specified gateway '{gateway}' does not support the wss protocol")]
     UnsupportedWssProtocol { gateway: String },
 
+    #[error(
+        "the file-based topology structure was requested, but no topology file has been specified"
+    )]
+    UnspecifiedTopologyFile,
+
+    #[error("the file-based topology structure is not supported on this platform")]
+    UnsupportedFileTopology,
+
     #[error(
     "failed to load custom topology using path '{}'. detailed message: {source}", file_path.display()
     )]
@@ -168,6 +207,15 @@ pub enum ClientCoreError {
         source: std::io::Error,
     },
 
+    #[error(
+    "failed to export the network topology to '{}'. detailed message: {source}", file_path.display()
+    )]
+    TopologyExportFailure {
+        file_path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
     #[error(
     "failed to save config file for client-{typ} id {id} using path '{}'. detailed message: {source}", path.display()
     )]
@@ -219,6 +267,48 @@ pub enum ClientCoreError {
         "fresh registration with gateway {gateway_id} somehow requires an additional key upgrade!"
     )]
     UnexpectedKeyUpgrade { gateway_id: String },
+
+    #[error("the client has failed to complete its {stage} within the startup deadline")]
+    StartupDeadlineExceeded { stage: StartupStage },
+
+    #[error("the background startup of the client has stopped unexpectedly")]
+    BackgroundStartupFailure,
+
+    #[error("failed to prepare the gateway throughput test packets: {source}")]
+    ThroughputTestPacketFailure {
+        #[from]
+        source: CoverMessageError,
+    },
+}
+
+impl ClientCoreError {
+    /// Wraps the failure of the client of the particular gateway,
+    /// surfacing the reason of the registration handshake failure (if applicable).
+    pub fn gateway_client_failure(gateway_id: String, source: GatewayClientError) -> Self {
+        match source {
+            GatewayClientError::RegistrationFailure(source) => {
+                ClientCoreError::GatewayHandshakeFailure {
+                    gateway_id,
+                    reason: source.failure_reason(),
+                    source,
+                }
+            }
+            source => ClientCoreError::GatewayClientError { gateway_id, source },
+        }
+    }
+
+    /// Returns the reason of the registration handshake failure, either determined locally
+    /// or reported by the gateway, if the error was caused by one.
+    pub fn handshake_failure_reason(&self) -> Option<HandshakeFailureReason> {
+        match self {
+            ClientCoreError::GatewayHandshakeFailure { reason, .. } => Some(*reason),
+            ClientCoreError::GatewayClientError {
+                source: GatewayClientError::RegistrationFailure(source),
+                ..
+            } => Some(source.failure_reason()),
+            _ => None,
+        }
+    }
 }
 
 /// Set of messages that the client can send to listeners via the task manager
@@ -230,4 +320,31 @@ pub enum ClientCoreStatusMessage {
     // NOTE: The nym-connect frontend listens for these strings, so don't change them until we have a more robust mechanism in place
     #[error("The connected gateway is very slow, or the connection to it is very slow")]
     GatewayIsVerySlow,
+
+    #[error("The local network connectivity has been lost")]
+    NetworkLost,
+
+    #[error("The local network has changed - re-establishing the gateway connection")]
+    NetworkChanged,
+
+    #[error("Re-established the gateway connection after the network change")]
+    GatewayReconnected,
+
+    #[error("Failed to re-establish the gateway connection after the network change")]
+    GatewayReconnectionFailed,
+
+    #[error("The client has not started within the startup deadline - it's running in the degraded mode until the startup completes in the background")]
+    DegradedStartup,
+
+    #[error("Failed to complete the {stage} (attempt {attempt}) - retrying")]
+    StartupStageFailed { stage: StartupStage, attempt: u32 },
+
+    #[error("The client has completed its startup")]
+    StartupCompleted,
+
+    #[error("The network topology is no longer routable - messages are going to be held until it recovers")]
+    TopologyUnroutable,
+
+    #[error("The network topology is routable - any held messages are going to be sent")]
+    TopologyRoutable,
 }
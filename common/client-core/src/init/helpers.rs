@@ -2,16 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::error::ClientCoreError;
+use crate::init::selector::CustomGatewaySelector;
 use crate::init::types::RegistrationResult;
 use futures::{SinkExt, StreamExt};
 use log::{debug, info, trace, warn};
+use nym_client_core_gateways_storage::GatewayLatency;
 use nym_crypto::asymmetric::identity;
 use nym_gateway_client::GatewayClient;
-use nym_topology::{filter::VersionFilterable, gateway, mix};
+use nym_topology::{filter::VersionConstraints, gateway, mix};
 use nym_validator_client::client::IdentityKeyRef;
 use nym_validator_client::UserAgent;
 use rand::{seq::SliceRandom, Rng};
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use tungstenite::Message;
 use url::Url;
 
@@ -46,6 +48,9 @@ const MEASUREMENTS: usize = 3;
 const CONN_TIMEOUT: Duration = Duration::from_millis(1500);
 const PING_TIMEOUT: Duration = Duration::from_millis(1000);
 
+/// Maximum age of a stored latency measurement for it to be used in place of re-probing the gateway.
+pub const LATENCY_HISTORY_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
 // The abstraction that some of these helpers use
 pub trait ConnectableGateway {
     fn identity(&self) -> &identity::PublicKey;
@@ -78,6 +83,15 @@ impl<'a, G: ConnectableGateway> GatewayWithLatency<'a, G> {
     }
 }
 
+/// Result of the latency-based gateway selection.
+pub struct LatencyBasedSelection<G> {
+    pub gateway: G,
+
+    /// Latencies measured during this selection, i.e. of gateways without recent historical data,
+    /// that should be persisted for future use.
+    pub new_measurements: Vec<(identity::PublicKey, Duration)>,
+}
+
 pub async fn current_gateways<R: Rng>(
     rng: &mut R,
     nym_apis: &[Url],
@@ -106,7 +120,11 @@ pub async fn current_gateways<R: Rng>(
     log::trace!("Valid gateways: {:#?}", valid_gateways);
 
     // we were always filtering by version so I'm not removing that 'feature'
-    let filtered_gateways = valid_gateways.filter_by_version(env!("CARGO_PKG_VERSION"));
+    let version_constraints = VersionConstraints::new_for_client(env!("CARGO_PKG_VERSION"));
+    let filtered_gateways = valid_gateways
+        .into_iter()
+        .filter(|gateway| version_constraints.allows_gateway(gateway))
+        .collect::<Vec<_>>();
     log::debug!("After filtering for version: {}", filtered_gateways.len());
     log::trace!("Filtered gateways: {:#?}", filtered_gateways);
 
@@ -133,7 +151,11 @@ pub async fn current_mixnodes<R: Rng>(
         .collect::<Vec<mix::Node>>();
 
     // we were always filtering by version so I'm not removing that 'feature'
-    let filtered_mixnodes = valid_mixnodes.filter_by_version(env!("CARGO_PKG_VERSION"));
+    let version_constraints = VersionConstraints::new_for_client(env!("CARGO_PKG_VERSION"));
+    let filtered_mixnodes = valid_mixnodes
+        .into_iter()
+        .filter(|mixnode| version_constraints.allows_mixnode(mixnode))
+        .collect::<Vec<_>>();
     Ok(filtered_mixnodes)
 }
 
@@ -211,26 +233,53 @@ where
     Ok(GatewayWithLatency::new(gateway, avg))
 }
 
+/// Chooses a gateway weighted by its latency. Gateways with non-stale entries in the provided `history`
+/// are not going to be probed again and their historical average is going to be used instead.
 pub async fn choose_gateway_by_latency<'a, R: Rng, G: ConnectableGateway + Clone>(
     rng: &mut R,
     gateways: &[G],
     must_use_tls: bool,
-) -> Result<G, ClientCoreError> {
+    history: &[GatewayLatency],
+) -> Result<LatencyBasedSelection<G>, ClientCoreError> {
     let gateways = filter_by_tls(gateways, must_use_tls)?;
 
+    let known_latencies = history
+        .iter()
+        .filter(|entry| !entry.is_stale(LATENCY_HISTORY_MAX_AGE))
+        .map(|entry| (entry.gateway_id.to_bytes(), entry.average))
+        .collect::<HashMap<_, _>>();
+
+    let (known, to_measure): (Vec<_>, Vec<_>) = gateways
+        .into_iter()
+        .partition(|gateway| known_latencies.contains_key(&gateway.identity().to_bytes()));
+
     info!(
-        "choosing gateway by latency, pinging {} gateways ...",
-        gateways.len()
+        "choosing gateway by latency, using historical data of {} gateways and pinging {} gateways ...",
+        known.len(),
+        to_measure.len()
     );
 
-    let gateways_with_latency = Arc::new(tokio::sync::Mutex::new(Vec::new()));
-    futures::stream::iter(gateways)
+    let mut with_history = Vec::with_capacity(known.len());
+    for gateway in known {
+        // SAFETY: we have just partitioned the gateways based on the map entries
+        #[allow(clippy::unwrap_used)]
+        let latency = *known_latencies.get(&gateway.identity().to_bytes()).unwrap();
+        with_history.push(GatewayWithLatency::new(gateway, latency))
+    }
+
+    let gateways_with_latency = Arc::new(tokio::sync::Mutex::new(with_history));
+    let new_measurements = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+    futures::stream::iter(to_measure)
         .for_each_concurrent(CONCURRENT_GATEWAYS_MEASURED, |gateway| async {
             let id = *gateway.identity();
             trace!("measuring latency to {id}...");
             match measure_latency(gateway).await {
                 Ok(with_latency) => {
                     debug!("{id}: {:?}", with_latency.latency);
+                    new_measurements
+                        .lock()
+                        .await
+                        .push((id, with_latency.latency));
                     gateways_with_latency.lock().await.push(with_latency);
                 }
                 Err(err) => {
@@ -251,7 +300,11 @@ pub async fn choose_gateway_by_latency<'a, R: Rng, G: ConnectableGateway + Clone
         chosen.latency
     );
 
-    Ok(chosen.gateway.clone())
+    let new_measurements = std::mem::take(&mut *new_measurements.lock().await);
+    Ok(LatencyBasedSelection {
+        gateway: chosen.gateway.clone(),
+        new_measurements,
+    })
 }
 
 fn filter_by_tls<G: ConnectableGateway>(
@@ -282,6 +335,25 @@ pub(super) fn uniformly_random_gateway<R: Rng>(
         .map(|&r| r.clone())
 }
 
+pub(super) async fn custom_selected_gateway(
+    selector: &CustomGatewaySelector,
+    gateways: &[gateway::Node],
+    must_use_tls: bool,
+) -> Result<gateway::Node, ClientCoreError> {
+    let candidates = filter_by_tls(gateways, must_use_tls)?
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        return Err(ClientCoreError::NoGatewaysOnNetwork);
+    }
+
+    selector
+        .select_gateway(&candidates)
+        .await
+        .map_err(|source| ClientCoreError::GatewaySelectorFailure { source })
+}
+
 pub(super) fn get_specified_gateway(
     gateway_identity: IdentityKeyRef,
     gateways: &[gateway::Node],
@@ -308,10 +380,9 @@ pub(super) fn get_specified_gateway(
 pub(super) async fn register_with_gateway(
     gateway_id: identity::PublicKey,
     gateway_listener: Url,
-    our_identity: Arc<identity::KeyPair>,
+    our_identity: Arc<dyn identity::IdentitySigner>,
 ) -> Result<RegistrationResult, ClientCoreError> {
-    let mut gateway_client =
-        GatewayClient::new_init(gateway_listener, gateway_id, our_identity.clone());
+    let mut gateway_client = GatewayClient::new_init(gateway_listener, gateway_id, our_identity);
 
     gateway_client.establish_connection().await.map_err(|err| {
         log::warn!("Failed to establish connection with gateway!");
@@ -325,10 +396,7 @@ pub(super) async fn register_with_gateway(
         .await
         .map_err(|err| {
             log::warn!("Failed to register with the gateway {gateway_id}: {err}");
-            ClientCoreError::GatewayClientError {
-                gateway_id: gateway_id.to_base58_string(),
-                source: err,
-            }
+            ClientCoreError::gateway_client_failure(gateway_id.to_base58_string(), err)
         })?;
 
     // this should NEVER happen, if it did, it means the function was misused,
@@ -4,20 +4,23 @@
 //! Collection of initialization steps used by client implementations
 
 use crate::client::base_client::storage::helpers::{
-    has_gateway_details, load_active_gateway_details, load_client_keys, load_gateway_details,
-    store_gateway_details,
+    get_all_registered_identities, has_gateway_details, load_active_gateway_details,
+    load_client_keys, load_gateway_bandwidths, load_gateway_details, load_gateway_latencies,
+    record_gateway_latency, store_gateway_details,
 };
 use crate::client::key_manager::persistence::KeyStore;
 use crate::client::key_manager::ClientKeys;
 use crate::error::ClientCoreError;
 use crate::init::helpers::{
-    choose_gateway_by_latency, get_specified_gateway, uniformly_random_gateway,
+    choose_gateway_by_latency, custom_selected_gateway, get_specified_gateway,
+    uniformly_random_gateway,
 };
 use crate::init::types::{
     GatewaySelectionSpecification, GatewaySetup, InitialisationResult, SelectedGateway,
 };
 use nym_client_core_gateways_storage::GatewaysDetailsStore;
 use nym_client_core_gateways_storage::{GatewayDetails, GatewayRegistration};
+use nym_crypto::asymmetric::identity;
 use nym_gateway_client::client::InitGatewayClient;
 use nym_topology::gateway;
 use rand::rngs::OsRng;
@@ -25,6 +28,7 @@ use rand::{CryptoRng, RngCore};
 use serde::Serialize;
 
 pub mod helpers;
+pub mod selector;
 pub mod types;
 
 // helpers for error wrapping
@@ -38,7 +42,7 @@ where
     K: KeyStore,
     K::StorageError: Send + Sync + 'static,
 {
-    ClientKeys::generate_new(rng)
+    ClientKeys::generate_for_store(rng, key_store)
         .persist_keys(key_store)
         .await
         .map_err(|source| ClientCoreError::KeyStoreError {
@@ -71,9 +75,18 @@ where
             SelectedGateway::from_topology_node(gateway, must_use_tls)?
         }
         GatewaySelectionSpecification::RemoteByLatency { must_use_tls } => {
-            let gateway =
-                choose_gateway_by_latency(&mut rng, &available_gateways, must_use_tls).await?;
-            SelectedGateway::from_topology_node(gateway, must_use_tls)?
+            let history = load_gateway_latencies(details_store).await?;
+            let selection =
+                choose_gateway_by_latency(&mut rng, &available_gateways, must_use_tls, &history)
+                    .await?;
+
+            // failing to persist the measurements is not critical, they'd just have to be redone next time
+            for (gateway_id, latency) in selection.new_measurements {
+                if let Err(err) = record_gateway_latency(details_store, gateway_id, latency).await {
+                    log::warn!("failed to persist latency measurement of {gateway_id}: {err}");
+                }
+            }
+            SelectedGateway::from_topology_node(selection.gateway, must_use_tls)?
         }
         GatewaySelectionSpecification::Specified {
             must_use_tls,
@@ -82,6 +95,14 @@ where
             let gateway = get_specified_gateway(&identity, &available_gateways, must_use_tls)?;
             SelectedGateway::from_topology_node(gateway, must_use_tls)?
         }
+        GatewaySelectionSpecification::RemoteWithSelector {
+            must_use_tls,
+            selector,
+        } => {
+            let gateway =
+                custom_selected_gateway(&selector, &available_gateways, must_use_tls).await?;
+            SelectedGateway::from_topology_node(gateway, must_use_tls)?
+        }
         GatewaySelectionSpecification::Custom {
             gateway_identity,
             additional_data,
@@ -104,7 +125,7 @@ where
             gateway_listener,
         } => {
             // if we're using a 'normal' gateway setup, do register
-            let our_identity = client_keys.identity_keypair();
+            let our_identity = client_keys.identity_signer();
 
             let registration =
                 helpers::register_with_gateway(gateway_id, gateway_listener.clone(), our_identity)
@@ -181,6 +202,39 @@ fn reuse_gateway_connection(
     }
 }
 
+/// Looks for a gateway we have previously registered with, other than the `excluded` ones,
+/// that still holds some of our bandwidth allowance, so that failing over to it would not require
+/// spending a fresh credential. If there are multiple candidates, the one with the most remaining
+/// bandwidth is chosen.
+pub async fn registered_gateway_with_allowance<D>(
+    details_store: &D,
+    excluded: &[identity::PublicKey],
+) -> Result<Option<GatewaySetup>, ClientCoreError>
+where
+    D: GatewaysDetailsStore + Sync,
+    D::StorageError: Send + Sync + 'static,
+{
+    let registered = get_all_registered_identities(details_store).await?;
+    let candidate = load_gateway_bandwidths(details_store)
+        .await?
+        .into_iter()
+        .filter(|bandwidth| bandwidth.has_allowance())
+        .filter(|bandwidth| registered.contains(&bandwidth.gateway_id))
+        .filter(|bandwidth| !excluded.contains(&bandwidth.gateway_id))
+        .max_by_key(|bandwidth| bandwidth.remaining);
+
+    Ok(candidate.map(|bandwidth| {
+        log::debug!(
+            "gateway {} still has {} bytes of our bandwidth available",
+            bandwidth.gateway_id,
+            bandwidth.remaining
+        );
+        GatewaySetup::MustLoad {
+            gateway_id: Some(bandwidth.gateway_id.to_base58_string()),
+        }
+    }))
+}
+
 pub async fn setup_gateway<K, D>(
     setup: GatewaySetup,
     key_store: &K,
@@ -229,3 +283,67 @@ pub fn output_to_json<T: Serialize>(init_results: &T, output_file: &str) {
         Err(err) => eprintln!("Could not save {output_file}: {err}"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_client_core_gateways_storage::InMemGatewaysDetails;
+
+    async fn register(details_store: &InMemGatewaysDetails) -> identity::PublicKey {
+        let gateway_id = *identity::KeyPair::new(&mut OsRng).public_key();
+        details_store
+            .store_gateway_details(&GatewayDetails::new_custom(gateway_id, None).into())
+            .await
+            .unwrap();
+        gateway_id
+    }
+
+    #[tokio::test]
+    async fn failover_prefers_registered_gateway_with_most_allowance() {
+        let details_store = InMemGatewaysDetails::default();
+        let some_left = register(&details_store).await;
+        let most_left = register(&details_store).await;
+        let depleted = register(&details_store).await;
+        let current = register(&details_store).await;
+        let unregistered = *identity::KeyPair::new(&mut OsRng).public_key();
+
+        for (gateway, remaining) in [
+            (some_left, 100),
+            (most_left, 500),
+            (depleted, 0),
+            (current, 1000),
+            (unregistered, 2000),
+        ] {
+            details_store
+                .record_gateway_bandwidth(gateway, remaining)
+                .await
+                .unwrap();
+        }
+
+        let selected = registered_gateway_with_allowance(&details_store, &[current])
+            .await
+            .unwrap();
+        assert!(matches!(
+            selected,
+            Some(GatewaySetup::MustLoad { gateway_id: Some(id) }) if id == most_left.to_base58_string()
+        ));
+
+        // forgetting about the gateway also forgets its bandwidth
+        details_store
+            .remove_gateway_details(&most_left.to_base58_string())
+            .await
+            .unwrap();
+        let selected = registered_gateway_with_allowance(&details_store, &[current])
+            .await
+            .unwrap();
+        assert!(matches!(
+            selected,
+            Some(GatewaySetup::MustLoad { gateway_id: Some(id) }) if id == some_left.to_base58_string()
+        ));
+
+        let selected = registered_gateway_with_allowance(&details_store, &[current, some_left])
+            .await
+            .unwrap();
+        assert!(selected.is_none());
+    }
+}
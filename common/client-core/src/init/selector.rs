@@ -0,0 +1,216 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pluggable gateway selection strategies used when registering with a new gateway.
+
+use async_trait::async_trait;
+use nym_crypto::asymmetric::identity;
+use nym_topology::gateway;
+use std::error::Error;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+pub type GatewaySelectorError = Box<dyn Error + Send + Sync>;
+
+/// Custom strategy of choosing the gateway the client is going to register with,
+/// for example based on its location, its operator or the amount of stake it holds.
+///
+/// The selector is provided with the list of all gateways available on the network
+/// (already restricted to the ones supporting TLS, if required). Any additional information,
+/// such as the gateway locations, has to be retrieved by the selector itself.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait GatewaySelector {
+    async fn select_gateway(
+        &self,
+        gateways: &[gateway::Node],
+    ) -> Result<gateway::Node, GatewaySelectorError>;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+type DynGatewaySelector = dyn GatewaySelector + Send + Sync;
+
+#[cfg(target_arch = "wasm32")]
+type DynGatewaySelector = dyn GatewaySelector;
+
+/// Cheaply cloneable handle to a [`GatewaySelector`].
+#[derive(Clone)]
+pub struct CustomGatewaySelector(Arc<DynGatewaySelector>);
+
+impl CustomGatewaySelector {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new<S: GatewaySelector + Send + Sync + 'static>(selector: S) -> Self {
+        CustomGatewaySelector(Arc::new(selector))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn new<S: GatewaySelector + 'static>(selector: S) -> Self {
+        CustomGatewaySelector(Arc::new(selector))
+    }
+
+    pub(crate) async fn select_gateway(
+        &self,
+        gateways: &[gateway::Node],
+    ) -> Result<gateway::Node, GatewaySelectorError> {
+        self.0.select_gateway(gateways).await
+    }
+}
+
+impl Debug for CustomGatewaySelector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CustomGatewaySelector")
+    }
+}
+
+/// Selector choosing uniformly at random among the gateways run by the allowed operators
+/// or with explicitly allowed identities.
+#[derive(Debug, Clone, Default)]
+pub struct AllowlistGatewaySelector {
+    operators: Vec<String>,
+    identities: Vec<identity::PublicKey>,
+}
+
+impl AllowlistGatewaySelector {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    #[must_use]
+    pub fn with_operator(mut self, owner_address: impl Into<String>) -> Self {
+        self.operators.push(owner_address.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_identity(mut self, identity: identity::PublicKey) -> Self {
+        self.identities.push(identity);
+        self
+    }
+
+    fn is_allowed(&self, node: &gateway::Node) -> bool {
+        self.identities.contains(&node.identity_key)
+            || node
+                .owner
+                .as_ref()
+                .map(|owner| self.operators.contains(owner))
+                .unwrap_or_default()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GatewaySelector for AllowlistGatewaySelector {
+    async fn select_gateway(
+        &self,
+        gateways: &[gateway::Node],
+    ) -> Result<gateway::Node, GatewaySelectorError> {
+        use rand::seq::SliceRandom;
+
+        let allowed = gateways
+            .iter()
+            .filter(|node| self.is_allowed(node))
+            .collect::<Vec<_>>();
+
+        allowed
+            .choose(&mut rand::thread_rng())
+            .map(|&node| node.clone())
+            .ok_or_else(|| "none of the allowed gateways is currently available".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ClientCoreError;
+    use crate::init::helpers::custom_selected_gateway;
+    use nym_crypto::asymmetric::encryption;
+    use rand::rngs::OsRng;
+    use std::sync::Mutex;
+
+    fn gateway(owner: Option<&str>, wss_port: Option<u16>) -> gateway::Node {
+        gateway::Node {
+            owner: owner.map(Into::into),
+            host: "1.2.3.4".parse().unwrap(),
+            mix_host: "1.2.3.4:1789".parse().unwrap(),
+            clients_ws_port: 9000,
+            clients_wss_port: wss_port,
+            identity_key: *identity::KeyPair::new(&mut OsRng).public_key(),
+            sphinx_key: *encryption::KeyPair::new(&mut OsRng).public_key(),
+            version: "1.1.0".into(),
+        }
+    }
+
+    // selector recording the candidates it was given and always picking the last one
+    #[derive(Default)]
+    struct RecordingSelector {
+        seen: Mutex<Vec<identity::PublicKey>>,
+    }
+
+    #[async_trait]
+    impl GatewaySelector for RecordingSelector {
+        async fn select_gateway(
+            &self,
+            gateways: &[gateway::Node],
+        ) -> Result<gateway::Node, GatewaySelectorError> {
+            *self.seen.lock().unwrap() = gateways.iter().map(|g| g.identity_key).collect();
+            gateways.last().cloned().ok_or_else(|| "no gateways".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn allowlist_only_selects_allowed_gateways() {
+        let by_operator = gateway(Some("n1operator"), None);
+        let by_identity = gateway(None, None);
+        let gateways = vec![
+            gateway(Some("n1someoneelse"), None),
+            by_operator.clone(),
+            gateway(None, None),
+            by_identity.clone(),
+        ];
+
+        let selector = AllowlistGatewaySelector::new().with_operator("n1operator");
+        for _ in 0..10 {
+            let selected = selector.select_gateway(&gateways).await.unwrap();
+            assert_eq!(selected.identity_key, by_operator.identity_key);
+        }
+
+        let selector = selector.with_identity(by_identity.identity_key);
+        for _ in 0..10 {
+            let selected = selector.select_gateway(&gateways).await.unwrap();
+            assert!(
+                selected.identity_key == by_operator.identity_key
+                    || selected.identity_key == by_identity.identity_key
+            );
+        }
+
+        let selector = AllowlistGatewaySelector::new().with_operator("n1nobody");
+        assert!(selector.select_gateway(&gateways).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn custom_selector_is_only_offered_tls_gateways_if_required() {
+        let tls = gateway(None, Some(9001));
+        let gateways = vec![gateway(None, None), tls.clone(), gateway(None, None)];
+
+        let recording = Arc::new(RecordingSelector::default());
+        let selector = CustomGatewaySelector(recording.clone());
+
+        let selected = custom_selected_gateway(&selector, &gateways, true)
+            .await
+            .unwrap();
+        assert_eq!(selected.identity_key, tls.identity_key);
+        assert_eq!(*recording.seen.lock().unwrap(), vec![tls.identity_key]);
+
+        custom_selected_gateway(&selector, &gateways, false)
+            .await
+            .unwrap();
+        assert_eq!(recording.seen.lock().unwrap().len(), 3);
+
+        // selector failures are propagated
+        let failing = CustomGatewaySelector::new(AllowlistGatewaySelector::new());
+        assert!(matches!(
+            custom_selected_gateway(&failing, &gateways, false).await,
+            Err(ClientCoreError::GatewaySelectorFailure { .. })
+        ));
+    }
+}
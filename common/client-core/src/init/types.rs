@@ -5,6 +5,7 @@ use crate::client::key_manager::persistence::KeyStore;
 use crate::client::key_manager::ClientKeys;
 use crate::config::Config;
 use crate::error::ClientCoreError;
+use crate::init::selector::CustomGatewaySelector;
 use crate::init::{setup_gateway, use_loaded_gateway_details};
 use nym_client_core_gateways_storage::{
     GatewayRegistration, GatewaysDetailsStore, RemoteGatewayDetails,
@@ -140,7 +141,7 @@ impl InitialisationResult {
 
     pub fn client_address(&self) -> Recipient {
         Recipient::new(
-            *self.client_keys.identity_keypair().public_key(),
+            self.client_keys.identity_public_key(),
             *self.client_keys.encryption_keypair().public_key(),
             // TODO: below only works under assumption that gateway address == gateway id
             // (which currently is true)
@@ -168,6 +169,12 @@ pub enum GatewaySelectionSpecification {
         identity: IdentityKey,
     },
 
+    /// The new, remote, gateway should be chosen by the provided custom selector.
+    RemoteWithSelector {
+        must_use_tls: bool,
+        selector: CustomGatewaySelector,
+    },
+
     // TODO: this doesn't really fit in here..., but where else to put it?
     /// This client has handled the selection by itself
     Custom {
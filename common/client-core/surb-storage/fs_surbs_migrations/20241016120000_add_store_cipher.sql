@@ -0,0 +1,10 @@
+/*
+ * Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- if present, all the reply keys, reply surbs and sender tag recipients are stored encrypted
+CREATE TABLE store_cipher
+(
+    exported_cipher TEXT NOT NULL
+);
@@ -0,0 +1,141 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::backend::fs_backend::error::StorageError;
+use nym_crypto::asymmetric::identity;
+use nym_crypto::symmetric::at_rest;
+use nym_store_cipher::{
+    Aes256Gcm, EncryptedData, ExportedStoreCipher, StoreCipher, AES256GCM_NONCE_SIZE,
+};
+use std::fmt::{Debug, Formatter};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+const IDENTITY_PASSPHRASE_DERIVATION_SALT: &[u8] = b"NYM_REPLY_STORAGE_ENCRYPTION_V1";
+const DERIVED_PASSPHRASE_SIZE: usize = 32;
+
+/// Secret used for deriving the key encrypting the records of the on-disk reply storage.
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct ReplyStoragePassphrase(Vec<u8>);
+
+impl ReplyStoragePassphrase {
+    /// Uses the user-supplied passphrase.
+    pub fn new<P: Into<Vec<u8>>>(passphrase: P) -> Self {
+        ReplyStoragePassphrase(passphrase.into())
+    }
+
+    /// Derives the passphrase from the client's identity key, so that the storage could be
+    /// unlocked without any user interaction, but would be useless without the key itself.
+    /// The derivation follows the `fips` feature, so such storage can only be unlocked by clients
+    /// built the same way. Otherwise, the client refuses to start with
+    /// [`StorageError::InvalidPassphrase`] rather than discarding the existing storage.
+    pub fn derive_from_identity(identity_key: &identity::PrivateKey) -> Self {
+        let okm = at_rest::derive_secret(
+            IDENTITY_PASSPHRASE_DERIVATION_SALT,
+            &identity_key.to_bytes(),
+            None,
+            DERIVED_PASSPHRASE_SIZE,
+        );
+
+        ReplyStoragePassphrase(okm.to_vec())
+    }
+}
+
+/// Cipher used for encrypting the individual records before they're written to the database.
+pub(crate) struct RecordCipher {
+    inner: StoreCipher<Aes256Gcm>,
+}
+
+impl Debug for RecordCipher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RecordCipher")
+    }
+}
+
+impl RecordCipher {
+    pub(crate) fn new(passphrase: &ReplyStoragePassphrase) -> Result<Self, StorageError> {
+        Ok(RecordCipher {
+            inner: StoreCipher::new_with_default_kdf(&passphrase.0)?,
+        })
+    }
+
+    pub(crate) fn import(
+        passphrase: &ReplyStoragePassphrase,
+        exported: &str,
+    ) -> Result<Self, StorageError> {
+        let exported: ExportedStoreCipher =
+            serde_json::from_str(exported).map_err(|err| StorageError::CorruptedData {
+                details: format!("failed to recover the stored cipher information: {err}"),
+            })?;
+
+        match StoreCipher::import_aes256gcm(&passphrase.0, exported) {
+            Ok(inner) => Ok(RecordCipher { inner }),
+            Err(
+                nym_store_cipher::Error::InvalidImportPassphrase
+                | nym_store_cipher::Error::VerificationPhraseMismatch,
+            ) => Err(StorageError::InvalidPassphrase),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub(crate) fn export(&self) -> Result<String, StorageError> {
+        let exported = self.inner.export_aes256gcm()?;
+        serde_json::to_string(&exported).map_err(|err| StorageError::CorruptedData {
+            details: format!("failed to serialize the cipher information: {err}"),
+        })
+    }
+
+    /// Encrypts the record. The result is a concatenation of the cipher version,
+    /// the random nonce and the actual ciphertext.
+    pub(crate) fn encrypt(&self, record: Vec<u8>) -> Result<Vec<u8>, StorageError> {
+        let encrypted = self.inner.encrypt_data(record)?;
+
+        let mut out = Vec::with_capacity(1 + encrypted.nonce.len() + encrypted.ciphertext.len());
+        out.push(encrypted.version);
+        out.extend_from_slice(&encrypted.nonce);
+        out.extend_from_slice(&encrypted.ciphertext);
+        Ok(out)
+    }
+
+    pub(crate) fn decrypt(&self, record: &[u8]) -> Result<Vec<u8>, StorageError> {
+        if record.len() < 1 + AES256GCM_NONCE_SIZE {
+            return Err(StorageError::CorruptedData {
+                details: format!(
+                    "the encrypted record has length of {} which is too short to be valid",
+                    record.len()
+                ),
+            });
+        }
+
+        let (nonce, ciphertext) = record[1..].split_at(AES256GCM_NONCE_SIZE);
+        Ok(self.inner.decrypt_data(EncryptedData {
+            version: record[0],
+            ciphertext: ciphertext.to_vec(),
+            nonce: nonce.to_vec(),
+        })?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_can_only_be_recovered_with_the_same_passphrase() {
+        let passphrase = ReplyStoragePassphrase::new("my-secret-passphrase");
+        let cipher = RecordCipher::new(&passphrase).unwrap();
+        let exported = cipher.export().unwrap();
+
+        let record = b"some very secret reply key".to_vec();
+        let encrypted = cipher.encrypt(record.clone()).unwrap();
+        assert_ne!(encrypted, record);
+
+        let imported = RecordCipher::import(&passphrase, &exported).unwrap();
+        assert_eq!(imported.decrypt(&encrypted).unwrap(), record);
+
+        let wrong = ReplyStoragePassphrase::new("not-my-passphrase");
+        assert!(matches!(
+            RecordCipher::import(&wrong, &exported),
+            Err(StorageError::InvalidPassphrase)
+        ));
+    }
+}
@@ -57,6 +57,18 @@ pub enum StorageError {
         // err: Option<Box<dyn std::error::Error>>
     },
 
+    #[error("the reply storage is encrypted, but no passphrase has been provided")]
+    MissingPassphrase,
+
+    #[error("the provided passphrase can't be used for unlocking the encrypted reply storage")]
+    InvalidPassphrase,
+
+    #[error("failed to encrypt or decrypt the stored data: {source}")]
+    StoreCipherError {
+        #[from]
+        source: nym_store_cipher::Error,
+    },
+
     #[error("failed to create storage")]
     FailedToCreateStorage {
         source: Box<dyn std::error::Error + Send + Sync>,
@@ -232,6 +232,23 @@ impl StorageManager {
         Ok(())
     }
 
+    pub async fn get_store_cipher(&self) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query!("SELECT exported_cipher FROM store_cipher;")
+            .fetch_optional(&self.connection_pool)
+            .await
+            .map(|r| r.map(|r| r.exported_cipher))
+    }
+
+    pub async fn insert_store_cipher(&self, exported_cipher: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO store_cipher(exported_cipher) VALUES (?);",
+            exported_cipher
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn get_reply_surb_storage_metadata(
         &self,
     ) -> Result<ReplySurbStorageMetadata, sqlx::Error> {
@@ -1,6 +1,7 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::backend::fs_backend::encryption::RecordCipher;
 use crate::backend::fs_backend::manager::StorageManager;
 use crate::backend::fs_backend::models::{
     ReplySurbStorageMetadata, StoredReplyKey, StoredReplySurb, StoredSenderTag, StoredSurbSender,
@@ -16,8 +17,10 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use time::OffsetDateTime;
 
+pub use self::encryption::ReplyStoragePassphrase;
 pub use self::error::StorageError;
 
+mod encryption;
 mod error;
 mod manager;
 mod models;
@@ -27,12 +30,49 @@ pub struct Backend {
     temporary_old_path: Option<PathBuf>,
     database_path: PathBuf,
     manager: StorageManager,
+
+    /// Cipher used for encrypting the records during the flush.
+    /// If not present, the records are stored in plaintext.
+    cipher: Option<RecordCipher>,
+
+    /// Indicates whether the records currently in the database are encrypted.
+    /// It might differ from the presence of the cipher if plaintext storage is being migrated.
+    encrypted_records: bool,
 }
 
 impl Backend {
     const OLD_EXTENSION: &'static str = "old";
 
     pub async fn init<P: AsRef<Path>>(database_path: P) -> Result<Self, StorageError> {
+        Self::init_inner(database_path, None).await
+    }
+
+    /// Initialises fresh storage whose records are going to be encrypted
+    /// with a key derived from the provided passphrase.
+    pub async fn init_encrypted<P: AsRef<Path>>(
+        database_path: P,
+        passphrase: &ReplyStoragePassphrase,
+    ) -> Result<Self, StorageError> {
+        Self::init_inner(database_path, Some(passphrase)).await
+    }
+
+    pub async fn try_load<P: AsRef<Path>>(database_path: P) -> Result<Self, StorageError> {
+        Self::try_load_inner(database_path, None).await
+    }
+
+    /// Attempts to load existing storage encrypted with the provided passphrase.
+    /// If the existing storage is not encrypted, it's going to become encrypted on the next flush.
+    pub async fn try_load_encrypted<P: AsRef<Path>>(
+        database_path: P,
+        passphrase: &ReplyStoragePassphrase,
+    ) -> Result<Self, StorageError> {
+        Self::try_load_inner(database_path, Some(passphrase)).await
+    }
+
+    async fn init_inner<P: AsRef<Path>>(
+        database_path: P,
+        passphrase: Option<&ReplyStoragePassphrase>,
+    ) -> Result<Self, StorageError> {
         let owned_path: PathBuf = database_path.as_ref().into();
         if owned_path.file_name().is_none() {
             return Err(StorageError::DatabasePathWithoutFilename {
@@ -43,16 +83,23 @@ impl Backend {
         let manager = StorageManager::init(database_path, true).await?;
         manager.create_status_table().await?;
 
+        let cipher = passphrase.map(RecordCipher::new).transpose()?;
         let backend = Backend {
             temporary_old_path: None,
             database_path: owned_path,
             manager,
+            encrypted_records: cipher.is_some(),
+            cipher,
         };
+        backend.dump_store_cipher().await?;
 
         Ok(backend)
     }
 
-    pub async fn try_load<P: AsRef<Path>>(database_path: P) -> Result<Self, StorageError> {
+    async fn try_load_inner<P: AsRef<Path>>(
+        database_path: P,
+        passphrase: Option<&ReplyStoragePassphrase>,
+    ) -> Result<Self, StorageError> {
         let owned_path: PathBuf = database_path.as_ref().into();
         if owned_path.file_name().is_none() {
             return Err(StorageError::DatabasePathWithoutFilename {
@@ -74,6 +121,19 @@ impl Backend {
             return Err(StorageError::IncompleteDataFlush);
         }
 
+        // make sure we can actually read the data before we purge anything
+        let (cipher, encrypted_records) = match (manager.get_store_cipher().await?, passphrase) {
+            (Some(exported), Some(passphrase)) => {
+                (Some(RecordCipher::import(passphrase, &exported)?), true)
+            }
+            (Some(_), None) => return Err(StorageError::MissingPassphrase),
+            (None, Some(passphrase)) => {
+                info!("the existing reply storage is not encrypted. it's going to get encrypted on the next flush");
+                (Some(RecordCipher::new(passphrase)?), false)
+            }
+            (None, None) => (None, false),
+        };
+
         // the process has gone down without full graceful shutdown,
         // meaning the database doesn't contain valid data anymore
         // so we have to purge it
@@ -125,9 +185,35 @@ impl Backend {
             database_path: owned_path,
             // manager: StorageManagerState::Storage(manager),
             manager,
+            cipher,
+            encrypted_records,
         })
     }
 
+    fn seal_record(&self, record: Vec<u8>) -> Result<Vec<u8>, StorageError> {
+        match &self.cipher {
+            Some(cipher) => cipher.encrypt(record),
+            None => Ok(record),
+        }
+    }
+
+    fn open_record(&self, record: Vec<u8>) -> Result<Vec<u8>, StorageError> {
+        if !self.encrypted_records {
+            return Ok(record);
+        }
+        match &self.cipher {
+            Some(cipher) => cipher.decrypt(&record),
+            None => Err(StorageError::MissingPassphrase),
+        }
+    }
+
+    async fn dump_store_cipher(&self) -> Result<(), StorageError> {
+        if let Some(cipher) = &self.cipher {
+            self.manager.insert_store_cipher(&cipher.export()?).await?;
+        }
+        Ok(())
+    }
+
     async fn close_pool(&mut self) {
         self.manager.connection_pool.close().await;
     }
@@ -191,7 +277,10 @@ impl Backend {
         // something weird has happened and we can't trust the rest of the data
         let raw = stored
             .into_iter()
-            .map(TryInto::try_into)
+            .map(|mut stored| -> Result<_, StorageError> {
+                stored.recipient = self.open_record(stored.recipient)?;
+                stored.try_into()
+            })
             .collect::<Result<_, _>>()?;
 
         Ok(UsedSenderTags::from_raw(raw))
@@ -200,9 +289,9 @@ impl Backend {
     async fn dump_sender_tags(&self, tags: &UsedSenderTags) -> Result<(), StorageError> {
         for map_ref in tags.as_raw_iter() {
             let (recipient, tag) = map_ref.pair();
-            self.manager
-                .insert_tag(StoredSenderTag::new(*recipient, *tag))
-                .await?;
+            let mut stored = StoredSenderTag::new(*recipient, *tag);
+            stored.recipient = self.seal_record(stored.recipient)?;
+            self.manager.insert_tag(stored).await?;
         }
         Ok(())
     }
@@ -214,7 +303,10 @@ impl Backend {
         // something weird has happened and we can't trust the rest of the data
         let raw = stored
             .into_iter()
-            .map(TryInto::try_into)
+            .map(|mut stored| -> Result<_, StorageError> {
+                stored.reply_key = self.open_record(stored.reply_key)?;
+                stored.try_into()
+            })
             .collect::<Result<_, _>>()?;
 
         Ok(SentReplyKeys::from_raw(raw))
@@ -223,9 +315,9 @@ impl Backend {
     async fn dump_sender_reply_keys(&self, reply_keys: &SentReplyKeys) -> Result<(), StorageError> {
         for map_ref in reply_keys.as_raw_iter() {
             let (digest, key) = map_ref.pair();
-            self.manager
-                .insert_reply_key(StoredReplyKey::new(*digest, *key))
-                .await?;
+            let mut stored = StoredReplyKey::new(*digest, *key);
+            stored.reply_key = self.seal_record(stored.reply_key)?;
+            self.manager.insert_reply_key(stored).await?;
         }
         Ok(())
     }
@@ -244,7 +336,10 @@ impl Backend {
                 .get_reply_surbs(sender_id)
                 .await?
                 .into_iter()
-                .map(|raw| raw.try_into())
+                .map(|mut raw| -> Result<_, StorageError> {
+                    raw.reply_surb = self.open_record(raw.reply_surb)?;
+                    raw.try_into()
+                })
                 .collect::<Result<_, _>>()?;
 
             received_surbs.push((
@@ -275,9 +370,9 @@ impl Backend {
                 .await?;
 
             for reply_surb in received_surbs.surbs_ref() {
-                self.manager
-                    .insert_reply_surb(StoredReplySurb::new(sender_id, reply_surb))
-                    .await?
+                let mut stored = StoredReplySurb::new(sender_id, reply_surb);
+                stored.reply_surb = self.seal_record(stored.reply_surb)?;
+                self.manager.insert_reply_surb(stored).await?
             }
         }
         Ok(())
@@ -322,6 +417,10 @@ impl ReplyStorageBackend for Backend {
         self.rotate().await?;
         self.start_storage_flush().await?;
 
+        // from now on, the database only contains records encrypted with our current cipher (if any)
+        self.encrypted_records = self.cipher.is_some();
+        self.dump_store_cipher().await?;
+
         self.dump_sender_tags(storage.tags_storage_ref()).await?;
         self.dump_sender_reply_keys(storage.key_storage_ref())
             .await?;
@@ -351,3 +450,133 @@ impl ReplyStorageBackend for Backend {
         self.stop_client_use().await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_crypto::asymmetric::{encryption, identity};
+    use nym_sphinx::addressing::clients::Recipient;
+    use nym_sphinx::anonymous_replies::SurbEncryptionKey;
+    use rand::rngs::OsRng;
+
+    struct StoredData {
+        storage: CombinedReplyStorage,
+        key: SurbEncryptionKey,
+        recipient: Recipient,
+        tag: AnonymousSenderTag,
+    }
+
+    impl StoredData {
+        fn new() -> Self {
+            let mut rng = OsRng;
+            let storage = CombinedReplyStorage::new(10, 100);
+            let key = SurbEncryptionKey::new(&mut rng);
+            storage.key_storage_ref().insert_multiple(vec![key]);
+
+            let recipient = Recipient::new(
+                *identity::KeyPair::new(&mut rng).public_key(),
+                *encryption::KeyPair::new(&mut rng).public_key(),
+                *identity::KeyPair::new(&mut rng).public_key(),
+            );
+            let tag = AnonymousSenderTag::new_random(&mut rng);
+            storage.tags_storage_ref().insert_new(&recipient, tag);
+
+            StoredData {
+                storage,
+                key,
+                recipient,
+                tag,
+            }
+        }
+
+        fn assert_loaded(&self, loaded: &CombinedReplyStorage) {
+            assert_eq!(
+                loaded.tags_storage_ref().try_get_existing(&self.recipient),
+                Some(self.tag)
+            );
+            let digest = self.key.compute_digest();
+            assert!(loaded
+                .key_storage_ref()
+                .as_raw_iter()
+                .any(|entry| *entry.key() == digest));
+        }
+
+        // whether the raw database file contains any of the stored secrets in plaintext
+        fn leaked_into(&self, database_path: &Path) -> bool {
+            let raw = fs::read(database_path).unwrap();
+            let contains = |needle: &[u8]| raw.windows(needle.len()).any(|w| w == needle);
+            contains(self.key.as_bytes()) || contains(&self.recipient.to_bytes())
+        }
+    }
+
+    async fn flush(mut backend: Backend, data: &StoredData) {
+        backend.init_fresh(&data.storage).await.unwrap();
+        backend.start_storage_session().await.unwrap();
+        backend.flush_surb_storage(&data.storage).await.unwrap();
+        backend.stop_storage_session().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn encrypted_storage_can_only_be_loaded_with_the_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("surbs.sqlite");
+        let passphrase = ReplyStoragePassphrase::new("my-secret-passphrase");
+        let data = StoredData::new();
+
+        let backend = Backend::init_encrypted(&db_path, &passphrase)
+            .await
+            .unwrap();
+        flush(backend, &data).await;
+        assert!(!data.leaked_into(&db_path));
+
+        assert!(matches!(
+            Backend::try_load(&db_path).await,
+            Err(StorageError::MissingPassphrase)
+        ));
+        let wrong = ReplyStoragePassphrase::new("not-my-passphrase");
+        assert!(matches!(
+            Backend::try_load_encrypted(&db_path, &wrong).await,
+            Err(StorageError::InvalidPassphrase)
+        ));
+
+        let backend = Backend::try_load_encrypted(&db_path, &passphrase)
+            .await
+            .unwrap();
+        data.assert_loaded(&backend.load_surb_storage().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn plaintext_storage_gets_encrypted_on_flush() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("surbs.sqlite");
+        let passphrase = ReplyStoragePassphrase::derive_from_identity(
+            identity::KeyPair::new(&mut OsRng).private_key(),
+        );
+        let data = StoredData::new();
+
+        let backend = Backend::init(&db_path).await.unwrap();
+        flush(backend, &data).await;
+        assert!(data.leaked_into(&db_path));
+
+        // the existing plaintext records are still readable...
+        let mut backend = Backend::try_load_encrypted(&db_path, &passphrase)
+            .await
+            .unwrap();
+        data.assert_loaded(&backend.load_surb_storage().await.unwrap());
+
+        // ...but they're written back encrypted
+        backend.start_storage_session().await.unwrap();
+        backend.flush_surb_storage(&data.storage).await.unwrap();
+        backend.stop_storage_session().await.unwrap();
+        assert!(!data.leaked_into(&db_path));
+
+        assert!(matches!(
+            Backend::try_load(&db_path).await,
+            Err(StorageError::MissingPassphrase)
+        ));
+        let backend = Backend::try_load_encrypted(&db_path, &passphrase)
+            .await
+            .unwrap();
+        data.assert_loaded(&backend.load_surb_storage().await.unwrap());
+    }
+}
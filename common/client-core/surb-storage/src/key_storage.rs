@@ -23,6 +23,10 @@ impl Default for SentReplyKeys {
 #[derive(Debug)]
 struct SentReplyKeysInner {
     data: DashMap<EncryptionKeyDigest, UsedReplyKey>,
+
+    // digests of keys that got used for decrypting a reply alongside the time of their usage,
+    // so that the information could be propagated to other devices sharing the same identity
+    used: DashMap<EncryptionKeyDigest, i64>,
 }
 
 impl SentReplyKeys {
@@ -30,6 +34,7 @@ impl SentReplyKeys {
         SentReplyKeys {
             inner: Arc::new(SentReplyKeysInner {
                 data: DashMap::new(),
+                used: DashMap::new(),
             }),
         }
     }
@@ -39,6 +44,7 @@ impl SentReplyKeys {
         SentReplyKeys {
             inner: Arc::new(SentReplyKeysInner {
                 data: raw.into_iter().collect(),
+                used: DashMap::new(),
             }),
         }
     }
@@ -59,12 +65,82 @@ impl SentReplyKeys {
     }
 
     pub fn try_pop(&self, digest: EncryptionKeyDigest) -> Option<UsedReplyKey> {
-        self.inner.data.remove(&digest).map(|(_k, v)| v)
+        let popped = self.inner.data.remove(&digest).map(|(_k, v)| v);
+        if popped.is_some() {
+            self.inner
+                .used
+                .insert(digest, OffsetDateTime::now_utc().unix_timestamp());
+        }
+        popped
     }
 
     pub fn remove(&self, digest: EncryptionKeyDigest) {
         self.inner.data.remove(&digest);
     }
+
+    /// Inserts the key unless it has already been used. If the key already exists,
+    /// the earlier of the two timestamps is kept.
+    /// Returns whether a new key has been inserted.
+    pub fn merge(&self, key: UsedReplyKey) -> bool {
+        let digest = key.compute_digest();
+        if self.was_used(&digest) {
+            return false;
+        }
+
+        let mut inserted = false;
+        self.inner
+            .data
+            .entry(digest)
+            .and_modify(|existing| {
+                existing.sent_at_timestamp = existing.sent_at_timestamp.min(key.sent_at_timestamp)
+            })
+            .or_insert_with(|| {
+                inserted = true;
+                key
+            });
+        inserted
+    }
+
+    /// Returns all keys that were sent at or after the provided timestamp.
+    pub fn sent_since(&self, timestamp: i64) -> Vec<UsedReplyKey> {
+        self.inner
+            .data
+            .iter()
+            .filter(|entry| entry.sent_at_timestamp >= timestamp)
+            .map(|entry| *entry.value())
+            .collect()
+    }
+
+    /// Returns digests of all keys that were used at or after the provided timestamp
+    /// alongside the time of their usage.
+    pub fn used_since(&self, timestamp: i64) -> Vec<(EncryptionKeyDigest, i64)> {
+        self.inner
+            .used
+            .iter()
+            .filter(|entry| *entry.value() >= timestamp)
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect()
+    }
+
+    pub fn was_used(&self, digest: &EncryptionKeyDigest) -> bool {
+        self.inner.used.contains_key(digest)
+    }
+
+    /// Removes the key with the provided digest (if it exists) and marks it as used,
+    /// so that it would not get re-inserted.
+    pub fn mark_used(&self, digest: EncryptionKeyDigest, used_at_timestamp: i64) {
+        self.inner.data.remove(&digest);
+        self.inner
+            .used
+            .entry(digest)
+            .and_modify(|existing| *existing = (*existing).min(used_at_timestamp))
+            .or_insert(used_at_timestamp);
+    }
+
+    /// Forgets about all keys that were used before the provided timestamp.
+    pub fn prune_used(&self, older_than: i64) {
+        self.inner.used.retain(|_, used_at| *used_at >= older_than)
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
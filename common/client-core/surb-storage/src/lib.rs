@@ -1,18 +1,33 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use futures::channel::{mpsc, oneshot};
+use futures::future::{self, Either};
+use futures::StreamExt;
+use std::pin::pin;
+
 pub use backend::*;
 pub use combined::CombinedReplyStorage;
 pub use key_storage::SentReplyKeys;
 pub use surb_storage::ReceivedReplySurbsMap;
+pub use sync::{
+    ReplyStorageDelta, ReplyStorageSyncKey, ReplyStorageSynchroniser, ReplySyncError, SurbHandover,
+    SyncMergeSummary,
+};
 pub use tag_storage::UsedSenderTags;
 
 mod backend;
 mod combined;
 mod key_storage;
 mod surb_storage;
+mod sync;
 mod tag_storage;
 
+/// Channel used for requesting the reply-related data to be flushed to the underlying storage
+/// ahead of the shutdown. The responder receives an indication of whether the flush has succeeded.
+pub type FlushRequestSender = mpsc::UnboundedSender<oneshot::Sender<bool>>;
+pub type FlushRequestReceiver = mpsc::UnboundedReceiver<oneshot::Sender<bool>>;
+
 // only really exists to get information about shutdown and save data to the backing storage
 pub struct PersistentReplyStorage<T = backend::Empty>
 where
@@ -37,6 +52,7 @@ where
     pub async fn flush_on_shutdown(
         mut self,
         mem_state: CombinedReplyStorage,
+        mut flush_requests: FlushRequestReceiver,
         mut shutdown: nym_task::TaskClient,
     ) {
         use log::{debug, error, info};
@@ -47,7 +63,29 @@ where
             return;
         }
 
-        shutdown.recv().await;
+        loop {
+            let request = match future::select(pin!(shutdown.recv()), flush_requests.next()).await {
+                Either::Left(_) => break,
+                Either::Right((request, _)) => request,
+            };
+            let Some(responder) = request else {
+                // nobody is going to request the flush anymore
+                shutdown.recv().await;
+                break;
+            };
+
+            info!("PersistentReplyStorage is flushing all reply-related data to underlying storage on request");
+            let flushed = match self.backend.flush_surb_storage(&mem_state).await {
+                Ok(_) => true,
+                Err(err) => {
+                    error!(
+                        "failed to flush our reply-related data to the persistent storage: {err}"
+                    );
+                    false
+                }
+            };
+            let _ = responder.send(flushed);
+        }
 
         info!("PersistentReplyStorage is flushing all reply-related data to underlying storage");
         info!("you MUST NOT forcefully shutdown now or you risk data corruption!");
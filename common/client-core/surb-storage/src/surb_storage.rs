@@ -134,10 +134,21 @@ impl ReceivedReplySurbsMap {
         &self,
         target: &AnonymousSenderTag,
         amount: usize,
+    ) -> (Option<Vec<ReplySurb>>, usize) {
+        self.get_reply_surbs_with_threshold(target, amount, self.min_surb_threshold())
+    }
+
+    /// Retrieves the specified amount of reply surbs as long as at least `min_threshold` surbs
+    /// would remain in the storage afterwards, rather than the globally configured minimum.
+    pub fn get_reply_surbs_with_threshold(
+        &self,
+        target: &AnonymousSenderTag,
+        amount: usize,
+        min_threshold: usize,
     ) -> (Option<Vec<ReplySurb>>, usize) {
         if let Some(mut entry) = self.inner.data.get_mut(target) {
             let surbs_left = entry.items_left();
-            if surbs_left < self.min_surb_threshold() + amount {
+            if surbs_left < min_threshold + amount {
                 (None, surbs_left)
             } else {
                 entry.get_reply_surbs(amount)
@@ -160,10 +171,18 @@ impl ReceivedReplySurbsMap {
     pub fn get_reply_surb(
         &self,
         target: &AnonymousSenderTag,
+    ) -> Option<(Option<ReplySurb>, usize)> {
+        self.get_reply_surb_with_threshold(target, self.min_surb_threshold())
+    }
+
+    pub fn get_reply_surb_with_threshold(
+        &self,
+        target: &AnonymousSenderTag,
+        min_threshold: usize,
     ) -> Option<(Option<ReplySurb>, usize)> {
         self.inner.data.get_mut(target).map(|mut entry| {
             let surbs_left = entry.items_left();
-            if surbs_left < self.min_surb_threshold() {
+            if surbs_left < min_threshold {
                 (None, surbs_left)
             } else {
                 entry.get_reply_surb()
@@ -171,6 +190,19 @@ impl ReceivedReplySurbsMap {
         })
     }
 
+    /// Removes up to the specified amount of reply surbs for the given target,
+    /// regardless of the minimum threshold, for example in order to hand them over to another device.
+    pub fn take_reply_surbs(&self, target: &AnonymousSenderTag, amount: usize) -> Vec<ReplySurb> {
+        self.inner
+            .data
+            .get_mut(target)
+            .map(|mut entry| {
+                let amount = amount.min(entry.items_left());
+                entry.data.drain(..amount).collect()
+            })
+            .unwrap_or_default()
+    }
+
     pub fn insert_surbs<I: IntoIterator<Item = ReplySurb>>(
         &self,
         target: &AnonymousSenderTag,
@@ -216,7 +248,6 @@ impl ReceivedReplySurbs {
         }
     }
 
-    #[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
     pub fn surbs_ref(&self) -> &VecDeque<ReplySurb> {
         &self.data
     }
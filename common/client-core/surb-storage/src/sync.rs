@@ -0,0 +1,761 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Synchronisation of reply-related data between multiple devices running the same client identity.
+//!
+//! Every device periodically exports a [`ReplyStorageDelta`] with all the changes since its previous
+//! export. The delta is encrypted with a key derived from the shared identity key, so it could be
+//! transported to the other device through any (even untrusted) channel and merged into its storage.
+//!
+//! Conflicts are resolved based on digests of the stored items:
+//! - reply keys are merged by their digest. If the same key is known to both devices, the earlier
+//!   timestamp is kept, while a key that has already been used by either of the devices is never re-inserted,
+//! - reply SURBs are never copied between devices, they are moved instead, i.e. the exporting device
+//!   relinquishes them, so that every SURB could only ever be used by a single device. When merging,
+//!   SURBs whose digest is already known are ignored,
+//! - sender tags are merged per recipient. In case of a conflict, the tag with the lower
+//!   byte representation is kept so that all devices eventually converge on the same one.
+
+use crate::key_storage::UsedReplyKey;
+use crate::CombinedReplyStorage;
+use log::{debug, warn};
+use nym_crypto::asymmetric::identity;
+use nym_crypto::symmetric::at_rest::{AtRestEncryptionError, AtRestKey};
+use nym_crypto::{crypto_hash, Digest};
+use nym_sphinx::addressing::clients::{Recipient, RecipientBytes};
+use nym_sphinx::anonymous_replies::encryption_key::EncryptionKeyDigest;
+use nym_sphinx::anonymous_replies::requests::{AnonymousSenderTag, SENDER_TAG_SIZE};
+use nym_sphinx::anonymous_replies::{ReplySurb, SurbEncryptionKey, SurbEncryptionKeySize};
+use nym_sphinx::params::ReplySurbKeyDigestAlgorithm;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+use time::OffsetDateTime;
+
+const SYNC_KEY_DERIVATION_SALT: &[u8] = b"NYM_REPLY_STORAGE_SYNC_V1";
+
+/// Increment it whenever the serialization format of the [`ReplyStorageDelta`] changes.
+const DELTA_FORMAT_VERSION: u8 = 1;
+
+#[derive(Debug, Error)]
+pub enum ReplySyncError {
+    #[error("failed to encrypt the reply storage delta")]
+    EncryptionFailure,
+
+    #[error("failed to decrypt the reply storage delta - it's either malformed or it was created for a different identity")]
+    DecryptionFailure,
+
+    #[error("the reply storage delta can't be decrypted by this device: {source}")]
+    IncompatibleEncryption { source: AtRestEncryptionError },
+
+    #[error("the reply storage delta uses an unsupported format version {version}")]
+    UnsupportedVersion { version: u8 },
+
+    #[error("the reply storage delta is malformed: {details}")]
+    MalformedDelta { details: String },
+
+    #[error("the reply storage delta {id} has already been applied")]
+    AlreadyApplied { id: u64 },
+
+    #[error("reply storage synchronisation is unavailable: {reason}")]
+    Unavailable { reason: String },
+}
+
+impl ReplySyncError {
+    fn malformed<S: Into<String>>(details: S) -> Self {
+        ReplySyncError::MalformedDelta {
+            details: details.into(),
+        }
+    }
+
+    pub fn unavailable<S: Into<String>>(reason: S) -> Self {
+        ReplySyncError::Unavailable {
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Specifies which reply SURBs should be handed over to the other device during the export.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SurbHandover {
+    /// Keep all reply SURBs on this device.
+    #[default]
+    None,
+
+    /// Hand over half of the reply SURBs received from every sender, so that both devices
+    /// would be able to send replies.
+    Half,
+
+    /// Hand over all reply SURBs, for example before this device goes offline.
+    All,
+}
+
+impl SurbHandover {
+    fn amount(&self, available: usize) -> usize {
+        match self {
+            SurbHandover::None => 0,
+            SurbHandover::Half => available / 2,
+            SurbHandover::All => available,
+        }
+    }
+}
+
+/// Summary of changes applied to the storage after merging a [`ReplyStorageDelta`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SyncMergeSummary {
+    pub new_reply_keys: usize,
+    pub used_reply_keys: usize,
+    pub new_reply_surbs: usize,
+    pub duplicate_reply_surbs: usize,
+}
+
+/// Set of reply-related changes exported by one device to be merged by another one.
+#[derive(Debug)]
+pub struct ReplyStorageDelta {
+    id: u64,
+    created_at: i64,
+    reply_keys: Vec<UsedReplyKey>,
+    used_reply_keys: Vec<(EncryptionKeyDigest, i64)>,
+    sender_tags: Vec<(RecipientBytes, AnonymousSenderTag)>,
+    reply_surbs: Vec<(AnonymousSenderTag, Vec<ReplySurb>)>,
+}
+
+impl ReplyStorageDelta {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn created_at(&self) -> i64 {
+        self.created_at
+    }
+
+    pub fn reply_surbs_count(&self) -> usize {
+        self.reply_surbs.iter().map(|(_, surbs)| surbs.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reply_keys.is_empty()
+            && self.used_reply_keys.is_empty()
+            && self.sender_tags.is_empty()
+            && self.reply_surbs_count() == 0
+    }
+
+    // VERSION || ID || CREATED_AT ||
+    // NUM_KEYS || (KEY || SENT_AT)* ||
+    // NUM_USED || (DIGEST || USED_AT)* ||
+    // NUM_TAGS || (RECIPIENT || TAG)* ||
+    // NUM_SENDERS || (TAG || NUM_SURBS || (SURB_LEN || SURB)*)*
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![DELTA_FORMAT_VERSION];
+        bytes.extend_from_slice(&self.id.to_be_bytes());
+        bytes.extend_from_slice(&self.created_at.to_be_bytes());
+
+        bytes.extend_from_slice(&(self.reply_keys.len() as u32).to_be_bytes());
+        for key in &self.reply_keys {
+            bytes.extend_from_slice(&key.to_bytes());
+            bytes.extend_from_slice(&key.sent_at_timestamp.to_be_bytes());
+        }
+
+        bytes.extend_from_slice(&(self.used_reply_keys.len() as u32).to_be_bytes());
+        for (digest, used_at) in &self.used_reply_keys {
+            bytes.extend_from_slice(digest);
+            bytes.extend_from_slice(&used_at.to_be_bytes());
+        }
+
+        bytes.extend_from_slice(&(self.sender_tags.len() as u32).to_be_bytes());
+        for (recipient, tag) in &self.sender_tags {
+            bytes.extend_from_slice(recipient);
+            bytes.extend_from_slice(&tag.to_bytes());
+        }
+
+        bytes.extend_from_slice(&(self.reply_surbs.len() as u32).to_be_bytes());
+        for (tag, surbs) in &self.reply_surbs {
+            bytes.extend_from_slice(&tag.to_bytes());
+            bytes.extend_from_slice(&(surbs.len() as u32).to_be_bytes());
+            for surb in surbs {
+                let surb_bytes = surb.to_bytes();
+                bytes.extend_from_slice(&(surb_bytes.len() as u32).to_be_bytes());
+                bytes.extend_from_slice(&surb_bytes);
+            }
+        }
+
+        bytes
+    }
+
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, ReplySyncError> {
+        let mut reader = DeltaReader { bytes };
+
+        let version = reader.read_u8()?;
+        if version != DELTA_FORMAT_VERSION {
+            return Err(ReplySyncError::UnsupportedVersion { version });
+        }
+        let id = reader.read_u64()?;
+        let created_at = reader.read_i64()?;
+
+        let num_keys = reader.read_u32()?;
+        let mut reply_keys = Vec::new();
+        for _ in 0..num_keys {
+            let key = SurbEncryptionKey::try_from_bytes(reader.take(SurbEncryptionKeySize::USIZE)?)
+                .map_err(|err| ReplySyncError::malformed(format!("invalid reply key: {err}")))?;
+            let sent_at = reader.read_i64()?;
+            reply_keys.push(UsedReplyKey::new(key, sent_at));
+        }
+
+        let num_used = reader.read_u32()?;
+        let mut used_reply_keys = Vec::new();
+        for _ in 0..num_used {
+            let digest_bytes = reader.take(ReplySurbKeyDigestAlgorithm::output_size())?;
+            let digest = EncryptionKeyDigest::clone_from_slice(digest_bytes);
+            let used_at = reader.read_i64()?;
+            used_reply_keys.push((digest, used_at));
+        }
+
+        let num_tags = reader.read_u32()?;
+        let mut sender_tags = Vec::new();
+        for _ in 0..num_tags {
+            let recipient = reader.read_array::<{ Recipient::LEN }>()?;
+            let tag = AnonymousSenderTag::from_bytes(reader.read_array::<SENDER_TAG_SIZE>()?);
+            sender_tags.push((recipient, tag));
+        }
+
+        let num_senders = reader.read_u32()?;
+        let mut reply_surbs = Vec::new();
+        for _ in 0..num_senders {
+            let tag = AnonymousSenderTag::from_bytes(reader.read_array::<SENDER_TAG_SIZE>()?);
+            let num_surbs = reader.read_u32()?;
+            let mut surbs = Vec::new();
+            for _ in 0..num_surbs {
+                let surb_len = reader.read_u32()? as usize;
+                // make sure we won't attempt any out of bound reads during the recovery
+                if surb_len <= SurbEncryptionKeySize::USIZE {
+                    return Err(ReplySyncError::malformed(format!(
+                        "reply surb of {surb_len} bytes is too short"
+                    )));
+                }
+                let surb = ReplySurb::from_bytes(reader.take(surb_len)?).map_err(|err| {
+                    ReplySyncError::malformed(format!("failed to recover reply surb: {err}"))
+                })?;
+                surbs.push(surb);
+            }
+            reply_surbs.push((tag, surbs));
+        }
+
+        if !reader.bytes.is_empty() {
+            return Err(ReplySyncError::malformed(format!(
+                "{} trailing bytes",
+                reader.bytes.len()
+            )));
+        }
+
+        Ok(ReplyStorageDelta {
+            id,
+            created_at,
+            reply_keys,
+            used_reply_keys,
+            sender_tags,
+            reply_surbs,
+        })
+    }
+}
+
+struct DeltaReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> DeltaReader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ReplySyncError> {
+        if self.bytes.len() < n {
+            return Err(ReplySyncError::malformed("unexpected end of data"));
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], ReplySyncError> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, ReplySyncError> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ReplySyncError> {
+        Ok(u32::from_be_bytes(self.read_array()?))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, ReplySyncError> {
+        Ok(u64::from_be_bytes(self.read_array()?))
+    }
+
+    fn read_i64(&mut self) -> Result<i64, ReplySyncError> {
+        Ok(i64::from_be_bytes(self.read_array()?))
+    }
+}
+
+type SurbDigest = EncryptionKeyDigest;
+
+fn surb_digest(surb: &ReplySurb) -> SurbDigest {
+    crypto_hash::compute_digest::<ReplySurbKeyDigestAlgorithm>(&surb.to_bytes())
+}
+
+impl CombinedReplyStorage {
+    /// Exports all reply-related changes made at or after the provided timestamp.
+    ///
+    /// Note that the reply SURBs selected by the `surb_handover` are removed from this storage,
+    /// so the resultant delta MUST be delivered to the other device or merged back,
+    /// otherwise they are going to be lost.
+    pub fn export_sync_delta(&self, since: i64, surb_handover: SurbHandover) -> ReplyStorageDelta {
+        let reply_keys = self.key_storage_ref().sent_since(since);
+        let used_reply_keys = self.key_storage_ref().used_since(since);
+        let sender_tags = self
+            .tags_storage_ref()
+            .as_raw_iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect();
+
+        // note: we can't be holding any references into the map while taking the surbs out of it
+        let to_handover = self
+            .surbs_storage_ref()
+            .as_raw_iter()
+            .map(|entry| {
+                let available = entry.surbs_ref().len();
+                (*entry.key(), surb_handover.amount(available))
+            })
+            .filter(|(_, amount)| *amount > 0)
+            .collect::<Vec<_>>();
+
+        let reply_surbs = to_handover
+            .into_iter()
+            .map(|(tag, amount)| (tag, self.surbs_storage_ref().take_reply_surbs(&tag, amount)))
+            .collect();
+
+        ReplyStorageDelta {
+            id: OsRng.next_u64(),
+            created_at: OffsetDateTime::now_utc().unix_timestamp(),
+            reply_keys,
+            used_reply_keys,
+            sender_tags,
+            reply_surbs,
+        }
+    }
+
+    /// Merges changes exported by another device into this storage.
+    pub fn merge_sync_delta(&self, delta: ReplyStorageDelta) -> SyncMergeSummary {
+        let mut summary = SyncMergeSummary::default();
+
+        // make sure to process the used keys first so that they wouldn't get re-inserted
+        for (digest, used_at) in delta.used_reply_keys {
+            self.key_storage_ref().mark_used(digest, used_at);
+            summary.used_reply_keys += 1;
+        }
+
+        for key in delta.reply_keys {
+            if self.key_storage_ref().merge(key) {
+                summary.new_reply_keys += 1;
+            }
+        }
+
+        for (recipient, tag) in delta.sender_tags {
+            self.tags_storage_ref().merge_raw(recipient, tag)
+        }
+
+        let mut known_surbs: HashMap<AnonymousSenderTag, HashSet<SurbDigest>> = delta
+            .reply_surbs
+            .iter()
+            .map(|(tag, _)| (*tag, HashSet::new()))
+            .collect();
+        for entry in self.surbs_storage_ref().as_raw_iter() {
+            if let Some(known) = known_surbs.get_mut(entry.key()) {
+                known.extend(entry.surbs_ref().iter().map(surb_digest))
+            }
+        }
+
+        for (tag, surbs) in delta.reply_surbs {
+            let known = known_surbs.entry(tag).or_default();
+            let received = surbs.len();
+            let new_surbs = surbs
+                .into_iter()
+                .filter(|surb| known.insert(surb_digest(surb)))
+                .collect::<Vec<_>>();
+
+            summary.duplicate_reply_surbs += received - new_surbs.len();
+            summary.new_reply_surbs += new_surbs.len();
+            if !new_surbs.is_empty() {
+                self.surbs_storage_ref().insert_surbs(&tag, new_surbs)
+            }
+        }
+
+        if summary.duplicate_reply_surbs > 0 {
+            warn!(
+                "ignored {} reply surbs we already knew about while merging the reply storage delta",
+                summary.duplicate_reply_surbs
+            )
+        }
+        debug!("merged reply storage delta {}: {summary:?}", delta.id);
+
+        summary
+    }
+}
+
+/// Key used for encrypting [`ReplyStorageDelta`]s exchanged between devices.
+/// It's derived from the identity key, so that only devices sharing the same identity
+/// are able to recover the content.
+/// Note that devices built with the `fips` feature can read the deltas of the devices without it,
+/// but not the other way around.
+pub struct ReplyStorageSyncKey {
+    key: AtRestKey,
+}
+
+impl ReplyStorageSyncKey {
+    pub fn derive_from_identity(identity_key: &identity::PrivateKey) -> Self {
+        ReplyStorageSyncKey {
+            key: AtRestKey::derive(SYNC_KEY_DERIVATION_SALT, &identity_key.to_bytes(), None),
+        }
+    }
+
+    pub fn encrypt(&self, delta: &ReplyStorageDelta) -> Result<Vec<u8>, ReplySyncError> {
+        self.key
+            .encrypt(&delta.to_bytes())
+            .map_err(|_| ReplySyncError::EncryptionFailure)
+    }
+
+    pub fn decrypt(&self, encrypted: &[u8]) -> Result<ReplyStorageDelta, ReplySyncError> {
+        let plaintext = self.key.decrypt(encrypted).map_err(|err| match err {
+            AtRestEncryptionError::UnsupportedSuite { .. }
+            | AtRestEncryptionError::UnknownSuite { .. } => {
+                ReplySyncError::IncompatibleEncryption { source: err }
+            }
+            _ => ReplySyncError::DecryptionFailure,
+        })?;
+
+        ReplyStorageDelta::try_from_bytes(&plaintext)
+    }
+}
+
+/// Keeps the reply storage of this device in sync with other devices sharing the same identity.
+pub struct ReplyStorageSynchroniser {
+    storage: CombinedReplyStorage,
+    key: ReplyStorageSyncKey,
+
+    // timestamp of the previous export, so that only new changes would get exported next time
+    last_export: i64,
+
+    // ids of deltas we have already merged, so that replaying them would have no effect
+    applied_deltas: HashSet<u64>,
+}
+
+impl ReplyStorageSynchroniser {
+    pub fn new(storage: CombinedReplyStorage, identity_key: &identity::PrivateKey) -> Self {
+        ReplyStorageSynchroniser {
+            storage,
+            key: ReplyStorageSyncKey::derive_from_identity(identity_key),
+            last_export: 0,
+            applied_deltas: HashSet::new(),
+        }
+    }
+
+    /// Exports and encrypts all changes made since the previous export
+    /// (or all the data if this is the first one).
+    pub fn export(&mut self, surb_handover: SurbHandover) -> Result<Vec<u8>, ReplySyncError> {
+        let delta = self
+            .storage
+            .export_sync_delta(self.last_export, surb_handover);
+        let created_at = delta.created_at();
+        self.applied_deltas.insert(delta.id());
+
+        match self.key.encrypt(&delta) {
+            Ok(encrypted) => {
+                self.last_export = created_at;
+                Ok(encrypted)
+            }
+            Err(err) => {
+                // put back any surbs we have taken out
+                self.storage.merge_sync_delta(delta);
+                Err(err)
+            }
+        }
+    }
+
+    /// Decrypts the delta exported by another device and merges it into our storage.
+    pub fn import(&mut self, encrypted: &[u8]) -> Result<SyncMergeSummary, ReplySyncError> {
+        let delta = self.key.decrypt(encrypted)?;
+        if !self.applied_deltas.insert(delta.id()) {
+            return Err(ReplySyncError::AlreadyApplied { id: delta.id() });
+        }
+
+        Ok(self.storage.merge_sync_delta(delta))
+    }
+
+    /// Merges back our own exported delta that could not be delivered to the other device,
+    /// so that the reply SURBs handed over in it would not be lost.
+    /// The next export is going to contain all the data again.
+    pub fn restore(&mut self, encrypted: &[u8]) -> Result<SyncMergeSummary, ReplySyncError> {
+        let delta = self.key.decrypt(encrypted)?;
+        self.last_export = 0;
+        Ok(self.storage.merge_sync_delta(delta))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_crypto::asymmetric::encryption;
+    use nym_sphinx::{
+        Delay, Destination, DestinationAddressBytes, Node, NodeAddressBytes, PrivateKey,
+        SURBMaterial, NODE_ADDRESS_LENGTH,
+    };
+
+    fn reply_surb() -> ReplySurb {
+        let mut rng = OsRng;
+        let route = (0..4)
+            .map(|_| {
+                let mut address_bytes = [0; NODE_ADDRESS_LENGTH];
+                rng.fill_bytes(&mut address_bytes);
+                let private_key = PrivateKey::new_with_rng(&mut rng);
+                Node {
+                    address: NodeAddressBytes::from_bytes(address_bytes),
+                    pub_key: (&private_key).into(),
+                }
+            })
+            .collect();
+        let delays = (0..4).map(|_| Delay::new_from_nanos(1000)).collect();
+        let mut destination_bytes = [0u8; 32];
+        rng.fill_bytes(&mut destination_bytes);
+        let destination = Destination::new(
+            DestinationAddressBytes::from_bytes(destination_bytes),
+            [0u8; 16],
+        );
+        let surb = SURBMaterial::new(route, delays, destination)
+            .construct_SURB()
+            .unwrap();
+
+        let mut bytes = SurbEncryptionKey::new(&mut rng).to_bytes();
+        bytes.extend_from_slice(&surb.to_bytes());
+        ReplySurb::from_bytes(&bytes).unwrap()
+    }
+
+    fn recipient() -> Recipient {
+        let mut rng = OsRng;
+        Recipient::new(
+            *identity::KeyPair::new(&mut rng).public_key(),
+            *encryption::KeyPair::new(&mut rng).public_key(),
+            *identity::KeyPair::new(&mut rng).public_key(),
+        )
+    }
+
+    fn populated_storage(
+        tag: AnonymousSenderTag,
+        surbs: Vec<ReplySurb>,
+    ) -> (CombinedReplyStorage, SurbEncryptionKey, Recipient) {
+        let storage = CombinedReplyStorage::new(0, 100);
+        let key = SurbEncryptionKey::new(&mut OsRng);
+        storage.key_storage_ref().insert_multiple(vec![key]);
+
+        let recipient = recipient();
+        storage.tags_storage_ref().insert_new(&recipient, tag);
+        storage.surbs_storage_ref().insert_surbs(&tag, surbs);
+        (storage, key, recipient)
+    }
+
+    fn surb_digests(storage: &CombinedReplyStorage, tag: &AnonymousSenderTag) -> Vec<SurbDigest> {
+        storage
+            .surbs_storage_ref()
+            .as_raw_iter()
+            .filter(|entry| entry.key() == tag)
+            .flat_map(|entry| {
+                entry
+                    .surbs_ref()
+                    .iter()
+                    .map(surb_digest)
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn delta_survives_serialization_and_encryption_roundtrip() {
+        let tag = AnonymousSenderTag::new_random(&mut OsRng);
+        let surbs = vec![reply_surb(), reply_surb()];
+        let expected_surbs = surbs.iter().map(surb_digest).collect::<Vec<_>>();
+        let (storage, key, recipient) = populated_storage(tag, surbs);
+        storage
+            .key_storage_ref()
+            .mark_used(EncryptionKeyDigest::clone_from_slice(&[1; 32]), 42);
+
+        let delta = storage.export_sync_delta(0, SurbHandover::All);
+        assert_eq!(delta.reply_surbs_count(), 2);
+        assert!(!delta.is_empty());
+
+        let decoded = ReplyStorageDelta::try_from_bytes(&delta.to_bytes()).unwrap();
+        assert_eq!(decoded.to_bytes(), delta.to_bytes());
+
+        let identity = identity::KeyPair::new(&mut OsRng);
+        let sync_key = ReplyStorageSyncKey::derive_from_identity(identity.private_key());
+        let encrypted = sync_key.encrypt(&delta).unwrap();
+        let decrypted = sync_key.decrypt(&encrypted).unwrap();
+        assert_eq!(decrypted.id(), delta.id());
+
+        let other_device = CombinedReplyStorage::new(0, 100);
+        let summary = other_device.merge_sync_delta(decrypted);
+        assert_eq!(summary.new_reply_keys, 1);
+        assert_eq!(summary.used_reply_keys, 1);
+        assert_eq!(summary.new_reply_surbs, 2);
+        assert_eq!(summary.duplicate_reply_surbs, 0);
+
+        assert!(other_device
+            .key_storage_ref()
+            .try_pop(key.compute_digest())
+            .is_some());
+        assert_eq!(
+            other_device.tags_storage_ref().try_get_existing(&recipient),
+            Some(tag)
+        );
+        assert_eq!(surb_digests(&other_device, &tag), expected_surbs);
+    }
+
+    #[test]
+    fn tampered_or_foreign_deltas_are_rejected() {
+        let tag = AnonymousSenderTag::new_random(&mut OsRng);
+        let (storage, _, _) = populated_storage(tag, vec![reply_surb()]);
+        let delta = storage.export_sync_delta(0, SurbHandover::None);
+
+        let identity = identity::KeyPair::new(&mut OsRng);
+        let other_identity = identity::KeyPair::new(&mut OsRng);
+        let sync_key = ReplyStorageSyncKey::derive_from_identity(identity.private_key());
+        let other_key = ReplyStorageSyncKey::derive_from_identity(other_identity.private_key());
+
+        let mut encrypted = sync_key.encrypt(&delta).unwrap();
+        assert!(matches!(
+            other_key.decrypt(&encrypted),
+            Err(ReplySyncError::DecryptionFailure)
+        ));
+
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 1;
+        assert!(matches!(
+            sync_key.decrypt(&encrypted),
+            Err(ReplySyncError::DecryptionFailure)
+        ));
+        assert!(matches!(
+            sync_key.decrypt(&encrypted[..4]),
+            Err(ReplySyncError::DecryptionFailure)
+        ));
+
+        let mut bytes = delta.to_bytes();
+        bytes.push(0);
+        assert!(matches!(
+            ReplyStorageDelta::try_from_bytes(&bytes),
+            Err(ReplySyncError::MalformedDelta { .. })
+        ));
+        bytes[0] = DELTA_FORMAT_VERSION + 1;
+        assert!(matches!(
+            ReplyStorageDelta::try_from_bytes(&bytes),
+            Err(ReplySyncError::UnsupportedVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn handed_over_surbs_are_moved_and_never_duplicated() {
+        let tag = AnonymousSenderTag::new_random(&mut OsRng);
+        let (storage, _, _) = populated_storage(tag, (0..4).map(|_| reply_surb()).collect());
+
+        let delta = storage.export_sync_delta(0, SurbHandover::Half);
+        assert_eq!(delta.reply_surbs_count(), 2);
+        assert_eq!(storage.surbs_storage_ref().available_surbs(&tag), 2);
+
+        // merging our own surbs back is idempotent
+        let bytes = delta.to_bytes();
+        let summary = storage.merge_sync_delta(delta);
+        assert_eq!(summary.new_reply_surbs, 2);
+        let summary = storage.merge_sync_delta(ReplyStorageDelta::try_from_bytes(&bytes).unwrap());
+        assert_eq!(summary.new_reply_surbs, 0);
+        assert_eq!(summary.duplicate_reply_surbs, 2);
+        assert_eq!(storage.surbs_storage_ref().available_surbs(&tag), 4);
+    }
+
+    #[test]
+    fn conflicting_keys_and_tags_are_resolved_deterministically() {
+        let device_a = CombinedReplyStorage::new(0, 100);
+        let device_b = CombinedReplyStorage::new(0, 100);
+        let key = SurbEncryptionKey::new(&mut OsRng);
+        let digest = key.compute_digest();
+
+        // the earlier timestamp wins
+        assert!(device_a
+            .key_storage_ref()
+            .merge(UsedReplyKey::new(key, 200)));
+        assert!(!device_a
+            .key_storage_ref()
+            .merge(UsedReplyKey::new(key, 100)));
+        let popped = device_a.key_storage_ref().try_pop(digest).unwrap();
+        assert_eq!(popped.sent_at_timestamp, 100);
+
+        // keys used by either device are never re-inserted
+        assert!(!device_a
+            .key_storage_ref()
+            .merge(UsedReplyKey::new(key, 300)));
+        device_b
+            .key_storage_ref()
+            .merge(UsedReplyKey::new(key, 100));
+        device_b.merge_sync_delta(device_a.export_sync_delta(0, SurbHandover::None));
+        assert!(device_b.key_storage_ref().try_pop(digest).is_none());
+
+        // both devices converge on the same sender tag
+        let recipient = recipient();
+        let tag_a = AnonymousSenderTag::new_random(&mut OsRng);
+        let tag_b = AnonymousSenderTag::new_random(&mut OsRng);
+        let lower = if tag_a.to_bytes() < tag_b.to_bytes() {
+            tag_a
+        } else {
+            tag_b
+        };
+        device_a.tags_storage_ref().insert_new(&recipient, tag_a);
+        device_b.tags_storage_ref().insert_new(&recipient, tag_b);
+
+        let from_a = device_a.export_sync_delta(0, SurbHandover::None);
+        let from_b = device_b.export_sync_delta(0, SurbHandover::None);
+        device_a.merge_sync_delta(from_b);
+        device_b.merge_sync_delta(from_a);
+        assert_eq!(
+            device_a.tags_storage_ref().try_get_existing(&recipient),
+            Some(lower)
+        );
+        assert_eq!(
+            device_b.tags_storage_ref().try_get_existing(&recipient),
+            Some(lower)
+        );
+    }
+
+    #[test]
+    fn synchroniser_rejects_replayed_deltas() {
+        let identity = identity::KeyPair::new(&mut OsRng);
+        let tag = AnonymousSenderTag::new_random(&mut OsRng);
+        let (storage, _, _) = populated_storage(tag, vec![reply_surb()]);
+
+        let mut device_a = ReplyStorageSynchroniser::new(storage, identity.private_key());
+        let mut device_b = ReplyStorageSynchroniser::new(
+            CombinedReplyStorage::new(0, 100),
+            identity.private_key(),
+        );
+
+        let exported = device_a.export(SurbHandover::All).unwrap();
+        let summary = device_b.import(&exported).unwrap();
+        assert_eq!(summary.new_reply_keys, 1);
+        assert_eq!(summary.new_reply_surbs, 1);
+        assert!(matches!(
+            device_b.import(&exported),
+            Err(ReplySyncError::AlreadyApplied { .. })
+        ));
+
+        // our own deltas are never merged back, unless explicitly restored
+        assert!(matches!(
+            device_a.import(&exported),
+            Err(ReplySyncError::AlreadyApplied { .. })
+        ));
+        assert_eq!(device_a.restore(&exported).unwrap().new_reply_surbs, 1);
+    }
+}
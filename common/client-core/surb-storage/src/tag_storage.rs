@@ -1,14 +1,12 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use dashmap::iter::Iter;
 use dashmap::DashMap;
 use nym_sphinx::addressing::clients::{Recipient, RecipientBytes};
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use std::sync::Arc;
 
-#[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
-use dashmap::iter::Iter;
-
 #[derive(Debug, Clone)]
 pub struct UsedSenderTags {
     inner: Arc<UsedSenderTagsInner>,
@@ -43,7 +41,6 @@ impl UsedSenderTags {
         }
     }
 
-    #[cfg(all(not(target_arch = "wasm32"), feature = "fs-surb-storage"))]
     pub fn as_raw_iter(&self) -> Iter<'_, RecipientBytes, AnonymousSenderTag> {
         self.inner.data.iter()
     }
@@ -52,6 +49,21 @@ impl UsedSenderTags {
         self.inner.data.insert(recipient.to_bytes(), tag);
     }
 
+    /// Inserts the tag for the given recipient unless one already exists, in which case
+    /// the one with the lower byte representation is kept, so that all devices sharing
+    /// the same identity would eventually converge on the same tag.
+    pub fn merge_raw(&self, recipient: RecipientBytes, tag: AnonymousSenderTag) {
+        self.inner
+            .data
+            .entry(recipient)
+            .and_modify(|existing| {
+                if tag.to_bytes() < existing.to_bytes() {
+                    *existing = tag
+                }
+            })
+            .or_insert(tag);
+    }
+
     pub fn try_get_existing(&self, recipient: &Recipient) -> Option<AnonymousSenderTag> {
         self.inner
             .data
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use si_scale::helpers::bibytes2;
-use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use time::OffsetDateTime;
@@ -30,6 +30,9 @@ struct ClientBandwidthInner {
     /// the actual bandwidth amount (in bytes) available
     available: AtomicI64,
 
+    /// the total amount of bandwidth (in bytes) consumed, as implied by the decreases of the available amount
+    consumed: AtomicU64,
+
     /// flag to indicate whether this client is currently in the process of claiming additional bandwidth
     claiming_more: AtomicBool,
 
@@ -45,6 +48,7 @@ impl ClientBandwidth {
         ClientBandwidth {
             inner: Arc::new(ClientBandwidthInner {
                 available: AtomicI64::new(0),
+                consumed: AtomicU64::new(0),
                 claiming_more: AtomicBool::new(false),
                 last_logged_ts: AtomicI64::new(0),
                 last_updated_ts: AtomicI64::new(0),
@@ -71,6 +75,22 @@ impl ClientBandwidth {
         self.inner.available.load(Ordering::Acquire)
     }
 
+    pub(crate) fn consumed(&self) -> u64 {
+        self.inner.consumed.load(Ordering::Relaxed)
+    }
+
+    fn update(&self, remaining: i64, now: OffsetDateTime) {
+        let previous = self.inner.available.swap(remaining, Ordering::AcqRel);
+        if previous > remaining {
+            self.inner
+                .consumed
+                .fetch_add(previous.abs_diff(remaining), Ordering::Relaxed);
+        }
+        self.inner
+            .last_updated_ts
+            .store(now.unix_timestamp(), Ordering::Relaxed);
+    }
+
     pub(crate) fn maybe_log_bandwidth(&self, now: Option<OffsetDateTime>) {
         let last = self.last_logged();
         let now = now.unwrap_or_else(OffsetDateTime::now_utc);
@@ -98,19 +118,13 @@ impl ClientBandwidth {
 
     pub(crate) fn update_and_maybe_log(&self, remaining: i64) {
         let now = OffsetDateTime::now_utc();
-        self.inner.available.store(remaining, Ordering::Release);
-        self.inner
-            .last_updated_ts
-            .store(now.unix_timestamp(), Ordering::Relaxed);
+        self.update(remaining, now);
         self.maybe_log_bandwidth(Some(now))
     }
 
     pub(crate) fn update_and_log(&self, remaining: i64) {
         let now = OffsetDateTime::now_utc();
-        self.inner.available.store(remaining, Ordering::Release);
-        self.inner
-            .last_updated_ts
-            .store(now.unix_timestamp(), Ordering::Relaxed);
+        self.update(remaining, now);
         self.log_bandwidth(Some(now))
     }
 
@@ -4,24 +4,30 @@
 use crate::bandwidth::ClientBandwidth;
 use crate::client::config::GatewayClientConfig;
 use crate::error::GatewayClientError;
+use crate::notices::{try_forward_notice, GatewayNoticeReceiver, GatewayNoticeSender};
 use crate::packet_router::PacketRouter;
 pub use crate::packet_router::{
     AcknowledgementReceiver, AcknowledgementSender, MixnetMessageReceiver, MixnetMessageSender,
 };
+use crate::session::GatewaySessionStats;
 use crate::socket_state::{ws_fd, PartiallyDelegatedHandle, SocketState};
 use crate::traits::GatewayPacketRouter;
+use crate::transport::{GatewayConnector, WebSocketConnector};
 use crate::{cleanup_socket_message, try_decrypt_binary_message};
+use futures::channel::mpsc;
 use futures::{SinkExt, StreamExt};
-use nym_bandwidth_controller::{BandwidthController, BandwidthStatusMessage};
+use nym_bandwidth_controller::{
+    BandwidthController, BandwidthStatusMessage, PreparedCredentialMetadata,
+};
 use nym_credential_storage::ephemeral_storage::EphemeralStorage as EphemeralCredentialStorage;
 use nym_credential_storage::storage::Storage as CredentialStorage;
 use nym_credentials::CredentialSpendingData;
 use nym_crypto::asymmetric::identity;
 use nym_gateway_requests::registration::handshake::client_handshake;
 use nym_gateway_requests::{
-    BinaryRequest, ClientControlRequest, ClientRequest, SensitiveServerResponse, ServerResponse,
-    SharedGatewayKey, SharedSymmetricKey, AES_GCM_SIV_PROTOCOL_VERSION,
-    CREDENTIAL_UPDATE_V2_PROTOCOL_VERSION, CURRENT_PROTOCOL_VERSION,
+    BinaryRequest, CipherSuite, ClientControlRequest, ClientRequest, FrameBufferPool,
+    ProtocolStats, SensitiveServerResponse, ServerResponse, SharedGatewayKey, SharedSymmetricKey,
+    AES_GCM_SIV_PROTOCOL_VERSION, CREDENTIAL_UPDATE_V2_PROTOCOL_VERSION, CURRENT_PROTOCOL_VERSION,
 };
 use nym_sphinx::forwarding::packet::MixPacket;
 use nym_task::TaskClient;
@@ -37,18 +43,17 @@ use url::Url;
 use std::os::fd::RawFd;
 #[cfg(not(target_arch = "wasm32"))]
 use tokio::time::sleep;
-#[cfg(not(target_arch = "wasm32"))]
-use tokio_tungstenite::connect_async;
 
 #[cfg(not(unix))]
 use std::os::raw::c_int as RawFd;
 #[cfg(target_arch = "wasm32")]
-use wasm_utils::websocket::JSWebsocket;
-#[cfg(target_arch = "wasm32")]
 use wasmtimer::tokio::sleep;
 use zeroize::Zeroizing;
 
 pub mod config;
+mod throughput;
+
+pub use throughput::ThroughputTestResult;
 
 pub struct GatewayConfig {
     pub gateway_identity: identity::PublicKey,
@@ -88,53 +93,137 @@ pub struct GatewayClient<C, St = EphemeralCredentialStorage> {
     bandwidth: ClientBandwidth,
     gateway_address: String,
     gateway_identity: identity::PublicKey,
-    local_identity: Arc<identity::KeyPair>,
+    local_identity: Arc<dyn identity::IdentitySigner>,
     shared_key: Option<Arc<SharedGatewayKey>>,
     connection: SocketState,
+    connector: Arc<dyn GatewayConnector>,
     packet_router: PacketRouter,
     bandwidth_controller: Option<BandwidthController<C, St>>,
 
     // currently unused (but populated)
     negotiated_protocol: Option<u8>,
 
+    // set once we have subscribed to the gateway notices
+    notice_sender: Option<GatewayNoticeSender>,
+
+    session_stats: GatewaySessionStats,
+
+    // buffers of the received frames, reused for the outgoing ones
+    frame_buffers: FrameBufferPool,
+
     /// Listen to shutdown messages and send notifications back to the task manager
     task_client: TaskClient,
 }
 
+// with the FIPS suite we must never end up using a legacy key
+fn ensure_approved_key(
+    cipher_suite: CipherSuite,
+    shared_key: &SharedGatewayKey,
+) -> Result<(), GatewayClientError> {
+    if cipher_suite.is_fips() && shared_key.is_legacy() {
+        return Err(GatewayClientError::NonApprovedSharedKey);
+    }
+    Ok(())
+}
+
+// the gateway must support the primitives we're using,
+// otherwise we'd end up with incompatible shared keys
+fn check_gateway_cipher_suite(
+    cipher_suite: CipherSuite,
+    gateway_suite: CipherSuite,
+) -> Result<(), GatewayClientError> {
+    if cipher_suite.is_supported_by(gateway_suite) {
+        return Ok(());
+    }
+    let err = GatewayClientError::IncompatibleCipherSuite {
+        gateway: gateway_suite,
+        current: cipher_suite,
+    };
+    error!("{err}");
+    Err(err)
+}
+
+// the suite is not persisted alongside the key, so make sure it's the one this binary uses
+fn with_cipher_suite(
+    mut shared_key: Arc<SharedGatewayKey>,
+    cipher_suite: CipherSuite,
+) -> Arc<SharedGatewayKey> {
+    // legacy keys don't have any associated suite
+    let Some(bytes) = shared_key.aes256_gcm_siv() else {
+        return shared_key;
+    };
+    if shared_key.cipher_suite() == Some(cipher_suite) {
+        return shared_key;
+    }
+    if let Some(key) = Arc::get_mut(&mut shared_key) {
+        key.set_cipher_suite(cipher_suite);
+        return shared_key;
+    }
+
+    // somebody else is holding the key, so we have to create a new instance
+    let key = SharedSymmetricKey::try_from_bytes(&bytes)
+        .expect("the bytes came from a valid key")
+        .with_cipher_suite(cipher_suite);
+    Arc::new(key.into())
+}
+
 impl<C, St> GatewayClient<C, St> {
     pub fn new(
         cfg: GatewayClientConfig,
         gateway_config: GatewayConfig,
-        local_identity: Arc<identity::KeyPair>,
+        local_identity: Arc<dyn identity::IdentitySigner>,
         // TODO: make it mandatory. if you don't want to pass it, use `new_init`
         shared_key: Option<Arc<SharedGatewayKey>>,
         packet_router: PacketRouter,
         bandwidth_controller: Option<BandwidthController<C, St>>,
         task_client: TaskClient,
     ) -> Self {
+        let bandwidth = ClientBandwidth::new_empty();
+        let shared_key = shared_key.map(|key| with_cipher_suite(key, CipherSuite::local()));
         GatewayClient {
             cfg,
             authenticated: false,
-            bandwidth: ClientBandwidth::new_empty(),
+            session_stats: GatewaySessionStats::new(bandwidth.clone()),
+            bandwidth,
             gateway_address: gateway_config.gateway_listener,
             gateway_identity: gateway_config.gateway_identity,
             local_identity,
             shared_key,
             connection: SocketState::NotConnected,
+            connector: Arc::new(WebSocketConnector),
             packet_router,
             bandwidth_controller,
             negotiated_protocol: None,
+            notice_sender: None,
+            frame_buffers: FrameBufferPool::default(),
             task_client,
         }
     }
 
+    /// Use the provided connector, rather than the default WebSocket one,
+    /// for establishing connections to the gateway.
+    #[must_use]
+    pub fn with_connector(mut self, connector: Arc<dyn GatewayConnector>) -> Self {
+        self.connector = connector;
+        self
+    }
+
+    /// Use the provided pool for the buffers of the exchanged frames.
+    /// It could be shared with the [`FramedTransport`](crate::transport::FramedTransport)s
+    /// created by a custom connector so that the buffers could also be recycled by the transport itself.
+    #[must_use]
+    pub fn with_frame_buffer_pool(mut self, frame_buffers: FrameBufferPool) -> Self {
+        self.frame_buffers = frame_buffers;
+        self
+    }
+
     pub fn gateway_identity(&self) -> identity::PublicKey {
         self.gateway_identity
     }
 
     pub fn ws_fd(&self) -> Option<RawFd> {
         match &self.connection {
-            SocketState::Available(conn) => ws_fd(conn.as_ref()),
+            SocketState::Available(conn) => ws_fd(conn),
             SocketState::PartiallyDelegated(conn) => conn.ws_fd(),
             _ => None,
         }
@@ -144,24 +233,15 @@ impl<C, St> GatewayClient<C, St> {
         self.bandwidth.remaining()
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
-    async fn _close_connection(&mut self) -> Result<(), GatewayClientError> {
-        match std::mem::replace(&mut self.connection, SocketState::NotConnected) {
-            SocketState::Available(mut socket) => Ok((*socket).close(None).await?),
-            SocketState::PartiallyDelegated(_) => {
-                unreachable!("this branch should have never been reached!")
-            }
-            _ => Ok(()), // no need to do anything in those cases
-        }
+    /// Returns the handle to the statistics of the session with the gateway,
+    /// which remains valid once the client is moved into its own task.
+    pub fn session_stats(&self) -> GatewaySessionStats {
+        self.session_stats.clone()
     }
 
-    #[cfg(target_arch = "wasm32")]
     async fn _close_connection(&mut self) -> Result<(), GatewayClientError> {
         match std::mem::replace(&mut self.connection, SocketState::NotConnected) {
-            SocketState::Available(socket) => {
-                (*socket).close(None, None).await?;
-                Ok(())
-            }
+            SocketState::Available(mut socket) => Ok(socket.close().await?),
             SocketState::PartiallyDelegated(_) => {
                 unreachable!("this branch should have never been reached!")
             }
@@ -177,36 +257,14 @@ impl<C, St> GatewayClient<C, St> {
         self._close_connection().await
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
     pub async fn establish_connection(&mut self) -> Result<(), GatewayClientError> {
         debug!(
             "Attemting to establish connection to gateway at: {}",
             self.gateway_address
         );
-        let ws_stream = match connect_async(&self.gateway_address).await {
-            Ok((ws_stream, _)) => ws_stream,
-            Err(error) => {
-                return Err(GatewayClientError::NetworkConnectionFailed {
-                    address: self.gateway_address.clone(),
-                    source: error,
-                })
-            }
-        };
+        let conn = self.connector.connect(&self.gateway_address).await?;
 
-        self.connection = SocketState::Available(Box::new(ws_stream));
-        Ok(())
-    }
-
-    #[cfg(target_arch = "wasm32")]
-    pub async fn establish_connection(&mut self) -> Result<(), GatewayClientError> {
-        let ws_stream = match JSWebsocket::new(&self.gateway_address) {
-            Ok(ws_stream) => ws_stream,
-            Err(e) => {
-                return Err(GatewayClientError::NetworkErrorWasm(e));
-            }
-        };
-
-        self.connection = SocketState::Available(Box::new(ws_stream));
+        self.connection = SocketState::Available(conn);
         Ok(())
     }
 
@@ -216,6 +274,7 @@ impl<C, St> GatewayClient<C, St> {
     async fn attempt_reconnection(&mut self) -> Result<(), GatewayClientError> {
         info!("Attempting gateway reconnection...");
         self.authenticated = false;
+        self.session_stats.disconnected();
 
         for i in 1..self.cfg.connection.reconnection_attempts {
             info!("reconnection attempt {}...", i);
@@ -278,8 +337,11 @@ impl<C, St> GatewayClient<C, St> {
                         Message::Binary(bin_msg) => {
                             // if we have established the shared key already, attempt to use it for decryption
                             // otherwise there's not much we can do apart from just routing what we have on hand
+                            self.session_stats.received_packets();
                             if let Some(shared_keys) = &self.shared_key {
-                                if let Some(plaintext) = try_decrypt_binary_message(bin_msg, shared_keys) {
+                                let maybe_plaintext = try_decrypt_binary_message(&bin_msg, shared_keys);
+                                self.frame_buffers.release(bin_msg);
+                                if let Some(plaintext) = maybe_plaintext {
                                     if let Err(err) = self.packet_router.route_received(vec![plaintext]) {
                                         log::warn!("Route received failed: {err}");
                                     }
@@ -289,7 +351,16 @@ impl<C, St> GatewayClient<C, St> {
                             }
                         }
                         Message::Text(txt_msg) => {
-                            break ServerResponse::try_from(txt_msg).map_err(|_| GatewayClientError::MalformedResponse);
+                            let Ok(response) = ServerResponse::try_from(txt_msg) else {
+                                break Err(GatewayClientError::MalformedResponse);
+                            };
+                            // the gateway might push a notice while we're waiting for the actual response
+                            if let (Some(notice_sender), Some(shared_key)) = (&self.notice_sender, &self.shared_key) {
+                                if try_forward_notice(&response, shared_key, notice_sender) {
+                                    continue;
+                                }
+                            }
+                            break Ok(response);
                         }
                         _ => (),
                     }
@@ -331,8 +402,7 @@ impl<C, St> GatewayClient<C, St> {
     ) -> Result<(), GatewayClientError> {
         match self.connection {
             SocketState::Available(ref mut conn) => {
-                let stream_messages: Vec<_> = messages.into_iter().map(Ok).collect();
-                let mut send_stream = futures::stream::iter(stream_messages);
+                let mut send_stream = futures::stream::iter(messages.into_iter().map(Ok));
                 Ok(conn.send_all(&mut send_stream).await?)
             }
             SocketState::PartiallyDelegated(ref mut partially_delegated) => {
@@ -433,6 +503,7 @@ impl<C, St> GatewayClient<C, St> {
                 self.gateway_identity,
                 self.cfg.bandwidth.require_tickets,
                 derive_aes256_gcm_siv_key,
+                CipherSuite::local(),
                 #[cfg(not(target_arch = "wasm32"))]
                 self.task_client.clone(),
             )
@@ -444,19 +515,27 @@ impl<C, St> GatewayClient<C, St> {
         let (authentication_status, gateway_protocol) = match self.read_control_response().await? {
             ServerResponse::Register {
                 protocol_version,
+                cipher_suite,
                 status,
-            } => (status, protocol_version),
-            ServerResponse::Error { message } => {
-                return Err(GatewayClientError::GatewayError(message))
+            } => {
+                check_gateway_cipher_suite(CipherSuite::local(), cipher_suite)?;
+                (status, protocol_version)
+            }
+            ServerResponse::Error { code, message } => {
+                return Err(GatewayClientError::from_gateway_response(code, message))
             }
             other => return Err(GatewayClientError::UnexpectedResponse { name: other.name() }),
         };
 
         self.check_gateway_protocol(gateway_protocol)?;
+        ensure_approved_key(CipherSuite::local(), &shared_key)?;
         self.authenticated = authentication_status;
 
         if self.authenticated {
+            self.session_stats
+                .set_cipher_suite(shared_key.cipher_suite());
             self.shared_key = Some(Arc::new(shared_key));
+            self.session_stats.connected();
         }
 
         // populate the negotiated protocol for future uses
@@ -486,6 +565,11 @@ impl<C, St> GatewayClient<C, St> {
             return Err(GatewayClientError::KeyAlreadyUpgraded);
         }
 
+        // the upgrade itself relies on the legacy, non-approved, primitives
+        if CipherSuite::local().is_fips() {
+            return Err(GatewayClientError::NonApprovedSharedKey);
+        }
+
         // make sure we have the only reference, so we could safely swap it
         if Arc::strong_count(shared_key) != 1 {
             return Err(GatewayClientError::KeyAlreadyInUse);
@@ -505,8 +589,8 @@ impl<C, St> GatewayClient<C, St> {
         info!("sending upgrade request and awaiting the acknowledgement back");
         let (ciphertext, nonce) = match self.send_websocket_message(upgrade_request).await? {
             ServerResponse::EncryptedResponse { ciphertext, nonce } => (ciphertext, nonce),
-            ServerResponse::Error { message } => {
-                return Err(GatewayClientError::GatewayError(message))
+            ServerResponse::Error { code, message } => {
+                return Err(GatewayClientError::from_gateway_response(code, message))
             }
             other => return Err(GatewayClientError::UnexpectedResponse { name: other.name() }),
         };
@@ -531,6 +615,203 @@ impl<C, St> GatewayClient<C, St> {
         Ok(zeroizing_updated_key)
     }
 
+    /// Instructs the gateway to remove all messages it has stored for us whilst we were offline.
+    /// If `deregister` is set, the gateway is also going to remove all of our registration data
+    /// (i.e. the shared keys and bandwidth), after which the connection is closed and this client
+    /// would have to register again before it could be used.
+    ///
+    /// Returns the number of stored messages that got removed.
+    pub async fn purge_remote_state(
+        &mut self,
+        deregister: bool,
+    ) -> Result<u64, GatewayClientError> {
+        if !self.connection.is_established() {
+            return Err(GatewayClientError::ConnectionNotEstablished);
+        }
+
+        if !self.authenticated {
+            return Err(GatewayClientError::NotAuthenticated);
+        }
+
+        let Some(shared_key) = self.shared_key.as_ref() else {
+            return Err(GatewayClientError::NoSharedKeyAvailable);
+        };
+        let shared_key = Arc::clone(shared_key);
+
+        let purge_request = ClientRequest::PurgeStoredData { deregister }.encrypt(&*shared_key)?;
+
+        let (ciphertext, nonce) = match self.send_websocket_message(purge_request).await? {
+            ServerResponse::EncryptedResponse { ciphertext, nonce } => (ciphertext, nonce),
+            ServerResponse::Error { code, message } => {
+                return Err(GatewayClientError::from_gateway_response(code, message))
+            }
+            ServerResponse::TypedError { error } => {
+                return Err(GatewayClientError::TypedGatewayError(error))
+            }
+            other => return Err(GatewayClientError::UnexpectedResponse { name: other.name() }),
+        };
+
+        let removed_messages =
+            match SensitiveServerResponse::decrypt(&ciphertext, &nonce, &*shared_key)? {
+                SensitiveServerResponse::StoredDataPurged {
+                    removed_messages,
+                    deregistered,
+                } => {
+                    if deregistered != deregister {
+                        return Err(GatewayClientError::MalformedResponse);
+                    }
+                    removed_messages
+                }
+                _ => return Err(GatewayClientError::MalformedResponse),
+            };
+        info!("the gateway has removed {removed_messages} stored messages");
+
+        if deregister {
+            info!("we have been deregistered from the gateway");
+            // the keys are no longer recognised by the gateway, so there's no point in keeping them around
+            self.authenticated = false;
+            self.shared_key = None;
+            self.session_stats.disconnected();
+            self.close_connection().await?;
+        }
+
+        Ok(removed_messages)
+    }
+
+    /// Shares the provided protocol statistics with the gateway and returns the ones it reported back.
+    /// Note that the gateway might choose not to share anything.
+    pub async fn exchange_protocol_stats(
+        &mut self,
+        stats: ProtocolStats,
+    ) -> Result<ProtocolStats, GatewayClientError> {
+        if !self.connection.is_established() {
+            return Err(GatewayClientError::ConnectionNotEstablished);
+        }
+
+        if !self.authenticated {
+            return Err(GatewayClientError::NotAuthenticated);
+        }
+
+        let Some(shared_key) = self.shared_key.as_ref() else {
+            return Err(GatewayClientError::NoSharedKeyAvailable);
+        };
+        let shared_key = Arc::clone(shared_key);
+
+        let stats_request = ClientRequest::ExchangeProtocolStats { stats }.encrypt(&*shared_key)?;
+
+        let (ciphertext, nonce) = match self.send_websocket_message(stats_request).await? {
+            ServerResponse::EncryptedResponse { ciphertext, nonce } => (ciphertext, nonce),
+            ServerResponse::Error { code, message } => {
+                return Err(GatewayClientError::from_gateway_response(code, message))
+            }
+            ServerResponse::TypedError { error } => {
+                return Err(GatewayClientError::TypedGatewayError(error))
+            }
+            other => return Err(GatewayClientError::UnexpectedResponse { name: other.name() }),
+        };
+
+        match SensitiveServerResponse::decrypt(&ciphertext, &nonce, &*shared_key)? {
+            SensitiveServerResponse::ProtocolStats { stats } => Ok(stats.sanitized()),
+            _ => Err(GatewayClientError::MalformedResponse),
+        }
+    }
+
+    /// Returns the number of messages the gateway still holds for us, i.e. the ones that have been
+    /// received whilst we were offline and haven't yet been retrieved nor removed due to its retention limits.
+    pub async fn stored_messages_count(&mut self) -> Result<u64, GatewayClientError> {
+        if !self.connection.is_established() {
+            return Err(GatewayClientError::ConnectionNotEstablished);
+        }
+
+        if !self.authenticated {
+            return Err(GatewayClientError::NotAuthenticated);
+        }
+
+        let Some(shared_key) = self.shared_key.as_ref() else {
+            return Err(GatewayClientError::NoSharedKeyAvailable);
+        };
+        let shared_key = Arc::clone(shared_key);
+
+        let count_request = ClientRequest::GetStoredMessagesCount {}.encrypt(&*shared_key)?;
+
+        let (ciphertext, nonce) = match self.send_websocket_message(count_request).await? {
+            ServerResponse::EncryptedResponse { ciphertext, nonce } => (ciphertext, nonce),
+            ServerResponse::Error { code, message } => {
+                return Err(GatewayClientError::from_gateway_response(code, message))
+            }
+            ServerResponse::TypedError { error } => {
+                return Err(GatewayClientError::TypedGatewayError(error))
+            }
+            other => return Err(GatewayClientError::UnexpectedResponse { name: other.name() }),
+        };
+
+        match SensitiveServerResponse::decrypt(&ciphertext, &nonce, &*shared_key)? {
+            SensitiveServerResponse::StoredMessagesCount { count } => Ok(count),
+            _ => Err(GatewayClientError::MalformedResponse),
+        }
+    }
+
+    async fn request_notices(&mut self) -> Result<(), GatewayClientError> {
+        if !self.connection.is_established() {
+            return Err(GatewayClientError::ConnectionNotEstablished);
+        }
+
+        if !self.authenticated {
+            return Err(GatewayClientError::NotAuthenticated);
+        }
+
+        let Some(shared_key) = self.shared_key.as_ref() else {
+            return Err(GatewayClientError::NoSharedKeyAvailable);
+        };
+        let shared_key = Arc::clone(shared_key);
+
+        let subscribe_request = ClientRequest::SubscribeNotices {}.encrypt(&*shared_key)?;
+
+        let (ciphertext, nonce) = match self.send_websocket_message(subscribe_request).await? {
+            ServerResponse::EncryptedResponse { ciphertext, nonce } => (ciphertext, nonce),
+            ServerResponse::Error { code, message } => {
+                return Err(GatewayClientError::from_gateway_response(code, message))
+            }
+            ServerResponse::TypedError { error } => {
+                return Err(GatewayClientError::TypedGatewayError(error))
+            }
+            other => return Err(GatewayClientError::UnexpectedResponse { name: other.name() }),
+        };
+
+        match SensitiveServerResponse::decrypt(&ciphertext, &nonce, &*shared_key)? {
+            SensitiveServerResponse::NoticesSubscribed { active } => {
+                if let Some(notice_sender) = &self.notice_sender {
+                    for notice in active {
+                        info!("gateway notice in effect: {notice}");
+                        // if the subscriber has gone away, there's nothing to do
+                        let _ = notice_sender.unbounded_send(notice);
+                    }
+                }
+                Ok(())
+            }
+            _ => Err(GatewayClientError::MalformedResponse),
+        }
+    }
+
+    /// Subscribes to the notices pushed by the gateway, such as the scheduled maintenance,
+    /// and returns the stream they're going to be delivered on, starting with the ones already in effect.
+    /// The subscription is renewed whenever the client reconnects to the gateway.
+    /// Note that gateways running older versions don't support the notices and will reject the request.
+    pub async fn subscribe_to_notices(
+        &mut self,
+    ) -> Result<GatewayNoticeReceiver, GatewayClientError> {
+        // the sender has to be in place before the request is sent,
+        // so that the restarted mixnet listener would already forward the notices
+        let (notice_sender, notice_receiver) = mpsc::unbounded();
+        let previous = self.notice_sender.replace(notice_sender);
+
+        if let Err(err) = self.request_notices().await {
+            self.notice_sender = previous;
+            return Err(err);
+        }
+        Ok(notice_receiver)
+    }
+
     async fn authenticate(&mut self) -> Result<(), GatewayClientError> {
         let Some(shared_key) = self.shared_key.as_ref() else {
             return Err(GatewayClientError::NoSharedKeyAvailable);
@@ -543,8 +824,7 @@ impl<C, St> GatewayClient<C, St> {
 
         let self_address = self
             .local_identity
-            .as_ref()
-            .public_key()
+            .identity_key()
             .derive_destination_address();
 
         let msg = ClientControlRequest::new_authenticate(
@@ -556,12 +836,19 @@ impl<C, St> GatewayClient<C, St> {
         match self.send_websocket_message(msg).await? {
             ServerResponse::Authenticate {
                 protocol_version,
+                cipher_suite,
                 status,
                 bandwidth_remaining,
             } => {
                 self.check_gateway_protocol(protocol_version)?;
+                check_gateway_cipher_suite(CipherSuite::local(), cipher_suite)?;
                 self.authenticated = status;
                 self.bandwidth.update_and_maybe_log(bandwidth_remaining);
+                if status {
+                    let cipher_suite = self.shared_key.as_ref().and_then(|key| key.cipher_suite());
+                    self.session_stats.set_cipher_suite(cipher_suite);
+                    self.session_stats.connected();
+                }
 
                 self.negotiated_protocol = protocol_version;
                 log::debug!("authenticated: {status}, bandwidth remaining: {bandwidth_remaining}");
@@ -571,7 +858,9 @@ impl<C, St> GatewayClient<C, St> {
                 ));
                 Ok(())
             }
-            ServerResponse::Error { message } => Err(GatewayClientError::GatewayError(message)),
+            ServerResponse::Error { code, message } => {
+                Err(GatewayClientError::from_gateway_response(code, message))
+            }
             other => Err(GatewayClientError::UnexpectedResponse { name: other.name() }),
         }
     }
@@ -586,6 +875,8 @@ impl<C, St> GatewayClient<C, St> {
     pub async fn perform_initial_authentication(
         &mut self,
     ) -> Result<AuthenticationResponse, GatewayClientError> {
+        let cipher_suite = CipherSuite::local();
+
         if !self.connection.is_established() {
             self.establish_connection().await?;
         }
@@ -593,6 +884,7 @@ impl<C, St> GatewayClient<C, St> {
         // 1. check gateway's protocol version
         let supports_aes_gcm_siv = match self.get_gateway_protocol().await {
             Ok(protocol) => protocol >= AES_GCM_SIV_PROTOCOL_VERSION,
+            Err(err @ GatewayClientError::IncompatibleCipherSuite { .. }) => return Err(err),
             Err(_) => {
                 // if we failed to send the request, it means the gateway is running the old binary,
                 // so it has reset our connection - we have to reconnect
@@ -602,9 +894,17 @@ impl<C, St> GatewayClient<C, St> {
         };
 
         if !supports_aes_gcm_siv {
+            if cipher_suite.is_fips() {
+                return Err(GatewayClientError::NonApprovedGateway);
+            }
             warn!("this gateway is on an old version that doesn't support AES256-GCM-SIV");
         }
 
+        // make sure we're not going to use any existing legacy keys
+        if let Some(shared_key) = &self.shared_key {
+            ensure_approved_key(cipher_suite, shared_key)?;
+        }
+
         if self.authenticated {
             debug!("Already authenticated");
             return if let Some(shared_key) = &self.shared_key {
@@ -657,8 +957,16 @@ impl<C, St> GatewayClient<C, St> {
             .send_websocket_message(ClientControlRequest::SupportedProtocol {})
             .await?
         {
-            ServerResponse::SupportedProtocol { version } => Ok(version),
-            ServerResponse::Error { message } => Err(GatewayClientError::GatewayError(message)),
+            ServerResponse::SupportedProtocol {
+                version,
+                cipher_suite,
+            } => {
+                check_gateway_cipher_suite(CipherSuite::local(), cipher_suite)?;
+                Ok(version)
+            }
+            ServerResponse::Error { code, message } => {
+                Err(GatewayClientError::from_gateway_response(code, message))
+            }
             other => Err(GatewayClientError::UnexpectedResponse { name: other.name() }),
         }
     }
@@ -673,7 +981,9 @@ impl<C, St> GatewayClient<C, St> {
         )?;
         let bandwidth_remaining = match self.send_websocket_message(msg).await? {
             ServerResponse::Bandwidth { available_total } => Ok(available_total),
-            ServerResponse::Error { message } => Err(GatewayClientError::GatewayError(message)),
+            ServerResponse::Error { code, message } => {
+                Err(GatewayClientError::from_gateway_response(code, message))
+            }
             ServerResponse::TypedError { error } => {
                 Err(GatewayClientError::TypedGatewayError(error))
             }
@@ -691,7 +1001,9 @@ impl<C, St> GatewayClient<C, St> {
         let msg = ClientControlRequest::ClaimFreeTestnetBandwidth;
         let bandwidth_remaining = match self.send_websocket_message(msg).await? {
             ServerResponse::Bandwidth { available_total } => Ok(available_total),
-            ServerResponse::Error { message } => Err(GatewayClientError::GatewayError(message)),
+            ServerResponse::Error { code, message } => {
+                Err(GatewayClientError::from_gateway_response(code, message))
+            }
             other => Err(GatewayClientError::UnexpectedResponse { name: other.name() }),
         }?;
 
@@ -713,6 +1025,7 @@ impl<C, St> GatewayClient<C, St> {
     {
         // TODO: make it configurable
         const TICKETS_TO_SPEND: u32 = 1;
+        const MAX_CREDENTIAL_ATTEMPTS: usize = 3;
 
         if !self.authenticated {
             return Err(GatewayClientError::NotAuthenticated);
@@ -746,30 +1059,75 @@ impl<C, St> GatewayClient<C, St> {
                 negotiated_protocol: Some(gateway_protocol),
             });
         }
-        let prepared_credential = self
-            .unchecked_bandwidth_controller()
-            .prepare_ecash_ticket(self.gateway_identity.to_bytes(), TICKETS_TO_SPEND)
-            .await?;
-
-        match self.claim_ecash_bandwidth(prepared_credential.data).await {
-            Ok(_) => Ok(()),
-            Err(err) => {
-                error!("failed to claim ecash bandwidth with the gateway...: {err}");
-                if err.is_ticket_replay() {
-                    warn!("this was due to our ticket being replayed! have you messed with the database file?")
-                } else {
-                    // TODO: tracing span
-                    info!("attempting to revert ticket withdrawal...");
-                    self.unchecked_bandwidth_controller()
-                        .attempt_revert_ticket_usage(prepared_credential.metadata)
-                        .await?;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+
+            let prepared_credential = self
+                .unchecked_bandwidth_controller()
+                .prepare_ecash_ticket(self.gateway_identity.to_bytes(), TICKETS_TO_SPEND)
+                .await?;
+            let metadata = prepared_credential.metadata;
+
+            let err = match self.claim_ecash_bandwidth(prepared_credential.data).await {
+                Ok(_) => {
+                    self.session_stats.credential_spent(TICKETS_TO_SPEND);
+                    return Ok(());
                 }
+                Err(err) => err,
+            };
 
-                Err(err)
+            error!("failed to claim ecash bandwidth with the gateway...: {err}");
+            if err.is_credential_rejection() {
+                // there's no point in ever attempting to use this ticketbook again,
+                // but we might still have other, valid, ones
+                self.quarantine_rejected_ticketbook(metadata, &err).await?;
+                if attempt < MAX_CREDENTIAL_ATTEMPTS {
+                    info!("retrying the bandwidth claim with a different ticketbook...");
+                    continue;
+                }
+            } else {
+                // TODO: tracing span
+                info!("attempting to revert ticket withdrawal...");
+                self.unchecked_bandwidth_controller()
+                    .attempt_revert_ticket_usage(metadata)
+                    .await?;
             }
+
+            return Err(err);
         }
     }
 
+    async fn quarantine_rejected_ticketbook(
+        &mut self,
+        metadata: PreparedCredentialMetadata,
+        rejection: &GatewayClientError,
+    ) -> Result<(), GatewayClientError>
+    where
+        St: CredentialStorage,
+        <St as CredentialStorage>::StorageError: Send + Sync + 'static,
+    {
+        if rejection.is_ticket_replay() {
+            warn!("this was due to our ticket being replayed! have you messed with the database file?")
+        }
+
+        let ticketbook_id = metadata.ticketbook_id;
+        let error_code = rejection.gateway_error_code().map(u16::from);
+        let reason = rejection.to_string();
+        warn!("quarantining ticketbook {ticketbook_id}");
+
+        self.unchecked_bandwidth_controller()
+            .quarantine_ticketbook(metadata, error_code, reason.clone())
+            .await?;
+        self.task_client
+            .send_status_msg(Box::new(BandwidthStatusMessage::CredentialRejected {
+                ticketbook_id,
+                error_code,
+                reason,
+            }));
+        Ok(())
+    }
+
     pub async fn batch_send_mix_packets(
         &mut self,
         packets: Vec<MixPacket>,
@@ -799,10 +1157,11 @@ impl<C, St> GatewayClient<C, St> {
         let messages: Result<Vec<_>, _> = packets
             .into_iter()
             .map(|mix_packet| {
-                BinaryRequest::ForwardSphinx { packet: mix_packet }.into_ws_message(
+                BinaryRequest::ForwardSphinx { packet: mix_packet }.into_pooled_ws_message(
                     self.shared_key
                         .as_ref()
                         .expect("no shared key present even though we're authenticated!"),
+                    &self.frame_buffers,
                 )
             })
             .collect();
@@ -872,10 +1231,12 @@ impl<C, St> GatewayClient<C, St> {
         }
         // note: into_ws_message encrypts the requests and adds a MAC on it. Perhaps it should
         // be more explicit in the naming?
-        let msg = BinaryRequest::ForwardSphinx { packet: mix_packet }.into_ws_message(
+        // the frame is serialised and encrypted in place within a single buffer taken from the pool
+        let msg = BinaryRequest::ForwardSphinx { packet: mix_packet }.into_pooled_ws_message(
             self.shared_key
                 .as_ref()
                 .expect("no shared key present even though we're authenticated!"),
+            &self.frame_buffers,
         )?;
         self.send_with_reconnection_on_failure(msg).await
     }
@@ -893,7 +1254,7 @@ impl<C, St> GatewayClient<C, St> {
             _ => unreachable!(),
         };
 
-        self.connection = SocketState::Available(Box::new(conn));
+        self.connection = SocketState::Available(conn);
         Ok(())
     }
 
@@ -913,7 +1274,7 @@ impl<C, St> GatewayClient<C, St> {
             match std::mem::replace(&mut self.connection, SocketState::Invalid) {
                 SocketState::Available(conn) => {
                     PartiallyDelegatedHandle::split_and_listen_for_mixnet_messages(
-                        *conn,
+                        conn,
                         self.packet_router.clone(),
                         Arc::clone(
                             self.shared_key
@@ -921,6 +1282,9 @@ impl<C, St> GatewayClient<C, St> {
                                 .expect("no shared key present even though we're authenticated!"),
                         ),
                         self.bandwidth.clone(),
+                        self.session_stats.clone(),
+                        self.notice_sender.clone(),
+                        self.frame_buffers.clone(),
                         self.task_client.clone(),
                     )
                 }
@@ -939,15 +1303,36 @@ impl<C, St> GatewayClient<C, St> {
         // if we're reconnecting, because we lost connection, we need to re-authenticate the connection
         self.authenticate().await?;
 
+        // the subscription to the notices doesn't survive the connection
+        if self.notice_sender.is_some() {
+            if let Err(err) = self.request_notices().await {
+                warn!("failed to renew the subscription to the gateway notices: {err}");
+            }
+        }
+
         // this call is NON-blocking
         self.start_listening_for_mixnet_messages()?;
+        self.session_stats.reconnected();
 
         Ok(())
     }
 
+    /// Drops the current connection, regardless of its state, and establishes a fresh one.
+    /// It's meant to be used whenever the local network has changed (for example the device switched
+    /// to a different Wi-Fi) as the existing socket is most likely bound to an address that's no longer
+    /// usable and it would otherwise take a long time before the failure got detected.
+    pub async fn reconnect_after_network_change(&mut self) -> Result<(), GatewayClientError> {
+        if let Err(err) = self.recover_socket_connection().await {
+            debug!("could not recover the existing gateway socket: {err}");
+        }
+        self.connection = SocketState::NotConnected;
+        self.attempt_reconnection().await
+    }
+
     pub async fn disconnect(&mut self) -> Result<(), GatewayClientError> {
         self.recover_socket_connection().await?;
         self.connection = SocketState::NotConnected;
+        self.session_stats.disconnected();
         Ok(())
     }
 
@@ -1002,10 +1387,9 @@ impl GatewayClient<InitOnly, EphemeralCredentialStorage> {
     pub fn new_init(
         gateway_listener: Url,
         gateway_identity: identity::PublicKey,
-        local_identity: Arc<identity::KeyPair>,
+        local_identity: Arc<dyn identity::IdentitySigner>,
     ) -> Self {
         log::trace!("Initialising gateway client");
-        use futures::channel::mpsc;
 
         // note: this packet_router is completely invalid in normal circumstances, but "works"
         // perfectly fine here, because it's not meant to be used
@@ -1014,18 +1398,23 @@ impl GatewayClient<InitOnly, EphemeralCredentialStorage> {
         let task_client = TaskClient::dummy();
         let packet_router = PacketRouter::new(ack_tx, mix_tx, task_client.clone());
 
+        let bandwidth = ClientBandwidth::new_empty();
         GatewayClient {
             cfg: GatewayClientConfig::default().with_disabled_credentials_mode(true),
             authenticated: false,
-            bandwidth: ClientBandwidth::new_empty(),
+            session_stats: GatewaySessionStats::new(bandwidth.clone()),
+            bandwidth,
             gateway_address: gateway_listener.to_string(),
             gateway_identity,
             local_identity,
             shared_key: None,
             connection: SocketState::NotConnected,
+            connector: Arc::new(WebSocketConnector),
             packet_router,
             bandwidth_controller: None,
             negotiated_protocol: None,
+            notice_sender: None,
+            frame_buffers: FrameBufferPool::default(),
             task_client,
         }
     }
@@ -1051,10 +1440,189 @@ impl GatewayClient<InitOnly, EphemeralCredentialStorage> {
             local_identity: self.local_identity,
             shared_key: self.shared_key,
             connection: self.connection,
+            connector: self.connector,
             packet_router,
             bandwidth_controller,
             negotiated_protocol: self.negotiated_protocol,
+            notice_sender: self.notice_sender,
+            session_stats: self.session_stats,
+            frame_buffers: self.frame_buffers,
             task_client,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_gateway_requests::LegacySharedKeys;
+
+    #[test]
+    fn gateways_without_support_for_our_cipher_suite_are_rejected() {
+        let standard = CipherSuite::Standard;
+        let fips = CipherSuite::Fips;
+        assert!(check_gateway_cipher_suite(standard, standard).is_ok());
+        assert!(check_gateway_cipher_suite(fips, fips).is_ok());
+        assert!(matches!(
+            check_gateway_cipher_suite(fips, standard),
+            Err(GatewayClientError::IncompatibleCipherSuite { gateway, current })
+                if gateway == standard && current == fips
+        ));
+        assert!(matches!(
+            check_gateway_cipher_suite(standard, fips),
+            Err(GatewayClientError::IncompatibleCipherSuite { gateway, current })
+                if gateway == fips && current == standard
+        ));
+    }
+
+    #[test]
+    fn loaded_keys_are_assigned_the_local_cipher_suite() {
+        let key = || -> SharedGatewayKey {
+            SharedSymmetricKey::try_from_bytes(&[42u8; 32])
+                .unwrap()
+                .into()
+        };
+
+        let unique = with_cipher_suite(Arc::new(key()), CipherSuite::Fips);
+        assert_eq!(unique.cipher_suite(), Some(CipherSuite::Fips));
+
+        let shared = Arc::new(key());
+        let _other_reference = Arc::clone(&shared);
+        let updated = with_cipher_suite(shared, CipherSuite::Fips);
+        assert_eq!(updated.cipher_suite(), Some(CipherSuite::Fips));
+        assert_eq!(updated.aes256_gcm_siv(), key().aes256_gcm_siv());
+
+        let legacy: SharedGatewayKey = LegacySharedKeys::try_from_bytes(&[42u8; 32])
+            .unwrap()
+            .into();
+        let legacy = with_cipher_suite(Arc::new(legacy), CipherSuite::Fips);
+        assert!(legacy.cipher_suite().is_none());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    mod purging_remote_state {
+        use super::*;
+        use crate::transport::FramedTcpConnector;
+        use nym_gateway_requests::transport::FramedTransport;
+        use tokio::net::TcpListener;
+        use tokio_util::compat::TokioAsyncReadCompatExt;
+
+        fn shared_key() -> Arc<SharedGatewayKey> {
+            Arc::new(
+                SharedSymmetricKey::try_from_bytes(&[42u8; 32])
+                    .unwrap()
+                    .into(),
+            )
+        }
+
+        fn test_client(port: u16) -> InitGatewayClient {
+            let identity =
+                identity::KeyPair::from(identity::PrivateKey::from_bytes(&[1u8; 32]).unwrap());
+            let gateway_address = format!("ws://127.0.0.1:{port}").parse().unwrap();
+            GatewayClient::new_init(gateway_address, *identity.public_key(), Arc::new(identity))
+                .with_connector(Arc::new(FramedTcpConnector::new(port)))
+        }
+
+        /// Accepts a single connection and replies to the purge request with the provided number
+        /// of removed messages, as the gateway would. Returns whether the client asked to be deregistered.
+        async fn fake_gateway(
+            listener: TcpListener,
+            key: Arc<SharedGatewayKey>,
+            removed_messages: u64,
+        ) -> bool {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut transport = FramedTransport::new(socket.compat());
+
+            let Some(Ok(Message::Text(text))) = transport.next().await else {
+                panic!("expected a text request")
+            };
+            let ClientControlRequest::EncryptedRequest { ciphertext, nonce } =
+                text.parse().unwrap()
+            else {
+                panic!("expected an encrypted request")
+            };
+            let ClientRequest::PurgeStoredData { deregister } =
+                ClientRequest::decrypt(&ciphertext, &nonce, &*key).unwrap()
+            else {
+                panic!("expected a purge request")
+            };
+
+            let response = SensitiveServerResponse::StoredDataPurged {
+                removed_messages,
+                deregistered: deregister,
+            }
+            .encrypt(&*key)
+            .unwrap();
+            transport.send(response.into()).await.unwrap();
+
+            // wait for the client to close the connection (or to drop it)
+            let _ = transport.next().await;
+            deregister
+        }
+
+        async fn authenticated_client(listener: &TcpListener) -> InitGatewayClient {
+            let port = listener.local_addr().unwrap().port();
+            let mut client = test_client(port);
+            client.establish_connection().await.unwrap();
+            client.authenticated = true;
+            client.shared_key = Some(shared_key());
+            client
+        }
+
+        #[tokio::test]
+        async fn purging_keeps_the_registration_unless_deregistering() {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let mut client = authenticated_client(&listener).await;
+            let gateway = tokio::spawn(fake_gateway(listener, shared_key(), 5));
+
+            assert_eq!(client.purge_remote_state(false).await.unwrap(), 5);
+            assert!(client.authenticated);
+            assert!(client.shared_key.is_some());
+            assert!(client.connection.is_established());
+
+            client.close_connection().await.unwrap();
+            assert!(!gateway.await.unwrap());
+        }
+
+        #[tokio::test]
+        async fn deregistering_forgets_the_shared_key() {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let mut client = authenticated_client(&listener).await;
+            let gateway = tokio::spawn(fake_gateway(listener, shared_key(), 3));
+
+            assert_eq!(client.purge_remote_state(true).await.unwrap(), 3);
+            assert!(gateway.await.unwrap());
+            assert!(!client.authenticated);
+            assert!(client.shared_key.is_none());
+            assert!(!client.connection.is_established());
+        }
+
+        #[tokio::test]
+        async fn purging_requires_authentication() {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let mut client = test_client(port);
+            assert!(matches!(
+                client.purge_remote_state(true).await,
+                Err(GatewayClientError::ConnectionNotEstablished)
+            ));
+
+            // the connection alone is not sufficient, so the request is never sent
+            client.establish_connection().await.unwrap();
+            client.shared_key = Some(shared_key());
+            assert!(matches!(
+                client.purge_remote_state(true).await,
+                Err(GatewayClientError::NotAuthenticated)
+            ));
+            assert!(client.shared_key.is_some());
+
+            let (socket, _) = listener.accept().await.unwrap();
+            client.close_connection().await.unwrap();
+            let mut transport = FramedTransport::new(socket.compat());
+            assert!(!matches!(
+                transport.next().await,
+                Some(Ok(Message::Text(_)))
+            ));
+        }
+    }
+}
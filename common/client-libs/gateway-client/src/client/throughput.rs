@@ -0,0 +1,270 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::GatewayClient;
+use crate::error::GatewayClientError;
+use crate::socket_state::SocketState;
+use crate::traits::GatewayPacketRouter;
+use crate::{cleanup_socket_message, try_decrypt_binary_message};
+use futures::{SinkExt, StreamExt};
+use nym_credential_storage::storage::Storage as CredentialStorage;
+use nym_gateway_requests::BinaryRequest;
+use nym_sphinx::forwarding::packet::MixPacket;
+use nym_sphinx::params::PacketSize;
+use nym_validator_client::nyxd::contract_traits::DkgQueryClient;
+use si_scale::helpers::bibytes2;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+use tracing::*;
+use tungstenite::Message;
+
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::{sleep, Instant};
+
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::{std::Instant, tokio::sleep};
+
+/// Results of the gateway throughput self-test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThroughputTestResult {
+    /// Number of packets pushed to the gateway.
+    pub packets_sent: usize,
+
+    /// Number of (non-ack) packets the gateway has pushed back to us before the test has finished.
+    pub packets_received: usize,
+
+    /// Total number of bytes written to the gateway socket.
+    pub bytes_sent: usize,
+
+    /// Total number of bytes of the received (non-ack) packets.
+    pub bytes_received: usize,
+
+    /// Time it took to write all the packets to the gateway socket.
+    pub upload_duration: Duration,
+
+    /// Time elapsed between the start of the test and receiving the last packet back.
+    pub round_trip_duration: Duration,
+}
+
+impl ThroughputTestResult {
+    fn rate(bytes: usize, duration: Duration) -> f64 {
+        if duration.is_zero() {
+            return 0.;
+        }
+        bytes as f64 / duration.as_secs_f64()
+    }
+
+    /// Upload goodput expressed in bytes per second.
+    pub fn upload_goodput(&self) -> f64 {
+        Self::rate(self.bytes_sent, self.upload_duration)
+    }
+
+    /// Download goodput expressed in bytes per second.
+    pub fn download_goodput(&self) -> f64 {
+        Self::rate(self.bytes_received, self.round_trip_duration)
+    }
+
+    /// Ratio of packets that haven't been received back.
+    pub fn packet_loss(&self) -> f64 {
+        if self.packets_sent == 0 {
+            return 0.;
+        }
+        1. - (self.packets_received as f64 / self.packets_sent as f64)
+    }
+}
+
+impl Display for ThroughputTestResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "sent {} packets ({}) at {}/s, received {} packets ({}) at {}/s. packet loss: {:.2}%",
+            self.packets_sent,
+            bibytes2(self.bytes_sent as f64),
+            bibytes2(self.upload_goodput()),
+            self.packets_received,
+            bibytes2(self.bytes_received as f64),
+            bibytes2(self.download_goodput()),
+            self.packet_loss() * 100.
+        )
+    }
+}
+
+// the gateway strips the SURB-ACKs from the final hop payloads and forwards them separately,
+// so they could (and should) be trivially distinguished by their size
+fn is_ack(received: &[u8]) -> bool {
+    received.len() == PacketSize::AckPacket.plaintext_size()
+        || received.len() <= PacketSize::OutfoxAckPacket.plaintext_size()
+}
+
+impl<C, St> GatewayClient<C, St> {
+    /// Measures the throughput of the connection to the gateway by pushing the provided packets
+    /// and waiting for them to be sent back to us.
+    /// The packets are expected to be addressed to this client and not to go through any mixnodes,
+    /// for example they could be created with `nym_sphinx::cover::generate_gateway_loop_cover_packet`.
+    ///
+    /// Note that it requires full control over the socket, so if the client is currently
+    /// listening for mixnet messages, the listener is going to be temporarily paused.
+    /// Any messages received during the test are still routed to the underlying packet router.
+    pub async fn run_throughput_test(
+        &mut self,
+        packets: Vec<MixPacket>,
+    ) -> Result<ThroughputTestResult, GatewayClientError>
+    where
+        C: DkgQueryClient + Send + Sync,
+        St: CredentialStorage,
+        <St as CredentialStorage>::StorageError: Send + Sync + 'static,
+    {
+        if !self.authenticated {
+            return Err(GatewayClientError::NotAuthenticated);
+        }
+        let bandwidth_remaining = self.bandwidth.remaining();
+        if bandwidth_remaining < self.cfg.bandwidth.remaining_bandwidth_threshold {
+            self.cfg
+                .bandwidth
+                .ensure_above_cutoff(bandwidth_remaining)?;
+            self.claim_bandwidth().await?;
+        }
+
+        let should_restart_mixnet_listener = if self.connection.is_partially_delegated() {
+            self.recover_socket_connection().await?;
+            true
+        } else {
+            false
+        };
+
+        let result = self.measure_throughput(packets).await;
+
+        if should_restart_mixnet_listener {
+            self.start_listening_for_mixnet_messages()?;
+        }
+        result
+    }
+
+    async fn measure_throughput(
+        &mut self,
+        packets: Vec<MixPacket>,
+    ) -> Result<ThroughputTestResult, GatewayClientError> {
+        let shared_key = self
+            .shared_key
+            .as_ref()
+            .ok_or(GatewayClientError::NoSharedKeyAvailable)?;
+
+        let packets_sent = packets.len();
+        let messages = packets
+            .into_iter()
+            .map(|packet| BinaryRequest::ForwardSphinx { packet }.into_ws_message(shared_key))
+            .collect::<Result<Vec<_>, _>>()?;
+        let bytes_sent = messages.iter().map(|msg| msg.len()).sum();
+
+        let conn = match self.connection {
+            SocketState::Available(ref mut conn) => conn,
+            SocketState::NotConnected => return Err(GatewayClientError::ConnectionNotEstablished),
+            _ => return Err(GatewayClientError::ConnectionInInvalidState),
+        };
+
+        debug!("starting the throughput test with {packets_sent} packets");
+        let start = Instant::now();
+        let mut send_stream = futures::stream::iter(messages.into_iter().map(Ok));
+        conn.send_all(&mut send_stream).await?;
+        let upload_duration = start.elapsed();
+
+        let mut packets_received = 0;
+        let mut bytes_received = 0;
+        let mut round_trip_duration = upload_duration;
+
+        let timeout = sleep(self.cfg.connection.response_timeout_duration);
+        tokio::pin!(timeout);
+
+        while packets_received < packets_sent {
+            tokio::select! {
+                _ = self.task_client.recv() => {
+                    return Err(GatewayClientError::ConnectionClosedGatewayShutdown);
+                }
+                _ = &mut timeout => {
+                    warn!("timed out while waiting for the throughput test packets. received {packets_received}/{packets_sent}");
+                    break;
+                }
+                msg = conn.next() => {
+                    let Message::Binary(bin_msg) = cleanup_socket_message(msg)? else {
+                        continue
+                    };
+                    let Some(plaintext) = try_decrypt_binary_message(&bin_msg, shared_key) else {
+                        continue
+                    };
+                    if !is_ack(&plaintext) {
+                        packets_received += 1;
+                        bytes_received += plaintext.len();
+                        round_trip_duration = start.elapsed();
+                    }
+                    if let Err(err) = self.packet_router.route_received(vec![plaintext]) {
+                        warn!("Route received failed: {err}");
+                    }
+                }
+            }
+        }
+
+        Ok(ThroughputTestResult {
+            packets_sent,
+            packets_received,
+            bytes_sent,
+            bytes_received,
+            upload_duration,
+            round_trip_duration,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(packets_received: usize, round_trip: Duration) -> ThroughputTestResult {
+        ThroughputTestResult {
+            packets_sent: 4,
+            packets_received,
+            bytes_sent: 4000,
+            bytes_received: packets_received * 1000,
+            upload_duration: Duration::from_secs(2),
+            round_trip_duration: round_trip,
+        }
+    }
+
+    #[test]
+    fn goodput_and_loss_are_derived_from_the_measurements() {
+        let res = result(3, Duration::from_secs(3));
+        assert_eq!(res.upload_goodput(), 2000.);
+        assert_eq!(res.download_goodput(), 1000.);
+        assert_eq!(res.packet_loss(), 0.25);
+
+        let res = result(4, Duration::from_secs(4));
+        assert_eq!(res.packet_loss(), 0.);
+    }
+
+    #[test]
+    fn empty_measurements_do_not_divide_by_zero() {
+        let res = ThroughputTestResult {
+            packets_sent: 0,
+            packets_received: 0,
+            bytes_sent: 0,
+            bytes_received: 0,
+            upload_duration: Duration::ZERO,
+            round_trip_duration: Duration::ZERO,
+        };
+        assert_eq!(res.upload_goodput(), 0.);
+        assert_eq!(res.download_goodput(), 0.);
+        assert_eq!(res.packet_loss(), 0.);
+    }
+
+    #[test]
+    fn acks_are_told_apart_from_returned_packets() {
+        assert!(is_ack(&vec![0; PacketSize::AckPacket.plaintext_size()]));
+        assert!(is_ack(&vec![
+            0;
+            PacketSize::OutfoxAckPacket.plaintext_size()
+        ]));
+        assert!(!is_ack(&vec![
+            0;
+            PacketSize::RegularPacket.plaintext_size()
+        ]));
+    }
+}
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use nym_gateway_requests::registration::handshake::error::HandshakeError;
-use nym_gateway_requests::{GatewayRequestsError, SimpleGatewayRequestsError};
+use nym_gateway_requests::{
+    CipherSuite, GatewayErrorCode, GatewayRequestsError, SimpleGatewayRequestsError,
+};
 use std::io;
 use thiserror::Error;
 use tungstenite::Error as WsError;
@@ -15,8 +17,23 @@ pub enum GatewayClientError {
     #[error("Connection to the gateway is not established")]
     ConnectionNotEstablished,
 
-    #[error("gateway returned an error response: {0}")]
-    GatewayError(String),
+    #[error("gateway returned an error response ({code}): {message}")]
+    GatewayError {
+        code: GatewayErrorCode,
+        message: String,
+    },
+
+    #[error("the gateway has rejected our request due to insufficient bandwidth: {message}")]
+    GatewayOutOfBandwidth { message: String },
+
+    #[error("the gateway has rejected our sphinx packet as malformed: {message}")]
+    GatewayRejectedMalformedPacket { message: String },
+
+    #[error("the gateway has rejected our request as malformed: {message}")]
+    GatewayRejectedMalformedRequest { message: String },
+
+    #[error("the gateway has rejected our bandwidth credential: {message}")]
+    GatewayRejectedCredential { message: String },
 
     #[error("gateway returned an error response: {0}")]
     TypedGatewayError(SimpleGatewayRequestsError),
@@ -36,6 +53,18 @@ pub enum GatewayClientError {
     #[error("can't perform key upgrade as the key is already being used elsewhere")]
     KeyAlreadyInUse,
 
+    #[error("refusing to use the legacy (AES128-CTR + blake3 HMAC) shared key with the FIPS cipher suite")]
+    NonApprovedSharedKey,
+
+    #[error("the gateway does not support the approved (AES256-GCM) shared keys required by the FIPS cipher suite")]
+    NonApprovedGateway,
+
+    #[error("the gateway does not support the {current} cipher suite used by this client (it advertised the {gateway} one)")]
+    IncompatibleCipherSuite {
+        gateway: CipherSuite,
+        current: CipherSuite,
+    },
+
     #[cfg(target_arch = "wasm32")]
     #[error("There was a network error: {0}")]
     NetworkErrorWasm(#[from] JsError),
@@ -121,6 +150,50 @@ pub enum GatewayClientError {
 }
 
 impl GatewayClientError {
+    /// Converts the generic error response received from the gateway into the appropriate typed error.
+    pub fn from_gateway_response(code: Option<GatewayErrorCode>, message: String) -> Self {
+        match code.unwrap_or(GatewayErrorCode::Unspecified) {
+            GatewayErrorCode::OutOfBandwidth => {
+                GatewayClientError::GatewayOutOfBandwidth { message }
+            }
+            GatewayErrorCode::MalformedPacket => {
+                GatewayClientError::GatewayRejectedMalformedPacket { message }
+            }
+            GatewayErrorCode::MalformedRequest => {
+                GatewayClientError::GatewayRejectedMalformedRequest { message }
+            }
+            GatewayErrorCode::InvalidCredential => {
+                GatewayClientError::GatewayRejectedCredential { message }
+            }
+            code => GatewayClientError::GatewayError { code, message },
+        }
+    }
+
+    /// Returns the machine-readable code of the error reported by the gateway (if applicable).
+    pub fn gateway_error_code(&self) -> Option<GatewayErrorCode> {
+        match self {
+            GatewayClientError::GatewayError { code, .. } => Some(*code),
+            GatewayClientError::GatewayOutOfBandwidth { .. } => {
+                Some(GatewayErrorCode::OutOfBandwidth)
+            }
+            GatewayClientError::GatewayRejectedMalformedPacket { .. } => {
+                Some(GatewayErrorCode::MalformedPacket)
+            }
+            GatewayClientError::GatewayRejectedMalformedRequest { .. } => {
+                Some(GatewayErrorCode::MalformedRequest)
+            }
+            GatewayClientError::GatewayRejectedCredential { .. } => {
+                Some(GatewayErrorCode::InvalidCredential)
+            }
+            GatewayClientError::TypedGatewayError(err) => Some(err.error_code()),
+            _ => None,
+        }
+    }
+
+    pub fn is_out_of_bandwidth(&self) -> bool {
+        self.gateway_error_code() == Some(GatewayErrorCode::OutOfBandwidth)
+    }
+
     pub fn is_closed_connection(&self) -> bool {
         match self {
             GatewayClientError::NetworkError(ws_err) => match ws_err {
@@ -138,9 +211,15 @@ impl GatewayClientError {
     }
 
     pub fn is_ticket_replay(&self) -> bool {
-        match self {
-            GatewayClientError::TypedGatewayError(err) => err.is_ticket_replay(),
-            _ => false,
-        }
+        self.gateway_error_code() == Some(GatewayErrorCode::TicketReplay)
+    }
+
+    /// Checks whether the gateway has refused to accept the provided credential itself
+    /// (as opposed to, for example, failing due to a network issue).
+    pub fn is_credential_rejection(&self) -> bool {
+        matches!(
+            self.gateway_error_code(),
+            Some(GatewayErrorCode::InvalidCredential) | Some(GatewayErrorCode::TicketReplay)
+        )
     }
 }
@@ -6,7 +6,8 @@ use nym_gateway_requests::BinaryResponse;
 use tracing::{error, warn};
 use tungstenite::{protocol::Message, Error as WsError};
 
-pub use client::{config::GatewayClientConfig, GatewayClient, GatewayConfig};
+pub use client::{config::GatewayClientConfig, GatewayClient, GatewayConfig, ThroughputTestResult};
+pub use notices::{GatewayNoticeReceiver, GatewayNoticeSender};
 pub use nym_gateway_requests::shared_key::{
     LegacySharedKeys, SharedGatewayKey, SharedSymmetricKey,
 };
@@ -14,14 +15,25 @@ pub use packet_router::{
     AcknowledgementReceiver, AcknowledgementSender, MixnetMessageReceiver, MixnetMessageSender,
     PacketRouter,
 };
+pub use session::{GatewaySessionEvent, GatewaySessionStats};
 pub use traits::GatewayPacketRouter;
+pub use transport::{
+    BoxedGatewayConnection, FramedTransport, GatewayConnection, GatewayConnector,
+    WebSocketConnector,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use transport::FramedTcpConnector;
 
 mod bandwidth;
 pub mod client;
 pub mod error;
+mod notices;
 pub mod packet_router;
+mod session;
 pub mod socket_state;
 pub mod traits;
+pub mod transport;
 
 /// Helper method for reading from websocket stream. Helps to flatten the structure.
 pub(crate) fn cleanup_socket_message(
@@ -46,7 +58,7 @@ pub(crate) fn cleanup_socket_messages(
 }
 
 pub(crate) fn try_decrypt_binary_message(
-    bin_msg: Vec<u8>,
+    bin_msg: &[u8],
     shared_keys: &SharedGatewayKey,
 ) -> Option<Vec<u8>> {
     match BinaryResponse::try_from_encrypted_tagged_bytes(bin_msg, shared_keys) {
@@ -0,0 +1,34 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::channel::mpsc;
+use nym_gateway_requests::{
+    GatewayNotice, SensitiveServerResponse, ServerResponse, SharedGatewayKey,
+};
+use tracing::*;
+
+pub type GatewayNoticeSender = mpsc::UnboundedSender<GatewayNotice>;
+pub type GatewayNoticeReceiver = mpsc::UnboundedReceiver<GatewayNotice>;
+
+/// Checks whether the received response is a notice pushed by the gateway rather than an actual response
+/// to any of our requests and if so, forwards it to the subscriber.
+pub(crate) fn try_forward_notice(
+    response: &ServerResponse,
+    shared_key: &SharedGatewayKey,
+    notice_sender: &GatewayNoticeSender,
+) -> bool {
+    let ServerResponse::EncryptedResponse { ciphertext, nonce } = response else {
+        return false;
+    };
+
+    match SensitiveServerResponse::decrypt(ciphertext, nonce, shared_key) {
+        Ok(SensitiveServerResponse::Notice { notice }) => {
+            info!("received gateway notice: {notice}");
+            if notice_sender.unbounded_send(notice).is_err() {
+                debug!("the gateway notices subscriber has gone away");
+            }
+            true
+        }
+        _ => false,
+    }
+}
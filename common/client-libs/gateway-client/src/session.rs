@@ -0,0 +1,157 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::bandwidth::ClientBandwidth;
+use nym_gateway_requests::CipherSuite;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use time::OffsetDateTime;
+use tokio::sync::broadcast;
+
+// used in place of the timestamps that haven't been set yet
+const UNSET: i64 = i64::MIN;
+
+// the events are meant to be consumed straight away, so there's no need for a big buffer
+const SESSION_EVENTS_CHANNEL_CAPACITY: usize = 16;
+
+/// Changes to the session with the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GatewaySessionEvent {
+    /// The connection with the gateway has been (re-)established and authenticated.
+    Connected,
+
+    /// The connection with the gateway has been lost or closed.
+    Disconnected,
+
+    /// The provided number of zk-nym tickets has been spent to obtain more bandwidth.
+    CredentialSpent { tickets: u32 },
+}
+
+/// Statistics of the session with the gateway that can be observed while the client is running.
+#[derive(Clone)]
+pub struct GatewaySessionStats {
+    inner: Arc<GatewaySessionStatsInner>,
+}
+
+struct GatewaySessionStatsInner {
+    bandwidth: ClientBandwidth,
+
+    /// unix timestamp of the moment the current connection has been authenticated
+    connected_at: AtomicI64,
+
+    /// number of times the connection has been re-established after the initial one
+    reconnections: AtomicU64,
+
+    /// unix timestamp of the moment we have last received any packets from the gateway
+    last_received_at: AtomicI64,
+
+    /// cipher suite of the shared key used for the current connection
+    cipher_suite: Mutex<Option<CipherSuite>>,
+
+    events: broadcast::Sender<GatewaySessionEvent>,
+}
+
+fn timestamp(value: &AtomicI64) -> Option<OffsetDateTime> {
+    match value.load(Ordering::Relaxed) {
+        UNSET => None,
+        ts => OffsetDateTime::from_unix_timestamp(ts).ok(),
+    }
+}
+
+fn now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+impl GatewaySessionStats {
+    pub(crate) fn new(bandwidth: ClientBandwidth) -> Self {
+        GatewaySessionStats {
+            inner: Arc::new(GatewaySessionStatsInner {
+                bandwidth,
+                connected_at: AtomicI64::new(UNSET),
+                reconnections: AtomicU64::new(0),
+                last_received_at: AtomicI64::new(UNSET),
+                cipher_suite: Mutex::new(None),
+                events: broadcast::channel(SESSION_EVENTS_CHANNEL_CAPACITY).0,
+            }),
+        }
+    }
+
+    fn emit(&self, event: GatewaySessionEvent) {
+        // it's fine if nobody is listening
+        let _ = self.inner.events.send(event);
+    }
+
+    pub(crate) fn connected(&self) {
+        self.inner.connected_at.store(now(), Ordering::Relaxed);
+        self.emit(GatewaySessionEvent::Connected)
+    }
+
+    pub(crate) fn reconnected(&self) {
+        self.inner.reconnections.fetch_add(1, Ordering::Relaxed);
+        self.connected()
+    }
+
+    pub(crate) fn disconnected(&self) {
+        self.set_cipher_suite(None);
+        // only report the actual change as we might be told about it multiple times
+        if self.inner.connected_at.swap(UNSET, Ordering::Relaxed) != UNSET {
+            self.emit(GatewaySessionEvent::Disconnected)
+        }
+    }
+
+    pub(crate) fn credential_spent(&self, tickets: u32) {
+        self.emit(GatewaySessionEvent::CredentialSpent { tickets })
+    }
+
+    pub(crate) fn set_cipher_suite(&self, cipher_suite: Option<CipherSuite>) {
+        *self
+            .inner
+            .cipher_suite
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = cipher_suite
+    }
+
+    pub(crate) fn received_packets(&self) {
+        self.inner.last_received_at.store(now(), Ordering::Relaxed)
+    }
+
+    /// The moment the current connection has been established, if we're connected.
+    pub fn connected_since(&self) -> Option<OffsetDateTime> {
+        timestamp(&self.inner.connected_at)
+    }
+
+    /// The number of times the connection had to be re-established.
+    pub fn reconnections(&self) -> u64 {
+        self.inner.reconnections.load(Ordering::Relaxed)
+    }
+
+    /// The moment we have last received any packets from the gateway.
+    pub fn last_received(&self) -> Option<OffsetDateTime> {
+        timestamp(&self.inner.last_received_at)
+    }
+
+    /// The cipher suite negotiated with the gateway for the shared key in use.
+    /// It's not set for the legacy keys nor before the connection has been authenticated.
+    pub fn cipher_suite(&self) -> Option<CipherSuite> {
+        *self
+            .inner
+            .cipher_suite
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The amount of bandwidth (in bytes) consumed since the client has been created.
+    pub fn bandwidth_consumed(&self) -> u64 {
+        self.inner.bandwidth.consumed()
+    }
+
+    /// The amount of bandwidth (in bytes) still available, as last reported by the gateway.
+    pub fn remaining_bandwidth(&self) -> i64 {
+        self.inner.bandwidth.remaining()
+    }
+
+    /// Subscribes to all subsequent changes to the session.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<GatewaySessionEvent> {
+        self.inner.events.subscribe()
+    }
+}
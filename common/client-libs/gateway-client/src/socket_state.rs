@@ -3,14 +3,17 @@
 
 use crate::bandwidth::ClientBandwidth;
 use crate::error::GatewayClientError;
+use crate::notices::{try_forward_notice, GatewayNoticeSender};
 use crate::packet_router::PacketRouter;
+use crate::session::GatewaySessionStats;
 use crate::traits::GatewayPacketRouter;
+use crate::transport::BoxedGatewayConnection;
 use crate::{cleanup_socket_messages, try_decrypt_binary_message};
 use futures::channel::oneshot;
 use futures::stream::{SplitSink, SplitStream};
 use futures::{SinkExt, StreamExt};
 use nym_gateway_requests::shared_key::SharedGatewayKey;
-use nym_gateway_requests::{ServerResponse, SimpleGatewayRequestsError};
+use nym_gateway_requests::{FrameBufferPool, ServerResponse, SimpleGatewayRequestsError};
 use nym_task::TaskClient;
 use si_scale::helpers::bibytes2;
 use std::os::raw::c_int as RawFd;
@@ -18,23 +21,9 @@ use std::sync::Arc;
 use tracing::*;
 use tungstenite::{protocol::Message, Error as WsError};
 
-#[cfg(unix)]
-use std::os::fd::AsRawFd;
-#[cfg(not(target_arch = "wasm32"))]
-use tokio::net::TcpStream;
-#[cfg(not(target_arch = "wasm32"))]
-use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
-
-#[cfg(target_arch = "wasm32")]
-use wasm_utils::websocket::JSWebsocket;
-
-// type alias for not having to type the whole thing every single time (and now it makes it easier
-// to use different types based on compilation target)
-#[cfg(not(target_arch = "wasm32"))]
-type WsConn = WebSocketStream<MaybeTlsStream<TcpStream>>;
-
-#[cfg(target_arch = "wasm32")]
-type WsConn = JSWebsocket;
+// type alias for not having to type the whole thing every single time
+// (the underlying transport is determined by the `GatewayConnector` used by the client)
+type WsConn = BoxedGatewayConnection;
 
 // We have ownership over sink half of the connection, but the stream is owned
 // by some other task, however, we can notify it to get the stream back.
@@ -42,14 +31,8 @@ type WsConn = JSWebsocket;
 type SplitStreamReceiver = oneshot::Receiver<Result<SplitStream<WsConn>, GatewayClientError>>;
 type SplitStreamSender = oneshot::Sender<Result<SplitStream<WsConn>, GatewayClientError>>;
 
-pub(crate) fn ws_fd(_conn: &WsConn) -> Option<RawFd> {
-    #[cfg(unix)]
-    match _conn.get_ref() {
-        MaybeTlsStream::Plain(stream) => Some(stream.as_raw_fd()),
-        &_ => None,
-    }
-    #[cfg(not(unix))]
-    None
+pub(crate) fn ws_fd(conn: &WsConn) -> Option<RawFd> {
+    conn.raw_fd()
 }
 
 #[derive(Debug)]
@@ -64,16 +47,23 @@ struct PartiallyDelegatedRouter {
     packet_router: PacketRouter,
     shared_key: Arc<SharedGatewayKey>,
     client_bandwidth: ClientBandwidth,
+    session_stats: GatewaySessionStats,
+    notice_sender: Option<GatewayNoticeSender>,
+    frame_buffers: FrameBufferPool,
 
     stream_return: SplitStreamSender,
     stream_return_requester: oneshot::Receiver<()>,
 }
 
 impl PartiallyDelegatedRouter {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         packet_router: PacketRouter,
         shared_key: Arc<SharedGatewayKey>,
         client_bandwidth: ClientBandwidth,
+        session_stats: GatewaySessionStats,
+        notice_sender: Option<GatewayNoticeSender>,
+        frame_buffers: FrameBufferPool,
         stream_return: SplitStreamSender,
         stream_return_requester: oneshot::Receiver<()>,
     ) -> PartiallyDelegatedRouter {
@@ -81,6 +71,9 @@ impl PartiallyDelegatedRouter {
             packet_router,
             shared_key,
             client_bandwidth,
+            session_stats,
+            notice_sender,
+            frame_buffers,
             stream_return,
             stream_return_requester,
         }
@@ -131,6 +124,7 @@ impl PartiallyDelegatedRouter {
         let ws_msgs = cleanup_socket_messages(msgs)?;
         let plaintexts = self.recover_received_plaintexts(ws_msgs)?;
         if !plaintexts.is_empty() {
+            self.session_stats.received_packets();
             self.packet_router.route_received(plaintexts)?
         }
 
@@ -139,7 +133,10 @@ impl PartiallyDelegatedRouter {
 
     fn handle_binary_message(&self, binary_msg: Vec<u8>) -> Result<Vec<u8>, GatewayClientError> {
         // this function decrypts the request and checks the MAC
-        match try_decrypt_binary_message(binary_msg, &self.shared_key) {
+        let maybe_plaintext = try_decrypt_binary_message(&binary_msg, &self.shared_key);
+        // the ciphertext is no longer needed, so its buffer could be reused for an outgoing frame
+        self.frame_buffers.release(binary_msg);
+        match maybe_plaintext {
             Some(plaintext) => Ok(plaintext),
             None => {
                 error!("failed to decrypt and verify received message!");
@@ -151,7 +148,15 @@ impl PartiallyDelegatedRouter {
     // only returns an error on **critical** failures
     fn handle_text_message(&self, text: String) -> Result<(), GatewayClientError> {
         // if we fail to deserialise the response, return a hard error. we can't handle garbage
-        match ServerResponse::try_from(text).map_err(|_| GatewayClientError::MalformedResponse)? {
+        let response =
+            ServerResponse::try_from(text).map_err(|_| GatewayClientError::MalformedResponse)?;
+        if let Some(notice_sender) = &self.notice_sender {
+            if try_forward_notice(&response, &self.shared_key, notice_sender) {
+                return Ok(());
+            }
+        }
+
+        match response {
             ServerResponse::Send {
                 remaining_bandwidth,
             } => {
@@ -159,9 +164,10 @@ impl PartiallyDelegatedRouter {
                     .update_and_maybe_log(remaining_bandwidth);
                 Ok(())
             }
-            ServerResponse::Error { message } => {
-                error!("[1] gateway failure: {message}");
-                Err(GatewayClientError::GatewayError(message))
+            ServerResponse::Error { code, message } => {
+                let err = GatewayClientError::from_gateway_response(code, message);
+                error!("[1] gateway failure: {err}");
+                Err(err)
             }
             ServerResponse::TypedError { error } => {
                 match error {
@@ -244,11 +250,15 @@ impl PartiallyDelegatedRouter {
 }
 
 impl PartiallyDelegatedHandle {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn split_and_listen_for_mixnet_messages(
         conn: WsConn,
         packet_router: PacketRouter,
         shared_key: Arc<SharedGatewayKey>,
         client_bandwidth: ClientBandwidth,
+        session_stats: GatewaySessionStats,
+        notice_sender: Option<GatewayNoticeSender>,
+        frame_buffers: FrameBufferPool,
         shutdown: TaskClient,
     ) -> Self {
         // when called for, it NEEDS TO yield back the stream so that we could merge it and
@@ -263,6 +273,9 @@ impl PartiallyDelegatedHandle {
             packet_router,
             shared_key,
             client_bandwidth,
+            session_stats,
+            notice_sender,
+            frame_buffers,
             stream_sender,
             notify_receiver,
         )
@@ -336,7 +349,7 @@ impl PartiallyDelegatedHandle {
 // which should be almost immediate (or an invalid state which should never, ever happen)
 #[derive(Debug)]
 pub(crate) enum SocketState {
-    Available(Box<WsConn>),
+    Available(WsConn),
     PartiallyDelegated(PartiallyDelegatedHandle),
     NotConnected,
     Invalid,
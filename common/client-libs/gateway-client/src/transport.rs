@@ -0,0 +1,193 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Transports used for establishing the connection to the gateway.
+//!
+//! By default, the client talks to the gateway over a WebSocket, but any other reliable channel
+//! could be used instead by providing a custom [`GatewayConnector`]. Byte-oriented streams
+//! can be turned into a valid [`GatewayConnection`] by wrapping them in a [`FramedTransport`],
+//! so that neither the handshake nor the message framing have to be re-implemented.
+//! The [`FramedTcpConnector`] does exactly that for the raw TCP listener optionally exposed by gateways.
+
+use crate::error::GatewayClientError;
+use futures::future::BoxFuture;
+use futures::io::{AsyncRead, AsyncWrite};
+use std::fmt::Debug;
+use std::os::raw::c_int as RawFd;
+
+pub use nym_gateway_requests::transport::{FramedTransport, GatewayTransport};
+
+#[cfg(unix)]
+use std::os::fd::AsRawFd;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::net::TcpStream;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+#[cfg(not(target_arch = "wasm32"))]
+use tokio_util::compat::TokioAsyncReadCompatExt;
+#[cfg(not(target_arch = "wasm32"))]
+use url::Url;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_utils::websocket::JSWebsocket;
+
+/// An established connection to the gateway.
+///
+/// Note that in wasm the `Send` bound is only satisfied by the [`JSWebsocket`], which is safe to do
+/// as everything runs on a single thread there. Other connection types are not supported in wasm.
+pub trait GatewayConnection: GatewayTransport + Unpin + Send + Debug {
+    /// Returns the raw file descriptor of the underlying socket, if applicable.
+    fn raw_fd(&self) -> Option<RawFd> {
+        None
+    }
+}
+
+pub type BoxedGatewayConnection = Box<dyn GatewayConnection>;
+
+/// Establishes new connections to gateways.
+pub trait GatewayConnector: Send + Sync {
+    fn connect<'a>(
+        &'a self,
+        gateway_address: &'a str,
+    ) -> BoxFuture<'a, Result<BoxedGatewayConnection, GatewayClientError>>;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl GatewayConnection for WebSocketStream<MaybeTlsStream<TcpStream>> {
+    fn raw_fd(&self) -> Option<RawFd> {
+        #[cfg(unix)]
+        match self.get_ref() {
+            MaybeTlsStream::Plain(stream) => Some(stream.as_raw_fd()),
+            &_ => None,
+        }
+        #[cfg(not(unix))]
+        None
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl GatewayConnection for JSWebsocket {}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T> GatewayConnection for FramedTransport<T> where T: AsyncRead + AsyncWrite + Unpin + Send {}
+
+/// The default connector establishing WebSocket connections to gateways.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WebSocketConnector;
+
+impl GatewayConnector for WebSocketConnector {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn connect<'a>(
+        &'a self,
+        gateway_address: &'a str,
+    ) -> BoxFuture<'a, Result<BoxedGatewayConnection, GatewayClientError>> {
+        Box::pin(async move {
+            match connect_async(gateway_address).await {
+                Ok((ws_stream, _)) => Ok(Box::new(ws_stream) as BoxedGatewayConnection),
+                Err(source) => Err(GatewayClientError::NetworkConnectionFailed {
+                    address: gateway_address.to_string(),
+                    source,
+                }),
+            }
+        })
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn connect<'a>(
+        &'a self,
+        gateway_address: &'a str,
+    ) -> BoxFuture<'a, Result<BoxedGatewayConnection, GatewayClientError>> {
+        Box::pin(async move {
+            let ws_stream = JSWebsocket::new(gateway_address)?;
+            Ok(Box::new(ws_stream) as BoxedGatewayConnection)
+        })
+    }
+}
+
+/// Connector establishing raw TCP connections to the framed client listener of the gateway,
+/// rather than to its WebSocket one.
+///
+/// The host is taken from the gateway address, while the port has to be provided explicitly
+/// as it is not announced by the gateways.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Copy)]
+pub struct FramedTcpConnector {
+    port: u16,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FramedTcpConnector {
+    pub fn new(port: u16) -> Self {
+        FramedTcpConnector { port }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl GatewayConnector for FramedTcpConnector {
+    fn connect<'a>(
+        &'a self,
+        gateway_address: &'a str,
+    ) -> BoxFuture<'a, Result<BoxedGatewayConnection, GatewayClientError>> {
+        Box::pin(async move {
+            let url = Url::parse(gateway_address)
+                .map_err(|err| GatewayClientError::InvalidURL(err.to_string()))?;
+            let Some(host) = url.host_str() else {
+                return Err(GatewayClientError::InvalidURL(format!(
+                    "{gateway_address} does not specify the host"
+                )));
+            };
+
+            match TcpStream::connect((host, self.port)).await {
+                Ok(stream) => {
+                    Ok(Box::new(FramedTransport::new(stream.compat())) as BoxedGatewayConnection)
+                }
+                Err(err) => Err(GatewayClientError::NetworkConnectionFailed {
+                    address: format!("{host}:{}", self.port),
+                    source: err.into(),
+                }),
+            }
+        })
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use futures::{SinkExt, StreamExt};
+    use tokio::net::TcpListener;
+    use tungstenite::Message;
+
+    #[tokio::test]
+    async fn framed_tcp_connector_reaches_the_framed_listener() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut framed = FramedTransport::new(socket.compat());
+            let received = framed.next().await.unwrap().unwrap();
+            framed.send(received).await.unwrap();
+        });
+
+        // the port of the websocket address is ignored
+        let mut conn = FramedTcpConnector::new(port)
+            .connect("ws://127.0.0.1:9000")
+            .await
+            .unwrap();
+        conn.send(Message::Binary(vec![1, 2, 3])).await.unwrap();
+        assert_eq!(
+            conn.next().await.unwrap().unwrap(),
+            Message::Binary(vec![1, 2, 3])
+        );
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn framed_tcp_connector_rejects_invalid_addresses() {
+        let connector = FramedTcpConnector::new(1234);
+        assert!(matches!(
+            connector.connect("not a url").await,
+            Err(GatewayClientError::InvalidURL(_))
+        ));
+    }
+}
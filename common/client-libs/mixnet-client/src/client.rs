@@ -4,6 +4,7 @@
 use futures::channel::mpsc;
 use futures::StreamExt;
 use log::*;
+use nym_metrics::REGISTRY;
 use nym_sphinx::addressing::nodes::NymNodeRoutingAddress;
 use nym_sphinx::framing::codec::NymCodec;
 use nym_sphinx::framing::packet::FramedNymPacket;
@@ -45,6 +46,33 @@ impl Config {
     }
 }
 
+/// Builds name of a metric tracked separately for each destination we're forwarding packets to.
+pub(crate) fn destination_metric_name(destination: SocketAddr, metric: &str) -> String {
+    let sanitized_destination = destination
+        .to_string()
+        .replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+    format!("nym_mixnet_client_destination_{sanitized_destination}_{metric}")
+}
+
+/// Failure to hand over a packet to the connection with its next hop.
+#[derive(Debug)]
+pub struct ForwardingFailure {
+    pub error: io::Error,
+
+    /// The packet that has not been queued for sending, if any,
+    /// so that it could be retried at a later point.
+    pub unsent_packet: Option<NymPacket>,
+}
+
+impl ForwardingFailure {
+    fn new(error: io::Error, unsent_packet: Option<NymPacket>) -> Self {
+        ForwardingFailure {
+            error,
+            unsent_packet,
+        }
+    }
+}
+
 pub trait SendWithoutResponse {
     // Without response in this context means we will not listen for anything we might get back (not
     // that we should get anything), including any possible io errors
@@ -83,6 +111,24 @@ impl Client {
         }
     }
 
+    // drops all the packets waiting to be sent to the unreachable node
+    fn drop_buffered_packets(address: SocketAddr, mut receiver: mpsc::Receiver<FramedNymPacket>) {
+        REGISTRY.inc(&destination_metric_name(address, "connection_failures"));
+
+        receiver.close();
+        let mut dropped = 0;
+        while let Ok(Some(_)) = receiver.try_next() {
+            dropped += 1;
+        }
+        if dropped > 0 {
+            debug!("dropping {dropped} packets buffered for {address}");
+            REGISTRY.inc_by(
+                &destination_metric_name(address, "packets_dropped"),
+                dropped,
+            );
+        }
+    }
+
     async fn manage_connection(
         address: SocketAddr,
         receiver: mpsc::Receiver<FramedNymPacket>,
@@ -104,6 +150,7 @@ impl Client {
                         "failed to establish connection to {} (err: {})",
                         address, err
                     );
+                    Self::drop_buffered_packets(address, receiver);
                     return;
                 }
             },
@@ -115,6 +162,7 @@ impl Client {
 
                 // we failed to connect - increase reconnection attempt
                 current_reconnection.fetch_add(1, Ordering::SeqCst);
+                Self::drop_buffered_packets(address, receiver);
                 return;
             }
         };
@@ -193,13 +241,16 @@ impl Client {
     }
 }
 
-impl SendWithoutResponse for Client {
-    fn send_without_response(
+impl Client {
+    /// Attempts to queue the packet for sending to the specified address.
+    /// Unlike [`SendWithoutResponse::send_without_response`], if the packet could not be queued,
+    /// it is returned back to the caller so that it could be retried later.
+    pub fn try_forward_packet(
         &mut self,
         address: NymNodeRoutingAddress,
         packet: NymPacket,
         packet_type: PacketType,
-    ) -> io::Result<()> {
+    ) -> Result<(), ForwardingFailure> {
         trace!("Sending packet to {:?}", address);
         let framed_packet =
             FramedNymPacket::new(packet, packet_type, self.config.use_legacy_version);
@@ -207,12 +258,12 @@ impl SendWithoutResponse for Client {
         if let Some(sender) = self.conn_new.get_mut(&address) {
             if let Err(err) = sender.channel.try_send(framed_packet) {
                 if err.is_full() {
-                    debug!("Connection to {} seems to not be able to handle all the traffic - dropping the current packet", address);
+                    debug!("Connection to {} seems to not be able to handle all the traffic - rejecting the current packet", address);
                     // it's not a 'big' error, but we did not manage to send the packet
-                    // if the queue is full, we can't really do anything but to drop the packet
-                    Err(io::Error::new(
-                        io::ErrorKind::WouldBlock,
-                        "connection queue is full",
+                    // if the queue is full, we can't really do anything but to hand it back
+                    Err(ForwardingFailure::new(
+                        io::Error::new(io::ErrorKind::WouldBlock, "connection queue is full"),
+                        Some(err.into_inner().into_inner()),
                     ))
                 } else if err.is_disconnected() {
                     debug!(
@@ -222,15 +273,18 @@ impl SendWithoutResponse for Client {
                     // it's not a 'big' error, but we did not manage to send the packet, but queue
                     // it up to send it as soon as the connection is re-established
                     self.make_connection(address, err.into_inner());
-                    Err(io::Error::new(
-                        io::ErrorKind::ConnectionAborted,
-                        "reconnection attempt is in progress",
+                    Err(ForwardingFailure::new(
+                        io::Error::new(
+                            io::ErrorKind::ConnectionAborted,
+                            "reconnection attempt is in progress",
+                        ),
+                        None,
                     ))
                 } else {
                     // this can't really happen, but let's safe-guard against it in case something changes in futures library
-                    Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        "unknown connection buffer error",
+                    Err(ForwardingFailure::new(
+                        io::Error::other("unknown connection buffer error"),
+                        Some(err.into_inner().into_inner()),
                     ))
                 }
             } else {
@@ -242,14 +296,31 @@ impl SendWithoutResponse for Client {
             // it's not a 'big' error, but we did not manage to send the packet, but queue the packet
             // for sending for as soon as the connection is created
             self.make_connection(address, framed_packet);
-            Err(io::Error::new(
-                io::ErrorKind::NotConnected,
-                "connection is in progress",
+            Err(ForwardingFailure::new(
+                io::Error::new(io::ErrorKind::NotConnected, "connection is in progress"),
+                None,
             ))
         }
     }
 }
 
+impl SendWithoutResponse for Client {
+    fn send_without_response(
+        &mut self,
+        address: NymNodeRoutingAddress,
+        packet: NymPacket,
+        packet_type: PacketType,
+    ) -> io::Result<()> {
+        self.try_forward_packet(address, packet, packet_type)
+            .map_err(|failure| {
+                if failure.unsent_packet.is_some() {
+                    REGISTRY.inc(&destination_metric_name(address.into(), "packets_dropped"));
+                }
+                failure.error
+            })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,21 +1,77 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::client::{Client, Config, SendWithoutResponse};
+use crate::client::{destination_metric_name, Client, Config};
 use futures::channel::mpsc;
 use futures::StreamExt;
 use log::*;
+use nym_metrics::REGISTRY;
 use nym_sphinx::forwarding::packet::MixPacket;
 use std::time::Duration;
+use tokio_util::time::DelayQueue;
 
 pub type MixForwardingSender = mpsc::UnboundedSender<MixPacket>;
 type MixForwardingReceiver = mpsc::UnboundedReceiver<MixPacket>;
 
+/// Specifies how packets that could not be immediately handed over to the connection with their
+/// next hop (for example because it's unreachable and its buffer got filled) should be retried.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Maximum number of times forwarding of a packet is going to be retried before it's dropped.
+    /// Setting it to 0 disables retries altogether.
+    pub maximum_retries: u32,
+
+    /// Initial value of the exponential backoff between subsequent retries.
+    pub initial_backoff: Duration,
+
+    /// Maximum value of the exponential backoff between subsequent retries.
+    pub maximum_backoff: Duration,
+
+    /// Maximum number of packets waiting to be retried at any given time.
+    /// Any packets that don't fit in the queue are dropped.
+    pub maximum_queue_size: usize,
+}
+
+impl RetryPolicy {
+    pub fn disabled() -> Self {
+        RetryPolicy {
+            maximum_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        2_u32
+            .checked_pow(attempt)
+            .and_then(|exp| self.initial_backoff.checked_mul(exp))
+            .unwrap_or(self.maximum_backoff)
+            .min(self.maximum_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            maximum_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            maximum_backoff: Duration::from_secs(5),
+            maximum_queue_size: 10_000,
+        }
+    }
+}
+
+struct PendingRetry {
+    packet: MixPacket,
+    attempt: u32,
+}
+
 /// A specialisation of client such that it forwards any received packets on the channel into the
 /// mix network immediately, i.e. will not try to listen for any responses.
 pub struct PacketForwarder {
     mixnet_client: Client,
     packet_receiver: MixForwardingReceiver,
+    retry_policy: RetryPolicy,
+    retry_queue: DelayQueue<PendingRetry>,
     shutdown: nym_task::TaskClient,
 }
 
@@ -26,6 +82,7 @@ impl PacketForwarder {
         initial_connection_timeout: Duration,
         maximum_connection_buffer_size: usize,
         use_legacy_version: bool,
+        retry_policy: RetryPolicy,
         shutdown: nym_task::TaskClient,
     ) -> (PacketForwarder, MixForwardingSender) {
         let client_config = Config::new(
@@ -42,12 +99,69 @@ impl PacketForwarder {
             PacketForwarder {
                 mixnet_client: Client::new(client_config),
                 packet_receiver,
+                retry_policy,
+                retry_queue: DelayQueue::new(),
                 shutdown,
             },
             packet_sender,
         )
     }
 
+    fn drop_packet(&self, packet: &MixPacket, reason: &str) {
+        debug!("dropping packet to {}: {reason}", packet.next_hop());
+        REGISTRY.inc("nym_mixnet_client_forwarder_packets_dropped");
+        REGISTRY.inc(&destination_metric_name(
+            packet.next_hop().into(),
+            "packets_dropped",
+        ));
+    }
+
+    fn schedule_retry(&mut self, packet: MixPacket, attempt: u32) {
+        if attempt > self.retry_policy.maximum_retries {
+            self.drop_packet(&packet, "maximum number of retries has been reached");
+            return;
+        }
+        if self.retry_queue.len() >= self.retry_policy.maximum_queue_size {
+            self.drop_packet(&packet, "the retry queue is full");
+            return;
+        }
+
+        REGISTRY.inc("nym_mixnet_client_forwarder_packets_retried");
+        let backoff = self.retry_policy.backoff(attempt - 1);
+        trace!(
+            "going to retry forwarding packet to {} in {backoff:?}",
+            packet.next_hop()
+        );
+        self.retry_queue
+            .insert(PendingRetry { packet, attempt }, backoff);
+    }
+
+    fn forward_packet(&mut self, mix_packet: MixPacket, attempt: u32) {
+        trace!("Going to forward packet to {}", mix_packet.next_hop());
+
+        let next_hop = mix_packet.next_hop();
+        let packet_type = mix_packet.packet_type();
+        let packet = mix_packet.into_packet();
+        // we don't care about responses, we just want to fire packets
+        // as quickly as possible
+
+        if let Err(failure) = self
+            .mixnet_client
+            .try_forward_packet(next_hop, packet, packet_type)
+        {
+            debug!("failed to forward the packet - {}", failure.error);
+            REGISTRY.inc(&destination_metric_name(
+                next_hop.into(),
+                "forwarding_failures",
+            ));
+
+            // if the packet got queued for the pending connection, there's nothing more to do
+            if let Some(packet) = failure.unsent_packet {
+                self.schedule_retry(MixPacket::new(next_hop, packet, packet_type), attempt + 1)
+            }
+        }
+    }
+
     pub async fn run(&mut self) {
         while !self.shutdown.is_shutdown() {
             tokio::select! {
@@ -56,22 +170,41 @@ impl PacketForwarder {
                     log::trace!("PacketForwarder: Received shutdown");
                 }
                 Some(mix_packet) = self.packet_receiver.next() => {
-                     trace!("Going to forward packet to {}", mix_packet.next_hop());
-
-                    let next_hop = mix_packet.next_hop();
-                    let packet_type = mix_packet.packet_type();
-                    let packet = mix_packet.into_packet();
-                    // we don't care about responses, we just want to fire packets
-                    // as quickly as possible
-
-                    if let Err(err) =
-                        self.mixnet_client
-                            .send_without_response(next_hop, packet, packet_type)
-                    {
-                        debug!("failed to forward the packet - {err}")
-                    }
+                    self.forward_packet(mix_packet, 0)
+                }
+                Some(expired) = self.retry_queue.next(), if !self.retry_queue.is_empty() => {
+                    let retry = expired.into_inner();
+                    self.forward_packet(retry.packet, retry.attempt)
                 }
             }
         }
+
+        if !self.retry_queue.is_empty() {
+            debug!(
+                "dropping {} packets waiting to be retried",
+                self.retry_queue.len()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_backoff_is_bounded() {
+        let policy = RetryPolicy {
+            maximum_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            maximum_backoff: Duration::from_secs(1),
+            maximum_queue_size: 10,
+        };
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(800));
+        assert_eq!(policy.backoff(4), Duration::from_secs(1));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(1));
     }
 }
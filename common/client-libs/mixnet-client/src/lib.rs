@@ -4,4 +4,5 @@
 pub mod client;
 pub mod forwarder;
 
-pub use client::{Client, Config, SendWithoutResponse};
+pub use client::{Client, Config, ForwardingFailure, SendWithoutResponse};
+pub use forwarder::RetryPolicy;
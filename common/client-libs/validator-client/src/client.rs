@@ -17,11 +17,14 @@ use nym_api_requests::ecash::{
     BlindSignRequestBody, BlindedSignatureResponse, PartialCoinIndicesSignatureResponse,
     PartialExpirationDateSignatureResponse, VerificationKeyResponse,
 };
-use nym_api_requests::models::{DescribedGateway, MixNodeBondAnnotated};
+use nym_api_requests::models::{
+    ApiHealthBreakdownResponse, DescribedGateway, GatewayBondAnnotated, MixNodeBondAnnotated,
+};
 use nym_api_requests::models::{
     GatewayCoreStatusResponse, MixnodeCoreStatusResponse, MixnodeStatusResponse,
     RewardEstimationResponse, StakeSaturationResponse,
 };
+use nym_api_requests::node_location::{NodeDiversityResponse, NodeLocationResponse};
 use nym_api_requests::nym_nodes::SkimmedNode;
 use nym_coconut_dkg_common::types::EpochId;
 use nym_http_api_client::UserAgent;
@@ -31,7 +34,7 @@ use url::Url;
 
 pub use crate::nym_api::NymApiClientExt;
 pub use nym_mixnet_contract_common::{
-    mixnode::MixNodeDetails, GatewayBond, IdentityKey, IdentityKeyRef, MixId,
+    mixnode::MixNodeDetails, GatewayBond, IdentityKey, IdentityKeyRef, Interval, MixId,
 };
 
 // re-export the type to not break existing imports
@@ -305,12 +308,40 @@ impl NymApiClient {
             .nodes)
     }
 
+    pub async fn get_basic_mixnodes_with_standby(
+        &self,
+        semver_compatibility: Option<String>,
+    ) -> Result<Vec<SkimmedNode>, ValidatorClientError> {
+        Ok(self
+            .nym_api
+            .get_basic_mixnodes_with_standby(semver_compatibility)
+            .await?
+            .nodes)
+    }
+
+    pub async fn get_basic_gateways_with_standby(
+        &self,
+        semver_compatibility: Option<String>,
+    ) -> Result<Vec<SkimmedNode>, ValidatorClientError> {
+        Ok(self
+            .nym_api
+            .get_basic_gateways_with_standby(semver_compatibility)
+            .await?
+            .nodes)
+    }
+
     pub async fn get_cached_active_mixnodes(
         &self,
     ) -> Result<Vec<MixNodeDetails>, ValidatorClientError> {
         Ok(self.nym_api.get_active_mixnodes().await?)
     }
 
+    pub async fn get_cached_active_mixnodes_detailed(
+        &self,
+    ) -> Result<Vec<MixNodeBondAnnotated>, ValidatorClientError> {
+        Ok(self.nym_api.get_active_mixnodes_detailed().await?)
+    }
+
     pub async fn get_cached_rewarded_mixnodes(
         &self,
     ) -> Result<Vec<MixNodeDetails>, ValidatorClientError> {
@@ -325,6 +356,33 @@ impl NymApiClient {
         Ok(self.nym_api.get_gateways().await?)
     }
 
+    pub async fn get_cached_gateways_detailed(
+        &self,
+    ) -> Result<Vec<GatewayBondAnnotated>, ValidatorClientError> {
+        Ok(self.nym_api.get_gateways_detailed().await?)
+    }
+
+    pub async fn get_node_location(
+        &self,
+        identity_key: &str,
+    ) -> Result<NodeLocationResponse, ValidatorClientError> {
+        Ok(self.nym_api.get_node_location(identity_key).await?)
+    }
+
+    pub async fn get_node_diversity(&self) -> Result<NodeDiversityResponse, ValidatorClientError> {
+        Ok(self.nym_api.get_node_diversity().await?)
+    }
+
+    pub async fn get_current_epoch(&self) -> Result<Option<Interval>, ValidatorClientError> {
+        Ok(self.nym_api.get_current_epoch().await?)
+    }
+
+    pub async fn get_health_breakdown(
+        &self,
+    ) -> Result<ApiHealthBreakdownResponse, ValidatorClientError> {
+        Ok(self.nym_api.get_health_breakdown().await?)
+    }
+
     pub async fn get_cached_described_gateways(
         &self,
     ) -> Result<Vec<DescribedGateway>, ValidatorClientError> {
@@ -11,6 +11,9 @@ use nym_api_requests::ecash::models::{
 };
 use nym_api_requests::ecash::VerificationKeyResponse;
 use nym_api_requests::models::DescribedMixNode;
+use nym_api_requests::node_location::{
+    NodeDiversityResponse, NodeLocationResponse, SignedNodeLocationAttestation,
+};
 use nym_api_requests::nym_nodes::{CachedNodesResponse, SkimmedNode};
 pub use nym_api_requests::{
     ecash::{
@@ -23,11 +26,11 @@ pub use nym_api_requests::{
         VerifyEcashCredentialBody,
     },
     models::{
-        ComputeRewardEstParam, DescribedGateway, GatewayBondAnnotated, GatewayCoreStatusResponse,
-        GatewayStatusReportResponse, GatewayUptimeHistoryResponse, InclusionProbabilityResponse,
-        MixNodeBondAnnotated, MixnodeCoreStatusResponse, MixnodeStatusReportResponse,
-        MixnodeStatusResponse, MixnodeUptimeHistoryResponse, RewardEstimationResponse,
-        StakeSaturationResponse, UptimeResponse,
+        ApiHealthBreakdownResponse, ComputeRewardEstParam, DescribedGateway, GatewayBondAnnotated,
+        GatewayCoreStatusResponse, GatewayStatusReportResponse, GatewayUptimeHistoryResponse,
+        InclusionProbabilityResponse, MixNodeBondAnnotated, MixnodeCoreStatusResponse,
+        MixnodeStatusReportResponse, MixnodeStatusResponse, MixnodeUptimeHistoryResponse,
+        RewardEstimationResponse, StakeSaturationResponse, UptimeResponse,
     },
 };
 pub use nym_coconut_dkg_common::types::EpochId;
@@ -35,7 +38,7 @@ use nym_contracts_common::IdentityKey;
 pub use nym_http_api_client::Client;
 use nym_http_api_client::{ApiClient, NO_PARAMS};
 use nym_mixnet_contract_common::mixnode::MixNodeDetails;
-use nym_mixnet_contract_common::{GatewayBond, IdentityKeyRef, MixId};
+use nym_mixnet_contract_common::{GatewayBond, IdentityKeyRef, Interval, MixId};
 use time::format_description::BorrowedFormatItem;
 use time::Date;
 
@@ -100,6 +103,67 @@ pub trait NymApiClientExt: ApiClient {
             .await
     }
 
+    async fn get_current_epoch(&self) -> Result<Option<Interval>, NymAPIError> {
+        self.get_json(
+            &[routes::API_VERSION, routes::EPOCH, routes::CURRENT],
+            NO_PARAMS,
+        )
+        .await
+    }
+
+    /// Obtains the health of the nym-api together with the status of each of its dependencies,
+    /// which can be used for deciding whether to fail over to a different api instance.
+    async fn get_health_breakdown(&self) -> Result<ApiHealthBreakdownResponse, NymAPIError> {
+        self.get_json(
+            &[
+                routes::API_VERSION,
+                routes::API_STATUS_ROUTES,
+                routes::HEALTHZ,
+            ],
+            NO_PARAMS,
+        )
+        .await
+    }
+
+    async fn get_node_location(
+        &self,
+        identity_key: &str,
+    ) -> Result<NodeLocationResponse, NymAPIError> {
+        self.get_json(
+            &[routes::API_VERSION, routes::NODE_LOCATION, identity_key],
+            NO_PARAMS,
+        )
+        .await
+    }
+
+    async fn get_node_diversity(&self) -> Result<NodeDiversityResponse, NymAPIError> {
+        self.get_json(
+            &[
+                routes::API_VERSION,
+                routes::NODE_LOCATION,
+                routes::DIVERSITY,
+            ],
+            NO_PARAMS,
+        )
+        .await
+    }
+
+    async fn submit_node_location_attestation(
+        &self,
+        attestation: &SignedNodeLocationAttestation,
+    ) -> Result<NodeLocationResponse, NymAPIError> {
+        self.post_json(
+            &[
+                routes::API_VERSION,
+                routes::NODE_LOCATION,
+                routes::ATTESTATION,
+            ],
+            NO_PARAMS,
+            attestation,
+        )
+        .await
+    }
+
     async fn get_gateways_described(&self) -> Result<Vec<DescribedGateway>, NymAPIError> {
         self.get_json(
             &[routes::API_VERSION, routes::GATEWAYS, routes::DESCRIBED],
@@ -162,6 +226,52 @@ pub trait NymApiClientExt: ApiClient {
         .await
     }
 
+    /// Retrieves the active mixnodes alongside the standby ones, i.e. rewarded set nodes outside the active set.
+    async fn get_basic_mixnodes_with_standby(
+        &self,
+        semver_compatibility: Option<String>,
+    ) -> Result<CachedNodesResponse<SkimmedNode>, NymAPIError> {
+        let mut params = vec![("include_standby", "true")];
+        if let Some(semver_compatibility) = &semver_compatibility {
+            params.push(("semver_compatibility", semver_compatibility.as_str()))
+        }
+
+        self.get_json(
+            &[
+                routes::API_VERSION,
+                "unstable",
+                "nym-nodes",
+                "mixnodes",
+                "skimmed",
+            ],
+            &params,
+        )
+        .await
+    }
+
+    /// Retrieves all gateways with the currently underperforming ones being annotated with the standby role.
+    async fn get_basic_gateways_with_standby(
+        &self,
+        semver_compatibility: Option<String>,
+    ) -> Result<CachedNodesResponse<SkimmedNode>, NymAPIError> {
+        let mut params = vec![("include_standby", "true")];
+        if let Some(semver_compatibility) = &semver_compatibility {
+            params.push(("semver_compatibility", semver_compatibility.as_str()))
+        }
+
+        self.get_json(
+            &[
+                routes::API_VERSION,
+                "unstable",
+                "nym-nodes",
+                "gateways",
+                "skimmed",
+            ],
+            &params,
+        )
+        .await
+    }
+
     async fn get_active_mixnodes(&self) -> Result<Vec<MixNodeDetails>, NymAPIError> {
         self.get_json(
             &[routes::API_VERSION, routes::MIXNODES, routes::ACTIVE],
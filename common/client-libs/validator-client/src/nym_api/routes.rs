@@ -35,6 +35,13 @@ pub mod ecash {
     pub const EPOCH_ID_PARAM: &str = "epoch_id";
 }
 
+pub const EPOCH: &str = "epoch";
+pub const CURRENT: &str = "current";
+
+pub const NODE_LOCATION: &str = "node-location";
+pub const ATTESTATION: &str = "attestation";
+pub const DIVERSITY: &str = "diversity";
+
 pub const STATUS_ROUTES: &str = "status";
 pub const MIXNODE: &str = "mixnode";
 pub const GATEWAY: &str = "gateway";
@@ -54,3 +61,6 @@ pub const SUBMIT_GATEWAY: &str = "submit-gateway-monitoring-results";
 pub const SUBMIT_NODE: &str = "submit-node-monitoring-results";
 
 pub const SERVICE_PROVIDERS: &str = "services";
+
+pub const API_STATUS_ROUTES: &str = "api-status";
+pub const HEALTHZ: &str = "healthz";
@@ -16,12 +16,17 @@ use nym_coconut_dkg_common::dealer::RegisteredDealerDetails;
 pub use nym_coconut_dkg_common::{
     dealer::{DealerDetailsResponse, PagedDealerIndexResponse, PagedDealerResponse},
     dealing::{
-        DealerDealingsStatusResponse, DealingChunkResponse, DealingChunkStatusResponse,
-        DealingMetadataResponse, DealingStatusResponse,
+        DealerDealingsStatusResponse, DealerSubmissionCursorResponse, DealingChunkResponse,
+        DealingChunkStatusResponse, DealingMetadataResponse, DealingStatusResponse,
     },
     msg::QueryMsg as DkgQueryMsg,
-    types::{DealerDetails, DealingIndex, Epoch, EpochId, EpochState, State},
-    verification_key::{ContractVKShare, PagedVKSharesResponse, VkShareResponse},
+    types::{
+        DealerDetails, DealingIndex, DkgPublicParameters, Epoch, EpochId, EpochState,
+        PublicParametersResponse, State,
+    },
+    verification_key::{
+        ContractVKShare, MasterVerificationKeyResponse, PagedVKSharesResponse, VkShareResponse,
+    },
 };
 
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
@@ -41,6 +46,11 @@ pub trait DkgQueryClient {
         self.query_dkg_contract(request).await
     }
 
+    async fn get_public_parameters(&self) -> Result<PublicParametersResponse, NyxdError> {
+        let request = DkgQueryMsg::GetPublicParameters {};
+        self.query_dkg_contract(request).await
+    }
+
     async fn can_advance_state(&self) -> Result<StateAdvanceResponse, NyxdError> {
         let request = DkgQueryMsg::CanAdvanceState {};
         self.query_dkg_contract(request).await
@@ -121,6 +131,16 @@ pub trait DkgQueryClient {
         self.query_dkg_contract(request).await
     }
 
+    async fn get_dealer_submission_cursor(
+        &self,
+        epoch_id: EpochId,
+        dealer: String,
+    ) -> Result<DealerSubmissionCursorResponse, NyxdError> {
+        let request = DkgQueryMsg::GetDealerSubmissionCursor { epoch_id, dealer };
+
+        self.query_dkg_contract(request).await
+    }
+
     async fn get_dealing_status(
         &self,
         epoch_id: EpochId,
@@ -193,6 +213,14 @@ pub trait DkgQueryClient {
         self.query_dkg_contract(request).await
     }
 
+    async fn get_master_verification_key(
+        &self,
+        epoch_id: EpochId,
+    ) -> Result<MasterVerificationKeyResponse, NyxdError> {
+        let request = DkgQueryMsg::GetMasterVerificationKey { epoch_id };
+        self.query_dkg_contract(request).await
+    }
+
     async fn get_contract_cw2_version(&self) -> Result<cw2::ContractVersion, NyxdError> {
         self.query_dkg_contract(DkgQueryMsg::GetCW2ContractVersion {})
             .await
@@ -257,6 +285,7 @@ mod tests {
         match msg {
             DkgQueryMsg::GetState {} => client.get_state().ignore(),
             DkgQueryMsg::GetCurrentEpochState {} => client.get_current_epoch().ignore(),
+            DkgQueryMsg::GetPublicParameters {} => client.get_public_parameters().ignore(),
             DkgQueryMsg::CanAdvanceState {} => client.can_advance_state().ignore(),
             DkgQueryMsg::GetCurrentEpochThreshold {} => {
                 client.get_current_epoch_threshold().ignore()
@@ -296,6 +325,9 @@ mod tests {
             QueryMsg::GetDealerDealingsStatus { epoch_id, dealer } => {
                 client.get_dealer_dealings_status(epoch_id, dealer).ignore()
             }
+            DkgQueryMsg::GetDealerSubmissionCursor { epoch_id, dealer } => client
+                .get_dealer_submission_cursor(epoch_id, dealer)
+                .ignore(),
             DkgQueryMsg::GetDealingChunkStatus {
                 epoch_id,
                 dealer,
@@ -322,6 +354,9 @@ mod tests {
             } => client
                 .get_vk_shares_paged(epoch_id, start_after, limit)
                 .ignore(),
+            DkgQueryMsg::GetMasterVerificationKey { epoch_id } => {
+                client.get_master_verification_key(epoch_id).ignore()
+            }
             DkgQueryMsg::GetCW2ContractVersion {} => client.get_contract_cw2_version().ignore(),
         };
     }
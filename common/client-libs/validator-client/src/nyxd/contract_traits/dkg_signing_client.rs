@@ -8,10 +8,12 @@ use crate::nyxd::{Coin, Fee, SigningCosmWasmClient};
 use crate::signing::signer::OfflineSigner;
 use async_trait::async_trait;
 use cosmrs::AccountId;
-use nym_coconut_dkg_common::dealing::{DealingChunkInfo, PartialContractDealing};
+use nym_coconut_dkg_common::dealing::{DealingChecksum, DealingChunkInfo, PartialContractDealing};
 use nym_coconut_dkg_common::msg::ExecuteMsg as DkgExecuteMsg;
-use nym_coconut_dkg_common::types::{DealingIndex, EncodedBTEPublicKeyWithProof};
-use nym_coconut_dkg_common::verification_key::VerificationKeyShare;
+use nym_coconut_dkg_common::types::{
+    DealingIndex, DkgPublicParameters, EncodedBTEPublicKeyWithProof,
+};
+use nym_coconut_dkg_common::verification_key::{MasterVerificationKey, VerificationKeyShare};
 use nym_contracts_common::IdentityKey;
 
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
@@ -25,8 +27,12 @@ pub trait DkgSigningClient {
         funds: Vec<Coin>,
     ) -> Result<ExecuteResult, NyxdError>;
 
-    async fn initiate_dkg(&self, fee: Option<Fee>) -> Result<ExecuteResult, NyxdError> {
-        let req = DkgExecuteMsg::InitiateDkg {};
+    async fn initiate_dkg(
+        &self,
+        public_parameters: Option<DkgPublicParameters>,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        let req = DkgExecuteMsg::InitiateDkg { public_parameters };
 
         self.execute_dkg_contract(fee, req, "initiating the DKG".to_string(), vec![])
             .await
@@ -63,12 +69,14 @@ pub trait DkgSigningClient {
         dealing_index: DealingIndex,
         chunks: Vec<DealingChunkInfo>,
         resharing: bool,
+        checksum: Option<DealingChecksum>,
         fee: Option<Fee>,
     ) -> Result<ExecuteResult, NyxdError> {
         let req = DkgExecuteMsg::CommitDealingsMetadata {
             dealing_index,
             chunks,
             resharing,
+            checksum,
         };
 
         self.execute_dkg_contract(fee, req, "dealing metadata commitment".to_string(), vec![])
@@ -123,6 +131,22 @@ pub trait DkgSigningClient {
         .await
     }
 
+    async fn submit_master_verification_key(
+        &self,
+        key: MasterVerificationKey,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        let req = DkgExecuteMsg::CommitMasterVerificationKey { key };
+
+        self.execute_dkg_contract(
+            fee,
+            req,
+            "master verification key commitment".to_string(),
+            vec![],
+        )
+        .await
+    }
+
     async fn trigger_dkg_reset(&self, fee: Option<Fee>) -> Result<ExecuteResult, NyxdError> {
         let req = DkgExecuteMsg::TriggerReset {};
 
@@ -176,7 +200,9 @@ mod tests {
         msg: DkgExecuteMsg,
     ) {
         match msg {
-            DkgExecuteMsg::InitiateDkg {} => client.initiate_dkg(None).ignore(),
+            DkgExecuteMsg::InitiateDkg { public_parameters } => {
+                client.initiate_dkg(public_parameters, None).ignore()
+            }
             DkgExecuteMsg::RegisterDealer {
                 bte_key_with_proof,
                 identity_key,
@@ -195,8 +221,9 @@ mod tests {
                 dealing_index,
                 chunks,
                 resharing,
+                checksum,
             } => client
-                .submit_dealing_metadata(dealing_index, chunks, resharing, None)
+                .submit_dealing_metadata(dealing_index, chunks, resharing, checksum, None)
                 .ignore(),
             DkgExecuteMsg::CommitDealingsChunk { chunk } => {
                 client.submit_dealing_chunk(chunk, None).ignore()
@@ -207,6 +234,9 @@ mod tests {
             DkgExecuteMsg::VerifyVerificationKeyShare { owner, resharing } => client
                 .verify_verification_key_share(&owner.parse().unwrap(), resharing, None)
                 .ignore(),
+            DkgExecuteMsg::CommitMasterVerificationKey { key } => {
+                client.submit_master_verification_key(key, None).ignore()
+            }
             DkgExecuteMsg::AdvanceEpochState {} => client.advance_dkg_epoch_state(None).ignore(),
             DkgExecuteMsg::TriggerReset {} => client.trigger_dkg_reset(None).ignore(),
             DkgExecuteMsg::TriggerResharing {} => client.trigger_dkg_resharing(None).ignore(),
@@ -13,23 +13,25 @@ use nym_mixnet_contract_common::{
     delegation::{MixNodeDelegationResponse, OwnerProxySubKey},
     families::{Family, FamilyHead},
     mixnode::{
-        MixnodeRewardingDetailsResponse, PagedMixnodesDetailsResponse,
-        PagedUnbondedMixnodesResponse, StakeSaturationResponse, UnbondedMixnodeResponse,
+        MixnodeRewardingDetailsResponse, OperatorPayoutAddressResponse,
+        PagedMixnodesDetailsResponse, PagedUnbondedMixnodesResponse, StakeSaturationResponse,
+        UnbondedMixnodeResponse,
     },
     reward_params::{Performance, RewardingParams},
     rewarding::{EstimatedCurrentEpochRewardResponse, PendingRewardResponse},
-    ContractBuildInformation, ContractState, ContractStateParams, CurrentIntervalResponse,
-    Delegation, EpochEventId, EpochStatus, FamilyByHeadResponse, FamilyByLabelResponse,
-    FamilyMembersByHeadResponse, FamilyMembersByLabelResponse, GatewayBond, GatewayBondResponse,
-    GatewayOwnershipResponse, IdentityKey, IdentityKeyRef, IntervalEventId, LayerDistribution,
+    ConfigChangeId, ConfigChangelogEntry, ContractBuildInformation, ContractState,
+    ContractStateParams, CurrentIntervalResponse, Delegation, EpochEventId, EpochStatus,
+    FamilyByHeadResponse, FamilyByLabelResponse, FamilyMembersByHeadResponse,
+    FamilyMembersByLabelResponse, GatewayBond, GatewayBondResponse, GatewayOwnershipResponse,
+    GovernanceAddressResponse, IdentityKey, IdentityKeyRef, IntervalEventId, LayerDistribution,
     MixId, MixNodeBond, MixNodeDetails, MixOwnershipResponse, MixnodeDetailsByIdentityResponse,
     MixnodeDetailsResponse, NumberOfPendingEventsResponse, PagedAllDelegationsResponse,
-    PagedDelegatorDelegationsResponse, PagedFamiliesResponse, PagedGatewayResponse,
-    PagedMembersResponse, PagedMixNodeDelegationsResponse, PagedMixnodeBondsResponse,
-    PagedRewardedSetResponse, PendingEpochEvent, PendingEpochEventResponse,
-    PendingEpochEventsResponse, PendingIntervalEvent, PendingIntervalEventResponse,
-    PendingIntervalEventsResponse, QueryMsg as MixnetQueryMsg, RewardedSetNodeStatus,
-    UnbondedMixnode,
+    PagedConfigChangelogResponse, PagedDelegatorDelegationsResponse, PagedFamiliesResponse,
+    PagedGatewayResponse, PagedMembersResponse, PagedMixNodeDelegationsResponse,
+    PagedMixnodeBondsResponse, PagedRewardedSetResponse, PendingEpochEvent,
+    PendingEpochEventResponse, PendingEpochEventsResponse, PendingIntervalEvent,
+    PendingIntervalEventResponse, PendingIntervalEventsResponse, QueryMsg as MixnetQueryMsg,
+    RewardedSetNodeStatus, StakingFloorsResponse, UnbondedMixnode,
 };
 use serde::Deserialize;
 
@@ -81,6 +83,11 @@ pub trait MixnetQueryClient {
             .await
     }
 
+    async fn get_staking_floors(&self) -> Result<StakingFloorsResponse, NyxdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetStakingFloors {})
+            .await
+    }
+
     async fn get_current_epoch_status(&self) -> Result<EpochStatus, NyxdError> {
         self.query_mixnet_contract(MixnetQueryMsg::GetEpochStatus {})
             .await
@@ -237,6 +244,14 @@ pub trait MixnetQueryClient {
             .await
     }
 
+    async fn get_operator_payout_address(
+        &self,
+        mix_id: MixId,
+    ) -> Result<OperatorPayoutAddressResponse, NyxdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetOperatorPayoutAddress { mix_id })
+            .await
+    }
+
     async fn get_unbonded_mixnode_information(
         &self,
         mix_id: MixId,
@@ -444,6 +459,20 @@ pub trait MixnetQueryClient {
             .await
     }
 
+    async fn get_governance_address(&self) -> Result<GovernanceAddressResponse, NyxdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetGovernanceAddress {})
+            .await
+    }
+
+    async fn get_config_changelog_paged(
+        &self,
+        start_after: Option<ConfigChangeId>,
+        limit: Option<u32>,
+    ) -> Result<PagedConfigChangelogResponse, NyxdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetConfigChangelog { start_after, limit })
+            .await
+    }
+
     async fn get_signing_nonce(&self, address: &AccountId) -> Result<Nonce, NyxdError> {
         self.query_mixnet_contract(MixnetQueryMsg::GetSigningNonce {
             address: address.to_string(),
@@ -549,6 +578,10 @@ pub trait PagedMixnetQueryClient: MixnetQueryClient {
     ) -> Result<Vec<PendingIntervalEvent>, NyxdError> {
         collect_paged!(self, get_pending_interval_events_paged, events)
     }
+
+    async fn get_full_config_changelog(&self) -> Result<Vec<ConfigChangelogEntry>, NyxdError> {
+        collect_paged!(self, get_config_changelog_paged, changes)
+    }
 }
 
 #[async_trait]
@@ -613,6 +646,7 @@ mod tests {
             MixnetQueryMsg::GetStateParams {} => client.get_mixnet_contract_state_params().ignore(),
             MixnetQueryMsg::GetState {} => client.get_mixnet_contract_state().ignore(),
             MixnetQueryMsg::GetRewardingParams {} => client.get_rewarding_parameters().ignore(),
+            MixnetQueryMsg::GetStakingFloors {} => client.get_staking_floors().ignore(),
             MixnetQueryMsg::GetEpochStatus {} => client.get_current_epoch_status().ignore(),
             MixnetQueryMsg::GetCurrentIntervalDetails {} => {
                 client.get_current_interval_details().ignore()
@@ -655,6 +689,9 @@ mod tests {
             MixnetQueryMsg::GetStakeSaturation { mix_id } => {
                 client.get_mixnode_stake_saturation(mix_id).ignore()
             }
+            MixnetQueryMsg::GetOperatorPayoutAddress { mix_id } => {
+                client.get_operator_payout_address(mix_id).ignore()
+            }
             MixnetQueryMsg::GetUnbondedMixNodeInformation { mix_id } => {
                 client.get_unbonded_mixnode_information(mix_id).ignore()
             }
@@ -742,6 +779,10 @@ mod tests {
             MixnetQueryMsg::GetNumberOfPendingEvents {} => {
                 client.get_number_of_pending_events().ignore()
             }
+            MixnetQueryMsg::GetGovernanceAddress {} => client.get_governance_address().ignore(),
+            MixnetQueryMsg::GetConfigChangelog { limit, start_after } => client
+                .get_config_changelog_paged(start_after, limit)
+                .ignore(),
             MixnetQueryMsg::GetSigningNonce { address } => {
                 client.get_signing_nonce(&address.parse().unwrap()).ignore()
             }
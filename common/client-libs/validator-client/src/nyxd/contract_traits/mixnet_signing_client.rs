@@ -16,7 +16,7 @@ use nym_mixnet_contract_common::mixnode::{MixNodeConfigUpdate, MixNodeCostParams
 use nym_mixnet_contract_common::reward_params::{IntervalRewardingParamsUpdate, Performance};
 use nym_mixnet_contract_common::{
     ContractStateParams, ExecuteMsg as MixnetExecuteMsg, Gateway, Layer, LayerAssignment, MixId,
-    MixNode,
+    MixNode, StakingFloorsUpdate,
 };
 
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
@@ -68,6 +68,19 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn update_governance_address(
+        &self,
+        address: Option<String>,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::UpdateGovernanceAddress { address },
+            vec![],
+        )
+        .await
+    }
+
     async fn update_active_set_size(
         &self,
         active_set_size: u32,
@@ -121,6 +134,23 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn update_staking_floors(
+        &self,
+        update: StakingFloorsUpdate,
+        force_immediately: bool,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::UpdateStakingFloors {
+                update,
+                force_immediately,
+            },
+            vec![],
+        )
+        .await
+    }
+
     async fn begin_epoch_transition(&self, fee: Option<Fee>) -> Result<ExecuteResult, NyxdError> {
         self.execute_mixnet_contract(fee, MixnetExecuteMsg::BeginEpochTransition {}, vec![])
             .await
@@ -452,6 +482,23 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn update_operator_payout_address(
+        &self,
+        payout_address: Option<AccountId>,
+        node_signature: MessageSignature,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::UpdateOperatorPayoutAddress {
+                payout_address: payout_address.map(|address| address.to_string()),
+                node_signature,
+            },
+            vec![],
+        )
+        .await
+    }
+
     async fn update_mixnode_config_on_behalf(
         &self,
         owner: AccountId,
@@ -662,6 +709,19 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn withdraw_operator_reward_to_payout_address(
+        &self,
+        mix_id: MixId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::WithdrawOperatorRewardToPayoutAddress { mix_id },
+            vec![],
+        )
+        .await
+    }
+
     async fn withdraw_delegator_reward(
         &self,
         mix_id: MixId,
@@ -692,6 +752,16 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    /// Withdraws the rewards of all delegations of this account. If there are too many of them,
+    /// the call has to be repeated (until the contract reports there's nothing more remaining).
+    async fn claim_all_delegator_rewards(
+        &self,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(fee, MixnetExecuteMsg::ClaimAllDelegatorRewards {}, vec![])
+            .await
+    }
+
     async fn migrate_vested_mixnode(&self, fee: Option<Fee>) -> Result<ExecuteResult, NyxdError> {
         self.execute_mixnet_contract(fee, MixnetExecuteMsg::MigrateVestedMixNode {}, vec![])
             .await
@@ -815,6 +885,9 @@ mod tests {
             MixnetExecuteMsg::UpdateContractStateParams { updated_parameters } => client
                 .update_contract_state_params(updated_parameters, None)
                 .ignore(),
+            MixnetExecuteMsg::UpdateGovernanceAddress { address } => {
+                client.update_governance_address(address, None).ignore()
+            }
             MixnetExecuteMsg::UpdateActiveSetSize {
                 active_set_size,
                 force_immediately,
@@ -839,6 +912,12 @@ mod tests {
                     None,
                 )
                 .ignore(),
+            MixnetExecuteMsg::UpdateStakingFloors {
+                update,
+                force_immediately,
+            } => client
+                .update_staking_floors(update, force_immediately, None)
+                .ignore(),
             MixnetExecuteMsg::BeginEpochTransition {} => {
                 client.begin_epoch_transition(None).ignore()
             }
@@ -899,6 +978,16 @@ mod tests {
             MixnetExecuteMsg::UpdateMixnodeConfigOnBehalf { new_config, owner } => client
                 .update_mixnode_config_on_behalf(owner.parse().unwrap(), new_config, None)
                 .ignore(),
+            MixnetExecuteMsg::UpdateOperatorPayoutAddress {
+                payout_address,
+                node_signature,
+            } => client
+                .update_operator_payout_address(
+                    payout_address.map(|address| address.parse().unwrap()),
+                    node_signature,
+                    None,
+                )
+                .ignore(),
             MixnetExecuteMsg::BondGateway {
                 gateway,
                 owner_signature,
@@ -950,18 +1039,29 @@ mod tests {
             MixnetExecuteMsg::WithdrawOperatorRewardOnBehalf { owner } => client
                 .withdraw_operator_reward_on_behalf(owner.parse().unwrap(), None)
                 .ignore(),
+            MixnetExecuteMsg::WithdrawOperatorRewardToPayoutAddress { mix_id } => client
+                .withdraw_operator_reward_to_payout_address(mix_id, None)
+                .ignore(),
             MixnetExecuteMsg::WithdrawDelegatorReward { mix_id } => {
                 client.withdraw_delegator_reward(mix_id, None).ignore()
             }
             MixnetExecuteMsg::WithdrawDelegatorRewardOnBehalf { mix_id, owner } => client
                 .withdraw_delegator_reward_on_behalf(owner.parse().unwrap(), mix_id, None)
                 .ignore(),
+            MixnetExecuteMsg::ClaimAllDelegatorRewards {} => {
+                client.claim_all_delegator_rewards(None).ignore()
+            }
             MixnetExecuteMsg::MigrateVestedMixNode { .. } => {
                 client.migrate_vested_mixnode(None).ignore()
             }
             MixnetExecuteMsg::MigrateVestedDelegation { mix_id } => {
                 client.migrate_vested_delegation(mix_id, None).ignore()
             }
+            // those will never be manually called by clients
+            MixnetExecuteMsg::MigrateVestedMixNodeOnBehalf { .. } => "explicitly_ignored".ignore(),
+            MixnetExecuteMsg::MigrateVestedDelegationOnBehalf { .. } => {
+                "explicitly_ignored".ignore()
+            }
 
             #[cfg(feature = "contract-testing")]
             MixnetExecuteMsg::TestingResolveAllPendingEvents { .. } => {
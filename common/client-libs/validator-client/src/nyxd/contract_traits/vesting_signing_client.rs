@@ -355,6 +355,19 @@ pub trait VestingSigningClient {
         .await
     }
 
+    async fn vesting_migrate_vested_stake(
+        &self,
+        limit: Option<u32>,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_vesting_contract(
+            fee,
+            VestingExecuteMsg::MigrateVestedStake { limit },
+            Vec::new(),
+        )
+        .await
+    }
+
     async fn vesting_create_family(
         &self,
         label: String,
@@ -561,6 +574,9 @@ mod tests {
             VestingExecuteMsg::UpdateLockedPledgeCap { address, cap } => client
                 .update_locked_pledge_cap(address.parse().unwrap(), cap, None)
                 .ignore(),
+            VestingExecuteMsg::MigrateVestedStake { limit } => {
+                client.vesting_migrate_vested_stake(limit, None).ignore()
+            }
             // those will never be manually called by clients
             ExecuteMsg::TrackMigratedMixnode { .. } => "explicitly_ignored".ignore(),
             ExecuteMsg::TrackMigratedDelegation { .. } => "explicitly_ignored".ignore(),
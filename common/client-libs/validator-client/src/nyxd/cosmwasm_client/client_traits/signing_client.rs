@@ -22,9 +22,10 @@ use cosmrs::feegrant::{
     AllowedMsgAllowance, BasicAllowance, MsgGrantAllowance, MsgRevokeAllowance,
 };
 use cosmrs::proto::cosmos::tx::signing::v1beta1::SignMode;
+use cosmrs::proto::ibc::applications::transfer::v1::MsgTransfer;
 use cosmrs::staking::{MsgDelegate, MsgUndelegate};
 use cosmrs::tx::{self, Msg};
-use cosmrs::{cosmwasm, AccountId, Any, Tx};
+use cosmrs::{cosmwasm, AccountId, Any, Coin as CosmosCoin, Tx};
 use log::debug;
 use serde::Serialize;
 use sha2::Digest;
@@ -364,14 +365,33 @@ where
         let messages = msgs
             .into_iter()
             .map(|(msg, funds)| {
-                cosmwasm::MsgExecuteContract {
+                Ok(cosmwasm::MsgExecuteContract {
                     sender: sender_address.clone(),
                     contract: contract_address.clone(),
                     msg: serde_json::to_vec(&msg)?,
                     funds: funds.into_iter().map(Into::into).collect(),
-                }
-                .to_any()
-                .map_err(|_| NyxdError::SerializationError("MsgExecuteContract".to_owned()))
+                })
+            })
+            .collect::<Result<Vec<_>, NyxdError>>()?;
+
+        self.execute_contract_messages(sender_address, messages, fee, memo)
+            .await
+    }
+
+    /// Executes the provided messages, possibly targeting different contracts, within a single transaction,
+    /// i.e. either all of them are going to succeed or none of them will be applied.
+    async fn execute_contract_messages(
+        &self,
+        sender_address: &AccountId,
+        msgs: Vec<cosmwasm::MsgExecuteContract>,
+        fee: Fee,
+        memo: impl Into<String> + Send + 'static,
+    ) -> Result<ExecuteResult, NyxdError> {
+        let messages = msgs
+            .into_iter()
+            .map(|msg| {
+                msg.to_any()
+                    .map_err(|_| NyxdError::SerializationError("MsgExecuteContract".to_owned()))
             })
             .collect::<Result<_, _>>()?;
 
@@ -442,6 +462,40 @@ where
             .check_response()
     }
 
+    /// Submits an ICS20 fungible token transfer over the specified port and channel.
+    /// `timeout_timestamp` is expressed as nanoseconds since unix epoch.
+    #[allow(clippy::too_many_arguments)]
+    async fn ibc_transfer(
+        &self,
+        sender_address: &AccountId,
+        receiver: String,
+        source_port: String,
+        source_channel: String,
+        token: Coin,
+        timeout_timestamp: u64,
+        fee: Fee,
+        memo: impl Into<String> + Send + 'static,
+    ) -> Result<TxResponse, NyxdError> {
+        let transfer_msg = MsgTransfer {
+            source_port,
+            source_channel,
+            token: Some(CosmosCoin::from(token).into()),
+            sender: sender_address.to_string(),
+            receiver,
+            timeout_height: None,
+            timeout_timestamp,
+            memo: String::new(),
+        };
+        let transfer_msg = Any {
+            type_url: "/ibc.applications.transfer.v1.MsgTransfer".to_owned(),
+            value: prost::Message::encode_to_vec(&transfer_msg),
+        };
+
+        self.sign_and_broadcast(sender_address, vec![transfer_msg], fee, memo)
+            .await?
+            .check_response()
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn grant_allowance(
         &self,
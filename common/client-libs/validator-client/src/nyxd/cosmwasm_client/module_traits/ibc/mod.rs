@@ -0,0 +1,187 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nyxd::error::NyxdError;
+use crate::nyxd::helpers::find_tx_attribute;
+use crate::nyxd::TxResponse;
+use cosmrs::tendermint::Hash;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+pub mod query;
+
+pub const ICS20_TRANSFER_PORT: &str = "transfer";
+pub const DEFAULT_IBC_TRANSFER_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+const SEND_PACKET_EVENT: &str = "send_packet";
+
+/// Description of an ICS20 channel between nyx and some counterparty chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IbcChannel {
+    /// Human readable identifier of the counterparty chain, e.g. `osmosis-1`.
+    pub counterparty_chain_id: String,
+
+    /// Bech32 prefix used for addresses on the counterparty chain.
+    pub counterparty_prefix: String,
+
+    /// Port on the nyx side of the channel.
+    #[serde(default = "default_transfer_port")]
+    pub source_port: String,
+
+    /// Channel identifier on the nyx side, e.g. `channel-0`.
+    pub source_channel: String,
+
+    /// Channel identifier on the counterparty side.
+    pub counterparty_channel: String,
+
+    /// Timeout applied to transfers over this channel if none is explicitly specified.
+    #[serde(default = "default_transfer_timeout", with = "humantime_serde")]
+    pub default_timeout: Duration,
+}
+
+fn default_transfer_port() -> String {
+    ICS20_TRANSFER_PORT.to_string()
+}
+
+fn default_transfer_timeout() -> Duration {
+    DEFAULT_IBC_TRANSFER_TIMEOUT
+}
+
+impl IbcChannel {
+    pub fn new(
+        counterparty_chain_id: impl Into<String>,
+        counterparty_prefix: impl Into<String>,
+        source_channel: impl Into<String>,
+        counterparty_channel: impl Into<String>,
+    ) -> Self {
+        IbcChannel {
+            counterparty_chain_id: counterparty_chain_id.into(),
+            counterparty_prefix: counterparty_prefix.into(),
+            source_port: default_transfer_port(),
+            source_channel: source_channel.into(),
+            counterparty_channel: counterparty_channel.into(),
+            default_timeout: DEFAULT_IBC_TRANSFER_TIMEOUT,
+        }
+    }
+
+    #[must_use]
+    pub fn with_default_timeout(mut self, default_timeout: Duration) -> Self {
+        self.default_timeout = default_timeout;
+        self
+    }
+
+    /// Returns the voucher denom that tokens of the provided (native) denom are going to be
+    /// represented as on the counterparty chain, i.e. `ibc/{SHA256(port/channel/denom)}`.
+    pub fn counterparty_denom(&self, base_denom: &str) -> String {
+        let trace = format!(
+            "{}/{}/{base_denom}",
+            ICS20_TRANSFER_PORT, self.counterparty_channel
+        );
+        format!("ibc/{:X}", Sha256::digest(trace.as_bytes()))
+    }
+}
+
+/// Information required to track an ICS20 transfer submitted on nyx until it's been relayed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IbcTransfer {
+    pub tx_hash: Hash,
+    pub sequence: u64,
+    pub source_port: String,
+    pub source_channel: String,
+    pub destination_port: String,
+    pub destination_channel: String,
+
+    /// Timeout of the packet expressed as nanoseconds since unix epoch.
+    pub timeout_timestamp: u64,
+}
+
+impl IbcTransfer {
+    /// Attempts to recover transfer information from the `send_packet` event emitted by the
+    /// transaction that included `MsgTransfer`.
+    pub fn from_tx_response(tx: &TxResponse) -> Result<Self, NyxdError> {
+        let attribute = |key: &str| {
+            find_tx_attribute(tx, SEND_PACKET_EVENT, key).ok_or_else(|| {
+                NyxdError::MissingIbcPacketAttribute {
+                    attribute: key.to_string(),
+                }
+            })
+        };
+        let parse_u64 = |key: &str| {
+            attribute(key)?
+                .parse::<u64>()
+                .map_err(|_| NyxdError::DeserializationError(format!("{SEND_PACKET_EVENT}.{key}")))
+        };
+
+        Ok(IbcTransfer {
+            tx_hash: tx.hash,
+            sequence: parse_u64("packet_sequence")?,
+            source_port: attribute("packet_src_port")?,
+            source_channel: attribute("packet_src_channel")?,
+            destination_port: attribute("packet_dst_port")?,
+            destination_channel: attribute("packet_dst_channel")?,
+            timeout_timestamp: parse_u64("packet_timeout_timestamp")?,
+        })
+    }
+
+    pub fn has_expired(&self, chain_time_nanos: u64) -> bool {
+        self.timeout_timestamp != 0 && chain_time_nanos >= self.timeout_timestamp
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PacketStatus {
+    /// The packet commitment still exists on nyx and the timeout has not yet been reached.
+    Pending,
+
+    /// The packet commitment still exists on nyx, but its timeout has already passed.
+    /// The tokens are going to be refunded once a relayer submits the timeout proof.
+    TimedOut,
+
+    /// The packet commitment has been removed from nyx, meaning the packet has either been
+    /// acknowledged by the counterparty or its timeout has already been processed.
+    Completed,
+}
+
+impl PacketStatus {
+    pub fn is_in_flight(&self) -> bool {
+        !matches!(self, PacketStatus::Completed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counterparty_denom_matches_ics20_trace_hash() {
+        // well-known voucher denom of ATOM on osmosis
+        let channel = IbcChannel::new("osmosis-1", "osmo", "channel-141", "channel-0");
+        assert_eq!(
+            channel.counterparty_denom("uatom"),
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+        );
+    }
+
+    #[test]
+    fn transfer_without_timeout_never_expires() {
+        let transfer = IbcTransfer {
+            tx_hash: Hash::None,
+            sequence: 1,
+            source_port: ICS20_TRANSFER_PORT.to_string(),
+            source_channel: "channel-0".to_string(),
+            destination_port: ICS20_TRANSFER_PORT.to_string(),
+            destination_channel: "channel-1".to_string(),
+            timeout_timestamp: 0,
+        };
+        assert!(!transfer.has_expired(u64::MAX));
+
+        let transfer = IbcTransfer {
+            timeout_timestamp: 100,
+            ..transfer
+        };
+        assert!(!transfer.has_expired(99));
+        assert!(transfer.has_expired(100));
+    }
+}
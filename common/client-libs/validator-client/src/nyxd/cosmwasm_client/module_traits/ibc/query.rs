@@ -0,0 +1,114 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{IbcTransfer, PacketStatus};
+use crate::nyxd::error::NyxdError;
+use crate::nyxd::CosmWasmClient;
+use async_trait::async_trait;
+use cosmrs::proto::ibc::core::channel::v1::{
+    QueryPacketCommitmentRequest, QueryPacketCommitmentResponse,
+};
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::sleep;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::Instant;
+
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::std::Instant;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio::sleep;
+
+pub const DEFAULT_IBC_PACKET_POLLING_RATE: Duration = Duration::from_secs(10);
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait IbcQueryClient: CosmWasmClient {
+    /// Returns the commitment of the specified packet if it still exists on chain,
+    /// i.e. if it has neither been acknowledged nor timed out.
+    async fn packet_commitment(
+        &self,
+        port_id: String,
+        channel_id: String,
+        sequence: u64,
+    ) -> Result<Option<Vec<u8>>, NyxdError> {
+        let path = Some("/ibc.core.channel.v1.Query/PacketCommitment".to_owned());
+
+        let req = QueryPacketCommitmentRequest {
+            port_id,
+            channel_id,
+            sequence,
+        };
+
+        match self
+            .make_abci_query::<_, QueryPacketCommitmentResponse>(path, req)
+            .await
+        {
+            Ok(res) if res.commitment.is_empty() => Ok(None),
+            Ok(res) => Ok(Some(res.commitment)),
+            // ibc-go returns a `NotFound` error rather than an empty response for removed commitments
+            Err(NyxdError::AbciError { log, .. }) if log.contains("not found") => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn ibc_packet_status(&self, transfer: &IbcTransfer) -> Result<PacketStatus, NyxdError> {
+        let commitment = self
+            .packet_commitment(
+                transfer.source_port.clone(),
+                transfer.source_channel.clone(),
+                transfer.sequence,
+            )
+            .await?;
+
+        if commitment.is_none() {
+            return Ok(PacketStatus::Completed);
+        }
+
+        // the timeout is evaluated against the block time of the chain rather than the local clock
+        let chain_time = self.status().await?.sync_info.latest_block_time;
+        let chain_time_nanos = u64::try_from(chain_time.unix_timestamp_nanos()).unwrap_or_default();
+
+        if transfer.has_expired(chain_time_nanos) {
+            Ok(PacketStatus::TimedOut)
+        } else {
+            Ok(PacketStatus::Pending)
+        }
+    }
+
+    /// Polls the chain until the packet is no longer pending or the specified duration has elapsed,
+    /// in which case the last observed status is returned.
+    async fn wait_for_ibc_packet(
+        &self,
+        transfer: &IbcTransfer,
+        poll_interval: Option<Duration>,
+        max_wait: Duration,
+    ) -> Result<PacketStatus, NyxdError> {
+        let poll_interval = poll_interval.unwrap_or(DEFAULT_IBC_PACKET_POLLING_RATE);
+        let start = Instant::now();
+
+        loop {
+            let status = self.ibc_packet_status(transfer).await?;
+            if status != PacketStatus::Pending {
+                return Ok(status);
+            }
+
+            if Instant::now().duration_since(start) >= max_wait {
+                return Ok(status);
+            }
+
+            log::debug!(
+                "IBC packet {} on {}/{} is still pending...",
+                transfer.sequence,
+                transfer.source_port,
+                transfer.source_channel
+            );
+            sleep(poll_interval).await;
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T> IbcQueryClient for T where T: CosmWasmClient {}
@@ -1,8 +1,10 @@
 // Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod ibc;
 pub mod slashing;
 pub mod staking;
 
+pub use ibc::query::IbcQueryClient;
 pub use staking::query::StakingQueryClient;
 // pub use slashing::query
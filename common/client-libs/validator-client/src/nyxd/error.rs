@@ -38,6 +38,9 @@ pub enum NyxdError {
     #[error("cosmwasm attribute not found")]
     ComswasmAttributeNotFound,
 
+    #[error("the transaction did not emit 'send_packet' event with the '{attribute}' attribute")]
+    MissingIbcPacketAttribute { attribute: String },
+
     #[error("Failed to derive account address")]
     AccountDerivationError,
 
@@ -7,6 +7,7 @@
 #![allow(unexpected_cfgs)]
 
 use crate::nyxd::contract_traits::{NymContractsProvider, TypedNymContracts};
+use crate::nyxd::cosmwasm_client::module_traits::ibc::{IbcChannel, IbcTransfer, PacketStatus};
 use crate::nyxd::cosmwasm_client::types::{
     ChangeAdminResult, ContractCodeId, ExecuteResult, InstantiateOptions, InstantiateResult,
     MigrateResult, SequenceResponse, SimulateResponse, UploadResult,
@@ -27,7 +28,7 @@ use cosmwasm_std::Addr;
 use nym_network_defaults::{ChainDetails, NymNetworkDetails};
 use serde::{de::DeserializeOwned, Serialize};
 use std::fmt::Debug;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tendermint_rpc::endpoint::block::Response as BlockResponse;
 use tendermint_rpc::endpoint::*;
 use tendermint_rpc::{Error as TendermintRpcError, Order};
@@ -36,7 +37,7 @@ use url::Url;
 pub use crate::nyxd::{
     cosmwasm_client::{
         client_traits::{CosmWasmClient, SigningCosmWasmClient},
-        module_traits::{self, StakingQueryClient},
+        module_traits::{self, IbcQueryClient, StakingQueryClient},
     },
     fee::Fee,
 };
@@ -321,6 +322,13 @@ where
         self.client.get_height().await
     }
 
+    pub async fn ibc_packet_status(
+        &self,
+        transfer: &IbcTransfer,
+    ) -> Result<PacketStatus, NyxdError> {
+        self.client.ibc_packet_status(transfer).await
+    }
+
     /// Obtains the hash of a block specified by the provided height.
     ///
     /// # Arguments
@@ -454,6 +462,47 @@ where
             .await
     }
 
+    /// Send funds to an account on another chain over the provided ICS20 channel.
+    /// If no timeout is specified, the default timeout of the channel is used.
+    pub async fn ibc_transfer(
+        &self,
+        channel: &IbcChannel,
+        receiver: &AccountId,
+        amount: Coin,
+        timeout: Option<Duration>,
+        memo: impl Into<String> + Send + 'static,
+        fee: Option<Fee>,
+    ) -> Result<TxResponse, NyxdError> {
+        if receiver.prefix() != channel.counterparty_prefix {
+            return Err(NyxdError::UnexpectedBech32Prefix {
+                got: receiver.prefix().to_string(),
+                expected: channel.counterparty_prefix.clone(),
+            });
+        }
+
+        // use the chain time rather than the local clock so that the timeout is meaningful
+        // for the counterparty light client
+        let timeout = timeout.unwrap_or(channel.default_timeout);
+        let chain_time = self.get_current_block_timestamp().await?;
+        let timeout_timestamp = u64::try_from(chain_time.unix_timestamp_nanos())
+            .unwrap_or_default()
+            .saturating_add(timeout.as_nanos() as u64);
+
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.config.simulated_gas_multiplier)));
+        self.client
+            .ibc_transfer(
+                &self.address(),
+                receiver.to_string(),
+                channel.source_port.clone(),
+                channel.source_channel.clone(),
+                amount,
+                timeout_timestamp,
+                fee,
+                memo,
+            )
+            .await
+    }
+
     /// Send funds from one address to multiple others
     pub async fn send_multiple(
         &self,
@@ -538,6 +587,19 @@ where
             .await
     }
 
+    /// Executes the provided messages, possibly targeting different contracts, atomically within a single transaction.
+    pub async fn execute_contract_messages(
+        &self,
+        msgs: Vec<cosmwasm::MsgExecuteContract>,
+        fee: Option<Fee>,
+        memo: impl Into<String> + Send + 'static,
+    ) -> Result<ExecuteResult, NyxdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.config.simulated_gas_multiplier)));
+        self.client
+            .execute_contract_messages(&self.address(), msgs, fee, memo)
+            .await
+    }
+
     pub async fn upload(
         &self,
         wasm_code: Vec<u8>,
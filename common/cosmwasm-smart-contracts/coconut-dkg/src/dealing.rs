@@ -5,6 +5,7 @@ use crate::types::{ChunkIndex, DealingIndex, EpochId, PartialContractDealingData
 use contracts_common::dealings::ContractSafeBytes;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Addr;
+use sha2::{Digest, Sha256};
 use std::collections::{BTreeMap, HashMap};
 
 /// Defines the maximum size of a dealing chunk. Currently set to 2kB
@@ -19,6 +20,14 @@ pub const MAX_DEALING_CHUNKS: usize = MAX_DEALING_SIZE / MAX_DEALING_CHUNK_SIZE;
 // 2 public attributes, 2 private attributes, 1 fixed for coconut credential
 pub const DEFAULT_DEALINGS: usize = 2 + 2 + 1;
 
+pub type DealingChecksum = ContractSafeBytes;
+
+/// Computes the checksum of the full (i.e. non-chunked) dealing that's verified by the contract
+/// once all of its chunks have been submitted.
+pub fn dealing_checksum(dealing_bytes: &[u8]) -> DealingChecksum {
+    Sha256::digest(dealing_bytes).to_vec().into()
+}
+
 pub fn chunk_dealing(
     dealing_index: DealingIndex,
     dealing_bytes: Vec<u8>,
@@ -109,10 +118,19 @@ pub struct DealingMetadata {
     pub dealing_index: DealingIndex,
 
     pub submitted_chunks: BTreeMap<ChunkIndex, SubmittedChunk>,
+
+    /// Optional checksum of the full dealing. If provided, the contract assembles all the chunks
+    /// once they have been submitted and verifies them against it before marking the dealing as complete.
+    #[serde(default)]
+    pub checksum: Option<DealingChecksum>,
 }
 
 impl DealingMetadata {
-    pub fn new(dealing_index: DealingIndex, chunks: Vec<DealingChunkInfo>) -> Self {
+    pub fn new(
+        dealing_index: DealingIndex,
+        chunks: Vec<DealingChunkInfo>,
+        checksum: Option<DealingChecksum>,
+    ) -> Self {
         DealingMetadata {
             dealing_index,
             submitted_chunks: chunks
@@ -120,6 +138,7 @@ impl DealingMetadata {
                 .enumerate()
                 .map(|(id, chunk)| (id as ChunkIndex, chunk.into()))
                 .collect(),
+            checksum,
         }
     }
 
@@ -127,6 +146,14 @@ impl DealingMetadata {
         self.submitted_chunks.values().all(|c| c.submitted())
     }
 
+    /// Returns the index of the first chunk that hasn't yet been submitted, if any.
+    pub fn first_missing_chunk(&self) -> Option<ChunkIndex> {
+        self.submitted_chunks
+            .iter()
+            .find(|(_, c)| !c.submitted())
+            .map(|(id, _)| *id)
+    }
+
     pub fn total_size(&self) -> usize {
         self.submitted_chunks
             .values()
@@ -255,6 +282,29 @@ impl DealerDealingsStatusResponse {
     }
 }
 
+/// Position from which a dealer should resume its dealings submission.
+#[cw_serde]
+pub struct DealingSubmissionCursor {
+    pub dealing_index: DealingIndex,
+
+    /// Indicates whether the metadata for this dealing still has to be committed.
+    pub metadata_missing: bool,
+
+    /// The first chunk of this dealing that hasn't been committed yet.
+    /// It's always `None` if the metadata is missing.
+    pub chunk_index: Option<ChunkIndex>,
+}
+
+#[cw_serde]
+pub struct DealerSubmissionCursorResponse {
+    pub epoch_id: EpochId,
+
+    pub dealer: Addr,
+
+    /// The next expected submission of this dealer. If `None`, all dealings have been fully submitted.
+    pub cursor: Option<DealingSubmissionCursor>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -287,4 +337,23 @@ mod tests {
             assert_eq!(chunks.last().unwrap().size, expected_last as u64);
         }
     }
+
+    #[test]
+    fn finding_first_missing_chunk() {
+        let mut metadata = DealingMetadata::new(0, DealingChunkInfo::construct(100, 30), None);
+        assert_eq!(metadata.first_missing_chunk(), Some(0));
+
+        metadata
+            .submitted_chunks
+            .get_mut(&0)
+            .unwrap()
+            .status
+            .submission_height = Some(42);
+        assert_eq!(metadata.first_missing_chunk(), Some(1));
+
+        for chunk in metadata.submitted_chunks.values_mut() {
+            chunk.status.submission_height = Some(42);
+        }
+        assert_eq!(metadata.first_missing_chunk(), None);
+    }
 }
@@ -3,3 +3,7 @@
 
 pub const NODE_INDEX: &str = "node_index";
 pub const DKG_PROPOSAL_ID: &str = "proposal_id";
+
+pub const DEALING_CHECKSUM_MISMATCH_EVENT: &str = "dealing_checksum_mismatch";
+pub const DEALING_INDEX: &str = "dealing_index";
+pub const EPOCH_ID: &str = "epoch_id";
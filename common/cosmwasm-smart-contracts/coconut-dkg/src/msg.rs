@@ -1,11 +1,12 @@
 // Copyright 2022-2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::dealing::{DealingChunkInfo, PartialContractDealing};
+use crate::dealing::{DealingChecksum, DealingChunkInfo, PartialContractDealing};
 use crate::types::{
-    ChunkIndex, DealingIndex, EncodedBTEPublicKeyWithProof, EpochId, TimeConfiguration,
+    ChunkIndex, DealingIndex, DkgPublicParameters, EncodedBTEPublicKeyWithProof, EpochId,
+    TimeConfiguration,
 };
-use crate::verification_key::VerificationKeyShare;
+use crate::verification_key::{MasterVerificationKey, VerificationKeyShare};
 use contracts_common::IdentityKey;
 use cosmwasm_schema::cw_serde;
 
@@ -16,11 +17,11 @@ use crate::{
         RegisteredDealerDetails,
     },
     dealing::{
-        DealerDealingsStatusResponse, DealingChunkResponse, DealingChunkStatusResponse,
-        DealingMetadataResponse, DealingStatusResponse,
+        DealerDealingsStatusResponse, DealerSubmissionCursorResponse, DealingChunkResponse,
+        DealingChunkStatusResponse, DealingMetadataResponse, DealingStatusResponse,
     },
-    types::{Epoch, State, StateAdvanceResponse},
-    verification_key::{PagedVKSharesResponse, VkShareResponse},
+    types::{Epoch, PublicParametersResponse, State, StateAdvanceResponse},
+    verification_key::{MasterVerificationKeyResponse, PagedVKSharesResponse, VkShareResponse},
 };
 #[cfg(feature = "schema")]
 use cosmwasm_schema::QueryResponses;
@@ -39,7 +40,12 @@ pub struct InstantiateMsg {
 #[cw_serde]
 pub enum ExecuteMsg {
     // we could have just re-used AdvanceEpochState, but imo an explicit message is better
-    InitiateDkg {},
+    InitiateDkg {
+        /// Public parameters all the dealers are going to use. If not provided, the dealers
+        /// fall back to the parameters compiled into their binaries.
+        #[serde(default)]
+        public_parameters: Option<DkgPublicParameters>,
+    },
 
     RegisterDealer {
         bte_key_with_proof: EncodedBTEPublicKeyWithProof,
@@ -52,6 +58,10 @@ pub enum ExecuteMsg {
         dealing_index: DealingIndex,
         chunks: Vec<DealingChunkInfo>,
         resharing: bool,
+
+        /// Optional checksum of the full dealing used for verifying the assembled chunks.
+        #[serde(default)]
+        checksum: Option<DealingChecksum>,
     },
 
     CommitDealingsChunk {
@@ -68,6 +78,12 @@ pub enum ExecuteMsg {
         resharing: bool,
     },
 
+    /// Attest to the master verification key aggregated from all verified shares of the current epoch.
+    /// The key gets finalized once the threshold number of dealers submitted the same value.
+    CommitMasterVerificationKey {
+        key: MasterVerificationKey,
+    },
+
     AdvanceEpochState {},
 
     TriggerReset {},
@@ -84,6 +100,10 @@ pub enum QueryMsg {
     #[cfg_attr(feature = "schema", returns(Epoch))]
     GetCurrentEpochState {},
 
+    /// Gets the public parameters published when the DKG was initiated.
+    #[cfg_attr(feature = "schema", returns(PublicParametersResponse))]
+    GetPublicParameters {},
+
     #[cfg_attr(feature = "schema", returns(u64))]
     GetCurrentEpochThreshold {},
 
@@ -124,6 +144,10 @@ pub enum QueryMsg {
     #[cfg_attr(feature = "schema", returns(DealerDealingsStatusResponse))]
     GetDealerDealingsStatus { epoch_id: EpochId, dealer: String },
 
+    /// Gets the position from which the dealer should resume submitting its dealings for the given epoch.
+    #[cfg_attr(feature = "schema", returns(DealerSubmissionCursorResponse))]
+    GetDealerSubmissionCursor { epoch_id: EpochId, dealer: String },
+
     #[cfg_attr(feature = "schema", returns(DealingStatusResponse))]
     GetDealingStatus {
         epoch_id: EpochId,
@@ -157,6 +181,11 @@ pub enum QueryMsg {
         start_after: Option<String>,
     },
 
+    /// Gets the aggregated master verification key of the specified epoch
+    /// (if it has already been finalized).
+    #[cfg_attr(feature = "schema", returns(MasterVerificationKeyResponse))]
+    GetMasterVerificationKey { epoch_id: EpochId },
+
     /// Gets the stored contract version information that's required by the CW2 spec interface for migrations.
     #[serde(rename = "get_cw2_contract_version")]
     #[cfg_attr(feature = "schema", returns(cw2::ContractVersion))]
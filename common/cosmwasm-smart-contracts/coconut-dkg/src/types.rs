@@ -117,6 +117,21 @@ pub struct State {
     pub key_size: u32,
 }
 
+/// Public cryptographic parameters used throughout the DKG, published in the contract at initiation
+/// so that all the dealers and verifiers are guaranteed to use identical values.
+#[cw_serde]
+pub struct DkgPublicParameters {
+    /// Serialized parameters of the BTE (binary tree encryption) setup, i.e. the group elements
+    /// used for encrypting the shares and constructing the associated proofs.
+    pub bte_params: ContractSafeBytes,
+}
+
+#[cw_serde]
+pub struct PublicParametersResponse {
+    /// The published parameters, if they were provided when the DKG was initiated.
+    pub parameters: Option<DkgPublicParameters>,
+}
+
 #[cw_serde]
 #[derive(Copy, Default)]
 pub struct StateProgress {
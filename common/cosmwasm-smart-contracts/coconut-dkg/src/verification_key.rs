@@ -9,6 +9,7 @@ use cw_utils::Expiration;
 use nym_multisig_contract_common::msg::ExecuteMsg as MultisigExecuteMsg;
 
 pub type VerificationKeyShare = String;
+pub type MasterVerificationKey = String;
 
 #[cw_serde]
 pub struct ContractVKShare {
@@ -36,6 +37,15 @@ pub struct PagedVKSharesResponse {
     pub start_next_after: Option<Addr>,
 }
 
+#[cw_serde]
+pub struct MasterVerificationKeyResponse {
+    pub epoch_id: EpochId,
+
+    /// The aggregated master verification key. It's only available once the DKG for the epoch
+    /// has completed and the threshold number of dealers attested to the same value.
+    pub key: Option<MasterVerificationKey>,
+}
+
 pub fn to_cosmos_msg(
     owner: Addr,
     resharing: bool,
@@ -13,6 +13,10 @@ pub mod verifier;
 
 pub type Nonce = u32;
 
+/// Header prepended to the human-readable rendering of a [`SignableMessage`], in the spirit of EIP-191,
+/// so that the displayed preview can never be confused with an arbitrary transaction.
+pub const HUMAN_READABLE_HEADER: &str = "Nym Signed Message";
+
 // define this type explicitly for [hopefully] better usability
 // (so you wouldn't need to worry about whether you should use bytes, bs58, etc.)
 #[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -98,6 +102,41 @@ pub trait SigningPurpose {
     fn message_type() -> MessageType;
 }
 
+/// Canonical field-by-field rendering of signable content that can be shown to the user
+/// (for example by the wallet or a hardware wallet) before they sign the underlying plaintext.
+///
+/// Note that the rendering is purely informational: the signature is always produced over
+/// [`SignableMessage::to_plaintext`].
+pub trait HumanReadable {
+    fn human_readable_fields(&self) -> Vec<(String, String)>;
+}
+
+/// Helper for rendering nested [`HumanReadable`] content under the provided prefix,
+/// i.e. `host` of `mix_node` becomes `mix_node.host`.
+pub fn prefixed_human_readable_fields<T: HumanReadable + ?Sized>(
+    prefix: &str,
+    content: &T,
+) -> Vec<(String, String)> {
+    content
+        .human_readable_fields()
+        .into_iter()
+        .map(|(field, value)| (format!("{prefix}.{field}"), value))
+        .collect()
+}
+
+/// Renders the provided coins in a stable format, i.e. `100unym, 42unyx`.
+pub fn human_readable_coins(coins: &[Coin]) -> String {
+    if coins.is_empty() {
+        return "none".to_string();
+    }
+
+    coins
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct MessageType(String);
@@ -106,6 +145,10 @@ impl MessageType {
     pub fn new<S: Into<String>>(typ: S) -> Self {
         MessageType(typ.into())
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 impl<T> From<T> for MessageType
@@ -131,6 +174,15 @@ impl SigningAlgorithm {
     }
 }
 
+impl Display for SigningAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SigningAlgorithm::Ed25519 => write!(f, "ed25519"),
+            SigningAlgorithm::Secp256k1 => write!(f, "secp256k1"),
+        }
+    }
+}
+
 // TODO: maybe move this one to repo-wide common?
 // TODO: should it perhaps also include the public key itself?
 #[derive(Serialize, Deserialize)]
@@ -191,6 +243,32 @@ where
         self.to_plaintext().map(|s| bs58::encode(s).into_string())
     }
 
+    /// Produces the canonical human-readable preview of this message.
+    /// It consists of the [`HUMAN_READABLE_HEADER`] followed by the length of the canonical plaintext
+    /// (that is the data that's actually going to get signed) and then each field on a separate line.
+    pub fn to_human_readable(&self) -> StdResult<String>
+    where
+        T: Serialize + HumanReadable,
+    {
+        let plaintext_len = self.to_plaintext()?.len();
+
+        let mut lines = vec![
+            format!("{HUMAN_READABLE_HEADER}:"),
+            format!("plaintext length: {plaintext_len}"),
+            format!("message type: {}", self.message_type.as_str()),
+            format!("nonce: {}", self.nonce),
+            format!("algorithm: {}", self.algorithm),
+        ];
+        lines.extend(
+            self.content
+                .human_readable_fields()
+                .into_iter()
+                .map(|(field, value)| format!("{field}: {value}")),
+        );
+
+        Ok(lines.join("\n"))
+    }
+
     pub fn try_from_bytes(bytes: &[u8]) -> StdResult<SignableMessage<T>>
     where
         T: DeserializeOwned,
@@ -249,6 +327,20 @@ impl<T> ContractMessageContent<T> {
     }
 }
 
+impl<T> HumanReadable for ContractMessageContent<T>
+where
+    T: HumanReadable,
+{
+    fn human_readable_fields(&self) -> Vec<(String, String)> {
+        let mut fields = vec![
+            ("sender".to_string(), self.sender.to_string()),
+            ("funds".to_string(), human_readable_coins(&self.funds)),
+        ];
+        fields.extend(self.data.human_readable_fields());
+        fields
+    }
+}
+
 impl<T> From<ContractMessageContent<T>> for LegacyContractMessageContent<T> {
     fn from(value: ContractMessageContent<T>) -> Self {
         LegacyContractMessageContent {
@@ -276,3 +368,24 @@ where
         T::message_type()
     }
 }
+
+impl<T> HumanReadable for LegacyContractMessageContent<T>
+where
+    T: HumanReadable,
+{
+    fn human_readable_fields(&self) -> Vec<(String, String)> {
+        let proxy = self
+            .proxy
+            .as_ref()
+            .map(|p| p.to_string())
+            .unwrap_or_else(|| "none".to_string());
+
+        let mut fields = vec![
+            ("sender".to_string(), self.sender.to_string()),
+            ("proxy".to_string(), proxy),
+            ("funds".to_string(), human_readable_coins(&self.funds)),
+        ];
+        fields.extend(self.data.human_readable_fields());
+        fields
+    }
+}
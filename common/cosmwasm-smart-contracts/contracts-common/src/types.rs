@@ -4,7 +4,7 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Decimal;
 use cosmwasm_std::OverflowError;
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{StdError, Uint128, Uint256};
 use serde::de::Error;
 use serde::{Deserialize, Deserializer};
 use std::fmt::{self, Display, Formatter};
@@ -32,6 +32,16 @@ pub enum ContractsCommonError {
     },
 }
 
+/// Number of basis points making up 100%.
+pub const BASIS_POINTS_IN_HUNDRED_PERCENT: u32 = 10_000;
+
+/// Explicit rounding mode to use when the result of an operation can't be represented exactly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    Floor,
+    Ceil,
+}
+
 /// Percent represents a value between 0 and 100%
 /// (i.e. between 0.0 and 1.0)
 #[cw_serde]
@@ -63,6 +73,28 @@ impl Percent {
         Percent::new(Decimal::percent(value))
     }
 
+    /// Creates a percent out of the provided number of basis points, i.e. hundredths of a percent.
+    pub fn from_basis_points(value: u32) -> Result<Self, ContractsCommonError> {
+        Percent::new(Decimal::from_ratio(value, BASIS_POINTS_IN_HUNDRED_PERCENT))
+    }
+
+    /// Returns the number of basis points represented by this percent, rounded according to the provided mode.
+    pub fn to_basis_points(&self, rounding: Rounding) -> u32 {
+        // a single basis point corresponds to 10^14 atomics of the underlying decimal
+        let atomics_per_point = Uint128::new(100_000_000_000_000);
+        let atomics = self.0.atomics();
+        let floor = atomics / atomics_per_point;
+
+        let points = match rounding {
+            Rounding::Floor => floor,
+            Rounding::Ceil if (atomics % atomics_per_point).is_zero() => floor,
+            Rounding::Ceil => floor + Uint128::one(),
+        };
+
+        // we know the cast from u128 to u32 is a safe one since the internal value must be within 0 - 1 range
+        points.u128() as u32
+    }
+
     pub fn value(&self) -> Decimal {
         self.0
     }
@@ -76,6 +108,63 @@ impl Percent {
     pub fn checked_pow(&self, exp: u32) -> Result<Self, OverflowError> {
         self.0.checked_pow(exp).map(Percent)
     }
+
+    /// Returns the remaining part of the whole, i.e. `100% - self`.
+    pub fn complement(&self) -> Self {
+        // this can't underflow as the value is never greater than 100%
+        Percent(Decimal::one() - self.0)
+    }
+
+    pub fn checked_add(&self, other: Percent) -> Result<Self, ContractsCommonError> {
+        let sum = self.0.checked_add(other.0).map_err(StdError::from)?;
+        Percent::new(sum)
+    }
+
+    pub fn checked_sub(&self, other: Percent) -> Result<Self, OverflowError> {
+        self.0.checked_sub(other.0).map(Percent)
+    }
+
+    /// Calculates this percent of the provided amount, i.e. `amount * self`,
+    /// rounded according to the provided mode.
+    pub fn checked_mul_amount(
+        &self,
+        amount: Uint128,
+        rounding: Rounding,
+    ) -> Result<Uint128, ContractsCommonError> {
+        let denominator = Uint256::from(Decimal::one().atomics());
+        let numerator = amount.full_mul(self.0.atomics());
+        let floor = numerator.checked_div(denominator).map_err(StdError::from)?;
+
+        let result = match rounding {
+            Rounding::Floor => floor,
+            Rounding::Ceil if (numerator % denominator).is_zero() => floor,
+            Rounding::Ceil => floor.checked_add(Uint256::one()).map_err(StdError::from)?,
+        };
+
+        Ok(Uint128::try_from(result).map_err(StdError::from)?)
+    }
+
+    /// Increases the provided amount by this percent of it, i.e. `amount + amount * self`,
+    /// with the added part rounded according to the provided mode.
+    pub fn checked_add_to_amount(
+        &self,
+        amount: Uint128,
+        rounding: Rounding,
+    ) -> Result<Uint128, ContractsCommonError> {
+        let share = self.checked_mul_amount(amount, rounding)?;
+        Ok(amount.checked_add(share).map_err(StdError::from)?)
+    }
+
+    /// Decreases the provided amount by this percent of it, i.e. `amount - amount * self`,
+    /// with the subtracted part rounded according to the provided mode.
+    pub fn checked_sub_from_amount(
+        &self,
+        amount: Uint128,
+        rounding: Rounding,
+    ) -> Result<Uint128, ContractsCommonError> {
+        let share = self.checked_mul_amount(amount, rounding)?;
+        Ok(amount.checked_sub(share).map_err(StdError::from)?)
+    }
 }
 
 impl Display for Percent {
@@ -223,6 +312,96 @@ mod tests {
         )
     }
 
+    #[test]
+    fn percent_basis_points() {
+        assert_eq!(
+            Percent::from_basis_points(1234).unwrap(),
+            Percent::from_str("0.1234").unwrap()
+        );
+        assert_eq!(Percent::from_basis_points(0).unwrap(), Percent::zero());
+        assert_eq!(
+            Percent::from_basis_points(BASIS_POINTS_IN_HUNDRED_PERCENT).unwrap(),
+            Percent::hundred()
+        );
+        assert!(Percent::from_basis_points(BASIS_POINTS_IN_HUNDRED_PERCENT + 1).is_err());
+
+        let p = Percent::from_str("0.12345").unwrap();
+        assert_eq!(p.to_basis_points(Rounding::Floor), 1234);
+        assert_eq!(p.to_basis_points(Rounding::Ceil), 1235);
+
+        let p = Percent::from_basis_points(42).unwrap();
+        assert_eq!(p.to_basis_points(Rounding::Floor), 42);
+        assert_eq!(p.to_basis_points(Rounding::Ceil), 42);
+    }
+
+    #[test]
+    fn percent_arithmetic() {
+        let a = Percent::from_percentage_value(60).unwrap();
+        let b = Percent::from_percentage_value(30).unwrap();
+
+        assert_eq!(
+            a.checked_add(b).unwrap(),
+            Percent::from_percentage_value(90).unwrap()
+        );
+        assert!(a.checked_add(a).is_err());
+        assert_eq!(
+            a.checked_sub(b).unwrap(),
+            Percent::from_percentage_value(30).unwrap()
+        );
+        assert!(b.checked_sub(a).is_err());
+        assert_eq!(a.complement(), Percent::from_percentage_value(40).unwrap());
+        assert_eq!(Percent::hundred().complement(), Percent::zero());
+    }
+
+    #[test]
+    fn percent_amount_arithmetic() {
+        let p = Percent::from_str("0.333").unwrap();
+        let amount = Uint128::new(1000);
+        assert_eq!(
+            p.checked_mul_amount(amount, Rounding::Floor).unwrap(),
+            Uint128::new(333)
+        );
+        assert_eq!(
+            p.checked_mul_amount(amount, Rounding::Ceil).unwrap(),
+            Uint128::new(333)
+        );
+
+        let amount = Uint128::new(10);
+        assert_eq!(
+            p.checked_mul_amount(amount, Rounding::Floor).unwrap(),
+            Uint128::new(3)
+        );
+        assert_eq!(
+            p.checked_mul_amount(amount, Rounding::Ceil).unwrap(),
+            Uint128::new(4)
+        );
+        assert_eq!(
+            p.checked_add_to_amount(amount, Rounding::Floor).unwrap(),
+            Uint128::new(13)
+        );
+        assert_eq!(
+            p.checked_sub_from_amount(amount, Rounding::Ceil).unwrap(),
+            Uint128::new(6)
+        );
+
+        // behaves the same as the truncating multiplication
+        assert_eq!(
+            p.checked_mul_amount(amount, Rounding::Floor).unwrap(),
+            p * amount
+        );
+
+        // no overflow for the multiplication itself, even for the largest amounts
+        assert_eq!(
+            Percent::hundred()
+                .checked_mul_amount(Uint128::MAX, Rounding::Ceil)
+                .unwrap(),
+            Uint128::MAX
+        );
+        assert!(Percent::hundred()
+            .checked_add_to_amount(Uint128::MAX, Rounding::Floor)
+            .is_err());
+    }
+
     #[test]
     fn percent_to_absolute_integer() {
         let p = serde_json::from_str::<'_, Percent>("\"0.0001\"").unwrap();
@@ -87,6 +87,12 @@ pub enum MixnetContractError {
         vesting_contract: Addr,
     },
 
+    #[error("This operation can only be performed by the vesting contract ({vesting_contract}), got {received}")]
+    SenderIsNotVestingContract {
+        received: Addr,
+        vesting_contract: Addr,
+    },
+
     #[error("Failed to recover ed25519 public key from its base58 representation - {0}")]
     MalformedEd25519IdentityKey(String),
 
@@ -258,6 +264,18 @@ pub enum MixnetContractError {
         provided: Uint128,
         range: OperatingCostRange,
     },
+
+    #[error("Provided message to update the staking floors did not contain any updates")]
+    EmptyStakingFloorsUpdate,
+
+    #[error("the minimum node pledge can't be set to zero")]
+    ZeroMinimumPledge,
+
+    #[error("mixnode {mix_id} does not have any operator payout address set")]
+    NoOperatorPayoutAddress { mix_id: MixId },
+
+    #[error("{sender} is neither the owner nor the payout address of mixnode {mix_id}")]
+    NotOwnerOrPayoutAddress { sender: Addr, mix_id: MixId },
 }
 
 impl MixnetContractError {
@@ -5,7 +5,9 @@ use crate::gateway::GatewayConfigUpdate;
 use crate::mixnode::{MixNodeConfigUpdate, MixNodeCostParams};
 use crate::reward_params::{IntervalRewardParams, IntervalRewardingParamsUpdate};
 use crate::rewarding::RewardDistribution;
-use crate::{BlockHeight, ContractStateParams, IdentityKeyRef, Interval, Layer, MixId};
+use crate::{
+    BlockHeight, ContractStateParams, IdentityKeyRef, Interval, Layer, MixId, StakingFloorsUpdate,
+};
 pub use contracts_common::events::*;
 use cosmwasm_std::{Addr, Coin, Decimal, Event};
 use std::fmt::Display;
@@ -27,7 +29,9 @@ pub enum MixnetEventType {
     MixnodeCostParamsUpdate,
     MixnodeRewarding,
     WithdrawDelegatorReward,
+    ClaimAllDelegatorRewards,
     WithdrawOperatorReward,
+    OperatorPayoutAddressUpdate,
     PendingActiveSetUpdate,
     ActiveSetUpdate,
     PendingIntervalRewardingParamsUpdate,
@@ -39,6 +43,7 @@ pub enum MixnetEventType {
     Undelegation,
     ContractSettingsUpdate,
     RewardingValidatorUpdate,
+    GovernanceAddressUpdate,
     BeginEpochTransition,
     AdvanceEpoch,
     ExecutePendingEpochEvents,
@@ -47,6 +52,7 @@ pub enum MixnetEventType {
     PendingIntervalConfigUpdate,
     IntervalConfigUpdate,
     GatewayConfigUpdate,
+    PendingStakingFloorsUpdate,
 }
 
 impl From<MixnetEventType> for String {
@@ -72,7 +78,9 @@ impl Display for MixnetEventType {
             MixnetEventType::MixnodeCostParamsUpdate => "mixnode_cost_params_update",
             MixnetEventType::MixnodeRewarding => "mix_rewarding",
             MixnetEventType::WithdrawDelegatorReward => "withdraw_delegator_reward",
+            MixnetEventType::ClaimAllDelegatorRewards => "claim_all_delegator_rewards",
             MixnetEventType::WithdrawOperatorReward => "withdraw_operator_reward",
+            MixnetEventType::OperatorPayoutAddressUpdate => "operator_payout_address_update",
             MixnetEventType::PendingActiveSetUpdate => "pending_active_set_update",
             MixnetEventType::ActiveSetUpdate => "active_set_update",
             MixnetEventType::PendingIntervalRewardingParamsUpdate => {
@@ -85,6 +93,7 @@ impl Display for MixnetEventType {
             MixnetEventType::Undelegation => "undelegation",
             MixnetEventType::ContractSettingsUpdate => "settings_update",
             MixnetEventType::RewardingValidatorUpdate => "rewarding_validator_address_update",
+            MixnetEventType::GovernanceAddressUpdate => "governance_address_update",
             MixnetEventType::BeginEpochTransition => "beginning_epoch_transition",
             MixnetEventType::AdvanceEpoch => "advance_epoch",
             MixnetEventType::ExecutePendingEpochEvents => "execute_pending_epoch_events",
@@ -94,6 +103,7 @@ impl Display for MixnetEventType {
             MixnetEventType::IntervalConfigUpdate => "interval_config_update",
             MixnetEventType::DelegationOnUnbonding => "delegation_on_unbonding_node",
             MixnetEventType::GatewayConfigUpdate => "gateway_config_update",
+            MixnetEventType::PendingStakingFloorsUpdate => "pending_staking_floors_update",
         };
 
         write!(f, "{EVENT_VERSION_PREFIX}{event_name}")
@@ -110,9 +120,12 @@ pub const AMOUNT_KEY: &str = "amount";
 pub const DELEGATOR_KEY: &str = "delegator";
 pub const DELEGATION_TARGET_KEY: &str = "delegation_target";
 pub const UNIT_REWARD_KEY: &str = "unit_reward";
+pub const DELEGATIONS_PROCESSED_KEY: &str = "delegations_processed";
+pub const MORE_DELEGATIONS_REMAINING_KEY: &str = "more_delegations_remaining";
 
 // bonding/unbonding
 pub const MIX_ID_KEY: &str = "mix_id";
+pub const PAYOUT_ADDRESS_KEY: &str = "payout_address";
 pub const NODE_IDENTITY_KEY: &str = "identity";
 pub const ASSIGNED_LAYER_KEY: &str = "assigned_layer";
 
@@ -124,10 +137,15 @@ pub const OLD_MINIMUM_DELEGATION_KEY: &str = "old_minimum_delegation";
 pub const NEW_MINIMUM_MIXNODE_PLEDGE_KEY: &str = "new_minimum_mixnode_pledge";
 pub const NEW_MINIMUM_GATEWAY_PLEDGE_KEY: &str = "new_minimum_gateway_pledge";
 pub const NEW_MINIMUM_DELEGATION_KEY: &str = "new_minimum_delegation";
+pub const STAKING_FLOORS_UPDATE_KEY: &str = "staking_floors_update";
 
 pub const OLD_REWARDING_VALIDATOR_ADDRESS_KEY: &str = "old_rewarding_validator_address";
 pub const NEW_REWARDING_VALIDATOR_ADDRESS_KEY: &str = "new_rewarding_validator_address";
 
+pub const OLD_GOVERNANCE_ADDRESS_KEY: &str = "old_governance_address";
+pub const NEW_GOVERNANCE_ADDRESS_KEY: &str = "new_governance_address";
+pub const CONFIG_CHANGE_ID_KEY: &str = "config_change_id";
+
 pub const UPDATED_MIXNODE_CONFIG_KEY: &str = "updated_mixnode_config";
 pub const UPDATED_GATEWAY_CONFIG_KEY: &str = "updated_gateway_config";
 pub const UPDATED_MIXNODE_COST_PARAMS_KEY: &str = "updated_mixnode_cost_params";
@@ -194,6 +212,30 @@ pub fn new_withdraw_operator_reward_event(owner: &Addr, amount: Coin, mix_id: Mi
         .add_attribute(MIX_ID_KEY, mix_id.to_string())
 }
 
+pub fn new_withdraw_operator_reward_to_payout_address_event(
+    owner: &Addr,
+    payout_address: &Addr,
+    amount: Coin,
+    mix_id: MixId,
+) -> Event {
+    new_withdraw_operator_reward_event(owner, amount, mix_id)
+        .add_attribute(PAYOUT_ADDRESS_KEY, payout_address.as_str())
+}
+
+pub fn new_operator_payout_address_update_event(
+    owner: &Addr,
+    mix_id: MixId,
+    payout_address: Option<&Addr>,
+) -> Event {
+    Event::new(MixnetEventType::OperatorPayoutAddressUpdate)
+        .add_attribute(OWNER_KEY, owner.as_str())
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+        .add_attribute(
+            PAYOUT_ADDRESS_KEY,
+            payout_address.map(Addr::as_str).unwrap_or("None"),
+        )
+}
+
 pub fn new_withdraw_delegator_reward_event(delegator: &Addr, amount: Coin, mix_id: MixId) -> Event {
     Event::new(MixnetEventType::WithdrawDelegatorReward)
         .add_attribute(DELEGATOR_KEY, delegator)
@@ -201,6 +243,19 @@ pub fn new_withdraw_delegator_reward_event(delegator: &Addr, amount: Coin, mix_i
         .add_attribute(DELEGATION_TARGET_KEY, mix_id.to_string())
 }
 
+pub fn new_claim_all_delegator_rewards_event(
+    delegator: &Addr,
+    amount: Coin,
+    delegations_processed: usize,
+    more_remaining: bool,
+) -> Event {
+    Event::new(MixnetEventType::ClaimAllDelegatorRewards)
+        .add_attribute(DELEGATOR_KEY, delegator)
+        .add_attribute(AMOUNT_KEY, amount.to_string())
+        .add_attribute(DELEGATIONS_PROCESSED_KEY, delegations_processed.to_string())
+        .add_attribute(MORE_DELEGATIONS_REMAINING_KEY, more_remaining.to_string())
+}
+
 pub fn new_active_set_update_event(created_at: BlockHeight, new_size: u32) -> Event {
     Event::new(MixnetEventType::ActiveSetUpdate)
         .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
@@ -391,6 +446,17 @@ pub fn new_rewarding_validator_address_update_event(old: Addr, new: Addr) -> Eve
         .add_attribute(NEW_REWARDING_VALIDATOR_ADDRESS_KEY, new)
 }
 
+pub fn new_governance_address_update_event(old: Option<Addr>, new: Option<Addr>) -> Event {
+    let mut event = Event::new(MixnetEventType::GovernanceAddressUpdate);
+    if let Some(old) = old {
+        event = event.add_attribute(OLD_GOVERNANCE_ADDRESS_KEY, old)
+    }
+    if let Some(new) = new {
+        event = event.add_attribute(NEW_GOVERNANCE_ADDRESS_KEY, new)
+    }
+    event
+}
+
 pub fn new_settings_update_event(
     old_params: &ContractStateParams,
     new_params: &ContractStateParams,
@@ -547,3 +613,15 @@ pub fn new_pending_interval_config_update_event(
             approximate_time_remaining_secs.to_string(),
         )
 }
+
+pub fn new_pending_staking_floors_update_event(
+    update: &StakingFloorsUpdate,
+    approximate_time_remaining_secs: i64,
+) -> Event {
+    Event::new(MixnetEventType::PendingStakingFloorsUpdate)
+        .add_attribute(STAKING_FLOORS_UPDATE_KEY, update.to_inline_json())
+        .add_attribute(
+            APPROXIMATE_TIME_LEFT_SECS_KEY,
+            approximate_time_remaining_secs.to_string(),
+        )
+}
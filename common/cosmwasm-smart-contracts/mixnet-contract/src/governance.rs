@@ -0,0 +1,106 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::reward_params::IntervalRewardingParamsUpdate;
+use crate::types::StakingFloorsUpdate;
+use crate::BlockHeight;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+
+pub type ConfigChangeId = u32;
+
+/// Enum encompassing all system parameter changes recorded in the on-chain changelog.
+#[cw_serde]
+pub enum ConfigChangeKind {
+    /// Change of the underlying rewarding parameters used by the system.
+    RewardingParams {
+        /// The detailed specification of the update.
+        update: IntervalRewardingParamsUpdate,
+    },
+
+    /// Change of the interval configuration.
+    IntervalConfig {
+        /// The new number of epochs in intervals.
+        epochs_in_interval: u32,
+
+        /// The new epoch duration.
+        epoch_duration_secs: u64,
+    },
+
+    /// Change of the minimum pledge and delegation amounts.
+    StakingFloors {
+        /// The detailed specification of the update.
+        update: StakingFloorsUpdate,
+    },
+}
+
+/// Details of a particular system parameter change request.
+#[cw_serde]
+pub struct ConfigChange {
+    /// The address of the entity (contract admin or the governance module) that requested the change.
+    pub requested_by: Addr,
+
+    /// The block height at which the request has been made.
+    pub requested_at: BlockHeight,
+
+    /// The unix timestamp at which the request has been made.
+    pub requested_at_timestamp: i64,
+
+    /// The unix timestamp at which the change has been (or is going to be) applied.
+    /// Unless forced, changes only take effect once the current interval finishes,
+    /// giving the operators notice of the upcoming updates.
+    pub activation_timestamp: i64,
+
+    /// Indicates whether the change bypassed the activation delay.
+    pub forced: bool,
+
+    /// The underlying change details.
+    pub kind: ConfigChangeKind,
+}
+
+/// A recorded system parameter change.
+#[cw_serde]
+pub struct ConfigChangelogEntry {
+    /// The unique id associated with the change.
+    pub id: ConfigChangeId,
+
+    /// The details of the change.
+    pub change: ConfigChange,
+}
+
+impl From<(ConfigChangeId, ConfigChange)> for ConfigChangelogEntry {
+    fn from(data: (ConfigChangeId, ConfigChange)) -> Self {
+        ConfigChangelogEntry {
+            id: data.0,
+            change: data.1,
+        }
+    }
+}
+
+/// Response containing paged list of all system parameter changes.
+#[cw_serde]
+pub struct PagedConfigChangelogResponse {
+    /// The recorded changes.
+    pub changes: Vec<ConfigChangelogEntry>,
+
+    /// Field indicating paging information for the following queries if the caller wishes to get further entries.
+    pub start_next_after: Option<ConfigChangeId>,
+}
+
+impl PagedConfigChangelogResponse {
+    pub fn new(
+        changes: Vec<ConfigChangelogEntry>,
+        start_next_after: Option<ConfigChangeId>,
+    ) -> Self {
+        PagedConfigChangelogResponse {
+            changes,
+            start_next_after,
+        }
+    }
+}
+
+/// Response containing the address of the governance module allowed to update system parameters.
+#[cw_serde]
+pub struct GovernanceAddressResponse {
+    pub governance_address: Option<Addr>,
+}
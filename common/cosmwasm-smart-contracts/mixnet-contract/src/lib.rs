@@ -10,6 +10,7 @@ pub mod error;
 pub mod events;
 pub mod families;
 pub mod gateway;
+pub mod governance;
 pub mod helpers;
 pub mod interval;
 pub mod mixnode;
@@ -34,13 +35,18 @@ pub use gateway::{
     Gateway, GatewayBond, GatewayBondResponse, GatewayConfigUpdate, GatewayOwnershipResponse,
     PagedGatewayResponse,
 };
+pub use governance::{
+    ConfigChange, ConfigChangeId, ConfigChangeKind, ConfigChangelogEntry,
+    GovernanceAddressResponse, PagedConfigChangelogResponse,
+};
 pub use interval::{
     CurrentIntervalResponse, EpochId, EpochState, EpochStatus, Interval, IntervalId,
 };
 pub use mixnode::{
     Layer, MixNode, MixNodeBond, MixNodeConfigUpdate, MixNodeCostParams, MixNodeDetails,
     MixNodeRewarding, MixOwnershipResponse, MixnodeDetailsByIdentityResponse,
-    MixnodeDetailsResponse, PagedMixnodeBondsResponse, RewardedSetNodeStatus, UnbondedMixnode,
+    MixnodeDetailsResponse, OperatorPayoutAddressResponse, PagedMixnodeBondsResponse,
+    RewardedSetNodeStatus, UnbondedMixnode,
 };
 pub use msg::*;
 pub use pending_events::{
@@ -269,10 +269,10 @@ impl MixNodeRewarding {
         let alpha = reward_params.interval.sybil_resistance;
 
         reward_params.interval.epoch_reward_budget
-            * node_params.performance.value()
+            * node_params.performance
             * self.bond_saturation(reward_params)
             * (work
-                + alpha.value() * self.pledge_saturation(reward_params)
+                + alpha * self.pledge_saturation(reward_params)
                     / reward_params.dec_rewarded_set_size())
             / (Decimal::one() + alpha.value())
     }
@@ -285,17 +285,17 @@ impl MixNodeRewarding {
         epochs_in_interval: u32,
     ) -> RewardDistribution {
         let node_cost =
-            self.cost_params.epoch_operating_cost(epochs_in_interval) * node_performance.value();
+            self.cost_params.epoch_operating_cost(epochs_in_interval) * node_performance;
 
         // check if profit is positive
         if node_reward > node_cost {
             let profit = node_reward - node_cost;
-            let profit_margin = self.cost_params.profit_margin_percent.value();
-            let one = Decimal::one();
+            let profit_margin = self.cost_params.profit_margin_percent;
 
             let operator_share = self.operator / self.node_bond();
 
-            let operator = profit * (profit_margin + (one - profit_margin) * operator_share);
+            let operator =
+                profit * (profit_margin.value() + profit_margin.complement() * operator_share);
             let delegates = profit - operator;
 
             debug_assert_eq!(operator + delegates + node_cost, node_reward);
@@ -876,3 +876,13 @@ pub struct StakeSaturationResponse {
     /// However, anything beyond that value has no effect on the total node reward.
     pub uncapped_saturation: Option<Decimal>,
 }
+
+/// Response containing the address receiving the operator rewards of a mixnode with the provided id.
+#[cw_serde]
+pub struct OperatorPayoutAddressResponse {
+    /// Id of the requested mixnode.
+    pub mix_id: MixId,
+
+    /// The address the operator rewards are sent to, if it's different from the owner of the node.
+    pub payout_address: Option<Addr>,
+}
@@ -5,13 +5,14 @@ use crate::delegation::{self, OwnerProxySubKey};
 use crate::error::MixnetContractError;
 use crate::families::FamilyHead;
 use crate::gateway::{Gateway, GatewayConfigUpdate};
+use crate::governance::ConfigChangeId;
 use crate::helpers::IntoBaseDecimal;
 use crate::mixnode::{Layer, MixNode, MixNodeConfigUpdate, MixNodeCostParams};
 use crate::pending_events::{EpochEventId, IntervalEventId};
 use crate::reward_params::{
     IntervalRewardParams, IntervalRewardingParamsUpdate, Performance, RewardingParams,
 };
-use crate::types::{ContractStateParams, LayerAssignment, MixId};
+use crate::types::{ContractStateParams, LayerAssignment, MixId, StakingFloorsUpdate};
 use crate::{OperatingCostRange, ProfitMarginRange};
 use contracts_common::{signing::MessageSignature, IdentityKey, Percent};
 use cosmwasm_schema::cw_serde;
@@ -29,11 +30,13 @@ use crate::{
         FamilyMembersByLabelResponse, PagedFamiliesResponse, PagedMembersResponse,
     },
     gateway::{GatewayBondResponse, GatewayOwnershipResponse, PagedGatewayResponse},
+    governance::{GovernanceAddressResponse, PagedConfigChangelogResponse},
     interval::{CurrentIntervalResponse, EpochStatus},
     mixnode::{
         MixOwnershipResponse, MixnodeDetailsByIdentityResponse, MixnodeDetailsResponse,
-        MixnodeRewardingDetailsResponse, PagedMixnodeBondsResponse, PagedMixnodesDetailsResponse,
-        PagedUnbondedMixnodesResponse, StakeSaturationResponse, UnbondedMixnodeResponse,
+        MixnodeRewardingDetailsResponse, OperatorPayoutAddressResponse, PagedMixnodeBondsResponse,
+        PagedMixnodesDetailsResponse, PagedUnbondedMixnodesResponse, StakeSaturationResponse,
+        UnbondedMixnodeResponse,
     },
     pending_events::{
         NumberOfPendingEventsResponse, PendingEpochEventResponse, PendingEpochEventsResponse,
@@ -42,7 +45,7 @@ use crate::{
     rewarding::{
         EstimatedCurrentEpochRewardResponse, PagedRewardedSetResponse, PendingRewardResponse,
     },
-    types::{ContractState, LayerDistribution},
+    types::{ContractState, LayerDistribution, StakingFloorsResponse},
 };
 #[cfg(feature = "schema")]
 use contracts_common::{signing::Nonce, ContractBuildInformation};
@@ -161,6 +164,11 @@ pub enum ExecuteMsg {
     UpdateContractStateParams {
         updated_parameters: ContractStateParams,
     },
+    /// Sets (or removes) the address of the governance module that, alongside the contract admin,
+    /// is allowed to update the interval configuration and the rewarding parameters.
+    UpdateGovernanceAddress {
+        address: Option<String>,
+    },
     UpdateActiveSetSize {
         active_set_size: u32,
         force_immediately: bool,
@@ -174,6 +182,12 @@ pub enum ExecuteMsg {
         epoch_duration_secs: u64,
         force_immediately: bool,
     },
+    /// Updates the minimum pledge and delegation amounts. Unless forced, the change only takes effect
+    /// once the current interval finishes.
+    UpdateStakingFloors {
+        update: StakingFloorsUpdate,
+        force_immediately: bool,
+    },
     BeginEpochTransition {},
     AdvanceCurrentEpoch {
         new_rewarded_set: Vec<LayerAssignment>,
@@ -225,6 +239,12 @@ pub enum ExecuteMsg {
         new_config: MixNodeConfigUpdate,
         owner: String,
     },
+    /// Sets (or, if `None`, removes) the address the operator rewards get sent to instead of the owner.
+    /// The update has to be signed with the identity key of the mixnode.
+    UpdateOperatorPayoutAddress {
+        payout_address: Option<String>,
+        node_signature: MessageSignature,
+    },
 
     // gateway-related:
     BondGateway {
@@ -273,6 +293,11 @@ pub enum ExecuteMsg {
     WithdrawOperatorRewardOnBehalf {
         owner: String,
     },
+    /// Sends the operator rewards of the mixnode to its payout address.
+    /// Can be executed either by the owner of the node or by the payout address itself.
+    WithdrawOperatorRewardToPayoutAddress {
+        mix_id: MixId,
+    },
     WithdrawDelegatorReward {
         mix_id: MixId,
     },
@@ -280,12 +305,24 @@ pub enum ExecuteMsg {
         mix_id: MixId,
         owner: String,
     },
+    /// Withdraws the rewards of all delegations of the sender. If there are too many of them
+    /// to process within a single transaction, the next call continues from where this one has finished.
+    ClaimAllDelegatorRewards {},
 
     // vesting migration:
     MigrateVestedMixNode {},
     MigrateVestedDelegation {
         mix_id: MixId,
     },
+    /// Only callable by the vesting contract once the vesting schedule of the owner has completed.
+    MigrateVestedMixNodeOnBehalf {
+        owner: String,
+    },
+    /// Only callable by the vesting contract once the vesting schedule of the owner has completed.
+    MigrateVestedDelegationOnBehalf {
+        owner: String,
+        mix_id: MixId,
+    },
 
     // testing-only
     #[cfg(feature = "contract-testing")]
@@ -327,6 +364,10 @@ impl ExecuteMsg {
             ExecuteMsg::UpdateContractStateParams { .. } => {
                 "updating mixnet state parameters".into()
             }
+            ExecuteMsg::UpdateGovernanceAddress { address } => match address {
+                Some(address) => format!("updating governance address to {address}"),
+                None => "removing governance address".into(),
+            },
             ExecuteMsg::UpdateActiveSetSize {
                 active_set_size,
                 force_immediately,
@@ -339,6 +380,11 @@ impl ExecuteMsg {
             ExecuteMsg::UpdateIntervalConfig {
                 force_immediately, ..
             } => format!("updating mixnet interval configuration. forced: {force_immediately}"),
+            ExecuteMsg::UpdateStakingFloors {
+                force_immediately, ..
+            } => format!(
+                "updating minimum pledge and delegation amounts. forced: {force_immediately}"
+            ),
             ExecuteMsg::BeginEpochTransition {} => "beginning epoch transition".into(),
             ExecuteMsg::AdvanceCurrentEpoch { .. } => "advancing current epoch".into(),
             ExecuteMsg::ReconcileEpochEvents { .. } => "reconciling epoch events".into(),
@@ -364,6 +410,12 @@ impl ExecuteMsg {
             ExecuteMsg::UpdateMixnodeConfigOnBehalf { .. } => {
                 "updating mixnode configuration on behalf".into()
             }
+            ExecuteMsg::UpdateOperatorPayoutAddress { payout_address, .. } => {
+                match payout_address {
+                    Some(address) => format!("updating operator payout address to {address}"),
+                    None => "removing operator payout address".into(),
+                }
+            }
             ExecuteMsg::BondGateway { gateway, .. } => {
                 format!("bonding gateway {}", gateway.identity_key)
             }
@@ -394,14 +446,24 @@ impl ExecuteMsg {
             ExecuteMsg::WithdrawOperatorRewardOnBehalf { .. } => {
                 "withdrawing operator reward on behalf".into()
             }
+            ExecuteMsg::WithdrawOperatorRewardToPayoutAddress { mix_id } => {
+                format!("withdrawing operator reward of mixnode {mix_id} to its payout address")
+            }
             ExecuteMsg::WithdrawDelegatorReward { mix_id } => {
                 format!("withdrawing delegator reward from mixnode {mix_id}")
             }
             ExecuteMsg::WithdrawDelegatorRewardOnBehalf { mix_id, .. } => {
                 format!("withdrawing delegator reward from mixnode {mix_id} on behalf")
             }
+            ExecuteMsg::ClaimAllDelegatorRewards {} => "claiming all delegator rewards".into(),
             ExecuteMsg::MigrateVestedMixNode { .. } => "migrate vested mixnode".into(),
             ExecuteMsg::MigrateVestedDelegation { .. } => "migrate vested delegation".to_string(),
+            ExecuteMsg::MigrateVestedMixNodeOnBehalf { .. } => {
+                "migrate vested mixnode on behalf".into()
+            }
+            ExecuteMsg::MigrateVestedDelegationOnBehalf { .. } => {
+                "migrate vested delegation on behalf".into()
+            }
 
             #[cfg(feature = "contract-testing")]
             ExecuteMsg::TestingResolveAllPendingEvents { .. } => {
@@ -480,6 +542,10 @@ pub enum QueryMsg {
     #[cfg_attr(feature = "schema", returns(RewardingParams))]
     GetRewardingParams {},
 
+    /// Gets the minimum pledge and delegation amounts alongside any pending changes to them.
+    #[cfg_attr(feature = "schema", returns(StakingFloorsResponse))]
+    GetStakingFloors {},
+
     /// Gets the status of the current rewarding epoch.
     #[cfg_attr(feature = "schema", returns(EpochStatus))]
     GetEpochStatus {},
@@ -590,6 +656,13 @@ pub enum QueryMsg {
         mix_id: MixId,
     },
 
+    /// Gets the address receiving the operator rewards of a mixnode with the provided id.
+    #[cfg_attr(feature = "schema", returns(OperatorPayoutAddressResponse))]
+    GetOperatorPayoutAddress {
+        /// Id of the node to query.
+        mix_id: MixId,
+    },
+
     /// Gets the detailed mixnode information of a node given its current identity key.
     #[cfg_attr(feature = "schema", returns(MixnodeDetailsByIdentityResponse))]
     GetBondedMixnodeDetailsByIdentity {
@@ -774,6 +847,21 @@ pub enum QueryMsg {
     #[cfg_attr(feature = "schema", returns(NumberOfPendingEventsResponse))]
     GetNumberOfPendingEvents {},
 
+    // governance-related
+    /// Gets the address of the governance module allowed to update system parameters.
+    #[cfg_attr(feature = "schema", returns(GovernanceAddressResponse))]
+    GetGovernanceAddress {},
+
+    /// Gets the changelog of all system parameter (interval configuration and rewarding parameters) changes.
+    #[cfg_attr(feature = "schema", returns(PagedConfigChangelogResponse))]
+    GetConfigChangelog {
+        /// Controls the maximum number of entries returned by the query. Note that too large values will be overwritten by a saner default.
+        limit: Option<u32>,
+
+        /// Pagination control for the values returned by the query. Note that the provided value itself will **not** be used for the response.
+        start_after: Option<ConfigChangeId>,
+    },
+
     // signing-related
     /// Gets the signing nonce associated with the particular cosmos address.
     #[cfg_attr(feature = "schema", returns(Nonce))]
@@ -3,7 +3,7 @@
 
 use crate::mixnode::MixNodeCostParams;
 use crate::reward_params::IntervalRewardingParamsUpdate;
-use crate::{BlockHeight, MixId};
+use crate::{BlockHeight, MixId, StakingFloorsUpdate};
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Coin};
 
@@ -188,6 +188,12 @@ pub enum PendingIntervalEventKind {
         /// The new epoch duration.
         epoch_duration_secs: u64,
     },
+
+    /// Request to update the minimum pledge and delegation amounts.
+    UpdateStakingFloors {
+        /// The detailed specification of the update.
+        update: StakingFloorsUpdate,
+    },
 }
 
 impl PendingIntervalEventKind {
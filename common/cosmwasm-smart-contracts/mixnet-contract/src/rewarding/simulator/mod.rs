@@ -49,7 +49,7 @@ impl Simulator {
                     * self.pending_reward_pool_emission;
             let epoch_reward_budget = reward_pool
                 / self.interval.epochs_in_interval().into_base_decimal()?
-                * old.interval_pool_emission.value();
+                * old.interval_pool_emission;
             let stake_saturation_point = staking_supply
                 / self
                     .system_rewarding_params
@@ -2,10 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::families::FamilyHead;
-use crate::{Gateway, IdentityKey, MixNode, MixNodeCostParams};
+use crate::{Gateway, IdentityKey, MixId, MixNode, MixNodeCostParams};
 use contracts_common::signing::{
-    ContractMessageContent, LegacyContractMessageContent, MessageType, Nonce, SignableMessage,
-    SigningPurpose,
+    ContractMessageContent, HumanReadable, LegacyContractMessageContent, MessageType, Nonce,
+    SignableMessage, SigningPurpose,
 };
 use cosmwasm_std::{Addr, Coin};
 use serde::Serialize;
@@ -17,6 +17,10 @@ pub type SignableLegacyMixNodeBondingMsg =
 pub type SignableLegacyGatewayBondingMsg =
     SignableMessage<LegacyContractMessageContent<GatewayBondingPayload>>;
 pub type SignableFamilyJoinPermitMsg = SignableMessage<FamilyJoinPermit>;
+pub type SignableMixNodePledgeAdjustmentMsg =
+    SignableMessage<ContractMessageContent<MixnodePledgeAdjustmentPayload>>;
+pub type SignableOperatorPayoutAddressMsg =
+    SignableMessage<ContractMessageContent<OperatorPayoutAddressPayload>>;
 
 #[derive(Serialize)]
 pub struct MixnodeBondingPayload {
@@ -39,6 +43,43 @@ impl SigningPurpose for MixnodeBondingPayload {
     }
 }
 
+impl HumanReadable for MixnodeBondingPayload {
+    fn human_readable_fields(&self) -> Vec<(String, String)> {
+        vec![
+            ("mix_node.host".into(), self.mix_node.host.clone()),
+            (
+                "mix_node.mix_port".into(),
+                self.mix_node.mix_port.to_string(),
+            ),
+            (
+                "mix_node.verloc_port".into(),
+                self.mix_node.verloc_port.to_string(),
+            ),
+            (
+                "mix_node.http_api_port".into(),
+                self.mix_node.http_api_port.to_string(),
+            ),
+            (
+                "mix_node.sphinx_key".into(),
+                self.mix_node.sphinx_key.clone(),
+            ),
+            (
+                "mix_node.identity_key".into(),
+                self.mix_node.identity_key.clone(),
+            ),
+            ("mix_node.version".into(), self.mix_node.version.clone()),
+            (
+                "cost_params.profit_margin_percent".into(),
+                self.cost_params.profit_margin_percent.to_string(),
+            ),
+            (
+                "cost_params.interval_operating_cost".into(),
+                self.cost_params.interval_operating_cost.to_string(),
+            ),
+        ]
+    }
+}
+
 pub fn construct_mixnode_bonding_sign_payload(
     nonce: Nonce,
     sender: Addr,
@@ -83,6 +124,26 @@ impl SigningPurpose for GatewayBondingPayload {
     }
 }
 
+impl HumanReadable for GatewayBondingPayload {
+    fn human_readable_fields(&self) -> Vec<(String, String)> {
+        vec![
+            ("gateway.host".into(), self.gateway.host.clone()),
+            ("gateway.mix_port".into(), self.gateway.mix_port.to_string()),
+            (
+                "gateway.clients_port".into(),
+                self.gateway.clients_port.to_string(),
+            ),
+            ("gateway.location".into(), self.gateway.location.clone()),
+            ("gateway.sphinx_key".into(), self.gateway.sphinx_key.clone()),
+            (
+                "gateway.identity_key".into(),
+                self.gateway.identity_key.clone(),
+            ),
+            ("gateway.version".into(), self.gateway.version.clone()),
+        ]
+    }
+}
+
 pub fn construct_gateway_bonding_sign_payload(
     nonce: Nonce,
     sender: Addr,
@@ -143,5 +204,259 @@ pub fn construct_family_join_permit(
     SignableMessage::new(nonce, payload)
 }
 
+/// Change of the pledge of an already bonded node.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PledgeAdjustment {
+    Increase(Coin),
+    Decrease(Coin),
+}
+
+impl PledgeAdjustment {
+    pub fn amount(&self) -> &Coin {
+        match self {
+            PledgeAdjustment::Increase(amount) => amount,
+            PledgeAdjustment::Decrease(amount) => amount,
+        }
+    }
+
+    // only the pledge increase is actually sending any tokens to the contract
+    fn funds(&self) -> Vec<Coin> {
+        match self {
+            PledgeAdjustment::Increase(amount) => vec![amount.clone()],
+            PledgeAdjustment::Decrease(_) => Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct MixnodePledgeAdjustmentPayload {
+    mix_id: MixId,
+    identity_key: IdentityKey,
+    adjustment: PledgeAdjustment,
+}
+
+impl MixnodePledgeAdjustmentPayload {
+    pub fn new(mix_id: MixId, identity_key: IdentityKey, adjustment: PledgeAdjustment) -> Self {
+        Self {
+            mix_id,
+            identity_key,
+            adjustment,
+        }
+    }
+}
+
+impl SigningPurpose for MixnodePledgeAdjustmentPayload {
+    fn message_type() -> MessageType {
+        MessageType::new("mixnode-pledge-adjustment")
+    }
+}
+
+impl HumanReadable for MixnodePledgeAdjustmentPayload {
+    fn human_readable_fields(&self) -> Vec<(String, String)> {
+        let direction = match self.adjustment {
+            PledgeAdjustment::Increase(_) => "increase",
+            PledgeAdjustment::Decrease(_) => "decrease",
+        };
+        vec![
+            ("mix_id".into(), self.mix_id.to_string()),
+            ("identity_key".into(), self.identity_key.clone()),
+            ("adjustment".into(), direction.into()),
+            (
+                "adjustment.amount".into(),
+                self.adjustment.amount().to_string(),
+            ),
+        ]
+    }
+}
+
+// note: the contract itself does not require (nor verify) this signature, it's only used by the wallet
+// to ensure the operator is in control of the node whose pledge is being adjusted
+pub fn construct_mixnode_pledge_adjustment_sign_payload(
+    nonce: Nonce,
+    sender: Addr,
+    mix_id: MixId,
+    identity_key: IdentityKey,
+    adjustment: PledgeAdjustment,
+) -> SignableMixNodePledgeAdjustmentMsg {
+    let funds = adjustment.funds();
+    let payload = MixnodePledgeAdjustmentPayload::new(mix_id, identity_key, adjustment);
+    let content = ContractMessageContent::new(sender, funds, payload);
+
+    SignableMessage::new(nonce, content)
+}
+
+#[derive(Serialize)]
+pub struct OperatorPayoutAddressPayload {
+    mix_id: MixId,
+    identity_key: IdentityKey,
+    payout_address: Option<String>,
+}
+
+impl OperatorPayoutAddressPayload {
+    pub fn new(mix_id: MixId, identity_key: IdentityKey, payout_address: Option<String>) -> Self {
+        Self {
+            mix_id,
+            identity_key,
+            payout_address,
+        }
+    }
+}
+
+impl SigningPurpose for OperatorPayoutAddressPayload {
+    fn message_type() -> MessageType {
+        MessageType::new("operator-payout-address")
+    }
+}
+
+impl HumanReadable for OperatorPayoutAddressPayload {
+    fn human_readable_fields(&self) -> Vec<(String, String)> {
+        vec![
+            ("mix_id".into(), self.mix_id.to_string()),
+            ("identity_key".into(), self.identity_key.clone()),
+            (
+                "payout_address".into(),
+                self.payout_address
+                    .clone()
+                    .unwrap_or_else(|| "none (owner)".into()),
+            ),
+        ]
+    }
+}
+
+// unlike the pledge adjustments, this one is verified by the contract itself, so that a compromised
+// owner key on its own would not be enough to silently redirect the rewards
+pub fn construct_operator_payout_address_sign_payload(
+    nonce: Nonce,
+    sender: Addr,
+    mix_id: MixId,
+    identity_key: IdentityKey,
+    payout_address: Option<String>,
+) -> SignableOperatorPayoutAddressMsg {
+    let payload = OperatorPayoutAddressPayload::new(mix_id, identity_key, payout_address);
+    let content = ContractMessageContent::new(sender, Vec::new(), payload);
+
+    SignableMessage::new(nonce, content)
+}
+
 // TODO: depending on our threat model, we should perhaps extend it to include all _on_behalf methods
 // (update: but we trust our vesting contract since its compromise would be even more devastating so there's no need)
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use contracts_common::Percent;
+    use cosmwasm_std::coin;
+
+    fn dummy_mixnode() -> MixNode {
+        MixNode {
+            host: "1.2.3.4".to_string(),
+            mix_port: 1789,
+            verloc_port: 1790,
+            http_api_port: 8000,
+            sphinx_key: "sphinxkey".to_string(),
+            identity_key: "identitykey".to_string(),
+            version: "1.1.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn human_readable_mixnode_bonding_preview() {
+        let msg = construct_mixnode_bonding_sign_payload(
+            42,
+            Addr::unchecked("n1sender"),
+            coin(100_000000, "unym"),
+            dummy_mixnode(),
+            MixNodeCostParams {
+                profit_margin_percent: Percent::from_percentage_value(10).unwrap(),
+                interval_operating_cost: coin(40_000000, "unym"),
+            },
+        );
+
+        let preview = msg.to_human_readable().unwrap();
+        let plaintext_len = msg.to_plaintext().unwrap().len();
+
+        let lines = preview.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "Nym Signed Message:");
+        assert_eq!(lines[1], format!("plaintext length: {plaintext_len}"));
+        assert!(lines.contains(&"message type: mixnode-bonding"));
+        assert!(lines.contains(&"nonce: 42"));
+        assert!(lines.contains(&"algorithm: ed25519"));
+        assert!(lines.contains(&"sender: n1sender"));
+        assert!(lines.contains(&"funds: 100000000unym"));
+        assert!(lines.contains(&"mix_node.identity_key: identitykey"));
+        assert!(lines.contains(&"cost_params.interval_operating_cost: 40000000unym"));
+    }
+
+    #[test]
+    fn human_readable_legacy_gateway_bonding_preview() {
+        let msg = construct_legacy_gateway_bonding_sign_payload(
+            1,
+            Addr::unchecked("n1sender"),
+            coin(100_000000, "unym"),
+            Gateway {
+                host: "1.2.3.4".to_string(),
+                mix_port: 1789,
+                clients_port: 9000,
+                location: "Neuchatel".to_string(),
+                sphinx_key: "sphinxkey".to_string(),
+                identity_key: "identitykey".to_string(),
+                version: "1.1.0".to_string(),
+            },
+        );
+
+        let preview = msg.to_human_readable().unwrap();
+        let lines = preview.lines().collect::<Vec<_>>();
+        assert!(lines.contains(&"message type: gateway-bonding"));
+        assert!(lines.contains(&"proxy: none"));
+        assert!(lines.contains(&"gateway.location: Neuchatel"));
+        assert!(lines.contains(&"gateway.clients_port: 9000"));
+    }
+
+    #[test]
+    fn human_readable_pledge_adjustment_preview() {
+        let increase = construct_mixnode_pledge_adjustment_sign_payload(
+            3,
+            Addr::unchecked("n1sender"),
+            42,
+            "identitykey".to_string(),
+            PledgeAdjustment::Increase(coin(5_000000, "unym")),
+        );
+        let preview = increase.to_human_readable().unwrap();
+        let lines = preview.lines().collect::<Vec<_>>();
+        assert!(lines.contains(&"message type: mixnode-pledge-adjustment"));
+        assert!(lines.contains(&"funds: 5000000unym"));
+        assert!(lines.contains(&"adjustment: increase"));
+        assert!(lines.contains(&"adjustment.amount: 5000000unym"));
+
+        // decreasing the pledge does not send anything to the contract
+        let decrease = construct_mixnode_pledge_adjustment_sign_payload(
+            3,
+            Addr::unchecked("n1sender"),
+            42,
+            "identitykey".to_string(),
+            PledgeAdjustment::Decrease(coin(5_000000, "unym")),
+        );
+        let preview = decrease.to_human_readable().unwrap();
+        let lines = preview.lines().collect::<Vec<_>>();
+        assert!(lines.contains(&"funds: none"));
+        assert!(lines.contains(&"adjustment: decrease"));
+        assert!(lines.contains(&"mix_id: 42"));
+    }
+
+    #[test]
+    fn human_readable_operator_payout_address_preview() {
+        let msg = construct_operator_payout_address_sign_payload(
+            7,
+            Addr::unchecked("n1cold"),
+            42,
+            "identitykey".to_string(),
+            Some("n1hot".to_string()),
+        );
+        let preview = msg.to_human_readable().unwrap();
+        let lines = preview.lines().collect::<Vec<_>>();
+        assert!(lines.contains(&"message type: operator-payout-address"));
+        assert!(lines.contains(&"sender: n1cold"));
+        assert!(lines.contains(&"payout_address: n1hot"));
+    }
+}
@@ -230,3 +230,106 @@ pub struct ContractStateParams {
     #[serde(default)]
     pub interval_operating_cost: OperatingCostRange,
 }
+
+/// Specification of an update to the minimum pledge and delegation amounts enforced by the contract.
+#[cw_serde]
+#[derive(Default)]
+pub struct StakingFloorsUpdate {
+    /// Defines the new minimum amount a mixnode must pledge to get into the system.
+    pub minimum_mixnode_pledge: Option<Coin>,
+
+    /// Defines the new minimum amount a gateway must pledge to get into the system.
+    pub minimum_gateway_pledge: Option<Coin>,
+
+    /// Defines the new minimum amount a delegator must stake in order for the delegation to get accepted.
+    /// Setting it to a zero amount removes the requirement altogether.
+    pub minimum_mixnode_delegation: Option<Coin>,
+}
+
+impl StakingFloorsUpdate {
+    pub fn contains_updates(&self) -> bool {
+        // essentially at least a single field has to be a `Some`
+        self.minimum_mixnode_pledge.is_some()
+            || self.minimum_gateway_pledge.is_some()
+            || self.minimum_mixnode_delegation.is_some()
+    }
+
+    pub fn apply_to(&self, params: &mut ContractStateParams) {
+        if let Some(minimum_mixnode_pledge) = &self.minimum_mixnode_pledge {
+            params.minimum_mixnode_pledge = minimum_mixnode_pledge.clone();
+        }
+        if let Some(minimum_gateway_pledge) = &self.minimum_gateway_pledge {
+            params.minimum_gateway_pledge = minimum_gateway_pledge.clone();
+        }
+        if let Some(minimum_mixnode_delegation) = &self.minimum_mixnode_delegation {
+            params.minimum_mixnode_delegation = if minimum_mixnode_delegation.amount.is_zero() {
+                None
+            } else {
+                Some(minimum_mixnode_delegation.clone())
+            };
+        }
+    }
+
+    pub fn to_inline_json(&self) -> String {
+        serde_json_wasm::to_string(self).unwrap_or_else(|_| "serialisation failure".into())
+    }
+}
+
+/// Response containing the minimum pledge and delegation amounts enforced by the contract.
+#[cw_serde]
+pub struct StakingFloorsResponse {
+    /// Minimum amount a mixnode must currently pledge to get into the system.
+    pub minimum_mixnode_pledge: Coin,
+
+    /// Minimum amount a gateway must currently pledge to get into the system.
+    pub minimum_gateway_pledge: Coin,
+
+    /// Minimum amount a delegator must currently stake in order for the delegation to get accepted.
+    pub minimum_mixnode_delegation: Option<Coin>,
+
+    /// Updates to the floors that are going to be applied once the current interval finishes,
+    /// in the order of their execution.
+    pub pending_updates: Vec<StakingFloorsUpdate>,
+
+    /// The unix timestamp at which the pending updates are going to be applied.
+    pub pending_activation_timestamp: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::coin;
+
+    fn params() -> ContractStateParams {
+        ContractStateParams {
+            minimum_mixnode_delegation: Some(coin(100, "unym")),
+            minimum_mixnode_pledge: coin(1000, "unym"),
+            minimum_gateway_pledge: coin(500, "unym"),
+            profit_margin: Default::default(),
+            interval_operating_cost: Default::default(),
+        }
+    }
+
+    #[test]
+    fn applying_staking_floors_update() {
+        let mut params = params();
+        let update = StakingFloorsUpdate {
+            minimum_mixnode_pledge: Some(coin(2000, "unym")),
+            ..Default::default()
+        };
+        update.apply_to(&mut params);
+        assert_eq!(params.minimum_mixnode_pledge, coin(2000, "unym"));
+        assert_eq!(params.minimum_gateway_pledge, coin(500, "unym"));
+        assert_eq!(params.minimum_mixnode_delegation, Some(coin(100, "unym")));
+
+        // zero amount removes the delegation requirement
+        let update = StakingFloorsUpdate {
+            minimum_mixnode_delegation: Some(coin(0, "unym")),
+            ..Default::default()
+        };
+        update.apply_to(&mut params);
+        assert!(params.minimum_mixnode_delegation.is_none());
+
+        assert!(!StakingFloorsUpdate::default().contains_updates());
+    }
+}
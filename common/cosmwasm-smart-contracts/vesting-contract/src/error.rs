@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::account::VestingAccountStorageKey;
-use cosmwasm_std::{Addr, Coin, OverflowError, StdError, Uint128};
+use cosmwasm_std::{Addr, Coin, OverflowError, StdError, Timestamp, Uint128};
 use mixnet_contract_common::MixId;
 use thiserror::Error;
 
@@ -106,6 +106,14 @@ pub enum VestingContractError {
         error_message: String,
     },
 
+    #[error(
+        "VESTING: The vesting schedule of {owner} has not completed yet. It ends at {end_time}"
+    )]
+    VestingNotCompleted { owner: Addr, end_time: Timestamp },
+
+    #[error("VESTING: {owner} has no vesting mixnode bond or delegations left to migrate")]
+    NoVestedStakeToMigrate { owner: Addr },
+
     #[error("VESTING: {message}")]
     Other { message: String },
 }
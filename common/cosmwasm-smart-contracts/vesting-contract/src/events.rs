@@ -27,6 +27,7 @@ pub const TRACK_MIXNODE_PLEDGE_DECREASE_EVENT_TYPE: &str = "track_mixnode_pledge
 pub const TRACK_GATEWAY_UNBOND_EVENT_TYPE: &str = "track_gateway_unbond";
 pub const TRACK_UNDELEGATION_EVENT_TYPE: &str = "track_undelegation";
 pub const TRACK_REWARD_EVENT_TYPE: &str = "track_reaward";
+pub const VESTED_STAKE_MIGRATION_EVENT_TYPE: &str = "vested_stake_migration";
 
 // attributes that are used in multiple places
 pub const OWNER_KEY: &str = "owner";
@@ -46,6 +47,11 @@ pub const NO_VALUE_VALUE: &str = "none";
 pub const START_TIME_KEY: &str = "start_time";
 pub const STAKING_ADDRESS_KEY: &str = "staking_address";
 
+// vested stake migration
+pub const MIGRATED_MIXNODE_KEY: &str = "migrated_mixnode";
+pub const MIGRATED_DELEGATIONS_KEY: &str = "migrated_delegations";
+pub const MIGRATION_COMPLETED_KEY: &str = "migration_completed";
+
 // OPEN QUESTION: would it make sense to also emit amount of vesting/locked coins here?
 // however, then it would require additional storage reads.
 pub fn new_vested_coins_withdraw_event(
@@ -100,6 +106,19 @@ pub fn new_periodic_vesting_account_event(
     event.add_attribute(START_TIME_KEY, start_time.to_string())
 }
 
+pub fn new_vested_stake_migration_event(
+    owner: &Addr,
+    migrated_mixnode: bool,
+    migrated_delegations: usize,
+    completed: bool,
+) -> Event {
+    Event::new(VESTED_STAKE_MIGRATION_EVENT_TYPE)
+        .add_attribute(OWNER_KEY, owner)
+        .add_attribute(MIGRATED_MIXNODE_KEY, migrated_mixnode.to_string())
+        .add_attribute(MIGRATED_DELEGATIONS_KEY, migrated_delegations.to_string())
+        .add_attribute(MIGRATION_COMPLETED_KEY, completed.to_string())
+}
+
 // In most cases the events are rather barebone as there's no point in attaching
 // bunch of data to them as it would be redundant. It is because in most cases when the event is emitted
 // a call to the mixnet contract is made that throws another event with relevant attributes already attached.
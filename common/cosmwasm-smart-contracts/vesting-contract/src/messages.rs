@@ -144,6 +144,12 @@ pub enum ExecuteMsg {
         owner: String,
         mix_id: MixId,
     },
+    /// Once the vesting schedule of the sender has completed, converts its mixnode bond and up to `limit`
+    /// of its delegations into ones that are no longer owned by the vesting contract.
+    /// It should be repeated until there are no more vesting delegations left.
+    MigrateVestedStake {
+        limit: Option<u32>,
+    },
 }
 
 impl ExecuteMsg {
@@ -183,6 +189,7 @@ impl ExecuteMsg {
             ExecuteMsg::TrackMigratedDelegation { .. } => {
                 "VestingExecuteMsg::TrackMigratedDelegation"
             }
+            ExecuteMsg::MigrateVestedStake { .. } => "VestingExecuteMsg::MigrateVestedStake",
         }
     }
 }
@@ -0,0 +1,18 @@
+/*
+ * Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- ticketbooks that got rejected by a gateway and thus must no longer be used for spending
+CREATE TABLE ecash_ticketbook_quarantine
+(
+    ticketbook_id INTEGER NOT NULL PRIMARY KEY REFERENCES ecash_ticketbook(id) ON DELETE CASCADE,
+
+    -- the error code returned by the gateway (if any)
+    error_code INTEGER,
+
+    -- human-readable reason for the rejection
+    reason TEXT NOT NULL,
+
+    quarantined_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
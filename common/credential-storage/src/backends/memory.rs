@@ -1,7 +1,10 @@
 // Copyright 2023-2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::models::{BasicTicketbookInformation, RetrievedPendingTicketbook, RetrievedTicketbook};
+use crate::models::{
+    BasicTicketbookInformation, QuarantinedTicketbook, RetrievedPendingTicketbook,
+    RetrievedTicketbook,
+};
 use nym_compact_ecash::scheme::coin_indices_signatures::AnnotatedCoinIndexSignature;
 use nym_compact_ecash::scheme::expiration_date_signatures::AnnotatedExpirationDateSignature;
 use nym_compact_ecash::VerificationKeyAuth;
@@ -26,6 +29,7 @@ pub struct MemoryEcachTicketbookManager {
 struct EcashCredentialManagerInner {
     ticketbooks: HashMap<i64, RetrievedTicketbook>,
     pending: HashMap<i64, RetrievedPendingTicketbook>,
+    quarantined: HashMap<i64, QuarantinedTicketbook>,
     master_vk: HashMap<u64, VerificationKeyAuth>,
     coin_indices_sigs: HashMap<u64, Vec<AnnotatedCoinIndexSignature>>,
     expiration_date_sigs: HashMap<Date, Vec<AnnotatedExpirationDateSignature>>,
@@ -68,6 +72,7 @@ impl MemoryEcachTicketbookManager {
 
         for id in to_remove {
             guard.ticketbooks.remove(&id);
+            guard.quarantined.remove(&id);
         }
     }
 
@@ -76,8 +81,12 @@ impl MemoryEcachTicketbookManager {
         tickets: u32,
     ) -> Option<RetrievedTicketbook> {
         let mut guard = self.inner.write().await;
+        let inner = &mut *guard;
 
-        for t in guard.ticketbooks.values_mut() {
+        for t in inner.ticketbooks.values_mut() {
+            if inner.quarantined.contains_key(&t.ticketbook_id) {
+                continue;
+            }
             if !t.ticketbook.expired()
                 && t.ticketbook.spent_tickets() + tickets as u64
                     <= t.ticketbook.params_total_tickets()
@@ -115,6 +124,20 @@ impl MemoryEcachTicketbookManager {
         }
     }
 
+    pub(crate) async fn quarantine_ticketbook(&self, quarantined: QuarantinedTicketbook) {
+        let mut guard = self.inner.write().await;
+
+        guard
+            .quarantined
+            .insert(quarantined.ticketbook_id, quarantined);
+    }
+
+    pub(crate) async fn get_quarantined_ticketbooks(&self) -> Vec<QuarantinedTicketbook> {
+        let guard = self.inner.read().await;
+
+        guard.quarantined.values().cloned().collect()
+    }
+
     pub(crate) async fn insert_pending_ticketbook(&self, ticketbook: &IssuanceTicketBook) {
         let mut guard = self.inner.write().await;
 
@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::models::{
-    BasicTicketbookInformation, RawCoinIndexSignatures, RawExpirationDateSignatures,
-    RawVerificationKey, StoredIssuedTicketbook, StoredPendingTicketbook,
+    BasicTicketbookInformation, QuarantinedTicketbook, RawCoinIndexSignatures,
+    RawExpirationDateSignatures, RawVerificationKey, StoredIssuedTicketbook,
+    StoredPendingTicketbook,
 };
 use nym_ecash_time::Date;
 use sqlx::{Executor, Sqlite, Transaction};
@@ -127,6 +128,34 @@ impl SqliteEcashTicketbookManager {
         Ok(affected > 0)
     }
 
+    pub(crate) async fn quarantine_ticketbook(
+        &self,
+        ticketbook_id: i64,
+        error_code: Option<u16>,
+        reason: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+                INSERT OR REPLACE INTO ecash_ticketbook_quarantine(ticketbook_id, error_code, reason)
+                VALUES (?, ?, ?)
+            "#,
+            ticketbook_id,
+            error_code,
+            reason
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn get_quarantined_ticketbooks(
+        &self,
+    ) -> Result<Vec<QuarantinedTicketbook>, sqlx::Error> {
+        sqlx::query_as("SELECT ticketbook_id, error_code, reason FROM ecash_ticketbook_quarantine")
+            .fetch_all(&self.connection_pool)
+            .await
+    }
+
     pub(crate) async fn get_pending_ticketbooks(
         &self,
     ) -> Result<Vec<StoredPendingTicketbook>, sqlx::Error> {
@@ -296,6 +325,7 @@ where
                 FROM ecash_ticketbook
                 WHERE used_tickets + ? <= total_tickets
                 AND expiration_date >= ?
+                AND id NOT IN (SELECT ticketbook_id FROM ecash_ticketbook_quarantine)
                 ORDER BY expiration_date ASC
                 LIMIT 1
             "#,
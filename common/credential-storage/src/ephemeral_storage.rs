@@ -3,7 +3,10 @@
 
 use crate::backends::memory::MemoryEcachTicketbookManager;
 use crate::error::StorageError;
-use crate::models::{BasicTicketbookInformation, RetrievedPendingTicketbook, RetrievedTicketbook};
+use crate::models::{
+    BasicTicketbookInformation, QuarantinedTicketbook, RetrievedPendingTicketbook,
+    RetrievedTicketbook,
+};
 use crate::storage::Storage;
 use async_trait::async_trait;
 use nym_compact_ecash::scheme::coin_indices_signatures::AnnotatedCoinIndexSignature;
@@ -111,6 +114,22 @@ impl Storage for EphemeralStorage {
             .await)
     }
 
+    async fn quarantine_ticketbook(
+        &self,
+        quarantined: QuarantinedTicketbook,
+    ) -> Result<(), Self::StorageError> {
+        self.storage_manager
+            .quarantine_ticketbook(quarantined)
+            .await;
+        Ok(())
+    }
+
+    async fn get_quarantined_ticketbooks(
+        &self,
+    ) -> Result<Vec<QuarantinedTicketbook>, Self::StorageError> {
+        Ok(self.storage_manager.get_quarantined_ticketbooks().await)
+    }
+
     async fn get_master_verification_key(
         &self,
         epoch_id: u64,
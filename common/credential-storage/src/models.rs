@@ -10,6 +10,18 @@ pub struct RetrievedTicketbook {
     pub ticketbook: IssuedTicketBook,
 }
 
+/// Ticketbook that got rejected by a gateway and must no longer be used.
+#[cfg_attr(not(target_arch = "wasm32"), derive(sqlx::FromRow))]
+#[derive(Debug, Clone)]
+pub struct QuarantinedTicketbook {
+    pub ticketbook_id: i64,
+
+    /// The error code returned by the gateway, if it has provided any.
+    pub error_code: Option<u16>,
+
+    pub reason: String,
+}
+
 pub struct RetrievedPendingTicketbook {
     pub pending_id: i64,
     pub pending_ticketbook: IssuanceTicketBook,
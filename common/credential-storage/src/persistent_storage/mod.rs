@@ -7,7 +7,10 @@ use crate::backends::sqlite::{
     get_next_unspent_ticketbook, increase_used_ticketbook_tickets, SqliteEcashTicketbookManager,
 };
 use crate::error::StorageError;
-use crate::models::{BasicTicketbookInformation, RetrievedPendingTicketbook, RetrievedTicketbook};
+use crate::models::{
+    BasicTicketbookInformation, QuarantinedTicketbook, RetrievedPendingTicketbook,
+    RetrievedTicketbook,
+};
 use crate::persistent_storage::legacy_helpers::{
     deserialise_v1_coin_index_signatures, deserialise_v1_expiration_date_signatures,
     deserialise_v1_master_verification_key,
@@ -221,6 +224,26 @@ impl Storage for PersistentStorage {
             .await?)
     }
 
+    async fn quarantine_ticketbook(
+        &self,
+        quarantined: QuarantinedTicketbook,
+    ) -> Result<(), Self::StorageError> {
+        self.storage_manager
+            .quarantine_ticketbook(
+                quarantined.ticketbook_id,
+                quarantined.error_code,
+                &quarantined.reason,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_quarantined_ticketbooks(
+        &self,
+    ) -> Result<Vec<QuarantinedTicketbook>, Self::StorageError> {
+        Ok(self.storage_manager.get_quarantined_ticketbooks().await?)
+    }
+
     async fn get_master_verification_key(
         &self,
         epoch_id: u64,
@@ -1,7 +1,10 @@
 // Copyright 2022-2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::models::{BasicTicketbookInformation, RetrievedPendingTicketbook, RetrievedTicketbook};
+use crate::models::{
+    BasicTicketbookInformation, QuarantinedTicketbook, RetrievedPendingTicketbook,
+    RetrievedTicketbook,
+};
 use async_trait::async_trait;
 use nym_compact_ecash::scheme::coin_indices_signatures::AnnotatedCoinIndexSignature;
 use nym_compact_ecash::scheme::expiration_date_signatures::AnnotatedExpirationDateSignature;
@@ -61,6 +64,17 @@ pub trait Storage: Send + Sync {
         expected_current_total_spent: u32,
     ) -> Result<bool, Self::StorageError>;
 
+    /// Marks the ticketbook as rejected so that it would no longer be returned
+    /// by `get_next_unspent_usable_ticketbook`.
+    async fn quarantine_ticketbook(
+        &self,
+        quarantined: QuarantinedTicketbook,
+    ) -> Result<(), Self::StorageError>;
+
+    async fn get_quarantined_ticketbooks(
+        &self,
+    ) -> Result<Vec<QuarantinedTicketbook>, Self::StorageError>;
+
     async fn get_master_verification_key(
         &self,
         epoch_id: u64,
@@ -91,3 +105,142 @@ pub trait Storage: Send + Sync {
         signatures: &AggregatedExpirationDateSignatures,
     ) -> Result<(), Self::StorageError>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ephemeral_storage::EphemeralStorage;
+    use nym_compact_ecash::ttp_keygen;
+    use nym_credentials::IssuanceTicketBook;
+    use nym_credentials_interface::TicketType;
+    use nym_crypto::asymmetric::identity;
+    use nym_ecash_time::{ecash_today, Date, EcashTime};
+    use rand::rngs::OsRng;
+    use std::fmt::Debug;
+    use time::Duration;
+
+    fn issue_ticketbook(deposit_id: u32, expiration_date: Date) -> IssuedTicketBook {
+        let keypair = ttp_keygen(1, 1).unwrap().remove(0);
+        let issuance = IssuanceTicketBook::new_with_expiration(
+            deposit_id,
+            deposit_id.to_be_bytes(),
+            identity::PrivateKey::new(&mut OsRng),
+            TicketType::V1MixnetEntry,
+            expiration_date,
+        );
+        let sig_req = issuance.prepare_for_signing();
+        let blind_sig = nym_compact_ecash::issue(
+            keypair.secret_key(),
+            sig_req.ecash_pub_key.clone(),
+            &sig_req.withdrawal_request,
+            expiration_date.ecash_unix_timestamp(),
+            issuance.ticketbook_type().encode(),
+        )
+        .unwrap();
+        let partial_wallet = issuance
+            .unblind_signature(
+                &keypair.verification_key(),
+                &sig_req,
+                blind_sig,
+                keypair.index.unwrap(),
+            )
+            .unwrap();
+        let wallet = issuance
+            .aggregate_signature_shares(&keypair.verification_key(), &[partial_wallet], sig_req)
+            .unwrap();
+        issuance.into_issued_ticketbook(wallet, 1)
+    }
+
+    async fn quarantined_ticketbooks_are_never_used<S>(storage: S)
+    where
+        S: Storage,
+        S::StorageError: Debug,
+    {
+        let today = ecash_today().date();
+        storage
+            .insert_coin_index_signatures(&AggregatedCoinIndicesSignatures {
+                epoch_id: 1,
+                signatures: Vec::new(),
+            })
+            .await
+            .unwrap();
+        for days in [1, 2] {
+            storage
+                .insert_expiration_date_signatures(&AggregatedExpirationDateSignatures {
+                    epoch_id: 1,
+                    expiration_date: today + Duration::days(days),
+                    signatures: Vec::new(),
+                })
+                .await
+                .unwrap();
+        }
+        storage
+            .insert_issued_ticketbook(&issue_ticketbook(1, today + Duration::days(1)))
+            .await
+            .unwrap();
+        storage
+            .insert_issued_ticketbook(&issue_ticketbook(2, today + Duration::days(2)))
+            .await
+            .unwrap();
+
+        let first = storage
+            .get_next_unspent_usable_ticketbook(1)
+            .await
+            .unwrap()
+            .unwrap();
+        storage
+            .quarantine_ticketbook(QuarantinedTicketbook {
+                ticketbook_id: first.ticketbook_id,
+                error_code: Some(42),
+                reason: "double spending".to_string(),
+            })
+            .await
+            .unwrap();
+
+        // so once it's rejected, the other one gets picked instead
+        let second = storage
+            .get_next_unspent_usable_ticketbook(1)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_ne!(second.ticketbook_id, first.ticketbook_id);
+
+        storage
+            .quarantine_ticketbook(QuarantinedTicketbook {
+                ticketbook_id: second.ticketbook_id,
+                error_code: None,
+                reason: "invalid signature".to_string(),
+            })
+            .await
+            .unwrap();
+        assert!(storage
+            .get_next_unspent_usable_ticketbook(1)
+            .await
+            .unwrap()
+            .is_none());
+
+        let mut quarantined = storage.get_quarantined_ticketbooks().await.unwrap();
+        quarantined.sort_by_key(|q| q.ticketbook_id);
+        assert_eq!(quarantined.len(), 2);
+        assert_eq!(quarantined[0].ticketbook_id, first.ticketbook_id);
+        assert_eq!(quarantined[0].error_code, Some(42));
+        assert_eq!(quarantined[0].reason, "double spending");
+        assert_eq!(quarantined[1].error_code, None);
+    }
+
+    #[tokio::test]
+    async fn ephemeral_storage_skips_quarantined_ticketbooks() {
+        quarantined_ticketbooks_are_never_used(EphemeralStorage::default()).await
+    }
+
+    #[cfg(feature = "persistent-storage")]
+    #[tokio::test]
+    async fn persistent_storage_skips_quarantined_ticketbooks() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage =
+            crate::persistent_storage::PersistentStorage::init(dir.path().join("credentials.db"))
+                .await
+                .unwrap();
+        quarantined_ticketbooks_are_never_used(storage).await
+    }
+}
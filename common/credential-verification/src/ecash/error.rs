@@ -19,7 +19,7 @@ pub enum EcashTicketError {
     #[error("the provided ticket failed to get verified")]
     MalformedTicket,
 
-    #[error("failed to verify provided ticket due to invalid expiration date signatures")]
+    #[error("failed to verify provided ticket: it has either expired or its expiration date signatures are invalid")]
     MalformedTicketInvalidDateSignatures,
 
     #[error("provided payinfo's public key does not match provider's")]
@@ -134,6 +134,14 @@ impl IssuedTicketBook {
         let params = nym_credentials_interface::ecash_parameters();
         let spend_date = ecash_today();
 
+        // the expiration date is proven in zero knowledge against the spend date,
+        // so there's no point in even attempting to construct the payment
+        if self.expired() {
+            return Err(Error::ExpiredTicketbook {
+                expiration_date: self.expiration_date,
+            });
+        }
+
         // make sure we still have enough tickets to spend
         Wallet::ensure_allowance(params, self.spent_tickets, tickets_to_spend)?;
 
@@ -182,6 +190,112 @@ impl VersionedSerialise for IssuedTicketBook {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::IssuanceTicketBook;
+    use nym_compact_ecash::tests::helpers::{
+        generate_coin_indices_signatures, generate_expiration_date_signatures,
+    };
+    use nym_compact_ecash::{issue, ttp_keygen, KeyPairAuth};
+    use nym_crypto::asymmetric::ed25519;
+    use rand::rngs::OsRng;
+    use time::Duration;
+
+    struct TestTicketBook {
+        keypair: KeyPairAuth,
+        ticketbook: IssuedTicketBook,
+        coin_indices_signatures: Vec<CoinIndexSignature>,
+        expiration_date_signatures: Vec<ExpirationDateSignature>,
+    }
+
+    impl TestTicketBook {
+        fn issue(expiration_date: Date) -> Self {
+            let keypair = ttp_keygen(1, 1).unwrap().remove(0);
+            let signing_key = ed25519::PrivateKey::new(&mut OsRng);
+
+            let issuance = IssuanceTicketBook::new_with_expiration(
+                42,
+                [],
+                signing_key,
+                TicketType::V1MixnetEntry,
+                expiration_date,
+            );
+            let sig_req = issuance.prepare_for_signing();
+            let expiration_date_signatures = generate_expiration_date_signatures(
+                sig_req.expiration_date.ecash_unix_timestamp(),
+                &[keypair.secret_key()],
+                &[keypair.verification_key()],
+                &keypair.verification_key(),
+                &[keypair.index.unwrap()],
+            )
+            .unwrap();
+            let blind_sig = issue(
+                keypair.secret_key(),
+                sig_req.ecash_pub_key.clone(),
+                &sig_req.withdrawal_request,
+                expiration_date.ecash_unix_timestamp(),
+                issuance.ticketbook_type().encode(),
+            )
+            .unwrap();
+            let partial_wallet = issuance
+                .unblind_signature(
+                    &keypair.verification_key(),
+                    &sig_req,
+                    blind_sig,
+                    keypair.index.unwrap(),
+                )
+                .unwrap();
+            let wallet = issuance
+                .aggregate_signature_shares(&keypair.verification_key(), &[partial_wallet], sig_req)
+                .unwrap();
+            let coin_indices_signatures = generate_coin_indices_signatures(
+                nym_credentials_interface::ecash_parameters(),
+                &[keypair.secret_key()],
+                &[keypair.verification_key()],
+                &keypair.verification_key(),
+                &[keypair.index.unwrap()],
+            )
+            .unwrap();
+
+            TestTicketBook {
+                ticketbook: issuance.into_issued_ticketbook(wallet, 1),
+                keypair,
+                coin_indices_signatures,
+                expiration_date_signatures,
+            }
+        }
+
+        fn spend(&mut self, tickets: u64) -> Result<CredentialSpendingData, Error> {
+            self.ticketbook.prepare_for_spending(
+                &self.keypair.verification_key(),
+                NymPayInfo::generate([6u8; 32]).into(),
+                &self.coin_indices_signatures,
+                &self.expiration_date_signatures,
+                tickets,
+            )
+        }
+    }
+
+    #[test]
+    fn valid_ticketbook_can_be_spent() {
+        let mut book = TestTicketBook::issue(ecash_today().date() + Duration::days(1));
+        assert!(!book.ticketbook.expired());
+
+        book.spend(1).unwrap();
+        assert_eq!(book.ticketbook.spent_tickets(), 1);
+    }
+
+    #[test]
+    fn expired_ticketbook_is_not_spent() {
+        let expiration_date = ecash_today().date() - Duration::days(1);
+        let mut book = TestTicketBook::issue(expiration_date);
+        assert!(book.ticketbook.expired());
+
+        let err = book.spend(1).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::ExpiredTicketbook { expiration_date: date } if date == expiration_date
+        ));
+        assert_eq!(book.ticketbook.spent_tickets(), 0);
+    }
 
     fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
 
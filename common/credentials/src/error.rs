@@ -6,6 +6,7 @@ use nym_credentials_interface::CompactEcashError;
 use nym_crypto::asymmetric::encryption::KeyRecoveryError;
 use nym_validator_client::ValidatorClientError;
 use thiserror::Error;
+use time::Date;
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -37,6 +38,9 @@ pub enum Error {
     #[error("Ran into a validator client error - {0}")]
     ValidatorClientError(#[from] ValidatorClientError),
 
+    #[error("the ticketbook has expired on {expiration_date} and can no longer be spent")]
+    ExpiredTicketbook { expiration_date: Date },
+
     #[error("Bandwidth operation overflowed. {0}")]
     BandwidthOverflow(String),
 
@@ -99,6 +99,36 @@ impl From<PrivateKey> for KeyPair {
     }
 }
 
+#[derive(Debug, Error)]
+#[error("failed to produce the signature: {0}")]
+pub struct SigningError(pub Box<dyn std::error::Error + Send + Sync>);
+
+/// Anything capable of producing signatures on behalf of an ed25519 identity.
+/// It allows the private key to live in an external signer, such as an HSM accessed via PKCS#11
+/// or the secure enclave of the OS, that never exposes the key itself.
+pub trait IdentitySigner: Send + Sync {
+    /// Returns the public key corresponding to the private key used for signing.
+    fn identity_key(&self) -> PublicKey;
+
+    fn try_sign(&self, message: &[u8]) -> Result<Signature, SigningError>;
+
+    /// Signs text, returning a base58 signature
+    fn try_sign_text(&self, text: &str) -> Result<String, SigningError> {
+        let signature_bytes = self.try_sign(text.as_bytes())?.to_bytes();
+        Ok(bs58::encode(signature_bytes).into_string())
+    }
+}
+
+impl IdentitySigner for KeyPair {
+    fn identity_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    fn try_sign(&self, message: &[u8]) -> Result<Signature, SigningError> {
+        Ok(self.private_key.sign(message))
+    }
+}
+
 impl PemStorableKeyPair for KeyPair {
     type PrivatePemKey = PrivateKey;
     type PublicPemKey = PublicKey;
@@ -398,4 +428,19 @@ mod tests {
         assert_zeroize::<PrivateKey>();
         assert_zeroize_on_drop::<PrivateKey>();
     }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn keypair_signer_matches_private_key() {
+        let keypair = KeyPair::new(&mut rand::thread_rng());
+        let signer: &dyn IdentitySigner = &keypair;
+
+        let signature = signer.try_sign(b"foomp").unwrap();
+        assert_eq!(signature, keypair.private_key().sign(b"foomp"));
+        assert!(signer.identity_key().verify(b"foomp", &signature).is_ok());
+        assert_eq!(
+            signer.try_sign_text("foomp").unwrap(),
+            keypair.private_key().sign_text("foomp")
+        );
+    }
 }
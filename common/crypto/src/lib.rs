@@ -23,9 +23,13 @@ pub use generic_array;
 // etc. algorithms and import them elsewhere as needed via common/crypto
 #[cfg(feature = "stream_cipher")]
 pub use aes;
+#[cfg(feature = "fips")]
+pub use aes_gcm::Aes256Gcm;
 #[cfg(feature = "aead")]
 pub use aes_gcm_siv::{Aes128GcmSiv, Aes256GcmSiv};
 #[cfg(feature = "hashing")]
 pub use blake3;
 #[cfg(feature = "stream_cipher")]
 pub use ctr;
+#[cfg(feature = "fips")]
+pub use sha2;
@@ -83,6 +83,20 @@ where
     cipher.encrypt_in_place(nonce, associated_data, buffer)
 }
 
+#[inline]
+pub fn encrypt_in_place_detached<A>(
+    key: &AeadKey<A>,
+    nonce: &Nonce<A>,
+    associated_data: &[u8],
+    buffer: &mut [u8],
+) -> Result<Tag<A>, AeadError>
+where
+    A: AeadInPlace + KeyInit,
+{
+    let cipher = A::new(key);
+    cipher.encrypt_in_place_detached(nonce, associated_data, buffer)
+}
+
 #[inline]
 pub fn decrypt_in_place<A>(
     key: &AeadKey<A>,
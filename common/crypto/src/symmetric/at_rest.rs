@@ -0,0 +1,305 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Encryption of data persisted (or otherwise exported) by a single party, such as the stored
+//! client messages, the journaled outbox or the reply storage records.
+//!
+//! The key is derived with HKDF from a secret the party already holds, so no additional key
+//! material has to be managed. Every encryption uses a fresh random nonce, which is stored together
+//! with the ciphertext, and the envelope starts with a byte identifying the primitives used:
+//! - [`AtRestSuite::Standard`]: HKDF with blake3 and AES-256-GCM-SIV, which, unlike plain
+//!   AES-GCM, does not catastrophically fail if a random nonce ever repeats,
+//! - [`AtRestSuite::Fips`]: HKDF with SHA-256 and AES-256-GCM, using only NIST-approved primitives.
+//!
+//! The suite is chosen at compile time. Binaries built with the `fips` feature only ever write
+//! the FIPS suite, but they can still read the data written with the standard suite before
+//! the feature got enabled, so that switching to such build doesn't lose any stored data.
+//! All other binaries use only the standard suite and can't read the FIPS data at all, which is
+//! reported as [`AtRestEncryptionError::UnsupportedSuite`] rather than as a generic decryption failure.
+
+use crate::generic_array::typenum::Unsigned;
+use crate::hkdf;
+use crate::symmetric::aead::{self, nonce_size, AeadKey, KeySizeUser, Nonce};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+#[derive(Debug, Error)]
+pub enum AtRestEncryptionError {
+    #[error("failed to encrypt the data")]
+    EncryptionFailure,
+
+    #[error("failed to decrypt the data - it's either corrupted or it was encrypted with a different key")]
+    DecryptionFailure,
+
+    #[error("the encrypted data has length of {received} which is too short to be valid")]
+    TooShort { received: usize },
+
+    #[error("the data has been encrypted with {suite} which is not supported by this binary")]
+    UnsupportedSuite { suite: AtRestSuite },
+
+    #[error("the data has been encrypted with unknown suite {tag}")]
+    UnknownSuite { tag: u8 },
+}
+
+/// Set of primitives used for deriving the key and encrypting the data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AtRestSuite {
+    Standard = 0,
+    Fips = 1,
+}
+
+impl std::fmt::Display for AtRestSuite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AtRestSuite::Standard => f.write_str("the standard suite (blake3, AES-256-GCM-SIV)"),
+            AtRestSuite::Fips => f.write_str("the FIPS suite (SHA-256, AES-256-GCM)"),
+        }
+    }
+}
+
+impl TryFrom<u8> for AtRestSuite {
+    type Error = AtRestEncryptionError;
+
+    fn try_from(tag: u8) -> Result<Self, Self::Error> {
+        match tag {
+            0 => Ok(AtRestSuite::Standard),
+            1 => Ok(AtRestSuite::Fips),
+            tag => Err(AtRestEncryptionError::UnknownSuite { tag }),
+        }
+    }
+}
+
+impl AtRestSuite {
+    /// The suite used by this binary.
+    pub const fn local() -> Self {
+        if cfg!(feature = "fips") {
+            AtRestSuite::Fips
+        } else {
+            AtRestSuite::Standard
+        }
+    }
+}
+
+type StandardEncryptionAlgorithm = crate::Aes256GcmSiv;
+type StandardHkdfAlgorithm = crate::blake3::Hasher;
+
+#[cfg(not(feature = "fips"))]
+type LocalEncryptionAlgorithm = StandardEncryptionAlgorithm;
+#[cfg(not(feature = "fips"))]
+type LocalHkdfAlgorithm = StandardHkdfAlgorithm;
+
+#[cfg(feature = "fips")]
+type LocalEncryptionAlgorithm = crate::Aes256Gcm;
+#[cfg(feature = "fips")]
+type LocalHkdfAlgorithm = crate::sha2::Sha256;
+
+const KEY_SIZE: usize = <LocalEncryptionAlgorithm as KeySizeUser>::KeySize::USIZE;
+
+/// Key for encrypting data at rest, derived for a particular purpose.
+pub struct AtRestKey {
+    key: Zeroizing<Vec<u8>>,
+
+    /// Key of the standard suite, only used for reading the data written before the binary
+    /// has been built with the `fips` feature.
+    #[cfg(feature = "fips")]
+    standard_key: Zeroizing<Vec<u8>>,
+}
+
+impl AtRestKey {
+    /// Derives the key from the provided secret. The `salt` must be unique to the purpose
+    /// of the key, while the optional `info` can bind it to a particular context, such as the owner.
+    pub fn derive(salt: &[u8], secret: &[u8], info: Option<&[u8]>) -> Self {
+        AtRestKey {
+            key: derive_secret(salt, secret, info, KEY_SIZE),
+            #[cfg(feature = "fips")]
+            standard_key: derive_standard_secret(salt, secret, info, KEY_SIZE),
+        }
+    }
+
+    fn aead_key(&self) -> &AeadKey<LocalEncryptionAlgorithm> {
+        AeadKey::<LocalEncryptionAlgorithm>::from_slice(&self.key)
+    }
+
+    /// Encrypts the data. The result is a concatenation of the suite tag,
+    /// the random nonce and the actual ciphertext.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, AtRestEncryptionError> {
+        let nonce = aead::random_nonce::<LocalEncryptionAlgorithm, _>(&mut rand::thread_rng());
+        let ciphertext =
+            aead::encrypt::<LocalEncryptionAlgorithm>(self.aead_key(), &nonce, plaintext)
+                .map_err(|_| AtRestEncryptionError::EncryptionFailure)?;
+
+        let mut stored = Vec::with_capacity(1 + nonce.len() + ciphertext.len());
+        stored.push(AtRestSuite::local() as u8);
+        stored.extend_from_slice(&nonce);
+        stored.extend_from_slice(&ciphertext);
+        Ok(stored)
+    }
+
+    /// Decrypts the data written with the local suite or, in `fips` builds,
+    /// with the standard suite used before the feature got enabled.
+    pub fn decrypt(&self, stored: &[u8]) -> Result<Vec<u8>, AtRestEncryptionError> {
+        // both suites use 96-bit nonces
+        let nonce_size = nonce_size::<LocalEncryptionAlgorithm>();
+        if stored.len() < 1 + nonce_size {
+            return Err(AtRestEncryptionError::TooShort {
+                received: stored.len(),
+            });
+        }
+
+        let suite = AtRestSuite::try_from(stored[0])?;
+        let (nonce, ciphertext) = stored[1..].split_at(nonce_size);
+        match suite {
+            suite if suite == AtRestSuite::local() => aead::decrypt::<LocalEncryptionAlgorithm>(
+                self.aead_key(),
+                Nonce::<LocalEncryptionAlgorithm>::from_slice(nonce),
+                ciphertext,
+            ),
+            #[cfg(feature = "fips")]
+            AtRestSuite::Standard => aead::decrypt::<StandardEncryptionAlgorithm>(
+                AeadKey::<StandardEncryptionAlgorithm>::from_slice(&self.standard_key),
+                Nonce::<StandardEncryptionAlgorithm>::from_slice(nonce),
+                ciphertext,
+            ),
+            suite => return Err(AtRestEncryptionError::UnsupportedSuite { suite }),
+        }
+        .map_err(|_| AtRestEncryptionError::DecryptionFailure)
+    }
+}
+
+/// Derives a secret of the requested length with the HKDF of the local [`AtRestSuite`],
+/// for data protected with a cipher other than [`AtRestKey`], e.g. the passphrase of a store cipher.
+/// Unlike with [`AtRestKey`], there's no fallback to the standard suite, so the secrets derived
+/// by the `fips` builds differ from the ones derived by all other binaries.
+pub fn derive_secret(
+    salt: &[u8],
+    secret: &[u8],
+    info: Option<&[u8]>,
+    length: usize,
+) -> Zeroizing<Vec<u8>> {
+    let okm = hkdf::extract_then_expand::<LocalHkdfAlgorithm>(Some(salt), secret, info, length)
+        .expect("somehow too long okm was provided");
+
+    Zeroizing::new(okm)
+}
+
+#[cfg(feature = "fips")]
+fn derive_standard_secret(
+    salt: &[u8],
+    secret: &[u8],
+    info: Option<&[u8]>,
+    length: usize,
+) -> Zeroizing<Vec<u8>> {
+    let okm = hkdf::extract_then_expand::<StandardHkdfAlgorithm>(Some(salt), secret, info, length)
+        .expect("somehow too long okm was provided");
+
+    Zeroizing::new(okm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SALT: &[u8] = b"NYM_AT_REST_TEST";
+
+    #[test]
+    fn encryption_roundtrip() {
+        let key = AtRestKey::derive(SALT, b"secret", None);
+        let data = b"hello world".to_vec();
+
+        let stored = key.encrypt(&data).unwrap();
+        assert_eq!(stored[0], AtRestSuite::local() as u8);
+        assert!(!stored.windows(data.len()).any(|w| w == data));
+        assert_eq!(key.decrypt(&stored).unwrap(), data);
+
+        // nonces are random, so the same data is never stored the same way
+        assert_ne!(key.encrypt(&data).unwrap(), stored);
+    }
+
+    #[test]
+    fn keys_are_bound_to_their_inputs() {
+        let key = AtRestKey::derive(SALT, b"secret", Some(b"owner"));
+        let stored = key.encrypt(b"hello world").unwrap();
+
+        for other in [
+            AtRestKey::derive(b"NYM_AT_REST_OTHER", b"secret", Some(b"owner")),
+            AtRestKey::derive(SALT, b"other secret", Some(b"owner")),
+            AtRestKey::derive(SALT, b"secret", Some(b"other owner")),
+            AtRestKey::derive(SALT, b"secret", None),
+        ] {
+            assert!(matches!(
+                other.decrypt(&stored),
+                Err(AtRestEncryptionError::DecryptionFailure)
+            ));
+        }
+    }
+
+    #[test]
+    fn malformed_data_is_rejected() {
+        let key = AtRestKey::derive(SALT, b"secret", None);
+        let mut stored = key.encrypt(b"hello world").unwrap();
+
+        assert!(matches!(
+            key.decrypt(&[]),
+            Err(AtRestEncryptionError::TooShort { received: 0 })
+        ));
+        assert!(matches!(
+            key.decrypt(&stored[..4]),
+            Err(AtRestEncryptionError::TooShort { received: 4 })
+        ));
+
+        let last = stored.len() - 1;
+        stored[last] ^= 1;
+        assert!(matches!(
+            key.decrypt(&stored),
+            Err(AtRestEncryptionError::DecryptionFailure)
+        ));
+
+        stored[0] = 42;
+        assert!(matches!(
+            key.decrypt(&stored),
+            Err(AtRestEncryptionError::UnknownSuite { tag: 42 })
+        ));
+    }
+
+    #[test]
+    #[cfg(not(feature = "fips"))]
+    fn fips_data_is_refused() {
+        let key = AtRestKey::derive(SALT, b"secret", None);
+        let mut stored = key.encrypt(b"hello world").unwrap();
+
+        stored[0] = AtRestSuite::Fips as u8;
+        assert!(matches!(
+            key.decrypt(&stored),
+            Err(AtRestEncryptionError::UnsupportedSuite {
+                suite: AtRestSuite::Fips
+            })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "fips")]
+    fn standard_data_can_still_be_read() {
+        let standard_key = derive_standard_secret(SALT, b"secret", None, KEY_SIZE);
+        let nonce = aead::random_nonce::<StandardEncryptionAlgorithm, _>(&mut rand::thread_rng());
+        let ciphertext = aead::encrypt::<StandardEncryptionAlgorithm>(
+            AeadKey::<StandardEncryptionAlgorithm>::from_slice(&standard_key),
+            &nonce,
+            b"hello world".as_slice(),
+        )
+        .unwrap();
+
+        let mut stored = vec![AtRestSuite::Standard as u8];
+        stored.extend_from_slice(&nonce);
+        stored.extend_from_slice(&ciphertext);
+
+        let key = AtRestKey::derive(SALT, b"secret", None);
+        assert_eq!(key.decrypt(&stored).unwrap(), b"hello world");
+
+        // but it's never written that way again
+        assert_eq!(
+            key.encrypt(b"hello world").unwrap()[0],
+            AtRestSuite::Fips as u8
+        );
+    }
+}
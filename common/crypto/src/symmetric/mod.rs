@@ -3,5 +3,7 @@
 
 #[cfg(feature = "aead")]
 pub mod aead;
+#[cfg(feature = "at_rest")]
+pub mod at_rest;
 #[cfg(feature = "stream_cipher")]
 pub mod stream_cipher;
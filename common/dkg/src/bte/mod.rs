@@ -1,10 +1,11 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::utils::hash_g2;
+use crate::error::DkgError;
+use crate::utils::{deserialize_g2, hash_g2};
 use crate::{Chunk, Share};
 use bls12_381::{G1Affine, G2Affine, G2Prepared, G2Projective, Gt};
-use group::Curve;
+use group::{Curve, GroupEncoding};
 use lazy_static::lazy_static;
 
 pub mod encryption;
@@ -73,3 +74,110 @@ pub fn setup() -> Params {
         _h_prepared: G2Prepared::from(h.to_affine()),
     }
 }
+
+impl Params {
+    /// Serializes the public parameters so that they could be published, for example in the DKG contract.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let g2_elements = self.fh.len() + 2;
+
+        // the extra 4 comes from the u32 we use for encoding the length of fh
+        let mut bytes = Vec::with_capacity(g2_elements * 96 + 4);
+
+        bytes.extend_from_slice(self.f0.to_bytes().as_ref());
+        bytes.extend_from_slice(&((self.fh.len() as u32).to_be_bytes()));
+        for fh_i in &self.fh {
+            bytes.extend_from_slice(fh_i.to_bytes().as_ref());
+        }
+        bytes.extend_from_slice(self.h.to_bytes().as_ref());
+
+        bytes
+    }
+
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, DkgError> {
+        // at the very least we require bytes for:
+        // - f0 ( 96 )
+        // - length indication of fh ( 4 )
+        // - h ( 96 )
+        if bytes.len() < 96 + 4 + 96 {
+            return Err(DkgError::new_deserialization_failure(
+                "Params",
+                "insufficient number of bytes provided",
+            ));
+        }
+
+        let mut i = 0;
+        let f0 = deserialize_g2(&bytes[i..i + 96]).ok_or_else(|| {
+            DkgError::new_deserialization_failure("Params.f0", "invalid curve point")
+        })?;
+        i += 96;
+
+        let fh_len = u32::from_be_bytes((&bytes[i..i + 4]).try_into().unwrap()) as usize;
+        i += 4;
+
+        if bytes[i..].len() != (fh_len + 1) * 96 {
+            return Err(DkgError::new_deserialization_failure(
+                "Params",
+                "insufficient number of bytes provided (fh)",
+            ));
+        }
+
+        let mut fh = Vec::with_capacity(fh_len);
+        for j in 0..fh_len {
+            let fh_i = deserialize_g2(&bytes[i..i + 96]).ok_or_else(|| {
+                DkgError::new_deserialization_failure(
+                    format!("Params.fh_{j}"),
+                    "invalid curve point",
+                )
+            })?;
+
+            fh.push(fh_i);
+            i += 96;
+        }
+
+        let h = deserialize_g2(&bytes[i..]).ok_or_else(|| {
+            DkgError::new_deserialization_failure("Params.h", "invalid curve point")
+        })?;
+
+        Ok(Params {
+            lambda_h: fh_len,
+            f0,
+            fh,
+            h,
+            _h_prepared: G2Prepared::from(h.to_affine()),
+        })
+    }
+}
+
+#[cfg(feature = "cw-types")]
+impl<'a> From<&'a Params> for nym_contracts_common::dealings::ContractSafeBytes {
+    fn from(params: &'a Params) -> Self {
+        nym_contracts_common::dealings::ContractSafeBytes(params.to_bytes())
+    }
+}
+
+#[cfg(feature = "cw-types")]
+impl<'a> TryFrom<&'a nym_contracts_common::dealings::ContractSafeBytes> for Params {
+    type Error = DkgError;
+
+    fn try_from(
+        value: &'a nym_contracts_common::dealings::ContractSafeBytes,
+    ) -> Result<Self, Self::Error> {
+        Params::try_from_bytes(&value.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params_roundtrip() {
+        let params = setup();
+        let bytes = params.to_bytes();
+        let recovered = Params::try_from_bytes(&bytes).unwrap();
+
+        assert_eq!(recovered.lambda_h, params.lambda_h);
+        assert_eq!(recovered.to_bytes(), bytes);
+        assert!(Params::try_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
+/// Default maximum number of buffers kept in a [`FrameBufferPool`].
+pub const DEFAULT_MAX_POOLED_BUFFERS: usize = 64;
+
+// buffers that have grown beyond this size (e.g. because of some unusually large message)
+// are not worth keeping around
+const MAX_POOLED_BUFFER_CAPACITY: usize = 64 * 1024;
+
+/// Pool of reusable buffers for the binary frames exchanged with the gateway.
+///
+/// Buffers of the received frames are released into the pool once their content has been
+/// decrypted and they are then reused for serialising and encrypting the outgoing frames,
+/// so that at a steady state, sending a mix packet does not require any fresh allocations.
+/// Cloning the pool is cheap and all the clones share the same buffers.
+#[derive(Clone)]
+pub struct FrameBufferPool {
+    buffers: Arc<Mutex<Vec<Vec<u8>>>>,
+    max_buffers: usize,
+}
+
+impl Debug for FrameBufferPool {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrameBufferPool")
+            .field("pooled", &self.pooled())
+            .field("max_buffers", &self.max_buffers)
+            .finish()
+    }
+}
+
+impl Default for FrameBufferPool {
+    fn default() -> Self {
+        FrameBufferPool::new(DEFAULT_MAX_POOLED_BUFFERS)
+    }
+}
+
+impl FrameBufferPool {
+    pub fn new(max_buffers: usize) -> Self {
+        FrameBufferPool {
+            buffers: Arc::new(Mutex::new(Vec::with_capacity(max_buffers))),
+            max_buffers,
+        }
+    }
+
+    /// Returns an empty buffer with at least the specified capacity,
+    /// reusing a pooled one if any is available.
+    pub fn acquire(&self, capacity: usize) -> Vec<u8> {
+        let pooled = match self.buffers.lock() {
+            Ok(mut buffers) => buffers.pop(),
+            Err(_) => None,
+        };
+
+        match pooled {
+            Some(mut buffer) => {
+                buffer.reserve(capacity);
+                buffer
+            }
+            None => Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the buffer into the pool so that it could be reused for a future frame.
+    /// The buffer is dropped instead if the pool is already full or if it has grown too large.
+    pub fn release(&self, mut buffer: Vec<u8>) {
+        if buffer.capacity() == 0 || buffer.capacity() > MAX_POOLED_BUFFER_CAPACITY {
+            return;
+        }
+
+        buffer.clear();
+        if let Ok(mut buffers) = self.buffers.lock() {
+            if buffers.len() < self.max_buffers {
+                buffers.push(buffer)
+            }
+        }
+    }
+
+    /// Number of buffers currently available in the pool.
+    pub fn pooled(&self) -> usize {
+        self.buffers
+            .lock()
+            .map(|buffers| buffers.len())
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn released_buffers_are_reused() {
+        let pool = FrameBufferPool::new(4);
+        let mut buffer = pool.acquire(128);
+        buffer.extend_from_slice(&[42; 100]);
+        let ptr = buffer.as_ptr();
+
+        pool.release(buffer);
+        assert_eq!(pool.pooled(), 1);
+
+        let reused = pool.acquire(64);
+        assert!(reused.is_empty());
+        assert!(reused.capacity() >= 128);
+        assert_eq!(reused.as_ptr(), ptr);
+        assert_eq!(pool.pooled(), 0);
+    }
+
+    #[test]
+    fn acquired_buffers_have_requested_capacity() {
+        let pool = FrameBufferPool::new(4);
+        pool.release(Vec::with_capacity(16));
+
+        assert!(pool.acquire(1024).capacity() >= 1024);
+        assert!(pool.acquire(1024).capacity() >= 1024);
+    }
+
+    #[test]
+    fn pool_size_is_bounded() {
+        let pool = FrameBufferPool::new(2);
+        for _ in 0..5 {
+            pool.release(Vec::with_capacity(16));
+        }
+        assert_eq!(pool.pooled(), 2);
+    }
+
+    #[test]
+    fn oversized_and_empty_buffers_are_not_pooled() {
+        let pool = FrameBufferPool::new(2);
+        pool.release(Vec::new());
+        pool.release(Vec::with_capacity(MAX_POOLED_BUFFER_CAPACITY + 1));
+        assert_eq!(pool.pooled(), 0);
+    }
+
+    #[test]
+    fn clones_share_the_buffers() {
+        let pool = FrameBufferPool::new(2);
+        let clone = pool.clone();
+        clone.release(Vec::with_capacity(16));
+        assert_eq!(pool.pooled(), 1);
+    }
+}
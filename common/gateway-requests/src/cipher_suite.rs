@@ -0,0 +1,115 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// Set of primitives used for deriving and using the shared key of the client-gateway channel.
+///
+/// Every binary supports exactly one suite, determined at compile time: binaries built with
+/// the `fips` feature only ever use the [`CipherSuite::Fips`] suite and refuse any non-approved
+/// fallbacks (including the legacy keys), while all other binaries only use the standard one.
+/// The suite is advertised during the registration and authentication, so that mismatched
+/// peers explicitly reject each other rather than silently derive incompatible keys.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CipherSuite {
+    /// HKDF with blake3 and AES256-GCM-SIV.
+    /// It is implied if the remote has not specified any suite, i.e. it predates the suite negotiation.
+    #[default]
+    Standard,
+
+    /// HKDF with SHA-256 and AES256-GCM, i.e. only the NIST-approved primitives.
+    Fips,
+}
+
+impl CipherSuite {
+    /// The only cipher suite supported by this binary.
+    pub const fn local() -> CipherSuite {
+        if cfg!(feature = "fips") {
+            CipherSuite::Fips
+        } else {
+            CipherSuite::Standard
+        }
+    }
+
+    // note: the standard suite is never explicitly serialized to stay compatible with older remotes
+    pub fn is_standard(&self) -> bool {
+        matches!(self, CipherSuite::Standard)
+    }
+
+    pub fn is_fips(&self) -> bool {
+        matches!(self, CipherSuite::Fips)
+    }
+
+    /// Checks whether this binary is able to use the suite.
+    pub fn is_supported(&self) -> bool {
+        *self == CipherSuite::local()
+    }
+
+    /// Checks whether a remote advertising the provided suite is able to use this suite.
+    pub fn is_supported_by(&self, advertised: CipherSuite) -> bool {
+        *self == advertised
+    }
+}
+
+impl Display for CipherSuite {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CipherSuite::Standard => write!(f, "standard (HKDF-blake3, AES256-GCM-SIV)"),
+            CipherSuite::Fips => write!(f, "fips (HKDF-SHA256, AES256-GCM)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper {
+        #[serde(default, skip_serializing_if = "CipherSuite::is_standard")]
+        cipher_suite: CipherSuite,
+    }
+
+    #[test]
+    fn only_the_local_suite_is_supported() {
+        assert!(CipherSuite::local().is_supported());
+        assert_eq!(
+            CipherSuite::Standard.is_supported(),
+            !cfg!(feature = "fips")
+        );
+        assert_eq!(CipherSuite::Fips.is_supported(), cfg!(feature = "fips"));
+    }
+
+    #[test]
+    fn suites_are_never_mixed_with_the_remote() {
+        assert!(CipherSuite::Standard.is_supported_by(CipherSuite::Standard));
+        assert!(!CipherSuite::Standard.is_supported_by(CipherSuite::Fips));
+        assert!(!CipherSuite::Fips.is_supported_by(CipherSuite::Standard));
+        assert!(CipherSuite::Fips.is_supported_by(CipherSuite::Fips));
+    }
+
+    #[test]
+    fn missing_suite_is_treated_as_standard() {
+        let wrapper: Wrapper = serde_json::from_str("{}").unwrap();
+        assert_eq!(wrapper.cipher_suite, CipherSuite::Standard);
+    }
+
+    #[test]
+    fn only_non_standard_suite_is_serialized() {
+        let standard = Wrapper {
+            cipher_suite: CipherSuite::Standard,
+        };
+        assert_eq!(serde_json::to_string(&standard).unwrap(), "{}");
+
+        let fips = Wrapper {
+            cipher_suite: CipherSuite::Fips,
+        };
+        let serialized = serde_json::to_string(&fips).unwrap();
+        assert_eq!(serialized, r#"{"cipher_suite":"fips"}"#);
+
+        let deserialized: Wrapper = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.cipher_suite, CipherSuite::Fips);
+    }
+}
@@ -8,11 +8,16 @@ use nym_sphinx::params::GatewayIntegrityHmacAlgorithm;
 pub use types::*;
 
 pub mod authentication;
+pub mod buffer_pool;
+pub mod cipher_suite;
 pub mod models;
 pub mod registration;
 pub mod shared_key;
+pub mod transport;
 pub mod types;
 
+pub use buffer_pool::FrameBufferPool;
+pub use cipher_suite::CipherSuite;
 pub use shared_key::helpers::SymmetricKey;
 pub use shared_key::legacy::{LegacySharedKeySize, LegacySharedKeys};
 pub use shared_key::{
@@ -2,8 +2,92 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::shared_key::SharedKeyUsageError;
+use crate::CipherSuite;
+use nym_crypto::asymmetric::identity::SigningError;
+use std::fmt::{self, Display, Formatter};
 use thiserror::Error;
 
+/// Reason of the handshake failure, as reported to the remote in the final `DONE(status)` message
+/// (or alongside the error message if the handshake failed before that).
+/// The numeric values are part of the wire protocol and thus must never be changed or reused.
+///
+/// Note that the reported reason is not authenticated, so it must only ever be used for diagnostics
+/// and never to influence the protocol choices, e.g. by retrying the handshake with the legacy keys.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum HandshakeFailureReason {
+    /// The remote did not specify any particular reason for the failure.
+    Unspecified,
+
+    /// The key material could not be decrypted with the derived shared key.
+    KeyMismatch,
+
+    /// The signature over the exchanged ephemeral keys was invalid.
+    InvalidSignature,
+
+    /// One of the handshake messages could not be parsed.
+    MalformedPayload,
+
+    /// The remote did not send the expected handshake message in time.
+    Timeout,
+
+    /// The remote is using a version of the handshake that is not supported.
+    UnsupportedVersion,
+
+    /// The remote has been built with a different cipher suite.
+    UnsupportedCipherSuite,
+
+    /// Reason that is not known to us, most likely introduced by a newer remote.
+    Unknown(u8),
+}
+
+impl HandshakeFailureReason {
+    /// Status byte of the final handshake message indicating success.
+    pub const SUCCESS_STATUS: u8 = 1;
+
+    pub const fn code(&self) -> u8 {
+        match self {
+            HandshakeFailureReason::Unspecified => 0,
+            HandshakeFailureReason::KeyMismatch => 2,
+            HandshakeFailureReason::InvalidSignature => 3,
+            HandshakeFailureReason::MalformedPayload => 4,
+            HandshakeFailureReason::Timeout => 5,
+            HandshakeFailureReason::UnsupportedVersion => 6,
+            HandshakeFailureReason::UnsupportedCipherSuite => 7,
+            HandshakeFailureReason::Unknown(code) => *code,
+        }
+    }
+}
+
+impl From<u8> for HandshakeFailureReason {
+    fn from(code: u8) -> Self {
+        match code {
+            0 => HandshakeFailureReason::Unspecified,
+            2 => HandshakeFailureReason::KeyMismatch,
+            3 => HandshakeFailureReason::InvalidSignature,
+            4 => HandshakeFailureReason::MalformedPayload,
+            5 => HandshakeFailureReason::Timeout,
+            6 => HandshakeFailureReason::UnsupportedVersion,
+            7 => HandshakeFailureReason::UnsupportedCipherSuite,
+            other => HandshakeFailureReason::Unknown(other),
+        }
+    }
+}
+
+impl From<HandshakeFailureReason> for u8 {
+    fn from(reason: HandshakeFailureReason) -> Self {
+        reason.code()
+    }
+}
+
+impl Display for HandshakeFailureReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HandshakeFailureReason::Unknown(code) => write!(f, "unknown reason {code}"),
+            known => write!(f, "{known:?} ({})", known.code()),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum HandshakeError {
     #[error("received key material of invalid length: {received}. Expected: {expected}")]
@@ -12,26 +96,102 @@ pub enum HandshakeError {
     #[error("no nonce has been provided for aes256-gcm-siv key derivation")]
     MissingNonceForCurrentKey,
 
+    #[error("the remote is using the {remote} cipher suite whilst we are using the {local} one")]
+    IncompatibleCipherSuite {
+        remote: CipherSuite,
+        local: CipherSuite,
+    },
+
+    #[error("the {suite} cipher suite is not supported by this binary")]
+    UnsupportedCipherSuite { suite: CipherSuite },
+
+    #[error("the legacy key derivation can't be used with the {suite} cipher suite")]
+    LegacyKeyWithNonStandardSuite { suite: CipherSuite },
+
+    #[error(
+        "the received key material could not be decrypted with the derived shared key: {source}"
+    )]
+    KeyMismatch { source: SharedKeyUsageError },
+
     #[error(transparent)]
     KeyUsageFailure(#[from] SharedKeyUsageError),
 
     #[error("received invalid signature")]
     InvalidSignature,
+
+    #[error("failed to sign the key material: {0}")]
+    SigningFailure(#[from] SigningError),
+
     #[error("encountered network error")]
     NetworkError,
-    #[error("encountered network error")]
+    #[error("the connection has been closed")]
     ClosedStream,
-    #[error("error on the remote: {0}")]
-    RemoteError(String),
-    #[error("received response was malformed:")]
+    #[error("error on the remote ({reason}): {message}")]
+    RemoteError {
+        message: String,
+        reason: HandshakeFailureReason,
+    },
+    #[error("the remote has rejected the handshake ({reason})")]
+    RemoteFailure { reason: HandshakeFailureReason },
+    #[error("received response was malformed")]
     MalformedResponse,
     #[error("sent request was malformed")]
     MalformedRequest,
-    #[error("sent request was malformed")]
-    HandshakeFailure,
     #[error("received shutdown")]
     ReceivedShutdown,
 
     #[error("timed out waiting for a handshake message")]
     Timeout,
 }
+
+impl HandshakeError {
+    /// Returns the reason of the failure to report to the remote,
+    /// or, if the failure has been reported by the remote, the reason it has sent.
+    pub fn failure_reason(&self) -> HandshakeFailureReason {
+        match self {
+            HandshakeError::KeyMismatch { .. } => HandshakeFailureReason::KeyMismatch,
+            HandshakeError::InvalidSignature => HandshakeFailureReason::InvalidSignature,
+            HandshakeError::KeyMaterialOfInvalidSize { .. }
+            | HandshakeError::MalformedResponse
+            | HandshakeError::MalformedRequest => HandshakeFailureReason::MalformedPayload,
+            HandshakeError::Timeout => HandshakeFailureReason::Timeout,
+            // the remote was expected to use the current key derivation, but it sent the legacy material
+            HandshakeError::MissingNonceForCurrentKey => HandshakeFailureReason::UnsupportedVersion,
+            HandshakeError::IncompatibleCipherSuite { .. }
+            | HandshakeError::UnsupportedCipherSuite { .. }
+            | HandshakeError::LegacyKeyWithNonStandardSuite { .. } => {
+                HandshakeFailureReason::UnsupportedCipherSuite
+            }
+            HandshakeError::RemoteError { reason, .. }
+            | HandshakeError::RemoteFailure { reason } => *reason,
+            HandshakeError::KeyUsageFailure(_)
+            | HandshakeError::SigningFailure(_)
+            | HandshakeError::NetworkError
+            | HandshakeError::ClosedStream
+            | HandshakeError::ReceivedShutdown => HandshakeFailureReason::Unspecified,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failure_reason_codes_roundtrip() {
+        for code in 0..=u8::MAX {
+            if code == HandshakeFailureReason::SUCCESS_STATUS {
+                continue;
+            }
+            assert_eq!(HandshakeFailureReason::from(code).code(), code);
+        }
+        assert_eq!(
+            HandshakeFailureReason::from(0),
+            HandshakeFailureReason::Unspecified
+        );
+        assert_eq!(
+            HandshakeFailureReason::from(42),
+            HandshakeFailureReason::Unknown(42)
+        );
+    }
+}
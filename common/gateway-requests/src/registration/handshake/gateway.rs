@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::registration::handshake::messages::{
-    HandshakeMessage, Initialisation, MaterialExchange,
+    Finalization, HandshakeMessage, Initialisation, MaterialExchange,
 };
 use crate::registration::handshake::state::State;
 use crate::registration::handshake::SharedGatewayKey;
@@ -11,10 +11,12 @@ use futures::{Sink, Stream};
 use tungstenite::Message as WsMessage;
 
 impl<'a, S, R> State<'a, S, R> {
+    // performs all the steps up to receiving the key material of the client,
+    // whose verification outcome is then reported in the final message
     async fn gateway_handshake_inner(
         &mut self,
         raw_init_message: Vec<u8>,
-    ) -> Result<(), HandshakeError>
+    ) -> Result<(Initialisation, MaterialExchange), HandshakeError>
     where
         S: Stream<Item = WsItem> + Sink<WsMessage> + Unpin,
     {
@@ -29,7 +31,7 @@ impl<'a, S, R> State<'a, S, R> {
         self.derive_shared_key(
             &init_message.ephemeral_dh,
             init_message.initiator_salt.as_deref(),
-        );
+        )?;
 
         // 3. send ephemeral x25519 pubkey alongside the encrypted signature
         // g^y || AES(k, sig(gate_priv, (g^y || g^x))
@@ -41,14 +43,7 @@ impl<'a, S, R> State<'a, S, R> {
         // 4. wait for the remote response with their own encrypted signature
         let materials = self.receive_handshake_message::<MaterialExchange>().await?;
 
-        // 5. verify the received signature using the locally derived keys
-        self.verify_remote_key_material(&materials, &init_message.ephemeral_dh)?;
-
-        // 6. finally send the finalization message to conclude the exchange
-        let finalizer = self.finalization_message();
-        self.send_handshake_data(finalizer).await?;
-
-        Ok(())
+        Ok((init_message, materials))
     }
 
     pub(crate) async fn perform_gateway_handshake(
@@ -59,8 +54,19 @@ impl<'a, S, R> State<'a, S, R> {
         S: Stream<Item = WsItem> + Sink<WsMessage> + Unpin,
     {
         let handshake_res = self.gateway_handshake_inner(raw_init_message).await;
-        self.check_for_handshake_processing_error(handshake_res)
+        let (init_message, materials) = self
+            .check_for_handshake_processing_error(handshake_res)
+            .await?;
+
+        // 5. verify the received signature using the locally derived keys
+        let verification = self.verify_remote_key_material(&materials, &init_message.ephemeral_dh);
+
+        // 6. finally send the finalization message to conclude the exchange.
+        // if the verification has failed, it carries the reason so that the client could tell what went wrong
+        self.send_handshake_data(Finalization::new(&verification))
             .await?;
+        verification?;
+
         Ok(self.finalize_handshake())
     }
 }
@@ -1,7 +1,7 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::registration::handshake::error::HandshakeError;
+use crate::registration::handshake::error::{HandshakeError, HandshakeFailureReason};
 use crate::registration::handshake::KDF_SALT_LENGTH;
 use nym_crypto::asymmetric::{ed25519, x25519};
 use nym_crypto::symmetric::aead::{nonce_size, tag_size};
@@ -53,15 +53,24 @@ pub struct GatewayMaterialExchange {
     pub materials: MaterialExchange,
 }
 
+// DONE(status)
 #[derive(Debug)]
 pub struct Finalization {
-    pub success: bool,
+    /// Reason of the failure, if the handshake has not succeeded.
+    pub failure: Option<HandshakeFailureReason>,
 }
 
 impl Finalization {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new<T>(result: &Result<T, HandshakeError>) -> Self {
+        Finalization {
+            failure: result.as_ref().err().map(HandshakeError::failure_reason),
+        }
+    }
+
     pub fn ensure_success(&self) -> Result<(), HandshakeError> {
-        if !self.success {
-            return Err(HandshakeError::HandshakeFailure);
+        if let Some(reason) = self.failure {
+            return Err(HandshakeError::RemoteFailure { reason });
         }
         Ok(())
     }
@@ -206,11 +215,15 @@ impl HandshakeMessage for GatewayMaterialExchange {
 }
 
 impl HandshakeMessage for Finalization {
+    // the legacy remotes only ever sent 0 (failure) or 1 (success) and treat any status other than 1 as a failure
     fn into_bytes(self) -> Vec<u8> {
-        if self.success {
-            vec![1]
-        } else {
-            vec![0]
+        match self.failure {
+            None => vec![HandshakeFailureReason::SUCCESS_STATUS],
+            // make sure a failure could never be mistaken for the success
+            Some(reason) if reason.code() == HandshakeFailureReason::SUCCESS_STATUS => {
+                vec![HandshakeFailureReason::Unspecified.code()]
+            }
+            Some(reason) => vec![reason.code()],
         }
     }
 
@@ -222,7 +235,7 @@ impl HandshakeMessage for Finalization {
             return Err(HandshakeError::MalformedResponse);
         }
 
-        let success = bytes[0] == 1;
-        Ok(Finalization { success })
+        let failure = (bytes[0] != HandshakeFailureReason::SUCCESS_STATUS).then(|| bytes[0].into());
+        Ok(Finalization { failure })
     }
 }
@@ -3,9 +3,9 @@
 
 use self::error::HandshakeError;
 use crate::registration::handshake::state::State;
-use crate::SharedGatewayKey;
+use crate::transport::GatewayTransport;
+use crate::{CipherSuite, SharedGatewayKey};
 use futures::future::BoxFuture;
-use futures::{Sink, Stream};
 use nym_crypto::asymmetric::identity;
 use rand::{CryptoRng, RngCore};
 use std::future::Future;
@@ -28,10 +28,8 @@ mod state;
 // realistically even 32bit would have sufficed, so 128 is definitely enough
 pub const KDF_SALT_LENGTH: usize = 16;
 
-// Note: the handshake is built on top of WebSocket, but in principle it shouldn't be too difficult
-// to remove that restriction, by just changing Sink<WsMessage> and Stream<Item = WsMessage> into
-// AsyncWrite and AsyncRead and slightly adjusting the implementation. But right now
-// we do not need to worry about that.
+// Note: the handshake is performed over any `GatewayTransport`, which by default is a WebSocket.
+// Plain byte streams (AsyncRead + AsyncWrite) can be used by wrapping them in a `FramedTransport`.
 
 pub struct GatewayHandshake<'a> {
     handshake_future: BoxFuture<'a, Result<SharedGatewayKey, HandshakeError>>,
@@ -48,14 +46,15 @@ impl<'a> Future for GatewayHandshake<'a> {
 pub fn client_handshake<'a, S, R>(
     rng: &'a mut R,
     ws_stream: &'a mut S,
-    identity: &'a identity::KeyPair,
+    identity: &'a dyn identity::IdentitySigner,
     gateway_pubkey: identity::PublicKey,
     expects_credential_usage: bool,
     derive_aes256_gcm_siv_key: bool,
+    cipher_suite: CipherSuite,
     #[cfg(not(target_arch = "wasm32"))] shutdown: TaskClient,
 ) -> GatewayHandshake<'a>
 where
-    S: Stream<Item = WsItem> + Sink<WsMessage> + Unpin + Send + 'a,
+    S: GatewayTransport + Unpin + Send + 'a,
     R: CryptoRng + RngCore + Send,
 {
     let state = State::new(
@@ -67,7 +66,8 @@ where
         shutdown,
     )
     .with_credential_usage(expects_credential_usage)
-    .with_aes256_gcm_siv_key(derive_aes256_gcm_siv_key);
+    .with_aes256_gcm_siv_key(derive_aes256_gcm_siv_key)
+    .with_cipher_suite(cipher_suite);
 
     GatewayHandshake {
         handshake_future: Box::pin(state.perform_client_handshake()),
@@ -78,15 +78,17 @@ where
 pub fn gateway_handshake<'a, S, R>(
     rng: &'a mut R,
     ws_stream: &'a mut S,
-    identity: &'a identity::KeyPair,
+    identity: &'a dyn identity::IdentitySigner,
     received_init_payload: Vec<u8>,
+    client_cipher_suite: CipherSuite,
     shutdown: TaskClient,
 ) -> GatewayHandshake<'a>
 where
-    S: Stream<Item = WsItem> + Sink<WsMessage> + Unpin + Send + 'a,
+    S: GatewayTransport + Unpin + Send + 'a,
     R: CryptoRng + RngCore + Send,
 {
-    let state = State::new(rng, ws_stream, identity, None, shutdown);
+    let state =
+        State::new(rng, ws_stream, identity, None, shutdown).with_cipher_suite(client_cipher_suite);
     GatewayHandshake {
         handshake_future: Box::pin(state.perform_gateway_handshake(received_init_payload)),
     }
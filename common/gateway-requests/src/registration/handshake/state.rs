@@ -1,15 +1,15 @@
 // Copyright 2020-2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::registration::handshake::error::HandshakeError;
+use crate::registration::handshake::error::{HandshakeError, HandshakeFailureReason};
 use crate::registration::handshake::messages::{
     HandshakeMessage, Initialisation, MaterialExchange,
 };
 use crate::registration::handshake::{SharedGatewayKey, WsItem, KDF_SALT_LENGTH};
 use crate::shared_key::SharedKeySize;
 use crate::{
-    types, LegacySharedKeySize, LegacySharedKeys, SharedSymmetricKey, AES_GCM_SIV_PROTOCOL_VERSION,
-    CREDENTIAL_UPDATE_V2_PROTOCOL_VERSION, INITIAL_PROTOCOL_VERSION,
+    types, CipherSuite, LegacySharedKeySize, LegacySharedKeys, SharedSymmetricKey,
+    AES_GCM_SIV_PROTOCOL_VERSION, CREDENTIAL_UPDATE_V2_PROTOCOL_VERSION, INITIAL_PROTOCOL_VERSION,
 };
 use futures::{Sink, SinkExt, Stream, StreamExt};
 use nym_crypto::asymmetric::{ed25519, x25519};
@@ -19,7 +19,11 @@ use nym_crypto::{
     generic_array::typenum::Unsigned,
     hkdf,
 };
-use nym_sphinx::params::{GatewayEncryptionAlgorithm, GatewaySharedKeyHkdfAlgorithm};
+#[cfg(feature = "fips")]
+use nym_sphinx::params::FipsGatewaySharedKeyHkdfAlgorithm;
+use nym_sphinx::params::GatewayEncryptionAlgorithm;
+#[cfg(not(feature = "fips"))]
+use nym_sphinx::params::GatewaySharedKeyHkdfAlgorithm;
 use rand::{thread_rng, CryptoRng, RngCore};
 use std::any::{type_name, Any};
 use std::str::FromStr;
@@ -45,8 +49,8 @@ pub(crate) struct State<'a, S, R> {
     rng: &'a mut R,
 
     /// Identity of the local "node" (client or gateway) which is used
-    /// during the handshake.
-    identity: &'a ed25519::KeyPair,
+    /// during the handshake. The private key itself might be held by an external signer.
+    identity: &'a dyn ed25519::IdentitySigner,
 
     /// Local ephemeral Diffie-Hellman keypair generated as a part of the handshake.
     ephemeral_keypair: x25519::KeyPair,
@@ -65,6 +69,10 @@ pub(crate) struct State<'a, S, R> {
     /// Specifies whether the end product should be an AES128Ctr + blake3 HMAC keys (legacy) or AES256-GCM-SIV (current)
     derive_aes256_gcm_siv_key: bool,
 
+    /// Set of primitives used for deriving and using the shared key.
+    /// It has to match the suite used by the remote.
+    cipher_suite: CipherSuite,
+
     // channel to receive shutdown signal
     #[cfg(not(target_arch = "wasm32"))]
     shutdown: TaskClient,
@@ -74,7 +82,7 @@ impl<'a, S, R> State<'a, S, R> {
     pub(crate) fn new(
         rng: &'a mut R,
         ws_stream: &'a mut S,
-        identity: &'a identity::KeyPair,
+        identity: &'a dyn identity::IdentitySigner,
         remote_pubkey: Option<identity::PublicKey>,
         #[cfg(not(target_arch = "wasm32"))] shutdown: TaskClient,
     ) -> Self
@@ -92,6 +100,7 @@ impl<'a, S, R> State<'a, S, R> {
             // later on this should become the default
             expects_credential_usage: false,
             derive_aes256_gcm_siv_key: false,
+            cipher_suite: CipherSuite::Standard,
             #[cfg(not(target_arch = "wasm32"))]
             shutdown,
         }
@@ -107,6 +116,11 @@ impl<'a, S, R> State<'a, S, R> {
         self
     }
 
+    pub(crate) fn with_cipher_suite(mut self, cipher_suite: CipherSuite) -> Self {
+        self.cipher_suite = cipher_suite;
+        self
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     pub(crate) fn set_aes256_gcm_siv_key_derivation(&mut self, derive_aes256_gcm_siv_key: bool) {
         self.derive_aes256_gcm_siv_key = derive_aes256_gcm_siv_key;
@@ -135,24 +149,29 @@ impl<'a, S, R> State<'a, S, R> {
     // initializer's identity from another source.
     pub(crate) fn init_message(&self, initiator_salt: Option<Vec<u8>>) -> Initialisation {
         Initialisation {
-            identity: *self.identity.public_key(),
+            identity: self.identity.identity_key(),
             ephemeral_dh: *self.ephemeral_keypair.public_key(),
             initiator_salt,
         }
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
-    pub(crate) fn finalization_message(
-        &self,
-    ) -> crate::registration::handshake::messages::Finalization {
-        crate::registration::handshake::messages::Finalization { success: true }
-    }
-
     pub(crate) fn derive_shared_key(
         &mut self,
         remote_ephemeral_key: &encryption::PublicKey,
         initiator_salt: Option<&[u8]>,
-    ) {
+    ) -> Result<(), HandshakeError> {
+        if !self.cipher_suite.is_supported() {
+            return Err(HandshakeError::UnsupportedCipherSuite {
+                suite: self.cipher_suite,
+            });
+        }
+        // the legacy keys are only ever derived with the standard primitives
+        if !self.derive_aes256_gcm_siv_key && !self.cipher_suite.is_standard() {
+            return Err(HandshakeError::LegacyKeyWithNonStandardSuite {
+                suite: self.cipher_suite,
+            });
+        }
+
         let dh_result = self
             .ephemeral_keypair
             .private_key()
@@ -165,24 +184,37 @@ impl<'a, S, R> State<'a, S, R> {
         };
 
         // there is no reason for this to fail as our okm is expected to be only 16 bytes
-        let okm = hkdf::extract_then_expand::<GatewaySharedKeyHkdfAlgorithm>(
-            initiator_salt,
-            &dh_result,
-            None,
-            key_size,
-        )
+        let okm = match self.cipher_suite {
+            #[cfg(not(feature = "fips"))]
+            CipherSuite::Standard => hkdf::extract_then_expand::<GatewaySharedKeyHkdfAlgorithm>(
+                initiator_salt,
+                &dh_result,
+                None,
+                key_size,
+            ),
+            #[cfg(feature = "fips")]
+            CipherSuite::Fips => hkdf::extract_then_expand::<FipsGatewaySharedKeyHkdfAlgorithm>(
+                initiator_salt,
+                &dh_result,
+                None,
+                key_size,
+            ),
+            _ => unreachable!("the suite support has already been checked"),
+        }
         .expect("somehow too long okm was provided");
 
         let shared_key = if self.derive_aes256_gcm_siv_key {
             let current_key = SharedSymmetricKey::try_from_bytes(&okm)
-                .expect("okm was expanded to incorrect length!");
+                .expect("okm was expanded to incorrect length!")
+                .with_cipher_suite(self.cipher_suite);
             SharedGatewayKey::Current(current_key)
         } else {
             let legacy_key = LegacySharedKeys::try_from_bytes(&okm)
                 .expect("okm was expanded to incorrect length!");
             SharedGatewayKey::Legacy(legacy_key)
         };
-        self.derived_shared_keys = Some(shared_key)
+        self.derived_shared_keys = Some(shared_key);
+        Ok(())
     }
 
     // produces AES(k, SIG(ID_PRIV, G^x || G^y),
@@ -198,7 +230,7 @@ impl<'a, S, R> State<'a, S, R> {
             .into_iter()
             .chain(remote_ephemeral_key.to_bytes())
             .collect();
-        let signature = self.identity.private_key().sign(plaintext);
+        let signature = self.identity.try_sign(&plaintext)?;
 
         let nonce = if self.derive_aes256_gcm_siv_key {
             let mut rng = thread_rng();
@@ -237,10 +269,12 @@ impl<'a, S, R> State<'a, S, R> {
         }
 
         // first decrypt received data
-        let decrypted_signature = derived_shared_key.decrypt_naive(
-            &remote_response.signature_ciphertext,
-            remote_response.nonce.as_deref(),
-        )?;
+        let decrypted_signature = derived_shared_key
+            .decrypt_naive(
+                &remote_response.signature_ciphertext,
+                remote_response.nonce.as_deref(),
+            )
+            .map_err(|source| HandshakeError::KeyMismatch { source })?;
 
         // now verify signature itself
         let signature = identity::Signature::from_bytes(&decrypted_signature)
@@ -265,7 +299,10 @@ impl<'a, S, R> State<'a, S, R> {
         self.remote_pubkey = Some(remote_pubkey)
     }
 
-    fn on_wg_msg(msg: Option<WsItem>) -> Result<Option<Vec<u8>>, HandshakeError> {
+    fn on_wg_msg(
+        msg: Option<WsItem>,
+        local_suite: CipherSuite,
+    ) -> Result<Option<Vec<u8>>, HandshakeError> {
         let Some(msg) = msg else {
             return Err(HandshakeError::ClosedStream);
         };
@@ -281,11 +318,27 @@ impl<'a, S, R> State<'a, S, R> {
                             // hehe, that's a bit disgusting that the type system requires we explicitly ignore the
                             // protocol_version field that we actually never attach at this point
                             // yet another reason for the overdue refactor
-                            types::RegistrationHandshake::HandshakePayload { data, .. } => {
+                            types::RegistrationHandshake::HandshakePayload {
+                                data,
+                                cipher_suite,
+                                ..
+                            } => {
+                                // if the suites differ, we'd have derived different keys anyway
+                                if cipher_suite != local_suite {
+                                    return Err(HandshakeError::IncompatibleCipherSuite {
+                                        remote: cipher_suite,
+                                        local: local_suite,
+                                    });
+                                }
                                 Ok(Some(data))
                             }
-                            types::RegistrationHandshake::HandshakeError { message } => {
-                                Err(HandshakeError::RemoteError(message))
+                            types::RegistrationHandshake::HandshakeError { message, code } => {
+                                Err(HandshakeError::RemoteError {
+                                    message,
+                                    reason: code
+                                        .map(Into::into)
+                                        .unwrap_or(HandshakeFailureReason::Unspecified),
+                                })
                             }
                         }
                     }
@@ -312,7 +365,7 @@ impl<'a, S, R> State<'a, S, R> {
                 biased;
                 _ = self.shutdown.recv() => return Err(HandshakeError::ReceivedShutdown),
                 msg = self.ws_stream.next() => {
-                    let Some(ret) = Self::on_wg_msg(msg)? else {
+                    let Some(ret) = Self::on_wg_msg(msg, self.cipher_suite)? else {
                         continue;
                     };
                     return Ok(ret);
@@ -328,7 +381,7 @@ impl<'a, S, R> State<'a, S, R> {
     {
         loop {
             let msg = self.ws_stream.next().await;
-            let Some(ret) = Self::on_wg_msg(msg)? else {
+            let Some(ret) = Self::on_wg_msg(msg, self.cipher_suite)? else {
                 continue;
             };
             return Ok(ret);
@@ -355,11 +408,12 @@ impl<'a, S, R> State<'a, S, R> {
     pub(crate) async fn send_handshake_error<M: Into<String>>(
         &mut self,
         message: M,
+        reason: HandshakeFailureReason,
     ) -> Result<(), HandshakeError>
     where
         S: Sink<WsMessage> + Unpin,
     {
-        let handshake_message = types::RegistrationHandshake::new_error(message);
+        let handshake_message = types::RegistrationHandshake::new_error(message, reason);
         self.ws_stream
             .send(WsMessage::Text(handshake_message.try_into().unwrap()))
             .await
@@ -389,6 +443,7 @@ impl<'a, S, R> State<'a, S, R> {
         let handshake_message = types::RegistrationHandshake::new_payload(
             inner_message.into_bytes(),
             self.request_protocol_version(),
+            self.cipher_suite,
         );
         self.ws_stream
             .send(WsMessage::Text(handshake_message.try_into().unwrap()))
@@ -415,7 +470,8 @@ impl<'a, S, R> State<'a, S, R> {
         match result {
             Ok(ok) => Ok(ok),
             Err(err) => {
-                self.send_handshake_error(err.to_string()).await?;
+                self.send_handshake_error(err.to_string(), err.failure_reason())
+                    .await?;
                 Err(err)
             }
         }
@@ -5,16 +5,19 @@ use nym_crypto::blake3;
 use nym_crypto::crypto_hash::compute_digest;
 use nym_crypto::generic_array::{typenum::Unsigned, GenericArray};
 use nym_crypto::symmetric::aead::{
-    self, nonce_size, random_nonce, AeadError, AeadKey, KeySizeUser, Nonce,
+    self, nonce_size, random_nonce, tag_size, AeadError, AeadKey, KeySizeUser, Nonce,
 };
 use nym_crypto::symmetric::stream_cipher::{iv_size, random_iv, IV};
 use nym_pemstore::traits::PemStorableKey;
+#[cfg(feature = "fips")]
+use nym_sphinx::params::FipsGatewayEncryptionAlgorithm;
 use nym_sphinx::params::{GatewayEncryptionAlgorithm, LegacyGatewayEncryptionAlgorithm};
 use rand::thread_rng;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
+use crate::{CipherSuite, LegacyGatewayMacSize};
 pub use legacy::LegacySharedKeys;
 
 pub mod helpers;
@@ -33,6 +36,21 @@ impl SharedGatewayKey {
         matches!(self, SharedGatewayKey::Legacy(..))
     }
 
+    /// Returns the cipher suite the key is meant to be used with, unless it's a legacy key.
+    pub fn cipher_suite(&self) -> Option<CipherSuite> {
+        match self {
+            SharedGatewayKey::Current(key) => Some(key.cipher_suite()),
+            SharedGatewayKey::Legacy(_) => None,
+        }
+    }
+
+    /// Sets the cipher suite the key is meant to be used with. It has no effect on the legacy keys.
+    pub fn set_cipher_suite(&mut self, cipher_suite: CipherSuite) {
+        if let SharedGatewayKey::Current(key) = self {
+            key.cipher_suite = cipher_suite
+        }
+    }
+
     pub fn aes128_ctr_hmac_bs58(&self) -> Option<Zeroizing<String>> {
         match self {
             SharedGatewayKey::Current(_) => None,
@@ -79,6 +97,14 @@ impl SharedGatewayKey {
             SharedGatewayKey::Legacy(_) => iv_size::<LegacyGatewayEncryptionAlgorithm>(),
         }
     }
+
+    /// Size of the authentication tag (or the legacy MAC) attached to every ciphertext.
+    pub fn tag_size(&self) -> usize {
+        match self {
+            SharedGatewayKey::Current(_) => tag_size::<GatewayEncryptionAlgorithm>(),
+            SharedGatewayKey::Legacy(_) => LegacyGatewayMacSize::to_usize(),
+        }
+    }
 }
 
 impl From<LegacySharedKeys> for SharedGatewayKey {
@@ -109,9 +135,22 @@ pub enum SharedKeyUsageError {
 
     #[error("failed to either encrypt or decrypt provided message")]
     AeadFailure(#[from] AeadError),
+
+    #[error("the key is meant to be used with the '{suite}' cipher suite which is not supported by this binary")]
+    UnsupportedCipherSuite { suite: CipherSuite },
 }
 
 impl SharedGatewayKey {
+    // the legacy keys rely on the non-approved primitives (AES128-CTR with blake3 HMAC)
+    fn ensure_legacy_allowed() -> Result<(), SharedKeyUsageError> {
+        if cfg!(feature = "fips") {
+            return Err(SharedKeyUsageError::UnsupportedCipherSuite {
+                suite: CipherSuite::Standard,
+            });
+        }
+        Ok(())
+    }
+
     fn validate_aead_nonce(
         raw: Option<&[u8]>,
     ) -> Result<Nonce<GatewayEncryptionAlgorithm>, SharedKeyUsageError> {
@@ -151,6 +190,7 @@ impl SharedGatewayKey {
                 aes_gcm_siv.encrypt(plaintext, &nonce)
             }
             SharedGatewayKey::Legacy(aes_ctr) => {
+                Self::ensure_legacy_allowed()?;
                 let iv = Self::validate_cipher_iv(raw_nonce)?;
                 Ok(aes_ctr.encrypt_and_tag(plaintext, iv))
             }
@@ -169,6 +209,7 @@ impl SharedGatewayKey {
                 aes_gcm_siv.decrypt(ciphertext, &nonce)
             }
             SharedGatewayKey::Legacy(aes_ctr) => {
+                Self::ensure_legacy_allowed()?;
                 let iv = Self::validate_cipher_iv(raw_nonce)?;
                 aes_ctr.decrypt_tagged(ciphertext, iv)
             }
@@ -188,6 +229,7 @@ impl SharedGatewayKey {
                 aes_gcm_siv.encrypt(plaintext, &nonce)
             }
             SharedGatewayKey::Legacy(aes_ctr) => {
+                Self::ensure_legacy_allowed()?;
                 let iv = Self::validate_cipher_iv(raw_nonce)?;
                 Ok(aes_ctr.encrypt_without_tagging(plaintext, iv))
             }
@@ -207,6 +249,7 @@ impl SharedGatewayKey {
                 aes_gcm_siv.decrypt(ciphertext, &nonce)
             }
             SharedGatewayKey::Legacy(aes_ctr) => {
+                Self::ensure_legacy_allowed()?;
                 let iv = Self::validate_cipher_iv(raw_nonce)?;
                 aes_ctr.decrypt_without_tag(ciphertext, iv)
             }
@@ -215,7 +258,14 @@ impl SharedGatewayKey {
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
-pub struct SharedSymmetricKey(AeadKey<GatewayEncryptionAlgorithm>);
+pub struct SharedSymmetricKey {
+    key: AeadKey<GatewayEncryptionAlgorithm>,
+
+    // the suite is not part of the key material, so it's up to the owner to restore it when loading the key
+    #[serde(default, skip)]
+    #[zeroize(skip)]
+    cipher_suite: CipherSuite,
+}
 
 type KeySize = <GatewayEncryptionAlgorithm as KeySizeUser>::KeySize;
 
@@ -238,11 +288,27 @@ impl SharedSymmetricKey {
             });
         }
 
-        Ok(SharedSymmetricKey(GenericArray::clone_from_slice(bytes)))
+        Ok(SharedSymmetricKey {
+            key: GenericArray::clone_from_slice(bytes),
+            cipher_suite: CipherSuite::local(),
+        })
+    }
+
+    #[must_use]
+    pub fn with_cipher_suite(mut self, cipher_suite: CipherSuite) -> Self {
+        self.cipher_suite = cipher_suite;
+        self
+    }
+
+    pub fn cipher_suite(&self) -> CipherSuite {
+        self.cipher_suite
     }
 
     pub fn zeroizing_clone(&self) -> Zeroizing<Self> {
-        Zeroizing::new(SharedSymmetricKey(self.0))
+        Zeroizing::new(SharedSymmetricKey {
+            key: self.key,
+            cipher_suite: self.cipher_suite,
+        })
     }
 
     pub fn digest(&self) -> Vec<u8> {
@@ -250,11 +316,11 @@ impl SharedSymmetricKey {
     }
 
     pub fn as_bytes(&self) -> &[u8] {
-        self.0.as_slice()
+        self.key.as_slice()
     }
 
     pub fn to_bytes(&self) -> Vec<u8> {
-        self.0.iter().copied().collect()
+        self.key.iter().copied().collect()
     }
 
     pub fn try_from_base58_string<S: Into<String>>(
@@ -270,12 +336,30 @@ impl SharedSymmetricKey {
         bs58::encode(bytes).into_string()
     }
 
+    // only the suite of this binary is compiled in, see `CipherSuite::local`
+    fn unsupported_suite(&self) -> SharedKeyUsageError {
+        SharedKeyUsageError::UnsupportedCipherSuite {
+            suite: self.cipher_suite,
+        }
+    }
+
     pub fn encrypt(
         &self,
         plaintext: &[u8],
         nonce: &Nonce<GatewayEncryptionAlgorithm>,
     ) -> Result<Vec<u8>, SharedKeyUsageError> {
-        aead::encrypt::<GatewayEncryptionAlgorithm>(&self.0, nonce, plaintext).map_err(Into::into)
+        match self.cipher_suite {
+            #[cfg(not(feature = "fips"))]
+            CipherSuite::Standard => {
+                aead::encrypt::<GatewayEncryptionAlgorithm>(&self.key, nonce, plaintext)
+            }
+            #[cfg(feature = "fips")]
+            CipherSuite::Fips => {
+                aead::encrypt::<FipsGatewayEncryptionAlgorithm>(&self.key, nonce, plaintext)
+            }
+            _ => return Err(self.unsupported_suite()),
+        }
+        .map_err(Into::into)
     }
 
     pub fn decrypt(
@@ -283,7 +367,46 @@ impl SharedSymmetricKey {
         ciphertext: &[u8],
         nonce: &Nonce<GatewayEncryptionAlgorithm>,
     ) -> Result<Vec<u8>, SharedKeyUsageError> {
-        aead::decrypt::<GatewayEncryptionAlgorithm>(&self.0, nonce, ciphertext).map_err(Into::into)
+        match self.cipher_suite {
+            #[cfg(not(feature = "fips"))]
+            CipherSuite::Standard => {
+                aead::decrypt::<GatewayEncryptionAlgorithm>(&self.key, nonce, ciphertext)
+            }
+            #[cfg(feature = "fips")]
+            CipherSuite::Fips => {
+                aead::decrypt::<FipsGatewayEncryptionAlgorithm>(&self.key, nonce, ciphertext)
+            }
+            _ => return Err(self.unsupported_suite()),
+        }
+        .map_err(Into::into)
+    }
+
+    /// Encrypts the plaintext located at the end of the buffer, starting at the `offset`,
+    /// and appends the authentication tag, producing the same output as [`Self::encrypt`]
+    /// without any intermediate allocations.
+    pub fn encrypt_in_place(
+        &self,
+        buffer: &mut Vec<u8>,
+        offset: usize,
+        nonce: &Nonce<GatewayEncryptionAlgorithm>,
+    ) -> Result<(), SharedKeyUsageError> {
+        let plaintext = buffer
+            .get_mut(offset..)
+            .ok_or(SharedKeyUsageError::TooShortRequest)?;
+        let tag =
+            match self.cipher_suite {
+                #[cfg(not(feature = "fips"))]
+                CipherSuite::Standard => aead::encrypt_in_place_detached::<
+                    GatewayEncryptionAlgorithm,
+                >(&self.key, nonce, &[], plaintext)?,
+                #[cfg(feature = "fips")]
+                CipherSuite::Fips => aead::encrypt_in_place_detached::<
+                    FipsGatewayEncryptionAlgorithm,
+                >(&self.key, nonce, &[], plaintext)?,
+                _ => return Err(self.unsupported_suite()),
+            };
+        buffer.extend_from_slice(&tag);
+        Ok(())
     }
 }
 
@@ -302,3 +425,56 @@ impl PemStorableKey for SharedSymmetricKey {
         Self::try_from_bytes(bytes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn other_suite() -> CipherSuite {
+        match CipherSuite::local() {
+            CipherSuite::Standard => CipherSuite::Fips,
+            CipherSuite::Fips => CipherSuite::Standard,
+        }
+    }
+
+    #[test]
+    fn keys_can_only_be_used_with_the_local_suite() {
+        let local = SharedSymmetricKey::try_from_bytes(&[42u8; 32]).unwrap();
+        assert_eq!(local.cipher_suite(), CipherSuite::local());
+        let other = SharedSymmetricKey::try_from_bytes(&[42u8; 32])
+            .unwrap()
+            .with_cipher_suite(other_suite());
+        let nonce = random_nonce::<GatewayEncryptionAlgorithm, _>(&mut thread_rng());
+
+        let ciphertext = local.encrypt(b"foomp", &nonce).unwrap();
+        assert_eq!(local.decrypt(&ciphertext, &nonce).unwrap(), b"foomp");
+
+        assert!(matches!(
+            other.encrypt(b"foomp", &nonce),
+            Err(SharedKeyUsageError::UnsupportedCipherSuite { suite }) if suite == other_suite()
+        ));
+        assert!(matches!(
+            other.decrypt(&ciphertext, &nonce),
+            Err(SharedKeyUsageError::UnsupportedCipherSuite { suite }) if suite == other_suite()
+        ));
+    }
+
+    #[test]
+    fn legacy_keys_are_refused_in_fips_builds() {
+        let legacy: SharedGatewayKey = LegacySharedKeys::try_from_bytes(&[42u8; 32])
+            .unwrap()
+            .into();
+        let encrypted = legacy.encrypt(b"foomp", None);
+        if cfg!(feature = "fips") {
+            assert!(matches!(
+                encrypted,
+                Err(SharedKeyUsageError::UnsupportedCipherSuite {
+                    suite: CipherSuite::Standard
+                })
+            ));
+        } else {
+            let ciphertext = encrypted.unwrap();
+            assert_eq!(legacy.decrypt(&ciphertext, None).unwrap(), b"foomp");
+        }
+    }
+}
@@ -0,0 +1,403 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::FrameBufferPool;
+use bytes::{Buf, BufMut, BytesMut};
+use futures::io::{AsyncRead, AsyncWrite};
+use futures::{ready, Sink, Stream};
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Formatter};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tungstenite::error::CapacityError;
+use tungstenite::protocol::frame::coding::CloseCode;
+use tungstenite::protocol::CloseFrame;
+use tungstenite::{Error as WsError, Message as WsMessage};
+
+/// Maximum size of a payload of a single frame.
+/// It matches the default maximum message size of a WebSocket connection.
+pub const MAX_FRAME_PAYLOAD_SIZE: usize = 64 << 20;
+
+// FRAME_KIND (1 byte) || PAYLOAD_LENGTH (4 bytes, big endian)
+const FRAME_HEADER_SIZE: usize = 5;
+
+// once this many bytes are buffered, they have to be written out before accepting any more messages
+const WRITE_BACKPRESSURE_BOUNDARY: usize = 128 * 1024;
+
+const READ_CHUNK_SIZE: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum FrameKind {
+    Text = 0,
+    Binary = 1,
+    Ping = 2,
+    Pong = 3,
+    Close = 4,
+}
+
+impl TryFrom<u8> for FrameKind {
+    type Error = WsError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FrameKind::Text),
+            1 => Ok(FrameKind::Binary),
+            2 => Ok(FrameKind::Ping),
+            3 => Ok(FrameKind::Pong),
+            4 => Ok(FrameKind::Close),
+            other => Err(malformed_frame(format!("unknown frame kind {other}"))),
+        }
+    }
+}
+
+fn malformed_frame<S: Into<String>>(reason: S) -> WsError {
+    WsError::Io(io::Error::new(io::ErrorKind::InvalidData, reason.into()))
+}
+
+fn encode_message(
+    message: WsMessage,
+    buf: &mut BytesMut,
+    buffer_pool: Option<&FrameBufferPool>,
+) -> Result<(), WsError> {
+    let (kind, payload) = match message {
+        WsMessage::Text(text) => (FrameKind::Text, text.into_bytes()),
+        WsMessage::Binary(data) => (FrameKind::Binary, data),
+        WsMessage::Ping(data) => (FrameKind::Ping, data),
+        WsMessage::Pong(data) => (FrameKind::Pong, data),
+        WsMessage::Close(frame) => {
+            let mut payload = Vec::new();
+            if let Some(frame) = frame {
+                payload.extend_from_slice(&u16::from(frame.code).to_be_bytes());
+                payload.extend_from_slice(frame.reason.as_bytes());
+            }
+            (FrameKind::Close, payload)
+        }
+        WsMessage::Frame(_) => {
+            return Err(malformed_frame(
+                "raw websocket frames can't be sent through a framed transport",
+            ))
+        }
+    };
+
+    if payload.len() > MAX_FRAME_PAYLOAD_SIZE {
+        return Err(WsError::Capacity(CapacityError::MessageTooLong {
+            size: payload.len(),
+            max_size: MAX_FRAME_PAYLOAD_SIZE,
+        }));
+    }
+
+    buf.reserve(FRAME_HEADER_SIZE + payload.len());
+    buf.put_u8(kind as u8);
+    buf.put_u32(payload.len() as u32);
+    buf.extend_from_slice(&payload);
+
+    // the payload has been copied into the write buffer, so its allocation could be reused
+    if let Some(pool) = buffer_pool {
+        pool.release(payload)
+    }
+    Ok(())
+}
+
+fn decode_message(
+    buf: &mut BytesMut,
+    buffer_pool: Option<&FrameBufferPool>,
+) -> Result<Option<WsMessage>, WsError> {
+    if buf.len() < FRAME_HEADER_SIZE {
+        return Ok(None);
+    }
+
+    let payload_len = u32::from_be_bytes([buf[1], buf[2], buf[3], buf[4]]) as usize;
+    if payload_len > MAX_FRAME_PAYLOAD_SIZE {
+        return Err(WsError::Capacity(CapacityError::MessageTooLong {
+            size: payload_len,
+            max_size: MAX_FRAME_PAYLOAD_SIZE,
+        }));
+    }
+
+    if buf.len() < FRAME_HEADER_SIZE + payload_len {
+        return Ok(None);
+    }
+
+    let kind = FrameKind::try_from(buf[0])?;
+    buf.advance(FRAME_HEADER_SIZE);
+    let frame = buf.split_to(payload_len);
+    let payload = match buffer_pool {
+        Some(pool) => {
+            let mut payload = pool.acquire(payload_len);
+            payload.extend_from_slice(&frame);
+            payload
+        }
+        None => frame.to_vec(),
+    };
+
+    let message = match kind {
+        FrameKind::Text => WsMessage::Text(
+            String::from_utf8(payload)
+                .map_err(|_| malformed_frame("received text frame with invalid utf8 payload"))?,
+        ),
+        FrameKind::Binary => WsMessage::Binary(payload),
+        FrameKind::Ping => WsMessage::Ping(payload),
+        FrameKind::Pong => WsMessage::Pong(payload),
+        FrameKind::Close => match payload.len() {
+            0 => WsMessage::Close(None),
+            1 => return Err(malformed_frame("received close frame with truncated code")),
+            _ => {
+                let code = u16::from_be_bytes([payload[0], payload[1]]);
+                let reason = String::from_utf8(payload[2..].to_vec()).map_err(|_| {
+                    malformed_frame("received close frame with invalid utf8 reason")
+                })?;
+                WsMessage::Close(Some(CloseFrame {
+                    code: CloseCode::from(code),
+                    reason: Cow::Owned(reason),
+                }))
+            }
+        },
+    };
+
+    Ok(Some(message))
+}
+
+/// Adapter turning any reliable, ordered byte stream (such as a TCP connection) into
+/// a [`GatewayTransport`](super::GatewayTransport), so that it could be used for the
+/// client-gateway communication in place of a WebSocket.
+///
+/// Each message is sent as a single frame consisting of its kind, the length of its payload
+/// and the payload itself.
+///
+/// Note that it operates on the `futures` IO traits, so tokio-based streams have to be adapted
+/// first, for example with `tokio_util::compat`.
+pub struct FramedTransport<T> {
+    inner: T,
+    read_buffer: BytesMut,
+    write_buffer: BytesMut,
+    buffer_pool: Option<FrameBufferPool>,
+
+    // indicates that either the underlying reader got exhausted or it has sent us malformed data,
+    // and thus no further messages are going to be produced
+    read_finished: bool,
+}
+
+impl<T> FramedTransport<T> {
+    pub fn new(inner: T) -> Self {
+        FramedTransport {
+            inner,
+            read_buffer: BytesMut::new(),
+            write_buffer: BytesMut::new(),
+            buffer_pool: None,
+            read_finished: false,
+        }
+    }
+
+    /// Makes the transport recycle the payloads of the sent messages and take the buffers
+    /// for the payloads of the received ones from the provided pool.
+    #[must_use]
+    pub fn with_buffer_pool(mut self, buffer_pool: FrameBufferPool) -> Self {
+        self.buffer_pool = Some(buffer_pool);
+        self
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Consumes the transport returning the underlying stream.
+    /// Note that any buffered, but not yet processed, data is going to be lost.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> Debug for FramedTransport<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FramedTransport")
+            .field("buffered_read", &self.read_buffer.len())
+            .field("buffered_write", &self.write_buffer.len())
+            .field("read_finished", &self.read_finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> FramedTransport<T>
+where
+    T: AsyncWrite + Unpin,
+{
+    fn poll_write_buffer(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), WsError>> {
+        while !self.write_buffer.is_empty() {
+            let written = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.write_buffer))?;
+            if written == 0 {
+                return Poll::Ready(Err(WsError::Io(io::ErrorKind::WriteZero.into())));
+            }
+            self.write_buffer.advance(written);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T> Stream for FramedTransport<T>
+where
+    T: AsyncRead + Unpin,
+{
+    type Item = Result<WsMessage, WsError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match decode_message(&mut this.read_buffer, this.buffer_pool.as_ref()) {
+                Ok(Some(message)) => return Poll::Ready(Some(Ok(message))),
+                Ok(None) => (),
+                Err(err) => {
+                    this.read_finished = true;
+                    this.read_buffer.clear();
+                    return Poll::Ready(Some(Err(err)));
+                }
+            }
+
+            if this.read_finished {
+                return Poll::Ready(None);
+            }
+
+            // read directly into the spare space at the end of the buffer
+            let buffered = this.read_buffer.len();
+            this.read_buffer.resize(buffered + READ_CHUNK_SIZE, 0);
+            let poll_result =
+                Pin::new(&mut this.inner).poll_read(cx, &mut this.read_buffer[buffered..]);
+            let read = match poll_result {
+                Poll::Ready(Ok(read)) => read,
+                Poll::Ready(Err(err)) => {
+                    this.read_buffer.truncate(buffered);
+                    return Poll::Ready(Some(Err(WsError::Io(err))));
+                }
+                Poll::Pending => {
+                    this.read_buffer.truncate(buffered);
+                    return Poll::Pending;
+                }
+            };
+            this.read_buffer.truncate(buffered + read);
+
+            if read == 0 {
+                this.read_finished = true;
+                if !this.read_buffer.is_empty() {
+                    // the stream got closed in the middle of a frame
+                    this.read_buffer.clear();
+                    return Poll::Ready(Some(Err(WsError::Io(
+                        io::ErrorKind::UnexpectedEof.into(),
+                    ))));
+                }
+                return Poll::Ready(None);
+            }
+        }
+    }
+}
+
+impl<T> Sink<WsMessage> for FramedTransport<T>
+where
+    T: AsyncWrite + Unpin,
+{
+    type Error = WsError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if this.write_buffer.len() >= WRITE_BACKPRESSURE_BOUNDARY {
+            ready!(this.poll_write_buffer(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: WsMessage) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        encode_message(item, &mut this.write_buffer, this.buffer_pool.as_ref())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_buffer(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx).map_err(Into::into)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_buffer(cx))?;
+        Pin::new(&mut this.inner).poll_close(cx).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::io::Cursor;
+    use futures::{SinkExt, StreamExt};
+
+    #[test]
+    fn messages_survive_framing_roundtrip() {
+        let messages = vec![
+            WsMessage::Text("hello gateway".to_string()),
+            WsMessage::Binary(vec![42; 3 * READ_CHUNK_SIZE]),
+            WsMessage::Ping(vec![1, 2, 3]),
+            WsMessage::Pong(Vec::new()),
+            WsMessage::Close(Some(CloseFrame {
+                code: CloseCode::Normal,
+                reason: Cow::Borrowed("bye"),
+            })),
+            WsMessage::Close(None),
+        ];
+
+        let mut writer = FramedTransport::new(Cursor::new(Vec::new()));
+        block_on(async {
+            for message in messages.clone() {
+                writer.feed(message).await.unwrap();
+            }
+            writer.flush().await.unwrap();
+        });
+
+        let written = writer.into_inner().into_inner();
+        let reader = FramedTransport::new(Cursor::new(written));
+        let received: Vec<_> = block_on(reader.map(Result::unwrap).collect());
+        assert_eq!(received, messages);
+    }
+
+    #[test]
+    fn sent_payloads_are_recycled_through_the_pool() {
+        let pool = FrameBufferPool::new(4);
+        let mut writer =
+            FramedTransport::new(Cursor::new(Vec::new())).with_buffer_pool(pool.clone());
+        block_on(async {
+            writer.feed(WsMessage::Binary(vec![1; 100])).await.unwrap();
+            writer
+                .feed(WsMessage::Text("hello gateway".to_string()))
+                .await
+                .unwrap();
+            writer.flush().await.unwrap();
+        });
+        assert_eq!(pool.pooled(), 2);
+
+        let written = writer.into_inner().into_inner();
+        let reader = FramedTransport::new(Cursor::new(written)).with_buffer_pool(pool.clone());
+        let received: Vec<_> = block_on(reader.map(Result::unwrap).collect());
+        assert_eq!(
+            received,
+            vec![
+                WsMessage::Binary(vec![1; 100]),
+                WsMessage::Text("hello gateway".to_string())
+            ]
+        );
+        assert_eq!(pool.pooled(), 0);
+    }
+
+    #[test]
+    fn truncated_frames_are_rejected() {
+        let mut buf = BytesMut::new();
+        encode_message(WsMessage::Text("hello".to_string()), &mut buf, None).unwrap();
+        buf.truncate(buf.len() - 1);
+
+        let mut reader = FramedTransport::new(Cursor::new(buf.to_vec()));
+        assert!(block_on(reader.next()).unwrap().is_err());
+        assert!(block_on(reader.next()).is_none());
+    }
+}
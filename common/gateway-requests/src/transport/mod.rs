@@ -0,0 +1,31 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Abstraction over the underlying channel used for the client-gateway communication.
+//!
+//! Both the registration handshake and all subsequent requests are exchanged as discrete
+//! [`WsMessage`]s. By default they are carried directly by a WebSocket, however, any other
+//! reliable and ordered byte stream could be used instead by wrapping it in a [`FramedTransport`]
+//! that takes care of the message framing. Gateways can optionally accept such framed
+//! connections over raw TCP on a dedicated port.
+
+use futures::{Sink, Stream};
+use tungstenite::{Error as WsError, Message as WsMessage};
+
+pub use framed::{FramedTransport, MAX_FRAME_PAYLOAD_SIZE};
+
+mod framed;
+
+/// A bidirectional channel capable of carrying the messages exchanged between clients and gateways.
+///
+/// It is automatically implemented for all types with the appropriate [`Stream`] and [`Sink`]
+/// implementations, such as WebSocket streams or the [`FramedTransport`].
+pub trait GatewayTransport:
+    Stream<Item = Result<WsMessage, WsError>> + Sink<WsMessage, Error = WsError>
+{
+}
+
+impl<T> GatewayTransport for T where
+    T: Stream<Item = Result<WsMessage, WsError>> + Sink<WsMessage, Error = WsError>
+{
+}
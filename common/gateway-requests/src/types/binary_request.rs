@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::types::helpers::BinaryData;
-use crate::{GatewayRequestsError, SharedGatewayKey};
+use crate::{FrameBufferPool, GatewayRequestsError, SharedGatewayKey};
 use nym_sphinx::forwarding::packet::MixPacket;
 use strum::FromRepr;
 use tungstenite::Message;
@@ -64,14 +64,54 @@ impl BinaryRequest {
         BinaryData::make_encrypted_blob(kind as u8, &plaintext, shared_key)
     }
 
+    /// Size of the wire representation of this request once encrypted with the provided key.
+    pub fn encrypted_tagged_size(&self, shared_key: &SharedGatewayKey) -> usize {
+        let plaintext_len = match self {
+            BinaryRequest::ForwardSphinx { packet } => packet.serialised_len(),
+        };
+
+        BinaryData::encrypted_blob_size(plaintext_len, shared_key)
+    }
+
+    /// Serialises and encrypts this request, appending the result to the provided buffer.
+    /// Unlike [`Self::into_encrypted_tagged_bytes`], it does not allocate any intermediate buffers
+    /// (unless legacy keys are used).
+    pub fn write_encrypted_tagged_bytes(
+        &self,
+        shared_key: &SharedGatewayKey,
+        out: &mut Vec<u8>,
+    ) -> Result<(), GatewayRequestsError> {
+        let kind = self.kind();
+
+        BinaryData::write_encrypted_blob(kind as u8, shared_key, out, |buf| match self {
+            BinaryRequest::ForwardSphinx { packet } => Ok(packet.write_bytes(buf)?),
+        })
+    }
+
     pub fn into_ws_message(
         self,
         shared_key: &SharedGatewayKey,
     ) -> Result<Message, GatewayRequestsError> {
-        // all variants are currently encrypted
-        let blob = match self {
-            BinaryRequest::ForwardSphinx { .. } => self.into_encrypted_tagged_bytes(shared_key)?,
-        };
+        // all variants are currently encrypted.
+        // allocate the exact amount of space for the frame and write everything directly into it
+        let mut blob = Vec::with_capacity(self.encrypted_tagged_size(shared_key));
+        self.write_encrypted_tagged_bytes(shared_key, &mut blob)?;
+
+        Ok(Message::Binary(blob))
+    }
+
+    /// Equivalent of [`Self::into_ws_message`] that writes the frame into a buffer taken from the pool
+    /// rather than allocating a fresh one.
+    pub fn into_pooled_ws_message(
+        self,
+        shared_key: &SharedGatewayKey,
+        pool: &FrameBufferPool,
+    ) -> Result<Message, GatewayRequestsError> {
+        let mut blob = pool.acquire(self.encrypted_tagged_size(shared_key));
+        if let Err(err) = self.write_encrypted_tagged_bytes(shared_key, &mut blob) {
+            pool.release(blob);
+            return Err(err);
+        }
 
         Ok(Message::Binary(blob))
     }
@@ -36,11 +36,11 @@ impl BinaryResponse {
         }
     }
 
-    pub fn try_from_encrypted_tagged_bytes(
-        bytes: Vec<u8>,
+    pub fn try_from_encrypted_tagged_bytes<B: AsRef<[u8]>>(
+        bytes: B,
         shared_key: &SharedGatewayKey,
     ) -> Result<Self, GatewayRequestsError> {
-        BinaryData::from_raw(&bytes, shared_key)?.into_response(shared_key)
+        BinaryData::from_raw(bytes.as_ref(), shared_key)?.into_response(shared_key)
     }
 
     pub fn into_encrypted_tagged_bytes(
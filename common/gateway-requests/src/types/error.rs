@@ -7,9 +7,110 @@ use nym_sphinx::addressing::nodes::NymNodeRoutingAddressError;
 use nym_sphinx::forwarding::packet::MixPacketFormattingError;
 use nym_sphinx::params::packet_sizes::PacketSize;
 use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
 use std::string::FromUtf8Error;
 use thiserror::Error;
 
+/// Stable, machine-readable identifier of an error returned by the gateway.
+/// The numeric values are part of the wire protocol and thus must never be changed or reused.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "u16", into = "u16")]
+pub enum GatewayErrorCode {
+    /// The gateway did not specify any particular reason for the failure.
+    Unspecified,
+
+    /// The gateway has experienced an internal failure unrelated to the client's request.
+    Internal,
+
+    /// The request could not be parsed or decrypted.
+    MalformedRequest,
+
+    /// The forwarded sphinx packet was malformed or had an invalid size.
+    MalformedPacket,
+
+    /// The request is not valid in the current context, e.g. sending packets before authenticating.
+    UnexpectedRequest,
+
+    /// The client is using a protocol version that is not supported by the gateway.
+    IncompatibleProtocol,
+
+    /// The registration handshake or authentication has failed.
+    AuthenticationFailure,
+
+    /// The client already has an active session that could not be replaced.
+    DuplicateSession,
+
+    /// There was not enough bandwidth available to handle the request.
+    OutOfBandwidth,
+
+    /// The provided bandwidth credential was invalid or got rejected.
+    InvalidCredential,
+
+    /// The provided ticket has already been spent at this gateway.
+    TicketReplay,
+
+    /// The shared key upgrade could not be completed.
+    KeyUpgradeFailure,
+
+    /// Code that is not known to this client, most likely introduced by a newer gateway.
+    Unknown(u16),
+}
+
+impl GatewayErrorCode {
+    pub const fn code(&self) -> u16 {
+        match self {
+            GatewayErrorCode::Unspecified => 0,
+            GatewayErrorCode::Internal => 1,
+            GatewayErrorCode::MalformedRequest => 2,
+            GatewayErrorCode::MalformedPacket => 3,
+            GatewayErrorCode::UnexpectedRequest => 4,
+            GatewayErrorCode::IncompatibleProtocol => 5,
+            GatewayErrorCode::AuthenticationFailure => 6,
+            GatewayErrorCode::DuplicateSession => 7,
+            GatewayErrorCode::OutOfBandwidth => 8,
+            GatewayErrorCode::InvalidCredential => 9,
+            GatewayErrorCode::TicketReplay => 10,
+            GatewayErrorCode::KeyUpgradeFailure => 11,
+            GatewayErrorCode::Unknown(code) => *code,
+        }
+    }
+}
+
+impl From<u16> for GatewayErrorCode {
+    fn from(code: u16) -> Self {
+        match code {
+            0 => GatewayErrorCode::Unspecified,
+            1 => GatewayErrorCode::Internal,
+            2 => GatewayErrorCode::MalformedRequest,
+            3 => GatewayErrorCode::MalformedPacket,
+            4 => GatewayErrorCode::UnexpectedRequest,
+            5 => GatewayErrorCode::IncompatibleProtocol,
+            6 => GatewayErrorCode::AuthenticationFailure,
+            7 => GatewayErrorCode::DuplicateSession,
+            8 => GatewayErrorCode::OutOfBandwidth,
+            9 => GatewayErrorCode::InvalidCredential,
+            10 => GatewayErrorCode::TicketReplay,
+            11 => GatewayErrorCode::KeyUpgradeFailure,
+            other => GatewayErrorCode::Unknown(other),
+        }
+    }
+}
+
+impl From<GatewayErrorCode> for u16 {
+    fn from(code: GatewayErrorCode) -> Self {
+        code.code()
+    }
+}
+
+impl Display for GatewayErrorCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GatewayErrorCode::Unknown(code) => write!(f, "unknown error code {code}"),
+            known => write!(f, "{known:?} ({})", known.code()),
+        }
+    }
+}
+
 // specific errors (that should not be nested!!) for clients to match on
 #[derive(Debug, Copy, Clone, Error, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -25,6 +126,13 @@ impl SimpleGatewayRequestsError {
     pub fn is_ticket_replay(&self) -> bool {
         matches!(self, SimpleGatewayRequestsError::TicketReplay)
     }
+
+    pub fn error_code(&self) -> GatewayErrorCode {
+        match self {
+            SimpleGatewayRequestsError::OutOfBandwidth { .. } => GatewayErrorCode::OutOfBandwidth,
+            SimpleGatewayRequestsError::TicketReplay => GatewayErrorCode::TicketReplay,
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -96,3 +204,58 @@ pub enum GatewayRequestsError {
     #[error("{0}")]
     Other(String),
 }
+
+impl GatewayRequestsError {
+    pub fn error_code(&self) -> GatewayErrorCode {
+        match self {
+            GatewayRequestsError::KeyUsageFailure(_)
+            | GatewayRequestsError::MalformedRequest { .. }
+            | GatewayRequestsError::UnknownRequestKind { .. }
+            | GatewayRequestsError::InvalidEncryptionFlag
+            | GatewayRequestsError::TooShortRequest
+            | GatewayRequestsError::InvalidMac
+            | GatewayRequestsError::MalformedEncryption
+            | GatewayRequestsError::InvalidPacketMode => GatewayErrorCode::MalformedRequest,
+            GatewayRequestsError::IncorrectlyEncodedAddress { .. }
+            | GatewayRequestsError::RequestOfInvalidSize(_)
+            | GatewayRequestsError::MalformedSphinxPacket
+            | GatewayRequestsError::SphinxSerialisationFailure(_) => {
+                GatewayErrorCode::MalformedPacket
+            }
+            GatewayRequestsError::EcashCredentialDeserializationFailure(_)
+            | GatewayRequestsError::CredentialDeserializationFailureEOF
+            | GatewayRequestsError::CredentialDeserializationFailureMalformedString(_)
+            | GatewayRequestsError::InvalidNumberOfEmbededParameters(_) => {
+                GatewayErrorCode::InvalidCredential
+            }
+            GatewayRequestsError::MalformedResponse { .. }
+            | GatewayRequestsError::UnknownResponseKind { .. } => GatewayErrorCode::Internal,
+            GatewayRequestsError::Other(_) => GatewayErrorCode::Unspecified,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_codes_roundtrip() {
+        for code in 0..=u16::from(GatewayErrorCode::KeyUpgradeFailure) + 1 {
+            assert_eq!(GatewayErrorCode::from(code).code(), code);
+        }
+        assert_eq!(
+            GatewayErrorCode::from(1234),
+            GatewayErrorCode::Unknown(1234)
+        );
+    }
+
+    #[test]
+    fn error_codes_are_serialised_as_numbers() {
+        let serialised = serde_json::to_string(&GatewayErrorCode::OutOfBandwidth).unwrap();
+        assert_eq!(serialised, "8");
+
+        let deserialised: GatewayErrorCode = serde_json::from_str("9999").unwrap();
+        assert_eq!(deserialised, GatewayErrorCode::Unknown(9999));
+    }
+}
@@ -5,6 +5,9 @@ use crate::{
     BinaryRequest, BinaryRequestKind, BinaryResponse, BinaryResponseKind, GatewayRequestsError,
     SharedGatewayKey,
 };
+use nym_crypto::symmetric::aead::random_nonce;
+use nym_sphinx::params::GatewayEncryptionAlgorithm;
+use rand::thread_rng;
 use std::iter::once;
 
 // each binary message consists of the following structure (for non-legacy messages)
@@ -97,6 +100,55 @@ impl<'a> BinaryData<'a> {
         .into_raw(key.is_legacy()))
     }
 
+    // size of the wire representation of encrypted plaintext of the provided length
+    pub fn encrypted_blob_size(plaintext_len: usize, key: &SharedGatewayKey) -> usize {
+        if key.is_legacy() {
+            key.tag_size() + plaintext_len
+        } else {
+            2 + key.nonce_size() + plaintext_len + key.tag_size()
+        }
+    }
+
+    // equivalent of `make_encrypted_blob` that writes the plaintext directly into the output buffer
+    // and encrypts it in place, rather than allocating intermediate buffers for the plaintext,
+    // ciphertext and the final blob.
+    // on failure, the buffer is restored to its original length
+    pub fn write_encrypted_blob<F>(
+        kind: u8,
+        key: &SharedGatewayKey,
+        out: &mut Vec<u8>,
+        write_plaintext: F,
+    ) -> Result<(), GatewayRequestsError>
+    where
+        F: FnOnce(&mut Vec<u8>) -> Result<(), GatewayRequestsError>,
+    {
+        let start = out.len();
+        let res = match key {
+            SharedGatewayKey::Current(key) => {
+                let nonce = random_nonce::<GatewayEncryptionAlgorithm, _>(&mut thread_rng());
+                out.push(kind);
+                out.push(1);
+                out.extend_from_slice(&nonce);
+
+                let offset = out.len();
+                write_plaintext(out).and_then(|_| Ok(key.encrypt_in_place(out, offset, &nonce)?))
+            }
+            SharedGatewayKey::Legacy(_) => {
+                // legacy keys are only around for backwards compatibility,
+                // so there's no point in optimising them
+                let mut plaintext = Vec::new();
+                write_plaintext(&mut plaintext)
+                    .and_then(|_| Self::make_encrypted_blob(kind, &plaintext, key))
+                    .map(|blob| out.extend_from_slice(&blob))
+            }
+        };
+
+        if res.is_err() {
+            out.truncate(start);
+        }
+        res
+    }
+
     // attempts to parse previously recovered bytes into a [`BinaryRequest`]
     pub fn into_request(
         self,
@@ -131,3 +183,63 @@ impl<'a> BinaryData<'a> {
         BinaryResponse::from_plaintext(kind, plaintext)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LegacySharedKeys, SharedSymmetricKey};
+
+    fn test_keys() -> Vec<SharedGatewayKey> {
+        vec![
+            SharedSymmetricKey::try_from_bytes(&[42u8; 32])
+                .unwrap()
+                .into(),
+            LegacySharedKeys::try_from_bytes(&[42u8; 32])
+                .unwrap()
+                .into(),
+        ]
+    }
+
+    #[test]
+    fn written_encrypted_blob_can_be_recovered() {
+        let plaintext = b"some plaintext that is going to get encrypted".to_vec();
+        for key in test_keys() {
+            let mut out = Vec::new();
+            BinaryData::write_encrypted_blob(1, &key, &mut out, |buf| {
+                buf.extend_from_slice(&plaintext);
+                Ok(())
+            })
+            .unwrap();
+
+            assert_eq!(
+                out.len(),
+                BinaryData::encrypted_blob_size(plaintext.len(), &key)
+            );
+            assert_eq!(
+                out.len(),
+                BinaryData::make_encrypted_blob(1, &plaintext, &key)
+                    .unwrap()
+                    .len()
+            );
+
+            let data = BinaryData::from_raw(&out, &key).unwrap();
+            assert_eq!(data.kind, 1);
+            assert!(data.encrypted);
+            let recovered = key.decrypt(data.data, data.maybe_nonce).unwrap();
+            assert_eq!(recovered, plaintext);
+        }
+    }
+
+    #[test]
+    fn failed_blob_write_leaves_buffer_intact() {
+        for key in test_keys() {
+            let mut out = vec![1, 2, 3];
+            let res = BinaryData::write_encrypted_blob(1, &key, &mut out, |buf| {
+                buf.extend_from_slice(b"partial");
+                Err(GatewayRequestsError::TooShortRequest)
+            });
+            assert!(res.is_err());
+            assert_eq!(out, vec![1, 2, 3]);
+        }
+    }
+}
@@ -5,6 +5,8 @@ pub mod binary_request;
 pub mod binary_response;
 pub mod error;
 mod helpers;
+pub mod notice;
+pub mod protocol_stats;
 pub mod registration_handshake_wrapper;
 pub mod text_request;
 pub mod text_response;
@@ -13,6 +15,8 @@ pub mod text_response;
 pub use binary_request::*;
 pub use binary_response::*;
 pub use error::*;
+pub use notice::*;
+pub use protocol_stats::*;
 pub use registration_handshake_wrapper::*;
 pub use text_request::*;
 pub use text_response::*;
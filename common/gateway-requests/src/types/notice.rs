@@ -0,0 +1,114 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// Notice pushed by the gateway to its subscribed clients, without being prompted by any request,
+/// so that they could inform their users about upcoming changes to the service ahead of time.
+/// All the timestamps are expressed as unix timestamps in seconds.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum GatewayNotice {
+    /// The gateway is going to become unavailable for the specified period.
+    MaintenanceScheduled {
+        starts_at: i64,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expected_duration_secs: Option<u64>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    },
+
+    /// The protocol version currently used by the client is going to stop being supported.
+    ProtocolDeprecation {
+        deprecated_version: u8,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        unsupported_after: Option<i64>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    },
+
+    /// The way the bandwidth of the clients is accounted for is going to change.
+    BandwidthPolicyChange {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        effective_from: Option<i64>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    },
+}
+
+impl GatewayNotice {
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            GatewayNotice::MaintenanceScheduled { message, .. }
+            | GatewayNotice::ProtocolDeprecation { message, .. }
+            | GatewayNotice::BandwidthPolicyChange { message, .. } => message.as_deref(),
+        }
+    }
+}
+
+impl Display for GatewayNotice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GatewayNotice::MaintenanceScheduled {
+                starts_at,
+                expected_duration_secs,
+                ..
+            } => {
+                write!(f, "maintenance scheduled to start at {starts_at}")?;
+                if let Some(duration) = expected_duration_secs {
+                    write!(f, " for {duration}s")?;
+                }
+            }
+            GatewayNotice::ProtocolDeprecation {
+                deprecated_version,
+                unsupported_after,
+                ..
+            } => {
+                write!(f, "protocol version {deprecated_version} is deprecated")?;
+                if let Some(unsupported_after) = unsupported_after {
+                    write!(f, " and won't be supported after {unsupported_after}")?;
+                }
+            }
+            GatewayNotice::BandwidthPolicyChange { effective_from, .. } => {
+                write!(f, "bandwidth policy is changing")?;
+                if let Some(effective_from) = effective_from {
+                    write!(f, " from {effective_from}")?;
+                }
+            }
+        }
+        if let Some(message) = self.message() {
+            write!(f, ": {message}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notices_are_tagged_by_kind() {
+        let notice = GatewayNotice::MaintenanceScheduled {
+            starts_at: 1730000000,
+            expected_duration_secs: Some(3600),
+            message: None,
+        };
+        let serialised = serde_json::to_string(&notice).unwrap();
+        assert_eq!(
+            serialised,
+            r#"{"kind":"maintenanceScheduled","starts_at":1730000000,"expected_duration_secs":3600}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<GatewayNotice>(&serialised).unwrap(),
+            notice
+        );
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+/// Protocol statistics periodically exchanged between the client and its gateway
+/// so that either side could adapt its behaviour, such as the sending rate.
+/// Every field is optional as each party decides on its own what (if anything) it's willing to share.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct ProtocolStats {
+    /// Fraction of packets, in the range of [0, 1], that the reporting party considers to have been lost
+    /// since the previous exchange.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub observed_loss: Option<f32>,
+
+    /// Number of packets currently waiting to be sent by the reporting party.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_depth: Option<u32>,
+}
+
+impl ProtocolStats {
+    pub fn is_empty(&self) -> bool {
+        self.observed_loss.is_none() && self.queue_depth.is_none()
+    }
+
+    /// Makes sure the received values are within their expected ranges.
+    #[must_use]
+    pub fn sanitized(mut self) -> Self {
+        self.observed_loss = self
+            .observed_loss
+            .filter(|loss| loss.is_finite())
+            .map(|loss| loss.clamp(0., 1.));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unshared_fields_are_omitted() {
+        let empty = ProtocolStats::default();
+        assert_eq!(serde_json::to_string(&empty).unwrap(), "{}");
+        assert_eq!(serde_json::from_str::<ProtocolStats>("{}").unwrap(), empty);
+
+        let partial = ProtocolStats {
+            observed_loss: None,
+            queue_depth: Some(42),
+        };
+        assert_eq!(
+            serde_json::to_string(&partial).unwrap(),
+            r#"{"queue_depth":42}"#
+        );
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::registration::handshake::error::HandshakeFailureReason;
+use crate::CipherSuite;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
@@ -10,24 +12,32 @@ pub enum RegistrationHandshake {
     HandshakePayload {
         #[serde(default)]
         protocol_version: Option<u8>,
+        #[serde(default, skip_serializing_if = "CipherSuite::is_standard")]
+        cipher_suite: CipherSuite,
         data: Vec<u8>,
     },
     HandshakeError {
         message: String,
+
+        // numeric `HandshakeFailureReason`, it's optional for compatibility with remotes that only sent the message
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        code: Option<u8>,
     },
 }
 
 impl RegistrationHandshake {
-    pub fn new_payload(data: Vec<u8>, protocol_version: u8) -> Self {
+    pub fn new_payload(data: Vec<u8>, protocol_version: u8, cipher_suite: CipherSuite) -> Self {
         RegistrationHandshake::HandshakePayload {
             protocol_version: Some(protocol_version),
+            cipher_suite,
             data,
         }
     }
 
-    pub fn new_error<S: Into<String>>(message: S) -> Self {
+    pub fn new_error<S: Into<String>>(message: S, reason: HandshakeFailureReason) -> Self {
         RegistrationHandshake::HandshakeError {
             message: message.into(),
+            code: Some(reason.code()),
         }
     }
 }
@@ -66,6 +76,7 @@ mod tests {
         let handshake_data = vec![1, 2, 3, 4, 5, 6];
         let handshake_payload_with_protocol = RegistrationHandshake::HandshakePayload {
             protocol_version: Some(42),
+            cipher_suite: CipherSuite::Fips,
             data: handshake_data.clone(),
         };
         let serialized = serde_json::to_string(&handshake_payload_with_protocol).unwrap();
@@ -74,9 +85,11 @@ mod tests {
         match deserialized {
             ClientControlRequest::RegisterHandshakeInitRequest {
                 protocol_version,
+                cipher_suite,
                 data,
             } => {
                 assert_eq!(protocol_version, Some(42));
+                assert_eq!(cipher_suite, CipherSuite::Fips);
                 assert_eq!(data, handshake_data)
             }
             _ => unreachable!("this branch shouldn't have been reached!"),
@@ -84,6 +97,7 @@ mod tests {
 
         let handshake_payload_without_protocol = RegistrationHandshake::HandshakePayload {
             protocol_version: None,
+            cipher_suite: CipherSuite::Standard,
             data: handshake_data.clone(),
         };
         let serialized = serde_json::to_string(&handshake_payload_without_protocol).unwrap();
@@ -92,9 +106,11 @@ mod tests {
         match deserialized {
             ClientControlRequest::RegisterHandshakeInitRequest {
                 protocol_version,
+                cipher_suite,
                 data,
             } => {
                 assert!(protocol_version.is_none());
+                assert_eq!(cipher_suite, CipherSuite::Standard);
                 assert_eq!(data, handshake_data)
             }
             _ => unreachable!("this branch shouldn't have been reached!"),
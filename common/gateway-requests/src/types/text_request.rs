@@ -2,9 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::models::CredentialSpendingRequest;
+use crate::types::ProtocolStats;
 use crate::{
-    GatewayRequestsError, SharedGatewayKey, SymmetricKey, AES_GCM_SIV_PROTOCOL_VERSION,
-    CREDENTIAL_UPDATE_V2_PROTOCOL_VERSION, INITIAL_PROTOCOL_VERSION,
+    CipherSuite, GatewayRequestsError, SharedGatewayKey, SymmetricKey,
+    AES_GCM_SIV_PROTOCOL_VERSION, CREDENTIAL_UPDATE_V2_PROTOCOL_VERSION, INITIAL_PROTOCOL_VERSION,
 };
 use nym_credentials_interface::CredentialSpendingData;
 use nym_sphinx::DestinationAddressBytes;
@@ -20,6 +21,16 @@ pub enum ClientRequest {
         hkdf_salt: Vec<u8>,
         derived_key_digest: Vec<u8>,
     },
+    /// Request removal of all messages stored for the client whilst it was offline
+    /// and, if `deregister` is set, of all of its registration data, such as the shared keys.
+    PurgeStoredData { deregister: bool },
+    /// Share the client's protocol statistics and request the gateway's in return.
+    ExchangeProtocolStats { stats: ProtocolStats },
+    /// Request the gateway to push its notices, such as the scheduled maintenance,
+    /// for the remainder of this connection.
+    SubscribeNotices {},
+    /// Request the number of messages the gateway still holds for the client.
+    GetStoredMessagesCount {},
 }
 
 impl ClientRequest {
@@ -60,6 +71,8 @@ pub enum ClientControlRequest {
     Authenticate {
         #[serde(default)]
         protocol_version: Option<u8>,
+        #[serde(default, skip_serializing_if = "CipherSuite::is_standard")]
+        cipher_suite: CipherSuite,
         address: String,
         enc_address: String,
         iv: String,
@@ -68,6 +81,8 @@ pub enum ClientControlRequest {
     RegisterHandshakeInitRequest {
         #[serde(default)]
         protocol_version: Option<u8>,
+        #[serde(default, skip_serializing_if = "CipherSuite::is_standard")]
+        cipher_suite: CipherSuite,
         data: Vec<u8>,
     },
     BandwidthCredential {
@@ -113,6 +128,8 @@ impl ClientControlRequest {
 
         Ok(ClientControlRequest::Authenticate {
             protocol_version,
+            // legacy keys are only ever used with the standard suite
+            cipher_suite: shared_key.cipher_suite().unwrap_or_default(),
             address: address.as_base58_string(),
             enc_address: bs58::encode(&ciphertext).into_string(),
             iv: bs58::encode(&nonce).into_string(),
@@ -1,7 +1,10 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{GatewayRequestsError, SimpleGatewayRequestsError, SymmetricKey};
+use crate::types::{GatewayNotice, ProtocolStats};
+use crate::{
+    CipherSuite, GatewayErrorCode, GatewayRequestsError, SimpleGatewayRequestsError, SymmetricKey,
+};
 use serde::{Deserialize, Serialize};
 use tungstenite::Message;
 
@@ -11,6 +14,25 @@ use tungstenite::Message;
 #[non_exhaustive]
 pub enum SensitiveServerResponse {
     KeyUpgradeAck {},
+    StoredDataPurged {
+        removed_messages: u64,
+        deregistered: bool,
+    },
+    ProtocolStats {
+        stats: ProtocolStats,
+    },
+    /// Acknowledgement of the subscription alongside the notices that are still in effect.
+    NoticesSubscribed {
+        active: Vec<GatewayNotice>,
+    },
+    /// Notice pushed to a subscribed client whenever it is published by the gateway.
+    Notice {
+        notice: GatewayNotice,
+    },
+    /// Number of messages the gateway still holds for the client.
+    StoredMessagesCount {
+        count: u64,
+    },
 }
 
 impl SensitiveServerResponse {
@@ -48,12 +70,16 @@ pub enum ServerResponse {
     Authenticate {
         #[serde(default)]
         protocol_version: Option<u8>,
+        #[serde(default, skip_serializing_if = "CipherSuite::is_standard")]
+        cipher_suite: CipherSuite,
         status: bool,
         bandwidth_remaining: i64,
     },
     Register {
         #[serde(default)]
         protocol_version: Option<u8>,
+        #[serde(default, skip_serializing_if = "CipherSuite::is_standard")]
+        cipher_suite: CipherSuite,
         status: bool,
     },
     EncryptedResponse {
@@ -68,9 +94,16 @@ pub enum ServerResponse {
     },
     SupportedProtocol {
         version: u8,
+        #[serde(default, skip_serializing_if = "CipherSuite::is_standard")]
+        cipher_suite: CipherSuite,
     },
     // Generic error
     Error {
+        // the code is optional for compatibility with older gateways that only sent the message
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        code: Option<GatewayErrorCode>,
+
+        // human-readable description of the failure. clients should not attempt to parse it
         message: String,
     },
     // Specific typed errors
@@ -93,14 +126,30 @@ impl ServerResponse {
             ServerResponse::EncryptedResponse { .. } => "EncryptedResponse".to_string(),
         }
     }
-    pub fn new_error<S: Into<String>>(msg: S) -> Self {
+    pub fn new_error<S: Into<String>>(code: GatewayErrorCode, msg: S) -> Self {
         ServerResponse::Error {
+            code: Some(code),
             message: msg.into(),
         }
     }
 
     pub fn is_error(&self) -> bool {
-        matches!(self, ServerResponse::Error { .. })
+        matches!(
+            self,
+            ServerResponse::Error { .. } | ServerResponse::TypedError { .. }
+        )
+    }
+
+    /// Returns the machine-readable code of the error response (if applicable).
+    /// Errors sent by legacy gateways without an explicit code are reported as `Unspecified`.
+    pub fn error_code(&self) -> Option<GatewayErrorCode> {
+        match self {
+            ServerResponse::Error { code, .. } => {
+                Some(code.unwrap_or(GatewayErrorCode::Unspecified))
+            }
+            ServerResponse::TypedError { error } => Some(error.error_code()),
+            _ => None,
+        }
     }
 
     pub fn implies_successful_authentication(&self) -> bool {
@@ -128,3 +177,93 @@ impl TryFrom<String> for ServerResponse {
         serde_json::from_str(&msg)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ClientControlRequest, ClientRequest};
+    use crate::{SharedGatewayKey, SharedSymmetricKey};
+
+    #[test]
+    fn legacy_error_responses_are_still_understood() {
+        let legacy = r#"{"type":"error","message":"something went wrong"}"#.to_string();
+        let response = ServerResponse::try_from(legacy).unwrap();
+        assert_eq!(response.error_code(), Some(GatewayErrorCode::Unspecified));
+    }
+
+    #[test]
+    fn error_code_is_sent_alongside_the_message() {
+        let response = ServerResponse::new_error(GatewayErrorCode::MalformedPacket, "bad packet");
+        let serialised = serde_json::to_string(&response).unwrap();
+        assert_eq!(
+            serialised,
+            r#"{"type":"error","code":3,"message":"bad packet"}"#
+        );
+
+        let deserialised = ServerResponse::try_from(serialised).unwrap();
+        assert_eq!(
+            deserialised.error_code(),
+            Some(GatewayErrorCode::MalformedPacket)
+        );
+    }
+
+    #[test]
+    fn gateways_without_cipher_suite_are_assumed_to_use_the_standard_one() {
+        let legacy = r#"{"type":"supportedProtocol","version":3}"#.to_string();
+        let ServerResponse::SupportedProtocol {
+            version,
+            cipher_suite,
+        } = ServerResponse::try_from(legacy).unwrap()
+        else {
+            panic!("unexpected response")
+        };
+        assert_eq!(version, 3);
+        assert_eq!(cipher_suite, CipherSuite::Standard);
+
+        let fips = ServerResponse::Register {
+            protocol_version: Some(3),
+            cipher_suite: CipherSuite::Fips,
+            status: true,
+        };
+        let serialised = serde_json::to_string(&fips).unwrap();
+        let ServerResponse::Register { cipher_suite, .. } =
+            ServerResponse::try_from(serialised).unwrap()
+        else {
+            panic!("unexpected response")
+        };
+        assert_eq!(cipher_suite, CipherSuite::Fips);
+    }
+
+    #[test]
+    fn stored_messages_count_can_be_exchanged() {
+        let key: SharedGatewayKey = SharedSymmetricKey::try_from_bytes(&[42u8; 32])
+            .unwrap()
+            .into();
+
+        let ClientControlRequest::EncryptedRequest { ciphertext, nonce } =
+            ClientRequest::GetStoredMessagesCount {}
+                .encrypt(&key)
+                .unwrap()
+        else {
+            panic!("unexpected request")
+        };
+        assert!(matches!(
+            ClientRequest::decrypt(&ciphertext, &nonce, &key).unwrap(),
+            ClientRequest::GetStoredMessagesCount {}
+        ));
+
+        let response = SensitiveServerResponse::StoredMessagesCount { count: 42 }
+            .encrypt(&key)
+            .unwrap();
+        let serialised = serde_json::to_string(&response).unwrap();
+        let ServerResponse::EncryptedResponse { ciphertext, nonce } =
+            ServerResponse::try_from(serialised).unwrap()
+        else {
+            panic!("unexpected response")
+        };
+        assert!(matches!(
+            SensitiveServerResponse::decrypt(&ciphertext, &nonce, &key).unwrap(),
+            SensitiveServerResponse::StoredMessagesCount { count: 42 }
+        ));
+    }
+}
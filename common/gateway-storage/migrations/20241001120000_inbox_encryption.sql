@@ -0,0 +1,8 @@
+/*
+ * Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: GPL-3.0-only
+ */
+
+-- messages stored before the introduction of at-rest encryption remain in plaintext
+ALTER TABLE message_store
+ADD COLUMN encrypted BOOLEAN NOT NULL DEFAULT FALSE;
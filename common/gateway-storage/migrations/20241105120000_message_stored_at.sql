@@ -0,0 +1,11 @@
+/*
+ * Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: GPL-3.0-only
+ */
+
+-- unix timestamp of the moment the message got stored, used for offloading cold messages.
+-- messages stored before its introduction are treated as the oldest ones
+ALTER TABLE message_store
+ADD COLUMN stored_at INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX message_store_stored_at ON message_store(stored_at);
@@ -0,0 +1,8 @@
+/*
+ * Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: GPL-3.0-only
+ */
+
+-- messages stored before the introduction of padding are kept at their original length
+ALTER TABLE message_store
+ADD COLUMN padded BOOLEAN NOT NULL DEFAULT FALSE;
@@ -22,4 +22,10 @@ pub enum StorageError {
 
     #[error("Failed to convert from type of database: {0}")]
     TypeConversion(String),
+
+    #[error("failed to encrypt message for storage: {0}")]
+    InboxEncryptionFailure(String),
+
+    #[error("the message store backend has failed: {0}")]
+    MessageStoreBackendFailure(String),
 }
@@ -0,0 +1,128 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! At-rest encryption of messages stored for offline clients.
+//!
+//! Every message is encrypted with a key derived from the shared key the gateway has established
+//! with its recipient, so that the message store alone (which might live in a separate RocksDB
+//! instance or an object store bucket) is not enough to recover any of the queued messages.
+//! Messages stored before the client re-registered with a fresh shared key can no longer be
+//! decrypted.
+
+use crate::error::StorageError;
+use crate::models::PersistedSharedKeys;
+use nym_crypto::symmetric::at_rest::AtRestKey;
+
+const INBOX_KEY_DERIVATION_SALT: &[u8] = b"NYM_GATEWAY_INBOX_ENCRYPTION_V2";
+
+/// Key used for encrypting the messages stored for a particular client.
+pub(crate) struct ClientInboxKey {
+    key: AtRestKey,
+}
+
+impl ClientInboxKey {
+    /// Derives the inbox key from the shared key of the client.
+    pub(crate) fn derive(shared_keys: &PersistedSharedKeys) -> Result<Self, StorageError> {
+        let ikm = match (
+            &shared_keys.derived_aes256_gcm_siv_key,
+            &shared_keys.derived_aes128_ctr_blake3_hmac_keys_bs58,
+        ) {
+            (Some(current), _) => current.as_slice(),
+            (None, Some(legacy)) => legacy.as_bytes(),
+            (None, None) => {
+                return Err(StorageError::DataCorruption(format!(
+                    "there are no shared keys stored for client {}",
+                    shared_keys.client_address_bs58
+                )))
+            }
+        };
+
+        Ok(ClientInboxKey {
+            key: AtRestKey::derive(
+                INBOX_KEY_DERIVATION_SALT,
+                ikm,
+                Some(shared_keys.client_address_bs58.as_bytes()),
+            ),
+        })
+    }
+
+    pub(crate) fn encrypt(&self, content: &[u8]) -> Result<Vec<u8>, StorageError> {
+        self.key
+            .encrypt(content)
+            .map_err(|err| StorageError::InboxEncryptionFailure(err.to_string()))
+    }
+
+    pub(crate) fn decrypt(&self, stored: &[u8]) -> Result<Vec<u8>, StorageError> {
+        self.key.decrypt(stored).map_err(|err| {
+            StorageError::DataCorruption(format!("failed to decrypt stored message: {err}"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shared_keys(address: &str, key: [u8; 32]) -> PersistedSharedKeys {
+        PersistedSharedKeys {
+            client_id: 1,
+            client_address_bs58: address.to_string(),
+            derived_aes128_ctr_blake3_hmac_keys_bs58: None,
+            derived_aes256_gcm_siv_key: Some(key.to_vec()),
+        }
+    }
+
+    #[test]
+    fn encryption_roundtrip() {
+        let key = ClientInboxKey::derive(&shared_keys("client", [1; 32])).unwrap();
+        let message = b"hello world".to_vec();
+
+        let stored = key.encrypt(&message).unwrap();
+        assert_ne!(stored, message);
+        assert_eq!(key.decrypt(&stored).unwrap(), message);
+
+        // nonces are random, so the same message is never stored the same way
+        assert_ne!(key.encrypt(&message).unwrap(), stored);
+    }
+
+    #[test]
+    fn keys_are_bound_to_the_client_and_its_shared_key() {
+        let key = ClientInboxKey::derive(&shared_keys("client", [1; 32])).unwrap();
+        let other_client = ClientInboxKey::derive(&shared_keys("other", [1; 32])).unwrap();
+        let reregistered = ClientInboxKey::derive(&shared_keys("client", [2; 32])).unwrap();
+
+        let stored = key.encrypt(b"hello world").unwrap();
+        assert!(other_client.decrypt(&stored).is_err());
+        assert!(reregistered.decrypt(&stored).is_err());
+    }
+
+    #[test]
+    fn tampered_messages_are_rejected() {
+        let key = ClientInboxKey::derive(&shared_keys("client", [1; 32])).unwrap();
+        let mut stored = key.encrypt(b"hello world").unwrap();
+        let last = stored.len() - 1;
+        stored[last] ^= 1;
+
+        assert!(key.decrypt(&stored).is_err());
+        assert!(key.decrypt(&stored[..4]).is_err());
+    }
+
+    #[test]
+    fn legacy_shared_keys_can_be_used() {
+        let legacy = PersistedSharedKeys {
+            client_id: 1,
+            client_address_bs58: "client".to_string(),
+            derived_aes128_ctr_blake3_hmac_keys_bs58: Some("legacykeys".to_string()),
+            derived_aes256_gcm_siv_key: None,
+        };
+        let key = ClientInboxKey::derive(&legacy).unwrap();
+        let stored = key.encrypt(b"hello world").unwrap();
+        assert_eq!(key.decrypt(&stored).unwrap(), b"hello world");
+
+        let missing = PersistedSharedKeys {
+            derived_aes128_ctr_blake3_hmac_keys_bs58: None,
+            ..legacy
+        };
+        assert!(ClientInboxKey::derive(&missing).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Padding of messages stored for offline clients to a multiple of a fixed bucket size,
+//! so that the database does not reveal the exact lengths of the queued messages.
+//!
+//! The padded message consists of the big-endian length of the original content,
+//! the content itself and zeroes up to the bucket boundary. Stripping the padding only
+//! depends on the encoded length, never on the content.
+
+use crate::error::StorageError;
+use std::num::NonZeroUsize;
+
+const LENGTH_PREFIX_SIZE: usize = 8;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MessagePadding {
+    bucket_size: NonZeroUsize,
+}
+
+impl MessagePadding {
+    pub(crate) fn new(bucket_size: NonZeroUsize) -> Self {
+        MessagePadding { bucket_size }
+    }
+
+    pub(crate) fn pad(&self, content: &[u8]) -> Vec<u8> {
+        let bucket_size = self.bucket_size.get();
+        let unpadded_len = LENGTH_PREFIX_SIZE + content.len();
+        let padded_len = unpadded_len.div_ceil(bucket_size) * bucket_size;
+
+        let mut padded = Vec::with_capacity(padded_len);
+        padded.extend_from_slice(&(content.len() as u64).to_be_bytes());
+        padded.extend_from_slice(content);
+        padded.resize(padded_len, 0);
+        padded
+    }
+
+    // note: it doesn't depend on the bucket size so that the messages could be recovered
+    // even if the padding got disabled or reconfigured in the meantime
+    pub(crate) fn unpad(mut stored: Vec<u8>) -> Result<Vec<u8>, StorageError> {
+        let malformed = || {
+            StorageError::DataCorruption(
+                "the stored padded message has an invalid length prefix".into(),
+            )
+        };
+
+        let prefix: [u8; LENGTH_PREFIX_SIZE] = stored
+            .get(..LENGTH_PREFIX_SIZE)
+            .and_then(|prefix| prefix.try_into().ok())
+            .ok_or_else(malformed)?;
+        let content_len = usize::try_from(u64::from_be_bytes(prefix)).map_err(|_| malformed())?;
+        if content_len > stored.len() - LENGTH_PREFIX_SIZE {
+            return Err(malformed());
+        }
+
+        stored.drain(..LENGTH_PREFIX_SIZE);
+        stored.truncate(content_len);
+        Ok(stored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn padding(bucket_size: usize) -> MessagePadding {
+        MessagePadding::new(NonZeroUsize::new(bucket_size).unwrap())
+    }
+
+    #[test]
+    fn padded_messages_round_trip() {
+        let padding = padding(32);
+        for len in [0, 1, 23, 24, 25, 100] {
+            let content = vec![42u8; len];
+            let padded = padding.pad(&content);
+            assert_eq!(padded.len() % 32, 0);
+            assert_eq!(MessagePadding::unpad(padded).unwrap(), content);
+        }
+    }
+
+    #[test]
+    fn messages_are_padded_to_the_bucket_boundary() {
+        let padding = padding(32);
+
+        // the content alongside its length prefix fits exactly into a single bucket
+        assert_eq!(padding.pad(&[1; 24]).len(), 32);
+        // whilst a single byte more requires another one
+        assert_eq!(padding.pad(&[1; 25]).len(), 64);
+        // and even empty content takes a whole bucket
+        assert_eq!(padding.pad(&[]).len(), 32);
+
+        // all the lengths within the same bucket are indistinguishable
+        assert_eq!(padding.pad(&[1; 30]).len(), padding.pad(&[1; 50]).len());
+    }
+
+    #[test]
+    fn unpadding_doesnt_depend_on_the_bucket_size() {
+        let padded = padding(7).pad(b"hello world");
+        assert_eq!(MessagePadding::unpad(padded).unwrap(), b"hello world");
+
+        let padded = padding(1).pad(b"hello world");
+        assert_eq!(padded.len(), LENGTH_PREFIX_SIZE + 11);
+        assert_eq!(MessagePadding::unpad(padded).unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn malformed_padded_messages_are_rejected() {
+        // too short to even contain the length prefix
+        assert!(MessagePadding::unpad(Vec::new()).is_err());
+        assert!(MessagePadding::unpad(vec![0; LENGTH_PREFIX_SIZE - 1]).is_err());
+
+        // the length prefix exceeds the remaining content
+        let mut padded = padding(16).pad(b"hello");
+        padded[..LENGTH_PREFIX_SIZE].copy_from_slice(&9u64.to_be_bytes());
+        assert!(MessagePadding::unpad(padded).is_err());
+
+        let mut padded = padding(16).pad(b"hello");
+        padded[..LENGTH_PREFIX_SIZE].copy_from_slice(&u64::MAX.to_be_bytes());
+        assert!(MessagePadding::unpad(padded).is_err());
+
+        // but the prefix on its own describes a valid, empty, message
+        assert!(MessagePadding::unpad(vec![0; LENGTH_PREFIX_SIZE])
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -1,8 +1,13 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
-use crate::models::StoredMessage;
+use crate::error::StorageError;
+use crate::message_store::MessageStore;
+use crate::models::RawStoredMessage;
+use async_trait::async_trait;
+use time::OffsetDateTime;
 
+/// The default, sqlite-backed, [MessageStore].
 #[derive(Clone)]
 pub(crate) struct InboxManager {
     connection_pool: sqlx::SqlitePool,
@@ -29,56 +34,49 @@ impl InboxManager {
             retrieval_limit,
         }
     }
+}
 
-    /// Inserts new message to the storage for an offline client for future retrieval.
-    ///
-    /// # Arguments
-    ///
-    /// * `client_address_bs58`: base58-encoded address of the client
-    /// * `content`: raw content of the message to store.
-    pub(crate) async fn insert_message(
+#[async_trait]
+impl MessageStore for InboxManager {
+    async fn insert_message(
         &self,
         client_address_bs58: &str,
         content: Vec<u8>,
-    ) -> Result<(), sqlx::Error> {
+        encrypted: bool,
+        padded: bool,
+    ) -> Result<(), StorageError> {
+        let stored_at = OffsetDateTime::now_utc().unix_timestamp();
         sqlx::query!(
-            "INSERT INTO message_store(client_address_bs58, content) VALUES (?, ?)",
+            "INSERT INTO message_store(client_address_bs58, content, encrypted, padded, stored_at) VALUES (?, ?, ?, ?, ?)",
             client_address_bs58,
             content,
+            encrypted,
+            padded,
+            stored_at,
         )
         .execute(&self.connection_pool)
         .await?;
         Ok(())
     }
 
-    /// Retrieves messages stored for the particular client specified by the provided address.
-    ///
-    /// It also respects the specified retrieval limit. If there are more messages stored than allowed
-    /// by the limit, it returns id of the last message retrieved to indicate start of the next query.
-    ///
-    /// # Arguments
-    ///
-    /// * `client_address_bs58`: base58-encoded address of the client
-    /// * `start_after`: optional starting id of the messages to grab
-    ///
-    /// returns the retrieved messages alongside optional id of the last message retrieved if
-    /// there are more messages to retrieve.
-    pub(crate) async fn get_messages(
+    async fn get_messages(
         &self,
         client_address_bs58: &str,
         start_after: Option<i64>,
-    ) -> Result<(Vec<StoredMessage>, Option<i64>), sqlx::Error> {
+    ) -> Result<(Vec<RawStoredMessage>, Option<i64>), StorageError> {
         // get 1 additional message to check whether there will be more to grab
         // next time
         let limit = self.retrieval_limit + 1;
         let mut res = if let Some(start_after) = start_after {
             sqlx::query_as!(
-                StoredMessage,
+                RawStoredMessage,
                 r#"
                     SELECT 
                         id as "id!",
                         client_address_bs58 as "client_address_bs58!",
-                        content as "content!" 
+                        content as "content!",
+                        encrypted as "encrypted!: bool",
+                        padded as "padded!: bool"
                     FROM message_store 
                     WHERE client_address_bs58 = ? AND id > ?
                     ORDER BY id ASC
@@ -92,12 +90,14 @@ impl InboxManager {
             .await?
         } else {
             sqlx::query_as!(
-                StoredMessage,
+                RawStoredMessage,
                 r#"
                    SELECT 
                         id as "id!",
                         client_address_bs58 as "client_address_bs58!",
-                        content as "content!"
+                        content as "content!",
+                        encrypted as "encrypted!: bool",
+                        padded as "padded!: bool"
                     FROM message_store
                     WHERE client_address_bs58 = ?
                     ORDER BY id ASC
@@ -122,15 +122,211 @@ impl InboxManager {
         }
     }
 
-    /// Removes message with the specified id
-    ///
-    /// # Arguments
-    ///
-    /// * `id`: id of the message to remove
-    pub(crate) async fn remove_message(&self, id: i64) -> Result<(), sqlx::Error> {
+    async fn remove_message(&self, id: i64) -> Result<(), StorageError> {
         sqlx::query!("DELETE FROM message_store WHERE id = ?", id)
             .execute(&self.connection_pool)
             .await?;
         Ok(())
     }
+
+    async fn remove_all_messages(&self, client_address_bs58: &str) -> Result<u64, StorageError> {
+        let res = sqlx::query!(
+            "DELETE FROM message_store WHERE client_address_bs58 = ?",
+            client_address_bs58
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+
+    async fn count_messages(&self, client_address_bs58: &str) -> Result<u64, StorageError> {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) as count FROM message_store WHERE client_address_bs58 = ?",
+            client_address_bs58
+        )
+        .fetch_one(&self.connection_pool)
+        .await?
+        .count;
+        Ok(count as u64)
+    }
+
+    async fn remove_messages_stored_before(
+        &self,
+        cutoff: OffsetDateTime,
+    ) -> Result<u64, StorageError> {
+        let cutoff = cutoff.unix_timestamp();
+        let res = sqlx::query!("DELETE FROM message_store WHERE stored_at < ?", cutoff)
+            .execute(&self.connection_pool)
+            .await?;
+        Ok(res.rows_affected())
+    }
+
+    async fn trim_client_messages(&self, max_per_client: u64) -> Result<u64, StorageError> {
+        let max_per_client = i64::try_from(max_per_client).unwrap_or(i64::MAX);
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM message_store
+                WHERE id IN (
+                    SELECT id FROM (
+                        SELECT
+                            id,
+                            ROW_NUMBER() OVER (PARTITION BY client_address_bs58 ORDER BY id DESC) AS position
+                        FROM message_store
+                    )
+                    WHERE position > ?
+                );
+            "#,
+            max_per_client
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+
+    async fn trim_to_content_size(&self, max_total_content_size: u64) -> Result<u64, StorageError> {
+        let max_total_content_size = i64::try_from(max_total_content_size).unwrap_or(i64::MAX);
+        // keep the newest messages whose cumulative size still fits within the limit
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM message_store
+                WHERE id IN (
+                    SELECT id FROM (
+                        SELECT
+                            id,
+                            SUM(LENGTH(content)) OVER (ORDER BY id DESC) AS cumulative_size
+                        FROM message_store
+                    )
+                    WHERE cumulative_size > ?
+                );
+            "#,
+            max_total_content_size
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+
+    async fn get_messages_stored_before(
+        &self,
+        cutoff: OffsetDateTime,
+        limit: u32,
+    ) -> Result<Vec<RawStoredMessage>, StorageError> {
+        let cutoff = cutoff.unix_timestamp();
+        Ok(sqlx::query_as!(
+            RawStoredMessage,
+            r#"
+                SELECT
+                    id as "id!",
+                    client_address_bs58 as "client_address_bs58!",
+                    content as "content!",
+                    encrypted as "encrypted!: bool",
+                    padded as "padded!: bool"
+                FROM message_store
+                WHERE stored_at < ?
+                ORDER BY id ASC
+                LIMIT ?;
+            "#,
+            cutoff,
+            limit
+        )
+        .fetch_all(&self.connection_pool)
+        .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PersistentStorage;
+    use std::sync::Arc;
+
+    const ALICE: &str = "alice";
+    const BOB: &str = "bob";
+
+    async fn test_store(dir: &tempfile::TempDir) -> Arc<dyn MessageStore> {
+        PersistentStorage::init(dir.path().join("storage.sqlite"), 100)
+            .await
+            .unwrap()
+            .message_store()
+    }
+
+    async fn insert(store: &Arc<dyn MessageStore>, client_address_bs58: &str, content: &[u8]) {
+        store
+            .insert_message(client_address_bs58, content.to_vec(), false, false)
+            .await
+            .unwrap()
+    }
+
+    async fn contents(store: &Arc<dyn MessageStore>, client_address_bs58: &str) -> Vec<Vec<u8>> {
+        let (messages, _) = store.get_messages(client_address_bs58, None).await.unwrap();
+        messages.into_iter().map(|m| m.content).collect()
+    }
+
+    #[tokio::test]
+    async fn trimming_keeps_the_newest_messages_of_every_client() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = test_store(&dir).await;
+        for content in [b"1", b"2", b"3", b"4"] {
+            insert(&store, ALICE, content).await;
+        }
+        insert(&store, BOB, b"5").await;
+
+        assert_eq!(store.trim_client_messages(2).await.unwrap(), 2);
+        assert_eq!(contents(&store, ALICE).await, vec![b"3", b"4"]);
+        assert_eq!(contents(&store, BOB).await, vec![b"5"]);
+        assert_eq!(store.count_messages(ALICE).await.unwrap(), 2);
+
+        // nothing left to remove
+        assert_eq!(store.trim_client_messages(2).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn trimming_to_content_size_keeps_the_newest_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = test_store(&dir).await;
+        insert(&store, ALICE, b"aaa").await;
+        insert(&store, BOB, b"bbbb").await;
+        insert(&store, ALICE, b"cc").await;
+
+        // the newest two messages take exactly 6 bytes
+        assert_eq!(store.trim_to_content_size(6).await.unwrap(), 1);
+        assert_eq!(contents(&store, ALICE).await, vec![b"cc"]);
+        assert_eq!(contents(&store, BOB).await, vec![b"bbbb"]);
+
+        assert_eq!(store.trim_to_content_size(0).await.unwrap(), 2);
+        assert_eq!(store.count_messages(ALICE).await.unwrap(), 0);
+        assert_eq!(store.count_messages(BOB).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn messages_are_removed_by_their_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = test_store(&dir).await;
+        insert(&store, ALICE, b"1").await;
+        insert(&store, BOB, b"2").await;
+        insert(&store, ALICE, b"3").await;
+
+        let past = OffsetDateTime::now_utc() - time::Duration::hours(1);
+        assert!(store
+            .get_messages_stored_before(past, 10)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(store.remove_messages_stored_before(past).await.unwrap(), 0);
+
+        // the oldest messages are returned first
+        let future = OffsetDateTime::now_utc() + time::Duration::hours(1);
+        let stored_before = store.get_messages_stored_before(future, 2).await.unwrap();
+        let stored_before = stored_before
+            .iter()
+            .map(|m| (m.client_address_bs58.as_str(), m.content.as_slice()))
+            .collect::<Vec<_>>();
+        assert_eq!(stored_before, vec![(ALICE, b"1".as_slice()), (BOB, b"2")]);
+
+        assert_eq!(
+            store.remove_messages_stored_before(future).await.unwrap(),
+            3
+        );
+        assert_eq!(store.count_messages(ALICE).await.unwrap(), 0);
+    }
 }
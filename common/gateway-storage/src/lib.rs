@@ -5,7 +5,10 @@ use async_trait::async_trait;
 use bandwidth::BandwidthManager;
 use clients::{ClientManager, ClientType};
 use error::StorageError;
+use inbox_encryption::ClientInboxKey;
+use inbox_padding::MessagePadding;
 use inboxes::InboxManager;
+use message_store::{MessageStore, RetentionPolicy};
 use models::{
     Client, PersistedBandwidth, PersistedSharedKeys, RedemptionProposal, StoredMessage,
     VerifiedTicket, WireguardPeer,
@@ -15,15 +18,20 @@ use nym_gateway_requests::shared_key::SharedGatewayKey;
 use nym_sphinx::DestinationAddressBytes;
 use shared_keys::SharedKeysManager;
 use sqlx::ConnectOptions;
+use std::num::NonZeroUsize;
 use std::path::Path;
+use std::sync::Arc;
 use tickets::TicketStorageManager;
 use time::OffsetDateTime;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 pub mod bandwidth;
 mod clients;
 pub mod error;
+mod inbox_encryption;
+mod inbox_padding;
 mod inboxes;
+pub mod message_store;
 pub mod models;
 mod shared_keys;
 mod tickets;
@@ -66,9 +74,6 @@ pub trait Storage: Send + Sync {
     /// # Arguments
     ///
     /// * `client_address`: address of the client
-    // currently there is no code flow that causes removal (not overwriting)
-    // of the stored keys. However, retain the function for consistency and completion sake
-    #[allow(dead_code)]
     async fn remove_shared_keys(
         &self,
         client_address: DestinationAddressBytes,
@@ -82,6 +87,9 @@ pub trait Storage: Send + Sync {
     #[allow(dead_code)]
     async fn get_client(&self, client_id: i64) -> Result<Option<Client>, StorageError>;
 
+    /// Retrieves addresses of all clients that have registered with this gateway.
+    async fn get_all_client_addresses(&self) -> Result<Vec<DestinationAddressBytes>, StorageError>;
+
     /// Inserts new message to the storage for an offline client for future retrieval.
     ///
     /// # Arguments
@@ -116,9 +124,64 @@ pub trait Storage: Send + Sync {
     /// * `ids`: ids of the messages to remove
     async fn remove_messages(&self, ids: Vec<i64>) -> Result<(), StorageError>;
 
+    /// Removes all messages stored for the particular client and returns the number of removed messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_address`: address of the client
+    async fn remove_all_messages(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<u64, StorageError>;
+
+    /// Removes all messages stored for the particular client and returns the number of removed messages.
+    /// If `deregister` is set, its shared keys and bandwidth are removed as well, so that it would have
+    /// to register again. The client entry itself is retained as it might still be referenced
+    /// by tickets pending redemption.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_address`: address of the client
+    /// * `client_id`: id of the client
+    /// * `deregister`: whether the client's registration should be removed as well
+    async fn purge_client_data(
+        &self,
+        client_address: DestinationAddressBytes,
+        client_id: i64,
+        deregister: bool,
+    ) -> Result<u64, StorageError>;
+
+    /// Moves the messages that haven't been retrieved for a while out of the message store,
+    /// if it's been configured with a secondary storage, and returns the number of moved messages.
+    async fn offload_cold_messages(&self) -> Result<usize, StorageError>;
+
+    /// Returns the number of messages currently stored for the particular client.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_address`: address of the client
+    async fn count_stored_messages(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<u64, StorageError>;
+
+    /// Removes the oldest stored messages exceeding any of the limits of the provided policy
+    /// and returns the number of removed messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy`: the limits to enforce
+    async fn enforce_message_retention(
+        &self,
+        policy: &RetentionPolicy,
+    ) -> Result<u64, StorageError>;
+
     /// Creates a new bandwidth entry for the particular client.
     async fn create_bandwidth_entry(&self, client_id: i64) -> Result<(), StorageError>;
 
+    /// Removes the bandwidth entry of the particular client.
+    async fn remove_bandwidth_entry(&self, client_id: i64) -> Result<(), StorageError>;
+
     /// Set the freepass expiration date of the particular client to the provided date.
     ///
     /// # Arguments
@@ -262,10 +325,17 @@ pub trait Storage: Send + Sync {
 pub struct PersistentStorage {
     client_manager: ClientManager,
     shared_key_manager: SharedKeysManager,
-    inbox_manager: InboxManager,
+    message_store: Arc<dyn MessageStore>,
     bandwidth_manager: BandwidthManager,
     ticket_manager: TicketStorageManager,
     wireguard_peer_manager: wireguard_peers::WgPeerManager,
+
+    /// Specifies whether messages stored for offline clients are encrypted
+    /// with keys derived from the shared keys of their recipients.
+    inbox_encryption: bool,
+
+    /// Optional padding applied to messages stored for offline clients.
+    message_padding: Option<MessagePadding>,
 }
 
 impl PersistentStorage {
@@ -312,11 +382,64 @@ impl PersistentStorage {
             client_manager: clients::ClientManager::new(connection_pool.clone()),
             wireguard_peer_manager: wireguard_peers::WgPeerManager::new(connection_pool.clone()),
             shared_key_manager: SharedKeysManager::new(connection_pool.clone()),
-            inbox_manager: InboxManager::new(connection_pool.clone(), message_retrieval_limit),
+            message_store: Arc::new(InboxManager::new(
+                connection_pool.clone(),
+                message_retrieval_limit,
+            )),
             bandwidth_manager: BandwidthManager::new(connection_pool.clone()),
             ticket_manager: TicketStorageManager::new(connection_pool),
+            inbox_encryption: true,
+            message_padding: None,
         })
     }
+
+    /// Specifies whether all messages stored for offline clients from now on should be encrypted at rest
+    /// (which is the default). Any messages stored before encryption got enabled are still going to be retrievable,
+    /// and so are the encrypted messages after it got disabled.
+    #[must_use]
+    pub fn with_inbox_encryption(mut self, enabled: bool) -> Self {
+        self.inbox_encryption = enabled;
+        self
+    }
+
+    async fn client_inbox_key(
+        &self,
+        client_address_bs58: &str,
+    ) -> Result<ClientInboxKey, StorageError> {
+        let shared_keys = self
+            .shared_key_manager
+            .get_shared_keys(client_address_bs58)
+            .await?
+            .ok_or_else(|| {
+                StorageError::InboxEncryptionFailure(format!(
+                    "there are no shared keys stored for client {client_address_bs58}"
+                ))
+            })?;
+        ClientInboxKey::derive(&shared_keys)
+    }
+
+    /// Pads all messages stored for offline clients from now on to a multiple of the provided bucket size,
+    /// so that their lengths are not revealed by the storage. The padding is applied before the encryption.
+    /// Any messages stored before padding got enabled are still going to be retrievable.
+    #[must_use]
+    pub fn with_message_padding(mut self, bucket_size: NonZeroUsize) -> Self {
+        self.message_padding = Some(MessagePadding::new(bucket_size));
+        self
+    }
+
+    /// Replaces the default, sqlite-backed, store of messages for offline clients with the provided one.
+    /// Messages held by the previous store are not migrated.
+    #[must_use]
+    pub fn with_message_store(mut self, message_store: impl MessageStore + 'static) -> Self {
+        self.message_store = Arc::new(message_store);
+        self
+    }
+
+    /// Returns the store currently used for messages of offline clients,
+    /// for example so that it could be wrapped by the [message_store::OffloadingMessageStore].
+    pub fn message_store(&self) -> Arc<dyn MessageStore> {
+        Arc::clone(&self.message_store)
+    }
 }
 
 #[async_trait]
@@ -371,7 +494,6 @@ impl Storage for PersistentStorage {
         Ok(keys)
     }
 
-    #[allow(dead_code)]
     async fn remove_shared_keys(
         &self,
         client_address: DestinationAddressBytes,
@@ -387,15 +509,38 @@ impl Storage for PersistentStorage {
         Ok(client)
     }
 
+    async fn get_all_client_addresses(&self) -> Result<Vec<DestinationAddressBytes>, StorageError> {
+        self.shared_key_manager
+            .client_addresses()
+            .await?
+            .into_iter()
+            .map(|address| {
+                DestinationAddressBytes::try_from_base58_string(address)
+                    .map_err(|err| StorageError::DataCorruption(err.to_string()))
+            })
+            .collect()
+    }
+
     async fn store_message(
         &self,
         client_address: DestinationAddressBytes,
         message: Vec<u8>,
     ) -> Result<(), StorageError> {
-        self.inbox_manager
-            .insert_message(&client_address.as_base58_string(), message)
-            .await?;
-        Ok(())
+        let client_address_bs58 = client_address.as_base58_string();
+        let (message, padded) = match &self.message_padding {
+            Some(padding) => (padding.pad(&message), true),
+            None => (message, false),
+        };
+        let (content, encrypted) = if self.inbox_encryption {
+            let key = self.client_inbox_key(&client_address_bs58).await?;
+            (key.encrypt(&message)?, true)
+        } else {
+            (message, false)
+        };
+
+        self.message_store
+            .insert_message(&client_address_bs58, content, encrypted, padded)
+            .await
     }
 
     async fn retrieve_messages(
@@ -403,25 +548,133 @@ impl Storage for PersistentStorage {
         client_address: DestinationAddressBytes,
         start_after: Option<i64>,
     ) -> Result<(Vec<StoredMessage>, Option<i64>), StorageError> {
-        let messages = self
-            .inbox_manager
+        let (raw_messages, start_next_after) = self
+            .message_store
             .get_messages(&client_address.as_base58_string(), start_after)
             .await?;
-        Ok(messages)
+
+        // all the retrieved messages belong to the same client, so we only need to derive its key once
+        let mut inbox_key = None;
+        let mut messages = Vec::with_capacity(raw_messages.len());
+        for raw in raw_messages {
+            let content = if raw.encrypted {
+                let key = match &inbox_key {
+                    Some(key) => key,
+                    None => {
+                        inbox_key.insert(self.client_inbox_key(&raw.client_address_bs58).await?)
+                    }
+                };
+                match key.decrypt(&raw.content) {
+                    Ok(content) => content,
+                    Err(err) => {
+                        // the message has been stored before the client re-registered with a fresh shared key,
+                        // so it will never be readable again
+                        warn!("discarding stored message {}: {err}", raw.id);
+                        self.message_store.remove_message(raw.id).await?;
+                        continue;
+                    }
+                }
+            } else {
+                raw.content
+            };
+            let content = if raw.padded {
+                MessagePadding::unpad(content)?
+            } else {
+                content
+            };
+
+            messages.push(StoredMessage {
+                id: raw.id,
+                client_address_bs58: raw.client_address_bs58,
+                content,
+            })
+        }
+
+        Ok((messages, start_next_after))
     }
 
     async fn remove_messages(&self, ids: Vec<i64>) -> Result<(), StorageError> {
         for id in ids {
-            self.inbox_manager.remove_message(id).await?;
+            self.message_store.remove_message(id).await?;
         }
         Ok(())
     }
 
+    async fn remove_all_messages(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<u64, StorageError> {
+        self.message_store
+            .remove_all_messages(&client_address.as_base58_string())
+            .await
+    }
+
+    async fn purge_client_data(
+        &self,
+        client_address: DestinationAddressBytes,
+        client_id: i64,
+        deregister: bool,
+    ) -> Result<u64, StorageError> {
+        let removed_messages = self.remove_all_messages(client_address).await?;
+        if deregister {
+            self.remove_shared_keys(client_address).await?;
+            self.remove_bandwidth_entry(client_id).await?;
+        }
+        Ok(removed_messages)
+    }
+
+    async fn offload_cold_messages(&self) -> Result<usize, StorageError> {
+        self.message_store.offload_cold_messages().await
+    }
+
+    async fn count_stored_messages(
+        &self,
+        client_address: DestinationAddressBytes,
+    ) -> Result<u64, StorageError> {
+        self.message_store
+            .count_messages(&client_address.as_base58_string())
+            .await
+    }
+
+    async fn enforce_message_retention(
+        &self,
+        policy: &RetentionPolicy,
+    ) -> Result<u64, StorageError> {
+        let mut removed = 0;
+
+        // get rid of the expired messages first so that they wouldn't count towards the remaining limits
+        if let Some(max_age) = policy.max_age {
+            let cutoff = OffsetDateTime::now_utc() - max_age;
+            removed += self
+                .message_store
+                .remove_messages_stored_before(cutoff)
+                .await?;
+        }
+        if let Some(max_per_client) = policy.max_messages_per_client {
+            removed += self
+                .message_store
+                .trim_client_messages(max_per_client)
+                .await?;
+        }
+        if let Some(max_total_content_size) = policy.max_total_content_size {
+            removed += self
+                .message_store
+                .trim_to_content_size(max_total_content_size)
+                .await?;
+        }
+        Ok(removed)
+    }
+
     async fn create_bandwidth_entry(&self, client_id: i64) -> Result<(), StorageError> {
         self.bandwidth_manager.insert_new_client(client_id).await?;
         Ok(())
     }
 
+    async fn remove_bandwidth_entry(&self, client_id: i64) -> Result<(), StorageError> {
+        self.bandwidth_manager.remove_client(client_id).await?;
+        Ok(())
+    }
+
     async fn set_expiration(
         &self,
         client_id: i64,
@@ -700,3 +953,294 @@ impl Storage for PersistentStorage {
         Ok(())
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use nym_gateway_requests::shared_key::SharedSymmetricKey;
+
+    async fn test_storage(dir: &tempfile::TempDir) -> PersistentStorage {
+        PersistentStorage::init(dir.path().join("storage.sqlite"), 100)
+            .await
+            .unwrap()
+    }
+
+    fn client() -> DestinationAddressBytes {
+        DestinationAddressBytes::from_bytes([42; 32])
+    }
+
+    fn shared_key(byte: u8) -> SharedGatewayKey {
+        SharedSymmetricKey::try_from_bytes(&[byte; 32])
+            .unwrap()
+            .into()
+    }
+
+    /// Registers the client, as the gateway would, with some bandwidth and stored messages
+    /// and returns its id.
+    pub(crate) async fn registered_client(
+        storage: &PersistentStorage,
+        address: DestinationAddressBytes,
+        messages: u8,
+    ) -> i64 {
+        let client_id = storage
+            .insert_shared_keys(address, &shared_key(1))
+            .await
+            .unwrap();
+        storage.create_bandwidth_entry(client_id).await.unwrap();
+        storage.increase_bandwidth(client_id, 1000).await.unwrap();
+        for i in 0..messages {
+            storage.store_message(address, vec![i; 10]).await.unwrap();
+        }
+        client_id
+    }
+
+    pub(crate) async fn assert_deregistered(
+        storage: &PersistentStorage,
+        address: DestinationAddressBytes,
+        client_id: i64,
+    ) {
+        assert_eq!(storage.count_stored_messages(address).await.unwrap(), 0);
+        assert!(storage.get_shared_keys(address).await.unwrap().is_none());
+        assert!(storage
+            .get_available_bandwidth(client_id)
+            .await
+            .unwrap()
+            .is_none());
+
+        // the client entry itself is kept for the tickets pending redemption
+        assert!(storage.get_client(client_id).await.unwrap().is_some());
+    }
+
+    async fn stored_contents(storage: &PersistentStorage) -> Vec<Vec<u8>> {
+        let (messages, _) = storage.retrieve_messages(client(), None).await.unwrap();
+        messages
+            .into_iter()
+            .map(|message| message.content)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn stored_messages_are_encrypted_at_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = test_storage(&dir).await;
+        storage
+            .insert_shared_keys(client(), &shared_key(1))
+            .await
+            .unwrap();
+
+        storage
+            .store_message(client(), b"hello world".to_vec())
+            .await
+            .unwrap();
+
+        let (raw, _) = storage
+            .message_store()
+            .get_messages(&client().as_base58_string(), None)
+            .await
+            .unwrap();
+        assert!(raw[0].encrypted);
+        assert_ne!(raw[0].content, b"hello world");
+
+        assert_eq!(
+            stored_contents(&storage).await,
+            vec![b"hello world".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn encryption_can_be_disabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = test_storage(&dir).await.with_inbox_encryption(false);
+
+        // no shared keys are needed if the messages are not encrypted
+        storage
+            .store_message(client(), b"hello world".to_vec())
+            .await
+            .unwrap();
+
+        let (raw, _) = storage
+            .message_store()
+            .get_messages(&client().as_base58_string(), None)
+            .await
+            .unwrap();
+        assert!(!raw[0].encrypted);
+        assert_eq!(raw[0].content, b"hello world");
+    }
+
+    #[tokio::test]
+    async fn messages_cannot_be_stored_for_unknown_clients() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = test_storage(&dir).await;
+
+        assert!(storage
+            .store_message(client(), b"hello world".to_vec())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn messages_stored_under_previous_shared_key_are_discarded() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = test_storage(&dir).await;
+        storage
+            .insert_shared_keys(client(), &shared_key(1))
+            .await
+            .unwrap();
+        storage
+            .store_message(client(), b"old".to_vec())
+            .await
+            .unwrap();
+
+        // the client has re-registered
+        storage
+            .insert_shared_keys(client(), &shared_key(2))
+            .await
+            .unwrap();
+        storage
+            .store_message(client(), b"new".to_vec())
+            .await
+            .unwrap();
+
+        assert_eq!(stored_contents(&storage).await, vec![b"new".to_vec()]);
+
+        // and the unreadable message got removed
+        let (raw, _) = storage
+            .message_store()
+            .get_messages(&client().as_base58_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(raw.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn padded_messages_are_retrieved_unpadded() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = test_storage(&dir).await.with_inbox_encryption(false);
+        storage
+            .store_message(client(), b"unpadded".to_vec())
+            .await
+            .unwrap();
+
+        let storage = storage.with_message_padding(NonZeroUsize::new(64).unwrap());
+        storage
+            .store_message(client(), b"hello world".to_vec())
+            .await
+            .unwrap();
+
+        // changing the bucket size doesn't affect the already stored messages
+        let storage = storage.with_message_padding(NonZeroUsize::new(16).unwrap());
+        storage
+            .store_message(client(), b"hello again".to_vec())
+            .await
+            .unwrap();
+
+        let (raw, _) = storage
+            .message_store()
+            .get_messages(&client().as_base58_string(), None)
+            .await
+            .unwrap();
+        assert!(!raw[0].padded);
+        assert!(raw[1].padded && raw[2].padded);
+        assert_eq!(raw[1].content.len(), 64);
+        assert_eq!(raw[2].content.len(), 32);
+
+        assert_eq!(
+            stored_contents(&storage).await,
+            vec![
+                b"unpadded".to_vec(),
+                b"hello world".to_vec(),
+                b"hello again".to_vec()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn retention_policy_limits_are_combined() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = test_storage(&dir).await.with_inbox_encryption(false);
+        let other = DestinationAddressBytes::from_bytes([43; 32]);
+        for _ in 0..3 {
+            storage.store_message(client(), vec![1; 10]).await.unwrap();
+        }
+        storage.store_message(other, vec![2; 10]).await.unwrap();
+
+        assert_eq!(
+            storage
+                .enforce_message_retention(&RetentionPolicy::default())
+                .await
+                .unwrap(),
+            0
+        );
+
+        // none of the messages are old enough to be removed, but only the newest two of the first client
+        // are kept, and then only the newest two messages overall still fit
+        let policy = RetentionPolicy {
+            max_messages_per_client: Some(2),
+            max_age: Some(std::time::Duration::from_secs(3600)),
+            max_total_content_size: Some(25),
+        };
+        assert_eq!(storage.enforce_message_retention(&policy).await.unwrap(), 2);
+        assert_eq!(storage.count_stored_messages(client()).await.unwrap(), 1);
+        assert_eq!(storage.count_stored_messages(other).await.unwrap(), 1);
+    }
+
+    async fn check_purge(storage: PersistentStorage) {
+        let other = DestinationAddressBytes::from_bytes([43; 32]);
+        let client_id = registered_client(&storage, client(), 2).await;
+        let other_id = registered_client(&storage, other, 1).await;
+
+        // without deregistering, only the messages are removed
+        assert_eq!(
+            storage
+                .purge_client_data(client(), client_id, false)
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(storage.count_stored_messages(client()).await.unwrap(), 0);
+        assert!(storage.get_shared_keys(client()).await.unwrap().is_some());
+        assert_eq!(
+            storage
+                .get_available_bandwidth(client_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .available,
+            1000
+        );
+
+        storage.store_message(client(), vec![1; 10]).await.unwrap();
+        assert_eq!(
+            storage
+                .purge_client_data(client(), client_id, true)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_deregistered(&storage, client(), client_id).await;
+
+        // and the data of other clients is left alone
+        assert_eq!(storage.count_stored_messages(other).await.unwrap(), 1);
+        assert!(storage.get_shared_keys(other).await.unwrap().is_some());
+        assert!(storage
+            .get_available_bandwidth(other_id)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn purging_removes_the_client_data() {
+        let dir = tempfile::tempdir().unwrap();
+        check_purge(test_storage(&dir).await).await;
+    }
+
+    #[cfg(feature = "rocksdb")]
+    #[tokio::test]
+    async fn purging_removes_the_client_data_from_rocksdb() {
+        let dir = tempfile::tempdir().unwrap();
+        let message_store =
+            message_store::RocksDbMessageStore::open(dir.path().join("messages"), 100).unwrap();
+        check_purge(test_storage(&dir).await.with_message_store(message_store)).await;
+    }
+}
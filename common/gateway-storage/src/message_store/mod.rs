@@ -0,0 +1,154 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Backends persisting the messages received for clients that are currently offline.
+//!
+//! By default the messages live in the same sqlite database as the rest of the client data.
+//! Gateways serving a lot of clients can instead keep them in a dedicated RocksDB database
+//! (behind the `rocksdb` feature) and/or move the messages that haven't been retrieved for a while
+//! to an object store, such as S3 (behind the `object-store-offload` feature).
+
+use crate::error::StorageError;
+use crate::models::RawStoredMessage;
+use async_trait::async_trait;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+#[cfg(feature = "object-store-offload")]
+mod offload;
+#[cfg(feature = "rocksdb")]
+mod rocksdb_store;
+
+#[cfg(feature = "object-store-offload")]
+pub use offload::OffloadingMessageStore;
+#[cfg(feature = "rocksdb")]
+pub use rocksdb_store::RocksDbMessageStore;
+
+/// Limits on the messages kept for offline clients. Once any of them is exceeded,
+/// the oldest messages are removed, regardless of whether they have ever been retrieved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Maximum number of messages kept for a single client.
+    pub max_messages_per_client: Option<u64>,
+
+    /// Maximum age of the kept messages.
+    pub max_age: Option<Duration>,
+
+    /// Maximum total size, in bytes, of the contents of all the kept messages, as they're stored,
+    /// i.e. after the padding and the encryption. Note that it doesn't include any overhead
+    /// of the backend, so it's a lower bound of the disk space the messages take rather than its measure.
+    pub max_total_content_size: Option<u64>,
+}
+
+impl RetentionPolicy {
+    /// Returns whether the policy doesn't impose any limits.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_messages_per_client.is_none()
+            && self.max_age.is_none()
+            && self.max_total_content_size.is_none()
+    }
+}
+
+#[async_trait]
+pub trait MessageStore: Send + Sync {
+    /// Inserts new message to the storage for an offline client for future retrieval.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_address_bs58`: base58-encoded address of the client
+    /// * `content`: raw content of the message to store.
+    /// * `encrypted`: indicates whether the content has been encrypted before being stored.
+    /// * `padded`: indicates whether the content has been padded to the bucket size before being stored.
+    async fn insert_message(
+        &self,
+        client_address_bs58: &str,
+        content: Vec<u8>,
+        encrypted: bool,
+        padded: bool,
+    ) -> Result<(), StorageError>;
+
+    /// Retrieves messages stored for the particular client specified by the provided address.
+    ///
+    /// It also respects the retrieval limit of the store. If there are more messages stored than allowed
+    /// by the limit, it returns id of the last message retrieved to indicate start of the next query.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_address_bs58`: base58-encoded address of the client
+    /// * `start_after`: optional starting id of the messages to grab
+    ///
+    /// returns the retrieved messages alongside optional id of the last message retrieved if
+    /// there are more messages to retrieve.
+    async fn get_messages(
+        &self,
+        client_address_bs58: &str,
+        start_after: Option<i64>,
+    ) -> Result<(Vec<RawStoredMessage>, Option<i64>), StorageError>;
+
+    /// Removes message with the specified id
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: id of the message to remove
+    async fn remove_message(&self, id: i64) -> Result<(), StorageError>;
+
+    /// Removes all messages stored for the particular client and returns the number of removed messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_address_bs58`: base58-encoded address of the client
+    async fn remove_all_messages(&self, client_address_bs58: &str) -> Result<u64, StorageError>;
+
+    /// Returns the number of messages currently stored for the particular client.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_address_bs58`: base58-encoded address of the client
+    async fn count_messages(&self, client_address_bs58: &str) -> Result<u64, StorageError>;
+
+    /// Removes the messages of all clients that have been stored before the provided cutoff
+    /// and returns the number of removed messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `cutoff`: messages stored at or after this moment are kept
+    async fn remove_messages_stored_before(
+        &self,
+        cutoff: OffsetDateTime,
+    ) -> Result<u64, StorageError>;
+
+    /// Removes the oldest messages of every client that has more than `max_per_client` of them stored
+    /// and returns the number of removed messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_per_client`: maximum number of messages kept for a single client
+    async fn trim_client_messages(&self, max_per_client: u64) -> Result<u64, StorageError>;
+
+    /// Removes the oldest messages, regardless of their recipients, until the total size of the contents
+    /// of the remaining ones doesn't exceed `max_total_content_size` and returns the number of removed messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_total_content_size`: maximum total size, in bytes, of the contents of the kept messages
+    async fn trim_to_content_size(&self, max_total_content_size: u64) -> Result<u64, StorageError>;
+
+    /// Retrieves, oldest first, up to `limit` messages of any client that have been stored before the provided cutoff.
+    ///
+    /// # Arguments
+    ///
+    /// * `cutoff`: messages stored at or after this moment are ignored
+    /// * `limit`: maximum number of messages to retrieve
+    async fn get_messages_stored_before(
+        &self,
+        cutoff: OffsetDateTime,
+        limit: u32,
+    ) -> Result<Vec<RawStoredMessage>, StorageError>;
+
+    /// Moves the messages that haven't been retrieved for a while to a secondary storage
+    /// and returns the number of moved messages.
+    /// Stores without any secondary storage don't do anything.
+    async fn offload_cold_messages(&self) -> Result<usize, StorageError> {
+        Ok(0)
+    }
+}
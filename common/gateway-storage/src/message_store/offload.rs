@@ -0,0 +1,649 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::error::StorageError;
+use crate::message_store::MessageStore;
+use crate::models::RawStoredMessage;
+use async_trait::async_trait;
+use futures::{StreamExt, TryStreamExt};
+use nym_crypto::blake3;
+use object_store::aws::AmazonS3Builder;
+use object_store::path::Path;
+use object_store::{ObjectMeta, ObjectStore};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tracing::debug;
+use url::Url;
+
+// maximum number of messages moved out of the hot store at once
+const OFFLOAD_BATCH_SIZE: u32 = 1000;
+
+// (encrypted, content, padded)
+type OffloadedMessage = (bool, Vec<u8>, bool);
+
+// (encrypted, content), as offloaded before the introduction of padding
+type LegacyOffloadedMessage = (bool, Vec<u8>);
+
+// context of the derivation of the key of the hash of the client addresses used in the object locations
+const LOCATION_KEY_CONTEXT: &str = "nym-gateway-storage 2024-11-12 offloaded message locations";
+
+// suffix of the names of objects holding the messages in the current format
+const OFFLOADED_FORMAT_SUFFIX: &str = "-v2";
+
+// the objects are named `{offloaded_at}-{first_id}-{count}-v2`, so that the messages could be counted
+// without being downloaded. objects offloaded before the count got included in the name are missing it
+fn offloaded_count(location: &Path) -> Option<u64> {
+    let name = location.filename()?.strip_suffix(OFFLOADED_FORMAT_SUFFIX)?;
+    match name.split('-').collect::<Vec<_>>().as_slice() {
+        [_, _, count] => count.parse().ok(),
+        _ => None,
+    }
+}
+
+fn backend_failure(err: impl Display) -> StorageError {
+    StorageError::MessageStoreBackendFailure(err.to_string())
+}
+
+/// [MessageStore] that keeps recent messages in the wrapped "hot" store and moves the ones that
+/// haven't been retrieved for a while into an object store, such as S3.
+///
+/// The offloaded messages of a client are brought back into the hot store once it comes back online
+/// and starts retrieving its messages. Note that their contents stay encrypted if inbox encryption is enabled.
+///
+/// Since the object store doesn't take any local disk space, the per-client and total content size retention limits
+/// only apply to the hot store, whilst the maximum age applies to the offloaded messages as well.
+///
+/// The messages of each client are put under a keyed hash of its address, so that the clients
+/// could not be identified by anyone with access to the object store, but not to the key.
+pub struct OffloadingMessageStore {
+    hot: Arc<dyn MessageStore>,
+    cold: Arc<dyn ObjectStore>,
+
+    /// Location under which all the offloaded messages are put.
+    prefix: Path,
+
+    /// Key of the hash of the client addresses used in the object locations.
+    location_key: [u8; 32],
+
+    /// Age after which the messages are moved out of the hot store.
+    offload_after: Duration,
+}
+
+impl OffloadingMessageStore {
+    /// Creates new instance of the store offloading messages to the object store at the provided url,
+    /// e.g. `s3://bucket/path` or `file:///var/lib/nym/offloaded`.
+    /// The S3 credentials and region are read from the standard `AWS_*` environment variables.
+    ///
+    /// # Arguments
+    ///
+    /// * `hot`: the store holding the messages until they're offloaded.
+    /// * `url`: url of the object store to offload the messages to.
+    /// * `namespace`: location relative to the url under which the messages are put,
+    ///   so that multiple gateways could share the same bucket.
+    /// * `location_secret`: secret the key of the hash of the client addresses used in the object locations
+    ///   is derived from. It has to stay the same between the restarts for the offloaded messages to be found.
+    /// * `offload_after`: age after which the messages are moved out of the hot store.
+    pub fn new(
+        hot: Arc<dyn MessageStore>,
+        url: &Url,
+        namespace: &str,
+        location_secret: &[u8],
+        offload_after: Duration,
+    ) -> Result<Self, StorageError> {
+        let (cold, base): (Box<dyn ObjectStore>, Path) = if url.scheme() == "s3" {
+            let store = AmazonS3Builder::from_env()
+                .with_url(url.as_str())
+                .build()
+                .map_err(backend_failure)?;
+            let base = Path::from_url_path(url.path()).map_err(backend_failure)?;
+            (Box::new(store), base)
+        } else {
+            object_store::parse_url(url).map_err(backend_failure)?
+        };
+
+        Ok(Self::with_object_store(
+            hot,
+            Arc::from(cold),
+            base.child(namespace),
+            blake3::derive_key(LOCATION_KEY_CONTEXT, location_secret),
+            offload_after,
+        ))
+    }
+
+    fn with_object_store(
+        hot: Arc<dyn MessageStore>,
+        cold: Arc<dyn ObjectStore>,
+        prefix: Path,
+        location_key: [u8; 32],
+        offload_after: Duration,
+    ) -> Self {
+        OffloadingMessageStore {
+            hot,
+            cold,
+            prefix,
+            location_key,
+            offload_after,
+        }
+    }
+
+    fn client_prefix(&self, client_address_bs58: &str) -> Path {
+        let hashed = blake3::keyed_hash(&self.location_key, client_address_bs58.as_bytes());
+        self.prefix.child(hashed.to_hex().as_str())
+    }
+
+    // messages offloaded before the addresses got hashed were put directly under them
+    fn legacy_client_prefix(&self, client_address_bs58: &str) -> Path {
+        self.prefix.child(client_address_bs58)
+    }
+
+    async fn list_objects(&self, prefix: &Path) -> Result<Vec<ObjectMeta>, StorageError> {
+        self.cold
+            .list(Some(prefix))
+            .try_collect()
+            .await
+            .map_err(backend_failure)
+    }
+
+    /// Lists all the objects holding offloaded messages of the particular client,
+    /// in the order they've been offloaded in.
+    async fn list_client_objects(
+        &self,
+        client_address_bs58: &str,
+    ) -> Result<Vec<ObjectMeta>, StorageError> {
+        let mut objects = self
+            .list_objects(&self.client_prefix(client_address_bs58))
+            .await?;
+        objects.extend(
+            self.list_objects(&self.legacy_client_prefix(client_address_bs58))
+                .await?,
+        );
+
+        // the object names start with the offload timestamp
+        objects.sort_by(|a, b| a.location.filename().cmp(&b.location.filename()));
+        Ok(objects)
+    }
+
+    async fn count_object_messages(&self, location: &Path) -> Result<u64, StorageError> {
+        match offloaded_count(location) {
+            Some(count) => Ok(count),
+            None => Ok(self.read_object(location).await?.len() as u64),
+        }
+    }
+
+    async fn delete_objects(&self, locations: Vec<Path>) -> Result<(), StorageError> {
+        if locations.is_empty() {
+            return Ok(());
+        }
+        // allow the store to remove them in bulk, if it's supported
+        self.cold
+            .delete_stream(futures::stream::iter(locations.into_iter().map(Ok)).boxed())
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(backend_failure)?;
+        Ok(())
+    }
+
+    async fn read_object(&self, location: &Path) -> Result<Vec<OffloadedMessage>, StorageError> {
+        let data = self
+            .cold
+            .get(location)
+            .await
+            .map_err(backend_failure)?
+            .bytes()
+            .await
+            .map_err(backend_failure)?;
+        let malformed = |err: bincode::Error| {
+            StorageError::DataCorruption(format!(
+                "offloaded messages at '{location}' are malformed: {err}"
+            ))
+        };
+        if location.as_ref().ends_with(OFFLOADED_FORMAT_SUFFIX) {
+            bincode::deserialize(&data).map_err(malformed)
+        } else {
+            Ok(bincode::deserialize::<Vec<LegacyOffloadedMessage>>(&data)
+                .map_err(malformed)?
+                .into_iter()
+                .map(|(encrypted, content)| (encrypted, content, false))
+                .collect())
+        }
+    }
+
+    /// Moves all offloaded messages of the particular client back into the hot store.
+    async fn restore_messages(&self, client_address_bs58: &str) -> Result<(), StorageError> {
+        for object in self.list_client_objects(client_address_bs58).await? {
+            let messages = self.read_object(&object.location).await?;
+
+            debug!(
+                "restoring {} offloaded messages of {client_address_bs58}",
+                messages.len()
+            );
+            // if we crash before removing the object, the messages will get delivered twice,
+            // which is still better than losing them
+            for (encrypted, content, padded) in messages {
+                self.hot
+                    .insert_message(client_address_bs58, content, encrypted, padded)
+                    .await?;
+            }
+            self.cold
+                .delete(&object.location)
+                .await
+                .map_err(backend_failure)?;
+        }
+
+        Ok(())
+    }
+
+    async fn offload_client_messages(
+        &self,
+        client_address_bs58: &str,
+        messages: Vec<RawStoredMessage>,
+    ) -> Result<(), StorageError> {
+        let ids = messages.iter().map(|m| m.id).collect::<Vec<_>>();
+        let Some(first_id) = ids.first() else {
+            return Ok(());
+        };
+
+        let name = format!(
+            "{:020}-{first_id}-{}{OFFLOADED_FORMAT_SUFFIX}",
+            OffsetDateTime::now_utc().unix_timestamp_nanos(),
+            ids.len()
+        );
+        let location = self.client_prefix(client_address_bs58).child(name);
+
+        let offloaded = messages
+            .into_iter()
+            .map(|m| (m.encrypted, m.content, m.padded))
+            .collect::<Vec<OffloadedMessage>>();
+        let payload = bincode::serialize(&offloaded).map_err(backend_failure)?;
+        self.cold
+            .put(&location, payload.into())
+            .await
+            .map_err(backend_failure)?;
+
+        // only remove the messages once they've been safely persisted in the object store
+        for id in ids {
+            self.hot.remove_message(id).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MessageStore for OffloadingMessageStore {
+    async fn insert_message(
+        &self,
+        client_address_bs58: &str,
+        content: Vec<u8>,
+        encrypted: bool,
+        padded: bool,
+    ) -> Result<(), StorageError> {
+        self.hot
+            .insert_message(client_address_bs58, content, encrypted, padded)
+            .await
+    }
+
+    async fn get_messages(
+        &self,
+        client_address_bs58: &str,
+        start_after: Option<i64>,
+    ) -> Result<(Vec<RawStoredMessage>, Option<i64>), StorageError> {
+        // the retrieval always starts from the beginning, which is when we bring the cold messages back
+        if start_after.is_none() {
+            self.restore_messages(client_address_bs58).await?;
+        }
+        self.hot
+            .get_messages(client_address_bs58, start_after)
+            .await
+    }
+
+    async fn remove_message(&self, id: i64) -> Result<(), StorageError> {
+        self.hot.remove_message(id).await
+    }
+
+    async fn remove_all_messages(&self, client_address_bs58: &str) -> Result<u64, StorageError> {
+        let mut removed = 0;
+        let mut locations = Vec::new();
+        for object in self.list_client_objects(client_address_bs58).await? {
+            removed += self.count_object_messages(&object.location).await?;
+            locations.push(object.location);
+        }
+        self.delete_objects(locations).await?;
+
+        Ok(removed + self.hot.remove_all_messages(client_address_bs58).await?)
+    }
+
+    async fn count_messages(&self, client_address_bs58: &str) -> Result<u64, StorageError> {
+        let mut count = self.hot.count_messages(client_address_bs58).await?;
+        for object in self.list_client_objects(client_address_bs58).await? {
+            count += self.count_object_messages(&object.location).await?;
+        }
+        Ok(count)
+    }
+
+    async fn remove_messages_stored_before(
+        &self,
+        cutoff: OffsetDateTime,
+    ) -> Result<u64, StorageError> {
+        let mut removed = self.hot.remove_messages_stored_before(cutoff).await?;
+
+        // all the messages of an object have been stored before it got offloaded,
+        // so it's safe to remove any object offloaded before the cutoff
+        let cutoff = cutoff.unix_timestamp_nanos();
+        let mut expired = Vec::new();
+        for object in self.list_objects(&self.prefix).await? {
+            let offloaded_at = object
+                .location
+                .filename()
+                .and_then(|name| name.split('-').next())
+                .and_then(|timestamp| timestamp.parse::<i128>().ok());
+            if offloaded_at.is_some_and(|offloaded_at| offloaded_at < cutoff) {
+                removed += self.count_object_messages(&object.location).await?;
+                expired.push(object.location);
+            }
+        }
+        self.delete_objects(expired).await?;
+        Ok(removed)
+    }
+
+    async fn trim_client_messages(&self, max_per_client: u64) -> Result<u64, StorageError> {
+        self.hot.trim_client_messages(max_per_client).await
+    }
+
+    async fn trim_to_content_size(&self, max_total_content_size: u64) -> Result<u64, StorageError> {
+        self.hot.trim_to_content_size(max_total_content_size).await
+    }
+
+    async fn get_messages_stored_before(
+        &self,
+        cutoff: OffsetDateTime,
+        limit: u32,
+    ) -> Result<Vec<RawStoredMessage>, StorageError> {
+        self.hot.get_messages_stored_before(cutoff, limit).await
+    }
+
+    async fn offload_cold_messages(&self) -> Result<usize, StorageError> {
+        let cutoff = OffsetDateTime::now_utc() - self.offload_after;
+        let mut offloaded = 0;
+
+        loop {
+            let batch = self
+                .hot
+                .get_messages_stored_before(cutoff, OFFLOAD_BATCH_SIZE)
+                .await?;
+            let batch_size = batch.len();
+
+            let mut per_client: HashMap<String, Vec<RawStoredMessage>> = HashMap::new();
+            for message in batch {
+                per_client
+                    .entry(message.client_address_bs58.clone())
+                    .or_default()
+                    .push(message);
+            }
+            for (client_address_bs58, messages) in per_client {
+                self.offload_client_messages(&client_address_bs58, messages)
+                    .await?;
+            }
+
+            offloaded += batch_size;
+            if batch_size < OFFLOAD_BATCH_SIZE as usize {
+                return Ok(offloaded);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{assert_deregistered, registered_client};
+    use crate::{PersistentStorage, Storage};
+    use nym_sphinx::DestinationAddressBytes;
+    use object_store::memory::InMemory;
+
+    const CLIENT: &str = "client";
+
+    struct TestStore {
+        store: OffloadingMessageStore,
+        cold: Arc<InMemory>,
+        _dir: tempfile::TempDir,
+    }
+
+    async fn test_store() -> TestStore {
+        let dir = tempfile::tempdir().unwrap();
+        let hot = PersistentStorage::init(dir.path().join("storage.sqlite"), 100)
+            .await
+            .unwrap()
+            .message_store();
+        let cold = Arc::new(InMemory::new());
+        let store = OffloadingMessageStore::with_object_store(
+            hot,
+            cold.clone(),
+            Path::from("gateway"),
+            [1; 32],
+            Duration::from_secs(60),
+        );
+        TestStore {
+            store,
+            cold,
+            _dir: dir,
+        }
+    }
+
+    impl TestStore {
+        async fn insert(&self, content: &[u8]) {
+            self.store
+                .insert_message(CLIENT, content.to_vec(), false, false)
+                .await
+                .unwrap()
+        }
+
+        async fn offload_all(&self) {
+            let (messages, _) = self.store.hot.get_messages(CLIENT, None).await.unwrap();
+            self.store
+                .offload_client_messages(CLIENT, messages)
+                .await
+                .unwrap()
+        }
+
+        async fn cold_locations(&self) -> Vec<Path> {
+            self.cold
+                .list(None)
+                .map_ok(|object| object.location)
+                .try_collect()
+                .await
+                .unwrap()
+        }
+
+        async fn put_object(&self, location: Path, payload: Vec<u8>) {
+            self.cold.put(&location, payload.into()).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn offloaded_messages_are_counted_and_restored() {
+        let test = test_store().await;
+        test.insert(b"1").await;
+        test.insert(b"2").await;
+        test.offload_all().await;
+        test.insert(b"3").await;
+
+        assert_eq!(test.store.hot.count_messages(CLIENT).await.unwrap(), 1);
+        assert_eq!(test.store.count_messages(CLIENT).await.unwrap(), 3);
+
+        // the address of the client is not revealed by the object store
+        let locations = test.cold_locations().await;
+        assert_eq!(locations.len(), 1);
+        assert!(!locations[0].as_ref().contains(CLIENT));
+        assert_eq!(offloaded_count(&locations[0]), Some(2));
+
+        let (messages, _) = test.store.get_messages(CLIENT, None).await.unwrap();
+        let contents = messages
+            .iter()
+            .map(|m| m.content.as_slice())
+            .collect::<Vec<_>>();
+        assert_eq!(contents, vec![b"3", b"1", b"2"]);
+        assert!(test.cold_locations().await.is_empty());
+        assert_eq!(test.store.count_messages(CLIENT).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn removing_all_messages_deletes_the_offloaded_objects() {
+        let test = test_store().await;
+        test.insert(b"1").await;
+        test.offload_all().await;
+        test.insert(b"2").await;
+        test.offload_all().await;
+        test.insert(b"3").await;
+        assert_eq!(test.cold_locations().await.len(), 2);
+
+        assert_eq!(test.store.remove_all_messages(CLIENT).await.unwrap(), 3);
+        assert!(test.cold_locations().await.is_empty());
+        assert_eq!(test.store.count_messages(CLIENT).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn expired_objects_are_removed() {
+        let test = test_store().await;
+        test.insert(b"1").await;
+        test.offload_all().await;
+
+        let past = OffsetDateTime::now_utc() - time::Duration::hours(1);
+        assert_eq!(
+            test.store
+                .remove_messages_stored_before(past)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(test.cold_locations().await.len(), 1);
+
+        let future = OffsetDateTime::now_utc() + time::Duration::hours(1);
+        assert_eq!(
+            test.store
+                .remove_messages_stored_before(future)
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(test.cold_locations().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn messages_offloaded_under_the_plain_address_are_found() {
+        let test = test_store().await;
+        let messages: Vec<OffloadedMessage> =
+            vec![(false, b"1".to_vec(), false), (false, b"2".to_vec(), false)];
+        let location = test
+            .store
+            .legacy_client_prefix(CLIENT)
+            .child(format!("{:020}-1{OFFLOADED_FORMAT_SUFFIX}", 1));
+        test.put_object(location, bincode::serialize(&messages).unwrap())
+            .await;
+
+        // the count is not included in the name, so the object has to be read
+        assert_eq!(test.store.count_messages(CLIENT).await.unwrap(), 2);
+
+        let (restored, _) = test.store.get_messages(CLIENT, None).await.unwrap();
+        assert_eq!(restored.len(), 2);
+        assert!(test.cold_locations().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn padding_flag_survives_the_offloading() {
+        let test = test_store().await;
+        test.store
+            .insert_message(CLIENT, b"padded".to_vec(), true, true)
+            .await
+            .unwrap();
+        test.insert(b"plain").await;
+        test.offload_all().await;
+
+        let (restored, _) = test.store.get_messages(CLIENT, None).await.unwrap();
+        assert!(restored[0].padded && restored[0].encrypted);
+        assert!(!restored[1].padded && !restored[1].encrypted);
+    }
+
+    #[tokio::test]
+    async fn messages_offloaded_before_the_padding_are_decoded() {
+        let test = test_store().await;
+        let messages: Vec<LegacyOffloadedMessage> =
+            vec![(true, b"1".to_vec()), (false, b"2".to_vec())];
+        // objects in the legacy format are missing the format suffix
+        let location = test
+            .store
+            .client_prefix(CLIENT)
+            .child(format!("{:020}-1", 1));
+        test.put_object(location, bincode::serialize(&messages).unwrap())
+            .await;
+
+        assert_eq!(test.store.count_messages(CLIENT).await.unwrap(), 2);
+        let (restored, _) = test.store.get_messages(CLIENT, None).await.unwrap();
+        assert_eq!(restored[0].content, b"1");
+        assert!(restored[0].encrypted && !restored[0].padded);
+        assert_eq!(restored[1].content, b"2");
+        assert!(!restored[1].encrypted && !restored[1].padded);
+    }
+
+    #[tokio::test]
+    async fn malformed_objects_are_rejected() {
+        let test = test_store().await;
+        let location = test
+            .store
+            .client_prefix(CLIENT)
+            .child(format!("{:020}-1-1{OFFLOADED_FORMAT_SUFFIX}", 1));
+        test.put_object(location.clone(), b"definitely not messages".to_vec())
+            .await;
+
+        assert!(matches!(
+            test.store.read_object(&location).await,
+            Err(StorageError::DataCorruption(_))
+        ));
+        assert!(test.store.get_messages(CLIENT, None).await.is_err());
+        // the messages are not lost
+        assert_eq!(test.cold_locations().await, vec![location]);
+    }
+
+    #[tokio::test]
+    async fn purging_client_data_removes_the_offloaded_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = PersistentStorage::init(dir.path().join("storage.sqlite"), 100)
+            .await
+            .unwrap();
+        let hot = storage.message_store();
+        let cold = Arc::new(InMemory::new());
+        let offloading_store = || {
+            OffloadingMessageStore::with_object_store(
+                hot.clone(),
+                cold.clone(),
+                Path::from("gateway"),
+                [1; 32],
+                Duration::from_secs(60),
+            )
+        };
+        let storage = storage.with_message_store(offloading_store());
+
+        let address = DestinationAddressBytes::from_bytes([42; 32]);
+        let client = address.as_base58_string();
+        let client_id = registered_client(&storage, address, 2).await;
+        let (messages, _) = hot.get_messages(&client, None).await.unwrap();
+        offloading_store()
+            .offload_client_messages(&client, messages)
+            .await
+            .unwrap();
+        storage.store_message(address, vec![1; 10]).await.unwrap();
+        assert_eq!(cold.list(None).count().await, 1);
+
+        assert_eq!(
+            storage
+                .purge_client_data(address, client_id, true)
+                .await
+                .unwrap(),
+            3
+        );
+        assert_eq!(cold.list(None).count().await, 0);
+        assert_deregistered(&storage, address, client_id).await;
+    }
+}
@@ -0,0 +1,658 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::error::StorageError;
+use crate::message_store::MessageStore;
+use crate::models::RawStoredMessage;
+use async_trait::async_trait;
+use rocksdb::{Direction, IteratorMode, MergeOperands, Options, WriteBatch, DB};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use time::OffsetDateTime;
+
+// messages themselves are kept under `m{id}` keys whilst the (empty) `c{client}/{id}` entries index them
+// by their recipient. the ids are big-endian encoded so that the order of the keys matches the order of the ids
+const MESSAGE_PREFIX: u8 = b'm';
+const CLIENT_INDEX_PREFIX: u8 = b'c';
+const CLIENT_INDEX_SEPARATOR: u8 = b'/';
+
+// the number of messages of each client is kept under `n{client}` and updated with the `CLIENT_COUNT_MERGE`
+// operator, so that the counts wouldn't have to be recomputed by going through the index
+const CLIENT_COUNT_PREFIX: u8 = b'n';
+const CLIENT_COUNT_MERGE: &str = "client_message_count";
+
+// present once the counts of all the clients have been computed,
+// i.e. it's missing in databases created before the counts had been introduced
+const CLIENT_COUNTS_MARKER: &[u8] = b"v";
+
+// (client_address_bs58, encrypted, stored_at, content, padded)
+type EncodedMessage = (String, bool, i64, Vec<u8>, bool);
+
+// messages stored before the introduction of padding are missing the trailing flag
+type LegacyEncodedMessage = (String, bool, i64, Vec<u8>);
+
+fn message_key(id: i64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(9);
+    key.push(MESSAGE_PREFIX);
+    key.extend_from_slice(&id.to_be_bytes());
+    key
+}
+
+fn client_index_prefix(client_address_bs58: &str) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(client_address_bs58.len() + 2);
+    prefix.push(CLIENT_INDEX_PREFIX);
+    prefix.extend_from_slice(client_address_bs58.as_bytes());
+    prefix.push(CLIENT_INDEX_SEPARATOR);
+    prefix
+}
+
+fn client_index_key(client_address_bs58: &str, id: i64) -> Vec<u8> {
+    let mut key = client_index_prefix(client_address_bs58);
+    key.extend_from_slice(&id.to_be_bytes());
+    key
+}
+
+fn client_count_key(client_address_bs58: &str) -> Vec<u8> {
+    let mut key = Vec::with_capacity(client_address_bs58.len() + 1);
+    key.push(CLIENT_COUNT_PREFIX);
+    key.extend_from_slice(client_address_bs58.as_bytes());
+    key
+}
+
+fn decode_count(value: &[u8]) -> i64 {
+    value.try_into().map(i64::from_be_bytes).unwrap_or_default()
+}
+
+// the operands are the (possibly negative) changes to the count
+fn merge_count(_key: &[u8], existing: Option<&[u8]>, operands: &MergeOperands) -> Option<Vec<u8>> {
+    let count = operands.iter().fold(
+        existing.map(decode_count).unwrap_or_default(),
+        |count, delta| count.saturating_add(decode_count(delta)),
+    );
+    Some(count.to_be_bytes().to_vec())
+}
+
+fn decode_id(key: &[u8]) -> Result<i64, StorageError> {
+    key.len()
+        .checked_sub(8)
+        .and_then(|start| key[start..].try_into().ok())
+        .map(i64::from_be_bytes)
+        .ok_or_else(|| StorageError::DataCorruption(format!("malformed message store key {key:?}")))
+}
+
+fn decode_message(id: i64, value: &[u8]) -> Result<(RawStoredMessage, i64), StorageError> {
+    let (client_address_bs58, encrypted, stored_at, content, padded): EncodedMessage =
+        bincode::deserialize(value)
+            .or_else(|_| {
+                bincode::deserialize::<LegacyEncodedMessage>(value).map(
+                    |(client_address_bs58, encrypted, stored_at, content)| {
+                        (client_address_bs58, encrypted, stored_at, content, false)
+                    },
+                )
+            })
+            .map_err(|err| {
+                StorageError::DataCorruption(format!("stored message {id} is malformed: {err}"))
+            })?;
+    Ok((
+        RawStoredMessage {
+            id,
+            client_address_bs58,
+            content,
+            encrypted,
+            padded,
+        },
+        stored_at,
+    ))
+}
+
+fn decode_index_client(key: &[u8]) -> Result<String, StorageError> {
+    key.len()
+        .checked_sub(9)
+        .filter(|&end| end >= 1)
+        .and_then(|end| String::from_utf8(key[1..end].to_vec()).ok())
+        .ok_or_else(|| StorageError::DataCorruption(format!("malformed message index key {key:?}")))
+}
+
+/// Messages removed within a single write, alongside the resulting changes to the per-client counts.
+#[derive(Default)]
+struct Removals {
+    batch: WriteBatch,
+    counts: HashMap<String, i64>,
+    removed: u64,
+}
+
+impl Removals {
+    fn remove(&mut self, client_address_bs58: &str, id: i64) {
+        self.batch.delete(message_key(id));
+        self.batch.delete(client_index_key(client_address_bs58, id));
+        *self
+            .counts
+            .entry(client_address_bs58.to_string())
+            .or_default() -= 1;
+        self.removed += 1;
+    }
+
+    // removes the oldest of the provided (ascending) ids of a single client so that at most `max` of them remain
+    fn trim_client(&mut self, client_address_bs58: &str, ids: &[i64], max: u64) {
+        let excess = (ids.len() as u64).saturating_sub(max);
+        for &id in ids.iter().take(excess as usize) {
+            self.remove(client_address_bs58, id)
+        }
+    }
+
+    fn write(mut self, db: &DB) -> Result<u64, StorageError> {
+        for (client_address_bs58, delta) in self.counts {
+            self.batch
+                .merge(client_count_key(&client_address_bs58), delta.to_be_bytes());
+        }
+        db.write(self.batch).map_err(backend_failure)?;
+        Ok(self.removed)
+    }
+}
+
+// goes through the whole index to compute the number of messages of every client
+fn compute_client_counts(db: &DB) -> Result<(), StorageError> {
+    let mut counts: HashMap<String, i64> = HashMap::new();
+    for entry in db.iterator(IteratorMode::From(
+        &[CLIENT_INDEX_PREFIX],
+        Direction::Forward,
+    )) {
+        let (key, _) = entry.map_err(backend_failure)?;
+        if key.first() != Some(&CLIENT_INDEX_PREFIX) {
+            break;
+        }
+        *counts.entry(decode_index_client(&key)?).or_default() += 1;
+    }
+
+    let mut batch = WriteBatch::default();
+    for (client_address_bs58, count) in counts {
+        batch.put(client_count_key(&client_address_bs58), count.to_be_bytes());
+    }
+    batch.put(CLIENT_COUNTS_MARKER, b"");
+    db.write(batch).map_err(backend_failure)
+}
+
+fn backend_failure(err: impl Display) -> StorageError {
+    StorageError::MessageStoreBackendFailure(err.to_string())
+}
+
+/// [MessageStore] keeping the messages in a dedicated RocksDB database,
+/// better suited than sqlite for gateways handling a lot of offline traffic.
+#[derive(Clone)]
+pub struct RocksDbMessageStore {
+    db: Arc<DB>,
+    next_id: Arc<AtomicI64>,
+
+    /// Serialises the removals, so that a message removed concurrently wouldn't decrease the count twice.
+    removal_lock: Arc<Mutex<()>>,
+
+    /// Maximum number of messages that can be obtained from the database per operation.
+    retrieval_limit: usize,
+}
+
+impl RocksDbMessageStore {
+    /// Opens (or creates) the RocksDB message store at the provided path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: path to the database directory.
+    /// * `retrieval_limit`: maximum number of stored client messages that can be retrieved at once.
+    pub fn open<P: AsRef<Path>>(path: P, retrieval_limit: i64) -> Result<Self, StorageError> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.set_merge_operator_associative(CLIENT_COUNT_MERGE, merge_count);
+        let db = DB::open(&options, path).map_err(backend_failure)?;
+
+        if db
+            .get(CLIENT_COUNTS_MARKER)
+            .map_err(backend_failure)?
+            .is_none()
+        {
+            compute_client_counts(&db)?;
+        }
+
+        // resume the ids after the highest one currently stored
+        let mut last_key = vec![MESSAGE_PREFIX];
+        last_key.extend_from_slice(&i64::MAX.to_be_bytes());
+        let last_id = match db
+            .iterator(IteratorMode::From(&last_key, Direction::Reverse))
+            .next()
+        {
+            Some(entry) => {
+                let (key, _) = entry.map_err(backend_failure)?;
+                if key.first() == Some(&MESSAGE_PREFIX) {
+                    decode_id(&key)?
+                } else {
+                    0
+                }
+            }
+            None => 0,
+        };
+
+        Ok(RocksDbMessageStore {
+            db: Arc::new(db),
+            next_id: Arc::new(AtomicI64::new(last_id + 1)),
+            removal_lock: Arc::new(Mutex::new(())),
+            retrieval_limit: if retrieval_limit > 0 {
+                retrieval_limit as usize
+            } else {
+                100
+            },
+        })
+    }
+
+    // rocksdb calls are blocking, so make sure not to stall the runtime while they're executed
+    async fn with_db<F, T>(&self, f: F) -> Result<T, StorageError>
+    where
+        F: FnOnce(&DB) -> Result<T, StorageError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let db = Arc::clone(&self.db);
+        tokio::task::spawn_blocking(move || f(&db))
+            .await
+            .map_err(backend_failure)?
+    }
+
+    // as above, but whilst holding the removal lock
+    async fn removing<F, T>(&self, f: F) -> Result<T, StorageError>
+    where
+        F: FnOnce(&DB) -> Result<T, StorageError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let removal_lock = Arc::clone(&self.removal_lock);
+        self.with_db(move |db| {
+            let _guard = removal_lock
+                .lock()
+                .map_err(|_| backend_failure("the removal lock got poisoned"))?;
+            f(db)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl MessageStore for RocksDbMessageStore {
+    async fn insert_message(
+        &self,
+        client_address_bs58: &str,
+        content: Vec<u8>,
+        encrypted: bool,
+        padded: bool,
+    ) -> Result<(), StorageError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let stored_at = OffsetDateTime::now_utc().unix_timestamp();
+        let client_index = client_index_key(client_address_bs58, id);
+        let client_count = client_count_key(client_address_bs58);
+        let value =
+            bincode::serialize(&(client_address_bs58, encrypted, stored_at, content, padded))
+                .map_err(backend_failure)?;
+
+        self.with_db(move |db| {
+            let mut batch = WriteBatch::default();
+            batch.put(message_key(id), value);
+            batch.put(client_index, b"");
+            batch.merge(client_count, 1i64.to_be_bytes());
+            db.write(batch).map_err(backend_failure)
+        })
+        .await
+    }
+
+    async fn get_messages(
+        &self,
+        client_address_bs58: &str,
+        start_after: Option<i64>,
+    ) -> Result<(Vec<RawStoredMessage>, Option<i64>), StorageError> {
+        let prefix = client_index_prefix(client_address_bs58);
+        let start = client_index_key(client_address_bs58, start_after.unwrap_or(0) + 1);
+        let limit = self.retrieval_limit;
+
+        self.with_db(move |db| {
+            // get 1 additional message to check whether there will be more to grab next time
+            let mut messages = Vec::new();
+            for entry in db.iterator(IteratorMode::From(&start, Direction::Forward)) {
+                let (key, _) = entry.map_err(backend_failure)?;
+                if !key.starts_with(&prefix) || messages.len() > limit {
+                    break;
+                }
+                let id = decode_id(&key)?;
+                let Some(value) = db.get(message_key(id)).map_err(backend_failure)? else {
+                    return Err(StorageError::DataCorruption(format!(
+                        "message {id} is indexed, but it's not present in the store"
+                    )));
+                };
+                messages.push(decode_message(id, &value)?.0);
+            }
+
+            if messages.len() > limit {
+                messages.truncate(limit);
+                let start_after = messages.last().map(|message| message.id);
+                Ok((messages, start_after))
+            } else {
+                Ok((messages, None))
+            }
+        })
+        .await
+    }
+
+    async fn remove_message(&self, id: i64) -> Result<(), StorageError> {
+        self.removing(move |db| {
+            let Some(value) = db.get(message_key(id)).map_err(backend_failure)? else {
+                return Ok(());
+            };
+            let (message, _) = decode_message(id, &value)?;
+
+            let mut removals = Removals::default();
+            removals.remove(&message.client_address_bs58, id);
+            removals.write(db).map(|_| ())
+        })
+        .await
+    }
+
+    async fn remove_all_messages(&self, client_address_bs58: &str) -> Result<u64, StorageError> {
+        let client_address_bs58 = client_address_bs58.to_string();
+        let prefix = client_index_prefix(&client_address_bs58);
+
+        self.removing(move |db| {
+            let mut removals = Removals::default();
+            for entry in db.iterator(IteratorMode::From(&prefix, Direction::Forward)) {
+                let (key, _) = entry.map_err(backend_failure)?;
+                if !key.starts_with(&prefix) {
+                    break;
+                }
+                removals.remove(&client_address_bs58, decode_id(&key)?);
+            }
+            removals.write(db)
+        })
+        .await
+    }
+
+    async fn count_messages(&self, client_address_bs58: &str) -> Result<u64, StorageError> {
+        let key = client_count_key(client_address_bs58);
+
+        self.with_db(move |db| {
+            let count = db
+                .get(key)
+                .map_err(backend_failure)?
+                .map(|value| decode_count(&value))
+                .unwrap_or_default();
+            Ok(count.max(0) as u64)
+        })
+        .await
+    }
+
+    async fn remove_messages_stored_before(
+        &self,
+        cutoff: OffsetDateTime,
+    ) -> Result<u64, StorageError> {
+        let cutoff = cutoff.unix_timestamp();
+
+        self.removing(move |db| {
+            let mut removals = Removals::default();
+            // the ids are increasing, so all the expired messages come before the first one that isn't
+            for entry in db.iterator(IteratorMode::From(&[MESSAGE_PREFIX], Direction::Forward)) {
+                let (key, value) = entry.map_err(backend_failure)?;
+                if key.first() != Some(&MESSAGE_PREFIX) {
+                    break;
+                }
+                let id = decode_id(&key)?;
+                let (message, stored_at) = decode_message(id, &value)?;
+                if stored_at >= cutoff {
+                    break;
+                }
+                removals.remove(&message.client_address_bs58, id);
+            }
+            removals.write(db)
+        })
+        .await
+    }
+
+    async fn trim_client_messages(&self, max_per_client: u64) -> Result<u64, StorageError> {
+        self.removing(move |db| {
+            let mut removals = Removals::default();
+
+            // the index keys are ordered by the client and then by the message id,
+            // so the messages of each client are iterated through together, oldest first
+            let mut client = String::new();
+            let mut ids = Vec::new();
+            for entry in db.iterator(IteratorMode::From(
+                &[CLIENT_INDEX_PREFIX],
+                Direction::Forward,
+            )) {
+                let (key, _) = entry.map_err(backend_failure)?;
+                if key.first() != Some(&CLIENT_INDEX_PREFIX) {
+                    break;
+                }
+                let id = decode_id(&key)?;
+                let key_client = decode_index_client(&key)?;
+                if key_client != client {
+                    removals.trim_client(&client, &ids, max_per_client);
+                    client = key_client;
+                    ids.clear();
+                }
+                ids.push(id);
+            }
+            removals.trim_client(&client, &ids, max_per_client);
+
+            removals.write(db)
+        })
+        .await
+    }
+
+    async fn trim_to_content_size(&self, max_total_content_size: u64) -> Result<u64, StorageError> {
+        let mut last_key = vec![MESSAGE_PREFIX];
+        last_key.extend_from_slice(&i64::MAX.to_be_bytes());
+
+        self.removing(move |db| {
+            let mut removals = Removals::default();
+            let mut total_size = 0u64;
+
+            // go from the newest message and remove everything past the point the limit got exceeded
+            for entry in db.iterator(IteratorMode::From(&last_key, Direction::Reverse)) {
+                let (key, value) = entry.map_err(backend_failure)?;
+                if key.first() != Some(&MESSAGE_PREFIX) {
+                    break;
+                }
+                let id = decode_id(&key)?;
+                let (message, _) = decode_message(id, &value)?;
+                total_size = total_size.saturating_add(message.content.len() as u64);
+                if total_size > max_total_content_size {
+                    removals.remove(&message.client_address_bs58, id);
+                }
+            }
+            removals.write(db)
+        })
+        .await
+    }
+
+    async fn get_messages_stored_before(
+        &self,
+        cutoff: OffsetDateTime,
+        limit: u32,
+    ) -> Result<Vec<RawStoredMessage>, StorageError> {
+        let cutoff = cutoff.unix_timestamp();
+
+        self.with_db(move |db| {
+            let mut messages = Vec::new();
+            // the ids are increasing, so the messages are iterated through in the order they were stored in
+            for entry in db.iterator(IteratorMode::From(&[MESSAGE_PREFIX], Direction::Forward)) {
+                let (key, value) = entry.map_err(backend_failure)?;
+                if key.first() != Some(&MESSAGE_PREFIX) || messages.len() >= limit as usize {
+                    break;
+                }
+                let (message, stored_at) = decode_message(decode_id(&key)?, &value)?;
+                if stored_at >= cutoff {
+                    break;
+                }
+                messages.push(message);
+            }
+            Ok(messages)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "alice";
+    const BOB: &str = "bob";
+
+    async fn insert(store: &RocksDbMessageStore, client_address_bs58: &str, content: &[u8]) {
+        store
+            .insert_message(client_address_bs58, content.to_vec(), false, false)
+            .await
+            .unwrap()
+    }
+
+    fn contents(messages: &[RawStoredMessage]) -> Vec<&[u8]> {
+        messages.iter().map(|m| m.content.as_slice()).collect()
+    }
+
+    #[tokio::test]
+    async fn messages_are_retrieved_in_order_and_in_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RocksDbMessageStore::open(dir.path(), 2).unwrap();
+        for content in [b"1", b"2", b"3"] {
+            insert(&store, ALICE, content).await;
+        }
+        insert(&store, BOB, b"other").await;
+
+        let (first, start_after) = store.get_messages(ALICE, None).await.unwrap();
+        assert_eq!(contents(&first), vec![b"1", b"2"]);
+        assert!(start_after.is_some());
+
+        let (second, start_after) = store.get_messages(ALICE, start_after).await.unwrap();
+        assert_eq!(contents(&second), vec![b"3"]);
+        assert!(start_after.is_none());
+        assert!(second.iter().all(|m| m.client_address_bs58 == ALICE));
+    }
+
+    #[tokio::test]
+    async fn counts_follow_the_insertions_and_removals() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RocksDbMessageStore::open(dir.path(), 100).unwrap();
+        for content in [b"1", b"2", b"3", b"4"] {
+            insert(&store, ALICE, content).await;
+        }
+        insert(&store, BOB, b"other").await;
+        assert_eq!(store.count_messages(ALICE).await.unwrap(), 4);
+        assert_eq!(store.count_messages(BOB).await.unwrap(), 1);
+
+        let (messages, _) = store.get_messages(ALICE, None).await.unwrap();
+        store.remove_message(messages[0].id).await.unwrap();
+        // removing it again doesn't change anything
+        store.remove_message(messages[0].id).await.unwrap();
+        assert_eq!(store.count_messages(ALICE).await.unwrap(), 3);
+
+        assert_eq!(store.trim_client_messages(2).await.unwrap(), 1);
+        assert_eq!(store.count_messages(ALICE).await.unwrap(), 2);
+        let (messages, _) = store.get_messages(ALICE, None).await.unwrap();
+        assert_eq!(contents(&messages), vec![b"3", b"4"]);
+
+        assert_eq!(store.remove_all_messages(ALICE).await.unwrap(), 2);
+        assert_eq!(store.count_messages(ALICE).await.unwrap(), 0);
+        assert_eq!(store.count_messages(BOB).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn oldest_messages_are_removed_above_the_total_content_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RocksDbMessageStore::open(dir.path(), 100).unwrap();
+        insert(&store, ALICE, b"old").await;
+        insert(&store, BOB, b"newer").await;
+        insert(&store, ALICE, b"newest").await;
+
+        assert_eq!(store.trim_to_content_size(11).await.unwrap(), 1);
+        let (messages, _) = store.get_messages(ALICE, None).await.unwrap();
+        assert_eq!(contents(&messages), vec![b"newest"]);
+        assert_eq!(store.count_messages(ALICE).await.unwrap(), 1);
+        assert_eq!(store.count_messages(BOB).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn messages_are_removed_by_their_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RocksDbMessageStore::open(dir.path(), 100).unwrap();
+        insert(&store, ALICE, b"1").await;
+        insert(&store, BOB, b"2").await;
+
+        let past = OffsetDateTime::now_utc() - time::Duration::hours(1);
+        assert!(store
+            .get_messages_stored_before(past, 10)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(store.remove_messages_stored_before(past).await.unwrap(), 0);
+
+        let future = OffsetDateTime::now_utc() + time::Duration::hours(1);
+        assert_eq!(
+            contents(&store.get_messages_stored_before(future, 1).await.unwrap()),
+            vec![b"1"]
+        );
+        assert_eq!(
+            store.remove_messages_stored_before(future).await.unwrap(),
+            2
+        );
+        assert_eq!(store.count_messages(ALICE).await.unwrap(), 0);
+        assert_eq!(store.count_messages(BOB).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn ids_and_counts_persist_across_reopening() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RocksDbMessageStore::open(dir.path(), 100).unwrap();
+        insert(&store, ALICE, b"1").await;
+        insert(&store, ALICE, b"2").await;
+        let (before, _) = store.get_messages(ALICE, None).await.unwrap();
+
+        // pretend the database has been created before the counts got introduced
+        store.db.delete(CLIENT_COUNTS_MARKER).unwrap();
+        store.db.delete(client_count_key(ALICE)).unwrap();
+        drop(store);
+
+        let store = RocksDbMessageStore::open(dir.path(), 100).unwrap();
+        assert_eq!(store.count_messages(ALICE).await.unwrap(), 2);
+
+        insert(&store, ALICE, b"3").await;
+        let (after, _) = store.get_messages(ALICE, None).await.unwrap();
+        assert_eq!(contents(&after), vec![b"1", b"2", b"3"]);
+        assert!(after[2].id > before[1].id);
+        assert_eq!(store.count_messages(ALICE).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn messages_stored_before_the_padding_are_decoded() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RocksDbMessageStore::open(dir.path(), 100).unwrap();
+        store
+            .insert_message(ALICE, b"padded".to_vec(), true, true)
+            .await
+            .unwrap();
+
+        // put the message directly, as it'd have been stored before the padding flag got introduced
+        let legacy: LegacyEncodedMessage = (ALICE.to_string(), true, 0, b"legacy".to_vec());
+        let mut batch = WriteBatch::default();
+        batch.put(message_key(100), bincode::serialize(&legacy).unwrap());
+        batch.put(client_index_key(ALICE, 100), b"");
+        store.db.write(batch).unwrap();
+
+        let (messages, _) = store.get_messages(ALICE, None).await.unwrap();
+        assert_eq!(contents(&messages), vec![b"padded".as_slice(), b"legacy"]);
+        assert!(messages[0].padded);
+        assert!(!messages[1].padded);
+        assert!(messages.iter().all(|m| m.encrypted));
+    }
+
+    #[test]
+    fn malformed_messages_are_rejected() {
+        assert!(matches!(
+            decode_message(1, b"definitely not a message"),
+            Err(StorageError::DataCorruption(_))
+        ));
+        assert!(decode_id(&[MESSAGE_PREFIX, 1, 2]).is_err());
+    }
+}
@@ -55,6 +55,15 @@ pub struct StoredMessage {
     pub content: Vec<u8>,
 }
 
+/// Message as it's persisted in the database, i.e. with the content possibly being encrypted.
+pub struct RawStoredMessage {
+    pub id: i64,
+    pub client_address_bs58: String,
+    pub content: Vec<u8>,
+    pub encrypted: bool,
+    pub padded: bool,
+}
+
 #[derive(Debug, Clone, FromRow)]
 pub struct PersistedBandwidth {
     #[allow(dead_code)]
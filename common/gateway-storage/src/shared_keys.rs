@@ -86,14 +86,18 @@ impl SharedKeysManager {
         .await
     }
 
+    /// Retrieves base58-encoded addresses of all clients that have registered with this gateway.
+    pub(crate) async fn client_addresses(&self) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!("SELECT client_address_bs58 FROM shared_keys")
+            .fetch_all(&self.connection_pool)
+            .await
+    }
+
     /// Removes from the database shared keys derived with the particular client.
     ///
     /// # Arguments
     ///
     /// * `client_address_bs58`: base58-encoded address of the client
-    // currently there is no code flow that causes removal (not overwriting)
-    // of the stored keys. However, retain the function for consistency and completion sake
-    #[allow(dead_code)]
     pub(crate) async fn remove_shared_keys(
         &self,
         client_address_bs58: &str,
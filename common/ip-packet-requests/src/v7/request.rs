@@ -468,6 +468,7 @@ mod tests {
             &nym_sphinx::receiver::ReconstructedMessage {
                 message: serialized,
                 sender_tag: None,
+                fragment_set_id: None,
             },
         )
         .unwrap();
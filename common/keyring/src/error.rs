@@ -0,0 +1,29 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum KeyringError {
+    #[error("the OS keyring failed to process the request for '{name}': {source}")]
+    OsKeyringFailure {
+        name: String,
+        #[source]
+        source: keyring::Error,
+    },
+
+    #[error("failed to access the secret file at {path}: {source}")]
+    SecretFileFailure {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("the secret file at {path} does not contain valid utf8")]
+    MalformedSecretFile { path: PathBuf },
+
+    #[error("'{name}' is not a valid secret name. only alphanumeric characters, '-', '_' and '.' are allowed")]
+    InvalidSecretName { name: String },
+}
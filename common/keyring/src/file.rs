@@ -0,0 +1,127 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{KeyringError, Secret, SecretStore};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+/// Fallback for platforms without a usable keyring, where each secret is stored in a separate
+/// file that is only readable by its owner.
+#[derive(Debug, Clone)]
+pub struct FileSecrets {
+    directory: PathBuf,
+}
+
+impl FileSecrets {
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Self {
+        FileSecrets {
+            directory: directory.into(),
+        }
+    }
+
+    fn secret_path(&self, name: &str) -> Result<PathBuf, KeyringError> {
+        let valid = !name.is_empty()
+            && !name.starts_with('.')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.');
+        if !valid {
+            return Err(KeyringError::InvalidSecretName {
+                name: name.to_string(),
+            });
+        }
+        Ok(self.directory.join(name))
+    }
+}
+
+fn file_failure(path: &Path) -> impl FnOnce(io::Error) -> KeyringError + '_ {
+    |source| KeyringError::SecretFileFailure {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+impl SecretStore for FileSecrets {
+    fn store_secret(&self, name: &str, secret: &str) -> Result<(), KeyringError> {
+        let path = self.secret_path(name)?;
+        fs::create_dir_all(&self.directory).map_err(file_failure(&self.directory))?;
+
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options.open(&path).map_err(file_failure(&path))?;
+        file.write_all(secret.as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(file_failure(&path))
+    }
+
+    fn load_secret(&self, name: &str) -> Result<Option<Secret>, KeyringError> {
+        let path = self.secret_path(name)?;
+        match fs::read(&path) {
+            Ok(bytes) => String::from_utf8(bytes)
+                .map(|secret| Some(Zeroizing::new(secret)))
+                .map_err(|err| {
+                    // make sure the raw bytes don't linger around
+                    let _ = Zeroizing::new(err.into_bytes());
+                    KeyringError::MalformedSecretFile { path }
+                }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(KeyringError::SecretFileFailure { path, source }),
+        }
+    }
+
+    fn remove_secret(&self, name: &str) -> Result<(), KeyringError> {
+        let path = self.secret_path(name)?;
+        match fs::remove_file(&path) {
+            Ok(_) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(source) => Err(KeyringError::SecretFileFailure { path, source }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrets_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileSecrets::new(dir.path().join("secrets"));
+
+        assert!(store.load_secret("password").unwrap().is_none());
+
+        store.store_secret("password", "hunter2").unwrap();
+        assert_eq!(
+            store.load_secret("password").unwrap().unwrap().as_str(),
+            "hunter2"
+        );
+
+        store.store_secret("password", "correct horse").unwrap();
+        assert_eq!(
+            store.load_secret("password").unwrap().unwrap().as_str(),
+            "correct horse"
+        );
+
+        store.remove_secret("password").unwrap();
+        assert!(store.load_secret("password").unwrap().is_none());
+
+        // removing non-existent secret is fine
+        store.remove_secret("password").unwrap();
+    }
+
+    #[test]
+    fn secret_names_cant_escape_the_directory() {
+        let store = FileSecrets::new("/tmp/foo");
+        assert!(store.store_secret("../password", "foo").is_err());
+        assert!(store.store_secret(".hidden", "foo").is_err());
+        assert!(store.store_secret("", "foo").is_err());
+    }
+}
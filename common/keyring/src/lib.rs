@@ -0,0 +1,63 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Storage of small secrets, such as passwords and passphrases, in the keyring provided
+//! by the operating system (macOS Keychain, Windows Credential Manager or the Secret Service
+//! via libsecret on linux) with a fallback to plain files if no keyring is available.
+
+use std::path::PathBuf;
+use zeroize::Zeroizing;
+
+pub use error::KeyringError;
+pub use file::FileSecrets;
+pub use os::OsKeyring;
+
+mod error;
+mod file;
+mod os;
+
+pub type Secret = Zeroizing<String>;
+
+pub trait SecretStore: Send + Sync {
+    /// Stores the secret under the provided name, overwriting any existing value.
+    fn store_secret(&self, name: &str, secret: &str) -> Result<(), KeyringError>;
+
+    /// Attempts to retrieve the secret stored under the provided name.
+    fn load_secret(&self, name: &str) -> Result<Option<Secret>, KeyringError>;
+
+    /// Removes the secret stored under the provided name. It is not an error if it didn't exist.
+    fn remove_secret(&self, name: &str) -> Result<(), KeyringError>;
+}
+
+impl<T: SecretStore + ?Sized> SecretStore for Box<T> {
+    fn store_secret(&self, name: &str, secret: &str) -> Result<(), KeyringError> {
+        (**self).store_secret(name, secret)
+    }
+
+    fn load_secret(&self, name: &str) -> Result<Option<Secret>, KeyringError> {
+        (**self).load_secret(name)
+    }
+
+    fn remove_secret(&self, name: &str) -> Result<(), KeyringError> {
+        (**self).remove_secret(name)
+    }
+}
+
+/// Opens the OS keyring for the provided service if it's available on this machine,
+/// otherwise falls back to storing secrets as individual files inside `fallback_directory`.
+pub fn open_secret_store<S: Into<String>, P: Into<PathBuf>>(
+    service: S,
+    fallback_directory: P,
+) -> Box<dyn SecretStore> {
+    let keyring = OsKeyring::new(service);
+    if keyring.is_available() {
+        Box::new(keyring)
+    } else {
+        let fallback_directory = fallback_directory.into();
+        log::warn!(
+            "the OS keyring is not available - secrets are going to be stored in {}",
+            fallback_directory.display()
+        );
+        Box::new(FileSecrets::new(fallback_directory))
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{KeyringError, Secret, SecretStore};
+use keyring::Entry;
+use zeroize::Zeroizing;
+
+// name of the entry used for checking whether the keyring is usable at all
+const AVAILABILITY_PROBE: &str = "nym-keyring-availability-probe";
+
+/// Secrets stored in the native keyring of the operating system.
+#[derive(Debug, Clone)]
+pub struct OsKeyring {
+    service: String,
+}
+
+impl OsKeyring {
+    pub fn new<S: Into<String>>(service: S) -> Self {
+        OsKeyring {
+            service: service.into(),
+        }
+    }
+
+    /// Checks whether the keyring could be accessed, i.e. the platform has a keyring and,
+    /// on linux, that the Secret Service daemon is running.
+    pub fn is_available(&self) -> bool {
+        match self.entry(AVAILABILITY_PROBE) {
+            Ok(entry) => match entry.get_password() {
+                Ok(_) | Err(keyring::Error::NoEntry) => true,
+                Err(err) => {
+                    log::debug!("the OS keyring is not available: {err}");
+                    false
+                }
+            },
+            Err(_) => false,
+        }
+    }
+
+    fn entry(&self, name: &str) -> Result<Entry, KeyringError> {
+        Entry::new(&self.service, name).map_err(|source| KeyringError::OsKeyringFailure {
+            name: name.to_string(),
+            source,
+        })
+    }
+}
+
+impl SecretStore for OsKeyring {
+    fn store_secret(&self, name: &str, secret: &str) -> Result<(), KeyringError> {
+        self.entry(name)?
+            .set_password(secret)
+            .map_err(|source| KeyringError::OsKeyringFailure {
+                name: name.to_string(),
+                source,
+            })
+    }
+
+    fn load_secret(&self, name: &str) -> Result<Option<Secret>, KeyringError> {
+        match self.entry(name)?.get_password() {
+            Ok(secret) => Ok(Some(Zeroizing::new(secret))),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(source) => Err(KeyringError::OsKeyringFailure {
+                name: name.to_string(),
+                source,
+            }),
+        }
+    }
+
+    fn remove_secret(&self, name: &str) -> Result<(), KeyringError> {
+        match self.entry(name)?.delete_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(source) => Err(KeyringError::OsKeyringFailure {
+                name: name.to_string(),
+                source,
+            }),
+        }
+    }
+}
@@ -39,6 +39,9 @@ pub struct NodeTester<R> {
     /// Note that it does not include gateway hops.
     num_mix_hops: u8,
 
+    /// Type of the packets (sphinx or outfox) used for wrapping the test messages.
+    packet_type: PacketType,
+
     // while acks are going to be ignored they still need to be constructed
     // so that the gateway would be able to correctly process and forward the message
     ack_key: Arc<AckKey>,
@@ -65,6 +68,7 @@ where
             average_packet_delay,
             average_ack_delay,
             num_mix_hops: DEFAULT_NUM_MIX_HOPS,
+            packet_type: PacketType::Mix,
             ack_key,
         }
     }
@@ -76,6 +80,20 @@ where
         self
     }
 
+    /// Allows setting non-default type of the constructed test packets.
+    /// Note that outfox packets always use their dedicated packet size.
+    pub fn with_packet_type(mut self, packet_type: PacketType) -> Self {
+        if packet_type == PacketType::Outfox {
+            self.packet_size = PacketSize::OutfoxRegularPacket;
+        }
+        self.packet_type = packet_type;
+        self
+    }
+
+    pub fn packet_type(&self) -> PacketType {
+        self.packet_type
+    }
+
     pub fn testable_mix_topology(&self, node: &mix::Node) -> NymTopology {
         let mut topology = self.base_topology.clone();
         topology.set_mixes_in_layer(node.layer as u8, vec![node.clone()]);
@@ -251,13 +269,14 @@ where
 
         // TODO: can we avoid this arc clone?
         let ack_key = Arc::clone(&self.ack_key);
+        let packet_type = self.packet_type;
         Ok(self.prepare_chunk_for_sending(
             fragment,
             topology,
             &ack_key,
             &address,
             &address,
-            PacketType::Mix,
+            packet_type,
             None,
         )?)
     }
@@ -0,0 +1,134 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+// Compares scheduling of synthetic mixnode load (100k packets per second with
+// exponentially distributed delays averaging 50ms) using a binary heap, tokio's `DelayQueue`
+// and the hierarchical timing wheel.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use nym_nonexhaustive_delayqueue::timing_wheel::TimingWheel;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, Exp};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::future::poll_fn;
+use std::task::Poll;
+use std::time::Duration;
+
+const PACKETS_PER_SECOND: u64 = 100_000;
+const AVERAGE_DELAY_MS: f64 = 50.0;
+
+// (arrival tick, deadline tick) for every packet received during one second of load
+fn synthetic_load() -> Vec<(u64, u64)> {
+    let mut rng = StdRng::seed_from_u64(42);
+    let delay = Exp::new(1.0 / AVERAGE_DELAY_MS).unwrap();
+
+    (0..PACKETS_PER_SECOND)
+        .map(|i| {
+            let arrival = i * 1000 / PACKETS_PER_SECOND;
+            let deadline = arrival + 1 + delay.sample(&mut rng) as u64;
+            (arrival, deadline)
+        })
+        .collect()
+}
+
+fn binary_heap(load: &[(u64, u64)]) -> usize {
+    let mut heap = BinaryHeap::new();
+    let mut released = 0;
+    let mut packets = load.iter().peekable();
+
+    let mut now = 0;
+    while packets.peek().is_some() || !heap.is_empty() {
+        while let Some((_, deadline)) = packets.next_if(|(arrival, _)| *arrival <= now) {
+            heap.push(Reverse(*deadline));
+        }
+        while heap
+            .peek()
+            .is_some_and(|Reverse(deadline)| *deadline <= now)
+        {
+            heap.pop();
+            released += 1;
+        }
+        now += 1;
+    }
+    released
+}
+
+fn timing_wheel(load: &[(u64, u64)]) -> usize {
+    let mut wheel = TimingWheel::new();
+    let mut released = 0;
+    let mut packets = load.iter().peekable();
+
+    let mut now = 0;
+    while packets.peek().is_some() || !wheel.is_empty() {
+        while let Some((_, deadline)) = packets.next_if(|(arrival, _)| *arrival <= now) {
+            wheel.insert(*deadline, *deadline);
+        }
+        released += wheel.advance(now).len();
+        now += 1;
+    }
+    released
+}
+
+// tokio's `DelayQueue` requires an actual runtime with its timer, so we run it on paused time
+async fn tokio_delay_queue(load: &[(u64, u64)]) -> usize {
+    let mut queue = tokio_util::time::DelayQueue::new();
+    let mut released = 0;
+    let mut packets = load.iter().peekable();
+
+    let start = tokio::time::Instant::now();
+    let mut now = 0;
+    while packets.peek().is_some() || !queue.is_empty() {
+        while let Some((_, deadline)) = packets.next_if(|(arrival, _)| *arrival <= now) {
+            queue.insert_at(*deadline, start + Duration::from_millis(*deadline));
+        }
+        // drain everything that has already expired without waiting for anything else
+        while poll_fn(|cx| Poll::Ready(matches!(queue.poll_expired(cx), Poll::Ready(Some(_)))))
+            .await
+        {
+            released += 1;
+        }
+        tokio::time::advance(Duration::from_millis(1)).await;
+        now += 1;
+    }
+    released
+}
+
+fn bench_delay_queues(c: &mut Criterion) {
+    let load = synthetic_load();
+
+    let mut group = c.benchmark_group("delay_queue_100k_pps");
+    group.throughput(Throughput::Elements(load.len() as u64));
+    group.sample_size(20);
+
+    group.bench_function("binary_heap", |b| {
+        b.iter(|| assert_eq!(binary_heap(&load), load.len()))
+    });
+
+    group.bench_function("timing_wheel", |b| {
+        b.iter(|| assert_eq!(timing_wheel(&load), load.len()))
+    });
+
+    group.bench_function("tokio_delay_queue", |b| {
+        b.iter_batched(
+            || {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_time()
+                    .start_paused(true)
+                    .build()
+                    .unwrap()
+            },
+            |runtime| {
+                let released = runtime.block_on(tokio_delay_queue(&load));
+                assert_eq!(released, load.len())
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_delay_queues);
+criterion_main!(benches);
@@ -6,6 +6,11 @@ use std::task::{Context, Poll, Waker};
 use std::time::Duration;
 use tokio_stream::Stream;
 
+pub mod timing_wheel;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use timing_wheel::TimingWheelQueue;
+
 // this is a copy of tokio-util delay_queue with `Sleep` and `Instant` being replaced with
 // `wasm_timer` equivalents
 
@@ -0,0 +1,420 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hierarchical timing wheel optimised for high-throughput scheduling of items with short,
+//! mostly uniform, delays, such as sphinx packets being delayed by mixnodes.
+//!
+//! Time is divided into ticks of `TICK_RESOLUTION`. The wheel consists of `LEVELS` levels,
+//! each containing `SLOTS_PER_LEVEL` slots, where every slot at level `n` spans
+//! `SLOTS_PER_LEVEL^n` ticks. Items are placed at the lowest level able to distinguish their
+//! deadline from the current time and get cascaded down as the time advances.
+//! Insertion is O(1) and all items that are due are returned together in a single batch.
+
+use std::time::Duration;
+
+const SLOT_BITS: u32 = 6;
+const SLOTS_PER_LEVEL: usize = 1 << SLOT_BITS;
+const SLOT_MASK: u64 = (SLOTS_PER_LEVEL - 1) as u64;
+const LEVELS: usize = 6;
+
+/// The furthest, in ticks, an item can be scheduled into the future.
+/// Anything beyond that is going to be clamped to this value (it's over 2 years at 1ms resolution).
+pub const MAX_TICKS: u64 = (1 << (SLOT_BITS * LEVELS as u32)) - 1;
+
+/// Duration represented by a single tick of the wheel.
+pub const TICK_RESOLUTION: Duration = Duration::from_millis(1);
+
+struct Entry<T> {
+    deadline: u64,
+    value: T,
+}
+
+struct Level<T> {
+    level: usize,
+    // bitmask of slots containing at least a single entry
+    occupied: u64,
+    slots: Vec<Vec<Entry<T>>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Expiration {
+    level: usize,
+    slot: usize,
+    deadline: u64,
+}
+
+const fn slot_range(level: usize) -> u64 {
+    1 << (SLOT_BITS * level as u32)
+}
+
+const fn level_range(level: usize) -> u64 {
+    1 << (SLOT_BITS * (level as u32 + 1))
+}
+
+fn slot_for(deadline: u64, level: usize) -> usize {
+    ((deadline >> (SLOT_BITS * level as u32)) & SLOT_MASK) as usize
+}
+
+fn level_for(elapsed: u64, deadline: u64) -> usize {
+    // find the most significant bit that differs between the current time and the deadline
+    // (ignoring the ones distinguishable at the lowest level)
+    let masked = (elapsed ^ deadline) | SLOT_MASK;
+    let significant = 63 - masked.leading_zeros() as usize;
+    (significant / SLOT_BITS as usize).min(LEVELS - 1)
+}
+
+impl<T> Level<T> {
+    fn new(level: usize) -> Self {
+        Level {
+            level,
+            occupied: 0,
+            slots: (0..SLOTS_PER_LEVEL).map(|_| Vec::new()).collect(),
+        }
+    }
+
+    fn push(&mut self, entry: Entry<T>) {
+        let slot = slot_for(entry.deadline, self.level);
+        self.slots[slot].push(entry);
+        self.occupied |= 1 << slot;
+    }
+
+    fn take_slot(&mut self, slot: usize) -> Vec<Entry<T>> {
+        self.occupied &= !(1 << slot);
+        std::mem::take(&mut self.slots[slot])
+    }
+
+    fn next_expiration(&self, now: u64) -> Option<Expiration> {
+        if self.occupied == 0 {
+            return None;
+        }
+
+        // look for the first occupied slot, starting from the one corresponding to `now`
+        let now_slot = slot_for(now, self.level);
+        let rotated = self.occupied.rotate_right(now_slot as u32);
+        let slot = (rotated.trailing_zeros() as usize + now_slot) % SLOTS_PER_LEVEL;
+
+        let level_start = now & !(level_range(self.level) - 1);
+        let mut deadline = level_start + slot as u64 * slot_range(self.level);
+        if deadline < now {
+            // the slot is "behind" the current one, meaning it's actually in the next rotation
+            deadline += level_range(self.level);
+        }
+
+        Some(Expiration {
+            level: self.level,
+            slot,
+            deadline,
+        })
+    }
+}
+
+/// Hierarchical timing wheel operating on abstract ticks.
+/// It does not perform any timekeeping on its own, instead it's up to the caller
+/// to advance it to the current tick.
+pub struct TimingWheel<T> {
+    elapsed: u64,
+    levels: Vec<Level<T>>,
+    // items that were inserted with deadlines that have already passed
+    ready: Vec<T>,
+    len: usize,
+}
+
+impl<T> TimingWheel<T> {
+    pub fn new() -> Self {
+        TimingWheel {
+            elapsed: 0,
+            levels: (0..LEVELS).map(Level::new).collect(),
+            ready: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// The tick the wheel has been advanced to.
+    pub fn elapsed(&self) -> u64 {
+        self.elapsed
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Schedule the value to get expired at the provided tick.
+    pub fn insert(&mut self, value: T, deadline: u64) {
+        self.len += 1;
+        if deadline <= self.elapsed {
+            self.ready.push(value);
+            return;
+        }
+
+        let deadline = deadline.min(self.elapsed + MAX_TICKS);
+        self.place(Entry { deadline, value })
+    }
+
+    fn place(&mut self, entry: Entry<T>) {
+        let level = level_for(self.elapsed, entry.deadline);
+        self.levels[level].push(entry)
+    }
+
+    fn next_expiration(&self) -> Option<Expiration> {
+        // lower levels always expire before the higher ones
+        self.levels
+            .iter()
+            .find_map(|level| level.next_expiration(self.elapsed))
+    }
+
+    /// Returns the tick at which the wheel should next be advanced, if it contains any items.
+    /// Note that this might correspond to an internal cascade rather than an actual item expiry.
+    pub fn next_deadline(&self) -> Option<u64> {
+        if !self.ready.is_empty() {
+            return Some(self.elapsed);
+        }
+        self.next_expiration().map(|expiration| expiration.deadline)
+    }
+
+    /// Advances the wheel to the provided tick and returns all items whose deadlines have passed.
+    pub fn advance(&mut self, now: u64) -> Vec<T> {
+        let mut expired = std::mem::take(&mut self.ready);
+
+        while let Some(expiration) = self.next_expiration() {
+            if expiration.deadline > now {
+                break;
+            }
+
+            self.elapsed = expiration.deadline;
+            for entry in self.levels[expiration.level].take_slot(expiration.slot) {
+                if entry.deadline <= now {
+                    expired.push(entry.value)
+                } else {
+                    // cascade it down to a more precise level
+                    self.place(entry)
+                }
+            }
+        }
+
+        self.elapsed = self.elapsed.max(now);
+        self.len -= expired.len();
+        expired
+    }
+}
+
+impl<T> Default for TimingWheel<T> {
+    fn default() -> Self {
+        TimingWheel::new()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use queue::TimingWheelQueue;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod queue {
+    use super::{TimingWheel, TICK_RESOLUTION};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll, Waker};
+    use std::time::Duration;
+    use tokio::time::{sleep_until, Instant, Sleep};
+    use tokio_stream::Stream;
+
+    /// Delay queue backed by a [`TimingWheel`] that yields all expired items in batches.
+    /// Similarly to `NonExhaustiveDelayQueue`, its `Stream` implementation never returns a `None`.
+    pub struct TimingWheelQueue<T> {
+        wheel: TimingWheel<T>,
+        start: Instant,
+        timer: Pin<Box<Sleep>>,
+        waker: Option<Waker>,
+    }
+
+    impl<T> TimingWheelQueue<T> {
+        pub fn new() -> Self {
+            let start = Instant::now();
+            TimingWheelQueue {
+                wheel: TimingWheel::new(),
+                start,
+                timer: Box::pin(sleep_until(start)),
+                waker: None,
+            }
+        }
+
+        pub fn len(&self) -> usize {
+            self.wheel.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.wheel.is_empty()
+        }
+
+        // round up so that an item would never get released before its deadline
+        fn instant_to_tick(&self, instant: Instant) -> u64 {
+            let since_start = instant.saturating_duration_since(self.start);
+            let resolution = TICK_RESOLUTION.as_nanos();
+            since_start.as_nanos().div_ceil(resolution) as u64
+        }
+
+        fn now_tick(&self) -> u64 {
+            let since_start = Instant::now().saturating_duration_since(self.start);
+            (since_start.as_nanos() / TICK_RESOLUTION.as_nanos()) as u64
+        }
+
+        fn tick_to_instant(&self, tick: u64) -> Instant {
+            let since_start = (TICK_RESOLUTION.as_nanos() as u64).saturating_mul(tick);
+            self.start + Duration::from_nanos(since_start)
+        }
+
+        pub fn insert(&mut self, value: T, timeout: Duration) {
+            self.insert_at(value, Instant::now() + timeout)
+        }
+
+        pub fn insert_at(&mut self, value: T, when: Instant) {
+            let tick = self.instant_to_tick(when);
+            self.wheel.insert(value, tick);
+            if let Some(waker) = self.waker.take() {
+                // the new item might expire before whatever we were waiting for - wake the executor!
+                waker.wake()
+            }
+        }
+    }
+
+    impl<T> Default for TimingWheelQueue<T> {
+        fn default() -> Self {
+            TimingWheelQueue::new()
+        }
+    }
+
+    impl<T> Unpin for TimingWheelQueue<T> {}
+
+    impl<T> Stream for TimingWheelQueue<T> {
+        type Item = Vec<T>;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            loop {
+                let now = self.now_tick();
+                let expired = self.wheel.advance(now);
+                if !expired.is_empty() {
+                    return Poll::Ready(Some(expired));
+                }
+
+                // either we're waiting for the next deadline or for a new item to arrive
+                self.waker = Some(cx.waker().clone());
+
+                let Some(next) = self.wheel.next_deadline() else {
+                    return Poll::Pending;
+                };
+                let when = self.tick_to_instant(next);
+                self.timer.as_mut().reset(when);
+                if self.timer.as_mut().poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain_all(wheel: &mut TimingWheel<u64>) -> Vec<(u64, Vec<u64>)> {
+        let mut batches = Vec::new();
+        while let Some(next) = wheel.next_deadline() {
+            let expired = wheel.advance(next);
+            if !expired.is_empty() {
+                batches.push((next, expired))
+            }
+        }
+        batches
+    }
+
+    #[test]
+    fn items_are_not_released_early() {
+        let mut wheel = TimingWheel::new();
+        wheel.insert(1, 10);
+        wheel.insert(2, 100);
+        wheel.insert(3, 5000);
+        wheel.insert(4, 300_000);
+
+        assert!(wheel.advance(9).is_empty());
+        assert_eq!(wheel.advance(10), vec![1]);
+        assert!(wheel.advance(99).is_empty());
+        assert_eq!(wheel.advance(100), vec![2]);
+        assert!(wheel.advance(4999).is_empty());
+        assert_eq!(wheel.advance(5000), vec![3]);
+        assert!(wheel.advance(299_999).is_empty());
+        assert_eq!(wheel.advance(300_000), vec![4]);
+        assert!(wheel.is_empty());
+    }
+
+    #[test]
+    fn items_are_released_at_their_exact_deadlines() {
+        let mut wheel = TimingWheel::new();
+        let deadlines = [
+            1, 63, 64, 65, 127, 4095, 4096, 4097, 262_143, 262_144, 1_000_000,
+        ];
+        for deadline in deadlines {
+            wheel.insert(deadline, deadline);
+        }
+
+        let batches = drain_all(&mut wheel);
+        let expected = deadlines
+            .iter()
+            .map(|&deadline| (deadline, vec![deadline]))
+            .collect::<Vec<_>>();
+        assert_eq!(batches, expected);
+        assert!(wheel.is_empty());
+    }
+
+    #[test]
+    fn all_due_items_are_returned_in_single_batch() {
+        let mut wheel = TimingWheel::new();
+        for i in 1..=1000 {
+            wheel.insert(i, i);
+        }
+
+        let mut expired = wheel.advance(500);
+        expired.sort_unstable();
+        assert_eq!(expired, (1..=500).collect::<Vec<_>>());
+        assert_eq!(wheel.len(), 500);
+
+        let mut expired = wheel.advance(10_000);
+        expired.sort_unstable();
+        assert_eq!(expired, (501..=1000).collect::<Vec<_>>());
+        assert!(wheel.is_empty());
+    }
+
+    #[test]
+    fn items_with_passed_deadlines_are_immediately_ready() {
+        let mut wheel = TimingWheel::new();
+        wheel.advance(1000);
+        wheel.insert(1, 42);
+        wheel.insert(2, 1000);
+
+        assert_eq!(wheel.next_deadline(), Some(1000));
+        assert_eq!(wheel.advance(1000), vec![1, 2]);
+    }
+
+    #[test]
+    fn inserting_after_advancing_preserves_ordering() {
+        let mut wheel = TimingWheel::new();
+        wheel.insert(1, 5000);
+        wheel.advance(4000);
+        wheel.insert(2, 4010);
+        wheel.insert(3, 70_000);
+
+        assert_eq!(
+            drain_all(&mut wheel),
+            vec![(4010, vec![2]), (5000, vec![1]), (70_000, vec![3])]
+        );
+    }
+
+    #[test]
+    fn distant_deadlines_are_clamped() {
+        let mut wheel = TimingWheel::new();
+        wheel.insert(1, u64::MAX);
+        assert!(wheel.advance(MAX_TICKS - 1).is_empty());
+        assert_eq!(wheel.advance(MAX_TICKS), vec![1]);
+    }
+}
@@ -4,7 +4,10 @@ use log::{debug, warn};
 use std::fmt;
 pub use std::time::Instant;
 
-use prometheus::{core::Collector, Encoder as _, IntCounter, IntGauge, Registry, TextEncoder};
+use prometheus::{
+    core::Collector, Encoder as _, Histogram, HistogramOpts, IntCounter, IntGauge, Registry,
+    TextEncoder,
+};
 
 #[macro_export]
 macro_rules! prepend_package_name {
@@ -35,6 +38,13 @@ macro_rules! inc {
     };
 }
 
+#[macro_export]
+macro_rules! observe {
+    ($name:literal, $x:expr) => {
+        $crate::REGISTRY.observe($crate::prepend_package_name!($name), $x as f64);
+    };
+}
+
 #[macro_export]
 macro_rules! metrics {
     () => {
@@ -68,6 +78,7 @@ pub struct MetricsController {
 enum Metric {
     C(Box<IntCounter>),
     G(Box<IntGauge>),
+    H(Box<Histogram>),
 }
 
 fn fq_name(c: &dyn Collector) -> String {
@@ -83,6 +94,7 @@ impl Metric {
         match self {
             Metric::C(c) => fq_name(c.as_ref()),
             Metric::G(g) => fq_name(g.as_ref()),
+            Metric::H(h) => fq_name(h.as_ref()),
         }
     }
 
@@ -91,6 +103,9 @@ impl Metric {
         match self {
             Metric::C(c) => c.inc(),
             Metric::G(g) => g.inc(),
+            Metric::H(_h) => {
+                warn!("Cannot increment histogram {:?}", self.fq_name());
+            }
         }
     }
 
@@ -99,6 +114,9 @@ impl Metric {
         match self {
             Metric::C(c) => c.inc_by(value as u64),
             Metric::G(g) => g.add(value),
+            Metric::H(_h) => {
+                warn!("Cannot increment histogram {:?}", self.fq_name());
+            }
         }
     }
 
@@ -109,6 +127,19 @@ impl Metric {
                 warn!("Cannot set value for counter {:?}", self.fq_name());
             }
             Metric::G(g) => g.set(value),
+            Metric::H(_h) => {
+                warn!("Cannot set value for histogram {:?}", self.fq_name());
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn observe(&self, value: f64) {
+        match self {
+            Metric::H(h) => h.observe(value),
+            _ => {
+                warn!("Cannot observe value for {:?}", self.fq_name());
+            }
         }
     }
 }
@@ -193,6 +224,25 @@ impl MetricsController {
         }
     }
 
+    /// Records the provided observation (such as a latency in seconds) in the histogram
+    /// using the default prometheus buckets.
+    pub fn observe(&self, name: &str, value: f64) {
+        if let Some(metric) = self.registry_index.get(name) {
+            metric.observe(value);
+        } else {
+            let histogram =
+                match Histogram::with_opts(HistogramOpts::new(sanitize_metric_name(name), name)) {
+                    Ok(h) => h,
+                    Err(e) => {
+                        debug!("Failed to create histogram {:?}:\n{}", name, e);
+                        return;
+                    }
+                };
+            self.register_histogram(Box::new(histogram));
+            self.observe(name, value)
+        }
+    }
+
     fn register_gauge(&self, metric: Box<IntGauge>) {
         let fq_name = metric
             .desc()
@@ -235,6 +285,27 @@ impl MetricsController {
             }
         }
     }
+
+    fn register_histogram(&self, metric: Box<Histogram>) {
+        let fq_name = metric
+            .desc()
+            .first()
+            .map(|d| d.fq_name.clone())
+            .unwrap_or_default();
+
+        if self.registry_index.contains_key(&fq_name) {
+            return;
+        }
+        match self.registry.register(metric.clone()) {
+            Ok(_) => {
+                self.registry_index
+                    .insert(fq_name, Metric::H(metric.clone()));
+            }
+            Err(e) => {
+                debug!("Failed to register {:?}:\n{}", fq_name, e)
+            }
+        }
+    }
 }
 
 fn sanitize_metric_name(name: &str) -> String {
@@ -275,4 +346,16 @@ mod tests {
             "packets_sent_34_242_65_133:1789"
         )
     }
+
+    #[test]
+    fn observations_are_recorded_in_histograms() {
+        let controller = MetricsController::default();
+        controller.observe("ack_latency_seconds", 0.2);
+        controller.observe("ack_latency_seconds", 3.0);
+        controller.inc("ack_latency_seconds");
+
+        let metrics = controller.to_string();
+        assert!(metrics.contains("ack_latency_seconds_count 2"));
+        assert!(metrics.contains("ack_latency_seconds_sum 3.2"));
+    }
 }
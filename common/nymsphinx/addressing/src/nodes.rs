@@ -64,6 +64,16 @@ impl NymNodeRoutingAddress {
         }
     }
 
+    /// Writes the byte representation of self (as returned by `as_bytes`) into the provided buffer.
+    pub fn write_bytes(&self, buf: &mut Vec<u8>) {
+        buf.push(self.addr_type_as_u8());
+        buf.extend_from_slice(&self.0.port().to_be_bytes());
+        match self.0.ip() {
+            IpAddr::V4(ip) => buf.extend_from_slice(&ip.octets()),
+            IpAddr::V6(ip) => buf.extend_from_slice(&ip.octets()),
+        }
+    }
+
     /// Converts self into a vector of bytes.
     /// Note, this represents a generic bytes vector, not necessarily a NodeAddressBytes
     /// and hence is not zero-padded.
@@ -247,6 +257,23 @@ mod tests {
         )
     }
 
+    #[test]
+    fn nym_node_routing_address_written_bytes_match_allocated_representation() {
+        let address_v4 = NymNodeRoutingAddress(SocketAddr::new(IpAddr::from([1, 2, 3, 4]), 42));
+        let address_v6 = NymNodeRoutingAddress(SocketAddr::new(
+            IpAddr::from([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]),
+            42,
+        ));
+
+        for address in [address_v4, address_v6] {
+            let mut buf = vec![0xff];
+            address.write_bytes(&mut buf);
+            assert_eq!(buf[0], 0xff);
+            assert_eq!(buf[1..], address.as_bytes());
+            assert_eq!(buf.len() - 1, address.bytes_min_len());
+        }
+    }
+
     #[test]
     fn nym_node_routing_address_can_be_converted_to_and_from_node_address_bytes_with_no_data_loss()
     {
@@ -67,6 +67,99 @@ pub fn generate_loop_cover_packet<R>(
     packet_size: PacketSize,
     packet_type: PacketType,
 ) -> Result<MixPacket, CoverMessageError>
+where
+    R: RngCore + CryptoRng,
+{
+    generate_cover_packet_with_hops(
+        rng,
+        topology,
+        ack_key,
+        full_address,
+        full_address,
+        average_ack_delay,
+        average_packet_delay,
+        packet_size,
+        packet_type,
+        DEFAULT_NUM_MIX_HOPS,
+    )
+}
+
+/// Generates a cover packet addressed to another client rather than to ourselves.
+/// The recipient will recognise it as cover traffic and discard it, while the attached SURB-ACK
+/// still comes back to us. The payload is encrypted with a fresh ephemeral key, so it never shares
+/// any key material with the real messages sent to that recipient.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_correspondent_cover_packet<R>(
+    rng: &mut R,
+    topology: &NymTopology,
+    ack_key: &AckKey,
+    our_address: &Recipient,
+    correspondent: &Recipient,
+    average_ack_delay: time::Duration,
+    average_packet_delay: time::Duration,
+    packet_size: PacketSize,
+    packet_type: PacketType,
+) -> Result<MixPacket, CoverMessageError>
+where
+    R: RngCore + CryptoRng,
+{
+    generate_cover_packet_with_hops(
+        rng,
+        topology,
+        ack_key,
+        our_address,
+        correspondent,
+        average_ack_delay,
+        average_packet_delay,
+        packet_size,
+        packet_type,
+        DEFAULT_NUM_MIX_HOPS,
+    )
+}
+
+/// Generates a loop cover packet that does not go through any mixnodes, i.e. it's sent to our own
+/// gateway and immediately pushed back to us. It's useful for measuring the throughput of the
+/// gateway connection itself.
+/// Note that the attached SURB-ACK still goes through the mixnet.
+pub fn generate_gateway_loop_cover_packet<R>(
+    rng: &mut R,
+    topology: &NymTopology,
+    ack_key: &AckKey,
+    full_address: &Recipient,
+    average_ack_delay: time::Duration,
+    packet_size: PacketSize,
+    packet_type: PacketType,
+) -> Result<MixPacket, CoverMessageError>
+where
+    R: RngCore + CryptoRng,
+{
+    generate_cover_packet_with_hops(
+        rng,
+        topology,
+        ack_key,
+        full_address,
+        full_address,
+        average_ack_delay,
+        time::Duration::ZERO,
+        packet_size,
+        packet_type,
+        0,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn generate_cover_packet_with_hops<R>(
+    rng: &mut R,
+    topology: &NymTopology,
+    ack_key: &AckKey,
+    ack_address: &Recipient,
+    full_address: &Recipient,
+    average_ack_delay: time::Duration,
+    average_packet_delay: time::Duration,
+    packet_size: PacketSize,
+    packet_type: PacketType,
+    mix_hops: u8,
+) -> Result<MixPacket, CoverMessageError>
 where
     R: RngCore + CryptoRng,
 {
@@ -75,7 +168,7 @@ where
         rng,
         topology,
         ack_key,
-        full_address,
+        ack_address,
         average_ack_delay,
         packet_type,
     )?
@@ -83,7 +176,7 @@ where
 
     // cover message can't be distinguishable from a normal traffic so we have to go through
     // all the effort of key generation, encryption, etc. Note here we are generating shared key
-    // with ourselves!
+    // with the recipient of the packet, which, for loop cover traffic, is ourselves!
     let (ephemeral_keypair, shared_key) = new_ephemeral_shared_key::<
         PacketEncryptionAlgorithm,
         PacketHkdfAlgorithm,
@@ -118,8 +211,7 @@ where
         .chain(cover_content)
         .collect();
 
-    let route =
-        topology.random_route_to_gateway(rng, DEFAULT_NUM_MIX_HOPS, full_address.gateway())?;
+    let route = topology.random_route_to_gateway(rng, mix_hops, full_address.gateway())?;
     let delays = nym_sphinx_routing::generate_hop_delays(average_packet_delay, route.len());
     let destination = full_address.as_sphinx_destination();
 
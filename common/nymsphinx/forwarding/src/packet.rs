@@ -104,6 +104,19 @@ impl MixPacket {
         }
     }
 
+    /// Length of the serialised representation of this packet.
+    pub fn serialised_len(&self) -> usize {
+        1 + self.next_hop.bytes_min_len() + self.packet.len()
+    }
+
+    /// Serialises the packet (in the same format as `into_bytes`) by appending it to the provided buffer.
+    pub fn write_bytes(&self, buf: &mut Vec<u8>) -> Result<(), MixPacketFormattingError> {
+        buf.push(self.packet_type as u8);
+        self.next_hop.write_bytes(buf);
+        buf.extend_from_slice(&self.packet.to_bytes()?);
+        Ok(())
+    }
+
     pub fn into_bytes(self) -> Result<Vec<u8>, MixPacketFormattingError> {
         Ok(std::iter::once(self.packet_type as u8)
             .chain(self.next_hop.as_bytes())
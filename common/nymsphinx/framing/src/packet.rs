@@ -50,6 +50,10 @@ impl FramedNymPacket {
         self.header.packet_type
     }
 
+    pub fn packet(&self) -> &NymPacket {
+        &self.packet
+    }
+
     pub fn into_inner(self) -> NymPacket {
         self.packet
     }
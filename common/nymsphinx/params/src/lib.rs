@@ -6,6 +6,9 @@ use nym_crypto::blake3;
 use nym_crypto::ctr;
 use nym_crypto::Aes256GcmSiv;
 
+#[cfg(feature = "fips")]
+use nym_crypto::{sha2, Aes256Gcm};
+
 type Aes128Ctr = ctr::Ctr64BE<Aes128>;
 
 // Re-export for ease of use
@@ -45,6 +48,11 @@ pub type PacketHkdfAlgorithm = blake3::Hasher;
 /// Hashing algorithm used during hkdf while establishing long-term shared key between client and gateway.
 pub type GatewaySharedKeyHkdfAlgorithm = blake3::Hasher;
 
+/// Hashing algorithm used during hkdf while establishing long-term shared key between client and gateway
+/// if they have been built with the `fips` feature.
+#[cfg(feature = "fips")]
+pub type FipsGatewaySharedKeyHkdfAlgorithm = sha2::Sha256;
+
 /// Hashing algorithm used when computing digest of a reply SURB encryption key.
 pub type ReplySurbKeyDigestAlgorithm = blake3::Hasher;
 
@@ -71,6 +79,12 @@ pub type LegacyGatewayEncryptionAlgorithm = Aes128Ctr;
 // NOTE: if updated, the pem type defined in gateway\gateway-requests\src\registration\handshake\shared_key
 pub type GatewayEncryptionAlgorithm = Aes256GcmSiv;
 
+/// Encryption algorithm used for end-to-end encryption of messages exchanged between clients
+/// and their gateways if they have been built with the `fips` feature.
+// NOTE: its key, nonce and tag sizes must match those of [`GatewayEncryptionAlgorithm`]
+#[cfg(feature = "fips")]
+pub type FipsGatewayEncryptionAlgorithm = Aes256Gcm;
+
 /// Encryption algorithm used for end-to-end encryption of messages exchanged between clients that are
 /// encapsulated inside sphinx packets.
 pub type PacketEncryptionAlgorithm = Aes128Ctr;
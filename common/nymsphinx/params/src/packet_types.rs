@@ -18,7 +18,7 @@ pub struct InvalidPacketType {
 
 #[repr(u8)]
 #[allow(deprecated)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub enum PacketType {
     /// Represents 'normal' packet sent through the network that should be delayed by an appropriate
     /// value at each hop.
@@ -0,0 +1,189 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optional versioned header of the plaintext message envelope (i.e. the serialised [`NymMessage`]
+//! before it gets padded and chunked) that allows introducing new message features without
+//! breaking existing receivers.
+//!
+//! The legacy envelope is in the format of:
+//! typ || msg
+//!
+//! while the versioned one is in the format of:
+//! (VERSIONED_ENVELOPE_MARKER | typ) || version || flags || msg
+//!
+//! The flags are split into two groups:
+//! - the lower byte contains *critical* flags. If the receiver encounters a critical flag it does
+//!   not understand, it must reject the message as it won't be able to correctly interpret it,
+//! - the upper byte contains *optional* flags. Any unknown optional flags are ignored by the
+//!   receiver as the message content is still meaningful without understanding them.
+//!
+//! [`NymMessage`]: crate::message::NymMessage
+
+use crate::message::NymMessageError;
+
+/// The most recent envelope version understood by this client.
+pub const CURRENT_ENVELOPE_VERSION: u8 = 1;
+
+/// Bit set on the message type byte to indicate the presence of the versioned envelope header.
+pub(crate) const VERSIONED_ENVELOPE_MARKER: u8 = 0b1000_0000;
+
+/// Size of the header (excluding the type byte) attached to versioned envelopes.
+pub(crate) const VERSIONED_ENVELOPE_HEADER_SIZE: usize = 3;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct EnvelopeFlags(u16);
+
+impl EnvelopeFlags {
+    const CRITICAL_MASK: u16 = 0x00ff;
+    const OPTIONAL_MASK: u16 = 0xff00;
+
+    /// Critical flags understood by this client. There are none defined yet.
+    pub const KNOWN_CRITICAL: u16 = 0;
+
+    /// Optional flags understood by this client. There are none defined yet.
+    pub const KNOWN_OPTIONAL: u16 = 0;
+
+    pub const fn empty() -> Self {
+        EnvelopeFlags(0)
+    }
+
+    pub const fn from_bits(bits: u16) -> Self {
+        EnvelopeFlags(bits)
+    }
+
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+
+    #[must_use]
+    pub const fn with(self, flag: u16) -> Self {
+        EnvelopeFlags(self.0 | flag)
+    }
+
+    pub const fn contains(&self, flag: u16) -> bool {
+        self.0 & flag == flag
+    }
+
+    /// Critical flags set on the envelope that this client does not understand.
+    pub const fn unknown_critical(&self) -> u16 {
+        self.0 & Self::CRITICAL_MASK & !Self::KNOWN_CRITICAL
+    }
+
+    /// Returns the flags with all the unknown optional bits cleared.
+    #[must_use]
+    pub const fn without_unknown_optional(self) -> Self {
+        let unknown_optional = self.0 & Self::OPTIONAL_MASK & !Self::KNOWN_OPTIONAL;
+        EnvelopeFlags(self.0 & !unknown_optional)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EnvelopeHeader {
+    pub version: u8,
+    pub flags: EnvelopeFlags,
+}
+
+impl Default for EnvelopeHeader {
+    fn default() -> Self {
+        EnvelopeHeader::new(EnvelopeFlags::empty())
+    }
+}
+
+impl EnvelopeHeader {
+    pub fn new(flags: EnvelopeFlags) -> Self {
+        EnvelopeHeader {
+            version: CURRENT_ENVELOPE_VERSION,
+            flags,
+        }
+    }
+
+    /// Header implicitly associated with messages that were sent without the versioned envelope.
+    pub fn legacy() -> Self {
+        EnvelopeHeader {
+            version: 0,
+            flags: EnvelopeFlags::empty(),
+        }
+    }
+
+    pub fn is_legacy(&self) -> bool {
+        self.version == 0
+    }
+
+    pub(crate) fn to_bytes(self) -> [u8; VERSIONED_ENVELOPE_HEADER_SIZE] {
+        let flags = self.flags.bits().to_be_bytes();
+        [self.version, flags[0], flags[1]]
+    }
+
+    /// Attempts to parse the versioned envelope header, applying the compatibility rules,
+    /// i.e. rejecting unknown versions and unknown critical flags while ignoring unknown optional flags.
+    pub(crate) fn try_from_bytes(b: &[u8]) -> Result<Self, NymMessageError> {
+        if b.len() < VERSIONED_ENVELOPE_HEADER_SIZE {
+            return Err(NymMessageError::TruncatedEnvelopeHeader {
+                received: b.len(),
+                expected: VERSIONED_ENVELOPE_HEADER_SIZE,
+            });
+        }
+
+        let version = b[0];
+        if version == 0 || version > CURRENT_ENVELOPE_VERSION {
+            return Err(NymMessageError::UnsupportedEnvelopeVersion {
+                received: version,
+                current: CURRENT_ENVELOPE_VERSION,
+            });
+        }
+
+        let flags = EnvelopeFlags::from_bits(u16::from_be_bytes([b[1], b[2]]));
+        let unknown_critical = flags.unknown_critical();
+        if unknown_critical != 0 {
+            return Err(NymMessageError::UnsupportedCriticalEnvelopeFlags {
+                flags: unknown_critical,
+            });
+        }
+
+        Ok(EnvelopeHeader {
+            version,
+            flags: flags.without_unknown_optional(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_roundtrip() {
+        let header = EnvelopeHeader::default();
+        assert_eq!(
+            header,
+            EnvelopeHeader::try_from_bytes(&header.to_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn unknown_optional_flags_are_ignored() {
+        let header = EnvelopeHeader::new(EnvelopeFlags::from_bits(0b0000_0101_0000_0000));
+        let parsed = EnvelopeHeader::try_from_bytes(&header.to_bytes()).unwrap();
+        assert_eq!(parsed.flags, EnvelopeFlags::empty());
+    }
+
+    #[test]
+    fn unknown_critical_flags_are_rejected() {
+        let header = EnvelopeHeader::new(EnvelopeFlags::from_bits(0b0000_0000_0000_0010));
+        assert!(matches!(
+            EnvelopeHeader::try_from_bytes(&header.to_bytes()),
+            Err(NymMessageError::UnsupportedCriticalEnvelopeFlags { flags: 0b10 })
+        ));
+    }
+
+    #[test]
+    fn unsupported_versions_are_rejected() {
+        let future = [CURRENT_ENVELOPE_VERSION + 1, 0, 0];
+        assert!(EnvelopeHeader::try_from_bytes(&future).is_err());
+
+        let legacy = [0, 0, 0];
+        assert!(EnvelopeHeader::try_from_bytes(&legacy).is_err());
+
+        assert!(EnvelopeHeader::try_from_bytes(&[CURRENT_ENVELOPE_VERSION]).is_err());
+    }
+}
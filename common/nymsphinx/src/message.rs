@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::chunking;
+use crate::envelope::{EnvelopeHeader, VERSIONED_ENVELOPE_HEADER_SIZE, VERSIONED_ENVELOPE_MARKER};
 use nym_crypto::asymmetric::encryption;
 use nym_crypto::Digest;
 use nym_sphinx_addressing::clients::Recipient;
@@ -33,6 +34,17 @@ pub enum NymMessageError {
 
     #[error("Received empty message for deserialization")]
     EmptyMessage,
+
+    #[error(
+        "the message envelope header is truncated. got {received} bytes while expected {expected}"
+    )]
+    TruncatedEnvelopeHeader { received: usize, expected: usize },
+
+    #[error("the message envelope has version {received} while the most recent supported one is {current}")]
+    UnsupportedEnvelopeVersion { received: u8, current: u8 },
+
+    #[error("the message envelope has critical flags set that are not understood: {flags:#06x}")]
+    UnsupportedCriticalEnvelopeFlags { flags: u16 },
 }
 
 #[repr(u8)]
@@ -141,39 +153,67 @@ impl NymMessage {
 
     // the message is in the format of:
     // typ || msg
-    fn into_bytes(self) -> Vec<u8> {
-        let typ = self.typ();
-
-        std::iter::once(typ as u8)
-            .chain(self.inner_bytes())
-            .collect()
+    // or, if the versioned envelope is used:
+    // (VERSIONED_ENVELOPE_MARKER | typ) || version || flags || msg
+    fn into_bytes(self, envelope: Option<EnvelopeHeader>) -> Vec<u8> {
+        let typ = self.typ() as u8;
+
+        match envelope {
+            None => std::iter::once(typ).chain(self.inner_bytes()).collect(),
+            Some(header) => std::iter::once(typ | VERSIONED_ENVELOPE_MARKER)
+                .chain(header.to_bytes())
+                .chain(self.inner_bytes())
+                .collect(),
+        }
     }
 
-    fn try_from_bytes(bytes: &[u8], num_mix_hops: u8) -> Result<Self, NymMessageError> {
+    fn try_from_bytes(
+        bytes: &[u8],
+        num_mix_hops: u8,
+    ) -> Result<(Self, EnvelopeHeader), NymMessageError> {
         if bytes.is_empty() {
             return Err(NymMessageError::EmptyMessage);
         }
 
-        let typ_tag = NymMessageType::try_from(bytes[0])?;
-        match typ_tag {
-            NymMessageType::Plain => Ok(NymMessage::Plain(bytes[1..].to_vec())),
-            NymMessageType::Repliable => Ok(NymMessage::Repliable(
-                RepliableMessage::try_from_bytes(&bytes[1..], num_mix_hops)?,
-            )),
-            NymMessageType::Reply => Ok(NymMessage::Reply(ReplyMessage::try_from_bytes(
+        let (typ_tag, header, content) = if bytes[0] & VERSIONED_ENVELOPE_MARKER != 0 {
+            let typ_tag = NymMessageType::try_from(bytes[0] & !VERSIONED_ENVELOPE_MARKER)?;
+            let header = EnvelopeHeader::try_from_bytes(&bytes[1..])?;
+            (
+                typ_tag,
+                header,
+                &bytes[1 + VERSIONED_ENVELOPE_HEADER_SIZE..],
+            )
+        } else {
+            (
+                NymMessageType::try_from(bytes[0])?,
+                EnvelopeHeader::legacy(),
                 &bytes[1..],
-            )?)),
-        }
+            )
+        };
+
+        let message = match typ_tag {
+            NymMessageType::Plain => NymMessage::Plain(content.to_vec()),
+            NymMessageType::Repliable => {
+                NymMessage::Repliable(RepliableMessage::try_from_bytes(content, num_mix_hops)?)
+            }
+            NymMessageType::Reply => NymMessage::Reply(ReplyMessage::try_from_bytes(content)?),
+        };
+        Ok((message, header))
     }
 
-    fn serialized_size(&self, num_mix_hops: u8) -> usize {
+    fn serialized_size(&self, num_mix_hops: u8, envelope: Option<EnvelopeHeader>) -> usize {
         let inner_size = match self {
             NymMessage::Plain(msg) => msg.len(),
             NymMessage::Repliable(msg) => msg.serialized_size(num_mix_hops),
             NymMessage::Reply(msg) => msg.serialized_size(),
         };
         let message_type_size = 1;
-        message_type_size + inner_size
+        let envelope_size = if envelope.is_some() {
+            VERSIONED_ENVELOPE_HEADER_SIZE
+        } else {
+            0
+        };
+        message_type_size + envelope_size + inner_size
     }
 
     /// Length of plaintext (from the **sphinx** point of view) data that is available per sphinx
@@ -207,9 +247,14 @@ impl NymMessage {
     }
 
     /// Determines the number of required packets of the provided size for the split message.
-    pub fn required_packets(&self, packet_size: PacketSize, num_mix_hops: u8) -> usize {
+    pub fn required_packets(
+        &self,
+        packet_size: PacketSize,
+        num_mix_hops: u8,
+        envelope: Option<EnvelopeHeader>,
+    ) -> usize {
         let plaintext_per_packet = self.true_available_plaintext_per_packet(packet_size);
-        let serialized_len = self.serialized_size(num_mix_hops);
+        let serialized_len = self.serialized_size(num_mix_hops, envelope);
 
         let (num_fragments, _) =
             chunking::number_of_required_fragments(serialized_len, plaintext_per_packet);
@@ -225,10 +270,15 @@ impl NymMessage {
 
     /// Pads the message so that after it gets chunked, it will occupy exactly N sphinx packets.
     /// Produces new_message = message || 1 || 0000....
-    pub fn pad_to_full_packet_lengths(self, plaintext_per_packet: usize) -> PaddedMessage {
+    /// If the envelope header is provided, the message is going to be serialised using the versioned envelope.
+    pub fn pad_to_full_packet_lengths(
+        self,
+        plaintext_per_packet: usize,
+        envelope: Option<EnvelopeHeader>,
+    ) -> PaddedMessage {
         let self_display = self.to_string();
 
-        let bytes = self.into_bytes();
+        let bytes = self.into_bytes(envelope);
 
         // 1 (chunking::MIN_PADDING_OVERHEAD) is added as there will always have to be at least a single byte of padding (1) added
         // to be able to later distinguish the actual padding from the underlying message
@@ -280,6 +330,16 @@ impl PaddedMessage {
 
     // reverse of NymMessage::pad_to_full_packet_lengths
     pub fn remove_padding(self, num_mix_hops: u8) -> Result<NymMessage, NymMessageError> {
+        self.remove_padding_with_envelope(num_mix_hops)
+            .map(|(message, _)| message)
+    }
+
+    /// Removes the padding and parses the message alongside its envelope header.
+    /// Messages sent without the versioned envelope are associated with the legacy header.
+    pub fn remove_padding_with_envelope(
+        self,
+        num_mix_hops: u8,
+    ) -> Result<(NymMessage, EnvelopeHeader), NymMessageError> {
         // we are looking for first occurrence of 1 in the tail and we get its index
         if let Some(padding_end) = self.0.iter().rposition(|b| *b == 1) {
             // and now we only take bytes until that point (but not including it)
@@ -304,7 +364,14 @@ mod tests {
     fn serialized_size_matches_actual_serialization() {
         // plain
         let plain = NymMessage::new_plain(vec![1, 2, 3, 4, 5]);
-        assert_eq!(plain.serialized_size(3), plain.into_bytes().len());
+        assert_eq!(plain.serialized_size(3, None), plain.into_bytes(None).len());
+
+        let plain = NymMessage::new_plain(vec![1, 2, 3, 4, 5]);
+        let envelope = Some(EnvelopeHeader::default());
+        assert_eq!(
+            plain.serialized_size(3, envelope),
+            plain.into_bytes(envelope).len()
+        );
 
         // a single variant for each repliable and reply is enough as they are more thoroughly tested
         // internally
@@ -313,9 +380,33 @@ mod tests {
             [42u8; 16].into(),
             vec![],
         ));
-        assert_eq!(repliable.serialized_size(3), repliable.into_bytes().len());
+        assert_eq!(
+            repliable.serialized_size(3, None),
+            repliable.into_bytes(None).len()
+        );
+
+        let reply = NymMessage::new_reply(ReplyMessage::new_data_message(vec![1, 2, 3, 4, 5]));
+        assert_eq!(reply.serialized_size(3, None), reply.into_bytes(None).len());
+    }
 
+    #[test]
+    fn versioned_envelope_roundtrip() {
+        let envelope = EnvelopeHeader::default();
         let reply = NymMessage::new_reply(ReplyMessage::new_data_message(vec![1, 2, 3, 4, 5]));
-        assert_eq!(reply.serialized_size(3), reply.into_bytes().len());
+        let padded = reply.pad_to_full_packet_lengths(100, Some(envelope));
+
+        let (message, header) = padded.remove_padding_with_envelope(3).unwrap();
+        assert_eq!(header, envelope);
+        assert_eq!(message.into_inner_data(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn legacy_envelope_is_still_understood() {
+        let plain = NymMessage::new_plain(vec![1, 2, 3, 4, 5]);
+        let padded = plain.pad_to_full_packet_lengths(100, None);
+
+        let (message, header) = padded.remove_padding_with_envelope(3).unwrap();
+        assert!(header.is_legacy());
+        assert_eq!(message.into_inner_data(), vec![1, 2, 3, 4, 5]);
     }
 }
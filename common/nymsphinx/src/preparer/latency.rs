@@ -0,0 +1,149 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-message latency budgets used by the [`MessagePreparer`](super::MessagePreparer).
+//!
+//! By default each mix hop delays a packet by an exponentially distributed amount of time with the
+//! configured average. A budget puts an upper bound on the total delay the packets of a message are
+//! subjected to by the mix nodes, which is achieved by lowering the average per-hop delay
+//! and, if permitted, by routing the packets through fewer mix nodes.
+//! Either way it directly reduces the anonymity provided by the mixnet for that particular message,
+//! so it should only be used when the latency genuinely matters more.
+
+use nym_sphinx_types::Delay;
+use std::time::Duration;
+
+/// Upper bound on the total delay introduced by the mix nodes on the route of a packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyBudget {
+    max_delay: Duration,
+    allow_fewer_hops: bool,
+}
+
+impl LatencyBudget {
+    pub fn new(max_delay: Duration) -> Self {
+        LatencyBudget {
+            max_delay,
+            allow_fewer_hops: false,
+        }
+    }
+
+    /// Allows routing the packets through fewer mix nodes than usual (but never fewer than
+    /// the minimum published in the topology), so that each remaining node could delay
+    /// the packets by its regular amount rather than barely mixing them.
+    #[must_use]
+    pub fn allowing_fewer_hops(mut self) -> Self {
+        self.allow_fewer_hops = true;
+        self
+    }
+
+    pub fn max_delay(&self) -> Duration {
+        self.max_delay
+    }
+
+    pub fn allows_fewer_hops(&self) -> bool {
+        self.allow_fewer_hops
+    }
+
+    /// Determines the route length and the average per-hop delay fitting within the budget.
+    pub fn plan(
+        &self,
+        mix_hops: u8,
+        minimum_mix_hops: Option<u8>,
+        average_packet_delay: Duration,
+    ) -> RoutePlan {
+        let mut mix_hops = mix_hops.max(1);
+        if self.allow_fewer_hops {
+            if let Some(minimum) = minimum_mix_hops {
+                // the number of hops that could still delay the packets by the regular amount
+                let affordable = self.max_delay.as_nanos() / average_packet_delay.as_nanos().max(1);
+                let affordable = u8::try_from(affordable).unwrap_or(u8::MAX);
+                mix_hops = mix_hops.min(affordable.max(minimum).max(1));
+            }
+        }
+
+        RoutePlan {
+            mix_hops,
+            average_packet_delay: average_packet_delay.min(self.max_delay / mix_hops as u32),
+        }
+    }
+
+    /// Scales down the sampled delays of the mix hops if their sum exceeds the budget,
+    /// as the exponential distribution is quite likely to produce a value way above the average.
+    pub fn fit_delays(&self, mix_delays: &mut [Delay]) {
+        let total = mix_delays
+            .iter()
+            .map(|delay| delay.to_duration())
+            .sum::<Duration>();
+        if total <= self.max_delay {
+            return;
+        }
+
+        let factor = self.max_delay.as_secs_f64() / total.as_secs_f64();
+        for delay in mix_delays {
+            *delay = *delay * factor;
+        }
+    }
+}
+
+/// Route parameters chosen in order to meet a [`LatencyBudget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoutePlan {
+    pub mix_hops: u8,
+    pub average_packet_delay: Duration,
+}
+
+impl RoutePlan {
+    /// The expected total delay introduced by the mix nodes.
+    pub fn expected_delay(&self) -> Duration {
+        self.average_packet_delay * self.mix_hops as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_fits_within_budget() {
+        let average = Duration::from_millis(50);
+
+        let generous = LatencyBudget::new(Duration::from_secs(1));
+        assert_eq!(
+            generous.plan(3, Some(2), average),
+            RoutePlan {
+                mix_hops: 3,
+                average_packet_delay: average
+            }
+        );
+
+        let tight = LatencyBudget::new(Duration::from_millis(60));
+        assert_eq!(
+            tight.plan(3, Some(2), average),
+            RoutePlan {
+                mix_hops: 3,
+                average_packet_delay: Duration::from_millis(20)
+            }
+        );
+
+        // we can't go below the published minimum
+        let plan = tight.allowing_fewer_hops().plan(3, Some(2), average);
+        assert_eq!(plan.mix_hops, 2);
+        assert_eq!(plan.expected_delay(), Duration::from_millis(60));
+
+        // nor reduce the hops if the topology doesn't publish any minimum
+        assert_eq!(
+            tight.allowing_fewer_hops().plan(3, None, average).mix_hops,
+            3
+        );
+
+        let mut delays = vec![
+            Delay::new_from_millis(100),
+            Delay::new_from_millis(20),
+            Delay::new_from_millis(0),
+        ];
+        tight.fit_delays(&mut delays);
+        let total = delays.iter().map(|d| d.to_duration()).sum::<Duration>();
+        assert!(total <= Duration::from_millis(60));
+    }
+}
@@ -1,7 +1,9 @@
 // Copyright 2021-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::envelope::EnvelopeHeader;
 use crate::message::{NymMessage, ACK_OVERHEAD, OUTFOX_ACK_OVERHEAD};
+use crate::preparer::latency::LatencyBudget;
 use crate::NymPayloadBuilder;
 use log::debug;
 use nym_crypto::asymmetric::encryption;
@@ -23,6 +25,7 @@ use rand_chacha::ChaCha20Rng;
 
 use std::time::Duration;
 
+pub mod latency;
 pub(crate) mod payload;
 
 /// Represents fully packed and prepared [`Fragment`] that can be sent through the mix network.
@@ -57,6 +60,12 @@ pub trait FragmentPreparer {
     fn average_packet_delay(&self) -> Duration;
     fn average_ack_delay(&self) -> Duration;
 
+    /// Optional versioned envelope header attached to all outgoing messages.
+    /// Disabled unless explicitly overridden so that the messages could be understood by legacy receivers.
+    fn message_envelope(&self) -> Option<EnvelopeHeader> {
+        None
+    }
+
     fn generate_reply_surbs(
         &mut self,
         amount: usize,
@@ -195,6 +204,33 @@ pub trait FragmentPreparer {
         packet_recipient: &Recipient,
         packet_type: PacketType,
         mix_hops: Option<u8>,
+    ) -> Result<PreparedFragment, NymTopologyError> {
+        self.prepare_chunk_for_sending_within_budget(
+            fragment,
+            topology,
+            ack_key,
+            packet_sender,
+            packet_recipient,
+            packet_type,
+            mix_hops,
+            None,
+        )
+    }
+
+    /// Same as [`FragmentPreparer::prepare_chunk_for_sending`], but if the latency budget is specified,
+    /// the number of mix hops and their delays are adjusted so that the total delay of the packet
+    /// at the mix nodes would not exceed it.
+    #[allow(clippy::too_many_arguments)]
+    fn prepare_chunk_for_sending_within_budget(
+        &mut self,
+        fragment: Fragment,
+        topology: &NymTopology,
+        ack_key: &AckKey,
+        packet_sender: &Recipient,
+        packet_recipient: &Recipient,
+        packet_type: PacketType,
+        mix_hops: Option<u8>,
+        latency_budget: Option<LatencyBudget>,
     ) -> Result<PreparedFragment, NymTopologyError> {
         debug!("Preparing chunk for sending");
         // each plain or repliable packet (i.e. not a reply) attaches an ephemeral public key so that the recipient
@@ -205,7 +241,19 @@ pub trait FragmentPreparer {
         let mut rng = ChaCha20Rng::seed_from_u64(seed as u64);
 
         let destination = packet_recipient.gateway();
-        let hops = mix_hops.unwrap_or(self.num_mix_hops());
+        let mut hops = mix_hops.unwrap_or(self.num_mix_hops());
+        let mut average_packet_delay = self.average_packet_delay();
+        if let Some(budget) = latency_budget {
+            let plan = budget.plan(hops, topology.minimum_mix_hops(), average_packet_delay);
+            debug!(
+                "fitting the packet into the latency budget of {:?}: {} mix hops with the average delay of {:?}",
+                budget.max_delay(),
+                plan.mix_hops,
+                plan.average_packet_delay
+            );
+            hops = plan.mix_hops;
+            average_packet_delay = plan.average_packet_delay;
+        }
         fragment_sent(&fragment, self.nonce(), *destination, hops);
 
         let non_reply_overhead = encryption::PUBLIC_KEY_SIZE;
@@ -246,8 +294,12 @@ pub trait FragmentPreparer {
         let destination = packet_recipient.as_sphinx_destination();
 
         // including set of delays
-        let delays =
-            nym_sphinx_routing::generate_hop_delays(self.average_packet_delay(), route.len());
+        let mut delays = nym_sphinx_routing::generate_hop_delays(average_packet_delay, route.len());
+        if let Some(budget) = latency_budget {
+            // the last hop is the gateway, which doesn't delay the packets
+            let mix_delays = delays.len() - 1;
+            budget.fit_delays(&mut delays[..mix_delays]);
+        }
 
         // create the actual sphinx packet here. With valid route and correct payload size,
         // there's absolutely no reason for this call to fail.
@@ -295,9 +347,10 @@ pub trait FragmentPreparer {
         packet_size: PacketSize,
     ) -> Vec<Fragment> {
         let plaintext_per_packet = message.available_sphinx_plaintext_per_packet(packet_size);
+        let envelope = self.message_envelope();
 
         message
-            .pad_to_full_packet_lengths(plaintext_per_packet)
+            .pad_to_full_packet_lengths(plaintext_per_packet, envelope)
             .split_into_fragments(self.rng(), plaintext_per_packet)
     }
 }
@@ -328,6 +381,10 @@ pub struct MessagePreparer<R> {
     num_mix_hops: u8,
 
     nonce: i32,
+
+    /// Optional versioned envelope header attached to all outgoing messages.
+    /// Note that receivers that predate the envelope versioning are unable to parse such messages.
+    message_envelope: Option<EnvelopeHeader>,
 }
 
 impl<R> MessagePreparer<R>
@@ -349,15 +406,27 @@ where
             average_ack_delay,
             num_mix_hops: DEFAULT_NUM_MIX_HOPS,
             nonce,
+            message_envelope: None,
         }
     }
 
+    /// Allows attaching the versioned envelope header to all outgoing messages.
+    pub fn with_message_envelope(mut self, envelope: Option<EnvelopeHeader>) -> Self {
+        self.message_envelope = envelope;
+        self
+    }
+
     /// Allows setting non-default number of expected mix hops in the network.
     pub fn with_mix_hops(mut self, hops: u8) -> Self {
         self.num_mix_hops = hops;
         self
     }
 
+    /// Overwrites the average delay a data packet is going to get delayed at a single mixnode.
+    pub fn set_average_packet_delay(&mut self, average_packet_delay: Duration) {
+        self.average_packet_delay = average_packet_delay;
+    }
+
     /// Overwrites existing sender address with the provided value.
     pub fn set_sender_address(&mut self, sender_address: Recipient) {
         self.sender_address = sender_address;
@@ -426,6 +495,34 @@ where
         )
     }
 
+    /// Prepares the fragment for being sent such that its delay at the mix nodes would fit
+    /// within the specified latency budget.
+    #[allow(clippy::too_many_arguments)]
+    pub fn prepare_chunk_for_sending_within_budget(
+        &mut self,
+        fragment: Fragment,
+        topology: &NymTopology,
+        ack_key: &AckKey,
+        packet_recipient: &Recipient,
+        packet_type: PacketType,
+        mix_hops: Option<u8>,
+        latency_budget: Option<LatencyBudget>,
+    ) -> Result<PreparedFragment, NymTopologyError> {
+        let sender = self.sender_address;
+
+        <Self as FragmentPreparer>::prepare_chunk_for_sending_within_budget(
+            self,
+            fragment,
+            topology,
+            ack_key,
+            &sender,
+            packet_recipient,
+            packet_type,
+            mix_hops,
+            latency_budget,
+        )
+    }
+
     /// Construct an acknowledgement SURB for the given [`FragmentIdentifier`]
     pub fn generate_surb_ack(
         &mut self,
@@ -476,6 +573,10 @@ impl<R: CryptoRng + Rng> FragmentPreparer for MessagePreparer<R> {
     fn nonce(&self) -> i32 {
         self.nonce
     }
+
+    fn message_envelope(&self) -> Option<EnvelopeHeader> {
+        self.message_envelope
+    }
 }
 
 /*
@@ -27,6 +27,11 @@ pub struct ReconstructedMessage {
     /// Optional ephemeral sender tag indicating pseudo-identity of the party who sent us the message
     /// (alongside any reply SURBs)
     pub sender_tag: Option<AnonymousSenderTag>,
+
+    /// Id of the (first) fragment set the message has been reconstructed from, if known.
+    /// Unlike the content, it's unique to the particular message that has been sent
+    /// and it's shared by all of its retransmissions.
+    pub fragment_set_id: Option<i32>,
 }
 
 impl From<ReconstructedMessage> for (Vec<u8>, Option<AnonymousSenderTag>) {
@@ -40,9 +45,16 @@ impl ReconstructedMessage {
         Self {
             message,
             sender_tag: Some(sender_tag),
+            fragment_set_id: None,
         }
     }
 
+    #[must_use]
+    pub fn with_fragment_set_id(mut self, fragment_set_id: i32) -> Self {
+        self.fragment_set_id = Some(fragment_set_id);
+        self
+    }
+
     pub fn into_inner(self) -> (Vec<u8>, Option<AnonymousSenderTag>) {
         self.into()
     }
@@ -53,6 +65,7 @@ impl From<PlainMessage> for ReconstructedMessage {
         ReconstructedMessage {
             message,
             sender_tag: None,
+            fragment_set_id: None,
         }
     }
 }
@@ -1,10 +1,13 @@
 // Copyright 2021-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
 pub use nym_client_core::config::Config as BaseClientConfig;
 use nym_config::defaults::DEFAULT_SOCKS5_LISTENING_PORT;
 use nym_config::OptionalSet;
 use nym_sphinx::addressing::clients::Recipient;
+use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
@@ -19,6 +22,9 @@ pub use nym_socks5_requests::Socks5ProtocolVersion;
 
 const DEFAULT_CONNECTION_START_SURBS: u32 = 20;
 const DEFAULT_PER_REQUEST_SURBS: u32 = 3;
+const DEFAULT_MULTIPLEXED_LANES: u32 = 8;
+const DEFAULT_WARM_LANE_CONNECTION_START_SURBS: u32 = 5;
+const DEFAULT_ACTIVITY_LOG_RETENTION: usize = 100;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -63,6 +69,12 @@ impl Config {
         self
     }
 
+    #[must_use]
+    pub fn with_authentication(mut self, authentication: Option<Socks5Authentication>) -> Self {
+        self.socks5.authentication = authentication;
+        self
+    }
+
     // poor man's 'builder' method
     pub fn with_base<F, T>(mut self, f: F, val: T) -> Self
     where
@@ -135,6 +147,14 @@ pub struct Socks5 {
     #[serde(default)]
     pub send_anonymously: bool,
 
+    /// Optional username/password pair that applications connecting to the local listener
+    /// have to present (RFC 1929). If not set, no authentication is going to be required.
+    #[serde(default)]
+    pub authentication: Option<Socks5Authentication>,
+
+    #[serde(default)]
+    pub activity_log: ActivityLog,
+
     #[serde(default)]
     pub socks5_debug: Socks5Debug,
 }
@@ -150,6 +170,8 @@ impl Socks5 {
             provider_interface_version: ProviderInterfaceVersion::Legacy,
             socks5_protocol_version: Socks5ProtocolVersion::Legacy,
             send_anonymously: false,
+            authentication: None,
+            activity_log: Default::default(),
             socks5_debug: Default::default(),
         }
     }
@@ -160,6 +182,69 @@ impl Socks5 {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Socks5Authentication {
+    pub username: String,
+
+    /// Salted argon2 hash of the password, in the PHC string format.
+    /// The password itself is never persisted.
+    pub password_hash: String,
+}
+
+impl Socks5Authentication {
+    pub fn new<S: Into<String>>(
+        username: S,
+        password: &str,
+    ) -> Result<Self, argon2::password_hash::Error> {
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hash = Argon2::default()
+            .hash_password(password.as_bytes(), &salt)?
+            .to_string();
+
+        Ok(Socks5Authentication {
+            username: username.into(),
+            password_hash,
+        })
+    }
+
+    /// Checks whether the provided credentials match the configured ones.
+    pub fn verify(&self, username: &str, password: &str) -> bool {
+        if self.username != username {
+            return false;
+        }
+        let Ok(password_hash) = PasswordHash::new(&self.password_hash) else {
+            return false;
+        };
+        Argon2::default()
+            .verify_password(password.as_bytes(), &password_hash)
+            .is_ok()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ActivityLog {
+    /// Specifies whether the recently proxied connections should be kept track of (in memory only).
+    pub enabled: bool,
+
+    /// Maximum number of the connections kept in the log.
+    pub retention: usize,
+
+    /// Specifies whether the destinations should be stored in plaintext rather than hashed.
+    pub plaintext_destinations: bool,
+}
+
+impl Default for ActivityLog {
+    fn default() -> Self {
+        ActivityLog {
+            enabled: false,
+            retention: DEFAULT_ACTIVITY_LOG_RETENTION,
+            plaintext_destinations: false,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct Socks5Debug {
@@ -168,6 +253,20 @@ pub struct Socks5Debug {
 
     /// Number of reply SURBs attached to each `Request::Send` message.
     pub per_request_surbs: u32,
+
+    /// Specifies whether the proxied connections should be multiplexed over a small set of
+    /// persistent transmission lanes rather than each of them getting a lane of its own.
+    /// This reduces the setup cost of the many short-lived connections typical of web browsing,
+    /// but the bytes sent are then accounted to the lanes rather than the individual connections.
+    pub connection_multiplexing: bool,
+
+    /// Maximum number of the lanes the connections are multiplexed over.
+    pub multiplexed_lanes: u32,
+
+    /// Number of reply SURBs attached to each `Request::Connect` message of a connection that got
+    /// assigned a lane that has already been used, as the provider should still have plenty of
+    /// reply SURBs left over from the previous connections.
+    pub warm_lane_connection_start_surbs: u32,
 }
 
 impl Default for Socks5Debug {
@@ -175,6 +274,9 @@ impl Default for Socks5Debug {
         Socks5Debug {
             connection_start_surbs: DEFAULT_CONNECTION_START_SURBS,
             per_request_surbs: DEFAULT_PER_REQUEST_SURBS,
+            connection_multiplexing: true,
+            multiplexed_lanes: DEFAULT_MULTIPLEXED_LANES,
+            warm_lane_connection_start_surbs: DEFAULT_WARM_LANE_CONNECTION_START_SURBS,
         }
     }
 }
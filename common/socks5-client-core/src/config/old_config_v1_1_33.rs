@@ -73,6 +73,8 @@ impl From<Socks5V1_1_33> for Socks5 {
             provider_interface_version: value.provider_interface_version,
             socks5_protocol_version: value.socks5_protocol_version,
             send_anonymously: value.send_anonymously,
+            authentication: None,
+            activity_log: Default::default(),
             socks5_debug: value.socks5_debug.into(),
         }
     }
@@ -93,6 +95,7 @@ impl From<Socks5DebugV1_1_33> for Socks5Debug {
         Socks5Debug {
             connection_start_surbs: value.connection_start_surbs,
             per_request_surbs: value.per_request_surbs,
+            ..Default::default()
         }
     }
 }
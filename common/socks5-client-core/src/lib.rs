@@ -4,8 +4,7 @@
 use crate::config::Config;
 use crate::error::Socks5ClientCoreError;
 use crate::socks::{
-    authentication::{AuthenticationMethods, Authenticator, User},
-    server::NymSocksServer,
+    activity::ConnectionActivityLog, authentication::Authenticator, server::NymSocksServer,
 };
 use futures::channel::mpsc;
 use futures::StreamExt;
@@ -51,6 +50,9 @@ pub struct StartedSocks5Client {
 
     /// Address of the started client
     pub address: Recipient,
+
+    /// Log of the recently proxied connections, if enabled in the config.
+    pub activity_log: ConnectionActivityLog,
 }
 
 pub struct NymClient<S> {
@@ -72,6 +74,9 @@ impl<S> NymClient<S>
 where
     S: MixnetClientStorage + 'static,
     S::ReplyStore: Send + Sync,
+    S::InboxStore: Send + Sync,
+    S::OutboxStore: Send + Sync,
+    S::GatewaysDetailsStore: Send + Sync,
     <S::ReplyStore as ReplyStorageBackend>::StorageError: Sync + Send,
     <S::CredentialStore as CredentialStorage>::StorageError: Send + Sync,
     <S::GatewaysDetailsStore as GatewaysDetailsStore>::StorageError: Sync + Send,
@@ -107,18 +112,17 @@ where
         self_address: Recipient,
         shutdown: TaskClient,
         packet_type: PacketType,
-    ) {
+    ) -> ConnectionActivityLog {
         info!("Starting socks5 listener...");
-        let auth_methods = vec![AuthenticationMethods::NoAuth as u8];
-        let allowed_users: Vec<User> = Vec::new();
-
         let ClientInput {
             connection_command_sender,
             input_sender,
+            ..
         } = client_input;
 
         let ClientOutput {
             received_buffer_request_sender,
+            ..
         } = client_output;
 
         let ClientState {
@@ -131,13 +135,15 @@ where
             .secondary_packet_size
             .unwrap_or(base_debug.traffic.primary_packet_size);
 
-        let authenticator = Authenticator::new(auth_methods, allowed_users);
+        let authenticator = Authenticator::from_config(socks5_config.authentication.as_ref());
+        let activity_log = ConnectionActivityLog::new(socks5_config.activity_log);
         let mut sphinx_socks = NymSocksServer::new(
             socks5_config.bind_address,
             authenticator,
             socks5_config.get_provider_mix_address(),
             self_address,
             shared_lane_queue_lengths,
+            activity_log.clone(),
             socks::client::Config::new(
                 packet_size,
                 socks5_config.provider_interface_version,
@@ -145,6 +151,7 @@ where
                 socks5_config.send_anonymously,
                 socks5_config.socks5_debug,
             ),
+            socks::lanes::LanePool::new(&socks5_config.socks5_debug),
             shutdown.clone(),
             packet_type,
         );
@@ -160,6 +167,7 @@ where
             },
             shutdown,
         );
+        activity_log
     }
 
     /// blocking version of `start` method. Will run forever (or until SIGINT is sent)
@@ -251,7 +259,7 @@ where
 
         info!("Running with {packet_type} packets",);
 
-        Self::start_socks5_listener(
+        let activity_log = Self::start_socks5_listener(
             &self.config.socks5,
             self.config.base.debug,
             client_input,
@@ -268,6 +276,7 @@ where
         Ok(StartedSocks5Client {
             shutdown_handle: started_client.task_handle,
             address: self_address,
+            activity_log,
         })
     }
 }
@@ -0,0 +1,178 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optional, purely in-memory, log of the recently proxied connections that lets users figure out
+//! which of their applications is misbehaving.
+//!
+//! By default the destinations are not stored in plaintext. Instead, they're hashed with a key
+//! generated when the log is created, so that connections to the same host can be correlated
+//! within the session whilst the hosts themselves can't be recovered from the log.
+
+use crate::config;
+use nym_socks5_requests::ConnectionId;
+use nym_sphinx::addressing::clients::Recipient;
+use std::collections::hash_map::RandomState;
+use std::collections::VecDeque;
+use std::hash::BuildHasher;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionRecord {
+    pub connection_id: ConnectionId,
+
+    /// The requested destination, either in plaintext or as a keyed hash of it.
+    pub destination: String,
+
+    /// Number of bytes (of the full mix packets) sent into the mixnet on behalf of the connection.
+    pub bytes_sent: u64,
+
+    /// Number of payload bytes received back from the mixnet.
+    pub bytes_received: u64,
+
+    pub duration: Duration,
+
+    /// The service provider the connection was proxied through.
+    pub service_provider: Recipient,
+
+    pub closed_at: SystemTime,
+}
+
+struct ActivityLogInner {
+    enabled: bool,
+    retention: usize,
+    hash_destinations: bool,
+    destination_key: RandomState,
+
+    // newest records are at the front
+    records: VecDeque<ConnectionRecord>,
+}
+
+impl ActivityLogInner {
+    fn truncate(&mut self) {
+        self.records.truncate(self.retention);
+    }
+}
+
+/// Shared handle to the log of the recently proxied connections.
+#[derive(Clone)]
+pub struct ConnectionActivityLog {
+    inner: Arc<Mutex<ActivityLogInner>>,
+}
+
+impl ConnectionActivityLog {
+    pub fn new(config: config::ActivityLog) -> Self {
+        ConnectionActivityLog {
+            inner: Arc::new(Mutex::new(ActivityLogInner {
+                enabled: config.enabled,
+                retention: config.retention,
+                hash_destinations: !config.plaintext_destinations,
+                destination_key: RandomState::new(),
+                records: VecDeque::new(),
+            })),
+        }
+    }
+
+    fn inner(&self) -> MutexGuard<'_, ActivityLogInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.inner().enabled
+    }
+
+    /// Enables or disables the log. Disabling it also removes all the existing records.
+    pub fn set_enabled(&self, enabled: bool) {
+        let mut inner = self.inner();
+        inner.enabled = enabled;
+        if !enabled {
+            inner.records.clear();
+        }
+    }
+
+    /// Changes the maximum number of records kept, dropping the oldest ones if needed.
+    pub fn set_retention(&self, retention: usize) {
+        let mut inner = self.inner();
+        inner.retention = retention;
+        inner.truncate();
+    }
+
+    pub fn clear(&self) {
+        self.inner().records.clear()
+    }
+
+    /// Returns the recorded connections, starting with the most recently closed one.
+    pub fn recent_connections(&self) -> Vec<ConnectionRecord> {
+        self.inner().records.iter().cloned().collect()
+    }
+
+    pub(crate) fn record(
+        &self,
+        connection_id: ConnectionId,
+        destination: &str,
+        bytes_sent: u64,
+        bytes_received: u64,
+        duration: Duration,
+        service_provider: Recipient,
+    ) {
+        let mut inner = self.inner();
+        if !inner.enabled || inner.retention == 0 {
+            return;
+        }
+
+        let destination = if inner.hash_destinations {
+            format!("{:016x}", inner.destination_key.hash_one(destination))
+        } else {
+            destination.to_string()
+        };
+
+        inner.records.push_front(ConnectionRecord {
+            connection_id,
+            destination,
+            bytes_sent,
+            bytes_received,
+            duration,
+            service_provider,
+            closed_at: SystemTime::now(),
+        });
+        inner.truncate();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider() -> Recipient {
+        Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap()
+    }
+
+    #[test]
+    fn destinations_are_hashed_and_old_records_dropped() {
+        let log = ConnectionActivityLog::new(config::ActivityLog {
+            enabled: true,
+            retention: 2,
+            plaintext_destinations: false,
+        });
+
+        for id in 0..3 {
+            log.record(
+                id,
+                "example.com:443",
+                100,
+                200,
+                Duration::from_secs(1),
+                provider(),
+            );
+        }
+
+        let recent = log.recent_connections();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].connection_id, 2);
+        assert_ne!(recent[0].destination, "example.com:443");
+        assert_eq!(recent[0].destination, recent[1].destination);
+
+        log.set_enabled(false);
+        assert!(log.recent_connections().is_empty());
+    }
+}
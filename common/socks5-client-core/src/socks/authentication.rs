@@ -1,3 +1,8 @@
+use crate::config::Socks5Authentication;
+
+/// Version of the username/password subnegotiation, as defined by RFC 1929
+pub(crate) const USER_PASS_AUTH_VERSION: u8 = 0x01;
+
 /// Client Authentication Methods
 pub(crate) enum AuthenticationMethods {
     /// No Authentication
@@ -21,6 +26,9 @@ pub struct User {
 /// and keeps a list of users who have access if that method is enabled.
 pub(crate) struct Authenticator {
     allowed_users: Vec<User>,
+
+    /// Credentials loaded from the config, for which only the password hash is known.
+    configured_credentials: Option<Socks5Authentication>,
     pub(crate) auth_methods: Vec<u8>,
 }
 
@@ -28,20 +36,51 @@ impl Authenticator {
     pub(crate) fn new(auth_methods: Vec<u8>, allowed_users: Vec<User>) -> Authenticator {
         Authenticator {
             allowed_users,
+            configured_credentials: None,
             auth_methods,
         }
     }
 
+    /// Creates an authenticator for the local listener. If credentials are provided,
+    /// every connecting client must present them, otherwise no authentication is required.
+    pub(crate) fn from_config(authentication: Option<&Socks5Authentication>) -> Authenticator {
+        match authentication {
+            Some(auth) => Authenticator {
+                allowed_users: Vec::new(),
+                configured_credentials: Some(auth.clone()),
+                auth_methods: vec![AuthenticationMethods::UserPass as u8],
+            },
+            None => Authenticator::new(vec![AuthenticationMethods::NoAuth as u8], Vec::new()),
+        }
+    }
+
+    /// Check whether connecting clients are required to present valid credentials
+    pub(crate) fn requires_authentication(&self) -> bool {
+        !self
+            .auth_methods
+            .contains(&(AuthenticationMethods::NoAuth as u8))
+    }
+
     /// Check if username + password pair are valid
-    pub fn is_allowed(&self, user: &User) -> bool {
-        if self
+    pub async fn is_allowed(&self, user: &User) -> bool {
+        if !self
             .auth_methods
             .contains(&(AuthenticationMethods::UserPass as u8))
         {
-            self.allowed_users.contains(user)
-        } else {
-            false
+            return false;
+        }
+        if self.allowed_users.contains(user) {
+            return true;
         }
+        let Some(credentials) = self.configured_credentials.clone() else {
+            return false;
+        };
+
+        // argon2 verification is deliberately expensive, so it must not block the executor
+        let user = user.clone();
+        tokio::task::spawn_blocking(move || credentials.verify(&user.username, &user.password))
+            .await
+            .unwrap_or(false)
     }
 }
 
@@ -73,11 +112,73 @@ mod tests {
             .contains(&(AuthenticationMethods::UserPass as u8)));
     }
 
+    #[test]
+    fn from_config_without_credentials_only_allows_no_auth() {
+        let authenticator = Authenticator::from_config(None);
+
+        assert_eq!(
+            authenticator.auth_methods,
+            vec![AuthenticationMethods::NoAuth as u8]
+        );
+        assert!(authenticator.allowed_users.is_empty());
+    }
+
+    #[tokio::test]
+    async fn from_config_with_credentials_requires_user_pass() {
+        let credentials = Socks5Authentication::new("foo", "bar").unwrap();
+        let authenticator = Authenticator::from_config(Some(&credentials));
+
+        assert_eq!(
+            authenticator.auth_methods,
+            vec![AuthenticationMethods::UserPass as u8]
+        );
+        assert!(
+            authenticator
+                .is_allowed(&User {
+                    username: "foo".to_string(),
+                    password: "bar".to_string(),
+                })
+                .await
+        );
+        assert!(
+            !authenticator
+                .is_allowed(&User {
+                    username: "foo".to_string(),
+                    password: "baz".to_string(),
+                })
+                .await
+        );
+    }
+
+    #[test]
+    fn configured_credentials_only_store_password_hash() {
+        let credentials = Socks5Authentication::new("foo", "bar").unwrap();
+        assert!(!credentials.password_hash.contains("bar"));
+
+        // salts are random, so hashing the same password twice yields different results
+        let other = Socks5Authentication::new("foo", "bar").unwrap();
+        assert_ne!(credentials.password_hash, other.password_hash);
+
+        assert!(credentials.verify("foo", "bar"));
+        assert!(other.verify("foo", "bar"));
+        assert!(!credentials.verify("foo", "baz"));
+        assert!(!credentials.verify("fooo", "bar"));
+    }
+
+    #[test]
+    fn malformed_password_hash_never_verifies() {
+        let credentials = Socks5Authentication {
+            username: "foo".to_string(),
+            password_hash: "bar".to_string(),
+        };
+        assert!(!credentials.verify("foo", "bar"));
+    }
+
     mod without_user_and_password_auth_enabled {
         use super::*;
 
-        #[test]
-        fn user_pass_authentication_fails() {
+        #[tokio::test]
+        async fn user_pass_authentication_fails() {
             let auth_methods: Vec<u8> = Vec::new(); // it's empty
 
             let admin = User {
@@ -89,7 +190,7 @@ mod tests {
 
             let authenticator = Authenticator::new(auth_methods, allowed_users);
 
-            assert!(!authenticator.is_allowed(&admin));
+            assert!(!authenticator.is_allowed(&admin).await);
         }
     }
 
@@ -97,8 +198,8 @@ mod tests {
     mod with_user_and_password_auth_enabled {
         use super::*;
 
-        #[test]
-        fn allowed_user_passes_authentication_check() {
+        #[tokio::test]
+        async fn allowed_user_passes_authentication_check() {
             let auth_methods = vec![AuthenticationMethods::UserPass as u8];
 
             let admin = User {
@@ -110,11 +211,11 @@ mod tests {
 
             let authenticator = Authenticator::new(auth_methods, allowed_users);
 
-            assert!(authenticator.is_allowed(&admin));
+            assert!(authenticator.is_allowed(&admin).await);
         }
 
-        #[test]
-        fn disallowed_user_fails_authentication_check() {
+        #[tokio::test]
+        async fn disallowed_user_fails_authentication_check() {
             let auth_methods = vec![AuthenticationMethods::UserPass as u8];
 
             let bad_user = User {
@@ -126,7 +227,7 @@ mod tests {
 
             let authenticator = Authenticator::new(auth_methods, allowed_users);
 
-            assert!(!authenticator.is_allowed(&bad_user));
+            assert!(!authenticator.is_allowed(&bad_user).await);
         }
     }
 }
@@ -1,6 +1,8 @@
 #![forbid(unsafe_code)]
 
-use super::authentication::{AuthenticationMethods, Authenticator, User};
+use super::activity::ConnectionActivityLog;
+use super::authentication::{AuthenticationMethods, Authenticator, User, USER_PASS_AUTH_VERSION};
+use super::lanes::{LaneAssignment, LanePool};
 use super::request::{SocksCommand, SocksRequest};
 use super::types::{ResponseCodeV4, ResponseCodeV5, SocksProxyError};
 use super::{SocksVersion, RESERVED, SOCKS4_VERSION, SOCKS5_VERSION};
@@ -20,13 +22,14 @@ use nym_socks5_requests::{
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::params::PacketSize;
 use nym_sphinx::params::PacketType;
-use nym_task::connections::{LaneQueueLengths, TransmissionLane};
+use nym_task::connections::{LaneQueueLengths, MessagePriority, TransmissionLane};
 use nym_task::TaskClient;
 use pin_project::pin_project;
 use rand::RngCore;
 use std::io;
 use std::net::SocketAddr;
 use std::pin::Pin;
+use std::time::Instant;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
 use tokio::net::TcpStream;
 
@@ -139,6 +142,7 @@ pub(crate) struct Config {
     socks5_protocol_version: Socks5ProtocolVersion,
     use_surbs_for_responses: bool,
     connection_start_surbs: u32,
+    warm_lane_connection_start_surbs: u32,
     per_request_surbs: u32,
 }
 
@@ -156,6 +160,7 @@ impl Config {
             socks5_protocol_version,
             use_surbs_for_responses,
             connection_start_surbs: debug_config.connection_start_surbs,
+            warm_lane_connection_start_surbs: debug_config.warm_lane_connection_start_surbs,
             per_request_surbs: debug_config.per_request_surbs,
         }
     }
@@ -181,10 +186,13 @@ pub(crate) struct SocksClient {
     socks_version: Option<SocksVersion>,
     input_sender: InputMessageSender,
     connection_id: ConnectionId,
+    lane_pool: LanePool,
+    lane: LaneAssignment,
     service_provider: Recipient,
     self_address: Recipient,
     started_proxy: bool,
     lane_queue_lengths: LaneQueueLengths,
+    activity_log: ConnectionActivityLog,
     shutdown_listener: TaskClient,
     packet_type: Option<PacketType>,
 }
@@ -192,6 +200,7 @@ pub(crate) struct SocksClient {
 impl Drop for SocksClient {
     fn drop(&mut self) {
         debug!("Connection {} is getting closed", self.connection_id);
+        self.lane_pool.release(self.lane);
         // if we never managed to start a proxy, the entry will not exist in the controller
         if self.started_proxy {
             self.controller_sender
@@ -214,6 +223,8 @@ impl SocksClient {
         controller_sender: ControllerSender,
         self_address: &Recipient,
         lane_queue_lengths: LaneQueueLengths,
+        lane_pool: LanePool,
+        activity_log: ConnectionActivityLog,
         mut shutdown_listener: TaskClient,
         packet_type: Option<PacketType>,
     ) -> Self {
@@ -221,11 +232,14 @@ impl SocksClient {
         shutdown_listener.disarm();
 
         let connection_id = Self::generate_random();
+        let lane = lane_pool.acquire(connection_id);
 
         SocksClient {
             config,
             controller_sender,
             connection_id,
+            lane_pool,
+            lane,
             stream: StreamState::Available(stream),
             auth_nmethods: 0,
             socks_version: None,
@@ -235,6 +249,7 @@ impl SocksClient {
             self_address: *self_address,
             started_proxy: false,
             lane_queue_lengths,
+            activity_log,
             shutdown_listener,
             packet_type,
         }
@@ -245,6 +260,20 @@ impl SocksClient {
         rng.next_u64()
     }
 
+    fn transmission_lane(&self) -> TransmissionLane {
+        TransmissionLane::ConnectionId(self.lane.lane_id)
+    }
+
+    fn connection_start_surbs(&self) -> u32 {
+        // the reply surbs are not tied to particular connections, so if the lane has already been
+        // used, the provider is likely to still have the ones sent with the previous connections
+        if self.lane.warm {
+            self.config.warm_lane_connection_start_surbs
+        } else {
+            self.config.connection_start_surbs
+        }
+    }
+
     pub async fn send_error(&mut self, err: SocksProxyError) -> Result<(), SocksProxyError> {
         let error_text = format!("{err}");
         let Some(ref version) = self.socks_version else {
@@ -324,6 +353,18 @@ impl SocksClient {
             }
         };
 
+        // SOCKS4 has no way of carrying credentials, so it can't be used if authentication is required.
+        // the request is read in full before replying so that the rejection wouldn't get lost
+        // in a connection reset caused by unread data
+        if self.socks_version == Some(SocksVersion::V4)
+            && self.authenticator.requires_authentication()
+        {
+            warn!("Init: rejecting SOCKS4 connection as authentication is required");
+            SocksRequest::from_stream_socks4(&mut self.stream).await?;
+            self.reject_socks4().await?;
+            return self.shutdown().await;
+        }
+
         if self.socks_version == Some(SocksVersion::V5) {
             let mut auth = [0u8];
             self.stream
@@ -351,10 +392,11 @@ impl SocksClient {
         let input_message = InputMessage::new_anonymous(
             self.service_provider,
             msg.into_bytes(),
-            self.config.connection_start_surbs,
-            TransmissionLane::ConnectionId(self.connection_id),
+            self.connection_start_surbs(),
+            self.transmission_lane(),
             self.packet_type,
-        );
+        )
+        .with_priority(MessagePriority::High);
         self.input_sender
             .send(input_message)
             .await
@@ -375,9 +417,10 @@ impl SocksClient {
         let input_message = InputMessage::new_regular(
             self.service_provider,
             msg.into_bytes(),
-            TransmissionLane::ConnectionId(self.connection_id),
+            self.transmission_lane(),
             self.packet_type,
-        );
+        )
+        .with_priority(MessagePriority::High);
         self.input_sender
             .send(input_message)
             .await
@@ -408,6 +451,7 @@ impl SocksClient {
         let local_stream_remote = peer_addr.to_string();
 
         let connection_id = self.connection_id;
+        let lane = self.transmission_lane();
         let input_sender = self.input_sender.clone();
         let anonymous = self.config.use_surbs_for_responses;
         let per_request_surbs = self.config.per_request_surbs;
@@ -415,6 +459,7 @@ impl SocksClient {
 
         let recipient = self.service_provider;
         let packet_type = self.packet_type;
+        let max_interactive_size = self.config.biggest_packet_size.plaintext_size();
         let (stream, _) = ProxyRunner::new(
             stream,
             local_stream_remote,
@@ -428,15 +473,22 @@ impl SocksClient {
             Some(self.lane_queue_lengths.clone()),
             self.shutdown_listener.clone(),
         )
+        .with_lane(self.lane.lane_id)
         .run(move |socket_data| {
-            let lane = TransmissionLane::ConnectionId(socket_data.header.connection_id);
+            // data fitting into a single packet, such as keystrokes or small requests, is likely
+            // to be interactive, whereas larger reads indicate bulk transfers
+            let priority = if socket_data.data.len() <= max_interactive_size {
+                MessagePriority::High
+            } else {
+                MessagePriority::Normal
+            };
             let provider_request =
                 Socks5Request::new_send(request_version.provider_protocol, socket_data);
             let provider_message = Socks5ProviderRequest::new_provider_data(
                 request_version.provider_interface,
                 provider_request,
             );
-            if anonymous {
+            let message = if anonymous {
                 InputMessage::new_anonymous(
                     recipient,
                     provider_message.into_bytes(),
@@ -451,7 +503,8 @@ impl SocksClient {
                     lane,
                     packet_type,
                 )
-            }
+            };
+            message.with_priority(priority)
         })
         .await
         .into_inner();
@@ -496,15 +549,18 @@ impl SocksClient {
                     .unwrap();
 
                 info!(
-                    "Starting proxy for {} (id: {})",
+                    "Starting proxy for {} (id: {}, lane: {})",
                     remote_address.clone(),
-                    self.connection_id
+                    self.connection_id,
+                    self.lane.lane_id
                 );
+                let started = Instant::now();
                 self.run_proxy(mix_receiver, remote_address.clone()).await;
                 info!(
                     "Proxy for {} is finished (id: {})",
                     remote_address, self.connection_id
                 );
+                self.record_activity(&remote_address, started);
             }
 
             SocksCommand::Bind => return Err(SocksProxyError::BindNotSupported), // not handled
@@ -514,6 +570,21 @@ impl SocksClient {
         Ok(())
     }
 
+    fn record_activity(&self, remote_address: &str, started: Instant) {
+        let bandwidth = self
+            .lane_queue_lengths
+            .bandwidth(self.connection_id)
+            .unwrap_or_default();
+        self.activity_log.record(
+            self.connection_id,
+            remote_address,
+            bandwidth.bytes_sent,
+            bandwidth.bytes_received,
+            started.elapsed(),
+            self.service_provider,
+        );
+    }
+
     /// Writes a Socks5 header back to the requesting client's TCP stream,
     /// basically saying "I acknowledge your request and am dealing with it".
     async fn acknowledge_socks5(&mut self) {
@@ -551,6 +622,23 @@ impl SocksClient {
             .unwrap();
     }
 
+    /// Writes a Socks4 rejection back to the requesting client's TCP stream.
+    async fn reject_socks4(&mut self) -> Result<(), SocksProxyError> {
+        self.stream
+            .write_all(&[
+                0, // reply version
+                ResponseCodeV4::RequestRejected as u8,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+            ])
+            .await
+            .map_err(|source| SocksProxyError::SocketWriteError { source })
+    }
+
     /// Authenticate the incoming request. Each request is checked for its
     /// authentication method. A user/password request will extract the
     /// username and password from the stream, then check with the Authenticator
@@ -604,6 +692,10 @@ impl SocksClient {
                 .map_err(|source| SocksProxyError::SocketReadError { source })?;
 
             // debug!("Auth Header: [{}, {}]", header[0], header[1]);
+            if header[0] != USER_PASS_AUTH_VERSION {
+                self.shutdown().await?;
+                return Err(SocksProxyError::UnsupportedAuthVersion { version: header[0] });
+            }
 
             // Username parsing
             let ulen = header[1];
@@ -636,16 +728,16 @@ impl SocksClient {
             };
 
             // Authenticate passwords
-            if self.authenticator.is_allowed(&user) {
+            if self.authenticator.is_allowed(&user).await {
                 debug!("Access Granted. User: {}", user.username);
-                let response = [1, ResponseCodeV5::Success as u8];
+                let response = [USER_PASS_AUTH_VERSION, ResponseCodeV5::Success as u8];
                 self.stream
                     .write_all(&response)
                     .await
                     .map_err(|source| SocksProxyError::SocketWriteError { source })?;
             } else {
                 debug!("Access Denied. User: {}", user.username);
-                let response = [1, ResponseCodeV5::Failure as u8];
+                let response = [USER_PASS_AUTH_VERSION, ResponseCodeV5::Failure as u8];
                 self.stream
                     .write_all(&response)
                     .await
@@ -653,6 +745,9 @@ impl SocksClient {
 
                 // Shutdown
                 self.shutdown().await?;
+                return Err(SocksProxyError::AuthenticationFailure {
+                    username: user.username,
+                });
             }
 
             Ok(())
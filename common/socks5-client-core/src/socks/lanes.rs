@@ -0,0 +1,165 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Assignment of the proxied connections to the transmission lanes of the client.
+//!
+//! By default, the connections are multiplexed over a bounded set of persistent lanes, while
+//! the connection ids keep identifying the individual streams within them, so that the many
+//! short-lived connections opened by a browser don't each have to set up a lane of their own.
+//! Alternatively, every connection can still get a dedicated lane that is discarded
+//! once the connection is closed.
+
+use crate::config;
+use log::*;
+use nym_socks5_requests::ConnectionId;
+use rand::RngCore;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LaneAssignment {
+    /// Id of the `TransmissionLane::ConnectionId` the connection is going to be sent over.
+    pub(crate) lane_id: ConnectionId,
+
+    /// Indicates whether the lane has already been used by some other connection.
+    pub(crate) warm: bool,
+}
+
+struct SharedLanes {
+    max_lanes: usize,
+
+    // number of connections currently using each of the lanes
+    lanes: HashMap<ConnectionId, usize>,
+}
+
+impl SharedLanes {
+    fn acquire(&mut self) -> LaneAssignment {
+        let least_loaded = self
+            .lanes
+            .iter()
+            .min_by_key(|(_, streams)| **streams)
+            .map(|(lane_id, streams)| (*lane_id, *streams));
+
+        let assignment = match least_loaded {
+            Some((lane_id, streams)) if streams == 0 || self.lanes.len() >= self.max_lanes => {
+                LaneAssignment {
+                    lane_id,
+                    warm: true,
+                }
+            }
+            _ => {
+                let lane_id = rand::rngs::OsRng.next_u64();
+                debug!("opening new shared lane {lane_id}");
+                LaneAssignment {
+                    lane_id,
+                    warm: false,
+                }
+            }
+        };
+
+        *self.lanes.entry(assignment.lane_id).or_default() += 1;
+        assignment
+    }
+
+    fn release(&mut self, lane_id: ConnectionId) {
+        match self.lanes.get_mut(&lane_id) {
+            Some(streams) => *streams = streams.saturating_sub(1),
+            None => warn!("attempted to release unknown shared lane {lane_id}"),
+        }
+    }
+}
+
+/// Shared handle for assigning the lanes to the proxied connections.
+#[derive(Clone)]
+pub(crate) struct LanePool {
+    // if not set, each connection uses its own lane
+    shared: Option<Arc<Mutex<SharedLanes>>>,
+}
+
+impl LanePool {
+    pub(crate) fn new(config: &config::Socks5Debug) -> Self {
+        let shared = (config.connection_multiplexing && config.multiplexed_lanes > 0).then(|| {
+            Arc::new(Mutex::new(SharedLanes {
+                max_lanes: config.multiplexed_lanes as usize,
+                lanes: HashMap::new(),
+            }))
+        });
+        LanePool { shared }
+    }
+
+    fn shared(&self) -> Option<MutexGuard<'_, SharedLanes>> {
+        self.shared
+            .as_ref()
+            .map(|shared| shared.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Picks the lane for a new connection, preferring the idle lanes and opening new ones
+    /// until the limit is reached, after which the least loaded lane is shared.
+    pub(crate) fn acquire(&self, connection_id: ConnectionId) -> LaneAssignment {
+        match self.shared() {
+            Some(mut shared) => shared.acquire(),
+            None => LaneAssignment {
+                lane_id: connection_id,
+                warm: false,
+            },
+        }
+    }
+
+    /// Informs the pool the connection assigned to the lane has been closed.
+    /// Note that shared lanes are kept around for reuse even once they're no longer used.
+    pub(crate) fn release(&self, assignment: LaneAssignment) {
+        if let Some(mut shared) = self.shared() {
+            shared.release(assignment.lane_id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(max_lanes: u32) -> LanePool {
+        LanePool::new(&config::Socks5Debug {
+            multiplexed_lanes: max_lanes,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn connections_are_spread_over_bounded_lanes() {
+        let pool = pool(2);
+
+        let first = pool.acquire(1);
+        let second = pool.acquire(2);
+        assert!(!first.warm && !second.warm);
+        assert_ne!(first.lane_id, second.lane_id);
+
+        // the limit has been reached, so the lanes have to be shared
+        let third = pool.acquire(3);
+        assert!(third.warm);
+        assert!([first.lane_id, second.lane_id].contains(&third.lane_id));
+
+        // idle lanes get reused rather than new ones being opened
+        pool.release(first);
+        pool.release(second);
+        pool.release(third);
+        let fourth = pool.acquire(4);
+        assert!(fourth.warm);
+        assert!([first.lane_id, second.lane_id].contains(&fourth.lane_id));
+    }
+
+    #[test]
+    fn dedicated_lanes_follow_connection_ids() {
+        let pool = LanePool::new(&config::Socks5Debug {
+            connection_multiplexing: false,
+            ..Default::default()
+        });
+        assert_eq!(
+            pool.acquire(42),
+            LaneAssignment {
+                lane_id: 42,
+                warm: false
+            }
+        );
+    }
+}
@@ -2,8 +2,10 @@
 
 use self::types::SocksProxyError;
 
+pub mod activity;
 pub mod authentication;
 pub(crate) mod client;
+pub(crate) mod lanes;
 pub(crate) mod mixnet_responses;
 mod request;
 pub mod server;
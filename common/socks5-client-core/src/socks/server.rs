@@ -1,7 +1,8 @@
 use crate::error::Socks5ClientCoreError;
 
 use super::{
-    authentication::Authenticator, client::SocksClient, mixnet_responses::MixnetResponseListener,
+    activity::ConnectionActivityLog, authentication::Authenticator, client::SocksClient,
+    lanes::LanePool, mixnet_responses::MixnetResponseListener,
 };
 use crate::socks::client;
 use log::*;
@@ -25,6 +26,8 @@ pub struct NymSocksServer {
     self_address: Recipient,
     client_config: client::Config,
     lane_queue_lengths: LaneQueueLengths,
+    lane_pool: LanePool,
+    activity_log: ConnectionActivityLog,
     shutdown: TaskClient,
     packet_type: PacketType,
 }
@@ -38,7 +41,9 @@ impl NymSocksServer {
         service_provider: Recipient,
         self_address: Recipient,
         lane_queue_lengths: LaneQueueLengths,
+        activity_log: ConnectionActivityLog,
         client_config: client::Config,
+        lane_pool: LanePool,
         shutdown: TaskClient,
         packet_type: PacketType,
     ) -> Self {
@@ -50,6 +55,8 @@ impl NymSocksServer {
             self_address,
             client_config,
             lane_queue_lengths,
+            lane_pool,
+            activity_log,
             shutdown,
             packet_type,
         }
@@ -105,6 +112,8 @@ impl NymSocksServer {
                         controller_sender.clone(),
                         &self.self_address,
                         self.lane_queue_lengths.clone(),
+                        self.lane_pool.clone(),
+                        self.activity_log.clone(),
                         self.shutdown.clone(),
                         Some(self.packet_type)
                     );
@@ -75,6 +75,12 @@ pub enum SocksProxyError {
         source: FromUtf8Error,
     },
 
+    #[error("{version} is not a supported version of the username/password authentication")]
+    UnsupportedAuthVersion { version: u8 },
+
+    #[error("user '{username}' failed to authenticate")]
+    AuthenticationFailure { username: String },
+
     #[error(transparent)]
     Socks5ResponseFailure(#[from] ResponseCodeV5),
 
@@ -17,16 +17,11 @@ use std::time::Duration;
 use tokio::select;
 use tokio::{net::tcp::OwnedReadHalf, sync::Notify, time::sleep};
 
-async fn wait_until_lane_empty(lane_queue_lengths: &Option<LaneQueueLengths>, connection_id: u64) {
+async fn wait_until_lane_empty(lane_queue_lengths: &Option<LaneQueueLengths>, lane_id: u64) {
     if let Some(lane_queue_lengths) = lane_queue_lengths {
         if tokio::time::timeout(
             Duration::from_secs(4 * 60),
-            wait_for_lane(
-                lane_queue_lengths,
-                connection_id,
-                0,
-                Duration::from_millis(500),
-            ),
+            wait_for_lane(lane_queue_lengths, lane_id, 0, Duration::from_millis(500)),
         )
         .await
         .is_err()
@@ -36,16 +31,13 @@ async fn wait_until_lane_empty(lane_queue_lengths: &Option<LaneQueueLengths>, co
     }
 }
 
-async fn wait_until_lane_almost_empty(
-    lane_queue_lengths: &Option<LaneQueueLengths>,
-    connection_id: u64,
-) {
+async fn wait_until_lane_almost_empty(lane_queue_lengths: &Option<LaneQueueLengths>, lane_id: u64) {
     if let Some(lane_queue_lengths) = lane_queue_lengths {
         if tokio::time::timeout(
             Duration::from_secs(4 * 60),
             wait_for_lane(
                 lane_queue_lengths,
-                connection_id,
+                lane_id,
                 // With only 30 packets in the queue, we treat it as basically empty.
                 30,
                 Duration::from_millis(100),
@@ -61,11 +53,11 @@ async fn wait_until_lane_almost_empty(
 
 async fn wait_for_lane(
     lane_queue_lengths: &LaneQueueLengths,
-    connection_id: u64,
+    lane_id: u64,
     queue_length_threshold: usize,
     sleep_duration: Duration,
 ) {
-    while let Some(queue) = lane_queue_lengths.get(&TransmissionLane::ConnectionId(connection_id)) {
+    while let Some(queue) = lane_queue_lengths.get(&TransmissionLane::ConnectionId(lane_id)) {
         if queue > queue_length_threshold {
             sleep(sleep_duration).await;
         } else {
@@ -74,10 +66,12 @@ async fn wait_for_lane(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn run_inbound<F, S>(
     mut reader: OwnedReadHalf,
     mut message_sender: OrderedMessageSender<F, S>,
     connection_id: ConnectionId,
+    lane_id: ConnectionId,
     available_plaintext_per_mix_packet: usize,
     shutdown_notify: Arc<Notify>,
     lane_queue_lengths: Option<LaneQueueLengths>,
@@ -111,7 +105,7 @@ where
             // each connection task and then send the chunks to the `OutQueueControl` directly.
             sleep(Duration::from_secs(2))
         })
-        .then(|_| wait_until_lane_empty(&lane_queue_lengths, connection_id));
+        .then(|_| wait_until_lane_empty(&lane_queue_lengths, lane_id));
     tokio::pin!(closing_future);
 
     // Once we are closed, we need to disable the branch in the select that reads from the socket.
@@ -148,7 +142,7 @@ where
             // Read the next data when there is space in the lane.
             // The purpose of chaining the wait here is that it makes sure we can cancel the
             // waiting on connection close.
-            read_data = wait_until_lane_almost_empty(&lane_queue_lengths, connection_id)
+            read_data = wait_until_lane_almost_empty(&lane_queue_lengths, lane_id)
                 .then(|_| available_reader.next()), if !we_are_closed =>
             {
                 let processed = message_sender.process_data(read_data);
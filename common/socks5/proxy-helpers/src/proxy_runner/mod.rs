@@ -52,6 +52,9 @@ pub struct ProxyRunner<S> {
     local_destination_address: String,
     remote_source_address: String,
     connection_id: ConnectionId,
+
+    // id of the lane the data is sent over, which might be shared with other connections
+    lane_id: ConnectionId,
     lane_queue_lengths: Option<LaneQueueLengths>,
 
     available_plaintext_per_mix_packet: usize,
@@ -83,12 +86,21 @@ where
             local_destination_address,
             remote_source_address,
             connection_id,
+            lane_id: connection_id,
             lane_queue_lengths,
             available_plaintext_per_mix_packet,
             shutdown_listener,
         }
     }
 
+    /// Makes the runner wait on the queue of the specified lane rather than the one
+    /// identified by the connection id, for when the connections are multiplexed over shared lanes.
+    #[must_use]
+    pub fn with_lane(mut self, lane_id: ConnectionId) -> Self {
+        self.lane_id = lane_id;
+        self
+    }
+
     // The `adapter_fn` is used to transform whatever was read into appropriate
     // request/response as required by entity running particular side of the proxy.
     pub async fn run<F>(mut self, adapter_fn: F) -> Self
@@ -110,6 +122,7 @@ where
             read_half,
             ordered_sender,
             self.connection_id,
+            self.lane_id,
             self.available_plaintext_per_mix_packet,
             Arc::clone(&shutdown_notify),
             self.lane_queue_lengths.clone(),
@@ -123,6 +136,7 @@ where
             self.mix_receiver.take().unwrap(),
             self.connection_id,
             shutdown_notify,
+            self.lane_queue_lengths.clone(),
             self.shutdown_listener.clone(),
         );
 
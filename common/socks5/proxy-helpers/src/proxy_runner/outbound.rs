@@ -7,6 +7,7 @@ use futures::FutureExt;
 use futures::StreamExt;
 use log::*;
 use nym_socks5_requests::ConnectionId;
+use nym_task::connections::LaneQueueLengths;
 use nym_task::TaskClient;
 use std::{sync::Arc, time::Duration};
 use tokio::io::AsyncWriteExt;
@@ -51,6 +52,7 @@ pub(super) async fn run_outbound(
     mut mix_receiver: ConnectionReceiver,
     connection_id: ConnectionId,
     shutdown_notify: Arc<Notify>,
+    lane_queue_lengths: Option<LaneQueueLengths>,
     mut shutdown_listener: TaskClient,
 ) -> (OwnedWriteHalf, ConnectionReceiver) {
     let shutdown_future = shutdown_notify.notified().then(|_| sleep(SHUTDOWN_TIMEOUT));
@@ -62,6 +64,9 @@ pub(super) async fn run_outbound(
         select! {
             connection_message = mix_receiver.next() => {
                 if let Some(connection_message) = connection_message {
+                    if let Some(lane_queue_lengths) = &lane_queue_lengths {
+                        lane_queue_lengths.record_received(connection_id, connection_message.payload.len());
+                    }
                     if deal_with_message(connection_message, &mut writer, &local_destination_address, &remote_source_address, connection_id).await {
                         break;
                     }
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use futures::channel::mpsc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub type ConnectionId = u64;
 
@@ -17,6 +17,15 @@ pub enum TransmissionLane {
     ConnectionId(ConnectionId),
 }
 
+/// Scheduling priority of a transmission lane. Packets from the high priority lanes, such as
+/// the interactive traffic, are sent out ahead of the ones from the normal (bulk) lanes.
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub enum MessagePriority {
+    High,
+    #[default]
+    Normal,
+}
+
 /// Used by the connection controller to report current state for client connections.
 pub type ConnectionCommandSender = mpsc::UnboundedSender<ConnectionCommand>;
 pub type ConnectionCommandReceiver = mpsc::UnboundedReceiver<ConnectionCommand>;
@@ -27,8 +36,69 @@ pub enum ConnectionCommand {
     Close(ConnectionId),
 }
 
+/// Amount of data transferred over a single client connection.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConnectionBandwidth {
+    /// Number of bytes (of the full mix packets) sent into the mixnet.
+    pub bytes_sent: u64,
+
+    /// Number of mix packets sent into the mixnet.
+    pub packets_sent: u64,
+
+    /// Number of payload bytes received back from the mixnet.
+    pub bytes_received: u64,
+}
+
+impl std::ops::AddAssign for ConnectionBandwidth {
+    fn add_assign(&mut self, rhs: Self) {
+        self.bytes_sent += rhs.bytes_sent;
+        self.packets_sent += rhs.packets_sent;
+        self.bytes_received += rhs.bytes_received;
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    /// Number of packets still waiting to be sent out.
+    pub queue_length: usize,
+
+    pub bandwidth: ConnectionBandwidth,
+
+    /// Maximum sending rate (in bytes per second) imposed on the connection, if any.
+    pub rate_limit: Option<u64>,
+}
+
+/// Snapshot of the state of all the client connections.
+#[derive(Clone, Debug, Default)]
+pub struct ClientStats {
+    /// State of the currently open connections.
+    pub connections: HashMap<ConnectionId, ConnectionStats>,
+
+    /// Combined bandwidth used by all the connections that have already been closed.
+    pub closed_connections: ConnectionBandwidth,
+}
+
+impl ClientStats {
+    pub fn total_bytes_sent(&self) -> u64 {
+        self.connections
+            .values()
+            .map(|stats| stats.bandwidth.bytes_sent)
+            .sum::<u64>()
+            + self.closed_connections.bytes_sent
+    }
+
+    pub fn total_bytes_received(&self) -> u64 {
+        self.connections
+            .values()
+            .map(|stats| stats.bandwidth.bytes_received)
+            .sum::<u64>()
+            + self.closed_connections.bytes_received
+    }
+}
+
 // The `OutQueueControl` publishes the backlog per lane, primarily so that upstream can slow down
-// if needed.
+// if needed. It also keeps track of the bandwidth used by each connection and of the rate limits
+// the upstream might have imposed on them.
 #[derive(Clone, Debug)]
 pub struct LaneQueueLengths(std::sync::Arc<std::sync::Mutex<LaneQueueLengthsInner>>);
 
@@ -37,6 +107,10 @@ impl LaneQueueLengths {
         LaneQueueLengths(std::sync::Arc::new(std::sync::Mutex::new(
             LaneQueueLengthsInner {
                 map: HashMap::new(),
+                bandwidth: HashMap::new(),
+                closed_bandwidth: ConnectionBandwidth::default(),
+                rate_limits: HashMap::new(),
+                high_priority: HashSet::new(),
             },
         )))
     }
@@ -67,6 +141,140 @@ impl LaneQueueLengths {
             }
         }
     }
+
+    /// Returns the number of packets waiting to be sent across all the lanes.
+    pub fn total(&self) -> usize {
+        match self.0.lock() {
+            Ok(inner) => inner.values().sum(),
+            Err(err) => {
+                log::warn!("Failed to get lane queue lengths: {err}");
+                0
+            }
+        }
+    }
+
+    /// Records a packet of `bytes` size being sent into the mixnet on behalf of the connection.
+    pub fn record_sent(&self, connection_id: ConnectionId, bytes: usize) {
+        match self.0.lock() {
+            Ok(mut inner) => {
+                let bandwidth = inner.bandwidth.entry(connection_id).or_default();
+                bandwidth.bytes_sent += bytes as u64;
+                bandwidth.packets_sent += 1;
+            }
+            Err(err) => log::warn!("Failed to record sent bandwidth: {err}"),
+        }
+    }
+
+    /// Records `bytes` of data being received from the mixnet for the connection.
+    pub fn record_received(&self, connection_id: ConnectionId, bytes: usize) {
+        match self.0.lock() {
+            Ok(mut inner) => {
+                inner
+                    .bandwidth
+                    .entry(connection_id)
+                    .or_default()
+                    .bytes_received += bytes as u64
+            }
+            Err(err) => log::warn!("Failed to record received bandwidth: {err}"),
+        }
+    }
+
+    pub fn bandwidth(&self, connection_id: ConnectionId) -> Option<ConnectionBandwidth> {
+        match self.0.lock() {
+            Ok(inner) => inner.bandwidth.get(&connection_id).copied(),
+            Err(err) => {
+                log::warn!("Failed to get connection bandwidth: {err}");
+                None
+            }
+        }
+    }
+
+    /// Limits the rate (in bytes per second) at which the packets from the lane are sent out.
+    /// Setting it to `None` removes the limit.
+    pub fn set_rate_limit(&self, lane: TransmissionLane, bytes_per_second: Option<u64>) {
+        match self.0.lock() {
+            Ok(mut inner) => {
+                if let Some(limit) = bytes_per_second {
+                    inner.rate_limits.insert(lane, limit);
+                } else {
+                    inner.rate_limits.remove(&lane);
+                }
+            }
+            Err(err) => log::warn!("Failed to set lane rate limit: {err}"),
+        }
+    }
+
+    /// Returns the rate limit (in bytes per second) imposed on the lane, if any.
+    pub fn rate_limit(&self, lane: &TransmissionLane) -> Option<u64> {
+        self.with_rate_limits(|limits| limits.get(lane).copied())
+    }
+
+    /// Inspects the rate limits of all the lanes without copying them out of the lock.
+    pub fn with_rate_limits<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce(&HashMap<TransmissionLane, u64>) -> T,
+    {
+        match self.0.lock() {
+            Ok(inner) => f(&inner.rate_limits),
+            Err(err) => {
+                log::warn!("Failed to get lane rate limits: {err}");
+                f(&HashMap::new())
+            }
+        }
+    }
+
+    /// Sets the scheduling priority of the lane, as requested by the most recent message sent on it.
+    pub fn set_priority(&self, lane: TransmissionLane, priority: MessagePriority) {
+        match self.0.lock() {
+            Ok(mut inner) => match priority {
+                MessagePriority::High => {
+                    inner.high_priority.insert(lane);
+                }
+                MessagePriority::Normal => {
+                    inner.high_priority.remove(&lane);
+                }
+            },
+            Err(err) => log::warn!("Failed to set lane priority: {err}"),
+        }
+    }
+
+    pub fn high_priority_lanes(&self) -> HashSet<TransmissionLane> {
+        match self.0.lock() {
+            Ok(inner) => inner.high_priority.clone(),
+            Err(err) => {
+                log::warn!("Failed to get lane priorities: {err}");
+                HashSet::new()
+            }
+        }
+    }
+
+    /// Removes all the information kept about the (closed) connection.
+    /// The bandwidth it has used is still accounted for in the closed connections' total.
+    pub fn remove_connection(&self, connection_id: ConnectionId) {
+        match self.0.lock() {
+            Ok(mut inner) => {
+                let lane = TransmissionLane::ConnectionId(connection_id);
+                inner.map.remove(&lane);
+                inner.rate_limits.remove(&lane);
+                inner.high_priority.remove(&lane);
+                if let Some(bandwidth) = inner.bandwidth.remove(&connection_id) {
+                    inner.closed_bandwidth += bandwidth;
+                }
+            }
+            Err(err) => log::warn!("Failed to remove connection: {err}"),
+        }
+    }
+
+    /// Returns the current state of all the known client connections.
+    pub fn client_stats(&self) -> ClientStats {
+        match self.0.lock() {
+            Ok(inner) => inner.client_stats(),
+            Err(err) => {
+                log::warn!("Failed to get client stats: {err}");
+                ClientStats::default()
+            }
+        }
+    }
 }
 
 impl Default for LaneQueueLengths {
@@ -86,6 +294,10 @@ impl std::ops::Deref for LaneQueueLengths {
 #[derive(Debug)]
 pub struct LaneQueueLengthsInner {
     pub map: HashMap<TransmissionLane, usize>,
+    pub bandwidth: HashMap<ConnectionId, ConnectionBandwidth>,
+    pub closed_bandwidth: ConnectionBandwidth,
+    pub rate_limits: HashMap<TransmissionLane, u64>,
+    pub high_priority: HashSet<TransmissionLane>,
 }
 
 impl LaneQueueLengthsInner {
@@ -103,4 +315,67 @@ impl LaneQueueLengthsInner {
     {
         self.map.entry(*lane).and_modify(f);
     }
+
+    pub fn client_stats(&self) -> ClientStats {
+        let mut connections: HashMap<ConnectionId, ConnectionStats> = HashMap::new();
+        for (lane, length) in &self.map {
+            if let TransmissionLane::ConnectionId(id) = lane {
+                connections.entry(*id).or_default().queue_length = *length;
+            }
+        }
+        for (id, bandwidth) in &self.bandwidth {
+            connections.entry(*id).or_default().bandwidth = *bandwidth;
+        }
+        for (lane, limit) in &self.rate_limits {
+            if let TransmissionLane::ConnectionId(id) = lane {
+                connections.entry(*id).or_default().rate_limit = Some(*limit);
+            }
+        }
+        ClientStats {
+            connections,
+            closed_connections: self.closed_bandwidth,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closed_connections_remain_accounted_for() {
+        let lanes = LaneQueueLengths::new();
+        lanes.record_sent(1, 100);
+        lanes.record_sent(1, 100);
+        lanes.record_received(1, 50);
+        lanes.record_sent(2, 10);
+        lanes.set_rate_limit(TransmissionLane::ConnectionId(1), Some(1000));
+
+        let stats = lanes.client_stats();
+        assert_eq!(stats.connections[&1].bandwidth.packets_sent, 2);
+        assert_eq!(stats.connections[&1].rate_limit, Some(1000));
+        assert_eq!(stats.total_bytes_sent(), 210);
+
+        lanes.remove_connection(1);
+        let stats = lanes.client_stats();
+        assert!(!stats.connections.contains_key(&1));
+        assert_eq!(stats.closed_connections.bytes_sent, 200);
+        assert_eq!(stats.closed_connections.bytes_received, 50);
+        assert_eq!(stats.total_bytes_sent(), 210);
+        assert_eq!(stats.total_bytes_received(), 50);
+        assert_eq!(lanes.rate_limit(&TransmissionLane::ConnectionId(1)), None);
+    }
+
+    #[test]
+    fn rate_limits_can_be_lifted() {
+        let lanes = LaneQueueLengths::new();
+        let lane = TransmissionLane::ConnectionId(1);
+        lanes.set_rate_limit(lane, Some(1000));
+        assert_eq!(lanes.rate_limit(&lane), Some(1000));
+        assert_eq!(lanes.rate_limit(&TransmissionLane::General), None);
+
+        lanes.set_rate_limit(lane, None);
+        assert_eq!(lanes.rate_limit(&lane), None);
+        assert!(lanes.with_rate_limits(|limits| limits.is_empty()));
+    }
 }
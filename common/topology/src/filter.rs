@@ -1,55 +1,123 @@
-// Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
+// Copyright 2021-2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use nym_bin_common::version_checker;
-use std::collections::{BTreeMap, HashMap};
-use std::hash::Hash;
+use crate::{gateway, mix, NodeVersion};
+use semver::{Version, VersionReq};
 
-pub trait Versioned: Clone {
-    fn version(&self) -> String;
+/// Semantic version constraints a node has to satisfy in order to be used for constructing routes.
+#[derive(Debug, Clone)]
+pub struct VersionConstraints {
+    /// Range of node versions that speak a protocol compatible with this client.
+    compatible_range: VersionReq,
+
+    /// Explicitly configured minimum version of mixnodes.
+    minimum_mixnode_version: Option<Version>,
+
+    /// Explicitly configured minimum version of gateways.
+    minimum_gateway_version: Option<Version>,
 }
 
-pub trait VersionFilterable<T> {
-    #[must_use]
-    fn filter_by_version(&self, expected_version: &str) -> Self;
+/// Number of nodes that got removed due to not satisfying the [`VersionConstraints`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VersionFilteringResult {
+    pub filtered_mixnodes: usize,
+    pub filtered_gateways: usize,
 }
 
-impl<T> VersionFilterable<T> for Vec<T>
-where
-    T: Versioned,
-{
-    fn filter_by_version(&self, expected_version: &str) -> Self {
-        self.iter()
-            .filter(|node| {
-                version_checker::is_minor_version_compatible(&node.version(), expected_version)
-            })
-            .cloned()
-            .collect()
+impl VersionFilteringResult {
+    pub fn total(&self) -> usize {
+        self.filtered_mixnodes + self.filtered_gateways
     }
 }
 
-impl<T, K, V> VersionFilterable<T> for HashMap<K, V>
-where
-    K: Eq + Hash + Clone,
-    V: VersionFilterable<T>,
-    T: Versioned,
-{
-    fn filter_by_version(&self, expected_version: &str) -> Self {
-        self.iter()
-            .map(|(k, v)| (k.clone(), v.filter_by_version(expected_version)))
-            .collect()
+impl VersionConstraints {
+    /// Derives the compatible version range from the version of the client itself:
+    /// any node with the same major version is considered compatible
+    /// (for pre-1.0 versions, the minor version has to match instead).
+    pub fn new_for_client(client_version: &str) -> Self {
+        let compatible_range = match Version::parse(client_version) {
+            Ok(version) if version.major > 0 => VersionReq::parse(&format!("^{}", version.major)),
+            Ok(version) => VersionReq::parse(&format!("^0.{}", version.minor)),
+            Err(_) => Ok(VersionReq::STAR),
+        }
+        .unwrap_or(VersionReq::STAR);
+
+        VersionConstraints {
+            compatible_range,
+            minimum_mixnode_version: None,
+            minimum_gateway_version: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_minimum_mixnode_version(mut self, version: Option<Version>) -> Self {
+        self.minimum_mixnode_version = version;
+        self
+    }
+
+    #[must_use]
+    pub fn with_minimum_gateway_version(mut self, version: Option<Version>) -> Self {
+        self.minimum_gateway_version = version;
+        self
+    }
+
+    fn allows(&self, version: &NodeVersion, minimum: Option<&Version>) -> bool {
+        let NodeVersion::Explicit(version) = version else {
+            return false;
+        };
+
+        // release candidates and custom builds are judged by their base version
+        let base = Version::new(version.major, version.minor, version.patch);
+        if !self.compatible_range.matches(&base) {
+            return false;
+        }
+
+        minimum.map(|minimum| &base >= minimum).unwrap_or(true)
+    }
+
+    pub fn allows_mixnode(&self, node: &mix::Node) -> bool {
+        self.allows(&node.version, self.minimum_mixnode_version.as_ref())
+    }
+
+    pub fn allows_gateway(&self, node: &gateway::Node) -> bool {
+        self.allows(&node.version, self.minimum_gateway_version.as_ref())
     }
 }
 
-impl<T, K, V> VersionFilterable<T> for BTreeMap<K, V>
-where
-    K: Eq + Ord + Clone,
-    V: VersionFilterable<T>,
-    T: Versioned,
-{
-    fn filter_by_version(&self, expected_version: &str) -> Self {
-        self.iter()
-            .map(|(k, v)| (k.clone(), v.filter_by_version(expected_version)))
-            .collect()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allows(constraints: &VersionConstraints, version: &str) -> bool {
+        constraints.allows(&version.into(), None)
+    }
+
+    #[test]
+    fn compatible_range_is_derived_from_client_version() {
+        let constraints = VersionConstraints::new_for_client("1.1.5");
+        assert!(allows(&constraints, "1.1.5"));
+        assert!(allows(&constraints, "1.0.0"));
+        assert!(allows(&constraints, "1.4.2"));
+        assert!(allows(&constraints, "1.2.0-rc.1"));
+        assert!(!allows(&constraints, "2.0.0"));
+        assert!(!allows(&constraints, "0.9.0"));
+        assert!(!allows(&constraints, "foo"));
+
+        let constraints = VersionConstraints::new_for_client("0.3.2");
+        assert!(allows(&constraints, "0.3.0"));
+        assert!(allows(&constraints, "0.3.7"));
+        assert!(!allows(&constraints, "0.4.0"));
+        assert!(!allows(&constraints, "1.3.2"));
+    }
+
+    #[test]
+    fn minimum_version_is_respected() {
+        let constraints = VersionConstraints::new_for_client("1.1.5");
+        let minimum = Version::new(1, 1, 3);
+
+        assert!(constraints.allows(&"1.1.3".into(), Some(&minimum)));
+        assert!(constraints.allows(&"1.2.0".into(), Some(&minimum)));
+        assert!(!constraints.allows(&"1.1.2".into(), Some(&minimum)));
+        assert!(!constraints.allows(&"1.1.3-rc.1".into(), Some(&Version::new(1, 1, 4))));
     }
 }
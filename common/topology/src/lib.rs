@@ -4,7 +4,7 @@
 #![allow(unknown_lints)]
 // clippy::to_string_trait_impl is not on stable as of 1.77
 
-use crate::filter::VersionFilterable;
+use crate::filter::{VersionConstraints, VersionFilteringResult};
 pub use error::NymTopologyError;
 use log::{debug, info, warn};
 use mix::Node;
@@ -45,7 +45,7 @@ pub use crate::serde::{
 };
 
 #[cfg(feature = "provider-trait")]
-pub use provider_trait::{HardcodedTopologyProvider, TopologyProvider};
+pub use provider_trait::{EpochBoundary, HardcodedTopologyProvider, TopologyProvider};
 
 #[derive(Debug, Default, Clone)]
 pub enum NodeVersion {
@@ -122,6 +122,9 @@ pub type MixLayer = u8;
 pub struct NymTopology {
     mixes: BTreeMap<MixLayer, Vec<mix::Node>>,
     gateways: Vec<gateway::Node>,
+
+    // the smallest number of mix hops the packets are allowed to traverse, if published
+    minimum_mix_hops: Option<u8>,
 }
 
 impl NymTopology {
@@ -153,7 +156,11 @@ impl NymTopology {
     }
 
     pub fn new(mixes: BTreeMap<MixLayer, Vec<mix::Node>>, gateways: Vec<gateway::Node>) -> Self {
-        NymTopology { mixes, gateways }
+        NymTopology {
+            mixes,
+            gateways,
+            minimum_mix_hops: None,
+        }
     }
 
     pub fn new_unordered(unordered_mixes: Vec<mix::Node>, gateways: Vec<gateway::Node>) -> Self {
@@ -164,7 +171,11 @@ impl NymTopology {
             layer_entry.push(node)
         }
 
-        NymTopology { mixes, gateways }
+        NymTopology {
+            mixes,
+            gateways,
+            minimum_mix_hops: None,
+        }
     }
 
     pub fn from_unordered<MI, GI, M, G>(unordered_mixes: MI, unordered_gateways: GI) -> Self
@@ -205,6 +216,18 @@ impl NymTopology {
         serde_json::from_reader(file).map_err(Into::into)
     }
 
+    /// Saves this topology to the provided file so that it could be later restored with
+    /// [`NymTopology::new_from_file`], for example to replay a fixed network view across runs.
+    #[cfg(feature = "serializable")]
+    pub fn save_to_file<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(file, self).map_err(Into::into)
+    }
+
     pub fn from_detailed(
         mix_details: Vec<MixNodeDetails>,
         gateway_bonds: Vec<GatewayBond>,
@@ -240,6 +263,19 @@ impl NymTopology {
             .find(|&gateway| gateway.identity_key.to_base58_string() == gateway_identity)
     }
 
+    #[must_use]
+    pub fn with_minimum_mix_hops(mut self, minimum_mix_hops: Option<u8>) -> Self {
+        self.minimum_mix_hops = minimum_mix_hops;
+        self
+    }
+
+    /// The smallest number of mix hops the packets are allowed to traverse, as published by
+    /// the source of this topology. If not set, the packets must never take shorter routes
+    /// than the default ones.
+    pub fn minimum_mix_hops(&self) -> Option<u8> {
+        self.minimum_mix_hops
+    }
+
     pub fn mixes(&self) -> &BTreeMap<MixLayer, Vec<mix::Node>> {
         &self.mixes
     }
@@ -456,17 +492,33 @@ impl NymTopology {
         Ok(())
     }
 
-    #[must_use]
-    pub fn filter_system_version(&self, expected_version: &str) -> Self {
-        self.filter_node_versions(expected_version)
-    }
+    /// Removes all nodes that do not satisfy the provided version constraints
+    /// and returns the number of nodes that got filtered out.
+    pub fn apply_version_constraints(
+        &mut self,
+        constraints: &VersionConstraints,
+    ) -> VersionFilteringResult {
+        let mut result = VersionFilteringResult::default();
 
-    #[must_use]
-    pub fn filter_node_versions(&self, expected_mix_version: &str) -> Self {
-        NymTopology {
-            mixes: self.mixes.filter_by_version(expected_mix_version),
-            gateways: self.gateways.clone(),
+        for nodes in self.mixes.values_mut() {
+            let before = nodes.len();
+            nodes.retain(|node| constraints.allows_mixnode(node));
+            result.filtered_mixnodes += before - nodes.len();
+        }
+
+        let before = self.gateways.len();
+        self.gateways
+            .retain(|node| constraints.allows_gateway(node));
+        result.filtered_gateways = before - self.gateways.len();
+
+        if result.total() > 0 {
+            debug!(
+                "filtered out {} mixnodes and {} gateways due to version constraints",
+                result.filtered_mixnodes, result.filtered_gateways
+            );
         }
+
+        result
     }
 }
 
@@ -623,3 +675,46 @@ mod converting_mixes_to_vec {
         }
     }
 }
+
+#[cfg(all(test, feature = "serializable"))]
+mod topology_snapshots {
+    use super::*;
+
+    #[test]
+    fn topology_survives_file_roundtrip() {
+        use nym_crypto::asymmetric::{encryption, identity};
+        use nym_mixnet_contract_common::Layer;
+
+        let node = mix::Node {
+            mix_id: 42,
+            owner: Some("N/A".to_string()),
+            host: "3.3.3.3".parse().unwrap(),
+            mix_host: "3.3.3.3:1789".parse().unwrap(),
+            identity_key: identity::PublicKey::from_base58_string(
+                "3ebjp1Fb9hdcS1AR6AZihgeJiMHkB5jjJUsvqNnfQwU7",
+            )
+            .unwrap(),
+            sphinx_key: encryption::PublicKey::from_base58_string(
+                "C7cown6dYCLZpLiMFC1PaBmhvLvmJmLDJGeRTbPD45bX",
+            )
+            .unwrap(),
+            layer: Layer::Two,
+            version: "0.2.0".into(),
+        };
+        let topology = NymTopology::new_unordered(vec![node], vec![]);
+
+        let path =
+            std::env::temp_dir().join(format!("nym-topology-snapshot-{}.json", std::process::id()));
+        topology.save_to_file(&path).unwrap();
+        let restored = NymTopology::new_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(restored.num_mixnodes(), 1);
+        let restored_node = restored.find_mix(42).unwrap();
+        assert_eq!(restored_node.layer, Layer::Two);
+        assert_eq!(
+            restored_node.identity_key,
+            topology.mixes_as_vec()[0].identity_key
+        );
+    }
+}
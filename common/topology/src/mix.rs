@@ -1,7 +1,7 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{filter, NetworkAddress, NodeVersion};
+use crate::{NetworkAddress, NodeVersion};
 use nym_crypto::asymmetric::{encryption, identity};
 pub use nym_mixnet_contract_common::Layer;
 use nym_mixnet_contract_common::{MixId, MixNodeBond};
@@ -92,13 +92,6 @@ impl Node {
     }
 }
 
-impl filter::Versioned for Node {
-    fn version(&self) -> String {
-        // TODO: return semver instead
-        self.version.to_string()
-    }
-}
-
 impl<'a> From<&'a Node> for SphinxNode {
     fn from(node: &'a Node) -> Self {
         let node_address_bytes = NymNodeRoutingAddress::from(node.mix_host)
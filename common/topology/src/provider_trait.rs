@@ -3,18 +3,52 @@
 
 use crate::NymTopology;
 pub use async_trait::async_trait;
+use time::OffsetDateTime;
+
+/// Information about the end of the current mixnet epoch,
+/// i.e. the point at which the active set might get rotated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpochBoundary {
+    pub epoch_id: u32,
+    pub epoch_end: OffsetDateTime,
+}
+
+impl EpochBoundary {
+    pub fn new(epoch_id: u32, epoch_end: OffsetDateTime) -> Self {
+        EpochBoundary {
+            epoch_id,
+            epoch_end,
+        }
+    }
+
+    /// Time remaining until the epoch is expected to end. Returns zero if the end is already in the past.
+    pub fn until_end(&self) -> std::time::Duration {
+        let remaining = self.epoch_end - OffsetDateTime::now_utc();
+        remaining.try_into().unwrap_or_default()
+    }
+}
 
 // hehe, wasm
 #[cfg(not(target_arch = "wasm32"))]
 #[async_trait]
 pub trait TopologyProvider: Send {
     async fn get_new_topology(&mut self) -> Option<NymTopology>;
+
+    /// Retrieve information about the current epoch boundary, if the provider is aware of it.
+    async fn epoch_boundary(&mut self) -> Option<EpochBoundary> {
+        None
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
 #[async_trait(?Send)]
 pub trait TopologyProvider {
     async fn get_new_topology(&mut self) -> Option<NymTopology>;
+
+    /// Retrieve information about the current epoch boundary, if the provider is aware of it.
+    async fn epoch_boundary(&mut self) -> Option<EpochBoundary> {
+        None
+    }
 }
 
 pub struct HardcodedTopologyProvider {
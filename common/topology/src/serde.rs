@@ -59,6 +59,10 @@ impl From<SerializableTopologyError> for JsValue {
 pub struct SerializableNymTopology {
     pub mixnodes: BTreeMap<MixLayer, Vec<SerializableMixNode>>,
     pub gateways: Vec<SerializableGateway>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "wasm-serde-types", tsify(optional))]
+    pub minimum_mix_hops: Option<u8>,
 }
 
 impl TryFrom<SerializableNymTopology> for NymTopology {
@@ -82,7 +86,8 @@ impl TryFrom<SerializableNymTopology> for NymTopology {
             .map(TryInto::try_into)
             .collect::<Result<_, _>>()?;
 
-        Ok(NymTopology::new(converted_mixes, gateways))
+        Ok(NymTopology::new(converted_mixes, gateways)
+            .with_minimum_mix_hops(value.minimum_mix_hops))
     }
 }
 
@@ -95,6 +100,7 @@ impl From<NymTopology> for SerializableNymTopology {
                 .map(|(&l, nodes)| (l, nodes.iter().map(Into::into).collect()))
                 .collect(),
             gateways: value.gateways().iter().map(Into::into).collect(),
+            minimum_mix_hops: value.minimum_mix_hops(),
         }
     }
 }
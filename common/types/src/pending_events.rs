@@ -171,6 +171,11 @@ pub enum PendingIntervalEventData {
         epochs_in_interval: u32,
         epoch_duration_secs: u64,
     },
+    UpdateStakingFloors {
+        minimum_mixnode_pledge: Option<DecCoin>,
+        minimum_gateway_pledge: Option<DecCoin>,
+        minimum_mixnode_delegation: Option<DecCoin>,
+    },
 }
 
 impl PendingIntervalEventData {
@@ -197,6 +202,17 @@ impl PendingIntervalEventData {
                 epochs_in_interval,
                 epoch_duration_secs,
             }),
+            MixnetContractPendingIntervalEventKind::UpdateStakingFloors { update } => {
+                let convert = |coin: Option<nym_mixnet_contract_common::Coin>| {
+                    coin.map(|coin| reg.attempt_convert_to_display_dec_coin(coin.into()))
+                        .transpose()
+                };
+                Ok(PendingIntervalEventData::UpdateStakingFloors {
+                    minimum_mixnode_pledge: convert(update.minimum_mixnode_pledge)?,
+                    minimum_gateway_pledge: convert(update.minimum_gateway_pledge)?,
+                    minimum_mixnode_delegation: convert(update.minimum_mixnode_delegation)?,
+                })
+            }
         }
     }
 }
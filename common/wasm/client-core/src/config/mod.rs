@@ -13,12 +13,15 @@ use wasm_bindgen::prelude::*;
 
 pub mod r#override;
 
+pub use nym_client_core::client::topology_control::geo_aware_provider::CountryGroup;
 pub use nym_client_core::config::{
     Acknowledgements as ConfigAcknowledgements, Config as BaseClientConfig,
     CoverTraffic as ConfigCoverTraffic, DebugConfig as ConfigDebug,
-    GatewayConnection as ConfigGatewayConnection, ReplySurbs as ConfigReplySurbs,
-    Topology as ConfigTopology, Traffic as ConfigTraffic,
+    GatewayConnection as ConfigGatewayConnection, GroupBy, ReplySurbs as ConfigReplySurbs,
+    Retransmission as ConfigRetransmission, Topology as ConfigTopology, TopologyStructure,
+    Traffic as ConfigTraffic,
 };
+pub use nym_network_defaults::NymNetworkDetails;
 
 pub fn new_base_client_config(
     id: String,
@@ -101,6 +104,11 @@ pub struct DebugWasm {
     /// Defines all configuration options related to acknowledgements, such as delays or wait timeouts.
     pub acknowledgements: AcknowledgementsWasm,
 
+    /// Defines all configuration options related to the retransmission of the unacknowledged packets,
+    /// such as the backoff or the maximum number of attempts.
+    #[serde(default)]
+    pub retransmission: RetransmissionWasm,
+
     /// Defines all configuration options related topology, such as refresh rates or timeouts.
     pub topology: TopologyWasm,
 
@@ -121,8 +129,12 @@ impl From<DebugWasm> for ConfigDebug {
             cover_traffic: debug.cover_traffic.into(),
             gateway_connection: debug.gateway_connection.into(),
             acknowledgements: debug.acknowledgements.into(),
+            retransmission: debug.retransmission.into(),
             topology: debug.topology.into(),
             reply_surbs: debug.reply_surbs.into(),
+            // the startup deadline is not (yet) configurable in wasm
+            startup: Default::default(),
+            protocol_stats: Default::default(),
         }
     }
 }
@@ -134,6 +146,7 @@ impl From<ConfigDebug> for DebugWasm {
             cover_traffic: debug.cover_traffic.into(),
             gateway_connection: debug.gateway_connection.into(),
             acknowledgements: debug.acknowledgements.into(),
+            retransmission: debug.retransmission.into(),
             topology: debug.topology.into(),
             reply_surbs: debug.reply_surbs.into(),
         }
@@ -195,6 +208,7 @@ impl From<TrafficWasm> for ConfigTraffic {
             primary_packet_size: PacketSize::RegularPacket,
             secondary_packet_size: use_extended_packet_size,
             packet_type,
+            ..ConfigTraffic::default()
         }
     }
 }
@@ -244,6 +258,7 @@ impl From<CoverTrafficWasm> for ConfigCoverTraffic {
             ),
             cover_traffic_primary_size_ratio: cover_traffic.cover_traffic_primary_size_ratio,
             disable_loop_cover_traffic_stream: cover_traffic.disable_loop_cover_traffic_stream,
+            ..Default::default()
         }
     }
 }
@@ -281,6 +296,8 @@ impl From<GatewayConnectionWasm> for ConfigGatewayConnection {
             gateway_response_timeout: Duration::from_millis(
                 gateway_connection.gateway_response_timeout_ms as u64,
             ),
+            // there's no way of monitoring the network from within the browser
+            ..ConfigGatewayConnection::default()
         }
     }
 }
@@ -341,6 +358,65 @@ impl From<ConfigAcknowledgements> for AcknowledgementsWasm {
     }
 }
 
+#[wasm_bindgen(inspectable)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetransmissionWasm {
+    /// Defines how long the client waits for the acknowledgement of the first transmission of a fragment
+    /// before retransmitting it.
+    /// If not set, it is derived from the expected round trip time of the packet.
+    pub initial_timeout_ms: Option<u32>,
+
+    /// Value the acknowledgement timeout is multiplied by with every subsequent retransmission
+    /// of the same fragment. The value of 1 disables the backoff.
+    pub backoff_multiplier: f64,
+
+    /// Upper bound on the acknowledgement timeout regardless of the number of retransmissions.
+    pub maximum_timeout_ms: u32,
+
+    /// Maximum number of times a fragment is going to be sent, including its first transmission,
+    /// before the client gives up on it.
+    /// If not set, the fragments are retransmitted until they get acknowledged.
+    pub max_attempts: Option<u32>,
+
+    /// Fraction of the acknowledgement timeout by which it is randomly extended or shortened.
+    pub jitter: f64,
+}
+
+impl Default for RetransmissionWasm {
+    fn default() -> Self {
+        ConfigRetransmission::default().into()
+    }
+}
+
+impl From<RetransmissionWasm> for ConfigRetransmission {
+    fn from(retransmission: RetransmissionWasm) -> Self {
+        ConfigRetransmission {
+            initial_timeout: retransmission
+                .initial_timeout_ms
+                .map(|timeout| Duration::from_millis(timeout as u64)),
+            backoff_multiplier: retransmission.backoff_multiplier,
+            maximum_timeout: Duration::from_millis(retransmission.maximum_timeout_ms as u64),
+            max_attempts: retransmission.max_attempts,
+            jitter: retransmission.jitter,
+        }
+    }
+}
+
+impl From<ConfigRetransmission> for RetransmissionWasm {
+    fn from(retransmission: ConfigRetransmission) -> Self {
+        RetransmissionWasm {
+            initial_timeout_ms: retransmission
+                .initial_timeout
+                .map(|timeout| timeout.as_millis() as u32),
+            backoff_multiplier: retransmission.backoff_multiplier,
+            maximum_timeout_ms: retransmission.maximum_timeout.as_millis() as u32,
+            max_attempts: retransmission.max_attempts,
+            jitter: retransmission.jitter,
+        }
+    }
+}
+
 #[wasm_bindgen(inspectable)]
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -370,6 +446,11 @@ pub struct TopologyWasm {
     /// Specifies a minimum performance of a gateway that is used on route construction.
     /// This setting is only applicable when `NymApi` topology is used.
     pub minimum_gateway_performance: u8,
+
+    /// If set, the routes are only going to be constructed out of the mixnodes located within
+    /// the specified group of countries, as determined by the geo-aware topology provider.
+    #[wasm_bindgen(skip)]
+    pub geo_aware_group: Option<CountryGroup>,
 }
 
 impl Default for TopologyWasm {
@@ -389,15 +470,24 @@ impl From<TopologyWasm> for ConfigTopology {
             max_startup_gateway_waiting_period: Duration::from_millis(
                 topology.max_startup_gateway_waiting_period_ms as u64,
             ),
-            topology_structure: Default::default(),
+            topology_structure: topology
+                .geo_aware_group
+                .map(|group| TopologyStructure::GeoAware(GroupBy::CountryGroup(group)))
+                .unwrap_or_default(),
             minimum_mixnode_performance: topology.minimum_mixnode_performance,
             minimum_gateway_performance: topology.minimum_gateway_performance,
+            ..Default::default()
         }
     }
 }
 
 impl From<ConfigTopology> for TopologyWasm {
     fn from(topology: ConfigTopology) -> Self {
+        let geo_aware_group = match topology.topology_structure {
+            TopologyStructure::GeoAware(GroupBy::CountryGroup(group)) => Some(group),
+            _ => None,
+        };
+
         TopologyWasm {
             topology_refresh_rate_ms: topology.topology_refresh_rate.as_millis() as u32,
             topology_resolution_timeout_ms: topology.topology_resolution_timeout.as_millis() as u32,
@@ -407,6 +497,7 @@ impl From<ConfigTopology> for TopologyWasm {
             disable_refreshing: topology.disable_refreshing,
             minimum_mixnode_performance: topology.minimum_mixnode_performance,
             minimum_gateway_performance: topology.minimum_gateway_performance,
+            geo_aware_group,
         }
     }
 }
@@ -480,6 +571,8 @@ impl From<ReplySurbsWasm> for ConfigReplySurbs {
                 reply_surbs.maximum_reply_key_age_ms as u64,
             ),
             surb_mix_hops: reply_surbs.surb_mix_hops,
+            // the limits on the reply surb requests are not (yet) configurable in wasm
+            ..ConfigReplySurbs::default()
         }
     }
 }
@@ -9,9 +9,9 @@
 
 use super::{
     AcknowledgementsWasm, CoverTrafficWasm, DebugWasm, GatewayConnectionWasm, ReplySurbsWasm,
-    TopologyWasm, TrafficWasm,
+    RetransmissionWasm, TopologyWasm, TrafficWasm,
 };
-use crate::config::ConfigDebug;
+use crate::config::{ConfigDebug, CountryGroup};
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 
@@ -36,6 +36,11 @@ pub struct DebugWasmOverride {
     #[tsify(optional)]
     pub acknowledgements: Option<AcknowledgementsWasmOverride>,
 
+    /// Defines all configuration options related to the retransmission of the unacknowledged packets,
+    /// such as the backoff or the maximum number of attempts.
+    #[tsify(optional)]
+    pub retransmission: Option<RetransmissionWasmOverride>,
+
     /// Defines all configuration options related topology, such as refresh rates or timeouts.
     #[tsify(optional)]
     pub topology: Option<TopologyWasmOverride>,
@@ -52,6 +57,7 @@ impl From<DebugWasmOverride> for DebugWasm {
             cover_traffic: value.cover_traffic.map(Into::into).unwrap_or_default(),
             gateway_connection: value.gateway_connection.map(Into::into).unwrap_or_default(),
             acknowledgements: value.acknowledgements.map(Into::into).unwrap_or_default(),
+            retransmission: value.retransmission.map(Into::into).unwrap_or_default(),
             topology: value.topology.map(Into::into).unwrap_or_default(),
             reply_surbs: value.reply_surbs.map(Into::into).unwrap_or_default(),
         }
@@ -219,6 +225,49 @@ impl From<AcknowledgementsWasmOverride> for AcknowledgementsWasm {
     }
 }
 
+#[derive(Tsify, Debug, Copy, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(rename_all = "camelCase")]
+pub struct RetransmissionWasmOverride {
+    /// Defines how long the client waits for the acknowledgement of the first transmission of a fragment
+    /// before retransmitting it.
+    /// If not set, it is derived from the expected round trip time of the packet.
+    #[tsify(optional)]
+    pub initial_timeout_ms: Option<u32>,
+
+    /// Value the acknowledgement timeout is multiplied by with every subsequent retransmission
+    /// of the same fragment. The value of 1 disables the backoff.
+    #[tsify(optional)]
+    pub backoff_multiplier: Option<f64>,
+
+    /// Upper bound on the acknowledgement timeout regardless of the number of retransmissions.
+    #[tsify(optional)]
+    pub maximum_timeout_ms: Option<u32>,
+
+    /// Maximum number of times a fragment is going to be sent, including its first transmission,
+    /// before the client gives up on it.
+    #[tsify(optional)]
+    pub max_attempts: Option<u32>,
+
+    /// Fraction of the acknowledgement timeout by which it is randomly extended or shortened.
+    #[tsify(optional)]
+    pub jitter: Option<f64>,
+}
+
+impl From<RetransmissionWasmOverride> for RetransmissionWasm {
+    fn from(value: RetransmissionWasmOverride) -> Self {
+        let def = RetransmissionWasm::default();
+
+        RetransmissionWasm {
+            initial_timeout_ms: value.initial_timeout_ms.or(def.initial_timeout_ms),
+            backoff_multiplier: value.backoff_multiplier.unwrap_or(def.backoff_multiplier),
+            maximum_timeout_ms: value.maximum_timeout_ms.unwrap_or(def.maximum_timeout_ms),
+            max_attempts: value.max_attempts.or(def.max_attempts),
+            jitter: value.jitter.unwrap_or(def.jitter),
+        }
+    }
+}
+
 #[derive(Tsify, Debug, Copy, Clone, Serialize, Deserialize)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 #[serde(rename_all = "camelCase")]
@@ -254,6 +303,41 @@ pub struct TopologyWasmOverride {
     /// This setting is only applicable when `NymApi` topology is used.
     #[tsify(optional)]
     pub minimum_gateway_performance: Option<u8>,
+
+    /// Restricts the routes to the mixnodes located within the specified region.
+    /// It accepts either a two-letter country code, whose continent is then used, or one of the group codes,
+    /// i.e. `EU`, `NA`, `SA`, `OC`, `AS` or `AF`.
+    #[tsify(optional, type = "string")]
+    #[serde(default, with = "country_group")]
+    pub geo_aware_group: Option<CountryGroup>,
+}
+
+// (de)serialises the country groups as their codes rather than the variant names
+mod country_group {
+    use super::CountryGroup;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        group: &Option<CountryGroup>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match group {
+            Some(group) => serializer.serialize_some(&group.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<CountryGroup>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|code| {
+                code.parse()
+                    .map_err(|_| D::Error::custom(format!("unknown country group: {code}")))
+            })
+            .transpose()
+    }
 }
 
 impl From<TopologyWasmOverride> for TopologyWasm {
@@ -277,6 +361,7 @@ impl From<TopologyWasmOverride> for TopologyWasm {
             minimum_gateway_performance: value
                 .minimum_gateway_performance
                 .unwrap_or(def.minimum_gateway_performance),
+            geo_aware_group: value.geo_aware_group.or(def.geo_aware_group),
         }
     }
 }
@@ -366,3 +451,51 @@ impl From<ReplySurbsWasmOverride> for ReplySurbsWasm {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ConfigTopology, GroupBy, TopologyStructure};
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn geo_aware_group_override_selects_the_geo_aware_topology() {
+        let with_country: TopologyWasmOverride =
+            serde_json::from_str(r#"{"geoAwareGroup":"DE"}"#).unwrap();
+        let with_group: TopologyWasmOverride =
+            serde_json::from_str(r#"{"geoAwareGroup":"EU"}"#).unwrap();
+        assert_eq!(with_country.geo_aware_group, Some(CountryGroup::Europe));
+        assert_eq!(with_group.geo_aware_group, Some(CountryGroup::Europe));
+
+        let topology = ConfigTopology::from(TopologyWasm::from(with_country));
+        assert_eq!(
+            topology.topology_structure,
+            TopologyStructure::GeoAware(GroupBy::CountryGroup(CountryGroup::Europe))
+        );
+
+        // and it survives going back to the wasm representation
+        let topology = TopologyWasm::from(topology);
+        assert_eq!(topology.geo_aware_group, Some(CountryGroup::Europe));
+    }
+
+    #[wasm_bindgen_test]
+    fn geo_aware_group_is_serialised_as_its_code() {
+        let mut topology: TopologyWasmOverride = serde_json::from_str("{}").unwrap();
+        assert!(topology.geo_aware_group.is_none());
+        assert_eq!(
+            ConfigTopology::from(TopologyWasm::from(topology)).topology_structure,
+            TopologyStructure::default()
+        );
+
+        topology.geo_aware_group = Some(CountryGroup::NorthAmerica);
+        let serialised = serde_json::to_value(topology).unwrap();
+        assert_eq!(serialised["geoAwareGroup"], "NA");
+    }
+
+    #[wasm_bindgen_test]
+    fn unknown_geo_aware_group_is_rejected() {
+        let res: Result<TopologyWasmOverride, _> =
+            serde_json::from_str(r#"{"geoAwareGroup":"XX"}"#);
+        assert!(res.is_err());
+    }
+}
@@ -10,6 +10,7 @@ use nym_gateway_client::error::GatewayClientError;
 use nym_sphinx::addressing::clients::RecipientFormattingError;
 use nym_sphinx::anonymous_replies::requests::InvalidAnonymousSenderTagRepresentation;
 use nym_topology::NymTopologyError;
+use nym_validator_client::nyxd::error::NyxdError;
 use nym_validator_client::ValidatorClientError;
 use thiserror::Error;
 use wasm_utils::wasm_error;
@@ -37,6 +38,15 @@ pub enum WasmCoreError {
         source: ValidatorClientError,
     },
 
+    #[error("failed to construct the nyxd query client: {source}")]
+    NyxdClientError {
+        #[from]
+        source: NyxdError,
+    },
+
+    #[error("credentials mode is enabled, but no nyxd endpoints have been provided")]
+    NoNyxdEndpoints,
+
     #[error("The provided wasm topology was invalid: {source}")]
     WasmTopologyError {
         #[from]
@@ -96,6 +106,9 @@ pub enum WasmCoreError {
 
     #[error("this client has already registered with a gateway: {gateway_id:?}")]
     AlreadyRegistered { gateway_id: String },
+
+    #[error("identity keys held by an external signer can't be persisted in the browser storage")]
+    UnsupportedExternalIdentity,
 }
 
 wasm_error!(WasmCoreError);
@@ -7,17 +7,20 @@ use crate::storage::ClientStorage;
 use js_sys::Promise;
 use nym_client_core::client::replies::reply_storage::browser_backend;
 use nym_client_core::config;
+use nym_client_core::config::Config as BaseClientConfig;
 use nym_client_core::init::helpers::current_gateways;
 use nym_client_core::init::types::GatewaySelectionSpecification;
 use nym_client_core::init::{
     self,
     types::{GatewaySetup, InitialisationResult},
 };
+use nym_network_defaults::NymNetworkDetails;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nym_topology::{gateway, NymTopology, SerializableNymTopology};
 use nym_validator_client::client::IdentityKey;
-use nym_validator_client::NymApiClient;
+use nym_validator_client::nyxd::{self, NyxdClient};
+use nym_validator_client::{NymApiClient, QueryReqwestRpcNyxdClient};
 use rand::thread_rng;
 use url::Url;
 use wasm_bindgen::prelude::wasm_bindgen;
@@ -35,6 +38,26 @@ pub fn setup_reply_surb_storage_backend(config: config::ReplySurbs) -> browser_b
     )
 }
 
+/// Creates the query client used by the bandwidth controller for retrieving the DKG data
+/// required for spending ticketbooks. The queries are performed over HTTP against the first
+/// configured nyxd endpoint using the contract addresses of the provided network.
+/// If credentials mode is disabled, no client is created.
+pub fn dkg_query_client_from_config(
+    config: &BaseClientConfig,
+    network_details: &NymNetworkDetails,
+) -> Result<Option<QueryReqwestRpcNyxdClient>, WasmCoreError> {
+    if config.get_disabled_credentials_mode() {
+        return Ok(None);
+    }
+
+    let Some(nyxd_url) = config.get_validator_endpoints().into_iter().next() else {
+        return Err(WasmCoreError::NoNyxdEndpoints);
+    };
+
+    let client_config = nyxd::Config::try_from_nym_network_details(network_details)?;
+    Ok(Some(NyxdClient::connect_reqwest(client_config, nyxd_url)?))
+}
+
 pub fn parse_recipient(recipient: &str) -> Result<Recipient, WasmCoreError> {
     Recipient::try_from_base58_string(recipient).map_err(|source| {
         WasmCoreError::MalformedRecipient {
@@ -134,3 +157,63 @@ pub async fn setup_from_topology(
     let gateways = topology.gateways();
     setup_gateway_wasm(client_store, force_tls, explicit_gateway, gateways).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn config(disabled_credentials: bool, nyxd_urls: Vec<Url>) -> BaseClientConfig {
+        BaseClientConfig::new("dkg-query-client-test", "1.0.0")
+            .with_disabled_credentials(disabled_credentials)
+            .with_custom_nyxd(nyxd_urls)
+    }
+
+    fn nyxd_url() -> Url {
+        "https://rpc.nymtech.net".parse().unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn no_client_is_created_without_credentials() {
+        let network = NymNetworkDetails::new_mainnet();
+        assert!(
+            dkg_query_client_from_config(&config(true, vec![]), &network)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            dkg_query_client_from_config(&config(true, vec![nyxd_url()]), &network)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn client_is_created_for_the_first_nyxd_endpoint() {
+        let network = NymNetworkDetails::new_mainnet();
+        let other: Url = "https://other-rpc.nymtech.net".parse().unwrap();
+        assert!(
+            dkg_query_client_from_config(&config(false, vec![nyxd_url(), other]), &network)
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn credentials_mode_requires_nyxd_endpoints() {
+        let network = NymNetworkDetails::new_mainnet();
+        assert!(matches!(
+            dkg_query_client_from_config(&config(false, vec![]), &network),
+            Err(WasmCoreError::NoNyxdEndpoints)
+        ));
+    }
+
+    #[wasm_bindgen_test]
+    fn malformed_network_details_are_rejected() {
+        let network = NymNetworkDetails::new_mainnet().with_mixnet_contract(Some("not-a-contract"));
+        assert!(matches!(
+            dkg_query_client_from_config(&config(false, vec![nyxd_url()]), &network),
+            Err(WasmCoreError::NyxdClientError { .. })
+        ));
+    }
+}
@@ -4,19 +4,24 @@
 use crate::config::BaseClientConfig;
 use crate::error::WasmCoreError;
 use crate::helpers::setup_reply_surb_storage_backend;
+use crate::storage::outbox::WasmOutbox;
 use crate::storage::wasm_client_traits::WasmClientStorage;
 use crate::storage::ClientStorage;
 use async_trait::async_trait;
 use nym_client_core::client::base_client::storage::{
-    gateways_storage::{ActiveGateway, GatewayRegistration, GatewaysDetailsStore},
+    gateways_storage::{
+        ActiveGateway, GatewayBandwidth, GatewayLatency, GatewayRegistration, GatewaysDetailsStore,
+    },
     MixnetClientStorage,
 };
+use nym_client_core::client::inbox;
 use nym_client_core::client::key_manager::persistence::KeyStore;
 use nym_client_core::client::key_manager::ClientKeys;
 use nym_client_core::client::replies::reply_storage::browser_backend;
 use nym_credential_storage::ephemeral_storage::EphemeralStorage as EphemeralCredentialStorage;
 use nym_crypto::asymmetric::ed25519::PublicKey;
 use nym_gateway_client::SharedSymmetricKey;
+use std::time::Duration;
 use wasm_utils::console_log;
 
 // temporary until other variants are properly implemented (probably it should get changed into `ClientStorage`
@@ -25,6 +30,8 @@ pub struct FullWasmClientStorage {
     pub(crate) keys_and_gateway_store: ClientStorage,
     pub(crate) reply_storage: browser_backend::Backend,
     pub(crate) credential_storage: EphemeralCredentialStorage,
+    pub(crate) inbox_storage: inbox::Disabled,
+    pub(crate) outbox_storage: Option<WasmOutbox>,
 }
 
 impl FullWasmClientStorage {
@@ -34,8 +41,18 @@ impl FullWasmClientStorage {
             keys_and_gateway_store: base_storage,
             reply_storage: setup_reply_surb_storage_backend(base_config.debug.reply_surbs),
             credential_storage: EphemeralCredentialStorage::default(),
+            inbox_storage: inbox::Disabled,
+            outbox_storage: None,
         }
     }
+
+    /// Journal all accepted input messages in the provided outbox so that they could be
+    /// replayed if the client got stopped before managing to process them.
+    #[must_use]
+    pub fn with_persistent_outbox(mut self, outbox: WasmOutbox) -> Self {
+        self.outbox_storage = Some(outbox);
+        self
+    }
 }
 
 impl MixnetClientStorage for FullWasmClientStorage {
@@ -44,6 +61,8 @@ impl MixnetClientStorage for FullWasmClientStorage {
     type CredentialStore = EphemeralCredentialStorage;
 
     type GatewaysDetailsStore = ClientStorage;
+    type InboxStore = inbox::Disabled;
+    type OutboxStore = Option<WasmOutbox>;
 
     fn into_runtime_stores(
         self,
@@ -51,11 +70,15 @@ impl MixnetClientStorage for FullWasmClientStorage {
         Self::ReplyStore,
         Self::CredentialStore,
         Self::GatewaysDetailsStore,
+        Self::InboxStore,
+        Self::OutboxStore,
     ) {
         (
             self.reply_storage,
             self.credential_storage,
             self.keys_and_gateway_store,
+            self.inbox_storage,
+            self.outbox_storage,
         )
     }
 
@@ -74,6 +97,14 @@ impl MixnetClientStorage for FullWasmClientStorage {
     fn gateway_details_store(&self) -> &Self::GatewaysDetailsStore {
         &self.keys_and_gateway_store
     }
+
+    fn inbox_store(&self) -> &Self::InboxStore {
+        &self.inbox_storage
+    }
+
+    fn outbox_store(&self) -> &Self::OutboxStore {
+        &self.outbox_storage
+    }
 }
 
 #[async_trait(?Send)]
@@ -98,14 +129,23 @@ impl KeyStore for ClientStorage {
     async fn store_keys(&self, keys: &ClientKeys) -> Result<(), Self::StorageError> {
         console_log!("attempting to store cryptographic keys...");
 
-        self.store_identity_keypair(&keys.identity_keypair())
-            .await?;
+        let identity_keypair = keys
+            .identity_keypair()
+            .ok_or(WasmCoreError::UnsupportedExternalIdentity)?;
+        self.store_identity_keypair(&identity_keypair).await?;
         self.store_encryption_keypair(&keys.encryption_keypair())
             .await?;
         self.store_ack_key(&keys.ack_key()).await?;
 
         Ok(())
     }
+
+    async fn store_rotated_keys(&self, keys: &ClientKeys) -> Result<(), Self::StorageError> {
+        console_log!("attempting to store rotated encryption keys...");
+
+        self.store_encryption_keypair(&keys.encryption_keypair())
+            .await
+    }
 }
 
 #[async_trait(?Send)]
@@ -170,4 +210,30 @@ impl GatewaysDetailsStore for ClientStorage {
     async fn remove_gateway_details(&self, gateway_id: &str) -> Result<(), Self::StorageError> {
         self.remove_registered_gateway(gateway_id).await
     }
+
+    // latencies are not persisted in the browser, so they're measured anew on each gateway selection
+    async fn record_gateway_latency(
+        &self,
+        _gateway_id: PublicKey,
+        _latency: Duration,
+    ) -> Result<(), Self::StorageError> {
+        Ok(())
+    }
+
+    async fn gateway_latencies(&self) -> Result<Vec<GatewayLatency>, Self::StorageError> {
+        Ok(Vec::new())
+    }
+
+    // neither is the remaining bandwidth, which is going to be re-learned from the gateway itself
+    async fn record_gateway_bandwidth(
+        &self,
+        _gateway_id: PublicKey,
+        _remaining: i64,
+    ) -> Result<(), Self::StorageError> {
+        Ok(())
+    }
+
+    async fn gateway_bandwidths(&self) -> Result<Vec<GatewayBandwidth>, Self::StorageError> {
+        Ok(Vec::new())
+    }
 }
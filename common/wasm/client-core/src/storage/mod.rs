@@ -15,6 +15,7 @@ use wasm_utils::error::{simple_js_error, PromisableResult};
 use zeroize::Zeroizing;
 
 pub mod core_client_traits;
+pub mod outbox;
 mod types;
 pub mod wasm_client_traits;
 
@@ -0,0 +1,146 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::WasmCoreError;
+use async_trait::async_trait;
+use nym_client_core::client::outbox::{OutboxMessageId, OutboxStorage};
+use wasm_bindgen::JsValue;
+use wasm_storage::{IdbVersionChangeEvent, WasmStorage};
+use wasm_utils::console_warn;
+use zeroize::Zeroizing;
+
+const STORAGE_NAME_PREFIX: &str = "wasm-client-outbox";
+const STORAGE_VERSION: u32 = 1;
+
+const OUTBOX_STORE: &str = "outbox";
+
+/// Outbox journaling all messages in the browser's IndexedDB.
+pub struct WasmOutbox {
+    inner: WasmStorage,
+}
+
+// SAFETY: wasm is (currently) single-threaded, so the underlying database handle is never going
+// to be accessed from multiple threads. The bound is only required since the base client
+// has to work with the native multithreaded runtime.
+unsafe impl Send for WasmOutbox {}
+unsafe impl Sync for WasmOutbox {}
+
+impl WasmOutbox {
+    fn db_name(client_id: &str) -> String {
+        format!("{STORAGE_NAME_PREFIX}-{client_id}")
+    }
+
+    pub async fn new_async(
+        client_id: &str,
+        passphrase: Option<String>,
+    ) -> Result<WasmOutbox, WasmCoreError> {
+        let name = Self::db_name(client_id);
+        let passphrase = Zeroizing::new(passphrase);
+
+        let migrate_fn = Some(|evt: &IdbVersionChangeEvent| -> Result<(), JsValue> {
+            let old_version = evt.old_version() as u32;
+            let db = evt.db();
+
+            if old_version < 1 {
+                db.create_object_store(OUTBOX_STORE)?;
+            }
+
+            Ok(())
+        });
+
+        let inner = WasmStorage::new(
+            &name,
+            STORAGE_VERSION,
+            migrate_fn,
+            passphrase.as_ref().map(|p| p.as_bytes()),
+        )
+        .await?;
+
+        Ok(WasmOutbox { inner })
+    }
+
+    // ids are sequential so realistically they're never going to exceed the f64 integer precision
+    fn key(id: OutboxMessageId) -> JsValue {
+        JsValue::from_f64(id.as_u64() as f64)
+    }
+}
+
+#[async_trait(?Send)]
+impl OutboxStorage for WasmOutbox {
+    type StorageError = WasmCoreError;
+
+    async fn store(&self, id: OutboxMessageId, content: &[u8]) -> Result<(), Self::StorageError> {
+        Ok(self
+            .inner
+            .store_value(OUTBOX_STORE, Self::key(id), &content)
+            .await?)
+    }
+
+    async fn remove(&self, id: OutboxMessageId) -> Result<(), Self::StorageError> {
+        Ok(self.inner.remove_value(OUTBOX_STORE, Self::key(id)).await?)
+    }
+
+    async fn pending(&self) -> Result<Vec<(OutboxMessageId, Vec<u8>)>, Self::StorageError> {
+        let keys = self.inner.get_all_keys(OUTBOX_STORE).await?;
+
+        let mut pending = Vec::with_capacity(keys.length() as usize);
+        for key in keys.iter() {
+            let Some(raw_id) = key.as_f64() else {
+                console_warn!("outbox contains a non-numeric key");
+                continue;
+            };
+            let id = OutboxMessageId::new(raw_id as u64);
+
+            if let Some(content) = self
+                .inner
+                .read_value::<Vec<u8>, _>(OUTBOX_STORE, key)
+                .await?
+            {
+                pending.push((id, content))
+            }
+        }
+
+        pending.sort_by_key(|(id, _)| *id);
+        Ok(pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    async fn messages_are_persisted_until_removed() {
+        let outbox = WasmOutbox::new_async("outbox-test", Some("passphrase".into()))
+            .await
+            .unwrap();
+        outbox
+            .store(OutboxMessageId::new(2), b"second")
+            .await
+            .unwrap();
+        outbox
+            .store(OutboxMessageId::new(1), b"first")
+            .await
+            .unwrap();
+        outbox
+            .store(OutboxMessageId::new(3), b"third")
+            .await
+            .unwrap();
+        outbox.remove(OutboxMessageId::new(3)).await.unwrap();
+        drop(outbox);
+
+        let outbox = WasmOutbox::new_async("outbox-test", Some("passphrase".into()))
+            .await
+            .unwrap();
+        assert_eq!(
+            outbox.pending().await.unwrap(),
+            vec![
+                (OutboxMessageId::new(1), b"first".to_vec()),
+                (OutboxMessageId::new(2), b"second".to_vec()),
+            ]
+        );
+    }
+}
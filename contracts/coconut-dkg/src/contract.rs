@@ -7,8 +7,8 @@ use crate::dealers::queries::{
 };
 use crate::dealers::transactions::try_add_dealer;
 use crate::dealings::queries::{
-    query_dealer_dealings_status, query_dealing_chunk, query_dealing_chunk_status,
-    query_dealing_metadata, query_dealing_status,
+    query_dealer_dealings_status, query_dealer_submission_cursor, query_dealing_chunk,
+    query_dealing_chunk_status, query_dealing_metadata, query_dealing_status,
 };
 use crate::dealings::transactions::{try_commit_dealings_chunk, try_submit_dealings_metadata};
 use crate::epoch_state::queries::{
@@ -20,11 +20,15 @@ use crate::epoch_state::transactions::{
     try_advance_epoch_state, try_initiate_dkg, try_trigger_reset, try_trigger_resharing,
 };
 use crate::error::ContractError;
-use crate::state::queries::query_state;
+use crate::state::queries::{query_public_parameters, query_state};
 use crate::state::storage::{DKG_ADMIN, MULTISIG, STATE};
-use crate::verification_key_shares::queries::{query_vk_share, query_vk_shares_paged};
+use crate::verification_key_shares::queries::{
+    query_master_verification_key, query_vk_share, query_vk_shares_paged,
+};
 use crate::verification_key_shares::transactions::try_commit_verification_key_share;
-use crate::verification_key_shares::transactions::try_verify_verification_key_share;
+use crate::verification_key_shares::transactions::{
+    try_commit_master_verification_key, try_verify_verification_key_share,
+};
 use cosmwasm_std::{
     entry_point, to_binary, Deps, DepsMut, Env, MessageInfo, QueryResponse, Response,
 };
@@ -92,7 +96,9 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::InitiateDkg {} => try_initiate_dkg(deps, env, info),
+        ExecuteMsg::InitiateDkg { public_parameters } => {
+            try_initiate_dkg(deps, env, info, public_parameters)
+        }
         ExecuteMsg::RegisterDealer {
             bte_key_with_proof,
             identity_key,
@@ -110,7 +116,8 @@ pub fn execute(
             dealing_index,
             chunks,
             resharing,
-        } => try_submit_dealings_metadata(deps, info, dealing_index, chunks, resharing),
+            checksum,
+        } => try_submit_dealings_metadata(deps, info, dealing_index, chunks, resharing, checksum),
         ExecuteMsg::CommitDealingsChunk { chunk } => {
             try_commit_dealings_chunk(deps, env, info, chunk)
         }
@@ -120,6 +127,9 @@ pub fn execute(
         ExecuteMsg::VerifyVerificationKeyShare { owner, resharing } => {
             try_verify_verification_key_share(deps, info, owner, resharing)
         }
+        ExecuteMsg::CommitMasterVerificationKey { key } => {
+            try_commit_master_verification_key(deps, info, key)
+        }
         ExecuteMsg::AdvanceEpochState {} => try_advance_epoch_state(deps, env),
         ExecuteMsg::TriggerReset {} => try_trigger_reset(deps, env, info),
         ExecuteMsg::TriggerResharing {} => try_trigger_resharing(deps, env, info),
@@ -131,6 +141,7 @@ pub fn query(deps: Deps<'_>, env: Env, msg: QueryMsg) -> Result<QueryResponse, C
     let response = match msg {
         QueryMsg::GetState {} => to_binary(&query_state(deps.storage)?)?,
         QueryMsg::GetCurrentEpochState {} => to_binary(&query_current_epoch(deps.storage)?)?,
+        QueryMsg::GetPublicParameters {} => to_binary(&query_public_parameters(deps.storage)?)?,
         QueryMsg::CanAdvanceState {} => to_binary(&query_can_advance_state(deps.storage, env)?)?,
         QueryMsg::GetCurrentEpochThreshold {} => {
             to_binary(&query_current_epoch_threshold(deps.storage)?)?
@@ -168,6 +179,9 @@ pub fn query(deps: Deps<'_>, env: Env, msg: QueryMsg) -> Result<QueryResponse, C
         QueryMsg::GetDealerDealingsStatus { epoch_id, dealer } => {
             to_binary(&query_dealer_dealings_status(deps, epoch_id, dealer)?)?
         }
+        QueryMsg::GetDealerSubmissionCursor { epoch_id, dealer } => {
+            to_binary(&query_dealer_submission_cursor(deps, epoch_id, dealer)?)?
+        }
         QueryMsg::GetDealingStatus {
             epoch_id,
             dealer,
@@ -210,6 +224,9 @@ pub fn query(deps: Deps<'_>, env: Env, msg: QueryMsg) -> Result<QueryResponse, C
             limit,
             start_after,
         } => to_binary(&query_vk_shares_paged(deps, epoch_id, start_after, limit)?)?,
+        QueryMsg::GetMasterVerificationKey { epoch_id } => {
+            to_binary(&query_master_verification_key(deps, epoch_id)?)?
+        }
         QueryMsg::GetCW2ContractVersion {} => to_binary(&cw2::get_contract_version(deps.storage)?)?,
     };
 
@@ -341,7 +358,9 @@ mod tests {
         app.execute_contract(
             Addr::unchecked(ADMIN_ADDRESS),
             coconut_dkg_contract_addr.clone(),
-            &InitiateDkg {},
+            &InitiateDkg {
+                public_parameters: None,
+            },
             &[],
         )
         .unwrap();
@@ -96,7 +96,13 @@ pub(crate) mod tests {
     fn invalid_state() {
         let mut deps = helpers::init_contract();
         let mut env = mock_env();
-        try_initiate_dkg(deps.as_mut(), env.clone(), mock_info(ADMIN_ADDRESS, &[])).unwrap();
+        try_initiate_dkg(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN_ADDRESS, &[]),
+            None,
+        )
+        .unwrap();
 
         let owner = Addr::unchecked("owner");
         let info = mock_info(owner.as_str(), &[]);
@@ -5,8 +5,9 @@ use crate::dealings::storage::{StoredDealing, DEALINGS_METADATA};
 use crate::state::storage::STATE;
 use cosmwasm_std::{Deps, StdResult};
 use nym_coconut_dkg_common::dealing::{
-    DealerDealingsStatusResponse, DealingChunkResponse, DealingChunkStatusResponse,
-    DealingMetadataResponse, DealingStatus, DealingStatusResponse,
+    DealerDealingsStatusResponse, DealerSubmissionCursorResponse, DealingChunkResponse,
+    DealingChunkStatusResponse, DealingMetadataResponse, DealingStatus, DealingStatusResponse,
+    DealingSubmissionCursor,
 };
 use nym_coconut_dkg_common::types::{ChunkIndex, DealingIndex, EpochId};
 use std::collections::BTreeMap;
@@ -57,6 +58,47 @@ pub fn query_dealer_dealings_status(
     })
 }
 
+/// Get the position from which the dealer should resume submitting its dealings,
+/// i.e. the first dealing without metadata or the first uncommitted chunk.
+pub fn query_dealer_submission_cursor(
+    deps: Deps<'_>,
+    epoch_id: EpochId,
+    dealer: String,
+) -> StdResult<DealerSubmissionCursorResponse> {
+    let dealer = deps.api.addr_validate(&dealer)?;
+    let state = STATE.load(deps.storage)?;
+
+    let mut cursor = None;
+    for dealing_index in 0..state.key_size {
+        match DEALINGS_METADATA.may_load(deps.storage, (epoch_id, &dealer, dealing_index))? {
+            None => {
+                cursor = Some(DealingSubmissionCursor {
+                    dealing_index,
+                    metadata_missing: true,
+                    chunk_index: None,
+                });
+                break;
+            }
+            Some(metadata) => {
+                if let Some(chunk_index) = metadata.first_missing_chunk() {
+                    cursor = Some(DealingSubmissionCursor {
+                        dealing_index,
+                        metadata_missing: false,
+                        chunk_index: Some(chunk_index),
+                    });
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(DealerSubmissionCursorResponse {
+        epoch_id,
+        dealer,
+        cursor,
+    })
+}
+
 /// Get the status of particular dealing, i.e. whether it has been fully submitted.
 pub fn query_dealing_status(
     deps: Deps<'_>,
@@ -127,7 +169,9 @@ pub(crate) mod tests {
     use crate::support::tests::fixtures::{dealing_bytes_fixture, partial_dealing_fixture};
     use crate::support::tests::helpers::init_contract;
     use cosmwasm_std::{Addr, DepsMut};
-    use nym_coconut_dkg_common::dealing::{DealingChunkInfo, PartialContractDealing};
+    use nym_coconut_dkg_common::dealing::{
+        DealingChunkInfo, DealingMetadata, PartialContractDealing,
+    };
 
     #[allow(unused)]
     fn fill_dealings(
@@ -227,4 +271,64 @@ pub(crate) mod tests {
         // assert_eq!(retrieved.dealer, Addr::unchecked("foo"));
         // assert!(retrieved.dealing_submitted)
     }
+
+    #[test]
+    fn test_query_dealer_submission_cursor() {
+        let mut deps = init_contract();
+        let dealer = Addr::unchecked("foo");
+        let key_size = STATE.load(deps.as_ref().storage).unwrap().key_size;
+
+        let res = query_dealer_submission_cursor(deps.as_ref(), 0, dealer.to_string()).unwrap();
+        assert_eq!(
+            res.cursor,
+            Some(DealingSubmissionCursor {
+                dealing_index: 0,
+                metadata_missing: true,
+                chunk_index: None,
+            })
+        );
+
+        let mut metadata = DealingMetadata::new(
+            0,
+            vec![DealingChunkInfo::new(10), DealingChunkInfo::new(5)],
+            None,
+        );
+        metadata
+            .submitted_chunks
+            .get_mut(&0)
+            .unwrap()
+            .status
+            .submission_height = Some(123);
+        DEALINGS_METADATA
+            .save(deps.as_mut().storage, (0, &dealer, 0), &metadata)
+            .unwrap();
+
+        let res = query_dealer_submission_cursor(deps.as_ref(), 0, dealer.to_string()).unwrap();
+        assert_eq!(
+            res.cursor,
+            Some(DealingSubmissionCursor {
+                dealing_index: 0,
+                metadata_missing: false,
+                chunk_index: Some(1),
+            })
+        );
+
+        // complete all dealings
+        for chunk in metadata.submitted_chunks.values_mut() {
+            chunk.status.submission_height = Some(123);
+        }
+        for dealing_index in 0..key_size {
+            metadata.dealing_index = dealing_index;
+            DEALINGS_METADATA
+                .save(
+                    deps.as_mut().storage,
+                    (0, &dealer, dealing_index),
+                    &metadata,
+                )
+                .unwrap();
+        }
+
+        let res = query_dealer_submission_cursor(deps.as_ref(), 0, dealer.to_string()).unwrap();
+        assert!(res.cursor.is_none());
+    }
 }
@@ -38,6 +38,25 @@ pub(crate) fn must_read_metadata(
         })
 }
 
+/// Removes the metadata alongside all the stored chunks of the particular dealing.
+pub(crate) fn remove_dealing(
+    storage: &mut dyn Storage,
+    epoch_id: EpochId,
+    dealer: Dealer,
+    metadata: &DealingMetadata,
+) {
+    for chunk_index in metadata.submitted_chunks.keys() {
+        StoredDealing::remove(
+            storage,
+            epoch_id,
+            dealer,
+            metadata.dealing_index,
+            *chunk_index,
+        );
+    }
+    DEALINGS_METADATA.remove(storage, (epoch_id, dealer, metadata.dealing_index));
+}
+
 pub(crate) fn store_metadata(
     storage: &mut dyn Storage,
     epoch_id: EpochId,
@@ -162,6 +181,17 @@ impl StoredDealing {
         storage.set(&storage_key, dealng_chunk.data.as_slice());
     }
 
+    pub(crate) fn remove(
+        storage: &mut dyn Storage,
+        epoch_id: EpochId,
+        dealer: Dealer,
+        dealing_index: DealingIndex,
+        chunk_index: ChunkIndex,
+    ) {
+        let storage_key = Self::storage_key(epoch_id, dealer, dealing_index, chunk_index);
+        storage.remove(&storage_key);
+    }
+
     pub(crate) fn read(
         storage: &dyn Storage,
         epoch_id: EpochId,
@@ -3,15 +3,19 @@
 
 use crate::dealers::storage::ensure_dealer;
 use crate::dealings::storage::{
-    metadata_exists, must_read_metadata, store_metadata, StoredDealing,
+    metadata_exists, must_read_metadata, remove_dealing, store_metadata, StoredDealing,
 };
 use crate::epoch_state::storage::CURRENT_EPOCH;
 use crate::epoch_state::utils::check_epoch_state;
 use crate::error::ContractError;
 use crate::state::storage::STATE;
-use cosmwasm_std::{Addr, DepsMut, Env, MessageInfo, Response, Storage};
+use cosmwasm_std::{Addr, DepsMut, Env, Event, MessageInfo, Response, Storage};
 use nym_coconut_dkg_common::dealing::{
-    DealingChunkInfo, DealingMetadata, PartialContractDealing, MAX_DEALING_CHUNKS,
+    dealing_checksum, DealingChecksum, DealingChunkInfo, DealingMetadata, PartialContractDealing,
+    MAX_DEALING_CHUNKS,
+};
+use nym_coconut_dkg_common::event_attributes::{
+    DEALING_CHECKSUM_MISMATCH_EVENT, DEALING_INDEX, EPOCH_ID,
 };
 use nym_coconut_dkg_common::types::{ChunkIndex, DealingIndex, EpochId, EpochState};
 
@@ -41,6 +45,7 @@ pub fn try_submit_dealings_metadata(
     dealing_index: DealingIndex,
     chunks: Vec<DealingChunkInfo>,
     resharing: bool,
+    checksum: Option<DealingChecksum>,
 ) -> Result<Response, ContractError> {
     let epoch = CURRENT_EPOCH.load(deps.storage)?;
     let state = STATE.load(deps.storage)?;
@@ -115,7 +120,7 @@ pub fn try_submit_dealings_metadata(
     }
 
     // finally, construct and store the metadata
-    let metadata = DealingMetadata::new(dealing_index, chunks);
+    let metadata = DealingMetadata::new(dealing_index, chunks, checksum);
 
     store_metadata(
         deps.storage,
@@ -196,6 +201,22 @@ pub fn try_commit_dealings_chunk(
     // this is less than ideal since we have to iterate through all the chunks, but realistically,
     // there won't be a lot of them
     if metadata.is_complete() {
+        if !assembled_dealing_matches_checksum(
+            deps.storage,
+            epoch.epoch_id,
+            &info.sender,
+            &metadata,
+        ) {
+            // the dealing is corrupted, but since all chunks are write-once, the dealer wouldn't be able
+            // to ever fix it. so rather than failing the tx, purge the dealing so it could be resubmitted.
+            remove_dealing(deps.storage, epoch.epoch_id, &info.sender, &metadata);
+            return Ok(Response::new().add_event(
+                Event::new(DEALING_CHECKSUM_MISMATCH_EVENT)
+                    .add_attribute(EPOCH_ID, epoch.epoch_id.to_string())
+                    .add_attribute(DEALING_INDEX, metadata.dealing_index.to_string()),
+            ));
+        }
+
         epoch.state_progress.submitted_dealings += 1;
         CURRENT_EPOCH.save(deps.storage, &epoch)?;
     }
@@ -203,6 +224,34 @@ pub fn try_commit_dealings_chunk(
     Ok(Response::new())
 }
 
+// assemble all chunks of the dealing and verify them against the declared checksum (if any)
+fn assembled_dealing_matches_checksum(
+    storage: &dyn Storage,
+    epoch_id: EpochId,
+    dealer: &Addr,
+    metadata: &DealingMetadata,
+) -> bool {
+    let Some(expected) = &metadata.checksum else {
+        return true;
+    };
+
+    let mut dealing = Vec::with_capacity(metadata.total_size());
+    for chunk_index in metadata.submitted_chunks.keys() {
+        let Some(chunk) = StoredDealing::read(
+            storage,
+            epoch_id,
+            dealer,
+            metadata.dealing_index,
+            *chunk_index,
+        ) else {
+            return false;
+        };
+        dealing.extend_from_slice(&chunk);
+    }
+
+    &dealing_checksum(&dealing) == expected
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
@@ -218,7 +267,13 @@ pub(crate) mod tests {
     fn invalid_commit_dealing_chunk() {
         let mut deps = helpers::init_contract();
         let mut env = mock_env();
-        try_initiate_dkg(deps.as_mut(), env.clone(), mock_info(ADMIN_ADDRESS, &[])).unwrap();
+        try_initiate_dkg(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN_ADDRESS, &[]),
+            None,
+        )
+        .unwrap();
 
         let owner = Addr::unchecked("owner1");
         let info = mock_info(owner.as_str(), &[]);
@@ -258,6 +313,7 @@ pub(crate) mod tests {
             chunk.dealing_index,
             dealing_metadata_fixture(),
             false,
+            None,
         )
         .unwrap();
 
@@ -318,10 +374,102 @@ pub(crate) mod tests {
             0,
             dealing_metadata_fixture(),
             false,
+            None,
         )
         .unwrap();
 
         let ret = try_commit_dealings_chunk(deps.as_mut(), env, info, chunk.clone());
         assert!(ret.is_ok());
     }
+
+    #[test]
+    fn committing_chunks_verifies_declared_checksum() {
+        let mut deps = helpers::init_contract();
+        let mut env = mock_env();
+        try_initiate_dkg(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN_ADDRESS, &[]),
+            None,
+        )
+        .unwrap();
+
+        let owner = Addr::unchecked("owner1");
+        let info = mock_info(owner.as_str(), &[]);
+        let chunk = partial_dealing_fixture();
+
+        env.block.time = env
+            .block
+            .time
+            .plus_seconds(TimeConfiguration::default().public_key_submission_time_secs);
+        try_advance_epoch_state(deps.as_mut(), env.clone()).unwrap();
+        let dealer_details = DealerDetails {
+            address: owner.clone(),
+            bte_public_key_with_proof: String::new(),
+            ed25519_identity: String::new(),
+            announce_address: String::new(),
+            assigned_index: 1,
+        };
+        add_current_dealer(deps.as_mut(), &dealer_details);
+
+        // bad checksum: the dealing gets purged so that it could be resubmitted
+        try_submit_dealings_metadata(
+            deps.as_mut(),
+            info.clone(),
+            chunk.dealing_index,
+            dealing_metadata_fixture(),
+            false,
+            Some(dealing_checksum(&[42])),
+        )
+        .unwrap();
+        let res =
+            try_commit_dealings_chunk(deps.as_mut(), env.clone(), info.clone(), chunk.clone())
+                .unwrap();
+        assert_eq!(res.events[0].ty, DEALING_CHECKSUM_MISMATCH_EVENT);
+        assert!(!metadata_exists(
+            deps.as_ref().storage,
+            0,
+            &owner,
+            chunk.dealing_index
+        ));
+        assert!(StoredDealing::read(
+            deps.as_ref().storage,
+            0,
+            &owner,
+            chunk.dealing_index,
+            chunk.chunk_index
+        )
+        .is_none());
+        assert_eq!(
+            CURRENT_EPOCH
+                .load(deps.as_ref().storage)
+                .unwrap()
+                .state_progress
+                .submitted_dealings,
+            0
+        );
+
+        // valid checksum
+        try_submit_dealings_metadata(
+            deps.as_mut(),
+            info.clone(),
+            chunk.dealing_index,
+            dealing_metadata_fixture(),
+            false,
+            Some(dealing_checksum(&chunk.data)),
+        )
+        .unwrap();
+        let res =
+            try_commit_dealings_chunk(deps.as_mut(), env.clone(), info.clone(), chunk.clone())
+                .unwrap();
+        assert!(res.events.is_empty());
+        assert_eq!(
+            CURRENT_EPOCH
+                .load(deps.as_ref().storage)
+                .unwrap()
+                .state_progress
+                .submitted_dealings,
+            1
+        );
+    }
 }
@@ -68,7 +68,13 @@ pub(crate) mod test {
         assert_eq!(epoch.deadline, None);
 
         let env = mock_env();
-        try_initiate_dkg(deps.as_mut(), env.clone(), mock_info(ADMIN_ADDRESS, &[])).unwrap();
+        try_initiate_dkg(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN_ADDRESS, &[]),
+            None,
+        )
+        .unwrap();
 
         let epoch = query_current_epoch(deps.as_mut().storage).unwrap();
         assert_eq!(
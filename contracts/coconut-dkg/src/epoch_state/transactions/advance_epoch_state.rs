@@ -360,7 +360,13 @@ mod tests {
             ContractError::WaitingInitialisation
         );
 
-        try_initiate_dkg(deps.as_mut(), env.clone(), mock_info(ADMIN_ADDRESS, &[])).unwrap();
+        try_initiate_dkg(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN_ADDRESS, &[]),
+            None,
+        )
+        .unwrap();
 
         let epoch = CURRENT_EPOCH.load(deps.as_mut().storage).unwrap();
         assert_eq!(
@@ -585,7 +591,13 @@ mod tests {
     fn verify_threshold() {
         let mut deps = init_contract();
         let mut env = mock_env();
-        try_initiate_dkg(deps.as_mut(), env.clone(), mock_info(ADMIN_ADDRESS, &[])).unwrap();
+        try_initiate_dkg(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN_ADDRESS, &[]),
+            None,
+        )
+        .unwrap();
 
         assert!(THRESHOLD.may_load(deps.as_mut().storage).unwrap().is_none());
 
@@ -3,9 +3,9 @@
 
 use crate::epoch_state::storage::{CURRENT_EPOCH, THRESHOLD};
 use crate::error::ContractError;
-use crate::state::storage::DKG_ADMIN;
+use crate::state::storage::{DKG_ADMIN, PUBLIC_PARAMETERS};
 use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, Storage};
-use nym_coconut_dkg_common::types::{Epoch, EpochState};
+use nym_coconut_dkg_common::types::{DkgPublicParameters, Epoch, EpochState};
 
 pub use advance_epoch_state::try_advance_epoch_state;
 
@@ -25,6 +25,7 @@ pub(crate) fn try_initiate_dkg(
     deps: DepsMut<'_>,
     env: Env,
     info: MessageInfo,
+    public_parameters: Option<DkgPublicParameters>,
 ) -> Result<Response, ContractError> {
     // only the admin is allowed to kick start the process
     DKG_ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
@@ -34,6 +35,14 @@ pub(crate) fn try_initiate_dkg(
         return Err(ContractError::AlreadyInitialised);
     }
 
+    // the parameters stay the same for all subsequent epochs, including resets and resharing
+    if let Some(public_parameters) = public_parameters {
+        if public_parameters.bte_params.is_empty() {
+            return Err(ContractError::EmptyPublicParameters);
+        }
+        PUBLIC_PARAMETERS.save(deps.storage, &public_parameters)?;
+    }
+
     // the first exchange won't involve resharing
     let initial_state = EpochState::PublicKeySubmission { resharing: false };
     let initial_epoch = Epoch::new(initial_state, 0, epoch.time_configuration, env.block.time);
@@ -89,6 +98,7 @@ pub(crate) fn try_trigger_resharing(
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
+    use crate::state::queries::query_public_parameters;
     use crate::support::tests::helpers::{init_contract, ADMIN_ADDRESS};
     use cosmwasm_std::testing::{mock_env, mock_info};
     use cw_controllers::AdminError;
@@ -102,16 +112,31 @@ pub(crate) mod tests {
         assert!(initial_epoch_info.deadline.is_none());
 
         // can only be executed by the admin
-        let res = try_initiate_dkg(deps.as_mut(), env.clone(), mock_info("not an admin", &[]))
-            .unwrap_err();
+        let res = try_initiate_dkg(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("not an admin", &[]),
+            None,
+        )
+        .unwrap_err();
         assert_eq!(ContractError::Admin(AdminError::NotAdmin {}), res);
 
-        let res = try_initiate_dkg(deps.as_mut(), env.clone(), mock_info(ADMIN_ADDRESS, &[]));
+        let res = try_initiate_dkg(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN_ADDRESS, &[]),
+            None,
+        );
         assert!(res.is_ok());
 
         // can't be initialised more than once
-        let res = try_initiate_dkg(deps.as_mut(), env.clone(), mock_info(ADMIN_ADDRESS, &[]))
-            .unwrap_err();
+        let res = try_initiate_dkg(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN_ADDRESS, &[]),
+            None,
+        )
+        .unwrap_err();
         assert_eq!(ContractError::AlreadyInitialised, res);
 
         // sets the correct epoch data
@@ -133,6 +158,43 @@ pub(crate) mod tests {
         );
     }
 
+    #[test]
+    fn initialising_dkg_with_public_parameters() {
+        let mut deps = init_contract();
+        let env = mock_env();
+
+        let empty = DkgPublicParameters {
+            bte_params: Vec::new().into(),
+        };
+        let res = try_initiate_dkg(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN_ADDRESS, &[]),
+            Some(empty),
+        )
+        .unwrap_err();
+        assert_eq!(ContractError::EmptyPublicParameters, res);
+        assert!(query_public_parameters(&deps.storage)
+            .unwrap()
+            .parameters
+            .is_none());
+
+        let params = DkgPublicParameters {
+            bte_params: vec![1, 2, 3].into(),
+        };
+        try_initiate_dkg(
+            deps.as_mut(),
+            env,
+            mock_info(ADMIN_ADDRESS, &[]),
+            Some(params.clone()),
+        )
+        .unwrap();
+        assert_eq!(
+            query_public_parameters(&deps.storage).unwrap().parameters,
+            Some(params)
+        );
+    }
+
     #[test]
     fn reset_state() {
         let mut deps = init_contract();
@@ -22,6 +22,9 @@ pub enum ContractError {
     #[error("Dkg has already been initialised")]
     AlreadyInitialised,
 
+    #[error("the provided DKG public parameters are empty")]
+    EmptyPublicParameters,
+
     #[error("Group contract invalid address '{addr}'")]
     InvalidGroup { addr: String },
 
@@ -128,6 +131,11 @@ pub enum ContractError {
     #[error("No verification key committed for owner {owner}")]
     NoCommitForOwner { owner: String },
 
+    #[error(
+        "dealer {dealer} does not have a verified verification key share for epoch {epoch_id}"
+    )]
+    UnverifiedVerificationKeyShare { dealer: Addr, epoch_id: EpochId },
+
     #[error("cannot perform DKG reset during an ongoing exchange")]
     CantResetDuringExchange,
 
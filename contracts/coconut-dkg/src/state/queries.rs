@@ -1,10 +1,18 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::state::storage::STATE;
+use crate::state::storage::{PUBLIC_PARAMETERS, STATE};
 use cosmwasm_std::{StdResult, Storage};
-use nym_coconut_dkg_common::types::State;
+use nym_coconut_dkg_common::types::{PublicParametersResponse, State};
 
 pub(crate) fn query_state(storage: &dyn Storage) -> StdResult<State> {
     STATE.load(storage)
 }
+
+pub(crate) fn query_public_parameters(
+    storage: &dyn Storage,
+) -> StdResult<PublicParametersResponse> {
+    Ok(PublicParametersResponse {
+        parameters: PUBLIC_PARAMETERS.may_load(storage)?,
+    })
+}
@@ -3,11 +3,13 @@
 
 use cw_controllers::Admin;
 use cw_storage_plus::Item;
-use nym_coconut_dkg_common::types::State;
+use nym_coconut_dkg_common::types::{DkgPublicParameters, State};
 
 // unique items
 pub const DKG_ADMIN: Admin = Admin::new("dkg-admin");
 
 pub const STATE: Item<State> = Item::new("state");
 
+pub const PUBLIC_PARAMETERS: Item<DkgPublicParameters> = Item::new("public_parameters");
+
 pub const MULTISIG: Admin = Admin::new("multisig");
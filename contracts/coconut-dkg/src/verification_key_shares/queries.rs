@@ -2,11 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::verification_key_shares::storage;
-use crate::verification_key_shares::storage::vk_shares;
+use crate::verification_key_shares::storage::{vk_shares, MASTER_VERIFICATION_KEYS};
 use cosmwasm_std::{Deps, Order, StdResult};
 use cw_storage_plus::Bound;
 use nym_coconut_dkg_common::types::EpochId;
-use nym_coconut_dkg_common::verification_key::{PagedVKSharesResponse, VkShareResponse};
+use nym_coconut_dkg_common::verification_key::{
+    MasterVerificationKeyResponse, PagedVKSharesResponse, VkShareResponse,
+};
 
 // TODO: unit tests
 pub fn query_vk_share(
@@ -58,6 +60,16 @@ pub fn query_vk_shares_paged(
     })
 }
 
+pub fn query_master_verification_key(
+    deps: Deps<'_>,
+    epoch_id: EpochId,
+) -> StdResult<MasterVerificationKeyResponse> {
+    Ok(MasterVerificationKeyResponse {
+        epoch_id,
+        key: MASTER_VERIFICATION_KEYS.may_load(deps.storage, epoch_id)?,
+    })
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
@@ -3,15 +3,24 @@
 
 use crate::constants::{VK_SHARES_EPOCH_ID_IDX_NAMESPACE, VK_SHARES_PK_NAMESPACE};
 use cosmwasm_std::Addr;
-use cw_storage_plus::{Index, IndexList, IndexedMap, MultiIndex};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Map, MultiIndex};
 use nym_coconut_dkg_common::types::EpochId;
-use nym_coconut_dkg_common::verification_key::ContractVKShare;
+use nym_coconut_dkg_common::verification_key::{ContractVKShare, MasterVerificationKey};
 
 pub(crate) const VERIFICATION_KEY_SHARES_PAGE_MAX_LIMIT: u32 = 30;
 pub(crate) const VERIFICATION_KEY_SHARES_PAGE_DEFAULT_LIMIT: u32 = 10;
 
 type VKShareKey<'a> = (&'a Addr, EpochId);
 
+/// Master verification keys submitted by individual dealers that are yet to reach the threshold.
+pub(crate) const MASTER_VK_ATTESTATIONS: Map<(EpochId, &Addr), MasterVerificationKey> =
+    Map::new("mvk_attestations");
+
+/// Finalized master verification keys, i.e. ones that the threshold number of dealers has agreed on.
+/// Once set for given epoch, they're never modified.
+pub(crate) const MASTER_VERIFICATION_KEYS: Map<EpochId, MasterVerificationKey> =
+    Map::new("master_vks");
+
 pub(crate) struct VkShareIndex<'a> {
     pub(crate) epoch_id: MultiIndex<'a, EpochId, ContractVKShare, VKShareKey<'a>>,
 }
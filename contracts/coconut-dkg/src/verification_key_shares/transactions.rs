@@ -3,15 +3,17 @@
 
 use crate::constants::BLOCK_TIME_FOR_VERIFICATION_SECS;
 use crate::dealers::storage::get_dealer_details;
-use crate::epoch_state::storage::CURRENT_EPOCH;
+use crate::epoch_state::storage::{CURRENT_EPOCH, EPOCH_THRESHOLDS};
 use crate::epoch_state::utils::check_epoch_state;
 use crate::error::ContractError;
 use crate::state::storage::{MULTISIG, STATE};
-use crate::verification_key_shares::storage::vk_shares;
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+use crate::verification_key_shares::storage::{
+    vk_shares, MASTER_VERIFICATION_KEYS, MASTER_VK_ATTESTATIONS,
+};
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Order, Response, StdResult};
 use nym_coconut_dkg_common::types::EpochState;
 use nym_coconut_dkg_common::verification_key::{
-    to_cosmos_msg, ContractVKShare, VerificationKeyShare,
+    to_cosmos_msg, ContractVKShare, MasterVerificationKey, VerificationKeyShare,
 };
 
 pub fn try_commit_verification_key_share(
@@ -98,6 +100,58 @@ pub fn try_verify_verification_key_share(
     Ok(Response::default())
 }
 
+pub fn try_commit_master_verification_key(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    key: MasterVerificationKey,
+) -> Result<Response, ContractError> {
+    check_epoch_state(deps.storage, EpochState::InProgress)?;
+    let epoch_id = CURRENT_EPOCH.load(deps.storage)?.epoch_id;
+
+    // only dealers whose shares went into the aggregated key are allowed to attest to it
+    let share = vk_shares()
+        .may_load(deps.storage, (&info.sender, epoch_id))?
+        .ok_or(ContractError::NotADealer { epoch_id })?;
+    if !share.verified {
+        return Err(ContractError::UnverifiedVerificationKeyShare {
+            dealer: info.sender,
+            epoch_id,
+        });
+    }
+
+    if MASTER_VK_ATTESTATIONS.has(deps.storage, (epoch_id, &info.sender)) {
+        return Err(ContractError::AlreadyCommitted {
+            commitment: String::from("master verification key"),
+        });
+    }
+    MASTER_VK_ATTESTATIONS.save(deps.storage, (epoch_id, &info.sender), &key)?;
+
+    // the key has already been finalized, nothing more to do
+    if MASTER_VERIFICATION_KEYS.has(deps.storage, epoch_id) {
+        return Ok(Response::default());
+    }
+
+    let threshold = EPOCH_THRESHOLDS
+        .may_load(deps.storage, epoch_id)?
+        .ok_or(ContractError::EpochNotInitialised)?;
+
+    // the number of dealers per epoch is small, so it's fine to iterate through all of them
+    let matching = MASTER_VK_ATTESTATIONS
+        .prefix(epoch_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .filter(|(_, attested)| attested == &key)
+        .count() as u64;
+
+    if matching < threshold {
+        return Ok(Response::default());
+    }
+
+    MASTER_VERIFICATION_KEYS.save(deps.storage, epoch_id, &key)?;
+    Ok(Response::new().add_attribute("finalized_master_verification_key", epoch_id.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,17 +160,24 @@ mod tests {
     use crate::support::tests::helpers::{
         add_current_dealer, add_fixture_dealer, ADMIN_ADDRESS, MULTISIG_CONTRACT,
     };
+    use crate::verification_key_shares::queries::query_master_verification_key;
     use cosmwasm_std::testing::{mock_env, mock_info};
-    use cosmwasm_std::Addr;
+    use cosmwasm_std::{Addr, Deps};
     use cw_controllers::AdminError;
     use nym_coconut_dkg_common::dealer::DealerDetails;
-    use nym_coconut_dkg_common::types::TimeConfiguration;
+    use nym_coconut_dkg_common::types::{Epoch, TimeConfiguration};
 
     #[test]
     fn current_epoch_id() {
         let mut deps = helpers::init_contract();
         let mut env = mock_env();
-        try_initiate_dkg(deps.as_mut(), env.clone(), mock_info(ADMIN_ADDRESS, &[])).unwrap();
+        try_initiate_dkg(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN_ADDRESS, &[]),
+            None,
+        )
+        .unwrap();
 
         let info = mock_info("requester", &[]);
         let share = "share".to_string();
@@ -163,7 +224,13 @@ mod tests {
     fn commit_vk_share() {
         let mut deps = helpers::init_contract();
         let mut env = mock_env();
-        try_initiate_dkg(deps.as_mut(), env.clone(), mock_info(ADMIN_ADDRESS, &[])).unwrap();
+        try_initiate_dkg(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN_ADDRESS, &[]),
+            None,
+        )
+        .unwrap();
 
         let info = mock_info("requester", &[]);
         let share = "share".to_string();
@@ -238,7 +305,13 @@ mod tests {
     fn invalid_verify_vk_share() {
         let mut deps = helpers::init_contract();
         let mut env = mock_env();
-        try_initiate_dkg(deps.as_mut(), env.clone(), mock_info(ADMIN_ADDRESS, &[])).unwrap();
+        try_initiate_dkg(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN_ADDRESS, &[]),
+            None,
+        )
+        .unwrap();
 
         let info = mock_info("requester", &[]);
         let owner = "owner".to_string();
@@ -297,7 +370,13 @@ mod tests {
     fn verify_vk_share() {
         let mut deps = helpers::init_contract();
         let mut env = mock_env();
-        try_initiate_dkg(deps.as_mut(), env.clone(), mock_info(ADMIN_ADDRESS, &[])).unwrap();
+        try_initiate_dkg(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN_ADDRESS, &[]),
+            None,
+        )
+        .unwrap();
 
         let owner = "owner".to_string();
         let info = mock_info(owner.as_ref(), &[]);
@@ -340,4 +419,99 @@ mod tests {
 
         try_verify_verification_key_share(deps.as_mut(), multisig_info, owner, false).unwrap();
     }
+
+    #[test]
+    fn master_verification_key_is_finalized_at_threshold() {
+        let mut deps = helpers::init_contract();
+        let env = mock_env();
+        let epoch_id = 0;
+        CURRENT_EPOCH
+            .save(
+                deps.as_mut().storage,
+                &Epoch::new(
+                    EpochState::InProgress,
+                    epoch_id,
+                    TimeConfiguration::default(),
+                    env.block.time,
+                ),
+            )
+            .unwrap();
+        EPOCH_THRESHOLDS
+            .save(deps.as_mut().storage, epoch_id, &2)
+            .unwrap();
+
+        for (i, owner) in ["dealer1", "dealer2", "dealer3", "unverified"]
+            .into_iter()
+            .enumerate()
+        {
+            let owner = Addr::unchecked(owner);
+            let share = ContractVKShare {
+                share: String::new(),
+                announce_address: String::new(),
+                node_index: i as u64 + 1,
+                owner: owner.clone(),
+                epoch_id,
+                verified: owner.as_str() != "unverified",
+            };
+            vk_shares()
+                .save(deps.as_mut().storage, (&owner, epoch_id), &share)
+                .unwrap();
+        }
+
+        let key = "master-key".to_string();
+        let query = |deps: Deps<'_>| query_master_verification_key(deps, epoch_id).unwrap().key;
+
+        let err = try_commit_master_verification_key(
+            deps.as_mut(),
+            mock_info("unverified", &[]),
+            key.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UnverifiedVerificationKeyShare {
+                dealer: Addr::unchecked("unverified"),
+                epoch_id
+            }
+        );
+
+        let err = try_commit_master_verification_key(
+            deps.as_mut(),
+            mock_info("random", &[]),
+            key.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotADealer { epoch_id });
+
+        // a single attestation is not enough
+        try_commit_master_verification_key(deps.as_mut(), mock_info("dealer1", &[]), key.clone())
+            .unwrap();
+        assert!(query(deps.as_ref()).is_none());
+
+        // nor is a conflicting one
+        try_commit_master_verification_key(
+            deps.as_mut(),
+            mock_info("dealer2", &[]),
+            "bad-key".to_string(),
+        )
+        .unwrap();
+        assert!(query(deps.as_ref()).is_none());
+
+        let err = try_commit_master_verification_key(
+            deps.as_mut(),
+            mock_info("dealer1", &[]),
+            key.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AlreadyCommitted {
+                commitment: String::from("master verification key")
+            }
+        );
+
+        try_commit_master_verification_key(deps.as_mut(), mock_info("dealer3", &[]), key.clone())
+            .unwrap();
+        assert_eq!(query(deps.as_ref()), Some(key));
+    }
 }
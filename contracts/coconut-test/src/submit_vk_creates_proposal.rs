@@ -103,7 +103,9 @@ fn dkg_proposal() {
     app.execute_contract(
         Addr::unchecked(OWNER),
         coconut_dkg_contract_addr.clone(),
-        &InitiateDkg {},
+        &InitiateDkg {
+            public_parameters: None,
+        },
         &[],
     )
     .unwrap();
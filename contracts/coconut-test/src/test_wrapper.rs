@@ -8,7 +8,9 @@ use cw4::{Cw4Contract, Member};
 use cw_multi_test::{App, AppBuilder, Executor};
 use cw_utils::{Duration, Threshold};
 use nym_coconut_bandwidth_contract_common::msg::InstantiateMsg as BandwidthInstantiateMsg;
-use nym_coconut_dkg_common::dealing::{chunk_dealing, DealingChunkInfo, MAX_DEALING_CHUNK_SIZE};
+use nym_coconut_dkg_common::dealing::{
+    chunk_dealing, dealing_checksum, DealingChunkInfo, MAX_DEALING_CHUNK_SIZE,
+};
 use nym_coconut_dkg_common::msg::ExecuteMsg as DkgExecuteMsg;
 use nym_coconut_dkg_common::msg::InstantiateMsg as DkgInstantiateMsg;
 use nym_coconut_dkg_common::msg::QueryMsg as DkgQueryMsg;
@@ -249,7 +251,9 @@ impl TestSetup {
             .execute_contract(
                 self.admin(),
                 self.dkg_contract.clone(),
-                &DkgExecuteMsg::InitiateDkg {},
+                &DkgExecuteMsg::InitiateDkg {
+                    public_parameters: None,
+                },
                 &[],
             )
             .unwrap();
@@ -328,6 +332,7 @@ impl TestSetup {
                         dealing_index,
                         chunks,
                         resharing,
+                        checksum: Some(dealing_checksum(&dealing_bytes)),
                     },
                     &[],
                 )
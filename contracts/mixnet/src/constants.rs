@@ -38,6 +38,13 @@ pub const REWARDED_SET_MAX_RETRIEVAL_LIMIT: u32 = 1000;
 pub const FAMILIES_DEFAULT_RETRIEVAL_LIMIT: u32 = 10;
 pub const FAMILIES_MAX_RETRIEVAL_LIMIT: u32 = 20;
 
+pub const CONFIG_CHANGELOG_DEFAULT_RETRIEVAL_LIMIT: u32 = 50;
+pub const CONFIG_CHANGELOG_MAX_RETRIEVAL_LIMIT: u32 = 100;
+
+/// Maximum number of delegations whose rewards are withdrawn in a single `ClaimAllDelegatorRewards` call,
+/// so that the gas usage stayed bounded regardless of the number of delegations.
+pub const CLAIM_ALL_REWARDS_PAGE_SIZE: usize = 25;
+
 // storage keys
 pub const DELEGATION_PK_NAMESPACE: &str = "dl";
 pub const DELEGATION_OWNER_IDX_NAMESPACE: &str = "dlo";
@@ -59,6 +66,9 @@ pub const LAST_INTERVAL_EVENT_ID_KEY: &str = "lie";
 
 pub const ADMIN_STORAGE_KEY: &str = "admin";
 pub const CONTRACT_STATE_KEY: &str = "state";
+pub const GOVERNANCE_ADDRESS_KEY: &str = "gov";
+pub const CONFIG_CHANGE_ID_COUNTER_KEY: &str = "cci";
+pub const CONFIG_CHANGELOG_NAMESPACE: &str = "ccl";
 
 pub const LAYER_DISTRIBUTION_KEY: &str = "layers";
 pub const NODE_ID_COUNTER_KEY: &str = "nic";
@@ -75,6 +85,8 @@ pub const UNBONDED_MIXNODES_IDENTITY_IDX_NAMESPACE: &str = "umi";
 pub const REWARDING_PARAMS_KEY: &str = "rparams";
 pub const PENDING_REWARD_POOL_KEY: &str = "prp";
 pub const MIXNODES_REWARDING_PK_NAMESPACE: &str = "mnr";
+pub const CLAIM_ALL_REWARDS_CURSORS_NAMESPACE: &str = "carc";
+pub const OPERATOR_PAYOUT_ADDRESSES_NAMESPACE: &str = "opa";
 
 pub const FAMILIES_INDEX_NAMESPACE: &str = "faml2";
 pub const FAMILIES_MAP_NAMESPACE: &str = "fam2";
@@ -145,6 +145,11 @@ pub fn execute(
                 updated_parameters,
             )
         }
+        ExecuteMsg::UpdateGovernanceAddress { address } => {
+            crate::mixnet_contract_settings::transactions::try_update_governance_address(
+                deps, info, address,
+            )
+        }
         ExecuteMsg::UpdateActiveSetSize {
             active_set_size,
             force_immediately,
@@ -177,6 +182,16 @@ pub fn execute(
             epoch_duration_secs,
             force_immediately,
         ),
+        ExecuteMsg::UpdateStakingFloors {
+            update,
+            force_immediately,
+        } => crate::mixnet_contract_settings::transactions::try_update_staking_floors(
+            deps,
+            env,
+            info,
+            update,
+            force_immediately,
+        ),
         ExecuteMsg::BeginEpochTransition {} => {
             crate::interval::transactions::try_begin_epoch_transition(deps, env, info)
         }
@@ -225,6 +240,15 @@ pub fn execute(
         ExecuteMsg::UpdateMixnodeConfig { new_config } => {
             crate::mixnodes::transactions::try_update_mixnode_config(deps, info, new_config)
         }
+        ExecuteMsg::UpdateOperatorPayoutAddress {
+            payout_address,
+            node_signature,
+        } => crate::mixnodes::transactions::try_update_operator_payout_address(
+            deps,
+            info,
+            payout_address,
+            node_signature,
+        ),
 
         // gateway-related:
         ExecuteMsg::BondGateway {
@@ -263,9 +287,17 @@ pub fn execute(
         ExecuteMsg::WithdrawOperatorReward {} => {
             crate::rewards::transactions::try_withdraw_operator_reward(deps, info)
         }
+        ExecuteMsg::WithdrawOperatorRewardToPayoutAddress { mix_id } => {
+            crate::rewards::transactions::try_withdraw_operator_reward_to_payout_address(
+                deps, info, mix_id,
+            )
+        }
         ExecuteMsg::WithdrawDelegatorReward { mix_id } => {
             crate::rewards::transactions::try_withdraw_delegator_reward(deps, info, mix_id)
         }
+        ExecuteMsg::ClaimAllDelegatorRewards {} => {
+            crate::rewards::transactions::try_claim_all_delegator_rewards(deps, info)
+        }
 
         // vesting migration:
         ExecuteMsg::MigrateVestedMixNode { .. } => {
@@ -274,6 +306,14 @@ pub fn execute(
         ExecuteMsg::MigrateVestedDelegation { mix_id } => {
             crate::vesting_migration::try_migrate_vested_delegation(deps, info, mix_id)
         }
+        ExecuteMsg::MigrateVestedMixNodeOnBehalf { owner } => {
+            crate::vesting_migration::try_migrate_vested_mixnode_on_behalf(deps, info, owner)
+        }
+        ExecuteMsg::MigrateVestedDelegationOnBehalf { owner, mix_id } => {
+            crate::vesting_migration::try_migrate_vested_delegation_on_behalf(
+                deps, info, owner, mix_id,
+            )
+        }
 
         // legacy vesting
         ExecuteMsg::CreateFamilyOnBehalf { .. }
@@ -348,6 +388,9 @@ pub fn query(
         QueryMsg::GetRewardingParams {} => {
             to_binary(&crate::rewards::queries::query_rewarding_params(deps)?)
         }
+        QueryMsg::GetStakingFloors {} => {
+            to_binary(&crate::mixnet_contract_settings::queries::query_staking_floors(deps)?)
+        }
         QueryMsg::GetEpochStatus {} => {
             to_binary(&crate::interval::queries::query_epoch_status(deps)?)
         }
@@ -401,6 +444,9 @@ pub fn query(
         QueryMsg::GetMixnodeRewardingDetails { mix_id } => to_binary(
             &crate::mixnodes::queries::query_mixnode_rewarding_details(deps, mix_id)?,
         ),
+        QueryMsg::GetOperatorPayoutAddress { mix_id } => to_binary(
+            &crate::mixnodes::queries::query_operator_payout_address(deps, mix_id)?,
+        ),
         QueryMsg::GetStakeSaturation { mix_id } => to_binary(
             &crate::mixnodes::queries::query_stake_saturation(deps, mix_id)?,
         ),
@@ -527,6 +573,18 @@ pub fn query(
             &crate::interval::queries::query_number_of_pending_events(deps)?,
         ),
 
+        // governance-related
+        QueryMsg::GetGovernanceAddress {} => {
+            to_binary(&crate::mixnet_contract_settings::queries::query_governance_address(deps)?)
+        }
+        QueryMsg::GetConfigChangelog { limit, start_after } => to_binary(
+            &crate::mixnet_contract_settings::queries::query_config_changelog_paged(
+                deps,
+                start_after,
+                limit,
+            )?,
+        ),
+
         // signing-related
         QueryMsg::GetSigningNonce { address } => to_binary(
             &crate::signing::queries::query_current_signing_nonce(deps, address)?,
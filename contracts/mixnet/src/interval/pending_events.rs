@@ -7,7 +7,8 @@ use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::events::{
     new_active_set_update_event, new_delegation_event, new_delegation_on_unbonded_node_event,
     new_mixnode_cost_params_update_event, new_mixnode_unbonding_event, new_pledge_decrease_event,
-    new_pledge_increase_event, new_rewarding_params_update_event, new_undelegation_event,
+    new_pledge_increase_event, new_rewarding_params_update_event, new_settings_update_event,
+    new_undelegation_event, EVENT_CREATION_HEIGHT_KEY,
 };
 use mixnet_contract_common::mixnode::MixNodeCostParams;
 use mixnet_contract_common::pending_events::{
@@ -15,12 +16,13 @@ use mixnet_contract_common::pending_events::{
     PendingIntervalEventKind,
 };
 use mixnet_contract_common::reward_params::IntervalRewardingParamsUpdate;
-use mixnet_contract_common::{BlockHeight, Delegation, MixId};
+use mixnet_contract_common::{BlockHeight, Delegation, MixId, StakingFloorsUpdate};
 
 use crate::delegations;
 use crate::delegations::storage as delegations_storage;
 use crate::interval::helpers::change_interval_config;
 use crate::interval::storage;
+use crate::mixnet_contract_settings::storage as mixnet_params_storage;
 use crate::mixnodes::helpers::{cleanup_post_unbond_mixnode_storage, get_mixnode_details_by_id};
 use crate::mixnodes::storage as mixnodes_storage;
 use crate::rewards::storage as rewards_storage;
@@ -174,16 +176,30 @@ pub(crate) fn unbond_mixnode(
         .operator_pledge_with_reward(rewarding_denom);
 
     let owner = &node_details.bond_information.owner;
+    let payout_address =
+        rewards_storage::OPERATOR_PAYOUT_ADDRESSES.may_load(deps.storage, mix_id)?;
 
     // remove the bond and if there are no delegations left, also the rewarding information
     // decrement the associated layer count
     cleanup_post_unbond_mixnode_storage(deps.storage, env, &node_details)?;
 
-    let response = Response::new()
-        .send_tokens(owner, tokens.clone())
-        .add_event(new_mixnode_unbonding_event(created_at, mix_id));
+    let mut response = Response::new();
+    if let Some(payout_address) = payout_address {
+        // the pledge goes back to the owner, while the rewards go to the payout address
+        let reward = node_details.pending_operator_reward();
+        let pledge = Coin {
+            amount: tokens.amount.saturating_sub(reward.amount),
+            denom: tokens.denom,
+        };
+        response = response.send_tokens(owner, pledge);
+        if !reward.amount.is_zero() {
+            response = response.send_tokens(&payout_address, reward);
+        }
+    } else {
+        response = response.send_tokens(owner, tokens);
+    }
 
-    Ok(response)
+    Ok(response.add_event(new_mixnode_unbonding_event(created_at, mix_id)))
 }
 
 pub(crate) fn update_active_set_size(
@@ -399,6 +415,25 @@ pub(crate) fn update_interval_config(
     )
 }
 
+pub(crate) fn update_staking_floors(
+    deps: DepsMut,
+    created_at: BlockHeight,
+    update: StakingFloorsUpdate,
+) -> Result<Response, MixnetContractError> {
+    // We don't have to check for authorization as this event can only be pushed
+    // by the authorized entity.
+    // Also, we know the update is valid as we checked for that before pushing the event onto the queue.
+    let mut state = mixnet_params_storage::CONTRACT_STATE.load(deps.storage)?;
+    let old_params = state.params.clone();
+    update.apply_to(&mut state.params);
+    mixnet_params_storage::CONTRACT_STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_event(
+        new_settings_update_event(&old_params, &state.params)
+            .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string()),
+    ))
+}
+
 impl ContractExecutableEvent for PendingIntervalEventData {
     fn execute(self, deps: DepsMut<'_>, _env: &Env) -> Result<Response, MixnetContractError> {
         // note that the basic validation on all those events was already performed before
@@ -420,6 +455,9 @@ impl ContractExecutableEvent for PendingIntervalEventData {
                 epochs_in_interval,
                 epoch_duration_secs,
             ),
+            PendingIntervalEventKind::UpdateStakingFloors { update } => {
+                update_staking_floors(deps, self.created_at, update)
+            }
         }
     }
 }
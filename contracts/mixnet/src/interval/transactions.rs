@@ -5,12 +5,13 @@ use super::storage;
 use crate::interval::helpers::change_interval_config;
 use crate::interval::pending_events::ContractExecutableEvent;
 use crate::interval::storage::push_new_interval_event;
-use crate::mixnet_contract_settings::storage::ADMIN;
+use crate::mixnet_contract_settings::storage::record_config_change;
 use crate::mixnodes::transactions::update_mixnode_layer;
 use crate::rewards;
 use crate::rewards::storage as rewards_storage;
 use crate::support::helpers::{
-    ensure_can_advance_epoch, ensure_epoch_in_progress_state, ensure_is_authorized,
+    ensure_can_advance_epoch, ensure_epoch_in_progress_state, ensure_is_admin_or_governance,
+    ensure_is_authorized,
 };
 use cosmwasm_std::{DepsMut, Env, MessageInfo, Order, Response, Storage};
 use mixnet_contract_common::error::MixnetContractError;
@@ -18,9 +19,10 @@ use mixnet_contract_common::events::{
     new_advance_epoch_event, new_epoch_transition_start_event,
     new_pending_epoch_events_execution_event, new_pending_interval_config_update_event,
     new_pending_interval_events_execution_event, new_reconcile_pending_events,
+    CONFIG_CHANGE_ID_KEY,
 };
 use mixnet_contract_common::pending_events::PendingIntervalEventKind;
-use mixnet_contract_common::{EpochState, EpochStatus, LayerAssignment, MixId};
+use mixnet_contract_common::{ConfigChangeKind, EpochState, EpochStatus, LayerAssignment, MixId};
 use std::collections::BTreeSet;
 
 // those two should be called in separate tx (from advancing epoch),
@@ -326,7 +328,7 @@ pub(crate) fn try_update_interval_config(
     epoch_duration_secs: u64,
     force_immediately: bool,
 ) -> Result<Response, MixnetContractError> {
-    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    ensure_is_admin_or_governance(deps.as_ref(), &info.sender)?;
 
     if epochs_in_interval == 0 {
         return Err(MixnetContractError::EpochsInIntervalZero);
@@ -336,15 +338,29 @@ pub(crate) fn try_update_interval_config(
         return Err(MixnetContractError::EpochDurationZero);
     }
 
+    let change = ConfigChangeKind::IntervalConfig {
+        epochs_in_interval,
+        epoch_duration_secs,
+    };
+
     let interval = storage::current_interval(deps.storage)?;
     if force_immediately || interval.is_current_interval_over(&env) {
-        change_interval_config(
+        let change_id = record_config_change(
+            deps.storage,
+            &env,
+            info.sender,
+            env.block.time.seconds() as i64,
+            force_immediately,
+            change,
+        )?;
+        Ok(change_interval_config(
             deps.storage,
             env.block.height,
             interval,
             epochs_in_interval,
             epoch_duration_secs,
-        )
+        )?
+        .add_attribute(CONFIG_CHANGE_ID_KEY, change_id.to_string()))
     } else {
         // changing interval config is only allowed if the epoch is currently not in the process of being advanced
         // (unless the force flag was used)
@@ -356,14 +372,23 @@ pub(crate) fn try_update_interval_config(
             epoch_duration_secs,
         };
         push_new_interval_event(deps.storage, &env, interval_event)?;
+        let change_id = record_config_change(
+            deps.storage,
+            &env,
+            info.sender,
+            interval.current_interval_end_unix_timestamp(),
+            false,
+            change,
+        )?;
         let time_left = interval.secs_until_current_interval_end(&env);
-        Ok(
-            Response::new().add_event(new_pending_interval_config_update_event(
+        Ok(Response::new().add_event(
+            new_pending_interval_config_update_event(
                 epochs_in_interval,
                 epoch_duration_secs,
                 time_left,
-            )),
-        )
+            )
+            .add_attribute(CONFIG_CHANGE_ID_KEY, change_id.to_string()),
+        ))
     }
 }
 
@@ -1843,6 +1868,79 @@ mod tests {
             assert!(res.is_ok())
         }
 
+        #[test]
+        fn can_be_done_by_governance_module() {
+            let mut test = TestSetup::new();
+            let governance = mock_info("governance", &[]);
+            let env = test.env();
+
+            let res = try_update_interval_config(
+                test.deps_mut(),
+                env.clone(),
+                governance.clone(),
+                100,
+                1000,
+                false,
+            );
+            assert_eq!(res, Err(MixnetContractError::Admin(NotAdmin {})));
+
+            crate::mixnet_contract_settings::storage::GOVERNANCE_ADDRESS
+                .save(test.deps_mut().storage, &Addr::unchecked("governance"))
+                .unwrap();
+
+            let res =
+                try_update_interval_config(test.deps_mut(), env, governance, 100, 1000, false);
+            assert!(res.is_ok())
+        }
+
+        #[test]
+        fn changes_are_recorded_in_the_changelog() {
+            let mut test = TestSetup::new();
+            let owner = test.owner();
+            let env = test.env();
+            let interval = test.current_interval();
+
+            try_update_interval_config(
+                test.deps_mut(),
+                env.clone(),
+                owner.clone(),
+                100,
+                1000,
+                false,
+            )
+            .unwrap();
+            try_update_interval_config(test.deps_mut(), env.clone(), owner.clone(), 50, 500, true)
+                .unwrap();
+
+            let changelog = crate::mixnet_contract_settings::queries::query_config_changelog_paged(
+                test.deps(),
+                None,
+                None,
+            )
+            .unwrap();
+            assert_eq!(changelog.changes.len(), 2);
+
+            let delayed = &changelog.changes[0].change;
+            assert_eq!(delayed.requested_by, owner.sender);
+            assert_eq!(delayed.requested_at, env.block.height);
+            assert!(!delayed.forced);
+            assert_eq!(
+                delayed.activation_timestamp,
+                interval.current_interval_end_unix_timestamp()
+            );
+            assert_eq!(
+                delayed.kind,
+                ConfigChangeKind::IntervalConfig {
+                    epochs_in_interval: 100,
+                    epoch_duration_secs: 1000,
+                }
+            );
+
+            let forced = &changelog.changes[1].change;
+            assert!(forced.forced);
+            assert_eq!(forced.activation_timestamp, env.block.time.seconds() as i64);
+        }
+
         #[test]
         fn if_interval_is_finished_change_happens_immediately() {
             let mut test = TestSetup::new();
@@ -2,10 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::storage;
+use crate::constants::{
+    CONFIG_CHANGELOG_DEFAULT_RETRIEVAL_LIMIT, CONFIG_CHANGELOG_MAX_RETRIEVAL_LIMIT,
+};
+use crate::interval::storage as interval_storage;
 use crate::mixnet_contract_settings::storage::ADMIN;
-use cosmwasm_std::{Deps, StdResult};
+use cosmwasm_std::{Deps, Order, StdResult};
 use cw_controllers::AdminResponse;
-use mixnet_contract_common::{ContractBuildInformation, ContractState, ContractStateParams};
+use cw_storage_plus::Bound;
+use mixnet_contract_common::{
+    ConfigChangeId, ConfigChangelogEntry, ContractBuildInformation, ContractState,
+    ContractStateParams, GovernanceAddressResponse, PagedConfigChangelogResponse,
+    PendingIntervalEventKind, StakingFloorsResponse,
+};
 use nym_contracts_common::get_build_information;
 
 pub(crate) fn query_admin(deps: Deps<'_>) -> StdResult<AdminResponse> {
@@ -22,6 +31,38 @@ pub(crate) fn query_contract_settings_params(deps: Deps<'_>) -> StdResult<Contra
         .map(|settings| settings.params)
 }
 
+pub(crate) fn query_staking_floors(deps: Deps<'_>) -> StdResult<StakingFloorsResponse> {
+    let params = query_contract_settings_params(deps)?;
+
+    // there are only ever going to be very few pending interval events, so it's fine to go through all of them
+    let pending_updates = interval_storage::PENDING_INTERVAL_EVENTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter_map(|res| match res {
+            Ok((_, event)) => match event.kind {
+                PendingIntervalEventKind::UpdateStakingFloors { update } => Some(Ok(update)),
+                _ => None,
+            },
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let pending_activation_timestamp = if pending_updates.is_empty() {
+        None
+    } else {
+        Some(
+            interval_storage::current_interval(deps.storage)?.current_interval_end_unix_timestamp(),
+        )
+    };
+
+    Ok(StakingFloorsResponse {
+        minimum_mixnode_pledge: params.minimum_mixnode_pledge,
+        minimum_gateway_pledge: params.minimum_gateway_pledge,
+        minimum_mixnode_delegation: params.minimum_mixnode_delegation,
+        pending_updates,
+        pending_activation_timestamp,
+    })
+}
+
 pub(crate) fn query_rewarding_validator_address(deps: Deps<'_>) -> StdResult<String> {
     storage::CONTRACT_STATE
         .load(deps.storage)
@@ -32,6 +73,34 @@ pub(crate) fn query_contract_version() -> ContractBuildInformation {
     get_build_information!()
 }
 
+pub(crate) fn query_governance_address(deps: Deps<'_>) -> StdResult<GovernanceAddressResponse> {
+    Ok(GovernanceAddressResponse {
+        governance_address: storage::GOVERNANCE_ADDRESS.may_load(deps.storage)?,
+    })
+}
+
+pub(crate) fn query_config_changelog_paged(
+    deps: Deps<'_>,
+    start_after: Option<ConfigChangeId>,
+    limit: Option<u32>,
+) -> StdResult<PagedConfigChangelogResponse> {
+    let limit = limit
+        .unwrap_or(CONFIG_CHANGELOG_DEFAULT_RETRIEVAL_LIMIT)
+        .min(CONFIG_CHANGELOG_MAX_RETRIEVAL_LIMIT) as usize;
+
+    let start = start_after.map(Bound::exclusive);
+
+    let changes = storage::CONFIG_CHANGELOG
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(ConfigChangelogEntry::from))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let start_next_after = changes.last().map(|entry| entry.id);
+
+    Ok(PagedConfigChangelogResponse::new(changes, start_next_after))
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
@@ -1,17 +1,34 @@
 // Copyright 2021-2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::constants::{ADMIN_STORAGE_KEY, CONTRACT_STATE_KEY};
-use cosmwasm_std::{Addr, DepsMut, Storage};
+use crate::constants::{
+    ADMIN_STORAGE_KEY, CONFIG_CHANGELOG_NAMESPACE, CONFIG_CHANGE_ID_COUNTER_KEY,
+    CONTRACT_STATE_KEY, GOVERNANCE_ADDRESS_KEY,
+};
+use cosmwasm_std::{Addr, DepsMut, Env, Storage};
 use cosmwasm_std::{Coin, StdResult};
 use cw_controllers::Admin;
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map};
 use mixnet_contract_common::error::MixnetContractError;
-use mixnet_contract_common::{ContractState, OperatingCostRange, ProfitMarginRange};
+use mixnet_contract_common::{
+    ConfigChange, ConfigChangeId, ConfigChangeKind, ContractState, OperatingCostRange,
+    ProfitMarginRange,
+};
 
 pub(crate) const CONTRACT_STATE: Item<'_, ContractState> = Item::new(CONTRACT_STATE_KEY);
 pub(crate) const ADMIN: Admin = Admin::new(ADMIN_STORAGE_KEY);
 
+/// Address of the governance module that, alongside the admin, is allowed to update
+/// the interval configuration and the rewarding parameters.
+pub(crate) const GOVERNANCE_ADDRESS: Item<'_, Addr> = Item::new(GOVERNANCE_ADDRESS_KEY);
+
+pub(crate) const CONFIG_CHANGE_ID_COUNTER: Item<ConfigChangeId> =
+    Item::new(CONFIG_CHANGE_ID_COUNTER_KEY);
+
+/// Contains the history of all system parameter changes.
+pub(crate) const CONFIG_CHANGELOG: Map<ConfigChangeId, ConfigChange> =
+    Map::new(CONFIG_CHANGELOG_NAMESPACE);
+
 pub fn rewarding_validator_address(storage: &dyn Storage) -> Result<Addr, MixnetContractError> {
     Ok(CONTRACT_STATE
         .load(storage)
@@ -75,3 +92,34 @@ pub(crate) fn initialise_storage(
     CONTRACT_STATE.save(deps.storage, &initial_state)?;
     ADMIN.set(deps, Some(initial_admin))
 }
+
+pub(crate) fn next_config_change_id_counter(store: &mut dyn Storage) -> StdResult<ConfigChangeId> {
+    // the counter didn't exist before the changelog got introduced, so we can't assume it's present
+    let id = CONFIG_CHANGE_ID_COUNTER
+        .may_load(store)?
+        .unwrap_or_default()
+        + 1;
+    CONFIG_CHANGE_ID_COUNTER.save(store, &id)?;
+    Ok(id)
+}
+
+pub(crate) fn record_config_change(
+    storage: &mut dyn Storage,
+    env: &Env,
+    requested_by: Addr,
+    activation_timestamp: i64,
+    forced: bool,
+    kind: ConfigChangeKind,
+) -> StdResult<ConfigChangeId> {
+    let id = next_config_change_id_counter(storage)?;
+    let change = ConfigChange {
+        requested_by,
+        requested_at: env.block.height,
+        requested_at_timestamp: env.block.time.seconds() as i64,
+        activation_timestamp,
+        forced,
+        kind,
+    };
+    CONFIG_CHANGELOG.save(storage, id, &change)?;
+    Ok(id)
+}
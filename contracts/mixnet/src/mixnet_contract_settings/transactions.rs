@@ -2,15 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::storage;
-use crate::mixnet_contract_settings::storage::ADMIN;
+use crate::interval::storage as interval_storage;
+use crate::interval::storage::push_new_interval_event;
+use crate::mixnet_contract_settings::storage::{record_config_change, ADMIN};
+use crate::support::helpers::{ensure_epoch_in_progress_state, ensure_is_admin_or_governance};
 use cosmwasm_std::MessageInfo;
 use cosmwasm_std::Response;
-use cosmwasm_std::{DepsMut, StdResult};
+use cosmwasm_std::{Coin, DepsMut, Env, StdResult};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::events::{
-    new_rewarding_validator_address_update_event, new_settings_update_event,
+    new_governance_address_update_event, new_pending_staking_floors_update_event,
+    new_rewarding_validator_address_update_event, new_settings_update_event, CONFIG_CHANGE_ID_KEY,
 };
-use mixnet_contract_common::ContractStateParams;
+use mixnet_contract_common::pending_events::PendingIntervalEventKind;
+use mixnet_contract_common::{ConfigChangeKind, ContractStateParams, StakingFloorsUpdate};
 
 pub fn try_update_contract_admin(
     mut deps: DepsMut<'_>,
@@ -55,6 +60,31 @@ pub fn try_update_rewarding_validator_address(
     )
 }
 
+pub(crate) fn try_update_governance_address(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    address: Option<String>,
+) -> Result<Response, MixnetContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let old_address = storage::GOVERNANCE_ADDRESS.may_load(deps.storage)?;
+    let new_address = address
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+
+    match &new_address {
+        Some(address) => storage::GOVERNANCE_ADDRESS.save(deps.storage, address)?,
+        None => storage::GOVERNANCE_ADDRESS.remove(deps.storage),
+    }
+
+    Ok(
+        Response::new().add_event(new_governance_address_update_event(
+            old_address,
+            new_address,
+        )),
+    )
+}
+
 pub(crate) fn try_update_contract_settings(
     deps: DepsMut<'_>,
     info: MessageInfo,
@@ -72,6 +102,102 @@ pub(crate) fn try_update_contract_settings(
     Ok(response)
 }
 
+fn validate_staking_floors_update(
+    update: &StakingFloorsUpdate,
+    rewarding_denom: &str,
+) -> Result<(), MixnetContractError> {
+    if !update.contains_updates() {
+        return Err(MixnetContractError::EmptyStakingFloorsUpdate);
+    }
+
+    let ensure_denom = |coin: &Coin| {
+        if coin.denom != rewarding_denom {
+            return Err(MixnetContractError::WrongDenom {
+                received: coin.denom.clone(),
+                expected: rewarding_denom.to_string(),
+            });
+        }
+        Ok(())
+    };
+
+    for pledge in [
+        &update.minimum_mixnode_pledge,
+        &update.minimum_gateway_pledge,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        ensure_denom(pledge)?;
+        if pledge.amount.is_zero() {
+            return Err(MixnetContractError::ZeroMinimumPledge);
+        }
+    }
+
+    if let Some(delegation) = &update.minimum_mixnode_delegation {
+        ensure_denom(delegation)?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn try_update_staking_floors(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    update: StakingFloorsUpdate,
+    force_immediately: bool,
+) -> Result<Response, MixnetContractError> {
+    ensure_is_admin_or_governance(deps.as_ref(), &info.sender)?;
+
+    let mut state = storage::CONTRACT_STATE.load(deps.storage)?;
+    validate_staking_floors_update(&update, &state.rewarding_denom)?;
+
+    let change = ConfigChangeKind::StakingFloors {
+        update: update.clone(),
+    };
+
+    let interval = interval_storage::current_interval(deps.storage)?;
+    if force_immediately || interval.is_current_interval_over(&env) {
+        let old_params = state.params.clone();
+        update.apply_to(&mut state.params);
+        storage::CONTRACT_STATE.save(deps.storage, &state)?;
+        let change_id = record_config_change(
+            deps.storage,
+            &env,
+            info.sender,
+            env.block.time.seconds() as i64,
+            force_immediately,
+            change,
+        )?;
+        Ok(Response::new().add_event(
+            new_settings_update_event(&old_params, &state.params)
+                .add_attribute(CONFIG_CHANGE_ID_KEY, change_id.to_string()),
+        ))
+    } else {
+        // changing the floors is only allowed if the epoch is currently not in the process of being advanced
+        // (unless the force flag was used)
+        ensure_epoch_in_progress_state(deps.storage)?;
+
+        let interval_event = PendingIntervalEventKind::UpdateStakingFloors {
+            update: update.clone(),
+        };
+        push_new_interval_event(deps.storage, &env, interval_event)?;
+        let change_id = record_config_change(
+            deps.storage,
+            &env,
+            info.sender,
+            interval.current_interval_end_unix_timestamp(),
+            false,
+            change,
+        )?;
+        let time_left = interval.secs_until_current_interval_end(&env);
+        Ok(Response::new().add_event(
+            new_pending_staking_floors_update_event(&update, time_left)
+                .add_attribute(CONFIG_CHANGE_ID_KEY, change_id.to_string()),
+        ))
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -188,4 +314,136 @@ pub mod tests {
         // let res = try_update_contract_settings(deps.as_mut(), info, new_params);
         // assert_eq!(Err(MixnetContractError::ZeroActiveSet), res);
     }
+
+    mod updating_staking_floors {
+        use super::*;
+        use crate::mixnet_contract_settings::queries::query_staking_floors;
+        use crate::support::tests::test_helpers::TestSetup;
+        use cosmwasm_std::coin;
+
+        fn pledge_update(test: &TestSetup, amount: u128) -> StakingFloorsUpdate {
+            StakingFloorsUpdate {
+                minimum_mixnode_pledge: Some(test.coin(amount)),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn can_only_be_done_by_admin_or_governance() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let update = pledge_update(&test, 123_000_000);
+
+            let random = mock_info("random-guy", &[]);
+            let res = try_update_staking_floors(
+                test.deps_mut(),
+                env.clone(),
+                random,
+                update.clone(),
+                false,
+            );
+            assert_eq!(res, Err(MixnetContractError::Admin(NotAdmin {})));
+
+            let governance = mock_info("governance", &[]);
+            storage::GOVERNANCE_ADDRESS
+                .save(test.deps_mut().storage, &governance.sender)
+                .unwrap();
+            let res = try_update_staking_floors(
+                test.deps_mut(),
+                env.clone(),
+                governance,
+                update.clone(),
+                false,
+            );
+            assert!(res.is_ok());
+
+            let owner = test.owner();
+            let res = try_update_staking_floors(test.deps_mut(), env, owner, update, false);
+            assert!(res.is_ok());
+        }
+
+        #[test]
+        fn update_is_validated() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = test.owner();
+
+            let res = try_update_staking_floors(
+                test.deps_mut(),
+                env.clone(),
+                owner.clone(),
+                StakingFloorsUpdate::default(),
+                false,
+            );
+            assert_eq!(res, Err(MixnetContractError::EmptyStakingFloorsUpdate));
+
+            let update = StakingFloorsUpdate {
+                minimum_gateway_pledge: Some(coin(123, "wrongdenom")),
+                ..Default::default()
+            };
+            let res = try_update_staking_floors(
+                test.deps_mut(),
+                env.clone(),
+                owner.clone(),
+                update,
+                false,
+            );
+            assert!(matches!(res, Err(MixnetContractError::WrongDenom { .. })));
+
+            let update = pledge_update(&test, 0);
+            let res = try_update_staking_floors(test.deps_mut(), env, owner, update, false);
+            assert_eq!(res, Err(MixnetContractError::ZeroMinimumPledge));
+        }
+
+        #[test]
+        fn if_update_is_forced_it_happens_immediately() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = test.owner();
+            let update = StakingFloorsUpdate {
+                minimum_mixnode_delegation: Some(test.coin(42_000_000)),
+                ..pledge_update(&test, 123_000_000)
+            };
+
+            let res = try_update_staking_floors(test.deps_mut(), env, owner, update, true);
+            assert!(res.is_ok());
+
+            let floors = query_staking_floors(test.deps()).unwrap();
+            assert_eq!(floors.minimum_mixnode_pledge, test.coin(123_000_000));
+            assert_eq!(
+                floors.minimum_mixnode_delegation,
+                Some(test.coin(42_000_000))
+            );
+            assert!(floors.pending_updates.is_empty());
+            assert!(floors.pending_activation_timestamp.is_none());
+        }
+
+        #[test]
+        fn without_forcing_it_change_happens_upon_clearing_interval_events() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = test.owner();
+            let update = pledge_update(&test, 123_000_000);
+
+            let old = query_staking_floors(test.deps()).unwrap();
+            let res = try_update_staking_floors(test.deps_mut(), env, owner, update.clone(), false);
+            assert!(res.is_ok());
+
+            let floors = query_staking_floors(test.deps()).unwrap();
+            assert_eq!(floors.minimum_mixnode_pledge, old.minimum_mixnode_pledge);
+            assert_eq!(floors.pending_updates, vec![update]);
+            assert_eq!(
+                floors.pending_activation_timestamp,
+                Some(
+                    test.current_interval()
+                        .current_interval_end_unix_timestamp()
+                )
+            );
+
+            test.execute_all_pending_events();
+            let floors = query_staking_floors(test.deps()).unwrap();
+            assert_eq!(floors.minimum_mixnode_pledge, test.coin(123_000_000));
+            assert!(floors.pending_updates.is_empty());
+        }
+    }
 }
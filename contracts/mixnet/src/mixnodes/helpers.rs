@@ -145,6 +145,8 @@ pub(crate) fn cleanup_post_unbond_mixnode_storage(
         rewards_storage::MIXNODE_REWARDING.save(storage, mix_id, &zeroed)?;
     }
 
+    rewards_storage::OPERATOR_PAYOUT_ADDRESSES.remove(storage, mix_id);
+
     let identity = current_details.bond_information.identity().to_owned();
     let owner = current_details.bond_information.owner().to_owned();
     let proxy = current_details.bond_information.proxy.to_owned();
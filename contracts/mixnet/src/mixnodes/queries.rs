@@ -20,7 +20,7 @@ use mixnet_contract_common::mixnode::{
 };
 use mixnet_contract_common::{
     IdentityKey, LayerDistribution, MixId, MixOwnershipResponse, MixnodeDetailsByIdentityResponse,
-    MixnodeDetailsResponse, PagedMixnodeBondsResponse,
+    MixnodeDetailsResponse, OperatorPayoutAddressResponse, PagedMixnodeBondsResponse,
 };
 
 pub fn query_mixnode_bonds_paged(
@@ -222,6 +222,17 @@ pub fn query_unbonded_mixnode(deps: Deps<'_>, mix_id: MixId) -> StdResult<Unbond
     })
 }
 
+pub fn query_operator_payout_address(
+    deps: Deps<'_>,
+    mix_id: MixId,
+) -> StdResult<OperatorPayoutAddressResponse> {
+    Ok(OperatorPayoutAddressResponse {
+        mix_id,
+        payout_address: rewards_storage::OPERATOR_PAYOUT_ADDRESSES
+            .may_load(deps.storage, mix_id)?,
+    })
+}
+
 pub fn query_stake_saturation(deps: Deps<'_>, mix_id: MixId) -> StdResult<StakeSaturationResponse> {
     let mix_rewarding = match rewards_storage::MIXNODE_REWARDING.may_load(deps.storage, mix_id)? {
         Some(mix_rewarding) => mix_rewarding,
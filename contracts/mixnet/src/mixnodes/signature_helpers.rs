@@ -6,7 +6,8 @@ use crate::support::helpers::decode_ed25519_identity_key;
 use cosmwasm_std::{Addr, Coin, Deps};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::{
-    construct_legacy_mixnode_bonding_sign_payload, construct_mixnode_bonding_sign_payload, MixNode,
+    construct_legacy_mixnode_bonding_sign_payload, construct_mixnode_bonding_sign_payload,
+    construct_operator_payout_address_sign_payload, IdentityKeyRef, MixId, MixNode,
     MixNodeCostParams,
 };
 use nym_contracts_common::signing::MessageSignature;
@@ -57,3 +58,29 @@ pub(crate) fn verify_mixnode_bonding_signature(
         }
     }
 }
+
+pub(crate) fn verify_operator_payout_address_signature(
+    deps: Deps<'_>,
+    sender: Addr,
+    mix_id: MixId,
+    identity_key: IdentityKeyRef<'_>,
+    payout_address: Option<String>,
+    signature: MessageSignature,
+) -> Result<(), MixnetContractError> {
+    let public_key = decode_ed25519_identity_key(identity_key)?;
+
+    let nonce = signing_storage::get_signing_nonce(deps.storage, sender.clone())?;
+    let msg = construct_operator_payout_address_sign_payload(
+        nonce,
+        sender,
+        mix_id,
+        identity_key.to_owned(),
+        payout_address,
+    );
+
+    if deps.api.verify_message(msg, signature, &public_key)? {
+        Ok(())
+    } else {
+        Err(MixnetContractError::InvalidEd25519Signature)
+    }
+}
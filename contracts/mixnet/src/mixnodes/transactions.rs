@@ -5,8 +5,9 @@ use cosmwasm_std::{coin, Coin, DepsMut, Env, MessageInfo, Response, Storage};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::events::{
     new_mixnode_bonding_event, new_mixnode_config_update_event,
-    new_mixnode_pending_cost_params_update_event, new_pending_mixnode_unbonding_event,
-    new_pending_pledge_decrease_event, new_pending_pledge_increase_event,
+    new_mixnode_pending_cost_params_update_event, new_operator_payout_address_update_event,
+    new_pending_mixnode_unbonding_event, new_pending_pledge_decrease_event,
+    new_pending_pledge_increase_event,
 };
 use mixnet_contract_common::mixnode::{MixNodeConfigUpdate, MixNodeCostParams};
 use mixnet_contract_common::pending_events::{PendingEpochEventKind, PendingIntervalEventKind};
@@ -20,7 +21,10 @@ use crate::mixnet_contract_settings::storage::rewarding_denom;
 use crate::mixnodes::helpers::{
     get_mixnode_details_by_owner, must_get_mixnode_bond_by_owner, save_new_mixnode,
 };
-use crate::mixnodes::signature_helpers::verify_mixnode_bonding_signature;
+use crate::mixnodes::signature_helpers::{
+    verify_mixnode_bonding_signature, verify_operator_payout_address_signature,
+};
+use crate::rewards::storage as rewards_storage;
 use crate::signing::storage as signing_storage;
 use crate::support::helpers::{
     ensure_bonded, ensure_epoch_in_progress_state, ensure_is_authorized, ensure_no_existing_bond,
@@ -288,6 +292,55 @@ pub(crate) fn try_update_mixnode_config(
     Ok(Response::new().add_event(cfg_update_event))
 }
 
+pub(crate) fn try_update_operator_payout_address(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    payout_address: Option<String>,
+    node_signature: MessageSignature,
+) -> Result<Response, MixnetContractError> {
+    let existing_bond = must_get_mixnode_bond_by_owner(deps.storage, &info.sender)?;
+    ensure_bonded(&existing_bond)?;
+
+    // the rewards of the vesting nodes have to go back to the vesting contract
+    if existing_bond.proxy.is_some() {
+        return Err(MixnetContractError::DisabledVestingOperation);
+    }
+
+    let mix_id = existing_bond.mix_id;
+    let validated_address = payout_address
+        .as_ref()
+        .map(|address| deps.api.addr_validate(address))
+        .transpose()?;
+
+    // the owner alone is not enough, the update has to be authorised by the node itself
+    verify_operator_payout_address_signature(
+        deps.as_ref(),
+        info.sender.clone(),
+        mix_id,
+        existing_bond.identity(),
+        payout_address,
+        node_signature,
+    )?;
+    signing_storage::increment_signing_nonce(deps.storage, info.sender.clone())?;
+
+    // setting the payout address to the owner itself is equivalent to removing it
+    let payout_address = validated_address.filter(|address| address != existing_bond.owner());
+    match &payout_address {
+        Some(address) => {
+            rewards_storage::OPERATOR_PAYOUT_ADDRESSES.save(deps.storage, mix_id, address)?
+        }
+        None => rewards_storage::OPERATOR_PAYOUT_ADDRESSES.remove(deps.storage, mix_id),
+    }
+
+    Ok(
+        Response::new().add_event(new_operator_payout_address_update_event(
+            &info.sender,
+            mix_id,
+            payout_address.as_ref(),
+        )),
+    )
+}
+
 pub(crate) fn try_update_mixnode_cost_params(
     deps: DepsMut<'_>,
     env: Env,
@@ -2,11 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::constants::{
-    MIXNODES_REWARDING_PK_NAMESPACE, PENDING_REWARD_POOL_KEY, REWARDING_PARAMS_KEY,
+    CLAIM_ALL_REWARDS_CURSORS_NAMESPACE, MIXNODES_REWARDING_PK_NAMESPACE,
+    OPERATOR_PAYOUT_ADDRESSES_NAMESPACE, PENDING_REWARD_POOL_KEY, REWARDING_PARAMS_KEY,
 };
 use crate::rewards::models::RewardPoolChange;
-use cosmwasm_std::{Decimal, StdResult, Storage};
+use cosmwasm_std::{Addr, Decimal, StdResult, Storage};
 use cw_storage_plus::{Item, Map};
+use mixnet_contract_common::delegation::StorageKey as DelegationStorageKey;
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::mixnode::MixNodeRewarding;
 use mixnet_contract_common::reward_params::RewardingParams;
@@ -20,6 +22,14 @@ pub(crate) const PENDING_REWARD_POOL_CHANGE: Item<'_, RewardPoolChange> =
 pub const MIXNODE_REWARDING: Map<MixId, MixNodeRewarding> =
     Map::new(MIXNODES_REWARDING_PK_NAMESPACE);
 
+// the last delegation processed by an unfinished `ClaimAllDelegatorRewards` of given delegator
+pub(crate) const CLAIM_ALL_REWARDS_CURSORS: Map<&Addr, DelegationStorageKey> =
+    Map::new(CLAIM_ALL_REWARDS_CURSORS_NAMESPACE);
+
+// addresses receiving the operator rewards instead of the owners of the respective mixnodes
+pub(crate) const OPERATOR_PAYOUT_ADDRESSES: Map<MixId, Addr> =
+    Map::new(OPERATOR_PAYOUT_ADDRESSES_NAMESPACE);
+
 pub fn reward_accounting(
     storage: &mut dyn Storage,
     amount: Decimal,
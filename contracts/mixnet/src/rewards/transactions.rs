@@ -2,32 +2,36 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::storage;
+use crate::constants::CLAIM_ALL_REWARDS_PAGE_SIZE;
 use crate::delegations::storage as delegations_storage;
 use crate::interval::storage as interval_storage;
 use crate::interval::storage::{push_new_epoch_event, push_new_interval_event};
 use crate::mixnet_contract_settings::storage as mixnet_params_storage;
-use crate::mixnet_contract_settings::storage::ADMIN;
-use crate::mixnodes::helpers::get_mixnode_details_by_owner;
+use crate::mixnet_contract_settings::storage::{record_config_change, ADMIN};
+use crate::mixnodes::helpers::{get_mixnode_details_by_id, get_mixnode_details_by_owner};
 use crate::mixnodes::storage as mixnodes_storage;
 use crate::rewards::helpers;
 use crate::rewards::helpers::update_and_save_last_rewarded;
 use crate::support::helpers::{
-    ensure_bonded, ensure_can_advance_epoch, ensure_epoch_in_progress_state, AttachSendTokens,
+    ensure_bonded, ensure_can_advance_epoch, ensure_epoch_in_progress_state,
+    ensure_is_admin_or_governance, AttachSendTokens,
 };
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{coin, DepsMut, Env, MessageInfo, Order, Response, StdResult};
+use cw_storage_plus::Bound;
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::events::{
-    new_active_set_update_event, new_mix_rewarding_event,
+    new_active_set_update_event, new_claim_all_delegator_rewards_event, new_mix_rewarding_event,
     new_not_found_mix_operator_rewarding_event, new_pending_active_set_update_event,
     new_pending_rewarding_params_update_event, new_rewarding_params_update_event,
     new_withdraw_delegator_reward_event, new_withdraw_operator_reward_event,
-    new_zero_uptime_mix_operator_rewarding_event,
+    new_withdraw_operator_reward_to_payout_address_event,
+    new_zero_uptime_mix_operator_rewarding_event, CONFIG_CHANGE_ID_KEY,
 };
 use mixnet_contract_common::pending_events::{PendingEpochEventKind, PendingIntervalEventKind};
 use mixnet_contract_common::reward_params::{
     IntervalRewardingParamsUpdate, NodeRewardParams, Performance,
 };
-use mixnet_contract_common::{Delegation, EpochState, MixId};
+use mixnet_contract_common::{ConfigChangeKind, Delegation, EpochState, MixId};
 
 pub(crate) fn try_reward_mixnode(
     deps: DepsMut<'_>,
@@ -156,19 +160,64 @@ pub(crate) fn try_withdraw_operator_reward(
 
     ensure_bonded(&mix_details.bond_information)?;
 
+    let payout_address = storage::OPERATOR_PAYOUT_ADDRESSES.may_load(deps.storage, mix_id)?;
     let reward = helpers::withdraw_operator_reward(deps.storage, mix_details)?;
     let mut response = Response::new();
 
     // if the reward is zero, don't track or send anything - there's no point
     if !reward.amount.is_zero() {
-        response = response.send_tokens(&info.sender, reward.clone())
+        let recipient = payout_address.as_ref().unwrap_or(&info.sender);
+        response = response.send_tokens(recipient, reward.clone())
     }
 
-    Ok(response.add_event(new_withdraw_operator_reward_event(
-        &info.sender,
-        reward,
-        mix_id,
-    )))
+    let event = match &payout_address {
+        Some(payout_address) => new_withdraw_operator_reward_to_payout_address_event(
+            &info.sender,
+            payout_address,
+            reward,
+            mix_id,
+        ),
+        None => new_withdraw_operator_reward_event(&info.sender, reward, mix_id),
+    };
+    Ok(response.add_event(event))
+}
+
+pub(crate) fn try_withdraw_operator_reward_to_payout_address(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    mix_id: MixId,
+) -> Result<Response, MixnetContractError> {
+    let payout_address = storage::OPERATOR_PAYOUT_ADDRESSES
+        .may_load(deps.storage, mix_id)?
+        .ok_or(MixnetContractError::NoOperatorPayoutAddress { mix_id })?;
+
+    let mix_details = get_mixnode_details_by_id(deps.storage, mix_id)?
+        .ok_or(MixnetContractError::MixNodeBondNotFound { mix_id })?;
+    ensure_bonded(&mix_details.bond_information)?;
+
+    let owner = mix_details.bond_information.owner.clone();
+    if info.sender != owner && info.sender != payout_address {
+        return Err(MixnetContractError::NotOwnerOrPayoutAddress {
+            sender: info.sender,
+            mix_id,
+        });
+    }
+
+    let reward = helpers::withdraw_operator_reward(deps.storage, mix_details)?;
+    let mut response = Response::new();
+
+    if !reward.amount.is_zero() {
+        response = response.send_tokens(&payout_address, reward.clone())
+    }
+
+    Ok(
+        response.add_event(new_withdraw_operator_reward_to_payout_address_event(
+            &owner,
+            &payout_address,
+            reward,
+            mix_id,
+        )),
+    )
 }
 
 pub(crate) fn try_withdraw_delegator_reward(
@@ -220,6 +269,87 @@ pub(crate) fn try_withdraw_delegator_reward(
     )))
 }
 
+pub(crate) fn try_claim_all_delegator_rewards(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+) -> Result<Response, MixnetContractError> {
+    // continue from where the previous (unfinished) call has stopped
+    let start = storage::CLAIM_ALL_REWARDS_CURSORS
+        .may_load(deps.storage, &info.sender)?
+        .map(Bound::exclusive);
+
+    // grab one extra delegation to know whether there's anything left for the next call
+    let mut delegations = delegations_storage::delegations()
+        .idx
+        .owner
+        .prefix(info.sender.clone())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(CLAIM_ALL_REWARDS_PAGE_SIZE + 1)
+        .map(|record| record.map(|r| r.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let more_remaining = delegations.len() > CLAIM_ALL_REWARDS_PAGE_SIZE;
+    delegations.truncate(CLAIM_ALL_REWARDS_PAGE_SIZE);
+
+    let denom = mixnet_params_storage::rewarding_denom(deps.storage)?;
+    let mut total_reward = coin(0, denom);
+    let mut response = Response::new();
+
+    for delegation in &delegations {
+        // vesting delegations can no longer be operated on
+        if delegation.proxy.is_some() {
+            continue;
+        }
+
+        // if the node is unbonding or has already unbonded, the expected path
+        // of getting the rewards back is via undelegation
+        let mix_id = delegation.mix_id;
+        match mixnodes_storage::mixnode_bonds().may_load(deps.storage, mix_id)? {
+            Some(mix_bond) if !mix_bond.is_unbonding => (),
+            _ => continue,
+        }
+
+        let mix_rewarding =
+            storage::MIXNODE_REWARDING.may_load(deps.storage, mix_id)?.ok_or(MixnetContractError::inconsistent_state(
+                "mixnode rewarding got removed from the storage whilst there's still an existing delegation"
+            ))?;
+
+        let reward =
+            helpers::withdraw_delegator_reward(deps.storage, delegation.clone(), mix_rewarding)?;
+        if reward.amount.is_zero() {
+            continue;
+        }
+
+        total_reward.amount += reward.amount;
+        response = response.add_event(new_withdraw_delegator_reward_event(
+            &info.sender,
+            reward,
+            mix_id,
+        ));
+    }
+
+    match delegations.last() {
+        Some(last) if more_remaining => storage::CLAIM_ALL_REWARDS_CURSORS.save(
+            deps.storage,
+            &info.sender,
+            &last.storage_key(),
+        )?,
+        _ => storage::CLAIM_ALL_REWARDS_CURSORS.remove(deps.storage, &info.sender),
+    }
+
+    // if the reward is zero, don't send anything - there's no point
+    if !total_reward.amount.is_zero() {
+        response = response.send_tokens(&info.sender, total_reward.clone())
+    }
+
+    Ok(response.add_event(new_claim_all_delegator_rewards_event(
+        &info.sender,
+        total_reward,
+        delegations.len(),
+        more_remaining,
+    )))
+}
+
 pub(crate) fn try_update_active_set_size(
     deps: DepsMut<'_>,
     env: Env,
@@ -273,22 +403,37 @@ pub(crate) fn try_update_rewarding_params(
     updated_params: IntervalRewardingParamsUpdate,
     force_immediately: bool,
 ) -> Result<Response, MixnetContractError> {
-    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    ensure_is_admin_or_governance(deps.as_ref(), &info.sender)?;
 
     if !updated_params.contains_updates() {
         return Err(MixnetContractError::EmptyParamsChangeMsg);
     }
 
+    let change = ConfigChangeKind::RewardingParams {
+        update: updated_params,
+    };
+
     let interval = interval_storage::current_interval(deps.storage)?;
     if force_immediately || interval.is_current_interval_over(&env) {
         let mut rewarding_params = storage::REWARDING_PARAMS.load(deps.storage)?;
         rewarding_params.try_apply_updates(updated_params, interval.epochs_in_interval())?;
         storage::REWARDING_PARAMS.save(deps.storage, &rewarding_params)?;
-        Ok(Response::new().add_event(new_rewarding_params_update_event(
-            env.block.height,
-            updated_params,
-            rewarding_params.interval,
-        )))
+        let change_id = record_config_change(
+            deps.storage,
+            &env,
+            info.sender,
+            env.block.time.seconds() as i64,
+            force_immediately,
+            change,
+        )?;
+        Ok(Response::new().add_event(
+            new_rewarding_params_update_event(
+                env.block.height,
+                updated_params,
+                rewarding_params.interval,
+            )
+            .add_attribute(CONFIG_CHANGE_ID_KEY, change_id.to_string()),
+        ))
     } else {
         // changing rewarding parameters is only allowed if the epoch is currently not in the process of being advanced
         // (unless the force flag was used)
@@ -299,13 +444,19 @@ pub(crate) fn try_update_rewarding_params(
             update: updated_params,
         };
         push_new_interval_event(deps.storage, &env, interval_event)?;
+        let change_id = record_config_change(
+            deps.storage,
+            &env,
+            info.sender,
+            interval.current_interval_end_unix_timestamp(),
+            false,
+            change,
+        )?;
         let time_left = interval.secs_until_current_interval_end(&env);
-        Ok(
-            Response::new().add_event(new_pending_rewarding_params_update_event(
-                updated_params,
-                time_left,
-            )),
-        )
+        Ok(Response::new().add_event(
+            new_pending_rewarding_params_update_event(updated_params, time_left)
+                .add_attribute(CONFIG_CHANGE_ID_KEY, change_id.to_string()),
+        ))
     }
 }
 
@@ -1674,12 +1825,128 @@ pub mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod claiming_all_delegator_rewards {
+        use super::*;
+        use crate::support::tests::test_helpers::TestSetup;
+        use cosmwasm_std::{Addr, Uint128};
+        use mixnet_contract_common::events::{
+            MixnetEventType, DELEGATIONS_PROCESSED_KEY, MORE_DELEGATIONS_REMAINING_KEY,
+        };
+
+        #[test]
+        fn withdraws_rewards_of_all_delegations_in_single_transfer() {
+            let mut test = TestSetup::new();
+            let mix_ids = (0..3)
+                .map(|i| {
+                    test.add_dummy_mixnode(
+                        &format!("mix-owner{i}"),
+                        Some(Uint128::new(1_000_000_000_000)),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            // both delegators have identical delegations so their rewards should be the same
+            let claiming_delegator = "delegator1";
+            let individual_delegator = "delegator2";
+            for mix_id in &mix_ids {
+                test.add_immediate_delegation(claiming_delegator, 100_000_000u128, *mix_id);
+                test.add_immediate_delegation(individual_delegator, 100_000_000u128, *mix_id);
+            }
+
+            test.skip_to_next_epoch_end();
+            test.force_change_rewarded_set(mix_ids.clone());
+            test.start_epoch_transition();
+            for mix_id in &mix_ids {
+                test.reward_with_distribution(*mix_id, test_helpers::performance(100.0));
+            }
+
+            let mut expected = Uint128::zero();
+            for mix_id in &mix_ids {
+                let res = try_withdraw_delegator_reward(
+                    test.deps_mut(),
+                    mock_info(individual_delegator, &[]),
+                    *mix_id,
+                )
+                .unwrap();
+                let (_, reward) = test_helpers::get_bank_send_msg(&res).unwrap();
+                expected += reward[0].amount;
+            }
+
+            let res = try_claim_all_delegator_rewards(
+                test.deps_mut(),
+                mock_info(claiming_delegator, &[]),
+            )
+            .unwrap();
+            assert_eq!(res.messages.len(), 1);
+            let (receiver, reward) = test_helpers::get_bank_send_msg(&res).unwrap();
+            assert_eq!(receiver, claiming_delegator);
+            assert!(!expected.is_zero());
+            assert_eq!(reward[0].amount, expected);
+
+            // and nothing is left to claim afterwards
+            let res = try_claim_all_delegator_rewards(
+                test.deps_mut(),
+                mock_info(claiming_delegator, &[]),
+            )
+            .unwrap();
+            assert!(res.messages.is_empty());
+        }
+
+        #[test]
+        fn continues_from_stored_cursor_if_there_are_too_many_delegations() {
+            let mut test = TestSetup::new();
+            let delegator = "delegator";
+            let total = CLAIM_ALL_REWARDS_PAGE_SIZE + 5;
+            for i in 0..total {
+                let mix_id = test.add_dummy_mixnode(&format!("mix-owner{i}"), None);
+                test.add_immediate_delegation(delegator, 100_000_000u128, mix_id);
+            }
+
+            let event_type = MixnetEventType::ClaimAllDelegatorRewards.to_string();
+            let event_type = Some(event_type.as_str());
+            let sender = Addr::unchecked(delegator);
+
+            let res = try_claim_all_delegator_rewards(test.deps_mut(), mock_info(delegator, &[]))
+                .unwrap();
+            assert_eq!(
+                test_helpers::find_attribute(event_type, DELEGATIONS_PROCESSED_KEY, &res),
+                CLAIM_ALL_REWARDS_PAGE_SIZE.to_string()
+            );
+            assert_eq!(
+                test_helpers::find_attribute(event_type, MORE_DELEGATIONS_REMAINING_KEY, &res),
+                "true"
+            );
+            assert!(storage::CLAIM_ALL_REWARDS_CURSORS
+                .may_load(test.deps().storage, &sender)
+                .unwrap()
+                .is_some());
+
+            let res = try_claim_all_delegator_rewards(test.deps_mut(), mock_info(delegator, &[]))
+                .unwrap();
+            assert_eq!(
+                test_helpers::find_attribute(event_type, DELEGATIONS_PROCESSED_KEY, &res),
+                "5"
+            );
+            assert_eq!(
+                test_helpers::find_attribute(event_type, MORE_DELEGATIONS_REMAINING_KEY, &res),
+                "false"
+            );
+            assert!(storage::CLAIM_ALL_REWARDS_CURSORS
+                .may_load(test.deps().storage, &sender)
+                .unwrap()
+                .is_none());
+        }
+    }
+
     #[cfg(test)]
     mod withdrawing_operator_reward {
         use super::*;
         use crate::interval::pending_events;
+        use crate::mixnodes::transactions::try_update_operator_payout_address;
         use crate::support::tests::test_helpers::TestSetup;
         use cosmwasm_std::{Addr, BankMsg, CosmosMsg, Uint128};
+        use nym_crypto::asymmetric::identity;
 
         #[test]
         fn can_only_be_done_if_bond_exists() {
@@ -1792,6 +2059,93 @@ pub mod tests {
                 })
             );
         }
+
+        #[test]
+        fn rewards_are_sent_to_the_payout_address_if_set() {
+            let mut test = TestSetup::new();
+
+            let owner = "mix-owner";
+            let hot_wallet = "hot-wallet";
+            let (mix_id, keypair) =
+                test.add_dummy_mixnode_with_keypair(owner, Some(Uint128::new(1_000_000_000_000)));
+            let identity_key = keypair.public_key().to_base58_string();
+
+            let msg = test_helpers::operator_payout_address_sign_payload(
+                test.deps(),
+                owner,
+                mix_id,
+                identity_key.clone(),
+                Some(hot_wallet.to_string()),
+            );
+            let other_keypair = identity::KeyPair::new(&mut test.rng);
+            let bad_signature =
+                test_helpers::ed25519_sign_message(msg.clone(), other_keypair.private_key());
+            let res = try_update_operator_payout_address(
+                test.deps_mut(),
+                mock_info(owner, &[]),
+                Some(hot_wallet.to_string()),
+                bad_signature,
+            );
+            assert_eq!(res, Err(MixnetContractError::InvalidEd25519Signature));
+
+            let signature = test_helpers::ed25519_sign_message(msg, keypair.private_key());
+            try_update_operator_payout_address(
+                test.deps_mut(),
+                mock_info(owner, &[]),
+                Some(hot_wallet.to_string()),
+                signature.clone(),
+            )
+            .unwrap();
+
+            // the signature can't be replayed
+            let res = try_update_operator_payout_address(
+                test.deps_mut(),
+                mock_info(owner, &[]),
+                Some(hot_wallet.to_string()),
+                signature,
+            );
+            assert_eq!(res, Err(MixnetContractError::InvalidEd25519Signature));
+
+            test.skip_to_next_epoch_end();
+            test.force_change_rewarded_set(vec![mix_id]);
+            test.start_epoch_transition();
+            test.reward_with_distribution(mix_id, test_helpers::performance(100.0));
+
+            let res = try_withdraw_operator_reward(test.deps_mut(), mock_info(owner, &[])).unwrap();
+            assert!(matches!(
+                &res.messages[0].msg,
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount }) if to_address == hot_wallet && !amount[0].amount.is_zero()
+            ));
+
+            test.skip_to_next_epoch_end();
+            test.set_epoch_in_progress_state();
+            test.start_epoch_transition();
+            test.reward_with_distribution(mix_id, test_helpers::performance(100.0));
+
+            let res = try_withdraw_operator_reward_to_payout_address(
+                test.deps_mut(),
+                mock_info("random-guy", &[]),
+                mix_id,
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NotOwnerOrPayoutAddress {
+                    sender: Addr::unchecked("random-guy"),
+                    mix_id
+                })
+            );
+
+            let res = try_withdraw_operator_reward_to_payout_address(
+                test.deps_mut(),
+                mock_info(hot_wallet, &[]),
+                mix_id,
+            )
+            .unwrap();
+            assert!(matches!(
+                &res.messages[0].msg,
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount }) if to_address == hot_wallet && !amount[0].amount.is_zero()
+            ));
+        }
     }
 
     #[cfg(test)]
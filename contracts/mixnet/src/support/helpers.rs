@@ -4,7 +4,7 @@
 use crate::gateways::storage as gateways_storage;
 use crate::mixnet_contract_settings::storage as mixnet_params_storage;
 use crate::mixnodes::storage as mixnodes_storage;
-use cosmwasm_std::{Addr, BankMsg, Coin, CosmosMsg, Response, Storage};
+use cosmwasm_std::{Addr, BankMsg, Coin, CosmosMsg, Deps, Response, Storage};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::mixnode::PendingMixNodeChanges;
 use mixnet_contract_common::{EpochState, EpochStatus, IdentityKeyRef, MixNodeBond};
@@ -176,6 +176,19 @@ pub(crate) fn ensure_is_authorized(
     Ok(())
 }
 
+/// Ensures the sender is either the contract admin or the configured governance module.
+pub(crate) fn ensure_is_admin_or_governance(
+    deps: Deps<'_>,
+    sender: &Addr,
+) -> Result<(), MixnetContractError> {
+    let governance =
+        crate::mixnet_contract_settings::storage::GOVERNANCE_ADDRESS.may_load(deps.storage)?;
+    if governance.as_ref() == Some(sender) {
+        return Ok(());
+    }
+    Ok(crate::mixnet_contract_settings::storage::ADMIN.assert_admin(deps, sender)?)
+}
+
 pub(crate) fn ensure_can_advance_epoch(
     sender: &Addr,
     storage: &dyn Storage,
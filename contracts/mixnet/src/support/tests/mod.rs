@@ -59,10 +59,11 @@ pub mod test_helpers {
     use mixnet_contract_common::rewarding::simulator::Simulator;
     use mixnet_contract_common::rewarding::RewardDistribution;
     use mixnet_contract_common::{
-        construct_family_join_permit, Delegation, EpochEventId, EpochState, EpochStatus, Gateway,
-        GatewayBondingPayload, IdentityKey, IdentityKeyRef, InitialRewardingParams, InstantiateMsg,
-        Interval, MixId, MixNode, MixNodeBond, MixnodeBondingPayload, Percent,
-        RewardedSetNodeStatus, SignableGatewayBondingMsg, SignableMixNodeBondingMsg,
+        construct_family_join_permit, construct_operator_payout_address_sign_payload, Delegation,
+        EpochEventId, EpochState, EpochStatus, Gateway, GatewayBondingPayload, IdentityKey,
+        IdentityKeyRef, InitialRewardingParams, InstantiateMsg, Interval, MixId, MixNode,
+        MixNodeBond, MixnodeBondingPayload, Percent, RewardedSetNodeStatus,
+        SignableGatewayBondingMsg, SignableMixNodeBondingMsg, SignableOperatorPayoutAddressMsg,
     };
     use nym_contracts_common::signing::{
         ContractMessageContent, MessageSignature, SignableMessage, SigningAlgorithm, SigningPurpose,
@@ -958,6 +959,24 @@ pub mod test_helpers {
         SignableMixNodeBondingMsg::new(nonce, content)
     }
 
+    pub fn operator_payout_address_sign_payload(
+        deps: Deps<'_>,
+        owner: &str,
+        mix_id: MixId,
+        identity_key: IdentityKey,
+        payout_address: Option<String>,
+    ) -> SignableOperatorPayoutAddressMsg {
+        let nonce =
+            signing_storage::get_signing_nonce(deps.storage, Addr::unchecked(owner)).unwrap();
+        construct_operator_payout_address_sign_payload(
+            nonce,
+            Addr::unchecked(owner),
+            mix_id,
+            identity_key,
+            payout_address,
+        )
+    }
+
     pub fn gateway_bonding_sign_payload(
         deps: Deps<'_>,
         owner: &str,
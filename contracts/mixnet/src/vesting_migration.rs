@@ -8,18 +8,48 @@ use crate::mixnodes::storage as mixnodes_storage;
 use crate::support::helpers::{
     ensure_bonded, ensure_epoch_in_progress_state, ensure_no_pending_pledge_changes,
 };
-use cosmwasm_std::{wasm_execute, DepsMut, MessageInfo, Response};
+use cosmwasm_std::{wasm_execute, Addr, DepsMut, MessageInfo, Response};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::{Delegation, MixId};
 use vesting_contract_common::messages::ExecuteMsg as VestingExecuteMsg;
 
+fn ensure_sent_by_vesting_contract(
+    deps: &DepsMut<'_>,
+    info: &MessageInfo,
+) -> Result<(), MixnetContractError> {
+    let vesting_contract = mixnet_params_storage::vesting_contract_address(deps.storage)?;
+    if info.sender != vesting_contract {
+        return Err(MixnetContractError::SenderIsNotVestingContract {
+            received: info.sender.clone(),
+            vesting_contract,
+        });
+    }
+    Ok(())
+}
+
 pub(crate) fn try_migrate_vested_mixnode(
     deps: DepsMut<'_>,
     info: MessageInfo,
 ) -> Result<Response, MixnetContractError> {
-    let mix_details = get_mixnode_details_by_owner(deps.storage, info.sender.clone())?.ok_or(
+    migrate_vested_mixnode(deps, info.sender)
+}
+
+/// Migrates the vesting mixnode of the provided owner, invoked by the vesting contract
+/// once the vesting schedule of the owner has completed.
+pub(crate) fn try_migrate_vested_mixnode_on_behalf(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, MixnetContractError> {
+    ensure_sent_by_vesting_contract(&deps, &info)?;
+    let owner = deps.api.addr_validate(&owner)?;
+    migrate_vested_mixnode(deps, owner)
+}
+
+fn migrate_vested_mixnode(deps: DepsMut<'_>, owner: Addr) -> Result<Response, MixnetContractError> {
+    let mix_details = get_mixnode_details_by_owner(deps.storage, owner.clone())?.ok_or(
         MixnetContractError::NoAssociatedMixNodeBond {
-            owner: info.sender.clone(),
+            owner: owner.clone(),
         },
     )?;
     let mix_id = mix_details.mix_id();
@@ -52,7 +82,7 @@ pub(crate) fn try_migrate_vested_mixnode(
     Ok(Response::new().add_message(wasm_execute(
         vesting_contract,
         &VestingExecuteMsg::TrackMigratedMixnode {
-            owner: info.sender.into_string(),
+            owner: owner.into_string(),
         },
         vec![],
     )?))
@@ -62,13 +92,33 @@ pub(crate) fn try_migrate_vested_delegation(
     deps: DepsMut<'_>,
     info: MessageInfo,
     mix_id: MixId,
+) -> Result<Response, MixnetContractError> {
+    migrate_vested_delegation(deps, info.sender, mix_id)
+}
+
+/// Migrates the vesting delegation of the provided owner, invoked by the vesting contract
+/// once the vesting schedule of the owner has completed.
+pub(crate) fn try_migrate_vested_delegation_on_behalf(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    owner: String,
+    mix_id: MixId,
+) -> Result<Response, MixnetContractError> {
+    ensure_sent_by_vesting_contract(&deps, &info)?;
+    let owner = deps.api.addr_validate(&owner)?;
+    migrate_vested_delegation(deps, owner, mix_id)
+}
+
+fn migrate_vested_delegation(
+    deps: DepsMut<'_>,
+    owner: Addr,
+    mix_id: MixId,
 ) -> Result<Response, MixnetContractError> {
     ensure_epoch_in_progress_state(deps.storage)?;
 
     let vesting_contract = mixnet_params_storage::vesting_contract_address(deps.storage)?;
 
-    let storage_key =
-        Delegation::generate_storage_key(mix_id, &info.sender, Some(&vesting_contract));
+    let storage_key = Delegation::generate_storage_key(mix_id, &owner, Some(&vesting_contract));
     let Some(mut delegation) =
         delegations_storage::delegations().may_load(deps.storage, storage_key.clone())?
     else {
@@ -80,14 +130,14 @@ pub(crate) fn try_migrate_vested_delegation(
 
     // update the delegation and save it under the correct storage key
     delegation.proxy = None;
-    let updated_storage_key = Delegation::generate_storage_key(mix_id, &info.sender, None);
+    let updated_storage_key = Delegation::generate_storage_key(mix_id, &owner, None);
     delegations_storage::delegations().remove(deps.storage, storage_key)?;
     delegations_storage::delegations().save(deps.storage, updated_storage_key, &delegation)?;
 
     Ok(Response::new().add_message(wasm_execute(
         vesting_contract,
         &VestingExecuteMsg::TrackMigratedDelegation {
-            owner: info.sender.into_string(),
+            owner: owner.into_string(),
             mix_id,
         },
         vec![],
@@ -97,6 +97,9 @@ pub fn execute(
         ExecuteMsg::TrackMigratedDelegation { owner, mix_id } => {
             try_track_migrate_delegation(&owner, mix_id, info, deps)
         }
+        ExecuteMsg::MigrateVestedStake { limit } => {
+            try_migrate_vested_stake(limit, env, info, deps)
+        }
         _ => Err(VestingContractError::Other {
             message: "the contract has been disabled".to_string(),
         }),
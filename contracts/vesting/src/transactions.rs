@@ -10,17 +10,20 @@ use crate::traits::{
 };
 use crate::vesting::{populate_vesting_periods, StorableVestingAccountExt};
 use contracts_common::signing::MessageSignature;
-use cosmwasm_std::{coin, BankMsg, Coin, DepsMut, Env, MessageInfo, Response, Timestamp};
+use cosmwasm_std::{
+    coin, wasm_execute, BankMsg, Coin, DepsMut, Env, MessageInfo, Response, Timestamp,
+};
 use mixnet_contract_common::families::FamilyHead;
 use mixnet_contract_common::{
-    Gateway, GatewayConfigUpdate, MixId, MixNode, MixNodeConfigUpdate, MixNodeCostParams,
+    ExecuteMsg as MixnetExecuteMsg, Gateway, GatewayConfigUpdate, MixId, MixNode,
+    MixNodeConfigUpdate, MixNodeCostParams,
 };
 use vesting_contract_common::events::{
     new_ownership_transfer_event, new_periodic_vesting_account_event,
     new_staking_address_update_event, new_track_gateway_unbond_event,
     new_track_migrate_mixnode_event, new_track_mixnode_pledge_decrease_event,
     new_track_mixnode_unbond_event, new_track_reward_event, new_track_undelegation_event,
-    new_vested_coins_withdraw_event,
+    new_vested_coins_withdraw_event, new_vested_stake_migration_event,
 };
 use vesting_contract_common::{Account, PledgeCap, VestingContractError, VestingSpecification};
 
@@ -285,6 +288,81 @@ pub fn try_track_migrate_delegation(
     Ok(Response::new().add_event(new_track_migrate_mixnode_event()))
 }
 
+/// Converts the vesting stake of the sender into the liquid one once its vesting schedule has completed,
+/// sends [mixnet_contract_common::ExecuteMsg::MigrateVestedMixNodeOnBehalf] and (up to `limit`)
+/// [mixnet_contract_common::ExecuteMsg::MigrateVestedDelegationOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+/// The vesting data of the account is updated once the mixnet contract invokes the corresponding `TrackMigrated` messages.
+pub fn try_migrate_vested_stake(
+    limit: Option<u32>,
+    env: Env,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, VestingContractError> {
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    if info.sender != account.owner_address() {
+        return Err(VestingContractError::NotOwner(info.sender.into_string()));
+    }
+
+    let end_time = account.get_end_time();
+    if env.block.time < end_time {
+        return Err(VestingContractError::VestingNotCompleted {
+            owner: account.owner_address(),
+            end_time,
+        });
+    }
+
+    // every migrated delegation results in a call to the mixnet contract and a callback back into this contract,
+    // so don't attempt too many of them in a single transaction
+    let limit = limit.unwrap_or(10).min(25) as usize;
+    let mixnet_contract = MIXNET_CONTRACT_ADDRESS.load(deps.storage)?;
+    let owner = account.owner_address().into_string();
+
+    // note: there are no vesting gateways on mainnet, so there's no need to migrate them
+    let mut messages = Vec::new();
+    let migrated_mixnode = account.load_mixnode_pledge(deps.storage)?.is_some();
+    if migrated_mixnode {
+        messages.push(wasm_execute(
+            &mixnet_contract,
+            &MixnetExecuteMsg::MigrateVestedMixNodeOnBehalf {
+                owner: owner.clone(),
+            },
+            vec![],
+        )?);
+    }
+
+    // the migrated delegations get removed from the storage of this contract by the callbacks,
+    // so the next invocation is going to naturally pick up where this one has finished
+    let mut mix_ids = account.delegated_mixnodes(limit + 1, deps.storage)?;
+    let completed = mix_ids.len() <= limit;
+    mix_ids.truncate(limit);
+
+    if !migrated_mixnode && mix_ids.is_empty() {
+        return Err(VestingContractError::NoVestedStakeToMigrate {
+            owner: account.owner_address(),
+        });
+    }
+
+    for &mix_id in &mix_ids {
+        messages.push(wasm_execute(
+            &mixnet_contract,
+            &MixnetExecuteMsg::MigrateVestedDelegationOnBehalf {
+                owner: owner.clone(),
+                mix_id,
+            },
+            vec![],
+        )?);
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_event(new_vested_stake_migration_event(
+            &account.owner_address(),
+            migrated_mixnode,
+            mix_ids.len(),
+            completed,
+        )))
+}
+
 /// Bond a mixnode, sends [mixnet_contract_common::ExecuteMsg::BondMixnodeOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
 pub fn try_bond_mixnode(
     mix_node: MixNode,
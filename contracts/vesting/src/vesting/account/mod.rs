@@ -156,6 +156,13 @@ pub(crate) trait StorableVestingAccountExt: VestingAccount {
 
     fn num_subdelegations_for_mix(&self, mix_id: MixId, storage: &dyn Storage) -> u32;
 
+    /// Returns ids of (up to `limit`) mixnodes towards which this account has delegated, in ascending order.
+    fn delegated_mixnodes(
+        &self,
+        limit: usize,
+        storage: &dyn Storage,
+    ) -> Result<Vec<MixId>, VestingContractError>;
+
     fn remove_delegations_for_mix(
         &self,
         mix_id: MixId,
@@ -285,6 +292,29 @@ impl StorableVestingAccountExt for Account {
         count_subdelegations_for_mix((self.storage_key(), mix_id), storage)
     }
 
+    fn delegated_mixnodes(
+        &self,
+        limit: usize,
+        storage: &dyn Storage,
+    ) -> Result<Vec<MixId>, VestingContractError> {
+        let mut mix_ids: Vec<MixId> = Vec::new();
+        // the keys are ordered by the mix id, so all the subdelegations towards the same node are adjacent
+        for key in
+            DELEGATIONS
+                .sub_prefix(self.storage_key())
+                .keys(storage, None, None, Order::Ascending)
+        {
+            let (mix_id, _) = key?;
+            if mix_ids.last() != Some(&mix_id) {
+                if mix_ids.len() == limit {
+                    break;
+                }
+                mix_ids.push(mix_id)
+            }
+        }
+        Ok(mix_ids)
+    }
+
     fn remove_delegations_for_mix(
         &self,
         mix_id: MixId,
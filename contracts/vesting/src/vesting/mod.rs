@@ -25,9 +25,11 @@ mod tests {
     use crate::vesting::populate_vesting_periods;
     use contracts_common::signing::MessageSignature;
     use cosmwasm_std::testing::{mock_env, mock_info};
-    use cosmwasm_std::{coin, coins, Addr, Coin, Timestamp, Uint128};
+    use cosmwasm_std::{
+        coin, coins, from_binary, Addr, Coin, CosmosMsg, Timestamp, Uint128, WasmMsg,
+    };
     use mixnet_contract_common::mixnode::MixNodeCostParams;
-    use mixnet_contract_common::{Gateway, MixNode, Percent};
+    use mixnet_contract_common::{ExecuteMsg as MixnetExecuteMsg, Gateway, MixNode, Percent};
     use vesting_contract_common::messages::ExecuteMsg;
     use vesting_contract_common::{Account, PledgeCap, VestingSpecification};
     use vesting_contract_common::{Period, VestingContractError};
@@ -587,4 +589,104 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_vested_stake_migration() {
+        let mut deps = init_contract();
+        let mut env = mock_env();
+        let account = vesting_account_new_fixture(&mut deps.storage, &env);
+
+        // a couple of subdelegations towards each of the three nodes
+        for mix_id in 1..=3 {
+            for _ in 0..2 {
+                account
+                    .try_delegate_to_mixnode(
+                        mix_id,
+                        coin(100, TEST_COIN_DENOM),
+                        &env,
+                        &mut deps.storage,
+                    )
+                    .unwrap();
+                env.block.time = env.block.time.plus_seconds(42);
+            }
+        }
+
+        let migrate = ExecuteMsg::MigrateVestedStake { limit: Some(2) };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            migrate.clone(),
+        );
+        assert_eq!(
+            res,
+            Err(VestingContractError::VestingNotCompleted {
+                owner: account.owner_address(),
+                end_time: account.get_end_time()
+            })
+        );
+
+        env.block.time = account.get_end_time();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            migrate.clone(),
+        )
+        .unwrap();
+        let migrated = res
+            .messages
+            .iter()
+            .map(|sub_msg| match &sub_msg.msg {
+                CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => from_binary(msg).unwrap(),
+                other => panic!("unexpected message: {other:?}"),
+            })
+            .collect::<Vec<MixnetExecuteMsg>>();
+        assert_eq!(
+            migrated,
+            vec![
+                MixnetExecuteMsg::MigrateVestedDelegationOnBehalf {
+                    owner: "owner".to_string(),
+                    mix_id: 1
+                },
+                MixnetExecuteMsg::MigrateVestedDelegationOnBehalf {
+                    owner: "owner".to_string(),
+                    mix_id: 2
+                },
+            ]
+        );
+
+        // simulate the callbacks from the mixnet contract
+        for mix_id in 1..=2 {
+            let msg = ExecuteMsg::TrackMigratedDelegation {
+                owner: "owner".to_string(),
+                mix_id,
+            };
+            execute(deps.as_mut(), env.clone(), mock_info("test", &[]), msg).unwrap();
+        }
+
+        // the remaining delegation gets picked up by the next invocation
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            migrate.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        let msg = ExecuteMsg::TrackMigratedDelegation {
+            owner: "owner".to_string(),
+            mix_id: 3,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("test", &[]), msg).unwrap();
+
+        let res = execute(deps.as_mut(), env, mock_info("owner", &[]), migrate);
+        assert_eq!(
+            res,
+            Err(VestingContractError::NoVestedStakeToMigrate {
+                owner: account.owner_address()
+            })
+        );
+    }
 }
@@ -17,6 +17,7 @@ pub(crate) mod node_details;
 pub(crate) mod run;
 pub(crate) mod setup_ip_packet_router;
 pub(crate) mod setup_network_requester;
+pub(crate) mod setup_tenant;
 pub(crate) mod sign;
 
 #[derive(Subcommand)]
@@ -39,6 +40,9 @@ pub(crate) enum Commands {
     #[command(hide = true)]
     SetupIpPacketRouter(setup_ip_packet_router::CmdArgs),
 
+    /// Add an additional identity served by this gateway, with its own client listener and storage
+    SetupTenant(setup_tenant::CmdArgs),
+
     /// Sign text to prove ownership of this mixnode
     Sign(sign::Sign),
 
@@ -74,6 +78,7 @@ pub(crate) async fn execute(args: Cli) -> anyhow::Result<()> {
         Commands::Run(m) => run::execute(*m).await?,
         Commands::SetupNetworkRequester(m) => setup_network_requester::execute(m).await?,
         Commands::SetupIpPacketRouter(m) => setup_ip_packet_router::execute(m).await?,
+        Commands::SetupTenant(m) => setup_tenant::execute(m).await?,
         Commands::Sign(m) => sign::execute(m)?,
         Commands::BuildInfo(m) => build_info::execute(m),
         Commands::Completions(s) => s.generate(&mut crate::Cli::command(), bin_name),
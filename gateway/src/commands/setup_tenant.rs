@@ -0,0 +1,100 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::commands::helpers::try_load_current_config;
+use anyhow::bail;
+use clap::Args;
+use nym_bin_common::output_format::OutputFormat;
+use nym_crypto::asymmetric::identity;
+use nym_gateway::config::Tenant;
+use nym_gateway::helpers::load_public_key;
+use nym_pemstore::KeyPairPath;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use tracing::warn;
+
+#[derive(Args, Clone)]
+pub struct CmdArgs {
+    /// The id of the gateway you want to add the tenant to.
+    #[clap(long)]
+    id: String,
+
+    /// Human readable id of the new tenant.
+    #[clap(long)]
+    tenant_id: String,
+
+    /// Port used for listening for client websocket traffic of the new tenant.
+    /// It has to be announced in the bond of the tenant's identity.
+    #[clap(long)]
+    clients_port: u16,
+
+    #[clap(short, long, default_value_t = OutputFormat::default())]
+    output: OutputFormat,
+}
+
+#[derive(Serialize)]
+struct TenantDetails {
+    tenant_id: String,
+    identity_key: String,
+    clients_port: u16,
+}
+
+impl Display for TenantDetails {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Tenant id: {}", self.tenant_id)?;
+        writeln!(f, "Identity key: {}", self.identity_key)?;
+        write!(f, "Clients port: {}", self.clients_port)
+    }
+}
+
+pub async fn execute(args: CmdArgs) -> anyhow::Result<()> {
+    warn!("standalone gateways have been deprecated - please consider migrating it to a `nym-node` via `nym-node migrate gateway` command");
+
+    let mut config = try_load_current_config(&args.id)?;
+
+    if config.tenant(&args.tenant_id).is_some() {
+        bail!(
+            "tenant '{}' already exists for gateway '{}'",
+            args.tenant_id,
+            args.id
+        )
+    }
+    if config.gateway.clients_port == args.clients_port
+        || config
+            .tenants
+            .iter()
+            .any(|tenant| tenant.clients_port == args.clients_port)
+    {
+        bail!("clients port {} is already in use", args.clients_port)
+    }
+
+    let tenant = Tenant::new_default(&args.id, args.tenant_id, args.clients_port);
+    let identity_paths = KeyPairPath::new(
+        tenant.storage_paths.private_identity_key(),
+        tenant.storage_paths.public_identity_key(),
+    );
+
+    // don't overwrite keys of a tenant that might have been previously removed from the config
+    if !identity_paths.public_key_path.exists() {
+        let mut rng = rand::rngs::OsRng;
+        let identity_keys = identity::KeyPair::new(&mut rng);
+        nym_pemstore::store_keypair(&identity_keys, &identity_paths)?;
+    }
+    let identity_key: identity::PublicKey = load_public_key(
+        tenant.storage_paths.public_identity_key(),
+        "tenant identity",
+    )?;
+
+    let details = TenantDetails {
+        tenant_id: tenant.id.clone(),
+        identity_key: identity_key.to_base58_string(),
+        clients_port: tenant.clients_port,
+    };
+
+    config = config.with_tenant(tenant);
+    config.try_save()?;
+
+    args.output.to_stdout(&details);
+
+    Ok(())
+}
@@ -10,6 +10,7 @@ use nym_config::{
     must_get_home, read_config_from_toml_file, save_formatted_config_to_file, NymConfigTemplate,
     DEFAULT_CONFIG_DIR, DEFAULT_CONFIG_FILENAME, DEFAULT_DATA_DIR, NYM_DIR,
 };
+use nym_gateway_storage::message_store::RetentionPolicy;
 use nym_network_defaults::{mainnet, DEFAULT_NYM_NODE_HTTP_PORT, TICKETBOOK_VALIDITY_DAYS};
 use serde::{Deserialize, Serialize};
 use std::io;
@@ -20,7 +21,7 @@ use tracing::{debug, warn};
 use url::Url;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-pub use crate::config::persistence::paths::GatewayPaths;
+pub use crate::config::persistence::paths::{GatewayPaths, TenantPaths};
 
 pub mod persistence;
 mod template;
@@ -34,6 +35,11 @@ const DEFAULT_PACKET_FORWARDING_INITIAL_BACKOFF: Duration = Duration::from_milli
 const DEFAULT_PACKET_FORWARDING_MAXIMUM_BACKOFF: Duration = Duration::from_millis(300_000);
 const DEFAULT_INITIAL_CONNECTION_TIMEOUT: Duration = Duration::from_millis(1_500);
 const DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE: usize = 2000;
+const DEFAULT_PACKET_FORWARDING_MAXIMUM_RETRIES: u32 = 3;
+const DEFAULT_PACKET_FORWARDING_INITIAL_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+const DEFAULT_PACKET_FORWARDING_MAXIMUM_RETRY_BACKOFF: Duration = Duration::from_millis(5_000);
+const DEFAULT_PACKET_FORWARDING_MAXIMUM_RETRY_QUEUE_SIZE: usize = 10_000;
+const DEFAULT_MAXIMUM_DELAYED_FORWARD_HOPS: usize = 10_000;
 
 const DEFAULT_STORED_MESSAGE_FILENAME_LENGTH: u16 = 16;
 const DEFAULT_MESSAGE_RETRIEVAL_LIMIT: i64 = 100;
@@ -68,6 +74,7 @@ pub fn default_data_directory<P: AsRef<Path>>(id: P) -> PathBuf {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     // additional metadata holding on-disk location of this config file
@@ -93,6 +100,11 @@ pub struct Config {
 
     #[serde(default)]
     pub debug: Debug,
+
+    /// Additional identities served by this gateway process, each with its own client listener
+    /// and isolated client storage.
+    #[serde(default)]
+    pub tenants: Vec<Tenant>,
 }
 
 impl NymConfigTemplate for Config {
@@ -118,6 +130,7 @@ impl Config {
             ip_packet_router: Default::default(),
             logging: Default::default(),
             debug: Default::default(),
+            tenants: Vec::new(),
         }
     }
 
@@ -142,6 +155,7 @@ impl Config {
             ip_packet_router: ip_packet_router.into(),
             logging: logging.into(),
             debug: debug.into(),
+            tenants: Vec::new(),
         }
     }
 
@@ -259,6 +273,16 @@ impl Config {
         self
     }
 
+    #[must_use]
+    pub fn with_tenant(mut self, tenant: Tenant) -> Self {
+        self.tenants.push(tenant);
+        self
+    }
+
+    pub fn tenant(&self, tenant_id: &str) -> Option<&Tenant> {
+        self.tenants.iter().find(|tenant| tenant.id == tenant_id)
+    }
+
     pub fn get_nym_api_endpoints(&self) -> Vec<Url> {
         self.gateway.nym_api_urls.clone()
     }
@@ -274,6 +298,7 @@ impl Config {
 
 // TODO: this is very much a WIP. we need proper ssl certificate support here
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields)]
 pub struct Host {
     /// Ip address(es) of this host, such as 1.1.1.1 that external clients will use for connections.
@@ -296,6 +321,7 @@ impl Host {
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields)]
 pub struct Http {
     /// Socket address this node will use for binding its http API.
@@ -321,6 +347,7 @@ impl Default for Http {
 
 // we only really care about the mnemonic being zeroized
 #[derive(Debug, Deserialize, PartialEq, Eq, Serialize, Zeroize, ZeroizeOnDrop)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 pub struct Gateway {
     /// Version of the gateway for which this configuration was created.
     pub version: String,
@@ -350,6 +377,12 @@ pub struct Gateway {
     #[serde(deserialize_with = "de_maybe_port")]
     pub clients_wss_port: Option<u16>,
 
+    /// If applicable, port used for listening for client traffic using the raw framed TCP transport
+    /// rather than the WebSocket.
+    /// (default: None)
+    #[serde(default, deserialize_with = "de_maybe_port")]
+    pub clients_framed_port: Option<u16>,
+
     /// Addresses to APIs from which the node gets the view of the network.
     #[serde(alias = "validator_api_urls")]
     #[zeroize(skip)]
@@ -363,6 +396,7 @@ pub struct Gateway {
     /// Mnemonic of a cosmos wallet used in checking for double spending.
     // #[deprecated(note = "move to storage")]
     // TODO: I don't think this should be stored directly in the config...
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     pub cosmos_mnemonic: bip39::Mnemonic,
 }
 
@@ -378,6 +412,7 @@ impl Gateway {
             mix_port: DEFAULT_MIX_LISTENING_PORT,
             clients_port: DEFAULT_CLIENT_LISTENING_PORT,
             clients_wss_port: None,
+            clients_framed_port: None,
             nym_api_urls: vec![mainnet::NYM_API.parse().expect("Invalid default API URL")],
             nyxd_urls: vec![mainnet::NYXD_URL.parse().expect("Invalid default nyxd URL")],
             cosmos_mnemonic: bip39::Mnemonic::generate(24)
@@ -386,7 +421,36 @@ impl Gateway {
     }
 }
 
+/// An additional bonded identity served by this gateway, for example during identity migration
+/// or when a single operator runs multiple white-label gateways from one process.
+///
+/// Each tenant gets its own client websocket listener, so clients are routed to the correct
+/// identity based on the port announced in that tenant's bond.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct Tenant {
+    /// Human readable ID of this particular tenant. It must be unique within the gateway.
+    pub id: String,
+
+    /// Port used for listening for client websocket traffic of this tenant.
+    pub clients_port: u16,
+
+    pub storage_paths: TenantPaths,
+}
+
+impl Tenant {
+    pub fn new_default<S: AsRef<str>>(gateway_id: S, tenant_id: String, clients_port: u16) -> Self {
+        Tenant {
+            storage_paths: TenantPaths::new_default(gateway_id.as_ref(), &tenant_id),
+            id: tenant_id,
+            clients_port,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct NetworkRequester {
     /// Specifies whether network requester service is enabled in this process.
@@ -401,6 +465,7 @@ impl Default for NetworkRequester {
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct IpPacketRouter {
     /// Specifies whether ip packet router service is enabled in this process.
@@ -415,26 +480,48 @@ impl Default for IpPacketRouter {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct Debug {
     /// Initial value of an exponential backoff to reconnect to dropped TCP connection when
     /// forwarding sphinx packets.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub packet_forwarding_initial_backoff: Duration,
 
     /// Maximum value of an exponential backoff to reconnect to dropped TCP connection when
     /// forwarding sphinx packets.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub packet_forwarding_maximum_backoff: Duration,
 
     /// Timeout for establishing initial connection when trying to forward a sphinx packet.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub initial_connection_timeout: Duration,
 
     /// Maximum number of packets that can be stored waiting to get sent to a particular connection.
     pub maximum_connection_buffer_size: usize,
 
+    /// Maximum number of times forwarding of a sphinx packet is going to be retried if the connection
+    /// to its next hop can't accept it, before the packet gets dropped. Setting it to 0 disables retries.
+    pub packet_forwarding_maximum_retries: u32,
+
+    /// Initial value of an exponential backoff between subsequent attempts of forwarding a sphinx packet.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
+    #[serde(with = "humantime_serde")]
+    pub packet_forwarding_initial_retry_backoff: Duration,
+
+    /// Maximum value of an exponential backoff between subsequent attempts of forwarding a sphinx packet.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
+    #[serde(with = "humantime_serde")]
+    pub packet_forwarding_maximum_retry_backoff: Duration,
+
+    /// Maximum number of sphinx packets that can be waiting to be retried at any given time.
+    pub packet_forwarding_maximum_retry_queue_size: usize,
+
     /// Delay between each subsequent presence data being sent.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     // DEAD FIELD
     pub presence_sending_delay: Duration,
@@ -447,6 +534,7 @@ pub struct Debug {
     pub message_retrieval_limit: i64,
 
     /// Defines maximum delay between client bandwidth information being flushed to the persistent storage.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub client_bandwidth_max_flushing_rate: Duration,
 
@@ -461,6 +549,35 @@ pub struct Debug {
 
     #[serde(default)]
     pub zk_nym_tickets: ZkNymTicketHandlerDebug,
+
+    #[serde(default)]
+    pub client_sessions: ClientSessionsDebug,
+
+    /// Specifies whether the gateway should share its own observations, such as the fraction of
+    /// rejected packets, with the clients that request a protocol statistics exchange.
+    /// Clients' own statistics are accepted regardless.
+    #[serde(default)]
+    pub share_protocol_stats: bool,
+
+    /// Specifies whether the gateway should stop encrypting the messages stored for offline clients
+    /// with keys derived from the shared keys of their recipients.
+    #[serde(default)]
+    pub disable_stored_messages_encryption: bool,
+
+    /// Specifies whether the gateway should process the received packets that are meant to be forwarded
+    /// to another node, i.e. act as a regular mix hop, rather than dropping them.
+    #[serde(default)]
+    pub process_forward_hops: bool,
+
+    /// Maximum number of forward hop packets that can be waiting for their delay to expire at any given time.
+    /// Any packets received beyond that are dropped.
+    pub maximum_delayed_forward_hops: usize,
+
+    #[serde(default)]
+    pub directory_monitor: DirectoryMonitorDebug,
+
+    #[serde(default)]
+    pub message_store: MessageStoreDebug,
 }
 
 impl Default for Debug {
@@ -471,6 +588,13 @@ impl Default for Debug {
             initial_connection_timeout: DEFAULT_INITIAL_CONNECTION_TIMEOUT,
             presence_sending_delay: DEFAULT_PRESENCE_SENDING_DELAY,
             maximum_connection_buffer_size: DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE,
+            packet_forwarding_maximum_retries: DEFAULT_PACKET_FORWARDING_MAXIMUM_RETRIES,
+            packet_forwarding_initial_retry_backoff:
+                DEFAULT_PACKET_FORWARDING_INITIAL_RETRY_BACKOFF,
+            packet_forwarding_maximum_retry_backoff:
+                DEFAULT_PACKET_FORWARDING_MAXIMUM_RETRY_BACKOFF,
+            packet_forwarding_maximum_retry_queue_size:
+                DEFAULT_PACKET_FORWARDING_MAXIMUM_RETRY_QUEUE_SIZE,
             stored_messages_filename_length: DEFAULT_STORED_MESSAGE_FILENAME_LENGTH,
             message_retrieval_limit: DEFAULT_MESSAGE_RETRIEVAL_LIMIT,
             client_bandwidth_max_flushing_rate: DEFAULT_CLIENT_BANDWIDTH_MAX_FLUSHING_RATE,
@@ -478,11 +602,192 @@ impl Default for Debug {
                 DEFAULT_CLIENT_BANDWIDTH_MAX_DELTA_FLUSHING_AMOUNT,
             use_legacy_framed_packet_version: false,
             zk_nym_tickets: Default::default(),
+            client_sessions: Default::default(),
+            share_protocol_stats: false,
+            disable_stored_messages_encryption: false,
+            process_forward_hops: false,
+            maximum_delayed_forward_hops: DEFAULT_MAXIMUM_DELAYED_FORWARD_HOPS,
+            directory_monitor: Default::default(),
+            message_store: Default::default(),
+        }
+    }
+}
+
+/// Specifies how the gateway should behave when a client opens another websocket session
+/// while it already has an active one, e.g. when the same keys are used on two devices.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateSessionPolicy {
+    /// Reject the new session unless the existing one is no longer responsive.
+    #[default]
+    RejectNew,
+
+    /// Always terminate the existing session in favour of the new one.
+    KickOld,
+
+    /// Keep all the sessions (up to the configured limit) and deliver every received message to each of them.
+    FanOut,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ClientSessionsDebug {
+    /// Specifies the behaviour upon a client opening another websocket session.
+    pub duplicate_session_policy: DuplicateSessionPolicy,
+
+    /// Specifies the maximum number of concurrent sessions of a single client.
+    /// Only applicable if `duplicate_session_policy` is set to `fan_out`.
+    pub max_sessions_per_client: usize,
+}
+
+impl ClientSessionsDebug {
+    pub const DEFAULT_MAX_SESSIONS_PER_CLIENT: usize = 4;
+}
+
+impl Default for ClientSessionsDebug {
+    fn default() -> Self {
+        ClientSessionsDebug {
+            duplicate_session_policy: DuplicateSessionPolicy::default(),
+            max_sessions_per_client: Self::DEFAULT_MAX_SESSIONS_PER_CLIENT,
+        }
+    }
+}
+
+/// Specifies how the gateway should monitor its own entry in the network directory, so that
+/// any misconfiguration (or worsening performance) could be noticed early.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct DirectoryMonitorDebug {
+    pub enabled: bool,
+
+    /// Delay between subsequent checks of the directory entry.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
+    #[serde(with = "humantime_serde")]
+    pub check_interval: Duration,
+
+    /// Performance (in percent) below which a warning is raised.
+    pub minimum_performance: u8,
+
+    /// Drop in performance (in percentage points) between subsequent checks for which a warning is raised.
+    pub performance_drop_threshold: u8,
+}
+
+impl DirectoryMonitorDebug {
+    pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+    pub const DEFAULT_MINIMUM_PERFORMANCE: u8 = 50;
+    pub const DEFAULT_PERFORMANCE_DROP_THRESHOLD: u8 = 20;
+}
+
+impl Default for DirectoryMonitorDebug {
+    fn default() -> Self {
+        DirectoryMonitorDebug {
+            enabled: true,
+            check_interval: Self::DEFAULT_CHECK_INTERVAL,
+            minimum_performance: Self::DEFAULT_MINIMUM_PERFORMANCE,
+            performance_drop_threshold: Self::DEFAULT_PERFORMANCE_DROP_THRESHOLD,
+        }
+    }
+}
+
+/// Specifies where the messages received for offline clients are persisted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum MessageStoreBackend {
+    /// Keep the messages in the same sqlite database as the rest of the client data.
+    #[default]
+    Sqlite,
+
+    /// Keep the messages in a dedicated RocksDB database, better suited for gateways serving many clients.
+    /// Requires the gateway to be compiled with the `rocksdb` feature.
+    Rocksdb,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct MessageStoreDebug {
+    /// Specifies the backend used for storing messages of offline clients.
+    pub backend: MessageStoreBackend,
+
+    /// If set, messages that haven't been retrieved for `offload_after` are moved to the object store
+    /// at this url, e.g. `s3://bucket/path`, with the S3 credentials being read from the `AWS_*` environment variables.
+    /// Requires the gateway to be compiled with the `object-store-offload` feature.
+    #[cfg_attr(feature = "config_schema", schemars(with = "Option<String>"))]
+    #[serde(deserialize_with = "de_maybe_stringified")]
+    pub offload_url: Option<Url>,
+
+    /// Age after which the messages are moved to the object store.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
+    #[serde(with = "humantime_serde")]
+    pub offload_after: Duration,
+
+    /// Delay between subsequent checks for the messages to move to the object store.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
+    #[serde(with = "humantime_serde")]
+    pub offload_check_interval: Duration,
+
+    /// If non-zero, messages of offline clients are padded to a multiple of this many bytes before being stored,
+    /// so that their lengths could not be learned from the storage.
+    pub padding_bucket_size: usize,
+
+    /// If non-zero, specifies the maximum number of messages kept for a single offline client.
+    /// The oldest messages exceeding the limit are removed.
+    pub max_messages_per_client: u64,
+
+    /// If non-zero, specifies the maximum age of the kept messages.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
+    #[serde(with = "humantime_serde")]
+    pub max_message_age: Duration,
+
+    /// If non-zero, specifies the maximum total size, in bytes, of the (padded and encrypted) contents
+    /// of the kept messages of all the clients. The oldest messages exceeding the limit are removed.
+    /// Note that it's not the disk usage, as the storage overhead is not accounted for.
+    pub max_total_content_size: u64,
+
+    /// Delay between subsequent removals of the messages exceeding any of the retention limits.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
+    #[serde(with = "humantime_serde")]
+    pub retention_check_interval: Duration,
+}
+
+impl MessageStoreDebug {
+    pub const DEFAULT_OFFLOAD_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+    pub const DEFAULT_OFFLOAD_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+    pub const DEFAULT_RETENTION_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+    pub fn retention_policy(&self) -> RetentionPolicy {
+        RetentionPolicy {
+            max_messages_per_client: (self.max_messages_per_client != 0)
+                .then_some(self.max_messages_per_client),
+            max_age: (!self.max_message_age.is_zero()).then_some(self.max_message_age),
+            max_total_content_size: (self.max_total_content_size != 0)
+                .then_some(self.max_total_content_size),
+        }
+    }
+}
+
+impl Default for MessageStoreDebug {
+    fn default() -> Self {
+        MessageStoreDebug {
+            backend: MessageStoreBackend::default(),
+            offload_url: None,
+            offload_after: Self::DEFAULT_OFFLOAD_AFTER,
+            offload_check_interval: Self::DEFAULT_OFFLOAD_CHECK_INTERVAL,
+            padding_bucket_size: 0,
+            max_messages_per_client: 0,
+            max_message_age: Duration::ZERO,
+            max_total_content_size: 0,
+            retention_check_interval: Self::DEFAULT_RETENTION_CHECK_INTERVAL,
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 pub struct ZkNymTicketHandlerDebug {
     /// Specifies the multiplier for revoking a malformed/double-spent ticket
     /// (if it has to go all the way to the nym-api for verification)
@@ -492,6 +797,7 @@ pub struct ZkNymTicketHandlerDebug {
 
     /// Specifies the interval for attempting to resolve any failed, pending operations,
     /// such as ticket verification or redemption.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub pending_poller: Duration,
 
@@ -502,6 +808,7 @@ pub struct ZkNymTicketHandlerDebug {
 
     /// Specifies the maximum time between two subsequent tickets redemptions.
     /// That's required as nym-apis will purge all ticket information for tickets older than maximum validity.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub maximum_time_between_redemption: Duration,
 }
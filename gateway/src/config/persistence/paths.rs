@@ -19,6 +19,8 @@ pub const DEFAULT_NETWORK_REQUESTER_DATA_DIR: &str = "network-requester-data";
 pub const DEFAULT_IP_PACKET_ROUTER_CONFIG_FILENAME: &str = "ip_packet_router_config.toml";
 pub const DEFAULT_IP_PACKET_ROUTER_DATA_DIR: &str = "ip-packet-router-data";
 
+pub const DEFAULT_TENANTS_DATA_DIR: &str = "tenants";
+
 // pub const DEFAULT_DESCRIPTION_FILENAME: &str = "description.toml";
 
 pub fn default_network_requester_data_dir<P: AsRef<Path>>(id: P) -> PathBuf {
@@ -29,7 +31,14 @@ pub fn default_ip_packet_router_data_dir<P: AsRef<Path>>(id: P) -> PathBuf {
     default_data_directory(id).join(DEFAULT_IP_PACKET_ROUTER_DATA_DIR)
 }
 
+pub fn default_tenant_data_dir<P: AsRef<Path>>(id: P, tenant_id: &str) -> PathBuf {
+    default_data_directory(id)
+        .join(DEFAULT_TENANTS_DATA_DIR)
+        .join(tenant_id)
+}
+
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields)]
 pub struct GatewayPaths {
     pub keys: KeysPaths,
@@ -127,6 +136,7 @@ impl GatewayPaths {
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 pub struct KeysPaths {
     /// Path to file containing private identity key.
     pub private_identity_key_file: PathBuf,
@@ -169,3 +179,39 @@ impl KeysPaths {
         &self.public_sphinx_key_file
     }
 }
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct TenantPaths {
+    /// Path to file containing private identity key of this tenant.
+    pub private_identity_key_file: PathBuf,
+
+    /// Path to file containing public identity key of this tenant.
+    pub public_identity_key_file: PathBuf,
+
+    /// Path to sqlite database containing all persistent data of clients of this tenant.
+    /// It is kept separate from the main gateway storage so that the client namespaces of different
+    /// tenants never overlap.
+    pub clients_storage: PathBuf,
+}
+
+impl TenantPaths {
+    pub fn new_default<P: AsRef<Path>>(id: P, tenant_id: &str) -> Self {
+        let data_dir = default_tenant_data_dir(id, tenant_id);
+
+        TenantPaths {
+            private_identity_key_file: data_dir.join(DEFAULT_PRIVATE_IDENTITY_KEY_FILENAME),
+            public_identity_key_file: data_dir.join(DEFAULT_PUBLIC_IDENTITY_KEY_FILENAME),
+            clients_storage: data_dir.join(DEFAULT_CLIENTS_STORAGE_FILENAME),
+        }
+    }
+
+    pub fn private_identity_key(&self) -> &Path {
+        &self.private_identity_key_file
+    }
+
+    pub fn public_identity_key(&self) -> &Path {
+        &self.public_identity_key_file
+    }
+}
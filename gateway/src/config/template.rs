@@ -48,6 +48,10 @@ clients_port = {{ gateway.clients_port }}
 # (default: 0 - disabled)
 clients_wss_port ={{#if gateway.clients_wss_port }} {{ gateway.clients_wss_port }} {{else}} 0 {{/if}}
 
+# If applicable, port used for listening for client traffic using the raw framed TCP transport.
+# (default: 0 - disabled)
+clients_framed_port ={{#if gateway.clients_framed_port }} {{ gateway.clients_framed_port }} {{else}} 0 {{/if}}
+
 # Addresses to APIs running on validator from which the node gets the view of the network.
 nym_api_urls = [
     {{#each gateway.nym_api_urls }}
@@ -104,6 +108,26 @@ network_requester_config = '{{ storage_paths.network_requester_config }}'
 # Path to the configuration of the embedded ip packet router.
 ip_packet_router_config = '{{ storage_paths.ip_packet_router_config }}'
 
+##### additional tenants served by this gateway #####
+
+{{#each tenants }}
+[[tenants]]
+# Human readable ID of this particular tenant.
+id = '{{ this.id }}'
+
+# Port used for listening for client websocket traffic of this tenant.
+clients_port = {{ this.clients_port }}
+
+# Path to file containing private identity key of this tenant.
+storage_paths.private_identity_key_file = '{{ this.storage_paths.private_identity_key_file }}'
+
+# Path to file containing public identity key of this tenant.
+storage_paths.public_identity_key_file = '{{ this.storage_paths.public_identity_key_file }}'
+
+# Path to sqlite database containing all persistent data of clients of this tenant.
+storage_paths.clients_storage = '{{ this.storage_paths.clients_storage }}'
+
+{{/each}}
 ##### logging configuration options #####
 
 [logging]
@@ -115,6 +115,9 @@ pub enum GatewayError {
         source: StorageError,
     },
 
+    #[error("the configured message store requires the gateway to be compiled with the '{feature}' feature")]
+    MessageStoreFeatureDisabled { feature: &'static str },
+
     #[error("Path to network requester configuration file hasn't been specified. Perhaps try to run `setup-network-requester`?")]
     UnspecifiedNetworkRequesterConfig,
 
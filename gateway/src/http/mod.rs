@@ -10,6 +10,7 @@ use nym_network_requester::RequestFilter;
 use nym_node_http_api::api::api_requests;
 use nym_node_http_api::api::api_requests::v1::network_requester::exit_policy::models::UsedExitPolicy;
 use nym_node_http_api::api::api_requests::SignedHostInformation;
+use nym_node_http_api::state::gateway::SharedDirectoryStatus;
 use nym_node_http_api::NymNodeHttpError;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_task::TaskClient;
@@ -136,6 +137,7 @@ pub(crate) struct HttpApiBuilder<'a> {
     network_requester_config: Option<&'a nym_network_requester::Config>,
     exit_policy: Option<UsedExitPolicy>,
     ip_packet_router_config: Option<&'a nym_ip_packet_router::Config>,
+    directory_status: Option<SharedDirectoryStatus>,
 
     identity_keypair: &'a identity::KeyPair,
     // TODO: this should be a wg specific key and not re-used sphinx
@@ -153,6 +155,7 @@ impl<'a> HttpApiBuilder<'a> {
             network_requester_config: None,
             ip_packet_router_config: None,
             exit_policy: None,
+            directory_status: None,
             identity_keypair,
             sphinx_keypair,
         }
@@ -218,6 +221,15 @@ impl<'a> HttpApiBuilder<'a> {
         self
     }
 
+    #[must_use]
+    pub(crate) fn with_maybe_directory_status(
+        mut self,
+        directory_status: Option<SharedDirectoryStatus>,
+    ) -> Self {
+        self.directory_status = directory_status;
+        self
+    }
+
     pub(crate) fn start(self, task_client: TaskClient) -> Result<(), GatewayError> {
         debug!("starting http API");
 
@@ -249,6 +261,10 @@ impl<'a> HttpApiBuilder<'a> {
             }
         }
 
+        if let Some(directory_status) = self.directory_status {
+            config = config.with_gateway_directory_status(directory_status);
+        }
+
         if let Some(ipr_config) = self.ip_packet_router_config {
             config = config.with_ip_packet_router(load_ip_packet_router_details(
                 self.gateway_config,
@@ -3,31 +3,94 @@
 
 use super::websocket::message_receiver::{IsActiveRequestSender, MixMessageSender};
 use crate::node::client_handling::embedded_clients::LocalEmbeddedClientHandle;
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
 use nym_sphinx::DestinationAddressBytes;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tracing::warn;
 
+pub(crate) type SessionId = u64;
+
 enum ActiveClient {
-    /// Handle to a remote client connected via a network socket.
-    Remote(ClientIncomingChannels),
+    /// Handles to all the sessions of a remote client connected via network sockets.
+    Remote(Vec<RemoteSession>),
 
     /// Handle to a locally (inside the same process) running client.
     Embedded(LocalEmbeddedClientHandle),
 }
 
+struct RemoteSession {
+    id: SessionId,
+    channels: ClientIncomingChannels,
+}
+
+impl RemoteSession {
+    fn is_stale(&self) -> bool {
+        self.channels.mix_message_sender.is_closed()
+    }
+
+    // closing the channels will cause the associated connection handler to terminate
+    fn terminate(self) {
+        self.channels.mix_message_sender.close_channel();
+        self.channels.is_active_request_sender.close_channel();
+    }
+}
+
 impl ActiveClient {
-    fn get_sender_ref(&self) -> &MixMessageSender {
+    /// Removes all stale remote sessions and returns whether there are any active ones left.
+    fn prune(&mut self) -> bool {
         match self {
-            ActiveClient::Remote(remote) => &remote.mix_message_sender,
-            ActiveClient::Embedded(embedded) => &embedded.mix_message_sender,
+            ActiveClient::Remote(sessions) => {
+                sessions.retain(|session| !session.is_stale());
+                !sessions.is_empty()
+            }
+            ActiveClient::Embedded(embedded) => !embedded.mix_message_sender.is_closed(),
         }
     }
 
-    fn get_sender(&self) -> MixMessageSender {
+    fn get_senders(&self) -> ActiveClientSenders {
         match self {
-            ActiveClient::Remote(remote) => remote.mix_message_sender.clone(),
-            ActiveClient::Embedded(embedded) => embedded.mix_message_sender.clone(),
+            ActiveClient::Remote(sessions) => ActiveClientSenders(
+                sessions
+                    .iter()
+                    .map(|session| session.channels.mix_message_sender.clone())
+                    .collect(),
+            ),
+            ActiveClient::Embedded(embedded) => {
+                ActiveClientSenders(vec![embedded.mix_message_sender.clone()])
+            }
+        }
+    }
+}
+
+/// Sending channels to all the sessions of particular client.
+#[derive(Clone)]
+pub(crate) struct ActiveClientSenders(Vec<MixMessageSender>);
+
+impl ActiveClientSenders {
+    /// Checks whether any of the underlying sessions got closed, meaning the senders should be re-obtained.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.0.iter().any(|sender| sender.is_closed())
+    }
+
+    /// Attempts to deliver the messages to all the sessions of the client.
+    /// It is only considered a failure if none of the sessions received them,
+    /// in which case the messages are returned back.
+    pub(crate) fn unbounded_send(&self, messages: Vec<Vec<u8>>) -> Result<(), Vec<Vec<u8>>> {
+        let Some((last, rest)) = self.0.split_last() else {
+            return Err(messages);
+        };
+
+        let mut delivered = false;
+        for sender in rest {
+            delivered |= sender.unbounded_send(messages.clone()).is_ok();
+        }
+
+        match last.unbounded_send(messages) {
+            Ok(_) => Ok(()),
+            Err(_) if delivered => Ok(()),
+            Err(unsent) => Err(unsent.into_inner()),
         }
     }
 }
@@ -35,6 +98,7 @@ impl ActiveClient {
 #[derive(Clone)]
 pub(crate) struct ActiveClientsStore {
     inner: Arc<DashMap<DestinationAddressBytes, ActiveClient>>,
+    next_session_id: Arc<AtomicU64>,
 }
 
 #[derive(Clone)]
@@ -51,102 +115,153 @@ impl ActiveClientsStore {
     pub(crate) fn new() -> Self {
         ActiveClientsStore {
             inner: Arc::new(DashMap::new()),
+            next_session_id: Arc::new(AtomicU64::new(0)),
         }
     }
 
-    /// Tries to obtain sending channel to specified client. Note that if stale entry existed, it is
-    /// removed and a `None` is returned instead.
-    ///
-    /// # Arguments
-    ///
-    /// * `client`: address of the client for which to obtain the handle.
-    pub(crate) fn get_sender(&self, client: DestinationAddressBytes) -> Option<MixMessageSender> {
-        let entry = self.inner.get(&client)?;
-        let handle = entry.value().get_sender();
-
-        // if the entry is stale, remove it from the map
-        // if handle.is_valid() {
-        if !handle.is_closed() {
-            Some(handle)
+    /// Removes all stale sessions of the specified client and, if none are left, the whole entry.
+    /// Returns whether the client still has any active sessions.
+    fn prune(&self, client: DestinationAddressBytes) -> bool {
+        let Some(mut entry) = self.inner.get_mut(&client) else {
+            return false;
+        };
+        if entry.value_mut().prune() {
+            true
         } else {
             // drop the reference to the map to prevent deadlocks
             drop(entry);
             self.inner.remove(&client);
-            None
+            false
         }
     }
 
-    /// Attempts to get full handle to a remotely connected client
-    pub(crate) fn get_remote_client(
+    /// Tries to obtain sending channels to all sessions of the specified client. Note that any stale
+    /// sessions are removed and if there are none left, a `None` is returned instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `client`: address of the client for which to obtain the handle.
+    pub(crate) fn get_sender(
         &self,
-        address: DestinationAddressBytes,
-    ) -> Option<ClientIncomingChannels> {
-        let entry = self.inner.get(&address)?;
-        let handle = entry.value();
-
-        let ActiveClient::Remote(channels) = handle else {
-            warn!("attempted to get a remote handle to a embedded network requester");
+        client: DestinationAddressBytes,
+    ) -> Option<ActiveClientSenders> {
+        if !self.prune(client) {
             return None;
+        }
+        self.inner
+            .get(&client)
+            .map(|entry| entry.value().get_senders())
+    }
+
+    /// Attempts to get full handles to all the sessions of a remotely connected client
+    pub(crate) fn get_remote_sessions(
+        &self,
+        address: DestinationAddressBytes,
+    ) -> Vec<ClientIncomingChannels> {
+        if !self.prune(address) {
+            return Vec::new();
+        }
+        let Some(entry) = self.inner.get(&address) else {
+            return Vec::new();
         };
 
-        // if the entry is stale, remove it from the map
-        if !channels.mix_message_sender.is_closed() {
-            Some(channels.clone())
-        } else {
-            // drop the reference to the map to prevent deadlocks
-            drop(entry);
-            self.inner.remove(&address);
-            None
+        match entry.value() {
+            ActiveClient::Remote(sessions) => sessions
+                .iter()
+                .map(|session| session.channels.clone())
+                .collect(),
+            ActiveClient::Embedded(_) => {
+                warn!("attempted to get a remote handle to a embedded network requester");
+                Vec::new()
+            }
         }
     }
 
     /// Checks whether there's already an active connection to this client.
     /// It will also remove the entry from the map if its stale.
     pub(crate) fn is_active(&self, client: DestinationAddressBytes) -> bool {
-        let Some(entry) = self.inner.get(&client) else {
-            return false;
-        };
-        let handle = entry.value().get_sender_ref();
-
-        // if the entry is stale, remove it from the map
-        if !handle.is_closed() {
-            true
-        } else {
-            // drop the reference to the map to prevent deadlocks
-            drop(entry);
-            self.inner.remove(&client);
-            false
-        }
+        self.prune(client)
     }
 
-    /// Indicates particular client has disconnected from the gateway and its handle should get removed.
+    /// Indicates particular session of the client has disconnected from the gateway and its handle should get removed.
     ///
     /// # Arguments
     ///
     /// * `client`: address of the client for which to remove the handle.
-    pub(crate) fn disconnect(&self, client: DestinationAddressBytes) {
-        self.inner.remove(&client);
+    /// * `session`: id of the session that got disconnected.
+    pub(crate) fn disconnect(&self, client: DestinationAddressBytes, session: SessionId) {
+        self.inner.remove_if_mut(&client, |_, active| match active {
+            ActiveClient::Remote(sessions) => {
+                sessions.retain(|s| s.id != session);
+                sessions.is_empty()
+            }
+            ActiveClient::Embedded(_) => false,
+        });
+    }
+
+    /// Removes all sessions of the specified remote client and terminates their connection handlers.
+    ///
+    /// # Arguments
+    ///
+    /// * `client`: address of the client for which to remove the handles.
+    pub(crate) fn kick(&self, client: DestinationAddressBytes) {
+        let removed = self.inner.remove_if(&client, |_, active| {
+            matches!(active, ActiveClient::Remote(_))
+        });
+        if let Some((_, ActiveClient::Remote(sessions))) = removed {
+            for session in sessions {
+                session.terminate()
+            }
+        }
     }
 
-    /// Insert new client handle into the store.
+    /// Insert new client session handle into the store.
+    /// If multiple sessions are not allowed, any existing session is going to get terminated.
     ///
     /// # Arguments
     ///
     /// * `client`: address of the client for which to insert the handle.
     /// * `handle`: the sender channel for all mix packets to be pushed back onto the websocket
+    /// * `is_active_request_sender`: the sender channel for checking whether the session is still active
+    /// * `allow_multiple_sessions`: whether the session should be added alongside any existing ones
     pub(crate) fn insert_remote(
         &self,
         client: DestinationAddressBytes,
         handle: MixMessageSender,
         is_active_request_sender: IsActiveRequestSender,
-    ) {
-        let entry = ActiveClient::Remote(ClientIncomingChannels {
-            mix_message_sender: handle,
-            is_active_request_sender,
-        });
-        if self.inner.insert(client, entry).is_some() {
-            panic!("inserted a duplicate remote client")
+        allow_multiple_sessions: bool,
+    ) -> SessionId {
+        let id = self.next_session_id.fetch_add(1, Ordering::Relaxed);
+        let session = RemoteSession {
+            id,
+            channels: ClientIncomingChannels {
+                mix_message_sender: handle,
+                is_active_request_sender,
+            },
+        };
+
+        match self.inner.entry(client) {
+            Entry::Vacant(entry) => {
+                entry.insert(ActiveClient::Remote(vec![session]));
+            }
+            Entry::Occupied(mut entry) => match entry.get_mut() {
+                ActiveClient::Remote(sessions) => {
+                    if !allow_multiple_sessions {
+                        // we lost a race with another session of the same client
+                        warn!("replacing an existing session of {client}");
+                        for existing in sessions.drain(..) {
+                            existing.terminate()
+                        }
+                    }
+                    sessions.push(session)
+                }
+                ActiveClient::Embedded(_) => {
+                    panic!("attempted to insert a remote client with the same address as our local embedded client!")
+                }
+            },
         }
+
+        id
     }
 
     /// Inserts a handle to the embedded client
@@ -166,3 +281,130 @@ impl ActiveClientsStore {
         self.inner.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::client_handling::websocket::message_receiver::{
+        IsActiveRequestReceiver, MixMessageReceiver,
+    };
+    use futures::channel::mpsc;
+    use nym_sphinx::addressing::clients::Recipient;
+
+    struct TestSession {
+        id: SessionId,
+        mix_receiver: MixMessageReceiver,
+        _is_active_receiver: IsActiveRequestReceiver,
+    }
+
+    fn client() -> DestinationAddressBytes {
+        DestinationAddressBytes::from_bytes([42; 32])
+    }
+
+    fn connect(store: &ActiveClientsStore, allow_multiple_sessions: bool) -> TestSession {
+        let (mix_sender, mix_receiver) = mpsc::unbounded();
+        let (is_active_sender, is_active_receiver) = mpsc::unbounded();
+        let id = store.insert_remote(
+            client(),
+            mix_sender,
+            is_active_sender,
+            allow_multiple_sessions,
+        );
+        TestSession {
+            id,
+            mix_receiver,
+            _is_active_receiver: is_active_receiver,
+        }
+    }
+
+    #[test]
+    fn single_session_replaces_existing_one() {
+        let store = ActiveClientsStore::new();
+        let mut old = connect(&store, false);
+        let mut new = connect(&store, false);
+
+        // the old connection handler got terminated
+        assert!(matches!(old.mix_receiver.try_next(), Ok(None)));
+
+        let senders = store.get_sender(client()).unwrap();
+        senders.unbounded_send(vec![vec![1, 2, 3]]).unwrap();
+        assert_eq!(
+            new.mix_receiver.try_next().unwrap(),
+            Some(vec![vec![1, 2, 3]])
+        );
+        assert_eq!(store.get_remote_sessions(client()).len(), 1);
+    }
+
+    #[test]
+    fn fanned_out_sessions_receive_all_messages() {
+        let store = ActiveClientsStore::new();
+        let mut first = connect(&store, true);
+        let mut second = connect(&store, true);
+        assert_eq!(store.get_remote_sessions(client()).len(), 2);
+
+        let senders = store.get_sender(client()).unwrap();
+        senders.unbounded_send(vec![vec![42]]).unwrap();
+        assert_eq!(first.mix_receiver.try_next().unwrap(), Some(vec![vec![42]]));
+        assert_eq!(
+            second.mix_receiver.try_next().unwrap(),
+            Some(vec![vec![42]])
+        );
+
+        // it's enough for a single session to have received the messages
+        drop(first);
+        assert!(senders.is_closed());
+        senders.unbounded_send(vec![vec![43]]).unwrap();
+        assert_eq!(
+            second.mix_receiver.try_next().unwrap(),
+            Some(vec![vec![43]])
+        );
+
+        // and the stale session gets pruned
+        assert_eq!(store.get_remote_sessions(client()).len(), 1);
+    }
+
+    #[test]
+    fn disconnecting_old_session_does_not_remove_newer_one() {
+        let store = ActiveClientsStore::new();
+        let old = connect(&store, false);
+        let _new = connect(&store, false);
+
+        // the handler of the replaced session only learns about it after the new one got inserted
+        store.disconnect(client(), old.id);
+        assert!(store.is_active(client()));
+        assert_eq!(store.get_remote_sessions(client()).len(), 1);
+    }
+
+    #[test]
+    fn disconnecting_last_session_removes_the_client() {
+        let store = ActiveClientsStore::new();
+        let session = connect(&store, true);
+        store.disconnect(client(), session.id);
+        assert!(!store.is_active(client()));
+        assert_eq!(store.size(), 0);
+    }
+
+    #[test]
+    fn kick_terminates_all_remote_sessions() {
+        let store = ActiveClientsStore::new();
+        let mut first = connect(&store, true);
+        let mut second = connect(&store, true);
+
+        store.kick(client());
+        assert!(!store.is_active(client()));
+        assert!(matches!(first.mix_receiver.try_next(), Ok(None)));
+        assert!(matches!(second.mix_receiver.try_next(), Ok(None)));
+    }
+
+    #[test]
+    fn kick_does_not_affect_embedded_clients() {
+        let store = ActiveClientsStore::new();
+        let address = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+        let (mix_sender, _mix_receiver) = mpsc::unbounded();
+        store.insert_embedded(LocalEmbeddedClientHandle::new(address, mix_sender));
+
+        let destination = address.identity().derive_destination_address();
+        store.kick(destination);
+        assert!(store.is_active(destination));
+    }
+}
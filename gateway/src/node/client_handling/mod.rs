@@ -4,4 +4,5 @@
 pub(crate) mod active_clients;
 mod bandwidth;
 pub(crate) mod embedded_clients;
+pub(crate) mod notices;
 pub(crate) mod websocket;
@@ -0,0 +1,65 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use nym_gateway_requests::GatewayNotice;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use tokio::sync::broadcast;
+use tracing::*;
+
+// there's no point in retaining (or buffering) more than a handful of notices,
+// realistically there will never be more than one or two in effect at any given time
+const MAX_ACTIVE_NOTICES: usize = 16;
+const NOTICES_CHANNEL_CAPACITY: usize = 16;
+
+/// Handle for publishing notices, such as the scheduled maintenance, to all the connected clients
+/// that have subscribed to them, regardless of which of the identities of this gateway they're using.
+#[derive(Clone)]
+pub struct GatewayNotices {
+    // notices currently in effect, sent to the clients as soon as they subscribe
+    active: Arc<Mutex<Vec<GatewayNotice>>>,
+    sender: broadcast::Sender<GatewayNotice>,
+}
+
+impl Default for GatewayNotices {
+    fn default() -> Self {
+        GatewayNotices::new()
+    }
+}
+
+impl GatewayNotices {
+    pub fn new() -> Self {
+        GatewayNotices {
+            active: Arc::new(Mutex::new(Vec::new())),
+            sender: broadcast::channel(NOTICES_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    fn active(&self) -> MutexGuard<'_, Vec<GatewayNotice>> {
+        self.active.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Pushes the notice to all the currently subscribed clients and retains it
+    /// for the ones subscribing later on, until it's withdrawn.
+    pub fn publish(&self, notice: GatewayNotice) {
+        let mut active = self.active();
+        info!("publishing gateway notice: {notice}");
+        if active.len() >= MAX_ACTIVE_NOTICES {
+            active.remove(0);
+        }
+        active.push(notice.clone());
+
+        // an error only implies there are no subscribed clients at the moment
+        let _ = self.sender.send(notice);
+    }
+
+    /// Stops sending the previously published notices to the newly subscribed clients.
+    pub fn withdraw_all(&self) {
+        self.active().clear()
+    }
+
+    pub(crate) fn subscribe(&self) -> (Vec<GatewayNotice>, broadcast::Receiver<GatewayNotice>) {
+        // subscribe while holding the lock so that no notice would be missed or duplicated
+        let active = self.active();
+        (active.clone(), self.sender.subscribe())
+    }
+}
@@ -1,6 +1,9 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
+use crate::config::ClientSessionsDebug;
+use crate::node::client_handling::notices::GatewayNotices;
+use crate::node::tenants::{TenantMetrics, TenantRouter};
 use nym_credential_verification::{ecash::EcashManager, BandwidthFlushingBehaviourConfig};
 use nym_crypto::asymmetric::identity;
 use std::sync::Arc;
@@ -13,4 +16,12 @@ pub(crate) struct CommonHandlerState<S> {
     pub(crate) local_identity: Arc<identity::KeyPair>,
     pub(crate) only_coconut_credentials: bool,
     pub(crate) bandwidth_cfg: BandwidthFlushingBehaviourConfig,
+    pub(crate) client_sessions: ClientSessionsDebug,
+    pub(crate) share_protocol_stats: bool,
+    pub(crate) metrics: TenantMetrics,
+
+    /// Index of the tenant served by this handler or `None` for the primary identity.
+    pub(crate) tenant: Option<usize>,
+    pub(crate) tenant_router: TenantRouter,
+    pub(crate) notices: GatewayNotices,
 }
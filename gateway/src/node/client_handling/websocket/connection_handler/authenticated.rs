@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use crate::node::client_handling::{
+    active_clients::SessionId,
     bandwidth::BandwidthError,
     websocket::{
         connection_handler::{ClientDetails, FreshHandler},
@@ -19,9 +20,9 @@ use nym_credential_verification::{
     bandwidth_storage_manager::BandwidthStorageManager, ClientBandwidth,
 };
 use nym_gateway_requests::{
-    types::{BinaryRequest, ServerResponse},
-    ClientControlRequest, ClientRequest, GatewayRequestsError, SensitiveServerResponse,
-    SimpleGatewayRequestsError,
+    types::{BinaryRequest, ProtocolStats, ServerResponse},
+    ClientControlRequest, ClientRequest, GatewayErrorCode, GatewayNotice, GatewayRequestsError,
+    SensitiveServerResponse, SimpleGatewayRequestsError,
 };
 use nym_gateway_storage::{error::StorageError, Storage};
 use nym_sphinx::forwarding::packet::MixPacket;
@@ -31,6 +32,7 @@ use rand::{random, CryptoRng, Rng};
 use std::{process, time::Duration};
 use thiserror::Error;
 use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::broadcast::{self, error::RecvError};
 use tokio_tungstenite::tungstenite::{protocol::Message, Error as WsError};
 use tracing::*;
 
@@ -94,6 +96,42 @@ pub enum RequestHandlingError {
 }
 
 impl RequestHandlingError {
+    fn error_code(&self) -> GatewayErrorCode {
+        match self {
+            RequestHandlingError::StorageError(_)
+            | RequestHandlingError::MissingClientBandwidthEntry { .. }
+            | RequestHandlingError::APIError(_)
+            | RequestHandlingError::CoconutApiError(_)
+            | RequestHandlingError::InternalError
+            | RequestHandlingError::BandwidthRecoveryFailure(_) => GatewayErrorCode::Internal,
+            RequestHandlingError::UnknownBinaryRequest
+            | RequestHandlingError::UnknownTextRequest
+            | RequestHandlingError::UnknownEncryptedTextRequest
+            | RequestHandlingError::InvalidEncryptedTextRequest
+            | RequestHandlingError::InvalidTextRequest(_) => GatewayErrorCode::MalformedRequest,
+            RequestHandlingError::InvalidBinaryRequest(err) => err.error_code(),
+            RequestHandlingError::IllegalRequest { .. } => GatewayErrorCode::UnexpectedRequest,
+            RequestHandlingError::RejectedProposal
+            | RequestHandlingError::ProposalIdError { .. }
+            | RequestHandlingError::CompactEcashError(_)
+            | RequestHandlingError::CredentialError(_) => GatewayErrorCode::InvalidCredential,
+            RequestHandlingError::CredentialVerification(err) => match err {
+                nym_credential_verification::Error::OutOfBandwidth { .. } => {
+                    GatewayErrorCode::OutOfBandwidth
+                }
+                nym_credential_verification::Error::BandwidthCredentialAlreadySpent => {
+                    GatewayErrorCode::TicketReplay
+                }
+                nym_credential_verification::Error::NyxdError(_)
+                | nym_credential_verification::Error::StorageError(_)
+                | nym_credential_verification::Error::InvalidMultisigThreshold => {
+                    GatewayErrorCode::Internal
+                }
+                _ => GatewayErrorCode::InvalidCredential,
+            },
+        }
+    }
+
     fn into_error_message(self) -> Message {
         let server_response = match self {
             RequestHandlingError::CredentialVerification(
@@ -107,7 +145,7 @@ impl RequestHandlingError {
                     available,
                 },
             },
-            other => ServerResponse::new_error(other.to_string()),
+            other => ServerResponse::new_error(other.error_code(), other.to_string()),
         };
         server_response.into()
     }
@@ -138,12 +176,42 @@ pub(crate) struct AuthenticatedHandler<R, S, St> {
     inner: FreshHandler<R, S, St>,
     bandwidth_storage_manager: BandwidthStorageManager<St>,
     client: ClientDetails,
+    session_id: SessionId,
     mix_receiver: MixMessageReceiver,
     // Occasionally the handler is requested to ping the connected client for confirm that it's
     // active, such as when a duplicate connection is detected. This hashmap stores the oneshot
     // senders that are used to return the result of the ping to the handler requesting the ping.
     is_active_request_receiver: IsActiveRequestReceiver,
     is_active_ping_pending_reply: Option<(u64, IsActiveResultSender)>,
+
+    // set once the client has requested to be deregistered, at which point the connection
+    // has to be closed after the acknowledgement is sent back
+    deregistered: bool,
+
+    // the number of sphinx packets forwarded and rejected since the last protocol statistics exchange
+    forwarded_packets: u64,
+    rejected_packets: u64,
+
+    // set once the client has subscribed to the gateway notices
+    notices: Option<broadcast::Receiver<GatewayNotice>>,
+}
+
+async fn next_notice(
+    notices: &mut Option<broadcast::Receiver<GatewayNotice>>,
+) -> Option<GatewayNotice> {
+    let receiver = notices.as_mut()?;
+    let received = receiver.recv().await;
+    match received {
+        Ok(notice) => Some(notice),
+        Err(RecvError::Lagged(skipped)) => {
+            warn!("the client has missed {skipped} gateway notices");
+            None
+        }
+        Err(RecvError::Closed) => {
+            *notices = None;
+            None
+        }
+    }
 }
 
 // explicitly remove handle from the global store upon being dropped
@@ -151,7 +219,7 @@ impl<R, S, St> Drop for AuthenticatedHandler<R, S, St> {
     fn drop(&mut self) {
         self.inner
             .active_clients_store
-            .disconnect(self.client.address)
+            .disconnect(self.client.address, self.session_id)
     }
 }
 
@@ -169,10 +237,12 @@ where
     ///
     /// * `fresh`: fresh, unauthenticated, connection handler.
     /// * `client`: details (i.e. address and shared keys) of the registered client
+    /// * `session_id`: id of this session of the client within the active clients store.
     /// * `mix_receiver`: channel used for receiving messages from the mixnet destined for this client.
     pub(crate) async fn upgrade(
         fresh: FreshHandler<R, S, St>,
         client: ClientDetails,
+        session_id: SessionId,
         mix_receiver: MixMessageReceiver,
         is_active_request_receiver: IsActiveRequestReceiver,
     ) -> Result<Self, RequestHandlingError> {
@@ -199,9 +269,14 @@ where
             ),
             inner: fresh,
             client,
+            session_id,
             mix_receiver,
             is_active_request_receiver,
             is_active_ping_pending_reply: None,
+            deregistered: false,
+            forwarded_packets: 0,
+            rejected_packets: 0,
+            notices: None,
         })
     }
 
@@ -209,7 +284,7 @@ where
     fn disconnect(self) {
         self.inner
             .active_clients_store
-            .disconnect(self.client.address)
+            .disconnect(self.client.address, self.session_id)
     }
 
     /// Forwards the received mix packet from the client into the mix network.
@@ -218,6 +293,12 @@ where
     ///
     /// * `mix_packet`: packet received from the client that should get forwarded into the network.
     fn forward_packet(&self, mix_packet: MixPacket) {
+        #[cfg(feature = "pcap")]
+        {
+            nym_pcap::record_packet(nym_pcap::EventKind::Received, mix_packet.packet());
+            nym_pcap::record_packet(nym_pcap::EventKind::Forwarded, mix_packet.packet());
+        }
+
         if let Err(err) = self.inner.outbound_mix_sender.unbounded_send(mix_packet) {
             error!("We failed to forward requested mix packet - {err}. Presumably our mix forwarder has crashed. We cannot continue.");
             process::exit(1);
@@ -271,13 +352,26 @@ where
         &mut self,
         mix_packet: MixPacket,
     ) -> Result<ServerResponse, RequestHandlingError> {
-        let required_bandwidth = mix_packet.packet().len() as i64;
+        let packet_size = mix_packet.packet().len();
+        let required_bandwidth = packet_size as i64;
 
-        let remaining_bandwidth = self
+        let remaining_bandwidth = match self
             .bandwidth_storage_manager
             .try_use_bandwidth(required_bandwidth)
-            .await?;
+            .await
+        {
+            Ok(remaining_bandwidth) => remaining_bandwidth,
+            Err(err) => {
+                self.rejected_packets += 1;
+                return Err(err.into());
+            }
+        };
         self.forward_packet(mix_packet);
+        self.forwarded_packets += 1;
+        self.inner
+            .shared_state
+            .metrics
+            .packet_forwarded(packet_size);
 
         Ok(ServerResponse::Send {
             remaining_bandwidth,
@@ -314,12 +408,14 @@ where
     ) -> Result<ServerResponse, RequestHandlingError> {
         if !self.client.shared_keys.is_legacy() {
             return Ok(ServerResponse::new_error(
+                GatewayErrorCode::KeyUpgradeFailure,
                 "the connection is already using an aes256-gcm-siv key",
             ));
         }
         let legacy_key = self.client.shared_keys.unwrap_legacy();
         let Some(upgraded_key) = legacy_key.upgrade_verify(&hkdf_salt, &client_key_digest) else {
             return Ok(ServerResponse::new_error(
+                GatewayErrorCode::KeyUpgradeFailure,
                 "failed to derive matching aes256-gcm-siv key",
             ));
         };
@@ -336,6 +432,104 @@ where
         Ok(SensitiveServerResponse::KeyUpgradeAck {}.encrypt(&self.client.shared_keys)?)
     }
 
+    async fn handle_purge_stored_data(
+        &mut self,
+        deregister: bool,
+    ) -> Result<ServerResponse, RequestHandlingError> {
+        let removed_messages = self
+            .inner
+            .shared_state
+            .storage
+            .purge_client_data(self.client.address, self.client.id, deregister)
+            .await?;
+
+        if deregister {
+            self.deregistered = true;
+        }
+
+        info!(
+            "purged {removed_messages} stored messages of {} (deregistered: {deregister})",
+            self.client.address.as_base58_string()
+        );
+
+        Ok(SensitiveServerResponse::StoredDataPurged {
+            removed_messages,
+            deregistered: deregister,
+        }
+        .encrypt(&self.client.shared_keys)?)
+    }
+
+    async fn handle_get_stored_messages_count(
+        &mut self,
+    ) -> Result<ServerResponse, RequestHandlingError> {
+        let count = self
+            .inner
+            .shared_state
+            .storage
+            .count_stored_messages(self.client.address)
+            .await?;
+
+        Ok(SensitiveServerResponse::StoredMessagesCount { count }
+            .encrypt(&self.client.shared_keys)?)
+    }
+
+    fn handle_exchange_protocol_stats(
+        &mut self,
+        client_stats: ProtocolStats,
+    ) -> Result<ServerResponse, RequestHandlingError> {
+        let client_stats = client_stats.sanitized();
+        if !client_stats.is_empty() {
+            debug!(
+                observed_loss = ?client_stats.observed_loss,
+                queue_depth = ?client_stats.queue_depth,
+                "received protocol statistics of {}",
+                self.client.address.as_base58_string()
+            );
+        }
+
+        let total = self.forwarded_packets + self.rejected_packets;
+        let observed_loss = (total > 0).then(|| self.rejected_packets as f32 / total as f32);
+        self.forwarded_packets = 0;
+        self.rejected_packets = 0;
+
+        // unless explicitly enabled, we don't reveal anything about our own state
+        let stats = if self.inner.shared_state.share_protocol_stats {
+            ProtocolStats {
+                observed_loss,
+                // the forwarding queue doesn't expose its length
+                queue_depth: None,
+            }
+        } else {
+            ProtocolStats::default()
+        };
+
+        Ok(SensitiveServerResponse::ProtocolStats { stats }.encrypt(&self.client.shared_keys)?)
+    }
+
+    fn handle_subscribe_notices(&mut self) -> Result<ServerResponse, RequestHandlingError> {
+        let (active, receiver) = self.inner.shared_state.notices.subscribe();
+        self.notices = Some(receiver);
+
+        Ok(SensitiveServerResponse::NoticesSubscribed { active }
+            .encrypt(&self.client.shared_keys)?)
+    }
+
+    /// Sends the published notice to the subscribed client.
+    async fn push_notice(&mut self, notice: GatewayNotice) -> Result<(), WsError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let notice = SensitiveServerResponse::Notice { notice };
+        let response = match notice.encrypt(&self.client.shared_keys) {
+            Ok(response) => response,
+            Err(err) => {
+                error!("failed to encrypt the gateway notice: {err}");
+                return Ok(());
+            }
+        };
+        self.inner.send_websocket_message(response).await
+    }
+
     async fn handle_encrypted_text_request(
         &mut self,
         ciphertext: Vec<u8>,
@@ -350,6 +544,16 @@ where
                 hkdf_salt,
                 derived_key_digest,
             } => self.handle_key_upgrade(hkdf_salt, derived_key_digest).await,
+            ClientRequest::PurgeStoredData { deregister } => {
+                self.handle_purge_stored_data(deregister).await
+            }
+            ClientRequest::ExchangeProtocolStats { stats } => {
+                self.handle_exchange_protocol_stats(stats)
+            }
+            ClientRequest::SubscribeNotices {} => self.handle_subscribe_notices(),
+            ClientRequest::GetStoredMessagesCount {} => {
+                self.handle_get_stored_messages_count().await
+            }
             _ => Err(RequestHandlingError::UnknownEncryptedTextRequest),
         }
     }
@@ -584,6 +788,11 @@ where
                             break;
                         }
                     }
+
+                    if self.deregistered {
+                        debug!("the client has been deregistered. closing the connection");
+                        break;
+                    }
                 },
                 mix_messages = self.mix_receiver.next() => {
                     let mix_messages = match mix_messages {
@@ -598,6 +807,14 @@ where
                         break;
                     }
                 }
+                notice = next_notice(&mut self.notices), if self.notices.is_some() => {
+                    if let Some(notice) = notice {
+                        if let Err(err) = self.push_notice(notice).await {
+                            debug!("failed to send the gateway notice to the client - {err}, assuming the connection is dead");
+                            break;
+                        }
+                    }
+                }
             }
         }
 
@@ -1,10 +1,11 @@
 // Copyright 2021-2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
+use crate::config::{ClientSessionsDebug, DuplicateSessionPolicy};
 use crate::node::client_handling::websocket::common_state::CommonHandlerState;
 use crate::node::client_handling::websocket::connection_handler::INITIAL_MESSAGE_TIMEOUT;
 use crate::node::client_handling::{
-    active_clients::ActiveClientsStore,
+    active_clients::{ActiveClientsStore, ClientIncomingChannels},
     websocket::{
         connection_handler::{
             AuthenticatedHandler, ClientDetails, InitialAuthResult, SocketStream,
@@ -24,8 +25,10 @@ use nym_gateway_requests::authentication::encrypted_address::{
 };
 use nym_gateway_requests::{
     registration::handshake::{error::HandshakeError, gateway_handshake},
+    transport::FramedTransport,
     types::{ClientControlRequest, ServerResponse},
-    BinaryResponse, SharedGatewayKey, CURRENT_PROTOCOL_VERSION, INITIAL_PROTOCOL_VERSION,
+    BinaryResponse, CipherSuite, GatewayErrorCode, SharedGatewayKey, CURRENT_PROTOCOL_VERSION,
+    INITIAL_PROTOCOL_VERSION,
 };
 use nym_gateway_storage::{error::StorageError, Storage};
 use nym_mixnet_client::forwarder::MixForwardingSender;
@@ -38,6 +41,7 @@ use thiserror::Error;
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::time::timeout;
 use tokio_tungstenite::tungstenite::{protocol::Message, Error as WsError};
+use tokio_util::compat::TokioAsyncReadCompatExt;
 use tracing::*;
 
 #[derive(Debug, Error)]
@@ -67,6 +71,9 @@ pub(crate) enum InitialAuthenticationError {
     #[error("There is already an open connection to this client")]
     DuplicateConnection,
 
+    #[error("This client has already reached the maximum number of concurrent sessions ({limit})")]
+    TooManySessions { limit: usize },
+
     #[error("provided authentication IV is malformed: {0}")]
     MalformedIV(bs58::decode::Error),
 
@@ -79,6 +86,11 @@ pub(crate) enum InitialAuthenticationError {
     #[error("Attempted to negotiate connection with client using incompatible protocol version. Ours is {current} and the client reports {client:?}")]
     IncompatibleProtocol { client: Option<u8>, current: u8 },
 
+    #[error(
+        "the client is using the {client} cipher suite which is not supported by this gateway"
+    )]
+    UnsupportedCipherSuite { client: CipherSuite },
+
     #[error("failed to send authentication response: {source}")]
     ResponseSendFailure {
         #[source]
@@ -104,6 +116,96 @@ pub(crate) enum InitialAuthenticationError {
     EmptyClientDetails,
 }
 
+impl InitialAuthenticationError {
+    fn error_code(&self) -> GatewayErrorCode {
+        match self {
+            InitialAuthenticationError::StorageError(_)
+            | InitialAuthenticationError::MalformedStoredSharedKey { .. }
+            | InitialAuthenticationError::ConnectionError(_)
+            | InitialAuthenticationError::ResponseSendFailure { .. }
+            | InitialAuthenticationError::ClosedConnection
+            | InitialAuthenticationError::FailedToReadMessage { .. }
+            | InitialAuthenticationError::Timeout
+            | InitialAuthenticationError::EmptyClientDetails => GatewayErrorCode::Internal,
+            InitialAuthenticationError::HandshakeError(_) => {
+                GatewayErrorCode::AuthenticationFailure
+            }
+            InitialAuthenticationError::MalformedClientAddress(_)
+            | InitialAuthenticationError::MalformedEncryptedAddress(_)
+            | InitialAuthenticationError::MalformedIV(_) => GatewayErrorCode::MalformedRequest,
+            InitialAuthenticationError::DuplicateConnection
+            | InitialAuthenticationError::TooManySessions { .. } => {
+                GatewayErrorCode::DuplicateSession
+            }
+            InitialAuthenticationError::InvalidRequest
+            | InitialAuthenticationError::BinaryRequestWithoutAuthentication => {
+                GatewayErrorCode::UnexpectedRequest
+            }
+            InitialAuthenticationError::IncompatibleProtocol { .. }
+            | InitialAuthenticationError::UnsupportedCipherSuite { .. } => {
+                GatewayErrorCode::IncompatibleProtocol
+            }
+        }
+    }
+}
+
+/// Action to take when a client that already has active session(s) attempts to open another one.
+#[derive(Debug)]
+enum DuplicateSessionResolution {
+    /// Ask the existing session whether it's still active and only allow the new one if it's not.
+    CheckExisting(IsActiveRequestSender),
+
+    /// Terminate all the existing sessions in favour of the new one.
+    KickExisting,
+
+    /// Allow the new session alongside the existing ones.
+    Allow,
+
+    /// Reject the new session as the client has already reached the maximum number of sessions.
+    Reject { limit: usize },
+}
+
+fn resolve_duplicate_session(
+    sessions_cfg: ClientSessionsDebug,
+    existing_sessions: Vec<ClientIncomingChannels>,
+) -> DuplicateSessionResolution {
+    match sessions_cfg.duplicate_session_policy {
+        DuplicateSessionPolicy::RejectNew => {
+            // there can only be a single session if multiple are not allowed
+            match existing_sessions.into_iter().next() {
+                Some(existing) => {
+                    DuplicateSessionResolution::CheckExisting(existing.is_active_request_sender)
+                }
+                None => DuplicateSessionResolution::Allow,
+            }
+        }
+        DuplicateSessionPolicy::KickOld => DuplicateSessionResolution::KickExisting,
+        DuplicateSessionPolicy::FanOut => {
+            if existing_sessions.len() >= sessions_cfg.max_sessions_per_client {
+                return DuplicateSessionResolution::Reject {
+                    limit: sessions_cfg.max_sessions_per_client,
+                };
+            }
+            debug!(
+                "there are {} existing sessions. allowing another one",
+                existing_sessions.len()
+            );
+            DuplicateSessionResolution::Allow
+        }
+    }
+}
+
+/// Checks whether the request can be handled before the client has authenticated. Apart from
+/// the authentication itself, it's only the case for stateless requests, like `SupportedProtocol`.
+fn is_allowed_before_authentication(request: &ClientControlRequest) -> bool {
+    matches!(
+        request,
+        ClientControlRequest::Authenticate { .. }
+            | ClientControlRequest::RegisterHandshakeInitRequest { .. }
+            | ClientControlRequest::SupportedProtocol { .. }
+    )
+}
+
 pub(crate) struct FreshHandler<R, S, St> {
     rng: R,
     pub(crate) shared_state: CommonHandlerState<St>,
@@ -148,8 +250,24 @@ where
         }
     }
 
+    /// Makes the handler exchange the messages with the client using the [`FramedTransport`]
+    /// directly on top of the raw socket, rather than upgrading it to a WebSocket.
+    pub(crate) fn with_framed_transport(mut self) -> Self
+    where
+        S: AsyncRead + AsyncWrite,
+    {
+        self.socket_connection =
+            match std::mem::replace(&mut self.socket_connection, SocketStream::Invalid) {
+                SocketStream::RawTcp(conn) => {
+                    SocketStream::Framed(FramedTransport::new(conn.compat()))
+                }
+                other => other,
+            };
+        self
+    }
+
     /// Attempts to perform websocket handshake with the remote and upgrades the raw TCP socket
-    /// to the framed WebSocket.
+    /// to the framed WebSocket. It does nothing if the handler is using the [`FramedTransport`].
     pub(crate) async fn perform_websocket_handshake(&mut self) -> Result<(), WsError>
     where
         S: AsyncRead + AsyncWrite + Unpin,
@@ -176,12 +294,13 @@ where
     async fn perform_registration_handshake(
         &mut self,
         init_msg: Vec<u8>,
+        client_cipher_suite: CipherSuite,
     ) -> Result<SharedGatewayKey, HandshakeError>
     where
         S: AsyncRead + AsyncWrite + Unpin + Send,
         R: CryptoRng + RngCore + Send,
     {
-        debug_assert!(self.socket_connection.is_websocket());
+        debug_assert!(self.socket_connection.is_established());
         match &mut self.socket_connection {
             SocketStream::UpgradedWebSocket(ws_stream) => {
                 gateway_handshake(
@@ -189,6 +308,18 @@ where
                     ws_stream,
                     self.shared_state.local_identity.as_ref(),
                     init_msg,
+                    client_cipher_suite,
+                    self.shutdown.clone(),
+                )
+                .await
+            }
+            SocketStream::Framed(framed) => {
+                gateway_handshake(
+                    &mut self.rng,
+                    framed,
+                    self.shared_state.local_identity.as_ref(),
+                    init_msg,
+                    client_cipher_suite,
                     self.shutdown.clone(),
                 )
                 .await
@@ -204,6 +335,7 @@ where
     {
         match self.socket_connection {
             SocketStream::UpgradedWebSocket(ref mut ws_stream) => ws_stream.next().await,
+            SocketStream::Framed(ref mut framed) => framed.next().await,
             _ => panic!("impossible state - websocket handshake was somehow reverted"),
         }
     }
@@ -225,22 +357,23 @@ where
             // it got something to do with batching and flushing - it might be important if it
             // turns out somehow we've got a bottleneck here
             SocketStream::UpgradedWebSocket(ref mut ws_stream) => ws_stream.send(msg.into()).await,
+            SocketStream::Framed(ref mut framed) => framed.send(msg.into()).await,
             _ => panic!("impossible state - websocket handshake was somehow reverted"),
         }
     }
 
     pub(crate) async fn send_error_response(
         &mut self,
-        err: impl std::error::Error,
+        err: &InitialAuthenticationError,
     ) -> Result<(), WsError>
     where
         S: AsyncRead + AsyncWrite + Unpin + Send,
     {
-        self.send_websocket_message(ServerResponse::new_error(err.to_string()))
+        self.send_websocket_message(ServerResponse::new_error(err.error_code(), err.to_string()))
             .await
     }
 
-    pub(crate) async fn send_and_forget_error_response(&mut self, err: impl std::error::Error)
+    pub(crate) async fn send_and_forget_error_response(&mut self, err: &InitialAuthenticationError)
     where
         S: AsyncRead + AsyncWrite + Unpin + Send,
     {
@@ -281,6 +414,7 @@ where
             SocketStream::UpgradedWebSocket(ref mut ws_stream) => {
                 ws_stream.send_all(&mut send_stream).await
             }
+            SocketStream::Framed(ref mut framed) => framed.send_all(&mut send_stream).await,
             _ => panic!("impossible state - websocket handshake was somehow reverted"),
         }
     }
@@ -370,6 +504,7 @@ where
         client_address: DestinationAddressBytes,
         encrypted_address: EncryptedAddressBytes,
         nonce: &[u8],
+        client_cipher_suite: CipherSuite,
     ) -> Result<Option<SharedGatewayKey>, InitialAuthenticationError> {
         let shared_keys = self
             .shared_state
@@ -381,12 +516,15 @@ where
             return Ok(None);
         };
 
-        let keys = SharedGatewayKey::try_from(stored_shared_keys).map_err(|source| {
+        let mut keys = SharedGatewayKey::try_from(stored_shared_keys).map_err(|source| {
             InitialAuthenticationError::MalformedStoredSharedKey {
                 client_id: client_address.as_base58_string(),
                 source,
             }
         })?;
+        // the suite is not persisted alongside the key. if the client lied about it,
+        // it simply won't be able to prove the knowledge of the key
+        keys.set_cipher_suite(client_cipher_suite);
 
         // LEGACY ISSUE: we're not verifying HMAC key
         if encrypted_address.verify(&client_address, &keys, nonce) {
@@ -432,6 +570,20 @@ where
         }
     }
 
+    fn check_client_cipher_suite(
+        &self,
+        client_suite: CipherSuite,
+    ) -> Result<(), InitialAuthenticationError> {
+        if client_suite.is_supported() {
+            return Ok(());
+        }
+        let err = InitialAuthenticationError::UnsupportedCipherSuite {
+            client: client_suite,
+        };
+        error!("{err}");
+        Err(err)
+    }
+
     /// Using the received challenge data, i.e. client's address as well the ciphertext of it plus
     /// a fresh IV, attempts to authenticate the client by checking whether the ciphertext matches
     /// the expected value if encrypted with the shared key.
@@ -448,6 +600,7 @@ where
         client_address: DestinationAddressBytes,
         encrypted_address: EncryptedAddressBytes,
         nonce: &[u8],
+        client_cipher_suite: CipherSuite,
     ) -> Result<Option<SharedGatewayKey>, InitialAuthenticationError>
     where
         S: AsyncRead + AsyncWrite + Unpin,
@@ -458,7 +611,12 @@ where
         );
 
         let shared_keys = self
-            .verify_stored_shared_key(client_address, encrypted_address, nonce)
+            .verify_stored_shared_key(
+                client_address,
+                encrypted_address,
+                nonce,
+                client_cipher_suite,
+            )
             .await?;
 
         if let Some(shared_keys) = shared_keys {
@@ -471,6 +629,28 @@ where
     }
 
     async fn handle_duplicate_client(
+        &mut self,
+        address: DestinationAddressBytes,
+        existing_sessions: Vec<ClientIncomingChannels>,
+    ) -> Result<(), InitialAuthenticationError> {
+        match resolve_duplicate_session(self.shared_state.client_sessions, existing_sessions) {
+            DuplicateSessionResolution::CheckExisting(is_active_request_sender) => {
+                self.check_existing_session(address, is_active_request_sender)
+                    .await
+            }
+            DuplicateSessionResolution::KickExisting => {
+                info!("disconnecting the existing session(s) in favour of this new connection");
+                self.active_clients_store.kick(address);
+                Ok(())
+            }
+            DuplicateSessionResolution::Allow => Ok(()),
+            DuplicateSessionResolution::Reject { limit } => {
+                Err(InitialAuthenticationError::TooManySessions { limit })
+            }
+        }
+    }
+
+    async fn check_existing_session(
         &mut self,
         address: DestinationAddressBytes,
         mut is_active_request_tx: IsActiveRequestSender,
@@ -491,7 +671,7 @@ where
                         // The other handler reported that the client is not active, so we can
                         // disconnect the other client and continue with this connection.
                         debug!("Other handler reports it is not active");
-                        self.active_clients_store.disconnect(address);
+                        self.active_clients_store.kick(address);
                     }
                     IsActive::Active => {
                         // The other handled reported a positive reply, so we have to assume it's
@@ -510,14 +690,14 @@ where
             Ok(Err(_)) => {
                 // Other channel failed to reply (the channel sender probably dropped)
                 info!("Other connection failed to reply, disconnecting it in favour of this new connection");
-                self.active_clients_store.disconnect(address);
+                self.active_clients_store.kick(address);
             }
             Err(_) => {
                 // Timeout waiting for reply
                 warn!(
                     "Other connection timed out, disconnecting it in favour of this new connection"
                 );
-                self.active_clients_store.disconnect(address);
+                self.active_clients_store.kick(address);
             }
         }
         Ok(())
@@ -538,6 +718,7 @@ where
     async fn handle_authenticate(
         &mut self,
         client_protocol_version: Option<u8>,
+        client_cipher_suite: CipherSuite,
         address: String,
         enc_address: String,
         raw_nonce: String,
@@ -548,6 +729,7 @@ where
         debug!("handling client registration");
 
         let negotiated_protocol = self.negotiate_client_protocol(client_protocol_version)?;
+        self.check_client_cipher_suite(client_cipher_suite)?;
         // populate the negotiated protocol for future uses
         self.negotiated_protocol = Some(negotiated_protocol);
 
@@ -559,18 +741,22 @@ where
             .map_err(InitialAuthenticationError::MalformedIV)?;
 
         // Check for duplicate clients
-        if let Some(client_tx) = self.active_clients_store.get_remote_client(address) {
+        let existing_sessions = self.active_clients_store.get_remote_sessions(address);
+        if !existing_sessions.is_empty() {
             warn!("Detected duplicate connection for client: {address}");
-            self.handle_duplicate_client(address, client_tx.is_active_request_sender)
+            self.handle_duplicate_client(address, existing_sessions)
                 .await?;
         }
 
         let Some(shared_keys) = self
-            .authenticate_client(address, encrypted_address, &nonce)
+            .authenticate_client(address, encrypted_address, &nonce, client_cipher_suite)
             .await?
         else {
             // it feels weird to be returning an 'Ok' here, but I didn't want to change the existing behaviour
-            return Ok(InitialAuthResult::new_failed(Some(negotiated_protocol)));
+            return Ok(InitialAuthResult::new_failed(
+                Some(negotiated_protocol),
+                client_cipher_suite,
+            ));
         };
 
         let client_id = self
@@ -593,11 +779,13 @@ where
         } else {
             available_bandwidth.bytes
         };
+        self.shared_state.metrics.client_authenticated();
 
         Ok(InitialAuthResult::new(
             Some(ClientDetails::new(client_id, address, shared_keys)),
             ServerResponse::Authenticate {
                 protocol_version: Some(negotiated_protocol),
+                cipher_suite: client_cipher_suite,
                 status: true,
                 bandwidth_remaining,
             },
@@ -660,6 +848,7 @@ where
     async fn handle_register(
         &mut self,
         client_protocol_version: Option<u8>,
+        client_cipher_suite: CipherSuite,
         init_data: Vec<u8>,
     ) -> Result<InitialAuthResult, InitialAuthenticationError>
     where
@@ -669,6 +858,8 @@ where
         let negotiated_protocol = self.negotiate_client_protocol(client_protocol_version)?;
         // populate the negotiated protocol for future uses
         self.negotiated_protocol = Some(negotiated_protocol);
+        // reject the client early, otherwise we'd have derived different keys
+        self.check_client_cipher_suite(client_cipher_suite)?;
 
         let remote_identity = Self::extract_remote_identity_from_register_init(&init_data)?;
         let remote_address = remote_identity.derive_destination_address();
@@ -679,8 +870,14 @@ where
             return Err(InitialAuthenticationError::DuplicateConnection);
         }
 
-        let shared_keys = self.perform_registration_handshake(init_data).await?;
+        let shared_keys = self
+            .perform_registration_handshake(init_data, client_cipher_suite)
+            .await?;
         let client_id = self.register_client(remote_address, &shared_keys).await?;
+        self.shared_state.metrics.client_registered();
+        self.shared_state
+            .tenant_router
+            .client_registered(remote_address, self.shared_state.tenant);
 
         debug!(client_id = %client_id, "managed to finalize client registration");
 
@@ -690,6 +887,7 @@ where
             Some(client_details),
             ServerResponse::Register {
                 protocol_version: Some(negotiated_protocol),
+                cipher_suite: client_cipher_suite,
                 status: true,
             },
         ))
@@ -699,6 +897,7 @@ where
         debug!("returning gateway protocol version");
         ServerResponse::SupportedProtocol {
             version: CURRENT_PROTOCOL_VERSION,
+            cipher_suite: CipherSuite::local(),
         }
     }
 
@@ -722,29 +921,35 @@ where
         S: AsyncRead + AsyncWrite + Unpin + Send,
         R: CryptoRng + RngCore + Send,
     {
-        // we can handle stateless client requests without prior authentication, like `ClientControlRequest::SupportedProtocol`
+        if !is_allowed_before_authentication(&request) {
+            debug!("received an invalid client request");
+            return Err(InitialAuthenticationError::InvalidRequest);
+        }
+
         let auth_result = match request {
             ClientControlRequest::Authenticate {
                 protocol_version,
+                cipher_suite,
                 address,
                 enc_address,
                 iv,
             } => {
-                self.handle_authenticate(protocol_version, address, enc_address, iv)
+                self.handle_authenticate(protocol_version, cipher_suite, address, enc_address, iv)
                     .await
             }
             ClientControlRequest::RegisterHandshakeInitRequest {
                 protocol_version,
+                cipher_suite,
                 data,
-            } => self.handle_register(protocol_version, data).await,
+            } => {
+                self.handle_register(protocol_version, cipher_suite, data)
+                    .await
+            }
             ClientControlRequest::SupportedProtocol { .. } => {
                 self.handle_reply_supported_protocol_request().await;
                 return Ok(None);
             }
-            _ => {
-                debug!("received an invalid client request");
-                return Err(InitialAuthenticationError::InvalidRequest);
-            }
+            _ => unreachable!("requests requiring authentication have already been rejected"),
         };
 
         let auth_result = match auth_result {
@@ -754,6 +959,10 @@ where
                     InitialAuthenticationError::StorageError(inner_storage) => {
                         debug!("authentication failure due to storage issue: {inner_storage}")
                     }
+                    InitialAuthenticationError::HandshakeError(handshake_err) => debug!(
+                        reason = %handshake_err.failure_reason(),
+                        "registration handshake failure: {handshake_err}"
+                    ),
                     other => debug!("authentication failure: {other}"),
                 }
 
@@ -800,7 +1009,7 @@ where
             let initial_request = match req {
                 Ok(req) => req,
                 Err(err) => {
-                    self.send_and_forget_error_response(err).await;
+                    self.send_and_forget_error_response(&err).await;
                     return None;
                 }
             };
@@ -810,7 +1019,7 @@ where
                 Ok(maybe_auth_res) => maybe_auth_res,
                 Err(err) => {
                     debug!("initial client request handling error: {err}");
-                    self.send_and_forget_error_response(err).await;
+                    self.send_and_forget_error_response(&err).await;
                     return None;
                 }
             };
@@ -819,15 +1028,20 @@ where
                 let (mix_sender, mix_receiver) = mpsc::unbounded();
                 // Channel for handlers to ask other handlers if they are still active.
                 let (is_active_request_sender, is_active_request_receiver) = mpsc::unbounded();
-                self.active_clients_store.insert_remote(
+                let allow_multiple_sessions =
+                    self.shared_state.client_sessions.duplicate_session_policy
+                        == DuplicateSessionPolicy::FanOut;
+                let session_id = self.active_clients_store.insert_remote(
                     registration_details.address,
                     mix_sender,
                     is_active_request_sender,
+                    allow_multiple_sessions,
                 );
 
                 return AuthenticatedHandler::upgrade(
                     self,
                     registration_details,
+                    session_id,
                     mix_receiver,
                     is_active_request_receiver,
                 )
@@ -878,3 +1092,84 @@ where
         super::handle_connection(self).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_gateway_requests::shared_key::SharedSymmetricKey;
+    use nym_gateway_requests::ClientRequest;
+
+    fn sessions_cfg(policy: DuplicateSessionPolicy) -> ClientSessionsDebug {
+        ClientSessionsDebug {
+            duplicate_session_policy: policy,
+            max_sessions_per_client: 2,
+        }
+    }
+
+    fn existing_sessions(count: usize) -> Vec<ClientIncomingChannels> {
+        (0..count)
+            .map(|_| ClientIncomingChannels {
+                mix_message_sender: mpsc::unbounded().0,
+                is_active_request_sender: mpsc::unbounded().0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reject_new_checks_the_existing_session() {
+        let cfg = sessions_cfg(DuplicateSessionPolicy::RejectNew);
+        assert!(matches!(
+            resolve_duplicate_session(cfg, existing_sessions(1)),
+            DuplicateSessionResolution::CheckExisting(_)
+        ));
+        assert!(matches!(
+            resolve_duplicate_session(cfg, existing_sessions(0)),
+            DuplicateSessionResolution::Allow
+        ));
+    }
+
+    #[test]
+    fn kick_old_replaces_the_existing_sessions() {
+        let cfg = sessions_cfg(DuplicateSessionPolicy::KickOld);
+        assert!(matches!(
+            resolve_duplicate_session(cfg, existing_sessions(1)),
+            DuplicateSessionResolution::KickExisting
+        ));
+    }
+
+    #[test]
+    fn fan_out_allows_sessions_up_to_the_limit() {
+        let cfg = sessions_cfg(DuplicateSessionPolicy::FanOut);
+        assert!(matches!(
+            resolve_duplicate_session(cfg, existing_sessions(1)),
+            DuplicateSessionResolution::Allow
+        ));
+        assert!(matches!(
+            resolve_duplicate_session(cfg, existing_sessions(2)),
+            DuplicateSessionResolution::Reject { limit: 2 }
+        ));
+    }
+
+    #[test]
+    fn stored_data_cannot_be_purged_before_authentication() {
+        let key: SharedGatewayKey = SharedSymmetricKey::try_from_bytes(&[1; 32]).unwrap().into();
+        let purge = ClientRequest::PurgeStoredData { deregister: true }
+            .encrypt(&key)
+            .unwrap();
+        assert!(!is_allowed_before_authentication(&purge));
+        assert!(!is_allowed_before_authentication(
+            &ClientControlRequest::ClaimFreeTestnetBandwidth
+        ));
+
+        assert!(is_allowed_before_authentication(
+            &ClientControlRequest::SupportedProtocol {}
+        ));
+        assert!(is_allowed_before_authentication(
+            &ClientControlRequest::RegisterHandshakeInitRequest {
+                protocol_version: None,
+                cipher_suite: Default::default(),
+                data: vec![],
+            }
+        ));
+    }
+}
@@ -4,13 +4,15 @@
 use crate::config::Config;
 use nym_credential_verification::BandwidthFlushingBehaviourConfig;
 use nym_gateway_requests::shared_key::SharedGatewayKey;
-use nym_gateway_requests::ServerResponse;
+use nym_gateway_requests::transport::FramedTransport;
+use nym_gateway_requests::{CipherSuite, ServerResponse};
 use nym_gateway_storage::Storage;
 use nym_sphinx::DestinationAddressBytes;
 use rand::{CryptoRng, Rng};
 use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_tungstenite::WebSocketStream;
+use tokio_util::compat::Compat;
 use tracing::{debug, instrument, trace, warn};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
@@ -32,12 +34,16 @@ const INITIAL_MESSAGE_TIMEOUT: Duration = Duration::from_millis(10_000);
 pub(crate) enum SocketStream<S> {
     RawTcp(S),
     UpgradedWebSocket(WebSocketStream<S>),
+    Framed(FramedTransport<Compat<S>>),
     Invalid,
 }
 
 impl<S> SocketStream<S> {
-    fn is_websocket(&self) -> bool {
-        matches!(self, SocketStream::UpgradedWebSocket(_))
+    fn is_established(&self) -> bool {
+        matches!(
+            self,
+            SocketStream::UpgradedWebSocket(_) | SocketStream::Framed(_)
+        )
     }
 }
 
@@ -76,11 +82,12 @@ impl InitialAuthResult {
         }
     }
 
-    fn new_failed(protocol_version: Option<u8>) -> Self {
+    fn new_failed(protocol_version: Option<u8>, cipher_suite: CipherSuite) -> Self {
         InitialAuthResult {
             client_details: None,
             server_response: ServerResponse::Authenticate {
                 protocol_version,
+                cipher_suite,
                 status: false,
                 bandwidth_remaining: 0,
             },
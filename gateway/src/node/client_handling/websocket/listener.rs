@@ -15,6 +15,10 @@ use tracing::*;
 pub(crate) struct Listener<S> {
     address: SocketAddr,
     shared_state: CommonHandlerState<S>,
+
+    // indicates whether the clients are going to use the raw framed TCP transport
+    // rather than the WebSocket
+    framed: bool,
 }
 
 impl<S> Listener<S>
@@ -25,6 +29,23 @@ where
         Listener {
             address,
             shared_state,
+            framed: false,
+        }
+    }
+
+    /// Makes the listener accept the clients using the raw framed TCP transport
+    /// rather than the WebSocket.
+    #[must_use]
+    pub(crate) fn framed(mut self) -> Self {
+        self.framed = true;
+        self
+    }
+
+    fn kind(&self) -> &'static str {
+        if self.framed {
+            "framed client"
+        } else {
+            "websocket"
         }
     }
 
@@ -36,11 +57,11 @@ where
         active_clients_store: ActiveClientsStore,
         mut shutdown: nym_task::TaskClient,
     ) {
-        info!("Starting websocket listener at {}", self.address);
+        info!("Starting {} listener at {}", self.kind(), self.address);
         let tcp_listener = match tokio::net::TcpListener::bind(self.address).await {
             Ok(listener) => listener,
             Err(err) => {
-                error!("Failed to bind the websocket to {} - {err}. Are you sure nothing else is running on the specified port and your user has sufficient permission to bind to the requested address?", self.address);
+                error!("Failed to bind the {} listener to {} - {err}. Are you sure nothing else is running on the specified port and your user has sufficient permission to bind to the requested address?", self.kind(), self.address);
                 process::exit(1);
             }
         };
@@ -67,7 +88,11 @@ where
                                 remote_addr,
                                 shutdown,
                             );
-                            tokio::spawn(handle.start_handling());
+                            if self.framed {
+                                tokio::spawn(handle.with_framed_transport().start_handling());
+                            } else {
+                                tokio::spawn(handle.start_handling());
+                            }
                         }
                         Err(err) => warn!("failed to get client: {err}"),
                     }
@@ -0,0 +1,262 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Periodic self-check of the gateway's own entry in the network directory.
+//! It compares what the nym-api is announcing about this gateway against its local configuration,
+//! so that operators could catch a misconfiguration (say, a stale host after changing servers)
+//! before it translates into failed network monitor measurements and a tanking score.
+
+use crate::config::{Config, DirectoryMonitorDebug};
+use crate::error::GatewayError;
+use nym_api_requests::models::GatewayBondAnnotated;
+use nym_node_http_api::api::api_requests::v1::gateway::models::DirectoryDrift;
+use nym_node_http_api::state::gateway::SharedDirectoryStatus;
+use nym_task::TaskClient;
+use nym_validator_client::NymApiClient;
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use tracing::*;
+use url::Url;
+
+/// Values the gateway expects to see announced in the directory.
+#[derive(Debug, Clone)]
+struct ExpectedEntry {
+    identity: String,
+    hosts: Vec<String>,
+    mix_port: u16,
+    clients_port: u16,
+    version: String,
+}
+
+impl ExpectedEntry {
+    fn new(config: &Config, identity: String) -> Self {
+        let mut hosts: Vec<_> = config
+            .host
+            .public_ips
+            .iter()
+            .map(|ip| ip.to_string())
+            .collect();
+        hosts.extend(config.host.hostname.clone());
+
+        ExpectedEntry {
+            identity,
+            hosts,
+            mix_port: config.gateway.mix_port,
+            clients_port: config.gateway.clients_port,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// Subset of the directory entry relevant for detecting the drifts.
+#[derive(Debug, Clone)]
+struct AnnouncedEntry {
+    host: String,
+    mix_port: u16,
+    clients_port: u16,
+    version: String,
+    performance: u8,
+    blacklisted: bool,
+}
+
+impl From<&GatewayBondAnnotated> for AnnouncedEntry {
+    fn from(entry: &GatewayBondAnnotated) -> Self {
+        let gateway = &entry.gateway_bond.gateway;
+        AnnouncedEntry {
+            host: gateway.host.clone(),
+            mix_port: gateway.mix_port,
+            clients_port: gateway.clients_port,
+            version: gateway.version.clone(),
+            performance: entry.node_performance.most_recent.round_to_integer(),
+            blacklisted: entry.blacklisted,
+        }
+    }
+}
+
+fn detect_drifts(
+    expected: &ExpectedEntry,
+    announced: &AnnouncedEntry,
+    previous_performance: Option<u8>,
+    config: &DirectoryMonitorDebug,
+) -> Vec<DirectoryDrift> {
+    let mut drifts = Vec::new();
+
+    if !expected.hosts.contains(&announced.host) {
+        drifts.push(DirectoryDrift::HostMismatch {
+            announced: announced.host.clone(),
+            configured: expected.hosts.clone(),
+        })
+    }
+    if announced.mix_port != expected.mix_port {
+        drifts.push(DirectoryDrift::MixPortMismatch {
+            announced: announced.mix_port,
+            configured: expected.mix_port,
+        })
+    }
+    if announced.clients_port != expected.clients_port {
+        drifts.push(DirectoryDrift::ClientsPortMismatch {
+            announced: announced.clients_port,
+            configured: expected.clients_port,
+        })
+    }
+    if announced.version != expected.version {
+        drifts.push(DirectoryDrift::VersionMismatch {
+            announced: announced.version.clone(),
+            running: expected.version.clone(),
+        })
+    }
+    if announced.blacklisted {
+        drifts.push(DirectoryDrift::Blacklisted)
+    }
+    if announced.performance < config.minimum_performance {
+        drifts.push(DirectoryDrift::LowPerformance {
+            performance: announced.performance,
+        })
+    }
+    if let Some(previous) = previous_performance {
+        if previous.saturating_sub(announced.performance) >= config.performance_drop_threshold {
+            drifts.push(DirectoryDrift::PerformanceDrop {
+                previous,
+                current: announced.performance,
+            })
+        }
+    }
+
+    drifts
+}
+
+pub(crate) struct DirectoryMonitor {
+    config: DirectoryMonitorDebug,
+    expected: ExpectedEntry,
+    nym_api_urls: Vec<Url>,
+    status: SharedDirectoryStatus,
+    previous_performance: Option<u8>,
+}
+
+impl DirectoryMonitor {
+    pub(crate) fn new(config: &Config, identity: String, status: SharedDirectoryStatus) -> Self {
+        DirectoryMonitor {
+            config: config.debug.directory_monitor,
+            expected: ExpectedEntry::new(config, identity),
+            nym_api_urls: config.get_nym_api_endpoints(),
+            status,
+            previous_performance: None,
+        }
+    }
+
+    fn random_api_client(&self) -> Result<NymApiClient, GatewayError> {
+        let nym_api = self
+            .nym_api_urls
+            .choose(&mut thread_rng())
+            .ok_or(GatewayError::NoNymApisAvailable)?;
+        Ok(NymApiClient::new(nym_api.clone()))
+    }
+
+    async fn check(&mut self) -> Result<Vec<DirectoryDrift>, GatewayError> {
+        let gateways = self
+            .random_api_client()?
+            .get_cached_gateways_detailed()
+            .await
+            .map_err(|source| GatewayError::NetworkGatewaysQueryFailure { source })?;
+
+        let Some(entry) = gateways
+            .iter()
+            .find(|gateway| gateway.identity() == &self.expected.identity)
+        else {
+            return Ok(vec![DirectoryDrift::NotBonded]);
+        };
+
+        let announced = AnnouncedEntry::from(entry);
+        let drifts = detect_drifts(
+            &self.expected,
+            &announced,
+            self.previous_performance,
+            &self.config,
+        );
+        self.previous_performance = Some(announced.performance);
+        Ok(drifts)
+    }
+
+    async fn check_and_report(&mut self) {
+        match self.check().await {
+            Ok(drifts) => {
+                if drifts.is_empty() {
+                    debug!("the directory entry of this gateway matches its configuration");
+                }
+                for drift in &drifts {
+                    warn!("directory entry drift: {drift}")
+                }
+                self.status.record_check(drifts).await
+            }
+            Err(err) => {
+                warn!("failed to check the directory entry of this gateway: {err}");
+                self.status.record_failure(err.to_string()).await
+            }
+        }
+    }
+
+    pub(crate) async fn run(mut self, mut shutdown: TaskClient) {
+        let mut interval = tokio::time::interval(self.config.check_interval);
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = shutdown.recv() => {
+                    trace!("DirectoryMonitor: received shutdown");
+                }
+                _ = interval.tick() => self.check_and_report().await,
+            }
+        }
+        debug!("DirectoryMonitor: exiting");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected() -> ExpectedEntry {
+        ExpectedEntry {
+            identity: "foo".to_string(),
+            hosts: vec!["1.2.3.4".to_string(), "gateway.example.com".to_string()],
+            mix_port: 1789,
+            clients_port: 9000,
+            version: "1.1.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn drifts_are_detected() {
+        let config = DirectoryMonitorDebug::default();
+        let mut announced = AnnouncedEntry {
+            host: "gateway.example.com".to_string(),
+            mix_port: 1789,
+            clients_port: 9000,
+            version: "1.1.0".to_string(),
+            performance: 95,
+            blacklisted: false,
+        };
+        assert!(detect_drifts(&expected(), &announced, Some(100), &config).is_empty());
+
+        announced.host = "5.6.7.8".to_string();
+        announced.clients_port = 9001;
+        announced.performance = 40;
+        assert_eq!(
+            detect_drifts(&expected(), &announced, Some(95), &config),
+            vec![
+                DirectoryDrift::HostMismatch {
+                    announced: "5.6.7.8".to_string(),
+                    configured: expected().hosts,
+                },
+                DirectoryDrift::ClientsPortMismatch {
+                    announced: 9001,
+                    configured: 9000,
+                },
+                DirectoryDrift::LowPerformance { performance: 40 },
+                DirectoryDrift::PerformanceDrop {
+                    previous: 95,
+                    current: 40,
+                },
+            ]
+        );
+    }
+}
@@ -1,16 +1,23 @@
 // Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
-use crate::config::Config;
+use crate::config::{Config, MessageStoreBackend, Tenant};
 use crate::error::GatewayError;
+use crate::helpers::load_identity_keys;
+use crate::node::tenants::GatewayTenant;
 
-use nym_crypto::asymmetric::encryption;
+use nym_crypto::asymmetric::{encryption, identity};
 use nym_gateway_storage::PersistentStorage;
 use nym_pemstore::traits::PemStorableKeyPair;
 use nym_pemstore::KeyPairPath;
 
+use std::num::NonZeroUsize;
 use std::path::Path;
 
+// name of the RocksDB message store directory, placed next to the clients database
+#[cfg(feature = "rocksdb")]
+const ROCKSDB_MESSAGE_STORE_DIR: &str = "messages.rocksdb";
+
 pub async fn load_network_requester_config<P: AsRef<Path>>(
     id: &str,
     path: P,
@@ -70,8 +77,127 @@ pub(crate) async fn initialise_main_storage(
 ) -> Result<PersistentStorage, GatewayError> {
     let path = &config.storage_paths.clients_storage;
     let retrieval_limit = config.debug.message_retrieval_limit;
+    let identity_keys = load_identity_keys(config)?;
+
+    let storage = PersistentStorage::init(path, retrieval_limit)
+        .await?
+        .with_inbox_encryption(!config.debug.disable_stored_messages_encryption);
+    with_configured_message_store(config, storage, path, &identity_keys)
+}
+
+pub(crate) async fn initialise_tenant_storage(
+    config: &Config,
+    tenant: &Tenant,
+    identity_keys: &identity::KeyPair,
+) -> Result<PersistentStorage, GatewayError> {
+    let path = &tenant.storage_paths.clients_storage;
+    let retrieval_limit = config.debug.message_retrieval_limit;
 
-    Ok(PersistentStorage::init(path, retrieval_limit).await?)
+    let storage = PersistentStorage::init(path, retrieval_limit)
+        .await?
+        .with_inbox_encryption(!config.debug.disable_stored_messages_encryption);
+    with_configured_message_store(config, storage, path, identity_keys)
+}
+
+/// Applies the message store settings from the config, i.e. the padding, the backend and the offloading.
+pub fn with_configured_message_store(
+    config: &Config,
+    storage: PersistentStorage,
+    clients_storage: &Path,
+    identity_keys: &identity::KeyPair,
+) -> Result<PersistentStorage, GatewayError> {
+    let storage = match NonZeroUsize::new(config.debug.message_store.padding_bucket_size) {
+        Some(bucket_size) => storage.with_message_padding(bucket_size),
+        None => storage,
+    };
+
+    let storage = match config.debug.message_store.backend {
+        MessageStoreBackend::Sqlite => storage,
+        MessageStoreBackend::Rocksdb => {
+            with_rocksdb_message_store(config, storage, clients_storage)?
+        }
+    };
+
+    match &config.debug.message_store.offload_url {
+        Some(url) => with_cold_message_offload(config, storage, url, identity_keys),
+        None => Ok(storage),
+    }
+}
+
+#[cfg(feature = "rocksdb")]
+fn with_rocksdb_message_store(
+    config: &Config,
+    storage: PersistentStorage,
+    clients_storage: &Path,
+) -> Result<PersistentStorage, GatewayError> {
+    use nym_gateway_storage::message_store::RocksDbMessageStore;
+
+    let path = clients_storage.with_file_name(ROCKSDB_MESSAGE_STORE_DIR);
+    let message_store = RocksDbMessageStore::open(path, config.debug.message_retrieval_limit)?;
+    Ok(storage.with_message_store(message_store))
+}
+
+#[cfg(not(feature = "rocksdb"))]
+fn with_rocksdb_message_store(
+    _: &Config,
+    _: PersistentStorage,
+    _: &Path,
+) -> Result<PersistentStorage, GatewayError> {
+    Err(GatewayError::MessageStoreFeatureDisabled { feature: "rocksdb" })
+}
+
+#[cfg(feature = "object-store-offload")]
+fn with_cold_message_offload(
+    config: &Config,
+    storage: PersistentStorage,
+    url: &url::Url,
+    identity_keys: &identity::KeyPair,
+) -> Result<PersistentStorage, GatewayError> {
+    use nym_gateway_storage::message_store::OffloadingMessageStore;
+    use zeroize::Zeroizing;
+
+    // namespace the messages by the identity so that multiple gateways (and tenants) could share a bucket,
+    // whilst the locations of the messages of particular clients are only known to the owner of the private key
+    let location_secret = Zeroizing::new(identity_keys.private_key().to_bytes());
+    let message_store = OffloadingMessageStore::new(
+        storage.message_store(),
+        url,
+        &identity_keys.public_key().to_base58_string(),
+        location_secret.as_slice(),
+        config.debug.message_store.offload_after,
+    )?;
+    Ok(storage.with_message_store(message_store))
+}
+
+#[cfg(not(feature = "object-store-offload"))]
+fn with_cold_message_offload(
+    _: &Config,
+    _: PersistentStorage,
+    _: &url::Url,
+    _: &identity::KeyPair,
+) -> Result<PersistentStorage, GatewayError> {
+    Err(GatewayError::MessageStoreFeatureDisabled {
+        feature: "object-store-offload",
+    })
+}
+
+/// Loads identity keys and initialises isolated client storage of every configured tenant.
+pub(crate) async fn load_tenants(
+    config: &Config,
+) -> Result<Vec<GatewayTenant<PersistentStorage>>, GatewayError> {
+    let mut tenants = Vec::with_capacity(config.tenants.len());
+    for tenant in &config.tenants {
+        let identity_paths = KeyPairPath::new(
+            tenant.storage_paths.private_identity_key(),
+            tenant.storage_paths.public_identity_key(),
+        );
+        let identity_keys: identity::KeyPair =
+            load_keypair(identity_paths, format!("tenant '{}' identity", tenant.id))?;
+        let storage = initialise_tenant_storage(config, tenant, &identity_keys).await?;
+
+        tenants.push(GatewayTenant::new(tenant.clone(), identity_keys, storage))
+    }
+    Ok(tenants)
 }
 
 pub fn load_keypair<T: PemStorableKeyPair>(
@@ -0,0 +1,50 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use nym_gateway_storage::Storage;
+use nym_task::TaskClient;
+use std::time::Duration;
+use tracing::*;
+
+/// Periodically moves the messages that haven't been retrieved by offline clients for a while
+/// out of the message stores of the gateway and all of its tenants.
+pub(crate) struct MessageOffloader<St> {
+    check_interval: Duration,
+    storages: Vec<St>,
+}
+
+impl<St> MessageOffloader<St>
+where
+    St: Storage,
+{
+    pub(crate) fn new(check_interval: Duration, storages: Vec<St>) -> Self {
+        MessageOffloader {
+            check_interval,
+            storages,
+        }
+    }
+
+    async fn offload(&self) {
+        for storage in &self.storages {
+            match storage.offload_cold_messages().await {
+                Ok(0) => trace!("there were no cold messages to offload"),
+                Ok(offloaded) => info!("offloaded {offloaded} cold messages"),
+                Err(err) => warn!("failed to offload cold messages: {err}"),
+            }
+        }
+    }
+
+    pub(crate) async fn run(self, mut shutdown: TaskClient) {
+        let mut interval = tokio::time::interval(self.check_interval);
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = shutdown.recv() => {
+                    trace!("MessageOffloader: received shutdown");
+                }
+                _ = interval.tick() => self.offload().await,
+            }
+        }
+        debug!("MessageOffloader: exiting");
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use nym_gateway_storage::message_store::RetentionPolicy;
+use nym_gateway_storage::Storage;
+use nym_task::TaskClient;
+use std::time::Duration;
+use tracing::*;
+
+/// Periodically removes the oldest messages stored for offline clients that exceed any of the configured
+/// retention limits from the message stores of the gateway and all of its tenants.
+pub(crate) struct MessageRetention<St> {
+    check_interval: Duration,
+    policy: RetentionPolicy,
+    storages: Vec<St>,
+}
+
+impl<St> MessageRetention<St>
+where
+    St: Storage,
+{
+    pub(crate) fn new(
+        check_interval: Duration,
+        policy: RetentionPolicy,
+        storages: Vec<St>,
+    ) -> Self {
+        MessageRetention {
+            check_interval,
+            policy,
+            storages,
+        }
+    }
+
+    async fn enforce(&self) {
+        for storage in &self.storages {
+            match storage.enforce_message_retention(&self.policy).await {
+                Ok(0) => trace!("there were no stored messages exceeding the retention limits"),
+                Ok(removed) => {
+                    info!("removed {removed} stored messages exceeding the retention limits")
+                }
+                Err(err) => warn!("failed to enforce the retention of stored messages: {err}"),
+            }
+        }
+    }
+
+    pub(crate) async fn run(self, mut shutdown: TaskClient) {
+        let mut interval = tokio::time::interval(self.check_interval);
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = shutdown.recv() => {
+                    trace!("MessageRetention: received shutdown");
+                }
+                _ = interval.tick() => self.enforce().await,
+            }
+        }
+        debug!("MessageRetention: exiting");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_gateway_storage::PersistentStorage;
+    use nym_sphinx::DestinationAddressBytes;
+    use nym_task::TaskManager;
+
+    fn client() -> DestinationAddressBytes {
+        DestinationAddressBytes::from_bytes([42; 32])
+    }
+
+    async fn test_storage(dir: &tempfile::TempDir) -> PersistentStorage {
+        PersistentStorage::init(dir.path().join("storage.sqlite"), 100)
+            .await
+            .unwrap()
+            .with_inbox_encryption(false)
+    }
+
+    async fn store_messages(storage: &PersistentStorage, count: usize) {
+        for i in 0..count {
+            storage
+                .store_message(client(), vec![i as u8; 16])
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn wait_for_count(storages: &[PersistentStorage], expected: u64) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let mut counts = Vec::new();
+                for storage in storages {
+                    counts.push(storage.count_stored_messages(client()).await.unwrap());
+                }
+                if counts.iter().all(|&count| count == expected) {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("the retention limits have not been enforced")
+    }
+
+    #[tokio::test]
+    async fn limits_are_enforced_on_all_storages_until_shutdown() {
+        let (main_dir, tenant_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let storages = vec![
+            test_storage(&main_dir).await,
+            test_storage(&tenant_dir).await,
+        ];
+        for storage in &storages {
+            store_messages(storage, 3).await;
+        }
+
+        let policy = RetentionPolicy {
+            max_messages_per_client: Some(1),
+            ..Default::default()
+        };
+        let task_manager = TaskManager::default();
+        let retention = MessageRetention::new(Duration::from_millis(10), policy, storages.clone());
+        let handle = tokio::spawn(retention.run(task_manager.subscribe()));
+
+        // the first check happens straight away
+        wait_for_count(&storages, 1).await;
+
+        // and the messages stored afterwards are dealt with by the subsequent ones
+        store_messages(&storages[0], 2).await;
+        wait_for_count(&storages, 1).await;
+
+        task_manager.signal_shutdown().unwrap();
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("the task has not stopped")
+            .unwrap();
+    }
+}
@@ -1,9 +1,14 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
-use crate::node::client_handling::active_clients::ActiveClientsStore;
-use crate::node::client_handling::websocket::message_receiver::MixMessageSender;
-use crate::node::mixnet_handling::receiver::packet_processing::PacketProcessor;
+use crate::node::client_handling::active_clients::{ActiveClientSenders, ActiveClientsStore};
+use crate::node::mixnet_handling::receiver::forward_hops::{
+    ForwardHopDelayerShutdown, ForwardHopSender,
+};
+use crate::node::mixnet_handling::receiver::packet_processing::{
+    MixProcessingResult, PacketProcessor,
+};
+use crate::node::tenants::TenantRouter;
 use futures::channel::mpsc::SendError;
 use futures::StreamExt;
 use nym_gateway_storage::{error::StorageError, Storage};
@@ -12,12 +17,13 @@ use nym_mixnode_common::packet_processor::processor::ProcessedFinalHop;
 use nym_sphinx::forwarding::packet::MixPacket;
 use nym_sphinx::framing::codec::NymCodec;
 use nym_sphinx::framing::packet::FramedNymPacket;
-use nym_sphinx::DestinationAddressBytes;
+use nym_sphinx::{Delay as SphinxDelay, DestinationAddressBytes};
 use nym_task::TaskClient;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use thiserror::Error;
 use tokio::net::TcpStream;
+use tokio::time::Instant;
 use tokio_util::codec::Framed;
 use tracing::*;
 
@@ -26,6 +32,9 @@ use tracing::*;
 enum CriticalPacketProcessingError {
     #[error("failed to forward an ack")]
     AckForwardingFailure { source: SendError },
+
+    #[error("failed to pass a forward hop packet to the delayer")]
+    ForwardHopDelayingFailure { source: ForwardHopDelayerShutdown },
 }
 
 pub(crate) struct ConnectionHandler<St: Storage> {
@@ -35,10 +44,18 @@ pub(crate) struct ConnectionHandler<St: Storage> {
     // at this point.
     // keep the following in mind: each action on ActiveClientsStore requires going through RwLock
     // and each `get` internally copies the channel, however, is it really that expensive?
-    clients_store_cache: HashMap<DestinationAddressBytes, MixMessageSender>,
+    clients_store_cache: HashMap<DestinationAddressBytes, ActiveClientSenders>,
     active_clients_store: ActiveClientsStore,
     storage: St,
+
+    // storages of additional tenants served by this gateway. messages for offline clients
+    // are put into the storage of the tenant they have registered with
+    tenant_storages: Vec<St>,
+    tenant_router: TenantRouter,
     ack_sender: MixForwardingSender,
+
+    // only present if the gateway is configured to act as a regular mix hop
+    forward_hop_sender: Option<ForwardHopSender>,
 }
 
 impl<St: Storage + Clone> Clone for ConnectionHandler<St> {
@@ -56,7 +73,10 @@ impl<St: Storage + Clone> Clone for ConnectionHandler<St> {
             clients_store_cache,
             active_clients_store: self.active_clients_store.clone(),
             storage: self.storage.clone(),
+            tenant_storages: self.tenant_storages.clone(),
+            tenant_router: self.tenant_router.clone(),
             ack_sender: self.ack_sender.clone(),
+            forward_hop_sender: self.forward_hop_sender.clone(),
         }
     }
 }
@@ -72,11 +92,38 @@ impl<St: Storage> ConnectionHandler<St> {
             packet_processor,
             clients_store_cache: HashMap::new(),
             storage,
+            tenant_storages: Vec::new(),
+            tenant_router: TenantRouter::default(),
             active_clients_store,
             ack_sender,
+            forward_hop_sender: None,
         }
     }
 
+    #[must_use]
+    pub(crate) fn with_forward_hop_sender(mut self, forward_hop_sender: ForwardHopSender) -> Self {
+        self.forward_hop_sender = Some(forward_hop_sender);
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_tenant_storages(
+        mut self,
+        tenant_storages: Vec<St>,
+        tenant_router: TenantRouter,
+    ) -> Self {
+        self.tenant_storages = tenant_storages;
+        self.tenant_router = tenant_router;
+        self
+    }
+
+    fn client_storage(&self, client_address: &DestinationAddressBytes) -> &St {
+        self.tenant_router
+            .tenant_of(client_address)
+            .and_then(|tenant| self.tenant_storages.get(tenant))
+            .unwrap_or(&self.storage)
+    }
+
     fn update_clients_store_cache_entry(&mut self, client_address: DestinationAddressBytes) {
         if let Some(client_senders) = self.active_clients_store.get_sender(client_address) {
             self.clients_store_cache
@@ -105,11 +152,11 @@ impl<St: Storage> ConnectionHandler<St> {
         match self.clients_store_cache.get(&client_address) {
             None => Err(message),
             Some(sender_channel) => {
-                if let Err(unsent) = sender_channel.unbounded_send(vec![message]) {
+                if let Err(mut unsent) = sender_channel.unbounded_send(vec![message]) {
                     // the unwrap here is fine as the original message got returned;
                     // plus we're only ever sending 1 message at the time (for now)
                     #[allow(clippy::unwrap_used)]
-                    return Err(unsent.into_inner().pop().unwrap());
+                    return Err(unsent.pop().unwrap());
                 } else {
                     Ok(())
                 }
@@ -124,7 +171,9 @@ impl<St: Storage> ConnectionHandler<St> {
     ) -> Result<(), StorageError> {
         debug!("Storing received message for {client_address} on the disk...",);
 
-        self.storage.store_message(client_address, message).await
+        self.client_storage(&client_address)
+            .store_message(client_address, message)
+            .await
     }
 
     fn forward_ack(
@@ -147,6 +196,24 @@ impl<St: Storage> ConnectionHandler<St> {
         Ok(())
     }
 
+    fn delay_and_forward_packet(
+        &self,
+        mix_packet: MixPacket,
+        delay: Option<SphinxDelay>,
+    ) -> Result<(), CriticalPacketProcessingError> {
+        let Some(forward_hop_sender) = &self.forward_hop_sender else {
+            debug!("received a forward hop packet, but there's nothing to forward it with");
+            return Ok(());
+        };
+
+        // determine instant at which packet should get forwarded. this way we minimise effect of
+        // being stuck in the queue [of the channel] to get inserted into the delay queue
+        let forward_instant = delay.map(|delay| Instant::now() + delay.to_duration());
+        forward_hop_sender
+            .send(mix_packet, forward_instant)
+            .map_err(|source| CriticalPacketProcessingError::ForwardHopDelayingFailure { source })
+    }
+
     async fn handle_processed_packet(
         &mut self,
         processed_final_hop: ProcessedFinalHop,
@@ -184,15 +251,43 @@ impl<St: Storage> ConnectionHandler<St> {
         // question: can it also be per connection vs global?
         //
 
+        #[cfg(feature = "pcap")]
+        let received = {
+            let packet = framed_sphinx_packet.packet();
+            nym_pcap::record_packet(nym_pcap::EventKind::Received, packet);
+            (nym_pcap::PacketId::for_packet(packet), packet.len())
+        };
+
         let processed_final_hop = match self.packet_processor.process_received(framed_sphinx_packet)
         {
             Err(err) => {
+                #[cfg(feature = "pcap")]
+                nym_pcap::record(nym_pcap::EventKind::Dropped, received.0, None, received.1);
                 debug!("We failed to process received sphinx packet - {err}");
                 return Ok(());
             }
-            Ok(processed_final_hop) => processed_final_hop,
+            Ok(MixProcessingResult::ForwardHop(forward_packet, delay)) => {
+                #[cfg(feature = "pcap")]
+                nym_pcap::record(
+                    nym_pcap::EventKind::Processed,
+                    received.0,
+                    Some(nym_pcap::PacketId::for_packet(forward_packet.packet())),
+                    received.1,
+                );
+                return self.delay_and_forward_packet(forward_packet, delay);
+            }
+            Ok(MixProcessingResult::FinalHop(processed_final_hop)) => processed_final_hop,
         };
 
+        // the client is going to record the message under the same id once it receives it
+        #[cfg(feature = "pcap")]
+        nym_pcap::record(
+            nym_pcap::EventKind::Delivered,
+            received.0,
+            Some(nym_pcap::PacketId::from_bytes(&processed_final_hop.message)),
+            received.1,
+        );
+
         self.handle_processed_packet(processed_final_hop).await
     }
 
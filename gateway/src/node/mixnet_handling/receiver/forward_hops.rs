@@ -0,0 +1,247 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use futures::StreamExt;
+use nym_mixnet_client::forwarder::MixForwardingSender;
+use nym_sphinx::forwarding::packet::MixPacket;
+use nym_task::TaskClient;
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::time::Instant;
+use tokio_util::time::DelayQueue;
+use tracing::*;
+
+// maximum number of processed packets that can be waiting for the delayer to pick them up
+const FORWARD_HOP_CHANNEL_CAPACITY: usize = 1024;
+
+// rather than using Duration directly, we use an Instant, this way we minimise skew due to
+// time packet spent waiting in the channel to get delayed
+type ForwardHop = (MixPacket, Option<Instant>);
+
+#[derive(Debug, Error)]
+#[error("the forward hop delayer has already shut down")]
+pub(crate) struct ForwardHopDelayerShutdown;
+
+/// Passes the processed forward hop packets to the [ForwardHopDelayer].
+/// If the delayer can't keep up with the incoming packets, they get dropped.
+#[derive(Clone)]
+pub(crate) struct ForwardHopSender {
+    inner: mpsc::Sender<ForwardHop>,
+}
+
+impl ForwardHopSender {
+    pub(crate) fn send(
+        &self,
+        packet: MixPacket,
+        forward_at: Option<Instant>,
+    ) -> Result<(), ForwardHopDelayerShutdown> {
+        match self.inner.try_send((packet, forward_at)) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => {
+                debug!("the forward hop delayer is overloaded - dropping the packet");
+                Ok(())
+            }
+            Err(TrySendError::Closed(_)) => Err(ForwardHopDelayerShutdown),
+        }
+    }
+}
+
+/// Delays the processed forward hop packets, for which the gateway acts as a regular mix hop,
+/// and then passes them on to the packet forwarder.
+pub(crate) struct ForwardHopDelayer {
+    delay_queue: DelayQueue<MixPacket>,
+    maximum_delayed_packets: usize,
+    forwarding_channel: MixForwardingSender,
+    packet_receiver: mpsc::Receiver<ForwardHop>,
+}
+
+impl ForwardHopDelayer {
+    pub(crate) fn new(
+        forwarding_channel: MixForwardingSender,
+        maximum_delayed_packets: usize,
+    ) -> (Self, ForwardHopSender) {
+        let (packet_sender, packet_receiver) = mpsc::channel(FORWARD_HOP_CHANNEL_CAPACITY);
+        (
+            ForwardHopDelayer {
+                delay_queue: DelayQueue::new(),
+                maximum_delayed_packets,
+                forwarding_channel,
+                packet_receiver,
+            },
+            ForwardHopSender {
+                inner: packet_sender,
+            },
+        )
+    }
+
+    fn forward_packet(&self, packet: MixPacket) {
+        if let Err(err) = self.forwarding_channel.unbounded_send(packet) {
+            // this can only happen if the packet forwarder has already shut down
+            debug!("failed to forward a delayed packet: {err}")
+        }
+    }
+
+    fn handle_new_packet(&mut self, packet: MixPacket, forward_at: Option<Instant>) {
+        // there's no point in putting the packets whose delay has already expired through the queue
+        match forward_at {
+            Some(forward_at) if forward_at > Instant::now() => {
+                if self.delay_queue.len() >= self.maximum_delayed_packets {
+                    debug!("the forward hop delay queue is full - dropping the packet");
+                    return;
+                }
+                self.delay_queue.insert_at(packet, forward_at);
+            }
+            _ => self.forward_packet(packet),
+        }
+    }
+
+    pub(crate) async fn run(mut self, mut shutdown: TaskClient) {
+        trace!("Starting ForwardHopDelayer");
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                biased;
+                _ = shutdown.recv() => {
+                    trace!("ForwardHopDelayer: received shutdown");
+                }
+                // the queue resolves to `None` whenever it's empty, so only poll it if there's anything in it
+                Some(delayed) = self.delay_queue.next(), if !self.delay_queue.is_empty() => {
+                    self.forward_packet(delayed.into_inner())
+                }
+                new_packet = self.packet_receiver.recv() => match new_packet {
+                    Some((packet, forward_at)) => self.handle_new_packet(packet, forward_at),
+                    None => break,
+                },
+            }
+        }
+        debug!("ForwardHopDelayer: exiting");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::mpsc::{unbounded, UnboundedReceiver};
+    use nym_sphinx::addressing::nodes::NymNodeRoutingAddress;
+    use nym_sphinx::params::{PacketSize, PacketType};
+    use nym_sphinx::{
+        crypto, Delay, Destination, DestinationAddressBytes, Node, NodeAddressBytes, NymPacket,
+        DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
+    };
+    use nym_task::TaskManager;
+    use std::net::SocketAddr;
+    use std::time::Duration;
+
+    fn make_mix_packet() -> MixPacket {
+        let route = [5u8, 4, 2].map(|address| {
+            let (_, public_key) = crypto::keygen();
+            Node::new(
+                NodeAddressBytes::from_bytes([address; NODE_ADDRESS_LENGTH]),
+                public_key,
+            )
+        });
+        let destination = Destination::new(
+            DestinationAddressBytes::from_bytes([3u8; DESTINATION_ADDRESS_LENGTH]),
+            [4u8; IDENTIFIER_LENGTH],
+        );
+        let delays = vec![Delay::new_from_nanos(42); 3];
+        let packet = NymPacket::sphinx_build(
+            PacketSize::default().payload_size(),
+            b"foomp",
+            &route,
+            &destination,
+            &delays,
+        )
+        .unwrap();
+
+        let next_hop: SocketAddr = "127.0.0.1:1789".parse().unwrap();
+        MixPacket::new(
+            NymNodeRoutingAddress::from(next_hop),
+            packet,
+            PacketType::default(),
+        )
+    }
+
+    fn delayer(
+        maximum_delayed_packets: usize,
+    ) -> (
+        ForwardHopDelayer,
+        ForwardHopSender,
+        UnboundedReceiver<MixPacket>,
+    ) {
+        let (forwarding_channel, forwarded) = unbounded();
+        let (delayer, sender) = ForwardHopDelayer::new(forwarding_channel, maximum_delayed_packets);
+        (delayer, sender, forwarded)
+    }
+
+    #[tokio::test]
+    async fn packets_without_pending_delay_are_forwarded_immediately() {
+        let (mut delayer, _sender, mut forwarded) = delayer(10);
+
+        delayer.handle_new_packet(make_mix_packet(), None);
+        delayer.handle_new_packet(
+            make_mix_packet(),
+            Some(Instant::now() - Duration::from_secs(1)),
+        );
+
+        assert!(delayer.delay_queue.is_empty());
+        assert!(forwarded.try_next().unwrap().is_some());
+        assert!(forwarded.try_next().unwrap().is_some());
+        assert!(forwarded.try_next().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn delayed_packets_are_forwarded_once_their_delay_expires() {
+        let (delayer, sender, mut forwarded) = delayer(10);
+        let task_manager = TaskManager::default();
+        tokio::spawn(delayer.run(task_manager.subscribe()));
+
+        sender
+            .send(
+                make_mix_packet(),
+                Some(Instant::now() + Duration::from_secs(10)),
+            )
+            .unwrap();
+
+        let too_early = tokio::time::timeout(Duration::from_secs(5), forwarded.next()).await;
+        assert!(too_early.is_err());
+
+        let on_time = tokio::time::timeout(Duration::from_secs(10), forwarded.next()).await;
+        assert!(on_time.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn packets_are_dropped_when_the_delay_queue_is_full() {
+        let (mut delayer, _sender, mut forwarded) = delayer(2);
+        let forward_at = Instant::now() + Duration::from_secs(60);
+
+        for _ in 0..5 {
+            delayer.handle_new_packet(make_mix_packet(), Some(forward_at));
+        }
+
+        assert_eq!(delayer.delay_queue.len(), 2);
+        assert!(forwarded.try_next().is_err());
+    }
+
+    #[tokio::test]
+    async fn packets_are_dropped_when_the_delayer_cant_keep_up() {
+        let (inner, mut receiver) = mpsc::channel(2);
+        let sender = ForwardHopSender { inner };
+
+        for _ in 0..3 {
+            assert!(sender.send(make_mix_packet(), None).is_ok());
+        }
+
+        assert!(receiver.try_recv().is_ok());
+        assert!(receiver.try_recv().is_ok());
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn sending_fails_once_the_delayer_has_shut_down() {
+        let (delayer, sender, _forwarded) = delayer(10);
+        drop(delayer);
+
+        assert!(sender.send(make_mix_packet(), None).is_err());
+    }
+}
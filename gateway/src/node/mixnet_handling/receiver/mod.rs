@@ -2,5 +2,6 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 pub(crate) mod connection_handler;
+pub(crate) mod forward_hops;
 pub(crate) mod listener;
 pub(crate) mod packet_processing;
@@ -4,7 +4,7 @@
 use nym_crypto::asymmetric::encryption;
 use nym_mixnode_common::packet_processor::error::MixProcessingError;
 pub use nym_mixnode_common::packet_processor::processor::MixProcessingResult;
-use nym_mixnode_common::packet_processor::processor::{ProcessedFinalHop, SphinxPacketProcessor};
+use nym_mixnode_common::packet_processor::processor::SphinxPacketProcessor;
 use nym_sphinx::framing::packet::FramedNymPacket;
 use thiserror::Error;
 
@@ -21,24 +21,34 @@ pub enum GatewayProcessingError {
 #[derive(Clone)]
 pub struct PacketProcessor {
     inner_processor: SphinxPacketProcessor,
+
+    /// Specifies whether the forward hop packets should be processed rather than rejected.
+    forward_hops: bool,
 }
 
 impl PacketProcessor {
     pub(crate) fn new(encryption_key: &encryption::PrivateKey) -> Self {
         PacketProcessor {
             inner_processor: SphinxPacketProcessor::new(encryption_key.into()),
+            forward_hops: false,
         }
     }
 
+    #[must_use]
+    pub(crate) fn with_forward_hops(mut self, forward_hops: bool) -> Self {
+        self.forward_hops = forward_hops;
+        self
+    }
+
     pub(crate) fn process_received(
         &self,
         received: FramedNymPacket,
-    ) -> Result<ProcessedFinalHop, GatewayProcessingError> {
+    ) -> Result<MixProcessingResult, GatewayProcessingError> {
         match self.inner_processor.process_received(received)? {
-            MixProcessingResult::ForwardHop(..) => {
+            MixProcessingResult::ForwardHop(..) if !self.forward_hops => {
                 Err(GatewayProcessingError::ForwardHopReceivedError)
             }
-            MixProcessingResult::FinalHop(processed_final) => Ok(processed_final),
+            processed => Ok(processed),
         }
     }
 }
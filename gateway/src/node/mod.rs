@@ -12,16 +12,22 @@ use crate::http::HttpApiBuilder;
 use crate::node::client_handling::active_clients::ActiveClientsStore;
 use crate::node::client_handling::embedded_clients::{LocalEmbeddedClientHandle, MessageRouter};
 use crate::node::client_handling::websocket;
-use crate::node::helpers::{initialise_main_storage, load_network_requester_config};
+use crate::node::directory_monitor::DirectoryMonitor;
+use crate::node::helpers::{initialise_main_storage, load_network_requester_config, load_tenants};
+use crate::node::message_offloader::MessageOffloader;
+use crate::node::message_retention::MessageRetention;
 use crate::node::mixnet_handling::receiver::connection_handler::ConnectionHandler;
+use crate::node::mixnet_handling::receiver::forward_hops::ForwardHopDelayer;
+use crate::node::tenants::{TenantMetrics, TenantRouter};
 use futures::channel::{mpsc, oneshot};
 use nym_credential_verification::ecash::{
     credential_sender::CredentialHandlerConfig, EcashManager,
 };
 use nym_crypto::asymmetric::{encryption, identity};
-use nym_mixnet_client::forwarder::{MixForwardingSender, PacketForwarder};
+use nym_mixnet_client::forwarder::{MixForwardingSender, PacketForwarder, RetryPolicy};
 use nym_network_defaults::NymNetworkDetails;
 use nym_network_requester::{LocalGateway, NRServiceProviderBuilder, RequestFilter};
+use nym_node_http_api::state::gateway::SharedDirectoryStatus;
 use nym_task::{TaskClient, TaskHandle, TaskManager};
 use nym_types::gateway::GatewayNodeDetailsResponse;
 use nym_validator_client::nyxd::{Coin, CosmWasmClient};
@@ -34,10 +40,17 @@ use std::sync::Arc;
 use tracing::*;
 
 pub(crate) mod client_handling;
+pub(crate) mod directory_monitor;
 pub(crate) mod helpers;
+pub(crate) mod message_offloader;
+pub(crate) mod message_retention;
 pub(crate) mod mixnet_handling;
+pub(crate) mod tenants;
 
+pub use client_handling::notices::GatewayNotices;
+pub use helpers::with_configured_message_store;
 pub use nym_gateway_storage::{PersistentStorage, Storage};
+pub use tenants::GatewayTenant;
 
 // TODO: should this struct live here?
 struct StartedNetworkRequester {
@@ -91,6 +104,7 @@ pub async fn create_gateway(
     };
 
     let storage = initialise_main_storage(&config).await?;
+    let tenants = load_tenants(&config).await?;
 
     let nr_opts = network_requester_config.map(|config| LocalNetworkRequesterOpts {
         config: config.clone(),
@@ -102,7 +116,7 @@ pub async fn create_gateway(
         custom_mixnet_path: custom_mixnet.clone(),
     });
 
-    Gateway::new(config, nr_opts, ip_opts, storage)
+    Ok(Gateway::new(config, nr_opts, ip_opts, storage)?.with_tenants(tenants))
 }
 
 #[derive(Debug, Clone)]
@@ -145,8 +159,17 @@ pub struct Gateway<St = PersistentStorage> {
 
     storage: St,
 
+    /// Additional identities served by this gateway, each with its own client listener and storage.
+    tenants: Vec<GatewayTenant<St>>,
+
     wireguard_data: Option<nym_wireguard::WireguardData>,
 
+    /// Results of the self-monitoring of this gateway's directory entry.
+    directory_status: SharedDirectoryStatus,
+
+    /// Notices pushed to the clients subscribed to them.
+    notices: GatewayNotices,
+
     run_http_server: bool,
     task_client: Option<TaskClient>,
 }
@@ -167,7 +190,10 @@ impl<St> Gateway<St> {
             network_requester_opts,
             ip_packet_router_opts,
             authenticator_opts: None,
+            tenants: Vec::new(),
             wireguard_data: None,
+            directory_status: SharedDirectoryStatus::new(),
+            notices: GatewayNotices::new(),
             run_http_server: true,
             task_client: None,
         })
@@ -190,12 +216,21 @@ impl<St> Gateway<St> {
             identity_keypair,
             sphinx_keypair,
             storage,
+            tenants: Vec::new(),
             wireguard_data: None,
+            directory_status: SharedDirectoryStatus::new(),
+            notices: GatewayNotices::new(),
             run_http_server: true,
             task_client: None,
         }
     }
 
+    #[must_use]
+    pub fn with_tenants(mut self, tenants: Vec<GatewayTenant<St>>) -> Self {
+        self.tenants = tenants;
+        self
+    }
+
     pub fn disable_http_server(&mut self) {
         self.run_http_server = false
     }
@@ -208,6 +243,18 @@ impl<St> Gateway<St> {
         self.wireguard_data = Some(wireguard_data)
     }
 
+    /// Returns the handle to the results of the directory entry self-monitoring,
+    /// so that they could be exposed by the embedder's own http API.
+    pub fn directory_status(&self) -> SharedDirectoryStatus {
+        self.directory_status.clone()
+    }
+
+    /// Returns the handle for publishing notices, such as the scheduled maintenance,
+    /// to the connected clients.
+    pub fn notices(&self) -> GatewayNotices {
+        self.notices.clone()
+    }
+
     pub async fn node_details(&self) -> Result<GatewayNodeDetailsResponse, GatewayError> {
         // TODO: this is doing redundant key loads, but I guess that's fine for now
         crate::helpers::node_details(&self.config).await
@@ -217,21 +264,44 @@ impl<St> Gateway<St> {
         &self,
         ack_sender: MixForwardingSender,
         active_clients_store: ActiveClientsStore,
+        tenant_router: TenantRouter,
         shutdown: TaskClient,
     ) where
         St: Storage + Clone + 'static,
     {
         info!("Starting mix socket listener...");
 
+        let process_forward_hops = self.config.debug.process_forward_hops;
         let packet_processor =
-            mixnet_handling::PacketProcessor::new(self.sphinx_keypair.private_key());
+            mixnet_handling::PacketProcessor::new(self.sphinx_keypair.private_key())
+                .with_forward_hops(process_forward_hops);
 
-        let connection_handler = ConnectionHandler::new(
+        let tenant_storages = self
+            .tenants
+            .iter()
+            .map(|tenant| tenant.storage.clone())
+            .collect();
+
+        let mut connection_handler = ConnectionHandler::new(
             packet_processor,
             self.storage.clone(),
-            ack_sender,
+            ack_sender.clone(),
             active_clients_store,
-        );
+        )
+        .with_tenant_storages(tenant_storages, tenant_router);
+
+        if process_forward_hops {
+            info!("the gateway is going to act as a regular mix hop for the forward hop packets");
+            // the forward hops get their own packet forwarder so that they couldn't starve the acks
+            let forwarding_channel =
+                self.start_packet_forwarder(shutdown.fork("ForwardHopPacketForwarder"));
+            let (delayer, forward_hop_sender) = ForwardHopDelayer::new(
+                forwarding_channel,
+                self.config.debug.maximum_delayed_forward_hops,
+            );
+            tokio::spawn(delayer.run(shutdown.fork("ForwardHopDelayer")));
+            connection_handler = connection_handler.with_forward_hop_sender(forward_hop_sender);
+        }
 
         let listening_address = SocketAddr::new(
             self.config.gateway.listening_address,
@@ -350,6 +420,7 @@ impl<St> Gateway<St> {
         &self,
         forwarding_channel: MixForwardingSender,
         active_clients_store: ActiveClientsStore,
+        tenant_router: TenantRouter,
         shutdown: TaskClient,
         ecash_verifier: Arc<EcashManager<St>>,
     ) where
@@ -368,8 +439,26 @@ impl<St> Gateway<St> {
             local_identity: Arc::clone(&self.identity_keypair),
             only_coconut_credentials: self.config.gateway.only_coconut_credentials,
             bandwidth_cfg: (&self.config).into(),
+            client_sessions: self.config.debug.client_sessions,
+            share_protocol_stats: self.config.debug.share_protocol_stats,
+            metrics: TenantMetrics::new(self.identity_keypair.public_key()),
+            tenant: None,
+            tenant_router,
+            notices: self.notices.clone(),
         };
 
+        if let Some(framed_port) = self.config.gateway.clients_framed_port {
+            let framed_address =
+                SocketAddr::new(self.config.gateway.listening_address, framed_port);
+            websocket::Listener::new(framed_address, shared_state.clone())
+                .framed()
+                .start(
+                    forwarding_channel.clone(),
+                    active_clients_store.clone(),
+                    shutdown.fork("framed::Listener"),
+                );
+        }
+
         websocket::Listener::new(listening_address, shared_state).start(
             forwarding_channel,
             active_clients_store,
@@ -377,6 +466,81 @@ impl<St> Gateway<St> {
         );
     }
 
+    async fn start_tenant_websocket_listeners(
+        &self,
+        forwarding_channel: MixForwardingSender,
+        active_clients_store: ActiveClientsStore,
+        tenant_router: TenantRouter,
+        shutdown: &TaskHandle,
+    ) -> Result<(), GatewayError>
+    where
+        St: Storage + Send + Sync + Clone + 'static,
+    {
+        for (index, tenant) in self.tenants.iter().enumerate() {
+            info!(
+                "Starting client [web]socket listener for tenant '{}' ({})...",
+                tenant.id(),
+                tenant.identity()
+            );
+
+            // tickets are bound to the identity of the gateway they're spent with,
+            // so every tenant needs its own verifier
+            let ecash_verifier = EcashManager::new(
+                self.credential_handler_config(),
+                self.random_nyxd_client()?,
+                tenant.identity().to_bytes(),
+                shutdown.fork(format!("EcashVerifier_{}", tenant.id())),
+                tenant.storage.clone(),
+            )
+            .await?;
+
+            let listening_address = SocketAddr::new(
+                self.config.gateway.listening_address,
+                tenant.config.clients_port,
+            );
+
+            let shared_state = websocket::CommonHandlerState {
+                ecash_verifier: Arc::new(ecash_verifier),
+                storage: tenant.storage.clone(),
+                local_identity: Arc::clone(&tenant.identity_keypair),
+                only_coconut_credentials: self.config.gateway.only_coconut_credentials,
+                bandwidth_cfg: (&self.config).into(),
+                client_sessions: self.config.debug.client_sessions,
+                share_protocol_stats: self.config.debug.share_protocol_stats,
+                metrics: TenantMetrics::new(tenant.identity()),
+                tenant: Some(index),
+                tenant_router: tenant_router.clone(),
+                notices: self.notices.clone(),
+            };
+
+            websocket::Listener::new(listening_address, shared_state).start(
+                forwarding_channel.clone(),
+                active_clients_store.clone(),
+                shutdown.fork(format!("websocket::Listener_{}", tenant.id())),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn credential_handler_config(&self) -> CredentialHandlerConfig {
+        CredentialHandlerConfig {
+            revocation_bandwidth_penalty: self
+                .config
+                .debug
+                .zk_nym_tickets
+                .revocation_bandwidth_penalty,
+            pending_poller: self.config.debug.zk_nym_tickets.pending_poller,
+            minimum_api_quorum: self.config.debug.zk_nym_tickets.minimum_api_quorum,
+            minimum_redemption_tickets: self.config.debug.zk_nym_tickets.minimum_redemption_tickets,
+            maximum_time_between_redemption: self
+                .config
+                .debug
+                .zk_nym_tickets
+                .maximum_time_between_redemption,
+        }
+    }
+
     fn start_packet_forwarder(&self, shutdown: TaskClient) -> MixForwardingSender {
         info!("Starting mix packet forwarder...");
 
@@ -386,6 +550,12 @@ impl<St> Gateway<St> {
             self.config.debug.initial_connection_timeout,
             self.config.debug.maximum_connection_buffer_size,
             self.config.debug.use_legacy_framed_packet_version,
+            RetryPolicy {
+                maximum_retries: self.config.debug.packet_forwarding_maximum_retries,
+                initial_backoff: self.config.debug.packet_forwarding_initial_retry_backoff,
+                maximum_backoff: self.config.debug.packet_forwarding_maximum_retry_backoff,
+                maximum_queue_size: self.config.debug.packet_forwarding_maximum_retry_queue_size,
+            },
             shutdown,
         );
 
@@ -569,6 +739,12 @@ impl<St> Gateway<St> {
     {
         info!("Starting nym gateway!");
 
+        #[cfg(feature = "pcap")]
+        nym_pcap::init_from_env(format!(
+            "gateway-{}",
+            self.identity_keypair.public_key().to_base58_string()
+        ));
+
         if self.check_if_bonded().await? {
             warn!("You seem to have bonded your gateway before starting it - that's highly unrecommended as in the future it might result in slashing");
         }
@@ -600,25 +776,9 @@ impl<St> Gateway<St> {
             }
         }
 
-        let handler_config = CredentialHandlerConfig {
-            revocation_bandwidth_penalty: self
-                .config
-                .debug
-                .zk_nym_tickets
-                .revocation_bandwidth_penalty,
-            pending_poller: self.config.debug.zk_nym_tickets.pending_poller,
-            minimum_api_quorum: self.config.debug.zk_nym_tickets.minimum_api_quorum,
-            minimum_redemption_tickets: self.config.debug.zk_nym_tickets.minimum_redemption_tickets,
-            maximum_time_between_redemption: self
-                .config
-                .debug
-                .zk_nym_tickets
-                .maximum_time_between_redemption,
-        };
-
         let ecash_verifier = Arc::new(
             EcashManager::new(
-                handler_config,
+                self.credential_handler_config(),
                 nyxd_client,
                 self.identity_keypair.public_key().to_bytes(),
                 shutdown.fork("EcashVerifier"),
@@ -630,19 +790,30 @@ impl<St> Gateway<St> {
         let mix_forwarding_channel = self.start_packet_forwarder(shutdown.fork("PacketForwarder"));
 
         let active_clients_store = ActiveClientsStore::new();
+        let tenant_router = TenantRouter::load(&self.tenants).await?;
         self.start_mix_socket_listener(
             mix_forwarding_channel.clone(),
             active_clients_store.clone(),
+            tenant_router.clone(),
             shutdown.fork("mixnet_handling::Listener"),
         );
 
         self.start_client_websocket_listener(
             mix_forwarding_channel.clone(),
             active_clients_store.clone(),
+            tenant_router.clone(),
             shutdown.fork("websocket::Listener"),
             ecash_verifier.clone(),
         );
 
+        self.start_tenant_websocket_listeners(
+            mix_forwarding_channel.clone(),
+            active_clients_store.clone(),
+            tenant_router,
+            &shutdown,
+        )
+        .await?;
+
         let nr_request_filter = if self.config.network_requester.enabled {
             let embedded_nr = self
                 .start_network_requester(
@@ -685,6 +856,43 @@ impl<St> Gateway<St> {
             None
         };
 
+        let monitor_directory = self.config.debug.directory_monitor.enabled;
+        if monitor_directory {
+            let directory_monitor = DirectoryMonitor::new(
+                &self.config,
+                self.identity_keypair.public_key().to_base58_string(),
+                self.directory_status.clone(),
+            );
+            let monitor_shutdown = shutdown.fork("DirectoryMonitor");
+            tokio::spawn(directory_monitor.run(monitor_shutdown));
+        } else {
+            info!("directory entry monitoring is disabled");
+        }
+
+        if self.config.debug.message_store.offload_url.is_some() {
+            let storages = std::iter::once(self.storage.clone())
+                .chain(self.tenants.iter().map(|tenant| tenant.storage.clone()))
+                .collect();
+            let offloader = MessageOffloader::new(
+                self.config.debug.message_store.offload_check_interval,
+                storages,
+            );
+            tokio::spawn(offloader.run(shutdown.fork("MessageOffloader")));
+        }
+
+        let retention_policy = self.config.debug.message_store.retention_policy();
+        if !retention_policy.is_unlimited() {
+            let storages = std::iter::once(self.storage.clone())
+                .chain(self.tenants.iter().map(|tenant| tenant.storage.clone()))
+                .collect();
+            let retention = MessageRetention::new(
+                self.config.debug.message_store.retention_check_interval,
+                retention_policy,
+                storages,
+            );
+            tokio::spawn(retention.run(shutdown.fork("MessageRetention")));
+        }
+
         if self.run_http_server {
             HttpApiBuilder::new(
                 &self.config,
@@ -694,6 +902,7 @@ impl<St> Gateway<St> {
             .with_maybe_network_requester(self.network_requester_opts.as_ref().map(|o| &o.config))
             .with_maybe_network_request_filter(nr_request_filter)
             .with_maybe_ip_packet_router(self.ip_packet_router_opts.as_ref().map(|o| &o.config))
+            .with_maybe_directory_status(monitor_directory.then(|| self.directory_status.clone()))
             .start(shutdown.fork("http-api"))?;
         }
 
@@ -0,0 +1,157 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::config::Tenant;
+use dashmap::DashMap;
+use nym_crypto::asymmetric::identity;
+use nym_gateway_storage::error::StorageError;
+use nym_gateway_storage::Storage;
+use nym_metrics::REGISTRY;
+use nym_sphinx::DestinationAddressBytes;
+use std::sync::Arc;
+
+/// Additional bonded identity served by this gateway process alongside its primary identity.
+pub struct GatewayTenant<St> {
+    pub(crate) config: Tenant,
+
+    /// ed25519 keypair used to assert the identity of this tenant towards its clients.
+    pub(crate) identity_keypair: Arc<identity::KeyPair>,
+
+    /// Storage holding shared keys, bandwidth and pending messages of this tenant's clients only.
+    pub(crate) storage: St,
+}
+
+impl<St> GatewayTenant<St> {
+    pub fn new(config: Tenant, identity_keypair: identity::KeyPair, storage: St) -> Self {
+        GatewayTenant {
+            config,
+            identity_keypair: Arc::new(identity_keypair),
+            storage,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.config.id
+    }
+
+    pub fn identity(&self) -> &identity::PublicKey {
+        self.identity_keypair.public_key()
+    }
+}
+
+/// Index of the clients registered with the additional tenants, so that a message for an offline client
+/// could be put straight into the storage of its tenant without having to look it up in all of them.
+/// Clients that are not present in the index belong to the primary identity.
+#[derive(Clone, Default)]
+pub(crate) struct TenantRouter {
+    routes: Arc<DashMap<DestinationAddressBytes, usize>>,
+}
+
+impl TenantRouter {
+    /// Builds the index out of the clients that have already registered with any of the tenants.
+    pub(crate) async fn load<St: Storage>(
+        tenants: &[GatewayTenant<St>],
+    ) -> Result<Self, StorageError> {
+        let router = TenantRouter::default();
+        for (index, tenant) in tenants.iter().enumerate() {
+            for client in tenant.storage.get_all_client_addresses().await? {
+                router.client_registered(client, Some(index));
+            }
+        }
+        Ok(router)
+    }
+
+    /// Index of the tenant the client has registered with or `None` if it belongs to the primary identity.
+    pub(crate) fn tenant_of(&self, client: &DestinationAddressBytes) -> Option<usize> {
+        self.routes.get(client).map(|tenant| *tenant)
+    }
+
+    /// Records a (re-)registration of the client with the specified tenant (or the primary identity).
+    pub(crate) fn client_registered(&self, client: DestinationAddressBytes, tenant: Option<usize>) {
+        match tenant {
+            Some(index) => {
+                self.routes.insert(client, index);
+            }
+            None => {
+                self.routes.remove(&client);
+            }
+        }
+    }
+}
+
+/// Client activity counters kept separately for each identity served by the gateway
+/// (including the primary one).
+#[derive(Clone)]
+pub(crate) struct TenantMetrics {
+    prefix: Arc<str>,
+}
+
+impl TenantMetrics {
+    pub(crate) fn new(identity: &identity::PublicKey) -> Self {
+        TenantMetrics {
+            prefix: format!("nym_gateway_tenant_{}", identity.to_base58_string()).into(),
+        }
+    }
+
+    fn metric_name(&self, metric: &str) -> String {
+        format!("{}_{metric}", self.prefix)
+    }
+
+    pub(crate) fn client_registered(&self) {
+        REGISTRY.inc(&self.metric_name("clients_registered"))
+    }
+
+    pub(crate) fn client_authenticated(&self) {
+        REGISTRY.inc(&self.metric_name("clients_authenticated"))
+    }
+
+    pub(crate) fn packet_forwarded(&self, size: usize) {
+        REGISTRY.inc(&self.metric_name("packets_forwarded"));
+        REGISTRY.inc_by(&self.metric_name("packets_forwarded_size"), size as i64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(byte: u8) -> DestinationAddressBytes {
+        DestinationAddressBytes::from_bytes([byte; 32])
+    }
+
+    #[test]
+    fn unknown_clients_belong_to_the_primary_identity() {
+        let router = TenantRouter::default();
+        assert_eq!(router.tenant_of(&client(1)), None);
+    }
+
+    #[test]
+    fn clients_are_routed_to_the_tenant_they_registered_with() {
+        let router = TenantRouter::default();
+        router.client_registered(client(1), Some(0));
+        router.client_registered(client(2), Some(1));
+
+        assert_eq!(router.tenant_of(&client(1)), Some(0));
+        assert_eq!(router.tenant_of(&client(2)), Some(1));
+        assert_eq!(router.tenant_of(&client(3)), None);
+    }
+
+    #[test]
+    fn reregistration_moves_the_client() {
+        let router = TenantRouter::default();
+        router.client_registered(client(1), Some(0));
+        router.client_registered(client(1), Some(1));
+        assert_eq!(router.tenant_of(&client(1)), Some(1));
+
+        router.client_registered(client(1), None);
+        assert_eq!(router.tenant_of(&client(1)), None);
+    }
+
+    #[test]
+    fn clones_share_the_index() {
+        let router = TenantRouter::default();
+        let clone = router.clone();
+        router.client_registered(client(1), Some(2));
+        assert_eq!(clone.tenant_of(&client(1)), Some(2));
+    }
+}
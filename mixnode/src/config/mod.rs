@@ -46,6 +46,8 @@ const DEFAULT_PACKET_FORWARDING_MAXIMUM_BACKOFF: Duration = Duration::from_milli
 const DEFAULT_INITIAL_CONNECTION_TIMEOUT: Duration = Duration::from_millis(1_500);
 const DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE: usize = 2000;
 
+const DEFAULT_SPHINX_WORKER_QUEUE_SIZE: usize = 1024;
+
 /// Derive default path to mixnodes's config directory.
 /// It should get resolved to `$HOME/.nym/mixnodes/<id>/config`
 pub fn default_config_directory<P: AsRef<Path>>(id: P) -> PathBuf {
@@ -84,6 +86,7 @@ fn default_mixnode_http_config() -> Http {
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     // additional metadata holding on-disk location of this config file
@@ -102,6 +105,9 @@ pub struct Config {
     #[serde(default)]
     pub verloc: Verloc,
 
+    #[serde(default)]
+    pub sphinx_processing: SphinxProcessing,
+
     #[serde(default)]
     pub logging: LoggingSettings,
 
@@ -130,6 +136,7 @@ impl Config {
             mixnode: default_mixnode,
             storage_paths: MixNodePaths::new_default(id.as_ref()),
             verloc: Default::default(),
+            sphinx_processing: Default::default(),
             logging: Default::default(),
             debug: Default::default(),
         }
@@ -151,6 +158,7 @@ impl Config {
             mixnode: mixnode.into(),
             storage_paths: storage_paths.into(),
             verloc: verloc.into(),
+            sphinx_processing: Default::default(),
             logging: logging.into(),
             debug: debug.into(),
         }
@@ -227,6 +235,11 @@ impl Config {
         self.mixnode.nym_api_urls.clone()
     }
 
+    pub fn with_sphinx_processing(mut self, sphinx_processing: SphinxProcessing) -> Self {
+        self.sphinx_processing = sphinx_processing;
+        self
+    }
+
     pub fn with_metrics_key(mut self, metrics_key: String) -> Self {
         self.http.metrics_key = Some(metrics_key);
         self
@@ -239,6 +252,7 @@ impl Config {
 
 // TODO: this is very much a WIP. we need proper ssl certificate support here
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields)]
 pub struct Host {
     /// Ip address(es) of this host, such as 1.1.1.1 that external clients will use for connections.
@@ -261,6 +275,7 @@ impl Host {
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields)]
 pub struct Http {
     /// Socket address this node will use for binding its http API.
@@ -276,6 +291,7 @@ pub struct Http {
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 pub struct MixNode {
     /// Version of the mixnode for which this configuration was created.
     pub version: String,
@@ -312,20 +328,24 @@ impl MixNode {
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields)]
 pub struct Verloc {
     /// Specifies number of echo packets sent to each node during a measurement run.
     pub packets_per_node: usize,
 
     /// Specifies maximum amount of time to wait for the connection to get established.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub connection_timeout: Duration,
 
     /// Specifies maximum amount of time to wait for the reply packet to arrive before abandoning the test.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub packet_timeout: Duration,
 
     /// Specifies delay between subsequent test packets being sent (after receiving a reply).
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub delay_between_packets: Duration,
 
@@ -333,11 +353,13 @@ pub struct Verloc {
     pub tested_nodes_batch_size: usize,
 
     /// Specifies delay between subsequent test runs.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub testing_interval: Duration,
 
     /// Specifies delay between attempting to run the measurement again if the previous run failed
     /// due to being unable to get the list of nodes.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub retry_timeout: Duration,
 }
@@ -356,28 +378,72 @@ impl Default for Verloc {
     }
 }
 
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct SphinxProcessing {
+    /// Number of threads dedicated to unwrapping the received sphinx packets.
+    /// If set to 0, it's derived from the number of available CPU cores.
+    pub workers: usize,
+
+    /// Maximum number of received packets waiting to get processed by each of the workers.
+    /// Once the queue is full, the node stops reading from the connection the packet came from.
+    pub queue_size: usize,
+
+    /// Specifies whether each worker should be pinned to a single CPU core.
+    /// Only supported on Linux.
+    pub cpu_pinning: bool,
+
+    /// CPU cores the workers are allowed to be pinned to, assigned in round-robin fashion.
+    /// If empty, all cores available to the process (or to the specified NUMA node) are used.
+    pub cpu_cores: Vec<usize>,
+
+    /// If specified, the workers are only pinned to the cores belonging to this NUMA node,
+    /// so that the packets never cross the interconnect. Only relevant if `cpu_pinning` is enabled.
+    #[serde(deserialize_with = "de_maybe_stringified")]
+    pub numa_node: Option<usize>,
+}
+
+impl Default for SphinxProcessing {
+    fn default() -> Self {
+        SphinxProcessing {
+            workers: 0,
+            queue_size: DEFAULT_SPHINX_WORKER_QUEUE_SIZE,
+            cpu_pinning: false,
+            cpu_cores: Vec::new(),
+            numa_node: None,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "config_schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct Debug {
     /// Delay between each subsequent node statistics being logged to the console
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub node_stats_logging_delay: Duration,
 
     /// Delay between each subsequent node statistics being updated
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub node_stats_updating_delay: Duration,
 
     /// Initial value of an exponential backoff to reconnect to dropped TCP connection when
     /// forwarding sphinx packets.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub packet_forwarding_initial_backoff: Duration,
 
     /// Maximum value of an exponential backoff to reconnect to dropped TCP connection when
     /// forwarding sphinx packets.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub packet_forwarding_maximum_backoff: Duration,
 
     /// Timeout for establishing initial connection when trying to forward a sphinx packet.
+    #[cfg_attr(feature = "config_schema", schemars(with = "String"))]
     #[serde(with = "humantime_serde")]
     pub initial_connection_timeout: Duration,
 
@@ -76,6 +76,31 @@ keys.public_sphinx_key_file = '{{ storage_paths.keys.public_sphinx_key_file }}'
 # Path to file containing description of this node.
 node_description = '{{ storage_paths.node_description }}'
 
+##### sphinx processing options #####
+
+[sphinx_processing]
+
+# Number of threads dedicated to unwrapping the received sphinx packets.
+# If set to 0, it's derived from the number of available CPU cores.
+workers = {{ sphinx_processing.workers }}
+
+# Maximum number of received packets waiting to get processed by each of the workers.
+queue_size = {{ sphinx_processing.queue_size }}
+
+# Specifies whether each worker should be pinned to a single CPU core (Linux only).
+cpu_pinning = {{ sphinx_processing.cpu_pinning }}
+
+# CPU cores the workers are allowed to be pinned to.
+# If empty, all cores available to the process (or to the specified NUMA node) are used.
+cpu_cores = [
+    {{#each sphinx_processing.cpu_cores }}
+        {{this}},
+    {{/each}}
+]
+
+# If specified, the workers are only pinned to the cores belonging to this NUMA node.
+numa_node = '{{ sphinx_processing.numa_node }}'
+
 ##### logging configuration options #####
 
 [logging]
@@ -44,6 +44,12 @@ pub enum MixnodeError {
         source: io::Error,
     },
 
+    #[error("failed to start the sphinx processing workers: {source}")]
+    SphinxWorkersStartupFailure {
+        #[source]
+        source: io::Error,
+    },
+
     #[error(transparent)]
     NymNodeHttpError(#[from] nym_node_http_api::NymNodeHttpError),
 }
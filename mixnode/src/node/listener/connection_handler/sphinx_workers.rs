@@ -0,0 +1,317 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Pool of dedicated threads unwrapping the received sphinx packets, so that the cpu-heavy
+//! cryptographic operations do not compete with the networking tasks of the tokio runtime.
+
+use crate::config::SphinxProcessing;
+use crate::node::listener::connection_handler::packet_processing::{
+    MixProcessingResult, PacketProcessor,
+};
+use crate::node::packet_delayforwarder::PacketDelayForwardSender;
+use log::{debug, info, trace, warn};
+use nym_metrics::nanos;
+use nym_sphinx::forwarding::packet::MixPacket;
+use nym_sphinx::framing::packet::FramedNymPacket;
+use nym_sphinx::Delay as SphinxDelay;
+use std::io;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// Effective number of the workers and their placement, after resolving the configured values
+/// against the cores actually available to the process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct WorkersLayout {
+    workers: usize,
+    queue_size: usize,
+
+    /// Cores the workers are pinned to, assigned in round-robin fashion.
+    /// Empty if the pinning is disabled.
+    pinned_cores: Vec<usize>,
+}
+
+impl WorkersLayout {
+    pub(crate) fn resolve(config: &SphinxProcessing) -> Self {
+        let mut candidates = available_cores();
+        if let Some(numa_node) = config.numa_node {
+            match numa_node_cores(numa_node) {
+                Some(node_cores) => candidates.retain(|core| node_cores.contains(core)),
+                None => warn!("could not determine the cpu cores of NUMA node {numa_node}"),
+            }
+        }
+        if !config.cpu_cores.is_empty() {
+            for core in config.cpu_cores.iter().filter(|c| !candidates.contains(c)) {
+                warn!("cpu core {core} is not available to the sphinx workers - it's going to be ignored");
+            }
+            candidates.retain(|core| config.cpu_cores.contains(core));
+        }
+
+        let pinned_cores = if !config.cpu_pinning {
+            Vec::new()
+        } else if !cfg!(target_os = "linux") {
+            warn!("pinning the sphinx workers to cpu cores is only supported on linux");
+            Vec::new()
+        } else if candidates.is_empty() {
+            warn!("there are no cpu cores matching the configured constraints - the sphinx workers are not going to be pinned");
+            Vec::new()
+        } else {
+            candidates.clone()
+        };
+
+        let workers = if config.workers != 0 {
+            config.workers
+        } else if !pinned_cores.is_empty()
+            && (!config.cpu_cores.is_empty() || config.numa_node.is_some())
+        {
+            // the operator has explicitly dedicated those cores to the packet processing
+            pinned_cores.len()
+        } else {
+            // leave a core for the networking and the delay-forwarding
+            candidates.len().saturating_sub(1).max(1)
+        };
+
+        WorkersLayout {
+            workers,
+            queue_size: config.queue_size.max(1),
+            pinned_cores,
+        }
+    }
+
+    fn worker_core(&self, worker: usize) -> Option<usize> {
+        if self.pinned_cores.is_empty() {
+            None
+        } else {
+            Some(self.pinned_cores[worker % self.pinned_cores.len()])
+        }
+    }
+
+    fn log(&self) {
+        let placement = if self.pinned_cores.is_empty() {
+            "not pinned to any cpu cores".to_string()
+        } else {
+            format!("pinned to cpu cores {:?}", self.pinned_cores)
+        };
+        info!(
+            "using {} sphinx processing workers with queues of {} packets each, {placement} ({} cores are available to the process)",
+            self.workers,
+            self.queue_size,
+            available_cores().len(),
+        );
+    }
+}
+
+/// Handle for submitting the received packets to the worker pool.
+/// The workers exit once all the handles are dropped.
+#[derive(Clone)]
+pub(crate) struct SphinxWorkers {
+    senders: Arc<Vec<mpsc::Sender<FramedNymPacket>>>,
+    next_worker: Arc<AtomicUsize>,
+}
+
+impl SphinxWorkers {
+    pub(crate) fn start(
+        layout: &WorkersLayout,
+        packet_processor: PacketProcessor,
+        delay_forwarding_channel: PacketDelayForwardSender,
+    ) -> io::Result<Self> {
+        layout.log();
+
+        let mut senders = Vec::with_capacity(layout.workers);
+        for id in 0..layout.workers {
+            let (sender, receiver) = mpsc::channel(layout.queue_size);
+            let worker = SphinxWorker {
+                id,
+                packet_processor: packet_processor.clone(),
+                delay_forwarding_channel: delay_forwarding_channel.clone(),
+                packets: receiver,
+            };
+            let core = layout.worker_core(id);
+            thread::Builder::new()
+                .name(format!("sphinx-worker-{id}"))
+                .spawn(move || worker.run(core))?;
+            senders.push(sender);
+        }
+
+        Ok(SphinxWorkers {
+            senders: Arc::new(senders),
+            next_worker: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// Queues the packet for processing, waiting if the queue of the chosen worker is full.
+    pub(crate) async fn process(
+        &self,
+        packet: FramedNymPacket,
+    ) -> Result<(), mpsc::error::SendError<FramedNymPacket>> {
+        let worker = self.next_worker.fetch_add(1, Ordering::Relaxed) % self.senders.len();
+        self.senders[worker].send(packet).await
+    }
+}
+
+struct SphinxWorker {
+    id: usize,
+    packet_processor: PacketProcessor,
+    delay_forwarding_channel: PacketDelayForwardSender,
+    packets: mpsc::Receiver<FramedNymPacket>,
+}
+
+impl SphinxWorker {
+    fn delay_and_forward_packet(&self, mix_packet: MixPacket, delay: Option<SphinxDelay>) {
+        // determine instant at which packet should get forwarded. this way we minimise effect of
+        // being stuck in the queue [of the channel] to get inserted into the delay queue
+        let forward_instant = delay.map(|delay| Instant::now() + delay.to_duration());
+
+        // if unbounded_send() failed it means that the receiver channel was disconnected
+        // and hence something weird must have happened without a way of recovering
+        self.delay_forwarding_channel
+            .unbounded_send((mix_packet, forward_instant))
+            .expect("the delay-forwarder has died!");
+    }
+
+    fn handle_received_packet(&self, framed_sphinx_packet: FramedNymPacket) {
+        //
+        // TODO: here be replay attack detection - it will require similar key cache to the one in
+        // packet processor for vpn packets,
+        // question: can it also be per connection vs global?
+        //
+
+        #[cfg(feature = "pcap")]
+        let received = {
+            let packet = framed_sphinx_packet.packet();
+            nym_pcap::record_packet(nym_pcap::EventKind::Received, packet);
+            (nym_pcap::PacketId::for_packet(packet), packet.len())
+        };
+
+        // all processing such, key caching, etc. was done.
+        // however, if it was a forward hop, we still need to delay it
+        nanos!("handle_received_packet", {
+            match self.packet_processor.process_received(framed_sphinx_packet) {
+                Err(err) => {
+                    #[cfg(feature = "pcap")]
+                    nym_pcap::record(nym_pcap::EventKind::Dropped, received.0, None, received.1);
+                    debug!("We failed to process received sphinx packet - {err}")
+                }
+                Ok(res) => match res {
+                    MixProcessingResult::ForwardHop(forward_packet, delay) => {
+                        #[cfg(feature = "pcap")]
+                        nym_pcap::record(
+                            nym_pcap::EventKind::Processed,
+                            received.0,
+                            Some(nym_pcap::PacketId::for_packet(forward_packet.packet())),
+                            received.1,
+                        );
+                        self.delay_and_forward_packet(forward_packet, delay)
+                    }
+                    MixProcessingResult::FinalHop(..) => {
+                        warn!("Somehow processed a loop cover message that we haven't implemented yet!")
+                    }
+                },
+            }
+        })
+    }
+
+    fn run(mut self, core: Option<usize>) {
+        if let Some(core) = core {
+            match pin_current_thread(core) {
+                Ok(_) => trace!("sphinx worker {} pinned to cpu core {core}", self.id),
+                Err(err) => warn!(
+                    "failed to pin sphinx worker {} to cpu core {core}: {err}",
+                    self.id
+                ),
+            }
+        }
+
+        while let Some(packet) = self.packets.blocking_recv() {
+            self.handle_received_packet(packet)
+        }
+        trace!("sphinx worker {}: Exiting", self.id);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn pin_current_thread(core: usize) -> nix::Result<()> {
+    use nix::sched::{sched_setaffinity, CpuSet};
+    use nix::unistd::Pid;
+
+    let mut cpu_set = CpuSet::new();
+    cpu_set.set(core)?;
+    sched_setaffinity(Pid::from_raw(0), &cpu_set)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(_core: usize) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "cpu pinning is not supported on this platform",
+    ))
+}
+
+fn available_cores() -> Vec<usize> {
+    #[cfg(target_os = "linux")]
+    {
+        use nix::sched::{sched_getaffinity, CpuSet};
+        use nix::unistd::Pid;
+
+        if let Ok(cpu_set) = sched_getaffinity(Pid::from_raw(0)) {
+            return (0..CpuSet::count())
+                .filter(|&core| cpu_set.is_set(core).unwrap_or_default())
+                .collect();
+        }
+    }
+
+    let cores = thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(1);
+    (0..cores).collect()
+}
+
+fn numa_node_cores(numa_node: usize) -> Option<Vec<usize>> {
+    let path = format!("/sys/devices/system/node/node{numa_node}/cpulist");
+    parse_cpu_list(std::fs::read_to_string(path).ok()?.trim())
+}
+
+// parses the kernel cpu list format, e.g. "0-3,8,10-11"
+fn parse_cpu_list(raw: &str) -> Option<Vec<usize>> {
+    let mut cores = Vec::new();
+    for range in raw.split(',').filter(|r| !r.is_empty()) {
+        match range.split_once('-') {
+            Some((start, end)) => cores.extend(start.parse::<usize>().ok()?..=end.parse().ok()?),
+            None => cores.push(range.parse().ok()?),
+        }
+    }
+    Some(cores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_cpu_list() {
+        assert_eq!(
+            parse_cpu_list("0-3,8,10-11"),
+            Some(vec![0, 1, 2, 3, 8, 10, 11])
+        );
+        assert_eq!(parse_cpu_list("5"), Some(vec![5]));
+        assert_eq!(parse_cpu_list(""), Some(vec![]));
+        assert_eq!(parse_cpu_list("0-a"), None);
+    }
+
+    #[test]
+    fn explicit_workers_count_takes_precedence() {
+        let config = SphinxProcessing {
+            workers: 3,
+            queue_size: 0,
+            ..Default::default()
+        };
+        let layout = WorkersLayout::resolve(&config);
+        assert_eq!(layout.workers, 3);
+        assert_eq!(layout.queue_size, 1);
+        assert!(layout.pinned_cores.is_empty());
+        assert_eq!(layout.worker_core(2), None);
+    }
+}
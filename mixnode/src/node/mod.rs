@@ -6,6 +6,7 @@ use crate::error::MixnodeError;
 use crate::node::helpers::{load_identity_keys, load_sphinx_keys};
 use crate::node::http::HttpApiBuilder;
 use crate::node::listener::connection_handler::packet_processing::PacketProcessor;
+use crate::node::listener::connection_handler::sphinx_workers::{SphinxWorkers, WorkersLayout};
 use crate::node::listener::connection_handler::ConnectionHandler;
 use crate::node::listener::Listener;
 use crate::node::node_description::NodeDescription;
@@ -148,13 +149,18 @@ impl MixNode {
         node_stats_update_sender: node_statistics::UpdateSender,
         delay_forwarding_channel: PacketDelayForwardSender,
         shutdown: TaskClient,
-    ) {
+    ) -> Result<(), MixnodeError> {
         info!("Starting socket listener...");
 
         let packet_processor =
             PacketProcessor::new(self.sphinx_keypair.private_key(), node_stats_update_sender);
 
-        let connection_handler = ConnectionHandler::new(packet_processor, delay_forwarding_channel);
+        let workers_layout = WorkersLayout::resolve(&self.config.sphinx_processing);
+        let sphinx_workers =
+            SphinxWorkers::start(&workers_layout, packet_processor, delay_forwarding_channel)
+                .map_err(|source| MixnodeError::SphinxWorkersStartupFailure { source })?;
+
+        let connection_handler = ConnectionHandler::new(sphinx_workers);
 
         let listening_address = SocketAddr::new(
             self.config.mixnode.listening_address,
@@ -162,6 +168,7 @@ impl MixNode {
         );
 
         Listener::new(listening_address, shutdown).start(connection_handler);
+        Ok(())
     }
 
     fn start_packet_delay_forwarder(
@@ -259,6 +266,12 @@ impl MixNode {
     pub async fn run(&mut self) -> Result<(), MixnodeError> {
         info!("Starting nym mixnode");
 
+        #[cfg(feature = "pcap")]
+        nym_pcap::init_from_env(format!(
+            "mixnode-{}",
+            self.identity_keypair.public_key().to_base58_string()
+        ));
+
         if self.check_if_bonded().await {
             warn!("You seem to have bonded your mixnode before starting it - that's highly unrecommended as in the future it might result in slashing");
         }
@@ -281,7 +294,7 @@ impl MixNode {
             node_stats_update_sender,
             delay_forwarding_channel,
             shutdown.fork("Listener"),
-        );
+        )?;
         let atomic_verloc_results = self.start_verloc_measurements(shutdown.fork("VerlocMeasurer"));
 
         // Rocket handles shutdown on it's own, but its shutdown handling should be incorporated
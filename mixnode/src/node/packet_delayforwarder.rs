@@ -4,7 +4,7 @@
 use crate::node::node_statistics::UpdateSender;
 use futures::channel::mpsc;
 use futures::StreamExt;
-use nym_nonexhaustive_delayqueue::{Expired, NonExhaustiveDelayQueue};
+use nym_nonexhaustive_delayqueue::TimingWheelQueue;
 use nym_sphinx::forwarding::packet::MixPacket;
 use std::io;
 use tokio::time::Instant;
@@ -23,7 +23,7 @@ pub(crate) struct DelayForwarder<C>
 where
     C: nym_mixnet_client::SendWithoutResponse,
 {
-    delay_queue: NonExhaustiveDelayQueue<MixPacket>,
+    delay_queue: TimingWheelQueue<MixPacket>,
     mixnet_client: C,
     packet_sender: PacketDelayForwardSender,
     packet_receiver: PacketDelayForwardReceiver,
@@ -43,7 +43,7 @@ where
         let (packet_sender, packet_receiver) = mpsc::unbounded();
 
         DelayForwarder::<C> {
-            delay_queue: NonExhaustiveDelayQueue::new(),
+            delay_queue: TimingWheelQueue::new(),
             mixnet_client: client,
             packet_sender,
             packet_receiver,
@@ -61,31 +61,51 @@ where
         let packet_type = packet.packet_type();
         let packet = packet.into_packet();
 
+        #[cfg(feature = "pcap")]
+        let recorded = nym_pcap::is_recording()
+            .then(|| (nym_pcap::PacketId::for_packet(&packet), packet.len()));
+
         if let Err(err) = self
             .mixnet_client
             .send_without_response(next_hop, packet, packet_type)
         {
             if err.kind() == io::ErrorKind::WouldBlock {
+                #[cfg(feature = "pcap")]
+                if let Some((id, size)) = recorded {
+                    nym_pcap::record(nym_pcap::EventKind::Dropped, id, None, size)
+                }
+
                 // we only know for sure if we dropped a packet if our sending queue was full
                 // in any other case the connection might still be re-established (or created for the first time)
                 // and the packet might get sent, but we won't know about it
                 self.node_stats_update_sender
                     .report_dropped(next_hop.to_string())
             } else if err.kind() == io::ErrorKind::NotConnected {
+                #[cfg(feature = "pcap")]
+                if let Some((id, size)) = recorded {
+                    nym_pcap::record(nym_pcap::EventKind::Forwarded, id, None, size)
+                }
+
                 // let's give the benefit of the doubt and assume we manage to establish connection
                 self.node_stats_update_sender
                     .report_sent(next_hop.to_string());
             }
         } else {
+            #[cfg(feature = "pcap")]
+            if let Some((id, size)) = recorded {
+                nym_pcap::record(nym_pcap::EventKind::Forwarded, id, None, size)
+            }
+
             self.node_stats_update_sender
                 .report_sent(next_hop.to_string());
         }
     }
 
-    /// Upon packet being finished getting delayed, forward it to the mixnet.
-    fn handle_done_delaying(&mut self, packet: Expired<MixPacket>) {
-        let delayed_packet = packet.into_inner();
-        self.forward_packet(delayed_packet)
+    /// Upon packets being finished getting delayed, forward them to the mixnet.
+    fn handle_done_delaying(&mut self, packets: Vec<MixPacket>) {
+        for delayed_packet in packets {
+            self.forward_packet(delayed_packet)
+        }
     }
 
     fn handle_new_packet(&mut self, new_packet: (MixPacket, Option<Instant>)) {
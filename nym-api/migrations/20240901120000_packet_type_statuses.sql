@@ -0,0 +1,27 @@
+/*
+ * Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: GPL-3.0-only
+ */
+
+-- only the most recent result for each (node, packet type) pair is kept
+CREATE TABLE mixnode_packet_type_status
+(
+    mixnode_details_id INTEGER NOT NULL,
+    packet_type        INTEGER NOT NULL,
+    reliability        INTEGER NOT NULL,
+    timestamp          INTEGER NOT NULL,
+
+    PRIMARY KEY (mixnode_details_id, packet_type),
+    FOREIGN KEY (mixnode_details_id) REFERENCES mixnode_details (id)
+);
+
+CREATE TABLE gateway_packet_type_status
+(
+    gateway_details_id INTEGER NOT NULL,
+    packet_type        INTEGER NOT NULL,
+    reliability        INTEGER NOT NULL,
+    timestamp          INTEGER NOT NULL,
+
+    PRIMARY KEY (gateway_details_id, packet_type),
+    FOREIGN KEY (gateway_details_id) REFERENCES gateway_details (id)
+);
@@ -0,0 +1,15 @@
+/*
+ * Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: GPL-3.0-only
+ */
+
+-- only the most recent attestation of each node is kept
+CREATE TABLE node_location_attestation
+(
+    identity_key TEXT    NOT NULL PRIMARY KEY,
+    country_code TEXT    NOT NULL,
+    latitude     REAL,
+    longitude    REAL,
+    timestamp    INTEGER NOT NULL,
+    signature    TEXT    NOT NULL
+);
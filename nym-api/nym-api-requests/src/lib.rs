@@ -8,6 +8,7 @@ pub mod constants;
 pub mod ecash;
 mod helpers;
 pub mod models;
+pub mod node_location;
 pub mod nym_nodes;
 pub mod pagination;
 
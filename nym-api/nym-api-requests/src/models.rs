@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::helpers::unix_epoch;
+use crate::node_location::{AutonomousSystem, NodeLocation};
 use crate::nym_nodes::NodeRole;
 use crate::pagination::PaginatedResponse;
 use cosmwasm_std::{Addr, Coin, Decimal, Uint128};
@@ -126,6 +127,19 @@ pub struct NodePerformance {
     pub last_24h: Performance,
 }
 
+/// Reliability of a node when only taking packets of a particular type (sphinx or outfox) into consideration.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct PacketTypeReliability {
+    /// Type of the packets used for the measurement, e.g. `Mix` or `Outfox`.
+    pub packet_type: String,
+
+    /// Reliability of the node during the most recent network monitor run.
+    pub most_recent: u8,
+
+    /// Unix timestamp of the most recent network monitor run.
+    pub last_tested: i64,
+}
+
 #[derive(ToSchema)]
 #[schema(title = "MixNodeDetails")]
 pub struct MixNodeDetailsSchema {
@@ -151,6 +165,8 @@ pub struct MixNodeBondAnnotated {
     #[schema(value_type = String)]
     pub performance: Performance,
     pub node_performance: NodePerformance,
+    #[serde(default)]
+    pub packet_type_reliability: Vec<PacketTypeReliability>,
     pub estimated_operator_apy: Decimal,
     pub estimated_delegators_apy: Decimal,
     pub family: Option<FamilyHead>,
@@ -159,6 +175,12 @@ pub struct MixNodeBondAnnotated {
     // a rather temporary thing until we query self-described endpoints of mixnodes
     #[serde(default)]
     pub ip_addresses: Vec<IpAddr>,
+
+    #[serde(default)]
+    pub location: Option<NodeLocation>,
+
+    #[serde(default)]
+    pub autonomous_system: Option<AutonomousSystem>,
 }
 
 impl MixNodeBondAnnotated {
@@ -189,10 +211,18 @@ pub struct GatewayBondAnnotated {
     // NOTE: the performance field is deprecated in favour of node_performance
     pub performance: Performance,
     pub node_performance: NodePerformance,
+    #[serde(default)]
+    pub packet_type_reliability: Vec<PacketTypeReliability>,
     pub blacklisted: bool,
 
     #[serde(default)]
     pub ip_addresses: Vec<IpAddr>,
+
+    #[serde(default)]
+    pub location: Option<NodeLocation>,
+
+    #[serde(default)]
+    pub autonomous_system: Option<AutonomousSystem>,
 }
 
 impl GatewayBondAnnotated {
@@ -665,6 +695,114 @@ impl ApiStatus {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, schemars::JsonSchema, ToSchema)]
+pub struct ApiHealthBreakdownResponse {
+    pub status: ApiStatus,
+    pub uptime: u64,
+
+    /// Indicates whether the api is ready to serve requests, i.e. none of its dependencies are unhealthy.
+    pub ready: bool,
+
+    pub dependencies: ApiDependenciesHealth,
+}
+
+impl ApiHealthBreakdownResponse {
+    pub fn new(uptime: Duration, dependencies: ApiDependenciesHealth) -> Self {
+        ApiHealthBreakdownResponse {
+            status: ApiStatus::Up,
+            uptime: uptime.as_secs(),
+            ready: dependencies.all_ready(),
+            dependencies,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, schemars::JsonSchema, ToSchema)]
+pub struct ApiDependenciesHealth {
+    /// Status of the connection with the nyxd RPC the api is using for querying the chain.
+    pub nyxd: DependencyHealth,
+
+    /// Status of the local database.
+    pub database: DependencyHealth,
+
+    /// Status of the DKG and the zk-nym signing keys derived through it.
+    pub dkg: DependencyHealth,
+
+    /// Status of the network monitor, as determined by its recent test runs.
+    pub network_monitor: DependencyHealth,
+}
+
+impl ApiDependenciesHealth {
+    pub fn all_ready(&self) -> bool {
+        [&self.nyxd, &self.database, &self.dkg, &self.network_monitor]
+            .iter()
+            .all(|dependency| !dependency.status.is_unhealthy())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, schemars::JsonSchema, ToSchema)]
+pub struct DependencyHealth {
+    pub status: DependencyStatus,
+
+    /// Additional information explaining the status, such as the error returned by the dependency.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+impl DependencyHealth {
+    pub fn healthy() -> Self {
+        DependencyHealth {
+            status: DependencyStatus::Healthy,
+            details: None,
+        }
+    }
+
+    pub fn degraded(details: impl Into<String>) -> Self {
+        DependencyHealth {
+            status: DependencyStatus::Degraded,
+            details: Some(details.into()),
+        }
+    }
+
+    pub fn unhealthy(details: impl Into<String>) -> Self {
+        DependencyHealth {
+            status: DependencyStatus::Unhealthy,
+            details: Some(details.into()),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        DependencyHealth {
+            status: DependencyStatus::Disabled,
+            details: None,
+        }
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema, ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyStatus {
+    /// The dependency is working as expected.
+    Healthy,
+
+    /// The dependency is working, but some of the data it provides might be stale or incomplete.
+    Degraded,
+
+    /// The dependency is not working and the api can't properly serve requests relying on it.
+    Unhealthy,
+
+    /// The dependency is not used by this instance of the api.
+    Disabled,
+}
+
+impl DependencyStatus {
+    pub fn is_unhealthy(&self) -> bool {
+        matches!(self, DependencyStatus::Unhealthy)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, schemars::JsonSchema, ToSchema)]
 pub struct SignerInformationResponse {
     pub cosmos_address: String,
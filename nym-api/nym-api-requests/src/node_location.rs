@@ -0,0 +1,479 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::helpers::PlaceholderJsonSchemaImpl;
+use nym_crypto::asymmetric::identity;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+use utoipa::ToSchema;
+
+const ATTESTATION_DOMAIN: &str = "nym-node-location-attestation";
+
+#[derive(Debug, Error)]
+pub enum LocationAttestationError {
+    #[error("'{code}' is not a valid ISO 3166-1 alpha-2 country code")]
+    MalformedCountryCode { code: String },
+
+    #[error("the provided coordinates ({latitude}, {longitude}) are out of range")]
+    InvalidCoordinates { latitude: f64, longitude: f64 },
+
+    #[error("the provided identity key is malformed: {source}")]
+    MalformedIdentityKey {
+        #[from]
+        source: identity::Ed25519RecoveryError,
+    },
+
+    #[error("the attestation signature is invalid")]
+    InvalidSignature,
+}
+
+/// Location of a node as declared by its operator.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, ToSchema)]
+pub struct NodeLocationAttestation {
+    /// base58-encoded ed25519 identity key of the node
+    pub identity_key: String,
+
+    /// two-letter country code (ISO 3166-1 alpha-2)
+    pub two_letter_iso_country_code: String,
+
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+
+    /// unix timestamp at which the attestation has been created
+    pub timestamp: i64,
+}
+
+impl NodeLocationAttestation {
+    pub fn validate(&self) -> Result<(), LocationAttestationError> {
+        let code = &self.two_letter_iso_country_code;
+        if code.len() != 2 || !code.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(LocationAttestationError::MalformedCountryCode { code: code.clone() });
+        }
+
+        match (self.latitude, self.longitude) {
+            (None, None) => Ok(()),
+            (Some(latitude), Some(longitude))
+                if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) =>
+            {
+                Ok(())
+            }
+            (latitude, longitude) => Err(LocationAttestationError::InvalidCoordinates {
+                latitude: latitude.unwrap_or(f64::NAN),
+                longitude: longitude.unwrap_or(f64::NAN),
+            }),
+        }
+    }
+
+    /// Bytes that have to be signed with the node's identity key.
+    pub fn plaintext(&self) -> Vec<u8> {
+        let coordinate = |c: Option<f64>| c.map(|c| c.to_string()).unwrap_or_default();
+        format!(
+            "{ATTESTATION_DOMAIN}:{}:{}:{}:{}:{}",
+            self.identity_key,
+            self.two_letter_iso_country_code,
+            coordinate(self.latitude),
+            coordinate(self.longitude),
+            self.timestamp
+        )
+        .into_bytes()
+    }
+
+    pub fn sign(self, key: &identity::PrivateKey) -> SignedNodeLocationAttestation {
+        let signature = key.sign(self.plaintext());
+        SignedNodeLocationAttestation {
+            attestation: self,
+            signature,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, ToSchema)]
+pub struct SignedNodeLocationAttestation {
+    pub attestation: NodeLocationAttestation,
+
+    #[schemars(with = "PlaceholderJsonSchemaImpl")]
+    #[schema(value_type = String)]
+    pub signature: identity::Signature,
+}
+
+impl SignedNodeLocationAttestation {
+    /// Checks whether the attestation is well-formed and signed by the identity key it refers to.
+    pub fn verify(&self) -> Result<(), LocationAttestationError> {
+        self.attestation.validate()?;
+        let identity = identity::PublicKey::from_base58_string(&self.attestation.identity_key)?;
+        identity
+            .verify(self.attestation.plaintext(), &self.signature)
+            .map_err(|_| LocationAttestationError::InvalidSignature)
+    }
+}
+
+/// Location of a node as determined from a GeoIP lookup of its address.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, ToSchema)]
+pub struct GeoIpLocation {
+    pub two_letter_iso_country_code: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationVerification {
+    /// The operator attestation agrees with the GeoIP lookup.
+    Confirmed,
+
+    /// The operator has attested the location, but it couldn't have been cross-checked.
+    Unconfirmed,
+
+    /// The operator attestation contradicts the GeoIP lookup which takes precedence.
+    Disputed,
+
+    /// The location is solely based on the GeoIP lookup.
+    GeoIpOnly,
+}
+
+/// Location of a node reconciled from its operator attestation and a GeoIP lookup.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, ToSchema)]
+pub struct NodeLocation {
+    /// two-letter country code (ISO 3166-1 alpha-2)
+    pub two_letter_iso_country_code: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub verification: LocationVerification,
+
+    #[serde(default)]
+    pub attested_country_code: Option<String>,
+
+    #[serde(default)]
+    pub geoip_country_code: Option<String>,
+}
+
+impl NodeLocation {
+    pub fn reconcile(
+        attestation: Option<&NodeLocationAttestation>,
+        geoip: Option<&GeoIpLocation>,
+    ) -> Option<NodeLocation> {
+        let attested_country_code = attestation.map(|a| a.two_letter_iso_country_code.clone());
+        let geoip_country_code = geoip.map(|g| g.two_letter_iso_country_code.clone());
+
+        let (country, latitude, longitude, verification) = match (attestation, geoip) {
+            (None, None) => return None,
+            (Some(attested), None) => (
+                &attested.two_letter_iso_country_code,
+                attested.latitude,
+                attested.longitude,
+                LocationVerification::Unconfirmed,
+            ),
+            (None, Some(geoip)) => (
+                &geoip.two_letter_iso_country_code,
+                geoip.latitude,
+                geoip.longitude,
+                LocationVerification::GeoIpOnly,
+            ),
+            (Some(attested), Some(geoip))
+                if attested.two_letter_iso_country_code == geoip.two_letter_iso_country_code =>
+            {
+                // operators are likely to know the whereabouts of their machines better than GeoIP
+                let (latitude, longitude) = if attested.latitude.is_some() {
+                    (attested.latitude, attested.longitude)
+                } else {
+                    (geoip.latitude, geoip.longitude)
+                };
+                (
+                    &attested.two_letter_iso_country_code,
+                    latitude,
+                    longitude,
+                    LocationVerification::Confirmed,
+                )
+            }
+            (Some(_), Some(geoip)) => (
+                &geoip.two_letter_iso_country_code,
+                geoip.latitude,
+                geoip.longitude,
+                LocationVerification::Disputed,
+            ),
+        };
+
+        Some(NodeLocation {
+            two_letter_iso_country_code: country.clone(),
+            latitude,
+            longitude,
+            verification,
+            attested_country_code,
+            geoip_country_code,
+        })
+    }
+}
+
+/// Autonomous system announcing the address of a node as determined from a GeoIP ASN lookup.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema, ToSchema)]
+pub struct AutonomousSystem {
+    pub number: u32,
+    pub organization: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct NodeLocationResponse {
+    pub identity_key: String,
+    pub attestation: Option<SignedNodeLocationAttestation>,
+    pub geoip: Option<GeoIpLocation>,
+    pub location: Option<NodeLocation>,
+
+    #[serde(default)]
+    pub autonomous_system: Option<AutonomousSystem>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, ToSchema)]
+pub struct CountryShare {
+    /// two-letter country code (ISO 3166-1 alpha-2)
+    pub two_letter_iso_country_code: String,
+    pub nodes: usize,
+
+    /// fraction of all the nodes in the set located in this country
+    pub share: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, ToSchema)]
+pub struct AutonomousSystemShare {
+    pub autonomous_system: AutonomousSystem,
+    pub nodes: usize,
+
+    /// fraction of all the nodes in the set announced by this autonomous system
+    pub share: f64,
+}
+
+/// Distribution of a set of nodes across countries and autonomous systems.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, JsonSchema, ToSchema)]
+pub struct DiversityStatistics {
+    pub total_nodes: usize,
+
+    /// number of nodes whose country is not known
+    pub unlocated_nodes: usize,
+
+    /// number of nodes whose autonomous system is not known
+    pub unknown_autonomous_system_nodes: usize,
+
+    /// sorted by the number of nodes in descending order
+    pub countries: Vec<CountryShare>,
+
+    /// sorted by the number of nodes in descending order
+    pub autonomous_systems: Vec<AutonomousSystemShare>,
+
+    /// inverse Simpson index of the nodes with known country, i.e. the number of equally sized
+    /// countries that would have provided the same diversity
+    pub effective_countries: f64,
+
+    /// inverse Simpson index of the nodes with known autonomous system
+    pub effective_autonomous_systems: f64,
+}
+
+impl DiversityStatistics {
+    /// Computes the statistics out of the (optional) country codes and autonomous systems of the nodes.
+    pub fn compute<'a, I>(nodes: I) -> Self
+    where
+        I: IntoIterator<Item = (Option<&'a str>, Option<&'a AutonomousSystem>)>,
+    {
+        let mut total_nodes = 0;
+        let mut countries: HashMap<&str, usize> = HashMap::new();
+        let mut autonomous_systems: HashMap<&AutonomousSystem, usize> = HashMap::new();
+        for (country, autonomous_system) in nodes {
+            total_nodes += 1;
+            if let Some(country) = country {
+                *countries.entry(country).or_default() += 1;
+            }
+            if let Some(autonomous_system) = autonomous_system {
+                *autonomous_systems.entry(autonomous_system).or_default() += 1;
+            }
+        }
+
+        let share = |nodes: usize| nodes as f64 / total_nodes as f64;
+
+        let located_nodes = countries.values().sum::<usize>();
+        let effective_countries = inverse_simpson_index(countries.values(), located_nodes);
+        let mut countries = countries
+            .into_iter()
+            .map(|(country, nodes)| CountryShare {
+                two_letter_iso_country_code: country.to_string(),
+                nodes,
+                share: share(nodes),
+            })
+            .collect::<Vec<_>>();
+        countries.sort_by(|a, b| {
+            b.nodes.cmp(&a.nodes).then_with(|| {
+                a.two_letter_iso_country_code
+                    .cmp(&b.two_letter_iso_country_code)
+            })
+        });
+
+        let known_as_nodes = autonomous_systems.values().sum::<usize>();
+        let effective_autonomous_systems =
+            inverse_simpson_index(autonomous_systems.values(), known_as_nodes);
+        let mut autonomous_systems = autonomous_systems
+            .into_iter()
+            .map(|(autonomous_system, nodes)| AutonomousSystemShare {
+                autonomous_system: autonomous_system.clone(),
+                nodes,
+                share: share(nodes),
+            })
+            .collect::<Vec<_>>();
+        autonomous_systems.sort_by(|a, b| {
+            b.nodes
+                .cmp(&a.nodes)
+                .then_with(|| a.autonomous_system.number.cmp(&b.autonomous_system.number))
+        });
+
+        DiversityStatistics {
+            total_nodes,
+            unlocated_nodes: total_nodes - located_nodes,
+            unknown_autonomous_system_nodes: total_nodes - known_as_nodes,
+            countries,
+            autonomous_systems,
+            effective_countries,
+            effective_autonomous_systems,
+        }
+    }
+}
+
+fn inverse_simpson_index<'a>(counts: impl Iterator<Item = &'a usize>, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    let concentration = counts
+        .map(|&count| (count as f64 / total as f64).powi(2))
+        .sum::<f64>();
+    1.0 / concentration
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, ToSchema)]
+pub struct NodeDiversityResponse {
+    pub mixnodes: DiversityStatistics,
+    pub gateways: DiversityStatistics,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_crypto::asymmetric::identity::KeyPair;
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    fn test_rng() -> ChaCha20Rng {
+        ChaCha20Rng::from_seed([42u8; 32])
+    }
+
+    fn attestation(keys: &KeyPair, country: &str) -> NodeLocationAttestation {
+        NodeLocationAttestation {
+            identity_key: keys.public_key().to_base58_string(),
+            two_letter_iso_country_code: country.to_string(),
+            latitude: Some(52.52),
+            longitude: Some(13.405),
+            timestamp: 1700000000,
+        }
+    }
+
+    fn geoip(country: &str) -> GeoIpLocation {
+        GeoIpLocation {
+            two_letter_iso_country_code: country.to_string(),
+            latitude: Some(51.0),
+            longitude: Some(10.0),
+        }
+    }
+
+    #[test]
+    fn attestation_signature_is_verified() {
+        let mut rng = test_rng();
+        let keys = KeyPair::new(&mut rng);
+        let other = KeyPair::new(&mut rng);
+
+        let signed = attestation(&keys, "DE").sign(keys.private_key());
+        assert!(signed.verify().is_ok());
+
+        let forged = attestation(&keys, "DE").sign(other.private_key());
+        assert!(forged.verify().is_err());
+
+        let mut tampered = signed;
+        tampered.attestation.two_letter_iso_country_code = "FR".to_string();
+        assert!(tampered.verify().is_err());
+    }
+
+    #[test]
+    fn malformed_attestations_are_rejected() {
+        let mut rng = test_rng();
+        let keys = KeyPair::new(&mut rng);
+
+        let mut bad_country = attestation(&keys, "de");
+        assert!(bad_country.validate().is_err());
+        bad_country.two_letter_iso_country_code = "DEU".to_string();
+        assert!(bad_country.validate().is_err());
+
+        let mut bad_coordinates = attestation(&keys, "DE");
+        bad_coordinates.latitude = Some(91.0);
+        assert!(bad_coordinates.validate().is_err());
+        bad_coordinates.latitude = None;
+        assert!(bad_coordinates.validate().is_err());
+        bad_coordinates.longitude = None;
+        assert!(bad_coordinates.validate().is_ok());
+    }
+
+    #[test]
+    fn location_reconciliation() {
+        let mut rng = test_rng();
+        let keys = KeyPair::new(&mut rng);
+        let attested = attestation(&keys, "DE");
+
+        assert!(NodeLocation::reconcile(None, None).is_none());
+
+        let confirmed = NodeLocation::reconcile(Some(&attested), Some(&geoip("DE"))).unwrap();
+        assert_eq!(confirmed.verification, LocationVerification::Confirmed);
+        assert_eq!(confirmed.latitude, attested.latitude);
+
+        let disputed = NodeLocation::reconcile(Some(&attested), Some(&geoip("NL"))).unwrap();
+        assert_eq!(disputed.verification, LocationVerification::Disputed);
+        assert_eq!(disputed.two_letter_iso_country_code, "NL");
+        assert_eq!(disputed.attested_country_code.as_deref(), Some("DE"));
+
+        let unconfirmed = NodeLocation::reconcile(Some(&attested), None).unwrap();
+        assert_eq!(unconfirmed.verification, LocationVerification::Unconfirmed);
+        assert_eq!(unconfirmed.two_letter_iso_country_code, "DE");
+
+        let geoip_only = NodeLocation::reconcile(None, Some(&geoip("NL"))).unwrap();
+        assert_eq!(geoip_only.verification, LocationVerification::GeoIpOnly);
+    }
+
+    #[test]
+    fn diversity_statistics() {
+        let hetzner = AutonomousSystem {
+            number: 24940,
+            organization: Some("Hetzner Online GmbH".to_string()),
+        };
+        let ovh = AutonomousSystem {
+            number: 16276,
+            organization: None,
+        };
+
+        let stats = DiversityStatistics::compute([
+            (Some("DE"), Some(&hetzner)),
+            (Some("DE"), Some(&hetzner)),
+            (Some("FR"), Some(&ovh)),
+            (Some("FR"), None),
+            (None, None),
+        ]);
+        assert_eq!(stats.total_nodes, 5);
+        assert_eq!(stats.unlocated_nodes, 1);
+        assert_eq!(stats.unknown_autonomous_system_nodes, 2);
+        assert_eq!(stats.countries[0].two_letter_iso_country_code, "DE");
+        assert_eq!(stats.countries[1].two_letter_iso_country_code, "FR");
+        assert_eq!(stats.countries[1].share, 0.4);
+        assert_eq!(stats.autonomous_systems[0].autonomous_system, hetzner);
+        assert_eq!(stats.autonomous_systems[0].nodes, 2);
+        assert_eq!(stats.effective_countries, 2.0);
+        assert!(stats.effective_autonomous_systems < 2.0);
+
+        let no_nodes: [(Option<&str>, Option<&AutonomousSystem>); 0] = [];
+        assert_eq!(
+            DiversityStatistics::compute(no_nodes),
+            DiversityStatistics::default()
+        );
+    }
+}
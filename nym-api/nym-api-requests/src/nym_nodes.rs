@@ -48,6 +48,12 @@ pub enum NodeRole {
     Inactive,
 }
 
+impl NodeRole {
+    pub fn is_standby(&self) -> bool {
+        matches!(self, NodeRole::Standby)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, schemars::JsonSchema, ToSchema)]
 pub struct BasicEntryInformation {
     pub hostname: Option<String>,
@@ -107,6 +113,21 @@ impl SkimmedNode {
 
         base
     }
+
+    /// Converts a rewarded set mixnode that is not part of the active set, i.e. one that is not going
+    /// to be routing any traffic in the current epoch, but might get selected in the following ones.
+    pub fn from_standby_mixnode(annotated: &MixNodeBondAnnotated) -> Self {
+        SkimmedNode {
+            role: NodeRole::Standby,
+            ..annotated.into()
+        }
+    }
+
+    #[must_use]
+    pub fn with_role(mut self, role: NodeRole) -> Self {
+        self.role = role;
+        self
+    }
 }
 
 impl<'a> From<&'a MixNodeBondAnnotated> for SkimmedNode {
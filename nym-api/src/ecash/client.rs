@@ -8,14 +8,17 @@ use nym_coconut_dkg_common::dealer::{
     DealerDetails, DealerDetailsResponse, RegisteredDealerDetails,
 };
 use nym_coconut_dkg_common::dealing::{
-    DealerDealingsStatusResponse, DealingChunkInfo, DealingMetadata, DealingStatusResponse,
-    PartialContractDealing,
+    DealerDealingsStatusResponse, DealingChecksum, DealingChunkInfo, DealingMetadata,
+    DealingStatusResponse, PartialContractDealing,
 };
+use nym_coconut_dkg_common::types::DkgPublicParameters;
 use nym_coconut_dkg_common::types::{
     ChunkIndex, DealingIndex, EncodedBTEPublicKeyWithProof, Epoch, EpochId,
     PartialContractDealingData, State,
 };
-use nym_coconut_dkg_common::verification_key::{ContractVKShare, VerificationKeyShare};
+use nym_coconut_dkg_common::verification_key::{
+    ContractVKShare, MasterVerificationKey, VerificationKeyShare,
+};
 use nym_contracts_common::IdentityKey;
 use nym_dkg::Threshold;
 use nym_ecash_contract_common::blacklist::BlacklistedAccountResponse;
@@ -47,6 +50,8 @@ pub trait Client {
 
     async fn get_current_epoch(&self) -> Result<Epoch>;
 
+    async fn get_dkg_public_parameters(&self) -> Result<Option<DkgPublicParameters>>;
+
     async fn group_member(&self, addr: String) -> Result<MemberResponse>;
 
     async fn get_current_epoch_threshold(&self) -> Result<Option<Threshold>>;
@@ -124,6 +129,7 @@ pub trait Client {
         dealing_index: DealingIndex,
         chunks: Vec<DealingChunkInfo>,
         resharing: bool,
+        checksum: Option<DealingChecksum>,
     ) -> Result<ExecuteResult>;
 
     async fn submit_dealing_chunk(&self, chunk: PartialContractDealing) -> Result<ExecuteResult>;
@@ -133,4 +139,9 @@ pub trait Client {
         share: VerificationKeyShare,
         resharing: bool,
     ) -> Result<ExecuteResult>;
+
+    async fn submit_master_verification_key(
+        &self,
+        key: MasterVerificationKey,
+    ) -> Result<ExecuteResult>;
 }
@@ -7,13 +7,15 @@ use cw3::{ProposalResponse, Status, VoteResponse};
 use cw4::MemberResponse;
 use nym_coconut_dkg_common::dealer::{DealerDetails, DealerDetailsResponse};
 use nym_coconut_dkg_common::dealing::{
-    DealerDealingsStatusResponse, DealingChunkInfo, PartialContractDealing,
+    DealerDealingsStatusResponse, DealingChecksum, DealingChunkInfo, PartialContractDealing,
 };
 use nym_coconut_dkg_common::types::{
-    ChunkIndex, DealingIndex, EncodedBTEPublicKeyWithProof, Epoch, EpochId, NodeIndex,
-    PartialContractDealingData, State as ContractState,
+    ChunkIndex, DealingIndex, DkgPublicParameters, EncodedBTEPublicKeyWithProof, Epoch, EpochId,
+    NodeIndex, PartialContractDealingData, State as ContractState,
+};
+use nym_coconut_dkg_common::verification_key::{
+    ContractVKShare, MasterVerificationKey, VerificationKeyShare,
 };
-use nym_coconut_dkg_common::verification_key::{ContractVKShare, VerificationKeyShare};
 use nym_contracts_common::IdentityKey;
 use nym_dkg::Threshold;
 use nym_validator_client::nyxd::cosmwasm_client::logs::NODE_INDEX;
@@ -47,6 +49,12 @@ impl DkgClient {
         self.inner.get_current_epoch().await
     }
 
+    pub(crate) async fn get_public_parameters(
+        &self,
+    ) -> Result<Option<DkgPublicParameters>, EcashError> {
+        self.inner.get_dkg_public_parameters().await
+    }
+
     pub(crate) async fn get_contract_state(&self) -> Result<ContractState, EcashError> {
         self.inner.contract_state().await
     }
@@ -187,9 +195,10 @@ impl DkgClient {
         dealing_index: DealingIndex,
         chunks: Vec<DealingChunkInfo>,
         resharing: bool,
+        checksum: DealingChecksum,
     ) -> Result<(), EcashError> {
         self.inner
-            .submit_dealing_metadata(dealing_index, chunks, resharing)
+            .submit_dealing_metadata(dealing_index, chunks, resharing, Some(checksum))
             .await?;
         Ok(())
     }
@@ -212,6 +221,13 @@ impl DkgClient {
             .await
     }
 
+    pub(crate) async fn submit_master_verification_key(
+        &self,
+        key: MasterVerificationKey,
+    ) -> Result<ExecuteResult, EcashError> {
+        self.inner.submit_master_verification_key(key).await
+    }
+
     pub(crate) async fn vote_verification_key_share(
         &self,
         proposal_id: u64,
@@ -5,6 +5,7 @@ use crate::ecash::dkg::dealing::DealingGenerationError;
 use crate::ecash::dkg::key_derivation::KeyDerivationError;
 use crate::ecash::dkg::key_finalization::KeyFinalizationError;
 use crate::ecash::dkg::key_validation::KeyValidationError;
+use crate::ecash::dkg::master_key::MasterKeyAttestationError;
 use crate::ecash::dkg::public_key::PublicKeySubmissionError;
 use crate::ecash::error::EcashError;
 use std::path::PathBuf;
@@ -37,6 +38,23 @@ pub enum DkgError {
         source: EcashError,
     },
 
+    #[error("failed to query for the DKG public parameters: {source}")]
+    PublicParametersQueryFailure {
+        #[source]
+        source: EcashError,
+    },
+
+    #[error("the public parameters published in the DKG contract are malformed: {source}")]
+    MalformedPublicParameters {
+        #[source]
+        source: nym_dkg::error::DkgError,
+    },
+
+    #[error(
+        "the public parameters published in the DKG contract differ from the ones already in use"
+    )]
+    MismatchedPublicParameters,
+
     #[error("this API is currently not member of the DKG group and thus can't participate in the process")]
     NotInGroup,
 
@@ -70,6 +88,12 @@ pub enum DkgError {
         source: KeyFinalizationError,
     },
 
+    #[error("failed to attest to the master verification key in the DKG contract: {source}")]
+    MasterKeyAttestationFailure {
+        #[source]
+        source: MasterKeyAttestationError,
+    },
+
     #[error("failed to advance the DKG state: {source}")]
     StateAdvancementFailure {
         #[source]
@@ -4,6 +4,7 @@
 use crate::ecash::dkg::client::DkgClient;
 use crate::ecash::dkg::controller::error::DkgError;
 use crate::ecash::dkg::state::{PersistentState, State};
+use crate::ecash::dkg::use_published_params;
 use crate::ecash::keys::KeyPair as CoconutKeyPair;
 use crate::nyxd;
 use crate::support::config;
@@ -28,6 +29,9 @@ pub(crate) struct DkgController<R = OsRng> {
     pub(crate) state: State,
     pub(super) rng: R,
     polling_rate: Duration,
+
+    // indicates whether we have already checked the public parameters published in the contract
+    public_parameters_loaded: bool,
 }
 
 impl<R: RngCore + CryptoRng + Clone> DkgController<R> {
@@ -63,6 +67,7 @@ impl<R: RngCore + CryptoRng + Clone> DkgController<R> {
             ),
             rng,
             polling_rate: config.debug.dkg_contract_polling_rate,
+            public_parameters_loaded: false,
         })
     }
 
@@ -84,6 +89,35 @@ impl<R: RngCore + CryptoRng + Clone> DkgController<R> {
             .map_err(|source| DkgError::EpochQueryFailure { source })
     }
 
+    async fn ensure_public_parameters(&mut self) -> Result<(), DkgError> {
+        if self.public_parameters_loaded {
+            return Ok(());
+        }
+
+        let published = self
+            .dkg_client
+            .get_public_parameters()
+            .await
+            .map_err(|source| DkgError::PublicParametersQueryFailure { source })?;
+
+        match published {
+            None => {
+                warn!("the DKG contract does not contain any public parameters - the locally generated ones will be used");
+            }
+            Some(published) => {
+                let params = nym_dkg::bte::Params::try_from(&published.bte_params)
+                    .map_err(|source| DkgError::MalformedPublicParameters { source })?;
+                if !use_published_params(params) {
+                    return Err(DkgError::MismatchedPublicParameters);
+                }
+                debug!("using the public parameters published in the DKG contract");
+            }
+        }
+
+        self.public_parameters_loaded = true;
+        Ok(())
+    }
+
     async fn ensure_group_member(&self) -> Result<(), DkgError> {
         let membership_response = self
             .dkg_client
@@ -191,6 +225,12 @@ impl<R: RngCore + CryptoRng + Clone> DkgController<R> {
             self.state.in_progress_state_mut(epoch_id).unwrap().entered = true;
         }
 
+        // (the attestation is a no-op if it has already been performed)
+        self.attest_master_verification_key(epoch_id)
+            .await
+            .map_err(|source| DkgError::MasterKeyAttestationFailure { source })?;
+        self.persist_state()?;
+
         // so at this point we don't need to be polling the contract so often anymore, but we can't easily
         // adjust the existing interval.
         // however, what we can do is just wait here for a bit each iteration
@@ -221,6 +261,11 @@ impl<R: RngCore + CryptoRng + Clone> DkgController<R> {
 
         let epoch = self.current_epoch().await?;
 
+        // the parameters are only published once the DKG has been initiated
+        if epoch.state != EpochState::WaitingInitialisation {
+            self.ensure_public_parameters().await?;
+        }
+
         match epoch.state {
             EpochState::WaitingInitialisation => self.handle_awaiting_initialisation().await?,
             EpochState::PublicKeySubmission { resharing } => {
@@ -342,6 +387,7 @@ impl DkgController {
             state,
             rng: crate::ecash::tests::fixtures::test_rng([1u8; 32]),
             polling_rate: Default::default(),
+            public_parameters_loaded: false,
         }
     }
 
@@ -357,6 +403,7 @@ impl DkgController {
             state,
             rng,
             polling_rate: Default::default(),
+            public_parameters_loaded: false,
         }
     }
 }
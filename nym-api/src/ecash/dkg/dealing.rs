@@ -7,7 +7,9 @@ use crate::ecash::dkg::controller::DkgController;
 use crate::ecash::error::EcashError;
 use crate::ecash::keys::KeyPairWithEpoch;
 use log::debug;
-use nym_coconut_dkg_common::dealing::{chunk_dealing, DealingChunkInfo, MAX_DEALING_CHUNK_SIZE};
+use nym_coconut_dkg_common::dealing::{
+    chunk_dealing, dealing_checksum, DealingChunkInfo, MAX_DEALING_CHUNK_SIZE,
+};
 use nym_coconut_dkg_common::types::{DealingIndex, EpochId};
 use nym_dkg::{Dealing, Scalar};
 use rand::{CryptoRng, RngCore};
@@ -226,13 +228,14 @@ impl<R: RngCore + CryptoRng> DkgController<R> {
 
         // construct metadata
         let chunk_info = DealingChunkInfo::construct(bytes.len(), Self::DEALING_CHUNK_SIZE);
+        let checksum = dealing_checksum(&bytes);
 
         let total_chunks = chunk_info.len();
         debug!("dealing at index {dealing_index} has been chunked into {total_chunks} pieces",);
 
         // submit the metadata
         self.dkg_client
-            .submit_dealing_metadata(dealing_index, chunk_info, resharing)
+            .submit_dealing_metadata(dealing_index, chunk_info, resharing, checksum)
             .await?;
 
         // actually chunk the dealing and submit the chunks
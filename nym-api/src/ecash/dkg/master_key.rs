@@ -0,0 +1,80 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::ecash::dkg::controller::DkgController;
+use crate::ecash::error::EcashError;
+use nym_coconut_dkg_common::types::EpochId;
+use nym_compact_ecash::error::CompactEcashError;
+use nym_compact_ecash::{aggregate_verification_keys, Base58, VerificationKeyAuth};
+use rand::{CryptoRng, RngCore};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum MasterKeyAttestationError {
+    #[error(transparent)]
+    CoconutError(#[from] EcashError),
+
+    #[error("there are no verified key shares for epoch {epoch_id}")]
+    NoVerifiedShares { epoch_id: EpochId },
+
+    #[error("failed to aggregate the master verification key: {source}")]
+    AggregationFailure {
+        #[source]
+        source: CompactEcashError,
+    },
+}
+
+impl<R: RngCore + CryptoRng> DkgController<R> {
+    /// Aggregate all verified key shares of the epoch and attest to the result in the DKG contract
+    /// so that the verifiers could just query for the master key rather than aggregate it themselves.
+    pub(crate) async fn attest_master_verification_key(
+        &mut self,
+        epoch_id: EpochId,
+    ) -> Result<(), MasterKeyAttestationError> {
+        let in_progress_state = self.state.in_progress_state(epoch_id)?;
+        if in_progress_state.master_key_attested {
+            return Ok(());
+        }
+
+        let shares = self
+            .dkg_client
+            .get_verification_key_shares(epoch_id)
+            .await?;
+
+        let mut keys = Vec::new();
+        let mut indices = Vec::new();
+        for share in shares.into_iter().filter(|share| share.verified) {
+            // verified shares must have been valid, but don't let a single bad one prevent attestation
+            match VerificationKeyAuth::try_from_bs58(&share.share) {
+                Ok(key) => {
+                    keys.push(key);
+                    indices.push(share.node_index);
+                }
+                Err(err) => {
+                    warn!(
+                        "the verified key share of {} is malformed: {err}",
+                        share.owner
+                    )
+                }
+            }
+        }
+
+        if keys.is_empty() {
+            return Err(MasterKeyAttestationError::NoVerifiedShares { epoch_id });
+        }
+
+        let master_key = aggregate_verification_keys(&keys, Some(&indices))
+            .map_err(|source| MasterKeyAttestationError::AggregationFailure { source })?;
+
+        self.dkg_client
+            .submit_master_verification_key(master_key.to_bs58())
+            .await?;
+
+        self.state
+            .in_progress_state_mut(epoch_id)?
+            .master_key_attested = true;
+        info!("DKG: attested to the master verification key of epoch {epoch_id}");
+
+        Ok(())
+    }
+}
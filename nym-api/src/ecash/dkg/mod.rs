@@ -3,11 +3,21 @@
 
 use std::sync::OnceLock;
 
+static PARAMS: OnceLock<nym_dkg::bte::Params> = OnceLock::new();
+
 pub(crate) fn params() -> &'static nym_dkg::bte::Params {
-    static PARAMS: OnceLock<nym_dkg::bte::Params> = OnceLock::new();
     PARAMS.get_or_init(nym_dkg::bte::setup)
 }
 
+/// Attempts to use the parameters published in the DKG contract for all subsequent operations.
+/// If some parameters have already been in use, returns whether they're identical to the published ones.
+pub(crate) fn use_published_params(published: nym_dkg::bte::Params) -> bool {
+    match PARAMS.set(published) {
+        Ok(_) => true,
+        Err(published) => params().to_bytes() == published.to_bytes(),
+    }
+}
+
 pub(crate) mod client;
 pub(crate) mod controller;
 pub(crate) mod dealing;
@@ -15,6 +25,7 @@ mod helpers;
 pub(crate) mod key_derivation;
 pub(crate) mod key_finalization;
 pub(crate) mod key_validation;
+pub(crate) mod master_key;
 pub(crate) mod public_key;
 pub(crate) mod state;
 
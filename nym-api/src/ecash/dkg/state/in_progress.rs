@@ -8,4 +8,8 @@ use serde::{Deserialize, Serialize};
 pub struct InProgressState {
     // indicate whether this node has been in this state before and performed any one-off tasks
     pub(crate) entered: bool,
+
+    // indicate whether this node has already attested to the aggregated master verification key
+    #[serde(default)]
+    pub(crate) master_key_attested: bool,
 }
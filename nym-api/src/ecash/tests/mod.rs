@@ -20,15 +20,18 @@ use nym_coconut_dkg_common::dealer::{
     DealerDetails, DealerDetailsResponse, DealerType, RegisteredDealerDetails,
 };
 use nym_coconut_dkg_common::dealing::{
-    DealerDealingsStatusResponse, DealingChunkInfo, DealingMetadata, DealingStatus,
-    DealingStatusResponse, PartialContractDealing,
+    DealerDealingsStatusResponse, DealingChecksum, DealingChunkInfo, DealingMetadata,
+    DealingStatus, DealingStatusResponse, PartialContractDealing,
 };
 use nym_coconut_dkg_common::event_attributes::{DKG_PROPOSAL_ID, NODE_INDEX};
 use nym_coconut_dkg_common::types::{
-    ChunkIndex, DealerRegistrationDetails, DealingIndex, EncodedBTEPublicKeyWithProof, Epoch,
-    EpochId, EpochState, PartialContractDealingData, State as ContractState,
+    ChunkIndex, DealerRegistrationDetails, DealingIndex, DkgPublicParameters,
+    EncodedBTEPublicKeyWithProof, Epoch, EpochId, EpochState, PartialContractDealingData,
+    State as ContractState,
+};
+use nym_coconut_dkg_common::verification_key::{
+    ContractVKShare, MasterVerificationKey, VerificationKeyShare,
 };
-use nym_coconut_dkg_common::verification_key::{ContractVKShare, VerificationKeyShare};
 use nym_compact_ecash::BlindedSignature;
 use nym_compact_ecash::{ttp_keygen, VerificationKeyAuth};
 use nym_contracts_common::IdentityKey;
@@ -109,9 +112,10 @@ impl Dealing {
     pub(crate) fn new_metadata_submission(
         dealing_index: DealingIndex,
         chunks: Vec<DealingChunkInfo>,
+        checksum: Option<DealingChecksum>,
     ) -> Self {
         Dealing {
-            metadata: DealingMetadata::new(dealing_index, chunks),
+            metadata: DealingMetadata::new(dealing_index, chunks, checksum),
             chunks: Default::default(),
         }
     }
@@ -156,6 +160,11 @@ pub(crate) struct FakeDkgContractState {
     pub(crate) epoch: Epoch,
     pub(crate) contract_state: ContractState,
     pub(crate) threshold: HashMap<EpochId, Threshold>,
+
+    // map of epoch id -> dealer -> attested master verification key
+    pub(crate) master_key_attestations: HashMap<EpochId, HashMap<String, MasterVerificationKey>>,
+
+    pub(crate) public_parameters: Option<DkgPublicParameters>,
 }
 
 impl FakeDkgContractState {
@@ -338,6 +347,8 @@ impl Default for FakeChainState {
                 dealings: HashMap::new(),
                 verification_shares: HashMap::new(),
                 threshold: HashMap::new(),
+                master_key_attestations: HashMap::new(),
+                public_parameters: None,
             },
             group_contract: FakeGroupContractState {
                 address: group_contract,
@@ -604,6 +615,16 @@ impl super::client::Client for DummyClient {
         Ok(self.state.lock().unwrap().dkg_contract.epoch)
     }
 
+    async fn get_dkg_public_parameters(&self) -> Result<Option<DkgPublicParameters>> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .dkg_contract
+            .public_parameters
+            .clone())
+    }
+
     async fn group_member(&self, addr: String) -> Result<MemberResponse> {
         Ok(self
             .state
@@ -964,6 +985,7 @@ impl super::client::Client for DummyClient {
         dealing_index: DealingIndex,
         chunks: Vec<DealingChunkInfo>,
         _resharing: bool,
+        checksum: Option<DealingChecksum>,
     ) -> Result<ExecuteResult> {
         let mut guard = self.state.lock().unwrap();
         let current_epoch = guard.dkg_contract.epoch.epoch_id;
@@ -979,7 +1001,7 @@ impl super::client::Client for DummyClient {
             .or_default();
         dealer_dealings.insert(
             dealing_index,
-            Dealing::new_metadata_submission(dealing_index, chunks),
+            Dealing::new_metadata_submission(dealing_index, chunks, checksum),
         );
 
         let transaction_hash = guard._counters.next_tx_hash();
@@ -1104,6 +1126,30 @@ impl super::client::Client for DummyClient {
             gas_info: Default::default(),
         })
     }
+
+    async fn submit_master_verification_key(
+        &self,
+        key: MasterVerificationKey,
+    ) -> Result<ExecuteResult> {
+        let mut chain = self.state.lock().unwrap();
+        let epoch_id = chain.dkg_contract.epoch.epoch_id;
+
+        chain
+            .dkg_contract
+            .master_key_attestations
+            .entry(epoch_id)
+            .or_default()
+            .insert(self.validator_address.to_string(), key);
+
+        let transaction_hash = chain._counters.next_tx_hash();
+        Ok(ExecuteResult {
+            logs: vec![],
+            msg_responses: Default::default(),
+            events: Default::default(),
+            transaction_hash,
+            gas_info: Default::default(),
+        })
+    }
 }
 
 #[derive(Clone)]
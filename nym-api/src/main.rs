@@ -13,6 +13,7 @@ use crate::ecash::dkg::controller::keys::{
 use crate::epoch_operations::RewardedSetUpdater;
 use crate::network::models::NetworkDetails;
 use crate::node_describe_cache::DescribedNodes;
+use crate::node_location::GeoIpResolver;
 use crate::node_status_api::uptime_updater::HistoricalUptimeUpdater;
 use crate::support::caching::cache::SharedCache;
 use crate::support::cli;
@@ -38,6 +39,7 @@ mod epoch_operations;
 pub(crate) mod network;
 mod network_monitor;
 pub(crate) mod node_describe_cache;
+pub(crate) mod node_location;
 pub(crate) mod node_status_api;
 pub(crate) mod nym_contract_cache;
 pub(crate) mod nym_nodes;
@@ -121,6 +123,7 @@ async fn start_nym_api_tasks(config: Config) -> anyhow::Result<ShutdownHandles>
         storage::NymApiStorage::init(&config.node_status_api.storage_paths.database_path).await?
     };
     let described_nodes_state = rocket.state::<SharedCache<DescribedNodes>>().unwrap();
+    let geoip_resolver = rocket.state::<GeoIpResolver>().unwrap();
 
     // start note describe cache refresher
     // we should be doing the below, but can't due to our current startup structure
@@ -147,6 +150,7 @@ async fn start_nym_api_tasks(config: Config) -> anyhow::Result<ShutdownHandles>
         nym_contract_cache_state,
         node_status_cache_state,
         storage.to_owned(),
+        geoip_resolver.clone(),
         nym_contract_cache_listener,
         &shutdown,
     );
@@ -9,7 +9,7 @@ use crate::network_monitor::test_packet::NodeTestMessage;
 use crate::network_monitor::test_route::TestRoute;
 use crate::storage::NymApiStorage;
 use crate::support::config;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use nym_sphinx::params::PacketType;
 use nym_sphinx::receiver::MessageReceiver;
 use nym_task::TaskClient;
@@ -46,7 +46,9 @@ pub(super) struct Monitor<R: MessageReceiver + Send + 'static> {
     /// a monitor test run to be valid.
     minimum_test_routes: usize,
 
-    packet_type: PacketType,
+    /// Packet types used for testing the network. Each node receives the same number of test
+    /// packets of every type, so that per-type reliability could be determined.
+    packet_types: Vec<PacketType>,
 }
 
 impl<R: MessageReceiver + Send> Monitor<R> {
@@ -57,8 +59,14 @@ impl<R: MessageReceiver + Send> Monitor<R> {
         received_processor: ReceivedProcessor<R>,
         summary_producer: SummaryProducer,
         node_status_storage: NymApiStorage,
-        packet_type: PacketType,
     ) -> Self {
+        // we have to test the network with at least a single packet type
+        let packet_types = if config.debug.test_packet_types.is_empty() {
+            vec![PacketType::default()]
+        } else {
+            config.debug.test_packet_types.clone()
+        };
+
         Monitor {
             test_nonce: 1,
             packet_preparer,
@@ -72,7 +80,7 @@ impl<R: MessageReceiver + Send> Monitor<R> {
             route_test_packets: config.debug.route_test_packets,
             test_routes: config.debug.test_routes,
             minimum_test_routes: config.debug.minimum_test_routes,
-            packet_type,
+            packet_types,
         }
     }
 
@@ -99,6 +107,15 @@ impl<R: MessageReceiver + Send> Monitor<R> {
             // TODO: slightly more graceful shutdown here
             process::exit(1);
         }
+
+        // per packet type results are only informative, so failing to store them is not fatal
+        if let Err(err) = self
+            .node_status_storage
+            .insert_packet_type_results(test_summary.packet_type_results)
+            .await
+        {
+            warn!("Failed to submit per packet type results to the database - {err}");
+        }
     }
 
     fn analyse_received_test_route_packets(
@@ -133,7 +150,7 @@ impl<R: MessageReceiver + Send> Monitor<R> {
             let gateway_packets = packet_preparer.prepare_test_route_viability_packets(
                 &route,
                 self.route_test_packets,
-                self.packet_type,
+                &self.packet_types,
             );
             packets.push(gateway_packets);
         }
@@ -152,20 +169,18 @@ impl<R: MessageReceiver + Send> Monitor<R> {
             results.entry(route.id()).or_insert(0);
         }
 
+        let expected = self.route_test_packets * self.packet_types.len();
         for entry in results.iter() {
-            if *entry.1 == self.route_test_packets {
+            if *entry.1 == expected {
                 info!("✔️ {} succeeded", entry.0)
             } else {
-                info!(
-                    "❌️ {} failed ({}/{} received)",
-                    entry.0, entry.1, self.route_test_packets
-                )
+                info!("❌️ {} failed ({}/{} received)", entry.0, entry.1, expected)
             }
         }
 
         results
             .into_iter()
-            .map(|(k, v)| (k, v == self.route_test_packets))
+            .map(|(k, v)| (k, v == expected))
             .collect()
     }
 
@@ -241,7 +256,7 @@ impl<R: MessageReceiver + Send> Monitor<R> {
         info!("Generating test mix packets for all the network nodes...");
         let prepared_packets = self
             .packet_preparer
-            .prepare_test_packets(self.test_nonce, routes, self.packet_type)
+            .prepare_test_packets(self.test_nonce, routes, &self.packet_types)
             .await;
 
         let total_sent = prepared_packets
@@ -279,6 +294,7 @@ impl<R: MessageReceiver + Send> Monitor<R> {
             prepared_packets.invalid_mixnodes,
             prepared_packets.invalid_gateways,
             routes,
+            &self.packet_types,
         );
 
         let report = summary.create_report(total_sent, total_received);
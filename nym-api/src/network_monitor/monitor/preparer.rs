@@ -104,6 +104,7 @@ impl PacketPreparer {
         &self,
         test_route: &TestRoute,
         self_address: Option<Recipient>,
+        packet_type: PacketType,
     ) -> NodeTester<ThreadRng> {
         let rng = thread_rng();
         NodeTester::new(
@@ -116,17 +117,26 @@ impl PacketPreparer {
             DEFAULT_AVERAGE_ACK_DELAY,
             self.ack_key.clone(),
         )
+        .with_packet_type(packet_type)
     }
 
     // when we're testing mixnodes, the recipient is going to stay constant, so we can specify it ahead of time
-    fn ephemeral_mix_tester(&self, test_route: &TestRoute) -> NodeTester<ThreadRng> {
+    fn ephemeral_mix_tester(
+        &self,
+        test_route: &TestRoute,
+        packet_type: PacketType,
+    ) -> NodeTester<ThreadRng> {
         let self_address = self.create_packet_sender(test_route.gateway());
-        self.ephemeral_tester(test_route, Some(self_address))
+        self.ephemeral_tester(test_route, Some(self_address), packet_type)
     }
 
     #[allow(dead_code)]
-    fn ephemeral_gateway_tester(&self, test_route: &TestRoute) -> NodeTester<ThreadRng> {
-        self.ephemeral_tester(test_route, None)
+    fn ephemeral_gateway_tester(
+        &self,
+        test_route: &TestRoute,
+        packet_type: PacketType,
+    ) -> NodeTester<ThreadRng> {
+        self.ephemeral_tester(test_route, None, packet_type)
     }
 
     async fn topology_wait_backoff(&self, initialisation_backoff: Duration) {
@@ -299,22 +309,27 @@ impl PacketPreparer {
         &mut self,
         route: &TestRoute,
         num: usize,
-        // TODO: Maybe do this
-        _packet_type: PacketType,
+        packet_types: &[PacketType],
     ) -> GatewayPackets {
-        let mut tester = self.ephemeral_mix_tester(route);
         let topology = route.topology();
-        let plaintexts = route.self_test_messages(num);
-
-        // the unwrap here is fine as:
-        // 1. the topology is definitely valid (otherwise we wouldn't be here)
-        // 2. the recipient is specified (by calling **mix**_tester)
-        // 3. the test message is not too long, i.e. when serialized it will fit in a single sphinx packet
-        let mix_packets = plaintexts
-            .into_iter()
-            .map(|p| tester.wrap_plaintext_data(p, topology, None).unwrap())
-            .map(MixPacket::from)
-            .collect();
+
+        // each route has to be viable for every packet type we're going to test the network with
+        let mut mix_packets = Vec::with_capacity(num * packet_types.len());
+        for &packet_type in packet_types {
+            let mut tester = self.ephemeral_mix_tester(route, packet_type);
+            let plaintexts = route.self_test_messages(num, packet_type);
+
+            // the unwrap here is fine as:
+            // 1. the topology is definitely valid (otherwise we wouldn't be here)
+            // 2. the recipient is specified (by calling **mix**_tester)
+            // 3. the test message is not too long, i.e. when serialized it will fit in a single sphinx packet
+            mix_packets.extend(
+                plaintexts
+                    .into_iter()
+                    .map(|p| tester.wrap_plaintext_data(p, topology, None).unwrap())
+                    .map(MixPacket::from),
+            );
+        }
 
         GatewayPackets::new(
             route.gateway_clients_address(),
@@ -370,8 +385,7 @@ impl PacketPreparer {
         &mut self,
         test_nonce: u64,
         test_routes: &[TestRoute],
-        // TODO: Maybe do this
-        _packet_type: PacketType,
+        packet_types: &[PacketType],
     ) -> PreparedPackets {
         // only test mixnodes that are rewarded, i.e. that will be rewarded in this interval.
         // (remember that "idle" nodes are still part of that set)
@@ -385,80 +399,249 @@ impl PacketPreparer {
         let tested_mixnodes = mixnodes.iter().map(|node| node.into()).collect::<Vec<_>>();
         let tested_gateways = gateways.iter().map(|node| node.into()).collect::<Vec<_>>();
 
-        let packets_to_create = (test_routes.len() * self.per_node_test_packets)
-            * (tested_mixnodes.len() + tested_gateways.len());
+        let packets_to_create =
+            (test_routes.len() * packet_types.len() * self.per_node_test_packets)
+                * (tested_mixnodes.len() + tested_gateways.len());
         info!("Need to create {} mix packets", packets_to_create);
 
+        let packets = self.prepare_nodes_test_packets(
+            test_nonce,
+            test_routes,
+            packet_types,
+            &mixnodes,
+            &gateways,
+        );
+
+        PreparedPackets {
+            packets,
+            tested_mixnodes,
+            tested_gateways,
+            invalid_mixnodes,
+            invalid_gateways,
+        }
+    }
+
+    /// Creates test packets of each of the provided types for every node, sent through each of
+    /// the test routes, grouped by the gateways they have to be sent through.
+    fn prepare_nodes_test_packets(
+        &mut self,
+        test_nonce: u64,
+        test_routes: &[TestRoute],
+        packet_types: &[PacketType],
+        mixnodes: &[mix::Node],
+        gateways: &[gateway::Node],
+    ) -> Vec<GatewayPackets> {
         let mut all_gateway_packets = HashMap::new();
 
         // for each test route...
         for test_route in test_routes {
-            let route_ext = test_route.test_message_ext(test_nonce);
             let gateway_address = test_route.gateway_clients_address();
             let gateway_identity = test_route.gateway_identity();
 
-            let mut mix_tester = self.ephemeral_mix_tester(test_route);
-
-            // generate test packets for mixnodes
-            //
-            // the unwrap here is fine as:
-            // 1. the topology is definitely valid (otherwise we wouldn't be here)
-            // 2. the recipient is specified (by calling **mix**_tester)
-            // 3. the test message is not too long, i.e. when serialized it will fit in a single sphinx packet
-            let mixnode_test_packets = mix_tester
-                .mixnodes_test_packets(
-                    &mixnodes,
-                    route_ext,
-                    self.per_node_test_packets as u32,
-                    None,
-                )
-                .unwrap();
-            let mix_packets = mixnode_test_packets.into_iter().map(Into::into).collect();
-
-            let gateway_packets = all_gateway_packets
-                .entry(gateway_identity.to_bytes())
-                .or_insert_with(|| GatewayPackets::empty(gateway_address, gateway_identity));
-            gateway_packets.push_packets(mix_packets);
-
-            // and generate test packets for gateways (note the variable recipient)
-            for gateway in &gateways {
-                let recipient = self.create_packet_sender(gateway);
-                let gateway_identity = gateway.identity_key;
-                let gateway_address = gateway.clients_address();
+            // ... and each packet type...
+            for &packet_type in packet_types {
+                let route_ext = test_route.test_message_ext(test_nonce, packet_type);
+                let mut mix_tester = self.ephemeral_mix_tester(test_route, packet_type);
 
+                // generate test packets for mixnodes
+                //
                 // the unwrap here is fine as:
                 // 1. the topology is definitely valid (otherwise we wouldn't be here)
-                // 2. the recipient is specified
+                // 2. the recipient is specified (by calling **mix**_tester)
                 // 3. the test message is not too long, i.e. when serialized it will fit in a single sphinx packet
-                let gateway_test_packets = mix_tester
-                    .gateway_test_packets(
-                        gateway,
+                let mixnode_test_packets = mix_tester
+                    .mixnodes_test_packets(
+                        mixnodes,
                         route_ext,
                         self.per_node_test_packets as u32,
-                        Some(recipient),
+                        None,
                     )
                     .unwrap();
-                let gateway_mix_packets =
-                    gateway_test_packets.into_iter().map(Into::into).collect();
+                let mix_packets = mixnode_test_packets.into_iter().map(Into::into).collect();
 
-                // and push it into existing struct (if it's a "core" gateway being tested against another route)
-                // or create a new one
                 let gateway_packets = all_gateway_packets
                     .entry(gateway_identity.to_bytes())
-                    .or_insert_with(|| GatewayPackets::empty(gateway_address, gateway_identity));
-                gateway_packets.push_packets(gateway_mix_packets);
+                    .or_insert_with(|| {
+                        GatewayPackets::empty(gateway_address.clone(), gateway_identity)
+                    });
+                gateway_packets.push_packets(mix_packets);
+
+                // and generate test packets for gateways (note the variable recipient)
+                for gateway in gateways {
+                    let recipient = self.create_packet_sender(gateway);
+                    let gateway_identity = gateway.identity_key;
+                    let gateway_address = gateway.clients_address();
+
+                    // the unwrap here is fine as:
+                    // 1. the topology is definitely valid (otherwise we wouldn't be here)
+                    // 2. the recipient is specified
+                    // 3. the test message is not too long, i.e. when serialized it will fit in a single sphinx packet
+                    let gateway_test_packets = mix_tester
+                        .gateway_test_packets(
+                            gateway,
+                            route_ext,
+                            self.per_node_test_packets as u32,
+                            Some(recipient),
+                        )
+                        .unwrap();
+                    let gateway_mix_packets =
+                        gateway_test_packets.into_iter().map(Into::into).collect();
+
+                    // and push it into existing struct (if it's a "core" gateway being tested against another route)
+                    // or create a new one
+                    let gateway_packets = all_gateway_packets
+                        .entry(gateway_identity.to_bytes())
+                        .or_insert_with(|| {
+                            GatewayPackets::empty(gateway_address, gateway_identity)
+                        });
+                    gateway_packets.push_packets(gateway_mix_packets);
+                }
             }
         }
 
         // convert our hashmap back into a vec
-        let packets = all_gateway_packets.into_values().collect();
+        all_gateway_packets.into_values().collect()
+    }
+}
 
-        PreparedPackets {
-            packets,
-            tested_mixnodes,
-            tested_gateways,
-            invalid_mixnodes,
-            invalid_gateways,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coin, Addr};
+    use nym_mixnet_contract_common::{Gateway, MixNode};
+
+    const IDENTITY: &str = "3ebjp1Fb9hdcS1AR6AZihgeJiMHkB5jjJUsvqNnfQwU7";
+    const SPHINX_KEY: &str = "C7cown6dYCLZpLiMFC1PaBmhvLvmJmLDJGeRTbPD45bX";
+    const PER_NODE_TEST_PACKETS: usize = 2;
+
+    fn mixnode(mix_id: u32, layer: Layer) -> mix::Node {
+        let mix_node = MixNode {
+            host: "1.1.1.1".to_string(),
+            mix_port: 1789,
+            verloc_port: 1790,
+            http_api_port: 8000,
+            sphinx_key: SPHINX_KEY.to_string(),
+            identity_key: IDENTITY.to_string(),
+            version: "1.1.0".to_string(),
+        };
+        let bond = MixNodeBond::new(
+            mix_id,
+            Addr::unchecked("owner"),
+            coin(100_000_000, "unym"),
+            layer,
+            mix_node,
+            1,
+        );
+        (&bond).try_into().unwrap()
+    }
+
+    fn gateway() -> gateway::Node {
+        let gateway = Gateway {
+            host: "2.2.2.2".to_string(),
+            mix_port: 1789,
+            clients_port: 9000,
+            location: "Neuchatel".to_string(),
+            sphinx_key: SPHINX_KEY.to_string(),
+            identity_key: IDENTITY.to_string(),
+            version: "1.1.0".to_string(),
+        };
+        let bond = GatewayBond::new(
+            coin(100_000_000, "unym"),
+            Addr::unchecked("owner"),
+            1,
+            gateway,
+        );
+        (&bond).try_into().unwrap()
+    }
+
+    fn test_preparer() -> PacketPreparer {
+        let mut rng = thread_rng();
+        PacketPreparer::new(
+            NymContractCache::new(),
+            PER_NODE_TEST_PACKETS,
+            Arc::new(AckKey::new(&mut rng)),
+            *identity::KeyPair::new(&mut rng).public_key(),
+            *encryption::KeyPair::new(&mut rng).public_key(),
+        )
+    }
+
+    fn count_packets(packets: &GatewayPackets, packet_type: PacketType) -> usize {
+        packets
+            .packets
+            .iter()
+            .filter(|packet| packet.packet_type() == packet_type)
+            .count()
+    }
+
+    #[test]
+    fn every_node_is_tested_with_each_packet_type() {
+        let mixnodes = vec![
+            mixnode(1, Layer::One),
+            mixnode(2, Layer::Two),
+            mixnode(3, Layer::Three),
+        ];
+        let gateways = vec![gateway()];
+        let route = TestRoute::new(
+            42,
+            mixnodes[0].clone(),
+            mixnodes[1].clone(),
+            mixnodes[2].clone(),
+            gateways[0].clone(),
+        );
+
+        // each mixnode and the gateway get their own set of packets
+        let per_type = PER_NODE_TEST_PACKETS * (mixnodes.len() + gateways.len());
+
+        let mut preparer = test_preparer();
+        for packet_types in [
+            vec![PacketType::Mix],
+            vec![PacketType::Outfox],
+            vec![PacketType::Mix, PacketType::Outfox],
+        ] {
+            let packets = preparer.prepare_nodes_test_packets(
+                1,
+                &[route.clone()],
+                &packet_types,
+                &mixnodes,
+                &gateways,
+            );
+
+            // all of the packets are sent through the route's gateway
+            assert_eq!(packets.len(), 1);
+            assert_eq!(packets[0].pub_key, route.gateway_identity());
+            assert_eq!(packets[0].packets.len(), per_type * packet_types.len());
+            for packet_type in [PacketType::Mix, PacketType::Outfox] {
+                let expected = if packet_types.contains(&packet_type) {
+                    per_type
+                } else {
+                    0
+                };
+                assert_eq!(count_packets(&packets[0], packet_type), expected);
+            }
         }
     }
+
+    #[test]
+    fn routes_are_checked_with_each_packet_type() {
+        let route = TestRoute::new(
+            42,
+            mixnode(1, Layer::One),
+            mixnode(2, Layer::Two),
+            mixnode(3, Layer::Three),
+            gateway(),
+        );
+
+        let mut preparer = test_preparer();
+        let packets = preparer.prepare_test_route_viability_packets(
+            &route,
+            3,
+            &[PacketType::Mix, PacketType::Outfox],
+        );
+
+        assert_eq!(packets.pub_key, route.gateway_identity());
+        assert_eq!(packets.clients_address, route.gateway_clients_address());
+        assert_eq!(packets.packets.len(), 6);
+        assert_eq!(count_packets(&packets, PacketType::Mix), 3);
+        assert_eq!(count_packets(&packets, PacketType::Outfox), 3);
+    }
 }
@@ -209,7 +209,7 @@ impl PacketSender {
                 .with_disabled_credentials_mode(fresh_gateway_client_data.disabled_credentials_mode)
                 .with_response_timeout(fresh_gateway_client_data.gateway_response_timeout),
             config,
-            Arc::clone(&fresh_gateway_client_data.local_identity),
+            fresh_gateway_client_data.local_identity.clone(),
             None,
             gateway_packet_router,
             Some(fresh_gateway_client_data.bandwidth_controller.clone()),
@@ -5,6 +5,7 @@ use crate::network_monitor::monitor::preparer::InvalidNode;
 use crate::network_monitor::test_packet::NodeTestMessage;
 use crate::network_monitor::test_route::TestRoute;
 use nym_node_tester_utils::node::{NodeType, TestableNode};
+use nym_sphinx::params::PacketType;
 use nym_types::monitoring::{GatewayResult, MixnodeResult};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
@@ -205,10 +206,18 @@ impl Display for TestReport {
     }
 }
 
+/// Reliability of the tested nodes when taking into consideration only packets of a particular type.
+pub(crate) struct PacketTypeResults {
+    pub(crate) packet_type: PacketType,
+    pub(crate) mixnode_results: Vec<MixnodeResult>,
+    pub(crate) gateway_results: Vec<GatewayResult>,
+}
+
 pub(crate) struct TestSummary {
     pub(crate) mixnode_results: Vec<MixnodeResult>,
     pub(crate) gateway_results: Vec<GatewayResult>,
     pub(crate) route_results: Vec<RouteResult>,
+    pub(crate) packet_type_results: Vec<PacketTypeResults>,
 }
 
 impl TestSummary {
@@ -242,6 +251,34 @@ impl SummaryProducer {
         self
     }
 
+    fn split_node_results(
+        raw_results: HashMap<TestableNode, usize>,
+        expected: usize,
+    ) -> (Vec<MixnodeResult>, Vec<GatewayResult>) {
+        let mut mixnode_results = Vec::new();
+        let mut gateway_results = Vec::new();
+
+        for (node, received) in raw_results {
+            let performance = received as f32 / expected as f32 * 100.0;
+            let reliability = performance.round() as u8;
+
+            match node.typ {
+                NodeType::Mixnode { mix_id } => {
+                    let res =
+                        MixnodeResult::new(mix_id, node.encoded_identity, node.owner, reliability);
+                    mixnode_results.push(res)
+                }
+                NodeType::Gateway => {
+                    let res = GatewayResult::new(node.encoded_identity, node.owner, reliability);
+                    gateway_results.push(res)
+                }
+            }
+        }
+
+        (mixnode_results, gateway_results)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn produce_summary(
         &self,
         tested_mixnodes: Vec<TestableNode>,
@@ -250,11 +287,14 @@ impl SummaryProducer {
         invalid_mixnodes: Vec<InvalidNode>,
         invalid_gateways: Vec<InvalidNode>,
         test_routes: &[TestRoute],
+        packet_types: &[PacketType],
     ) -> TestSummary {
         // we expect each route to receive this many packets in the ideal world
-        let per_route_expected =
-            (tested_mixnodes.len() + tested_gateways.len()) * self.per_node_test_packets;
-        let per_node_expected = test_routes.len() * self.per_node_test_packets;
+        let per_route_expected = (tested_mixnodes.len() + tested_gateways.len())
+            * self.per_node_test_packets
+            * packet_types.len();
+        let per_node_per_type_expected = test_routes.len() * self.per_node_test_packets;
+        let per_node_expected = per_node_per_type_expected * packet_types.len();
 
         let mut raw_route_results = HashMap::new();
         for test_route in test_routes {
@@ -279,30 +319,41 @@ impl SummaryProducer {
             raw_results.insert(invalid_gateway.into(), 0);
         }
 
+        // every node starts with zero received packets of each type
+        let mut raw_packet_type_results = packet_types
+            .iter()
+            .map(|&packet_type| {
+                let per_type = raw_results
+                    .keys()
+                    .map(|node| (node.clone(), 0))
+                    .collect::<HashMap<_, _>>();
+                (packet_type, per_type)
+            })
+            .collect::<HashMap<_, _>>();
+
         for received in received_packets {
+            if let Some(per_type) = raw_packet_type_results.get_mut(&received.ext.packet_type) {
+                *per_type.entry(received.tested_node.clone()).or_default() += 1usize;
+            }
             *raw_results.entry(received.tested_node).or_default() += 1usize;
             *raw_route_results.entry(received.ext.route_id).or_default() += 1usize;
         }
 
-        let mut mixnode_results = Vec::new();
-        let mut gateway_results = Vec::new();
-
-        for (node, received) in raw_results {
-            let performance = received as f32 / per_node_expected as f32 * 100.0;
-            let reliability = performance.round() as u8;
+        let (mixnode_results, gateway_results) =
+            Self::split_node_results(raw_results, per_node_expected);
 
-            match node.typ {
-                NodeType::Mixnode { mix_id } => {
-                    let res =
-                        MixnodeResult::new(mix_id, node.encoded_identity, node.owner, reliability);
-                    mixnode_results.push(res)
-                }
-                NodeType::Gateway => {
-                    let res = GatewayResult::new(node.encoded_identity, node.owner, reliability);
-                    gateway_results.push(res)
+        let packet_type_results = raw_packet_type_results
+            .into_iter()
+            .map(|(packet_type, raw_results)| {
+                let (mixnode_results, gateway_results) =
+                    Self::split_node_results(raw_results, per_node_per_type_expected);
+                PacketTypeResults {
+                    packet_type,
+                    mixnode_results,
+                    gateway_results,
                 }
-            }
-        }
+            })
+            .collect();
 
         let route_results = raw_route_results
             .into_iter()
@@ -322,6 +373,7 @@ impl SummaryProducer {
             mixnode_results,
             gateway_results,
             route_results,
+            packet_type_results,
         }
     }
 }
@@ -3,6 +3,7 @@
 
 use nym_node_tester_utils::error::NetworkTestingError;
 use nym_node_tester_utils::TestMessage;
+use nym_sphinx::params::PacketType;
 use nym_topology::mix;
 use serde::{Deserialize, Serialize};
 
@@ -12,13 +13,19 @@ pub(crate) type NodeTestMessage = TestMessage<NymApiTestMessageExt>;
 pub(crate) struct NymApiTestMessageExt {
     pub(crate) route_id: u64,
     pub(crate) test_nonce: u64,
+
+    /// Type of the packet this message has been sent with so that the results could be
+    /// attributed to the correct packet format.
+    #[serde(default)]
+    pub(crate) packet_type: PacketType,
 }
 
 impl NymApiTestMessageExt {
-    pub fn new(route_id: u64, test_nonce: u64) -> Self {
+    pub fn new(route_id: u64, test_nonce: u64, packet_type: PacketType) -> Self {
         NymApiTestMessageExt {
             route_id,
             test_nonce,
+            packet_type,
         }
     }
 
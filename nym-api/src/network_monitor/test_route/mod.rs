@@ -4,6 +4,7 @@
 use crate::network_monitor::test_packet::NymApiTestMessageExt;
 use crate::network_monitor::ROUTE_TESTING_TEST_NONCE;
 use nym_crypto::asymmetric::identity;
+use nym_sphinx::params::PacketType;
 use nym_topology::{gateway, mix, NymTopology};
 use std::fmt::{Debug, Formatter};
 
@@ -67,11 +68,15 @@ impl TestRoute {
         &self.nodes
     }
 
-    pub(crate) fn test_message_ext(&self, test_nonce: u64) -> NymApiTestMessageExt {
-        NymApiTestMessageExt::new(self.id, test_nonce)
+    pub(crate) fn test_message_ext(
+        &self,
+        test_nonce: u64,
+        packet_type: PacketType,
+    ) -> NymApiTestMessageExt {
+        NymApiTestMessageExt::new(self.id, test_nonce, packet_type)
     }
 
-    pub(crate) fn self_test_messages(&self, count: usize) -> Vec<Vec<u8>> {
+    pub(crate) fn self_test_messages(&self, count: usize, packet_type: PacketType) -> Vec<Vec<u8>> {
         // it doesn't really matter which node is "chosen" as the packet has to always
         // go through the same sequence of hops.
         // let's just use layer 1 mixnode for this (this choice is completely arbitrary)
@@ -79,7 +84,7 @@ impl TestRoute {
 
         // the unwrap here is fine as the failure can only occur due to serialization and we're not
         // using any custom implementations
-        NymApiTestMessageExt::new(self.id, ROUTE_TESTING_TEST_NONCE)
+        NymApiTestMessageExt::new(self.id, ROUTE_TESTING_TEST_NONCE, packet_type)
             .mix_plaintexts(mix, count as u32)
             .unwrap()
     }
@@ -0,0 +1,96 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use maxminddb::{
+    geoip2::{Asn, City},
+    MaxMindDBError, Reader,
+};
+use nym_api_requests::node_location::{AutonomousSystem, GeoIpLocation};
+use serde::Deserialize;
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Arc;
+
+fn open_database(database_path: &Path) -> Option<Arc<Reader<Vec<u8>>>> {
+    match Reader::open_readfile(database_path) {
+        Ok(reader) => Some(Arc::new(reader)),
+        Err(err) => {
+            error!(
+                "failed to open GeoIP database at {}: {err}",
+                database_path.display()
+            );
+            None
+        }
+    }
+}
+
+fn lookup<'a, T: Deserialize<'a>>(db: &'a Reader<Vec<u8>>, ip: IpAddr) -> Option<T> {
+    match db.lookup::<T>(ip) {
+        Ok(record) => Some(record),
+        Err(MaxMindDBError::AddressNotFoundError(_)) => None,
+        Err(err) => {
+            warn!("GeoIP lookup of {ip} failed: {err}");
+            None
+        }
+    }
+}
+
+/// Resolves node addresses into their approximate location and the announcing autonomous systems
+/// using local MaxMind databases.
+/// If a database is not available, the corresponding lookups return nothing.
+#[derive(Clone, Default)]
+pub(crate) struct GeoIpResolver {
+    db: Option<Arc<Reader<Vec<u8>>>>,
+    asn_db: Option<Arc<Reader<Vec<u8>>>>,
+}
+
+impl GeoIpResolver {
+    pub(crate) fn new(database_path: Option<&Path>, asn_database_path: Option<&Path>) -> Self {
+        let db = match database_path {
+            Some(database_path) => open_database(database_path),
+            None => {
+                info!("no GeoIP database has been configured - node location attestations are not going to be cross-checked");
+                None
+            }
+        };
+        let asn_db = match asn_database_path {
+            Some(asn_database_path) => open_database(asn_database_path),
+            None => {
+                info!("no GeoIP ASN database has been configured - autonomous systems of the nodes are not going to be known");
+                None
+            }
+        };
+
+        GeoIpResolver { db, asn_db }
+    }
+
+    pub(crate) fn lookup(&self, ip: IpAddr) -> Option<GeoIpLocation> {
+        let city = lookup::<City>(self.db.as_ref()?, ip)?;
+
+        let iso_code = city.country.as_ref()?.iso_code?;
+        Some(GeoIpLocation {
+            two_letter_iso_country_code: iso_code.to_string(),
+            latitude: city.location.as_ref().and_then(|l| l.latitude),
+            longitude: city.location.as_ref().and_then(|l| l.longitude),
+        })
+    }
+
+    /// Returns the location of the first of the provided addresses that could be resolved.
+    pub(crate) fn lookup_any(&self, ips: &[IpAddr]) -> Option<GeoIpLocation> {
+        ips.iter().find_map(|ip| self.lookup(*ip))
+    }
+
+    pub(crate) fn lookup_autonomous_system(&self, ip: IpAddr) -> Option<AutonomousSystem> {
+        let asn = lookup::<Asn>(self.asn_db.as_ref()?, ip)?;
+
+        Some(AutonomousSystem {
+            number: asn.autonomous_system_number?,
+            organization: asn.autonomous_system_organization.map(ToString::to_string),
+        })
+    }
+
+    /// Returns the autonomous system of the first of the provided addresses that could be resolved.
+    pub(crate) fn lookup_any_autonomous_system(&self, ips: &[IpAddr]) -> Option<AutonomousSystem> {
+        ips.iter().find_map(|ip| self.lookup_autonomous_system(*ip))
+    }
+}
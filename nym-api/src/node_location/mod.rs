@@ -0,0 +1,21 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use okapi::openapi3::OpenApi;
+use rocket::Route;
+use rocket_okapi::openapi_get_routes_spec;
+use rocket_okapi::settings::OpenApiSettings;
+
+pub(crate) mod geoip;
+pub(crate) mod routes;
+
+pub(crate) use geoip::GeoIpResolver;
+
+/// Merges the routes with http information and returns it to Rocket for serving
+pub(crate) fn node_location_routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+    openapi_get_routes_spec![
+        settings: routes::submit_location_attestation,
+        routes::get_node_location,
+        routes::get_node_diversity,
+    ]
+}
@@ -0,0 +1,194 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::node_location::GeoIpResolver;
+use crate::node_status_api::models::RocketErrorResponse;
+use crate::node_status_api::NodeStatusCache;
+use crate::storage::NymApiStorage;
+use nym_api_requests::node_location::{
+    DiversityStatistics, NodeDiversityResponse, NodeLocation, NodeLocationResponse,
+    SignedNodeLocationAttestation,
+};
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use std::net::IpAddr;
+use time::OffsetDateTime;
+
+// attestations have to be fresh so that old signed messages couldn't be replayed
+const MAX_ATTESTATION_AGE_SECS: i64 = 60 * 60;
+const MAX_ATTESTATION_CLOCK_SKEW_SECS: i64 = 5 * 60;
+
+// attestations are only accepted for nodes we know about, i.e. those present in the annotated cache
+async fn bonded_node_addresses(cache: &NodeStatusCache, identity_key: &str) -> Option<Vec<IpAddr>> {
+    if let Some(mixnodes) = cache.mixnodes_annotated_full().await {
+        if let Some(mixnode) = mixnodes
+            .into_iter()
+            .find(|m| m.identity_key() == identity_key)
+        {
+            return Some(mixnode.ip_addresses);
+        }
+    }
+
+    cache
+        .gateways_annotated_full()
+        .await?
+        .into_iter()
+        .find(|g| g.identity() == identity_key)
+        .map(|g| g.ip_addresses)
+}
+
+#[openapi(tag = "Node Location")]
+#[post("/node-location/attestation", data = "<attestation>")]
+pub(crate) async fn submit_location_attestation(
+    attestation: Json<SignedNodeLocationAttestation>,
+    storage: &State<NymApiStorage>,
+    cache: &State<NodeStatusCache>,
+    geoip: &State<GeoIpResolver>,
+) -> Result<Json<NodeLocationResponse>, RocketErrorResponse> {
+    let attestation = attestation.into_inner();
+    if let Err(err) = attestation.verify() {
+        return Err(RocketErrorResponse::new(
+            err.to_string(),
+            Status::BadRequest,
+        ));
+    }
+
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let timestamp = attestation.attestation.timestamp;
+    if timestamp < now - MAX_ATTESTATION_AGE_SECS
+        || timestamp > now + MAX_ATTESTATION_CLOCK_SKEW_SECS
+    {
+        return Err(RocketErrorResponse::new(
+            "the attestation timestamp is outside the accepted window",
+            Status::BadRequest,
+        ));
+    }
+
+    let identity_key = attestation.attestation.identity_key.clone();
+    let Some(addresses) = bonded_node_addresses(cache, &identity_key).await else {
+        return Err(RocketErrorResponse::new(
+            format!("{identity_key} does not correspond to any bonded node"),
+            Status::NotFound,
+        ));
+    };
+
+    match storage.submit_node_location_attestation(&attestation).await {
+        Ok(true) => (),
+        Ok(false) => {
+            return Err(RocketErrorResponse::new(
+                "a more recent attestation has already been submitted for this node",
+                Status::Conflict,
+            ))
+        }
+        Err(err) => {
+            error!("failed to store location attestation of {identity_key}: {err}");
+            return Err(RocketErrorResponse::new(
+                "failed to store the location attestation",
+                Status::InternalServerError,
+            ));
+        }
+    }
+
+    // note: the annotated caches are going to pick it up on their next refresh
+    let autonomous_system = geoip.lookup_any_autonomous_system(&addresses);
+    let geoip = geoip.lookup_any(&addresses);
+    if let Some(location) = &geoip {
+        if location.two_letter_iso_country_code
+            != attestation.attestation.two_letter_iso_country_code
+        {
+            warn!(
+                "location attestation of {identity_key} ({}) disagrees with GeoIP ({})",
+                attestation.attestation.two_letter_iso_country_code,
+                location.two_letter_iso_country_code
+            )
+        }
+    }
+    let location = NodeLocation::reconcile(Some(&attestation.attestation), geoip.as_ref());
+
+    Ok(Json(NodeLocationResponse {
+        identity_key,
+        attestation: Some(attestation),
+        geoip,
+        location,
+        autonomous_system,
+    }))
+}
+
+#[openapi(tag = "Node Location")]
+#[get("/node-location/<identity_key>")]
+pub(crate) async fn get_node_location(
+    identity_key: &str,
+    storage: &State<NymApiStorage>,
+    cache: &State<NodeStatusCache>,
+    geoip: &State<GeoIpResolver>,
+) -> Result<Json<NodeLocationResponse>, RocketErrorResponse> {
+    let Some(addresses) = bonded_node_addresses(cache, identity_key).await else {
+        return Err(RocketErrorResponse::new(
+            format!("{identity_key} does not correspond to any bonded node"),
+            Status::NotFound,
+        ));
+    };
+
+    let attestation = storage
+        .get_node_location_attestation(identity_key)
+        .await
+        .map_err(|err| {
+            error!("failed to retrieve location attestation of {identity_key}: {err}");
+            RocketErrorResponse::new(
+                "failed to retrieve the location attestation",
+                Status::InternalServerError,
+            )
+        })?;
+
+    let autonomous_system = geoip.lookup_any_autonomous_system(&addresses);
+    let geoip = geoip.lookup_any(&addresses);
+    let location =
+        NodeLocation::reconcile(attestation.as_ref().map(|a| &a.attestation), geoip.as_ref());
+
+    Ok(Json(NodeLocationResponse {
+        identity_key: identity_key.to_string(),
+        attestation,
+        geoip,
+        location,
+        autonomous_system,
+    }))
+}
+
+#[openapi(tag = "Node Location")]
+#[get("/node-location/diversity")]
+pub(crate) async fn get_node_diversity(
+    cache: &State<NodeStatusCache>,
+) -> Result<Json<NodeDiversityResponse>, RocketErrorResponse> {
+    // the statistics are based on the reconciled locations and the ASN lookups of the annotated caches
+    // so that they're consistent with what's being served alongside the nodes themselves
+    let (Some(mixnodes), Some(gateways)) = (
+        cache.mixnodes_annotated_full().await,
+        cache.gateways_annotated_full().await,
+    ) else {
+        return Err(RocketErrorResponse::new(
+            "the node caches have not been initialised yet",
+            Status::ServiceUnavailable,
+        ));
+    };
+
+    Ok(Json(NodeDiversityResponse {
+        mixnodes: DiversityStatistics::compute(mixnodes.iter().map(|m| {
+            (
+                m.location
+                    .as_ref()
+                    .map(|l| l.two_letter_iso_country_code.as_str()),
+                m.autonomous_system.as_ref(),
+            )
+        })),
+        gateways: DiversityStatistics::compute(gateways.iter().map(|g| {
+            (
+                g.location
+                    .as_ref()
+                    .map(|l| l.two_letter_iso_country_code.as_str()),
+                g.autonomous_system.as_ref(),
+            )
+        })),
+    }))
+}
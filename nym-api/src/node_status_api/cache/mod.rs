@@ -8,7 +8,7 @@ use nym_api_requests::models::{GatewayBondAnnotated, MixNodeBondAnnotated, Mixno
 use nym_contracts_common::{IdentityKey, IdentityKeyRef};
 use nym_mixnet_contract_common::MixId;
 use rocket::fairing::AdHoc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::{sync::Arc, time::Duration};
 use thiserror::Error;
 use tokio::sync::RwLockReadGuard;
@@ -137,6 +137,14 @@ impl NodeStatusCache {
             .await
     }
 
+    /// Returns the rewarded set mixnodes that are not part of the current active set.
+    pub(crate) async fn standby_set_annotated(&self) -> Option<Vec<MixNodeBondAnnotated>> {
+        let rewarded_set = self.rewarded_set_annotated().await?;
+        let active_set = self.active_set_annotated().await?;
+
+        Some(standby_set(&rewarded_set, &active_set))
+    }
+
     pub(crate) async fn gateways_cache(
         &self,
     ) -> Option<RwLockReadGuard<Cache<HashMap<IdentityKey, GatewayBondAnnotated>>>> {
@@ -193,3 +201,20 @@ impl NodeStatusCache {
         }
     }
 }
+
+/// Returns the mixnodes of the rewarded set that are not part of the provided active set.
+pub(crate) fn standby_set(
+    rewarded_set: &[MixNodeBondAnnotated],
+    active_set: &[MixNodeBondAnnotated],
+) -> Vec<MixNodeBondAnnotated> {
+    let active_ids = active_set
+        .iter()
+        .map(|mixnode| mixnode.mix_id())
+        .collect::<HashSet<_>>();
+
+    rewarded_set
+        .iter()
+        .filter(|mixnode| !active_ids.contains(&mixnode.mix_id()))
+        .cloned()
+        .collect()
+}
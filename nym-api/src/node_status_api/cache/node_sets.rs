@@ -1,9 +1,11 @@
 // Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
+use crate::node_location::GeoIpResolver;
 use crate::node_status_api::reward_estimate::{compute_apy_from_reward, compute_reward_estimate};
 use crate::support::storage::NymApiStorage;
 use nym_api_requests::models::{GatewayBondAnnotated, MixNodeBondAnnotated, NodePerformance};
+use nym_api_requests::node_location::{AutonomousSystem, NodeLocation, NodeLocationAttestation};
 use nym_mixnet_contract_common::families::FamilyHead;
 use nym_mixnet_contract_common::{reward_params::Performance, Interval, MixId};
 use nym_mixnet_contract_common::{
@@ -11,7 +13,7 @@ use nym_mixnet_contract_common::{
 };
 use nym_topology::NetworkAddress;
 use std::collections::{HashMap, HashSet};
-use std::net::ToSocketAddrs;
+use std::net::{IpAddr, ToSocketAddrs};
 use std::str::FromStr;
 
 pub(super) fn to_rewarded_set_node_status(
@@ -81,8 +83,38 @@ pub(super) async fn get_gateway_performance_from_storage(
         .map(Into::into)
 }
 
+pub(crate) struct LocationSources<'a> {
+    geoip: &'a GeoIpResolver,
+    attestations: HashMap<IdentityKey, NodeLocationAttestation>,
+}
+
+impl<'a> LocationSources<'a> {
+    pub(crate) fn new(
+        geoip: &'a GeoIpResolver,
+        attestations: HashMap<IdentityKey, NodeLocationAttestation>,
+    ) -> Self {
+        LocationSources {
+            geoip,
+            attestations,
+        }
+    }
+
+    fn locate(&self, identity: &str, ip_addresses: &[IpAddr]) -> Option<NodeLocation> {
+        NodeLocation::reconcile(
+            self.attestations.get(identity),
+            self.geoip.lookup_any(ip_addresses).as_ref(),
+        )
+    }
+
+    fn autonomous_system(&self, ip_addresses: &[IpAddr]) -> Option<AutonomousSystem> {
+        self.geoip.lookup_any_autonomous_system(ip_addresses)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn annotate_nodes_with_details(
     storage: &NymApiStorage,
+    location_sources: &LocationSources<'_>,
     mixnodes: Vec<MixNodeDetails>,
     interval_reward_params: RewardingParams,
     current_interval: Interval,
@@ -94,6 +126,12 @@ pub(super) async fn annotate_nodes_with_details(
         .into_iter()
         .collect::<HashMap<IdentityKey, FamilyHead>>();
 
+    // retrieve the per packet type results of all nodes at once rather than querying them one by one
+    let mut packet_type_reliabilities = storage
+        .get_all_mixnode_packet_type_reliabilities()
+        .await
+        .unwrap_or_default();
+
     let mut annotated = HashMap::new();
     for mixnode in mixnodes {
         let stake_saturation = mixnode
@@ -128,6 +166,10 @@ pub(super) async fn annotate_nodes_with_details(
             .ok()
             .unwrap_or_default();
 
+        let packet_type_reliability = packet_type_reliabilities
+            .remove(&mixnode.mix_id())
+            .unwrap_or_default();
+
         // safety: this conversion is infallible
         let ip_addresses =
             match NetworkAddress::from_str(&mixnode.bond_information.mix_node.host).unwrap() {
@@ -151,6 +193,9 @@ pub(super) async fn annotate_nodes_with_details(
             .get(mixnode.bond_information.identity())
             .cloned();
 
+        let location = location_sources.locate(mixnode.bond_information.identity(), &ip_addresses);
+        let autonomous_system = location_sources.autonomous_system(&ip_addresses);
+
         annotated.insert(
             mixnode.mix_id(),
             MixNodeBondAnnotated {
@@ -160,10 +205,13 @@ pub(super) async fn annotate_nodes_with_details(
                 uncapped_stake_saturation,
                 performance,
                 node_performance,
+                packet_type_reliability,
                 estimated_operator_apy,
                 estimated_delegators_apy,
                 family,
                 ip_addresses,
+                location,
+                autonomous_system,
             },
         );
     }
@@ -172,10 +220,16 @@ pub(super) async fn annotate_nodes_with_details(
 
 pub(crate) async fn annotate_gateways_with_details(
     storage: &NymApiStorage,
+    location_sources: &LocationSources<'_>,
     gateway_bonds: Vec<GatewayBond>,
     current_interval: Interval,
     blacklist: &HashSet<IdentityKey>,
 ) -> HashMap<IdentityKey, GatewayBondAnnotated> {
+    let mut packet_type_reliabilities = storage
+        .get_all_gateway_packet_type_reliabilities()
+        .await
+        .unwrap_or_default();
+
     let mut annotated = HashMap::new();
     for gateway_bond in gateway_bonds {
         let performance = get_gateway_performance_from_storage(
@@ -193,6 +247,10 @@ pub(crate) async fn annotate_gateways_with_details(
             .ok()
             .unwrap_or_default();
 
+        let packet_type_reliability = packet_type_reliabilities
+            .remove(gateway_bond.identity())
+            .unwrap_or_default();
+
         // safety: this conversion is infallible
         let ip_addresses = match NetworkAddress::from_str(&gateway_bond.gateway.host).unwrap() {
             NetworkAddress::IpAddr(ip) => vec![ip],
@@ -205,6 +263,9 @@ pub(crate) async fn annotate_gateways_with_details(
             }
         };
 
+        let location = location_sources.locate(gateway_bond.identity(), &ip_addresses);
+        let autonomous_system = location_sources.autonomous_system(&ip_addresses);
+
         annotated.insert(
             gateway_bond.identity().to_string(),
             GatewayBondAnnotated {
@@ -213,7 +274,10 @@ pub(crate) async fn annotate_gateways_with_details(
                 self_described: None,
                 performance,
                 node_performance,
+                packet_type_reliability,
                 ip_addresses,
+                location,
+                autonomous_system,
             },
         );
     }
@@ -3,11 +3,12 @@
 
 use super::NodeStatusCache;
 use crate::{
+    node_location::GeoIpResolver,
     node_status_api::cache::{
         inclusion_probabilities::InclusionProbabilities,
         node_sets::{
             annotate_gateways_with_details, annotate_nodes_with_details,
-            split_into_active_and_rewarded_set, to_rewarded_set_node_status,
+            split_into_active_and_rewarded_set, to_rewarded_set_node_status, LocationSources,
         },
         NodeStatusCacheError,
     },
@@ -30,6 +31,7 @@ pub struct NodeStatusCacheRefresher {
     contract_cache: NymContractCache,
     contract_cache_listener: watch::Receiver<CacheNotification>,
     storage: NymApiStorage,
+    geoip: GeoIpResolver,
 }
 
 impl NodeStatusCacheRefresher {
@@ -39,6 +41,7 @@ impl NodeStatusCacheRefresher {
         contract_cache: NymContractCache,
         contract_cache_listener: watch::Receiver<CacheNotification>,
         storage: NymApiStorage,
+        geoip: GeoIpResolver,
     ) -> Self {
         Self {
             cache,
@@ -46,6 +49,7 @@ impl NodeStatusCacheRefresher {
             contract_cache,
             contract_cache_listener,
             storage,
+            geoip,
         }
     }
 
@@ -138,10 +142,22 @@ impl NodeStatusCacheRefresher {
             NodeStatusCacheError::SimulationFailed
         })?;
 
+        // operator-submitted location attestations to be reconciled with GeoIP lookups
+        let location_attestations = self
+            .storage
+            .get_all_node_location_attestations()
+            .await
+            .unwrap_or_else(|err| {
+                warn!("failed to retrieve node location attestations: {err}");
+                Default::default()
+            });
+        let location_sources = LocationSources::new(&self.geoip, location_attestations);
+
         // Create annotated data
         let rewarded_set_node_status = to_rewarded_set_node_status(&rewarded_set, &active_set);
         let mixnodes_annotated = annotate_nodes_with_details(
             &self.storage,
+            &location_sources,
             mixnode_details,
             interval_reward_params,
             current_interval,
@@ -157,6 +173,7 @@ impl NodeStatusCacheRefresher {
 
         let gateways_annotated = annotate_gateways_with_details(
             &self.storage,
+            &location_sources,
             gateway_bonds,
             current_interval,
             &gateways_blacklist,
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use self::cache::refresher::NodeStatusCacheRefresher;
+use crate::node_location::GeoIpResolver;
 use crate::support::config;
 use crate::{
     nym_contract_cache::cache::NymContractCache,
@@ -85,6 +86,7 @@ pub(crate) fn start_cache_refresh(
     nym_contract_cache_state: &NymContractCache,
     node_status_cache_state: &NodeStatusCache,
     storage: storage::NymApiStorage,
+    geoip: GeoIpResolver,
     nym_contract_cache_listener: tokio::sync::watch::Receiver<support::caching::CacheNotification>,
     shutdown: &TaskManager,
 ) {
@@ -94,6 +96,7 @@ pub(crate) fn start_cache_refresh(
         nym_contract_cache_state.to_owned(),
         nym_contract_cache_listener,
         storage,
+        geoip,
     );
     let shutdown_listener = shutdown.subscribe();
     tokio::spawn(async move { nym_api_cache_refresher.run(shutdown_listener).await });
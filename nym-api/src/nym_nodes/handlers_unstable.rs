@@ -19,12 +19,19 @@
 //!   - `?role` => filters based on the specific role (mixnode/gateway/(in the future: entry/exit))
 //!   - `/mixnodes/<tier>` => only returns mixnode role data
 //!   - `/gateway/<tier>` => only returns (entry) gateway role data
+//!
+//!   Finally, `/skimmed` routes accept `?include_standby` that makes them also return nodes
+//!   that are not currently active, but could be used as fallback, annotated with the `Standby` role:
+//!   - for mixnodes, those are rewarded set nodes outside the active set,
+//!   - for gateways, those are bonded gateways that are currently underperforming
 
 use crate::node_status_api::models::{AxumErrorResponse, AxumResult};
+use crate::nym_nodes::annotate_gateway_role;
 use crate::v2::AxumAppState;
 use axum::extract::Query;
 use axum::extract::State;
 use axum::{Json, Router};
+use nym_api_requests::models::MixNodeBondAnnotated;
 use nym_api_requests::nym_nodes::{
     CachedNodesResponse, FullFatNode, NodeRoleQueryParam, SemiSkimmedNode, SkimmedNode,
 };
@@ -59,6 +66,15 @@ struct NodesParams {
     #[param(inline)]
     role: Option<NodeRoleQueryParam>,
     semver_compatibility: Option<String>,
+    include_standby: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct SkimmedNodesParams {
+    semver_compatibility: Option<String>,
+
+    /// Specifies whether the response should also include standby nodes
+    include_standby: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, utoipa::IntoParams)]
@@ -88,24 +104,17 @@ async fn nodes_basic(
     Query(NodesParams {
         role,
         semver_compatibility,
+        include_standby,
     }): Query<NodesParams>,
 ) -> AxumResult<Json<CachedNodesResponse<SkimmedNode>>> {
     if let Some(role) = role {
+        let params = SkimmedNodesParams {
+            semver_compatibility,
+            include_standby,
+        };
         match role {
-            NodeRoleQueryParam::ActiveMixnode => {
-                return mixnodes_basic(
-                    state,
-                    Query(SemverCompatibilityQueryParam::new(semver_compatibility)),
-                )
-                .await
-            }
-            NodeRoleQueryParam::EntryGateway => {
-                return gateways_basic(
-                    state,
-                    Query(SemverCompatibilityQueryParam::new(semver_compatibility)),
-                )
-                .await;
-            }
+            NodeRoleQueryParam::ActiveMixnode => return mixnodes_basic(state, Query(params)).await,
+            NodeRoleQueryParam::EntryGateway => return gateways_basic(state, Query(params)).await,
             _ => {}
         }
     }
@@ -127,6 +136,7 @@ async fn nodes_expanded(
     Query(NodesParams {
         role,
         semver_compatibility,
+        ..
     }): Query<NodesParams>,
 ) -> AxumResult<Json<CachedNodesResponse<SemiSkimmedNode>>> {
     if let Some(role) = role {
@@ -166,6 +176,7 @@ async fn nodes_detailed(
     Query(NodesParams {
         role,
         semver_compatibility,
+        ..
     }): Query<NodesParams>,
 ) -> AxumResult<Json<CachedNodesResponse<FullFatNode>>> {
     if let Some(role) = role {
@@ -194,7 +205,7 @@ async fn nodes_detailed(
 #[utoipa::path(
     tag = "Unstable Nym Nodes",
     get,
-    params(SemverCompatibilityQueryParam),
+    params(SkimmedNodesParams),
     path = "/v1/unstable/nym-nodes/gateways/skimmed",
     responses(
         (status = 200, body = CachedNodesResponse<SkimmedNode>)
@@ -202,10 +213,12 @@ async fn nodes_detailed(
 )]
 async fn gateways_basic(
     state: State<AxumAppState>,
-    Query(SemverCompatibilityQueryParam {
+    Query(SkimmedNodesParams {
         semver_compatibility,
-    }): Query<SemverCompatibilityQueryParam>,
+        include_standby,
+    }): Query<SkimmedNodesParams>,
 ) -> AxumResult<Json<CachedNodesResponse<SkimmedNode>>> {
+    let include_standby = include_standby.unwrap_or_default();
     let status_cache = state.node_status_cache();
     let describe_cache = state.described_nodes_state();
     let gateways_cache =
@@ -227,7 +240,12 @@ async fn gateways_basic(
     let Ok(self_descriptions) = describe_cache.get().await else {
         return Ok(Json(CachedNodesResponse {
             refreshed_at: gateways_cache.timestamp().into(),
-            nodes: gateways_cache.values().map(Into::into).collect(),
+            nodes: gateways_cache
+                .values()
+                .map(|annotated_bond| {
+                    annotate_gateway_role(annotated_bond.into(), annotated_bond, include_standby)
+                })
+                .collect(),
         }));
     };
 
@@ -250,9 +268,13 @@ async fn gateways_basic(
                 }
             })
             .map(|annotated_bond| {
-                SkimmedNode::from_described_gateway(
+                annotate_gateway_role(
+                    SkimmedNode::from_described_gateway(
+                        annotated_bond,
+                        self_descriptions.deref().get(annotated_bond.identity()),
+                    ),
                     annotated_bond,
-                    self_descriptions.deref().get(annotated_bond.identity()),
+                    include_standby,
                 )
             })
             .collect(),
@@ -298,7 +320,7 @@ async fn gateways_detailed(
 #[utoipa::path(
     tag = "Unstable Nym Nodes",
     get,
-    params(SemverCompatibilityQueryParam),
+    params(SkimmedNodesParams),
     path = "/v1/unstable/nym-nodes/mixnodes/skimmed",
     responses(
         (status = 200, body = CachedNodesResponse<SkimmedNode>)
@@ -306,36 +328,60 @@ async fn gateways_detailed(
 )]
 async fn mixnodes_basic(
     state: State<AxumAppState>,
-    Query(SemverCompatibilityQueryParam {
+    Query(SkimmedNodesParams {
         semver_compatibility,
-    }): Query<SemverCompatibilityQueryParam>,
+        include_standby,
+    }): Query<SkimmedNodesParams>,
 ) -> AxumResult<Json<CachedNodesResponse<SkimmedNode>>> {
-    let mixnodes_cache = state
-        .node_status_cache()
-        .active_mixnodes_cache()
-        .await
-        .ok_or(AxumErrorResponse::internal_msg(
-            "could not obtain mixnodes cache",
-        ))?;
+    let status_cache = state.node_status_cache();
+
+    // retrieve the standby nodes before acquiring the read guard on the active set
+    let standby_set = if include_standby.unwrap_or_default() {
+        status_cache
+            .standby_set_annotated()
+            .await
+            .ok_or(AxumErrorResponse::internal_msg(
+                "could not obtain standby mixnodes",
+            ))?
+    } else {
+        Vec::new()
+    };
+
+    let mixnodes_cache =
+        status_cache
+            .active_mixnodes_cache()
+            .await
+            .ok_or(AxumErrorResponse::internal_msg(
+                "could not obtain mixnodes cache",
+            ))?;
+
+    let is_compatible = |annotated_bond: &&MixNodeBondAnnotated| {
+        if let Some(semver_compatibility) = semver_compatibility.as_ref() {
+            version_checker::is_minor_version_compatible(
+                &annotated_bond
+                    .mixnode_details
+                    .bond_information
+                    .mix_node
+                    .version,
+                semver_compatibility,
+            )
+        } else {
+            true
+        }
+    };
+
     Ok(Json(CachedNodesResponse {
         refreshed_at: mixnodes_cache.timestamp().into(),
         nodes: mixnodes_cache
             .iter()
-            .filter(|annotated_bond| {
-                if let Some(semver_compatibility) = semver_compatibility.as_ref() {
-                    version_checker::is_minor_version_compatible(
-                        &annotated_bond
-                            .mixnode_details
-                            .bond_information
-                            .mix_node
-                            .version,
-                        semver_compatibility,
-                    )
-                } else {
-                    true
-                }
-            })
-            .map(Into::into)
+            .filter(is_compatible)
+            .map(SkimmedNode::from)
+            .chain(
+                standby_set
+                    .iter()
+                    .filter(is_compatible)
+                    .map(SkimmedNode::from_standby_mixnode),
+            )
             .collect(),
     }))
 }
@@ -1,6 +1,8 @@
 // Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
+use nym_api_requests::models::GatewayBondAnnotated;
+use nym_api_requests::nym_nodes::{NodeRole, SkimmedNode};
 use okapi::openapi3::OpenApi;
 use rocket::Route;
 use rocket_okapi::openapi_get_routes_spec;
@@ -35,3 +37,164 @@ pub(crate) fn nym_node_routes_next(settings: &OpenApiSettings) -> (Vec<Route>, O
         unstable_routes::mixnodes_detailed,
     ]
 }
+
+/// If requested, marks gateways that are currently underperforming (and thus would normally be ignored by clients)
+/// as standby so that they could still be used as fallback during active set churn.
+pub(crate) fn annotate_gateway_role(
+    node: SkimmedNode,
+    annotated: &GatewayBondAnnotated,
+    include_standby: bool,
+) -> SkimmedNode {
+    if include_standby && annotated.blacklisted {
+        node.with_role(NodeRole::Standby)
+    } else {
+        node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_status_api::cache::standby_set;
+    use cosmwasm_std::{coin, Addr, Decimal};
+    use nym_api_requests::models::MixNodeBondAnnotated;
+    use nym_contracts_common::Percent;
+    use nym_mixnet_contract_common::{
+        Gateway, GatewayBond, Layer, MixNode, MixNodeBond, MixNodeCostParams, MixNodeDetails,
+        MixNodeRewarding,
+    };
+    use nym_topology::NymTopology;
+
+    const IDENTITY: &str = "3ebjp1Fb9hdcS1AR6AZihgeJiMHkB5jjJUsvqNnfQwU7";
+    const SPHINX_KEY: &str = "C7cown6dYCLZpLiMFC1PaBmhvLvmJmLDJGeRTbPD45bX";
+
+    fn mixnode(mix_id: u32, layer: Layer) -> MixNodeBondAnnotated {
+        let pledge = coin(100_000_000, "unym");
+        let mix_node = MixNode {
+            host: "1.1.1.1".to_string(),
+            mix_port: 1789,
+            verloc_port: 1790,
+            http_api_port: 8000,
+            sphinx_key: SPHINX_KEY.to_string(),
+            identity_key: IDENTITY.to_string(),
+            version: "1.1.0".to_string(),
+        };
+        let cost_params = MixNodeCostParams {
+            profit_margin_percent: Percent::from_percentage_value(10).unwrap(),
+            interval_operating_cost: coin(40_000_000, "unym"),
+        };
+
+        MixNodeBondAnnotated {
+            mixnode_details: MixNodeDetails::new(
+                MixNodeBond::new(
+                    mix_id,
+                    Addr::unchecked("owner"),
+                    pledge.clone(),
+                    layer,
+                    mix_node,
+                    1,
+                ),
+                MixNodeRewarding::initialise_new(cost_params, &pledge, 1).unwrap(),
+                Default::default(),
+            ),
+            stake_saturation: Decimal::zero(),
+            uncapped_stake_saturation: Decimal::zero(),
+            performance: Default::default(),
+            node_performance: Default::default(),
+            packet_type_reliability: vec![],
+            estimated_operator_apy: Decimal::zero(),
+            estimated_delegators_apy: Decimal::zero(),
+            family: None,
+            blacklisted: false,
+            ip_addresses: vec!["1.1.1.1".parse().unwrap()],
+            location: None,
+            autonomous_system: None,
+        }
+    }
+
+    fn gateway(blacklisted: bool) -> GatewayBondAnnotated {
+        let gateway = Gateway {
+            host: "2.2.2.2".to_string(),
+            mix_port: 1789,
+            clients_port: 9000,
+            location: "Neuchatel".to_string(),
+            sphinx_key: SPHINX_KEY.to_string(),
+            identity_key: IDENTITY.to_string(),
+            version: "1.1.0".to_string(),
+        };
+
+        GatewayBondAnnotated {
+            gateway_bond: GatewayBond::new(
+                coin(100_000_000, "unym"),
+                Addr::unchecked("owner"),
+                1,
+                gateway,
+            ),
+            self_described: None,
+            performance: Default::default(),
+            node_performance: Default::default(),
+            packet_type_reliability: vec![],
+            blacklisted,
+            ip_addresses: vec!["2.2.2.2".parse().unwrap()],
+            location: None,
+            autonomous_system: None,
+        }
+    }
+
+    #[test]
+    fn standby_set_excludes_the_active_mixnodes() {
+        let rewarded_set = vec![
+            mixnode(1, Layer::One),
+            mixnode(2, Layer::Two),
+            mixnode(3, Layer::Three),
+            mixnode(4, Layer::One),
+        ];
+        let active_set = rewarded_set[..3].to_vec();
+
+        let standby = standby_set(&rewarded_set, &active_set);
+        assert_eq!(
+            standby.iter().map(|m| m.mix_id()).collect::<Vec<_>>(),
+            vec![4]
+        );
+    }
+
+    #[test]
+    fn standby_mixnodes_are_exposed_but_never_routed_through() {
+        let active = [1, 2, 3]
+            .into_iter()
+            .zip([Layer::One, Layer::Two, Layer::Three])
+            .map(|(mix_id, layer)| SkimmedNode::from(&mixnode(mix_id, layer)))
+            .collect::<Vec<_>>();
+        let standby = SkimmedNode::from_standby_mixnode(&mixnode(4, Layer::One));
+        assert!(standby.role.is_standby());
+
+        let topology = NymTopology::from_unordered(
+            active.iter().chain(std::iter::once(&standby)),
+            std::iter::empty::<&SkimmedNode>(),
+        );
+        assert_eq!(topology.num_mixnodes(), 3);
+        assert!(topology.find_mix(4).is_none());
+    }
+
+    #[test]
+    fn underperforming_gateways_are_only_marked_as_standby_on_request() {
+        let healthy = gateway(false);
+        let underperforming = gateway(true);
+
+        for include_standby in [false, true] {
+            let node = annotate_gateway_role((&healthy).into(), &healthy, include_standby);
+            assert!(matches!(node.role, NodeRole::EntryGateway));
+        }
+
+        let node = annotate_gateway_role((&underperforming).into(), &underperforming, false);
+        assert!(matches!(node.role, NodeRole::EntryGateway));
+
+        let node = annotate_gateway_role((&underperforming).into(), &underperforming, true);
+        assert!(node.role.is_standby());
+
+        // standby gateways can't act as mixnodes either
+        let topology = NymTopology::from_unordered(std::iter::once(&node), std::iter::once(&node));
+        assert_eq!(topology.num_mixnodes(), 0);
+        assert_eq!(topology.gateways().len(), 1);
+    }
+}
@@ -4,7 +4,9 @@
 use crate::node_describe_cache::DescribedNodes;
 use crate::node_status_api::models::RocketErrorResponse;
 use crate::node_status_api::NodeStatusCache;
+use crate::nym_nodes::annotate_gateway_role;
 use crate::support::caching::cache::SharedCache;
+use nym_api_requests::models::MixNodeBondAnnotated;
 use nym_api_requests::nym_nodes::{
     CachedNodesResponse, FullFatNode, NodeRoleQueryParam, SemiSkimmedNode, SkimmedNode,
 };
@@ -29,24 +31,32 @@ use std::ops::Deref;
    /mixnodes/<tier> => only returns mixnode role data
    /gateway/<tier> => only returns (entry) gateway role data
 
+   // `/skimmed` routes additionally accept `?include_standby` to also return (annotated) standby nodes
 
 */
 
 #[openapi(tag = "Unstable Nym Nodes")]
-#[get("/skimmed?<role>&<semver_compatibility>")]
+#[get("/skimmed?<role>&<semver_compatibility>&<include_standby>")]
 pub async fn nodes_basic(
     status_cache: &State<NodeStatusCache>,
     describe_cache: &State<SharedCache<DescribedNodes>>,
     role: Option<NodeRoleQueryParam>,
     semver_compatibility: Option<String>,
+    include_standby: Option<bool>,
 ) -> Result<Json<CachedNodesResponse<SkimmedNode>>, RocketErrorResponse> {
     if let Some(role) = role {
         match role {
             NodeRoleQueryParam::ActiveMixnode => {
-                return mixnodes_basic(status_cache, semver_compatibility).await
+                return mixnodes_basic(status_cache, semver_compatibility, include_standby).await
             }
             NodeRoleQueryParam::EntryGateway => {
-                return gateways_basic(status_cache, describe_cache, semver_compatibility).await
+                return gateways_basic(
+                    status_cache,
+                    describe_cache,
+                    semver_compatibility,
+                    include_standby,
+                )
+                .await
             }
             _ => {}
         }
@@ -109,12 +119,14 @@ pub async fn nodes_detailed(
 }
 
 #[openapi(tag = "Unstable Nym Nodes")]
-#[get("/gateways/skimmed?<semver_compatibility>")]
+#[get("/gateways/skimmed?<semver_compatibility>&<include_standby>")]
 pub async fn gateways_basic(
     status_cache: &State<NodeStatusCache>,
     describe_cache: &State<SharedCache<DescribedNodes>>,
     semver_compatibility: Option<String>,
+    include_standby: Option<bool>,
 ) -> Result<Json<CachedNodesResponse<SkimmedNode>>, RocketErrorResponse> {
+    let include_standby = include_standby.unwrap_or_default();
     let gateways_cache = status_cache
         .gateways_cache()
         .await
@@ -134,7 +146,12 @@ pub async fn gateways_basic(
     let Ok(self_descriptions) = describe_cache.get().await else {
         return Ok(Json(CachedNodesResponse {
             refreshed_at: gateways_cache.timestamp().into(),
-            nodes: gateways_cache.values().map(Into::into).collect(),
+            nodes: gateways_cache
+                .values()
+                .map(|annotated_bond| {
+                    annotate_gateway_role(annotated_bond.into(), annotated_bond, include_standby)
+                })
+                .collect(),
         }));
     };
 
@@ -157,9 +174,13 @@ pub async fn gateways_basic(
                 }
             })
             .map(|annotated_bond| {
-                SkimmedNode::from_described_gateway(
+                annotate_gateway_role(
+                    SkimmedNode::from_described_gateway(
+                        annotated_bond,
+                        self_descriptions.deref().get(annotated_bond.identity()),
+                    ),
                     annotated_bond,
-                    self_descriptions.deref().get(annotated_bond.identity()),
+                    include_standby,
                 )
             })
             .collect(),
@@ -195,11 +216,25 @@ pub async fn gateways_detailed(
 }
 
 #[openapi(tag = "Unstable Nym Nodes")]
-#[get("/mixnodes/skimmed?<semver_compatibility>")]
+#[get("/mixnodes/skimmed?<semver_compatibility>&<include_standby>")]
 pub async fn mixnodes_basic(
     cache: &State<NodeStatusCache>,
     semver_compatibility: Option<String>,
+    include_standby: Option<bool>,
 ) -> Result<Json<CachedNodesResponse<SkimmedNode>>, RocketErrorResponse> {
+    // retrieve the standby nodes before acquiring the read guard on the active set
+    let standby_set = if include_standby.unwrap_or_default() {
+        cache
+            .standby_set_annotated()
+            .await
+            .ok_or(RocketErrorResponse::new(
+                "could not obtain standby mixnodes",
+                Status::InternalServerError,
+            ))?
+    } else {
+        Vec::new()
+    };
+
     let mixnodes_cache = cache
         .active_mixnodes_cache()
         .await
@@ -207,25 +242,34 @@ pub async fn mixnodes_basic(
             "could not obtain mixnodes cache",
             Status::InternalServerError,
         ))?;
+
+    let is_compatible = |annotated_bond: &&MixNodeBondAnnotated| {
+        if let Some(semver_compatibility) = semver_compatibility.as_ref() {
+            version_checker::is_minor_version_compatible(
+                &annotated_bond
+                    .mixnode_details
+                    .bond_information
+                    .mix_node
+                    .version,
+                semver_compatibility,
+            )
+        } else {
+            true
+        }
+    };
+
     Ok(Json(CachedNodesResponse {
         refreshed_at: mixnodes_cache.timestamp().into(),
         nodes: mixnodes_cache
             .iter()
-            .filter(|annotated_bond| {
-                if let Some(semver_compatibility) = semver_compatibility.as_ref() {
-                    version_checker::is_minor_version_compatible(
-                        &annotated_bond
-                            .mixnode_details
-                            .bond_information
-                            .mix_node
-                            .version,
-                        semver_compatibility,
-                    )
-                } else {
-                    true
-                }
-            })
-            .map(Into::into)
+            .filter(is_compatible)
+            .map(SkimmedNode::from)
+            .chain(
+                standby_set
+                    .iter()
+                    .filter(is_compatible)
+                    .map(SkimmedNode::from_standby_mixnode),
+            )
             .collect(),
     }))
 }
@@ -0,0 +1,240 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::ecash;
+use crate::ecash::client::Client as _;
+use crate::support::nyxd;
+use crate::support::storage::NymApiStorage;
+use nym_api_requests::models::{ApiDependenciesHealth, DependencyHealth};
+use nym_coconut_dkg_common::types::EpochState;
+use std::future::Future;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::time::timeout;
+
+// maximum amount of time a single dependency check is allowed to take before it's deemed unhealthy
+const DEPENDENCY_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+// if the latest block is older than that, the nyxd node is most likely not synced with the chain
+const MAX_BLOCK_AGE: Duration = Duration::from_secs(60);
+
+/// Handles to all the external dependencies of the api whose status is reported
+/// via the `/healthz` and `/readyz` endpoints.
+pub(crate) struct DependencyChecks {
+    nyxd_client: nyxd::Client,
+    storage: NymApiStorage,
+
+    // only set if this api is a zk-nym signer
+    ecash_keypair: Option<ecash::keys::KeyPair>,
+
+    // only set if the network monitor is enabled
+    monitor_run_interval: Option<Duration>,
+}
+
+impl DependencyChecks {
+    pub(crate) fn new(
+        nyxd_client: nyxd::Client,
+        storage: NymApiStorage,
+        ecash_keypair: Option<ecash::keys::KeyPair>,
+        monitor_run_interval: Option<Duration>,
+    ) -> Self {
+        DependencyChecks {
+            nyxd_client,
+            storage,
+            ecash_keypair,
+            monitor_run_interval,
+        }
+    }
+
+    pub(crate) async fn check(&self, uptime: Duration) -> ApiDependenciesHealth {
+        let (nyxd, database, dkg, network_monitor) = tokio::join!(
+            with_timeout(self.check_nyxd()),
+            with_timeout(self.check_database()),
+            with_timeout(self.check_dkg()),
+            with_timeout(self.check_network_monitor(uptime)),
+        );
+
+        ApiDependenciesHealth {
+            nyxd,
+            database,
+            dkg,
+            network_monitor,
+        }
+    }
+
+    async fn check_nyxd(&self) -> DependencyHealth {
+        let block_time = match self.nyxd_client.current_block_timestamp().await {
+            Ok(block_time) => block_time,
+            Err(err) => return DependencyHealth::unhealthy(format!("failed to query nyxd: {err}")),
+        };
+
+        let block_age = OffsetDateTime::now_utc().unix_timestamp() - block_time.unix_timestamp();
+        if block_age > MAX_BLOCK_AGE.as_secs() as i64 {
+            return DependencyHealth::degraded(format!(
+                "the latest block is {block_age}s old - the node might not be synced"
+            ));
+        }
+        DependencyHealth::healthy()
+    }
+
+    async fn check_database(&self) -> DependencyHealth {
+        match self.storage.ping().await {
+            Ok(_) => DependencyHealth::healthy(),
+            Err(err) => DependencyHealth::unhealthy(format!("the database is inaccessible: {err}")),
+        }
+    }
+
+    async fn check_dkg(&self) -> DependencyHealth {
+        let Some(keypair) = &self.ecash_keypair else {
+            return DependencyHealth::disabled();
+        };
+
+        let epoch = match self.nyxd_client.get_current_epoch().await {
+            Ok(epoch) => epoch,
+            Err(err) => {
+                return DependencyHealth::unhealthy(format!(
+                    "failed to query the current DKG epoch: {err}"
+                ))
+            }
+        };
+
+        if epoch.state != EpochState::InProgress {
+            return DependencyHealth::degraded(format!(
+                "DKG epoch {} is in the '{}' state",
+                epoch.epoch_id, epoch.state
+            ));
+        }
+
+        let issued_for = keypair
+            .read_keys()
+            .await
+            .as_ref()
+            .map(|keys| keys.issued_for_epoch);
+        match issued_for {
+            Some(issued_for) if keypair.is_valid() && issued_for == epoch.epoch_id => {
+                DependencyHealth::healthy()
+            }
+            _ => DependencyHealth::unhealthy(format!(
+                "there are no valid signing keys for the current DKG epoch {}",
+                epoch.epoch_id
+            )),
+        }
+    }
+
+    async fn check_network_monitor(&self, uptime: Duration) -> DependencyHealth {
+        let Some(run_interval) = self.monitor_run_interval else {
+            return DependencyHealth::disabled();
+        };
+
+        // give the monitor some leeway as the test runs themselves take a while
+        let window = run_interval * 2;
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let since = now - window.as_secs() as i64;
+
+        match self.storage.get_monitor_runs_count(since, now).await {
+            Ok(0) if uptime < window => {
+                DependencyHealth::degraded("waiting for the first network monitor run")
+            }
+            Ok(0) => DependencyHealth::degraded(format!(
+                "there have been no network monitor runs in the last {}s - the node performance data might be stale",
+                window.as_secs()
+            )),
+            Ok(_) => DependencyHealth::healthy(),
+            Err(err) => DependencyHealth::unhealthy(format!(
+                "failed to retrieve the network monitor runs: {err}"
+            )),
+        }
+    }
+}
+
+async fn with_timeout(check: impl Future<Output = DependencyHealth>) -> DependencyHealth {
+    timeout(DEPENDENCY_CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| {
+            DependencyHealth::unhealthy(format!(
+                "the dependency did not respond within {DEPENDENCY_CHECK_TIMEOUT:?}"
+            ))
+        })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::support::config::Config;
+    use nym_api_requests::models::DependencyStatus;
+    use nym_config::defaults::setup_env;
+    use tempfile::TempDir;
+
+    pub(crate) async fn test_checks(
+        monitor_run_interval: Option<Duration>,
+    ) -> (DependencyChecks, TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = NymApiStorage::init(dir.path().join("db.sqlite"))
+            .await
+            .unwrap();
+
+        // point the client at a port nothing is going to be listening on
+        setup_env::<&str>(None);
+        let mut config = Config::new("healthz-test");
+        config.base.local_validator = "http://127.0.0.1:1".parse().unwrap();
+        let nyxd_client = nyxd::Client::new(&config);
+
+        let checks = DependencyChecks::new(nyxd_client, storage, None, monitor_run_interval);
+        (checks, dir)
+    }
+
+    #[tokio::test]
+    async fn unreachable_nyxd_makes_the_api_not_ready() {
+        let (checks, _dir) = test_checks(None).await;
+
+        let health = checks.check(Duration::ZERO).await;
+        assert_eq!(health.nyxd.status, DependencyStatus::Unhealthy);
+        assert_eq!(health.database.status, DependencyStatus::Healthy);
+        assert_eq!(health.dkg.status, DependencyStatus::Disabled);
+        assert_eq!(health.network_monitor.status, DependencyStatus::Disabled);
+        assert!(!health.all_ready());
+    }
+
+    #[tokio::test]
+    async fn network_monitor_status_reflects_its_recent_runs() {
+        let run_interval = Duration::from_secs(60);
+        let (checks, _dir) = test_checks(Some(run_interval)).await;
+
+        // no runs yet, but the api has only just started
+        let health = checks.check_network_monitor(Duration::ZERO).await;
+        assert_eq!(health.status, DependencyStatus::Degraded);
+        assert_eq!(
+            health.details.as_deref(),
+            Some("waiting for the first network monitor run")
+        );
+
+        // no runs even though they were due
+        let health = checks.check_network_monitor(run_interval * 10).await;
+        assert_eq!(health.status, DependencyStatus::Degraded);
+        assert_ne!(
+            health.details.as_deref(),
+            Some("waiting for the first network monitor run")
+        );
+
+        // a run outside the window doesn't count
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let stale = now - (run_interval * 3).as_secs() as i64;
+        checks
+            .storage
+            .manager
+            .insert_monitor_run(stale)
+            .await
+            .unwrap();
+        let health = checks.check_network_monitor(run_interval * 10).await;
+        assert_eq!(health.status, DependencyStatus::Degraded);
+
+        checks
+            .storage
+            .manager
+            .insert_monitor_run(now - 10)
+            .await
+            .unwrap();
+        let health = checks.check_network_monitor(run_interval * 10).await;
+        assert_eq!(health.status, DependencyStatus::Healthy);
+    }
+}
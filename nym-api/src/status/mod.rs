@@ -2,6 +2,10 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use crate::ecash;
+use crate::status::health::DependencyChecks;
+use nym_api_requests::models::{
+    ApiDependenciesHealth, ApiHealthBreakdownResponse, DependencyHealth,
+};
 use nym_bin_common::bin_info;
 use nym_bin_common::build_information::BinaryBuildInformation;
 use okapi::openapi3::OpenApi;
@@ -12,12 +16,14 @@ use tokio::time::Instant;
 
 #[cfg(feature = "axum")]
 pub(crate) mod handlers;
+pub(crate) mod health;
 pub(crate) mod routes;
 
 pub(crate) struct ApiStatusState {
     startup_time: Instant,
     build_information: BinaryBuildInformation,
     signer_information: Option<SignerState>,
+    dependency_checks: Option<DependencyChecks>,
 }
 
 pub(crate) struct SignerState {
@@ -37,18 +43,39 @@ impl ApiStatusState {
             startup_time: Instant::now(),
             build_information: bin_info!(),
             signer_information: None,
+            dependency_checks: None,
         }
     }
 
     pub fn add_zk_nym_signer(&mut self, signer_information: SignerState) {
         self.signer_information = Some(signer_information)
     }
+
+    pub(crate) fn add_dependency_checks(&mut self, dependency_checks: DependencyChecks) {
+        self.dependency_checks = Some(dependency_checks)
+    }
+
+    pub(crate) async fn health_breakdown(&self) -> ApiHealthBreakdownResponse {
+        let uptime = self.startup_time.elapsed();
+        let dependencies = match &self.dependency_checks {
+            Some(checks) => checks.check(uptime).await,
+            None => ApiDependenciesHealth {
+                nyxd: DependencyHealth::disabled(),
+                database: DependencyHealth::disabled(),
+                dkg: DependencyHealth::disabled(),
+                network_monitor: DependencyHealth::disabled(),
+            },
+        };
+        ApiHealthBreakdownResponse::new(uptime, dependencies)
+    }
 }
 
 pub(crate) fn api_status_routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
     openapi_get_routes_spec![
         settings:
         routes::health,
+        routes::healthz,
+        routes::readyz,
         routes::build_information,
         routes::signer_information
     ]
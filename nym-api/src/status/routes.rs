@@ -3,13 +3,45 @@
 
 use crate::node_status_api::models::RocketErrorResponse;
 use crate::status::ApiStatusState;
-use nym_api_requests::models::{ApiHealthResponse, SignerInformationResponse};
+use nym_api_requests::models::{
+    ApiHealthBreakdownResponse, ApiHealthResponse, SignerInformationResponse,
+};
 use nym_bin_common::build_information::BinaryBuildInformationOwned;
 use nym_compact_ecash::Base58;
+use okapi::openapi3::Responses;
 use rocket::http::Status;
+use rocket::response::{self, Responder, Response};
 use rocket::serde::json::Json;
-use rocket::State;
+use rocket::{Request, State};
+use rocket_okapi::gen::OpenApiGenerator;
 use rocket_okapi::openapi;
+use rocket_okapi::response::OpenApiResponderInner;
+use rocket_okapi::util::ensure_status_code_exists;
+
+/// Health breakdown returned with `503 Service Unavailable` if any of the dependencies are unhealthy.
+pub(crate) struct ReadinessResponse(ApiHealthBreakdownResponse);
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for ReadinessResponse {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        let status = if self.0.ready {
+            Status::Ok
+        } else {
+            Status::ServiceUnavailable
+        };
+        Response::build()
+            .merge(Json(self.0).respond_to(req)?)
+            .status(status)
+            .ok()
+    }
+}
+
+impl OpenApiResponderInner for ReadinessResponse {
+    fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut responses = Json::<ApiHealthBreakdownResponse>::responses(gen)?;
+        ensure_status_code_exists(&mut responses, 503);
+        Ok(responses)
+    }
+}
 
 #[openapi(tag = "Api Status")]
 #[get("/health")]
@@ -19,6 +51,22 @@ pub(crate) async fn health(state: &State<ApiStatusState>) -> Json<ApiHealthRespo
     Json(health)
 }
 
+/// Reports the health of the api alongside the status of all of its dependencies.
+/// It always returns `200 OK` for as long as the api itself is running.
+#[openapi(tag = "Api Status")]
+#[get("/healthz")]
+pub(crate) async fn healthz(state: &State<ApiStatusState>) -> Json<ApiHealthBreakdownResponse> {
+    Json(state.health_breakdown().await)
+}
+
+/// Reports whether the api is ready to serve requests, i.e. none of its dependencies are unhealthy.
+/// Returns `503 Service Unavailable` otherwise.
+#[openapi(tag = "Api Status")]
+#[get("/readyz")]
+pub(crate) async fn readyz(state: &State<ApiStatusState>) -> ReadinessResponse {
+    ReadinessResponse(state.health_breakdown().await)
+}
+
 #[openapi(tag = "Api Status")]
 #[get("/build-information")]
 pub(crate) async fn build_information(
@@ -50,3 +98,56 @@ pub(crate) async fn signer_information(
             .map(|maybe_vk| maybe_vk.to_bs58()),
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::status::health::tests::test_checks;
+    use crate::status::{api_status_routes, ApiStatusState};
+    use nym_api_requests::models::{ApiHealthBreakdownResponse, DependencyStatus};
+    use rocket::http::Status;
+    use rocket::local::asynchronous::Client;
+
+    async fn test_client(state: ApiStatusState) -> Client {
+        let rocket = rocket::build()
+            .manage(state)
+            .mount("/v1/api-status", api_status_routes(&Default::default()).0);
+        Client::tracked(rocket)
+            .await
+            .expect("valid rocket instance")
+    }
+
+    #[tokio::test]
+    async fn readyz_fails_while_healthz_succeeds_with_unhealthy_dependencies() {
+        let (checks, _dir) = test_checks(None).await;
+
+        let mut state = ApiStatusState::new();
+        state.add_dependency_checks(checks);
+        let client = test_client(state).await;
+
+        let response = client.get("/v1/api-status/healthz").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        let health: ApiHealthBreakdownResponse = response.into_json().await.unwrap();
+        assert!(!health.ready);
+        assert_eq!(health.dependencies.nyxd.status, DependencyStatus::Unhealthy);
+        assert_eq!(
+            health.dependencies.database.status,
+            DependencyStatus::Healthy
+        );
+
+        let response = client.get("/v1/api-status/readyz").dispatch().await;
+        assert_eq!(response.status(), Status::ServiceUnavailable);
+        let health: ApiHealthBreakdownResponse = response.into_json().await.unwrap();
+        assert!(!health.ready);
+    }
+
+    #[tokio::test]
+    async fn readyz_succeeds_without_any_dependencies() {
+        let client = test_client(ApiStatusState::new()).await;
+
+        let response = client.get("/v1/api-status/readyz").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        let health: ApiHealthBreakdownResponse = response.into_json().await.unwrap();
+        assert!(health.ready);
+        assert_eq!(health.dependencies.dkg.status, DependencyStatus::Disabled);
+    }
+}
@@ -14,6 +14,7 @@ use nym_config::{
     must_get_home, read_config_from_toml_file, save_formatted_config_to_file, NymConfigTemplate,
     DEFAULT_CONFIG_DIR, DEFAULT_CONFIG_FILENAME, DEFAULT_DATA_DIR, DEFAULT_NYM_APIS_DIR, NYM_DIR,
 };
+use nym_sphinx::params::PacketType;
 use serde::{Deserialize, Serialize};
 use std::io;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
@@ -354,6 +355,11 @@ pub struct NetworkMonitorDebug {
 
     /// Number of test packets sent to each node during regular monitor test run.
     pub per_node_test_packets: usize,
+
+    /// Packet types (sphinx and/or outfox) that are going to be sent through each tested node.
+    /// The reliability of each node is also determined separately for every type.
+    /// By default only sphinx packets are used, outfox testing has to be explicitly enabled.
+    pub test_packet_types: Vec<PacketType>,
 }
 
 impl Default for NetworkMonitorDebug {
@@ -373,6 +379,7 @@ impl Default for NetworkMonitorDebug {
             minimum_test_routes: DEFAULT_MINIMUM_TEST_ROUTES,
             route_test_packets: DEFAULT_ROUTE_TEST_PACKETS,
             per_node_test_packets: DEFAULT_PER_NODE_TEST_PACKETS,
+            test_packet_types: vec![PacketType::Mix],
         }
     }
 }
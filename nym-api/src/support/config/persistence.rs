@@ -3,6 +3,7 @@
 
 use crate::support::config::default_data_directory;
 use anyhow::Context;
+use nym_config::serde_helpers::de_maybe_path;
 use nym_crypto::asymmetric::identity;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
@@ -60,6 +61,16 @@ impl NetworkMonitorPaths {
 pub struct NodeStatusAPIPaths {
     /// Path to the database file containing uptime statuses for all mixnodes and gateways.
     pub database_path: PathBuf,
+
+    /// Path to the GeoLite2 (or compatible) MaxMind database used for cross-checking
+    /// node location attestations submitted by operators.
+    #[serde(default, deserialize_with = "de_maybe_path")]
+    pub geoip_database_path: Option<PathBuf>,
+
+    /// Path to the GeoLite2 ASN (or compatible) MaxMind database used for determining
+    /// the autonomous systems announcing the addresses of the nodes.
+    #[serde(default, deserialize_with = "de_maybe_path")]
+    pub asn_database_path: Option<PathBuf>,
 }
 
 impl NodeStatusAPIPaths {
@@ -68,6 +79,8 @@ impl NodeStatusAPIPaths {
 
         NodeStatusAPIPaths {
             database_path: data_dir.join(DEFAULT_NODE_STATUS_API_DATABASE_FILENAME),
+            geoip_database_path: None,
+            asn_database_path: None,
         }
     }
 }
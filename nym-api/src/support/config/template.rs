@@ -63,6 +63,14 @@ route_test_packets = {{ network_monitor.debug.route_test_packets }}
 # Number of test packets sent to each node during regular monitor test run.
 per_node_test_packets = {{ network_monitor.debug.per_node_test_packets }}
 
+# Packet types ('mix' for sphinx and/or 'outfox') sent through each tested node.
+# Only sphinx packets are used by default, outfox testing has to be explicitly enabled.
+test_packet_types = [
+    {{#each network_monitor.debug.test_packet_types }}
+        '{{this}}',
+    {{/each}}
+]
+
 
 ##### node status api config options #####
 
@@ -71,6 +79,12 @@ per_node_test_packets = {{ network_monitor.debug.per_node_test_packets }}
 # Path to the database file containing uptime statuses for all mixnodes and gateways.
 database_path = '{{ node_status_api.storage_paths.database_path }}'
 
+# (Optional) Path to the MaxMind GeoIP database used for cross-checking node location attestations.
+geoip_database_path = '{{ node_status_api.storage_paths.geoip_database_path }}'
+
+# (Optional) Path to the MaxMind GeoIP ASN database used for determining the autonomous systems of the nodes.
+asn_database_path = '{{ node_status_api.storage_paths.asn_database_path }}'
+
 [node_status_api.debug]
 
 caching_interval = '{{ node_status_api.debug.caching_interval }}'
@@ -8,10 +8,12 @@ use crate::ecash::{self, comm::QueryCommunicationChannel};
 use crate::network::models::NetworkDetails;
 use crate::network::network_routes;
 use crate::node_describe_cache::DescribedNodes;
+use crate::node_location::{self, GeoIpResolver};
 use crate::node_status_api::routes_deprecated::unstable;
 use crate::node_status_api::{self, NodeStatusCache};
 use crate::nym_contract_cache::cache::NymContractCache;
 use crate::nym_nodes::{nym_node_routes_deprecated, nym_node_routes_next};
+use crate::status::health::DependencyChecks;
 use crate::status::{api_status_routes, ApiStatusState, SignerState};
 use crate::support::caching::cache::SharedCache;
 use crate::support::config::Config;
@@ -51,6 +53,7 @@ pub(crate) async fn setup_rocket(
         "/network" => network_routes(&openapi_settings),
         "/api-status" => api_status_routes(&openapi_settings),
         "/ecash" => ecash::routes_open_api(&openapi_settings, config.coconut_signer.enabled),
+        "" => node_location::node_location_routes(&openapi_settings),
         "" => nym_node_routes_deprecated(&openapi_settings),
 
         // => when we move those routes, we'll need to add a redirection for backwards compatibility
@@ -63,6 +66,18 @@ pub(crate) async fn setup_rocket(
     let rocket = rocket
         .manage(network_details)
         .manage(SharedCache::<DescribedNodes>::new())
+        .manage(GeoIpResolver::new(
+            config
+                .node_status_api
+                .storage_paths
+                .geoip_database_path
+                .as_deref(),
+            config
+                .node_status_api
+                .storage_paths
+                .asn_database_path
+                .as_deref(),
+        ))
         .mount("/swagger", make_swagger_ui(&openapi::get_docs()))
         .attach(setup_rocket_cors()?)
         .attach(NymContractCache::stage())
@@ -72,6 +87,18 @@ pub(crate) async fn setup_rocket(
         .manage(unstable::NodeInfoCache::default());
 
     let mut status_state = ApiStatusState::new();
+    status_state.add_dependency_checks(DependencyChecks::new(
+        nyxd_client.clone(),
+        storage.clone(),
+        config
+            .coconut_signer
+            .enabled
+            .then(|| coconut_keypair.clone()),
+        config
+            .network_monitor
+            .enabled
+            .then_some(config.network_monitor.debug.run_interval),
+    ));
 
     let rocket = if config.coconut_signer.enabled {
         // make sure we have some tokens to cover multisig fees
@@ -10,15 +10,17 @@ use cw3::{ProposalResponse, VoteResponse};
 use cw4::MemberResponse;
 use nym_coconut_dkg_common::dealer::RegisteredDealerDetails;
 use nym_coconut_dkg_common::dealing::{
-    DealerDealingsStatusResponse, DealingChunkInfo, DealingMetadata, DealingStatusResponse,
-    PartialContractDealing,
+    DealerDealingsStatusResponse, DealingChecksum, DealingChunkInfo, DealingMetadata,
+    DealingStatusResponse, PartialContractDealing,
 };
 use nym_coconut_dkg_common::msg::QueryMsg as DkgQueryMsg;
-use nym_coconut_dkg_common::types::{ChunkIndex, DealingIndex, PartialContractDealingData, State};
+use nym_coconut_dkg_common::types::{
+    ChunkIndex, DealingIndex, DkgPublicParameters, PartialContractDealingData, State,
+};
 use nym_coconut_dkg_common::{
     dealer::{DealerDetails, DealerDetailsResponse},
     types::{EncodedBTEPublicKeyWithProof, Epoch, EpochId},
-    verification_key::{ContractVKShare, VerificationKeyShare},
+    verification_key::{ContractVKShare, MasterVerificationKey, VerificationKeyShare},
 };
 use nym_config::defaults::{ChainDetails, NymNetworkDetails};
 use nym_dkg::Threshold;
@@ -186,8 +188,6 @@ impl Client {
             .map_err(|_| NyxdError::MalformedAccountAddress(cosmwasm_addr))
     }
 
-    // a helper function for the future to obtain the current block timestamp
-    #[allow(dead_code)]
     pub(crate) async fn current_block_timestamp(&self) -> Result<TendermintTime, NyxdError> {
         let time = nyxd_query!(self, get_current_block_timestamp().await?);
 
@@ -419,6 +419,12 @@ impl crate::ecash::client::Client for Client {
         Ok(nyxd_query!(self, get_current_epoch().await?))
     }
 
+    async fn get_dkg_public_parameters(
+        &self,
+    ) -> crate::ecash::error::Result<Option<DkgPublicParameters>> {
+        Ok(nyxd_query!(self, get_public_parameters().await?).parameters)
+    }
+
     async fn group_member(&self, addr: String) -> crate::ecash::error::Result<MemberResponse> {
         Ok(nyxd_query!(self, member(addr, None).await?))
     }
@@ -586,10 +592,11 @@ impl crate::ecash::client::Client for Client {
         dealing_index: DealingIndex,
         chunks: Vec<DealingChunkInfo>,
         resharing: bool,
+        checksum: Option<DealingChecksum>,
     ) -> crate::ecash::error::Result<ExecuteResult> {
         Ok(nyxd_signing!(
             self,
-            submit_dealing_metadata(dealing_index, chunks, resharing, None).await?
+            submit_dealing_metadata(dealing_index, chunks, resharing, checksum, None).await?
         ))
     }
 
@@ -613,6 +620,16 @@ impl crate::ecash::client::Client for Client {
             submit_verification_key_share(share, resharing, None).await?
         ))
     }
+
+    async fn submit_master_verification_key(
+        &self,
+        key: MasterVerificationKey,
+    ) -> crate::ecash::error::Result<ExecuteResult> {
+        Ok(nyxd_signing!(
+            self,
+            submit_master_verification_key(key, None).await?
+        ))
+    }
 }
 
 #[async_trait]
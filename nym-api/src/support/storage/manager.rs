@@ -3,7 +3,8 @@
 use crate::node_status_api::models::{HistoricalUptime, Uptime};
 use crate::node_status_api::utils::{ActiveGatewayStatuses, ActiveMixnodeStatuses};
 use crate::support::storage::models::{
-    ActiveGateway, ActiveMixnode, GatewayDetails, MixnodeDetails, NodeStatus, RewardingReport,
+    ActiveGateway, ActiveMixnode, GatewayDetails, GatewayPacketTypeStatus, MixnodeDetails,
+    MixnodePacketTypeStatus, NodeStatus, RewardingReport, StoredNodeLocationAttestation,
     TestedGatewayStatus, TestedMixnodeStatus, TestingRoute,
 };
 use nym_mixnet_contract_common::{EpochId, IdentityKey, MixId};
@@ -257,6 +258,48 @@ impl StorageManager {
         .await
     }
 
+    /// Gets the most recent reliability of all mixnodes for every packet type they have been tested with.
+    pub(crate) async fn get_all_mixnode_packet_type_statuses(
+        &self,
+    ) -> Result<Vec<MixnodePacketTypeStatus>, sqlx::Error> {
+        sqlx::query_as!(
+            MixnodePacketTypeStatus,
+            r#"
+                SELECT
+                    mixnode_details.mix_id as "mix_id: MixId",
+                    packet_type as "packet_type: u8",
+                    reliability as "reliability: u8",
+                    timestamp
+                FROM mixnode_packet_type_status
+                JOIN mixnode_details
+                ON mixnode_packet_type_status.mixnode_details_id = mixnode_details.id;
+            "#,
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
+    /// Gets the most recent reliability of all gateways for every packet type they have been tested with.
+    pub(crate) async fn get_all_gateway_packet_type_statuses(
+        &self,
+    ) -> Result<Vec<GatewayPacketTypeStatus>, sqlx::Error> {
+        sqlx::query_as!(
+            GatewayPacketTypeStatus,
+            r#"
+                SELECT
+                    gateway_details.identity,
+                    packet_type as "packet_type: u8",
+                    reliability as "reliability: u8",
+                    timestamp
+                FROM gateway_packet_type_status
+                JOIN gateway_details
+                ON gateway_packet_type_status.gateway_details_id = gateway_details.id;
+            "#,
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
+
     /// Gets all reliability statuses for gateway with particular identity that were inserted
     /// into the database after the specified unix timestamp.
     ///
@@ -608,6 +651,90 @@ impl StorageManager {
         tx.commit().await
     }
 
+    /// Replaces the most recent per packet type reliability of the provided mixnodes.
+    /// Nodes that do not yet exist in the database are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp`: unix timestamp indicating when the measurements took place.
+    /// * `packet_type`: u8 representation of the packet type used for the measurements.
+    /// * `mixnode_results`: reliability results of each node that got tested.
+    pub(crate) async fn submit_mixnode_packet_type_statuses(
+        &self,
+        timestamp: i64,
+        packet_type: u8,
+        mixnode_results: &[MixnodeResult],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.connection_pool.begin().await?;
+        for mixnode_result in mixnode_results {
+            let Some(mixnode_id) = sqlx::query!(
+                "SELECT id FROM mixnode_details WHERE mix_id = ?",
+                mixnode_result.mix_id
+            )
+            .fetch_optional(&mut tx)
+            .await?
+            .map(|row| row.id) else {
+                continue;
+            };
+
+            sqlx::query!(
+                r#"
+                    INSERT OR REPLACE INTO mixnode_packet_type_status (mixnode_details_id, packet_type, reliability, timestamp) VALUES (?, ?, ?, ?);
+                "#,
+                mixnode_id,
+                packet_type,
+                mixnode_result.reliability,
+                timestamp
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    /// Replaces the most recent per packet type reliability of the provided gateways.
+    /// Nodes that do not yet exist in the database are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp`: unix timestamp indicating when the measurements took place.
+    /// * `packet_type`: u8 representation of the packet type used for the measurements.
+    /// * `gateway_results`: reliability results of each node that got tested.
+    pub(crate) async fn submit_gateway_packet_type_statuses(
+        &self,
+        timestamp: i64,
+        packet_type: u8,
+        gateway_results: &[GatewayResult],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.connection_pool.begin().await?;
+        for gateway_result in gateway_results {
+            let Some(gateway_id) = sqlx::query!(
+                "SELECT id FROM gateway_details WHERE identity = ?",
+                gateway_result.identity
+            )
+            .fetch_optional(&mut tx)
+            .await?
+            .map(|row| row.id) else {
+                continue;
+            };
+
+            sqlx::query!(
+                r#"
+                    INSERT OR REPLACE INTO gateway_packet_type_status (gateway_details_id, packet_type, reliability, timestamp) VALUES (?, ?, ?, ?);
+                "#,
+                gateway_id,
+                packet_type,
+                gateway_result.reliability,
+                timestamp
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
     pub(crate) async fn submit_gateway_statuses_v2(
         &self,
         gateway_results: &[NodeResult],
@@ -828,6 +955,14 @@ impl StorageManager {
         Ok(res.last_insert_rowid())
     }
 
+    /// Performs a trivial query in order to check whether the database is accessible.
+    pub(crate) async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query!("SELECT 1 AS ping")
+            .fetch_one(&self.connection_pool)
+            .await?;
+        Ok(())
+    }
+
     /// Obtains number of network monitor test runs that have occurred within the specified interval.
     ///
     /// # Arguments
@@ -1203,4 +1338,72 @@ impl StorageManager {
         .fetch_all(&self.connection_pool)
         .await
     }
+
+    /// Inserts (or replaces) the location attestation of the specified node,
+    /// as long as it's more recent than the one currently stored.
+    pub(crate) async fn upsert_node_location_attestation(
+        &self,
+        attestation: &StoredNodeLocationAttestation,
+    ) -> Result<bool, sqlx::Error> {
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO node_location_attestation
+                    (identity_key, country_code, latitude, longitude, timestamp, signature)
+                VALUES (?, ?, ?, ?, ?, ?)
+                ON CONFLICT(identity_key) DO UPDATE SET
+                    country_code = excluded.country_code,
+                    latitude = excluded.latitude,
+                    longitude = excluded.longitude,
+                    timestamp = excluded.timestamp,
+                    signature = excluded.signature
+                WHERE excluded.timestamp > node_location_attestation.timestamp
+            "#,
+            attestation.identity_key,
+            attestation.country_code,
+            attestation.latitude,
+            attestation.longitude,
+            attestation.timestamp,
+            attestation.signature,
+        )
+        .execute(&self.connection_pool)
+        .await?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    /// Gets the most recent location attestation of the node with the specified identity.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity_key`: base58-encoded identity of the node.
+    pub(crate) async fn get_node_location_attestation(
+        &self,
+        identity_key: &str,
+    ) -> Result<Option<StoredNodeLocationAttestation>, sqlx::Error> {
+        sqlx::query_as!(
+            StoredNodeLocationAttestation,
+            r#"
+                SELECT identity_key, country_code, latitude, longitude, timestamp, signature
+                    FROM node_location_attestation
+                    WHERE identity_key = ?
+            "#,
+            identity_key,
+        )
+        .fetch_optional(&self.connection_pool)
+        .await
+    }
+
+    pub(crate) async fn get_all_node_location_attestations(
+        &self,
+    ) -> Result<Vec<StoredNodeLocationAttestation>, sqlx::Error> {
+        sqlx::query_as!(
+            StoredNodeLocationAttestation,
+            r#"
+                SELECT identity_key, country_code, latitude, longitude, timestamp, signature
+                    FROM node_location_attestation
+            "#,
+        )
+        .fetch_all(&self.connection_pool)
+        .await
+    }
 }
@@ -1,6 +1,7 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
+use crate::network_monitor::monitor::summary_producer::PacketTypeResults;
 use crate::network_monitor::test_route::TestRoute;
 use crate::node_status_api::models::{
     GatewayStatusReport, GatewayUptimeHistory, MixnodeStatusReport, MixnodeUptimeHistory,
@@ -10,12 +11,17 @@ use crate::node_status_api::{ONE_DAY, ONE_HOUR};
 use crate::storage::manager::StorageManager;
 use crate::storage::models::{NodeStatus, TestingRoute};
 use crate::support::storage::models::{
-    GatewayDetails, MixnodeDetails, TestedGatewayStatus, TestedMixnodeStatus,
+    GatewayDetails, MixnodeDetails, StoredNodeLocationAttestation, TestedGatewayStatus,
+    TestedMixnodeStatus,
 };
-use nym_mixnet_contract_common::MixId;
+use nym_api_requests::models::PacketTypeReliability;
+use nym_api_requests::node_location::{NodeLocationAttestation, SignedNodeLocationAttestation};
+use nym_mixnet_contract_common::{IdentityKey, MixId};
+use nym_sphinx::params::PacketType;
 use nym_types::monitoring::{GatewayResult, MixnodeResult};
 use rocket::fairing::AdHoc;
 use sqlx::ConnectOptions;
+use std::collections::HashMap;
 use std::path::Path;
 use time::OffsetDateTime;
 
@@ -24,6 +30,21 @@ use self::manager::{AvgGatewayReliability, AvgMixnodeReliability};
 pub(crate) mod manager;
 pub(crate) mod models;
 
+fn to_packet_type_reliability(
+    packet_type: u8,
+    reliability: u8,
+    timestamp: i64,
+) -> Option<PacketTypeReliability> {
+    // ignore any packet types we might no longer understand
+    PacketType::try_from(packet_type)
+        .ok()
+        .map(|packet_type| PacketTypeReliability {
+            packet_type: packet_type.to_string(),
+            most_recent: reliability,
+            last_tested: timestamp,
+        })
+}
+
 // note that clone here is fine as upon cloning the same underlying pool will be used
 #[derive(Clone)]
 pub(crate) struct NymApiStorage {
@@ -620,6 +641,75 @@ impl NymApiStorage {
         Ok(())
     }
 
+    /// Replaces the most recent per packet type results of all the tested nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet_type_results`: node reliabilities determined separately for each packet type.
+    pub(crate) async fn insert_packet_type_results(
+        &self,
+        packet_type_results: Vec<PacketTypeResults>,
+    ) -> Result<(), NymApiStorageError> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+
+        for results in packet_type_results {
+            let packet_type = results.packet_type as u8;
+            self.manager
+                .submit_mixnode_packet_type_statuses(now, packet_type, &results.mixnode_results)
+                .await?;
+            self.manager
+                .submit_gateway_packet_type_statuses(now, packet_type, &results.gateway_results)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Obtains the most recent reliability of all mixnodes for every packet type they have been tested with.
+    pub(crate) async fn get_all_mixnode_packet_type_reliabilities(
+        &self,
+    ) -> Result<HashMap<MixId, Vec<PacketTypeReliability>>, NymApiStorageError> {
+        let statuses = self.manager.get_all_mixnode_packet_type_statuses().await?;
+
+        let mut reliabilities: HashMap<_, Vec<_>> = HashMap::new();
+        for status in statuses {
+            if let Some(reliability) =
+                to_packet_type_reliability(status.packet_type, status.reliability, status.timestamp)
+            {
+                reliabilities
+                    .entry(status.mix_id)
+                    .or_default()
+                    .push(reliability)
+            }
+        }
+        Ok(reliabilities)
+    }
+
+    /// Obtains the most recent reliability of all gateways for every packet type they have been tested with.
+    pub(crate) async fn get_all_gateway_packet_type_reliabilities(
+        &self,
+    ) -> Result<HashMap<IdentityKey, Vec<PacketTypeReliability>>, NymApiStorageError> {
+        let statuses = self.manager.get_all_gateway_packet_type_statuses().await?;
+
+        let mut reliabilities: HashMap<_, Vec<_>> = HashMap::new();
+        for status in statuses {
+            if let Some(reliability) =
+                to_packet_type_reliability(status.packet_type, status.reliability, status.timestamp)
+            {
+                reliabilities
+                    .entry(status.identity)
+                    .or_default()
+                    .push(reliability)
+            }
+        }
+        Ok(reliabilities)
+    }
+
+    /// Checks whether the underlying database is accessible.
+    pub(crate) async fn ping(&self) -> Result<(), NymApiStorageError> {
+        Ok(self.manager.ping().await?)
+    }
+
     /// Obtains number of network monitor test runs that have occurred within the specified interval.
     ///
     /// # Arguments
@@ -782,4 +872,63 @@ impl NymApiStorage {
             .get_gateway_statuses(gateway_identity, limit, offset)
             .await?)
     }
+
+    /// Stores the provided (already verified) node location attestation unless a more recent one
+    /// already exists. Returns whether the attestation has been persisted.
+    pub(crate) async fn submit_node_location_attestation(
+        &self,
+        attestation: &SignedNodeLocationAttestation,
+    ) -> Result<bool, NymApiStorageError> {
+        let stored = StoredNodeLocationAttestation {
+            identity_key: attestation.attestation.identity_key.clone(),
+            country_code: attestation.attestation.two_letter_iso_country_code.clone(),
+            latitude: attestation.attestation.latitude,
+            longitude: attestation.attestation.longitude,
+            timestamp: attestation.attestation.timestamp,
+            signature: attestation.signature.to_base58_string(),
+        };
+
+        Ok(self
+            .manager
+            .upsert_node_location_attestation(&stored)
+            .await?)
+    }
+
+    pub(crate) async fn get_node_location_attestation(
+        &self,
+        identity_key: &str,
+    ) -> Result<Option<SignedNodeLocationAttestation>, NymApiStorageError> {
+        let Some(stored) = self
+            .manager
+            .get_node_location_attestation(identity_key)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        stored.try_into().map(Some).map_err(|err| {
+            NymApiStorageError::database_inconsistency(format!(
+                "stored location attestation of {identity_key} has a malformed signature: {err}"
+            ))
+        })
+    }
+
+    /// Obtains location attestations of all nodes, keyed by their identity.
+    pub(crate) async fn get_all_node_location_attestations(
+        &self,
+    ) -> Result<HashMap<IdentityKey, NodeLocationAttestation>, NymApiStorageError> {
+        Ok(self
+            .manager
+            .get_all_node_location_attestations()
+            .await?
+            .into_iter()
+            .filter_map(|stored| {
+                let signed: SignedNodeLocationAttestation = stored
+                    .try_into()
+                    .inspect_err(|err| warn!("malformed stored location attestation: {err}"))
+                    .ok()?;
+                Some((signed.attestation.identity_key.clone(), signed.attestation))
+            })
+            .collect())
+    }
 }
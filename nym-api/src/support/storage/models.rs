@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use nym_api_requests::models::TestNode;
+use nym_api_requests::node_location::{NodeLocationAttestation, SignedNodeLocationAttestation};
+use nym_crypto::asymmetric::identity;
 use nym_mixnet_contract_common::MixId;
 
 // Internally used struct to catch results from the database to calculate uptimes for given mixnode/gateway
@@ -20,6 +22,21 @@ impl NodeStatus {
     }
 }
 
+// Internally used structs to catch the most recent per packet type results of all mixnodes/gateways
+pub(crate) struct MixnodePacketTypeStatus {
+    pub mix_id: MixId,
+    pub packet_type: u8,
+    pub reliability: u8,
+    pub timestamp: i64,
+}
+
+pub(crate) struct GatewayPacketTypeStatus {
+    pub identity: String,
+    pub packet_type: u8,
+    pub reliability: u8,
+    pub timestamp: i64,
+}
+
 // Internally used structs to catch results from the database to find active mixnodes
 pub(crate) struct ActiveMixnode {
     pub(crate) id: i64,
@@ -111,3 +128,29 @@ pub struct TestedGatewayStatus {
     pub layer3_mix_id: i64,
     pub monitor_run_id: i64,
 }
+
+pub(crate) struct StoredNodeLocationAttestation {
+    pub identity_key: String,
+    pub country_code: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub timestamp: i64,
+    pub signature: String,
+}
+
+impl TryFrom<StoredNodeLocationAttestation> for SignedNodeLocationAttestation {
+    type Error = identity::Ed25519RecoveryError;
+
+    fn try_from(value: StoredNodeLocationAttestation) -> Result<Self, Self::Error> {
+        Ok(SignedNodeLocationAttestation {
+            attestation: NodeLocationAttestation {
+                identity_key: value.identity_key,
+                two_letter_iso_country_code: value.country_code,
+                latitude: value.latitude,
+                longitude: value.longitude,
+                timestamp: value.timestamp,
+            },
+            signature: identity::Signature::from_base58_string(value.signature)?,
+        })
+    }
+}
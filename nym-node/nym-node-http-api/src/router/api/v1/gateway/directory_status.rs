@@ -0,0 +1,35 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::router::api::{FormattedResponse, OutputParams};
+use crate::state::gateway::SharedDirectoryStatus;
+use axum::extract::Query;
+use axum::http::StatusCode;
+use nym_node_requests::api::v1::gateway::models::DirectoryStatus;
+
+/// Returns the discrepancies between this gateway's entry in the network directory and its configuration.
+/// This information is **PURELY** self-reported and in no way validated.
+#[utoipa::path(
+    get,
+    path = "/directory-status",
+    context_path = "/api/v1/gateway",
+    tag = "Gateway",
+    responses(
+        (status = 501, description = "the gateway is not monitoring its directory entry"),
+        (status = 200, content(
+            ("application/json" = DirectoryStatus),
+            ("application/yaml" = DirectoryStatus)
+        ))
+    ),
+    params(OutputParams)
+)]
+pub(crate) async fn directory_status(
+    status: Option<SharedDirectoryStatus>,
+    Query(output): Query<OutputParams>,
+) -> Result<DirectoryStatusResponse, StatusCode> {
+    let status = status.ok_or(StatusCode::NOT_IMPLEMENTED)?;
+    let output = output.output.unwrap_or_default();
+    Ok(output.to_response(status.current().await))
+}
+
+pub type DirectoryStatusResponse = FormattedResponse<DirectoryStatus>;
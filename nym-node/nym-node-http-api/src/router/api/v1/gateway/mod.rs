@@ -1,17 +1,20 @@
 // Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
+use crate::state::gateway::SharedDirectoryStatus;
 use axum::routing::get;
 use axum::Router;
 use nym_node_requests::api::v1::gateway::models;
 use nym_node_requests::routes::api::v1::gateway;
 
 pub mod client_interfaces;
+pub mod directory_status;
 pub mod root;
 
 #[derive(Debug, Clone, Default)]
 pub struct Config {
     pub details: Option<models::Gateway>,
+    pub directory_status: Option<SharedDirectoryStatus>,
 }
 
 pub(crate) fn routes<S: Send + Sync + 'static + Clone>(config: Config) -> Router<S> {
@@ -23,6 +26,13 @@ pub(crate) fn routes<S: Send + Sync + 'static + Clone>(config: Config) -> Router
                 move |query| root::root_gateway(gateway_details, query)
             }),
         )
+        .route(
+            gateway::DIRECTORY_STATUS,
+            get({
+                let status = config.directory_status.clone();
+                move |query| directory_status::directory_status(status, query)
+            }),
+        )
         .nest(
             gateway::CLIENT_INTERFACES,
             client_interfaces::routes(config.details.map(|g| g.client_interfaces)),
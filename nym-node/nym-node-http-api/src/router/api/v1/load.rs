@@ -0,0 +1,34 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::api::{FormattedResponse, OutputParams};
+use crate::state::AppState;
+use axum::extract::{Query, State};
+use nym_node_requests::api::v1::load::models::NodeLoad;
+
+/// Returns the current packet load of this node.
+/// This information is **PURELY** self-reported and in no way validated.
+#[utoipa::path(
+    get,
+    path = "/load",
+    context_path = "/api/v1",
+    tag = "Load",
+    responses(
+        (status = 200, content(
+            ("application/json" = NodeLoad),
+            ("application/yaml" = NodeLoad)
+        ))
+    ),
+    params(OutputParams)
+)]
+pub(crate) async fn root_load(
+    Query(output): Query<OutputParams>,
+    State(state): State<AppState>,
+) -> LoadResponse {
+    let output = output.output.unwrap_or_default();
+    let stats = state.metrics.mixing_stats.read().await.as_response();
+
+    output.to_response(NodeLoad::from(stats))
+}
+
+pub type LoadResponse = FormattedResponse<NodeLoad>;
@@ -10,6 +10,7 @@ pub mod authenticator;
 pub mod gateway;
 pub mod health;
 pub mod ip_packet_router;
+pub mod load;
 pub mod metrics;
 pub mod mixnode;
 pub mod network_requester;
@@ -30,6 +31,7 @@ pub struct Config {
 pub(super) fn routes(config: Config) -> Router<AppState> {
     Router::new()
         .route(v1::HEALTH, get(health::root_health))
+        .route(v1::LOAD, get(load::root_load))
         .nest(v1::METRICS, metrics::routes(config.metrics))
         .nest(v1::GATEWAY, gateway::routes(config.gateway))
         .nest(v1::MIXNODE, mixnode::routes(config.mixnode))
@@ -24,9 +24,11 @@ use utoipa_swagger_ui::SwaggerUi;
         api::v1::metrics::verloc::verloc_stats,
         api::v1::metrics::prometheus::prometheus_metrics,
         api::v1::health::root_health,
+        api::v1::load::root_load,
         api::v1::gateway::root::root_gateway,
         api::v1::gateway::client_interfaces::client_interfaces,
         api::v1::gateway::client_interfaces::mixnet_websockets,
+        api::v1::gateway::directory_status::directory_status,
         api::v1::mixnode::root::root_mixnode,
         api::v1::network_requester::root::root_network_requester,
         api::v1::network_requester::exit_policy::node_exit_policy,
@@ -39,6 +41,7 @@ use utoipa_swagger_ui::SwaggerUi;
             api::OutputParams,
             api_requests::v1::health::models::NodeHealth,
             api_requests::v1::health::models::NodeStatus,
+            api_requests::v1::load::models::NodeLoad,
             api_requests::v1::node::models::BinaryBuildInformationOwned,
             api_requests::v1::node::models::SignedHostInformation,
             api_requests::v1::node::models::HostInformation,
@@ -60,6 +63,8 @@ use utoipa_swagger_ui::SwaggerUi;
             api_requests::v1::gateway::models::Wireguard,
             api_requests::v1::gateway::models::ClientInterfaces,
             api_requests::v1::gateway::models::WebSockets,
+            api_requests::v1::gateway::models::DirectoryStatus,
+            api_requests::v1::gateway::models::DirectoryDrift,
             api_requests::v1::mixnode::models::Mixnode,
             api_requests::v1::network_requester::models::NetworkRequester,
             api_requests::v1::network_requester::exit_policy::models::AddressPolicy,
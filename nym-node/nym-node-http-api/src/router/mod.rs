@@ -3,6 +3,7 @@
 
 use crate::error::NymNodeHttpError;
 use crate::middleware::logging;
+use crate::state::gateway::SharedDirectoryStatus;
 use crate::state::AppState;
 use crate::NymNodeHTTPServer;
 use axum::response::Redirect;
@@ -108,6 +109,12 @@ impl Config {
         self
     }
 
+    #[must_use]
+    pub fn with_gateway_directory_status(mut self, status: SharedDirectoryStatus) -> Self {
+        self.api.v1_config.gateway.directory_status = Some(status);
+        self
+    }
+
     #[must_use]
     pub fn with_mixnode(mut self, mixnode: Mixnode) -> Self {
         self.api.v1_config.node.roles.mixnode_enabled = true;
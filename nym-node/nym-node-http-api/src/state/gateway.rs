@@ -0,0 +1,36 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use nym_node_requests::api::v1::gateway::models::{DirectoryDrift, DirectoryStatus};
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tokio::sync::RwLock;
+
+/// Status of the gateway's own directory entry, updated by its self-monitoring task.
+#[derive(Clone, Debug, Default)]
+pub struct SharedDirectoryStatus {
+    inner: Arc<RwLock<DirectoryStatus>>,
+}
+
+impl SharedDirectoryStatus {
+    pub fn new() -> SharedDirectoryStatus {
+        Default::default()
+    }
+
+    pub async fn current(&self) -> DirectoryStatus {
+        self.inner.read().await.clone()
+    }
+
+    pub async fn record_check(&self, drifts: Vec<DirectoryDrift>) {
+        let mut guard = self.inner.write().await;
+        guard.last_checked = Some(OffsetDateTime::now_utc());
+        guard.last_error = None;
+        guard.drifts = drifts;
+    }
+
+    /// Records the failure of the check. The drifts detected previously are kept as they're likely
+    /// still relevant.
+    pub async fn record_failure(&self, error: impl Into<String>) {
+        self.inner.write().await.last_error = Some(error.into());
+    }
+}
@@ -4,6 +4,7 @@
 use crate::state::metrics::{MetricsAppState, SharedMixingStats, SharedVerlocStats};
 use tokio::time::Instant;
 
+pub mod gateway;
 pub mod metrics;
 
 #[derive(Debug, Clone)]
@@ -1,8 +1,10 @@
 // Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::api::v1::gateway::models::WebSockets;
-use crate::api::v1::node::models::{AuxiliaryDetails, SignedHostInformation};
+use crate::api::v1::gateway::models::{DirectoryStatus, WebSockets};
+use crate::api::v1::node::models::{
+    AuxiliaryDetails, HostSystem, NodeDescription, NodeRoles, SignedHostInformation,
+};
 use crate::api::ErrorResponse;
 use crate::routes;
 use async_trait::async_trait;
@@ -12,6 +14,8 @@ use nym_http_api_client::{ApiClient, HttpClientError};
 use crate::api::v1::authenticator::models::Authenticator;
 use crate::api::v1::health::models::NodeHealth;
 use crate::api::v1::ip_packet_router::models::IpPacketRouter;
+use crate::api::v1::load::models::NodeLoad;
+use crate::api::v1::metrics::models::MixingStats;
 use crate::api::v1::network_requester::exit_policy::models::UsedExitPolicy;
 use crate::api::v1::network_requester::models::NetworkRequester;
 pub use nym_http_api_client::Client;
@@ -27,6 +31,29 @@ pub trait NymNodeApiClientExt: ApiClient {
         self.get_json_from(routes::api::v1::health_absolute()).await
     }
 
+    async fn get_load(&self) -> Result<NodeLoad, NymNodeApiClientError> {
+        self.get_json_from(routes::api::v1::load_absolute()).await
+    }
+
+    async fn get_roles(&self) -> Result<NodeRoles, NymNodeApiClientError> {
+        self.get_json_from(routes::api::v1::roles_absolute()).await
+    }
+
+    async fn get_description(&self) -> Result<NodeDescription, NymNodeApiClientError> {
+        self.get_json_from(routes::api::v1::description_absolute())
+            .await
+    }
+
+    async fn get_system_information(&self) -> Result<HostSystem, NymNodeApiClientError> {
+        self.get_json_from(routes::api::v1::system_info_absolute())
+            .await
+    }
+
+    async fn get_mixing_stats(&self) -> Result<MixingStats, NymNodeApiClientError> {
+        self.get_json_from(routes::api::v1::metrics::mixing_absolute())
+            .await
+    }
+
     async fn get_host_information(&self) -> Result<SignedHostInformation, NymNodeApiClientError> {
         self.get_json_from(routes::api::v1::host_info_absolute())
             .await
@@ -52,6 +79,11 @@ pub trait NymNodeApiClientExt: ApiClient {
         .await
     }
 
+    async fn get_gateway_directory_status(&self) -> Result<DirectoryStatus, NymNodeApiClientError> {
+        self.get_json_from(routes::api::v1::gateway::directory_status_absolute())
+            .await
+    }
+
     async fn get_network_requester(&self) -> Result<NetworkRequester, NymNodeApiClientError> {
         self.get_json_from(routes::api::v1::network_requester_absolute())
             .await
@@ -3,6 +3,8 @@
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use time::OffsetDateTime;
 
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
@@ -39,3 +41,104 @@ pub struct WebSockets {
 
     pub wss_port: Option<u16>,
 }
+
+/// Outcome of the gateway comparing its own entry in the network directory (as seen by the nym-api)
+/// against its local configuration.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DirectoryStatus {
+    /// Time of the most recent successful check, if any.
+    #[serde(with = "time::serde::rfc3339::option")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    pub last_checked: Option<OffsetDateTime>,
+
+    /// Reason for the failure of the most recent check, if it didn't succeed.
+    pub last_error: Option<String>,
+
+    /// Discrepancies detected during the most recent successful check.
+    pub drifts: Vec<DirectoryDrift>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DirectoryDrift {
+    /// The gateway is not present in the directory at all.
+    NotBonded,
+
+    /// The announced host doesn't match any of the configured public ips or the hostname.
+    HostMismatch {
+        announced: String,
+        configured: Vec<String>,
+    },
+
+    MixPortMismatch {
+        announced: u16,
+        configured: u16,
+    },
+
+    ClientsPortMismatch {
+        announced: u16,
+        configured: u16,
+    },
+
+    /// The announced version is different from the one that's actually running.
+    VersionMismatch {
+        announced: String,
+        running: String,
+    },
+
+    Blacklisted,
+
+    /// The most recent performance (in percent) is below the configured threshold.
+    LowPerformance {
+        performance: u8,
+    },
+
+    /// The performance (in percent) has dropped considerably since the previous check.
+    PerformanceDrop {
+        previous: u8,
+        current: u8,
+    },
+}
+
+impl Display for DirectoryDrift {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DirectoryDrift::NotBonded => write!(f, "the gateway is not present in the directory"),
+            DirectoryDrift::HostMismatch {
+                announced,
+                configured,
+            } => write!(
+                f,
+                "the announced host '{announced}' doesn't match any of the configured ones: {configured:?}"
+            ),
+            DirectoryDrift::MixPortMismatch {
+                announced,
+                configured,
+            } => write!(
+                f,
+                "the announced mix port {announced} is different from the configured {configured}"
+            ),
+            DirectoryDrift::ClientsPortMismatch {
+                announced,
+                configured,
+            } => write!(
+                f,
+                "the announced clients port {announced} is different from the configured {configured}"
+            ),
+            DirectoryDrift::VersionMismatch { announced, running } => write!(
+                f,
+                "the announced version {announced} is different from the running {running}"
+            ),
+            DirectoryDrift::Blacklisted => write!(f, "the gateway has been blacklisted"),
+            DirectoryDrift::LowPerformance { performance } => {
+                write!(f, "the most recent performance is only {performance}%")
+            }
+            DirectoryDrift::PerformanceDrop { previous, current } => write!(
+                f,
+                "the performance has dropped from {previous}% to {current}%"
+            ),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::api::v1::metrics::models::MixingStats;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// Current packet load of the node, derived from the most recent mixing statistics update.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NodeLoad {
+    /// Time of the most recent mixing statistics update the load has been derived from.
+    #[serde(with = "time::serde::rfc3339")]
+    pub measurement_time: OffsetDateTime,
+
+    /// Duration, in seconds, of the interval the load has been averaged over.
+    pub measurement_interval_secs: f64,
+
+    /// Average number of packets received per second.
+    pub received_per_second: f64,
+
+    /// Average number of packets sent per second.
+    // note: sent does not imply forwarded. We don't know if it was delivered successfully
+    pub sent_per_second: f64,
+
+    /// Average number of packets explicitly dropped per second.
+    pub dropped_per_second: f64,
+}
+
+impl NodeLoad {
+    pub fn from_mixing_stats(stats: &MixingStats) -> Self {
+        let interval = (stats.update_time - stats.previous_update_time).as_seconds_f64();
+
+        let rate = |packets: u64| {
+            if interval > 0. {
+                packets as f64 / interval
+            } else {
+                0.
+            }
+        };
+
+        NodeLoad {
+            measurement_time: stats.update_time,
+            measurement_interval_secs: interval.max(0.),
+            received_per_second: rate(stats.received_since_last_update),
+            sent_per_second: rate(stats.sent_since_last_update),
+            dropped_per_second: rate(stats.dropped_since_last_update),
+        }
+    }
+}
+
+impl From<MixingStats> for NodeLoad {
+    fn from(stats: MixingStats) -> Self {
+        NodeLoad::from_mixing_stats(&stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Duration;
+
+    fn stats(interval: Duration) -> MixingStats {
+        let now = OffsetDateTime::now_utc();
+        MixingStats {
+            update_time: now,
+            previous_update_time: now - interval,
+            received_since_startup: 1000,
+            sent_since_startup: 900,
+            dropped_since_startup: 100,
+            received_since_last_update: 100,
+            sent_since_last_update: 50,
+            dropped_since_last_update: 10,
+        }
+    }
+
+    #[test]
+    fn load_is_averaged_over_update_interval() {
+        let load = NodeLoad::from_mixing_stats(&stats(Duration::seconds(10)));
+        assert_eq!(load.measurement_interval_secs, 10.);
+        assert_eq!(load.received_per_second, 10.);
+        assert_eq!(load.sent_per_second, 5.);
+        assert_eq!(load.dropped_per_second, 1.);
+    }
+
+    #[test]
+    fn empty_interval_results_in_zero_load() {
+        let load = NodeLoad::from_mixing_stats(&stats(Duration::ZERO));
+        assert_eq!(load.received_per_second, 0.);
+        assert_eq!(load.sent_per_second, 0.);
+        assert_eq!(load.dropped_per_second, 0.);
+    }
+}
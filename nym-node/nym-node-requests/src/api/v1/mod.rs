@@ -5,6 +5,7 @@ pub mod authenticator;
 pub mod gateway;
 pub mod health;
 pub mod ip_packet_router;
+pub mod load;
 pub mod metrics;
 pub mod mixnode;
 pub mod network_requester;
@@ -35,6 +35,7 @@ pub mod routes {
             pub const NODE_DESCRIPTION: &str = "/description";
             pub const AUXILIARY: &str = "/auxiliary-details";
             pub const HEALTH: &str = "/health";
+            pub const LOAD: &str = "/load";
             pub const SWAGGER: &str = "/swagger";
 
             pub const GATEWAY: &str = "/gateway";
@@ -46,6 +47,7 @@ pub mod routes {
 
             // define helper functions to get absolute routes
             absolute_route!(health_absolute, v1_absolute(), HEALTH);
+            absolute_route!(load_absolute, v1_absolute(), LOAD);
             absolute_route!(roles_absolute, v1_absolute(), ROLES);
             absolute_route!(build_info_absolute, v1_absolute(), BUILD_INFO);
             absolute_route!(host_info_absolute, v1_absolute(), HOST_INFO);
@@ -77,12 +79,18 @@ pub mod routes {
                 use super::*;
 
                 pub const CLIENT_INTERFACES: &str = "/client-interfaces";
+                pub const DIRECTORY_STATUS: &str = "/directory-status";
 
                 absolute_route!(
                     client_interfaces_absolute,
                     gateway_absolute(),
                     CLIENT_INTERFACES
                 );
+                absolute_route!(
+                    directory_status_absolute,
+                    gateway_absolute(),
+                    DIRECTORY_STATUS
+                );
 
                 pub mod client_interfaces {
                     use super::*;
@@ -139,6 +147,7 @@ mod tests {
             routes::api::v1::host_info_absolute()
         );
         assert_eq!("/api/v1/roles", routes::api::v1::roles_absolute());
+        assert_eq!("/api/v1/load", routes::api::v1::load_absolute());
 
         assert_eq!("/api/v1/gateway", routes::api::v1::gateway_absolute());
         assert_eq!(
@@ -153,6 +162,10 @@ mod tests {
             "/api/v1/gateway/client-interfaces/mixnet-websockets",
             routes::api::v1::gateway::client_interfaces::mixnet_websockets_absolute()
         );
+        assert_eq!(
+            "/api/v1/gateway/directory-status",
+            routes::api::v1::gateway::directory_status_absolute()
+        );
 
         assert_eq!("/api/v1/mixnode", routes::api::v1::mixnode_absolute());
         assert_eq!(
@@ -410,6 +410,7 @@ async fn migrate_gateway(mut args: Args) -> Result<(), NymNodeError> {
                 bind_address: SocketAddr::new(ip, cfg.gateway.clients_port),
                 announce_ws_port: None,
                 announce_wss_port: cfg.gateway.clients_wss_port,
+                framed_port: cfg.gateway.clients_framed_port,
                 debug: config::entry_gateway::Debug {
                     message_retrieval_limit: cfg.debug.message_retrieval_limit,
                     zk_nym_tickets: ZkNymTicketHandlerDebug {
@@ -422,6 +423,13 @@ async fn migrate_gateway(mut args: Args) -> Result<(), NymNodeError> {
                         maximum_time_between_redemption:
                             cfg.debug.zk_nym_tickets.maximum_time_between_redemption,
                     },
+                    client_sessions: cfg.debug.client_sessions,
+                    share_protocol_stats: cfg.debug.share_protocol_stats,
+                    disable_stored_messages_encryption:
+                        cfg.debug.disable_stored_messages_encryption,
+                    message_store: cfg.debug.message_store.clone(),
+                    process_forward_hops: cfg.debug.process_forward_hops,
+                    maximum_delayed_forward_hops: cfg.debug.maximum_delayed_forward_hops,
                 },
             },
         ))
@@ -8,6 +8,7 @@ use crate::error::EntryGatewayError;
 use nym_config::defaults::{DEFAULT_CLIENT_LISTENING_PORT, TICKETBOOK_VALIDITY_DAYS};
 use nym_config::helpers::inaddr_any;
 use nym_config::serde_helpers::de_maybe_port;
+use nym_gateway::config::{ClientSessionsDebug, MessageStoreDebug};
 use nym_gateway::node::LocalAuthenticatorOpts;
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
@@ -43,6 +44,12 @@ pub struct EntryGatewayConfig {
     #[serde(deserialize_with = "de_maybe_port")]
     pub announce_wss_port: Option<u16>,
 
+    /// If applicable, port used for binding the client API using the raw framed TCP transport
+    /// (rather than the websocket) on the same ip as the `bind_address`.
+    /// default: None
+    #[serde(default, deserialize_with = "de_maybe_port")]
+    pub framed_port: Option<u16>,
+
     #[serde(default)]
     pub debug: Debug,
 }
@@ -55,10 +62,33 @@ pub struct Debug {
     pub message_retrieval_limit: i64,
 
     pub zk_nym_tickets: ZkNymTicketHandlerDebug,
+
+    /// Specifies the behaviour upon a client opening multiple concurrent websocket sessions.
+    pub client_sessions: ClientSessionsDebug,
+
+    /// Specifies whether the gateway should share its own observations with the clients
+    /// that request a protocol statistics exchange.
+    pub share_protocol_stats: bool,
+
+    /// Specifies whether the gateway should stop encrypting the messages stored for offline clients
+    /// with keys derived from the shared keys of their recipients.
+    pub disable_stored_messages_encryption: bool,
+
+    /// Specifies where and for how long the messages of offline clients are stored.
+    pub message_store: MessageStoreDebug,
+
+    /// Specifies whether the gateway should process the received packets that are meant to be forwarded
+    /// to another node, i.e. act as a regular mix hop, rather than dropping them.
+    pub process_forward_hops: bool,
+
+    /// Maximum number of forward hop packets that can be waiting for their delay to expire at any given time.
+    /// Any packets received beyond that are dropped.
+    pub maximum_delayed_forward_hops: usize,
 }
 
 impl Debug {
     const DEFAULT_MESSAGE_RETRIEVAL_LIMIT: i64 = 100;
+    const DEFAULT_MAXIMUM_DELAYED_FORWARD_HOPS: usize = 10_000;
 }
 
 impl Default for Debug {
@@ -66,6 +96,12 @@ impl Default for Debug {
         Debug {
             message_retrieval_limit: Self::DEFAULT_MESSAGE_RETRIEVAL_LIMIT,
             zk_nym_tickets: Default::default(),
+            client_sessions: Default::default(),
+            share_protocol_stats: false,
+            disable_stored_messages_encryption: false,
+            message_store: Default::default(),
+            process_forward_hops: false,
+            maximum_delayed_forward_hops: Self::DEFAULT_MAXIMUM_DELAYED_FORWARD_HOPS,
         }
     }
 }
@@ -144,6 +180,7 @@ impl EntryGatewayConfig {
             bind_address: SocketAddr::new(inaddr_any(), DEFAULT_WS_PORT),
             announce_ws_port: None,
             announce_wss_port: None,
+            framed_port: None,
             debug: Default::default(),
         }
     }
@@ -156,10 +193,10 @@ pub fn ephemeral_entry_gateway_config(
 ) -> Result<EphemeralConfig, EntryGatewayError> {
     let auth_opts = LocalAuthenticatorOpts {
         config: nym_authenticator::Config {
-            base: nym_client_core_config_types::Config {
-                client: base_client_config(&config),
-                debug: config.authenticator.debug.client_debug,
-            },
+            base: nym_client_core_config_types::Config::from_client_config(
+                base_client_config(&config),
+                config.authenticator.debug.client_debug,
+            ),
             authenticator: config.wireguard.clone().into(),
             storage_paths: nym_authenticator::config::AuthenticatorPaths {
                 common_paths: config
@@ -168,10 +168,10 @@ pub fn ephemeral_exit_gateway_config(
 ) -> Result<EphemeralConfig, ExitGatewayError> {
     let mut nr_opts = LocalNetworkRequesterOpts {
         config: nym_network_requester::Config {
-            base: nym_client_core_config_types::Config {
-                client: base_client_config(&config),
-                debug: config.exit_gateway.network_requester.debug.client_debug,
-            },
+            base: nym_client_core_config_types::Config::from_client_config(
+                base_client_config(&config),
+                config.exit_gateway.network_requester.debug.client_debug,
+            ),
             network_requester: nym_network_requester::config::NetworkRequester {
                 open_proxy: config.exit_gateway.open_proxy,
                 disable_poisson_rate: config
@@ -182,6 +182,7 @@ pub fn ephemeral_exit_gateway_config(
                 upstream_exit_policy_url: Some(
                     config.exit_gateway.upstream_exit_policy_url.clone(),
                 ),
+                host_allowlist: Vec::new(),
             },
             storage_paths: nym_network_requester::config::NetworkRequesterPaths {
                 common_paths: config
@@ -211,10 +212,10 @@ pub fn ephemeral_exit_gateway_config(
 
     let mut ipr_opts = LocalIpPacketRouterOpts {
         config: nym_ip_packet_router::Config {
-            base: nym_client_core_config_types::Config {
-                client: base_client_config(&config),
-                debug: config.exit_gateway.ip_packet_router.debug.client_debug,
-            },
+            base: nym_client_core_config_types::Config::from_client_config(
+                base_client_config(&config),
+                config.exit_gateway.ip_packet_router.debug.client_debug,
+            ),
             ip_packet_router: nym_ip_packet_router::config::IpPacketRouter {
                 disable_poisson_rate: config
                     .exit_gateway
@@ -245,10 +246,10 @@ pub fn ephemeral_exit_gateway_config(
 
     let auth_opts = LocalAuthenticatorOpts {
         config: nym_authenticator::Config {
-            base: nym_client_core_config_types::Config {
-                client: base_client_config(&config),
-                debug: config.authenticator.debug.client_debug,
-            },
+            base: nym_client_core_config_types::Config::from_client_config(
+                base_client_config(&config),
+                config.authenticator.debug.client_debug,
+            ),
             authenticator: config.wireguard.clone().into(),
             storage_paths: nym_authenticator::config::AuthenticatorPaths {
                 common_paths: config
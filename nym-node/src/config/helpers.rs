@@ -53,6 +53,7 @@ pub fn ephemeral_gateway_config(
         mix_port: config.mixnet.bind_address.port(),
         clients_port: config.entry_gateway.bind_address.port(),
         clients_wss_port: config.entry_gateway.announce_wss_port,
+        clients_framed_port: config.entry_gateway.framed_port,
         nym_api_urls: config.mixnet.nym_api_urls,
         nyxd_urls: config.mixnet.nyxd_urls,
 
@@ -100,6 +101,21 @@ pub fn ephemeral_gateway_config(
                     .zk_nym_tickets
                     .maximum_time_between_redemption,
             },
+            client_sessions: config.entry_gateway.debug.client_sessions,
+            share_protocol_stats: config.entry_gateway.debug.share_protocol_stats,
+            disable_stored_messages_encryption: config
+                .entry_gateway
+                .debug
+                .disable_stored_messages_encryption,
+            message_store: config.entry_gateway.debug.message_store,
+            process_forward_hops: config.entry_gateway.debug.process_forward_hops,
+            maximum_delayed_forward_hops: config.entry_gateway.debug.maximum_delayed_forward_hops,
+            // the announced version is the one of the nym-node rather than of the embedded gateway,
+            // and the status wouldn't be exposed anyway as the gateway's http server is not running
+            directory_monitor: nym_gateway::config::DirectoryMonitorDebug {
+                enabled: false,
+                ..Default::default()
+            },
             ..Default::default()
         },
     ))
@@ -113,6 +129,8 @@ pub fn base_client_config(config: &Config) -> nym_client_core_config_types::Clie
         disabled_credentials_mode: true,
         nyxd_urls: config.mixnet.nyxd_urls.clone(),
         nym_api_urls: config.mixnet.nym_api_urls.clone(),
+        // embedded clients always use the topology provided by the nym-api
+        topology_file: None,
     }
 }
 
@@ -1127,10 +1127,16 @@ pub async fn try_upgrade_config_v3<P: AsRef<Path>>(
             bind_address: old_cfg.entry_gateway.bind_address,
             announce_ws_port: old_cfg.entry_gateway.announce_ws_port,
             announce_wss_port: old_cfg.entry_gateway.announce_wss_port,
+            framed_port: None,
             debug: EntryGatewayConfigDebug {
                 message_retrieval_limit: old_cfg.entry_gateway.debug.message_retrieval_limit,
                 // \/ ADDED
                 zk_nym_tickets: Default::default(),
+                client_sessions: Default::default(),
+                share_protocol_stats: false,
+                disable_stored_messages_encryption: false,
+                message_store: Default::default(),
+                ..Default::default()
             },
         },
         exit_gateway: ExitGatewayConfig {
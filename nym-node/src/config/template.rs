@@ -167,6 +167,10 @@ announce_ws_port = {{#if entry_gateway.announce_ws_port }} {{ entry_gateway.anno
 # (default: 0 - disabled)
 announce_wss_port = {{#if entry_gateway.announce_wss_port }} {{ entry_gateway.announce_wss_port }} {{else}} 0 {{/if}}
 
+# If applicable, port used for binding the client API using the raw framed TCP transport.
+# (default: 0 - disabled)
+framed_port = {{#if entry_gateway.framed_port }} {{ entry_gateway.framed_port }} {{else}} 0 {{/if}}
+
 
 [entry_gateway.storage_paths]
 # Path to sqlite database containing all persistent data: messages for offline clients,
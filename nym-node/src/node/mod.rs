@@ -10,6 +10,7 @@ use crate::node::helpers::{
 use crate::node::http::{sign_host_details, system_info::get_system_info};
 use nym_bin_common::bin_info_owned;
 use nym_crypto::asymmetric::{ed25519, x25519};
+use nym_gateway::node::with_configured_message_store;
 use nym_gateway::Gateway;
 use nym_mixnode::MixNode;
 use nym_network_requester::{
@@ -569,6 +570,22 @@ impl NymNode {
 
         let config =
             ephemeral_entry_gateway_config(self.config.clone(), &self.entry_gateway.mnemonic)?;
+        let client_storage = with_configured_message_store(
+            &config.gateway,
+            self.entry_gateway
+                .client_storage
+                .clone()
+                .with_inbox_encryption(
+                    !self
+                        .config
+                        .entry_gateway
+                        .debug
+                        .disable_stored_messages_encryption,
+                ),
+            &self.config.entry_gateway.storage_paths.clients_storage,
+            &self.ed25519_identity_keys,
+        )
+        .map_err(EntryGatewayError::from)?;
         let mut entry_gateway = Gateway::new_loaded(
             config.gateway,
             config.nr_opts,
@@ -576,7 +593,7 @@ impl NymNode {
             Some(config.auth_opts),
             self.ed25519_identity_keys.clone(),
             self.x25519_sphinx_keys.clone(),
-            self.entry_gateway.client_storage.clone(),
+            client_storage,
         );
         entry_gateway.disable_http_server();
         entry_gateway.set_task_client(task_client);
@@ -598,6 +615,22 @@ impl NymNode {
         let config =
             ephemeral_exit_gateway_config(self.config.clone(), &self.entry_gateway.mnemonic)?;
 
+        let client_storage = with_configured_message_store(
+            &config.gateway,
+            self.exit_gateway
+                .client_storage
+                .clone()
+                .with_inbox_encryption(
+                    !self
+                        .config
+                        .entry_gateway
+                        .debug
+                        .disable_stored_messages_encryption,
+                ),
+            &self.config.exit_gateway.storage_paths.clients_storage,
+            &self.ed25519_identity_keys,
+        )
+        .map_err(ExitGatewayError::from)?;
         let mut exit_gateway = Gateway::new_loaded(
             config.gateway,
             config.nr_opts,
@@ -605,7 +638,7 @@ impl NymNode {
             Some(config.auth_opts),
             self.ed25519_identity_keys.clone(),
             self.x25519_sphinx_keys.clone(),
-            self.exit_gateway.client_storage.clone(),
+            client_storage,
         );
         exit_gateway.disable_http_server();
         exit_gateway.set_task_client(task_client);
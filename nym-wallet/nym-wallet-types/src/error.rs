@@ -0,0 +1,174 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use strum::EnumIter;
+
+/// Stable identifier of an error returned by the wallet backend.
+/// The codes are never renamed nor reused, so that the frontend could rely on them for localising
+/// the messages and for linking to the relevant remediation steps.
+#[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
+#[cfg_attr(
+    feature = "generate-ts",
+    ts(export_to = "nym-wallet/src/types/rust/BackendErrorCode.ts")
+)]
+#[derive(Clone, Copy, Debug, Deserialize, EnumIter, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendErrorCode {
+    /// An unexpected failure without any dedicated remediation.
+    Internal,
+
+    // connection
+    /// The validator (nyxd) endpoint could not be reached or it returned a transport error.
+    ValidatorUnreachable,
+    /// The chain rejected the request or the query has failed.
+    ChainRequestFailed,
+    /// The nym-api (network directory) could not be queried.
+    DirectoryUnavailable,
+    /// A generic http request has failed.
+    HttpRequestFailed,
+    /// The provided URL could not be parsed.
+    MalformedUrl,
+    /// The provided validator URL could not be connected to.
+    ValidatorConnectionFailed,
+    /// There isn't any default validator defined for the network.
+    NoDefaultValidator,
+    /// The client has not been initialised, i.e. the user is not signed in.
+    ClientNotInitialized,
+    /// The provided network is not supported.
+    NetworkNotSupported,
+
+    // wallet storage
+    /// The local data directory could not be accessed.
+    StorageUnavailable,
+    WalletFileAlreadyExists,
+    WalletFileNotFound,
+    WalletFileMalformed,
+    WalletFileArchiveFailed,
+    /// The stored wallet could not be decrypted, most likely due to an invalid password.
+    WalletDecryptionFailed,
+    LoginNotFound,
+    LoginAlreadyExists,
+    AccountNotFound,
+    AccountAlreadyExists,
+    MnemonicAlreadyExists,
+    NoRememberedPassword,
+    DifferentPassword,
+    UnexpectedMnemonicAccount,
+    KeyringUnavailable,
+
+    // keys and signatures
+    InvalidMnemonic,
+    AddressDerivationFailed,
+    SignatureFailed,
+    UnexpectedSigningAlgorithm,
+
+    // funds and operations
+    NoBalance,
+    InsufficientFunds,
+    InvalidAmount,
+    UnknownCoinDenom,
+    NoCoinsRegistered,
+    PledgeUpdateNoOp,
+    PledgeUpdateInvalidCurrency,
+    UnsupportedVestingOperation,
+    NoVestingDelegations,
+    NoDelegations,
+    NoBondedMixnode,
+    UnknownIbcChannel,
+
+    // application
+    WindowCreationFailed,
+    UpdateCheckFailed,
+    RemovedCommand,
+}
+
+/// Error, as sent to the frontend, containing all the information required for presenting it to the user.
+#[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
+#[cfg_attr(
+    feature = "generate-ts",
+    ts(export_to = "nym-wallet/src/types/rust/StructuredBackendError.ts")
+)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct StructuredBackendError {
+    pub code: BackendErrorCode,
+
+    /// Untranslated, human-readable, description of the error that can be used as the fallback.
+    pub message: String,
+
+    /// Named values to be interpolated into the localised message, such as the address or the amount.
+    pub params: BTreeMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    // the wire codes are relied upon by the frontend, so they must never change
+    fn wire_code(code: BackendErrorCode) -> &'static str {
+        match code {
+            BackendErrorCode::Internal => "internal",
+            BackendErrorCode::ValidatorUnreachable => "validator_unreachable",
+            BackendErrorCode::ChainRequestFailed => "chain_request_failed",
+            BackendErrorCode::DirectoryUnavailable => "directory_unavailable",
+            BackendErrorCode::HttpRequestFailed => "http_request_failed",
+            BackendErrorCode::MalformedUrl => "malformed_url",
+            BackendErrorCode::ValidatorConnectionFailed => "validator_connection_failed",
+            BackendErrorCode::NoDefaultValidator => "no_default_validator",
+            BackendErrorCode::ClientNotInitialized => "client_not_initialized",
+            BackendErrorCode::NetworkNotSupported => "network_not_supported",
+            BackendErrorCode::StorageUnavailable => "storage_unavailable",
+            BackendErrorCode::WalletFileAlreadyExists => "wallet_file_already_exists",
+            BackendErrorCode::WalletFileNotFound => "wallet_file_not_found",
+            BackendErrorCode::WalletFileMalformed => "wallet_file_malformed",
+            BackendErrorCode::WalletFileArchiveFailed => "wallet_file_archive_failed",
+            BackendErrorCode::WalletDecryptionFailed => "wallet_decryption_failed",
+            BackendErrorCode::LoginNotFound => "login_not_found",
+            BackendErrorCode::LoginAlreadyExists => "login_already_exists",
+            BackendErrorCode::AccountNotFound => "account_not_found",
+            BackendErrorCode::AccountAlreadyExists => "account_already_exists",
+            BackendErrorCode::MnemonicAlreadyExists => "mnemonic_already_exists",
+            BackendErrorCode::NoRememberedPassword => "no_remembered_password",
+            BackendErrorCode::DifferentPassword => "different_password",
+            BackendErrorCode::UnexpectedMnemonicAccount => "unexpected_mnemonic_account",
+            BackendErrorCode::KeyringUnavailable => "keyring_unavailable",
+            BackendErrorCode::InvalidMnemonic => "invalid_mnemonic",
+            BackendErrorCode::AddressDerivationFailed => "address_derivation_failed",
+            BackendErrorCode::SignatureFailed => "signature_failed",
+            BackendErrorCode::UnexpectedSigningAlgorithm => "unexpected_signing_algorithm",
+            BackendErrorCode::NoBalance => "no_balance",
+            BackendErrorCode::InsufficientFunds => "insufficient_funds",
+            BackendErrorCode::InvalidAmount => "invalid_amount",
+            BackendErrorCode::UnknownCoinDenom => "unknown_coin_denom",
+            BackendErrorCode::NoCoinsRegistered => "no_coins_registered",
+            BackendErrorCode::PledgeUpdateNoOp => "pledge_update_no_op",
+            BackendErrorCode::PledgeUpdateInvalidCurrency => "pledge_update_invalid_currency",
+            BackendErrorCode::UnsupportedVestingOperation => "unsupported_vesting_operation",
+            BackendErrorCode::NoVestingDelegations => "no_vesting_delegations",
+            BackendErrorCode::NoDelegations => "no_delegations",
+            BackendErrorCode::NoBondedMixnode => "no_bonded_mixnode",
+            BackendErrorCode::UnknownIbcChannel => "unknown_ibc_channel",
+            BackendErrorCode::WindowCreationFailed => "window_creation_failed",
+            BackendErrorCode::UpdateCheckFailed => "update_check_failed",
+            BackendErrorCode::RemovedCommand => "removed_command",
+        }
+    }
+
+    #[test]
+    fn every_error_code_has_a_stable_wire_representation() {
+        let mut seen = std::collections::HashSet::new();
+        for code in BackendErrorCode::iter() {
+            let serialized = serde_json::to_string(&code).unwrap();
+            assert_eq!(serialized, format!("\"{}\"", wire_code(code)));
+            assert!(
+                seen.insert(serialized.clone()),
+                "duplicate wire code {serialized}"
+            );
+
+            let deserialized: BackendErrorCode = serde_json::from_str(&serialized).unwrap();
+            assert_eq!(deserialized, code);
+        }
+    }
+}
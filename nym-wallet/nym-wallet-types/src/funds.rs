@@ -0,0 +1,35 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Specifies which funds should be used for given bonding or delegation operation.
+#[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
+#[cfg_attr(
+    feature = "generate-ts",
+    ts(export_to = "nym-wallet/src/types/rust/FundsSource.ts")
+)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FundsSource {
+    /// Use only the liquid tokens available on the account.
+    #[default]
+    Liquid,
+
+    /// Use only the (locked) tokens held by the vesting contract.
+    Vesting,
+
+    /// Use as much liquid tokens as possible and cover the remainder with the vesting tokens.
+    AutoSplit,
+}
+
+impl Display for FundsSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FundsSource::Liquid => write!(f, "liquid"),
+            FundsSource::Vesting => write!(f, "vesting"),
+            FundsSource::AutoSplit => write!(f, "auto-split"),
+        }
+    }
+}
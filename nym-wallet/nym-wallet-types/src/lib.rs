@@ -1,5 +1,9 @@
 pub mod admin;
 pub mod app;
+pub mod error;
+pub mod funds;
 pub mod interval;
 pub mod network;
 pub mod network_config;
+pub mod node_status;
+pub mod pledge;
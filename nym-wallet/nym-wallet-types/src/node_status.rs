@@ -0,0 +1,63 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use cosmwasm_std::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Combined view of the bond, the self-reported state and the nym-api annotations of a node
+/// bonded by the current account.
+#[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
+#[cfg_attr(
+    feature = "generate-ts",
+    ts(export_to = "nym-wallet/src/types/rust/NodeOperationalStatus.ts")
+)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NodeOperationalStatus {
+    pub identity_key: String,
+
+    /// Indicates whether the bonded node is a mixnode (as opposed to a gateway).
+    pub is_mixnode: bool,
+
+    /// The version of the node as declared in its bond.
+    pub bonded_version: String,
+
+    /// The version reported by the node itself, if it could have been queried.
+    pub reported_version: Option<String>,
+
+    /// Indicates whether the node reports a different version than the one declared in its bond.
+    pub version_mismatch: bool,
+
+    /// The health status reported by the node itself, if it could have been queried.
+    pub reported_health: Option<String>,
+
+    /// Reachability of the ports declared in the bond, as seen from this machine.
+    pub ports: Vec<PortReachability>,
+
+    /// The most recent performance of the node as determined by the nym-api.
+    #[cfg_attr(feature = "generate-ts", ts(type = "string | null"))]
+    pub performance: Option<Decimal>,
+
+    /// The stake saturation of the node as determined by the nym-api (mixnodes only).
+    #[cfg_attr(feature = "generate-ts", ts(type = "string | null"))]
+    pub stake_saturation: Option<Decimal>,
+
+    /// The uncapped stake saturation of the node as determined by the nym-api (mixnodes only).
+    #[cfg_attr(feature = "generate-ts", ts(type = "string | null"))]
+    pub uncapped_stake_saturation: Option<Decimal>,
+
+    /// Indicates whether the node is currently blacklisted by the nym-api.
+    pub blacklisted: bool,
+}
+
+#[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
+#[cfg_attr(
+    feature = "generate-ts",
+    ts(export_to = "nym-wallet/src/types/rust/PortReachability.ts")
+)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PortReachability {
+    /// Name of the port, such as `mix` or `clients`.
+    pub name: String,
+    pub port: u16,
+    pub reachable: bool,
+}
@@ -0,0 +1,33 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use cosmwasm_std::Decimal;
+use nym_mixnet_contract_common::rewarding::RewardEstimate;
+use nym_types::fees::FeeDetails;
+use serde::{Deserialize, Serialize};
+
+/// Pre-flight estimation of the effects of adjusting the pledge of an already bonded mixnode.
+#[cfg_attr(feature = "generate-ts", derive(ts_rs::TS))]
+#[cfg_attr(
+    feature = "generate-ts",
+    ts(export_to = "nym-wallet/src/types/rust/PledgeAdjustmentSimulation.ts")
+)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PledgeAdjustmentSimulation {
+    /// The fee of the transaction adjusting the pledge.
+    pub fee: FeeDetails,
+
+    /// The current (uncapped) saturation of the node.
+    #[cfg_attr(feature = "generate-ts", ts(type = "string"))]
+    pub current_saturation: Decimal,
+
+    /// The (uncapped) saturation of the node once the pledge got adjusted.
+    #[cfg_attr(feature = "generate-ts", ts(type = "string"))]
+    pub projected_saturation: Decimal,
+
+    /// The per-epoch reward estimation with the current pledge.
+    pub current_estimation: RewardEstimate,
+
+    /// The per-epoch reward estimation once the pledge got adjusted.
+    pub projected_estimation: RewardEstimate,
+}
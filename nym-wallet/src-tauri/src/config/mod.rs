@@ -7,6 +7,7 @@ use std::str::FromStr;
 use std::{fs, io, path::PathBuf};
 
 use itertools::Itertools;
+use nym_validator_client::nyxd::module_traits::ibc::IbcChannel;
 use nym_validator_client::nyxd::AccountId as CosmosAccountId;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
@@ -66,6 +67,10 @@ pub struct NetworkConfig {
     // Additional user provided validators.
     // It is an option for the purpose of file serialization.
     nyxd_urls: Option<Vec<ValidatorConfigEntry>>,
+
+    // User provided ICS20 channels to other chains.
+    // It is an option for the purpose of file serialization.
+    ibc_channels: Option<Vec<IbcChannel>>,
 }
 
 impl Default for Base {
@@ -93,6 +98,7 @@ impl Default for NetworkConfig {
             selected_nyxd_url: None,
             selected_api_url: None,
             nyxd_urls: None,
+            ibc_channels: None,
         }
     }
 }
@@ -350,6 +356,41 @@ impl Config {
             }
         }
     }
+
+    pub fn get_ibc_channels(&self, network: WalletNetwork) -> Vec<IbcChannel> {
+        self.networks
+            .get(&network.as_key())
+            .and_then(|config| config.ibc_channels.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn add_ibc_channel(&mut self, channel: IbcChannel, network: WalletNetwork) {
+        if let Some(network_config) = self.networks.get_mut(&network.as_key()) {
+            if let Some(ref mut channels) = network_config.ibc_channels {
+                // replace any previous definition of the same channel
+                channels.retain(|existing| existing.source_channel != channel.source_channel);
+                channels.push(channel);
+            } else {
+                network_config.ibc_channels = Some(vec![channel]);
+            }
+        } else {
+            self.networks.insert(
+                network.as_key(),
+                NetworkConfig {
+                    ibc_channels: Some(vec![channel]),
+                    ..NetworkConfig::default()
+                },
+            );
+        }
+    }
+
+    pub fn remove_ibc_channel(&mut self, source_channel: &str, network: WalletNetwork) {
+        if let Some(network_config) = self.networks.get_mut(&network.as_key()) {
+            if let Some(ref mut channels) = network_config.ibc_channels {
+                channels.retain(|existing| existing.source_channel != source_channel);
+            }
+        }
+    }
 }
 
 fn load_from_file<T>(file: PathBuf) -> Result<T, io::Error>
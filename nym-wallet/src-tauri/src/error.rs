@@ -1,11 +1,15 @@
 use nym_contracts_common::signing::SigningAlgorithm;
 use nym_crypto::asymmetric::identity::Ed25519RecoveryError;
+use nym_mixnet_contract_common::MixId;
 use nym_types::error::TypesError;
 use nym_validator_client::nym_api::error::NymAPIError;
 use nym_validator_client::signing::direct_wallet::DirectSecp256k1HdWalletError;
 use nym_validator_client::{nyxd::error::NyxdError, ValidatorClientError};
+use nym_wallet_types::error::{BackendErrorCode, StructuredBackendError};
+use nym_wallet_types::funds::FundsSource;
 use nym_wallet_types::network::Network;
 use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
 use std::io;
 use std::num::ParseIntError;
 use thiserror::Error;
@@ -80,6 +84,12 @@ pub enum BackendError {
         source: nym_store_cipher::Error,
     },
 
+    #[error(transparent)]
+    KeyringError {
+        #[from]
+        source: nym_keyring::KeyringError,
+    },
+
     #[error("Client has not been initialized yet, connect with mnemonic to initialize")]
     ClientNotInitialized,
     #[error("No balance available for address {0}")]
@@ -112,6 +122,8 @@ pub enum BackendError {
     WalletAccountIdAlreadyExistsInWalletLogin,
     #[error("Mnemonic already found in wallet login, was it already imported?")]
     WalletMnemonicAlreadyExistsInWalletLogin,
+    #[error("There is no remembered password for the wallet login")]
+    WalletNoRememberedPassword,
     #[error("Adding a different password to the wallet not currently supported")]
     WalletDifferentPasswordDetected,
     #[error("Unexpected mnemonic account for login")]
@@ -130,14 +142,20 @@ pub enum BackendError {
     NewWindowError,
     #[error("Failed to check for application update")]
     CheckAppVersionError,
-    #[error("Failed to connect to the provided validator URL")]
-    WalletValidatorConnectionFailed,
+    #[error("Failed to connect to the provided validator URL ({url})")]
+    WalletValidatorConnectionFailed { url: String },
     #[error("No defined default validator URL")]
     WalletNoDefaultValidator,
     #[error(
         "this vesting operation has been disabled. please use the non-vesting variant instead."
     )]
     UnsupportedVestingOperation,
+    #[error("insufficient {funds_source} funds to perform the operation. required: {required}, available: {available}")]
+    InsufficientFunds {
+        funds_source: FundsSource,
+        required: u128,
+        available: u128,
+    },
 
     #[error(transparent)]
     WalletError {
@@ -158,14 +176,191 @@ pub enum BackendError {
 
     #[error("there aren't any vesting delegations to migrate")]
     NoVestingDelegations,
+
+    #[error("there aren't any delegations towards mixnode {mix_id} to remove")]
+    NoDelegations { mix_id: MixId },
+
+    #[error("this account does not own any bonded mixnode")]
+    NoBondedMixnode,
+
+    #[error("there isn't any IBC channel {source_channel} configured for {network}")]
+    UnknownIbcChannel {
+        source_channel: String,
+        network: Network,
+    },
 }
 
+impl BackendError {
+    /// Stable code of the error that the frontend can use for localising it
+    /// and for providing the relevant remediation steps.
+    pub fn code(&self) -> BackendErrorCode {
+        match self {
+            BackendError::TypesError { source } => types_error_code(source),
+            BackendError::Bip39Error { .. } => BackendErrorCode::InvalidMnemonic,
+            BackendError::TendermintError { .. } => BackendErrorCode::ValidatorUnreachable,
+            BackendError::NyxdError { .. } | BackendError::CosmwasmStd { .. } => {
+                BackendErrorCode::ChainRequestFailed
+            }
+            BackendError::NymApiError { .. } => BackendErrorCode::DirectoryUnavailable,
+            BackendError::IOError { .. } | BackendError::UnknownStorageDirectory => {
+                BackendErrorCode::StorageUnavailable
+            }
+            BackendError::MalformedUrlProvided { .. } => BackendErrorCode::MalformedUrl,
+            BackendError::ReqwestError { .. } => BackendErrorCode::HttpRequestFailed,
+            BackendError::StoreCipherError { .. } => BackendErrorCode::WalletDecryptionFailed,
+            BackendError::KeyringError { .. } => BackendErrorCode::KeyringUnavailable,
+            BackendError::ClientNotInitialized => BackendErrorCode::ClientNotInitialized,
+            BackendError::NoBalance(_) => BackendErrorCode::NoBalance,
+            BackendError::NetworkNotSupported => BackendErrorCode::NetworkNotSupported,
+            BackendError::WalletFileAlreadyExists => BackendErrorCode::WalletFileAlreadyExists,
+            BackendError::WalletFileNotFound => BackendErrorCode::WalletFileNotFound,
+            BackendError::WalletPledgeUpdateNoOp => BackendErrorCode::PledgeUpdateNoOp,
+            BackendError::WalletPledgeUpdateInvalidCurrency => {
+                BackendErrorCode::PledgeUpdateInvalidCurrency
+            }
+            BackendError::WalletFileMalformedFilename => BackendErrorCode::WalletFileMalformed,
+            BackendError::WalletFileUnableToArchive => BackendErrorCode::WalletFileArchiveFailed,
+            BackendError::WalletNoSuchLoginId => BackendErrorCode::LoginNotFound,
+            BackendError::WalletNoSuchAccountIdInWalletLogin => BackendErrorCode::AccountNotFound,
+            BackendError::WalletLoginIdAlreadyExists => BackendErrorCode::LoginAlreadyExists,
+            BackendError::WalletAccountIdAlreadyExistsInWalletLogin => {
+                BackendErrorCode::AccountAlreadyExists
+            }
+            BackendError::WalletMnemonicAlreadyExistsInWalletLogin => {
+                BackendErrorCode::MnemonicAlreadyExists
+            }
+            BackendError::WalletNoRememberedPassword => BackendErrorCode::NoRememberedPassword,
+            BackendError::WalletDifferentPasswordDetected => BackendErrorCode::DifferentPassword,
+            BackendError::WalletUnexpectedMnemonicAccount => {
+                BackendErrorCode::UnexpectedMnemonicAccount
+            }
+            BackendError::FailedToDeriveAddress => BackendErrorCode::AddressDerivationFailed,
+            BackendError::ValueParseError(_) => BackendErrorCode::InvalidAmount,
+            BackendError::UnknownCoinDenom(_) => BackendErrorCode::UnknownCoinDenom,
+            BackendError::NoCoinsRegistered { .. } => BackendErrorCode::NoCoinsRegistered,
+            BackendError::SignatureError(_) | BackendError::K256Error { .. } => {
+                BackendErrorCode::SignatureFailed
+            }
+            BackendError::NewWindowError => BackendErrorCode::WindowCreationFailed,
+            BackendError::CheckAppVersionError => BackendErrorCode::UpdateCheckFailed,
+            BackendError::WalletValidatorConnectionFailed { .. } => {
+                BackendErrorCode::ValidatorConnectionFailed
+            }
+            BackendError::WalletNoDefaultValidator => BackendErrorCode::NoDefaultValidator,
+            BackendError::UnsupportedVestingOperation => {
+                BackendErrorCode::UnsupportedVestingOperation
+            }
+            BackendError::InsufficientFunds { .. } => BackendErrorCode::InsufficientFunds,
+            BackendError::WalletError { .. } | BackendError::Ed25519Recovery(_) => {
+                BackendErrorCode::AddressDerivationFailed
+            }
+            BackendError::UnexpectedSigningAlgorithm { .. } => {
+                BackendErrorCode::UnexpectedSigningAlgorithm
+            }
+            BackendError::RemovedCommand { .. } => BackendErrorCode::RemovedCommand,
+            BackendError::NoVestingDelegations => BackendErrorCode::NoVestingDelegations,
+            BackendError::NoDelegations { .. } => BackendErrorCode::NoDelegations,
+            BackendError::NoBondedMixnode => BackendErrorCode::NoBondedMixnode,
+            BackendError::UnknownIbcChannel { .. } => BackendErrorCode::UnknownIbcChannel,
+            BackendError::ErrorReport { .. } | BackendError::SerdeJsonError { .. } => {
+                BackendErrorCode::Internal
+            }
+        }
+    }
+
+    /// Named values, such as addresses or amounts, to be interpolated into the localised message.
+    pub fn params(&self) -> BTreeMap<String, String> {
+        let params: Vec<(&str, String)> = match self {
+            BackendError::TypesError { source } => return types_error_params(source),
+            BackendError::NoBalance(address) => vec![("address", address.clone())],
+            BackendError::UnknownCoinDenom(denom) => vec![("denom", denom.clone())],
+            BackendError::NoCoinsRegistered { network } => vec![("network", network.to_string())],
+            BackendError::SignatureError(reason) => vec![("reason", reason.clone())],
+            BackendError::WalletValidatorConnectionFailed { url } => vec![("url", url.clone())],
+            BackendError::InsufficientFunds {
+                funds_source,
+                required,
+                available,
+            } => vec![
+                ("funds_source", funds_source.to_string()),
+                ("required", required.to_string()),
+                ("available", available.to_string()),
+            ],
+            BackendError::UnexpectedSigningAlgorithm { received, expected } => vec![
+                ("received", format!("{received:?}")),
+                ("expected", format!("{expected:?}")),
+            ],
+            BackendError::RemovedCommand { name, alternative } => {
+                vec![("name", name.clone()), ("alternative", alternative.clone())]
+            }
+            BackendError::NoDelegations { mix_id } => vec![("mix_id", mix_id.to_string())],
+            BackendError::UnknownIbcChannel {
+                source_channel,
+                network,
+            } => vec![
+                ("source_channel", source_channel.clone()),
+                ("network", network.to_string()),
+            ],
+            _ => Vec::new(),
+        };
+
+        params
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
+    pub fn to_structured(&self) -> StructuredBackendError {
+        StructuredBackendError {
+            code: self.code(),
+            message: self.to_string(),
+            params: self.params(),
+        }
+    }
+}
+
+fn types_error_code(source: &TypesError) -> BackendErrorCode {
+    match source {
+        TypesError::NyxdError { .. } | TypesError::CosmwasmStd { .. } => {
+            BackendErrorCode::ChainRequestFailed
+        }
+        TypesError::TendermintRpcError { .. } => BackendErrorCode::ValidatorUnreachable,
+        TypesError::NymApiError { .. } | TypesError::NoNymApiUrlConfigured => {
+            BackendErrorCode::DirectoryUnavailable
+        }
+        TypesError::MalformedUrlProvided { .. } => BackendErrorCode::MalformedUrl,
+        TypesError::ReqwestError { .. } => BackendErrorCode::HttpRequestFailed,
+        TypesError::InvalidAmount(_) | TypesError::DecimalRangeExceeded { .. } => {
+            BackendErrorCode::InvalidAmount
+        }
+        TypesError::InvalidDenom(_) | TypesError::UnknownCoinDenom(_) => {
+            BackendErrorCode::UnknownCoinDenom
+        }
+        TypesError::UnknownNetwork(_) => BackendErrorCode::NetworkNotSupported,
+        _ => BackendErrorCode::Internal,
+    }
+}
+
+fn types_error_params(source: &TypesError) -> BTreeMap<String, String> {
+    let param = match source {
+        TypesError::InvalidAmount(amount) => ("amount", amount.clone()),
+        TypesError::InvalidDenom(denom) | TypesError::UnknownCoinDenom(denom) => {
+            ("denom", denom.clone())
+        }
+        TypesError::UnknownNetwork(network) => ("network", network.clone()),
+        _ => return BTreeMap::new(),
+    };
+    BTreeMap::from([(param.0.to_string(), param.1)])
+}
+
+// the frontend receives the structured error rather than just its message,
+// so that it could localise it based on the code and its parameters
 impl Serialize for BackendError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.collect_str(self)
+        self.to_structured().serialize(serializer)
     }
 }
 
@@ -202,3 +397,48 @@ impl From<ValidatorClientError> for BackendError {
         TypesError::from(e).into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn errors_are_serialized_with_their_code_and_params() {
+        let err = BackendError::InsufficientFunds {
+            funds_source: FundsSource::Vesting,
+            required: 100,
+            available: 42,
+        };
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({
+                "code": "insufficient_funds",
+                "message": err.to_string(),
+                "params": {
+                    "funds_source": "vesting",
+                    "required": "100",
+                    "available": "42",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn directory_and_connection_errors_have_dedicated_codes() {
+        let directory_err: BackendError = TypesError::NoNymApiUrlConfigured.into();
+        assert_eq!(directory_err.code(), BackendErrorCode::DirectoryUnavailable);
+
+        let connection_err = BackendError::WalletValidatorConnectionFailed {
+            url: "https://rpc.nymtech.net".to_string(),
+        };
+        assert_eq!(
+            connection_err.code(),
+            BackendErrorCode::ValidatorConnectionFailed
+        );
+        assert_eq!(
+            connection_err.params().get("url").map(String::as_str),
+            Some("https://rpc.nymtech.net")
+        );
+    }
+}
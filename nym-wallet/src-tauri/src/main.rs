@@ -43,15 +43,19 @@ fn main() {
             mixnet::account::create_new_mnemonic,
             mixnet::account::create_password,
             mixnet::account::does_password_file_exist,
+            mixnet::account::forget_remembered_password,
             mixnet::account::get_balance,
+            mixnet::account::has_remembered_password,
             mixnet::account::list_accounts,
             mixnet::account::logout,
+            mixnet::account::remember_password,
             mixnet::account::remove_account_for_password,
             mixnet::account::remove_password,
             mixnet::account::rename_account_for_password,
             mixnet::account::show_mnemonic_for_account_in_password,
             mixnet::account::sign_in_with_password,
             mixnet::account::sign_in_with_password_and_account_id,
+            mixnet::account::sign_in_with_remembered_password,
             mixnet::account::switch_network,
             mixnet::account::update_password,
             mixnet::account::validate_mnemonic,
@@ -62,6 +66,7 @@ fn main() {
             mixnet::bond::update_pledge,
             mixnet::bond::pledge_more,
             mixnet::bond::decrease_pledge,
+            mixnet::bond::adjust_mixnode_pledge,
             mixnet::bond::gateway_bond_details,
             mixnet::bond::get_pending_operator_rewards,
             mixnet::bond::mixnode_bond_details,
@@ -73,6 +78,7 @@ fn main() {
             mixnet::bond::get_number_of_mixnode_delegators,
             mixnet::bond::get_mix_node_description,
             mixnet::bond::get_mixnode_avg_uptime,
+            mixnet::node_status::get_node_operational_status,
             mixnet::delegate::delegate_to_mixnode,
             mixnet::delegate::get_pending_delegator_rewards,
             mixnet::delegate::get_pending_delegation_events,
@@ -84,10 +90,16 @@ fn main() {
             mixnet::interval::get_pending_epoch_events,
             mixnet::interval::get_pending_interval_events,
             mixnet::rewards::claim_delegator_reward,
+            mixnet::rewards::claim_all_delegator_rewards,
             mixnet::rewards::claim_operator_reward,
             mixnet::rewards::claim_locked_and_unlocked_delegator_reward,
             mixnet::rewards::get_current_rewarding_parameters,
             mixnet::send::send,
+            mixnet::ibc::add_ibc_channel,
+            mixnet::ibc::get_ibc_channels,
+            mixnet::ibc::get_in_flight_ibc_transfers,
+            mixnet::ibc::remove_ibc_channel,
+            mixnet::ibc::send_ibc_transfer,
             mixnet::bond::get_mixnode_uptime,
             network_config::add_validator,
             network_config::get_nym_api_urls,
@@ -161,6 +173,7 @@ fn main() {
             simulate::mixnet::simulate_bond_mixnode,
             simulate::mixnet::simulate_update_pledge,
             simulate::mixnet::simulate_pledge_more,
+            simulate::mixnet::simulate_mixnode_pledge_adjustment,
             simulate::mixnet::simulate_unbond_mixnode,
             simulate::mixnet::simulate_update_mixnode_config,
             simulate::mixnet::simulate_update_mixnode_cost_params,
@@ -182,6 +195,7 @@ fn main() {
             simulate::vesting::simulate_vesting_claim_delegator_reward,
             simulate::vesting::simulate_vesting_claim_operator_reward,
             simulate::mixnet::simulate_claim_delegator_reward,
+            simulate::mixnet::simulate_claim_all_delegator_rewards,
             simulate::mixnet::simulate_claim_operator_reward,
             signatures::sign::sign,
             signatures::sign::verify,
@@ -189,6 +203,10 @@ fn main() {
             signatures::ed25519_signing_payload::vesting_generate_mixnode_bonding_msg_payload,
             signatures::ed25519_signing_payload::generate_gateway_bonding_msg_payload,
             signatures::ed25519_signing_payload::vesting_generate_gateway_bonding_msg_payload,
+            signatures::ed25519_signing_payload::generate_mixnode_bonding_msg_preview,
+            signatures::ed25519_signing_payload::generate_gateway_bonding_msg_preview,
+            signatures::ed25519_signing_payload::generate_mixnode_pledge_adjustment_msg_payload,
+            signatures::ed25519_signing_payload::generate_mixnode_pledge_adjustment_msg_preview,
             help::log::help_log_toggle_window,
             app::window::create_main_window,
             app::window::create_auth_window,
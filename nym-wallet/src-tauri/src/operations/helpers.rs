@@ -9,13 +9,20 @@ use nym_contracts_common::signing::{
 };
 use nym_crypto::asymmetric::identity;
 use nym_mixnet_contract_common::{
-    construct_legacy_mixnode_bonding_sign_payload, Gateway, GatewayBondingPayload, MixNode,
-    MixNodeCostParams, SignableGatewayBondingMsg, SignableLegacyMixNodeBondingMsg,
+    construct_legacy_mixnode_bonding_sign_payload,
+    construct_mixnode_pledge_adjustment_sign_payload, ExecuteMsg as MixnetExecuteMsg, Gateway,
+    GatewayBondingPayload, MixId, MixNode, MixNodeCostParams, MixNodeDetails, PledgeAdjustment,
+    SignableGatewayBondingMsg, SignableLegacyMixNodeBondingMsg, SignableMixNodePledgeAdjustmentMsg,
+};
+use nym_validator_client::nyxd::contract_traits::{
+    MixnetQueryClient, NymContractsProvider, VestingQueryClient,
 };
-use nym_validator_client::nyxd::contract_traits::MixnetQueryClient;
 use nym_validator_client::nyxd::error::NyxdError;
-use nym_validator_client::nyxd::Coin;
+use nym_validator_client::nyxd::{cosmwasm, Coin, CosmWasmClient};
 use nym_validator_client::DirectSigningHttpRpcValidatorClient;
+use nym_vesting_contract_common::ExecuteMsg as VestingExecuteMsg;
+use nym_wallet_types::funds::FundsSource;
+use std::cmp::Ordering;
 
 // define this as a separate trait for mocking purposes
 #[async_trait]
@@ -35,17 +42,202 @@ impl AddressAndNonceProvider for DirectSigningHttpRpcValidatorClient {
     }
 }
 
+/// Amounts of liquid and vesting tokens to be used for a single operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FundsSplit {
+    pub(crate) liquid: Option<Coin>,
+    pub(crate) vesting: Option<Coin>,
+}
+
+impl FundsSplit {
+    pub(crate) fn liquid(amount: Coin) -> Self {
+        FundsSplit {
+            liquid: Some(amount),
+            vesting: None,
+        }
+    }
+
+    pub(crate) fn vesting(amount: Coin) -> Self {
+        FundsSplit {
+            liquid: None,
+            vesting: Some(amount),
+        }
+    }
+
+    /// Attempts to extract the liquid amount for operations that no longer support vesting tokens.
+    pub(crate) fn into_liquid_only(self) -> Result<Coin, BackendError> {
+        match (self.liquid, self.vesting) {
+            (Some(liquid), None) => Ok(liquid),
+            _ => Err(BackendError::UnsupportedVestingOperation),
+        }
+    }
+}
+
+/// Splits the requested amount between liquid and vesting tokens according to the specified source
+/// and makes sure there are sufficient funds available for the operation.
+pub(crate) fn split_funds(
+    funds_source: FundsSource,
+    amount: Coin,
+    liquid_available: u128,
+    vesting_available: u128,
+) -> Result<FundsSplit, BackendError> {
+    let required = amount.amount;
+    let ensure_sufficient = |available: u128| {
+        if required > available {
+            Err(BackendError::InsufficientFunds {
+                funds_source,
+                required,
+                available,
+            })
+        } else {
+            Ok(())
+        }
+    };
+
+    match funds_source {
+        FundsSource::Liquid => {
+            ensure_sufficient(liquid_available)?;
+            Ok(FundsSplit::liquid(amount))
+        }
+        FundsSource::Vesting => {
+            ensure_sufficient(vesting_available)?;
+            Ok(FundsSplit::vesting(amount))
+        }
+        FundsSource::AutoSplit => {
+            ensure_sufficient(liquid_available.saturating_add(vesting_available))?;
+
+            let liquid = required.min(liquid_available);
+            let vesting = required - liquid;
+            Ok(FundsSplit {
+                liquid: (liquid > 0).then(|| Coin::new(liquid, &amount.denom)),
+                vesting: (vesting > 0).then(|| Coin::new(vesting, &amount.denom)),
+            })
+        }
+    }
+}
+
+/// Queries the balances relevant for the specified funds source and splits the requested amount accordingly.
+// note: this does not account for the transaction fees
+pub(crate) async fn resolve_funds_source(
+    client: &DirectSigningHttpRpcValidatorClient,
+    funds_source: FundsSource,
+    amount: Coin,
+) -> Result<FundsSplit, BackendError> {
+    let address = client.nyxd.address();
+
+    let liquid_available = if funds_source == FundsSource::Vesting {
+        0
+    } else {
+        client
+            .nyxd
+            .get_balance(&address, amount.denom.clone())
+            .await?
+            .map(|balance| balance.amount)
+            .unwrap_or_default()
+    };
+
+    let vesting_available = match funds_source {
+        FundsSource::Liquid => 0,
+        FundsSource::Vesting => available_vesting_tokens(client).await?,
+        // the account might not have a vesting account at all
+        FundsSource::AutoSplit => available_vesting_tokens(client).await.unwrap_or_default(),
+    };
+
+    split_funds(funds_source, amount, liquid_available, vesting_available)
+}
+
+async fn available_vesting_tokens(
+    client: &DirectSigningHttpRpcValidatorClient,
+) -> Result<u128, BackendError> {
+    let address = client.nyxd.address();
+    let locked = client.nyxd.locked_coins(address.as_ref(), None).await?;
+    let staked = client.nyxd.get_staked_coins(address.as_ref()).await?;
+    Ok(locked.amount.saturating_sub(staked.amount))
+}
+
+/// Contract messages of a single operation that might involve both the liquid and the vesting tokens.
+/// They are meant to be executed within a single transaction so that the operation is atomic.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SplitContractMessages {
+    pub(crate) mixnet: Option<(MixnetExecuteMsg, Vec<Coin>)>,
+    pub(crate) vesting: Option<VestingExecuteMsg>,
+}
+
+impl SplitContractMessages {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.mixnet.is_none() && self.vesting.is_none()
+    }
+
+    pub(crate) fn wrap(
+        self,
+        client: &DirectSigningHttpRpcValidatorClient,
+    ) -> Result<Vec<cosmwasm::MsgExecuteContract>, BackendError> {
+        let mut wrapped = Vec::new();
+        if let Some((msg, funds)) = self.mixnet {
+            let mixnet_contract = client
+                .nyxd
+                .mixnet_contract_address()
+                .expect("mixnet contract address is not available");
+            wrapped.push(client.nyxd.wrap_contract_execute_message(
+                mixnet_contract,
+                &msg,
+                funds,
+            )?);
+        }
+        if let Some(msg) = self.vesting {
+            let vesting_contract = client
+                .nyxd
+                .vesting_contract_address()
+                .expect("vesting contract address is not available");
+            wrapped.push(client.nyxd.wrap_contract_execute_message(
+                vesting_contract,
+                &msg,
+                vec![],
+            )?);
+        }
+        Ok(wrapped)
+    }
+}
+
+/// Creates the messages required to delegate the provided split funds towards the specified mixnode.
+pub(crate) fn delegation_messages(mix_id: MixId, funds: FundsSplit) -> SplitContractMessages {
+    SplitContractMessages {
+        mixnet: funds
+            .liquid
+            .map(|amount| (MixnetExecuteMsg::DelegateToMixnode { mix_id }, vec![amount])),
+        vesting: funds
+            .vesting
+            .map(|amount| VestingExecuteMsg::DelegateToMixnode {
+                mix_id,
+                amount: amount.into(),
+                on_behalf_of: None,
+            }),
+    }
+}
+
+/// Creates the messages required to remove the liquid and/or the vesting delegation towards the specified mixnode.
+pub(crate) fn undelegation_messages(
+    mix_id: MixId,
+    liquid: bool,
+    vesting: bool,
+) -> SplitContractMessages {
+    SplitContractMessages {
+        mixnet: liquid.then(|| (MixnetExecuteMsg::UndelegateFromMixnode { mix_id }, vec![])),
+        vesting: vesting.then_some(VestingExecuteMsg::UndelegateFromMixnode {
+            mix_id,
+            on_behalf_of: None,
+        }),
+    }
+}
+
 // since the message has to go back to the user due to the increasing nonce, we might as well sign the entire payload
 pub(crate) async fn create_mixnode_bonding_sign_payload<P: AddressAndNonceProvider>(
     client: &P,
     mix_node: MixNode,
     cost_params: MixNodeCostParams,
-    pledge: Coin,
-    vesting: bool,
+    funds: FundsSplit,
 ) -> Result<SignableLegacyMixNodeBondingMsg, BackendError> {
-    if vesting {
-        return Err(BackendError::UnsupportedVestingOperation);
-    }
+    let pledge = funds.into_liquid_only()?;
     let sender = client.cw_address();
     let nonce = client.get_signing_nonce().await?;
 
@@ -62,13 +254,9 @@ pub(crate) async fn verify_mixnode_bonding_sign_payload<P: AddressAndNonceProvid
     client: &P,
     mix_node: &MixNode,
     cost_params: &MixNodeCostParams,
-    pledge: &Coin,
-    vesting: bool,
+    funds: &FundsSplit,
     msg_signature: &MessageSignature,
 ) -> Result<(), BackendError> {
-    if vesting {
-        return Err(BackendError::UnsupportedVestingOperation);
-    }
     let identity_key = identity::PublicKey::from_base58_string(&mix_node.identity_key)?;
     let signature = identity::Signature::from_bytes(msg_signature.as_ref())?;
 
@@ -77,8 +265,7 @@ pub(crate) async fn verify_mixnode_bonding_sign_payload<P: AddressAndNonceProvid
         client,
         mix_node.clone(),
         cost_params.clone(),
-        pledge.clone(),
-        vesting,
+        funds.clone(),
     )
     .await?;
     let plaintext = msg.to_plaintext()?;
@@ -99,12 +286,9 @@ pub(crate) async fn verify_mixnode_bonding_sign_payload<P: AddressAndNonceProvid
 pub(crate) async fn create_gateway_bonding_sign_payload<P: AddressAndNonceProvider>(
     client: &P,
     gateway: Gateway,
-    pledge: Coin,
-    vesting: bool,
+    funds: FundsSplit,
 ) -> Result<SignableGatewayBondingMsg, BackendError> {
-    if vesting {
-        return Err(BackendError::UnsupportedVestingOperation);
-    }
+    let pledge = funds.into_liquid_only()?;
     let payload = GatewayBondingPayload::new(gateway);
     let sender = client.cw_address();
     let content = ContractMessageContent::new(sender, vec![pledge.into()], payload);
@@ -116,19 +300,14 @@ pub(crate) async fn create_gateway_bonding_sign_payload<P: AddressAndNonceProvid
 pub(crate) async fn verify_gateway_bonding_sign_payload<P: AddressAndNonceProvider>(
     client: &P,
     gateway: &Gateway,
-    pledge: &Coin,
-    vesting: bool,
+    funds: &FundsSplit,
     msg_signature: &MessageSignature,
 ) -> Result<(), BackendError> {
-    if vesting {
-        return Err(BackendError::UnsupportedVestingOperation);
-    }
     let identity_key = identity::PublicKey::from_base58_string(&gateway.identity_key)?;
     let signature = identity::Signature::from_bytes(msg_signature.as_ref())?;
 
     // recreate the plaintext
-    let msg = create_gateway_bonding_sign_payload(client, gateway.clone(), pledge.clone(), vesting)
-        .await?;
+    let msg = create_gateway_bonding_sign_payload(client, gateway.clone(), funds.clone()).await?;
     let plaintext = msg.to_plaintext()?;
 
     if !msg.algorithm.is_ed25519() {
@@ -143,6 +322,97 @@ pub(crate) async fn verify_gateway_bonding_sign_payload<P: AddressAndNonceProvid
     Ok(())
 }
 
+/// Determines how the pledge has to change in order to go from the current to the new amount.
+pub(crate) fn pledge_adjustment(
+    current_pledge: &cosmwasm_std::Coin,
+    new_pledge: &cosmwasm_std::Coin,
+) -> Result<PledgeAdjustment, BackendError> {
+    if current_pledge.denom != new_pledge.denom {
+        return Err(BackendError::WalletPledgeUpdateInvalidCurrency);
+    }
+
+    match new_pledge.amount.cmp(&current_pledge.amount) {
+        Ordering::Greater => Ok(PledgeAdjustment::Increase(cosmwasm_std::coin(
+            (new_pledge.amount - current_pledge.amount).u128(),
+            &new_pledge.denom,
+        ))),
+        Ordering::Less => Ok(PledgeAdjustment::Decrease(cosmwasm_std::coin(
+            (current_pledge.amount - new_pledge.amount).u128(),
+            &new_pledge.denom,
+        ))),
+        Ordering::Equal => Err(BackendError::WalletPledgeUpdateNoOp),
+    }
+}
+
+/// Queries for the mixnode owned by this account and determines the adjustment required to reach the new pledge.
+pub(crate) async fn resolve_pledge_adjustment(
+    client: &DirectSigningHttpRpcValidatorClient,
+    new_pledge: Coin,
+) -> Result<(MixNodeDetails, PledgeAdjustment), BackendError> {
+    let details = client
+        .nyxd
+        .get_owned_mixnode(&client.nyxd.address())
+        .await?
+        .mixnode_details
+        .ok_or(BackendError::NoBondedMixnode)?;
+
+    let adjustment = pledge_adjustment(
+        &details.bond_information.original_pledge,
+        &new_pledge.into(),
+    )?;
+    Ok((details, adjustment))
+}
+
+// the contract does not care about this signature, but we use it to make sure the operator controls the node
+pub(crate) async fn create_mixnode_pledge_adjustment_sign_payload<P: AddressAndNonceProvider>(
+    client: &P,
+    mix_id: MixId,
+    identity_key: String,
+    adjustment: PledgeAdjustment,
+) -> Result<SignableMixNodePledgeAdjustmentMsg, BackendError> {
+    let sender = client.cw_address();
+    let nonce = client.get_signing_nonce().await?;
+
+    Ok(construct_mixnode_pledge_adjustment_sign_payload(
+        nonce,
+        sender,
+        mix_id,
+        identity_key,
+        adjustment,
+    ))
+}
+
+pub(crate) async fn verify_mixnode_pledge_adjustment_sign_payload<P: AddressAndNonceProvider>(
+    client: &P,
+    mix_id: MixId,
+    identity_key: &str,
+    adjustment: &PledgeAdjustment,
+    msg_signature: &MessageSignature,
+) -> Result<(), BackendError> {
+    let public_key = identity::PublicKey::from_base58_string(identity_key)?;
+    let signature = identity::Signature::from_bytes(msg_signature.as_ref())?;
+
+    // recreate the plaintext
+    let msg = create_mixnode_pledge_adjustment_sign_payload(
+        client,
+        mix_id,
+        identity_key.to_string(),
+        adjustment.clone(),
+    )
+    .await?;
+    let plaintext = msg.to_plaintext()?;
+
+    if !msg.algorithm.is_ed25519() {
+        return Err(BackendError::UnexpectedSigningAlgorithm {
+            received: msg.algorithm,
+            expected: SigningAlgorithm::Ed25519,
+        });
+    }
+
+    public_key.verify(plaintext, &signature)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,8 +472,7 @@ mod tests {
             &dummy_client,
             dummy_mixnode.clone(),
             dummy_cost_params.clone(),
-            dummy_pledge.clone(),
-            false,
+            FundsSplit::liquid(dummy_pledge.clone()),
         )
         .await
         .unwrap();
@@ -220,8 +489,7 @@ mod tests {
             &dummy_client,
             dummy_mixnode.clone(),
             dummy_cost_params.clone(),
-            dummy_pledge.clone(),
-            true,
+            FundsSplit::vesting(dummy_pledge.clone()),
         )
         .await;
         assert!(signing_msg_vesting.is_err());
@@ -230,8 +498,7 @@ mod tests {
             &dummy_client,
             &dummy_mixnode,
             &dummy_cost_params,
-            &dummy_pledge,
-            false,
+            &FundsSplit::liquid(dummy_pledge.clone()),
             &sig_liquid,
         )
         .await;
@@ -241,8 +508,7 @@ mod tests {
             &dummy_client,
             &dummy_mixnode,
             &dummy_cost_params,
-            &dummy_pledge,
-            true,
+            &FundsSplit::vesting(dummy_pledge.clone()),
             &sig_liquid,
         )
         .await;
@@ -274,8 +540,7 @@ mod tests {
         let signing_msg_liquid = create_gateway_bonding_sign_payload(
             &dummy_client,
             dummy_gateway.clone(),
-            dummy_pledge.clone(),
-            false,
+            FundsSplit::liquid(dummy_pledge.clone()),
         )
         .await
         .unwrap();
@@ -291,8 +556,7 @@ mod tests {
         let signing_msg_vesting = create_gateway_bonding_sign_payload(
             &dummy_client,
             dummy_gateway.clone(),
-            dummy_pledge.clone(),
-            true,
+            FundsSplit::vesting(dummy_pledge.clone()),
         )
         .await;
         assert!(signing_msg_vesting.is_err());
@@ -300,8 +564,7 @@ mod tests {
         let res = verify_gateway_bonding_sign_payload(
             &dummy_client,
             &dummy_gateway,
-            &dummy_pledge,
-            false,
+            &FundsSplit::liquid(dummy_pledge.clone()),
             &sig_liquid,
         )
         .await;
@@ -310,11 +573,179 @@ mod tests {
         let res = verify_gateway_bonding_sign_payload(
             &dummy_client,
             &dummy_gateway,
-            &dummy_pledge,
-            true,
+            &FundsSplit::vesting(dummy_pledge.clone()),
             &sig_liquid,
         )
         .await;
         assert!(res.is_err())
     }
+
+    #[tokio::test]
+    async fn dummy_pledge_adjustment_signature_verification() {
+        let mut rng = test_rng();
+        let identity_keypair = identity::KeyPair::new(&mut rng);
+        let identity_key = identity_keypair.public_key().to_base58_string();
+
+        let dummy_client = MockClient {
+            address: Addr::unchecked("n16t2umcd83zjpl5puyuuq6lgmy4p3qedjd8ynn6"),
+            signing_nonce: 42,
+        };
+
+        let increase = PledgeAdjustment::Increase(coin(10000000, "unym"));
+        let signing_msg = create_mixnode_pledge_adjustment_sign_payload(
+            &dummy_client,
+            123,
+            identity_key.clone(),
+            increase.clone(),
+        )
+        .await
+        .unwrap();
+        let plaintext = signing_msg.to_plaintext().unwrap();
+        let sig: MessageSignature = identity_keypair
+            .private_key()
+            .sign(&plaintext)
+            .to_bytes()
+            .as_ref()
+            .into();
+
+        let res = verify_mixnode_pledge_adjustment_sign_payload(
+            &dummy_client,
+            123,
+            &identity_key,
+            &increase,
+            &sig,
+        )
+        .await;
+        assert!(res.is_ok());
+
+        // the signature must not be valid for a different node or a different adjustment
+        let res = verify_mixnode_pledge_adjustment_sign_payload(
+            &dummy_client,
+            124,
+            &identity_key,
+            &increase,
+            &sig,
+        )
+        .await;
+        assert!(res.is_err());
+
+        let res = verify_mixnode_pledge_adjustment_sign_payload(
+            &dummy_client,
+            123,
+            &identity_key,
+            &PledgeAdjustment::Decrease(coin(10000000, "unym")),
+            &sig,
+        )
+        .await;
+        assert!(res.is_err())
+    }
+
+    #[test]
+    fn determining_pledge_adjustment() {
+        let current = coin(100, "unym");
+
+        assert_eq!(
+            pledge_adjustment(&current, &coin(150, "unym")).unwrap(),
+            PledgeAdjustment::Increase(coin(50, "unym"))
+        );
+        assert_eq!(
+            pledge_adjustment(&current, &coin(30, "unym")).unwrap(),
+            PledgeAdjustment::Decrease(coin(70, "unym"))
+        );
+        assert!(pledge_adjustment(&current, &coin(100, "unym")).is_err());
+        assert!(pledge_adjustment(&current, &coin(150, "unyx")).is_err());
+    }
+
+    #[test]
+    fn splitting_funds() {
+        let amount = Coin::new(100, "unym");
+
+        let liquid = split_funds(FundsSource::Liquid, amount.clone(), 150, 0).unwrap();
+        assert_eq!(liquid, FundsSplit::liquid(amount.clone()));
+        assert!(split_funds(FundsSource::Liquid, amount.clone(), 50, 1000).is_err());
+
+        let vesting = split_funds(FundsSource::Vesting, amount.clone(), 0, 100).unwrap();
+        assert_eq!(vesting, FundsSplit::vesting(amount.clone()));
+        assert!(split_funds(FundsSource::Vesting, amount.clone(), 1000, 99).is_err());
+
+        // liquid tokens are always used first
+        let auto = split_funds(FundsSource::AutoSplit, amount.clone(), 1000, 1000).unwrap();
+        assert_eq!(auto, FundsSplit::liquid(amount.clone()));
+
+        let auto = split_funds(FundsSource::AutoSplit, amount.clone(), 30, 1000).unwrap();
+        assert_eq!(
+            auto,
+            FundsSplit {
+                liquid: Some(Coin::new(30, "unym")),
+                vesting: Some(Coin::new(70, "unym")),
+            }
+        );
+
+        let auto = split_funds(FundsSource::AutoSplit, amount.clone(), 0, 1000).unwrap();
+        assert_eq!(auto, FundsSplit::vesting(amount.clone()));
+
+        assert!(split_funds(FundsSource::AutoSplit, amount, 30, 69).is_err());
+    }
+
+    #[test]
+    fn split_delegation_messages() {
+        let liquid = delegation_messages(42, FundsSplit::liquid(Coin::new(100, "unym")));
+        assert_eq!(
+            liquid.mixnet,
+            Some((
+                MixnetExecuteMsg::DelegateToMixnode { mix_id: 42 },
+                vec![Coin::new(100, "unym")]
+            ))
+        );
+        assert!(liquid.vesting.is_none());
+
+        // both parts of an auto-split delegation end up in the same set of messages
+        let split = delegation_messages(
+            42,
+            FundsSplit {
+                liquid: Some(Coin::new(30, "unym")),
+                vesting: Some(Coin::new(70, "unym")),
+            },
+        );
+        assert_eq!(
+            split.mixnet,
+            Some((
+                MixnetExecuteMsg::DelegateToMixnode { mix_id: 42 },
+                vec![Coin::new(30, "unym")]
+            ))
+        );
+        assert_eq!(
+            split.vesting,
+            Some(VestingExecuteMsg::DelegateToMixnode {
+                mix_id: 42,
+                amount: coin(70, "unym"),
+                on_behalf_of: None,
+            })
+        );
+    }
+
+    #[test]
+    fn split_undelegation_messages() {
+        assert!(undelegation_messages(42, false, false).is_empty());
+
+        let vesting = undelegation_messages(42, false, true);
+        assert!(vesting.mixnet.is_none());
+        assert_eq!(
+            vesting.vesting,
+            Some(VestingExecuteMsg::UndelegateFromMixnode {
+                mix_id: 42,
+                on_behalf_of: None,
+            })
+        );
+
+        let both = undelegation_messages(42, true, true);
+        assert_eq!(
+            both.mixnet,
+            Some((
+                MixnetExecuteMsg::UndelegateFromMixnode { mix_id: 42 },
+                vec![]
+            ))
+        );
+        assert!(both.vesting.is_some());
+    }
 }
@@ -352,7 +352,52 @@ pub fn update_password(
 ) -> Result<(), BackendError> {
     log::info!("Updating password");
 
-    wallet_storage::update_encrypted_logins(&current_password, &new_password)
+    wallet_storage::update_encrypted_logins(&current_password, &new_password)?;
+
+    // make sure the remembered password (if any) doesn't go stale
+    let login_id = wallet_storage::LoginId::new(DEFAULT_LOGIN_ID.to_string());
+    if wallet_storage::keyring::load_remembered_password(&login_id)?.is_some() {
+        wallet_storage::keyring::remember_password(&login_id, &new_password)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn remember_password(password: UserPassword) -> Result<(), BackendError> {
+    log::info!("Remembering password in the OS keyring");
+
+    // Currently we only support a single, default, id in the wallet
+    let login_id = wallet_storage::LoginId::new(DEFAULT_LOGIN_ID.to_string());
+
+    // make sure we're not going to remember an invalid password
+    wallet_storage::load_existing_login(&login_id, &password)?;
+    wallet_storage::keyring::remember_password(&login_id, &password)
+}
+
+#[tauri::command]
+pub fn forget_remembered_password() -> Result<(), BackendError> {
+    log::info!("Forgetting remembered password");
+    let login_id = wallet_storage::LoginId::new(DEFAULT_LOGIN_ID.to_string());
+    wallet_storage::keyring::forget_password(&login_id)
+}
+
+#[tauri::command]
+pub fn has_remembered_password() -> Result<bool, BackendError> {
+    let login_id = wallet_storage::LoginId::new(DEFAULT_LOGIN_ID.to_string());
+    Ok(wallet_storage::keyring::load_remembered_password(&login_id)?.is_some())
+}
+
+#[tauri::command]
+pub async fn sign_in_with_remembered_password(
+    state: tauri::State<'_, WalletState>,
+) -> Result<Account, BackendError> {
+    log::info!("Signing in with remembered password");
+
+    let login_id = wallet_storage::LoginId::new(DEFAULT_LOGIN_ID.to_string());
+    let password = wallet_storage::keyring::load_remembered_password(&login_id)?
+        .ok_or(BackendError::WalletNoRememberedPassword)?;
+
+    sign_in_with_password(password, state).await
 }
 
 #[tauri::command]
@@ -435,12 +480,17 @@ fn extract_mnemonic(
 pub fn remove_password() -> Result<(), BackendError> {
     log::info!("Removing password");
     let login_id = wallet_storage::LoginId::new(DEFAULT_LOGIN_ID.to_string());
-    wallet_storage::remove_login(&login_id)
+    wallet_storage::remove_login(&login_id)?;
+    wallet_storage::keyring::forget_password(&login_id)
 }
 
 #[tauri::command]
 pub fn archive_wallet_file() -> Result<(), BackendError> {
-    wallet_storage::archive_wallet_file()
+    wallet_storage::archive_wallet_file()?;
+
+    // the password of the archived wallet is meaningless for the new one
+    let login_id = wallet_storage::LoginId::new(DEFAULT_LOGIN_ID.to_string());
+    wallet_storage::keyring::forget_password(&login_id)
 }
 
 #[tauri::command]
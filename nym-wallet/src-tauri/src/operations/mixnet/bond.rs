@@ -3,13 +3,14 @@
 
 use crate::error::BackendError;
 use crate::operations::helpers::{
-    verify_gateway_bonding_sign_payload, verify_mixnode_bonding_sign_payload,
+    resolve_funds_source, resolve_pledge_adjustment, verify_gateway_bonding_sign_payload,
+    verify_mixnode_bonding_sign_payload, verify_mixnode_pledge_adjustment_sign_payload,
 };
 use crate::state::WalletState;
 use crate::{nyxd_client, Gateway, MixNode};
 use nym_contracts_common::signing::MessageSignature;
 use nym_mixnet_contract_common::gateway::GatewayConfigUpdate;
-use nym_mixnet_contract_common::{MixId, MixNodeConfigUpdate};
+use nym_mixnet_contract_common::{MixId, MixNodeConfigUpdate, PledgeAdjustment};
 use nym_types::currency::DecCoin;
 use nym_types::gateway::GatewayBond;
 use nym_types::mixnode::{MixNodeCostParams, MixNodeDetails};
@@ -17,6 +18,7 @@ use nym_types::transaction::TransactionExecuteResult;
 use nym_validator_client::client::NymApiClientExt;
 use nym_validator_client::nyxd::contract_traits::{MixnetQueryClient, MixnetSigningClient};
 use nym_validator_client::nyxd::Fee;
+use nym_wallet_types::funds::FundsSource;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::time::Duration;
@@ -34,6 +36,7 @@ pub async fn bond_gateway(
     gateway: Gateway,
     pledge: DecCoin,
     msg_signature: MessageSignature,
+    funds_source: Option<FundsSource>,
     fee: Option<Fee>,
     state: tauri::State<'_, WalletState>,
 ) -> Result<TransactionExecuteResult, BackendError> {
@@ -50,14 +53,16 @@ pub async fn bond_gateway(
     );
 
     let client = guard.current_client()?;
+    let funds = resolve_funds_source(client, funds_source.unwrap_or_default(), pledge_base).await?;
+
     // check the signature to make sure the user copied it correctly
     if let Err(err) =
-        verify_gateway_bonding_sign_payload(client, &gateway, &pledge_base, false, &msg_signature)
-            .await
+        verify_gateway_bonding_sign_payload(client, &gateway, &funds, &msg_signature).await
     {
         log::warn!("failed to verify provided gateway bonding signature: {err}");
         return Err(err);
     }
+    let pledge_base = funds.into_liquid_only()?;
 
     let res = client
         .nyxd
@@ -92,6 +97,7 @@ pub async fn bond_mixnode(
     cost_params: MixNodeCostParams,
     msg_signature: MessageSignature,
     pledge: DecCoin,
+    funds_source: Option<FundsSource>,
     fee: Option<Fee>,
     state: tauri::State<'_, WalletState>,
 ) -> Result<TransactionExecuteResult, BackendError> {
@@ -109,20 +115,17 @@ pub async fn bond_mixnode(
     );
 
     let client = guard.current_client()?;
+    let funds = resolve_funds_source(client, funds_source.unwrap_or_default(), pledge_base).await?;
+
     // check the signature to make sure the user copied it correctly
-    if let Err(err) = verify_mixnode_bonding_sign_payload(
-        client,
-        &mixnode,
-        &cost_params,
-        &pledge_base,
-        false,
-        &msg_signature,
-    )
-    .await
+    if let Err(err) =
+        verify_mixnode_bonding_sign_payload(client, &mixnode, &cost_params, &funds, &msg_signature)
+            .await
     {
         log::warn!("failed to verify provided mixnode bonding signature: {err}");
         return Err(err);
     }
+    let pledge_base = funds.into_liquid_only()?;
 
     let res = client
         .nyxd
@@ -184,6 +187,59 @@ pub async fn update_pledge(
     )?)
 }
 
+/// Increases or decreases the pledge of the owned mixnode so that it would match the new value.
+/// The adjustment has to be signed with the node identity key, similarly to bonding.
+#[tauri::command]
+pub async fn adjust_mixnode_pledge(
+    new_pledge: DecCoin,
+    msg_signature: MessageSignature,
+    fee: Option<Fee>,
+    state: tauri::State<'_, WalletState>,
+) -> Result<TransactionExecuteResult, BackendError> {
+    let guard = state.read().await;
+    let new_pledge_base = guard.attempt_convert_to_base_coin(new_pledge.clone())?;
+    let fee_amount = guard.convert_tx_fee(fee.as_ref());
+    log::info!(
+        ">>> Adjust mixnode pledge, new_pledge_display = {}, new_pledge_base = {}, fee = {:?}",
+        new_pledge,
+        new_pledge_base,
+        fee,
+    );
+
+    let client = guard.current_client()?;
+    let (details, adjustment) = resolve_pledge_adjustment(client, new_pledge_base).await?;
+
+    // check the signature to make sure the user copied it correctly
+    if let Err(err) = verify_mixnode_pledge_adjustment_sign_payload(
+        client,
+        details.bond_information.mix_id,
+        &details.bond_information.mix_node.identity_key,
+        &adjustment,
+        &msg_signature,
+    )
+    .await
+    {
+        log::warn!("failed to verify provided pledge adjustment signature: {err}");
+        return Err(err);
+    }
+
+    let res = match adjustment {
+        PledgeAdjustment::Increase(amount) => {
+            log::info!("Pledge increase by {amount}");
+            client.nyxd.pledge_more(amount.into(), fee).await?
+        }
+        PledgeAdjustment::Decrease(amount) => {
+            log::info!("Pledge decrease by {amount}");
+            client.nyxd.decrease_pledge(amount.into(), fee).await?
+        }
+    };
+    log::info!("<<< tx hash = {}", res.transaction_hash);
+    log::trace!("<<< {:?}", res);
+    Ok(TransactionExecuteResult::from_execute_result(
+        res, fee_amount,
+    )?)
+}
+
 #[tauri::command]
 pub async fn pledge_more(
     fee: Option<Fee>,
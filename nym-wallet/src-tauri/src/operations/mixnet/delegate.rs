@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::error::BackendError;
+use crate::operations::helpers::{
+    delegation_messages, resolve_funds_source, undelegation_messages,
+};
 use crate::state::WalletState;
-use crate::vesting::delegate::vesting_undelegate_from_mixnode;
 use nym_mixnet_contract_common::mixnode::StakeSaturationResponse;
 use nym_mixnet_contract_common::MixId;
 use nym_types::currency::DecCoin;
@@ -19,6 +21,7 @@ use nym_validator_client::nyxd::contract_traits::{
     MixnetQueryClient, MixnetSigningClient, NymContractsProvider, PagedMixnetQueryClient,
 };
 use nym_validator_client::nyxd::Fee;
+use nym_wallet_types::funds::FundsSource;
 use tap::TapFallible;
 
 #[tauri::command]
@@ -71,24 +74,34 @@ pub async fn get_pending_delegation_events(
 pub async fn delegate_to_mixnode(
     mix_id: MixId,
     amount: DecCoin,
+    funds_source: Option<FundsSource>,
     fee: Option<Fee>,
     state: tauri::State<'_, WalletState>,
 ) -> Result<TransactionExecuteResult, BackendError> {
     let guard = state.read().await;
     let client = guard.current_client()?;
     let delegation_base = guard.attempt_convert_to_base_coin(amount.clone())?;
+    let funds_source = funds_source.unwrap_or_default();
     let fee_amount = guard.convert_tx_fee(fee.as_ref());
 
     log::info!(
-        ">>> Delegate to mixnode: mix_id = {}, display_amount = {}, base_amount = {}, fee = {:?}",
+        ">>> Delegate to mixnode: mix_id = {}, display_amount = {}, base_amount = {}, funds_source = {}, fee = {:?}",
         mix_id,
         amount,
         delegation_base,
+        funds_source,
         fee,
     );
+
+    let funds = resolve_funds_source(client, funds_source, delegation_base).await?;
+    log::trace!("  --- funds split: {:?}", funds);
+
+    // if the delegation is split between the liquid and the vesting tokens,
+    // both parts are sent within the same transaction so that either both or neither of them succeed
+    let msgs = delegation_messages(mix_id, funds).wrap(client)?;
     let res = client
         .nyxd
-        .delegate_to_mixnode(mix_id, delegation_base, fee)
+        .execute_contract_messages(msgs, fee, "Delegating to mixnode")
         .await?;
     log::info!("<<< tx hash = {}", res.transaction_hash);
     log::trace!("<<< {:?}", res);
@@ -126,26 +139,62 @@ pub async fn undelegate_from_mixnode(
 #[tauri::command]
 pub async fn undelegate_all_from_mixnode(
     mix_id: MixId,
-    uses_vesting_contract_tokens: bool,
-    fee_liquid: Option<Fee>,
-    fee_vesting: Option<Fee>,
+    funds_source: FundsSource,
+    fee: Option<Fee>,
     state: tauri::State<'_, WalletState>,
-) -> Result<Vec<TransactionExecuteResult>, BackendError> {
+) -> Result<TransactionExecuteResult, BackendError> {
+    let guard = state.read().await;
+    let client = guard.current_client()?;
+    let fee_amount = guard.convert_tx_fee(fee.as_ref());
+
     log::info!(
-        ">>> Undelegate all from mixnode: mix_id = {}, uses_vesting_contract_tokens = {}, fee_liquid = {:?}, fee_vesting = {:?}",
+        ">>> Undelegate all from mixnode: mix_id = {}, funds_source = {}, fee = {:?}",
         mix_id,
-        uses_vesting_contract_tokens,
-        fee_liquid,
-        fee_vesting,
+        funds_source,
+        fee,
     );
-    let mut res: Vec<TransactionExecuteResult> =
-        vec![undelegate_from_mixnode(mix_id, fee_liquid, state.clone()).await?];
 
-    if uses_vesting_contract_tokens {
-        res.push(vesting_undelegate_from_mixnode(mix_id, fee_vesting, state).await?);
+    let (liquid, vesting) = match funds_source {
+        FundsSource::Liquid => (true, false),
+        FundsSource::Vesting => (false, true),
+        FundsSource::AutoSplit => {
+            // only attempt to remove the delegations that actually exist,
+            // otherwise the entire transaction would have failed
+            let address = client.nyxd.address();
+            let vesting_contract = client
+                .nyxd
+                .vesting_contract_address()
+                .expect("vesting contract address is not available")
+                .to_string();
+            let liquid_delegation = client
+                .nyxd
+                .get_delegation_details(mix_id, &address, None)
+                .await?;
+            let vesting_delegation = client
+                .nyxd
+                .get_delegation_details(mix_id, &address, Some(vesting_contract))
+                .await?;
+            (
+                liquid_delegation.delegation.is_some(),
+                vesting_delegation.delegation.is_some(),
+            )
+        }
+    };
+
+    let msgs = undelegation_messages(mix_id, liquid, vesting);
+    if msgs.is_empty() {
+        return Err(BackendError::NoDelegations { mix_id });
     }
 
-    Ok(res)
+    let res = client
+        .nyxd
+        .execute_contract_messages(msgs.wrap(client)?, fee, "Undelegating from mixnode")
+        .await?;
+    log::info!("<<< tx hash = {}", res.transaction_hash);
+    log::trace!("<<< {:?}", res);
+    Ok(TransactionExecuteResult::from_execute_result(
+        res, fee_amount,
+    )?)
 }
 
 #[tauri::command]
@@ -0,0 +1,116 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::BackendError;
+use crate::state::{InFlightIbcTransfer, WalletState};
+use nym_types::currency::DecCoin;
+use nym_validator_client::nyxd::module_traits::ibc::{IbcChannel, IbcTransfer, PacketStatus};
+use nym_validator_client::nyxd::{AccountId, Fee};
+use std::str::FromStr;
+
+#[tauri::command]
+pub async fn get_ibc_channels(
+    state: tauri::State<'_, WalletState>,
+) -> Result<Vec<IbcChannel>, BackendError> {
+    let guard = state.read().await;
+    Ok(guard.config().get_ibc_channels(guard.current_network()))
+}
+
+#[tauri::command]
+pub async fn add_ibc_channel(
+    channel: IbcChannel,
+    state: tauri::State<'_, WalletState>,
+) -> Result<(), BackendError> {
+    log::debug!(
+        "Adding IBC channel {} to {}",
+        channel.source_channel,
+        channel.counterparty_chain_id
+    );
+    state.write().await.add_ibc_channel(channel);
+    state.read().await.save_config_files()?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_ibc_channel(
+    source_channel: &str,
+    state: tauri::State<'_, WalletState>,
+) -> Result<(), BackendError> {
+    log::debug!("Removing IBC channel {source_channel}");
+    state.write().await.remove_ibc_channel(source_channel);
+    state.read().await.save_config_files()?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn send_ibc_transfer(
+    source_channel: &str,
+    address: &str,
+    amount: DecCoin,
+    memo: String,
+    fee: Option<Fee>,
+    state: tauri::State<'_, WalletState>,
+) -> Result<InFlightIbcTransfer, BackendError> {
+    let mut guard = state.write().await;
+    let channel = guard.get_ibc_channel(source_channel)?;
+    let amount_base = guard.attempt_convert_to_base_coin(amount.clone())?;
+    let to_address = AccountId::from_str(address)?;
+
+    log::info!(
+        ">>> IBC transfer: display_amount = {}, base_amount = {}, channel = {}, counterparty = {}, to = {}, fee = {:?}",
+        amount,
+        amount_base,
+        channel.source_channel,
+        channel.counterparty_chain_id,
+        to_address,
+        fee,
+    );
+    let raw_res = guard
+        .current_client()?
+        .nyxd
+        .ibc_transfer(&channel, &to_address, amount_base, None, memo, fee)
+        .await?;
+    log::info!("<<< tx hash = {}", raw_res.hash.to_string());
+
+    let transfer = IbcTransfer::from_tx_response(&raw_res)?;
+    let in_flight = InFlightIbcTransfer {
+        channel,
+        transfer,
+        amount,
+        recipient: to_address.to_string(),
+        status: PacketStatus::Pending,
+    };
+    guard.track_ibc_transfer(in_flight.clone());
+    log::trace!("<<< {:?}", in_flight);
+    Ok(in_flight)
+}
+
+/// Refreshes the status of all IBC transfers submitted on the current network during this session.
+/// Transfers that have completed are returned one final time and are no longer tracked afterwards.
+#[tauri::command]
+pub async fn get_in_flight_ibc_transfers(
+    state: tauri::State<'_, WalletState>,
+) -> Result<Vec<InFlightIbcTransfer>, BackendError> {
+    let mut guard = state.write().await;
+
+    let mut transfers = std::mem::take(guard.tracked_ibc_transfers_mut());
+    let client = guard.current_client()?;
+    for tracked in transfers.iter_mut() {
+        match client.nyxd.ibc_packet_status(&tracked.transfer).await {
+            Ok(status) => tracked.status = status,
+            Err(err) => log::warn!(
+                "failed to query status of IBC packet {} on {}: {err}",
+                tracked.transfer.sequence,
+                tracked.transfer.source_channel
+            ),
+        }
+    }
+
+    guard.tracked_ibc_transfers_mut().extend(
+        transfers
+            .iter()
+            .filter(|t| t.status.is_in_flight())
+            .cloned(),
+    );
+    Ok(transfers)
+}
@@ -2,6 +2,8 @@ pub mod account;
 pub mod admin;
 pub mod bond;
 pub mod delegate;
+pub mod ibc;
 pub mod interval;
+pub mod node_status;
 pub mod rewards;
 pub mod send;
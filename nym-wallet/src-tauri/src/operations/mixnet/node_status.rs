@@ -0,0 +1,200 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::BackendError;
+use crate::state::WalletState;
+use nym_config::defaults::DEFAULT_NYM_NODE_HTTP_PORT;
+use nym_validator_client::client::NymApiClientExt;
+use nym_validator_client::nyxd::contract_traits::MixnetQueryClient;
+use nym_wallet_types::node_status::{NodeOperationalStatus, PortReachability};
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::net::TcpStream;
+
+const NODE_QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+// the subset of the self-described information of the node we care about
+#[derive(Deserialize)]
+struct BuildInformation {
+    build_version: String,
+}
+
+#[derive(Deserialize)]
+struct NodeHealth {
+    status: String,
+}
+
+struct SelfReported {
+    version: Option<String>,
+    health: Option<String>,
+}
+
+async fn query_node(host: &str, http_port: u16) -> Result<SelfReported, BackendError> {
+    let client = reqwest::Client::builder()
+        .timeout(NODE_QUERY_TIMEOUT)
+        .build()?;
+    let base_url = format!("http://{host}:{http_port}/api/v1");
+
+    let version = match client
+        .get(format!("{base_url}/build-information"))
+        .send()
+        .await
+    {
+        Ok(res) => res
+            .json::<BuildInformation>()
+            .await
+            .ok()
+            .map(|info| info.build_version),
+        Err(err) => {
+            log::debug!("failed to query the build information of the node: {err}");
+            None
+        }
+    };
+
+    let health = match client.get(format!("{base_url}/health")).send().await {
+        Ok(res) => res
+            .json::<NodeHealth>()
+            .await
+            .ok()
+            .map(|health| health.status),
+        Err(err) => {
+            log::debug!("failed to query the health of the node: {err}");
+            None
+        }
+    };
+
+    Ok(SelfReported { version, health })
+}
+
+async fn check_ports(host: &str, ports: &[(&str, u16)]) -> Vec<PortReachability> {
+    let checks = ports.iter().map(|(name, port)| async move {
+        let connection = TcpStream::connect((host, *port));
+        let reachable = tokio::time::timeout(NODE_QUERY_TIMEOUT, connection)
+            .await
+            .is_ok_and(|res| res.is_ok());
+        PortReachability {
+            name: name.to_string(),
+            port: *port,
+            reachable,
+        }
+    });
+    futures::future::join_all(checks).await
+}
+
+fn version_mismatch(bonded: &str, reported: Option<&str>) -> bool {
+    let normalise = |version: &str| version.trim().trim_start_matches('v').to_owned();
+    reported.is_some_and(|reported| normalise(reported) != normalise(bonded))
+}
+
+/// Gathers everything an operator might want to know about the node bonded by the current account
+/// in order to tell whether it's working correctly.
+/// Note that the reachability of the ports is checked from this machine rather than the network.
+#[tauri::command]
+pub async fn get_node_operational_status(
+    state: tauri::State<'_, WalletState>,
+) -> Result<Option<NodeOperationalStatus>, BackendError> {
+    log::info!(">>> Get operational status of the bonded node");
+    let guard = state.read().await;
+    let client = guard.current_client()?;
+    let address = client.nyxd.address();
+
+    if let Some(details) = client
+        .nyxd
+        .get_owned_mixnode(&address)
+        .await?
+        .mixnode_details
+    {
+        let mix_id = details.mix_id();
+        let mix_node = details.bond_information.mix_node;
+
+        let annotated = client
+            .nym_api
+            .get_mixnodes_detailed()
+            .await?
+            .into_iter()
+            .find(|node| node.mix_id() == mix_id);
+        let self_reported = query_node(&mix_node.host, mix_node.http_api_port).await?;
+        let ports = check_ports(
+            &mix_node.host,
+            &[
+                ("mix", mix_node.mix_port),
+                ("verloc", mix_node.verloc_port),
+                ("http api", mix_node.http_api_port),
+            ],
+        )
+        .await;
+
+        let mismatch = version_mismatch(&mix_node.version, self_reported.version.as_deref());
+        let status = NodeOperationalStatus {
+            identity_key: mix_node.identity_key,
+            is_mixnode: true,
+            bonded_version: mix_node.version,
+            version_mismatch: mismatch,
+            reported_version: self_reported.version,
+            reported_health: self_reported.health,
+            ports,
+            performance: annotated.as_ref().map(|node| node.performance.value()),
+            stake_saturation: annotated.as_ref().map(|node| node.stake_saturation),
+            uncapped_stake_saturation: annotated
+                .as_ref()
+                .map(|node| node.uncapped_stake_saturation),
+            blacklisted: annotated.is_some_and(|node| node.blacklisted),
+        };
+        log::info!("<<< {status:?}");
+        return Ok(Some(status));
+    }
+
+    if let Some(bond) = client.nyxd.get_owned_gateway(&address).await?.gateway {
+        let gateway = bond.gateway;
+
+        let annotated = client
+            .nym_api
+            .get_gateways_detailed()
+            .await?
+            .into_iter()
+            .find(|node| node.identity() == &gateway.identity_key);
+        // gateways don't announce their http port, so assume they're running as a nym-node
+        let self_reported = query_node(&gateway.host, DEFAULT_NYM_NODE_HTTP_PORT).await?;
+        let ports = check_ports(
+            &gateway.host,
+            &[
+                ("mix", gateway.mix_port),
+                ("clients", gateway.clients_port),
+                ("http api", DEFAULT_NYM_NODE_HTTP_PORT),
+            ],
+        )
+        .await;
+
+        let mismatch = version_mismatch(&gateway.version, self_reported.version.as_deref());
+        let status = NodeOperationalStatus {
+            identity_key: gateway.identity_key,
+            is_mixnode: false,
+            bonded_version: gateway.version,
+            version_mismatch: mismatch,
+            reported_version: self_reported.version,
+            reported_health: self_reported.health,
+            ports,
+            performance: annotated.as_ref().map(|node| node.performance.value()),
+            stake_saturation: None,
+            uncapped_stake_saturation: None,
+            blacklisted: annotated.is_some_and(|node| node.blacklisted),
+        };
+        log::info!("<<< {status:?}");
+        return Ok(Some(status));
+    }
+
+    log::info!("<<< there's no node bonded by this account");
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_mismatch_ignores_prefix() {
+        assert!(!version_mismatch("1.1.3", None));
+        assert!(!version_mismatch("1.1.3", Some("v1.1.3")));
+        assert!(version_mismatch("1.1.3", Some("1.1.4")));
+    }
+}
@@ -1,13 +1,18 @@
 use crate::error::BackendError;
 use crate::state::WalletState;
 use crate::vesting::rewards::vesting_claim_delegator_reward;
+use nym_mixnet_contract_common::events::{MixnetEventType, MORE_DELEGATIONS_REMAINING_KEY};
 use nym_mixnet_contract_common::{MixId, RewardingParams};
 use nym_types::transaction::TransactionExecuteResult;
 use nym_validator_client::nyxd::contract_traits::{
     MixnetQueryClient, MixnetSigningClient, NymContractsProvider, PagedMixnetQueryClient,
 };
+use nym_validator_client::nyxd::helpers::find_attribute_value_in_logs_or_events;
 use nym_validator_client::nyxd::Fee;
 
+// upper bound on the number of transactions sent by a single `claim_all_delegator_rewards` call
+const MAX_CLAIM_ALL_REWARDS_TRANSACTIONS: usize = 20;
+
 #[tauri::command]
 pub async fn claim_operator_reward(
     fee: Option<Fee>,
@@ -50,6 +55,41 @@ pub async fn claim_delegator_reward(
     )?)
 }
 
+#[tauri::command]
+pub async fn claim_all_delegator_rewards(
+    fee: Option<Fee>,
+    state: tauri::State<'_, WalletState>,
+) -> Result<Vec<TransactionExecuteResult>, BackendError> {
+    log::info!(">>> Claim all delegator rewards");
+    let guard = state.read().await;
+    let client = guard.current_client()?;
+
+    // the contract only processes a limited number of delegations per transaction,
+    // so keep on claiming until it reports there's nothing more left
+    let event_type = format!("wasm-{}", MixnetEventType::ClaimAllDelegatorRewards);
+    let mut res = Vec::new();
+    for _ in 0..MAX_CLAIM_ALL_REWARDS_TRANSACTIONS {
+        let fee_amount = guard.convert_tx_fee(fee.as_ref());
+        let tx_res = client.nyxd.claim_all_delegator_rewards(fee.clone()).await?;
+        log::info!("<<< tx hash = {}", tx_res.transaction_hash);
+        log::trace!("<<< {:?}", tx_res);
+
+        let more_remaining = find_attribute_value_in_logs_or_events(
+            &tx_res.logs,
+            &tx_res.events,
+            &event_type,
+            MORE_DELEGATIONS_REMAINING_KEY,
+        );
+        res.push(TransactionExecuteResult::from_execute_result(
+            tx_res, fee_amount,
+        )?);
+        if more_remaining.as_deref() != Some("true") {
+            break;
+        }
+    }
+    Ok(res)
+}
+
 #[tauri::command]
 pub async fn claim_locked_and_unlocked_delegator_reward(
     mix_id: MixId,
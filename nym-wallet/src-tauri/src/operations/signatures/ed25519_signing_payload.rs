@@ -4,71 +4,136 @@
 use crate::error::BackendError;
 use crate::operations::helpers::{
     create_gateway_bonding_sign_payload, create_mixnode_bonding_sign_payload,
+    create_mixnode_pledge_adjustment_sign_payload, resolve_funds_source, resolve_pledge_adjustment,
 };
 use crate::state::WalletState;
-use nym_mixnet_contract_common::{Gateway, MixNode};
+use nym_mixnet_contract_common::{
+    Gateway, MixNode, SignableGatewayBondingMsg, SignableLegacyMixNodeBondingMsg,
+    SignableMixNodePledgeAdjustmentMsg,
+};
 use nym_types::currency::DecCoin;
 use nym_types::mixnode::MixNodeCostParams;
+use nym_wallet_types::funds::FundsSource;
 
-async fn mixnode_bonding_msg_payload(
+async fn mixnode_bonding_msg(
     mixnode: MixNode,
     cost_params: MixNodeCostParams,
     pledge: DecCoin,
-    vesting: bool,
+    funds_source: FundsSource,
     state: tauri::State<'_, WalletState>,
-) -> Result<String, BackendError> {
+) -> Result<SignableLegacyMixNodeBondingMsg, BackendError> {
     let guard = state.read().await;
     let reg = guard.registered_coins()?;
     let pledge_base = guard.attempt_convert_to_base_coin(pledge.clone())?;
     let cost_params = cost_params.try_convert_to_mixnet_contract_cost_params(reg)?;
     log::info!(
-        ">>> Bond mixnode bonding signature: identity_key = {}, pledge_display = {}, pledge_base = {}, vesting = {vesting}",
+        ">>> Bond mixnode bonding signature: identity_key = {}, pledge_display = {}, pledge_base = {}, funds_source = {funds_source}",
         mixnode.identity_key,
         pledge,
         pledge_base,
     );
 
+    // bonding with vesting tokens has been disabled, so don't bother querying for the balances
+    if funds_source == FundsSource::Vesting {
+        return Err(BackendError::UnsupportedVestingOperation);
+    }
+
     let client = guard.current_client()?;
 
     // TODO: decide on exact structure here. Json? base58? some hash?
     // to be determined
-    let msg =
-        create_mixnode_bonding_sign_payload(client, mixnode, cost_params, pledge_base, vesting)
-            .await?;
+    let funds = resolve_funds_source(client, funds_source, pledge_base).await?;
+    create_mixnode_bonding_sign_payload(client, mixnode, cost_params, funds).await
+}
+
+async fn mixnode_bonding_msg_payload(
+    mixnode: MixNode,
+    cost_params: MixNodeCostParams,
+    pledge: DecCoin,
+    funds_source: FundsSource,
+    state: tauri::State<'_, WalletState>,
+) -> Result<String, BackendError> {
+    let msg = mixnode_bonding_msg(mixnode, cost_params, pledge, funds_source, state).await?;
     Ok(msg.to_base58_string()?)
 }
 
-async fn gateway_bonding_msg_payload(
+async fn gateway_bonding_msg(
     gateway: Gateway,
     pledge: DecCoin,
-    vesting: bool,
+    funds_source: FundsSource,
     state: tauri::State<'_, WalletState>,
-) -> Result<String, BackendError> {
+) -> Result<SignableGatewayBondingMsg, BackendError> {
     let guard = state.read().await;
     let pledge_base = guard.attempt_convert_to_base_coin(pledge.clone())?;
     log::info!(
-        ">>> Bond gateway bonding signature: identity_key = {}, pledge_display = {}, pledge_base = {}, vesting = {vesting}",
+        ">>> Bond gateway bonding signature: identity_key = {}, pledge_display = {}, pledge_base = {}, funds_source = {funds_source}",
         gateway.identity_key,
         pledge,
         pledge_base,
     );
 
+    // bonding with vesting tokens has been disabled, so don't bother querying for the balances
+    if funds_source == FundsSource::Vesting {
+        return Err(BackendError::UnsupportedVestingOperation);
+    }
+
     let client = guard.current_client()?;
 
     // TODO: decide on exact structure here. Json? base58? some hash?
     // to be determined
-    let msg = create_gateway_bonding_sign_payload(client, gateway, pledge_base, vesting).await?;
+    let funds = resolve_funds_source(client, funds_source, pledge_base).await?;
+    create_gateway_bonding_sign_payload(client, gateway, funds).await
+}
+
+async fn gateway_bonding_msg_payload(
+    gateway: Gateway,
+    pledge: DecCoin,
+    funds_source: FundsSource,
+    state: tauri::State<'_, WalletState>,
+) -> Result<String, BackendError> {
+    let msg = gateway_bonding_msg(gateway, pledge, funds_source, state).await?;
     Ok(msg.to_base58_string()?)
 }
 
+async fn mixnode_pledge_adjustment_msg(
+    new_pledge: DecCoin,
+    state: tauri::State<'_, WalletState>,
+) -> Result<SignableMixNodePledgeAdjustmentMsg, BackendError> {
+    let guard = state.read().await;
+    let new_pledge_base = guard.attempt_convert_to_base_coin(new_pledge.clone())?;
+    log::info!(
+        ">>> Mixnode pledge adjustment signature: new_pledge_display = {}, new_pledge_base = {}",
+        new_pledge,
+        new_pledge_base,
+    );
+
+    let client = guard.current_client()?;
+    let (details, adjustment) = resolve_pledge_adjustment(client, new_pledge_base).await?;
+    create_mixnode_pledge_adjustment_sign_payload(
+        client,
+        details.bond_information.mix_id,
+        details.bond_information.mix_node.identity_key,
+        adjustment,
+    )
+    .await
+}
+
 #[tauri::command]
 pub async fn generate_mixnode_bonding_msg_payload(
     mixnode: MixNode,
     cost_params: MixNodeCostParams,
     pledge: DecCoin,
+    funds_source: Option<FundsSource>,
     state: tauri::State<'_, WalletState>,
 ) -> Result<String, BackendError> {
-    mixnode_bonding_msg_payload(mixnode, cost_params, pledge, false, state).await
+    mixnode_bonding_msg_payload(
+        mixnode,
+        cost_params,
+        pledge,
+        funds_source.unwrap_or_default(),
+        state,
+    )
+    .await
 }
 
 #[tauri::command]
@@ -78,16 +143,17 @@ pub async fn vesting_generate_mixnode_bonding_msg_payload(
     pledge: DecCoin,
     state: tauri::State<'_, WalletState>,
 ) -> Result<String, BackendError> {
-    mixnode_bonding_msg_payload(mixnode, cost_params, pledge, true, state).await
+    mixnode_bonding_msg_payload(mixnode, cost_params, pledge, FundsSource::Vesting, state).await
 }
 
 #[tauri::command]
 pub async fn generate_gateway_bonding_msg_payload(
     gateway: Gateway,
     pledge: DecCoin,
+    funds_source: Option<FundsSource>,
     state: tauri::State<'_, WalletState>,
 ) -> Result<String, BackendError> {
-    gateway_bonding_msg_payload(gateway, pledge, false, state).await
+    gateway_bonding_msg_payload(gateway, pledge, funds_source.unwrap_or_default(), state).await
 }
 
 #[tauri::command]
@@ -96,5 +162,59 @@ pub async fn vesting_generate_gateway_bonding_msg_payload(
     pledge: DecCoin,
     state: tauri::State<'_, WalletState>,
 ) -> Result<String, BackendError> {
-    gateway_bonding_msg_payload(gateway, pledge, true, state).await
+    gateway_bonding_msg_payload(gateway, pledge, FundsSource::Vesting, state).await
+}
+
+/// Returns the field-by-field rendering of the mixnode bonding message so that the operator
+/// can verify what they're signing. The signature itself is still over the base58-encoded payload.
+#[tauri::command]
+pub async fn generate_mixnode_bonding_msg_preview(
+    mixnode: MixNode,
+    cost_params: MixNodeCostParams,
+    pledge: DecCoin,
+    funds_source: Option<FundsSource>,
+    state: tauri::State<'_, WalletState>,
+) -> Result<String, BackendError> {
+    let msg = mixnode_bonding_msg(
+        mixnode,
+        cost_params,
+        pledge,
+        funds_source.unwrap_or_default(),
+        state,
+    )
+    .await?;
+    Ok(msg.to_human_readable()?)
+}
+
+/// Returns the field-by-field rendering of the gateway bonding message so that the operator
+/// can verify what they're signing. The signature itself is still over the base58-encoded payload.
+#[tauri::command]
+pub async fn generate_gateway_bonding_msg_preview(
+    gateway: Gateway,
+    pledge: DecCoin,
+    funds_source: Option<FundsSource>,
+    state: tauri::State<'_, WalletState>,
+) -> Result<String, BackendError> {
+    let msg = gateway_bonding_msg(gateway, pledge, funds_source.unwrap_or_default(), state).await?;
+    Ok(msg.to_human_readable()?)
+}
+
+#[tauri::command]
+pub async fn generate_mixnode_pledge_adjustment_msg_payload(
+    new_pledge: DecCoin,
+    state: tauri::State<'_, WalletState>,
+) -> Result<String, BackendError> {
+    let msg = mixnode_pledge_adjustment_msg(new_pledge, state).await?;
+    Ok(msg.to_base58_string()?)
+}
+
+/// Returns the field-by-field rendering of the pledge adjustment message so that the operator
+/// can verify what they're signing. The signature itself is still over the base58-encoded payload.
+#[tauri::command]
+pub async fn generate_mixnode_pledge_adjustment_msg_preview(
+    new_pledge: DecCoin,
+    state: tauri::State<'_, WalletState>,
+) -> Result<String, BackendError> {
+    let msg = mixnode_pledge_adjustment_msg(new_pledge, state).await?;
+    Ok(msg.to_human_readable()?)
 }
@@ -4,14 +4,20 @@
 use std::cmp::Ordering;
 
 use crate::error::BackendError;
+use crate::operations::helpers::resolve_pledge_adjustment;
 use crate::operations::simulate::FeeDetails;
 use crate::WalletState;
+use cosmwasm_std::Decimal;
 use nym_contracts_common::signing::MessageSignature;
-use nym_mixnet_contract_common::{ExecuteMsg, Gateway, MixId, MixNode};
+use nym_mixnet_contract_common::rewarding::helpers::truncate_reward_amount;
+use nym_mixnet_contract_common::{ExecuteMsg, Gateway, MixId, MixNode, PledgeAdjustment};
 use nym_mixnet_contract_common::{GatewayConfigUpdate, MixNodeConfigUpdate};
 use nym_types::currency::DecCoin;
 use nym_types::mixnode::MixNodeCostParams;
+use nym_validator_client::client::NymApiClientExt;
+use nym_validator_client::models::ComputeRewardEstParam;
 use nym_validator_client::nyxd::contract_traits::NymContractsProvider;
+use nym_wallet_types::pledge::PledgeAdjustmentSimulation;
 
 async fn simulate_mixnet_operation(
     msg: ExecuteMsg,
@@ -139,6 +145,85 @@ pub async fn simulate_update_pledge(
     }
 }
 
+/// Simulates the transaction adjusting the pledge of the owned mixnode alongside its impact on the node
+/// saturation and rewards, as computed by the nym-api reward estimation endpoint.
+#[tauri::command]
+pub async fn simulate_mixnode_pledge_adjustment(
+    new_pledge: DecCoin,
+    state: tauri::State<'_, WalletState>,
+) -> Result<PledgeAdjustmentSimulation, BackendError> {
+    let guard = state.read().await;
+    let new_pledge_base = guard.attempt_convert_to_base_coin(new_pledge.clone())?;
+    log::info!(">>> Simulate mixnode pledge adjustment, new pledge {new_pledge}");
+
+    let client = guard.current_client()?;
+    let (details, adjustment) = resolve_pledge_adjustment(client, new_pledge_base).await?;
+    let mix_id = details.bond_information.mix_id;
+
+    let (msg, funds) = match &adjustment {
+        PledgeAdjustment::Increase(amount) => {
+            (ExecuteMsg::PledgeMore {}, vec![amount.clone().into()])
+        }
+        PledgeAdjustment::Decrease(amount) => (
+            ExecuteMsg::DecreasePledge {
+                decrease_by: amount.clone(),
+            },
+            Vec::new(),
+        ),
+    };
+    let mixnet_contract = client
+        .nyxd
+        .mixnet_contract_address()
+        .expect("mixnet contract address is not available");
+    let msg = client
+        .nyxd
+        .wrap_contract_execute_message(mixnet_contract, &msg, funds)?;
+    let fee = guard.create_detailed_fee(client.nyxd.simulate(vec![msg], "").await?)?;
+
+    let mut projected_rewarding = details.rewarding_details.clone();
+    let delta = Decimal::from_ratio(adjustment.amount().amount, 1u64);
+    match adjustment {
+        PledgeAdjustment::Increase(_) => projected_rewarding.operator += delta,
+        PledgeAdjustment::Decrease(_) => {
+            projected_rewarding.operator = projected_rewarding.operator.saturating_sub(delta)
+        }
+    }
+
+    let current = client.nym_api.get_mixnode_reward_estimation(mix_id).await?;
+    let projected = client
+        .nym_api
+        .compute_mixnode_reward_estimation(
+            mix_id,
+            &ComputeRewardEstParam {
+                performance: None,
+                active_in_rewarded_set: None,
+                pledge_amount: Some(
+                    truncate_reward_amount(projected_rewarding.operator).u128() as u64
+                ),
+                total_delegation: None,
+                interval_operating_cost: None,
+                profit_margin_percent: None,
+            },
+        )
+        .await?;
+
+    let simulation = PledgeAdjustmentSimulation {
+        fee,
+        current_saturation: details
+            .rewarding_details
+            .uncapped_bond_saturation(&current.reward_params),
+        projected_saturation: projected_rewarding.uncapped_bond_saturation(&current.reward_params),
+        current_estimation: current.estimation,
+        projected_estimation: projected.estimation,
+    };
+    log::info!(
+        "<<< saturation: {} -> {}",
+        simulation.current_saturation,
+        simulation.projected_saturation
+    );
+    Ok(simulation)
+}
+
 #[tauri::command]
 pub async fn simulate_unbond_mixnode(
     state: tauri::State<'_, WalletState>,
@@ -225,3 +310,10 @@ pub async fn simulate_claim_delegator_reward(
 ) -> Result<FeeDetails, BackendError> {
     simulate_mixnet_operation(ExecuteMsg::WithdrawDelegatorReward { mix_id }, None, &state).await
 }
+
+#[tauri::command]
+pub async fn simulate_claim_all_delegator_rewards(
+    state: tauri::State<'_, WalletState>,
+) -> Result<FeeDetails, BackendError> {
+    simulate_mixnet_operation(ExecuteMsg::ClaimAllDelegatorRewards {}, None, &state).await
+}
@@ -1,7 +1,7 @@
 use crate::error::BackendError;
 use crate::nyxd_client;
 use crate::operations::helpers::{
-    verify_gateway_bonding_sign_payload, verify_mixnode_bonding_sign_payload,
+    verify_gateway_bonding_sign_payload, verify_mixnode_bonding_sign_payload, FundsSplit,
 };
 use crate::state::WalletState;
 use crate::{Gateway, MixNode};
@@ -34,10 +34,10 @@ pub async fn vesting_bond_gateway(
     );
 
     let client = guard.current_client()?;
+    let funds = FundsSplit::vesting(pledge_base.clone());
     // check the signature to make sure the user copied it correctly
     if let Err(err) =
-        verify_gateway_bonding_sign_payload(client, &gateway, &pledge_base, true, &msg_signature)
-            .await
+        verify_gateway_bonding_sign_payload(client, &gateway, &funds, &msg_signature).await
     {
         log::warn!("failed to verify provided gateway bonding signature: {err}");
         return Err(err);
@@ -102,8 +102,7 @@ pub async fn vesting_bond_mixnode(
         client,
         &mixnode,
         &cost_params,
-        &pledge_base,
-        true,
+        &FundsSplit::vesting(pledge_base.clone()),
         &msg_signature,
     )
     .await
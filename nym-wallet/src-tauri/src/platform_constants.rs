@@ -5,3 +5,5 @@ pub const CONFIG_DIR_NAME: &str = "nym-wallet";
 pub const CONFIG_FILENAME: &str = "config.toml";
 pub const STORAGE_DIR_NAME: &str = "nym-wallet";
 pub const WALLET_INFO_FILENAME: &str = "saved-wallet.json";
+pub const KEYRING_SERVICE_NAME: &str = "nym-wallet";
+pub const SECRETS_FALLBACK_DIR_NAME: &str = "secrets";
@@ -8,11 +8,13 @@ use log::warn;
 use nym_types::currency::{DecCoin, Denom, RegisteredCoins};
 use nym_types::fees::FeeDetails;
 use nym_validator_client::nyxd::cosmwasm_client::types::SimulateResponse;
+use nym_validator_client::nyxd::module_traits::ibc::{IbcChannel, IbcTransfer, PacketStatus};
 use nym_validator_client::nyxd::{AccountId as CosmosAccountId, Coin, Fee, SigningCosmWasmClient};
 use nym_validator_client::DirectSigningHttpRpcValidatorClient;
 use nym_wallet_types::network::Network;
 use nym_wallet_types::network_config;
 use once_cell::sync::Lazy;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -79,9 +81,21 @@ pub struct WalletStateInner {
     validator_metadata: HashMap<Url, ValidatorMetadata>,
     registered_coins: HashMap<Network, RegisteredCoins>,
 
+    /// IBC transfers submitted during this session that haven't yet been relayed
+    ibc_transfers: HashMap<Network, Vec<InFlightIbcTransfer>>,
+
     react_state: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct InFlightIbcTransfer {
+    pub channel: IbcChannel,
+    pub transfer: IbcTransfer,
+    pub amount: DecCoin,
+    pub recipient: String,
+    pub status: PacketStatus,
+}
+
 pub(crate) struct WalletAccountIds {
     // The wallet account id
     pub id: crate::wallet_storage::AccountId,
@@ -264,6 +278,37 @@ impl WalletStateInner {
         self.all_accounts.iter()
     }
 
+    pub fn get_ibc_channel(&self, source_channel: &str) -> Result<IbcChannel, BackendError> {
+        self.config
+            .get_ibc_channels(self.current_network)
+            .into_iter()
+            .find(|channel| channel.source_channel == source_channel)
+            .ok_or_else(|| BackendError::UnknownIbcChannel {
+                source_channel: source_channel.to_string(),
+                network: self.current_network,
+            })
+    }
+
+    pub fn add_ibc_channel(&mut self, channel: IbcChannel) {
+        self.config.add_ibc_channel(channel, self.current_network)
+    }
+
+    pub fn remove_ibc_channel(&mut self, source_channel: &str) {
+        self.config
+            .remove_ibc_channel(source_channel, self.current_network)
+    }
+
+    pub fn track_ibc_transfer(&mut self, transfer: InFlightIbcTransfer) {
+        self.ibc_transfers
+            .entry(self.current_network)
+            .or_default()
+            .push(transfer)
+    }
+
+    pub fn tracked_ibc_transfers_mut(&mut self) -> &mut Vec<InFlightIbcTransfer> {
+        self.ibc_transfers.entry(self.current_network).or_default()
+    }
+
     pub fn logout(&mut self) {
         self.signing_clients = HashMap::new();
     }
@@ -448,7 +493,9 @@ impl WalletStateInner {
         )
         .await?
         {
-            return Err(BackendError::WalletValidatorConnectionFailed);
+            return Err(BackendError::WalletValidatorConnectionFailed {
+                url: url.to_string(),
+            });
         }
         self.config.select_nyxd_url(url.parse()?, network);
         if let Ok(client) = self.client_mut(network) {
@@ -0,0 +1,37 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{get_storage_directory, LoginId, UserPassword};
+use crate::error::BackendError;
+use crate::platform_constants::{KEYRING_SERVICE_NAME, SECRETS_FALLBACK_DIR_NAME};
+use nym_keyring::{open_secret_store, SecretStore};
+
+// the remembered password is bound to the particular login so that we could support multiple
+// logins in the future
+fn login_secret_name(login_id: &LoginId) -> String {
+    format!("login-password-{login_id}")
+}
+
+fn secret_store() -> Result<Box<dyn SecretStore>, BackendError> {
+    let fallback_directory = get_storage_directory()?.join(SECRETS_FALLBACK_DIR_NAME);
+    Ok(open_secret_store(KEYRING_SERVICE_NAME, fallback_directory))
+}
+
+pub(crate) fn remember_password(
+    login_id: &LoginId,
+    password: &UserPassword,
+) -> Result<(), BackendError> {
+    secret_store()?.store_secret(&login_secret_name(login_id), password)?;
+    Ok(())
+}
+
+pub(crate) fn load_remembered_password(
+    login_id: &LoginId,
+) -> Result<Option<UserPassword>, BackendError> {
+    Ok(secret_store()?.load_secret(&login_secret_name(login_id))?)
+}
+
+pub(crate) fn forget_password(login_id: &LoginId) -> Result<(), BackendError> {
+    secret_store()?.remove_secret(&login_secret_name(login_id))?;
+    Ok(())
+}
@@ -27,6 +27,7 @@ use self::account_data::{EncryptedLogin, MultipleAccounts, StoredWallet};
 
 pub(crate) mod account_data;
 pub(crate) mod encryption;
+pub(crate) mod keyring;
 
 mod password;
 
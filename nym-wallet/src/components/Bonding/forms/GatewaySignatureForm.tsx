@@ -5,6 +5,7 @@ import { gatewayToTauri } from '../utils';
 import { CopyToClipboard } from '../../CopyToClipboard';
 import { useBondingContext } from '../../../context';
 import { Console } from '../../../utils/console';
+import { generateGatewayMsgPreview } from '../../../requests';
 import { ErrorModal } from '../../Modals/ErrorModal';
 import { GatewayData, GatewayAmount, Signature } from '../../../pages/bonding/types';
 
@@ -18,6 +19,7 @@ const GatewaySignatureForm = ({
   onNext: (data: Signature) => void;
 }) => {
   const [message, setMessage] = useState<string>();
+  const [preview, setPreview] = useState<string>();
   const [error, setError] = useState<string>();
   const { generateGatewayMsgPayload } = useBondingContext();
 
@@ -49,8 +51,23 @@ const GatewaySignatureForm = ({
     }
   };
 
+  const generatePreview = async () => {
+    try {
+      setPreview(
+        await generateGatewayMsgPreview({
+          pledge: amount.amount,
+          gateway: gatewayToTauri(gateway),
+        }),
+      );
+    } catch (e) {
+      // the preview is purely informational, so don't block the bonding flow if it can't be generated
+      Console.warn(e);
+    }
+  };
+
   useEffect(() => {
     generateMessage();
+    generatePreview();
   }, [gateway, amount]);
 
   if (error) {
@@ -72,6 +89,22 @@ const GatewaySignatureForm = ({
         <br />
         Then paste the signature in the next field.
       </Typography>
+      {preview && (
+        <>
+          <Typography variant="body2">
+            Before signing, make sure the details below match your node. The same values are encoded in the message:
+          </Typography>
+          <TextField
+            id="signing-preview"
+            multiline
+            rows={7}
+            value={preview}
+            fullWidth
+            disabled
+            InputProps={{ sx: { fontFamily: 'monospace', fontSize: 12 } }}
+          />
+        </>
+      )}
       <TextField id="outlined-multiline-static" multiline rows={7} value={message} fullWidth disabled />
       <Stack direction="row" alignItems="center" gap={1} justifyContent="end">
         <Typography fontWeight={600}>Copy Message</Typography>
@@ -5,6 +5,7 @@ import { costParamsToTauri, mixnodeToTauri } from '../utils';
 import { CopyToClipboard } from '../../CopyToClipboard';
 import { useBondingContext } from '../../../context';
 import { Console } from '../../../utils/console';
+import { generateMixnodeMsgPreview } from '../../../requests';
 import { ErrorModal } from '../../Modals/ErrorModal';
 import { MixnodeAmount, MixnodeData, Signature } from '../../../pages/bonding/types';
 
@@ -18,6 +19,7 @@ const MixnodeSignatureForm = ({
   onNext: (data: Signature) => void;
 }) => {
   const [message, setMessage] = useState<string>('');
+  const [preview, setPreview] = useState<string>();
   const [error, setError] = useState<string>();
   const { generateMixnodeMsgPayload } = useBondingContext();
 
@@ -50,8 +52,24 @@ const MixnodeSignatureForm = ({
     }
   };
 
+  const generatePreview = async () => {
+    try {
+      setPreview(
+        await generateMixnodeMsgPreview({
+          pledge: amount.amount,
+          mixnode: mixnodeToTauri(mixnode),
+          costParams: costParamsToTauri(amount),
+        }),
+      );
+    } catch (e) {
+      // the preview is purely informational, so don't block the bonding flow if it can't be generated
+      Console.warn(e);
+    }
+  };
+
   useEffect(() => {
     generateMessage();
+    generatePreview();
   }, [mixnode, amount]);
 
   if (error) {
@@ -73,6 +91,22 @@ const MixnodeSignatureForm = ({
         <br />
         Then paste the signature in the next field.
       </Typography>
+      {preview && (
+        <>
+          <Typography variant="body2">
+            Before signing, make sure the details below match your node. The same values are encoded in the message:
+          </Typography>
+          <TextField
+            id="signing-preview"
+            multiline
+            rows={7}
+            value={preview}
+            fullWidth
+            disabled
+            InputProps={{ sx: { fontFamily: 'monospace', fontSize: 12 } }}
+          />
+        </>
+      )}
       <TextField id="outlined-multiline-static" multiline rows={7} value={message} fullWidth disabled />
       <Stack direction="row" alignItems="center" gap={1} justifyContent="end">
         <Typography fontWeight={600}>Copy Message</Typography>
@@ -5,6 +5,7 @@ import { useGetFee } from 'src/hooks/useGetFee';
 import { send } from 'src/requests';
 import { Console } from 'src/utils/console';
 import { simulateSend } from 'src/requests/simulate';
+import { errorMessage } from 'src/types';
 import { LoadingModal } from '../Modals/LoadingModal';
 import { SendDetailsModal } from './SendDetailsModal';
 import { SendErrorModal } from './SendErrorModal';
@@ -56,7 +57,7 @@ export const SendModal = ({ onClose, hasStorybookStyles }: { onClose: () => void
         }
         setModal('send details');
       } catch (e) {
-        setError(e as string);
+        setError(errorMessage(e));
       } finally {
         setIsLoading(false);
       }
@@ -76,7 +77,7 @@ export const SendModal = ({ onClose, hasStorybookStyles }: { onClose: () => void
       });
     } catch (e) {
       Console.error(e as string);
-      if (/Raw log: out of gas/.test(e as string)) {
+      if (/Raw log: out of gas/.test(errorMessage(e))) {
         setGasError('Specified fee was too small. Please increase the amount and try again');
       } else {
         setSendError(true);
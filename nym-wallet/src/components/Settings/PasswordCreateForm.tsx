@@ -3,6 +3,7 @@ import { Button, FormControl, Stack } from '@mui/material';
 import { useSnackbar } from 'notistack';
 import { MnemonicInput } from '@nymproject/react/textfields/Mnemonic';
 import { PasswordInput } from '@nymproject/react/textfields/Password';
+import { errorMessage } from 'src/types';
 import { createPassword } from '../../requests';
 import { PasswordStrength } from '../../pages/auth/components';
 
@@ -28,7 +29,7 @@ const PasswordCreateForm = ({ onPwdSaved }: { onPwdSaved: () => void }) => {
       reset();
       onPwdSaved();
     } catch (e) {
-      enqueueSnackbar(e as string, { variant: 'error' });
+      enqueueSnackbar(errorMessage(e), { variant: 'error' });
     } finally {
       setIsLoading(false);
     }
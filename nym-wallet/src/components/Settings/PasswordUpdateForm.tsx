@@ -2,6 +2,7 @@ import React, { useState } from 'react';
 import { Button, FormControl, Stack } from '@mui/material';
 import { useSnackbar } from 'notistack';
 import { PasswordInput } from '@nymproject/react/textfields/Password';
+import { errorMessage } from 'src/types';
 import { updatePassword } from '../../requests';
 import { PasswordStrength } from '../../pages/auth/components';
 
@@ -27,7 +28,7 @@ const PasswordUpdateForm = ({ onPwdSaved }: { onPwdSaved: () => void }) => {
       reset();
       onPwdSaved();
     } catch (e) {
-      enqueueSnackbar(e as string, { variant: 'error' });
+      enqueueSnackbar(errorMessage(e), { variant: 'error' });
     } finally {
       setIsLoading(false);
     }
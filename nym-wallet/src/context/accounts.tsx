@@ -2,6 +2,7 @@ import React, { createContext, Dispatch, SetStateAction, useContext, useEffect,
 import { AccountEntry } from '@nymproject/types';
 import { addAccount as addAccountRequest, renameAccount, showMnemonicForAccount } from 'src/requests';
 import { useSnackbar } from 'notistack';
+import { errorMessage } from 'src/types';
 import { AppContext } from './main';
 
 type TAccounts = {
@@ -124,7 +125,7 @@ export const AccountsProvider: FCWithChildren = ({ children }) => {
       const mnemonic = await showMnemonicForAccount({ password, accountName });
       setAccountMnemonic({ value: mnemonic, accountName });
     } catch (e) {
-      setError(e as string);
+      setError(errorMessage(e));
     } finally {
       setIsLoading(false);
     }
@@ -9,6 +9,7 @@ import {
   WrappedDelegationEvent,
 } from '@nymproject/types';
 import type { Network } from 'src/types';
+import { errorMessage } from 'src/types';
 import {
   delegateToMixnode,
   getAllPendingDelegations,
@@ -91,7 +92,7 @@ export const DelegationContextProvider: FC<{
 
       return tx;
     } catch (e) {
-      throw new Error(e as string);
+      throw new Error(errorMessage(e));
     }
   };
 
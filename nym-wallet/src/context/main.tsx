@@ -4,7 +4,7 @@ import { useNavigate } from 'react-router-dom';
 import { useSnackbar } from 'notistack';
 import { Account, AccountEntry, MixNodeDetails } from '@nymproject/types';
 import { getVersion } from '@tauri-apps/api/app';
-import { AppEnv, Network, TauriContractStateParams } from '../types';
+import { AppEnv, errorMessage, Network, TauriContractStateParams } from '../types';
 import { TUseuserBalance, useGetBalance } from '../hooks/useGetBalance';
 import {
   getContractParams,
@@ -256,7 +256,7 @@ export const AppProvider: FCWithChildren = ({ children }) => {
       setNetwork('MAINNET');
       navigate('/balance');
     } catch (e) {
-      setError(e as string);
+      setError(errorMessage(e));
     } finally {
       setIsLoading(false);
     }
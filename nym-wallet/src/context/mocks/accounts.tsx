@@ -1,5 +1,6 @@
 import React, { useMemo, useState } from 'react';
 import { AccountEntry } from '@nymproject/types';
+import { errorMessage } from 'src/types';
 import { AccountsContext, TAccountMnemonic, TAccountsDialog } from '../accounts';
 
 export const MockAccountsProvider: FCWithChildren = ({ children }) => {
@@ -67,7 +68,7 @@ export const MockAccountsProvider: FCWithChildren = ({ children }) => {
       const mnemonic = 'test mnemonic';
       setAccountMnemonic({ value: mnemonic, accountName });
     } catch (e) {
-      setError(e as string);
+      setError(errorMessage(e));
     } finally {
       setIsLoading(false);
     }
@@ -88,6 +88,30 @@ export const MockRewardsContextProvider: FCWithChildren = ({ children }) => {
     ];
   };
 
+  const claimAllRewards = async (): Promise<TransactionExecuteResult[]> => {
+    if (!delegations) {
+      throw new Error('No delegations');
+    }
+
+    await mockSleep(1000);
+
+    return [
+      {
+        transaction_hash: '55303CD4B91FAC4C2715E40EBB52BB3B92829D9431B3A279D37B5CC58432E354',
+        fee: {
+          amount: '1',
+          denom: 'nym',
+        },
+        data_json: '[]',
+        logs_json: '[]',
+        gas_info: {
+          gas_wanted: { gas_units: BigInt(1) },
+          gas_used: { gas_units: BigInt(1) },
+        },
+      },
+    ];
+  };
+
   const redeemAllRewards = async (): Promise<TRewardsTransaction[]> => {
     if (!delegations) {
       throw new Error('No delegations');
@@ -116,6 +140,7 @@ export const MockRewardsContextProvider: FCWithChildren = ({ children }) => {
       totalRewards,
       refresh,
       claimRewards,
+      claimAllRewards,
       redeemAllRewards,
     }),
     [isLoading, error, totalRewards],
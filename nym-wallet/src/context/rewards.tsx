@@ -1,7 +1,7 @@
 import React, { createContext, useContext, useEffect, useMemo, useState } from 'react';
 import { FeeDetails, TransactionExecuteResult } from '@nymproject/types';
 import { useDelegationContext } from './delegations';
-import { claimDelegatorRewards } from '../requests';
+import { claimAllDelegatorRewards, claimDelegatorRewards } from '../requests';
 
 type TRewardsContext = {
   isLoading: boolean;
@@ -9,6 +9,7 @@ type TRewardsContext = {
   totalRewards?: string;
   refresh: () => Promise<void>;
   claimRewards: (mixId: number, fee?: FeeDetails) => Promise<TransactionExecuteResult[]>;
+  claimAllRewards: (fee?: FeeDetails) => Promise<TransactionExecuteResult[]>;
 };
 
 export type TRewardsTransaction = {
@@ -22,6 +23,9 @@ export const RewardsContext = createContext<TRewardsContext>({
   claimRewards: async () => {
     throw new Error('Not implemented');
   },
+  claimAllRewards: async () => {
+    throw new Error('Not implemented');
+  },
 });
 
 export const RewardsContextProvider: FCWithChildren = ({ children }) => {
@@ -43,9 +47,7 @@ export const RewardsContextProvider: FCWithChildren = ({ children }) => {
       totalRewards,
       refresh,
       claimRewards: claimDelegatorRewards,
-      redeemAllRewards: async () => {
-        throw new Error('Not implemented');
-      },
+      claimAllRewards: claimAllDelegatorRewards,
     }),
     [isLoading, error, totalRewards],
   );
@@ -2,7 +2,7 @@ import { useCallback, useContext, useEffect, useState } from 'react';
 import { Console } from '../utils/console';
 import { AppContext } from '../context/main';
 import { checkGatewayOwnership, checkMixnodeOwnership, getVestingPledgeInfo } from '../requests';
-import { EnumNodeType, TNodeOwnership } from '../types';
+import { EnumNodeType, errorMessage, TNodeOwnership } from '../types';
 
 const initial: TNodeOwnership = {
   hasOwnership: false,
@@ -43,7 +43,7 @@ export const useCheckOwnership = () => {
       setOwnership(status);
     } catch (e) {
       Console.error(e as string);
-      setError(e as string);
+      setError(errorMessage(e));
       setOwnership(initial);
     } finally {
       setIsLoading(false);
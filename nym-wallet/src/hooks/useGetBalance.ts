@@ -1,5 +1,6 @@
 import { useCallback, useEffect, useState } from 'react';
 import { Account, Balance, DecCoin, OriginalVestingResponse, Period, VestingAccountInfo } from '@nymproject/types';
+import { errorMessage } from 'src/types';
 import {
   getVestingCoins,
   getVestedCoins,
@@ -104,7 +105,7 @@ export const useGetBalance = (clientDetails?: Account): TUseuserBalance => {
       const bal = await userBalance();
       setBalance(bal);
     } catch (err) {
-      setError(err as string);
+      setError(errorMessage(err));
     } finally {
       setIsLoading(false);
     }
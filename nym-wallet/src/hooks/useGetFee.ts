@@ -1,6 +1,7 @@
 import { DecCoin, FeeDetails } from '@nymproject/types';
 import { useState } from 'react';
 import { Console } from 'src/utils/console';
+import { errorMessage } from 'src/types';
 import { getCustomFees } from '../requests';
 
 export function useGetFee() {
@@ -15,7 +16,7 @@ export function useGetFee() {
       setFee(simulatedFee);
     } catch (e) {
       Console.error(e);
-      setFeeError(e as string);
+      setFeeError(errorMessage(e));
     }
     setIsFeeLoading(false);
   }
@@ -27,7 +28,7 @@ export function useGetFee() {
       setFee(fees);
     } catch (e) {
       Console.error(e);
-      setFeeError(e as string);
+      setFeeError(errorMessage(e));
     }
     setIsFeeLoading(false);
   }
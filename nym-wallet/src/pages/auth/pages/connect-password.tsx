@@ -5,6 +5,7 @@ import { useSnackbar } from 'notistack';
 import { AuthContext } from 'src/context/auth';
 import { PasswordInput } from '@nymproject/react/textfields/Password';
 import { archiveWalletFile, createPassword, isPasswordCreated } from 'src/requests';
+import { errorMessage } from 'src/types';
 import { Subtitle, Title, PasswordStrength } from '../components';
 
 export const ConnectPassword = () => {
@@ -30,7 +31,7 @@ export const ConnectPassword = () => {
       enqueueSnackbar('Password successfully created', { variant: 'success' });
       navigate('/sign-in-password');
     } catch (e) {
-      enqueueSnackbar(e as string, { variant: 'error' });
+      enqueueSnackbar(errorMessage(e), { variant: 'error' });
       setIsLoading(false);
     }
   };
@@ -5,6 +5,7 @@ import { useSnackbar } from 'notistack';
 import { AuthContext } from 'src/context/auth';
 import { createPassword } from 'src/requests';
 import { PasswordInput } from '@nymproject/react/textfields/Password';
+import { errorMessage } from 'src/types';
 import { Subtitle, Title, PasswordStrength } from '../components';
 
 export const CreatePassword = () => {
@@ -31,7 +32,7 @@ export const CreatePassword = () => {
       navigate('/sign-in-password');
     } catch (e) {
       setIsLoading(false);
-      enqueueSnackbar(e as string, { variant: 'error' });
+      enqueueSnackbar(errorMessage(e), { variant: 'error' });
     }
   };
 
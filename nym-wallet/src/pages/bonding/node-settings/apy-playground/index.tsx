@@ -8,6 +8,7 @@ import { TBondedMixnode } from 'src/context';
 import { useSnackbar } from 'notistack';
 import { LoadingModal } from 'src/components/Modals/LoadingModal';
 import { Console } from 'src/utils/console';
+import { errorMessage } from 'src/types';
 import { computeEstimate, computeStakeSaturation, handleCalculatePeriodRewards } from './utils';
 
 export type DefaultInputValues = {
@@ -62,7 +63,7 @@ export const ApyPlayground = ({ bondedNode }: { bondedNode: TBondedMixnode }) =>
       });
       setIsLoading(false);
     } catch (e) {
-      enqueueSnackbar(e as string, { variant: 'error' });
+      enqueueSnackbar(errorMessage(e), { variant: 'error' });
     }
   };
 
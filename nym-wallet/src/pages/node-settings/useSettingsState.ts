@@ -1,6 +1,7 @@
 import { useState } from 'react';
 import { useSnackbar } from 'notistack';
 import { decimalToPercentage, InclusionProbabilityResponse, MixnodeStatus } from '@nymproject/types';
+import { errorMessage } from 'src/types';
 import { getInclusionProbability, getMixnodeStakeSaturation, getMixnodeStatus } from '../../requests';
 
 export const useSettingsState = () => {
@@ -48,7 +49,7 @@ export const useSettingsState = () => {
       await getStakeSaturation(mixId);
       await getMixnodeInclusionProbability(mixId);
     } catch (e) {
-      enqueueSnackbar(e as string, { variant: 'error', preventDuplicate: true });
+      enqueueSnackbar(errorMessage(e), { variant: 'error', preventDuplicate: true });
       reset();
     }
   };
@@ -28,6 +28,14 @@ export const updatePassword = async ({
 export const signInWithPassword = async (password: string) =>
   invokeWrapper<Account>('sign_in_with_password', { password });
 
+export const rememberPassword = async (password: string) => invokeWrapper<void>('remember_password', { password });
+
+export const forgetRememberedPassword = async () => invokeWrapper<void>('forget_remembered_password');
+
+export const hasRememberedPassword = async () => invokeWrapper<boolean>('has_remembered_password');
+
+export const signInWithRememberedPassword = async () => invokeWrapper<Account>('sign_in_with_remembered_password');
+
 export const switchAccount = async ({ accountId, password }: { accountId: string; password: string }) =>
   invokeWrapper<Account>('sign_in_with_password_and_account_id', { accountId, password });
 
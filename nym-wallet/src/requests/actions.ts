@@ -23,6 +23,9 @@ export const bondGateway = async (args: TBondGatewayArgs) =>
 export const generateGatewayMsgPayload = async (args: Omit<TBondGatewaySignatureArgs, 'tokenPool'>) =>
   invokeWrapper<string>('generate_gateway_bonding_msg_payload', args);
 
+export const generateGatewayMsgPreview = async (args: Omit<TBondGatewaySignatureArgs, 'tokenPool'>) =>
+  invokeWrapper<string>('generate_gateway_bonding_msg_preview', args);
+
 export const unbondGateway = async (fee?: Fee) => invokeWrapper<TransactionExecuteResult>('unbond_gateway', { fee });
 
 export const bondMixNode = async (args: TBondMixNodeArgs) =>
@@ -31,6 +34,9 @@ export const bondMixNode = async (args: TBondMixNodeArgs) =>
 export const generateMixnodeMsgPayload = async (args: Omit<TBondMixnodeSignatureArgs, 'tokenPool'>) =>
   invokeWrapper<string>('generate_mixnode_bonding_msg_payload', args);
 
+export const generateMixnodeMsgPreview = async (args: Omit<TBondMixnodeSignatureArgs, 'tokenPool'>) =>
+  invokeWrapper<string>('generate_mixnode_bonding_msg_preview', args);
+
 export const unbondMixNode = async (fee?: Fee) => invokeWrapper<TransactionExecuteResult>('unbond_mixnode', { fee });
 
 export const updateMixnodeCostParams = async (newCosts: MixNodeCostParams, fee?: Fee) =>
@@ -53,4 +59,13 @@ export const unbond = async (type: EnumNodeType) => {
 export const updateBond = async (args: TUpdateBondArgs) =>
   invokeWrapper<TransactionExecuteResult>('update_pledge', args);
 
+export const generatePledgeAdjustmentMsgPayload = async (args: { newPledge: DecCoin }) =>
+  invokeWrapper<string>('generate_mixnode_pledge_adjustment_msg_payload', args);
+
+export const generatePledgeAdjustmentMsgPreview = async (args: { newPledge: DecCoin }) =>
+  invokeWrapper<string>('generate_mixnode_pledge_adjustment_msg_preview', args);
+
+export const adjustMixnodePledge = async (args: { newPledge: DecCoin; msgSignature: string; fee?: Fee }) =>
+  invokeWrapper<TransactionExecuteResult>('adjust_mixnode_pledge', args);
+
 export const migrateVestedMixnode = async () => invokeWrapper<TransactionExecuteResult>('migrate_vested_mixnode');
@@ -3,9 +3,9 @@ import {
   DelegationsSummaryResponse,
   TransactionExecuteResult,
   DecCoin,
-  FeeDetails,
   Fee,
 } from '@nymproject/types';
+import { FundsSource } from 'src/types';
 import { invokeWrapper } from './wrapper';
 
 export const getMixNodeDelegationsForCurrentAccount = async () =>
@@ -16,21 +16,11 @@ export const getDelegationSummary = async () => invokeWrapper<DelegationsSummary
 export const undelegateFromMixnode = async (mixId: number, fee?: Fee) =>
   invokeWrapper<TransactionExecuteResult>('undelegate_from_mixnode', { mixId, fee });
 
-export const undelegateAllFromMixnode = async (
-  mixId: number,
-  usesVestingContractTokens: boolean,
-  fee_liquid?: FeeDetails,
-  fee_vesting?: FeeDetails,
-) =>
-  invokeWrapper<TransactionExecuteResult[]>('undelegate_all_from_mixnode', {
-    mixId,
-    usesVestingContractTokens,
-    fee_liquid,
-    fee_vesting,
-  });
+export const undelegateAllFromMixnode = async (mixId: number, fundsSource: FundsSource, fee?: Fee) =>
+  invokeWrapper<TransactionExecuteResult>('undelegate_all_from_mixnode', { mixId, fundsSource, fee });
 
-export const delegateToMixnode = async (mixId: number, amount: DecCoin, fee?: Fee) =>
-  invokeWrapper<TransactionExecuteResult>('delegate_to_mixnode', { mixId, amount, fee });
+export const delegateToMixnode = async (mixId: number, amount: DecCoin, fee?: Fee, fundsSource?: FundsSource) =>
+  invokeWrapper<TransactionExecuteResult>('delegate_to_mixnode', { mixId, amount, fundsSource, fee });
 
 export const migrateVestedDelegations = async () =>
   invokeWrapper<TransactionExecuteResult>('migrate_vested_delegations');
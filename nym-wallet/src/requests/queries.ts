@@ -9,7 +9,7 @@ import {
   StakeSaturationResponse,
   WrappedDelegationEvent,
 } from '@nymproject/types';
-import { Interval, TGatewayReport, TNodeDescription } from 'src/types';
+import { Interval, NodeOperationalStatus, TGatewayReport, TNodeDescription } from 'src/types';
 import { invokeWrapper } from './wrapper';
 
 export const getAllPendingDelegations = async () =>
@@ -61,3 +61,6 @@ export const computeMixnodeRewardEstimation = async (args: {
   intervalOperatingCost: { denom: 'unym'; amount: string };
 }) => invokeWrapper<RewardEstimationResponse>('compute_mixnode_reward_estimation', args);
 export const getMixnodeUptime = async (mixId: number) => invokeWrapper<number>('get_mixnode_uptime', { mixId });
+
+export const getNodeOperationalStatus = async () =>
+  invokeWrapper<NodeOperationalStatus | null>('get_node_operational_status');
@@ -10,5 +10,8 @@ export const claimDelegatorRewards = async (mixId: number, fee?: FeeDetails) =>
     fee: fee?.fee,
   });
 
+export const claimAllDelegatorRewards = async (fee?: FeeDetails) =>
+  invokeWrapper<TransactionExecuteResult[]>('claim_all_delegator_rewards', { fee: fee?.fee });
+
 export const getCurrentRewardingParameter = async () =>
   invokeWrapper<RewardingParams>('get_current_rewarding_parameters', {});
@@ -6,7 +6,7 @@ import {
   MixNodeConfigUpdate,
   GatewayConfigUpdate,
 } from '@nymproject/types';
-import { TBondGatewayArgs, TBondMixNodeArgs, TSimulateUpdateBondArgs } from 'src/types';
+import { PledgeAdjustmentSimulation, TBondGatewayArgs, TBondMixNodeArgs, TSimulateUpdateBondArgs } from 'src/types';
 import { invokeWrapper } from './wrapper';
 
 export const simulateBondGateway = async (args: TBondGatewayArgs) =>
@@ -37,6 +37,9 @@ export const simulateUndelegateFromMixnode = async (mixId: number) =>
 export const simulateClaimDelegatorReward = async (mixId: number) =>
   invokeWrapper<FeeDetails>('simulate_claim_delegator_reward', { mixId });
 
+export const simulateClaimAllDelegatorRewards = async () =>
+  invokeWrapper<FeeDetails>('simulate_claim_all_delegator_rewards');
+
 export const simulateVestingClaimDelegatorReward = async (mixId: number) =>
   invokeWrapper<FeeDetails>('simulate_vesting_claim_delegator_reward', { mixId });
 
@@ -85,3 +88,6 @@ export const simulateUpdateBond = async (args: TSimulateUpdateBondArgs) =>
 
 export const simulateVestingUpdateBond = async (args: TSimulateUpdateBondArgs) =>
   invokeWrapper<FeeDetails>('simulate_vesting_update_pledge', args);
+
+export const simulatePledgeAdjustment = async (args: { newPledge: DecCoin }) =>
+  invokeWrapper<PledgeAdjustmentSimulation>('simulate_mixnode_pledge_adjustment', args);
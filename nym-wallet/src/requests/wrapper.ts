@@ -1,9 +1,19 @@
 import { invoke } from '@tauri-apps/api';
 import { config } from '../config';
+import { BackendError, isStructuredBackendError } from '../types';
 import { Console } from '../utils/console';
 
 export async function invokeWrapper<T>(operationName: string, args?: any): Promise<T> {
-  const res = await invoke<T>(operationName, args);
+  let res: T;
+  try {
+    res = await invoke<T>(operationName, args);
+  } catch (e) {
+    if (isStructuredBackendError(e)) {
+      Console.error({ operationName, code: e.code, params: e.params });
+      throw new BackendError(e);
+    }
+    throw e;
+  }
   if (config.LOG_TAURI_OPERATIONS) {
     const argsToLog: any = {};
     if (args) {
@@ -0,0 +1,39 @@
+import { BackendErrorCode } from './rust/BackendErrorCode';
+import { StructuredBackendError } from './rust/StructuredBackendError';
+
+export const isStructuredBackendError = (e: unknown): e is StructuredBackendError =>
+  typeof e === 'object' && e !== null && 'code' in e && 'message' in e && 'params' in e;
+
+/**
+ * Error rejected by the wallet backend. It keeps the stable `code` and the `params`,
+ * so that the views could localise it, while still rendering as the (untranslated) message.
+ */
+export class BackendError extends Error implements StructuredBackendError {
+  readonly code: BackendErrorCode;
+
+  readonly params: Record<string, string>;
+
+  constructor({ code, message, params }: StructuredBackendError) {
+    super(message);
+    this.name = 'BackendError';
+    this.code = code;
+    this.params = params;
+  }
+
+  toString(): string {
+    return this.message;
+  }
+}
+
+/**
+ * Returns the human-readable message of anything thrown by a request.
+ */
+export const errorMessage = (e: unknown): string => {
+  if (e instanceof Error) {
+    return e.message;
+  }
+  if (isStructuredBackendError(e)) {
+    return e.message;
+  }
+  return String(e);
+};
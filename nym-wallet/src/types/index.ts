@@ -1,7 +1,14 @@
+export * from './errors';
 export * from './global';
 export * from './rust/AppEnv';
+export * from './rust/BackendErrorCode';
+export * from './rust/FundsSource';
 export * from './rust/Interval';
 export * from './rust/Network';
+export * from './rust/NodeOperationalStatus';
+export * from './rust/PledgeAdjustmentSimulation';
+export * from './rust/PortReachability';
 export * from './rust/StateParams';
+export * from './rust/StructuredBackendError';
 export * from './rust/ValidatorUrl';
 export * from './rust/ValidatorUrls';
@@ -0,0 +1,47 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type BackendErrorCode =
+  | 'internal'
+  | 'validator_unreachable'
+  | 'chain_request_failed'
+  | 'directory_unavailable'
+  | 'http_request_failed'
+  | 'malformed_url'
+  | 'validator_connection_failed'
+  | 'no_default_validator'
+  | 'client_not_initialized'
+  | 'network_not_supported'
+  | 'storage_unavailable'
+  | 'wallet_file_already_exists'
+  | 'wallet_file_not_found'
+  | 'wallet_file_malformed'
+  | 'wallet_file_archive_failed'
+  | 'wallet_decryption_failed'
+  | 'login_not_found'
+  | 'login_already_exists'
+  | 'account_not_found'
+  | 'account_already_exists'
+  | 'mnemonic_already_exists'
+  | 'no_remembered_password'
+  | 'different_password'
+  | 'unexpected_mnemonic_account'
+  | 'keyring_unavailable'
+  | 'invalid_mnemonic'
+  | 'address_derivation_failed'
+  | 'signature_failed'
+  | 'unexpected_signing_algorithm'
+  | 'no_balance'
+  | 'insufficient_funds'
+  | 'invalid_amount'
+  | 'unknown_coin_denom'
+  | 'no_coins_registered'
+  | 'pledge_update_no_op'
+  | 'pledge_update_invalid_currency'
+  | 'unsupported_vesting_operation'
+  | 'no_vesting_delegations'
+  | 'no_delegations'
+  | 'no_bonded_mixnode'
+  | 'unknown_ibc_channel'
+  | 'window_creation_failed'
+  | 'update_check_failed'
+  | 'removed_command';
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PortReachability } from './PortReachability';
+
+export interface NodeOperationalStatus {
+  identity_key: string;
+  is_mixnode: boolean;
+  bonded_version: string;
+  reported_version: string | null;
+  version_mismatch: boolean;
+  reported_health: string | null;
+  ports: Array<PortReachability>;
+  performance: string | null;
+  stake_saturation: string | null;
+  uncapped_stake_saturation: string | null;
+  blacklisted: boolean;
+}
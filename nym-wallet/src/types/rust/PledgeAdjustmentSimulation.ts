@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeeDetails } from '@nymproject/types/src/types/rust/FeeDetails';
+import type { RewardEstimate } from '@nymproject/types/src/types/rust/RewardEstimate';
+
+export interface PledgeAdjustmentSimulation {
+  fee: FeeDetails;
+  current_saturation: string;
+  projected_saturation: string;
+  current_estimation: RewardEstimate;
+  projected_estimation: RewardEstimate;
+}
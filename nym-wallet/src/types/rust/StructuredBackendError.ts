@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BackendErrorCode } from './BackendErrorCode';
+
+export interface StructuredBackendError {
+  code: BackendErrorCode;
+  message: string;
+  params: Record<string, string>;
+}
@@ -37,6 +37,14 @@ pub extern "C" fn get_self_address(callback: CStringCallback) -> c_int {
     }
 }
 
+#[no_mangle]
+pub extern "C" fn notify_network_change(available: bool) -> c_int {
+    match nym_ffi_shared::notify_network_change_internal(available) {
+        Ok(_) => StatusCode::NoError as c_int,
+        Err(_) => StatusCode::NetworkChangeError as c_int,
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn send_message(recipient: *const c_char, message: *const c_char) -> c_int {
     let c_str = unsafe {
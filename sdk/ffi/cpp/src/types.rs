@@ -18,6 +18,7 @@ pub mod types {
         ListenError = -6,
         RecipientNullError = -7,
         MessageNullError = -8,
+        NetworkChangeError = -9,
     }
 
     #[repr(C)]
@@ -28,6 +28,8 @@ enum GoWrapError {
     AddressGetterError {},
     #[error("Couldn't run proxy server")]
     ServerRunError {},
+    #[error("Couldn't notify the client about the network change")]
+    NetworkChangeError {},
 }
 
 #[no_mangle]
@@ -51,6 +53,14 @@ fn get_self_address() -> Result<String, GoWrapError> {
     }
 }
 
+#[no_mangle]
+fn notify_network_change(available: bool) -> Result<(), GoWrapError> {
+    match nym_ffi_shared::notify_network_change_internal(available) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(GoWrapError::NetworkChangeError {}),
+    }
+}
+
 #[no_mangle]
 fn send_message(recipient: String, message: String) -> Result<(), GoWrapError> {
     let nym_recipient_type =
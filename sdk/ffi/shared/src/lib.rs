@@ -4,8 +4,8 @@
 use anyhow::{anyhow, bail};
 use lazy_static::lazy_static;
 use nym_sdk::mixnet::{
-    MixnetClient, MixnetClientBuilder, MixnetMessageSender, Recipient, ReconstructedMessage,
-    StoragePaths,
+    MixnetClient, MixnetClientBuilder, MixnetMessageSender, NetworkChange, Recipient,
+    ReconstructedMessage, StoragePaths,
 };
 use nym_sdk::tcp_proxy::{NymProxyClient, NymProxyServer};
 use nym_sphinx_anonymous_replies::requests::AnonymousSenderTag;
@@ -78,6 +78,24 @@ pub fn get_self_address_internal() -> anyhow::Result<String, anyhow::Error> {
     Ok(nym_client.nym_address().to_string())
 }
 
+pub fn notify_network_change_internal(available: bool) -> anyhow::Result<(), anyhow::Error> {
+    let client = NYM_CLIENT.lock().expect("could not lock NYM_CLIENT");
+    if client.is_none() {
+        bail!("Client is not yet initialised");
+    }
+    let nym_client = client
+        .as_ref()
+        .ok_or_else(|| anyhow!("could not get client as_ref()"))?;
+
+    let change = if available {
+        NetworkChange::Available
+    } else {
+        NetworkChange::Lost
+    };
+    nym_client.network_change_notifier().notify(change);
+    Ok(())
+}
+
 // TODO split sender
 
 pub fn send_message_internal(
@@ -134,6 +152,7 @@ pub fn listen_for_incoming_internal() -> anyhow::Result<ReconstructedMessage, an
         Ok::<ReconstructedMessage, anyhow::Error>(ReconstructedMessage {
             message: received.message,
             sender_tag: received.sender_tag,
+            fragment_set_id: received.fragment_set_id,
         })
     })?;
 
@@ -3,7 +3,9 @@
 
 use crate::config::Config;
 use nym_client_core::client::base_client::storage::{InMemGatewaysDetails, MixnetClientStorage};
+use nym_client_core::client::inbox;
 use nym_client_core::client::key_manager::persistence::InMemEphemeralKeys;
+use nym_client_core::client::outbox;
 use nym_client_core::client::replies::reply_storage;
 use nym_credential_storage::ephemeral_storage::EphemeralStorage as EphemeralCredentialStorage;
 
@@ -14,6 +16,8 @@ pub struct MobileClientStorage {
 
     reply_store: reply_storage::Empty,
     credential_store: EphemeralCredentialStorage,
+    inbox_store: inbox::Disabled,
+    outbox_store: outbox::Disabled,
 }
 
 impl MixnetClientStorage for MobileClientStorage {
@@ -21,6 +25,8 @@ impl MixnetClientStorage for MobileClientStorage {
     type ReplyStore = reply_storage::Empty;
     type CredentialStore = EphemeralCredentialStorage;
     type GatewaysDetailsStore = InMemGatewaysDetails;
+    type InboxStore = inbox::Disabled;
+    type OutboxStore = outbox::Disabled;
 
     fn into_runtime_stores(
         self,
@@ -28,11 +34,15 @@ impl MixnetClientStorage for MobileClientStorage {
         Self::ReplyStore,
         Self::CredentialStore,
         Self::GatewaysDetailsStore,
+        Self::InboxStore,
+        Self::OutboxStore,
     ) {
         (
             self.reply_store,
             self.credential_store,
             self.gateway_details_store,
+            self.inbox_store,
+            self.outbox_store,
         )
     }
 
@@ -51,6 +61,14 @@ impl MixnetClientStorage for MobileClientStorage {
     fn gateway_details_store(&self) -> &Self::GatewaysDetailsStore {
         &self.gateway_details_store
     }
+
+    fn inbox_store(&self) -> &Self::InboxStore {
+        &self.inbox_store
+    }
+
+    fn outbox_store(&self) -> &Self::OutboxStore {
+        &self.outbox_store
+    }
 }
 
 impl MobileClientStorage {
@@ -60,6 +78,8 @@ impl MobileClientStorage {
             gateway_details_store: Default::default(),
             reply_store: Default::default(),
             credential_store: Default::default(),
+            inbox_store: Default::default(),
+            outbox_store: Default::default(),
         }
     }
 }
@@ -0,0 +1,22 @@
+use nym_sdk::mixnet::BlockingMixnetClient;
+
+fn main() {
+    nym_bin_common::logging::setup_logging();
+
+    // the blocking client owns its own runtime, so there's no need for `#[tokio::main]`
+    let mut client = BlockingMixnetClient::connect_new().unwrap();
+
+    // Be able to get our client address
+    let our_address = *client.nym_address();
+    println!("Our client nym address is: {our_address}");
+
+    // Send a message through the mixnet to ourselves
+    client
+        .send_plain_message(our_address, "hello there")
+        .unwrap();
+
+    println!("Waiting for message (ctrl-c to exit)");
+    for msg in client.messages() {
+        println!("Received: {}", String::from_utf8_lossy(&msg.message))
+    }
+}
@@ -1,10 +1,12 @@
 use nym_crypto::asymmetric::ed25519::PublicKey;
 use nym_gateway_requests::SharedSymmetricKey;
 use nym_sdk::mixnet::{
-    self, ActiveGateway, BadGateway, ClientKeys, EmptyReplyStorage, EphemeralCredentialStorage,
-    GatewayRegistration, GatewaysDetailsStore, KeyStore, MixnetClientStorage, MixnetMessageSender,
+    self, ActiveGateway, BadGateway, ClientKeys, DisabledInbox, DisabledOutbox, EmptyReplyStorage,
+    EphemeralCredentialStorage, GatewayBandwidth, GatewayLatency, GatewayRegistration,
+    GatewaysDetailsStore, KeyStore, MixnetClientStorage, MixnetMessageSender,
 };
 use nym_topology::provider_trait::async_trait;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() {
@@ -46,6 +48,8 @@ struct MockClientStorage {
     pub gateway_details_store: MockGatewayDetailsStore,
     pub reply_store: EmptyReplyStorage,
     pub credential_store: EphemeralCredentialStorage,
+    pub inbox_store: DisabledInbox,
+    pub outbox_store: DisabledOutbox,
 }
 
 impl MockClientStorage {
@@ -55,6 +59,8 @@ impl MockClientStorage {
             gateway_details_store: MockGatewayDetailsStore,
             reply_store: EmptyReplyStorage::default(),
             credential_store: EphemeralCredentialStorage::default(),
+            inbox_store: DisabledInbox,
+            outbox_store: DisabledOutbox,
         }
     }
 }
@@ -64,6 +70,8 @@ impl MixnetClientStorage for MockClientStorage {
     type ReplyStore = EmptyReplyStorage;
     type CredentialStore = EphemeralCredentialStorage;
     type GatewaysDetailsStore = MockGatewayDetailsStore;
+    type InboxStore = DisabledInbox;
+    type OutboxStore = DisabledOutbox;
 
     fn into_runtime_stores(
         self,
@@ -71,11 +79,15 @@ impl MixnetClientStorage for MockClientStorage {
         Self::ReplyStore,
         Self::CredentialStore,
         Self::GatewaysDetailsStore,
+        Self::InboxStore,
+        Self::OutboxStore,
     ) {
         (
             self.reply_store,
             self.credential_store,
             self.gateway_details_store,
+            self.inbox_store,
+            self.outbox_store,
         )
     }
 
@@ -94,6 +106,14 @@ impl MixnetClientStorage for MockClientStorage {
     fn gateway_details_store(&self) -> &Self::GatewaysDetailsStore {
         &self.gateway_details_store
     }
+
+    fn inbox_store(&self) -> &Self::InboxStore {
+        &self.inbox_store
+    }
+
+    fn outbox_store(&self) -> &Self::OutboxStore {
+        &self.outbox_store
+    }
 }
 
 struct MockKeyStore;
@@ -113,6 +133,12 @@ impl KeyStore for MockKeyStore {
 
         Ok(())
     }
+
+    async fn store_rotated_keys(&self, _keys: &ClientKeys) -> Result<(), Self::StorageError> {
+        println!("storing rotated keys");
+
+        Ok(())
+    }
 }
 
 struct MockGatewayDetailsStore;
@@ -178,6 +204,38 @@ impl GatewaysDetailsStore for MockGatewayDetailsStore {
 
         Ok(())
     }
+
+    async fn record_gateway_latency(
+        &self,
+        gateway_id: PublicKey,
+        latency: Duration,
+    ) -> Result<(), Self::StorageError> {
+        println!("recording latency of {latency:?} for {gateway_id}");
+
+        Ok(())
+    }
+
+    async fn gateway_latencies(&self) -> Result<Vec<GatewayLatency>, Self::StorageError> {
+        println!("getting gateway latencies");
+
+        Ok(Vec::new())
+    }
+
+    async fn record_gateway_bandwidth(
+        &self,
+        gateway_id: PublicKey,
+        remaining: i64,
+    ) -> Result<(), Self::StorageError> {
+        println!("recording {remaining} bytes of remaining bandwidth for {gateway_id}");
+
+        Ok(())
+    }
+
+    async fn gateway_bandwidths(&self) -> Result<Vec<GatewayBandwidth>, Self::StorageError> {
+        println!("getting gateway bandwidths");
+
+        Ok(Vec::new())
+    }
 }
 
 //
@@ -58,12 +58,10 @@ async fn main() {
         parsed, return_recipient
     );
 
-    // reply to self with it: note we use `send_str_reply` instead of `send_str`
+    // reply to self with it: note we use `reply` instead of `send_plain_message`.
+    // the number of SURBs used (and requested, if needed) is worked out automatically
     println!("Replying with using SURBs");
-    client
-        .send_reply(return_recipient, "hi an0n!")
-        .await
-        .unwrap();
+    client.reply(return_recipient, "hi an0n!").await.unwrap();
 
     println!("Waiting for message (once you see it, ctrl-c to exit)\n");
     client
@@ -0,0 +1,585 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+//! Discovery of the service providers, such as network requesters, available in the network.
+//!
+//! The results are merged from three sources: the wellknown directory of curated services,
+//! the health checks performed by the harbourmaster and the service providers embedded in
+//! the gateways bonded in the mixnet contract, as announced through the nym-api.
+//!
+//! # Basic example
+//!
+//! ```no_run
+//! use nym_sdk::discovery::{self, ServiceFilter, ServiceKind};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let filter = ServiceFilter::new()
+//!         .with_kinds([ServiceKind::NetworkRequester])
+//!         .with_exit_policy_required(true)
+//!         .with_minimum_routing_score(0.8);
+//!
+//!     // the services are ordered by their routing score, so the first one is the best pick
+//!     let services = discovery::discover_services(filter).await.unwrap();
+//!     if let Some(service) = services.first() {
+//!         println!("using {}", service.address);
+//!     }
+//! }
+//! ```
+
+use crate::{Error, NymNetworkDetails, Result};
+use log::{debug, warn};
+use nym_sphinx::addressing::clients::Recipient;
+use nym_validator_client::nym_api::Client as HttpClient;
+use nym_validator_client::NymApiClient;
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::time::Duration;
+use url::Url;
+
+const SERVICE_PROVIDER_WELLKNOWN_URL: &str =
+    "https://nymtech.net/.wellknown/connect/service-providers.json";
+
+const HARBOUR_MASTER_URL: &str = "https://harbourmaster.nymtech.net";
+const HM_SINCE_MIN: &str = "120";
+const HM_SIZE: &str = "100";
+
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Type of the service provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum ServiceKind {
+    NetworkRequester,
+    IpPacketRouter,
+}
+
+/// Source the information about the service has been obtained from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum ServiceSource {
+    /// The wellknown directory of curated services.
+    Directory,
+
+    /// The health checks of the harbourmaster.
+    Harbourmaster,
+
+    /// The gateways bonded in the mixnet contract.
+    Chain,
+}
+
+/// Results of the most recent health check of the service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceHealth {
+    /// Fraction of the test requests that have been successfully routed through the service.
+    pub routing_score: f32,
+
+    /// Timestamp of the last successful ping, as reported by the harbourmaster.
+    pub last_successful_ping: String,
+}
+
+/// Service provider merged from all the sources it has been found in.
+#[derive(Debug, Clone)]
+pub struct DiscoveredService {
+    pub address: Recipient,
+    pub kind: ServiceKind,
+
+    /// Human-readable description of the service, if it's listed in the directory.
+    pub description: Option<String>,
+
+    /// Location of the gateway the service is embedded in, as declared in its bond.
+    pub location: Option<String>,
+
+    /// Whether the service uses the exit policy rather than the deprecated allow list.
+    /// `None` if the service hasn't described its capabilities.
+    pub uses_exit_policy: Option<bool>,
+
+    /// `None` if the harbourmaster hasn't recently checked the service.
+    pub health: Option<ServiceHealth>,
+
+    pub sources: BTreeSet<ServiceSource>,
+}
+
+impl DiscoveredService {
+    fn new(address: Recipient, kind: ServiceKind, source: ServiceSource) -> Self {
+        DiscoveredService {
+            address,
+            kind,
+            description: None,
+            location: None,
+            uses_exit_policy: None,
+            health: None,
+            sources: BTreeSet::from([source]),
+        }
+    }
+
+    pub fn routing_score(&self) -> Option<f32> {
+        self.health.as_ref().map(|health| health.routing_score)
+    }
+}
+
+/// Criteria the discovered services have to satisfy.
+#[derive(Debug, Clone, Default)]
+pub struct ServiceFilter {
+    kinds: HashSet<ServiceKind>,
+    minimum_routing_score: Option<f32>,
+    exit_policy_required: bool,
+    limit: Option<usize>,
+}
+
+impl ServiceFilter {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Only return services of the specified kinds. By default, all kinds are returned.
+    #[must_use]
+    pub fn with_kinds(mut self, kinds: impl IntoIterator<Item = ServiceKind>) -> Self {
+        self.kinds.extend(kinds);
+        self
+    }
+
+    /// Only return services that have been recently checked by the harbourmaster
+    /// and achieved at least the specified routing score.
+    #[must_use]
+    pub fn with_minimum_routing_score(mut self, minimum_routing_score: f32) -> Self {
+        self.minimum_routing_score = Some(minimum_routing_score);
+        self
+    }
+
+    /// Only return services that are known to use the exit policy.
+    #[must_use]
+    pub fn with_exit_policy_required(mut self, exit_policy_required: bool) -> Self {
+        self.exit_policy_required = exit_policy_required;
+        self
+    }
+
+    /// Return at most the specified number of the best scoring services.
+    #[must_use]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn matches(&self, service: &DiscoveredService) -> bool {
+        if !self.kinds.is_empty() && !self.kinds.contains(&service.kind) {
+            return false;
+        }
+        if let Some(minimum_routing_score) = self.minimum_routing_score {
+            if !matches!(service.routing_score(), Some(score) if score >= minimum_routing_score) {
+                return false;
+            }
+        }
+        if self.exit_policy_required && service.uses_exit_policy != Some(true) {
+            return false;
+        }
+        true
+    }
+}
+
+// https://nymtech.net/.wellknown/connect/service-providers.json
+#[derive(Debug, Deserialize)]
+struct DirectoryService {
+    items: Vec<DirectoryServiceProvider>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DirectoryServiceProvider {
+    description: String,
+    address: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PagedResult<T> {
+    items: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HarbourMasterService {
+    service_provider_client_id: String,
+    last_successful_ping_utc: String,
+    routing_score: f32,
+}
+
+// services keyed by their kind and base58-encoded address
+struct DiscoveredServices(HashMap<(ServiceKind, String), DiscoveredService>);
+
+impl DiscoveredServices {
+    fn new() -> Self {
+        DiscoveredServices(HashMap::new())
+    }
+
+    fn insert(
+        &mut self,
+        address: &str,
+        kind: ServiceKind,
+        source: ServiceSource,
+    ) -> Option<&mut DiscoveredService> {
+        let recipient = match Recipient::try_from_base58_string(address) {
+            Ok(recipient) => recipient,
+            Err(err) => {
+                debug!("ignoring service with malformed address '{address}': {err}");
+                return None;
+            }
+        };
+        let service = self
+            .0
+            .entry((kind, recipient.to_string()))
+            .or_insert_with(|| DiscoveredService::new(recipient, kind, source));
+        service.sources.insert(source);
+        Some(service)
+    }
+
+    fn with_address<'a>(
+        &'a mut self,
+        address: &'a str,
+    ) -> impl Iterator<Item = &'a mut DiscoveredService> + 'a {
+        self.0
+            .iter_mut()
+            .filter(move |((_, service_address), _)| service_address == address)
+            .map(|(_, service)| service)
+    }
+
+    fn insert_directory(&mut self, directory: Vec<DirectoryService>) {
+        for provider in directory.into_iter().flat_map(|service| service.items) {
+            // the directory only lists network requesters
+            if let Some(service) = self.insert(
+                &provider.address,
+                ServiceKind::NetworkRequester,
+                ServiceSource::Directory,
+            ) {
+                service.description = Some(provider.description);
+            }
+        }
+    }
+
+    // the harbourmaster only provides health of the already known services
+    fn update_health(&mut self, checked: Vec<HarbourMasterService>) {
+        for checked_service in checked {
+            for service in self.with_address(&checked_service.service_provider_client_id) {
+                service.sources.insert(ServiceSource::Harbourmaster);
+                service.health = Some(ServiceHealth {
+                    routing_score: checked_service.routing_score,
+                    last_successful_ping: checked_service.last_successful_ping_utc.clone(),
+                });
+            }
+        }
+    }
+
+    // returns the services matching the filter, ordered by their routing score
+    fn into_matching(self, filter: &ServiceFilter) -> Vec<DiscoveredService> {
+        let mut discovered = self
+            .0
+            .into_values()
+            .filter(|service| filter.matches(service))
+            .collect::<Vec<_>>();
+        discovered.sort_by(|a, b| {
+            b.routing_score()
+                .unwrap_or(-1.0)
+                .total_cmp(&a.routing_score().unwrap_or(-1.0))
+        });
+        if let Some(limit) = filter.limit {
+            discovered.truncate(limit);
+        }
+        discovered
+    }
+}
+
+/// Queries all the service sources and merges their results.
+#[derive(Debug, Clone)]
+pub struct ServiceDiscovery {
+    wellknown_url: Url,
+    harbourmaster_url: Url,
+    nym_api_url: Url,
+    request_timeout: Duration,
+}
+
+impl Default for ServiceDiscovery {
+    fn default() -> Self {
+        ServiceDiscovery::new_with_network(&NymNetworkDetails::new_mainnet())
+    }
+}
+
+impl ServiceDiscovery {
+    /// Creates new instance querying the services of the mainnet.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Creates new instance obtaining the bonded services from the nym-api of the provided network.
+    pub fn new_with_network(network: &NymNetworkDetails) -> Self {
+        let nym_api_url = network
+            .endpoints
+            .iter()
+            .find_map(|endpoint| endpoint.api_url())
+            .unwrap_or_else(|| {
+                nym_network_defaults::mainnet::NYM_API
+                    .parse()
+                    .expect("invalid default nym-api url")
+            });
+
+        ServiceDiscovery {
+            wellknown_url: SERVICE_PROVIDER_WELLKNOWN_URL
+                .parse()
+                .expect("invalid default wellknown url"),
+            harbourmaster_url: HARBOUR_MASTER_URL
+                .parse()
+                .expect("invalid default harbourmaster url"),
+            nym_api_url,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+
+    #[must_use]
+    pub fn with_wellknown_url(mut self, wellknown_url: Url) -> Self {
+        self.wellknown_url = wellknown_url;
+        self
+    }
+
+    #[must_use]
+    pub fn with_harbourmaster_url(mut self, harbourmaster_url: Url) -> Self {
+        self.harbourmaster_url = harbourmaster_url;
+        self
+    }
+
+    #[must_use]
+    pub fn with_nym_api_url(mut self, nym_api_url: Url) -> Self {
+        self.nym_api_url = nym_api_url;
+        self
+    }
+
+    #[must_use]
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    async fn directory_services(&self) -> std::result::Result<Vec<DirectoryService>, String> {
+        HttpClient::new_url::<_, String>(self.wellknown_url.clone(), Some(self.request_timeout))
+            .map_err(|err| err.to_string())?
+            .get_json_endpoint::<_, _, String>(self.wellknown_url.as_str())
+            .await
+            .map_err(|err| err.to_string())
+    }
+
+    async fn harbourmaster_services(
+        &self,
+    ) -> std::result::Result<Vec<HarbourMasterService>, String> {
+        let paged: PagedResult<HarbourMasterService> = HttpClient::new_url::<_, String>(
+            self.harbourmaster_url.clone(),
+            Some(self.request_timeout),
+        )
+        .map_err(|err| err.to_string())?
+        .get_json::<_, _, _, String>(
+            &["v1", "services"],
+            &[("since_min", HM_SINCE_MIN), ("size", HM_SIZE)],
+        )
+        .await
+        .map_err(|err| err.to_string())?;
+        Ok(paged.items)
+    }
+
+    /// Obtains the services from all the sources, returning the ones matching the filter
+    /// ordered by their routing score. Fails only if none of the sources could be queried.
+    pub async fn discover(&self, filter: &ServiceFilter) -> Result<Vec<DiscoveredService>> {
+        let mut services = DiscoveredServices::new();
+        let mut reachable_sources = 0;
+
+        match self.directory_services().await {
+            Ok(directory) => {
+                reachable_sources += 1;
+                services.insert_directory(directory);
+            }
+            Err(err) => warn!("failed to query the service directory: {err}"),
+        }
+
+        let nym_api = NymApiClient::new(self.nym_api_url.clone());
+        match nym_api.get_cached_described_gateways().await {
+            Ok(gateways) => {
+                reachable_sources += 1;
+                for gateway in gateways {
+                    let Some(description) = gateway.self_described else {
+                        continue;
+                    };
+                    let location = gateway.bond.gateway.location;
+                    if let Some(nr) = description.network_requester {
+                        if let Some(service) = services.insert(
+                            &nr.address,
+                            ServiceKind::NetworkRequester,
+                            ServiceSource::Chain,
+                        ) {
+                            service.location = Some(location.clone());
+                            service.uses_exit_policy = Some(nr.uses_exit_policy);
+                        }
+                    }
+                    if let Some(ipr) = description.ip_packet_router {
+                        if let Some(service) = services.insert(
+                            &ipr.address,
+                            ServiceKind::IpPacketRouter,
+                            ServiceSource::Chain,
+                        ) {
+                            service.location = Some(location);
+                        }
+                    }
+                }
+            }
+            Err(err) => warn!("failed to query the bonded gateways: {err}"),
+        }
+
+        match self.harbourmaster_services().await {
+            Ok(checked) => {
+                reachable_sources += 1;
+                services.update_health(checked);
+            }
+            Err(err) => warn!("failed to query the harbourmaster: {err}"),
+        }
+
+        if reachable_sources == 0 {
+            return Err(Error::ServiceDiscoveryFailure);
+        }
+
+        Ok(services.into_matching(filter))
+    }
+}
+
+/// Discovers the mainnet services matching the provided filter.
+/// See [`ServiceDiscovery`] for querying other networks.
+pub async fn discover_services(filter: ServiceFilter) -> Result<Vec<DiscoveredService>> {
+    ServiceDiscovery::new().discover(&filter).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nym_crypto::asymmetric::{encryption, identity};
+    use rand::rngs::OsRng;
+
+    fn address() -> String {
+        Recipient::new(
+            *identity::KeyPair::new(&mut OsRng).public_key(),
+            *encryption::KeyPair::new(&mut OsRng).public_key(),
+            *identity::KeyPair::new(&mut OsRng).public_key(),
+        )
+        .to_string()
+    }
+
+    fn checked(address: &str, routing_score: f32) -> HarbourMasterService {
+        HarbourMasterService {
+            service_provider_client_id: address.to_string(),
+            last_successful_ping_utc: "2024-11-06T12:00:00Z".to_string(),
+            routing_score,
+        }
+    }
+
+    #[test]
+    fn services_from_all_sources_are_merged() {
+        let listed = address();
+        let bonded_only = address();
+        let unknown = address();
+
+        let mut services = DiscoveredServices::new();
+        services.insert_directory(vec![DirectoryService {
+            items: vec![
+                DirectoryServiceProvider {
+                    description: "curated".to_string(),
+                    address: listed.clone(),
+                },
+                DirectoryServiceProvider {
+                    description: "broken".to_string(),
+                    address: "not-an-address".to_string(),
+                },
+            ],
+        }]);
+        services
+            .insert(&listed, ServiceKind::NetworkRequester, ServiceSource::Chain)
+            .unwrap()
+            .uses_exit_policy = Some(true);
+        services.insert(&listed, ServiceKind::IpPacketRouter, ServiceSource::Chain);
+        services.insert(
+            &bonded_only,
+            ServiceKind::NetworkRequester,
+            ServiceSource::Chain,
+        );
+        services.update_health(vec![checked(&listed, 0.9), checked(&unknown, 1.0)]);
+
+        let discovered = services.into_matching(&ServiceFilter::new());
+        assert_eq!(discovered.len(), 3);
+
+        // the health applies to every service behind the address, but it doesn't introduce new ones
+        assert!(discovered
+            .iter()
+            .all(|service| service.address.to_string() != unknown));
+        let (healthy, unchecked) = discovered.split_at(2);
+        assert!(healthy.iter().all(|s| s.address.to_string() == listed));
+        assert!(healthy.iter().all(|s| s.routing_score() == Some(0.9)));
+        assert_eq!(unchecked[0].address.to_string(), bonded_only);
+        assert_eq!(unchecked[0].sources, BTreeSet::from([ServiceSource::Chain]));
+
+        let requester = healthy
+            .iter()
+            .find(|s| s.kind == ServiceKind::NetworkRequester)
+            .unwrap();
+        assert_eq!(requester.description.as_deref(), Some("curated"));
+        assert_eq!(requester.uses_exit_policy, Some(true));
+        assert_eq!(
+            requester.sources,
+            BTreeSet::from([
+                ServiceSource::Directory,
+                ServiceSource::Harbourmaster,
+                ServiceSource::Chain
+            ])
+        );
+    }
+
+    #[test]
+    fn services_are_filtered_and_ordered_by_their_routing_score() {
+        let scores = [0.5, 0.95, 0.8, 0.7];
+        let addresses = scores.map(|_| address());
+
+        let mut services = DiscoveredServices::new();
+        for address in &addresses {
+            services
+                .insert(address, ServiceKind::NetworkRequester, ServiceSource::Chain)
+                .unwrap()
+                .uses_exit_policy = Some(address != &addresses[2]);
+        }
+        services.insert(
+            &address(),
+            ServiceKind::IpPacketRouter,
+            ServiceSource::Chain,
+        );
+        services.update_health(
+            addresses
+                .iter()
+                .zip(scores)
+                .map(|(address, score)| checked(address, score))
+                .collect(),
+        );
+
+        let filter = ServiceFilter::new()
+            .with_kinds([ServiceKind::NetworkRequester])
+            .with_minimum_routing_score(0.6)
+            .with_exit_policy_required(true);
+        let discovered = services.into_matching(&filter);
+        let discovered = discovered
+            .iter()
+            .map(|service| service.address.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(discovered, vec![addresses[1].clone(), addresses[3].clone()]);
+    }
+
+    #[tokio::test]
+    async fn discovery_fails_if_no_source_is_reachable() {
+        // nothing is going to be listening on that port
+        let unreachable: Url = "http://127.0.0.1:1".parse().unwrap();
+        let discovery = ServiceDiscovery::new()
+            .with_wellknown_url(unreachable.clone())
+            .with_harbourmaster_url(unreachable.clone())
+            .with_nym_api_url(unreachable)
+            .with_request_timeout(Duration::from_secs(1));
+
+        assert!(matches!(
+            discovery.discover(&ServiceFilter::new()).await,
+            Err(Error::ServiceDiscoveryFailure)
+        ));
+    }
+}
@@ -1,3 +1,4 @@
+use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nym_validator_client::nyxd::error::NyxdError;
 use std::path::PathBuf;
 
@@ -16,6 +17,9 @@ pub enum Error {
     #[error("Ed25519 error: {0}")]
     Ed25519RecoveryError(#[from] nym_crypto::asymmetric::identity::Ed25519RecoveryError),
 
+    #[error(transparent)]
+    SigningFailure(#[from] nym_crypto::asymmetric::identity::SigningError),
+
     #[error(transparent)]
     ClientCoreError(#[from] nym_client_core::error::ClientCoreError),
 
@@ -91,6 +95,18 @@ pub enum Error {
     #[error("failed to send the provided message")]
     MessageSendingFailure,
 
+    #[error("we have never received any reply surbs from {sender_tag}, so we cannot reply to it")]
+    NoReplySurbs { sender_tag: AnonymousSenderTag },
+
+    #[error("failed to synchronise the reply storage: {0}")]
+    ReplySyncError(#[from] nym_client_core::client::replies::reply_storage::ReplySyncError),
+
+    #[error("failed to discover services: none of the sources could be queried")]
+    ServiceDiscoveryFailure,
+
+    #[error("the blocking client can't be used from within an async runtime - use the MixnetClient instead")]
+    BlockingClientInAsyncContext,
+
     #[error("this operation is currently unsupported: {details}")]
     Unsupported { details: String },
 }
@@ -2,10 +2,12 @@
 //!
 //! The main component currently is [`mixnet`].
 //! [`tcp_proxy`] is probably a good place to start for anyone wanting to integrate with existing app code and read/write from a socket.
+//! [`discovery`] helps with choosing the service providers, such as network requesters, to talk to.
 
 mod error;
 
 pub mod bandwidth;
+pub mod discovery;
 pub mod mixnet;
 pub mod tcp_proxy;
 
@@ -30,6 +30,7 @@
 //! }
 //! ```
 
+mod blocking;
 mod client;
 mod config;
 mod connection_state;
@@ -38,30 +39,50 @@ mod paths;
 mod socks5_client;
 mod traits;
 
+pub use blocking::{BlockingMixnetClient, Messages as BlockingMessages};
 pub use client::{DisconnectedMixnetClient, IncludedSurbs, MixnetClientBuilder};
 pub use config::Config;
 pub use native_client::MixnetClient;
 pub use native_client::MixnetClientSender;
 pub use nym_client_core::{
     client::{
+        base_client::gateway_throughput::{GatewayThroughputTestConfig, ThroughputTestResult},
         base_client::storage::{
             gateways_storage::{
-                ActiveGateway, BadGateway, GatewayRegistration, GatewaysDetailsStore,
+                ActiveGateway, BadGateway, GatewayBandwidth, GatewayLatency, GatewayRegistration,
+                GatewaysDetailsStore,
             },
             Ephemeral, MixnetClientStorage, OnDiskPersistent,
         },
+        control::{ClientControl, RuntimeParameters},
+        delivery::{DeliveryStatus, DeliveryStatusReceiver, DeliveryToken},
+        diagnostics::{
+            ClientDiagnostics, DiagnosticsConfig, EchoHealth, GatewayHealth, HealthReport,
+            TopologyHealth,
+        },
+        drain::{ClientDrain, DrainConfig, DrainSummary},
+        events::{ClientEvent, ClientEvents},
         inbound_messages::InputMessage,
+        inbox::{Disabled as DisabledInbox, InboxMessageId, InboxStorage, OnDiskInbox},
         key_manager::{
             persistence::{InMemEphemeralKeys, KeyStore, OnDiskKeys},
             ClientKeys,
         },
+        outbox::{Disabled as DisabledOutbox, OutboxMessageId, OutboxStorage, SqliteOutbox},
         replies::reply_storage::{
             fs_backend::Backend as ReplyStorage, CombinedReplyStorage, Empty as EmptyReplyStorage,
-            ReplyStorageBackend,
+            ReplyStorageBackend, ReplySyncError, SurbHandover, SyncMergeSummary,
+        },
+        roaming::{NetworkChange, NetworkChangeNotifier},
+        topology_control::{
+            combinators::{CachedProvider, FallbackProvider},
+            file_provider::FileTopologyProvider,
+            geo_aware_provider::{CountryGroup, GeoAwareTopologyProvider},
+            NodeAnnotation, RoutabilityListener, RoutingFilter, TopologyRoutability,
         },
-        topology_control::geo_aware_provider::{CountryGroup, GeoAwareTopologyProvider},
     },
     config::GroupBy,
+    init::selector::{AllowlistGatewaySelector, GatewaySelector, GatewaySelectorError},
 };
 pub use nym_credential_storage::{
     ephemeral_storage::EphemeralStorage as EphemeralCredentialStorage,
@@ -69,16 +90,18 @@ pub use nym_credential_storage::{
 };
 pub use nym_crypto::asymmetric::ed25519;
 pub use nym_network_defaults::NymNetworkDetails;
-pub use nym_socks5_client_core::config::Socks5;
+pub use nym_socks5_client_core::config::{ActivityLog, Socks5};
+pub use nym_socks5_client_core::socks::activity::{ConnectionActivityLog, ConnectionRecord};
 pub use nym_sphinx::{
     addressing::{
         clients::{ClientIdentity, Recipient, RecipientFormattingError},
         nodes::NodeIdentity,
     },
     anonymous_replies::requests::AnonymousSenderTag,
+    preparer::latency::LatencyBudget,
     receiver::ReconstructedMessage,
 };
-pub use nym_task::connections::TransmissionLane;
+pub use nym_task::connections::{MessagePriority, TransmissionLane};
 pub use nym_topology::{provider_trait::TopologyProvider, NymTopology};
 pub use paths::StoragePaths;
 pub use socks5_client::Socks5MixnetClient;
@@ -0,0 +1,269 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::mixnet::traits::MixnetMessageSender;
+use crate::mixnet::{AnonymousSenderTag, IncludedSurbs, MixnetClient, Recipient};
+use crate::{Error, Result};
+use futures::StreamExt;
+use nym_client_core::client::inbound_messages::InputMessage;
+use nym_sphinx::receiver::ReconstructedMessage;
+use std::future::Future;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+use tokio::runtime::{Handle, Runtime};
+
+/// Blocking wrapper around the [`MixnetClient`] for integrations that can't (or don't want to)
+/// use async Rust, such as simple scripts or language bindings.
+///
+/// The client owns its own multi-threaded runtime that drives all of the background tasks.
+/// Note that, like any other blocking API, none of its methods can be called from within
+/// an async context, and attempting to connect from one results in
+/// [`Error::BlockingClientInAsyncContext`].
+///
+/// # Example
+///
+/// ```no_run
+/// use nym_sdk::mixnet::BlockingMixnetClient;
+/// use std::time::Duration;
+///
+/// let mut client = BlockingMixnetClient::connect_new().unwrap();
+/// let our_address = *client.nym_address();
+///
+/// client.send_plain_message(our_address, "hello there").unwrap();
+/// if let Ok(received) = client.recv_timeout(Duration::from_secs(30)) {
+///     println!("Received: {}", String::from_utf8_lossy(&received.message));
+/// }
+///
+/// client.disconnect();
+/// ```
+pub struct BlockingMixnetClient {
+    // note: the client must always be dropped before the runtime. it's only ever taken out
+    // during disconnection
+    client: Option<MixnetClient>,
+    runtime: Runtime,
+}
+
+impl BlockingMixnetClient {
+    /// Create a new client and connect to the mixnet using ephemeral in-memory keys that are
+    /// discarded at application close.
+    pub fn connect_new() -> Result<Self> {
+        Self::connect_with(MixnetClient::connect_new)
+    }
+
+    /// Connect to the mixnet using a custom connection procedure, for example one relying on the
+    /// [`MixnetClientBuilder`](crate::mixnet::MixnetClientBuilder), that is executed on the runtime
+    /// owned by this client.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nym_sdk::mixnet::{BlockingMixnetClient, MixnetClientBuilder};
+    ///
+    /// let client = BlockingMixnetClient::connect_with(|| async {
+    ///     MixnetClientBuilder::new_ephemeral()
+    ///         .build()?
+    ///         .connect_to_mixnet()
+    ///         .await
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn connect_with<F, Fut>(connect: F) -> Result<Self>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<MixnetClient>>,
+    {
+        // blocking on our own runtime would have panicked anyway, so fail early instead
+        if Handle::try_current().is_ok() {
+            return Err(Error::BlockingClientInAsyncContext);
+        }
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let client = runtime.block_on(connect())?;
+
+        Ok(BlockingMixnetClient {
+            client: Some(client),
+            runtime,
+        })
+    }
+
+    fn client(&self) -> &MixnetClient {
+        self.client
+            .as_ref()
+            .expect("the client is only taken during disconnection")
+    }
+
+    /// Get the nym address of this client.
+    pub fn nym_address(&self) -> &Recipient {
+        self.client().nym_address()
+    }
+
+    /// Get access to the underlying async client, for example to use functionalities not exposed
+    /// by this wrapper through [`BlockingMixnetClient::block_on`].
+    pub fn inner(&self) -> &MixnetClient {
+        self.client()
+    }
+
+    /// Run the provided future to completion on the runtime owned by this client.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Sends a [`InputMessage`] to the mixnet.
+    pub fn send(&self, message: InputMessage) -> Result<()> {
+        self.runtime.block_on(self.client().send(message))
+    }
+
+    /// Sends data to the supplied Nym address with the default surb behaviour.
+    pub fn send_plain_message<M>(&self, address: Recipient, message: M) -> Result<()>
+    where
+        M: AsRef<[u8]> + Send,
+    {
+        self.runtime
+            .block_on(self.client().send_plain_message(address, message))
+    }
+
+    /// Sends bytes to the supplied Nym address with the specified reply-SURBs behaviour.
+    pub fn send_message<M>(
+        &self,
+        address: Recipient,
+        message: M,
+        surbs: IncludedSurbs,
+    ) -> Result<()>
+    where
+        M: AsRef<[u8]> + Send,
+    {
+        self.runtime
+            .block_on(self.client().send_message(address, message, surbs))
+    }
+
+    /// Sends reply data to the supplied anonymous recipient.
+    pub fn send_reply<M>(&self, recipient_tag: AnonymousSenderTag, message: M) -> Result<()>
+    where
+        M: AsRef<[u8]> + Send,
+    {
+        self.runtime
+            .block_on(self.client().send_reply(recipient_tag, message))
+    }
+
+    /// Acknowledge the received message has been fully processed so that it could be removed
+    /// from the persistent inbox. It has no effect unless the client storage has the inbox enabled.
+    pub fn ack(&self, message: &ReconstructedMessage) -> Result<()> {
+        self.client().ack(message)
+    }
+
+    /// Blocks until the next message is received from the mixnet.
+    /// Returns `None` if the client has been shut down.
+    pub fn recv(&mut self) -> Option<ReconstructedMessage> {
+        let client = self
+            .client
+            .as_mut()
+            .expect("the client is only taken during disconnection");
+        self.runtime.block_on(client.next())
+    }
+
+    /// Blocks until the next message is received from the mixnet or until the timeout elapses.
+    pub fn recv_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<ReconstructedMessage, RecvTimeoutError> {
+        let client = self
+            .client
+            .as_mut()
+            .expect("the client is only taken during disconnection");
+        match self
+            .runtime
+            .block_on(tokio::time::timeout(timeout, client.next()))
+        {
+            Ok(Some(message)) => Ok(message),
+            Ok(None) => Err(RecvTimeoutError::Disconnected),
+            Err(_elapsed) => Err(RecvTimeoutError::Timeout),
+        }
+    }
+
+    /// Returns an iterator that blocks waiting for the next received message.
+    /// The iterator finishes once the client has been shut down.
+    pub fn messages(&mut self) -> Messages<'_> {
+        Messages { client: self }
+    }
+
+    /// Disconnect from the mixnet and wait for all the background tasks to finish.
+    pub fn disconnect(mut self) {
+        self.shutdown()
+    }
+
+    fn shutdown(&mut self) {
+        if let Some(client) = self.client.take() {
+            self.runtime.block_on(client.disconnect())
+        }
+    }
+}
+
+impl Drop for BlockingMixnetClient {
+    fn drop(&mut self) {
+        self.shutdown()
+    }
+}
+
+/// Blocking iterator over messages received by the [`BlockingMixnetClient`].
+pub struct Messages<'a> {
+    client: &'a mut BlockingMixnetClient,
+}
+
+impl Iterator for Messages<'_> {
+    type Item = ReconstructedMessage;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.client.recv()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn refuses_to_run_inside_an_async_runtime() {
+        let res = BlockingMixnetClient::connect_with(|| async {
+            Err(Error::new_unsupported(
+                "the connection should have never been attempted",
+            ))
+        });
+        assert!(matches!(res, Err(Error::BlockingClientInAsyncContext)));
+        assert!(matches!(
+            BlockingMixnetClient::connect_new(),
+            Err(Error::BlockingClientInAsyncContext)
+        ));
+    }
+
+    #[test]
+    fn failed_connection_shuts_down_the_runtime() {
+        let background_task = Arc::new(());
+        let task_handle = Arc::clone(&background_task);
+
+        let res = BlockingMixnetClient::connect_with(|| async move {
+            // some of the client tasks might have already been started
+            tokio::spawn(async move {
+                let _handle = task_handle;
+                std::future::pending::<()>().await
+            });
+            Err(Error::new_unsupported("testing"))
+        });
+        assert!(matches!(res, Err(Error::Unsupported { .. })));
+
+        // the runtime has been dropped alongside all of its tasks
+        assert_eq!(Arc::strong_count(&background_task), 1);
+    }
+
+    #[test]
+    #[ignore = "requires access to the mainnet"]
+    fn connects_and_disconnects_cleanly() {
+        let client = BlockingMixnetClient::connect_new().unwrap();
+        let our_address = *client.nym_address();
+        client.send_plain_message(our_address, "hello").unwrap();
+
+        client.disconnect();
+    }
+}
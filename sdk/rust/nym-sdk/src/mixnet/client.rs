@@ -4,7 +4,10 @@
 use super::{connection_state::BuilderState, Config, StoragePaths};
 use crate::bandwidth::BandwidthAcquireClient;
 use crate::mixnet::socks5_client::Socks5MixnetClient;
-use crate::mixnet::{CredentialStorage, MixnetClient, Recipient};
+use crate::mixnet::{
+    CredentialStorage, GatewayThroughputTestConfig, MixnetClient, Recipient, RoutingFilter,
+    ThroughputTestResult,
+};
 use crate::GatewayTransceiver;
 use crate::NymNetworkDetails;
 use crate::{Error, Result};
@@ -26,13 +29,14 @@ use nym_client_core::client::{
 use nym_client_core::config::DebugConfig;
 use nym_client_core::error::ClientCoreError;
 use nym_client_core::init::helpers::current_gateways;
-use nym_client_core::init::setup_gateway;
+use nym_client_core::init::selector::{CustomGatewaySelector, GatewaySelector};
 use nym_client_core::init::types::{GatewaySelectionSpecification, GatewaySetup};
+use nym_client_core::init::{registered_gateway_with_allowance, setup_gateway};
 use nym_credentials_interface::TicketType;
 use nym_socks5_client_core::config::Socks5;
 use nym_task::manager::TaskStatus;
 use nym_task::{TaskClient, TaskHandle};
-use nym_topology::provider_trait::TopologyProvider;
+use nym_topology::provider_trait::{HardcodedTopologyProvider, TopologyProvider};
 use nym_validator_client::{nyxd, QueryHttpRpcNyxdClient, UserAgent};
 use rand::rngs::OsRng;
 use std::path::Path;
@@ -51,10 +55,13 @@ pub struct MixnetClientBuilder<S: MixnetClientStorage = Ephemeral> {
 
     wait_for_gateway: bool,
     custom_topology_provider: Option<Box<dyn TopologyProvider + Send + Sync>>,
+    routing_filter: Option<RoutingFilter>,
     custom_gateway_transceiver: Option<Box<dyn GatewayTransceiver + Send + Sync>>,
     custom_shutdown: Option<TaskClient>,
+    custom_gateway_selector: Option<CustomGatewaySelector>,
     force_tls: bool,
     user_agent: Option<UserAgent>,
+    gateway_throughput_test: Option<GatewayThroughputTestConfig>,
 
     // TODO: incorporate it properly into `MixnetClientStorage` (I will need it in wasm anyway)
     gateway_endpoint_config_path: Option<PathBuf>,
@@ -86,14 +93,17 @@ impl MixnetClientBuilder<OnDiskPersistent> {
             socks5_config: None,
             wait_for_gateway: false,
             custom_topology_provider: None,
+            routing_filter: None,
             storage: storage_paths
                 .initialise_default_persistent_storage()
                 .await?,
             gateway_endpoint_config_path: None,
             custom_shutdown: None,
             custom_gateway_transceiver: None,
+            custom_gateway_selector: None,
             force_tls: false,
             user_agent: None,
+            gateway_throughput_test: None,
         })
     }
 }
@@ -102,7 +112,9 @@ impl<S> MixnetClientBuilder<S>
 where
     S: MixnetClientStorage + 'static,
     S::ReplyStore: Send + Sync,
-    S::GatewaysDetailsStore: Sync,
+    S::GatewaysDetailsStore: Send + Sync,
+    S::InboxStore: Send + Sync,
+    S::OutboxStore: Send + Sync,
     <S::ReplyStore as ReplyStorageBackend>::StorageError: Sync + Send,
     <S::CredentialStore as CredentialStorage>::StorageError: Send + Sync,
     <S::KeyStore as KeyStore>::StorageError: Send + Sync,
@@ -117,10 +129,13 @@ where
             socks5_config: None,
             wait_for_gateway: false,
             custom_topology_provider: None,
+            routing_filter: None,
             custom_gateway_transceiver: None,
             custom_shutdown: None,
+            custom_gateway_selector: None,
             force_tls: false,
             user_agent: None,
+            gateway_throughput_test: None,
             gateway_endpoint_config_path: None,
             storage,
         }
@@ -135,10 +150,13 @@ where
             socks5_config: self.socks5_config,
             wait_for_gateway: self.wait_for_gateway,
             custom_topology_provider: self.custom_topology_provider,
+            routing_filter: self.routing_filter,
             custom_gateway_transceiver: self.custom_gateway_transceiver,
             custom_shutdown: self.custom_shutdown,
+            custom_gateway_selector: self.custom_gateway_selector,
             force_tls: self.force_tls,
             user_agent: self.user_agent,
+            gateway_throughput_test: self.gateway_throughput_test,
             gateway_endpoint_config_path: self.gateway_endpoint_config_path,
             storage,
         }
@@ -174,6 +192,17 @@ where
         self
     }
 
+    /// Use the provided strategy for choosing a new gateway to register with
+    /// if no specific gateway has been requested.
+    #[must_use]
+    pub fn gateway_selector<G>(mut self, selector: G) -> Self
+    where
+        G: GatewaySelector + Send + Sync + 'static,
+    {
+        self.custom_gateway_selector = Some(CustomGatewaySelector::new(selector));
+        self
+    }
+
     /// Enable paid coconut bandwidth credentials mode.
     #[must_use]
     pub fn enable_credentials_mode(mut self) -> Self {
@@ -212,6 +241,26 @@ where
         self
     }
 
+    /// Use a fixed network topology loaded from the provided file,
+    /// such as one exported with [`MixnetClient::export_current_topology`].
+    pub fn stored_topology<P: AsRef<Path>>(self, file: P) -> Result<Self> {
+        let file = file.as_ref();
+        let provider = HardcodedTopologyProvider::new_from_file(file).map_err(|source| {
+            ClientCoreError::CustomTopologyLoadFailure {
+                file_path: file.to_path_buf(),
+                source,
+            }
+        })?;
+        Ok(self.custom_topology_provider(Box::new(provider)))
+    }
+
+    /// Exclude the specified mix nodes from the routes of all packets sent by the client.
+    #[must_use]
+    pub fn routing_filter(mut self, routing_filter: RoutingFilter) -> Self {
+        self.routing_filter = Some(routing_filter);
+        self
+    }
+
     /// Use an externally managed shutdown mechanism.
     #[must_use]
     pub fn custom_shutdown(mut self, shutdown: TaskClient) -> Self {
@@ -232,6 +281,14 @@ where
         self
     }
 
+    /// Measure the throughput of the connection to the gateway before starting any of the client
+    /// tasks. The result is available via [`MixnetClient::gateway_throughput`].
+    #[must_use]
+    pub fn gateway_throughput_test(mut self, config: GatewayThroughputTestConfig) -> Self {
+        self.gateway_throughput_test = Some(config);
+        self
+    }
+
     /// Use custom mixnet sender that might not be the default websocket gateway connection.
     /// only for advanced use
     #[must_use]
@@ -256,10 +313,13 @@ where
 
         client.custom_gateway_transceiver = self.custom_gateway_transceiver;
         client.custom_topology_provider = self.custom_topology_provider;
+        client.routing_filter = self.routing_filter;
         client.custom_shutdown = self.custom_shutdown;
         client.wait_for_gateway = self.wait_for_gateway;
         client.force_tls = self.force_tls;
+        client.custom_gateway_selector = self.custom_gateway_selector;
         client.user_agent = self.user_agent;
+        client.gateway_throughput_test = self.gateway_throughput_test;
 
         Ok(client)
     }
@@ -296,6 +356,9 @@ where
     /// Alternative provider of network topology used for constructing sphinx packets.
     custom_topology_provider: Option<Box<dyn TopologyProvider + Send + Sync>>,
 
+    /// Constraints on the mix nodes used for constructing packet routes.
+    routing_filter: Option<RoutingFilter>,
+
     /// advanced usage of custom gateways
     custom_gateway_transceiver: Option<Box<dyn GatewayTransceiver + Send + Sync>>,
 
@@ -305,17 +368,25 @@ where
     /// Force the client to connect using wss protocol with the gateway.
     force_tls: bool,
 
+    /// Custom strategy of choosing a new gateway.
+    custom_gateway_selector: Option<CustomGatewaySelector>,
+
     /// Allows passing an externally controlled shutdown handle.
     custom_shutdown: Option<TaskClient>,
 
     user_agent: Option<UserAgent>,
+
+    /// If specified, the throughput of the gateway connection is measured before starting the client.
+    gateway_throughput_test: Option<GatewayThroughputTestConfig>,
 }
 
 impl<S> DisconnectedMixnetClient<S>
 where
     S: MixnetClientStorage + 'static,
     S::ReplyStore: Send + Sync,
-    S::GatewaysDetailsStore: Sync,
+    S::GatewaysDetailsStore: Send + Sync,
+    S::InboxStore: Send + Sync,
+    S::OutboxStore: Send + Sync,
     <S::ReplyStore as ReplyStorageBackend>::StorageError: Sync + Send,
     <S::CredentialStore as CredentialStorage>::StorageError: Send + Sync,
     <S::KeyStore as KeyStore>::StorageError: Send + Sync,
@@ -353,11 +424,14 @@ where
             dkg_query_client,
             storage,
             custom_topology_provider: None,
+            routing_filter: None,
             custom_gateway_transceiver: None,
             wait_for_gateway: false,
             force_tls: false,
+            custom_gateway_selector: None,
             custom_shutdown: None,
             user_agent: None,
+            gateway_throughput_test: None,
         })
     }
 
@@ -443,11 +517,20 @@ where
     async fn new_gateway_setup(&self) -> Result<GatewaySetup, ClientCoreError> {
         let nym_api_endpoints = self.get_api_endpoints();
 
-        let selection_spec = GatewaySelectionSpecification::new(
-            self.config.user_chosen_gateway.clone(),
-            None,
-            self.force_tls,
-        );
+        let selection_spec = match (
+            &self.config.user_chosen_gateway,
+            &self.custom_gateway_selector,
+        ) {
+            (None, Some(selector)) => GatewaySelectionSpecification::RemoteWithSelector {
+                must_use_tls: self.force_tls,
+                selector: selector.clone(),
+            },
+            _ => GatewaySelectionSpecification::new(
+                self.config.user_chosen_gateway.clone(),
+                None,
+                self.force_tls,
+            ),
+        };
 
         let user_agent = self.user_agent.clone();
 
@@ -503,8 +586,16 @@ where
             (Some(_), _) => self.new_gateway_setup().await?,
             // When no user-chosen gateway exists but there's an active gateway.
             (None, Some(_)) => GatewaySetup::MustLoad { gateway_id: None },
-            // When there's no user-chosen gateway and no active gateway.
-            (None, None) => self.new_gateway_setup().await?,
+            // When there's no user-chosen gateway and no active gateway, prefer any of the
+            // previously registered ones we still have bandwidth with, before registering a new one.
+            (None, None) => {
+                match registered_gateway_with_allowance(self.storage.gateway_details_store(), &[])
+                    .await?
+                {
+                    Some(setup) => setup,
+                    None => self.new_gateway_setup().await?,
+                }
+            }
         };
 
         // this will perform necessary key and details load and optional store
@@ -544,6 +635,11 @@ where
                     source: Box::new(e),
                 })?
                 .identity_keypair()
+                .ok_or_else(|| {
+                    Error::new_unsupported(
+                        "acquiring bandwidth with an externally managed identity key",
+                    )
+                })?
                 .private_key()
                 .to_bytes(),
         );
@@ -558,7 +654,9 @@ where
         )
     }
 
-    async fn connect_to_mixnet_common(mut self) -> Result<(BaseClient, Recipient)> {
+    async fn connect_to_mixnet_common(
+        mut self,
+    ) -> Result<(BaseClient, Recipient, Option<ThroughputTestResult>)> {
         self.setup_client_keys().await?;
         self.setup_gateway().await?;
 
@@ -582,6 +680,10 @@ where
             base_builder = base_builder.with_topology_provider(topology_provider);
         }
 
+        if let Some(routing_filter) = self.routing_filter {
+            base_builder = base_builder.with_routing_filter(routing_filter);
+        }
+
         if let Some(custom_shutdown) = self.custom_shutdown {
             base_builder = base_builder.with_shutdown(custom_shutdown)
         }
@@ -590,11 +692,18 @@ where
             base_builder = base_builder.with_gateway_transceiver(gateway_transceiver);
         }
 
-        let started_client = base_builder.start_base().await?;
+        let (started_client, gateway_throughput) = match self.gateway_throughput_test {
+            Some(test_config) => {
+                let mut prepared = base_builder.prepare().await?;
+                let throughput = prepared.run_gateway_throughput_test(test_config).await?;
+                (prepared.start().await?, Some(throughput))
+            }
+            None => (base_builder.start_base().await?, None),
+        };
         self.state = BuilderState::Registered {};
         let nym_address = started_client.address;
 
-        Ok((started_client, nym_address))
+        Ok((started_client, nym_address, gateway_throughput))
     }
 
     /// Connect the client to the mixnet via SOCKS5. A SOCKS5 configuration must be specified
@@ -628,14 +737,14 @@ where
             .ok_or(Error::Socks5Config { set: false })?;
         let debug_config = self.config.debug_config;
         let packet_type = self.config.debug_config.traffic.packet_type;
-        let (mut started_client, nym_address) = self.connect_to_mixnet_common().await?;
+        let (mut started_client, nym_address, _) = self.connect_to_mixnet_common().await?;
         let (socks5_status_tx, mut socks5_status_rx) = mpsc::channel(128);
 
         let client_input = started_client.client_input.register_producer();
         let client_output = started_client.client_output.register_consumer();
         let client_state = started_client.client_state;
 
-        nym_socks5_client_core::NymClient::<S>::start_socks5_listener(
+        let activity_log = nym_socks5_client_core::NymClient::<S>::start_socks5_listener(
             &socks5_config,
             debug_config,
             client_input,
@@ -676,6 +785,7 @@ where
             client_state,
             task_handle: started_client.task_handle,
             socks5_config,
+            activity_log,
         })
     }
 
@@ -703,7 +813,8 @@ where
         if self.socks5_config.is_some() {
             return Err(Error::Socks5Config { set: true });
         }
-        let (mut started_client, nym_address) = self.connect_to_mixnet_common().await?;
+        let (mut started_client, nym_address, gateway_throughput) =
+            self.connect_to_mixnet_common().await?;
         let client_input = started_client.client_input.register_producer();
         let mut client_output = started_client.client_output.register_consumer();
         let client_state: nym_client_core::client::base_client::ClientState =
@@ -721,7 +832,8 @@ where
             reconstructed_receiver,
             started_client.task_handle,
             None,
-        ))
+        )
+        .with_gateway_throughput(gateway_throughput))
     }
 }
 
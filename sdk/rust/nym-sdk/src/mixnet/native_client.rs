@@ -1,23 +1,35 @@
 use crate::mixnet::client::MixnetClientBuilder;
 use crate::mixnet::traits::MixnetMessageSender;
+use crate::mixnet::{AnonymousSenderTag, ThroughputTestResult};
 use crate::{Error, Result};
 use async_trait::async_trait;
 use futures::{ready, Stream, StreamExt};
-use log::error;
+use log::{debug, error};
 use nym_client_core::client::base_client::GatewayConnection;
 use nym_client_core::client::{
     base_client::{ClientInput, ClientOutput, ClientState},
+    control::ClientControl,
+    delivery::DeliveryStatusReceiver,
+    diagnostics::ClientDiagnostics,
+    drain::{DrainConfig, DrainSummary},
+    events::ClientEvents,
     inbound_messages::InputMessage,
+    inbox::InboxMessageId,
+    key_manager::ManagedKeys,
     received_buffer::ReconstructedMessagesReceiver,
+    replies::reply_storage::{SurbHandover, SyncMergeSummary},
+    roaming::NetworkChangeNotifier,
+    topology_control::{RoutabilityListener, RoutingFilter},
 };
 use nym_crypto::asymmetric::identity;
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::{params::PacketType, receiver::ReconstructedMessage};
 use nym_task::{
-    connections::{ConnectionCommandSender, LaneQueueLengths},
+    connections::{ClientStats, ConnectionCommandSender, LaneQueueLengths},
     TaskHandle,
 };
 use nym_topology::NymTopology;
+use std::path::Path;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -27,7 +39,7 @@ pub struct MixnetClient {
     /// The nym address of this connected client.
     pub(crate) nym_address: Recipient,
 
-    pub(crate) identity_keys: Arc<identity::KeyPair>,
+    pub(crate) identity_keys: Arc<dyn identity::IdentitySigner>,
 
     /// Input to the client from the users perspective. This can be either data to send or control
     /// messages.
@@ -49,6 +61,9 @@ pub struct MixnetClient {
     pub(crate) task_handle: TaskHandle,
     pub(crate) packet_type: Option<PacketType>,
 
+    /// Result of the gateway throughput test performed before the client has started, if requested.
+    pub(crate) gateway_throughput: Option<ThroughputTestResult>,
+
     // internal state used for the `Stream` implementation
     _buffered: Vec<ReconstructedMessage>,
 }
@@ -57,7 +72,7 @@ impl MixnetClient {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         nym_address: Recipient,
-        identity_keys: Arc<identity::KeyPair>,
+        identity_keys: Arc<dyn identity::IdentitySigner>,
         client_input: ClientInput,
         client_output: ClientOutput,
         client_state: ClientState,
@@ -74,10 +89,19 @@ impl MixnetClient {
             reconstructed_receiver,
             task_handle,
             packet_type,
+            gateway_throughput: None,
             _buffered: Vec::new(),
         }
     }
 
+    pub(crate) fn with_gateway_throughput(
+        mut self,
+        gateway_throughput: Option<ThroughputTestResult>,
+    ) -> Self {
+        self.gateway_throughput = gateway_throughput;
+        self
+    }
+
     /// Create a new client and connect to the mixnet using ephemeral in-memory keys that are
     /// discarded at application close.
     ///
@@ -99,26 +123,60 @@ impl MixnetClient {
             .await
     }
 
+    /// Result of the gateway throughput test, if it was requested via
+    /// [`MixnetClientBuilder::gateway_throughput_test`].
+    pub fn gateway_throughput(&self) -> Option<ThroughputTestResult> {
+        self.gateway_throughput
+    }
+
     /// Get the nym address for this client, if it is available. The nym address is composed of the
     /// client identity, the client encryption key, and the gateway identity.
+    /// Note that it's the address the client has been started with, which changes if its
+    /// encryption keys get rotated (see [`MixnetClient::managed_keys`]).
     pub fn nym_address(&self) -> &Recipient {
         &self.nym_address
     }
 
     /// Sign a message with the client's private identity key.
-    pub fn sign(&self, data: &[u8]) -> identity::Signature {
-        self.identity_keys.private_key().sign(data)
+    /// It might fail if the key is held by an external signer.
+    pub fn sign(&self, data: &[u8]) -> Result<identity::Signature> {
+        Ok(self.identity_keys.try_sign(data)?)
     }
 
     /// Sign a message with the client's private identity key and return it as a base58 encoded
-    /// signature.
-    pub fn sign_text(&self, text: &str) -> String {
-        self.identity_keys.private_key().sign_text(text)
+    /// signature. It might fail if the key is held by an external signer.
+    pub fn sign_text(&self, text: &str) -> Result<String> {
+        Ok(self.identity_keys.try_sign_text(text)?)
     }
 
     /// Get gateway connection information, like the file descriptor of the WebSocket
     pub fn gateway_connection(&self) -> GatewayConnection {
-        self.client_state.gateway_connection
+        self.client_state.gateway_connection.clone()
+    }
+
+    /// Get a handle for informing the client about changes to the local network, such as switching
+    /// to a different Wi-Fi, so that it could immediately re-establish its gateway connection.
+    /// This is primarily meant for platforms providing native connectivity notifications.
+    pub fn network_change_notifier(&self) -> NetworkChangeNotifier {
+        self.client_state.network_change_notifier.clone()
+    }
+
+    /// Get a handle for adjusting some of the client parameters, such as the average packet delay
+    /// or the cover traffic rate, without having to restart the client.
+    pub fn client_control(&self) -> ClientControl {
+        self.client_state.client_control.clone()
+    }
+
+    /// Get a handle for running the health checks of this client, such as checking whether
+    /// its gateway is reachable or whether messages can make it through the mixnet.
+    pub fn diagnostics(&self) -> ClientDiagnostics {
+        self.client_state.diagnostics.clone()
+    }
+
+    /// Get a handle to the keys used by this client, which allows rotating its encryption keys
+    /// without having to restart it.
+    pub fn managed_keys(&self) -> ManagedKeys {
+        self.client_state.managed_keys.clone()
     }
 
     /// Get a shallow clone of [`MixnetClientSender`]. Useful if you want split the send and
@@ -130,6 +188,87 @@ impl MixnetClient {
         }
     }
 
+    /// Replies to the anonymous sender with the provided data, regardless of its size.
+    ///
+    /// The reply is split into as many packets as needed and sent using the reply SURBs we hold
+    /// for that sender. If there are not enough of them (taking into account the SURBs that must
+    /// be kept in reserve and the ones needed for asking for more), the remainder gets buffered
+    /// and additional SURBs are automatically requested, with the rest of the reply being sent
+    /// as soon as they arrive.
+    ///
+    /// Unlike [`MixnetMessageSender::send_reply`], this returns an error if we have never
+    /// received any SURBs from the sender as the reply could never be delivered.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nym_sdk::mixnet;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = mixnet::MixnetClient::connect_new().await.unwrap();
+    ///     // note: the tag is something you would have received from a remote client sending you surbs!
+    ///     let tag = mixnet::AnonymousSenderTag::try_from_base58_string("foobar").unwrap();
+    ///     client.reply(tag, vec![42u8; 100_000]).await.unwrap();
+    /// }
+    /// ```
+    pub async fn reply<M>(&self, sender_tag: AnonymousSenderTag, data: M) -> Result<()>
+    where
+        M: AsRef<[u8]> + Send,
+    {
+        let Some(available) = self
+            .client_state
+            .reply_controller_sender
+            .available_reply_surbs(sender_tag)
+            .await
+        else {
+            return Err(Error::NoReplySurbs { sender_tag });
+        };
+        debug!("replying to {sender_tag} with {available} reply surbs currently available");
+
+        self.send_reply(sender_tag, data).await
+    }
+
+    /// Exports all the changes made to the reply storage (sent reply keys, used sender tags and,
+    /// depending on the `surb_handover`, received reply SURBs) since the previous export.
+    ///
+    /// The result is encrypted with a key derived from our identity, so it could be transferred
+    /// through any channel to another device running the same identity and imported there with
+    /// [`Self::import_reply_storage_delta`]. Note that the handed over reply SURBs are removed
+    /// from this client, so the delta must be imported, or they are going to be lost.
+    pub async fn export_reply_storage_delta(&self, surb_handover: SurbHandover) -> Result<Vec<u8>> {
+        self.client_state
+            .reply_controller_sender
+            .export_reply_storage_delta(surb_handover)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Imports the reply storage delta exported by another device running the same identity,
+    /// so that replies to its correspondents could be handled by this client.
+    pub async fn import_reply_storage_delta(&self, delta: Vec<u8>) -> Result<SyncMergeSummary> {
+        self.client_state
+            .reply_controller_sender
+            .import_reply_storage_delta(delta)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Acknowledge the received message has been fully processed so that it could be removed
+    /// from the persistent inbox. It has no effect unless the client storage has the inbox enabled.
+    pub fn ack(&self, message: &ReconstructedMessage) -> Result<()> {
+        self.client_output
+            .ack(InboxMessageId::for_message(message))
+            .map_err(Into::into)
+    }
+
+    /// Get the channel of the delivery statuses of the messages sent with a
+    /// [`DeliveryToken`](crate::mixnet::DeliveryToken) attached.
+    /// Only the most recently obtained channel receives the statuses.
+    pub fn delivery_statuses(&mut self) -> DeliveryStatusReceiver {
+        self.client_output.register_delivery_status_receiver()
+    }
+
     /// Get a shallow clone of [`ConnectionCommandSender`]. This is useful if you want to e.g
     /// explicitly close a transmission lane that is still sending data even though it should
     /// cancel.
@@ -143,6 +282,12 @@ impl MixnetClient {
         self.client_state.shared_lane_queue_lengths.clone()
     }
 
+    /// Get the current queue lengths, bandwidth usage and rate limits of all the client connections.
+    /// The rate limits themselves can be adjusted via [`LaneQueueLengths::set_rate_limit`].
+    pub fn client_stats(&self) -> ClientStats {
+        self.client_state.shared_lane_queue_lengths.client_stats()
+    }
+
     /// Change the network topology used by this client for constructing sphinx packets into the
     /// provided one.
     pub async fn manually_overwrite_topology(&self, new_topology: NymTopology) {
@@ -152,11 +297,42 @@ impl MixnetClient {
             .await
     }
 
+    /// Change the constraints on the mix nodes used for constructing the routes of all subsequent packets.
+    pub async fn set_routing_filter(&self, routing_filter: RoutingFilter) {
+        self.client_state
+            .topology_accessor
+            .set_routing_filter(routing_filter)
+            .await
+    }
+
     /// Gets the value of the currently used network topology.
     pub async fn read_current_topology(&self) -> Option<NymTopology> {
         self.client_state.topology_accessor.current_topology().await
     }
 
+    /// Saves the currently used network topology to the provided file so that the same network
+    /// view could be replayed in later runs via [`MixnetClientBuilder::stored_topology`].
+    pub async fn export_current_topology<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        Ok(self
+            .client_state
+            .topology_accessor
+            .export_current_topology(path)
+            .await?)
+    }
+
+    /// Subscribe to the changes of the routability of the network topology used by this client.
+    /// If the client has been started with `offline_tolerant_startup` enabled, this can be used
+    /// for finding out when its queued messages are actually going to start being sent.
+    pub fn topology_routability_changes(&self) -> RoutabilityListener {
+        self.client_state.topology_accessor.routability_changes()
+    }
+
+    /// Subscribe to the typed events published by the client, such as the gateway getting disconnected
+    /// or the reply SURBs running out, for example for driving the state of the UI.
+    pub fn events(&self) -> ClientEvents {
+        self.client_state.subscribe_events()
+    }
+
     /// Restore default topology refreshing behaviour of this client.
     pub fn restore_automatic_topology_refreshing(&self) {
         self.client_state.topology_accessor.release_manual_control()
@@ -190,6 +366,15 @@ impl MixnetClient {
         // note: it's important to take ownership of the struct as if the shutdown is `TaskHandle::External`,
         // it must be dropped to finalize the shutdown
     }
+
+    /// Disconnect from the mixnet after attempting to deliver all the already sent messages.
+    /// No new messages are accepted once this is called. Returns the summary of the messages
+    /// that have not been delivered before the timeout specified in the [`DrainConfig`].
+    pub async fn disconnect_gracefully(self, config: DrainConfig) -> DrainSummary {
+        let summary = self.client_state.drain.drain(config).await;
+        self.disconnect().await;
+        summary
+    }
 }
 
 #[derive(Clone)]
@@ -125,6 +125,22 @@ impl StoragePaths {
         .await?)
     }
 
+    /// Instantiates reply surb storage backend whose records are encrypted with a key derived
+    /// from the provided passphrase, for example one obtained via
+    /// [`ReplyStoragePassphrase::derive_from_identity`](fs_backend::ReplyStoragePassphrase::derive_from_identity).
+    pub async fn encrypted_persistent_fs_reply_backend(
+        &self,
+        surb_config: &config::ReplySurbs,
+        passphrase: &fs_backend::ReplyStoragePassphrase,
+    ) -> Result<fs_backend::Backend, Error> {
+        Ok(non_wasm_helpers::setup_encrypted_fs_reply_surb_backend(
+            &self.reply_surb_database_path,
+            surb_config,
+            passphrase,
+        )
+        .await?)
+    }
+
     /// Instantiates default persistent key storage.
     pub fn on_disk_key_storage_spec(&self) -> OnDiskKeys {
         OnDiskKeys::new(self.client_keys_paths())
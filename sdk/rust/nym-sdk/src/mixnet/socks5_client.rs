@@ -1,7 +1,12 @@
 use nym_client_core::client::base_client::ClientState;
+use nym_client_core::client::diagnostics::ClientDiagnostics;
 use nym_socks5_client_core::config::Socks5;
+use nym_socks5_client_core::socks::activity::{ConnectionActivityLog, ConnectionRecord};
 use nym_sphinx::addressing::clients::Recipient;
-use nym_task::{connections::LaneQueueLengths, TaskHandle};
+use nym_task::{
+    connections::{ClientStats, LaneQueueLengths},
+    TaskHandle,
+};
 
 use nym_topology::NymTopology;
 
@@ -22,6 +27,9 @@ pub struct Socks5MixnetClient {
 
     /// SOCKS5 configuration parameters.
     pub(crate) socks5_config: Socks5,
+
+    /// Log of the recently proxied connections.
+    pub(crate) activity_log: ConnectionActivityLog,
 }
 
 impl Socks5MixnetClient {
@@ -65,6 +73,29 @@ impl Socks5MixnetClient {
         self.client_state.shared_lane_queue_lengths.clone()
     }
 
+    /// Get the current queue lengths, bandwidth usage and rate limits of all the client connections.
+    /// The rate limits themselves can be adjusted via [`LaneQueueLengths::set_rate_limit`].
+    pub fn client_stats(&self) -> ClientStats {
+        self.client_state.shared_lane_queue_lengths.client_stats()
+    }
+
+    /// Get the recently proxied connections, starting with the most recent one. The log is only
+    /// populated if it has been enabled, either in [`Socks5::activity_log`] or via [`Self::activity_log`].
+    pub fn recent_connections(&self) -> Vec<ConnectionRecord> {
+        self.activity_log.recent_connections()
+    }
+
+    /// Get a handle for toggling, clearing or changing the retention of the connection activity log.
+    pub fn activity_log(&self) -> ConnectionActivityLog {
+        self.activity_log.clone()
+    }
+
+    /// Get a handle for running the health checks of this client, such as checking whether
+    /// its gateway is reachable or whether messages can make it through the mixnet.
+    pub fn diagnostics(&self) -> ClientDiagnostics {
+        self.client_state.diagnostics.clone()
+    }
+
     /// Change the network topology used by this client for constructing sphinx packets into the
     /// provided one.
     pub async fn manually_overwrite_topology(&self, new_topology: NymTopology) {
@@ -88,6 +88,48 @@ pub trait MixnetMessageSender {
         self.send(input_msg).await
     }
 
+    /// Sends the same bytes to all of the supplied Nym addresses. The message is split and encrypted
+    /// separately for each of the recipients, and each of them receives its own set of reply-SURBs.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nym_sdk::mixnet::{self, MixnetMessageSender};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let recipients = ["foo", "bar"]
+    ///         .iter()
+    ///         .map(|address| mixnet::Recipient::try_from_base58_string(address).unwrap())
+    ///         .collect();
+    ///     let mut client = mixnet::MixnetClient::connect_new().await.unwrap();
+    ///     let surbs = mixnet::IncludedSurbs::default();
+    ///     client.send_message_to_many(recipients, "hi all", surbs).await.unwrap();
+    /// }
+    /// ```
+    async fn send_message_to_many<M>(
+        &self,
+        addresses: Vec<Recipient>,
+        message: M,
+        surbs: IncludedSurbs,
+    ) -> Result<()>
+    where
+        M: AsRef<[u8]> + Send,
+    {
+        let reply_surbs = match surbs {
+            IncludedSurbs::Amount(surbs) => Some(surbs),
+            IncludedSurbs::ExposeSelfAddress => None,
+        };
+        let input_msg = InputMessage::new_multi_recipient(
+            addresses,
+            message.as_ref().to_vec(),
+            reply_surbs,
+            TransmissionLane::General,
+            self.packet_type(),
+        );
+        self.send(input_msg).await
+    }
+
     /// Sends reply data to the supplied anonymous recipient.
     ///
     /// # Example
@@ -25,7 +25,7 @@ pub(crate) struct Sign {
 }
 
 fn print_signed_contract_msg(
-    private_key: &identity::PrivateKey,
+    signer: &dyn identity::IdentitySigner,
     raw_msg: &str,
     output: OutputFormat,
 ) {
@@ -48,7 +48,13 @@ fn print_signed_contract_msg(
 
     // if this is a valid json, it MUST be a valid string
     let decoded_string = String::from_utf8(decoded.clone()).unwrap();
-    let signature = private_key.sign(&decoded).to_base58_string();
+    let signature = match signer.try_sign(&decoded) {
+        Ok(signature) => signature.to_base58_string(),
+        Err(err) => {
+            println!("failed to sign the message: {err}");
+            return;
+        }
+    };
 
     let sign_output = ConsoleSigningOutput::new(decoded_string, signature);
     println!("{}", output.format(&sign_output));
@@ -69,11 +75,7 @@ pub(crate) async fn execute(args: &Sign) -> Result<(), AuthenticatorError> {
         })
     })?;
 
-    print_signed_contract_msg(
-        identity_keypair.private_key(),
-        &args.contract_msg,
-        args.output,
-    );
+    print_signed_contract_msg(&identity_keypair, &args.contract_msg, args.output);
 
     Ok(())
 }
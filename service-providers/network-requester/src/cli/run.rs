@@ -5,6 +5,7 @@ use crate::cli::{try_load_current_config, version_check};
 use crate::{
     cli::{override_config, OverrideConfig},
     error::NetworkRequesterError,
+    request_filter::HostAllowList,
 };
 use clap::Args;
 use log::error;
@@ -64,7 +65,21 @@ pub(crate) async fn execute(args: &Run) -> Result<(), NetworkRequesterError> {
     }
 
     log::info!("Starting socks5 service provider");
+    let host_allowlist = &config.network_requester.host_allowlist;
+    let allowlist = if host_allowlist.is_empty() {
+        None
+    } else {
+        log::info!(
+            "restricting the requests to {} allowed hosts",
+            host_allowlist.len()
+        );
+        Some(HostAllowList::new(host_allowlist)?)
+    };
+
     let mut server = crate::core::NRServiceProviderBuilder::new(config);
+    if let Some(allowlist) = allowlist {
+        server = server.with_host_allowlist(allowlist)
+    }
     if let Some(custom_mixnet) = &args.common_args.custom_mixnet {
         server = server.with_stored_topology(custom_mixnet)?
     }
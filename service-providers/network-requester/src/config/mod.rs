@@ -216,6 +216,10 @@ pub struct NetworkRequester {
     /// Specifies the url for an upstream source of the exit policy used by this node.
     #[serde(deserialize_with = "de_maybe_stringified")]
     pub upstream_exit_policy_url: Option<Url>,
+
+    /// If not empty, restricts the requests to the listed hosts (and their subdomains)
+    /// on top of the exit policy, for example when acting as a personal exit for your own devices.
+    pub host_allowlist: Vec<String>,
 }
 
 impl Default for NetworkRequester {
@@ -228,6 +232,7 @@ impl Default for NetworkRequester {
                     .parse()
                     .expect("invalid default exit policy URL"),
             ),
+            host_allowlist: Vec::new(),
         }
     }
 }
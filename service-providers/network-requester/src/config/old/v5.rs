@@ -114,6 +114,7 @@ impl From<NetworkRequesterV5> for NetworkRequester {
             open_proxy: value.open_proxy,
             disable_poisson_rate: value.disable_poisson_rate,
             upstream_exit_policy_url: value.upstream_exit_policy_url,
+            host_allowlist: Vec::new(),
         }
     }
 }
@@ -88,6 +88,14 @@ disable_poisson_rate = {{ network_requester.disable_poisson_rate }}
 # Specifies the url for an upstream source of the exit policy used by this node.
 upstream_exit_policy_url = '{{ network_requester.upstream_exit_policy_url }}'
 
+# If not empty, restricts the requests to the listed hosts (and their subdomains)
+# on top of the exit policy, for example when acting as a personal exit for your own devices.
+host_allowlist = [
+    {{#each network_requester.host_allowlist }}
+        '{{this}}',
+    {{/each}}
+]
+
 ##### logging configuration options #####
 
 [logging]
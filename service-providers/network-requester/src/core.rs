@@ -4,7 +4,7 @@
 use crate::config::{BaseClientConfig, Config};
 use crate::error::NetworkRequesterError;
 use crate::reply::MixnetMessage;
-use crate::request_filter::RequestFilter;
+use crate::request_filter::{HostAllowList, RequestFilter};
 use crate::{reply, socks5};
 use async_trait::async_trait;
 use futures::channel::{mpsc, oneshot};
@@ -69,6 +69,7 @@ pub struct NRServiceProviderBuilder {
     custom_gateway_transceiver: Option<Box<dyn GatewayTransceiver + Send + Sync>>,
     shutdown: Option<TaskClient>,
     on_start: Option<oneshot::Sender<OnStartData>>,
+    allowlist: Option<HostAllowList>,
 }
 
 pub struct NRServiceProvider {
@@ -156,6 +157,7 @@ impl NRServiceProviderBuilder {
             custom_gateway_transceiver: None,
             shutdown: None,
             on_start: None,
+            allowlist: None,
         }
     }
 
@@ -207,6 +209,14 @@ impl NRServiceProviderBuilder {
         self
     }
 
+    /// Restricts the requests to the hosts present on the provided allowlist (on top of the exit policy).
+    /// The allowlist can be modified at runtime through any of its clones.
+    #[must_use]
+    pub fn with_host_allowlist(mut self, allowlist: HostAllowList) -> Self {
+        self.allowlist = Some(allowlist);
+        self
+    }
+
     #[must_use]
     // this is a false positive, this method is actually called when used as a library
     // but clippy complains about it when building the binary
@@ -277,7 +287,9 @@ impl NRServiceProviderBuilder {
             .await;
         });
 
-        let request_filter = RequestFilter::new(&self.config).await?;
+        let request_filter = RequestFilter::new(&self.config)
+            .await?
+            .with_allowlist(self.allowlist);
 
         let mut service_provider = NRServiceProvider {
             config: self.config,
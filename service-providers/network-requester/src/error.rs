@@ -70,6 +70,9 @@ pub enum NetworkRequesterError {
     #[error("can't setup an exit policy without any upstream urls")]
     NoUpstreamExitPolicy,
 
+    #[error("'{entry}' is not a valid allowlist entry - expected a domain name or an ip address")]
+    MalformedAllowlistEntry { entry: String },
+
     #[error(transparent)]
     ConfigUpgradeFailure(#[from] nym_client_core::config::ConfigUpgradeFailure),
 
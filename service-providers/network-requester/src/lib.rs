@@ -30,4 +30,4 @@ pub use nym_client_core::{
         types::{GatewaySelectionSpecification, GatewaySetup, InitResults, InitialisationResult},
     },
 };
-pub use request_filter::RequestFilter;
+pub use request_filter::{HostAllowList, RequestFilter};
@@ -12,7 +12,7 @@ use nym_socks5_requests::{
 use nym_sphinx::addressing::clients::Recipient;
 use nym_sphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nym_sphinx::params::PacketType;
-use nym_task::connections::TransmissionLane;
+use nym_task::connections::{MessagePriority, TransmissionLane};
 use std::fmt::{Debug, Formatter};
 
 /// Generic data this service provider will send back to the mixnet via its connected native client.
@@ -191,6 +191,9 @@ impl MixnetAddress {
                     recipient: *recipient,
                     data: message,
                     lane: TransmissionLane::ConnectionId(connection_id),
+                    priority: MessagePriority::Normal,
+                    delivery_token: None,
+                    latency_budget: None,
                     mix_hops: None,
                 }),
                 packet_type,
@@ -200,6 +203,8 @@ impl MixnetAddress {
                     recipient_tag: sender_tag,
                     data: message,
                     lane: TransmissionLane::ConnectionId(connection_id),
+                    priority: MessagePriority::Normal,
+                    delivery_token: None,
                 }),
                 packet_type,
             },
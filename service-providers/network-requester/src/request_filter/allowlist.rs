@@ -0,0 +1,234 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::error::NetworkRequesterError;
+use nym_socks5_requests::RemoteAddress;
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
+
+/// Additional, runtime-editable restriction on top of the exit policy.
+/// It's meant for embedded network requesters acting as a personal exit (e.g. for the user's own devices)
+/// where only a handful of hosts should be reachable regardless of how permissive the exit policy is.
+///
+/// An entry permits the host itself alongside all of its subdomains, i.e. `example.com` permits
+/// both `example.com:443` and `api.example.com:443`.
+#[derive(Clone, Debug, Default)]
+pub struct HostAllowList {
+    hosts: Arc<RwLock<BTreeSet<String>>>,
+}
+
+impl HostAllowList {
+    /// Creates the allowlist out of the provided hosts. Fails if any of them is malformed.
+    pub fn new<I, S>(hosts: I) -> Result<Self, NetworkRequesterError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Ok(HostAllowList {
+            hosts: Arc::new(RwLock::new(parse_hosts(hosts)?)),
+        })
+    }
+
+    /// Adds the host to the allowlist. Returns `false` if it was already present.
+    #[allow(unused)]
+    pub fn add_host(&self, host: &str) -> Result<bool, NetworkRequesterError> {
+        let host = parse_host(host)?;
+
+        #[allow(clippy::unwrap_used)]
+        Ok(self.hosts.write().unwrap().insert(host))
+    }
+
+    /// Removes the host from the allowlist. Returns `false` if it wasn't present.
+    #[allow(unused)]
+    pub fn remove_host(&self, host: &str) -> bool {
+        #[allow(clippy::unwrap_used)]
+        self.hosts.write().unwrap().remove(&normalise(host))
+    }
+
+    /// Replaces the whole content of the allowlist, for example after its source file got modified.
+    /// If any of the new hosts is malformed, the existing allowlist is left unchanged.
+    #[allow(unused)]
+    pub fn reload<I, S>(&self, hosts: I) -> Result<(), NetworkRequesterError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let hosts = parse_hosts(hosts)?;
+
+        #[allow(clippy::unwrap_used)]
+        let mut guard = self.hosts.write().unwrap();
+        *guard = hosts;
+        Ok(())
+    }
+
+    #[allow(unused)]
+    pub fn hosts(&self) -> Vec<String> {
+        #[allow(clippy::unwrap_used)]
+        self.hosts.read().unwrap().iter().cloned().collect()
+    }
+
+    pub fn allows(&self, remote: &RemoteAddress) -> bool {
+        let host = normalise(strip_port(remote));
+
+        #[allow(clippy::unwrap_used)]
+        let guard = self.hosts.read().unwrap();
+        guard.iter().any(|allowed| {
+            host == *allowed
+                || host
+                    .strip_suffix(allowed.as_str())
+                    .map(|prefix| prefix.ends_with('.'))
+                    .unwrap_or(false)
+        })
+    }
+}
+
+fn normalise(host: &str) -> String {
+    host.trim().trim_end_matches('.').to_ascii_lowercase()
+}
+
+fn parse_hosts<I, S>(hosts: I) -> Result<BTreeSet<String>, NetworkRequesterError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    hosts.into_iter().map(|h| parse_host(h.as_ref())).collect()
+}
+
+// an entry has to be either an ip address or a plain domain name,
+// i.e. without the scheme, the port, the path or any wildcards
+fn parse_host(host: &str) -> Result<String, NetworkRequesterError> {
+    let normalised = normalise(host);
+    if normalised.parse::<IpAddr>().is_ok() {
+        return Ok(normalised);
+    }
+
+    let is_valid_label = |label: &str| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+    if normalised.len() > 253 || !normalised.split('.').all(is_valid_label) {
+        return Err(NetworkRequesterError::MalformedAllowlistEntry {
+            entry: host.to_string(),
+        });
+    }
+
+    Ok(normalised)
+}
+
+// `RemoteAddress` is in the form of `host:port`, where the host might be a bracketed ipv6 address
+fn strip_port(remote: &str) -> &str {
+    if let Some(stripped) = remote.strip_prefix('[') {
+        return stripped.split(']').next().unwrap_or(stripped);
+    }
+    match remote.rsplit_once(':') {
+        // an unbracketed ipv6 address without a port
+        Some((host, _)) if host.contains(':') => remote,
+        Some((host, _)) => host,
+        None => remote,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn hosts_and_their_subdomains_are_allowed() {
+        let allowlist = HostAllowList::new(["Example.com.", "10.0.0.1", "::1"]).unwrap();
+
+        assert!(allowlist.allows(&"example.com:443".to_string()));
+        assert!(allowlist.allows(&"api.EXAMPLE.com:443".to_string()));
+        assert!(allowlist.allows(&"10.0.0.1:80".to_string()));
+        assert!(allowlist.allows(&"[::1]:80".to_string()));
+
+        assert!(!allowlist.allows(&"notexample.com:443".to_string()));
+        assert!(!allowlist.allows(&"example.com.evil.net:443".to_string()));
+        assert!(!allowlist.allows(&"10.0.0.2:80".to_string()));
+    }
+
+    #[test]
+    fn malformed_entries_are_rejected() {
+        for entry in [
+            "",
+            "https://example.com",
+            "example.com:443",
+            "example.com/path",
+            "*.example.com",
+            "exa mple.com",
+            "-example.com",
+            "example..com",
+        ] {
+            assert!(
+                matches!(
+                    HostAllowList::new([entry]),
+                    Err(NetworkRequesterError::MalformedAllowlistEntry { .. })
+                ),
+                "'{entry}' should have been rejected"
+            );
+        }
+
+        let allowlist = HostAllowList::new(["example.com"]).unwrap();
+        assert!(allowlist.add_host("example.com:443").is_err());
+        assert_eq!(allowlist.hosts(), vec!["example.com".to_string()]);
+    }
+
+    #[test]
+    fn reloading_replaces_the_entries() {
+        let allowlist = HostAllowList::new(["example.com", "nymtech.net"]).unwrap();
+        let shared = allowlist.clone();
+
+        allowlist.reload(["nymtech.net", "other.org"]).unwrap();
+        assert_eq!(shared.hosts(), vec!["nymtech.net", "other.org"]);
+        assert!(!shared.allows(&"example.com:443".to_string()));
+        assert!(shared.allows(&"other.org:443".to_string()));
+
+        // a single malformed entry invalidates the whole reload
+        assert!(allowlist.reload(["example.com", "not a host"]).is_err());
+        assert_eq!(shared.hosts(), vec!["nymtech.net", "other.org"]);
+    }
+
+    #[test]
+    fn reads_are_consistent_while_the_allowlist_is_edited() {
+        let allowlist = HostAllowList::new(["example.com"]).unwrap();
+        let done = Arc::new(AtomicBool::new(false));
+
+        let readers = (0..4)
+            .map(|_| {
+                let allowlist = allowlist.clone();
+                let done = done.clone();
+                std::thread::spawn(move || {
+                    while !done.load(Ordering::SeqCst) {
+                        // the hosts are swapped as a whole, so there's never an empty
+                        // or a partially updated allowlist in between
+                        let hosts = allowlist.hosts();
+                        assert!(
+                            hosts == ["example.com"] || hosts == ["nymtech.net"],
+                            "{hosts:?}"
+                        );
+                        assert!(!allowlist.allows(&"other.org:443".to_string()));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for i in 0..1000 {
+            let host = if i % 2 == 0 {
+                "nymtech.net"
+            } else {
+                "example.com"
+            };
+            allowlist.reload([host]).unwrap();
+        }
+        done.store(true, Ordering::SeqCst);
+
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(allowlist.hosts(), vec!["example.com"]);
+    }
+}
@@ -3,31 +3,55 @@
 
 use crate::config::Config;
 use crate::error::NetworkRequesterError;
-use log::warn;
+use log::{debug, warn};
 use nym_socks5_requests::RemoteAddress;
 use std::sync::Arc;
 
+pub mod allowlist;
 pub mod exit_policy;
 
+pub use allowlist::HostAllowList;
 pub use exit_policy::ExitPolicyRequestFilter;
 
 #[derive(Clone)]
 pub struct RequestFilter {
     inner: Arc<ExitPolicyRequestFilter>,
+
+    // optional restriction applied on top of the exit policy
+    allowlist: Option<HostAllowList>,
 }
 
 impl RequestFilter {
     pub(crate) async fn new(config: &Config) -> Result<Self, NetworkRequesterError> {
         Ok(RequestFilter {
             inner: Arc::new(ExitPolicyRequestFilter::new(config).await?),
+            allowlist: None,
         })
     }
 
+    #[must_use]
+    pub(crate) fn with_allowlist(mut self, allowlist: Option<HostAllowList>) -> Self {
+        self.allowlist = allowlist;
+        self
+    }
+
     pub fn current_exit_policy_filter(&self) -> &ExitPolicyRequestFilter {
         &self.inner
     }
 
+    #[allow(unused)]
+    pub fn allowlist(&self) -> Option<&HostAllowList> {
+        self.allowlist.as_ref()
+    }
+
     pub(crate) async fn check_address(&self, address: &RemoteAddress) -> bool {
+        if let Some(allowlist) = &self.allowlist {
+            if !allowlist.allows(address) {
+                debug!("'{address}' is not present on the local allowlist");
+                return false;
+            }
+        }
+
         self.inner.check(address).await.unwrap_or_else(|err| {
             warn!("failed to validate '{address}' against the exit policy: {err}");
             false
@@ -0,0 +1,194 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Context;
+use clap::Parser;
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Exports JSON schemas of all the public configuration files
+/// so that they could be used for validating or generating configs by external tooling.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+struct Args {
+    /// Directory to which the schema files are going to be written
+    #[arg(long, default_value = "./config-schemas")]
+    output_dir: PathBuf,
+}
+
+fn export(output_dir: &Path, name: &str, schema: RootSchema) {
+    let path = output_dir.join(format!("{name}.schema.json"));
+    let res = serde_json::to_string_pretty(&schema)
+        .context("failed to serialize the schema")
+        .and_then(|content| fs::write(&path, content).context("failed to write the schema"));
+
+    match res {
+        Ok(()) => println!("✅ {name}  =>  {}", path.display()),
+        Err(err) => println!("❌ {name} failed: {err:#}"),
+    }
+}
+
+macro_rules! do_export {
+    ($dir:expr, $name:literal, $a:ty) => {{
+        export($dir, $name, schema_for!($a))
+    }};
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    fs::create_dir_all(&args.output_dir).with_context(|| {
+        format!(
+            "failed to create the output directory {}",
+            args.output_dir.display()
+        )
+    })?;
+
+    println!("Starting export of config schemas...");
+    println!();
+
+    let dir = args.output_dir.as_path();
+
+    // common/client-core/config-types
+    do_export!(dir, "client-core", nym_client_core_config_types::Config);
+    do_export!(
+        dir,
+        "client-core-paths",
+        nym_client_core_config_types::disk_persistence::CommonClientPaths
+    );
+
+    // gateway
+    do_export!(dir, "gateway", nym_gateway::config::Config);
+
+    // mixnode
+    do_export!(dir, "mixnode", nym_mixnode::config::Config);
+
+    println!();
+    println!("Done");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use serde_json::{Map, Value};
+
+    fn resolve<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+        match schema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => {
+                let name = reference.trim_start_matches("#/definitions/");
+                resolve(root, &root["definitions"][name])
+            }
+            None => schema,
+        }
+    }
+
+    // collects the properties of the (possibly optional or flattened) object schema
+    fn properties(root: &Value, schema: &Value, out: &mut Map<String, Value>) {
+        let schema = resolve(root, schema);
+        if let Some(props) = schema.get("properties").and_then(Value::as_object) {
+            out.extend(props.clone());
+        }
+        for combinator in ["allOf", "anyOf", "oneOf"] {
+            for sub in schema
+                .get(combinator)
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                properties(root, sub, out);
+            }
+        }
+    }
+
+    fn required(root: &Value, schema: &Value) -> Vec<String> {
+        resolve(root, schema)
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|name| name.as_str().map(ToOwned::to_owned))
+            .collect()
+    }
+
+    fn assert_matches_schema(root: &Value, schema: &Value, value: &Value, path: &str) {
+        match value {
+            Value::Object(fields) => {
+                let mut props = Map::new();
+                properties(root, schema, &mut props);
+                if props.is_empty() {
+                    // arbitrary maps don't declare their keys
+                    return;
+                }
+
+                for (name, field) in fields {
+                    let field_schema = props
+                        .get(name)
+                        .unwrap_or_else(|| panic!("{path}.{name} is missing from the schema"));
+                    assert_matches_schema(root, field_schema, field, &format!("{path}.{name}"));
+                }
+                for name in required(root, schema) {
+                    assert!(
+                        fields.contains_key(&name),
+                        "{path}.{name} is required by the schema but it's not serialized"
+                    );
+                }
+            }
+            Value::Array(items) => {
+                if let Some(items_schema) = resolve(root, schema).get("items") {
+                    for (i, item) in items.iter().enumerate() {
+                        assert_matches_schema(root, items_schema, item, &format!("{path}[{i}]"));
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn assert_round_trip<T>(schema: RootSchema, config: T)
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let schema = serde_json::to_value(schema).unwrap();
+        let serialized = serde_json::to_value(&config).unwrap();
+        assert_matches_schema(&schema, &schema, &serialized, "$");
+
+        let deserialized: T = serde_json::from_value(serialized.clone()).unwrap();
+        assert_eq!(serde_json::to_value(deserialized).unwrap(), serialized);
+    }
+
+    #[test]
+    fn client_core_schema_matches_the_default_config() {
+        assert_round_trip(
+            schema_for!(nym_client_core_config_types::Config),
+            nym_client_core_config_types::Config::new("test-client", "1.0.0"),
+        );
+        assert_round_trip(
+            schema_for!(nym_client_core_config_types::disk_persistence::CommonClientPaths),
+            nym_client_core_config_types::disk_persistence::CommonClientPaths::new_base(
+                "/tmp/test-client",
+            ),
+        );
+    }
+
+    #[test]
+    fn gateway_schema_matches_the_default_config() {
+        assert_round_trip(
+            schema_for!(nym_gateway::config::Config),
+            nym_gateway::config::Config::new("test-gateway"),
+        );
+    }
+
+    #[test]
+    fn mixnode_schema_matches_the_default_config() {
+        assert_round_trip(
+            schema_for!(nym_mixnode::config::Config),
+            nym_mixnode::config::Config::new("test-mixnode"),
+        );
+    }
+}
@@ -0,0 +1,253 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reconstruction of the path a packet took through the recorded components.
+
+use crate::{EventKind, PacketEvent, PacketId};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+/// All the events recorded for a single form of the packet, i.e. between two sphinx transformations.
+#[derive(Debug, Clone)]
+pub struct Hop {
+    pub packet: PacketId,
+    pub events: Vec<PacketEvent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The payload of the packet has reached its recipient.
+    Delivered,
+
+    /// The packet has left `from`, but it was never received by anybody else.
+    LostInTransit { from: String },
+
+    /// The packet has been received by `node`, but it never left it.
+    LostInNode { node: String },
+
+    /// The packet has been explicitly dropped by `node`.
+    Dropped { node: String },
+
+    /// There are no events recorded for the packet.
+    Unknown,
+}
+
+impl Outcome {
+    pub fn is_delivered(&self) -> bool {
+        matches!(self, Outcome::Delivered)
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Delivered => write!(f, "delivered"),
+            Outcome::LostInTransit { from } => write!(f, "lost in transit after leaving {from}"),
+            Outcome::LostInNode { node } => write!(f, "lost inside {node}"),
+            Outcome::Dropped { node } => write!(f, "dropped by {node}"),
+            Outcome::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Journey {
+    pub hops: Vec<Hop>,
+    pub outcome: Outcome,
+}
+
+impl Journey {
+    pub fn events(&self) -> impl Iterator<Item = &PacketEvent> {
+        self.hops.iter().flat_map(|hop| hop.events.iter())
+    }
+}
+
+fn index_events(events: &[PacketEvent]) -> HashMap<&PacketId, Vec<&PacketEvent>> {
+    let mut indexed: HashMap<_, Vec<_>> = HashMap::new();
+    for event in events {
+        indexed.entry(&event.packet).or_default().push(event)
+    }
+    for packet_events in indexed.values_mut() {
+        packet_events.sort_by_key(|event| event.timestamp_ns);
+    }
+    indexed
+}
+
+fn follow(indexed: &HashMap<&PacketId, Vec<&PacketEvent>>, start: &PacketId) -> Journey {
+    let mut hops: Vec<Hop> = Vec::new();
+    let mut visited = HashSet::new();
+    let mut current = Some(start.clone());
+
+    while let Some(packet) = current.take() {
+        // guard against (very unlikely) hash collisions creating loops
+        if !visited.insert(packet.clone()) {
+            break;
+        }
+        let Some(packet_events) = indexed.get(&packet) else {
+            break;
+        };
+
+        current = packet_events.iter().find_map(|event| event.next.clone());
+        hops.push(Hop {
+            packet,
+            events: packet_events.iter().map(|&event| event.clone()).collect(),
+        });
+    }
+
+    let outcome = outcome(&hops);
+    Journey { hops, outcome }
+}
+
+fn outcome(hops: &[Hop]) -> Outcome {
+    let Some(last) = hops.last().and_then(|hop| hop.events.last()) else {
+        return Outcome::Unknown;
+    };
+
+    let was_delivered = hops
+        .iter()
+        .flat_map(|hop| hop.events.iter())
+        .any(|event| event.kind == EventKind::Delivered);
+
+    match last.kind {
+        EventKind::Dropped => Outcome::Dropped {
+            node: last.node.clone(),
+        },
+        EventKind::Received if was_delivered => Outcome::Delivered,
+        kind if kind.leaves_node() => Outcome::LostInTransit {
+            from: last.node.clone(),
+        },
+        _ => Outcome::LostInNode {
+            node: last.node.clone(),
+        },
+    }
+}
+
+/// Returns all the distinct packet ids starting with the provided prefix.
+pub fn find_packets(events: &[PacketEvent], prefix: &str) -> Vec<PacketId> {
+    let mut matching = events
+        .iter()
+        .map(|event| &event.packet)
+        .filter(|packet| packet.starts_with(prefix))
+        .cloned()
+        .collect::<Vec<_>>();
+    matching.sort();
+    matching.dedup();
+    matching
+}
+
+/// Follows the packet, through all of its transformations, across the recorded components.
+/// The journey always starts at the earliest recorded form of the packet,
+/// even if `packet` refers to one of its later hops.
+pub fn trace(events: &[PacketEvent], packet: &PacketId) -> Journey {
+    let previous = events
+        .iter()
+        .filter_map(|event| event.next.as_ref().map(|next| (next, &event.packet)))
+        .collect::<HashMap<_, _>>();
+
+    let mut origin = packet;
+    let mut visited = HashSet::new();
+    while let Some(&prev) = previous.get(origin) {
+        if !visited.insert(prev) {
+            break;
+        }
+        origin = prev;
+    }
+
+    follow(&index_events(events), origin)
+}
+
+/// Reconstructs the journeys of all the packets present in the capture.
+///
+/// If any client has recorded sending its packets, the journeys start at those. Otherwise
+/// they start at every packet that is not the result of processing another recorded packet.
+pub fn all_journeys(events: &[PacketEvent]) -> Vec<Journey> {
+    let indexed = index_events(events);
+
+    let mut origins = events
+        .iter()
+        .filter(|event| event.kind == EventKind::Sent)
+        .map(|event| &event.packet)
+        .collect::<Vec<_>>();
+
+    if origins.is_empty() {
+        let derived = events
+            .iter()
+            .filter_map(|event| event.next.as_ref())
+            .collect::<HashSet<_>>();
+        origins = events
+            .iter()
+            .map(|event| &event.packet)
+            .filter(|packet| !derived.contains(packet))
+            .collect();
+    }
+
+    let mut seen = HashSet::new();
+    origins
+        .into_iter()
+        .filter(|packet| seen.insert(*packet))
+        .map(|packet| follow(&indexed, packet))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(
+        timestamp_ns: u64,
+        node: &str,
+        kind: EventKind,
+        packet: &[u8],
+        next: Option<&[u8]>,
+    ) -> PacketEvent {
+        PacketEvent {
+            timestamp_ns,
+            node: node.to_string(),
+            kind,
+            packet: PacketId::from_bytes(packet),
+            next: next.map(PacketId::from_bytes),
+            size: 2048,
+        }
+    }
+
+    #[test]
+    fn reconstructing_journeys() {
+        let events = vec![
+            event(1, "client", EventKind::Sent, b"p0", None),
+            event(2, "gateway", EventKind::Received, b"p0", None),
+            event(3, "gateway", EventKind::Forwarded, b"p0", None),
+            event(4, "mix1", EventKind::Received, b"p0", None),
+            event(5, "mix1", EventKind::Processed, b"p0", Some(b"p1")),
+            event(6, "mix1", EventKind::Forwarded, b"p1", None),
+            event(7, "gateway", EventKind::Received, b"p1", None),
+            event(8, "gateway", EventKind::Delivered, b"p1", Some(b"msg")),
+            event(9, "client", EventKind::Received, b"msg", None),
+            // and a packet that never made it past the first mixnode
+            event(10, "client", EventKind::Sent, b"q0", None),
+            event(11, "gateway", EventKind::Received, b"q0", None),
+            event(12, "gateway", EventKind::Forwarded, b"q0", None),
+            event(13, "mix1", EventKind::Received, b"q0", None),
+            event(14, "mix1", EventKind::Processed, b"q0", Some(b"q1")),
+            event(15, "mix1", EventKind::Forwarded, b"q1", None),
+        ];
+
+        let delivered = trace(&events, &PacketId::from_bytes(b"p0"));
+        assert_eq!(delivered.hops.len(), 3);
+        assert_eq!(delivered.outcome, Outcome::Delivered);
+
+        let from_later_hop = trace(&events, &PacketId::from_bytes(b"p1"));
+        assert_eq!(from_later_hop.hops.len(), 3);
+
+        let journeys = all_journeys(&events);
+        assert_eq!(journeys.len(), 2);
+        assert_eq!(
+            journeys[1].outcome,
+            Outcome::LostInTransit {
+                from: "mix1".to_string()
+            }
+        );
+
+        let unknown = trace(&events, &PacketId::from_bytes(b"foomp"));
+        assert_eq!(unknown.outcome, Outcome::Unknown);
+    }
+}
@@ -0,0 +1,271 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recording of anonymised, per-hop, packet events for debugging packet loss
+//! between locally-run mixnet components.
+//!
+//! Clients, gateways and mixnodes built with their `pcap` feature record an event whenever
+//! a packet passes through them, as long as the `NYM_PCAP_FILE` environment variable is set.
+//! Only truncated hashes of the packets are ever recorded, so it's impossible to recover
+//! their content. However, as a packet looks the same on both ends of a connection,
+//! the events from different components can be linked together to reconstruct the journey
+//! of a packet through the network with the `nym-pcap` CLI.
+
+#![warn(clippy::expect_used)]
+#![warn(clippy::unwrap_used)]
+
+use nym_sphinx_types::NymPacket;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::{Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub mod journey;
+
+/// Environment variable specifying the file the packet events should be appended to.
+pub const PCAP_FILE_ENV: &str = "NYM_PCAP_FILE";
+
+// no need to keep the full digest around, the ids only have to be unique within a single capture
+const PACKET_ID_LEN: usize = 16;
+
+static RECORDER: OnceLock<Recorder> = OnceLock::new();
+
+#[derive(Debug, thiserror::Error)]
+pub enum PcapError {
+    #[error("failed to access the capture file: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("capture file contains a malformed event at line {line}: {source}")]
+    MalformedEvent {
+        line: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+/// Anonymised identifier of a packet as seen on the wire.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PacketId(String);
+
+impl PacketId {
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let digest = Sha256::digest(bytes);
+        PacketId(hex::encode(&digest[..PACKET_ID_LEN]))
+    }
+
+    pub fn for_packet(packet: &NymPacket) -> Self {
+        // a packet that can't be serialised wouldn't have been put on the wire anyway
+        PacketId::from_bytes(&packet.to_bytes().unwrap_or_default())
+    }
+
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.0.starts_with(prefix)
+    }
+}
+
+impl Display for PacketId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// The client has sent the packet to its gateway.
+    Sent,
+
+    /// The packet has been received by the node.
+    Received,
+
+    /// The packet has been processed and got transformed into the `next` packet.
+    Processed,
+
+    /// The packet has been sent to the next hop.
+    Forwarded,
+
+    /// The final payload of the packet (with the `next` id) got handed over to the client.
+    Delivered,
+
+    /// The node has given up on the packet.
+    Dropped,
+}
+
+impl EventKind {
+    /// Whether after this event the packet is expected to show up at another component.
+    pub fn leaves_node(&self) -> bool {
+        matches!(
+            self,
+            EventKind::Sent | EventKind::Forwarded | EventKind::Delivered
+        )
+    }
+}
+
+impl Display for EventKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            EventKind::Sent => "sent",
+            EventKind::Received => "received",
+            EventKind::Processed => "processed",
+            EventKind::Forwarded => "forwarded",
+            EventKind::Delivered => "delivered",
+            EventKind::Dropped => "dropped",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PacketEvent {
+    /// Unix timestamp (in nanoseconds) of the event.
+    pub timestamp_ns: u64,
+
+    /// Label of the component that has recorded the event.
+    pub node: String,
+
+    pub kind: EventKind,
+
+    pub packet: PacketId,
+
+    /// Id of the packet this packet got transformed into (if applicable).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<PacketId>,
+
+    pub size: usize,
+}
+
+struct Recorder {
+    node: String,
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl Recorder {
+    fn write(&self, event: &PacketEvent) -> Result<(), PcapError> {
+        let Ok(mut writer) = self.writer.lock() else {
+            return Ok(());
+        };
+        serde_json::to_writer(&mut *writer, event).map_err(io::Error::from)?;
+        writer.write_all(b"\n")?;
+        // make sure the events survive the component being killed
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Starts recording the packet events of this component into the provided file.
+/// Has no effect if the recording has already been started.
+pub fn init<P: AsRef<Path>>(node: impl Into<String>, path: P) -> Result<(), PcapError> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path.as_ref())?;
+
+    let _ = RECORDER.set(Recorder {
+        node: node.into(),
+        writer: Mutex::new(BufWriter::new(file)),
+    });
+    Ok(())
+}
+
+/// Starts recording the packet events if the capture file has been specified via `NYM_PCAP_FILE`.
+pub fn init_from_env(node: impl Into<String>) {
+    let Ok(path) = std::env::var(PCAP_FILE_ENV) else {
+        return;
+    };
+    let node = node.into();
+    match init(node.clone(), &path) {
+        Ok(_) => log::warn!("recording packet events of {node} into {path}. this should only ever be used for debugging!"),
+        Err(err) => log::error!("failed to start recording packet events into {path}: {err}"),
+    }
+}
+
+pub fn is_recording() -> bool {
+    RECORDER.get().is_some()
+}
+
+fn now_ns() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+/// Records the event (if the recording has been started).
+pub fn record(kind: EventKind, packet: PacketId, next: Option<PacketId>, size: usize) {
+    let Some(recorder) = RECORDER.get() else {
+        return;
+    };
+
+    let event = PacketEvent {
+        timestamp_ns: now_ns(),
+        node: recorder.node.clone(),
+        kind,
+        packet,
+        next,
+        size,
+    };
+    if let Err(err) = recorder.write(&event) {
+        log::warn!("failed to record packet event: {err}")
+    }
+}
+
+/// Records the event for the provided packet (if the recording has been started).
+pub fn record_packet(kind: EventKind, packet: &NymPacket) {
+    if is_recording() {
+        record(kind, PacketId::for_packet(packet), None, packet.len())
+    }
+}
+
+/// Records the packet got transformed into another one (if the recording has been started).
+pub fn record_transformation(kind: EventKind, packet: &NymPacket, next: &[u8]) {
+    if is_recording() {
+        record(
+            kind,
+            PacketId::for_packet(packet),
+            Some(PacketId::from_bytes(next)),
+            packet.len(),
+        )
+    }
+}
+
+/// Reads all the events from the capture file.
+pub fn read_events<P: AsRef<Path>>(path: P) -> Result<Vec<PacketEvent>, PcapError> {
+    let reader = BufReader::new(File::open(path)?);
+
+    let mut events = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str(&line).map_err(|source| PcapError::MalformedEvent {
+            line: i + 1,
+            source,
+        })?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// Writes the events into the file, overwriting any existing content.
+pub fn write_events<P: AsRef<Path>>(path: P, events: &[PacketEvent]) -> Result<(), PcapError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for event in events {
+        serde_json::to_writer(&mut writer, event).map_err(io::Error::from)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Merges events captured by different components into a single, chronologically ordered, list.
+pub fn merge(captures: Vec<Vec<PacketEvent>>) -> Vec<PacketEvent> {
+    let mut merged = captures.into_iter().flatten().collect::<Vec<_>>();
+    merged.sort_by_key(|event| event.timestamp_ns);
+    merged
+}
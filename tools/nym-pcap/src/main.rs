@@ -0,0 +1,170 @@
+// Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+#![warn(clippy::expect_used)]
+#![warn(clippy::unwrap_used)]
+
+use anyhow::bail;
+use clap::{Parser, Subcommand};
+use nym_pcap::journey::{all_journeys, find_packets, trace, Journey};
+use nym_pcap::{merge, read_events, write_events, EventKind, PacketEvent};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Tool for inspecting packet events recorded by components built with the `pcap` feature.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Merge captures of multiple components into a single, chronologically ordered, file.
+    Merge {
+        /// Path to the merged output file.
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Captures to merge.
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+    },
+
+    /// Show the journey of a packet across the recorded components.
+    Trace {
+        /// (Merged) capture file.
+        file: PathBuf,
+
+        /// Id (or its unambiguous prefix) of the packet, as recorded by any of the components.
+        packet: String,
+    },
+
+    /// Show per-component event counts and the packets that have not been delivered.
+    Summary {
+        /// (Merged) capture file.
+        file: PathBuf,
+
+        /// Print the full journeys of the undelivered packets.
+        #[arg(long)]
+        verbose: bool,
+    },
+}
+
+fn print_journey(journey: &Journey) {
+    let Some(first) = journey.events().next() else {
+        return;
+    };
+    let start = first.timestamp_ns;
+    let mut previous = start;
+
+    for event in journey.events() {
+        let since_start = Duration::from_nanos(event.timestamp_ns.saturating_sub(start));
+        let since_previous = Duration::from_nanos(event.timestamp_ns.saturating_sub(previous));
+        previous = event.timestamp_ns;
+
+        let next = event
+            .next
+            .as_ref()
+            .map(|next| format!(" -> {next}"))
+            .unwrap_or_default();
+        println!(
+            "{:>12.3?} (+{:>10.3?})  {:<24} {:<10} {}{next} [{}B]",
+            since_start, since_previous, event.node, event.kind, event.packet, event.size
+        );
+    }
+    println!("outcome: {}", journey.outcome);
+}
+
+fn summary(events: &[PacketEvent], verbose: bool) {
+    let kinds = [
+        EventKind::Sent,
+        EventKind::Received,
+        EventKind::Processed,
+        EventKind::Forwarded,
+        EventKind::Delivered,
+        EventKind::Dropped,
+    ];
+
+    let mut per_node: BTreeMap<&str, [usize; 6]> = BTreeMap::new();
+    for event in events {
+        let counts = per_node.entry(&event.node).or_default();
+        if let Some(i) = kinds.iter().position(|kind| *kind == event.kind) {
+            counts[i] += 1;
+        }
+    }
+
+    print!("{:<24}", "node");
+    for kind in kinds {
+        print!(" {:>10}", kind.to_string());
+    }
+    println!();
+    for (node, counts) in per_node {
+        print!("{node:<24}");
+        for count in counts {
+            print!(" {count:>10}");
+        }
+        println!();
+    }
+
+    let journeys = all_journeys(events);
+    let undelivered = journeys
+        .iter()
+        .filter(|journey| !journey.outcome.is_delivered())
+        .collect::<Vec<_>>();
+
+    println!(
+        "\n{} packets traced, {} not delivered",
+        journeys.len(),
+        undelivered.len()
+    );
+    for journey in undelivered {
+        let Some(origin) = journey.hops.first() else {
+            continue;
+        };
+        println!("{}: {}", origin.packet, journey.outcome);
+        if verbose {
+            print_journey(journey);
+            println!();
+        }
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Commands::Merge { output, inputs } => {
+            let mut captures = Vec::with_capacity(inputs.len());
+            for input in &inputs {
+                captures.push(read_events(input)?);
+            }
+            let merged = merge(captures);
+            write_events(&output, &merged)?;
+            println!(
+                "merged {} events from {} captures into {}",
+                merged.len(),
+                inputs.len(),
+                output.display()
+            );
+        }
+        Commands::Trace { file, packet } => {
+            let events = read_events(file)?;
+            let matching = find_packets(&events, &packet);
+            let start = match matching.as_slice() {
+                [] => bail!("no packet with id starting with '{packet}' has been recorded"),
+                [start] => start,
+                _ => bail!(
+                    "'{packet}' is ambiguous - it matches {} different packets",
+                    matching.len()
+                ),
+            };
+            print_journey(&trace(&events, start));
+        }
+        Commands::Summary { file, verbose } => summary(&read_events(file)?, verbose),
+    }
+
+    Ok(())
+}
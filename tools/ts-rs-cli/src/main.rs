@@ -32,9 +32,13 @@ use nym_wallet_types::admin::{
 };
 use nym_wallet_types::app::AppEnv;
 use nym_wallet_types::app::AppVersion;
+use nym_wallet_types::error::{BackendErrorCode, StructuredBackendError};
+use nym_wallet_types::funds::FundsSource;
 use nym_wallet_types::interval::Interval;
 use nym_wallet_types::network::Network;
 use nym_wallet_types::network_config::{Validator, ValidatorUrl, ValidatorUrls};
+use nym_wallet_types::node_status::{NodeOperationalStatus, PortReachability};
+use nym_wallet_types::pledge::PledgeAdjustmentSimulation;
 use std::path::Path;
 use ts_rs::TS;
 use walkdir::WalkDir;
@@ -130,8 +134,14 @@ fn main() {
     // nym-wallet
     do_export!(AppEnv);
     do_export!(AppVersion);
+    do_export!(BackendErrorCode);
+    do_export!(FundsSource);
     do_export!(Interval);
     do_export!(Network);
+    do_export!(NodeOperationalStatus);
+    do_export!(PledgeAdjustmentSimulation);
+    do_export!(PortReachability);
+    do_export!(StructuredBackendError);
     do_export!(TauriContractStateParams);
     do_export!(TauriOperatingCostRange);
     do_export!(TauriProfitMarginRange);
@@ -23,7 +23,8 @@ use wasm_client_core::client::{
 };
 use wasm_client_core::config::r#override::DebugWasmOverride;
 use wasm_client_core::helpers::{
-    parse_recipient, parse_sender_tag, setup_from_topology, setup_gateway_from_api,
+    dkg_query_client_from_config, parse_recipient, parse_sender_tag, setup_from_topology,
+    setup_gateway_from_api,
 };
 use wasm_client_core::init::types::GatewaySetup;
 use wasm_client_core::nym_task::connections::TransmissionLane;
@@ -173,10 +174,13 @@ impl NymClientBuilder {
         let storage = Self::initialise_storage(&self.config, client_store);
         let maybe_topology_provider = self.topology_provider();
 
+        let dkg_query_client =
+            dkg_query_client_from_config(&self.config.base, &self.config.network_details)?;
+
         let mut base_builder = BaseClientBuilder::<QueryReqwestRpcNyxdClient, _>::new(
             &self.config.base,
             storage,
-            None,
+            dkg_query_client,
         );
         if let Some(topology_provider) = maybe_topology_provider {
             base_builder = base_builder.with_topology_provider(topology_provider);
@@ -11,7 +11,9 @@ use crate::error::WasmClientError;
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 use wasm_bindgen::prelude::*;
-use wasm_client_core::config::{new_base_client_config, BaseClientConfig, ConfigDebug, DebugWasm};
+use wasm_client_core::config::{
+    new_base_client_config, BaseClientConfig, ConfigDebug, DebugWasm, NymNetworkDetails,
+};
 
 pub const DEFAULT_CLIENT_ID: &str = "nym-mixnet-client";
 
@@ -20,6 +22,10 @@ pub const DEFAULT_CLIENT_ID: &str = "nym-mixnet-client";
 #[serde(deny_unknown_fields)]
 pub struct ClientConfig {
     pub(crate) base: BaseClientConfig,
+
+    /// Details of the network the client is connecting to, such as the addresses of its contracts.
+    #[serde(default)]
+    pub(crate) network_details: NymNetworkDetails,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +41,11 @@ pub struct ClientConfigOpts {
     #[tsify(optional)]
     pub nyxd: Option<String>,
 
+    /// Specifies whether the client should attempt to spend ticketbooks with the gateway.
+    /// Note that it requires a valid `nyxd` endpoint for querying the DKG contract.
+    #[tsify(optional)]
+    pub enable_credentials_mode: Option<bool>,
+
     #[tsify(optional)]
     pub debug: Option<DebugWasm>,
 }
@@ -47,7 +58,9 @@ impl ClientConfig {
         let id = opts.id.unwrap_or_else(|| DEFAULT_CLIENT_ID.to_string());
 
         Ok(ClientConfig {
-            base: new_base_client_config(id, version, opts.nym_api, opts.nyxd, opts.debug)?,
+            base: new_base_client_config(id, version, opts.nym_api, opts.nyxd, opts.debug)?
+                .with_disabled_credentials(!opts.enable_credentials_mode.unwrap_or_default()),
+            network_details: NymNetworkDetails::new_mainnet(),
         })
     }
 
@@ -58,6 +71,7 @@ impl ClientConfig {
                 .with_disabled_credentials(true)
                 .with_disabled_cover_traffic(true)
                 .with_disabled_topology_refresh(true),
+            network_details: NymNetworkDetails::new_mainnet(),
         }
     }
 }
@@ -66,4 +80,10 @@ impl ClientConfig {
     pub fn override_debug<D: Into<ConfigDebug>>(&mut self, debug: D) {
         self.base.debug = debug.into();
     }
+
+    #[must_use]
+    pub fn with_network_details(mut self, network_details: NymNetworkDetails) -> Self {
+        self.network_details = network_details;
+        self
+    }
 }
@@ -16,7 +16,7 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::future_to_promise;
 use wasm_client_core::client::base_client::{BaseClientBuilder, ClientInput, ClientOutput};
 use wasm_client_core::client::inbound_messages::InputMessage;
-use wasm_client_core::helpers::setup_gateway_from_api;
+use wasm_client_core::helpers::{dkg_query_client_from_config, setup_gateway_from_api};
 use wasm_client_core::init::types::GatewaySetup;
 use wasm_client_core::nym_task::connections::TransmissionLane;
 use wasm_client_core::nym_task::TaskManager;
@@ -107,10 +107,13 @@ impl MixFetchClientBuilder {
 
         let storage = Self::initialise_storage(&self.config, client_store);
 
+        let dkg_query_client =
+            dkg_query_client_from_config(&self.config.base, &self.config.network_details)?;
+
         let mut base_builder = BaseClientBuilder::<QueryReqwestRpcNyxdClient, _>::new(
             &self.config.base,
             storage,
-            None,
+            dkg_query_client,
         );
 
         if let Ok(reuse_setup) = GatewaySetup::try_reuse_connection(init_res) {
@@ -14,7 +14,9 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tsify::Tsify;
 use wasm_bindgen::prelude::*;
-use wasm_client_core::config::{new_base_client_config, BaseClientConfig, ConfigDebug, DebugWasm};
+use wasm_client_core::config::{
+    new_base_client_config, BaseClientConfig, ConfigDebug, DebugWasm, NymNetworkDetails,
+};
 use wasm_client_core::helpers::parse_recipient;
 use wasm_client_core::Recipient;
 
@@ -36,6 +38,10 @@ fn make_mix_fetch_id(id: Option<String>) -> String {
 pub struct MixFetchConfig {
     pub(crate) base: BaseClientConfig,
 
+    /// Details of the network the client is connecting to, such as the addresses of its contracts.
+    #[serde(default)]
+    pub(crate) network_details: NymNetworkDetails,
+
     pub(crate) mix_fetch: MixFetch,
 }
 
@@ -73,11 +79,13 @@ impl MixFetchConfig {
                     opts.nyxd,
                     opts.debug,
                 )?,
+                network_details: NymNetworkDetails::new_mainnet(),
                 mix_fetch: MixFetch::new(network_requester_address)?,
             })
         } else {
             Ok(MixFetchConfig {
                 base: BaseClientConfig::new(make_mix_fetch_id(None), version),
+                network_details: NymNetworkDetails::new_mainnet(),
                 mix_fetch: MixFetch::new(network_requester_address)?,
             })
         }
@@ -97,6 +105,12 @@ impl MixFetchConfig {
         self.base.debug = debug.into();
     }
 
+    #[must_use]
+    pub fn with_network_details(mut self, network_details: NymNetworkDetails) -> Self {
+        self.network_details = network_details;
+        self
+    }
+
     pub fn override_mix_fetch_debug<D: Into<MixFetchDebug>>(&mut self, debug: D) {
         self.mix_fetch.debug = debug.into();
     }
@@ -86,7 +86,7 @@ pub struct NymNodeTesterBuilder {
 
 fn address(keys: &ClientKeys, gateway_identity: NodeIdentity) -> Recipient {
     Recipient::new(
-        *keys.identity_keypair().public_key(),
+        keys.identity_public_key(),
         *keys.encryption_keypair().public_key(),
         gateway_identity,
     )
@@ -205,7 +205,7 @@ impl NymNodeTesterBuilder {
                 GatewayClient::new(
                     GatewayClientConfig::new_default().with_disabled_credentials_mode(true),
                     cfg,
-                    managed_keys.identity_keypair(),
+                    managed_keys.identity_signer(),
                     Some(gateway_info.shared_key),
                     packet_router,
                     self.bandwidth_controller.take(),